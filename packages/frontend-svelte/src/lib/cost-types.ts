@@ -50,11 +50,14 @@ export type LaborType =
   | 'general_labor'
   | 'skilled_labor'
   | 'framing_carpentry'
+  | 'excavation'
+  | 'fill_compaction'
   | 'concrete_subgrade_prep'
   | 'concrete_form_install'
   | 'concrete_rebar_install'
   | 'concrete_place_finish'
   | 'roofing_install'
+  | 'gutter_install'
   | 'siding_install'
   | 'drywall_install'
   | 'painting_labor'
@@ -75,6 +78,8 @@ export type MaterialType =
   | 'concrete_forms'
   | 'concrete_vapor_barrier'
   | 'concrete_gravel'
+  // Site work
+  | 'fill_soil'
   // Framing
   | 'lumber_2x4'
   | 'lumber_2x6'
@@ -82,18 +87,26 @@ export type MaterialType =
   | 'lumber_2x10'
   | 'lumber_2x12'
   | 'lvl_beam'
+  | 'structural_post'
   | 'sheathing'
   // Roofing
   | 'asphalt_shingles'
   | 'metal_roofing'
   | 'tile_roofing'
   | 'roofing_underlayment'
+  | 'drip_edge'
+  | 'ridge_cap'
+  | 'valley_flashing'
+  | 'gutter'
+  | 'downspout'
   // Exterior
   | 'vinyl_siding'
   | 'hardie_board'
   | 'stucco'
   | 'brick'
   | 'stone'
+  | 'deck_board'
+  | 'deck_railing'
   // Openings
   | 'window_unit'
   | 'exterior_door'
@@ -111,7 +124,14 @@ export type MaterialType =
   // Fixtures
   | 'truss'
   | 'light_fixture'
+  | 'receptacle'
+  | 'gfci_receptacle'
+  | 'light_switch'
   | 'plumbing_fixture'
+  | 'toilet'
+  | 'sink'
+  | 'bathtub'
+  | 'shower'
   | 'cabinet'
   | 'countertop'
   | 'appliance';
@@ -22,7 +22,7 @@ export type HeaderType = '2x4' | '2x6' | '2x8' | '2x10' | '2x12' | 'LVL' | 'stee
 // Framing Member Types
 // ============================================================================
 
-/** All possible framing member types in a wall assembly */
+/** All possible framing member types in a wall or floor assembly */
 export type FramingMemberType =
   | 'bottom_plate'
   | 'top_plate'
@@ -32,10 +32,18 @@ export type FramingMemberType =
   | 'jack_stud'
   | 'cripple_stud'
   | 'corner_stud'
+  | 'partition_backing'
   | 'header'
   | 'sill'
   | 'fire_blocking'
-  | 'mid_height_blocking';
+  | 'mid_height_blocking'
+  | 'floor_joist'
+  | 'rim_board'
+  | 'floor_blocking'
+  | 'subfloor_sheet'
+  | 'ledger'
+  | 'deck_board'
+  | 'railing_post';
 
 // ============================================================================
 // Framing Member Interface
@@ -53,8 +61,8 @@ export interface FramingMember {
   length: number;
   /** Rotation in degrees (optional, defaults to 0) */
   rotation?: number;
-  /** Parent wall ID */
-  wallId: string;
+  /** Parent wall ID (absent for floor framing) */
+  wallId?: string;
   /** Associated opening ID (for king studs, jack studs, headers, sills) */
   openingId?: string;
 }
@@ -110,6 +118,40 @@ export interface RoughOpening {
   requiresSill: boolean;
 }
 
+// ============================================================================
+// Cut List (from get_wall_cut_list / get_level_cut_list)
+// ============================================================================
+
+/** One stock board and the pieces cut from it; lengths in inches */
+export interface StockBoard {
+  /** Serialized lumber size, e.g. 'two_by_six' */
+  lumber_size: string;
+  stock_length: number;
+  cuts: number[];
+}
+
+/** How many boards of one size and length to buy */
+export interface StockOrder {
+  lumber_size: string;
+  stock_length: number;
+  count: number;
+}
+
+/** Stock lumber to buy for framing, packed first-fit-decreasing */
+export interface CutList {
+  boards: StockBoard[];
+  orders: StockOrder[];
+  stock_length: number;
+  cut_length: number;
+  waste_percent: number;
+}
+
+export interface LevelCutList {
+  level_id: string;
+  walls: { wall_id: string; cut_list: CutList }[];
+  total: CutList;
+}
+
 // ============================================================================
 // Extended Wall Interface
 // ============================================================================
@@ -236,10 +278,18 @@ export const FRAMING_COLORS: Record<FramingMemberType, number> = {
   'jack_stud': 0xA8856A,
   'cripple_stud': 0xD8B994,
   'corner_stud': 0xC8A584,
+  'partition_backing': 0xC8A584,
   'header': 0x8B7355,
   'sill': 0xD4A574,
   'fire_blocking': 0xF08080,
   'mid_height_blocking': 0xD4A574,
+  'floor_joist': 0xE0BE92,
+  'rim_board': 0xC4956A,
+  'floor_blocking': 0xD4A574,
+  'subfloor_sheet': 0xCDB38B,
+  'ledger': 0xB8956A,
+  'deck_board': 0xA0785A,
+  'railing_post': 0x8B7355,
 };
 
 // ============================================================================
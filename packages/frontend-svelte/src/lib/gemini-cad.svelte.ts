@@ -41,7 +41,7 @@ import {
   type CirculationWarning
 } from './circulation-utils';
 import type { CostEstimate as ConstructionCostEstimate, CostCategory, CostLineItem, MaterialType, LaborType, PricingUnit } from './cost-types';
-import type { WasmStoreExtended } from './wasm-loader';
+import type { WallSummary, WasmStoreExtended } from './wasm-loader';
 
 // ============================================================================
 // Types
@@ -853,7 +853,7 @@ class GeminiCADManager {
           let wallIds: string[] = [];
 
           if (typeof s.get_level_walls === 'function') {
-            wallIds = s.get_level_walls(levelId) as string[];
+            wallIds = (s.get_level_walls(levelId) as WallSummary[]).map(w => w.id);
           } else {
             // Fallback: get walls from observable state
             wallIds = (wasmManager.observableState.floorplan.walls || []).map(w => w.id);
@@ -107,7 +107,11 @@ export interface ToolCall {
 
 export interface BuildingStats {
   total_area: number;
+  conditioned_area: number;
+  unconditioned_area: number;
   level_count: number;
+  /** Decks and porches, not in total_area */
+  deck_area: number;
 }
 
 export interface ToolSuccessResponse<T = unknown> {
@@ -12,10 +12,30 @@ import {
 } from 'three';
 
 // Type-only import for the WASM module types
-import type { WasmStore, WasmMesh as WasmMeshClass } from '../../../geometry-wasm/pkg/geometry_wasm';
+import type {
+  WasmStore,
+  WasmMesh as WasmMeshClass,
+  PickResult,
+  RoomSummary,
+  SnapResult,
+  WallSummary
+} from '../../../geometry-wasm/pkg/geometry_wasm';
+import type { CutList, FramingMemberType, LevelCutList } from './framing-types';
 
 // Re-export WasmStore type for consumers
 export type { WasmStore };
+// Result types generated from the Rust DTOs
+export type {
+  BuildingStatsSummary,
+  FloorFramingSummary,
+  HeaderSummary,
+  PickResult,
+  RoomSummary,
+  SnapResult,
+  StoreCounts,
+  WallFramingSummary,
+  WallSummary
+} from '../../../geometry-wasm/pkg/geometry_wasm';
 
 /**
  * Interface describing the shape of the loaded WASM module
@@ -86,11 +106,25 @@ export function getWasmModule(): WasmModule | null {
   return wasmModule;
 }
 
-// Interface matching the Rust WasmMesh struct
+/**
+ * Buffers moved out of a WasmMesh; JS owns them
+ */
+export interface MeshBuffers {
+  positions: Float32Array;
+  normals: Float32Array;
+  indices: Uint32Array;
+}
+
+/**
+ * Interface matching the Rust WasmMesh struct. The getters return copies
+ * owned by JS, never views into wasm memory. take_buffers() copies once and
+ * frees the mesh, which can't be used afterwards.
+ */
 export interface WasmMesh {
   positions: Float32Array;
   normals: Float32Array;
   indices: Uint32Array;
+  take_buffers(): MeshBuffers;
   free(): void;
 }
 
@@ -110,6 +144,87 @@ export interface FramingRenderItem {
   memberType: string;
 }
 
+export interface MeshExportOptions {
+  /** Merge coincident vertices (also recomputes normals) */
+  weld?: boolean;
+  /** Distance in feet under which vertices are merged */
+  weld_tolerance?: number;
+  recompute_normals?: boolean;
+}
+
+export interface ImportSummary {
+  level_ids: string[];
+  footprint_ids: string[];
+  wall_ids: string[];
+  room_ids: string[];
+  /** Entities that were read but not imported, with the reason */
+  warnings: string[];
+}
+
+export interface GridRenderItem {
+  mesh: WasmMesh;
+  name: string;
+  direction: 'horizontal' | 'vertical';
+  offset: number;
+}
+
+export interface StructuralRenderItem {
+  mesh: WasmMesh;
+  memberId: string;
+  kind: 'beam' | 'post';
+  description: string;
+}
+
+/**
+ * A level of a building scene: its shell, walls and rooms, the openings cut
+ * through the walls, and every other part
+ */
+export interface LevelScene {
+  levelId: string;
+  name: string;
+  elevation: number;
+  height: number;
+  /** Detail the level was meshed at */
+  quality: MeshQuality;
+  shell?: WasmMesh;
+  shellError?: string;
+  walls: { wallId: string; thickness: number; mesh: WasmMesh }[];
+  rooms: { roomId: string; mesh: WasmMesh }[];
+  openings: {
+    id: string;
+    wall_id: string;
+    opening_type: string | { other: string };
+    position_along_wall: number;
+    width: number;
+    height: number;
+    sill_height: number;
+  }[];
+  /** Slabs, ceilings, roof, framing, beams, posts, decks and stairs */
+  parts: {
+    kind: string;
+    id: string;
+    name: string;
+    material: 'wall' | 'floor' | 'ceiling' | 'roof' | 'framing' | 'structural' | 'stair';
+    mesh: WasmMesh;
+  }[];
+}
+
+/**
+ * Mesh detail for render calls. Medium and low tessellate curves more
+ * coarsely; low also decimates and leaves framing out of building scenes.
+ * Omitted means high.
+ */
+export type MeshQuality = 'high' | 'medium' | 'low';
+
+/**
+ * A whole building from render_building, lowest level first
+ */
+export interface BuildingScene {
+  buildingId: string;
+  name: string;
+  levels: LevelScene[];
+}
+
 /**
  * Cost estimate returned from WASM
  */
@@ -120,11 +235,42 @@ export interface CostEstimate {
   subtotals: Record<string, number>;
   labor_total: number;
   material_total: number;
+  /** Line items grouped by CSI division, in division order */
+  divisions: DivisionSubtotal[];
+  /** Sum of line items before markups */
+  direct_total: number;
+  markup_rates: Markups;
+  markups: MarkupLine[];
+  /** Direct total plus markups */
   grand_total: number;
+  /** Per trade (category): 'modeled', 'partial' or 'heuristic' quantities */
+  quantity_sources: Record<string, 'modeled' | 'partial' | 'heuristic'>;
   created_at: string;
   notes?: string;
 }
 
+export interface DivisionSubtotal {
+  /** CSI division, e.g. 'concrete', 'wood_plastics_composites', 'openings' */
+  division: string;
+  line_item_ids: string[];
+  material_total: number;
+  labor_total: number;
+  subtotal: number;
+}
+
+/** Percentages compounded on direct cost: contingency, then overhead, then profit */
+export interface Markups {
+  contingency_percent: number;
+  overhead_percent: number;
+  profit_percent: number;
+}
+
+export interface MarkupLine {
+  name: string;
+  percent: number;
+  amount: number;
+}
+
 export interface CostLineItem {
   id: string;
   category: string;
@@ -157,30 +303,775 @@ export interface LaborRate {
 export interface PriceTable {
   material_prices: Record<string, UnitPrice>;
   labor_rates: Record<string, LaborRate>;
+  /** Region tag of a regional price set */
+  region?: string;
+}
+
+// Quantity takeoff (from generate_quantity_takeoff)
+export interface TakeoffItem {
+  category: string;
+  description: string;
+  quantity: number;
+  /** each, sheet, linear_foot, square_foot, square, cubic_yard or pound */
+  unit: string;
+}
+
+export interface StudCount {
+  /** Serialized lumber size, e.g. 'two_by_six' */
+  lumber_size: string;
+  /** Cut length in inches */
+  length: number;
+  count: number;
+}
+
+export interface OpeningScheduleEntry {
+  opening_type: string;
+  catalog_code?: string | null;
+  width: number;
+  height: number;
+  count: number;
+}
+
+export interface QuantityTakeoff {
+  level_id: string;
+  studs: StudCount[];
+  items: TakeoffItem[];
+  openings: OpeningScheduleEntry[];
+  unframed_walls: number;
+}
+
+// Electrical devices (from get_level_electrical_devices, auto_layout_electrical, get_device_schedule)
+export type ElectricalDeviceKind = 'receptacle' | 'gfci_receptacle' | 'switch' | 'light_fixture';
+
+export interface ElectricalDevice {
+  id: string;
+  level_id: string;
+  kind: ElectricalDeviceKind;
+  location: { x: number; y: number };
+  wall_id?: string | null;
+  room_id?: string | null;
+  /** Placed by the auto-layout, and replaced when it runs again */
+  auto_placed: boolean;
+}
+
+export interface DeviceCounts {
+  receptacle: number;
+  gfci_receptacle: number;
+  switch: number;
+  light_fixture: number;
+}
+
+export interface ElectricalLayoutSummary {
+  device_ids: string[];
+  counts: DeviceCounts;
+  /** Auto-placed devices from an earlier run that were replaced */
+  replaced: number;
+}
+
+export interface DeviceScheduleEntry {
+  level_id: string;
+  level_name: string;
+  room_id?: string | null;
+  room_name?: string | null;
+  counts: DeviceCounts;
+}
+
+export interface DeviceSchedule {
+  entries: DeviceScheduleEntry[];
+  totals: DeviceCounts;
+}
+
+// Plumbing fixtures (from get_level_fixtures, check_fixture_clearances, get_wet_walls)
+export type PlumbingFixtureKind = 'toilet' | 'sink' | 'tub' | 'shower';
+
+export interface PlumbingFixture {
+  id: string;
+  level_id: string;
+  room_id: string;
+  kind: PlumbingFixtureKind;
+  /** Middle of the fixture's back edge, on the wall face */
+  location: { x: number; y: number };
+  /** Direction the fixture faces, in degrees from the +X axis */
+  rotation: number;
+}
+
+export interface FixtureClearance {
+  fixture_id: string;
+  room_id: string;
+  kind: PlumbingFixtureKind;
+  outside_room: boolean;
+  obstructed_by: string[];
+}
+
+export interface WetWallGroup {
+  wall_id?: string | null;
+  fixture_ids: string[];
+  room_ids: string[];
+}
+
+export interface WetWallAnalysis {
+  level_id: string;
+  groups: WetWallGroup[];
+  fixture_count: number;
+  shared_fixture_count: number;
+}
+
+// HVAC zones (from get_building_hvac_zones)
+export interface HvacZone {
+  id: string;
+  building_id: string;
+  name: string;
+  room_ids: string[];
+}
+
+// Decks and porches (from get_level_decks)
+export type DeckKind = 'deck' | 'porch';
+
+export interface Deck {
+  id: string;
+  level_id: string;
+  kind: DeckKind;
+  ledger_start: { x: number; y: number };
+  ledger_end: { x: number; y: number };
+  depth: number;
+  height: number;
+  /** Serialized lumber size, e.g. 'two_by_eight' */
+  joist_size: string;
+}
+
+// Deck framing (from get_deck_framing); positions in inches, z from the deck surface
+export interface DeckFramingMember {
+  id: string;
+  member_type: FramingMemberType;
+  /** Serialized lumber size; boards laid flat are { custom: { width, depth } } */
+  lumber_size: string | { custom: { width: number; depth: number } };
+  position: { x: number; y: number; z: number };
+  length: number;
+  /** Radians */
+  rotation: number;
+}
+
+export interface DeckFramingLayout {
+  deck_id: string;
+  members: DeckFramingMember[];
+  joist_count: number;
+  decking_courses: number;
+  decking_length: number;
+  railing_posts: number;
+  total_board_feet: number;
+}
+
+// Garage slab settings (from get_level_garages)
+export interface Garage {
+  id: string;
+  level_id: string;
+  room_id: string;
+  /** Inches */
+  slab_thickness: number;
+  /** Fall toward the vehicle door, inches per foot */
+  slab_slope: number;
+}
+
+// Garage slab quantities (from get_garage_slab)
+export interface GarageSlab {
+  area: number;
+  concrete_cy: number;
+  gravel_cy: number;
+  rebar_lbs: number;
+  /** Feet from the back of the slab to the vehicle door; 0 without a door */
+  run: number;
+  /** Inches */
+  fall: number;
+}
+
+// Room finish selections (from set_room_finishes)
+export type TrimLevel = 'basic' | 'standard' | 'premium';
+
+export interface RoomFinishes {
+  floor: string;
+  wall: string;
+  ceiling: string;
+  base: string;
+  trim: TrimLevel;
+}
+
+// Snapshots and estimate comparison (from get_project_snapshots, compare_estimates)
+export interface Snapshot {
+  id: string;
+  project_id: string;
+  event_count: number;
+  last_event_id: string;
+  created_at: string;
+  label?: string | null;
+}
+
+export interface LineItemDelta {
+  building_id: string;
+  building_name: string;
+  level_id: string;
+  level_name: string;
+  category: string;
+  description: string;
+  quantity_a: number;
+  quantity_b: number;
+  total_a: number;
+  total_b: number;
+  /** total_b - total_a */
+  delta: number;
+}
+
+export interface EstimateComparison {
+  project_id: string;
+  snapshot_a: string;
+  snapshot_b: string;
+  label_a?: string | null;
+  label_b?: string | null;
+  /** Changed line items only */
+  items: LineItemDelta[];
+  total_a: number;
+  total_b: number;
+  total_delta: number;
+}
+
+// Price CSV import result (from import_price_csv)
+export interface PriceCsvError {
+  /** Line number in the file, header is line 1 */
+  row: number;
+  message: string;
+}
+
+export interface PriceCsvImport {
+  imported: number;
+  errors: PriceCsvError[];
+}
+
+// Opening catalog (from get_opening_catalog)
+export interface WindowType {
+  code: string;
+  name: string;
+  width: number;
+  height: number;
+  rough_width: number;
+  rough_height: number;
+  clear_opening: { width: number; height: number } | null;
+  u_value: number;
+  shgc: number;
+  unit_cost: number;
+}
+
+export interface DoorType {
+  code: string;
+  name: string;
+  width: number;
+  height: number;
+  rough_width: number;
+  rough_height: number;
+  is_exterior: boolean;
+  fire_rating: number | null;
+  unit_cost: number;
+}
+
+export interface OpeningCatalog {
+  windows: WindowType[];
+  doors: DoorType[];
+}
+
+/**
+ * Footprint edge inside a lot setback, from check_zoning
+ */
+export interface SetbackViolation {
+  building_id: string;
+  level_id: string;
+  side: 'front' | 'rear' | 'left' | 'right';
+  lot_edge: number;
+  start: { x: number; y: number };
+  end: { x: number; y: number };
+  required: number;
+  distance: number;
+  encroachment: number;
+}
+
+export interface ZoningReport {
+  project_id: string;
+  site_id: string;
+  lot_area: number;
+  covered_area: number;
+  lot_coverage: number;
+  max_lot_coverage: number | null;
+  setback_violations: SetbackViolation[];
+  height_violations: { building_id: string; height: number; max_height: number }[];
+}
+
+/**
+ * Line of equal ground elevation, from get_site_contours
+ */
+export interface Contour {
+  elevation: number;
+  segments: [{ x: number; y: number }, { x: number; y: number }][];
+}
+
+/**
+ * Earthwork to grade a building pad, from get_cut_fill (cubic yards)
+ */
+export interface CutFill {
+  building_id: string;
+  pad_elevation: number;
+  pad_area: number;
+  unsurveyed_area: number;
+  cut_cy: number;
+  fill_cy: number;
+}
+
+export type EnergyComponentKind =
+  | 'wall'
+  | 'window'
+  | 'door'
+  | 'ceiling'
+  | 'floor'
+  | 'slab_edge'
+  | 'foundation_wall';
+
+/**
+ * Envelope UA from get_energy_summary; quantity is sq ft, or linear ft for slab edges
+ */
+export interface EnergySummary {
+  building_id: string;
+  ua: number;
+  levels: {
+    level_id: string;
+    name: string;
+    ua: number;
+    components: {
+      kind: EnergyComponentKind;
+      level_id: string;
+      wall_id: string | null;
+      opening_id: string | null;
+      quantity: number;
+      u_factor: number;
+      ua: number;
+      insulated: boolean;
+    }[];
+  }[];
+  insulation: {
+    kind: EnergyComponentKind;
+    quantity: number;
+    ua: number;
+    effective_r: number | null;
+    uninsulated: number;
+  }[];
+  solar: FacadeSolar[];
+}
+
+export type Facing = 'north' | 'east' | 'south' | 'west';
+
+/**
+ * Walls, windows and clear-day solar gain (Btu/day) facing one way
+ */
+export interface FacadeSolar {
+  facing: Facing;
+  wall_area: number;
+  window_area: number;
+  window_wall_ratio: number;
+  heating_gain: number;
+  cooling_gain: number;
+}
+
+/**
+ * Window orientation and solar gain from get_solar_report; shading is the
+ * share of a window the roof overhang shades in January and July
+ */
+export interface SolarReport {
+  building_id: string;
+  north_angle: number;
+  heating_gain: number;
+  cooling_gain: number;
+  facades: FacadeSolar[];
+  windows: {
+    opening_id: string;
+    wall_id: string;
+    level_id: string;
+    facing: Facing;
+    azimuth: number;
+    area: number;
+    shgc: number;
+    overhang: number;
+    winter_shading: number;
+    summer_shading: number;
+    heating_gain: number;
+    cooling_gain: number;
+  }[];
+}
+
+/**
+ * Design loads (Btu/h), registers and duct runs from get_hvac_summary;
+ * zone_id is null for the unzoned rooms
+ */
+export interface HvacSummary {
+  building_id: string;
+  rooms: {
+    room_id: string;
+    level_id: string;
+    name: string;
+    zone_id: string | null;
+    floor_area: number;
+    glazing_area: number;
+    ua: number;
+    heating_load: number;
+    cooling_load: number;
+    supply_registers: number;
+  }[];
+  zones: {
+    zone_id: string | null;
+    name: string;
+    room_ids: string[];
+    level_ids: string[];
+    floor_area: number;
+    heating_load: number;
+    cooling_load: number;
+    supply_registers: number;
+    return_registers: number;
+  }[];
+  heating_load: number;
+  cooling_load: number;
+  supply_registers: number;
+  return_registers: number;
+  duct_runs: number;
+  cooling_tons: number;
+}
+
+/**
+ * Rooms sharing boundary, from get_room_adjacencies
+ */
+export interface RoomAdjacencyGraph {
+  level_id: string;
+  rooms: string[];
+  adjacencies: {
+    room_a: string;
+    room_b: string;
+    segments: { start: { x: number; y: number }; end: { x: number; y: number } }[];
+    length: number;
+  }[];
+}
+
+/**
+ * Room connectivity from get_circulation; path lengths are walking feet
+ */
+export interface CirculationReport {
+  level_id: string;
+  rooms: string[];
+  entries: string[];
+  passages: {
+    room_a: string;
+    room_b: string;
+    kind: 'door' | 'cased_opening' | 'open';
+    opening_id: string | null;
+    location: { x: number; y: number };
+  }[];
+  unreachable: string[];
+  paths: { room_id: string; rooms: string[]; length: number }[];
+}
+
+/**
+ * A project's design constraint; lengths in feet, areas in square feet
+ */
+export type ConstraintKind =
+  | { type: 'room_min_area'; room_id: string; min_area: number }
+  | { type: 'room_aspect_ratio'; room_id: string; max_ratio: number }
+  | { type: 'wall_on_grid_axis'; wall_id: string; axis: string }
+  | { type: 'wall_distance'; wall_a: string; wall_b: string; distance: number };
+
+export interface Constraint {
+  id: string;
+  project_id: string;
+  name: string;
+  kind: ConstraintKind;
+}
+
+export interface ConstraintStatus {
+  constraint_id: string;
+  name: string;
+  kind: ConstraintKind['type'];
+  satisfied: boolean;
+  actual: number | null;
+  target: number;
+  message: string;
+}
+
+export interface ConstraintReport {
+  project_id: string;
+  statuses: ConstraintStatus[];
+}
+
+/**
+ * Constraints a mutation broke or brought back into line
+ */
+export interface ConstraintChanges {
+  broken: ConstraintStatus[];
+  resolved: ConstraintStatus[];
+}
+
+export interface SolveReport {
+  iterations: number;
+  adjusted_rooms: string[];
+  moved_walls: string[];
+  report: ConstraintReport;
+}
+
+/**
+ * A named Rhai script saved with a project
+ */
+export interface SavedScript {
+  id: string;
+  project_id: string;
+  name: string;
+  source: string;
+}
+
+/**
+ * A domain call a script made; errors are structured objects where the
+ * function raised one
+ */
+export interface TracedCall {
+  function: string;
+  args: unknown[];
+  result: unknown | null;
+  error: unknown | null;
+  line: number | null;
+}
+
+export interface ScriptRunResult {
+  success: boolean;
+  value: unknown;
+  error: string | null;
+  events_generated: number;
+  trace: TracedCall[];
+  /** Why the script was stopped early, if it was */
+  aborted: 'timeout' | 'cancelled' | null;
+}
+
+/**
+ * Starter layout parameters; omitted ones take the template's defaults
+ */
+export interface TemplateParams {
+  stories?: number;
+  width?: number;
+  depth?: number;
+  garage?: boolean;
+  bedrooms?: number;
+  story_height?: number;
+}
+
+export interface TemplateInstance {
+  template: 'ranch' | 'two_story_colonial';
+  building_id: string;
+  level_ids: string[];
+  room_ids: string[];
+  wall_ids: string[];
+  opening_ids: string[];
+  stair_ids: string[];
+}
+
+/**
+ * Changes to an opening; omitted fields keep their current value
+ */
+export interface OpeningEdit {
+  position?: number;
+  width?: number;
+  height?: number;
+  sill_height?: number;
+  opening_type?: 'window' | 'door';
+}
+
+export type FootprintRole = 'primary' | 'auxiliary';
+
+/**
+ * A footprint on a level; a level has one primary and any number of
+ * auxiliary ones (wings, attached garages)
+ */
+export interface Footprint {
+  id: string;
+  level_id: string;
+  polygon: { outer: { x: number; y: number }[]; holes: { x: number; y: number }[][] };
+  role: FootprintRole;
+  solid_id: string | null;
 }
 
 /**
  * Extended WasmStore interface with rendering methods.
  */
 export interface WasmStoreExtended {
-  render_level(level_id: string): WasmMesh;
-  render_level_shell(level_id: string, wall_thickness: number): WasmMesh;
-  render_rooms(level_id: string): WasmMesh[];
-  render_level_combined(level_id: string, wall_thickness: number): CombinedRenderResult;
+  render_level(level_id: string, quality?: MeshQuality): WasmMesh;
+  render_level_shell(level_id: string, wall_thickness: number, quality?: MeshQuality): WasmMesh;
+  render_rooms(level_id: string, quality?: MeshQuality): WasmMesh[];
+  render_level_combined(level_id: string, wall_thickness: number, quality?: MeshQuality): CombinedRenderResult;
   // Wall and framing rendering methods
-  render_walls?(level_id: string): WasmMesh[];
-  render_wall_framing?(wall_id: string): FramingRenderItem[];
+  render_walls?(level_id: string, quality?: MeshQuality): WasmMesh[];
+  render_wall_framing?(wall_id: string, quality?: MeshQuality): FramingRenderItem[];
+  render_floor_framing?(level_id: string, quality?: MeshQuality): FramingRenderItem[];
+  render_beams_and_posts?(level_id: string, quality?: MeshQuality): StructuralRenderItem[];
+  // Levels away from focus_level_id are meshed coarser
+  render_building?(
+    building_id: string,
+    wall_thickness?: number,
+    quality?: MeshQuality,
+    focus_level_id?: string
+  ): BuildingScene;
+  // Ray in mesh coordinates (feet, z up); null when nothing is hit
+  pick_entity?(level_id: string, ray_origin: Float64Array | number[], ray_dir: Float64Array | number[]): PickResult | null;
+  create_beam?(
+    level_id: string,
+    start_x: number,
+    start_y: number,
+    end_x: number,
+    end_y: number,
+    lumber_size: string,
+    plies: number,
+    material: string
+  ): string;
+  remove_beam?(beam_id: string): void;
+  create_post?(level_id: string, x: number, y: number, lumber_size: string, plies: number, material: string): string;
+  remove_post?(post_id: string): void;
+  // Electrical methods
+  add_electrical_device?(level_id: string, kind: ElectricalDeviceKind, x: number, y: number, wall_id?: string): string;
+  remove_electrical_device?(device_id: string): void;
+  get_level_electrical_devices?(level_id: string): ElectricalDevice[];
+  auto_layout_electrical?(level_id: string): ElectricalLayoutSummary;
+  get_device_schedule?(project_id: string, format?: 'json'): DeviceSchedule;
+  get_device_schedule?(project_id: string, format: 'csv'): string;
+  // Plumbing methods
+  add_fixture?(room_id: string, kind: PlumbingFixtureKind, x: number, y: number, rotation: number): string;
+  remove_fixture?(fixture_id: string): void;
+  get_level_fixtures?(level_id: string): PlumbingFixture[];
+  check_fixture_clearances?(level_id: string): FixtureClearance[];
+  get_wet_walls?(level_id: string): WetWallAnalysis;
+  // HVAC zone methods
+  create_hvac_zone?(building_id: string, name: string): string;
+  remove_hvac_zone?(zone_id: string): void;
+  assign_room_to_zone?(room_id: string, zone_id?: string): void;
+  get_building_hvac_zones?(building_id: string): HvacZone[];
+  // Deck methods
+  add_deck?(level_id: string, kind: DeckKind, edge_index: number, offset: number, width: number, depth: number, height: number): string;
+  remove_deck?(deck_id: string): void;
+  get_level_decks?(level_id: string): Deck[];
+  get_deck_framing?(deck_id: string): DeckFramingLayout;
+  // Garage methods
+  configure_garage?(room_id: string): string;
+  remove_garage?(garage_id: string): void;
+  set_garage_slab?(garage_id: string, slab_thickness: number, slab_slope: number): void;
+  get_level_garages?(level_id: string): Garage[];
+  get_garage_slab?(garage_id: string): GarageSlab;
+  is_framing_stale?(wall_id: string): boolean;
+  get_wall_cut_list?(wall_id: string): CutList;
+  get_level_cut_list?(level_id: string): LevelCutList;
+  // Opening catalog methods
+  add_catalog_opening?(wall_id: string, code: string, position: number, sill_height: number): string;
+  import_opening_catalog?(json: string): number;
+  get_opening_catalog?(): OpeningCatalog;
+  // Opening edits
+  update_opening?(opening_id: string, changes: OpeningEdit): void;
+  // Room edits
+  update_room_boundary?(room_id: string, points: [number, number][]): number;
+  update_room_meta?(room_id: string, name: string, room_type: string): void;
+  // 2D plans
+  render_plan_svg?(level_id: string): string;
+  export_plan_dxf?(level_id: string): Uint8Array;
+  // Model export (GLB/STL bytes, IFC/OBJ text)
+  export_level_glb?(level_id: string): Uint8Array;
+  export_building_glb?(building_id: string): Uint8Array;
+  export_project_ifc?(project_id: string): string;
+  export_level_obj?(level_id: string, options?: MeshExportOptions): string;
+  export_level_stl?(level_id: string, options?: MeshExportOptions): Uint8Array;
+  // Model import
+  import_dxf?(level_id: string, source: string): ImportSummary;
+  import_ifc?(project_id: string, source: string): ImportSummary;
+  // Grid snapping and overlay
+  snap_point_to_grid?(building_id: string, x: number, y: number, tolerance: number): [number, number];
+  snap_point?(level_id: string, x: number, y: number, tolerance: number, anchor_x?: number, anchor_y?: number): SnapResult;
+  render_grid?(building_id: string, elevation: number, extension: number, quality?: MeshQuality): GridRenderItem[];
+  // Footprint edits
+  add_auxiliary_footprint?(level_id: string, points: [number, number][]): string;
+  get_level_footprints?(level_id: string): Footprint[];
+  remove_footprint?(footprint_id: string): void;
+  split_footprint?(footprint_id: string, x1: number, y1: number, x2: number, y2: number): [string, string];
+  // Site and zoning methods
+  create_site?(project_id: string): string;
+  set_site_boundary?(site_id: string, points: [number, number][]): void;
+  set_site_setbacks?(site_id: string, front: number, back: number, left: number, right: number): void;
+  set_site_zoning?(site_id: string, front_edge: number, max_lot_coverage?: number, max_height?: number): void;
+  check_zoning?(project_id: string): ZoningReport;
+  set_site_north_angle?(site_id: string, north_angle: number): void;
+  set_site_terrain?(site_id: string, points: [number, number, number][]): void;
+  get_site_contours?(site_id: string, interval: number): Contour[];
+  set_building_pad_elevation?(building_id: string, pad_elevation?: number): void;
+  get_cut_fill?(building_id: string, pad_elevation: number): CutFill;
+  // Energy model
+  get_energy_summary?(building_id: string): EnergySummary;
+  get_hvac_summary?(building_id: string): HvacSummary;
+  get_solar_report?(building_id: string): SolarReport;
+  // Named constraints
+  add_constraint?(project_id: string, name: string, kind: ConstraintKind): string;
+  list_constraints?(project_id: string): Constraint[];
+  remove_constraint?(constraint_id: string): void;
+  check_constraints?(project_id: string): ConstraintReport;
+  solve_constraints?(project_id: string, max_iterations: number): SolveReport;
+  // Saved scripts
+  save_script?(project_id: string, name: string, source: string): string;
+  list_scripts?(project_id: string): SavedScript[];
+  remove_script?(script_id: string): void;
+  run_saved_script?(
+    project_id: string,
+    name: string,
+    params?: Record<string, unknown>,
+    timeout_ms?: number
+  ): ScriptRunResult;
+  // Wall edits
+  set_wall_assembly?(wall_id: string, assembly_id: string): void;
+  set_wall_endpoints?(wall_id: string, start: [number, number], end: [number, number]): ConstraintChanges;
+  move_wall?(wall_id: string, dx: number, dy: number): ConstraintChanges;
+  split_wall?(wall_id: string, t: number): string;
+  merge_walls?(wall_a: string, wall_b: string): string;
+  trim_extend_to?(wall_id: string, other_id: string): ConstraintChanges;
+  // Level copies
+  duplicate_level?(level_id: string, new_elevation: number): string;
+  mirror_level?(level_id: string, direction: 'vertical' | 'horizontal', offset: number): void;
+  // Building templates
+  instantiate_template?(project_id: string, template: string, params?: TemplateParams): TemplateInstance;
+  list_templates?(): { name: string; displayName: string; defaults: TemplateParams }[];
   // Query methods for state derivation
-  get_level_rooms?(level_id: string): unknown;
-  get_level_walls?(level_id: string): string[];
+  get_level_rooms?(level_id: string): RoomSummary[];
+  get_level_walls?(level_id: string): WallSummary[];
+  get_room_adjacencies?(level_id: string): RoomAdjacencyGraph;
+  get_circulation?(level_id: string): CirculationReport;
   get_observable_state?(level_id: string): unknown;
-  get_mutation_count?(): number;
+  // Re-meshing only what changed
+  get_dirty_levels?(): string[];
+  get_dirty_walls?(level_id: string): string[];
+  mark_level_meshed?(level_id: string): void;
+  get_level_revision?(level_id: string): number;
+  get_entity_revision?(entity_id: string): number;
+  // Whole-store images for workers, IndexedDB and server sync
+  export_bytes?(): Uint8Array;
+  import_bytes?(bytes: Uint8Array): void;
   // Cost estimation methods
   generate_cost_estimate?(level_id: string): CostEstimate;
+  generate_quantity_takeoff?(level_id: string, format?: 'json'): QuantityTakeoff;
+  generate_quantity_takeoff?(level_id: string, format: 'csv'): string;
   set_material_price?(material_type: string, unit: string, price: number): void;
   set_labor_rate?(labor_type: string, unit: string, rate: number): void;
   get_price_table?(): PriceTable;
   import_price_table?(table: PriceTable): void;
+  export_price_csv?(region?: string): string;
+  import_price_csv?(text: string, region?: string): PriceCsvImport;
+  get_price_set_regions?(): string[];
+  set_project_price_region?(project_id: string, region?: string): void;
+  set_project_markups?(project_id: string, markups: Partial<Markups>): void;
+  compare_estimates?(snapshot_a: string, snapshot_b: string): EstimateComparison;
+  // Room finishes: { floor?, wall?, ceiling?, base?, trim? } by name
+  set_room_finishes?(room_id: string, finishes_json: string): RoomFinishes;
+  // Snapshots
+  create_snapshot?(project_id: string, label?: string): string;
+  label_snapshot?(snapshot_id: string, label: string): void;
+  get_project_snapshots?(project_id: string): Snapshot[];
   get_material_types?(): string[];
   get_labor_types?(): string[];
   get_pricing_units?(): string[];
@@ -198,20 +1089,12 @@ export class WasmGeometryLoader {
   static load(wasmMesh: WasmMesh): BufferGeometry {
     const geometry = new BufferGeometry();
 
-    geometry.setAttribute(
-      'position',
-      new BufferAttribute(wasmMesh.positions, 3)
-    );
-
-    geometry.setAttribute(
-      'normal',
-      new BufferAttribute(wasmMesh.normals, 3)
-    );
-
-    geometry.setIndex(new BufferAttribute(wasmMesh.indices, 1));
+    // Copies the buffers out once and frees the WASM mesh
+    const { positions, normals, indices } = wasmMesh.take_buffers();
 
-    // Cleanup WASM memory
-    wasmMesh.free();
+    geometry.setAttribute('position', new BufferAttribute(positions, 3));
+    geometry.setAttribute('normal', new BufferAttribute(normals, 3));
+    geometry.setIndex(new BufferAttribute(indices, 1));
 
     return geometry;
   }
@@ -21,12 +21,19 @@ export interface ObservableState {
     boundingBox: { width: number; depth: number };
     roomAdjacencies: [string, string][];
     circulation: string[];
+    /** Rooms with no route through doors or openings from an entry */
+    unreachableRooms?: string[];
   };
   constraints: {
     satisfied: string[];
     violated: string[];
     warnings: string[];
   };
+  structure?: {
+    buildingId: string;
+    name: string;
+    occupancy: 'dwelling' | 'adu' | 'garage' | 'shed';
+  } | null;
   footprint: {
     width: number;
     depth: number;
@@ -41,8 +48,14 @@ export interface RoomSummary {
   area: number;
   center: [number, number];
   dimensions: { width: number; depth: number };
+  /** Window area and openable area as shares of floor area */
+  glazingRatio?: number | null;
+  ventilationRatio?: number | null;
 }
 
+/** How a wall end meets other walls */
+export type WallJoin = 'free' | { corner: { wall: string } } | { tee: { wall: string } };
+
 export interface WallSummary {
   id: string;
   start: [number, number];
@@ -50,6 +63,8 @@ export interface WallSummary {
   thickness?: number;
   height: number;
   wallType?: string;  // 'exterior_2x6' | 'interior_partition' etc.
+  startJoin?: WallJoin;
+  endJoin?: WallJoin;
 }
 
 export interface RoomConnection {
@@ -0,0 +1,272 @@
+// Bearing continuity analysis - follows load paths down through a building
+// Each load-bearing wall above the lowest level is matched to the walls and
+// beams on the level directly below by collinear overlap of their centerlines.
+// Stretches with nothing beneath them are reported as unsupported spans.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::envelope::{collinear_overlap, point_along, EDGE_TOLERANCE};
+use crate::domain::{BeamId, BuildingId, LevelId, Point2, Wall, WallId};
+use crate::store::Store;
+
+/// Category of bearing discontinuity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BearingIssueKind {
+    /// Part of a bearing wall with no wall or beam beneath it
+    UnsupportedSpan,
+    /// Bearing wall sitting on a wall below that isn't marked load-bearing,
+    /// so its headers and plates are sized for no load
+    NonbearingSupport,
+}
+
+impl BearingIssueKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UnsupportedSpan => "unsupported_span",
+            Self::NonbearingSupport => "nonbearing_support",
+        }
+    }
+}
+
+/// A break in the load path, located along the upper wall
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BearingIssue {
+    pub kind: BearingIssueKind,
+    pub message: String,
+    pub level_id: LevelId,
+    /// The bearing wall whose load isn't carried down
+    pub wall_id: WallId,
+    /// Walls below involved in the issue
+    pub supporting_wall_ids: Vec<WallId>,
+    /// Plan segment of the upper wall affected
+    pub start: Point2,
+    pub end: Point2,
+    /// Length of the segment (feet)
+    pub length: f64,
+}
+
+/// Walls and beams below carrying an upper bearing wall
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BearingSupport {
+    pub wall_id: WallId,
+    pub supported_by: Vec<WallId>,
+    pub beam_ids: Vec<BeamId>,
+}
+
+/// Bearing continuity report for a building
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BearingReport {
+    pub building_id: BuildingId,
+    pub supports: Vec<BearingSupport>,
+    pub issues: Vec<BearingIssue>,
+}
+
+impl BearingReport {
+    pub fn new(building_id: BuildingId) -> Self {
+        Self {
+            building_id,
+            supports: Vec::new(),
+            issues: Vec::new(),
+        }
+    }
+
+    /// True when every bearing wall is carried down to the level below
+    pub fn is_continuous(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Get issues of a specific kind
+    pub fn issues_of_kind(&self, kind: BearingIssueKind) -> Vec<&BearingIssue> {
+        self.issues.iter().filter(|i| i.kind == kind).collect()
+    }
+}
+
+/// Check that bearing walls on each upper level of a building sit on walls
+/// or beams below. The lowest level bears on the foundation and isn't checked.
+pub fn check_bearing_continuity(store: &Store, building_id: BuildingId) -> Result<BearingReport> {
+    store
+        .get_building(building_id)
+        .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+
+    let mut report = BearingReport::new(building_id);
+
+    for level in store.get_building_levels_by_elevation(building_id) {
+        let Some(below) = store.get_level_below(level.id) else {
+            continue;
+        };
+        let lower_walls = store.get_level_walls(below.id);
+        let lower_beams = store.get_level_beams(below.id);
+
+        let mut upper_walls = store.get_bearing_walls(level.id);
+        upper_walls.sort_by_key(|w| w.id.to_string());
+        for wall in upper_walls {
+            let length = wall.start.distance_to(&wall.end);
+
+            // Stretches of the upper wall sitting on each wall below
+            let mut covered: Vec<(f64, f64)> = Vec::new();
+            let mut supported_by = Vec::new();
+            let mut nonbearing: Vec<&Wall> = Vec::new();
+            for lower in &lower_walls {
+                if let Some(range) = collinear_overlap(&wall.start, &wall.end, &lower.start, &lower.end) {
+                    covered.push(range);
+                    supported_by.push(lower.id);
+                    if !lower.framing_config.is_load_bearing {
+                        nonbearing.push(lower);
+                    }
+                }
+            }
+            let mut beam_ids = Vec::new();
+            for beam in &lower_beams {
+                if let Some(range) = collinear_overlap(&wall.start, &wall.end, &beam.start, &beam.end) {
+                    covered.push(range);
+                    beam_ids.push(beam.id);
+                }
+            }
+
+            for (lo, hi) in uncovered(length, &covered) {
+                report.issues.push(BearingIssue {
+                    kind: BearingIssueKind::UnsupportedSpan,
+                    message: format!(
+                        "{:.1}' of bearing wall on {} has no wall or beam beneath it on {}",
+                        hi - lo,
+                        level.name,
+                        below.name
+                    ),
+                    level_id: level.id,
+                    wall_id: wall.id,
+                    supporting_wall_ids: Vec::new(),
+                    start: point_along(&wall.start, &wall.end, lo),
+                    end: point_along(&wall.start, &wall.end, hi),
+                    length: hi - lo,
+                });
+            }
+
+            if !nonbearing.is_empty() {
+                report.issues.push(BearingIssue {
+                    kind: BearingIssueKind::NonbearingSupport,
+                    message: format!(
+                        "Bearing wall on {} sits on {} nonbearing wall(s) on {}",
+                        level.name,
+                        nonbearing.len(),
+                        below.name
+                    ),
+                    level_id: level.id,
+                    wall_id: wall.id,
+                    supporting_wall_ids: nonbearing.iter().map(|w| w.id).collect(),
+                    start: wall.start,
+                    end: wall.end,
+                    length,
+                });
+            }
+
+            if !supported_by.is_empty() || !beam_ids.is_empty() {
+                report.supports.push(BearingSupport {
+                    wall_id: wall.id,
+                    supported_by,
+                    beam_ids,
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Gaps in `covered` along [0, length] longer than the edge tolerance
+fn uncovered(length: f64, covered: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut sorted = covered.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut gaps = Vec::new();
+    let mut reached = 0.0;
+    for (lo, hi) in sorted {
+        if lo - reached > EDGE_TOLERANCE {
+            gaps.push((reached, lo));
+        }
+        reached = f64::max(reached, hi);
+    }
+    if length - reached > EDGE_TOLERANCE {
+        gaps.push((reached, length));
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    /// Two-story building with a 30' bearing wall down the middle of each
+    /// floor. The first-floor wall stops at `lower_end`.
+    fn setup(lower_end: f64) -> (Store, BuildingId, WallId, WallId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let first = store.add_level(building_id, "First", 0.0, 10.0).unwrap();
+        let second = store.add_level(building_id, "Second", 10.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Interior", vec![WallLayer::stud_2x4()])
+            .unwrap();
+
+        let lower = store
+            .create_wall(first, assembly_id, Point2::new(20.0, 0.0), Point2::new(20.0, lower_end), 9.0)
+            .unwrap();
+        let upper = store
+            .create_wall(second, assembly_id, Point2::new(20.0, 0.0), Point2::new(20.0, 30.0), 8.0)
+            .unwrap();
+
+        (store, building_id, lower, upper)
+    }
+
+    #[test]
+    fn test_stacked_bearing_walls() {
+        let (store, building_id, lower, upper) = setup(30.0);
+
+        let report = check_bearing_continuity(&store, building_id).unwrap();
+        assert!(report.is_continuous(), "unexpected issues: {:?}", report.issues);
+        assert_eq!(report.supports.len(), 1);
+        assert_eq!(report.supports[0].wall_id, upper);
+        assert_eq!(report.supports[0].supported_by, vec![lower]);
+    }
+
+    #[test]
+    fn test_unsupported_span() {
+        let (mut store, building_id, lower, upper) = setup(18.0);
+
+        let report = check_bearing_continuity(&store, building_id).unwrap();
+        let spans = report.issues_of_kind(BearingIssueKind::UnsupportedSpan);
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].wall_id, upper);
+        assert!((spans[0].length - 12.0).abs() < 1e-9);
+        assert!((spans[0].start.y - 18.0).abs() < 1e-9);
+
+        // A beam across the open span carries the rest
+        let first = store.get_wall(lower).unwrap().level_id;
+        let profile = MemberProfile::new(LumberSize::Custom { width: 1.75, depth: 11.875 }, 3);
+        let beam_id = store
+            .create_beam(first, Point2::new(20.0, 18.0), Point2::new(20.0, 30.0), profile, FramingMaterial::LVL)
+            .unwrap();
+        let report = check_bearing_continuity(&store, building_id).unwrap();
+        assert!(report.is_continuous(), "unexpected issues: {:?}", report.issues);
+        assert_eq!(report.supports[0].beam_ids, vec![beam_id]);
+        store.remove_beam(beam_id).unwrap();
+
+        // Nonbearing upper walls carry nothing, so aren't checked
+        store.walls.get_mut(&upper).unwrap().framing_config.is_load_bearing = false;
+        assert!(check_bearing_continuity(&store, building_id).unwrap().is_continuous());
+
+        // A bearing wall on a partition is flagged
+        store.walls.get_mut(&upper).unwrap().framing_config.is_load_bearing = true;
+        store.walls.get_mut(&lower).unwrap().framing_config.is_load_bearing = false;
+        let report = check_bearing_continuity(&store, building_id).unwrap();
+        let flagged = report.issues_of_kind(BearingIssueKind::NonbearingSupport);
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].supporting_wall_ids, vec![lower]);
+
+        assert!(check_bearing_continuity(&store, BuildingId::new()).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+// Circulation - how rooms on a level connect through doors and openings
+// Rooms are nodes; passages are doors on both rooms' boundaries, cased
+// openings (other openings that reach the floor), and shared boundary with
+// no wall along it. Entries are the rooms with an exterior door or a stair
+// toward grade. Walking distance runs from room center through each passage
+// to the next room's center.
+
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::egress::{level_exits, on_boundary, opening_center};
+use super::envelope::collinear_overlap;
+use crate::domain::{LevelId, OpeningId, OpeningType, Point2, Room, RoomId};
+use crate::geometry::planar_graph::interior_point;
+use crate::store::Store;
+
+/// Sill height under which an opening other than a door or window is a
+/// walk-through cased opening (feet)
+const CASED_OPENING_MAX_SILL: f64 = 0.1;
+
+/// How two rooms connect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PassageKind {
+    Door,
+    CasedOpening,
+    /// Shared boundary with no wall on it
+    Open,
+}
+
+impl PassageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Door => "door",
+            Self::CasedOpening => "cased_opening",
+            Self::Open => "open",
+        }
+    }
+}
+
+/// A way between two rooms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Passage {
+    pub room_a: RoomId,
+    pub room_b: RoomId,
+    pub kind: PassageKind,
+    pub opening_id: Option<OpeningId>,
+    /// Where the passage is crossed: the opening center or the middle of
+    /// the open boundary
+    pub location: Point2,
+}
+
+impl Passage {
+    pub fn other(&self, room_id: RoomId) -> Option<RoomId> {
+        if room_id == self.room_a {
+            Some(self.room_b)
+        } else if room_id == self.room_b {
+            Some(self.room_a)
+        } else {
+            None
+        }
+    }
+}
+
+/// Route between two rooms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CirculationPath {
+    /// Rooms from start to end
+    pub rooms: Vec<RoomId>,
+    /// Indices into the graph's passages, one per step
+    pub passages: Vec<usize>,
+    /// Walking distance from the start room's center to the end room's (feet)
+    pub length: f64,
+}
+
+/// Connectivity of the rooms on a level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CirculationGraph {
+    pub level_id: LevelId,
+    /// Rooms in the level's order
+    pub rooms: Vec<RoomId>,
+    /// Rooms with an exterior door or a stair toward grade
+    pub entries: Vec<RoomId>,
+    pub passages: Vec<Passage>,
+    /// Point inside each room that paths are measured from
+    #[serde(skip)]
+    pub centers: HashMap<RoomId, Point2>,
+}
+
+impl CirculationGraph {
+    /// Rooms one passage away
+    pub fn neighbors(&self, room_id: RoomId) -> Vec<RoomId> {
+        let mut neighbors: Vec<RoomId> = Vec::new();
+        for other in self.passages.iter().filter_map(|p| p.other(room_id)) {
+            if !neighbors.contains(&other) {
+                neighbors.push(other);
+            }
+        }
+        neighbors
+    }
+
+    /// Rooms reachable from any of the starting rooms, starts included
+    pub fn reachable_from(&self, starts: &[RoomId]) -> Vec<RoomId> {
+        let mut reached: Vec<RoomId> = Vec::new();
+        let mut queue: VecDeque<RoomId> = starts.iter().copied().collect();
+        while let Some(room_id) = queue.pop_front() {
+            if reached.contains(&room_id) {
+                continue;
+            }
+            reached.push(room_id);
+            queue.extend(self.neighbors(room_id));
+        }
+        reached
+    }
+
+    /// Rooms that can't be reached from an entry, in the level's order.
+    /// Every room when the level has no entry.
+    pub fn unreachable_from_entry(&self) -> Vec<RoomId> {
+        let reached = self.reachable_from(&self.entries);
+        self.rooms.iter().copied().filter(|id| !reached.contains(id)).collect()
+    }
+
+    /// Shortest walking route between two rooms
+    pub fn shortest_path(&self, from: RoomId, to: RoomId) -> Option<CirculationPath> {
+        self.shortest_from(&[from], to)
+    }
+
+    /// Shortest walking route from the nearest entry to a room
+    pub fn path_from_entry(&self, to: RoomId) -> Option<CirculationPath> {
+        self.shortest_from(&self.entries, to)
+    }
+
+    /// Dijkstra over room centers, starting from every room in `starts`
+    fn shortest_from(&self, starts: &[RoomId], to: RoomId) -> Option<CirculationPath> {
+        // Best distance and the (room, passage) it was reached through
+        let mut best: HashMap<RoomId, (f64, Option<(RoomId, usize)>)> = HashMap::new();
+        for start in starts {
+            if self.centers.contains_key(start) {
+                best.insert(*start, (0.0, None));
+            }
+        }
+        let mut done: Vec<RoomId> = Vec::new();
+
+        loop {
+            let (current, distance) = best
+                .iter()
+                .filter(|(id, _)| !done.contains(id))
+                .min_by(|a, b| a.1 .0.total_cmp(&b.1 .0))
+                .map(|(id, (d, _))| (*id, *d))?;
+            if current == to {
+                break;
+            }
+            done.push(current);
+
+            let center = self.centers[&current];
+            for (index, passage) in self.passages.iter().enumerate() {
+                let Some(next) = passage.other(current) else {
+                    continue;
+                };
+                let Some(next_center) = self.centers.get(&next) else {
+                    continue;
+                };
+                let step = center.distance_to(&passage.location) + passage.location.distance_to(next_center);
+                if best.get(&next).is_none_or(|(d, _)| distance + step < *d) {
+                    best.insert(next, (distance + step, Some((current, index))));
+                }
+            }
+        }
+
+        let length = best[&to].0;
+        let mut rooms = vec![to];
+        let mut passages = Vec::new();
+        let mut at = to;
+        while let Some((previous, passage)) = best[&at].1 {
+            rooms.push(previous);
+            passages.push(passage);
+            at = previous;
+        }
+        rooms.reverse();
+        passages.reverse();
+        Some(CirculationPath { rooms, passages, length })
+    }
+}
+
+/// Build the room connectivity graph of a level
+pub fn circulation_graph(store: &Store, level_id: LevelId) -> Result<CirculationGraph> {
+    let level = store
+        .get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+    let rooms: Vec<&Room> = level.room_ids.iter().filter_map(|id| store.get_room(*id)).collect();
+    let walls = store.get_level_walls(level_id);
+
+    // Doors and cased openings with their centers
+    let mut openings: Vec<(OpeningId, PassageKind, Point2)> = walls
+        .iter()
+        .flat_map(|w| store.get_wall_openings(w.id))
+        .filter_map(|o| {
+            let kind = match o.opening_type {
+                OpeningType::Door => PassageKind::Door,
+                OpeningType::Other(_) if o.sill_height <= CASED_OPENING_MAX_SILL => PassageKind::CasedOpening,
+                _ => return None,
+            };
+            opening_center(store, o).map(|center| (o.id, kind, center))
+        })
+        .collect();
+    openings.sort_by_key(|(id, _, _)| id.to_string());
+
+    let room = |id: RoomId| rooms.iter().find(|r| r.id == id);
+    let mut passages = Vec::new();
+    for adjacency in store.room_adjacency(level_id)?.adjacencies {
+        let (Some(a), Some(b)) = (room(adjacency.room_a), room(adjacency.room_b)) else {
+            continue;
+        };
+        for (opening_id, kind, center) in &openings {
+            if on_boundary(a, center) && on_boundary(b, center) {
+                passages.push(Passage {
+                    room_a: a.id,
+                    room_b: b.id,
+                    kind: *kind,
+                    opening_id: Some(*opening_id),
+                    location: *center,
+                });
+            }
+        }
+        let open = adjacency.segments.iter().find(|seg| {
+            !walls.iter().any(|w| collinear_overlap(&seg.start, &seg.end, &w.start, &w.end).is_some())
+        });
+        if let Some(seg) = open {
+            passages.push(Passage {
+                room_a: a.id,
+                room_b: b.id,
+                kind: PassageKind::Open,
+                opening_id: None,
+                location: Point2::new((seg.start.x + seg.end.x) / 2.0, (seg.start.y + seg.end.y) / 2.0),
+            });
+        }
+    }
+
+    let mut entries: Vec<RoomId> = Vec::new();
+    for exit in level_exits(store, level, &rooms) {
+        if !entries.contains(&exit.room_id) {
+            entries.push(exit.room_id);
+        }
+    }
+
+    let centers = rooms
+        .iter()
+        .map(|r| (r.id, interior_point(&r.boundary).unwrap_or_else(|| r.boundary.centroid())))
+        .collect();
+
+    Ok(CirculationGraph {
+        level_id,
+        rooms: rooms.iter().map(|r| r.id).collect(),
+        entries,
+        passages,
+        centers,
+    })
+}
+
+/// Rooms on a level with no route through doors or openings from an entry
+pub fn rooms_unreachable_from_entry(store: &Store, level_id: LevelId) -> Result<Vec<RoomId>> {
+    Ok(circulation_graph(store, level_id)?.unreachable_from_entry())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    #[test]
+    fn test_reachability_and_shortest_path() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Wall", vec![WallLayer::stud_2x4()])
+            .unwrap();
+        let rect = |x0: f64, x1: f64| {
+            Polygon2::new(vec![
+                Point2::new(x0, 0.0),
+                Point2::new(x1, 0.0),
+                Point2::new(x1, 10.0),
+                Point2::new(x0, 10.0),
+            ])
+        };
+
+        // Foyer | Hall | Den | Office, left to right
+        let foyer = store.create_room(level_id, RoomType::Foyer, "Foyer", rect(0.0, 10.0)).unwrap();
+        let hall = store.create_room(level_id, RoomType::Hallway, "Hall", rect(10.0, 20.0)).unwrap();
+        let den = store.create_room(level_id, RoomType::FamilyRoom, "Den", rect(20.0, 30.0)).unwrap();
+        let office = store.create_room(level_id, RoomType::Office, "Office", rect(30.0, 40.0)).unwrap();
+        let wall = |store: &mut Store, x0: f64, y0: f64, x1: f64, y1: f64| {
+            store
+                .create_wall(level_id, assembly_id, Point2::new(x0, y0), Point2::new(x1, y1), 9.0)
+                .unwrap()
+        };
+        let front = wall(&mut store, 0.0, 0.0, 0.0, 10.0);
+        let foyer_hall = wall(&mut store, 10.0, 0.0, 10.0, 10.0);
+        let hall_den = wall(&mut store, 20.0, 0.0, 20.0, 10.0);
+        wall(&mut store, 30.0, 0.0, 30.0, 10.0);
+
+        store.add_opening(front, OpeningType::Door, 0.5, 3.0, 6.67, 0.0).unwrap();
+        store.add_opening(foyer_hall, OpeningType::Door, 0.5, 3.0, 6.67, 0.0).unwrap();
+
+        let graph = circulation_graph(&store, level_id).unwrap();
+        assert_eq!(graph.entries, vec![foyer]);
+        assert_eq!(graph.unreachable_from_entry(), vec![den, office]);
+
+        // A cased opening joins the den; the office stays walled off
+        store
+            .add_opening(hall_den, OpeningType::Other("Cased Opening".to_string()), 0.5, 6.0, 8.0, 0.0)
+            .unwrap();
+        let graph = circulation_graph(&store, level_id).unwrap();
+        assert_eq!(graph.passages.iter().filter(|p| p.kind == PassageKind::CasedOpening).count(), 1);
+        assert_eq!(rooms_unreachable_from_entry(&store, level_id).unwrap(), vec![office]);
+
+        // Centers at y = 5 on the doors' line: 5 ft to each wall, 10 between
+        let path = graph.path_from_entry(den).unwrap();
+        assert_eq!(path.rooms, vec![foyer, hall, den]);
+        assert!((path.length - 20.0).abs() < 1e-9);
+        assert!(graph.shortest_path(foyer, office).is_none());
+
+        assert!(circulation_graph(&store, LevelId::new()).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+// Daylight and ventilation - glazing and openable area against floor area
+// Windows are attributed to the rooms whose boundary their wall runs along.
+// Catalog windows open by their clear opening (none for fixed units); other
+// windows are assumed to open over half their area, as hung and sliding
+// units do.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::egress::room_openings;
+use crate::domain::{Level, LevelId, Opening, OpeningId, OpeningType, Room, RoomId};
+use crate::store::Store;
+
+/// Share of a non-catalog window's area assumed to open
+const OPERABLE_FRACTION: f64 = 0.5;
+
+/// Glazing and openable window area of a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomDaylight {
+    pub room_id: RoomId,
+    pub level_id: LevelId,
+    pub floor_area: f64,
+    /// Total window area (sq ft)
+    pub glazing_area: f64,
+    /// Window area that opens to the outdoors (sq ft)
+    pub openable_area: f64,
+    pub window_ids: Vec<OpeningId>,
+}
+
+impl RoomDaylight {
+    /// Glazing area as a share of floor area
+    pub fn glazing_ratio(&self) -> f64 {
+        if self.floor_area > 0.0 { self.glazing_area / self.floor_area } else { 0.0 }
+    }
+
+    /// Openable area as a share of floor area
+    pub fn ventilation_ratio(&self) -> f64 {
+        if self.floor_area > 0.0 { self.openable_area / self.floor_area } else { 0.0 }
+    }
+}
+
+/// Daylight figures for every room on a level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaylightReport {
+    pub level_id: LevelId,
+    pub rooms: Vec<RoomDaylight>,
+}
+
+impl DaylightReport {
+    pub fn room(&self, room_id: RoomId) -> Option<&RoomDaylight> {
+        self.rooms.iter().find(|r| r.room_id == room_id)
+    }
+}
+
+/// Glazing and openable area for each room on a level
+pub fn analyze_daylight(store: &Store, level_id: LevelId) -> Result<DaylightReport> {
+    let level = store
+        .get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+    let rooms = level
+        .room_ids
+        .iter()
+        .filter_map(|id| store.get_room(*id))
+        .map(|room| room_daylight(store, level, room))
+        .collect();
+
+    Ok(DaylightReport { level_id, rooms })
+}
+
+/// Glazing and openable area of the windows along a room's boundary
+pub fn room_daylight(store: &Store, level: &Level, room: &Room) -> RoomDaylight {
+    let windows: Vec<&Opening> = room_openings(store, level, room)
+        .into_iter()
+        .filter(|o| o.opening_type == OpeningType::Window)
+        .collect();
+
+    RoomDaylight {
+        room_id: room.id,
+        level_id: level.id,
+        floor_area: room.area(),
+        glazing_area: windows.iter().map(|w| w.width * w.height).sum(),
+        openable_area: windows.iter().map(|w| openable_area(store, w)).sum(),
+        window_ids: windows.iter().map(|w| w.id).collect(),
+    }
+}
+
+/// Area of a window that opens: the catalog clear opening, nothing for a
+/// fixed catalog unit, else a share of the unit
+fn openable_area(store: &Store, window: &Opening) -> f64 {
+    match window.catalog_code.as_deref().and_then(|code| store.opening_catalog.window(code)) {
+        Some(unit) => unit.clear_opening.map(|c| c.area()).unwrap_or(0.0),
+        None => window.width * window.height * OPERABLE_FRACTION,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    #[test]
+    fn test_room_glazing_and_openable_area() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Wall", vec![WallLayer::stud_2x4()])
+            .unwrap();
+        let living = store
+            .create_room(level_id, RoomType::LivingRoom, "Living", Polygon2::rectangle(20.0, 15.0))
+            .unwrap();
+        let south = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+
+        let report = analyze_daylight(&store, level_id).unwrap();
+        assert_eq!(report.room(living).unwrap().glazing_ratio(), 0.0);
+
+        // A 4x5 window opens over half its area; a door adds no glazing
+        let window = store.add_opening(south, OpeningType::Window, 0.25, 4.0, 5.0, 2.0).unwrap();
+        store.add_opening(south, OpeningType::Door, 0.75, 3.0, 6.67, 0.0).unwrap();
+        let report = analyze_daylight(&store, level_id).unwrap();
+        let room = report.room(living).unwrap();
+        assert_eq!(room.window_ids, vec![window]);
+        assert!((room.glazing_area - 20.0).abs() < 1e-9);
+        assert!((room.openable_area - 10.0).abs() < 1e-9);
+        assert!((room.glazing_ratio() - 20.0 / 300.0).abs() < 1e-9);
+
+        // A catalog window opens by its clear opening
+        store.add_catalog_opening(south, "3060", 0.5, 2.0).unwrap();
+        let unit = store.opening_catalog.window("3060").unwrap().clone();
+        let report = analyze_daylight(&store, level_id).unwrap();
+        let room = report.room(living).unwrap();
+        assert!((room.glazing_area - 20.0 - unit.width * unit.height).abs() < 1e-9);
+        assert!((room.openable_area - 10.0 - unit.clear_opening.unwrap().area()).abs() < 1e-9);
+
+        assert!(analyze_daylight(&store, LevelId::new()).is_err());
+    }
+}
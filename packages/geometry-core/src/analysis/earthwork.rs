@@ -0,0 +1,154 @@
+// Earthwork - cut and fill to grade a building pad
+// The pad is the footprint of the building's lowest level, placed on the
+// site at the building's origin. Its area is sampled on a one-foot grid
+// against the site terrain: ground above the pad elevation is cut, ground
+// below it is filled. Samples off the surveyed area are left out and
+// reported.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{BuildingId, Point2, Point3, Polygon2};
+use crate::store::room_derivation::contains;
+use crate::store::Store;
+
+/// Sample spacing across the pad (feet)
+const SAMPLE_SPACING: f64 = 1.0;
+
+const CUBIC_FEET_PER_YARD: f64 = 27.0;
+
+/// Earthwork to bring a building pad to an elevation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CutFill {
+    pub building_id: BuildingId,
+    pub pad_elevation: f64,
+    /// Square feet of pad over the surveyed terrain
+    pub pad_area: f64,
+    /// Square feet of pad off the surveyed terrain, not counted
+    pub unsurveyed_area: f64,
+    pub cut_cy: f64,
+    pub fill_cy: f64,
+}
+
+impl CutFill {
+    /// Fill brought onto the site once the cut has been reused
+    pub fn import_cy(&self) -> f64 {
+        (self.fill_cy - self.cut_cy).max(0.0)
+    }
+
+    /// Cut hauled off the site once the fill has been placed
+    pub fn export_cy(&self) -> f64 {
+        (self.cut_cy - self.fill_cy).max(0.0)
+    }
+}
+
+/// Cut and fill to grade a building's pad to `pad_elevation`
+pub fn cut_fill(store: &Store, building_id: BuildingId, pad_elevation: f64) -> Result<CutFill> {
+    let building = store.get_building(building_id)
+        .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+    let terrain = store.get_project(building.project_id)
+        .and_then(|p| p.site_id)
+        .and_then(|id| store.get_site(id))
+        .and_then(|s| s.terrain.as_ref())
+        .ok_or_else(|| anyhow!("Site has no terrain"))?;
+    let lowest = store.get_building_levels_by_elevation(building_id)
+        .first()
+        .map(|l| l.id)
+        .ok_or_else(|| anyhow!("Building has no levels"))?;
+    let pad: Vec<Polygon2> = store.get_level_footprints(lowest)
+        .iter()
+        .map(|f| Polygon2::with_holes(
+            f.polygon.outer.iter().map(|p| offset(p, &building.origin)).collect(),
+            f.polygon.holes.iter().map(|h| h.iter().map(|p| offset(p, &building.origin)).collect()).collect(),
+        ))
+        .collect();
+    if pad.is_empty() {
+        return Err(anyhow!("Building has no footprint"));
+    }
+
+    let points = pad.iter().flat_map(|p| p.outer.iter());
+    let (min_x, max_x) = points.clone().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.x), hi.max(p.x)));
+    let (min_y, max_y) = points.fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+    let cell_area = SAMPLE_SPACING * SAMPLE_SPACING;
+
+    let mut result = CutFill {
+        building_id,
+        pad_elevation,
+        pad_area: 0.0,
+        unsurveyed_area: 0.0,
+        cut_cy: 0.0,
+        fill_cy: 0.0,
+    };
+    let mut y = min_y + SAMPLE_SPACING / 2.0;
+    while y < max_y {
+        let mut x = min_x + SAMPLE_SPACING / 2.0;
+        while x < max_x {
+            let sample = Point2::new(x, y);
+            if pad.iter().any(|p| contains(p, &sample)) {
+                match terrain.elevation_at(&sample) {
+                    Some(ground) => {
+                        result.pad_area += cell_area;
+                        let depth = ground - pad_elevation;
+                        if depth > 0.0 {
+                            result.cut_cy += depth * cell_area / CUBIC_FEET_PER_YARD;
+                        } else {
+                            result.fill_cy -= depth * cell_area / CUBIC_FEET_PER_YARD;
+                        }
+                    }
+                    None => result.unsurveyed_area += cell_area,
+                }
+            }
+            x += SAMPLE_SPACING;
+        }
+        y += SAMPLE_SPACING;
+    }
+    Ok(result)
+}
+
+fn offset(p: &Point2, origin: &Point3) -> Point2 {
+    Point2::new(p.x + origin.x, p.y + origin.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CodeRegion, UnitSystem};
+
+    #[test]
+    fn test_cut_fill_on_slope() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let site_id = store.create_site(project_id).unwrap();
+        // Ground rising 1' in 10' to the east, from 100' at x = 0
+        let survey: Vec<Point3> = [(0.0, 0.0), (60.0, 0.0), (60.0, 40.0), (0.0, 40.0), (30.0, 20.0)]
+            .iter()
+            .map(|&(x, y)| Point3::new(x, y, 100.0 + x / 10.0))
+            .collect();
+        store.set_site_terrain(site_id, survey).unwrap();
+
+        let building_id = store.add_building(project_id, "House").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0)).unwrap();
+
+        // A 102' pad is cut where the ground runs to 104' and filled where it
+        // drops to 100': 600 cf each
+        let earthwork = cut_fill(&store, building_id, 102.0).unwrap();
+        assert!((earthwork.pad_area - 1200.0).abs() < 1e-9);
+        assert!((earthwork.cut_cy - 600.0 / 27.0).abs() < 1e-9);
+        assert!((earthwork.fill_cy - 600.0 / 27.0).abs() < 1e-9);
+        assert_eq!(earthwork.import_cy(), 0.0);
+
+        // Moved 30' east, a third of it is off the survey
+        let building = store.get_building_mut(building_id).unwrap();
+        building.origin = Point3::new(30.0, 0.0, 0.0);
+        let earthwork = cut_fill(&store, building_id, 100.0).unwrap();
+        assert!((earthwork.unsurveyed_area - 300.0).abs() < 1e-9);
+        assert_eq!(earthwork.fill_cy, 0.0);
+        assert!(earthwork.export_cy() > 0.0);
+
+        store.set_site_terrain(site_id, Vec::new()).unwrap();
+        assert!(cut_fill(&store, building_id, 100.0).is_err());
+    }
+}
@@ -0,0 +1,389 @@
+// Egress analysis - escape openings and exit paths on a level
+// Every bedroom needs a window that qualifies as an emergency escape opening,
+// and every room needs a way out: through doors and open floor to an exterior
+// door, or to a stair leading toward grade, over the level's circulation
+// graph.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::circulation::circulation_graph;
+use super::envelope::{point_along, polygon_edges, EDGE_TOLERANCE};
+use crate::domain::opening_catalog::{
+    EGRESS_MAX_SILL, EGRESS_MIN_AREA, EGRESS_MIN_HEIGHT, EGRESS_MIN_WIDTH,
+};
+use crate::domain::{
+    Level, LevelId, Opening, OpeningId, OpeningType, Point2, Room, RoomId, RoomType, StairId,
+};
+use crate::store::room_derivation::contains;
+use crate::store::Store;
+
+/// Category of egress problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EgressIssueKind {
+    /// Bedroom with no window that qualifies for escape
+    NoEscapeOpening,
+    /// Bedroom whose only candidate windows have no catalog clear opening,
+    /// so they're judged by unit size
+    UnverifiedEscapeOpening,
+    /// Level with no exterior door or stair toward grade
+    NoExit,
+    /// Room with no route through doors or open floor to an exit
+    NoExitPath,
+}
+
+impl EgressIssueKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NoEscapeOpening => "no_escape_opening",
+            Self::UnverifiedEscapeOpening => "unverified_escape_opening",
+            Self::NoExit => "no_exit",
+            Self::NoExitPath => "no_exit_path",
+        }
+    }
+}
+
+/// An egress problem and the elements involved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EgressIssue {
+    pub kind: EgressIssueKind,
+    pub message: String,
+    pub room_ids: Vec<RoomId>,
+    pub opening_ids: Vec<OpeningId>,
+}
+
+/// Escape openings found for a bedroom
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BedroomEgress {
+    pub room_id: RoomId,
+    /// Catalog windows whose clear opening qualifies
+    pub qualifying: Vec<OpeningId>,
+    /// Windows without a catalog clear opening whose unit size could qualify
+    pub unverified: Vec<OpeningId>,
+}
+
+impl BedroomEgress {
+    pub fn has_escape(&self) -> bool {
+        !self.qualifying.is_empty()
+    }
+}
+
+/// A way off the level: an exterior door or a stair toward grade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EgressExit {
+    /// Room the exit is reached from
+    pub room_id: RoomId,
+    pub opening_id: Option<OpeningId>,
+    pub stair_id: Option<StairId>,
+}
+
+/// Egress report for a level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EgressReport {
+    pub level_id: LevelId,
+    pub bedrooms: Vec<BedroomEgress>,
+    pub exits: Vec<EgressExit>,
+    pub issues: Vec<EgressIssue>,
+}
+
+impl EgressReport {
+    pub fn new(level_id: LevelId) -> Self {
+        Self {
+            level_id,
+            bedrooms: Vec::new(),
+            exits: Vec::new(),
+            issues: Vec::new(),
+        }
+    }
+
+    /// True when nothing was flagged, including unverified windows
+    pub fn is_compliant(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Get issues of a specific kind
+    pub fn issues_of_kind(&self, kind: EgressIssueKind) -> Vec<&EgressIssue> {
+        self.issues.iter().filter(|i| i.kind == kind).collect()
+    }
+}
+
+/// Check escape openings for each bedroom on a level and an exit path from
+/// every room
+pub fn analyze_egress(store: &Store, level_id: LevelId) -> Result<EgressReport> {
+    let level = store
+        .get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+    let rooms: Vec<&Room> = level.room_ids.iter().filter_map(|id| store.get_room(*id)).collect();
+
+    let mut report = EgressReport::new(level_id);
+
+    for room in rooms.iter().filter(|r| r.room_type == RoomType::Bedroom) {
+        let bedroom = bedroom_egress(store, level, room);
+        if !bedroom.has_escape() {
+            let (kind, message) = if bedroom.unverified.is_empty() {
+                (
+                    EgressIssueKind::NoEscapeOpening,
+                    format!("{} has no window that qualifies as an emergency escape opening", room.name),
+                )
+            } else {
+                (
+                    EgressIssueKind::UnverifiedEscapeOpening,
+                    format!("{} egress window is sized by its unit; verify the clear opening", room.name),
+                )
+            };
+            report.issues.push(EgressIssue {
+                kind,
+                message,
+                room_ids: vec![room.id],
+                opening_ids: bedroom.unverified.clone(),
+            });
+        }
+        report.bedrooms.push(bedroom);
+    }
+
+    if rooms.is_empty() {
+        return Ok(report);
+    }
+
+    report.exits = level_exits(store, level, &rooms);
+    if report.exits.is_empty() {
+        report.issues.push(EgressIssue {
+            kind: EgressIssueKind::NoExit,
+            message: format!("{} has no exterior door or stair toward grade", level.name),
+            room_ids: Vec::new(),
+            opening_ids: Vec::new(),
+        });
+        return Ok(report);
+    }
+
+    // Walk out from the exits; any room not reached is cut off
+    let exit_rooms: Vec<RoomId> = report.exits.iter().map(|e| e.room_id).collect();
+    let reached = circulation_graph(store, level_id)?.reachable_from(&exit_rooms);
+    for room in &rooms {
+        if !reached.contains(&room.id) {
+            report.issues.push(EgressIssue {
+                kind: EgressIssueKind::NoExitPath,
+                message: format!("{} has no path through doors to an exit", room.name),
+                room_ids: vec![room.id],
+                opening_ids: Vec::new(),
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Windows on a bedroom's boundary sorted into qualifying and unverified
+pub fn bedroom_egress(store: &Store, level: &Level, room: &Room) -> BedroomEgress {
+    let mut bedroom = BedroomEgress {
+        room_id: room.id,
+        qualifying: Vec::new(),
+        unverified: Vec::new(),
+    };
+    for window in room_openings(store, level, room)
+        .into_iter()
+        .filter(|o| o.opening_type == OpeningType::Window)
+    {
+        match store.opening_meets_egress(window.id) {
+            Some(true) => bedroom.qualifying.push(window.id),
+            Some(false) => {}
+            None if window.catalog_code.is_none() && unit_meets_egress(window) => {
+                bedroom.unverified.push(window.id)
+            }
+            None => {}
+        }
+    }
+    bedroom
+}
+
+/// Openings in walls along a room's boundary, located by their center
+pub fn room_openings<'a>(store: &'a Store, level: &Level, room: &Room) -> Vec<&'a Opening> {
+    let mut openings: Vec<&Opening> = store
+        .get_level_walls(level.id)
+        .into_iter()
+        .flat_map(|wall| store.get_wall_openings(wall.id))
+        .filter(|o| opening_center(store, o).is_some_and(|c| on_boundary(room, &c)))
+        .collect();
+    openings.sort_by_key(|o| o.id.to_string());
+    openings
+}
+
+/// Whether a window's unit size and sill could meet egress, for windows
+/// without a catalog clear opening
+fn unit_meets_egress(opening: &Opening) -> bool {
+    opening.width * 12.0 >= EGRESS_MIN_WIDTH
+        && opening.height * 12.0 >= EGRESS_MIN_HEIGHT
+        && opening.width * opening.height >= EGRESS_MIN_AREA
+        && opening.sill_height * 12.0 <= EGRESS_MAX_SILL
+}
+
+/// Exterior doors, and stairs leading toward grade: down from an upper
+/// level, or up from a basement
+pub(crate) fn level_exits(store: &Store, level: &Level, rooms: &[&Room]) -> Vec<EgressExit> {
+    let mut exits = Vec::new();
+
+    for room in rooms {
+        for door in room_openings(store, level, room)
+            .into_iter()
+            .filter(|o| o.opening_type == OpeningType::Door)
+        {
+            let Some(center) = opening_center(store, door) else {
+                continue;
+            };
+            let exterior = door.door_properties.as_ref().is_some_and(|p| p.is_exterior)
+                || rooms.iter().filter(|r| on_boundary(r, &center)).count() == 1;
+            if exterior {
+                exits.push(EgressExit {
+                    room_id: room.id,
+                    opening_id: Some(door.id),
+                    stair_id: None,
+                });
+            }
+        }
+    }
+
+    let mut stairs: Vec<_> = store.stairs.values().collect();
+    stairs.sort_by_key(|s| s.id.to_string());
+    for stair in stairs {
+        let Ok(run) = store.get_stair_run(stair.id) else {
+            continue;
+        };
+        let landing = if stair.to_level_id == level.id {
+            stair.end(&run)
+        } else if stair.from_level_id == level.id && level.is_basement {
+            stair.start
+        } else {
+            continue;
+        };
+        if let Some(room) = rooms.iter().find(|r| contains(&r.boundary, &landing)) {
+            exits.push(EgressExit {
+                room_id: room.id,
+                opening_id: None,
+                stair_id: Some(stair.id),
+            });
+        }
+    }
+
+    exits
+}
+
+/// Plan point at the middle of an opening
+pub(crate) fn opening_center(store: &Store, opening: &Opening) -> Option<Point2> {
+    let wall = store.get_wall(opening.wall_id)?;
+    let length = wall.start.distance_to(&wall.end);
+    Some(point_along(&wall.start, &wall.end, opening.position_along_wall * length))
+}
+
+/// Whether a point lies on one of a room's boundary edges
+pub(crate) fn on_boundary(room: &Room, point: &Point2) -> bool {
+    polygon_edges(&room.boundary).iter().any(|(a, b)| {
+        let length = a.distance_to(b);
+        if length < 1e-10 {
+            return false;
+        }
+        let (ux, uy) = ((b.x - a.x) / length, (b.y - a.y) / length);
+        let along = (point.x - a.x) * ux + (point.y - a.y) * uy;
+        let across = ((point.x - a.x) * uy - (point.y - a.y) * ux).abs();
+        along >= -EDGE_TOLERANCE && along <= length + EDGE_TOLERANCE && across <= EDGE_TOLERANCE
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    /// A 12x12 bedroom off a 4' hall, with a wall between them and
+    /// exterior walls along the south face
+    fn setup() -> (Store, LevelId, RoomId, RoomId, WallId, WallId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Wall", vec![WallLayer::stud_2x4()])
+            .unwrap();
+
+        let bedroom = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(12.0, 12.0))
+            .unwrap();
+        let hall = Polygon2::new(vec![
+            Point2::new(12.0, 0.0),
+            Point2::new(16.0, 0.0),
+            Point2::new(16.0, 12.0),
+            Point2::new(12.0, 12.0),
+        ]);
+        let hall = store.create_room(level_id, RoomType::Hallway, "Hall", hall).unwrap();
+
+        let south = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(16.0, 0.0), 9.0)
+            .unwrap();
+        let partition = store
+            .create_wall(level_id, assembly_id, Point2::new(12.0, 0.0), Point2::new(12.0, 12.0), 9.0)
+            .unwrap();
+
+        (store, level_id, bedroom, hall, south, partition)
+    }
+
+    #[test]
+    fn test_bedroom_escape_openings() {
+        let (mut store, level_id, bedroom, _, south, _) = setup();
+
+        let report = analyze_egress(&store, level_id).unwrap();
+        assert_eq!(report.issues_of_kind(EgressIssueKind::NoEscapeOpening)[0].room_ids, vec![bedroom]);
+
+        // Too small to climb through, then large enough but unverified
+        store.add_opening(south, OpeningType::Window, 0.2, 2.0, 2.0, 3.0).unwrap();
+        let report = analyze_egress(&store, level_id).unwrap();
+        assert_eq!(report.issues_of_kind(EgressIssueKind::NoEscapeOpening).len(), 1);
+        let large = store.add_opening(south, OpeningType::Window, 0.4, 3.0, 5.0, 2.0).unwrap();
+        let report = analyze_egress(&store, level_id).unwrap();
+        assert_eq!(report.issues_of_kind(EgressIssueKind::UnverifiedEscapeOpening)[0].opening_ids, vec![large]);
+
+        // A catalog window with a qualifying clear opening
+        let window = store.add_catalog_opening(south, "3060", 0.3, 2.0).unwrap();
+        let report = analyze_egress(&store, level_id).unwrap();
+        assert_eq!(report.bedrooms[0].qualifying, vec![window]);
+        assert!(report.issues_of_kind(EgressIssueKind::UnverifiedEscapeOpening).is_empty());
+
+        assert!(analyze_egress(&store, LevelId::new()).is_err());
+    }
+
+    #[test]
+    fn test_exit_paths() {
+        let (mut store, level_id, bedroom, hall, south, partition) = setup();
+        store.add_catalog_opening(south, "3060", 0.3, 2.0).unwrap();
+
+        let report = analyze_egress(&store, level_id).unwrap();
+        assert_eq!(report.issues_of_kind(EgressIssueKind::NoExit).len(), 1);
+
+        // An exterior door from the hall; the bedroom is still walled off
+        let front = store.add_catalog_opening(south, "3068", 14.0 / 16.0, 0.0).unwrap();
+        let report = analyze_egress(&store, level_id).unwrap();
+        assert_eq!(report.exits.len(), 1);
+        assert_eq!(report.exits[0].room_id, hall);
+        assert_eq!(report.exits[0].opening_id, Some(front));
+        assert_eq!(report.issues_of_kind(EgressIssueKind::NoExitPath)[0].room_ids, vec![bedroom]);
+
+        // A door through the partition opens the path
+        store.add_opening(partition, OpeningType::Door, 0.5, 2.67, 6.67, 0.0).unwrap();
+        assert!(analyze_egress(&store, level_id).unwrap().is_compliant());
+
+        // Upper levels exit down the stair
+        let building_id = store.get_level(level_id).unwrap().building_id;
+        let upper = store.add_level(building_id, "Second Floor", 9.0, 8.0).unwrap();
+        store
+            .create_room(upper, RoomType::Office, "Loft", Polygon2::rectangle(16.0, 16.0))
+            .unwrap();
+        assert_eq!(analyze_egress(&store, upper).unwrap().issues_of_kind(EgressIssueKind::NoExit).len(), 1);
+        let stair_id = store
+            .create_stair(level_id, upper, Point2::new(14.0, 1.0), 90.0, 3.0, 7.75, 10.0)
+            .unwrap();
+        let report = analyze_egress(&store, upper).unwrap();
+        assert!(report.is_compliant(), "unexpected issues: {:?}", report.issues);
+        assert_eq!(report.exits[0].stair_id, Some(stair_id));
+    }
+}
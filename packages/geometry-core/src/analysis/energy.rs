@@ -0,0 +1,471 @@
+// Energy model - envelope UA (heat loss per degree) for a building
+// Each envelope component contributes U x A: exterior walls net of their
+// openings, windows and doors, the top-floor ceiling under the roof, and the
+// floor or foundation under the lowest level. Opaque U-factors come from the
+// layer R-values in series plus air films; slab edges and basement walls use
+// perimeter F- and C-factors for uninsulated concrete.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::envelope::{collinear_overlap, polygon_edges};
+use super::solar::{solar_report, FacadeSolar};
+use crate::domain::{
+    BuildingId, FloorSlab, FoundationType, Level, LevelId, Opening, OpeningId,
+    OpeningType, SlabBoundary, Wall, WallId, WindowProperties,
+};
+use crate::store::Store;
+
+/// Inside air film resistance (h·ft²·°F/Btu)
+pub const INSIDE_AIR_FILM: f64 = 0.68;
+/// Outside air film resistance, 15 mph wind
+pub const OUTSIDE_AIR_FILM: f64 = 0.17;
+/// Insulated steel or fiberglass exterior door
+const DOOR_U_FACTOR: f64 = 0.20;
+/// Uninsulated slab on grade, per foot of perimeter (Btu/h·ft·°F)
+const SLAB_EDGE_F_FACTOR: f64 = 0.73;
+/// Uninsulated 8" concrete basement wall (Btu/h·ft²·°F)
+const BASEMENT_WALL_C_FACTOR: f64 = 1.14;
+
+/// Part of the envelope a component belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnergyComponentKind {
+    /// Opaque exterior wall, net of openings
+    Wall,
+    Window,
+    Door,
+    /// Top-floor ceiling under the roof
+    Ceiling,
+    /// Framed floor over a crawlspace
+    Floor,
+    /// Perimeter of a slab on grade or basement slab
+    SlabEdge,
+    /// Below-grade basement wall
+    FoundationWall,
+}
+
+impl EnergyComponentKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Wall => "wall",
+            Self::Window => "window",
+            Self::Door => "door",
+            Self::Ceiling => "ceiling",
+            Self::Floor => "floor",
+            Self::SlabEdge => "slab_edge",
+            Self::FoundationWall => "foundation_wall",
+        }
+    }
+}
+
+/// One contribution to the envelope UA
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyComponent {
+    pub kind: EnergyComponentKind,
+    pub level_id: LevelId,
+    pub wall_id: Option<WallId>,
+    pub opening_id: Option<OpeningId>,
+    /// Square feet, or linear feet for a slab edge
+    pub quantity: f64,
+    /// Btu/h·°F per unit of quantity
+    pub u_factor: f64,
+    pub ua: f64,
+    /// Whether the component has insulation; glazing and doors count as insulated
+    pub insulated: bool,
+}
+
+impl EnergyComponent {
+    fn new(kind: EnergyComponentKind, level_id: LevelId, quantity: f64, u_factor: f64, insulated: bool) -> Self {
+        Self {
+            kind,
+            level_id,
+            wall_id: None,
+            opening_id: None,
+            quantity,
+            u_factor,
+            ua: quantity * u_factor,
+            insulated,
+        }
+    }
+}
+
+/// Envelope components of one level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelEnergy {
+    pub level_id: LevelId,
+    pub name: String,
+    pub ua: f64,
+    pub components: Vec<EnergyComponent>,
+}
+
+/// Totals for one kind of component across the building
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsulationSummary {
+    pub kind: EnergyComponentKind,
+    /// Square feet, or linear feet for slab edges
+    pub quantity: f64,
+    pub ua: f64,
+    /// Area-weighted R-value including air films; None for slab edges
+    pub effective_r: Option<f64>,
+    /// Quantity of this kind with no insulation
+    pub uninsulated: f64,
+}
+
+/// Envelope UA for a building, by level and by component kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergySummary {
+    pub building_id: BuildingId,
+    /// Whole-envelope heat loss (Btu/h·°F)
+    pub ua: f64,
+    pub levels: Vec<LevelEnergy>,
+    pub insulation: Vec<InsulationSummary>,
+    /// Window area and clear-day solar gain by facade
+    pub solar: Vec<FacadeSolar>,
+}
+
+impl EnergySummary {
+    pub fn components(&self) -> impl Iterator<Item = &EnergyComponent> {
+        self.levels.iter().flat_map(|l| &l.components)
+    }
+
+    pub fn insulation_for(&self, kind: EnergyComponentKind) -> Option<&InsulationSummary> {
+        self.insulation.iter().find(|s| s.kind == kind)
+    }
+}
+
+/// U-factor of an opaque assembly from its layer R-value
+pub fn opaque_u_factor(layers_r: f64) -> f64 {
+    1.0 / (layers_r + INSIDE_AIR_FILM + OUTSIDE_AIR_FILM)
+}
+
+/// U-factor of an opening by type: the window's rated U, an insulated door
+/// otherwise. Other openings are treated as glazing.
+pub fn opening_u_factor(opening: &Opening) -> f64 {
+    match opening.opening_type {
+        OpeningType::Door => DOOR_U_FACTOR,
+        OpeningType::Window | OpeningType::Other(_) => opening.window_properties
+            .as_ref()
+            .map(|w| w.u_value)
+            .unwrap_or_else(|| WindowProperties::double_pane().u_value),
+    }
+}
+
+/// Compute the envelope UA of a building level by level
+pub fn calculate_energy(store: &Store, building_id: BuildingId) -> Result<EnergySummary> {
+    store
+        .get_building(building_id)
+        .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+
+    let levels = store.get_building_levels_by_elevation(building_id);
+    let lowest = levels.first().map(|l| l.id);
+    let highest = levels.last().map(|l| l.id);
+
+    let mut summary = EnergySummary {
+        building_id,
+        ua: 0.0,
+        levels: Vec::new(),
+        insulation: Vec::new(),
+        solar: solar_report(store, building_id)?.facades,
+    };
+
+    for level in levels {
+        let mut components = wall_components(store, level);
+        if Some(level.id) == highest {
+            components.extend(ceiling_components(store, level));
+        }
+        if Some(level.id) == lowest {
+            components.extend(foundation_components(store, level));
+        }
+
+        let ua = components.iter().map(|c| c.ua).sum();
+        summary.ua += ua;
+        summary.levels.push(LevelEnergy {
+            level_id: level.id,
+            name: level.name.clone(),
+            ua,
+            components,
+        });
+    }
+
+    let mut kinds: Vec<EnergyComponentKind> = Vec::new();
+    for c in summary.components() {
+        if !kinds.contains(&c.kind) {
+            kinds.push(c.kind);
+        }
+    }
+    summary.insulation = kinds
+        .into_iter()
+        .map(|kind| {
+            let of_kind: Vec<&EnergyComponent> = summary.components().filter(|c| c.kind == kind).collect();
+            let quantity: f64 = of_kind.iter().map(|c| c.quantity).sum();
+            let ua: f64 = of_kind.iter().map(|c| c.ua).sum();
+            InsulationSummary {
+                kind,
+                quantity,
+                ua,
+                effective_r: (kind != EnergyComponentKind::SlabEdge && ua > 0.0).then(|| quantity / ua),
+                uninsulated: of_kind.iter().filter(|c| !c.insulated).map(|c| c.quantity).sum(),
+            }
+        })
+        .collect();
+
+    Ok(summary)
+}
+
+/// Exterior walls on the footprint, and the openings in them
+fn wall_components(store: &Store, level: &Level) -> Vec<EnergyComponent> {
+    let Some(footprint) = store.get_level_footprint(level.id) else {
+        return Vec::new();
+    };
+    let edges = polygon_edges(&footprint.polygon);
+
+    let mut walls: Vec<&Wall> = store.get_level_walls(level.id);
+    walls.sort_by_key(|w| w.id.to_string());
+
+    let mut components = Vec::new();
+    for wall in walls {
+        let exterior_length: f64 = edges
+            .iter()
+            .filter_map(|(a, b)| collinear_overlap(&wall.start, &wall.end, a, b))
+            .map(|(lo, hi)| hi - lo)
+            .sum::<f64>()
+            .min(wall.length());
+        if exterior_length <= 0.0 {
+            continue;
+        }
+
+        let mut opening_area = 0.0;
+        for opening in store.get_wall_openings(wall.id) {
+            let area = opening.width * opening.height;
+            opening_area += area;
+            let kind = match opening.opening_type {
+                OpeningType::Door => EnergyComponentKind::Door,
+                _ => EnergyComponentKind::Window,
+            };
+            let mut component = EnergyComponent::new(kind, level.id, area, opening_u_factor(opening), true);
+            component.wall_id = Some(wall.id);
+            component.opening_id = Some(opening.id);
+            components.push(component);
+        }
+
+        let (r_value, insulated) = store
+            .get_wall_assembly(wall.assembly_id)
+            .map(|a| (a.total_r_value(), a.is_insulated()))
+            .unwrap_or((0.0, false));
+        let net_area = (exterior_length * wall.height - opening_area).max(0.0);
+        let mut component = EnergyComponent::new(
+            EnergyComponentKind::Wall,
+            level.id,
+            net_area,
+            opaque_u_factor(r_value),
+            insulated,
+        );
+        component.wall_id = Some(wall.id);
+        components.push(component);
+    }
+    components
+}
+
+/// Ceiling under the roof: a whole-level ceiling assembly if there is one,
+/// else room ceilings, with any uncovered area counted as uninsulated
+fn ceiling_components(store: &Store, level: &Level) -> Vec<EnergyComponent> {
+    let Some(area) = level_area(store, level) else {
+        return Vec::new();
+    };
+    let ceilings = store.get_level_ceilings(level.id);
+
+    if let Some(ceiling) = ceilings.iter().find(|c| c.boundary == SlabBoundary::Level) {
+        let insulated = ceiling.is_insulated();
+        return vec![EnergyComponent::new(
+            EnergyComponentKind::Ceiling,
+            level.id,
+            area,
+            opaque_u_factor(ceiling.r_value()),
+            insulated,
+        )];
+    }
+
+    let mut components = Vec::new();
+    let mut covered = 0.0;
+    for ceiling in ceilings {
+        let Some(outline) = store.slab_outline(level.id, ceiling.boundary) else {
+            continue;
+        };
+        let ceiling_area = outline.area();
+        covered += ceiling_area;
+        let insulated = ceiling.is_insulated();
+        components.push(EnergyComponent::new(
+            EnergyComponentKind::Ceiling,
+            level.id,
+            ceiling_area,
+            opaque_u_factor(ceiling.r_value()),
+            insulated,
+        ));
+    }
+    if area - covered > 1e-6 {
+        components.push(EnergyComponent::new(
+            EnergyComponentKind::Ceiling,
+            level.id,
+            area - covered,
+            opaque_u_factor(0.0),
+            false,
+        ));
+    }
+    components
+}
+
+/// Floor or foundation under the lowest level, by foundation type. Without
+/// a foundation the ground floor isn't counted.
+fn foundation_components(store: &Store, level: &Level) -> Vec<EnergyComponent> {
+    let (Some(foundation), Some(footprint)) = (
+        store.get_level_foundation(level.id),
+        store.get_level_footprint(level.id),
+    ) else {
+        return Vec::new();
+    };
+    let perimeter = footprint.polygon.perimeter();
+
+    match foundation.foundation_type {
+        FoundationType::SlabOnGrade => vec![EnergyComponent::new(
+            EnergyComponentKind::SlabEdge,
+            level.id,
+            perimeter,
+            SLAB_EDGE_F_FACTOR,
+            false,
+        )],
+        FoundationType::Basement => vec![
+            EnergyComponent::new(
+                EnergyComponentKind::FoundationWall,
+                level.id,
+                perimeter * foundation.dimensions.stem_wall_height,
+                BASEMENT_WALL_C_FACTOR,
+                false,
+            ),
+            EnergyComponent::new(
+                EnergyComponentKind::SlabEdge,
+                level.id,
+                perimeter,
+                SLAB_EDGE_F_FACTOR,
+                false,
+            ),
+        ],
+        FoundationType::Crawlspace => {
+            let floor = store
+                .get_level_floor_slabs(level.id)
+                .into_iter()
+                .find(|s| s.boundary == SlabBoundary::Level);
+            let (r_value, insulated) = match floor {
+                Some(slab) => (slab.r_value(), slab.is_insulated()),
+                None => (FloorSlab::new(level.id, SlabBoundary::Level, FloorSlab::framed_layers()).r_value(), false),
+            };
+            vec![EnergyComponent::new(
+                EnergyComponentKind::Floor,
+                level.id,
+                footprint.polygon.area(),
+                opaque_u_factor(r_value),
+                insulated,
+            )]
+        }
+    }
+}
+
+/// Plan area of a level: its footprint, else the sum of its rooms
+fn level_area(store: &Store, level: &Level) -> Option<f64> {
+    if let Some(footprint) = store.get_level_footprint(level.id) {
+        return Some(footprint.polygon.area());
+    }
+    let rooms = store.get_level_rooms(level.id);
+    (!rooms.is_empty()).then(|| rooms.iter().map(|r| r.area()).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+    use crate::analysis::Facing;
+
+    /// One-story 30x40 house on a slab with 8' 2x6 walls
+    fn setup() -> (Store, BuildingId, LevelId, WallId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "House").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(30.0, 40.0)).unwrap();
+        let preset = WallAssembly::exterior_2x6();
+        let assembly_id = store.create_wall_assembly(preset.name, preset.layers).unwrap();
+
+        let corners = [(0.0, 0.0), (30.0, 0.0), (30.0, 40.0), (0.0, 40.0)];
+        let mut first = None;
+        for i in 0..4 {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            let id = store
+                .create_wall(level_id, assembly_id, Point2::new(a.0, a.1), Point2::new(b.0, b.1), 8.0)
+                .unwrap();
+            first.get_or_insert(id);
+        }
+        (store, building_id, level_id, first.unwrap())
+    }
+
+    #[test]
+    fn test_wall_and_opening_ua() {
+        let (mut store, building_id, _, wall_id) = setup();
+        let wall_r = WallAssembly::exterior_2x6().total_r_value();
+
+        let summary = calculate_energy(&store, building_id).unwrap();
+        let walls = summary.insulation_for(EnergyComponentKind::Wall).unwrap();
+        assert!((walls.quantity - 140.0 * 8.0).abs() < 1e-9);
+        assert!((walls.ua - 1120.0 * opaque_u_factor(wall_r)).abs() < 1e-9);
+        assert!((walls.effective_r.unwrap() - (wall_r + 0.85)).abs() < 1e-9);
+        assert_eq!(walls.uninsulated, 0.0);
+
+        // A 3x5 window and a 3x6.67 door come out of the wall area
+        store.add_opening(wall_id, OpeningType::Window, 0.3, 3.0, 5.0, 2.0).unwrap();
+        store.add_opening(wall_id, OpeningType::Door, 0.7, 3.0, 6.67, 0.0).unwrap();
+        let summary = calculate_energy(&store, building_id).unwrap();
+        let window = summary.insulation_for(EnergyComponentKind::Window).unwrap();
+        assert!((window.ua - 15.0 * 0.30).abs() < 1e-9);
+        let door = summary.insulation_for(EnergyComponentKind::Door).unwrap();
+        assert!((door.ua - 20.01 * DOOR_U_FACTOR).abs() < 1e-9);
+        let walls = summary.insulation_for(EnergyComponentKind::Wall).unwrap();
+        assert!((walls.quantity - (1120.0 - 35.01)).abs() < 1e-9);
+        assert!((summary.ua - summary.levels[0].ua).abs() < 1e-9);
+        // The window faces south, off the first wall along y = 0
+        let south = summary.solar.iter().find(|f| f.facing == Facing::South).unwrap();
+        assert!((south.window_area - 15.0).abs() < 1e-9);
+        assert!(south.heating_gain > 0.0);
+
+        assert!(calculate_energy(&store, BuildingId::new()).is_err());
+    }
+
+    #[test]
+    fn test_ceiling_and_foundation_ua() {
+        let (mut store, building_id, level_id, _) = setup();
+
+        // Without a ceiling assembly the top floor is flagged uninsulated
+        let summary = calculate_energy(&store, building_id).unwrap();
+        let ceiling = summary.insulation_for(EnergyComponentKind::Ceiling).unwrap();
+        assert_eq!(ceiling.uninsulated, 1200.0);
+        assert!(summary.insulation_for(EnergyComponentKind::SlabEdge).is_none());
+
+        store.create_ceiling(level_id, SlabBoundary::Level, CeilingAssembly::insulated_layers()).unwrap();
+        store.create_foundation(level_id, FoundationType::SlabOnGrade).unwrap();
+        let summary = calculate_energy(&store, building_id).unwrap();
+        let ceiling = summary.insulation_for(EnergyComponentKind::Ceiling).unwrap();
+        assert_eq!(ceiling.uninsulated, 0.0);
+        assert!((ceiling.effective_r.unwrap() - (38.45 + 0.85)).abs() < 1e-9);
+        let slab = summary.insulation_for(EnergyComponentKind::SlabEdge).unwrap();
+        assert!((slab.ua - 140.0 * SLAB_EDGE_F_FACTOR).abs() < 1e-9);
+        assert_eq!(slab.effective_r, None);
+
+        // A second story takes the ceiling; the first floor keeps the slab
+        let upper = store.add_level(building_id, "Second Floor", 9.0, 9.0).unwrap();
+        store.set_level_footprint(upper, Polygon2::rectangle(30.0, 40.0)).unwrap();
+        let summary = calculate_energy(&store, building_id).unwrap();
+        let upper_kinds: Vec<_> = summary.levels[1].components.iter().map(|c| c.kind).collect();
+        assert_eq!(upper_kinds, vec![EnergyComponentKind::Ceiling]);
+        assert_eq!(summary.insulation_for(EnergyComponentKind::Ceiling).unwrap().uninsulated, 1200.0);
+    }
+}
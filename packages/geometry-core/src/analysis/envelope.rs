@@ -0,0 +1,538 @@
+// Thermal envelope analysis - finds gaps in the insulated boundary of a level
+// Exterior edges come from the level footprint; walls and rooms are matched to
+// those edges by collinear overlap of their centerlines and boundaries
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{LevelId, Point2, Polygon2, Room, RoomId, Wall, WallId};
+use crate::store::Store;
+
+/// Distance (feet) within which a wall centerline is considered to run along an edge.
+/// Room boundaries are often drawn to wall faces, so this covers half a 2x6 wall.
+pub(crate) const EDGE_TOLERANCE: f64 = 0.5;
+
+/// Category of envelope discontinuity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvelopeIssueKind {
+    /// Wall on the footprint boundary whose assembly has no insulation layer
+    UninsulatedExteriorWall,
+    /// Conditioned room edge on the exterior that no insulated wall covers
+    UnprotectedExteriorEdge,
+    /// Unconditioned room (garage, porch) sharing an edge with a conditioned
+    /// room without an insulated wall between them. Fire-rated separation is a
+    /// code compliance concern and is not checked here.
+    UninsulatedSeparation,
+}
+
+impl EnvelopeIssueKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UninsulatedExteriorWall => "uninsulated_exterior_wall",
+            Self::UnprotectedExteriorEdge => "unprotected_exterior_edge",
+            Self::UninsulatedSeparation => "uninsulated_separation",
+        }
+    }
+}
+
+/// A single leakage point, located in plan and tied to the entities to fix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvelopeIssue {
+    pub kind: EnvelopeIssueKind,
+    pub message: String,
+    pub wall_ids: Vec<WallId>,
+    pub room_ids: Vec<RoomId>,
+    /// Plan segment where the discontinuity occurs
+    pub start: Point2,
+    pub end: Point2,
+}
+
+/// Envelope leakage report for one level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvelopeReport {
+    pub level_id: LevelId,
+    pub issues: Vec<EnvelopeIssue>,
+}
+
+impl EnvelopeReport {
+    pub fn new(level_id: LevelId) -> Self {
+        Self {
+            level_id,
+            issues: Vec::new(),
+        }
+    }
+
+    /// True when no discontinuities were found
+    pub fn is_continuous(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Get issues of a specific kind
+    pub fn issues_of_kind(&self, kind: EnvelopeIssueKind) -> Vec<&EnvelopeIssue> {
+        self.issues.iter().filter(|i| i.kind == kind).collect()
+    }
+}
+
+/// Check the thermal envelope of a level for discontinuities
+///
+/// Exterior checks need a footprint; without one only the conditioned /
+/// unconditioned separation check runs.
+pub fn analyze_envelope(store: &Store, level_id: LevelId) -> Result<EnvelopeReport> {
+    store
+        .get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+    let walls = store.get_level_walls(level_id);
+    let rooms = store.get_level_rooms(level_id);
+    let exterior: Vec<(Point2, Point2)> = store
+        .get_level_footprint(level_id)
+        .map(|fp| polygon_edges(&fp.polygon))
+        .unwrap_or_default();
+
+    let is_insulated = |wall: &Wall| {
+        store
+            .get_wall_assembly(wall.assembly_id)
+            .map(|a| a.is_insulated())
+            .unwrap_or(false)
+    };
+
+    let mut report = EnvelopeReport::new(level_id);
+
+    // Exterior walls without insulation
+    for wall in &walls {
+        let on_exterior = exterior
+            .iter()
+            .any(|(a, b)| collinear_overlap(a, b, &wall.start, &wall.end).is_some());
+        if !on_exterior || is_insulated(wall) {
+            continue;
+        }
+
+        // Walls that don't enclose any conditioned room sit outside the envelope
+        let bordering: Vec<&Room> = rooms
+            .iter()
+            .filter(|r| {
+                polygon_edges(&r.boundary)
+                    .iter()
+                    .any(|(a, b)| collinear_overlap(a, b, &wall.start, &wall.end).is_some())
+            })
+            .copied()
+            .collect();
+        if !bordering.is_empty() && bordering.iter().all(|r| r.is_conditioned() != Some(true)) {
+            continue;
+        }
+
+        let assembly_name = store
+            .get_wall_assembly(wall.assembly_id)
+            .map(|a| a.name.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        report.issues.push(EnvelopeIssue {
+            kind: EnvelopeIssueKind::UninsulatedExteriorWall,
+            message: format!(
+                "Exterior wall uses assembly '{}' which has no insulation layer",
+                assembly_name
+            ),
+            wall_ids: vec![wall.id],
+            room_ids: bordering.iter().map(|r| r.id).collect(),
+            start: wall.start,
+            end: wall.end,
+        });
+    }
+
+    // Conditioned room edges exposed to the exterior
+    for room in rooms.iter().filter(|r| r.is_conditioned() == Some(true)) {
+        for (a, b) in polygon_edges(&room.boundary) {
+            let exposed: Vec<(f64, f64)> = exterior
+                .iter()
+                .filter_map(|(e0, e1)| collinear_overlap(&a, &b, e0, e1))
+                .collect();
+            if exposed.is_empty() {
+                continue;
+            }
+
+            let (covered, uninsulated) = wall_coverage(&walls, &a, &b, &is_insulated);
+            let gap = uncovered_length(&exposed, &covered);
+            if gap <= EDGE_TOLERANCE {
+                continue;
+            }
+
+            report.issues.push(EnvelopeIssue {
+                kind: EnvelopeIssueKind::UnprotectedExteriorEdge,
+                message: format!(
+                    "{} has {:.1} ft of exterior edge without an insulated wall",
+                    room.name, gap
+                ),
+                wall_ids: uninsulated,
+                room_ids: vec![room.id],
+                start: a,
+                end: b,
+            });
+        }
+    }
+
+    // Unconditioned spaces sharing edges with conditioned rooms
+    // (rooms that can't be classified are skipped rather than guessed)
+    for unconditioned in rooms.iter().filter(|r| r.is_conditioned() == Some(false)) {
+        for conditioned in rooms.iter().filter(|r| r.is_conditioned() == Some(true)) {
+            for (a, b) in polygon_edges(&unconditioned.boundary) {
+                for (c0, c1) in polygon_edges(&conditioned.boundary) {
+                    let Some((lo, hi)) = collinear_overlap(&a, &b, &c0, &c1) else {
+                        continue;
+                    };
+                    let s0 = point_along(&a, &b, lo);
+                    let s1 = point_along(&a, &b, hi);
+
+                    let (covered, uninsulated) = wall_coverage(&walls, &s0, &s1, &is_insulated);
+                    let gap = uncovered_length(&[(0.0, hi - lo)], &covered);
+                    if gap <= EDGE_TOLERANCE {
+                        continue;
+                    }
+
+                    report.issues.push(EnvelopeIssue {
+                        kind: EnvelopeIssueKind::UninsulatedSeparation,
+                        message: format!(
+                            "{} shares {:.1} ft with {} without an insulated separation wall",
+                            unconditioned.name, gap, conditioned.name
+                        ),
+                        wall_ids: uninsulated,
+                        room_ids: vec![unconditioned.id, conditioned.id],
+                        start: s0,
+                        end: s1,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Intervals along `a0 -> a1` covered by insulated walls, plus the IDs of
+/// uninsulated walls running along the same segment
+fn wall_coverage(
+    walls: &[&Wall],
+    a0: &Point2,
+    a1: &Point2,
+    is_insulated: &dyn Fn(&Wall) -> bool,
+) -> (Vec<(f64, f64)>, Vec<WallId>) {
+    let mut covered = Vec::new();
+    let mut uninsulated = Vec::new();
+
+    for wall in walls {
+        if let Some(interval) = collinear_overlap(a0, a1, &wall.start, &wall.end) {
+            if is_insulated(wall) {
+                covered.push(interval);
+            } else {
+                uninsulated.push(wall.id);
+            }
+        }
+    }
+
+    (covered, uninsulated)
+}
+
+/// Closed edges of a polygon's outer ring and holes (courtyards are exterior too)
+pub(crate) fn polygon_edges(polygon: &Polygon2) -> Vec<(Point2, Point2)> {
+    std::iter::once(&polygon.outer)
+        .chain(polygon.holes.iter())
+        .flat_map(|ring| {
+            let n = ring.len();
+            (0..n).map(move |i| (ring[i], ring[(i + 1) % n]))
+        })
+        .collect()
+}
+
+pub(crate) fn point_along(a: &Point2, b: &Point2, distance: f64) -> Point2 {
+    let len = a.distance_to(b);
+    if len < 1e-10 {
+        return *a;
+    }
+    let t = distance / len;
+    Point2::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+/// Portion of segment `b` lying along segment `a`, as a distance range measured
+/// from `a0`. Returns None unless `b` is collinear with `a` within tolerance and
+/// the shared length exceeds the tolerance.
+pub(crate) fn collinear_overlap(a0: &Point2, a1: &Point2, b0: &Point2, b1: &Point2) -> Option<(f64, f64)> {
+    let len = a0.distance_to(a1);
+    if len < 1e-10 {
+        return None;
+    }
+    let ux = (a1.x - a0.x) / len;
+    let uy = (a1.y - a0.y) / len;
+
+    // Perpendicular distance of b's endpoints from a's line
+    let offset = |p: &Point2| ((p.x - a0.x) * uy - (p.y - a0.y) * ux).abs();
+    if offset(b0) > EDGE_TOLERANCE || offset(b1) > EDGE_TOLERANCE {
+        return None;
+    }
+
+    let t0 = (b0.x - a0.x) * ux + (b0.y - a0.y) * uy;
+    let t1 = (b1.x - a0.x) * ux + (b1.y - a0.y) * uy;
+    let lo = t0.min(t1).max(0.0);
+    let hi = t0.max(t1).min(len);
+
+    if hi - lo > EDGE_TOLERANCE {
+        Some((lo, hi))
+    } else {
+        None
+    }
+}
+
+/// Total length of `exposed` intervals not covered by any `covered` interval
+fn uncovered_length(exposed: &[(f64, f64)], covered: &[(f64, f64)]) -> f64 {
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    let mut sorted = covered.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    for (lo, hi) in sorted {
+        match merged.last_mut() {
+            Some(last) if lo <= last.1 + EDGE_TOLERANCE => last.1 = last.1.max(hi),
+            _ => merged.push((lo, hi)),
+        }
+    }
+
+    exposed
+        .iter()
+        .map(|&(lo, hi)| {
+            let hidden: f64 = merged
+                .iter()
+                .map(|&(c_lo, c_hi)| (hi.min(c_hi) - lo.max(c_lo)).max(0.0))
+                .sum();
+            (hi - lo - hidden).max(0.0)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    /// 40x30 footprint split into a living room (west) and garage (east)
+    fn setup() -> (Store, LevelId, WallAssemblyId, WallAssemblyId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First", 0.0, 9.0).unwrap();
+        store
+            .set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0))
+            .unwrap();
+
+        let exterior = WallAssembly::exterior_2x6();
+        let partition = WallAssembly::interior_partition();
+        let ext_id = store
+            .create_wall_assembly(exterior.name, exterior.layers)
+            .unwrap();
+        let part_id = store
+            .create_wall_assembly(partition.name, partition.layers)
+            .unwrap();
+
+        store
+            .create_room(
+                level_id,
+                RoomType::LivingRoom,
+                "Living",
+                Polygon2::new(vec![
+                    Point2::new(0.0, 0.0),
+                    Point2::new(20.0, 0.0),
+                    Point2::new(20.0, 30.0),
+                    Point2::new(0.0, 30.0),
+                ]),
+            )
+            .unwrap();
+        store
+            .create_room(
+                level_id,
+                RoomType::Garage,
+                "Garage",
+                Polygon2::new(vec![
+                    Point2::new(20.0, 0.0),
+                    Point2::new(40.0, 0.0),
+                    Point2::new(40.0, 30.0),
+                    Point2::new(20.0, 30.0),
+                ]),
+            )
+            .unwrap();
+
+        (store, level_id, ext_id, part_id)
+    }
+
+    fn wall(store: &mut Store, level_id: LevelId, assembly: WallAssemblyId, x0: f64, y0: f64, x1: f64, y1: f64) -> WallId {
+        store
+            .create_wall(level_id, assembly, Point2::new(x0, y0), Point2::new(x1, y1), 9.0)
+            .unwrap()
+    }
+
+    /// Insulated perimeter around the living room plus the given separation assembly
+    fn enclose_living(store: &mut Store, level_id: LevelId, ext: WallAssemblyId, separation: WallAssemblyId) -> WallId {
+        wall(store, level_id, ext, 0.0, 0.0, 20.0, 0.0);
+        wall(store, level_id, ext, 0.0, 30.0, 20.0, 30.0);
+        wall(store, level_id, ext, 0.0, 0.0, 0.0, 30.0);
+        wall(store, level_id, separation, 20.0, 0.0, 20.0, 30.0)
+    }
+
+    #[test]
+    fn test_continuous_envelope_has_no_issues() {
+        let (mut store, level_id, ext, part) = setup();
+        enclose_living(&mut store, level_id, ext, ext);
+        // Garage exterior walls can be uninsulated
+        wall(&mut store, level_id, part, 40.0, 0.0, 40.0, 30.0);
+
+        let report = analyze_envelope(&store, level_id).unwrap();
+        assert!(report.is_continuous(), "unexpected issues: {:?}", report.issues);
+    }
+
+    #[test]
+    fn test_garage_separation_without_insulation() {
+        let (mut store, level_id, ext, part) = setup();
+        let separation = enclose_living(&mut store, level_id, ext, part);
+
+        let report = analyze_envelope(&store, level_id).unwrap();
+        let issues = report.issues_of_kind(EnvelopeIssueKind::UninsulatedSeparation);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].wall_ids, vec![separation]);
+        assert_eq!(issues[0].room_ids.len(), 2);
+    }
+
+    #[test]
+    fn test_missing_exterior_wall_reported_on_room() {
+        let (mut store, level_id, ext, _) = setup();
+        wall(&mut store, level_id, ext, 0.0, 0.0, 20.0, 0.0);
+        wall(&mut store, level_id, ext, 0.0, 0.0, 0.0, 30.0);
+        wall(&mut store, level_id, ext, 20.0, 0.0, 20.0, 30.0);
+
+        let report = analyze_envelope(&store, level_id).unwrap();
+        let issues = report.issues_of_kind(EnvelopeIssueKind::UnprotectedExteriorEdge);
+        assert_eq!(issues.len(), 1);
+        assert!((issues[0].start.y - 30.0).abs() < 1e-9);
+        assert!(issues[0].wall_ids.is_empty());
+    }
+
+    #[test]
+    fn test_uninsulated_exterior_wall() {
+        let (mut store, level_id, ext, part) = setup();
+        let west = wall(&mut store, level_id, part, 0.0, 0.0, 0.0, 30.0);
+        wall(&mut store, level_id, ext, 0.0, 0.0, 20.0, 0.0);
+        wall(&mut store, level_id, ext, 0.0, 30.0, 20.0, 30.0);
+        wall(&mut store, level_id, ext, 20.0, 0.0, 20.0, 30.0);
+
+        let report = analyze_envelope(&store, level_id).unwrap();
+        let walls = report.issues_of_kind(EnvelopeIssueKind::UninsulatedExteriorWall);
+        assert_eq!(walls.len(), 1);
+        assert_eq!(walls[0].wall_ids, vec![west]);
+
+        // The room edge behind it is exposed too, pointing at the same wall
+        let edges = report.issues_of_kind(EnvelopeIssueKind::UnprotectedExteriorEdge);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].wall_ids, vec![west]);
+    }
+
+    #[test]
+    fn test_missing_level() {
+        let store = Store::new();
+        assert!(analyze_envelope(&store, LevelId::new()).is_err());
+    }
+
+    #[test]
+    fn test_porch_treated_as_unconditioned() {
+        let (mut store, level_id, ext, part) = setup();
+        // Replace the garage with a porch described only by a custom type
+        let garage = store
+            .get_level_rooms(level_id)
+            .iter()
+            .find(|r| r.room_type == RoomType::Garage)
+            .map(|r| r.id)
+            .unwrap();
+        store.remove_room(garage).unwrap();
+        store
+            .create_room(
+                level_id,
+                RoomType::from_str("porch"),
+                "Porch",
+                Polygon2::new(vec![
+                    Point2::new(20.0, 0.0),
+                    Point2::new(40.0, 0.0),
+                    Point2::new(40.0, 30.0),
+                    Point2::new(20.0, 30.0),
+                ]),
+            )
+            .unwrap();
+        enclose_living(&mut store, level_id, ext, part);
+
+        let report = analyze_envelope(&store, level_id).unwrap();
+        // Open porch edges aren't reported; the partition to the living room is
+        assert!(report.issues_of_kind(EnvelopeIssueKind::UnprotectedExteriorEdge).is_empty());
+        assert_eq!(report.issues_of_kind(EnvelopeIssueKind::UninsulatedSeparation).len(), 1);
+    }
+
+    #[test]
+    fn test_unclassified_room_skipped() {
+        let (mut store, level_id, _, _) = setup();
+        store
+            .create_room(level_id, RoomType::from_str("studio"), "Studio", Polygon2::rectangle(5.0, 5.0))
+            .unwrap();
+        let studio = store
+            .get_level_rooms(level_id)
+            .iter()
+            .find(|r| r.name == "Studio")
+            .map(|r| r.id)
+            .unwrap();
+
+        let report = analyze_envelope(&store, level_id).unwrap();
+        assert!(report.issues.iter().all(|i| !i.room_ids.contains(&studio)));
+    }
+
+    #[test]
+    fn test_courtyard_edges_are_exterior() {
+        let mut polygon = Polygon2::rectangle(40.0, 40.0);
+        polygon.holes.push(vec![
+            Point2::new(10.0, 10.0),
+            Point2::new(10.0, 30.0),
+            Point2::new(30.0, 30.0),
+            Point2::new(30.0, 10.0),
+        ]);
+        let edges = polygon_edges(&polygon);
+        assert_eq!(edges.len(), 8);
+        assert!(edges
+            .iter()
+            .any(|(a, b)| collinear_overlap(a, b, &Point2::new(10.0, 12.0), &Point2::new(10.0, 28.0)).is_some()));
+    }
+
+    #[test]
+    fn test_collinear_overlap() {
+        let a0 = Point2::new(0.0, 0.0);
+        let a1 = Point2::new(20.0, 0.0);
+
+        // Wall centerline 0.25 ft off a room boundary drawn to the wall face
+        let range = collinear_overlap(&a0, &a1, &Point2::new(-2.0, 0.25), &Point2::new(12.0, 0.25)).unwrap();
+        assert!((range.0 - 0.0).abs() < 1e-9);
+        assert!((range.1 - 12.0).abs() < 1e-9);
+
+        // Reversed segment gives the same range
+        let reversed = collinear_overlap(&a0, &a1, &Point2::new(12.0, 0.25), &Point2::new(-2.0, 0.25)).unwrap();
+        assert_eq!(range, reversed);
+
+        // Parallel but too far away, perpendicular, or only touching at an end
+        assert!(collinear_overlap(&a0, &a1, &Point2::new(0.0, 1.0), &Point2::new(20.0, 1.0)).is_none());
+        assert!(collinear_overlap(&a0, &a1, &Point2::new(5.0, 0.0), &Point2::new(5.0, 10.0)).is_none());
+        assert!(collinear_overlap(&a0, &a1, &Point2::new(20.0, 0.0), &Point2::new(30.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_uncovered_length() {
+        let exposed = [(0.0, 20.0)];
+        assert!((uncovered_length(&exposed, &[]) - 20.0).abs() < 1e-9);
+        assert!(uncovered_length(&exposed, &[(0.0, 20.0)]).abs() < 1e-9);
+
+        // Overlapping and unsorted coverage is merged, leaving 12..20 open
+        let covered = [(5.0, 12.0), (0.0, 8.0)];
+        assert!((uncovered_length(&exposed, &covered) - 8.0).abs() < 1e-9);
+
+        // Coverage outside the exposed range doesn't count
+        assert!((uncovered_length(&exposed, &[(25.0, 40.0)]) - 20.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,322 @@
+// HVAC sizing - design loads, registers and duct runs for a building
+// Each level's envelope UA is split among its conditioned rooms: a room takes
+// the UA of the windows on its boundary plus a floor-area share of the rest.
+// Heating load is UA x the heating design difference; cooling adds solar gain
+// through the room's glazing and an internal gain per square foot. Rooms get
+// a supply register per 150 sq ft or per register's capacity, and each zone a
+// return on every level it serves. Every register is fed by its own duct run.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::daylight::room_daylight;
+use super::energy::{calculate_energy, EnergyComponentKind};
+use crate::domain::{BuildingId, HVACZoneId, LevelId, OpeningId, RoomId};
+use crate::store::Store;
+
+/// Indoor 70°F against a 0°F winter design day
+pub const HEATING_DESIGN_DELTA_T: f64 = 70.0;
+/// Indoor 75°F against a 95°F summer design day
+pub const COOLING_DESIGN_DELTA_T: f64 = 20.0;
+/// Solar gain through glazing on the design day (Btu/h·ft²)
+const SOLAR_GAIN_PER_SQFT: f64 = 30.0;
+/// People, lights and appliances (Btu/h·ft² of floor)
+const INTERNAL_GAIN_PER_SQFT: f64 = 2.0;
+/// Floor area one supply register serves (sq ft)
+const REGISTER_AREA: f64 = 150.0;
+/// Load one supply register delivers (Btu/h)
+const REGISTER_CAPACITY: f64 = 6000.0;
+/// Cooling capacity per ton (Btu/h)
+const BTU_PER_TON: f64 = 12000.0;
+
+/// Design loads and supply registers for one conditioned room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomHvacLoad {
+    pub room_id: RoomId,
+    pub level_id: LevelId,
+    pub name: String,
+    pub zone_id: Option<HVACZoneId>,
+    pub floor_area: f64,
+    pub glazing_area: f64,
+    /// The room's share of the envelope UA (Btu/h·°F)
+    pub ua: f64,
+    /// Btu/h
+    pub heating_load: f64,
+    /// Btu/h
+    pub cooling_load: f64,
+    pub supply_registers: u32,
+}
+
+/// Totals for a zone, or for the rooms in no zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HvacZoneSummary {
+    /// None for the unzoned rooms
+    pub zone_id: Option<HVACZoneId>,
+    pub name: String,
+    pub room_ids: Vec<RoomId>,
+    /// Levels the zone's rooms are on, a return on each
+    pub level_ids: Vec<LevelId>,
+    pub floor_area: f64,
+    pub heating_load: f64,
+    pub cooling_load: f64,
+    pub supply_registers: u32,
+    pub return_registers: u32,
+}
+
+impl HvacZoneSummary {
+    /// Supply and return runs, one per register
+    pub fn duct_runs(&self) -> u32 {
+        self.supply_registers + self.return_registers
+    }
+
+    pub fn cooling_tons(&self) -> f64 {
+        self.cooling_load / BTU_PER_TON
+    }
+}
+
+/// Design loads, registers and duct runs for a building, by room and zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HvacSummary {
+    pub building_id: BuildingId,
+    pub rooms: Vec<RoomHvacLoad>,
+    /// Zones by name, with the unzoned rooms last
+    pub zones: Vec<HvacZoneSummary>,
+    pub heating_load: f64,
+    pub cooling_load: f64,
+    pub supply_registers: u32,
+    pub return_registers: u32,
+    pub duct_runs: u32,
+    pub cooling_tons: f64,
+}
+
+impl HvacSummary {
+    pub fn room(&self, room_id: RoomId) -> Option<&RoomHvacLoad> {
+        self.rooms.iter().find(|r| r.room_id == room_id)
+    }
+
+    pub fn zone(&self, zone_id: Option<HVACZoneId>) -> Option<&HvacZoneSummary> {
+        self.zones.iter().find(|z| z.zone_id == zone_id)
+    }
+
+    /// Supply and return registers on one level
+    pub fn level_registers(&self, level_id: LevelId) -> (u32, u32) {
+        let supply = self.rooms.iter().filter(|r| r.level_id == level_id).map(|r| r.supply_registers).sum();
+        let returns = self.zones.iter().filter(|z| z.level_ids.contains(&level_id)).count() as u32;
+        (supply, returns)
+    }
+}
+
+/// Heating and cooling loads, registers and duct runs for a building's
+/// conditioned rooms
+pub fn hvac_summary(store: &Store, building_id: BuildingId) -> Result<HvacSummary> {
+    let energy = calculate_energy(store, building_id)?;
+    let levels = store.get_building_levels_by_elevation(building_id);
+
+    let mut rooms: Vec<RoomHvacLoad> = Vec::new();
+    for level in levels {
+        let level_energy = energy.levels.iter().find(|l| l.level_id == level.id);
+        let window_ua = |id: OpeningId| {
+            level_energy
+                .into_iter()
+                .flat_map(|l| &l.components)
+                .filter(|c| c.kind == EnergyComponentKind::Window && c.opening_id == Some(id))
+                .map(|c| c.ua)
+                .sum::<f64>()
+        };
+
+        let conditioned: Vec<_> = store
+            .get_level_rooms(level.id)
+            .into_iter()
+            .filter(|r| r.is_conditioned().unwrap_or(true))
+            .map(|room| {
+                let daylight = room_daylight(store, level, room);
+                let windows: f64 = daylight.window_ids.iter().map(|id| window_ua(*id)).sum();
+                (room, daylight, windows)
+            })
+            .collect();
+
+        // What the rooms' own windows don't account for is shared by area
+        let level_ua = level_energy.map(|l| l.ua).unwrap_or(0.0);
+        let shared_ua = (level_ua - conditioned.iter().map(|(_, _, w)| w).sum::<f64>()).max(0.0);
+        let level_area: f64 = conditioned.iter().map(|(_, d, _)| d.floor_area).sum();
+
+        for (room, daylight, windows) in conditioned {
+            let share = if level_area > 0.0 { daylight.floor_area / level_area } else { 0.0 };
+            let ua = windows + shared_ua * share;
+            let heating_load = ua * HEATING_DESIGN_DELTA_T;
+            let cooling_load = ua * COOLING_DESIGN_DELTA_T
+                + daylight.glazing_area * SOLAR_GAIN_PER_SQFT
+                + daylight.floor_area * INTERNAL_GAIN_PER_SQFT;
+            let by_area = (daylight.floor_area / REGISTER_AREA).ceil();
+            let by_load = (heating_load.max(cooling_load) / REGISTER_CAPACITY).ceil();
+
+            rooms.push(RoomHvacLoad {
+                room_id: room.id,
+                level_id: level.id,
+                name: room.name.clone(),
+                zone_id: store.get_room_hvac_zone(room.id).map(|z| z.id),
+                floor_area: daylight.floor_area,
+                glazing_area: daylight.glazing_area,
+                ua,
+                heating_load,
+                cooling_load,
+                supply_registers: by_area.max(by_load).max(1.0) as u32,
+            });
+        }
+    }
+
+    let mut zones: Vec<HvacZoneSummary> = store
+        .get_building_hvac_zones(building_id)
+        .into_iter()
+        .map(|z| zone_summary(Some(z.id), z.name.clone(), &rooms))
+        .collect();
+    if rooms.iter().any(|r| r.zone_id.is_none()) {
+        zones.push(zone_summary(None, "Unzoned".to_string(), &rooms));
+    }
+
+    let heating_load: f64 = rooms.iter().map(|r| r.heating_load).sum();
+    let cooling_load: f64 = rooms.iter().map(|r| r.cooling_load).sum();
+    let supply_registers: u32 = zones.iter().map(|z| z.supply_registers).sum();
+    let return_registers: u32 = zones.iter().map(|z| z.return_registers).sum();
+
+    Ok(HvacSummary {
+        building_id,
+        rooms,
+        zones,
+        heating_load,
+        cooling_load,
+        supply_registers,
+        return_registers,
+        duct_runs: supply_registers + return_registers,
+        cooling_tons: cooling_load / BTU_PER_TON,
+    })
+}
+
+fn zone_summary(zone_id: Option<HVACZoneId>, name: String, rooms: &[RoomHvacLoad]) -> HvacZoneSummary {
+    let members: Vec<&RoomHvacLoad> = rooms.iter().filter(|r| r.zone_id == zone_id).collect();
+    let mut level_ids: Vec<LevelId> = Vec::new();
+    for room in &members {
+        if !level_ids.contains(&room.level_id) {
+            level_ids.push(room.level_id);
+        }
+    }
+
+    HvacZoneSummary {
+        zone_id,
+        name,
+        room_ids: members.iter().map(|r| r.room_id).collect(),
+        floor_area: members.iter().map(|r| r.floor_area).sum(),
+        heating_load: members.iter().map(|r| r.heating_load).sum(),
+        cooling_load: members.iter().map(|r| r.cooling_load).sum(),
+        supply_registers: members.iter().map(|r| r.supply_registers).sum(),
+        return_registers: level_ids.len() as u32,
+        level_ids,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    /// A 30x20 one-story house on a slab with 2x6 walls, split into a
+    /// 20x20 living room and a 10x20 bedroom, with a garage room alongside
+    fn setup() -> (Store, BuildingId, LevelId, RoomId, RoomId, WallId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "House").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(30.0, 20.0)).unwrap();
+        let preset = WallAssembly::exterior_2x6();
+        let assembly_id = store.create_wall_assembly(preset.name, preset.layers).unwrap();
+
+        let corners = [(0.0, 0.0), (30.0, 0.0), (30.0, 20.0), (0.0, 20.0)];
+        let mut south = None;
+        for i in 0..4 {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            let id = store
+                .create_wall(level_id, assembly_id, Point2::new(a.0, a.1), Point2::new(b.0, b.1), 8.0)
+                .unwrap();
+            south.get_or_insert(id);
+        }
+        let living = store
+            .create_room(level_id, RoomType::LivingRoom, "Living", Polygon2::rectangle(20.0, 20.0))
+            .unwrap();
+        let bedroom_outline = Polygon2::new(vec![
+            Point2::new(20.0, 0.0),
+            Point2::new(30.0, 0.0),
+            Point2::new(30.0, 20.0),
+            Point2::new(20.0, 20.0),
+        ]);
+        let bedroom = store.create_room(level_id, RoomType::Bedroom, "Bed", bedroom_outline).unwrap();
+        let garage_outline = Polygon2::new(vec![
+            Point2::new(30.0, 0.0),
+            Point2::new(50.0, 0.0),
+            Point2::new(50.0, 20.0),
+            Point2::new(30.0, 20.0),
+        ]);
+        store.create_room(level_id, RoomType::Garage, "Garage", garage_outline).unwrap();
+        (store, building_id, level_id, living, bedroom, south.unwrap())
+    }
+
+    #[test]
+    fn test_room_loads() {
+        let (mut store, building_id, level_id, living, bedroom, south) = setup();
+        let energy = calculate_energy(&store, building_id).unwrap();
+
+        let summary = hvac_summary(&store, building_id).unwrap();
+        assert_eq!(summary.rooms.len(), 2);
+        assert!((summary.heating_load - energy.ua * HEATING_DESIGN_DELTA_T).abs() < 1e-6);
+
+        // Without windows the envelope splits by floor area. The bare
+        // ceiling loses enough heat that load, not area, sets the registers.
+        let (a, b) = (summary.room(living).unwrap(), summary.room(bedroom).unwrap());
+        assert!((a.ua - 2.0 * b.ua).abs() < 1e-9);
+        assert_eq!(a.supply_registers, (a.heating_load / REGISTER_CAPACITY).ceil() as u32);
+        assert!(a.supply_registers > 3);
+        assert!((b.cooling_load - (b.ua * COOLING_DESIGN_DELTA_T + 400.0)).abs() < 1e-9);
+
+        // A window on the living room's wall adds its UA and solar gain there
+        store.add_opening(south, OpeningType::Window, 0.3, 4.0, 5.0, 3.0).unwrap();
+        let with_window = hvac_summary(&store, building_id).unwrap();
+        let room = with_window.room(living).unwrap();
+        assert!((room.glazing_area - 20.0).abs() < 1e-9);
+        assert!(room.cooling_load > a.cooling_load + 20.0 * SOLAR_GAIN_PER_SQFT);
+        assert!(with_window.room(bedroom).unwrap().ua < b.ua);
+
+        // Nothing zoned: one unzoned group with a return
+        assert_eq!(with_window.zones.len(), 1);
+        assert_eq!(with_window.zones[0].zone_id, None);
+        let (supply, returns) = with_window.level_registers(level_id);
+        assert_eq!(returns, 1);
+        assert_eq!(with_window.duct_runs, supply + 1);
+    }
+
+    #[test]
+    fn test_zones() {
+        let (mut store, building_id, level_id, living, bedroom, _) = setup();
+        let upper = store.add_level(building_id, "Second Floor", 9.0, 9.0).unwrap();
+        let loft = store
+            .create_room(upper, RoomType::Bedroom, "Loft", Polygon2::rectangle(10.0, 10.0))
+            .unwrap();
+
+        let day = store.create_hvac_zone(building_id, "Day").unwrap();
+        let night = store.create_hvac_zone(building_id, "Night").unwrap();
+        store.assign_room_to_zone(living, Some(day)).unwrap();
+        store.assign_room_to_zone(bedroom, Some(night)).unwrap();
+        store.assign_room_to_zone(loft, Some(night)).unwrap();
+
+        let summary = hvac_summary(&store, building_id).unwrap();
+        assert!(summary.zone(None).is_none());
+        let night_zone = summary.zone(Some(night)).unwrap();
+        assert_eq!(night_zone.room_ids.len(), 2);
+        assert_eq!(night_zone.return_registers, 2);
+        assert_eq!(night_zone.duct_runs(), night_zone.supply_registers + 2);
+        assert!((night_zone.floor_area - 300.0).abs() < 1e-9);
+        assert_eq!(summary.level_registers(level_id).1, 2);
+        assert_eq!(summary.level_registers(upper).1, 1);
+        assert!(hvac_summary(&store, BuildingId::new()).is_err());
+    }
+}
@@ -0,0 +1,24 @@
+// Analysis module - whole-model checks that read across store entities
+// Reports findings with entity IDs so they can be fixed from the UI or scripts
+
+pub mod bearing;
+pub mod circulation;
+pub mod daylight;
+pub mod earthwork;
+pub mod egress;
+pub mod energy;
+pub mod envelope;
+pub mod hvac;
+pub mod solar;
+pub mod zoning;
+
+pub use bearing::*;
+pub use circulation::*;
+pub use daylight::*;
+pub use earthwork::*;
+pub use egress::*;
+pub use energy::*;
+pub use envelope::*;
+pub use hvac::*;
+pub use solar::*;
+pub use zoning::*;
@@ -0,0 +1,360 @@
+// Solar - window orientation, solar gain and overhang shading
+// Each exterior wall faces away from the footprint edge it runs along; the
+// site's north angle turns that plan direction into a true bearing, binned
+// to the nearest cardinal direction. A window's gain on a clear design day
+// is its area x SHGC x the sun falling on a wall facing that way, less the
+// share the roof overhang above it shades. Sun figures are for about 40°N.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::envelope::collinear_overlap;
+use crate::domain::{
+    BuildingId, Level, LevelId, OpeningId, OpeningType, Point2, Polygon2, RoofPlan, WallId,
+    WindowProperties,
+};
+use crate::store::Store;
+
+/// Compass direction a facade faces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Facing {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Facing {
+    pub const ALL: [Facing; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::North => "north",
+            Self::East => "east",
+            Self::South => "south",
+            Self::West => "west",
+        }
+    }
+
+    /// Nearest cardinal direction to a bearing (degrees clockwise from true north)
+    pub fn from_azimuth(azimuth: f64) -> Self {
+        match azimuth.rem_euclid(360.0) {
+            a if !(45.0..315.0).contains(&a) => Self::North,
+            a if a < 135.0 => Self::East,
+            a if a < 225.0 => Self::South,
+            _ => Self::West,
+        }
+    }
+
+    /// Clear design-day sun on a vertical surface facing this way, for
+    /// January and July
+    fn design_days(&self) -> (DesignDay, DesignDay) {
+        match self {
+            Self::South => (DesignDay::new(1630.0, 27.0), DesignDay::new(500.0, 68.0)),
+            Self::East | Self::West => (DesignDay::new(550.0, 25.0), DesignDay::new(1150.0, 35.0)),
+            // Diffuse light only; no direct sun for an overhang to cut off
+            Self::North => (DesignDay::new(120.0, 0.0), DesignDay::new(330.0, 0.0)),
+        }
+    }
+}
+
+/// Sun on a wall over one day
+struct DesignDay {
+    /// Btu/ft²
+    insolation: f64,
+    /// Altitude of the sun projected onto the plane normal to the wall (degrees)
+    profile_angle: f64,
+}
+
+impl DesignDay {
+    fn new(insolation: f64, profile_angle: f64) -> Self {
+        Self { insolation, profile_angle }
+    }
+
+    /// Share of a window the overhang shades: its shadow falls `projection
+    /// * tan(profile)` down the wall, starting `gap` above the window head
+    fn shading(&self, projection: f64, gap: f64, height: f64) -> f64 {
+        if height <= 0.0 {
+            return 0.0;
+        }
+        let shadow = projection * self.profile_angle.to_radians().tan() - gap;
+        (shadow / height).clamp(0.0, 1.0)
+    }
+}
+
+/// Sun on one window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowSolar {
+    pub opening_id: OpeningId,
+    pub wall_id: WallId,
+    pub level_id: LevelId,
+    pub facing: Facing,
+    /// True bearing the window faces (degrees clockwise from north)
+    pub azimuth: f64,
+    /// Square feet
+    pub area: f64,
+    pub shgc: f64,
+    /// Roof overhang past the wall, out from the window (feet)
+    pub overhang: f64,
+    /// Share of the window shaded in January and July
+    pub winter_shading: f64,
+    pub summer_shading: f64,
+    /// Solar gain on a clear January and July day (Btu/day)
+    pub heating_gain: f64,
+    pub cooling_gain: f64,
+}
+
+/// Walls, windows and solar gain facing one way
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacadeSolar {
+    pub facing: Facing,
+    /// Gross exterior wall area (sqft)
+    pub wall_area: f64,
+    pub window_area: f64,
+    pub window_wall_ratio: f64,
+    /// Btu/day
+    pub heating_gain: f64,
+    pub cooling_gain: f64,
+}
+
+/// Window orientation and solar gain for a building
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolarReport {
+    pub building_id: BuildingId,
+    /// Site north angle the bearings were taken with (degrees)
+    pub north_angle: f64,
+    /// One per cardinal direction, north first, clockwise
+    pub facades: Vec<FacadeSolar>,
+    pub windows: Vec<WindowSolar>,
+    /// Btu/day
+    pub heating_gain: f64,
+    pub cooling_gain: f64,
+}
+
+impl SolarReport {
+    pub fn facade(&self, facing: Facing) -> Option<&FacadeSolar> {
+        self.facades.iter().find(|f| f.facing == facing)
+    }
+}
+
+/// Window areas by facade and clear-day solar gain for a building
+pub fn solar_report(store: &Store, building_id: BuildingId) -> Result<SolarReport> {
+    let building = store
+        .get_building(building_id)
+        .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+    let north_angle = store
+        .get_project(building.project_id)
+        .and_then(|p| p.site_id)
+        .and_then(|id| store.get_site(id))
+        .map(|s| s.north_angle)
+        .unwrap_or(0.0);
+
+    let mut facades: Vec<FacadeSolar> = Facing::ALL
+        .iter()
+        .map(|&facing| FacadeSolar {
+            facing,
+            wall_area: 0.0,
+            window_area: 0.0,
+            window_wall_ratio: 0.0,
+            heating_gain: 0.0,
+            cooling_gain: 0.0,
+        })
+        .collect();
+    let mut windows = Vec::new();
+
+    for level in store.get_building_levels_by_elevation(building_id) {
+        level_solar(store, level, north_angle, &mut facades, &mut windows);
+    }
+
+    for facade in &mut facades {
+        if facade.wall_area > 0.0 {
+            facade.window_wall_ratio = facade.window_area / facade.wall_area;
+        }
+    }
+    Ok(SolarReport {
+        building_id,
+        north_angle,
+        heating_gain: facades.iter().map(|f| f.heating_gain).sum(),
+        cooling_gain: facades.iter().map(|f| f.cooling_gain).sum(),
+        facades,
+        windows,
+    })
+}
+
+/// Add a level's exterior walls and windows to the facades they face
+fn level_solar(
+    store: &Store,
+    level: &Level,
+    north_angle: f64,
+    facades: &mut [FacadeSolar],
+    windows: &mut Vec<WindowSolar>,
+) {
+    let Some(footprint) = store.get_level_footprint(level.id) else {
+        return;
+    };
+    let edges = outward_edges(&footprint.polygon);
+    let roof_plan = store
+        .get_level_roof(level.id)
+        .and_then(|r| r.plan(&footprint.polygon));
+
+    let mut walls = store.get_level_walls(level.id);
+    walls.sort_by_key(|w| w.id.to_string());
+    for wall in walls {
+        // The footprint edge the wall runs along furthest decides its facing
+        let Some((length, normal)) = edges
+            .iter()
+            .filter_map(|(a, b, n)| collinear_overlap(&wall.start, &wall.end, a, b).map(|(lo, hi)| (hi - lo, *n)))
+            .max_by(|x, y| x.0.total_cmp(&y.0))
+        else {
+            continue;
+        };
+        let azimuth = (normal.0.atan2(normal.1).to_degrees() - north_angle).rem_euclid(360.0);
+        let facing = Facing::from_azimuth(azimuth);
+        let (winter, summer) = facing.design_days();
+        let facade = facades
+            .iter_mut()
+            .find(|f| f.facing == facing)
+            .expect("a facade per direction");
+        facade.wall_area += length.min(wall.length()) * wall.height;
+
+        let mut openings = store.get_wall_openings(wall.id);
+        openings.sort_by_key(|o| o.id.to_string());
+        for opening in openings {
+            if opening.opening_type != OpeningType::Window {
+                continue;
+            }
+            let area = opening.width * opening.height;
+            let shgc = opening.window_properties
+                .as_ref()
+                .map(|w| w.shgc)
+                .unwrap_or_else(|| WindowProperties::double_pane().shgc);
+            let center = Point2::new(
+                wall.start.x + (wall.end.x - wall.start.x) * opening.position_along_wall,
+                wall.start.y + (wall.end.y - wall.start.y) * opening.position_along_wall,
+            );
+            let overhang = roof_plan
+                .as_ref()
+                .map(|plan| projection(plan, &center, normal))
+                .unwrap_or(0.0);
+            let gap = (wall.height - opening.sill_height - opening.height).max(0.0);
+            let winter_shading = winter.shading(overhang, gap, opening.height);
+            let summer_shading = summer.shading(overhang, gap, opening.height);
+            let heating_gain = area * shgc * winter.insolation * (1.0 - winter_shading);
+            let cooling_gain = area * shgc * summer.insolation * (1.0 - summer_shading);
+
+            facade.window_area += area;
+            facade.heating_gain += heating_gain;
+            facade.cooling_gain += cooling_gain;
+            windows.push(WindowSolar {
+                opening_id: opening.id,
+                wall_id: wall.id,
+                level_id: level.id,
+                facing,
+                azimuth,
+                area,
+                shgc,
+                overhang,
+                winter_shading,
+                summer_shading,
+                heating_gain,
+                cooling_gain,
+            });
+        }
+    }
+}
+
+/// Footprint edges with the unit normal pointing out of the building: away
+/// from the outer ring, into the holes
+fn outward_edges(polygon: &Polygon2) -> Vec<(Point2, Point2, (f64, f64))> {
+    std::iter::once((&polygon.outer, true))
+        .chain(polygon.holes.iter().map(|h| (h, false)))
+        .flat_map(|(ring, outer)| {
+            let ccw = Polygon2::new(ring.clone()).signed_area() > 0.0;
+            // The right-hand normal points out of a counter-clockwise ring
+            let sign = if ccw == outer { 1.0 } else { -1.0 };
+            let n = ring.len();
+            (0..n).filter_map(move |i| {
+                let (a, b) = (ring[i], ring[(i + 1) % n]);
+                let len = a.distance_to(&b);
+                (len > 1e-10).then(|| (a, b, (sign * (b.y - a.y) / len, sign * -(b.x - a.x) / len)))
+            })
+        })
+        .collect()
+}
+
+/// How far the roof plan reaches past `point` in direction `normal`
+fn projection(plan: &RoofPlan, point: &Point2, normal: (f64, f64)) -> f64 {
+    let reach = |p: f64, n: f64, min: f64, max: f64| {
+        if n > 1e-9 {
+            (max - p) / n
+        } else if n < -1e-9 {
+            (min - p) / n
+        } else {
+            f64::INFINITY
+        }
+    };
+    reach(point.x, normal.0, plan.min.x, plan.max.x)
+        .min(reach(point.y, normal.1, plan.min.y, plan.max.y))
+        .max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CodeRegion, RoofType, UnitSystem};
+
+    #[test]
+    fn test_solar_report() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "House").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0)).unwrap();
+
+        let assembly = store.create_wall_assembly("2x6 Exterior", vec![]).unwrap();
+        let corners = [(0.0, 0.0), (40.0, 0.0), (40.0, 30.0), (0.0, 30.0)];
+        let walls: Vec<WallId> = (0..4)
+            .map(|i| {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                store.create_wall(level_id, assembly, Point2::new(a.0, a.1), Point2::new(b.0, b.1), 9.0).unwrap()
+            })
+            .collect();
+        // 5' x 4' windows, heads 2' below the top plate, on the south (y = 0) and east walls
+        let south = store.add_opening(walls[0], OpeningType::Window, 0.5, 5.0, 4.0, 3.0).unwrap();
+        store.add_opening(walls[1], OpeningType::Window, 0.5, 5.0, 4.0, 3.0).unwrap();
+
+        let report = solar_report(&store, building_id).unwrap();
+        let facade = report.facade(Facing::South).unwrap();
+        assert!((facade.wall_area - 360.0).abs() < 1e-9);
+        assert!((facade.window_wall_ratio - 20.0 / 360.0).abs() < 1e-9);
+        assert!(report.facade(Facing::North).unwrap().window_area == 0.0);
+        let window = report.windows.iter().find(|w| w.opening_id == south).unwrap();
+        assert!((window.azimuth - 180.0).abs() < 1e-9);
+        assert_eq!(window.summer_shading, 0.0);
+        let unshaded = 20.0 * 0.30 * 500.0;
+        assert!((window.cooling_gain - unshaded).abs() < 1e-9);
+
+        // A 2' overhang shades the high summer sun on the south window
+        // but lets the low winter sun in
+        store.create_roof(level_id, RoofType::Gable, 6.0, 2.0).unwrap();
+        let report = solar_report(&store, building_id).unwrap();
+        let window = report.windows.iter().find(|w| w.opening_id == south).unwrap();
+        assert!((window.overhang - 2.0).abs() < 1e-9);
+        let summer = (2.0 * 68f64.to_radians().tan() - 2.0) / 4.0;
+        assert!((window.summer_shading - summer).abs() < 1e-9);
+        assert_eq!(window.winter_shading, 0.0);
+        assert!(window.cooling_gain < unshaded);
+
+        // Turned so true north lies along the plan's +x, the south wall faces east
+        let site_id = store.create_site(project_id).unwrap();
+        store.set_site_north_angle(site_id, 450.0).unwrap();
+        assert_eq!(store.get_site(site_id).unwrap().north_angle, 90.0);
+        let report = solar_report(&store, building_id).unwrap();
+        let window = report.windows.iter().find(|w| w.opening_id == south).unwrap();
+        assert_eq!(window.facing, Facing::East);
+        assert!((report.facade(Facing::North).unwrap().window_area - 20.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,377 @@
+// Zoning analysis - building footprints against the site's setbacks, lot
+// coverage and height limits. Lot edges are classed front, rear, left or right
+// from the site's front edge; each footprint edge nearer a lot line than its
+// setback is reported with how far it encroaches.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{BuildingId, LevelId, Point2, Polygon2, ProjectId, Setbacks, SiteId};
+use crate::store::Store;
+
+/// Which setback a lot edge takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LotSide {
+    Front,
+    Rear,
+    /// Side on the left, looking into the lot from the street
+    Left,
+    Right,
+}
+
+impl LotSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Front => "front",
+            Self::Rear => "rear",
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
+
+    /// Setback distance for this side
+    pub fn setback(&self, setbacks: &Setbacks) -> f64 {
+        match self {
+            Self::Front => setbacks.front,
+            Self::Rear => setbacks.back,
+            Self::Left => setbacks.left,
+            Self::Right => setbacks.right,
+        }
+    }
+}
+
+/// A footprint edge inside a setback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetbackViolation {
+    pub building_id: BuildingId,
+    pub level_id: LevelId,
+    pub side: LotSide,
+    /// Index of the lot boundary edge
+    pub lot_edge: usize,
+    /// Offending footprint edge, in site coordinates
+    pub start: Point2,
+    pub end: Point2,
+    /// Required setback (feet)
+    pub required: f64,
+    /// Closest approach to the lot line; negative when over it (feet)
+    pub distance: f64,
+    /// How far the edge reaches into the setback (feet)
+    pub encroachment: f64,
+}
+
+/// A building taller than the height limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeightViolation {
+    pub building_id: BuildingId,
+    /// Top of the highest level or roof ridge above grade (feet)
+    pub height: f64,
+    pub max_height: f64,
+}
+
+/// Zoning report for a project's site
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoningReport {
+    pub project_id: ProjectId,
+    pub site_id: SiteId,
+    pub lot_area: f64,
+    /// Ground covered by building footprints (sqft)
+    pub covered_area: f64,
+    /// Covered area as a fraction of the lot
+    pub lot_coverage: f64,
+    pub max_lot_coverage: Option<f64>,
+    pub setback_violations: Vec<SetbackViolation>,
+    pub height_violations: Vec<HeightViolation>,
+}
+
+impl ZoningReport {
+    pub fn coverage_exceeded(&self) -> bool {
+        self.max_lot_coverage.is_some_and(|max| self.lot_coverage > max + 1e-9)
+    }
+
+    pub fn is_compliant(&self) -> bool {
+        self.setback_violations.is_empty() && self.height_violations.is_empty() && !self.coverage_exceeded()
+    }
+}
+
+/// Check every building in a project against its site's zoning
+pub fn check_zoning(store: &Store, project_id: ProjectId) -> Result<ZoningReport> {
+    let project = store.get_project(project_id)
+        .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+    let site = project.site_id
+        .and_then(|id| store.get_site(id))
+        .ok_or_else(|| anyhow!("Project has no site: {:?}", project_id))?;
+    let lot = site.boundary.as_ref()
+        .filter(|b| b.outer.len() >= 3)
+        .ok_or_else(|| anyhow!("Site has no boundary: {:?}", site.id))?;
+
+    let sides = lot_sides(lot, site.zoning.front_edge);
+    let lot_edges = ring_edges(&lot.outer);
+    let ccw = lot.signed_area() > 0.0;
+
+    let mut report = ZoningReport {
+        project_id,
+        site_id: site.id,
+        lot_area: lot.area(),
+        covered_area: 0.0,
+        lot_coverage: 0.0,
+        max_lot_coverage: site.zoning.max_lot_coverage,
+        setback_violations: Vec::new(),
+        height_violations: Vec::new(),
+    };
+
+    for building_id in &project.building_ids {
+        let Some(building) = store.get_building(*building_id) else {
+            continue;
+        };
+        let offset = |p: &Point2| Point2::new(p.x + building.origin.x, p.y + building.origin.y);
+
+        let mut covered: f64 = 0.0;
+        let mut height: f64 = 0.0;
+        let mut checked: Vec<(Point2, Point2)> = Vec::new();
+        for level in store.get_building_levels(*building_id) {
+            let footprint = store.get_level_footprint(level.id).map(|f| &f.polygon);
+            let plate = level.elevation + level.floor_to_floor;
+            let ridge = store
+                .get_level_roof(level.id)
+                .zip(footprint)
+                .and_then(|(roof, fp)| roof.plan(fp).map(|plan| roof.ridge_height(&plan)))
+                .unwrap_or(0.0);
+            height = height.max(plate + ridge);
+
+            // Wings and attached garages cover ground and hold setbacks
+            // like the primary block
+            let footprints = store.get_level_footprints(level.id);
+            if footprints.is_empty() {
+                continue;
+            }
+            if !level.is_basement {
+                covered = covered.max(footprints.iter().map(|f| f.area()).sum());
+            }
+
+            for footprint in footprints.iter().map(|f| &f.polygon) {
+                let footprint_ccw = footprint.signed_area() > 0.0;
+                for (a, b) in ring_edges(&footprint.outer) {
+                    let (start, end) = (offset(&a), offset(&b));
+                    let facing = outward_normal(&(a, b), footprint_ccw);
+                    // Stacked levels repeat the same edges; report each once
+                    let seen = checked.iter().any(|(s, e)| {
+                        s.distance_to(&start) < 1e-6 && e.distance_to(&end) < 1e-6
+                    });
+                    if seen {
+                        continue;
+                    }
+                    checked.push((start, end));
+
+                    // Setbacks are measured square to the lot line, so an edge
+                    // is held to the lines it faces
+                    for (index, (l0, l1)) in lot_edges.iter().enumerate() {
+                        let normal = outward_normal(&(*l0, *l1), ccw);
+                        if facing.0 * normal.0 + facing.1 * normal.1 < 0.5 {
+                            continue;
+                        }
+                        let side = sides[index];
+                        let required = side.setback(&site.setbacks);
+                        let distance = edge_clearance(&start, &end, l0, l1, ccw);
+                        if distance < required - 1e-6 {
+                            report.setback_violations.push(SetbackViolation {
+                                building_id: *building_id,
+                                level_id: level.id,
+                                side,
+                                lot_edge: index,
+                                start,
+                                end,
+                                required,
+                                distance,
+                                encroachment: required - distance,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        report.covered_area += covered;
+        if let Some(max_height) = site.zoning.max_height {
+            if height > max_height + 1e-9 {
+                report.height_violations.push(HeightViolation {
+                    building_id: *building_id,
+                    height,
+                    max_height,
+                });
+            }
+        }
+    }
+
+    if report.lot_area > 0.0 {
+        report.lot_coverage = report.covered_area / report.lot_area;
+    }
+    Ok(report)
+}
+
+/// Class each lot edge. Edges facing away from the front are the rear;
+/// the others are left or right of the lot's center seen from the street.
+pub fn lot_sides(lot: &Polygon2, front_edge: usize) -> Vec<LotSide> {
+    let edges = ring_edges(&lot.outer);
+    if edges.is_empty() {
+        return Vec::new();
+    }
+    let front_edge = front_edge.min(edges.len() - 1);
+    let ccw = lot.signed_area() > 0.0;
+    let front_normal = outward_normal(&edges[front_edge], ccw);
+    // Looking into the lot from the street, left is a quarter turn from inward
+    let inward = (-front_normal.0, -front_normal.1);
+    let left = (-inward.1, inward.0);
+    let center = lot.centroid();
+
+    edges
+        .iter()
+        .enumerate()
+        .map(|(i, edge)| {
+            if i == front_edge {
+                return LotSide::Front;
+            }
+            let normal = outward_normal(edge, ccw);
+            if normal.0 * front_normal.0 + normal.1 * front_normal.1 < -0.7 {
+                return LotSide::Rear;
+            }
+            let mid = ((edge.0.x + edge.1.x) / 2.0 - center.x, (edge.0.y + edge.1.y) / 2.0 - center.y);
+            if mid.0 * left.0 + mid.1 * left.1 >= 0.0 {
+                LotSide::Left
+            } else {
+                LotSide::Right
+            }
+        })
+        .collect()
+}
+
+/// Closest approach of a footprint edge to a lot edge. Footprint points on
+/// the far side of the lot line count as negative.
+fn edge_clearance(start: &Point2, end: &Point2, l0: &Point2, l1: &Point2, ccw: bool) -> f64 {
+    let normal = outward_normal(&(*l0, *l1), ccw);
+    let signed = |p: &Point2| {
+        let d = point_segment_distance(p, l0, l1);
+        let outside = (p.x - l0.x) * normal.0 + (p.y - l0.y) * normal.1 > 1e-9;
+        if outside { -d } else { d }
+    };
+    [
+        signed(start),
+        signed(end),
+        point_segment_distance(l0, start, end),
+        point_segment_distance(l1, start, end),
+    ]
+    .into_iter()
+    .fold(f64::MAX, f64::min)
+}
+
+fn point_segment_distance(p: &Point2, a: &Point2, b: &Point2) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len_sq = dx * dx + dy * dy;
+    if len_sq < 1e-20 {
+        return p.distance_to(a);
+    }
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / len_sq).clamp(0.0, 1.0);
+    p.distance_to(&Point2::new(a.x + t * dx, a.y + t * dy))
+}
+
+fn outward_normal(edge: &(Point2, Point2), ccw: bool) -> (f64, f64) {
+    let (dx, dy) = (edge.1.x - edge.0.x, edge.1.y - edge.0.y);
+    let len = (dx * dx + dy * dy).sqrt().max(1e-12);
+    if ccw {
+        (dy / len, -dx / len)
+    } else {
+        (-dy / len, dx / len)
+    }
+}
+
+fn ring_edges(ring: &[Point2]) -> Vec<(Point2, Point2)> {
+    (0..ring.len()).map(|i| (ring[i], ring[(i + 1) % ring.len()])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    /// 60x100 lot with the street along y = 0 and a 30x40 house
+    fn setup(house_origin: Point2) -> (Store, ProjectId, SiteId, LevelId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let site_id = store.create_site(project_id).unwrap();
+        store.set_site_boundary(site_id, Polygon2::rectangle(60.0, 100.0)).unwrap();
+
+        let building_id = store.add_building(project_id, "House").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 10.0).unwrap();
+        let footprint = Polygon2::new(vec![
+            house_origin,
+            Point2::new(house_origin.x + 30.0, house_origin.y),
+            Point2::new(house_origin.x + 30.0, house_origin.y + 40.0),
+            Point2::new(house_origin.x, house_origin.y + 40.0),
+        ]);
+        store.set_level_footprint(level_id, footprint).unwrap();
+        (store, project_id, site_id, level_id)
+    }
+
+    #[test]
+    fn test_lot_sides() {
+        let sides = lot_sides(&Polygon2::rectangle(60.0, 100.0), 0);
+        assert_eq!(sides, vec![LotSide::Front, LotSide::Right, LotSide::Rear, LotSide::Left]);
+
+        // Street along the east edge
+        let sides = lot_sides(&Polygon2::rectangle(60.0, 100.0), 1);
+        assert_eq!(sides, vec![LotSide::Left, LotSide::Front, LotSide::Right, LotSide::Rear]);
+    }
+
+    #[test]
+    fn test_setback_encroachment() {
+        // 20' from the street against a 25' front setback, 3' from the left line
+        let (mut store, project_id, site_id, _) = setup(Point2::new(3.0, 20.0));
+
+        let report = check_zoning(&store, project_id).unwrap();
+        assert_eq!(report.setback_violations.len(), 2);
+        let front = report.setback_violations.iter().find(|v| v.side == LotSide::Front).unwrap();
+        assert!((front.encroachment - 5.0).abs() < 1e-9);
+        assert!((front.start.y - 20.0).abs() < 1e-9 && (front.end.y - 20.0).abs() < 1e-9);
+        let left = report.setback_violations.iter().find(|v| v.side == LotSide::Left).unwrap();
+        assert!((left.encroachment - 2.0).abs() < 1e-9);
+        assert_eq!(left.lot_edge, 3);
+
+        // Relaxed setbacks clear both
+        store
+            .set_site_setbacks(site_id, Setbacks { front: 20.0, back: 15.0, left: 3.0, right: 3.0 })
+            .unwrap();
+        assert!(check_zoning(&store, project_id).unwrap().is_compliant());
+        assert!(store
+            .set_site_setbacks(site_id, Setbacks { front: -1.0, back: 15.0, left: 3.0, right: 3.0 })
+            .is_err());
+    }
+
+    #[test]
+    fn test_coverage_and_height_limits() {
+        let (mut store, project_id, site_id, level_id) = setup(Point2::new(10.0, 30.0));
+        store
+            .create_roof(level_id, RoofType::Gable, 12.0, 0.0)
+            .unwrap();
+
+        let report = check_zoning(&store, project_id).unwrap();
+        assert!(report.is_compliant());
+        assert!((report.lot_coverage - 0.2).abs() < 1e-9);
+
+        // 30' wide gable at 12/12 peaks 15' over a 10' plate
+        let zoning = ZoningRules { front_edge: 0, max_lot_coverage: Some(0.15), max_height: Some(24.0) };
+        store.set_site_zoning(site_id, zoning).unwrap();
+        let report = check_zoning(&store, project_id).unwrap();
+        assert!(report.coverage_exceeded());
+        assert!((report.height_violations[0].height - 25.0).abs() < 1e-9);
+
+        // Settings replay through undo
+        store.undo(project_id).unwrap();
+        assert!(check_zoning(&store, project_id).unwrap().is_compliant());
+
+        let bad = ZoningRules { front_edge: 4, ..ZoningRules::default() };
+        assert!(store.set_site_zoning(site_id, bad).is_err());
+        assert!(check_zoning(&store, ProjectId::new()).is_err());
+    }
+}
@@ -0,0 +1,728 @@
+// Code compliance - checks a project against the requirements of its
+// CodeRegion. Requirements are looked up per code and edition so a design can
+// be re-checked after switching jurisdiction or code year. Rules are plain
+// check functions held in a RuleRegistry; the standard registry covers room
+// sizes, light and ventilation, egress, hallways, stairs, garage
+// separation and plumbing fixture clearances.
+
+pub mod rules;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::stair::{MAX_RISER_HEIGHT, MIN_STAIR_WIDTH, MIN_TREAD_DEPTH};
+use crate::domain::{CodeRegion, FixtureId, Level, LevelId, Project, ProjectId, RoomId, StairId, WallId};
+use crate::store::Store;
+
+/// Dimensional requirements of a building code edition (feet / square feet)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeRequirements {
+    /// Minimum floor area of a habitable room (kitchens exempt)
+    pub min_habitable_area: f64,
+    /// Minimum horizontal dimension of a habitable room (kitchens exempt)
+    pub min_habitable_dimension: f64,
+    /// Minimum ceiling height of a habitable room
+    pub min_ceiling_height: f64,
+    /// Area at least one habitable room must reach, if the code requires it
+    pub min_largest_room_area: Option<f64>,
+    /// Minimum clear width of a hallway
+    pub min_hallway_width: f64,
+    /// Tallest stair riser (inches)
+    pub max_riser_height: f64,
+    /// Shallowest stair tread (inches)
+    pub min_tread_depth: f64,
+    /// Narrowest stair
+    pub min_stair_width: f64,
+    /// Glazing area of a habitable room as a share of its floor area
+    pub min_glazing_ratio: f64,
+    /// Openable window area of a habitable room as a share of its floor area
+    pub min_ventilation_ratio: f64,
+}
+
+impl CodeRequirements {
+    /// Requirements for a code region. IRC is the fallback for unknown codes.
+    pub fn for_region(region: &CodeRegion) -> Self {
+        match region.code.to_uppercase().as_str() {
+            // IBC 1208, 1011 and 1020 (44" corridors and stairs), 1202.5 and 1204.2
+            "IBC" => Self {
+                min_habitable_area: 70.0,
+                min_habitable_dimension: 7.0,
+                min_ceiling_height: 7.5,
+                min_largest_room_area: None,
+                min_hallway_width: 44.0 / 12.0,
+                max_riser_height: 7.0,
+                min_tread_depth: 11.0,
+                min_stair_width: 44.0 / 12.0,
+                min_glazing_ratio: 0.08,
+                min_ventilation_ratio: 0.04,
+            },
+            // IRC R303/R304/R305/R311; the 120 sqft room rule was dropped in 2015
+            _ => Self {
+                min_habitable_area: 70.0,
+                min_habitable_dimension: 7.0,
+                min_ceiling_height: 7.0,
+                min_largest_room_area: (region.year < 2015).then_some(120.0),
+                min_hallway_width: 3.0,
+                max_riser_height: MAX_RISER_HEIGHT,
+                min_tread_depth: MIN_TREAD_DEPTH,
+                min_stair_width: MIN_STAIR_WIDTH,
+                min_glazing_ratio: 0.08,
+                min_ventilation_ratio: 0.04,
+            },
+        }
+    }
+}
+
+/// A checked requirement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceRule {
+    MinRoomArea,
+    MinRoomDimension,
+    MinCeilingHeight,
+    LargestRoomArea,
+    NaturalLight,
+    NaturalVentilation,
+    BedroomEgress,
+    HallwayWidth,
+    StairGeometry,
+    GarageSeparation,
+    FixtureClearance,
+    GarageFloorSlope,
+    GarageOpening,
+}
+
+impl ComplianceRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MinRoomArea => "min_room_area",
+            Self::MinRoomDimension => "min_room_dimension",
+            Self::MinCeilingHeight => "min_ceiling_height",
+            Self::LargestRoomArea => "largest_room_area",
+            Self::NaturalLight => "natural_light",
+            Self::NaturalVentilation => "natural_ventilation",
+            Self::BedroomEgress => "bedroom_egress",
+            Self::HallwayWidth => "hallway_width",
+            Self::StairGeometry => "stair_geometry",
+            Self::GarageSeparation => "garage_separation",
+            Self::FixtureClearance => "fixture_clearance",
+            Self::GarageFloorSlope => "garage_floor_slope",
+            Self::GarageOpening => "garage_opening",
+        }
+    }
+
+    /// Code section for the rule under the given code
+    pub fn section(&self, region: &CodeRegion) -> &'static str {
+        let ibc = region.code.eq_ignore_ascii_case("IBC");
+        match (self, ibc) {
+            (Self::MinRoomArea, false) | (Self::LargestRoomArea, false) => "R304.1",
+            (Self::MinRoomDimension, false) => "R304.2",
+            (Self::MinCeilingHeight, false) => "R305.1",
+            (Self::NaturalLight, false) | (Self::NaturalVentilation, false) => "R303.1",
+            (Self::BedroomEgress, false) => "R310.1",
+            (Self::HallwayWidth, false) => "R311.6",
+            (Self::StairGeometry, false) => "R311.7",
+            (Self::GarageSeparation, false) => "R302.6",
+            (Self::FixtureClearance, false) => "R307.1",
+            (Self::GarageFloorSlope, false) => "R309.1",
+            (Self::GarageOpening, false) => "R302.5.1",
+            (Self::MinRoomArea, true) | (Self::LargestRoomArea, true) => "1208.3",
+            (Self::MinRoomDimension, true) => "1208.1",
+            (Self::MinCeilingHeight, true) => "1208.2",
+            (Self::NaturalLight, true) => "1204.2",
+            (Self::NaturalVentilation, true) => "1202.5",
+            (Self::BedroomEgress, true) => "1031.2",
+            (Self::HallwayWidth, true) => "1020.2",
+            (Self::StairGeometry, true) => "1011.5",
+            (Self::GarageSeparation, true) => "406.3.2",
+            // Commercial fixture spacing is in the plumbing code
+            (Self::FixtureClearance, true) => "IPC 405.3.1",
+            (Self::GarageFloorSlope, true) => "406.2.4",
+            (Self::GarageOpening, true) => "406.3.2.1",
+        }
+    }
+}
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The design fails the requirement
+    #[default]
+    Error,
+    /// The design may pass but the model can't confirm it
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// A failed requirement and the elements responsible
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceViolation {
+    pub rule: ComplianceRule,
+    pub section: String,
+    pub message: String,
+    #[serde(default)]
+    pub severity: Severity,
+    pub level_id: Option<LevelId>,
+    pub room_ids: Vec<RoomId>,
+    #[serde(default)]
+    pub wall_ids: Vec<WallId>,
+    #[serde(default)]
+    pub stair_ids: Vec<StairId>,
+    #[serde(default)]
+    pub fixture_ids: Vec<FixtureId>,
+}
+
+impl ComplianceViolation {
+    /// Identity used to compare reports (same rule on the same rooms)
+    pub fn same_as(&self, other: &ComplianceViolation) -> bool {
+        self.rule == other.rule
+            && self.room_ids == other.room_ids
+            && self.wall_ids == other.wall_ids
+            && self.stair_ids == other.stair_ids
+            && self.fixture_ids == other.fixture_ids
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.severity == Severity::Warning
+    }
+}
+
+/// Result of checking a project against its code region
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    pub project_id: ProjectId,
+    pub code_region: CodeRegion,
+    /// Rules evaluated, in registry order
+    #[serde(default)]
+    pub checked: Vec<ComplianceRule>,
+    pub violations: Vec<ComplianceViolation>,
+}
+
+impl ComplianceReport {
+    /// True when no requirement fails outright; warnings don't count
+    pub fn is_compliant(&self) -> bool {
+        self.violations.iter().all(|v| v.is_warning())
+    }
+
+    pub fn violations_of(&self, rule: ComplianceRule) -> Vec<&ComplianceViolation> {
+        self.violations.iter().filter(|v| v.rule == rule).collect()
+    }
+
+    /// Checked rules with nothing flagged
+    pub fn satisfied(&self) -> Vec<ComplianceRule> {
+        self.checked
+            .iter()
+            .copied()
+            .filter(|rule| !self.violations.iter().any(|v| v.rule == *rule))
+            .collect()
+    }
+
+    pub fn errors(&self) -> Vec<&ComplianceViolation> {
+        self.violations.iter().filter(|v| !v.is_warning()).collect()
+    }
+
+    pub fn warnings(&self) -> Vec<&ComplianceViolation> {
+        self.violations.iter().filter(|v| v.is_warning()).collect()
+    }
+}
+
+/// Outcome of switching a project's code region
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeRegionChange {
+    pub old_region: CodeRegion,
+    pub new_region: CodeRegion,
+    /// Violations under the new code that didn't exist under the old one
+    pub newly_violated: Vec<ComplianceViolation>,
+    /// Violations under the old code that the new one no longer flags
+    pub resolved: Vec<ComplianceViolation>,
+    pub report: ComplianceReport,
+}
+
+/// What a rule check sees: the store, the project and its requirements
+pub struct RuleContext<'a> {
+    pub store: &'a Store,
+    pub project: &'a Project,
+    pub requirements: CodeRequirements,
+}
+
+impl<'a> RuleContext<'a> {
+    pub fn new(store: &'a Store, project: &'a Project) -> Self {
+        Self {
+            store,
+            project,
+            requirements: CodeRequirements::for_region(&project.code_region),
+        }
+    }
+
+    /// Levels of each dwelling and ADU in the project. Garages and sheds
+    /// aren't held to dwelling rules.
+    pub fn dwellings(&self) -> Vec<Vec<&'a Level>> {
+        self.project.building_ids
+            .iter()
+            .filter(|id| {
+                self.store.get_building(**id)
+                    .map(|b| b.occupancy.is_habitable())
+                    .unwrap_or(false)
+            })
+            .map(|id| self.store.get_building_levels(*id))
+            .collect()
+    }
+
+    /// Violation of a rule, with the section under the project's code
+    pub fn violation(
+        &self,
+        rule: ComplianceRule,
+        message: String,
+        level_id: Option<LevelId>,
+        room_ids: Vec<RoomId>,
+    ) -> ComplianceViolation {
+        ComplianceViolation {
+            rule,
+            section: rule.section(&self.project.code_region).to_string(),
+            message,
+            severity: Severity::Error,
+            level_id,
+            room_ids,
+            wall_ids: Vec::new(),
+            stair_ids: Vec::new(),
+            fixture_ids: Vec::new(),
+        }
+    }
+}
+
+/// A rule check, returning every violation it finds
+pub type RuleCheck = fn(&RuleContext) -> Vec<ComplianceViolation>;
+
+/// A registered rule
+#[derive(Clone)]
+pub struct RuleDefinition {
+    pub rule: ComplianceRule,
+    pub check: RuleCheck,
+}
+
+/// Ordered set of rules evaluated against a project
+#[derive(Clone, Default)]
+pub struct RuleRegistry {
+    rules: Vec<RuleDefinition>,
+}
+
+impl RuleRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every built-in rule
+    pub fn standard() -> Self {
+        let mut registry = Self::new();
+        registry.register(ComplianceRule::MinRoomArea, rules::min_room_area);
+        registry.register(ComplianceRule::MinRoomDimension, rules::min_room_dimension);
+        registry.register(ComplianceRule::MinCeilingHeight, rules::min_ceiling_height);
+        registry.register(ComplianceRule::LargestRoomArea, rules::largest_room_area);
+        registry.register(ComplianceRule::NaturalLight, rules::natural_light);
+        registry.register(ComplianceRule::NaturalVentilation, rules::natural_ventilation);
+        registry.register(ComplianceRule::BedroomEgress, rules::bedroom_egress);
+        registry.register(ComplianceRule::HallwayWidth, rules::hallway_width);
+        registry.register(ComplianceRule::StairGeometry, rules::stair_geometry);
+        registry.register(ComplianceRule::GarageSeparation, rules::garage_separation);
+        registry.register(ComplianceRule::FixtureClearance, rules::fixture_clearance);
+        registry.register(ComplianceRule::GarageFloorSlope, rules::garage_floor_slope);
+        registry.register(ComplianceRule::GarageOpening, rules::garage_opening);
+        registry
+    }
+
+    /// Add a rule, replacing any check already registered for it
+    pub fn register(&mut self, rule: ComplianceRule, check: RuleCheck) {
+        match self.rules.iter_mut().find(|r| r.rule == rule) {
+            Some(existing) => existing.check = check,
+            None => self.rules.push(RuleDefinition { rule, check }),
+        }
+    }
+
+    pub fn remove(&mut self, rule: ComplianceRule) {
+        self.rules.retain(|r| r.rule != rule);
+    }
+
+    pub fn rules(&self) -> Vec<ComplianceRule> {
+        self.rules.iter().map(|r| r.rule).collect()
+    }
+
+    /// Run every rule against a project
+    pub fn evaluate(&self, store: &Store, project_id: ProjectId) -> Result<ComplianceReport> {
+        let project = store.get_project(project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+        let ctx = RuleContext::new(store, project);
+
+        let violations = self.rules
+            .iter()
+            .flat_map(|r| (r.check)(&ctx))
+            .collect();
+
+        Ok(ComplianceReport {
+            project_id,
+            code_region: project.code_region.clone(),
+            checked: self.rules(),
+            violations,
+        })
+    }
+}
+
+/// Check a project against every standard rule for its code region
+pub fn check_compliance(store: &Store, project_id: ProjectId) -> Result<ComplianceReport> {
+    RuleRegistry::standard().evaluate(store, project_id)
+}
+
+/// Switch a project to another code region and report how compliance changed
+pub fn switch_code_region(
+    store: &mut Store,
+    project_id: ProjectId,
+    region: CodeRegion,
+) -> Result<CodeRegionChange> {
+    let before = check_compliance(store, project_id)?;
+    store.set_project_code_region(project_id, region.clone())?;
+    let report = check_compliance(store, project_id)?;
+
+    let newly_violated = report.violations
+        .iter()
+        .filter(|v| !before.violations.iter().any(|b| b.same_as(v)))
+        .cloned()
+        .collect();
+    let resolved = before.violations
+        .iter()
+        .filter(|b| !report.violations.iter().any(|v| v.same_as(b)))
+        .cloned()
+        .collect();
+
+    Ok(CodeRegionChange {
+        old_region: before.code_region,
+        new_region: region,
+        newly_violated,
+        resolved,
+        report,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        AssemblyRole, BuildingOccupancy, DoorProperties, OpeningType, PlumbingFixtureKind, Point2, Polygon2, RoomType, UnitSystem,
+        WallLayer,
+    };
+
+    fn setup(region: CodeRegion, floor_to_floor: f64) -> (Store, ProjectId, LevelId) {
+        let mut store = Store::new();
+        let project_id = store.create_project("Test", UnitSystem::Imperial, region).unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, floor_to_floor).unwrap();
+        (store, project_id, level_id)
+    }
+
+    #[test]
+    fn test_small_rooms_flagged() {
+        let (mut store, project_id, level_id) = setup(CodeRegion::us_irc_2021(), 9.0);
+        let office = store
+            .create_room(level_id, RoomType::Office, "Office", Polygon2::rectangle(6.0, 10.0))
+            .unwrap();
+        // Kitchens are exempt from area and dimension minimums
+        store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(6.0, 8.0))
+            .unwrap();
+        // Closets aren't habitable
+        store
+            .create_room(level_id, RoomType::Closet, "Closet", Polygon2::rectangle(3.0, 4.0))
+            .unwrap();
+
+        let report = check_compliance(&store, project_id).unwrap();
+        assert_eq!(report.errors().len(), 2);
+        assert_eq!(report.violations_of(ComplianceRule::MinRoomArea)[0].room_ids, vec![office]);
+        assert_eq!(report.violations_of(ComplianceRule::MinRoomDimension)[0].section, "R304.2");
+    }
+
+    #[test]
+    fn test_switch_code_region_reports_changes() {
+        // 7'2" ceilings pass IRC but fail IBC's 7'6"
+        let (mut store, project_id, level_id) = setup(CodeRegion::us_irc_2021(), 7.17);
+        store
+            .create_room(level_id, RoomType::Office, "Office", Polygon2::rectangle(10.0, 10.0))
+            .unwrap();
+        assert!(check_compliance(&store, project_id).unwrap().is_compliant());
+
+        let change = switch_code_region(&mut store, project_id, CodeRegion::us_ibc_2021()).unwrap();
+        assert_eq!(change.old_region, CodeRegion::us_irc_2021());
+        assert_eq!(change.newly_violated.len(), 1);
+        assert_eq!(change.newly_violated[0].rule, ComplianceRule::MinCeilingHeight);
+        assert!(change.resolved.is_empty());
+        assert_eq!(store.get_project(project_id).unwrap().code_region.code, "IBC");
+
+        // Switching back resolves it, and the switch is undoable
+        let back = switch_code_region(&mut store, project_id, CodeRegion::us_irc_2021()).unwrap();
+        assert_eq!(back.resolved.len(), 1);
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_project(project_id).unwrap().code_region.code, "IBC");
+    }
+
+    #[test]
+    fn test_older_irc_requires_one_large_room() {
+        let (mut store, project_id, level_id) = setup(CodeRegion::new("IRC", 2012), 9.0);
+        store
+            .create_room(level_id, RoomType::Office, "Office", Polygon2::rectangle(10.0, 10.0))
+            .unwrap();
+
+        let report = check_compliance(&store, project_id).unwrap();
+        assert_eq!(report.violations_of(ComplianceRule::LargestRoomArea).len(), 1);
+
+        let change = switch_code_region(&mut store, project_id, CodeRegion::us_irc_2021()).unwrap();
+        assert_eq!(change.resolved.len(), 1);
+        assert!(change.report.is_compliant());
+        assert!(check_compliance(&store, ProjectId::new()).is_err());
+    }
+
+    #[test]
+    fn test_accessory_structures_skipped() {
+        let (mut store, project_id, _) = setup(CodeRegion::us_irc_2021(), 9.0);
+        let shed = store.add_building(project_id, "Shed").unwrap();
+        store.set_building_occupancy(shed, BuildingOccupancy::Shed).unwrap();
+        let level_id = store.add_level(shed, "Floor", 0.0, 7.0).unwrap();
+        store
+            .create_room(level_id, RoomType::Office, "Studio", Polygon2::rectangle(6.0, 8.0))
+            .unwrap();
+        assert!(check_compliance(&store, project_id).unwrap().is_compliant());
+
+        // The same structure as an ADU is held to habitable-room rules
+        store.set_building_occupancy(shed, BuildingOccupancy::Adu).unwrap();
+        assert!(!check_compliance(&store, project_id).unwrap().is_compliant());
+    }
+
+    #[test]
+    fn test_bedroom_egress_and_hallway_width() {
+        let (mut store, project_id, level_id) = setup(CodeRegion::us_irc_2021(), 9.0);
+        let bedroom = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(12.0, 12.0))
+            .unwrap();
+        let hall = Polygon2::new(vec![
+            Point2::new(12.0, 0.0),
+            Point2::new(14.75, 0.0),
+            Point2::new(14.75, 12.0),
+            Point2::new(12.0, 12.0),
+        ]);
+        let hallway = store.create_room(level_id, RoomType::Hallway, "Hall", hall).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(12.0, 0.0), 9.0)
+            .unwrap();
+
+        let report = check_compliance(&store, project_id).unwrap();
+        let egress = report.violations_of(ComplianceRule::BedroomEgress);
+        assert_eq!(egress.len(), 1);
+        assert_eq!(egress[0].room_ids, vec![bedroom]);
+        assert_eq!(report.violations_of(ComplianceRule::HallwayWidth)[0].room_ids, vec![hallway]);
+        assert!(report.satisfied().contains(&ComplianceRule::StairGeometry));
+
+        // A large enough window without a catalog clear opening only warns
+        store.add_opening(wall_id, OpeningType::Window, 0.25, 3.0, 5.0, 2.0).unwrap();
+        let report = check_compliance(&store, project_id).unwrap();
+        assert!(report.violations_of(ComplianceRule::BedroomEgress)[0].is_warning());
+        assert_eq!(report.warnings().len(), 1);
+
+        // A catalog window with a qualifying clear opening passes
+        store.add_catalog_opening(wall_id, "3060", 0.75, 2.0).unwrap();
+        let report = check_compliance(&store, project_id).unwrap();
+        assert!(report.violations_of(ComplianceRule::BedroomEgress).is_empty());
+        assert_eq!(report.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_natural_light_and_ventilation() {
+        let (mut store, project_id, level_id) = setup(CodeRegion::us_irc_2021(), 9.0);
+        let office = store
+            .create_room(level_id, RoomType::Office, "Office", Polygon2::rectangle(10.0, 10.0))
+            .unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), 9.0)
+            .unwrap();
+
+        // 6 sq ft of glazing is 6% of the floor; both rules warn
+        store.add_opening(wall_id, OpeningType::Window, 0.3, 2.0, 3.0, 3.0).unwrap();
+        let report = check_compliance(&store, project_id).unwrap();
+        let light = report.violations_of(ComplianceRule::NaturalLight);
+        assert_eq!(light[0].room_ids, vec![office]);
+        assert_eq!(light[0].section, "R303.1");
+        assert!(light[0].is_warning());
+        assert_eq!(report.violations_of(ComplianceRule::NaturalVentilation).len(), 1);
+        assert!(report.is_compliant());
+
+        // A second window brings glazing to 21% and openable area to 10.5%
+        store.add_opening(wall_id, OpeningType::Window, 0.7, 3.0, 5.0, 2.0).unwrap();
+        let report = check_compliance(&store, project_id).unwrap();
+        assert!(report.satisfied().contains(&ComplianceRule::NaturalLight));
+        assert!(report.satisfied().contains(&ComplianceRule::NaturalVentilation));
+    }
+
+    #[test]
+    fn test_fixture_clearance() {
+        let (mut store, project_id, level_id) = setup(CodeRegion::us_irc_2021(), 9.0);
+        let bath = store
+            .create_room(level_id, RoomType::Bathroom, "Bath", Polygon2::rectangle(5.0, 8.0))
+            .unwrap();
+        // A toilet 12" off the side wall; it needs 15"
+        let toilet = store
+            .add_fixture(bath, PlumbingFixtureKind::Toilet, Point2::new(1.0, 0.0), 90.0)
+            .unwrap();
+
+        let report = check_compliance(&store, project_id).unwrap();
+        let clearance = report.violations_of(ComplianceRule::FixtureClearance);
+        assert_eq!(clearance.len(), 1);
+        assert_eq!(clearance[0].fixture_ids, vec![toilet]);
+        assert_eq!(clearance[0].room_ids, vec![bath]);
+        assert_eq!(clearance[0].section, "R307.1");
+        assert!(clearance[0].message.starts_with("Bath: Toilet"));
+
+        store.remove_fixture(toilet).unwrap();
+        store
+            .add_fixture(bath, PlumbingFixtureKind::Toilet, Point2::new(2.5, 0.0), 90.0)
+            .unwrap();
+        assert!(check_compliance(&store, project_id)
+            .unwrap()
+            .satisfied()
+            .contains(&ComplianceRule::FixtureClearance));
+    }
+
+    #[test]
+    fn test_stair_geometry_and_garage_separation() {
+        let (mut store, project_id, level_id) = setup(CodeRegion::us_irc_2021(), 9.0);
+        let building_id = store.get_level(level_id).unwrap().building_id;
+        let upper = store.add_level(building_id, "Second Floor", 9.0, 8.0).unwrap();
+        let stair_id = store
+            .create_stair(level_id, upper, Point2::new(30.0, 2.0), 90.0, 3.0, 7.75, 10.0)
+            .unwrap();
+
+        store
+            .create_room(level_id, RoomType::Garage, "Garage", Polygon2::rectangle(20.0, 20.0))
+            .unwrap();
+        let family = Polygon2::new(vec![
+            Point2::new(20.0, 0.0),
+            Point2::new(34.0, 0.0),
+            Point2::new(34.0, 20.0),
+            Point2::new(20.0, 20.0),
+        ]);
+        store.create_room(level_id, RoomType::FamilyRoom, "Family", family).unwrap();
+        let stud_only = store
+            .create_wall_assembly("Bare Studs", vec![WallLayer::stud_2x4()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, stud_only, Point2::new(20.0, 0.0), Point2::new(20.0, 20.0), 9.0)
+            .unwrap();
+
+        let report = check_compliance(&store, project_id).unwrap();
+        let separation = report.violations_of(ComplianceRule::GarageSeparation);
+        assert_eq!(separation.len(), 1);
+        assert_eq!(separation[0].wall_ids, vec![wall_id]);
+        assert!(report.violations_of(ComplianceRule::StairGeometry).is_empty());
+
+        store.remove_wall(wall_id).unwrap();
+        let rated = store.assembly_for_role(project_id, AssemblyRole::FireSeparation).unwrap();
+        store
+            .create_wall(level_id, rated, Point2::new(20.0, 0.0), Point2::new(20.0, 20.0), 9.0)
+            .unwrap();
+        assert!(check_compliance(&store, project_id)
+            .unwrap()
+            .violations_of(ComplianceRule::GarageSeparation)
+            .is_empty());
+
+        // IRC stairs are too steep and narrow for IBC
+        let change = switch_code_region(&mut store, project_id, CodeRegion::us_ibc_2021()).unwrap();
+        let stairs = change.report.violations_of(ComplianceRule::StairGeometry);
+        assert_eq!(stairs.len(), 1);
+        assert_eq!(stairs[0].stair_ids, vec![stair_id]);
+        assert_eq!(stairs[0].section, "1011.5");
+
+        // Rules can be dropped from a registry
+        let mut registry = RuleRegistry::standard();
+        registry.remove(ComplianceRule::StairGeometry);
+        let report = registry.evaluate(&store, project_id).unwrap();
+        assert!(!report.checked.contains(&ComplianceRule::StairGeometry));
+        assert!(report.violations_of(ComplianceRule::StairGeometry).is_empty());
+    }
+
+    #[test]
+    fn test_garage_slope_and_openings() {
+        let (mut store, project_id, level_id) = setup(CodeRegion::us_irc_2021(), 9.0);
+        let garage = store
+            .create_room(level_id, RoomType::Garage, "Garage", Polygon2::rectangle(20.0, 20.0))
+            .unwrap();
+        let bedroom = Polygon2::new(vec![
+            Point2::new(20.0, 0.0),
+            Point2::new(32.0, 0.0),
+            Point2::new(32.0, 12.0),
+            Point2::new(20.0, 12.0),
+        ]);
+        store.create_room(level_id, RoomType::Bedroom, "Bedroom", bedroom).unwrap();
+        let mudroom = Polygon2::new(vec![
+            Point2::new(20.0, 12.0),
+            Point2::new(32.0, 12.0),
+            Point2::new(32.0, 20.0),
+            Point2::new(20.0, 20.0),
+        ]);
+        store.create_room(level_id, RoomType::Mudroom, "Mudroom", mudroom).unwrap();
+        let studs = store
+            .create_wall_assembly("Bare Studs", vec![WallLayer::stud_2x4()])
+            .unwrap();
+        let front = store
+            .create_wall(level_id, studs, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let shared = store
+            .create_wall(level_id, studs, Point2::new(20.0, 0.0), Point2::new(20.0, 20.0), 9.0)
+            .unwrap();
+
+        // Unconfigured, then configured but with no vehicle door
+        let report = check_compliance(&store, project_id).unwrap();
+        assert_eq!(report.violations_of(ComplianceRule::GarageFloorSlope)[0].severity, Severity::Warning);
+        let garage_id = store.configure_garage(garage).unwrap();
+        assert_eq!(check_compliance(&store, project_id).unwrap().violations_of(ComplianceRule::GarageFloorSlope).len(), 1);
+
+        store
+            .add_opening(front, OpeningType::Other("garage_door".to_string()), 0.5, 16.0, 7.0, 0.0)
+            .unwrap();
+        let report = check_compliance(&store, project_id).unwrap();
+        assert!(report.violations_of(ComplianceRule::GarageFloorSlope).is_empty());
+        store.set_garage_slab(garage_id, 5.0, 0.0).unwrap();
+        let report = check_compliance(&store, project_id).unwrap();
+        let slope = report.violations_of(ComplianceRule::GarageFloorSlope);
+        assert_eq!(slope[0].severity, Severity::Error);
+        assert_eq!(slope[0].section, "R309.1");
+
+        // A door into the bedroom is prohibited; one into the mudroom needs
+        // a rating
+        let into_bedroom = store.add_opening(shared, OpeningType::Door, 0.3, 3.0, 6.67, 0.0).unwrap();
+        let into_mudroom = store.add_opening(shared, OpeningType::Door, 0.8, 3.0, 6.67, 0.0).unwrap();
+        let report = check_compliance(&store, project_id).unwrap();
+        let openings = report.violations_of(ComplianceRule::GarageOpening);
+        assert_eq!(openings.len(), 2);
+        let prohibited: Vec<_> = openings.iter().filter(|v| v.severity == Severity::Error).collect();
+        assert_eq!(prohibited.len(), 1);
+        assert!(prohibited[0].message.contains("Bedroom"));
+        assert_eq!(prohibited[0].wall_ids, vec![shared]);
+
+        store.remove_opening(into_bedroom).unwrap();
+        store.openings.get_mut(&into_mudroom).unwrap().door_properties = Some(DoorProperties::fire_rated(20));
+        assert!(check_compliance(&store, project_id)
+            .unwrap()
+            .violations_of(ComplianceRule::GarageOpening)
+            .is_empty());
+    }
+}
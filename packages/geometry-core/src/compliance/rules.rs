@@ -0,0 +1,482 @@
+// Built-in compliance rules
+// Each check walks the dwellings of a project and returns the violations it
+// finds. Checks are registered with a RuleRegistry by ComplianceRule.
+
+use crate::analysis::{daylight, egress};
+use crate::analysis::envelope::{collinear_overlap, polygon_edges};
+use crate::domain::{Level, OpeningType, Room, RoomType, Wall};
+use crate::store::Store;
+
+use super::{ComplianceRule, ComplianceViolation, RuleContext, Severity};
+
+/// Habitable rooms under the area minimum (kitchens exempt)
+pub fn min_room_area(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let min = ctx.requirements.min_habitable_area;
+    habitable_rooms(ctx)
+        .into_iter()
+        .filter(|(_, room)| room.room_type != RoomType::Kitchen && room.area() < min)
+        .map(|(level, room)| {
+            ctx.violation(
+                ComplianceRule::MinRoomArea,
+                format!(
+                    "{} is {:.0} sq ft; habitable rooms need {:.0} sq ft",
+                    room.name,
+                    room.area(),
+                    min
+                ),
+                Some(level.id),
+                vec![room.id],
+            )
+        })
+        .collect()
+}
+
+/// Habitable rooms narrower than the minimum dimension (kitchens exempt)
+pub fn min_room_dimension(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let min = ctx.requirements.min_habitable_dimension;
+    habitable_rooms(ctx)
+        .into_iter()
+        .filter(|(_, room)| room.room_type != RoomType::Kitchen && min_dimension(room) < min)
+        .map(|(level, room)| {
+            ctx.violation(
+                ComplianceRule::MinRoomDimension,
+                format!(
+                    "{} is {:.1} ft across; habitable rooms need {:.1} ft",
+                    room.name,
+                    min_dimension(room),
+                    min
+                ),
+                Some(level.id),
+                vec![room.id],
+            )
+        })
+        .collect()
+}
+
+/// Habitable rooms with a ceiling under the minimum height
+pub fn min_ceiling_height(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let min = ctx.requirements.min_ceiling_height;
+    habitable_rooms(ctx)
+        .into_iter()
+        .filter_map(|(level, room)| {
+            let height = room.ceiling_height.unwrap_or(level.floor_to_floor);
+            (height < min).then(|| {
+                ctx.violation(
+                    ComplianceRule::MinCeilingHeight,
+                    format!(
+                        "{} has a {:.2} ft ceiling; minimum is {:.2} ft",
+                        room.name, height, min
+                    ),
+                    Some(level.id),
+                    vec![room.id],
+                )
+            })
+        })
+        .collect()
+}
+
+/// Dwellings with no habitable room reaching the large-room area
+pub fn largest_room_area(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let Some(required) = ctx.requirements.min_largest_room_area else {
+        return Vec::new();
+    };
+
+    // Each dwelling unit needs its own large room
+    let mut violations = Vec::new();
+    for levels in ctx.dwellings() {
+        let habitable: Vec<&Room> = levels
+            .iter()
+            .flat_map(|level| level_rooms(ctx.store, level))
+            .filter(|r| r.room_type.is_habitable())
+            .collect();
+        if !habitable.is_empty() && habitable.iter().all(|r| r.area() < required) {
+            violations.push(ctx.violation(
+                ComplianceRule::LargestRoomArea,
+                format!("At least one habitable room must be {:.0} sq ft", required),
+                None,
+                habitable.iter().map(|r| r.id).collect(),
+            ));
+        }
+    }
+    violations
+}
+
+/// Habitable rooms with too little glazing. Artificial light can stand in
+/// for windows, so these are warnings.
+pub fn natural_light(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let min = ctx.requirements.min_glazing_ratio;
+    habitable_rooms(ctx)
+        .into_iter()
+        .filter_map(|(level, room)| {
+            let daylight = daylight::room_daylight(ctx.store, level, room);
+            (daylight.glazing_ratio() < min - 1e-9).then(|| {
+                let mut violation = ctx.violation(
+                    ComplianceRule::NaturalLight,
+                    format!(
+                        "{} has {:.1} sq ft of glazing ({:.1}% of floor area); {:.0}% or artificial light is required",
+                        room.name,
+                        daylight.glazing_area,
+                        daylight.glazing_ratio() * 100.0,
+                        min * 100.0
+                    ),
+                    Some(level.id),
+                    vec![room.id],
+                );
+                violation.severity = Severity::Warning;
+                violation
+            })
+        })
+        .collect()
+}
+
+/// Habitable rooms with too little openable window area. Mechanical
+/// ventilation can stand in for windows, so these are warnings.
+pub fn natural_ventilation(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let min = ctx.requirements.min_ventilation_ratio;
+    habitable_rooms(ctx)
+        .into_iter()
+        .filter_map(|(level, room)| {
+            let daylight = daylight::room_daylight(ctx.store, level, room);
+            (daylight.ventilation_ratio() < min - 1e-9).then(|| {
+                let mut violation = ctx.violation(
+                    ComplianceRule::NaturalVentilation,
+                    format!(
+                        "{} has {:.1} sq ft of openable windows ({:.1}% of floor area); {:.0}% or mechanical ventilation is required",
+                        room.name,
+                        daylight.openable_area,
+                        daylight.ventilation_ratio() * 100.0,
+                        min * 100.0
+                    ),
+                    Some(level.id),
+                    vec![room.id],
+                );
+                violation.severity = Severity::Warning;
+                violation
+            })
+        })
+        .collect()
+}
+
+/// Bedrooms without an emergency escape window. Windows placed from the
+/// catalog are judged on their clear opening; others only on the unit size,
+/// so they pass with a warning.
+pub fn bedroom_egress(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let mut violations = Vec::new();
+    for levels in ctx.dwellings() {
+        for level in levels {
+            for room in level_rooms(ctx.store, level) {
+                if room.room_type != RoomType::Bedroom {
+                    continue;
+                }
+                let egress = egress::bedroom_egress(ctx.store, level, room);
+                if egress.has_escape() {
+                    continue;
+                }
+
+                let violation = if egress.unverified.is_empty() {
+                    ctx.violation(
+                        ComplianceRule::BedroomEgress,
+                        format!("{} has no window that qualifies as an emergency escape opening", room.name),
+                        Some(level.id),
+                        vec![room.id],
+                    )
+                } else {
+                    let mut violation = ctx.violation(
+                        ComplianceRule::BedroomEgress,
+                        format!(
+                            "{} egress window is sized by its unit; verify the clear opening",
+                            room.name
+                        ),
+                        Some(level.id),
+                        vec![room.id],
+                    );
+                    violation.severity = Severity::Warning;
+                    violation.wall_ids = egress.unverified
+                        .iter()
+                        .filter_map(|id| ctx.store.get_opening(*id))
+                        .map(|o| o.wall_id)
+                        .collect();
+                    violation
+                };
+                violations.push(violation);
+            }
+        }
+    }
+    violations
+}
+
+/// Hallways narrower than the minimum width
+pub fn hallway_width(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let min = ctx.requirements.min_hallway_width;
+    let mut violations = Vec::new();
+    for levels in ctx.dwellings() {
+        for level in levels {
+            for room in level_rooms(ctx.store, level) {
+                let width = min_dimension(room);
+                if room.room_type == RoomType::Hallway && width < min - 1e-9 {
+                    violations.push(ctx.violation(
+                        ComplianceRule::HallwayWidth,
+                        format!(
+                            "{} is {:.2} ft wide; hallways need {:.2} ft",
+                            room.name, width, min
+                        ),
+                        Some(level.id),
+                        vec![room.id],
+                    ));
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Stairs whose solved riser, tread or width falls outside the code limits
+pub fn stair_geometry(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let req = &ctx.requirements;
+    let mut violations = Vec::new();
+    for levels in ctx.dwellings() {
+        for level in levels {
+            let mut stairs = ctx.store.get_level_stairs(level.id);
+            stairs.sort_by_key(|s| s.id.to_string());
+            for stair in stairs {
+                let Ok(run) = ctx.store.get_stair_run(stair.id) else {
+                    continue;
+                };
+
+                let mut problems = Vec::new();
+                if run.riser_height > req.max_riser_height + 1e-9 {
+                    problems.push(format!(
+                        "{:.2}\" risers exceed {:.2}\"",
+                        run.riser_height, req.max_riser_height
+                    ));
+                }
+                if run.tread_depth < req.min_tread_depth - 1e-9 {
+                    problems.push(format!(
+                        "{:.2}\" treads are under {:.2}\"",
+                        run.tread_depth, req.min_tread_depth
+                    ));
+                }
+                if stair.width < req.min_stair_width - 1e-9 {
+                    problems.push(format!(
+                        "{:.2} ft width is under {:.2} ft",
+                        stair.width, req.min_stair_width
+                    ));
+                }
+
+                if !problems.is_empty() {
+                    let mut violation = ctx.violation(
+                        ComplianceRule::StairGeometry,
+                        format!("Stair on {}: {}", level.name, problems.join(", ")),
+                        Some(level.id),
+                        Vec::new(),
+                    );
+                    violation.stair_ids = vec![stair.id];
+                    violations.push(violation);
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Walls between a garage and the dwelling without gypsum board
+pub fn garage_separation(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let mut violations = Vec::new();
+    for levels in ctx.dwellings() {
+        for level in levels {
+            let mut walls = ctx.store.get_level_walls(level.id);
+            walls.sort_by_key(|w| w.id.to_string());
+            for wall in walls {
+                let rooms = rooms_along(ctx.store, level, wall);
+                let garage = rooms.iter().any(|r| r.room_type == RoomType::Garage);
+                let dwelling = rooms
+                    .iter()
+                    .any(|r| r.room_type != RoomType::Garage && r.is_conditioned() == Some(true));
+                if !garage || !dwelling {
+                    continue;
+                }
+
+                let protected = ctx.store
+                    .get_wall_assembly(wall.assembly_id)
+                    .is_some_and(|a| a.separates_garage());
+                if !protected {
+                    let mut violation = ctx.violation(
+                        ComplianceRule::GarageSeparation,
+                        format!(
+                            "Wall between the garage and dwelling on {} needs 1/2\" gypsum board",
+                            level.name
+                        ),
+                        Some(level.id),
+                        rooms.iter().map(|r| r.id).collect(),
+                    );
+                    violation.wall_ids = vec![wall.id];
+                    violations.push(violation);
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Garage slabs that don't drain toward a vehicle door. Detached garages
+/// are checked along with attached ones.
+pub fn garage_floor_slope(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let mut violations = Vec::new();
+    for building_id in &ctx.project.building_ids {
+        for level in ctx.store.get_building_levels(*building_id) {
+            for room in level_rooms(ctx.store, level) {
+                if room.room_type != RoomType::Garage {
+                    continue;
+                }
+                let (message, severity) = match ctx.store.get_room_garage(room.id) {
+                    None => (
+                        format!("{} has no slab settings; its floor slope can't be checked", room.name),
+                        Severity::Warning,
+                    ),
+                    Some(garage) if garage.slab_slope <= 0.0 => (
+                        format!("{} slab is level; it must slope toward the vehicle door", room.name),
+                        Severity::Error,
+                    ),
+                    Some(_) if ctx.store.garage_vehicle_doors(room.id).is_empty() => (
+                        format!("{} has no vehicle door for its slab to drain toward", room.name),
+                        Severity::Warning,
+                    ),
+                    Some(_) => continue,
+                };
+                let mut violation = ctx.violation(
+                    ComplianceRule::GarageFloorSlope,
+                    message,
+                    Some(level.id),
+                    vec![room.id],
+                );
+                violation.severity = severity;
+                violations.push(violation);
+            }
+        }
+    }
+    violations
+}
+
+/// Doors between a garage and the dwelling. Opening into a sleeping room
+/// is prohibited; other doors need a solid or 20-minute rated slab, which
+/// is a warning when the door doesn't record a rating.
+pub fn garage_opening(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let mut violations = Vec::new();
+    for levels in ctx.dwellings() {
+        for level in levels {
+            let mut walls = ctx.store.get_level_walls(level.id);
+            walls.sort_by_key(|w| w.id.to_string());
+            for wall in walls {
+                let rooms = rooms_along(ctx.store, level, wall);
+                let Some(garage) = rooms.iter().find(|r| r.room_type == RoomType::Garage) else {
+                    continue;
+                };
+                let length = wall.length();
+                let mut openings = ctx.store.get_wall_openings(wall.id);
+                openings.sort_by_key(|o| o.id.to_string());
+                for opening in openings {
+                    if opening.opening_type != OpeningType::Door {
+                        continue;
+                    }
+                    // Span of the door along the wall
+                    let center = opening.position_along_wall * length;
+                    let start = wall.point_at((center - opening.width / 2.0).max(0.0));
+                    let end = wall.point_at((center + opening.width / 2.0).min(length));
+                    let Some(room) = rooms.iter().find(|r| {
+                        r.room_type != RoomType::Garage
+                            && r.is_conditioned() == Some(true)
+                            && polygon_edges(&r.boundary)
+                                .iter()
+                                .any(|(a, b)| collinear_overlap(&start, &end, a, b).is_some())
+                    }) else {
+                        continue;
+                    };
+
+                    let (message, severity) = if room.room_type == RoomType::Bedroom {
+                        (
+                            format!("Door from {} opens into {}; garages can't open into sleeping rooms", garage.name, room.name),
+                            Severity::Error,
+                        )
+                    } else if opening.door_properties.as_ref().and_then(|d| d.fire_rating).is_none() {
+                        (
+                            format!("Door from {} into {} needs a solid or 20-minute rated door", garage.name, room.name),
+                            Severity::Warning,
+                        )
+                    } else {
+                        continue;
+                    };
+                    let mut violation = ctx.violation(
+                        ComplianceRule::GarageOpening,
+                        message,
+                        Some(level.id),
+                        vec![garage.id, room.id],
+                    );
+                    violation.severity = severity;
+                    violation.wall_ids = vec![wall.id];
+                    violations.push(violation);
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Toilets, sinks, tubs and showers without their clear floor space
+pub fn fixture_clearance(ctx: &RuleContext) -> Vec<ComplianceViolation> {
+    let mut violations = Vec::new();
+    for levels in ctx.dwellings() {
+        for level in levels {
+            for clearance in ctx.store.check_fixture_clearances(level.id) {
+                let room_name = ctx.store.get_room(clearance.room_id).map(|r| r.name.as_str()).unwrap_or("Room");
+                let mut violation = ctx.violation(
+                    ComplianceRule::FixtureClearance,
+                    format!("{}: {}", room_name, clearance.message()),
+                    Some(level.id),
+                    vec![clearance.room_id],
+                );
+                violation.fixture_ids = vec![clearance.fixture_id];
+                violations.push(violation);
+            }
+        }
+    }
+    violations
+}
+
+/// Habitable rooms of every dwelling, with their level
+fn habitable_rooms<'a>(ctx: &RuleContext<'a>) -> Vec<(&'a Level, &'a Room)> {
+    ctx.dwellings()
+        .into_iter()
+        .flatten()
+        .flat_map(|level| {
+            level_rooms(ctx.store, level)
+                .into_iter()
+                .filter(|r| r.room_type.is_habitable())
+                .map(move |room| (level, room))
+        })
+        .collect()
+}
+
+/// Rooms on a level in the level's own order
+fn level_rooms<'a>(store: &'a Store, level: &Level) -> Vec<&'a Room> {
+    level.room_ids.iter().filter_map(|id| store.get_room(*id)).collect()
+}
+
+/// Rooms with a boundary edge running along the wall
+fn rooms_along<'a>(store: &'a Store, level: &Level, wall: &Wall) -> Vec<&'a Room> {
+    level_rooms(store, level)
+        .into_iter()
+        .filter(|room| {
+            polygon_edges(&room.boundary)
+                .iter()
+                .any(|(a, b)| collinear_overlap(&wall.start, &wall.end, a, b).is_some())
+        })
+        .collect()
+}
+
+/// Smaller side of the room's bounding box
+pub(crate) fn min_dimension(room: &Room) -> f64 {
+    let points = &room.boundary.outer;
+    let (min_x, max_x) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.x), hi.max(p.x)));
+    let (min_y, max_y) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+    (max_x - min_x).min(max_y - min_y)
+}
@@ -0,0 +1,289 @@
+// Named constraint checking
+// Every constraint in a project is evaluated against the current geometry;
+// mutations wrapped in `check_mutation` report which constraints they broke
+// and which they brought back into line.
+
+pub mod solver;
+
+pub use solver::{solve_constraints, SolveReport};
+
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::*;
+use crate::store::Store;
+
+/// Slack allowed on lengths (feet) before a constraint counts as broken
+pub const CONSTRAINT_TOLERANCE: f64 = 0.01;
+
+/// Walls whose directions differ by more than this (sine of the angle)
+/// aren't parallel
+const PARALLEL_TOLERANCE: f64 = 1e-3;
+
+/// How one constraint stands against the current geometry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintStatus {
+    pub constraint_id: ConstraintId,
+    pub name: String,
+    pub kind: String,
+    pub satisfied: bool,
+    /// Measured value: area, ratio, deviation from the axis or distance.
+    /// None when it can't be measured.
+    pub actual: Option<f64>,
+    /// Value the constraint asks for
+    pub target: f64,
+    pub message: String,
+}
+
+/// Every constraint of a project, in name order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintReport {
+    pub project_id: ProjectId,
+    pub statuses: Vec<ConstraintStatus>,
+}
+
+impl ConstraintReport {
+    pub fn broken(&self) -> Vec<&ConstraintStatus> {
+        self.statuses.iter().filter(|s| !s.satisfied).collect()
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.statuses.iter().all(|s| s.satisfied)
+    }
+
+    fn broken_ids(&self) -> HashSet<ConstraintId> {
+        self.broken().iter().map(|s| s.constraint_id).collect()
+    }
+}
+
+/// Constraints a mutation broke and ones it fixed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConstraintChanges {
+    pub broken: Vec<ConstraintStatus>,
+    pub resolved: Vec<ConstraintStatus>,
+}
+
+impl ConstraintChanges {
+    pub fn is_empty(&self) -> bool {
+        self.broken.is_empty() && self.resolved.is_empty()
+    }
+}
+
+/// Evaluate every constraint of a project
+pub fn check_constraints(store: &Store, project_id: ProjectId) -> Result<ConstraintReport> {
+    if store.get_project(project_id).is_none() {
+        return Err(anyhow!("Project not found: {:?}", project_id));
+    }
+
+    let statuses = store
+        .get_project_constraints(project_id)
+        .into_iter()
+        .map(|c| evaluate(store, c))
+        .collect();
+
+    Ok(ConstraintReport { project_id, statuses })
+}
+
+/// Run a mutation and report the constraints it broke or resolved
+pub fn check_mutation<T>(
+    store: &mut Store,
+    project_id: ProjectId,
+    f: impl FnOnce(&mut Store) -> Result<T>,
+) -> Result<(T, ConstraintChanges)> {
+    let before = check_constraints(store, project_id)?;
+    let value = f(store)?;
+    let after = check_constraints(store, project_id)?;
+    Ok((value, diff_reports(&before, &after)))
+}
+
+/// Constraints broken in `after` but not `before`, and the reverse
+pub fn diff_reports(before: &ConstraintReport, after: &ConstraintReport) -> ConstraintChanges {
+    let was_broken = before.broken_ids();
+    let is_broken = after.broken_ids();
+
+    ConstraintChanges {
+        broken: after
+            .statuses
+            .iter()
+            .filter(|s| !s.satisfied && !was_broken.contains(&s.constraint_id))
+            .cloned()
+            .collect(),
+        resolved: after
+            .statuses
+            .iter()
+            .filter(|s| was_broken.contains(&s.constraint_id) && !is_broken.contains(&s.constraint_id))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Evaluate one constraint
+pub fn evaluate(store: &Store, constraint: &Constraint) -> ConstraintStatus {
+    let (actual, target, satisfied, message) = match &constraint.kind {
+        ConstraintKind::RoomMinArea { room_id, min_area } => match store.get_room(*room_id) {
+            Some(room) => {
+                let area = room.area();
+                let satisfied = area >= *min_area - CONSTRAINT_TOLERANCE;
+                let message = format!("{} is {:.1} sq ft, needs at least {:.1}", room.name, area, min_area);
+                (Some(area), *min_area, satisfied, message)
+            }
+            None => (None, *min_area, false, "Room no longer exists".to_string()),
+        },
+        ConstraintKind::RoomAspectRatio { room_id, max_ratio } => match store.get_room(*room_id) {
+            Some(room) => {
+                let ratio = aspect_ratio(&room.boundary);
+                let satisfied = ratio <= *max_ratio + 1e-6;
+                let message = format!("{} is {:.2}:1, allowed up to {:.2}:1", room.name, ratio, max_ratio);
+                (ratio.is_finite().then_some(ratio), *max_ratio, satisfied, message)
+            }
+            None => (None, *max_ratio, false, "Room no longer exists".to_string()),
+        },
+        ConstraintKind::WallOnGridAxis { wall_id, axis } => {
+            match (store.get_wall(*wall_id), store.wall_grid_axis(*wall_id, axis)) {
+                (Some(wall), Some(grid_axis)) => {
+                    let deviation = axis_deviation(wall, grid_axis);
+                    let message = format!("Wall is {:.2} ft off axis {}", deviation, grid_axis.name);
+                    (Some(deviation), 0.0, deviation <= CONSTRAINT_TOLERANCE, message)
+                }
+                (None, _) => (None, 0.0, false, "Wall no longer exists".to_string()),
+                (_, None) => (None, 0.0, false, format!("Grid axis {} no longer exists", axis)),
+            }
+        }
+        ConstraintKind::WallDistance { wall_a, wall_b, distance } => {
+            match (store.get_wall(*wall_a), store.get_wall(*wall_b)) {
+                (Some(a), Some(b)) => match wall_offset(a, b) {
+                    Some(offset) => {
+                        let actual = offset.abs();
+                        let satisfied = (actual - distance).abs() <= CONSTRAINT_TOLERANCE;
+                        let message = format!("Walls are {:.2} ft apart, should be {:.2}", actual, distance);
+                        (Some(actual), *distance, satisfied, message)
+                    }
+                    None => (None, *distance, false, "Walls are not parallel".to_string()),
+                },
+                _ => (None, *distance, false, "Wall no longer exists".to_string()),
+            }
+        }
+    };
+
+    ConstraintStatus {
+        constraint_id: constraint.id,
+        name: constraint.name.clone(),
+        kind: constraint.kind.as_str().to_string(),
+        satisfied,
+        actual,
+        target,
+        message,
+    }
+}
+
+/// Width and depth of a polygon's bounding box
+pub(crate) fn extents(polygon: &Polygon2) -> (f64, f64) {
+    let xs = polygon.outer.iter().map(|p| p.x);
+    let ys = polygon.outer.iter().map(|p| p.y);
+    let width = xs.clone().fold(f64::MIN, f64::max) - xs.fold(f64::MAX, f64::min);
+    let depth = ys.clone().fold(f64::MIN, f64::max) - ys.fold(f64::MAX, f64::min);
+    (width, depth)
+}
+
+/// Longer side of the bounding box over the shorter
+fn aspect_ratio(polygon: &Polygon2) -> f64 {
+    let (width, depth) = extents(polygon);
+    let short = width.min(depth);
+    if short <= 0.0 {
+        return f64::INFINITY;
+    }
+    width.max(depth) / short
+}
+
+/// Furthest a wall end sits from a grid axis
+pub(crate) fn axis_deviation(wall: &Wall, axis: &GridAxis) -> f64 {
+    let coord = |p: &Point2| match axis.direction {
+        GridDirection::Horizontal => p.y,
+        GridDirection::Vertical => p.x,
+    };
+    (coord(&wall.start) - axis.offset).abs().max((coord(&wall.end) - axis.offset).abs())
+}
+
+/// Unit normal to the left of a wall's chord
+pub(crate) fn wall_normal(wall: &Wall) -> Option<(f64, f64)> {
+    let dx = wall.end.x - wall.start.x;
+    let dy = wall.end.y - wall.start.y;
+    let length = (dx * dx + dy * dy).sqrt();
+    (length > 0.0).then(|| (-dy / length, dx / length))
+}
+
+/// Signed distance from wall A's line to the middle of wall B, measured
+/// along A's left normal. None when the walls aren't parallel.
+pub(crate) fn wall_offset(a: &Wall, b: &Wall) -> Option<f64> {
+    let (nax, nay) = wall_normal(a)?;
+    let (nbx, nby) = wall_normal(b)?;
+    if (nax * nby - nay * nbx).abs() > PARALLEL_TOLERANCE {
+        return None;
+    }
+    let mid_x = (b.start.x + b.end.x) / 2.0;
+    let mid_y = (b.start.y + b.end.y) / 2.0;
+    Some((mid_x - a.start.x) * nax + (mid_y - a.start.y) * nay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutation_reports_broken_constraints() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Wall", vec![WallLayer::stud_2x4()])
+            .unwrap();
+        let bedroom = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(12.0, 12.0))
+            .unwrap();
+        let west = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(0.0, 20.0), 9.0)
+            .unwrap();
+        let east = store
+            .create_wall(level_id, assembly_id, Point2::new(14.0, 0.0), Point2::new(14.0, 20.0), 9.0)
+            .unwrap();
+
+        store
+            .add_constraint(project_id, "Bedroom size", ConstraintKind::RoomMinArea { room_id: bedroom, min_area: 120.0 })
+            .unwrap();
+        store
+            .add_constraint(project_id, "Span", ConstraintKind::WallDistance { wall_a: west, wall_b: east, distance: 14.0 })
+            .unwrap();
+        assert!(store.add_constraint(project_id, "SPAN", ConstraintKind::RoomMinArea { room_id: bedroom, min_area: 1.0 }).is_err());
+        assert!(store.add_constraint(project_id, "Axis", ConstraintKind::WallOnGridAxis { wall_id: west, axis: "A".into() }).is_err());
+        assert!(check_constraints(&store, project_id).unwrap().is_satisfied());
+
+        // Moving the east wall in breaks the span; shrinking the bedroom
+        // breaks its size
+        let (_, changes) = check_mutation(&mut store, project_id, |s| {
+            s.set_wall_endpoints(east, Point2::new(12.0, 0.0), Point2::new(12.0, 20.0))
+        })
+        .unwrap();
+        assert_eq!(changes.broken.len(), 1);
+        assert_eq!(changes.broken[0].name, "Span");
+        assert_eq!(changes.broken[0].actual, Some(12.0));
+
+        let (_, changes) = check_mutation(&mut store, project_id, |s| {
+            s.set_room_boundary(bedroom, Polygon2::rectangle(10.0, 10.0))
+        })
+        .unwrap();
+        assert_eq!(changes.broken[0].name, "Bedroom size");
+        assert_eq!(check_constraints(&store, project_id).unwrap().broken().len(), 2);
+
+        // Undoing the move resolves the span
+        store.undo(project_id).unwrap();
+        store.undo(project_id).unwrap();
+        let report = check_constraints(&store, project_id).unwrap();
+        assert!(report.is_satisfied());
+        assert_eq!(store.get_wall(east).unwrap().start, Point2::new(14.0, 0.0));
+    }
+}
@@ -0,0 +1,230 @@
+// Iterative constraint solver
+// Each pass nudges the geometry behind every broken constraint: rooms are
+// scaled about their centroid, walls are snapped to grid axes or slid along
+// a partner wall's normal. Fixing one constraint can break another, so
+// passes repeat until everything holds or the iteration budget runs out.
+// The whole solve is one transaction - if an adjustment fails nothing moves.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{axis_deviation, check_constraints, extents, wall_normal, wall_offset, ConstraintReport};
+use crate::domain::*;
+use crate::store::Store;
+
+/// What a solve changed and where it ended up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolveReport {
+    /// Passes that adjusted geometry
+    pub iterations: usize,
+    pub adjusted_rooms: Vec<RoomId>,
+    pub moved_walls: Vec<WallId>,
+    /// Constraints after the last pass
+    pub report: ConstraintReport,
+}
+
+impl SolveReport {
+    pub fn converged(&self) -> bool {
+        self.report.is_satisfied()
+    }
+}
+
+/// Adjust geometry until a project's constraints hold, or give up after
+/// `max_iterations` passes
+pub fn solve_constraints(store: &mut Store, project_id: ProjectId, max_iterations: usize) -> Result<SolveReport> {
+    store.transaction(|store| {
+        let mut adjusted_rooms: Vec<RoomId> = Vec::new();
+        let mut moved_walls: Vec<WallId> = Vec::new();
+        let mut iterations = 0;
+
+        let mut report = check_constraints(store, project_id)?;
+        while iterations < max_iterations && !report.is_satisfied() {
+            let broken: Vec<Constraint> = report
+                .broken()
+                .iter()
+                .filter_map(|s| store.get_constraint(s.constraint_id).cloned())
+                .collect();
+
+            let mut changed = false;
+            for constraint in &broken {
+                match adjust(store, &constraint.kind)? {
+                    Some(Adjusted::Room(id)) => {
+                        if !adjusted_rooms.contains(&id) {
+                            adjusted_rooms.push(id);
+                        }
+                        changed = true;
+                    }
+                    Some(Adjusted::Wall(id)) => {
+                        if !moved_walls.contains(&id) {
+                            moved_walls.push(id);
+                        }
+                        changed = true;
+                    }
+                    None => {}
+                }
+            }
+            if !changed {
+                break;
+            }
+            iterations += 1;
+            report = check_constraints(store, project_id)?;
+        }
+
+        Ok(SolveReport {
+            iterations,
+            adjusted_rooms,
+            moved_walls,
+            report,
+        })
+    })
+}
+
+enum Adjusted {
+    Room(RoomId),
+    Wall(WallId),
+}
+
+/// Move the geometry behind one broken constraint towards satisfying it.
+/// None when there is nothing the solver can change.
+fn adjust(store: &mut Store, kind: &ConstraintKind) -> Result<Option<Adjusted>> {
+    match kind {
+        ConstraintKind::RoomMinArea { room_id, min_area } => {
+            let Some(room) = store.get_room(*room_id) else { return Ok(None) };
+            let area = room.area();
+            if area <= 0.0 {
+                return Ok(None);
+            }
+            let scale = (min_area / area).sqrt();
+            let boundary = scale_about_centroid(&room.boundary, scale, scale);
+            store.set_room_boundary(*room_id, boundary)?;
+            Ok(Some(Adjusted::Room(*room_id)))
+        }
+        ConstraintKind::RoomAspectRatio { room_id, max_ratio } => {
+            let Some(room) = store.get_room(*room_id) else { return Ok(None) };
+            let (width, depth) = extents(&room.boundary);
+            if width <= 0.0 || depth <= 0.0 {
+                return Ok(None);
+            }
+            // Squeeze the long side and stretch the short one by the same
+            // factor, which keeps the area
+            let (sx, sy) = if width >= depth {
+                let s = (max_ratio * depth / width).sqrt();
+                (s, 1.0 / s)
+            } else {
+                let s = (max_ratio * width / depth).sqrt();
+                (1.0 / s, s)
+            };
+            let boundary = scale_about_centroid(&room.boundary, sx, sy);
+            store.set_room_boundary(*room_id, boundary)?;
+            Ok(Some(Adjusted::Room(*room_id)))
+        }
+        ConstraintKind::WallOnGridAxis { wall_id, axis } => {
+            let (Some(wall), Some(axis)) = (store.get_wall(*wall_id), store.wall_grid_axis(*wall_id, axis)) else {
+                return Ok(None);
+            };
+            // A wall across the axis can't be snapped onto it
+            let along = match axis.direction {
+                GridDirection::Horizontal => (wall.end.x - wall.start.x).abs(),
+                GridDirection::Vertical => (wall.end.y - wall.start.y).abs(),
+            };
+            if along < 1e-10 || axis_deviation(wall, axis) == 0.0 {
+                return Ok(None);
+            }
+            let snap = |p: Point2| match axis.direction {
+                GridDirection::Horizontal => Point2::new(p.x, axis.offset),
+                GridDirection::Vertical => Point2::new(axis.offset, p.y),
+            };
+            let (start, end) = (snap(wall.start), snap(wall.end));
+            store.set_wall_endpoints(*wall_id, start, end)?;
+            Ok(Some(Adjusted::Wall(*wall_id)))
+        }
+        ConstraintKind::WallDistance { wall_a, wall_b, distance } => {
+            let (Some(a), Some(b)) = (store.get_wall(*wall_a), store.get_wall(*wall_b)) else {
+                return Ok(None);
+            };
+            let (Some(offset), Some((nx, ny))) = (wall_offset(a, b), wall_normal(a)) else {
+                return Ok(None);
+            };
+            // Keep wall B on the side of A it's already on
+            let target = if offset < 0.0 { -distance } else { *distance };
+            let shift = target - offset;
+            let start = Point2::new(b.start.x + nx * shift, b.start.y + ny * shift);
+            let end = Point2::new(b.end.x + nx * shift, b.end.y + ny * shift);
+            store.set_wall_endpoints(*wall_b, start, end)?;
+            Ok(Some(Adjusted::Wall(*wall_b)))
+        }
+    }
+}
+
+fn scale_about_centroid(polygon: &Polygon2, sx: f64, sy: f64) -> Polygon2 {
+    let center = polygon.centroid();
+    let scale = |ring: &[Point2]| -> Vec<Point2> {
+        ring.iter()
+            .map(|p| Point2::new(center.x + (p.x - center.x) * sx, center.y + (p.y - center.y) * sy))
+            .collect()
+    };
+    Polygon2::with_holes(scale(&polygon.outer), polygon.holes.iter().map(|h| scale(h)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solver_satisfies_constraints() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Wall", vec![WallLayer::stud_2x4()])
+            .unwrap();
+        store.create_grid(building_id).unwrap();
+        store
+            .add_grid_axis(building_id, GridAxis { name: "A".into(), direction: GridDirection::Horizontal, offset: 0.0 })
+            .unwrap();
+
+        let hall = store
+            .create_room(level_id, RoomType::Hallway, "Hall", Polygon2::rectangle(30.0, 4.0))
+            .unwrap();
+        let south = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.3), Point2::new(30.0, 0.5), 9.0)
+            .unwrap();
+        let north = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 10.0), Point2::new(30.0, 10.0), 9.0)
+            .unwrap();
+
+        store
+            .add_constraint(project_id, "Hall size", ConstraintKind::RoomMinArea { room_id: hall, min_area: 150.0 })
+            .unwrap();
+        store
+            .add_constraint(project_id, "Hall shape", ConstraintKind::RoomAspectRatio { room_id: hall, max_ratio: 3.0 })
+            .unwrap();
+        store
+            .add_constraint(project_id, "South on A", ConstraintKind::WallOnGridAxis { wall_id: south, axis: "A".into() })
+            .unwrap();
+        store
+            .add_constraint(project_id, "Depth", ConstraintKind::WallDistance { wall_a: south, wall_b: north, distance: 12.0 })
+            .unwrap();
+        assert_eq!(check_constraints(&store, project_id).unwrap().broken().len(), 4);
+
+        // Nothing moves without a budget
+        let solve = solve_constraints(&mut store, project_id, 0).unwrap();
+        assert_eq!(solve.iterations, 0);
+        assert!(!solve.converged());
+
+        let solve = solve_constraints(&mut store, project_id, 10).unwrap();
+        assert!(solve.converged(), "{:?}", solve.report.broken());
+        assert_eq!(solve.adjusted_rooms, vec![hall]);
+        assert_eq!(solve.moved_walls.len(), 2);
+
+        let room = store.get_room(hall).unwrap();
+        assert!(room.area() >= 150.0 - 0.01);
+        let (width, depth) = extents(&room.boundary);
+        assert!(width / depth <= 3.0 + 1e-6);
+        let north = store.get_wall(north).unwrap();
+        assert!((north.start.y - 12.0).abs() < 0.01 && (north.end.y - 12.0).abs() < 0.01);
+    }
+}
@@ -1,8 +1,42 @@
 //! Cost Calculation Engine
 //! Generates cost estimates from floor plan geometry and price tables
 
+use std::collections::HashMap;
+
 use crate::domain::costing::*;
-use crate::domain::{LevelId, OpeningId, RoomId};
+use crate::domain::{
+    DeckKind, ElectricalDeviceKind, FinishSurface, FoundationQuantities, FramingMaterial, GarageSlab, HeaderSize, HeaderType, LevelId, LumberSize,
+    MemberProfile, OpeningId, PlumbingFixtureKind, RoofTopology, RoomFinishes, RoomId, SlabLayer,
+};
+use crate::analysis::CutFill;
+use super::prices::PriceCsvImport;
+
+/// Rough-in and trim-out time per electrical device, for hourly rates
+const ELECTRICAL_HOURS_PER_DEVICE: f64 = 0.75;
+
+/// Drain, vent and supply rough-in per wet wall, for hourly rates
+const PLUMBING_HOURS_PER_WET_WALL: f64 = 8.0;
+
+/// Setting and connecting one plumbing fixture, for hourly rates
+const PLUMBING_HOURS_PER_FIXTURE: f64 = 2.0;
+
+/// Running one duct and setting its register, for hourly rates
+const HVAC_HOURS_PER_DUCT_RUN: f64 = 3.0;
+
+/// Framing, decking and railing per square foot of deck, for hourly rates
+const DECK_HOURS_PER_SQFT: f64 = 0.15;
+
+/// Digging a pad down per cubic yard, for hourly rates
+const EXCAVATION_HOURS_PER_CY: f64 = 0.1;
+
+/// Placing and compacting fill in lifts per cubic yard, for hourly rates
+const FILL_HOURS_PER_CY: f64 = 0.2;
+
+/// Hanging gutter per linear foot, for hourly rates
+const GUTTER_HOURS_PER_FOOT: f64 = 0.08;
+
+/// Running and strapping one downspout, for hourly rates
+const DOWNSPOUT_HOURS: f64 = 0.75;
 
 /// Input data for cost calculation
 pub struct CostInput {
@@ -14,8 +48,41 @@ pub struct CostInput {
     pub interior_wall_linear_ft: f64,
     pub roof_sqft: f64,
     pub foundation_sqft: f64,
+    /// Modeled foundation under this level, priced instead of `foundation_sqft`
+    pub foundation: Option<FoundationQuantities>,
     pub rooms: Vec<RoomCostInput>,
     pub openings: Vec<OpeningCostInput>,
+    /// Modeled floor slabs and ceilings
+    pub assemblies: Vec<AssemblyCostInput>,
+    /// Headers from generated wall framing
+    pub headers: Vec<HeaderCostInput>,
+    /// Stock lumber cut for generated wall framing, built-up headers included
+    pub lumber: Vec<LumberCostInput>,
+    /// Walls without generated framing, when some walls on the level have it
+    pub unframed_walls: Vec<UnframedWallCostInput>,
+    /// Roof area comes from a modeled roof
+    pub roof_modeled: bool,
+    /// Edges and drainage of the modeled roof
+    pub roof_topology: Option<RoofTopology>,
+    /// Modeled beams and posts
+    pub beams: Vec<BeamCostInput>,
+    pub posts: Vec<PostCostInput>,
+    /// Placed electrical devices, counted by kind
+    pub electrical: Vec<ElectricalCostInput>,
+    /// Placed plumbing fixtures, counted by kind
+    pub plumbing: Vec<PlumbingCostInput>,
+    /// Drain, vent and supply rough-ins the fixtures need, one per wet wall
+    pub wet_walls: u32,
+    /// Supply registers sized from the rooms' design loads
+    pub supply_registers: u32,
+    /// Returns for the HVAC zones serving this level, one per zone
+    pub return_registers: u32,
+    /// Decks and porches off this level, from their generated framing
+    pub decks: Vec<DeckCostInput>,
+    /// Garage slabs, poured apart from the foundation slab
+    pub garages: Vec<GarageCostInput>,
+    /// Grading the building pad, on the building's lowest level
+    pub earthwork: Option<CutFill>,
     pub wall_height: f64, // typical 8 or 9 feet
 }
 
@@ -25,6 +92,83 @@ pub struct RoomCostInput {
     pub floor_sqft: f64,
     pub wall_sqft: f64,
     pub perimeter_ft: f64,
+    pub finishes: RoomFinishes,
+    /// Ceiling drywall is priced by a modeled ceiling assembly
+    pub ceiling_modeled: bool,
+}
+
+/// A floor or ceiling assembly priced layer by layer
+pub struct AssemblyCostInput {
+    pub description: String,
+    pub area: f64,
+    pub layers: Vec<SlabLayer>,
+}
+
+/// Headers of one size, priced per ply
+pub struct HeaderCostInput {
+    pub size: HeaderSize,
+    pub count: u32,
+}
+
+/// Stock boards of one size and length from a cut list
+pub struct LumberCostInput {
+    pub lumber_size: LumberSize,
+    /// Stock length (inches)
+    pub stock_length: f64,
+    pub count: u32,
+    pub board_feet: f64,
+}
+
+/// Unframed walls of one stud size, priced with the per-foot heuristic
+pub struct UnframedWallCostInput {
+    pub lumber_size: LumberSize,
+    pub linear_ft: f64,
+}
+
+/// Beams of one profile and material, priced per board
+pub struct BeamCostInput {
+    pub profile: MemberProfile,
+    pub material: FramingMaterial,
+    /// Sawn beams take a stock board per ply for each 16' of span;
+    /// engineered beams are ordered to length, one piece per ply
+    pub boards: u32,
+}
+
+/// Posts of one profile, priced each
+pub struct PostCostInput {
+    pub profile: MemberProfile,
+    pub count: u32,
+}
+
+/// Electrical devices of one kind, priced each
+pub struct ElectricalCostInput {
+    pub kind: ElectricalDeviceKind,
+    pub count: u32,
+}
+
+/// Plumbing fixtures of one kind, priced each
+pub struct PlumbingCostInput {
+    pub kind: PlumbingFixtureKind,
+    pub count: u32,
+}
+
+/// A deck or porch: its framing lumber, decking and railing
+pub struct DeckCostInput {
+    pub kind: DeckKind,
+    pub area: f64,
+    /// Stock lumber cut for the ledger, rims and joists
+    pub lumber: Vec<LumberCostInput>,
+    /// Linear feet of decking boards
+    pub decking_length: f64,
+    /// Linear feet of guard rail
+    pub railing_length: f64,
+    pub railing_posts: u32,
+}
+
+/// A garage room's slab
+pub struct GarageCostInput {
+    pub name: String,
+    pub slab: GarageSlab,
 }
 
 pub struct OpeningCostInput {
@@ -33,16 +177,25 @@ pub struct OpeningCostInput {
     pub width: f64,
     pub height: f64,
     pub count: u32,
+    /// Catalog entry the openings were placed from
+    pub catalog_code: Option<String>,
+    /// Unit price from the catalog, used in place of the price table's
+    pub unit_cost: Option<f64>,
 }
 
 /// Cost Calculator - generates estimates from inputs and price tables
+#[derive(Clone)]
 pub struct CostCalculator {
     price_table: PriceTable,
+    /// Regional price sets, keyed by region tag
+    price_sets: HashMap<String, PriceTable>,
+    /// Markups carried by every estimate
+    markups: Markups,
 }
 
 impl CostCalculator {
     pub fn new(price_table: PriceTable) -> Self {
-        Self { price_table }
+        Self { price_table, price_sets: HashMap::new(), markups: Markups::default() }
     }
 
     pub fn with_defaults() -> Self {
@@ -52,6 +205,12 @@ impl CostCalculator {
     /// Generate a complete cost estimate
     pub fn calculate(&self, input: &CostInput) -> CostEstimate {
         let mut estimate = CostEstimate::new(input.level_id);
+        estimate.set_markups(self.markups);
+
+        // Pad grading
+        for item in self.calculate_earthwork(input) {
+            estimate.add_line_item(item);
+        }
 
         // Foundation costs (detailed concrete breakdown)
         for item in self.calculate_foundation(input) {
@@ -83,51 +242,167 @@ impl CostCalculator {
             estimate.add_line_item(item);
         }
 
+        // Modeled floor and ceiling assemblies
+        for item in self.calculate_assemblies(input) {
+            estimate.add_line_item(item);
+        }
+
+        // Placed electrical devices
+        for item in self.calculate_electrical(input) {
+            estimate.add_line_item(item);
+        }
+
+        // Placed plumbing fixtures
+        for item in self.calculate_plumbing(input) {
+            estimate.add_line_item(item);
+        }
+
+        // Duct runs to the estimated registers
+        for item in self.calculate_hvac(input) {
+            estimate.add_line_item(item);
+        }
+
+        // Decks and porches
+        for item in self.calculate_decks(input) {
+            estimate.add_line_item(item);
+        }
+
+        // Garage slabs
+        for item in self.calculate_garages(input) {
+            estimate.add_line_item(item);
+        }
+
+        let modeled = |modeled: bool| if modeled { QuantitySource::Modeled } else { QuantitySource::Heuristic };
+        let framing = match (input.lumber.is_empty(), input.unframed_walls.is_empty()) {
+            (true, _) => QuantitySource::Heuristic,
+            (false, true) => QuantitySource::Modeled,
+            (false, false) => QuantitySource::Partial,
+        };
+        estimate.quantity_sources = HashMap::from([
+            (CostCategory::Foundation, modeled(input.foundation.is_some())),
+            (CostCategory::Framing, framing),
+            (CostCategory::Roofing, modeled(input.roof_modeled)),
+        ]);
+        if !input.electrical.is_empty() {
+            estimate.quantity_sources.insert(CostCategory::Electrical, QuantitySource::Modeled);
+        }
+        if !input.plumbing.is_empty() {
+            estimate.quantity_sources.insert(CostCategory::Plumbing, QuantitySource::Modeled);
+        }
+        if input.earthwork.is_some() {
+            estimate.quantity_sources.insert(CostCategory::SiteWork, QuantitySource::Modeled);
+        }
+        if input.supply_registers + input.return_registers > 0 {
+            estimate.quantity_sources.insert(CostCategory::HVAC, QuantitySource::Heuristic);
+        }
+
         estimate
     }
 
-    /// Calculate foundation costs with detailed concrete breakdown
+    /// Cut and fill to grade the building pad, with fill brought in for
+    /// whatever the cut can't supply
+    fn calculate_earthwork(&self, input: &CostInput) -> Vec<CostLineItem> {
+        let mut items = Vec::new();
+        let Some(earthwork) = &input.earthwork else {
+            return items;
+        };
+
+        let work = [
+            (LaborType::Excavation, "Pad excavation", earthwork.cut_cy, EXCAVATION_HOURS_PER_CY),
+            (LaborType::FillCompaction, "Pad fill & compaction", earthwork.fill_cy, FILL_HOURS_PER_CY),
+        ];
+        for (labor, description, cubic_yards, hours_per_cy) in work {
+            if cubic_yards <= 0.0 {
+                continue;
+            }
+            if let Some(rate) = self.price_table.get_labor_rate(&labor) {
+                let quantity = match rate.unit {
+                    PricingUnit::PerHour => cubic_yards * hours_per_cy,
+                    _ => cubic_yards,
+                };
+                items.push(CostLineItem::labor(
+                    CostCategory::SiteWork,
+                    description.to_string(),
+                    labor,
+                    quantity,
+                    rate.unit,
+                    rate.rate,
+                ));
+            }
+        }
+
+        if earthwork.import_cy() > 0.0 {
+            if let Some(price) = self.price_table.get_material_price(&MaterialType::FillSoil) {
+                items.push(CostLineItem::material(
+                    CostCategory::SiteWork,
+                    "Imported fill".to_string(),
+                    MaterialType::FillSoil,
+                    earthwork.import_cy(),
+                    price.unit,
+                    price.price,
+                ));
+            }
+        }
+
+        items
+    }
+
+    /// Calculate foundation costs with detailed concrete breakdown. A modeled
+    /// foundation is priced from its quantities; otherwise a 4" slab over
+    /// `foundation_sqft` is assumed.
     fn calculate_foundation(&self, input: &CostInput) -> Vec<CostLineItem> {
         let mut items = Vec::new();
-        let sqft = input.foundation_sqft;
-        let perimeter = input.exterior_wall_linear_ft;
+        let q = input.foundation.unwrap_or_else(|| {
+            approximate_foundation(input.foundation_sqft, input.exterior_wall_linear_ft)
+        });
 
-        // Forms (per linear foot of perimeter)
+        // Forms (per linear foot of form board)
         if let Some(price) = self.price_table.get_material_price(&MaterialType::ConcreteForms) {
             items.push(CostLineItem::material(
                 CostCategory::Foundation,
                 "Concrete forms".to_string(),
                 MaterialType::ConcreteForms,
-                perimeter,
+                q.form_lf,
                 price.unit,
                 price.price,
             ));
         }
 
-        // Rebar (estimate ~0.5 lbs per sqft for typical slab)
         if let Some(price) = self.price_table.get_material_price(&MaterialType::ConcreteRebar) {
-            let rebar_lbs = sqft * 0.5;
             items.push(CostLineItem::material(
                 CostCategory::Foundation,
                 "Rebar".to_string(),
                 MaterialType::ConcreteRebar,
-                rebar_lbs,
+                q.rebar_lbs,
                 price.unit,
                 price.price,
             ));
         }
 
-        // Concrete mix (4" slab = 0.0123 cy per sqft)
+        // Concrete mix, poured separately for each part of a modeled foundation
         if let Some(price) = self.price_table.get_material_price(&MaterialType::ConcreteMix) {
-            let cubic_yards = sqft * 0.0123;
-            items.push(CostLineItem::material(
-                CostCategory::Foundation,
-                "Concrete mix".to_string(),
-                MaterialType::ConcreteMix,
-                cubic_yards,
-                price.unit,
-                price.price,
-            ));
+            let pours = if input.foundation.is_some() {
+                vec![
+                    ("Concrete mix - footings", q.footing_cy),
+                    ("Concrete mix - stem walls", q.stem_wall_cy),
+                    ("Concrete mix - slab", q.slab_cy),
+                ]
+            } else {
+                vec![("Concrete mix", q.concrete_cy())]
+            };
+            for (description, cubic_yards) in pours {
+                if input.foundation.is_some() && cubic_yards <= 0.0 {
+                    continue;
+                }
+                items.push(CostLineItem::material(
+                    CostCategory::Foundation,
+                    description.to_string(),
+                    MaterialType::ConcreteMix,
+                    cubic_yards,
+                    price.unit,
+                    price.price,
+                ));
+            }
         }
 
         // Vapor barrier
@@ -139,20 +414,18 @@ impl CostCalculator {
                 CostCategory::Foundation,
                 "Vapor barrier".to_string(),
                 MaterialType::ConcreteVaporBarrier,
-                sqft,
+                q.vapor_barrier_sqft,
                 price.unit,
                 price.price,
             ));
         }
 
-        // Gravel (4" base = 0.0123 cy per sqft)
         if let Some(price) = self.price_table.get_material_price(&MaterialType::ConcreteGravel) {
-            let cubic_yards = sqft * 0.0123;
             items.push(CostLineItem::material(
                 CostCategory::Foundation,
                 "Gravel base".to_string(),
                 MaterialType::ConcreteGravel,
-                cubic_yards,
+                q.gravel_cy,
                 price.unit,
                 price.price,
             ));
@@ -164,7 +437,7 @@ impl CostCalculator {
                 CostCategory::Foundation,
                 "Subgrade preparation".to_string(),
                 LaborType::ConcreteSubgradePrep,
-                sqft,
+                q.ground_area,
                 rate.unit,
                 rate.rate,
             ));
@@ -176,7 +449,7 @@ impl CostCalculator {
                 CostCategory::Foundation,
                 "Form installation".to_string(),
                 LaborType::ConcreteFormInstall,
-                perimeter,
+                q.form_lf,
                 rate.unit,
                 rate.rate,
             ));
@@ -188,7 +461,7 @@ impl CostCalculator {
                 CostCategory::Foundation,
                 "Rebar installation".to_string(),
                 LaborType::ConcreteRebarInstall,
-                sqft,
+                q.ground_area,
                 rate.unit,
                 rate.rate,
             ));
@@ -200,7 +473,7 @@ impl CostCalculator {
                 CostCategory::Foundation,
                 "Concrete place & finish".to_string(),
                 LaborType::ConcretePlaceFinish,
-                sqft,
+                q.slab_area,
                 rate.unit,
                 rate.rate,
             ));
@@ -213,6 +486,93 @@ impl CostCalculator {
     fn calculate_framing(&self, input: &CostInput) -> Vec<CostLineItem> {
         let mut items = Vec::new();
 
+        if input.lumber.is_empty() {
+            items.extend(self.calculate_heuristic_studs(input));
+        } else {
+            items.extend(self.calculate_framed_lumber(input));
+        }
+
+        // Headers sized in generated framing, one board per ply. Built-up
+        // headers are already in the framed lumber.
+        for header in &input.headers {
+            if !input.lumber.is_empty() && header.size.header_type.is_built_up() {
+                continue;
+            }
+            let Some(material) = header_material(&header.size) else {
+                continue;
+            };
+            if let Some(price) = self.price_table.get_material_price(&material) {
+                items.push(CostLineItem::material(
+                    CostCategory::Framing,
+                    format!("{} headers", header.size.description()),
+                    material,
+                    (header.size.plies as u32 * header.count) as f64,
+                    PricingUnit::PerBoard,
+                    price.price,
+                ));
+            }
+        }
+
+        // Modeled beams and posts
+        for beam in &input.beams {
+            let Some(material) = member_material(&beam.profile.lumber_size, beam.material) else {
+                continue;
+            };
+            if let Some(price) = self.price_table.get_material_price(&material) {
+                items.push(CostLineItem::material(
+                    CostCategory::Framing,
+                    format!("{} beams", beam_description(beam)),
+                    material,
+                    beam.boards as f64,
+                    PricingUnit::PerBoard,
+                    price.price,
+                ));
+            }
+        }
+        for post in &input.posts {
+            if let Some(price) = self.price_table.get_material_price(&MaterialType::StructuralPost) {
+                items.push(CostLineItem::material(
+                    CostCategory::Framing,
+                    format!("{} posts", post.profile.description()),
+                    MaterialType::StructuralPost,
+                    post.count as f64,
+                    PricingUnit::PerComponent,
+                    price.price,
+                ));
+            }
+        }
+
+        // Sheathing (exterior wall sqft)
+        if let Some(price) = self.price_table.get_material_price(&MaterialType::Sheathing) {
+            items.push(CostLineItem::material(
+                CostCategory::Framing,
+                "Wall sheathing".to_string(),
+                MaterialType::Sheathing,
+                input.exterior_wall_sqft,
+                price.unit,
+                price.price,
+            ));
+        }
+
+        // Framing labor
+        if let Some(rate) = self.price_table.get_labor_rate(&LaborType::FramingCarpentry) {
+            items.push(CostLineItem::labor(
+                CostCategory::Framing,
+                "Framing labor".to_string(),
+                LaborType::FramingCarpentry,
+                input.total_floor_area,
+                rate.unit,
+                rate.rate,
+            ));
+        }
+
+        items
+    }
+
+    /// Studs estimated from wall length, for levels without generated framing
+    fn calculate_heuristic_studs(&self, input: &CostInput) -> Vec<CostLineItem> {
+        let mut items = Vec::new();
+
         // Estimate lumber needs based on wall linear feet
         // Typical: 1 stud per 16" (0.75 studs/ft) * 2 plates + 10% waste
 
@@ -242,28 +602,53 @@ impl CostCalculator {
             ));
         }
 
-        // Sheathing (exterior wall sqft)
-        if let Some(price) = self.price_table.get_material_price(&MaterialType::Sheathing) {
-            items.push(CostLineItem::material(
-                CostCategory::Framing,
-                "Wall sheathing".to_string(),
-                MaterialType::Sheathing,
-                input.exterior_wall_sqft,
-                price.unit,
-                price.price,
-            ));
+        items
+    }
+
+    /// Stock lumber from the cut list of generated framing, priced per
+    /// board or per linear foot as the price table has it. Walls not yet
+    /// framed get the per-foot stud estimate in their own stud size.
+    fn calculate_framed_lumber(&self, input: &CostInput) -> Vec<CostLineItem> {
+        let mut items = Vec::new();
+
+        for lumber in &input.lumber {
+            let Some(material) = lumber_material(&lumber.lumber_size) else {
+                continue;
+            };
+            let Some(price) = self.price_table.get_material_price(&material) else {
+                continue;
+            };
+            let (quantity, unit) = match price.unit {
+                PricingUnit::PerLinearFoot => (lumber.count as f64 * lumber.stock_length / 12.0, PricingUnit::PerLinearFoot),
+                _ => (lumber.count as f64, PricingUnit::PerBoard),
+            };
+            items.push(
+                CostLineItem::material(
+                    CostCategory::Framing,
+                    format!("{} x {}' framing lumber", lumber.lumber_size.nominal_name(), lumber.stock_length / 12.0),
+                    material,
+                    quantity,
+                    unit,
+                    price.price,
+                )
+                .with_notes(format!("{:.1} board feet", lumber.board_feet)),
+            );
         }
 
-        // Framing labor
-        if let Some(rate) = self.price_table.get_labor_rate(&LaborType::FramingCarpentry) {
-            items.push(CostLineItem::labor(
-                CostCategory::Framing,
-                "Framing labor".to_string(),
-                LaborType::FramingCarpentry,
-                input.total_floor_area,
-                rate.unit,
-                rate.rate,
-            ));
+        for wall in &input.unframed_walls {
+            let Some(material) = lumber_material(&wall.lumber_size) else {
+                continue;
+            };
+            if let Some(price) = self.price_table.get_material_price(&material) {
+                items.push(CostLineItem::material(
+                    CostCategory::Framing,
+                    format!("{} studs, unframed walls", wall.lumber_size.nominal_name()),
+                    material,
+                    (wall.linear_ft * 0.75 * 1.1).ceil(),
+                    PricingUnit::PerBoard,
+                    price.price,
+                ));
+            }
         }
 
         items
@@ -313,6 +698,49 @@ impl CostCalculator {
             ));
         }
 
+        // Edge trim, flashing and drainage follow the modeled roof's edges
+        let Some(topology) = &input.roof_topology else {
+            return items;
+        };
+        let downspouts = topology.downspouts() as f64;
+        let edges = [
+            (MaterialType::DripEdge, "Drip edge", topology.eave + topology.rake),
+            (MaterialType::RidgeCap, "Ridge & hip cap", topology.ridge + topology.hip),
+            (MaterialType::ValleyFlashing, "Valley flashing", topology.valley),
+            (MaterialType::Gutter, "Gutters", topology.eave),
+            (MaterialType::Downspout, "Downspouts", downspouts),
+        ];
+        for (material, description, quantity) in edges {
+            if quantity <= 0.0 {
+                continue;
+            }
+            if let Some(price) = self.price_table.get_material_price(&material) {
+                items.push(CostLineItem::material(
+                    CostCategory::Roofing,
+                    description.to_string(),
+                    material,
+                    quantity,
+                    price.unit,
+                    price.price,
+                ));
+            }
+        }
+
+        if let Some(rate) = self.price_table.get_labor_rate(&LaborType::GutterInstall) {
+            let quantity = match rate.unit {
+                PricingUnit::PerHour => topology.eave * GUTTER_HOURS_PER_FOOT + downspouts * DOWNSPOUT_HOURS,
+                _ => topology.eave,
+            };
+            items.push(CostLineItem::labor(
+                CostCategory::Roofing,
+                "Gutter & downspout installation".to_string(),
+                LaborType::GutterInstall,
+                quantity,
+                rate.unit,
+                rate.rate,
+            ));
+        }
+
         items
     }
 
@@ -353,64 +781,35 @@ impl CostCalculator {
         let mut items = Vec::new();
 
         for opening in &input.openings {
-            match opening.opening_type.as_str() {
-                "window" => {
-                    if let Some(price) =
-                        self.price_table.get_material_price(&MaterialType::WindowUnit)
-                    {
-                        items.push(CostLineItem::material(
-                            CostCategory::Windows,
-                            format!("Window {}x{}", opening.width, opening.height),
-                            MaterialType::WindowUnit,
-                            opening.count as f64,
-                            PricingUnit::PerComponent,
-                            price.price,
-                        ));
-                    }
-                }
-                "exterior_door" => {
-                    if let Some(price) =
-                        self.price_table.get_material_price(&MaterialType::ExteriorDoor)
-                    {
-                        items.push(CostLineItem::material(
-                            CostCategory::Doors,
-                            "Exterior door".to_string(),
-                            MaterialType::ExteriorDoor,
-                            opening.count as f64,
-                            PricingUnit::PerComponent,
-                            price.price,
-                        ));
-                    }
-                }
-                "interior_door" => {
-                    if let Some(price) =
-                        self.price_table.get_material_price(&MaterialType::InteriorDoor)
-                    {
-                        items.push(CostLineItem::material(
-                            CostCategory::Doors,
-                            "Interior door".to_string(),
-                            MaterialType::InteriorDoor,
-                            opening.count as f64,
-                            PricingUnit::PerComponent,
-                            price.price,
-                        ));
-                    }
-                }
-                "garage_door" => {
-                    if let Some(price) =
-                        self.price_table.get_material_price(&MaterialType::GarageDoor)
-                    {
-                        items.push(CostLineItem::material(
-                            CostCategory::Doors,
-                            "Garage door".to_string(),
-                            MaterialType::GarageDoor,
-                            opening.count as f64,
-                            PricingUnit::PerComponent,
-                            price.price,
-                        ));
-                    }
-                }
-                _ => {}
+            let (category, description, material_type) = match opening.opening_type.as_str() {
+                "window" => (
+                    CostCategory::Windows,
+                    format!("Window {}x{}", opening.width, opening.height),
+                    MaterialType::WindowUnit,
+                ),
+                "exterior_door" => (CostCategory::Doors, "Exterior door".to_string(), MaterialType::ExteriorDoor),
+                "interior_door" => (CostCategory::Doors, "Interior door".to_string(), MaterialType::InteriorDoor),
+                "garage_door" => (CostCategory::Doors, "Garage door".to_string(), MaterialType::GarageDoor),
+                _ => continue,
+            };
+
+            // Catalog units carry their own price; others use the table's
+            let price = opening.unit_cost.or_else(|| {
+                self.price_table.get_material_price(&material_type).map(|p| p.price)
+            });
+            if let Some(price) = price {
+                let description = match &opening.catalog_code {
+                    Some(code) => format!("{} ({})", description, code),
+                    None => description,
+                };
+                items.push(CostLineItem::material(
+                    category,
+                    description,
+                    material_type,
+                    opening.count as f64,
+                    PricingUnit::PerComponent,
+                    price,
+                ));
             }
         }
 
@@ -422,8 +821,10 @@ impl CostCalculator {
         let mut items = Vec::new();
 
         for room in &input.rooms {
-            // Drywall (wall sqft + ceiling sqft)
-            let drywall_sqft = room.wall_sqft + room.floor_sqft; // ceiling ~ floor
+            // Drywall (wall sqft + ceiling sqft, ceiling ~ floor) unless a
+            // modeled ceiling prices its own board
+            let ceiling_sqft = if room.ceiling_modeled { 0.0 } else { room.floor_sqft };
+            let drywall_sqft = room.wall_sqft + ceiling_sqft;
             if let Some(price) = self.price_table.get_material_price(&MaterialType::Drywall) {
                 items.push(CostLineItem::material(
                     CostCategory::Drywall,
@@ -435,47 +836,37 @@ impl CostCalculator {
                 ));
             }
 
-            // Flooring based on room type
-            let flooring_type = match room.room_type.to_lowercase().as_str() {
-                "kitchen" | "bathroom" | "laundry" => MaterialType::Tile,
-                "living" | "dining" | "bedroom" | "office" => MaterialType::Hardwood,
-                "garage" => MaterialType::ConcreteMix, // epoxy or bare
-                _ => MaterialType::LVP,                // default to LVP
-            };
-
-            if let Some(price) = self.price_table.get_material_price(&flooring_type) {
-                items.push(CostLineItem::material(
-                    CostCategory::Flooring,
-                    format!("{} flooring", room.room_type),
-                    flooring_type,
-                    room.floor_sqft,
-                    price.unit,
-                    price.price,
-                ));
-            }
-
-            // Trim (baseboard per perimeter)
-            if let Some(price) = self.price_table.get_material_price(&MaterialType::Trim) {
-                items.push(CostLineItem::material(
-                    CostCategory::Trim,
-                    format!("{} baseboard", room.room_type),
-                    MaterialType::Trim,
-                    room.perimeter_ft,
-                    price.unit,
-                    price.price,
-                ));
-            }
-
-            // Paint (wall sqft)
-            if let Some(price) = self.price_table.get_material_price(&MaterialType::Paint) {
-                items.push(CostLineItem::material(
-                    CostCategory::Painting,
-                    format!("{} paint", room.room_type),
-                    MaterialType::Paint,
-                    room.wall_sqft,
-                    price.unit,
-                    price.price,
-                ));
+            // Selected finishes per surface. Unfinished drywall is covered
+            // above and an exposed slab by the foundation.
+            let surfaces = [
+                (FinishSurface::Floor, "flooring", room.floor_sqft),
+                (FinishSurface::Wall, "walls", room.wall_sqft),
+                (FinishSurface::Ceiling, "ceiling", room.floor_sqft), // ceiling ~ floor
+                (FinishSurface::Base, "baseboard", room.perimeter_ft),
+            ];
+            for (surface, label, quantity) in surfaces {
+                let material = room.finishes.get(surface);
+                if matches!(material, MaterialType::Drywall | MaterialType::ConcreteMix) {
+                    continue;
+                }
+                if let Some(price) = self.price_table.get_material_price(&material) {
+                    // Trim grade scales the price of wood base
+                    let (description, unit_price) = match material {
+                        MaterialType::Trim => (
+                            format!("{} {} ({} trim)", room.room_type, label, room.finishes.trim.as_str()),
+                            price.price * room.finishes.trim.price_factor(),
+                        ),
+                        _ => (format!("{} {}", room.room_type, label), price.price),
+                    };
+                    items.push(CostLineItem::material(
+                        surface.cost_category(),
+                        description,
+                        material,
+                        quantity,
+                        price.unit,
+                        unit_price,
+                    ));
+                }
             }
         }
 
@@ -508,14 +899,22 @@ impl CostCalculator {
             ));
         }
 
-        // Painting labor
+        // Painting labor on painted walls and ceilings
         if let Some(rate) = self.price_table.get_labor_rate(&LaborType::PaintingLabor) {
-            let total_wall_sqft: f64 = input.rooms.iter().map(|r| r.wall_sqft).sum();
+            let painted_sqft: f64 = input
+                .rooms
+                .iter()
+                .map(|r| {
+                    let walls = if r.finishes.wall == MaterialType::Paint { r.wall_sqft } else { 0.0 };
+                    let ceiling = if r.finishes.ceiling == MaterialType::Paint { r.floor_sqft } else { 0.0 };
+                    walls + ceiling
+                })
+                .sum();
             items.push(CostLineItem::labor(
                 CostCategory::Painting,
                 "Painting labor".to_string(),
                 LaborType::PaintingLabor,
-                total_wall_sqft,
+                painted_sqft,
                 rate.unit,
                 rate.rate,
             ));
@@ -524,6 +923,255 @@ impl CostCalculator {
         items
     }
 
+    /// Price each layer of the modeled floor and ceiling assemblies
+    fn calculate_assemblies(&self, input: &CostInput) -> Vec<CostLineItem> {
+        let mut items = Vec::new();
+
+        for assembly in &input.assemblies {
+            for layer in &assembly.layers {
+                if let Some(price) = self.price_table.get_material_price(&layer.material) {
+                    items.push(CostLineItem::material(
+                        layer.material.typical_category(),
+                        format!("{} {}", assembly.description, layer.name),
+                        layer.material,
+                        layer.quantity(assembly.area),
+                        price.unit,
+                        price.price,
+                    ));
+                }
+            }
+        }
+
+        items
+    }
+
+    /// Price placed electrical devices each, and their labor per device
+    /// (or per hour of rough-in and trim-out for hourly rates)
+    fn calculate_electrical(&self, input: &CostInput) -> Vec<CostLineItem> {
+        let mut items = Vec::new();
+
+        for device in &input.electrical {
+            let material = device.kind.material();
+            if let Some(price) = self.price_table.get_material_price(&material) {
+                items.push(CostLineItem::material(
+                    CostCategory::Electrical,
+                    format!("{}s", device.kind.display_name()),
+                    material,
+                    device.count as f64,
+                    PricingUnit::PerComponent,
+                    price.price,
+                ));
+            }
+        }
+
+        let count: u32 = input.electrical.iter().map(|d| d.count).sum();
+        if count > 0 {
+            if let Some(rate) = self.price_table.get_labor_rate(&LaborType::ElectricalLabor) {
+                let quantity = match rate.unit {
+                    PricingUnit::PerHour => count as f64 * ELECTRICAL_HOURS_PER_DEVICE,
+                    _ => count as f64,
+                };
+                items.push(CostLineItem::labor(
+                    CostCategory::Electrical,
+                    "Electrical labor".to_string(),
+                    LaborType::ElectricalLabor,
+                    quantity,
+                    rate.unit,
+                    rate.rate,
+                ));
+            }
+        }
+
+        items
+    }
+
+    /// Price placed plumbing fixtures each, a rough-in per wet wall and
+    /// setting labor per fixture. Fixtures sharing a wet wall share its
+    /// rough-in.
+    fn calculate_plumbing(&self, input: &CostInput) -> Vec<CostLineItem> {
+        let mut items = Vec::new();
+
+        for fixture in &input.plumbing {
+            let material = fixture.kind.material();
+            if let Some(price) = self.price_table.get_material_price(&material) {
+                items.push(CostLineItem::material(
+                    CostCategory::Plumbing,
+                    format!("{}s", fixture.kind.display_name()),
+                    material,
+                    fixture.count as f64,
+                    PricingUnit::PerComponent,
+                    price.price,
+                ));
+            }
+        }
+
+        let count: u32 = input.plumbing.iter().map(|f| f.count).sum();
+        if count > 0 {
+            if let Some(rate) = self.price_table.get_labor_rate(&LaborType::PlumbingLabor) {
+                let hourly = rate.unit == PricingUnit::PerHour;
+                let labor = [
+                    ("Plumbing rough-in", input.wet_walls, PLUMBING_HOURS_PER_WET_WALL),
+                    ("Plumbing fixture setting", count, PLUMBING_HOURS_PER_FIXTURE),
+                ];
+                for (description, quantity, hours) in labor {
+                    items.push(CostLineItem::labor(
+                        CostCategory::Plumbing,
+                        description.to_string(),
+                        LaborType::PlumbingLabor,
+                        if hourly { quantity as f64 * hours } else { quantity as f64 },
+                        rate.unit,
+                        rate.rate,
+                    ));
+                }
+            }
+        }
+
+        items
+    }
+
+    /// Install labor per duct run, one to each supply and return register
+    /// (or per hour of install for hourly rates)
+    fn calculate_hvac(&self, input: &CostInput) -> Vec<CostLineItem> {
+        let mut items = Vec::new();
+
+        let runs = input.supply_registers + input.return_registers;
+        if runs > 0 {
+            if let Some(rate) = self.price_table.get_labor_rate(&LaborType::HVACInstall) {
+                let quantity = match rate.unit {
+                    PricingUnit::PerHour => runs as f64 * HVAC_HOURS_PER_DUCT_RUN,
+                    _ => runs as f64,
+                };
+                items.push(CostLineItem::labor(
+                    CostCategory::HVAC,
+                    "HVAC duct runs and registers".to_string(),
+                    LaborType::HVACInstall,
+                    quantity,
+                    rate.unit,
+                    rate.rate,
+                ));
+            }
+        }
+
+        items
+    }
+
+    /// Framing lumber, decking, railing and posts for each deck, with
+    /// carpentry labor over its area (or per hour for hourly rates)
+    fn calculate_decks(&self, input: &CostInput) -> Vec<CostLineItem> {
+        let mut items = Vec::new();
+
+        for deck in &input.decks {
+            let name = deck.kind.display_name();
+            for lumber in &deck.lumber {
+                let Some(material) = lumber_material(&lumber.lumber_size) else {
+                    continue;
+                };
+                let Some(price) = self.price_table.get_material_price(&material) else {
+                    continue;
+                };
+                let (quantity, unit) = match price.unit {
+                    PricingUnit::PerLinearFoot => (lumber.count as f64 * lumber.stock_length / 12.0, PricingUnit::PerLinearFoot),
+                    _ => (lumber.count as f64, PricingUnit::PerBoard),
+                };
+                items.push(CostLineItem::material(
+                    CostCategory::Exterior,
+                    format!("{} {} x {}' framing lumber", name, lumber.lumber_size.nominal_name(), lumber.stock_length / 12.0),
+                    material,
+                    quantity,
+                    unit,
+                    price.price,
+                ));
+            }
+
+            let finishes = [
+                (MaterialType::DeckBoard, "decking", deck.decking_length),
+                (MaterialType::DeckRailing, "railing", deck.railing_length),
+            ];
+            for (material, part, quantity) in finishes {
+                if quantity <= 0.0 {
+                    continue;
+                }
+                if let Some(price) = self.price_table.get_material_price(&material) {
+                    items.push(CostLineItem::material(
+                        CostCategory::Exterior,
+                        format!("{} {}", name, part),
+                        material,
+                        quantity,
+                        PricingUnit::PerLinearFoot,
+                        price.price,
+                    ));
+                }
+            }
+            if deck.railing_posts > 0 {
+                if let Some(price) = self.price_table.get_material_price(&MaterialType::StructuralPost) {
+                    items.push(CostLineItem::material(
+                        CostCategory::Exterior,
+                        format!("{} railing posts", name),
+                        MaterialType::StructuralPost,
+                        deck.railing_posts as f64,
+                        PricingUnit::PerComponent,
+                        price.price,
+                    ));
+                }
+            }
+
+            if let Some(rate) = self.price_table.get_labor_rate(&LaborType::FramingCarpentry) {
+                let quantity = match rate.unit {
+                    PricingUnit::PerHour => deck.area * DECK_HOURS_PER_SQFT,
+                    _ => deck.area,
+                };
+                items.push(CostLineItem::labor(
+                    CostCategory::Exterior,
+                    format!("{} construction", name),
+                    LaborType::FramingCarpentry,
+                    quantity,
+                    rate.unit,
+                    rate.rate,
+                ));
+            }
+        }
+
+        items
+    }
+
+    /// Garage slabs: concrete, gravel base and rebar for vehicle loads
+    fn calculate_garages(&self, input: &CostInput) -> Vec<CostLineItem> {
+        let mut items = Vec::new();
+
+        for garage in &input.garages {
+            let materials = [
+                (MaterialType::ConcreteMix, "concrete", garage.slab.concrete_cy),
+                (MaterialType::ConcreteGravel, "gravel base", garage.slab.gravel_cy),
+                (MaterialType::ConcreteRebar, "rebar", garage.slab.rebar_lbs),
+            ];
+            for (material, part, quantity) in materials {
+                if let Some(price) = self.price_table.get_material_price(&material) {
+                    items.push(CostLineItem::material(
+                        CostCategory::Foundation,
+                        format!("{} slab {}", garage.name, part),
+                        material,
+                        quantity,
+                        price.unit,
+                        price.price,
+                    ));
+                }
+            }
+
+            if let Some(rate) = self.price_table.get_labor_rate(&LaborType::ConcretePlaceFinish) {
+                items.push(CostLineItem::labor(
+                    CostCategory::Foundation,
+                    format!("{} slab place & finish", garage.name),
+                    LaborType::ConcretePlaceFinish,
+                    garage.slab.area,
+                    rate.unit,
+                    rate.rate,
+                ));
+            }
+        }
+
+        items
+    }
+
     /// Update a material price
     pub fn set_material_price(&mut self, material: MaterialType, price: UnitPrice) {
         self.price_table.set_material_price(material, price);
@@ -543,6 +1191,71 @@ impl CostCalculator {
     pub fn price_table_mut(&mut self) -> &mut PriceTable {
         &mut self.price_table
     }
+
+    /// Set the overhead, profit and contingency percentages
+    pub fn set_markups(&mut self, markups: Markups) {
+        self.markups = markups;
+    }
+
+    pub fn markups(&self) -> Markups {
+        self.markups
+    }
+
+    /// Add or replace a region's price set, tagging the table with the region
+    pub fn set_price_set(&mut self, region: impl Into<String>, mut table: PriceTable) {
+        let region = region.into();
+        table.region = Some(region.clone());
+        self.price_sets.insert(region, table);
+    }
+
+    /// Get a region's price set
+    pub fn price_set(&self, region: &str) -> Option<&PriceTable> {
+        self.price_sets.get(region)
+    }
+
+    /// Remove a region's price set
+    pub fn remove_price_set(&mut self, region: &str) -> Option<PriceTable> {
+        self.price_sets.remove(region)
+    }
+
+    /// Regions with a price set, sorted
+    pub fn price_set_regions(&self) -> Vec<&str> {
+        let mut regions: Vec<&str> = self.price_sets.keys().map(String::as_str).collect();
+        regions.sort_unstable();
+        regions
+    }
+
+    /// Merge CSV rows into the default table, or into a region's price set
+    /// (created if new). Nothing changes if any row is invalid.
+    pub fn import_price_csv(&mut self, text: &str, region: Option<&str>) -> PriceCsvImport {
+        let table = match PriceTable::from_csv(text) {
+            Ok(table) => table,
+            Err(errors) => return PriceCsvImport { imported: 0, errors },
+        };
+        let imported = table.material_prices.len() + table.labor_rates.len();
+        match region {
+            Some(region) => self
+                .price_sets
+                .entry(region.to_string())
+                .or_insert_with(|| PriceTable::for_region(region))
+                .merge(table),
+            None => self.price_table.merge(table),
+        }
+        PriceCsvImport { imported, errors: Vec::new() }
+    }
+
+    /// Merge a table into the default table, or into its region's price
+    /// set (created if new) when it carries a region tag
+    pub fn import_price_table(&mut self, table: PriceTable) {
+        match table.region.clone() {
+            Some(region) => self
+                .price_sets
+                .entry(region.clone())
+                .or_insert_with(|| PriceTable::for_region(region))
+                .merge(table),
+            None => self.price_table.merge(table),
+        }
+    }
 }
 
 impl Default for CostCalculator {
@@ -551,9 +1264,64 @@ impl Default for CostCalculator {
     }
 }
 
+/// Quantities for an unmodeled foundation: a 4" slab on a 4" gravel base
+/// (0.0123 cy per sqft each), ~0.5 lb of rebar per sqft, and edge forms
+/// around the perimeter
+pub(super) fn approximate_foundation(sqft: f64, perimeter: f64) -> FoundationQuantities {
+    FoundationQuantities {
+        perimeter,
+        slab_area: sqft,
+        ground_area: sqft,
+        slab_cy: sqft * 0.0123,
+        gravel_cy: sqft * 0.0123,
+        form_lf: if sqft > 0.0 { perimeter } else { 0.0 },
+        rebar_lbs: sqft * 0.5,
+        vapor_barrier_sqft: sqft,
+        ..Default::default()
+    }
+}
+
+/// Material each ply of a header is bought as
+fn header_material(size: &HeaderSize) -> Option<MaterialType> {
+    if size.header_type == HeaderType::LVL {
+        return Some(MaterialType::LVLBeam);
+    }
+    lumber_material(&size.lumber_size)
+}
+
+/// Price table material for a beam; steel beams aren't priced
+fn member_material(lumber_size: &LumberSize, material: FramingMaterial) -> Option<MaterialType> {
+    match material {
+        FramingMaterial::LVL | FramingMaterial::PSL => Some(MaterialType::LVLBeam),
+        FramingMaterial::Steel => None,
+        _ => lumber_material(lumber_size),
+    }
+}
+
+fn lumber_material(lumber_size: &LumberSize) -> Option<MaterialType> {
+    match lumber_size {
+        LumberSize::TwoByFour => Some(MaterialType::Lumber2x4),
+        LumberSize::TwoBySix => Some(MaterialType::Lumber2x6),
+        LumberSize::TwoByEight => Some(MaterialType::Lumber2x8),
+        LumberSize::TwoByTen => Some(MaterialType::Lumber2x10),
+        LumberSize::TwoByTwelve => Some(MaterialType::Lumber2x12),
+        _ => None,
+    }
+}
+
+/// Takeoff description, e.g. "3-2x12" or "2-1.75x11.875 LVL"
+fn beam_description(beam: &BeamCostInput) -> String {
+    match beam.material {
+        FramingMaterial::LVL => format!("{} LVL", beam.profile.description()),
+        FramingMaterial::PSL => format!("{} PSL", beam.profile.description()),
+        _ => beam.profile.description(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::{Foundation, FoundationType, Polygon2, RoomType, TrimLevel};
 
     fn sample_input() -> CostInput {
         CostInput {
@@ -565,6 +1333,7 @@ mod tests {
             interior_wall_linear_ft: 100.0,
             roof_sqft: 2200.0,      // with overhang
             foundation_sqft: 2000.0,
+            foundation: None,
             wall_height: 8.0,
             rooms: vec![
                 RoomCostInput {
@@ -573,6 +1342,8 @@ mod tests {
                     floor_sqft: 400.0,
                     wall_sqft: 320.0,
                     perimeter_ft: 80.0,
+                    finishes: RoomFinishes::for_room_type(&RoomType::LivingRoom),
+                    ceiling_modeled: false,
                 },
                 RoomCostInput {
                     id: RoomId::new(),
@@ -580,6 +1351,8 @@ mod tests {
                     floor_sqft: 200.0,
                     wall_sqft: 160.0,
                     perimeter_ft: 60.0,
+                    finishes: RoomFinishes::for_room_type(&RoomType::Kitchen),
+                    ceiling_modeled: false,
                 },
             ],
             openings: vec![
@@ -589,6 +1362,8 @@ mod tests {
                     width: 3.0,
                     height: 4.0,
                     count: 6,
+                    catalog_code: None,
+                    unit_cost: None,
                 },
                 OpeningCostInput {
                     id: OpeningId::new(),
@@ -596,8 +1371,26 @@ mod tests {
                     width: 3.0,
                     height: 6.8,
                     count: 2,
+                    catalog_code: None,
+                    unit_cost: None,
                 },
             ],
+            assemblies: vec![],
+            headers: vec![],
+            lumber: vec![],
+            unframed_walls: vec![],
+            roof_modeled: false,
+            roof_topology: None,
+            beams: vec![],
+            posts: vec![],
+            electrical: vec![],
+            plumbing: vec![],
+            wet_walls: 0,
+            supply_registers: 0,
+            return_registers: 0,
+            decks: Vec::new(),
+            garages: Vec::new(),
+            earthwork: None,
         }
     }
 
@@ -654,6 +1447,33 @@ mod tests {
         assert!(foundation_items.len() >= 5);
     }
 
+    #[test]
+    fn test_modeled_foundation_replaces_approximation() {
+        let calc = CostCalculator::with_defaults();
+        let mut input = sample_input();
+        let foundation = Foundation::new(LevelId::new(), FoundationType::Basement);
+        let quantities = foundation.quantities(&Polygon2::rectangle(50.0, 40.0));
+        input.foundation = Some(quantities);
+        let estimate = calc.calculate(&input);
+
+        let concrete: Vec<_> = estimate
+            .line_items
+            .iter()
+            .filter(|i| i.material_type == Some(MaterialType::ConcreteMix))
+            .collect();
+        assert_eq!(concrete.len(), 3);
+        assert!(concrete.iter().any(|i| i.description == "Concrete mix - stem walls"));
+        let total: f64 = concrete.iter().map(|i| i.quantity).sum();
+        assert!((total - quantities.concrete_cy()).abs() < 1e-9);
+
+        let rebar = estimate
+            .line_items
+            .iter()
+            .find(|i| i.material_type == Some(MaterialType::ConcreteRebar))
+            .unwrap();
+        assert_eq!(rebar.quantity, quantities.rebar_lbs);
+    }
+
     #[test]
     fn test_framing_calculation() {
         let calc = CostCalculator::with_defaults();
@@ -670,6 +1490,276 @@ mod tests {
         assert!(framing_items.len() >= 3);
     }
 
+    #[test]
+    fn test_electrical_devices() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(
+            MaterialType::GfciReceptacle,
+            UnitPrice::new(MaterialType::GfciReceptacle, PricingUnit::PerComponent, 24.0),
+        );
+        calc.set_labor_rate(LaborType::ElectricalLabor, LaborRate::new(LaborType::ElectricalLabor, PricingUnit::PerHour, 80.0));
+        let mut input = sample_input();
+        assert!(!calc.calculate(&input).quantity_sources.contains_key(&CostCategory::Electrical));
+
+        input.electrical = vec![
+            ElectricalCostInput { kind: ElectricalDeviceKind::GfciReceptacle, count: 4 },
+            ElectricalCostInput { kind: ElectricalDeviceKind::Switch, count: 2 },
+        ];
+        let estimate = calc.calculate(&input);
+        let gfci = estimate
+            .line_items
+            .iter()
+            .find(|i| i.material_type == Some(MaterialType::GfciReceptacle))
+            .unwrap();
+        assert_eq!(gfci.description, "GFCI Receptacles");
+        assert_eq!(gfci.total, 96.0);
+        let labor = estimate
+            .line_items
+            .iter()
+            .find(|i| i.labor_type == Some(LaborType::ElectricalLabor))
+            .unwrap();
+        assert_eq!(labor.quantity, 6.0 * ELECTRICAL_HOURS_PER_DEVICE);
+        assert_eq!(estimate.quantity_sources[&CostCategory::Electrical], QuantitySource::Modeled);
+    }
+
+    #[test]
+    fn test_plumbing_fixtures() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(MaterialType::Toilet, UnitPrice::new(MaterialType::Toilet, PricingUnit::PerComponent, 300.0));
+        calc.set_labor_rate(LaborType::PlumbingLabor, LaborRate::new(LaborType::PlumbingLabor, PricingUnit::PerHour, 90.0));
+        let mut input = sample_input();
+        input.plumbing = vec![
+            PlumbingCostInput { kind: PlumbingFixtureKind::Toilet, count: 2 },
+            PlumbingCostInput { kind: PlumbingFixtureKind::Sink, count: 2 },
+        ];
+        let plumbing = |input: &CostInput| calc.calculate(input).category_subtotal(CostCategory::Plumbing);
+
+        // Back-to-back baths on one wet wall cost less than on two
+        input.wet_walls = 1;
+        let shared = plumbing(&input);
+        input.wet_walls = 2;
+        let separate = plumbing(&input);
+        assert_eq!(separate - shared, PLUMBING_HOURS_PER_WET_WALL * 90.0);
+        assert_eq!(shared, 600.0 + (PLUMBING_HOURS_PER_WET_WALL + 4.0 * PLUMBING_HOURS_PER_FIXTURE) * 90.0);
+        assert_eq!(calc.calculate(&input).quantity_sources[&CostCategory::Plumbing], QuantitySource::Modeled);
+    }
+
+    #[test]
+    fn test_hvac_duct_runs() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_labor_rate(LaborType::HVACInstall, LaborRate::new(LaborType::HVACInstall, PricingUnit::PerHour, 80.0));
+        let mut input = sample_input();
+        assert_eq!(calc.calculate(&input).category_subtotal(CostCategory::HVAC), 0.0);
+
+        input.supply_registers = 7;
+        input.return_registers = 1;
+        let estimate = calc.calculate(&input);
+        let labor = estimate
+            .line_items
+            .iter()
+            .find(|i| i.labor_type == Some(LaborType::HVACInstall))
+            .unwrap();
+        assert_eq!(labor.quantity, 8.0 * HVAC_HOURS_PER_DUCT_RUN);
+        assert_eq!(estimate.category_subtotal(CostCategory::HVAC), 8.0 * HVAC_HOURS_PER_DUCT_RUN * 80.0);
+        assert_eq!(estimate.quantity_sources[&CostCategory::HVAC], QuantitySource::Heuristic);
+    }
+
+    #[test]
+    fn test_deck_costs() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(MaterialType::Lumber2x8, UnitPrice::new(MaterialType::Lumber2x8, PricingUnit::PerBoard, 12.0));
+        calc.set_material_price(MaterialType::DeckBoard, UnitPrice::new(MaterialType::DeckBoard, PricingUnit::PerLinearFoot, 3.0));
+        calc.set_material_price(MaterialType::DeckRailing, UnitPrice::new(MaterialType::DeckRailing, PricingUnit::PerLinearFoot, 40.0));
+        calc.set_material_price(MaterialType::StructuralPost, UnitPrice::new(MaterialType::StructuralPost, PricingUnit::PerComponent, 25.0));
+        calc.set_labor_rate(
+            LaborType::FramingCarpentry,
+            LaborRate::new(LaborType::FramingCarpentry, PricingUnit::PerHour, 50.0),
+        );
+        let mut input = sample_input();
+        let before = calc.calculate(&input).category_subtotal(CostCategory::Exterior);
+
+        input.decks = vec![DeckCostInput {
+            kind: DeckKind::Deck,
+            area: 160.0,
+            lumber: vec![LumberCostInput { lumber_size: LumberSize::TwoByEight, stock_length: 192.0, count: 14, board_feet: 298.7 }],
+            decking_length: 336.0,
+            railing_length: 36.0,
+            railing_posts: 8,
+        }];
+        let estimate = calc.calculate(&input);
+        let expected = 14.0 * 12.0 + 336.0 * 3.0 + 36.0 * 40.0 + 8.0 * 25.0 + 160.0 * DECK_HOURS_PER_SQFT * 50.0;
+        assert!((estimate.category_subtotal(CostCategory::Exterior) - before - expected).abs() < 1e-6);
+        assert!(estimate.line_items.iter().any(|i| i.description == "Deck railing posts"));
+    }
+
+    #[test]
+    fn test_garage_slab_costs() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(MaterialType::ConcreteMix, UnitPrice::new(MaterialType::ConcreteMix, PricingUnit::PerCubicYard, 150.0));
+        calc.set_material_price(MaterialType::ConcreteGravel, UnitPrice::new(MaterialType::ConcreteGravel, PricingUnit::PerCubicYard, 40.0));
+        calc.set_material_price(MaterialType::ConcreteRebar, UnitPrice::new(MaterialType::ConcreteRebar, PricingUnit::PerPound, 1.0));
+        calc.set_labor_rate(
+            LaborType::ConcretePlaceFinish,
+            LaborRate::new(LaborType::ConcretePlaceFinish, PricingUnit::PerSquareFoot, 2.0),
+        );
+        let mut input = sample_input();
+        let before = calc.calculate(&input).category_subtotal(CostCategory::Foundation);
+
+        let slab = GarageSlab { area: 480.0, concrete_cy: 7.5, gravel_cy: 6.0, rebar_lbs: 470.0, run: 20.0, fall: 2.5 };
+        input.garages = vec![GarageCostInput { name: "Garage".to_string(), slab }];
+        let estimate = calc.calculate(&input);
+        let expected = 7.5 * 150.0 + 6.0 * 40.0 + 470.0 + 480.0 * 2.0;
+        assert!((estimate.category_subtotal(CostCategory::Foundation) - before - expected).abs() < 1e-6);
+        assert!(estimate.line_items.iter().any(|i| i.description == "Garage slab concrete"));
+    }
+
+    #[test]
+    fn test_earthwork_costs() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(MaterialType::FillSoil, UnitPrice::new(MaterialType::FillSoil, PricingUnit::PerCubicYard, 30.0));
+        calc.set_labor_rate(LaborType::Excavation, LaborRate::new(LaborType::Excavation, PricingUnit::PerCubicYard, 12.0));
+        calc.set_labor_rate(LaborType::FillCompaction, LaborRate::new(LaborType::FillCompaction, PricingUnit::PerHour, 60.0));
+        let mut input = sample_input();
+        input.earthwork = Some(CutFill {
+            building_id: crate::domain::BuildingId::new(),
+            pad_elevation: 100.0,
+            pad_area: 2000.0,
+            unsurveyed_area: 0.0,
+            cut_cy: 20.0,
+            fill_cy: 50.0,
+        });
+
+        let estimate = calc.calculate(&input);
+        // 30 yd of the fill comes from off site
+        let expected = 20.0 * 12.0 + 50.0 * FILL_HOURS_PER_CY * 60.0 + 30.0 * 30.0;
+        assert!((estimate.category_subtotal(CostCategory::SiteWork) - expected).abs() < 1e-6);
+        assert_eq!(estimate.quantity_sources.get(&CostCategory::SiteWork), Some(&QuantitySource::Modeled));
+    }
+
+    #[test]
+    fn test_roof_drainage_costs() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(MaterialType::Gutter, UnitPrice::new(MaterialType::Gutter, PricingUnit::PerLinearFoot, 8.0));
+        calc.set_material_price(MaterialType::Downspout, UnitPrice::new(MaterialType::Downspout, PricingUnit::PerComponent, 45.0));
+        calc.set_material_price(MaterialType::RidgeCap, UnitPrice::new(MaterialType::RidgeCap, PricingUnit::PerLinearFoot, 3.0));
+        calc.set_labor_rate(LaborType::GutterInstall, LaborRate::new(LaborType::GutterInstall, PricingUnit::PerHour, 50.0));
+        let mut input = sample_input();
+        let roof = crate::domain::Roof::new(input.level_id, crate::domain::RoofType::Gable, 6.0, 1.0);
+        input.roof_topology = roof.topology(&crate::domain::Polygon2::rectangle(40.0, 24.0));
+
+        let estimate = calc.calculate(&input);
+        let line = |description: &str| estimate.line_items.iter().find(|i| i.description == description);
+        // 84' of gutter to four downspouts, under a 42' ridge
+        assert!((line("Gutters").unwrap().total - 84.0 * 8.0).abs() < 1e-9);
+        assert!((line("Downspouts").unwrap().total - 4.0 * 45.0).abs() < 1e-9);
+        assert!((line("Ridge & hip cap").unwrap().total - 42.0 * 3.0).abs() < 1e-9);
+        assert!(line("Valley flashing").is_none());
+        let hours = 84.0 * GUTTER_HOURS_PER_FOOT + 4.0 * DOWNSPOUT_HOURS;
+        assert!((line("Gutter & downspout installation").unwrap().total - hours * 50.0).abs() < 1e-9);
+
+        input.roof_topology = None;
+        assert!(calc.calculate(&input).line_items.iter().all(|i| i.description != "Gutters"));
+    }
+
+    #[test]
+    fn test_header_takeoff() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(
+            MaterialType::Lumber2x10,
+            UnitPrice::new(MaterialType::Lumber2x10, PricingUnit::PerBoard, 18.0),
+        );
+        let mut input = sample_input();
+        input.headers = vec![HeaderCostInput {
+            size: HeaderSize { header_type: HeaderType::DoubleLumber, lumber_size: LumberSize::TwoByTen, plies: 2 },
+            count: 3,
+        }];
+
+        let estimate = calc.calculate(&input);
+        let headers = estimate
+            .line_items
+            .iter()
+            .find(|i| i.description == "2-2x10 headers")
+            .unwrap();
+        assert_eq!(headers.quantity, 6.0);
+        assert!((headers.total - 108.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_framed_lumber_replaces_heuristic() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(
+            MaterialType::Lumber2x6,
+            UnitPrice::new(MaterialType::Lumber2x6, PricingUnit::PerLinearFoot, 1.0),
+        );
+        let mut input = sample_input();
+        input.headers = vec![HeaderCostInput {
+            size: HeaderSize { header_type: HeaderType::DoubleLumber, lumber_size: LumberSize::TwoBySix, plies: 2 },
+            count: 1,
+        }];
+        input.lumber = vec![LumberCostInput {
+            lumber_size: LumberSize::TwoBySix,
+            stock_length: 192.0,
+            count: 10,
+            board_feet: 110.0,
+        }];
+
+        let estimate = calc.calculate(&input);
+        let framing: Vec<&str> = estimate
+            .items_by_category(CostCategory::Framing)
+            .iter()
+            .map(|i| i.description.as_str())
+            .collect();
+        assert!(!framing.contains(&"2x6 exterior wall studs"));
+        assert!(!framing.contains(&"2-2x6 headers"));
+        let lumber = estimate.line_items.iter().find(|i| i.description == "2x6 x 16' framing lumber").unwrap();
+        assert_eq!(lumber.quantity, 160.0);
+        assert_eq!(lumber.notes.as_deref(), Some("110.0 board feet"));
+        assert_eq!(estimate.quantity_sources[&CostCategory::Framing], QuantitySource::Modeled);
+
+        input.unframed_walls = vec![UnframedWallCostInput { lumber_size: LumberSize::TwoByFour, linear_ft: 20.0 }];
+        let estimate = calc.calculate(&input);
+        let studs = estimate.line_items.iter().find(|i| i.description == "2x4 studs, unframed walls").unwrap();
+        assert_eq!(studs.quantity, 17.0);
+        assert_eq!(estimate.quantity_sources[&CostCategory::Framing], QuantitySource::Partial);
+        assert_eq!(estimate.quantity_sources[&CostCategory::Roofing], QuantitySource::Heuristic);
+    }
+
+    #[test]
+    fn test_beam_and_post_takeoff() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(
+            MaterialType::LVLBeam,
+            UnitPrice::new(MaterialType::LVLBeam, PricingUnit::PerBoard, 240.0),
+        );
+        calc.set_material_price(
+            MaterialType::StructuralPost,
+            UnitPrice::new(MaterialType::StructuralPost, PricingUnit::PerComponent, 85.0),
+        );
+        let mut input = sample_input();
+        input.beams = vec![
+            BeamCostInput {
+                profile: MemberProfile::new(LumberSize::Custom { width: 1.75, depth: 11.875 }, 2),
+                material: FramingMaterial::LVL,
+                boards: 2,
+            },
+            // Steel has no price table entry
+            BeamCostInput {
+                profile: MemberProfile::new(LumberSize::Custom { width: 4.0, depth: 8.0 }, 1),
+                material: FramingMaterial::Steel,
+                boards: 1,
+            },
+        ];
+        input.posts = vec![PostCostInput { profile: MemberProfile::new(LumberSize::FourBySix, 1), count: 2 }];
+
+        let estimate = calc.calculate(&input);
+        let beams: Vec<_> = estimate.line_items.iter().filter(|i| i.description.ends_with("beams")).collect();
+        assert_eq!(beams.len(), 1);
+        assert_eq!(beams[0].description, "2-1.75x11.875 LVL beams");
+        assert!((beams[0].total - 480.0).abs() < 1e-9);
+        let posts = estimate.line_items.iter().find(|i| i.description == "1-4x6 posts").unwrap();
+        assert!((posts.total - 170.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_roofing_calculation() {
         let calc = CostCalculator::with_defaults();
@@ -732,6 +1822,76 @@ mod tests {
         assert!(flooring_items.len() >= 2); // 2 rooms
     }
 
+    #[test]
+    fn test_finish_selections_priced() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(
+            MaterialType::Carpet,
+            UnitPrice::new(MaterialType::Carpet, PricingUnit::PerSquareFoot, 4.0),
+        );
+        let mut input = sample_input();
+        input.rooms[0].finishes.set(FinishSurface::Floor, MaterialType::Carpet);
+        input.rooms[1].finishes.set(FinishSurface::Ceiling, MaterialType::Drywall);
+
+        let estimate = calc.calculate(&input);
+        let carpet: Vec<_> = estimate
+            .line_items
+            .iter()
+            .filter(|i| i.material_type == Some(MaterialType::Carpet))
+            .collect();
+        assert_eq!(carpet.len(), 1);
+        assert!((carpet[0].total - 1600.0).abs() < 1e-9);
+        assert!(!estimate.line_items.iter().any(|i| i.description == "kitchen ceiling"));
+        assert!(estimate.line_items.iter().any(|i| i.description == "living ceiling"));
+    }
+
+    #[test]
+    fn test_trim_level_priced() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(
+            MaterialType::Trim,
+            UnitPrice::new(MaterialType::Trim, PricingUnit::PerLinearFoot, 2.0),
+        );
+        let mut input = sample_input();
+        input.rooms[1].finishes.trim = TrimLevel::Basic;
+
+        let estimate = calc.calculate(&input);
+        let base = |description: &str| {
+            estimate.line_items.iter().find(|i| i.description == description).unwrap().unit_price
+        };
+        assert!((base("living baseboard (premium trim)") - 3.2).abs() < 1e-9);
+        assert!((base("kitchen baseboard (basic trim)") - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_assemblies_priced_by_layer() {
+        let mut calc = CostCalculator::with_defaults();
+        calc.set_material_price(
+            MaterialType::Drywall,
+            UnitPrice::new(MaterialType::Drywall, PricingUnit::PerSquareFoot, 1.0),
+        );
+        let mut input = sample_input();
+        input.rooms[0].ceiling_modeled = true;
+        input.assemblies.push(AssemblyCostInput {
+            description: "Living ceiling".to_string(),
+            area: 400.0,
+            layers: vec![SlabLayer::gypsum_1_2()],
+        });
+
+        let estimate = calc.calculate(&input);
+        let board = estimate
+            .line_items
+            .iter()
+            .find(|i| i.description == "Living ceiling Gypsum Board 1/2\"")
+            .unwrap();
+        assert_eq!(board.category, CostCategory::Drywall);
+        assert!((board.total - 400.0).abs() < 1e-9);
+
+        // The room's own drywall leaves the ceiling to the assembly
+        let walls = estimate.line_items.iter().find(|i| i.description == "living drywall").unwrap();
+        assert!((walls.total - 320.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_room_flooring_types() {
         let mut calc = CostCalculator::with_defaults();
@@ -834,9 +1994,26 @@ mod tests {
             interior_wall_linear_ft: 50.0,
             roof_sqft: 1100.0,
             foundation_sqft: 1000.0,
+            foundation: None,
             wall_height: 8.0,
             rooms: vec![],
             openings: vec![],
+            assemblies: vec![],
+            headers: vec![],
+            lumber: vec![],
+            unframed_walls: vec![],
+            roof_modeled: false,
+            roof_topology: None,
+            beams: vec![],
+            posts: vec![],
+            electrical: vec![],
+            plumbing: vec![],
+            wet_walls: 0,
+            supply_registers: 0,
+            return_registers: 0,
+            decks: Vec::new(),
+            garages: Vec::new(),
+            earthwork: None,
         };
 
         let estimate = calc.calculate(&input);
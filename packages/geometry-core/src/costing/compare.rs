@@ -0,0 +1,171 @@
+//! Estimate Comparison
+//! Cost difference between two snapshots of a project, line by line, for
+//! questions like "what does the bigger garage cost". Line items are matched
+//! by building, level, category and description.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::costing::CostCategory;
+use crate::domain::{BuildingId, LevelId, ProjectId, SnapshotId};
+use crate::store::Store;
+use super::calculator::CostCalculator;
+
+/// Change in one line item between two estimates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineItemDelta {
+    pub building_id: BuildingId,
+    pub building_name: String,
+    pub level_id: LevelId,
+    pub level_name: String,
+    pub category: CostCategory,
+    pub description: String,
+    pub quantity_a: f64,
+    pub quantity_b: f64,
+    pub total_a: f64,
+    pub total_b: f64,
+    /// total_b - total_a
+    pub delta: f64,
+}
+
+/// Cost difference between two snapshots of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstimateComparison {
+    pub project_id: ProjectId,
+    pub snapshot_a: SnapshotId,
+    pub snapshot_b: SnapshotId,
+    pub label_a: Option<String>,
+    pub label_b: Option<String>,
+    /// Line items that changed, in estimate order, with items only in the
+    /// first state last
+    pub items: Vec<LineItemDelta>,
+    /// Grand totals, markups included
+    pub total_a: f64,
+    pub total_b: f64,
+    pub total_delta: f64,
+}
+
+/// Line item key: building, level, category, description
+type ItemKey = (BuildingId, LevelId, CostCategory, String);
+
+impl CostCalculator {
+    /// Estimate a project at two of its snapshots and report what changed
+    pub fn compare_snapshots(&self, store: &Store, a: SnapshotId, b: SnapshotId) -> Result<EstimateComparison> {
+        let snapshot = |id: SnapshotId| store.get_snapshot(id)
+            .ok_or_else(|| anyhow!("Snapshot not found: {:?}", id));
+        let (snapshot_a, snapshot_b) = (snapshot(a)?, snapshot(b)?);
+        if snapshot_a.project_id != snapshot_b.project_id {
+            return Err(anyhow!("Snapshots belong to different projects"));
+        }
+        let project_id = snapshot_a.project_id;
+
+        let mut items: Vec<LineItemDelta> = Vec::new();
+        let mut index: HashMap<ItemKey, usize> = HashMap::new();
+        let mut totals = [0.0; 2];
+        // The later state goes first so rows follow its estimate order
+        for (side, id) in [(1, b), (0, a)] {
+            let mut state = store.at_snapshot(id)?;
+            state.regenerate_stale_framing()?;
+            let estimate = self.calculate_project(&state, project_id)?;
+            totals[side] = estimate.grand_total;
+
+            for section in &estimate.sections {
+                for level in &section.levels {
+                    let level_name = state.get_level(level.level_id).map(|l| l.name.clone()).unwrap_or_default();
+                    for item in &level.line_items {
+                        let key = (section.building_id, level.level_id, item.category, item.description.clone());
+                        let row = *index.entry(key).or_insert_with(|| {
+                            items.push(LineItemDelta {
+                                building_id: section.building_id,
+                                building_name: section.building_name.clone(),
+                                level_id: level.level_id,
+                                level_name: level_name.clone(),
+                                category: item.category,
+                                description: item.description.clone(),
+                                quantity_a: 0.0,
+                                quantity_b: 0.0,
+                                total_a: 0.0,
+                                total_b: 0.0,
+                                delta: 0.0,
+                            });
+                            items.len() - 1
+                        });
+                        let row = &mut items[row];
+                        if side == 0 {
+                            row.quantity_a += item.quantity;
+                            row.total_a += item.total;
+                        } else {
+                            row.quantity_b += item.quantity;
+                            row.total_b += item.total;
+                        }
+                    }
+                }
+            }
+        }
+
+        for item in &mut items {
+            item.delta = item.total_b - item.total_a;
+        }
+        items.retain(|item| item.delta.abs() > 1e-9 || (item.quantity_b - item.quantity_a).abs() > 1e-9);
+
+        Ok(EstimateComparison {
+            project_id,
+            snapshot_a: a,
+            snapshot_b: b,
+            label_a: snapshot_a.label.clone(),
+            label_b: snapshot_b.label.clone(),
+            items,
+            total_a: totals[0],
+            total_b: totals[1],
+            total_delta: totals[1] - totals[0],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::costing::{MaterialType, PricingUnit, UnitPrice};
+    use crate::domain::{CodeRegion, Polygon2, UnitSystem};
+
+    #[test]
+    fn test_compare_snapshots() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let garage = store.add_building(project_id, "Garage").unwrap();
+        let level_id = store.add_level(garage, "Slab", 0.0, 10.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(20.0, 20.0)).unwrap();
+        let small = store.create_snapshot(project_id).unwrap();
+        store.label_snapshot(small, "Two-car").unwrap();
+
+        let footprint_id = store.get_level_footprint(level_id).unwrap().id;
+        store.modify_footprint(footprint_id, Polygon2::rectangle(30.0, 20.0)).unwrap();
+        let large = store.create_snapshot(project_id).unwrap();
+        store.label_snapshot(large, "Three-car").unwrap();
+
+        let mut calculator = CostCalculator::with_defaults();
+        calculator.set_material_price(
+            MaterialType::ConcreteMix,
+            UnitPrice::new(MaterialType::ConcreteMix, PricingUnit::PerCubicYard, 150.0),
+        );
+        let comparison = calculator.compare_snapshots(&store, small, large).unwrap();
+        assert_eq!(comparison.label_b.as_deref(), Some("Three-car"));
+        assert!(comparison.total_delta > 0.0);
+        let sum: f64 = comparison.items.iter().map(|i| i.delta).sum();
+        assert!((sum - comparison.total_delta).abs() < 1e-6);
+
+        // 200 more sqft of slab at 0.0123 cy/sqft
+        let concrete = comparison.items.iter().find(|i| i.description == "Concrete mix").unwrap();
+        assert!((concrete.quantity_b - concrete.quantity_a - 2.46).abs() < 1e-9);
+        assert_eq!(concrete.level_name, "Slab");
+
+        // Comparing a state with itself changes nothing
+        let same = calculator.compare_snapshots(&store, large, large).unwrap();
+        assert!(same.items.is_empty());
+        assert!(calculator.compare_snapshots(&store, small, SnapshotId::new()).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! Cost Input Extraction
+//! Derives calculator inputs from the geometry in the store
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::analysis::{cut_fill, hvac_summary};
+use crate::domain::{FramingMemberType, LevelId, OpeningType, SlabBoundary};
+use crate::store::Store;
+use crate::framing::CutList;
+use super::calculator::{
+    AssemblyCostInput, BeamCostInput, CostInput, DeckCostInput, ElectricalCostInput, GarageCostInput, HeaderCostInput, LumberCostInput, OpeningCostInput,
+    PlumbingCostInput, PostCostInput, RoomCostInput, UnframedWallCostInput,
+};
+
+impl CostInput {
+    /// Build the cost input for one level from store data
+    pub fn from_level(store: &Store, level_id: LevelId) -> Result<CostInput> {
+        let level = store.get_level(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+        // Get footprint data
+        let footprint = store.get_level_footprint(level_id);
+        let footprint_sqft = footprint
+            .as_ref()
+            .map(|fp| fp.polygon.area())
+            .unwrap_or(0.0);
+        let exterior_perimeter = footprint
+            .as_ref()
+            .map(|fp| fp.polygon.perimeter())
+            .unwrap_or(0.0);
+
+        // Get rooms
+        let rooms = store.get_level_rooms(level_id);
+        let total_floor_area: f64 = rooms.iter().map(|r| r.area()).sum();
+
+        // Build room cost inputs
+        let room_inputs: Vec<RoomCostInput> = rooms
+            .iter()
+            .map(|room| {
+                let perimeter = room.boundary.perimeter();
+                let floor_sqft = room.area();
+                let wall_sqft = perimeter * level.floor_to_floor;
+                RoomCostInput {
+                    id: room.id,
+                    room_type: room.room_type.display_name().to_string(),
+                    floor_sqft,
+                    wall_sqft,
+                    perimeter_ft: perimeter,
+                    finishes: room.finishes(),
+                    ceiling_modeled: store.get_room_ceiling(room.id).is_some_and(|c| c.has_drywall()),
+                }
+            })
+            .collect();
+
+        // Get walls and calculate wall areas
+        let walls = store.get_level_walls(level_id);
+        let mut exterior_wall_linear_ft = 0.0;
+        let mut interior_wall_linear_ft = 0.0;
+
+        for wall in &walls {
+            let wall_length = wall.length();
+            // For now, assume all walls are interior
+            // TODO: Determine exterior vs interior based on footprint boundary
+            interior_wall_linear_ft += wall_length;
+        }
+
+        // Use footprint perimeter as exterior wall estimate if no explicit exterior walls
+        if exterior_wall_linear_ft == 0.0 {
+            exterior_wall_linear_ft = exterior_perimeter;
+        }
+
+        let exterior_wall_sqft = exterior_wall_linear_ft * level.floor_to_floor;
+
+        // Build opening cost inputs
+        let mut opening_inputs: Vec<OpeningCostInput> = Vec::new();
+        let mut opening_counts: HashMap<String, OpeningCostInput> = HashMap::new();
+
+        for wall in &walls {
+            let openings = store.get_wall_openings(wall.id);
+            for opening in openings {
+                let catalog_door = opening.catalog_code.as_deref().and_then(|code| store.opening_catalog.door(code));
+                let opening_type = match &opening.opening_type {
+                    OpeningType::Door if catalog_door.is_some_and(|door| !door.is_exterior) => "interior_door".to_string(),
+                    OpeningType::Door => "exterior_door".to_string(),
+                    OpeningType::Window => "window".to_string(),
+                    OpeningType::Other(name) => {
+                        if name.to_lowercase().contains("interior") {
+                            "interior_door".to_string()
+                        } else if opening.opening_type.is_garage_door() {
+                            "garage_door".to_string()
+                        } else {
+                            "window".to_string()
+                        }
+                    }
+                };
+
+                // Group catalog units by code, others by type and dimensions
+                let key = match &opening.catalog_code {
+                    Some(code) => format!("{}_{}", opening_type, code),
+                    None => format!("{}_{}x{}", opening_type, opening.width as i32, opening.height as i32),
+                };
+                let unit_cost = opening.catalog_code.as_deref().and_then(|code| store.opening_catalog.unit_cost(code));
+                let entry = opening_counts.entry(key).or_insert(OpeningCostInput {
+                    id: opening.id,
+                    opening_type,
+                    width: opening.width,
+                    height: opening.height,
+                    count: 0,
+                    catalog_code: opening.catalog_code.clone(),
+                    unit_cost,
+                });
+                entry.count += 1;
+            }
+        }
+
+        opening_inputs.extend(opening_counts.into_values());
+
+        // Headers from walls that have been framed, counted by size
+        let mut headers: Vec<HeaderCostInput> = Vec::new();
+        for layout in walls.iter().filter_map(|wall| store.get_wall_framing_layout(wall.id)) {
+            for selection in &layout.headers {
+                match headers.iter_mut().find(|h| h.size == selection.size) {
+                    Some(header) => header.count += 1,
+                    None => headers.push(HeaderCostInput { size: selection.size, count: 1 }),
+                }
+            }
+        }
+        headers.sort_by_key(|h| h.size.description());
+
+        // Modeled beams and posts, grouped by profile
+        let mut beams: Vec<BeamCostInput> = Vec::new();
+        for beam in store.get_level_beams(level_id) {
+            let boards = if beam.material.is_engineered() {
+                beam.profile.plies as u32
+            } else {
+                beam.profile.plies as u32 * (beam.length() / 16.0).ceil().max(1.0) as u32
+            };
+            match beams.iter_mut().find(|b| b.profile == beam.profile && b.material == beam.material) {
+                Some(entry) => entry.boards += boards,
+                None => beams.push(BeamCostInput { profile: beam.profile, material: beam.material, boards }),
+            }
+        }
+        let mut posts: Vec<PostCostInput> = Vec::new();
+        for post in store.get_level_posts(level_id) {
+            match posts.iter_mut().find(|p| p.profile == post.profile) {
+                Some(entry) => entry.count += 1,
+                None => posts.push(PostCostInput { profile: post.profile, count: 1 }),
+            }
+        }
+
+        // Placed electrical devices by kind
+        let mut electrical: Vec<ElectricalCostInput> = Vec::new();
+        for device in store.get_level_electrical_devices(level_id) {
+            match electrical.iter_mut().find(|e| e.kind == device.kind) {
+                Some(entry) => entry.count += 1,
+                None => electrical.push(ElectricalCostInput { kind: device.kind, count: 1 }),
+            }
+        }
+
+        // Placed plumbing fixtures by kind, with a rough-in per wet wall
+        let mut plumbing: Vec<PlumbingCostInput> = Vec::new();
+        for fixture in store.get_level_fixtures(level_id) {
+            match plumbing.iter_mut().find(|p| p.kind == fixture.kind) {
+                Some(entry) => entry.count += 1,
+                None => plumbing.push(PlumbingCostInput { kind: fixture.kind, count: 1 }),
+            }
+        }
+        let wet_walls = store.wet_walls(level_id).rough_in_count() as u32;
+
+        // Supply registers in this level's rooms and a return per zone
+        let (supply_registers, return_registers) = hvac_summary(store, level.building_id)?.level_registers(level_id);
+
+        // Lumber from generated wall framing, bought as the cut list orders
+        // it. Once any wall is framed, the rest are estimated wall by wall.
+        let layouts: Vec<_> = walls.iter().filter_map(|w| store.get_wall_framing_layout(w.id)).collect();
+        let lumber = lumber_inputs(CutList::from_layouts(layouts.iter().copied()));
+        let mut unframed_walls: Vec<UnframedWallCostInput> = Vec::new();
+        if !lumber.is_empty() {
+            for wall in walls.iter().filter(|w| store.get_wall_framing_layout(w.id).is_none()) {
+                let lumber_size = wall.framing_config.lumber_size;
+                match unframed_walls.iter_mut().find(|u| u.lumber_size == lumber_size) {
+                    Some(entry) => entry.linear_ft += wall.length(),
+                    None => unframed_walls.push(UnframedWallCostInput { lumber_size, linear_ft: wall.length() }),
+                }
+            }
+        }
+
+        // Use the modeled roof when there is one, otherwise a simple
+        // multiplier for pitch
+        let (roof_sqft, roof_modeled, roof_topology) = match (store.get_level_roof(level_id), footprint.as_ref()) {
+            (Some(roof), Some(fp)) => (roof.surface_area(&fp.polygon), true, roof.topology(&fp.polygon)),
+            _ => (footprint_sqft * 1.1, false, None), // 10% overhang/pitch factor
+        };
+
+        // Floors and ceilings priced over the area they cover. A level-wide
+        // assembly doesn't cover rooms that have their own.
+        let mut assemblies: Vec<AssemblyCostInput> = Vec::new();
+        let floors: Vec<_> = store.get_level_floor_slabs(level_id)
+            .into_iter()
+            .map(|s| (s.boundary, &s.layers))
+            .collect();
+        let ceilings: Vec<_> = store.get_level_ceilings(level_id)
+            .into_iter()
+            .map(|c| (c.boundary, &c.layers))
+            .collect();
+        for (kind, list) in [("floor", floors), ("ceiling", ceilings)] {
+            let room_area: f64 = list.iter()
+                .filter_map(|(boundary, _)| match boundary {
+                    SlabBoundary::Room { .. } => store.slab_outline(level_id, *boundary).map(|p| p.area()),
+                    SlabBoundary::Level => None,
+                })
+                .sum();
+            for (boundary, layers) in list {
+                let Some(outline) = store.slab_outline(level_id, boundary) else {
+                    continue;
+                };
+                let (name, area) = match boundary {
+                    SlabBoundary::Level => (level.name.as_str(), (outline.area() - room_area).max(0.0)),
+                    SlabBoundary::Room { room_id } => (
+                        store.get_room(room_id).map(|r| r.name.as_str()).unwrap_or("Room"),
+                        outline.area(),
+                    ),
+                };
+                assemblies.push(AssemblyCostInput {
+                    description: format!("{} {}", name, kind),
+                    area,
+                    layers: layers.clone(),
+                });
+            }
+        }
+
+        // Decks and porches, with lumber cut from their own framing
+        let mut decks: Vec<DeckCostInput> = Vec::new();
+        for deck in store.get_level_decks(level_id) {
+            let layout = store.get_deck_framing(deck.id)?;
+            let pieces = layout.members
+                .iter()
+                .filter(|m| matches!(m.member_type, FramingMemberType::Ledger | FramingMemberType::RimBoard | FramingMemberType::FloorJoist))
+                .map(|m| (m.lumber_size, m.length))
+                .collect();
+            decks.push(DeckCostInput {
+                kind: deck.kind,
+                area: deck.area(),
+                lumber: lumber_inputs(CutList::optimize(pieces)),
+                decking_length: layout.decking_length,
+                railing_length: deck.railing_length(),
+                railing_posts: layout.railing_posts,
+            });
+        }
+
+        // Garage slabs are priced on their own, and left out of the
+        // foundation slab under them
+        let mut garages: Vec<GarageCostInput> = Vec::new();
+        for garage in store.get_level_garages(level_id) {
+            let name = store.get_room(garage.room_id).map(|r| r.name.clone()).unwrap_or_else(|| "Garage".to_string());
+            garages.push(GarageCostInput { name, slab: store.garage_slab(garage.id)? });
+        }
+        let garage_area: f64 = garages.iter().map(|g| g.slab.area).sum();
+
+        // Pad grading is priced with the building's lowest level
+        let lowest = store.get_building_levels_by_elevation(level.building_id)
+            .first()
+            .is_some_and(|l| l.id == level_id);
+        let earthwork = store.get_building(level.building_id)
+            .and_then(|b| b.pad_elevation)
+            .filter(|_| lowest)
+            .and_then(|pad| cut_fill(store, level.building_id, pad).ok());
+
+        let foundation = store.get_level_foundation(level_id)
+            .and_then(|f| Some(store.foundation_quantities(f.id)?.without_slab(garage_area, &f.dimensions)));
+
+        Ok(CostInput {
+            level_id,
+            footprint_sqft,
+            total_floor_area: if total_floor_area > 0.0 { total_floor_area } else { footprint_sqft },
+            exterior_wall_linear_ft,
+            exterior_wall_sqft,
+            interior_wall_linear_ft,
+            roof_sqft,
+            foundation_sqft: (footprint_sqft - garage_area).max(0.0),
+            foundation,
+            rooms: room_inputs,
+            openings: opening_inputs,
+            assemblies,
+            headers,
+            lumber,
+            unframed_walls,
+            roof_modeled,
+            roof_topology,
+            beams,
+            posts,
+            electrical,
+            plumbing,
+            wet_walls,
+            supply_registers,
+            return_registers,
+            decks,
+            garages,
+            earthwork,
+            wall_height: level.floor_to_floor,
+        })
+    }
+}
+
+/// Stock boards as the cut list orders them
+fn lumber_inputs(cut_list: CutList) -> Vec<LumberCostInput> {
+    cut_list
+        .orders
+        .into_iter()
+        .map(|order| LumberCostInput {
+            lumber_size: order.lumber_size,
+            stock_length: order.stock_length,
+            count: order.count,
+            board_feet: order.lumber_size.board_feet_per_foot() * order.stock_length / 12.0 * order.count as f64,
+        })
+        .collect()
+}
@@ -2,5 +2,14 @@
 // Generates cost estimates from floor plan geometry and price tables
 
 pub mod calculator;
+pub mod compare;
+pub mod input;
+pub mod prices;
+pub mod project;
+pub mod takeoff;
 
 pub use calculator::*;
+pub use compare::{EstimateComparison, LineItemDelta};
+pub use prices::{PriceCsvError, PriceCsvImport};
+pub use project::{EstimateSection, ProjectEstimate};
+pub use takeoff::{OpeningScheduleEntry, QuantityTakeoff, StudCount, TakeoffItem, TakeoffUnit};
@@ -0,0 +1,305 @@
+//! Price Tables in Bulk
+//! CSV import/export of material prices and labor rates, and region-tagged
+//! price sets a project can be priced against instead of the default table.
+//!
+//! CSV columns: kind (material or labor), type, unit, price, description,
+//! supplier, last_updated. Types and units use the same names as the JSON
+//! price table (e.g. `lumber2x4`, `per_linear_foot`).
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::domain::costing::{LaborRate, PriceTable, UnitPrice};
+
+const HEADER: [&str; 7] = ["kind", "type", "unit", "price", "description", "supplier", "last_updated"];
+const REQUIRED: [&str; 4] = ["kind", "type", "unit", "price"];
+
+/// A CSV row that couldn't be imported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceCsvError {
+    /// Line number in the file, counting the header as line 1
+    pub row: usize,
+    pub message: String,
+}
+
+/// Outcome of a CSV import: nothing is applied unless every row is valid
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceCsvImport {
+    pub imported: usize,
+    pub errors: Vec<PriceCsvError>,
+}
+
+impl PriceTable {
+    /// Table tagged with a pricing region
+    pub fn for_region(region: impl Into<String>) -> Self {
+        Self {
+            region: Some(region.into()),
+            ..Self::new()
+        }
+    }
+
+    /// Copy another table's entries over this one's, keeping this region
+    pub fn merge(&mut self, other: PriceTable) {
+        self.material_prices.extend(other.material_prices);
+        self.labor_rates.extend(other.labor_rates);
+    }
+
+    /// Every entry as CSV, materials then labor, each sorted by type
+    pub fn to_csv(&self) -> String {
+        let mut rows: Vec<[String; 7]> = Vec::new();
+        for price in self.material_prices.values() {
+            rows.push([
+                "material".to_string(),
+                enum_name(&price.material_type),
+                enum_name(&price.unit),
+                price.price.to_string(),
+                price.description.clone().unwrap_or_default(),
+                price.supplier.clone().unwrap_or_default(),
+                price.last_updated.clone().unwrap_or_default(),
+            ]);
+        }
+        for rate in self.labor_rates.values() {
+            rows.push([
+                "labor".to_string(),
+                enum_name(&rate.labor_type),
+                enum_name(&rate.unit),
+                rate.rate.to_string(),
+                rate.description.clone().unwrap_or_default(),
+                String::new(),
+                String::new(),
+            ]);
+        }
+        rows.sort_by(|a, b| b[0].cmp(&a[0]).then_with(|| a[1].cmp(&b[1])));
+
+        let mut csv = HEADER.join(",");
+        csv.push('\n');
+        for row in rows {
+            let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Parse CSV into a table, or report every invalid row. Columns are
+    /// matched by header name; description, supplier and last_updated are
+    /// optional.
+    pub fn from_csv(text: &str) -> Result<PriceTable, Vec<PriceCsvError>> {
+        let mut rows = parse_csv(text).into_iter().filter(|(_, fields)| fields.iter().any(|f| !f.trim().is_empty()));
+        let Some((header_row, header)) = rows.next() else {
+            return Err(vec![PriceCsvError { row: 1, message: "CSV is empty".to_string() }]);
+        };
+        let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+        let column = |name: &str| header.iter().position(|h| h == name);
+        let missing: Vec<&str> = REQUIRED.iter().copied().filter(|name| column(name).is_none()).collect();
+        if !missing.is_empty() {
+            return Err(vec![PriceCsvError {
+                row: header_row,
+                message: format!("Missing columns: {}", missing.join(", ")),
+            }]);
+        }
+
+        let mut table = PriceTable::new();
+        let mut errors = Vec::new();
+        for (row, fields) in rows {
+            let field = |name: &str| {
+                column(name)
+                    .and_then(|i| fields.get(i))
+                    .map(|f| f.trim())
+                    .filter(|f| !f.is_empty())
+            };
+            let mut fail = |message: String| errors.push(PriceCsvError { row, message });
+
+            let Some(unit) = field("unit").and_then(enum_from_name) else {
+                fail(format!("Unknown unit: {:?}", field("unit").unwrap_or_default()));
+                continue;
+            };
+            let price = match field("price").map(str::parse::<f64>) {
+                Some(Ok(price)) if price.is_finite() && price >= 0.0 => price,
+                _ => {
+                    fail(format!("Price must be a non-negative number: {:?}", field("price").unwrap_or_default()));
+                    continue;
+                }
+            };
+            let name = field("type").unwrap_or_default();
+            let description = field("description").map(str::to_string);
+            match field("kind").map(str::to_lowercase).as_deref() {
+                Some("material") => match enum_from_name(name) {
+                    Some(material) if table.material_prices.contains_key(&material) => {
+                        fail(format!("Duplicate material: {}", name));
+                    }
+                    Some(material) => {
+                        let mut entry = UnitPrice::new(material, unit, price);
+                        entry.description = description;
+                        entry.supplier = field("supplier").map(str::to_string);
+                        entry.last_updated = field("last_updated").map(str::to_string);
+                        table.set_material_price(material, entry);
+                    }
+                    None => fail(format!("Unknown material type: {:?}", name)),
+                },
+                Some("labor") => match enum_from_name(name) {
+                    Some(labor) if table.labor_rates.contains_key(&labor) => {
+                        fail(format!("Duplicate labor type: {}", name));
+                    }
+                    Some(labor) => {
+                        let mut entry = LaborRate::new(labor, unit, price);
+                        entry.description = description;
+                        table.set_labor_rate(labor, entry);
+                    }
+                    None => fail(format!("Unknown labor type: {:?}", name)),
+                },
+                other => fail(format!("Kind must be material or labor: {:?}", other.unwrap_or_default())),
+            }
+        }
+
+        if errors.is_empty() { Ok(table) } else { Err(errors) }
+    }
+}
+
+/// Serde name of a unit-variant enum, e.g. `per_linear_foot`
+fn enum_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn enum_from_name<T: DeserializeOwned>(name: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(name.to_lowercase())).ok()
+}
+
+/// Quote a field when it holds a comma, quote or line break
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Rows of fields with the line each row starts on; quoted fields may hold
+/// commas, doubled quotes and line breaks
+fn parse_csv(text: &str) -> Vec<(usize, Vec<String>)> {
+    let mut rows = Vec::new();
+    let (mut fields, mut field) = (Vec::new(), String::new());
+    let (mut line, mut row_line, mut in_quotes) = (1, 1, false);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' => {
+                line += 1;
+                if in_quotes {
+                    field.push('\n');
+                } else {
+                    fields.push(std::mem::take(&mut field));
+                    rows.push((row_line, std::mem::take(&mut fields)));
+                    row_line = line;
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        rows.push((row_line, fields));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::costing::CostCalculator;
+    use crate::domain::costing::{LaborType, MaterialType, PricingUnit};
+    use crate::store::Store;
+
+    #[test]
+    fn test_price_csv_round_trip() {
+        let mut table = PriceTable::new();
+        table.set_material_price(
+            MaterialType::Lumber2x6,
+            UnitPrice::new(MaterialType::Lumber2x6, PricingUnit::PerLinearFoot, 1.25)
+                .with_supplier("Smith, Jones & Co")
+                .with_last_updated("2026-03-01"),
+        );
+        table.set_labor_rate(LaborType::FramingCarpentry, LaborRate::new(LaborType::FramingCarpentry, PricingUnit::PerHour, 65.0));
+
+        let csv = table.to_csv();
+        assert!(csv.starts_with("kind,type,unit,price,"));
+        assert!(csv.contains("\"Smith, Jones & Co\""));
+        let parsed = PriceTable::from_csv(&csv).unwrap();
+        let lumber = parsed.get_material_price(&MaterialType::Lumber2x6).unwrap();
+        assert_eq!(lumber.price, 1.25);
+        assert_eq!(lumber.supplier.as_deref(), Some("Smith, Jones & Co"));
+        assert_eq!(parsed.labor_rate_value(&LaborType::FramingCarpentry), 65.0);
+    }
+
+    #[test]
+    fn test_price_csv_row_errors() {
+        let csv = "type,kind,price,unit\n\
+            lumber2x4,material,0.85,per_linear_foot\n\
+            plywood,material,30,per_component\n\
+            drywall_install,labor,-4,per_square_foot\n\
+            \n\
+            lumber2x4,material,0.9,per_linear_foot\n\
+            drywall,material,12,per_bundle\n";
+        let errors = PriceTable::from_csv(csv).unwrap_err();
+        let rows: Vec<usize> = errors.iter().map(|e| e.row).collect();
+        assert_eq!(rows, vec![3, 4, 6, 7]);
+        assert!(errors[0].message.contains("plywood"));
+        assert!(errors[2].message.contains("Duplicate"));
+
+        assert_eq!(PriceTable::from_csv("kind,type\nmaterial,drywall\n").unwrap_err()[0].message, "Missing columns: unit, price");
+
+        // A bad file leaves the calculator's prices alone
+        let mut calculator = CostCalculator::with_defaults();
+        let import = calculator.import_price_csv(csv, None);
+        assert_eq!(import.imported, 0);
+        assert_eq!(calculator.price_table().material_price_value(&MaterialType::Lumber2x4), 0.0);
+    }
+
+    #[test]
+    fn test_project_price_region() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", crate::domain::UnitSystem::Imperial, crate::domain::CodeRegion::us_irc_2021())
+            .unwrap();
+        let mut calculator = CostCalculator::with_defaults();
+        let import = calculator.import_price_csv("kind,type,unit,price\nmaterial,drywall,per_component,14.5\n", Some("us-tx"));
+        assert_eq!(import.imported, 1);
+        assert_eq!(calculator.price_set_regions(), vec!["us-tx"]);
+        assert_eq!(calculator.price_set("us-tx").unwrap().region.as_deref(), Some("us-tx"));
+
+        let drywall = |c: &CostCalculator| c.price_table().material_price_value(&MaterialType::Drywall);
+        assert_eq!(drywall(&calculator.for_project(&store, project_id)), 0.0);
+        store.set_project_price_region(project_id, Some("us-tx".to_string())).unwrap();
+        assert_eq!(drywall(&calculator.for_project(&store, project_id)), 14.5);
+
+        // The region survives undo/replay like any other project setting
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_project(project_id).unwrap().price_region, None);
+    }
+
+    #[test]
+    fn test_import_price_table() {
+        let mut calculator = CostCalculator::with_defaults();
+        let mut table = PriceTable::new();
+        table.set_material_price(MaterialType::Drywall, UnitPrice::new(MaterialType::Drywall, PricingUnit::PerComponent, 14.5));
+        calculator.import_price_table(table.clone());
+        assert_eq!(calculator.price_table().material_price_value(&MaterialType::Drywall), 14.5);
+        assert!(calculator.price_set_regions().is_empty());
+
+        // A region tag routes the table to that region's set
+        table.region = Some("us-tx".to_string());
+        table.set_material_price(MaterialType::Drywall, UnitPrice::new(MaterialType::Drywall, PricingUnit::PerComponent, 16.0));
+        calculator.import_price_table(table);
+        assert_eq!(calculator.price_set("us-tx").unwrap().material_price_value(&MaterialType::Drywall), 16.0);
+        assert_eq!(calculator.price_table().material_price_value(&MaterialType::Drywall), 14.5);
+    }
+}
@@ -0,0 +1,221 @@
+//! Project Estimates
+//! Rolls level estimates up into one section per structure so a detached
+//! garage or ADU is priced separately from the primary dwelling
+
+use std::borrow::Cow;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::costing::{CostCategory, CostEstimate};
+use crate::domain::{BuildingId, BuildingOccupancy, LevelId, ProjectId};
+use crate::store::Store;
+use super::calculator::{CostCalculator, CostInput};
+
+/// Categories left out of structures without finished interiors
+const FINISH_CATEGORIES: [CostCategory; 4] = [
+    CostCategory::Insulation,
+    CostCategory::Drywall,
+    CostCategory::Flooring,
+    CostCategory::Painting,
+];
+
+/// Estimate for one structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstimateSection {
+    pub building_id: BuildingId,
+    pub building_name: String,
+    pub occupancy: BuildingOccupancy,
+    pub levels: Vec<CostEstimate>,
+    pub total: f64,
+    /// Heated area of the structure, excluding garages and porches
+    pub conditioned_area: f64,
+}
+
+/// Estimate for every structure in a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectEstimate {
+    pub project_id: ProjectId,
+    pub sections: Vec<EstimateSection>,
+    /// Sum of dwelling and ADU sections
+    pub habitable_total: f64,
+    /// Sum of every section other than the primary dwelling
+    pub accessory_total: f64,
+    pub grand_total: f64,
+    pub conditioned_area: f64,
+    /// Grand total over heated area, the figure appraisals compare
+    pub cost_per_conditioned_sqft: f64,
+}
+
+impl CostCalculator {
+    /// Calculator for a project: priced with its regional price set when
+    /// one is loaded for its region, and carrying its markups
+    pub fn for_project(&self, store: &Store, project_id: ProjectId) -> Cow<'_, CostCalculator> {
+        let Some(project) = store.get_project(project_id) else {
+            return Cow::Borrowed(self);
+        };
+        let price_set = project.price_region.as_deref().and_then(|region| self.price_set(region));
+        if price_set.is_none() && project.markups == self.markups() {
+            return Cow::Borrowed(self);
+        }
+        let mut calculator = CostCalculator::new(price_set.unwrap_or(self.price_table()).clone());
+        calculator.set_markups(project.markups);
+        Cow::Owned(calculator)
+    }
+
+    /// Estimate a level, priced for its project. Stale framing is
+    /// regenerated first, since headers are priced from framing layouts.
+    pub fn estimate_level(&self, store: &mut Store, level_id: LevelId) -> Result<CostEstimate> {
+        store.regenerate_stale_framing()?;
+        let input = CostInput::from_level(store, level_id)?;
+        Ok(match store.level_project_id(level_id) {
+            Some(project_id) => self.for_project(store, project_id).calculate(&input),
+            None => self.calculate(&input),
+        })
+    }
+
+    /// Estimate each building in a project as its own section. Foundations
+    /// are priced on the lowest level; roofs on the top level and on any
+    /// lower level with a modeled roof.
+    pub fn calculate_project(&self, store: &Store, project_id: ProjectId) -> Result<ProjectEstimate> {
+        let project = store.get_project(project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+        let calculator = self.for_project(store, project_id);
+
+        let mut sections = Vec::new();
+        for building_id in &project.building_ids {
+            let building = store.get_building(*building_id)
+                .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+            let mut levels = store.get_building_levels(*building_id);
+            levels.sort_by(|a, b| a.elevation.total_cmp(&b.elevation));
+            let last = levels.len().saturating_sub(1);
+
+            let mut estimates = Vec::with_capacity(levels.len());
+            for (index, level) in levels.iter().enumerate() {
+                let mut input = CostInput::from_level(store, level.id)?;
+                if index > 0 {
+                    input.foundation_sqft = 0.0;
+                    input.foundation = None;
+                }
+                if index < last && store.get_level_roof(level.id).is_none() {
+                    input.roof_sqft = 0.0;
+                }
+
+                let mut estimate = calculator.calculate(&input);
+                if !building.occupancy.is_habitable() {
+                    estimate.line_items.retain(|item| !FINISH_CATEGORIES.contains(&item.category));
+                    estimate.recalculate_totals();
+                }
+                estimates.push(estimate);
+            }
+
+            let conditioned_area = store.get_building_stats(*building_id)
+                .map(|stats| stats.conditioned_area)
+                .unwrap_or(0.0);
+            sections.push(EstimateSection {
+                building_id: building.id,
+                building_name: building.name.clone(),
+                occupancy: building.occupancy,
+                total: estimates.iter().map(|e| e.grand_total).sum(),
+                levels: estimates,
+                conditioned_area,
+            });
+        }
+
+        let sum = |pred: fn(&BuildingOccupancy) -> bool| -> f64 {
+            sections.iter().filter(|s| pred(&s.occupancy)).map(|s| s.total).sum()
+        };
+        let habitable_total = sum(BuildingOccupancy::is_habitable);
+        let accessory_total = sum(BuildingOccupancy::is_accessory);
+        let grand_total: f64 = sections.iter().map(|s| s.total).sum();
+        let conditioned_area: f64 = sections.iter().map(|s| s.conditioned_area).sum();
+        let cost_per_conditioned_sqft = if conditioned_area > 0.0 {
+            grand_total / conditioned_area
+        } else {
+            0.0
+        };
+
+        Ok(ProjectEstimate {
+            project_id,
+            sections,
+            habitable_total,
+            accessory_total,
+            grand_total,
+            conditioned_area,
+            cost_per_conditioned_sqft,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::costing::{CsiDivision, Markups, MaterialType, PricingUnit, UnitPrice};
+    use crate::domain::{CodeRegion, Polygon2, RoomType, UnitSystem};
+
+    #[test]
+    fn test_sections_per_structure() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+
+        let house = store.add_building(project_id, "House").unwrap();
+        let l1 = store.add_level(house, "First", 0.0, 9.0).unwrap();
+        store.set_level_footprint(l1, Polygon2::rectangle(40.0, 30.0)).unwrap();
+        store.create_room(l1, RoomType::LivingRoom, "Living", Polygon2::rectangle(20.0, 15.0)).unwrap();
+
+        let garage = store.add_building(project_id, "Garage").unwrap();
+        store.set_building_occupancy(garage, BuildingOccupancy::Garage).unwrap();
+        let g1 = store.add_level(garage, "Slab", 0.0, 10.0).unwrap();
+        store.set_level_footprint(g1, Polygon2::rectangle(24.0, 24.0)).unwrap();
+        store.create_room(g1, RoomType::Garage, "Garage", Polygon2::rectangle(24.0, 24.0)).unwrap();
+
+        let estimate = CostCalculator::with_defaults().calculate_project(&store, project_id).unwrap();
+        assert_eq!(estimate.sections.len(), 2);
+
+        let garage_section = estimate.sections.iter().find(|s| s.building_id == garage).unwrap();
+        assert_eq!(garage_section.occupancy, BuildingOccupancy::Garage);
+        assert!(garage_section.levels[0].items_by_category(CostCategory::Drywall).is_empty());
+        assert!(!garage_section.levels[0].items_by_category(CostCategory::Foundation).is_empty());
+
+        let house_section = estimate.sections.iter().find(|s| s.building_id == house).unwrap();
+        assert!(!house_section.levels[0].items_by_category(CostCategory::Drywall).is_empty());
+
+        assert!((estimate.grand_total - (house_section.total + garage_section.total)).abs() < 1e-6);
+        assert!((estimate.accessory_total - garage_section.total).abs() < 1e-6);
+
+        // Only the house counts toward heated area
+        assert!((garage_section.conditioned_area).abs() < 1e-10);
+        assert!((estimate.conditioned_area - 1200.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_project_markups() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building = store.add_building(project_id, "House").unwrap();
+        let level = store.add_level(building, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level, Polygon2::rectangle(40.0, 30.0)).unwrap();
+
+        let mut calculator = CostCalculator::with_defaults();
+        calculator.set_material_price(
+            MaterialType::ConcreteMix,
+            UnitPrice::new(MaterialType::ConcreteMix, PricingUnit::PerCubicYard, 150.0),
+        );
+        store.set_project_markups(project_id, Markups { overhead_percent: 10.0, ..Default::default() }).unwrap();
+
+        let estimate = calculator.calculate_project(&store, project_id).unwrap();
+        let level_estimate = &estimate.sections[0].levels[0];
+        assert!(level_estimate.direct_total > 0.0);
+        assert!((level_estimate.grand_total - level_estimate.direct_total * 1.1).abs() < 1e-6);
+        assert_eq!(level_estimate.divisions[0].division, CsiDivision::Concrete);
+
+        // A single level is priced the same way
+        let single = calculator.estimate_level(&mut store, level).unwrap();
+        assert!((single.grand_total - level_estimate.grand_total).abs() < 1e-6);
+        assert!(calculator.estimate_level(&mut store, LevelId::new()).is_err());
+    }
+}
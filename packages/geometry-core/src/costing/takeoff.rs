@@ -0,0 +1,283 @@
+//! Quantity Takeoff
+//! Raw quantities for a level, independent of the price table, for
+//! estimators who price from their own sources. Area and volume figures
+//! come from the same inputs as the cost estimate; stud and plate counts
+//! come from generated wall framing, so unframed walls are only counted.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::costing::CostCategory;
+use crate::domain::{FramingMemberType, LevelId, LumberSize};
+use crate::store::Store;
+use super::calculator::{approximate_foundation, CostInput};
+use super::prices::csv_field;
+
+/// Square feet in a 4' x 8' sheet of sheathing or drywall
+const SHEET_SQFT: f64 = 32.0;
+
+/// Unit a takeoff quantity is counted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TakeoffUnit {
+    Each,
+    /// 4' x 8' sheets
+    Sheet,
+    LinearFoot,
+    SquareFoot,
+    /// Roofing squares of 100 sqft
+    Square,
+    CubicYard,
+    Pound,
+}
+
+impl TakeoffUnit {
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            TakeoffUnit::Each => "ea",
+            TakeoffUnit::Sheet => "sheets",
+            TakeoffUnit::LinearFoot => "lf",
+            TakeoffUnit::SquareFoot => "sf",
+            TakeoffUnit::Square => "sq",
+            TakeoffUnit::CubicYard => "cy",
+            TakeoffUnit::Pound => "lb",
+        }
+    }
+}
+
+/// One quantity in the takeoff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TakeoffItem {
+    pub category: CostCategory,
+    pub description: String,
+    pub quantity: f64,
+    pub unit: TakeoffUnit,
+}
+
+/// Studs of one size cut to one length
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudCount {
+    pub lumber_size: LumberSize,
+    /// Cut length (inches, to the nearest 1/8")
+    pub length: f64,
+    pub count: u32,
+}
+
+/// Windows or doors of one type and size
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpeningScheduleEntry {
+    /// "window", "exterior_door", "interior_door" or "garage_door"
+    pub opening_type: String,
+    pub catalog_code: Option<String>,
+    pub width: f64,
+    pub height: f64,
+    pub count: u32,
+}
+
+/// Quantities for one level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantityTakeoff {
+    pub level_id: LevelId,
+    /// Studs from generated framing by size and cut length
+    pub studs: Vec<StudCount>,
+    pub items: Vec<TakeoffItem>,
+    /// Door and window schedule
+    pub openings: Vec<OpeningScheduleEntry>,
+    /// Walls without a framing layout, left out of stud and plate counts
+    pub unframed_walls: u32,
+}
+
+impl QuantityTakeoff {
+    /// Take off quantities for a level from store data
+    pub fn from_level(store: &Store, level_id: LevelId) -> Result<QuantityTakeoff> {
+        let input = CostInput::from_level(store, level_id)?;
+        let mut items = Vec::new();
+        let mut item = |category, description: String, quantity: f64, unit| {
+            items.push(TakeoffItem { category, description, quantity, unit });
+        };
+
+        // Foundation
+        let q = input.foundation.unwrap_or_else(|| {
+            approximate_foundation(input.foundation_sqft, input.exterior_wall_linear_ft)
+        });
+        let pours = [("footings", q.footing_cy), ("stem walls", q.stem_wall_cy), ("slab", q.slab_cy)];
+        for (part, cubic_yards) in pours.into_iter().filter(|(_, cy)| *cy > 0.0) {
+            item(CostCategory::Foundation, format!("Concrete - {}", part), cubic_yards, TakeoffUnit::CubicYard);
+        }
+        item(CostCategory::Foundation, "Gravel base".to_string(), q.gravel_cy, TakeoffUnit::CubicYard);
+        item(CostCategory::Foundation, "Rebar".to_string(), q.rebar_lbs, TakeoffUnit::Pound);
+        item(CostCategory::Foundation, "Forms".to_string(), q.form_lf, TakeoffUnit::LinearFoot);
+        item(CostCategory::Foundation, "Vapor barrier".to_string(), q.vapor_barrier_sqft, TakeoffUnit::SquareFoot);
+
+        // Wall framing members
+        let walls = store.get_level_walls(level_id);
+        let layouts: Vec<_> = walls.iter().filter_map(|w| store.get_wall_framing_layout(w.id)).collect();
+        let unframed_walls = (walls.len() - layouts.len()) as u32;
+        let mut studs: Vec<StudCount> = Vec::new();
+        let mut plates: Vec<(LumberSize, f64)> = Vec::new();
+        for member in layouts.iter().flat_map(|layout| &layout.members) {
+            if member.member_type.is_vertical() {
+                let length = (member.length * 8.0).round() / 8.0;
+                match studs.iter_mut().find(|s| s.lumber_size == member.lumber_size && s.length == length) {
+                    Some(entry) => entry.count += 1,
+                    None => studs.push(StudCount { lumber_size: member.lumber_size, length, count: 1 }),
+                }
+            } else if matches!(
+                member.member_type,
+                FramingMemberType::BottomPlate | FramingMemberType::TopPlate | FramingMemberType::DoubleTopPlate
+            ) {
+                match plates.iter_mut().find(|(size, _)| *size == member.lumber_size) {
+                    Some((_, inches)) => *inches += member.length,
+                    None => plates.push((member.lumber_size, member.length)),
+                }
+            }
+        }
+        studs.sort_by(|a, b| {
+            a.lumber_size.nominal_name().cmp(&b.lumber_size.nominal_name()).then(b.length.total_cmp(&a.length))
+        });
+        plates.sort_by_key(|(size, _)| size.nominal_name());
+        for (size, inches) in plates {
+            item(CostCategory::Framing, format!("{} plates", size.nominal_name()), inches / 12.0, TakeoffUnit::LinearFoot);
+        }
+        for header in &input.headers {
+            item(CostCategory::Framing, format!("{} headers", header.size.description()), header.count as f64, TakeoffUnit::Each);
+        }
+        for post in &input.posts {
+            item(CostCategory::Framing, format!("{} posts", post.profile.description()), post.count as f64, TakeoffUnit::Each);
+        }
+        item(CostCategory::Framing, "Wall sheathing".to_string(), input.exterior_wall_sqft, TakeoffUnit::SquareFoot);
+        item(CostCategory::Framing, "Wall sheathing".to_string(), sheets(input.exterior_wall_sqft), TakeoffUnit::Sheet);
+
+        // Roof and exterior
+        item(CostCategory::Roofing, "Roofing".to_string(), input.roof_sqft, TakeoffUnit::SquareFoot);
+        item(CostCategory::Roofing, "Roofing".to_string(), input.roof_sqft / 100.0, TakeoffUnit::Square);
+        if let Some(topology) = &input.roof_topology {
+            let edges = [
+                ("Eaves", topology.eave),
+                ("Rakes", topology.rake),
+                ("Ridge", topology.ridge),
+                ("Hips", topology.hip),
+                ("Valleys", topology.valley),
+            ];
+            for (edge, length) in edges.into_iter().filter(|(_, lf)| *lf > 0.0) {
+                item(CostCategory::Roofing, edge.to_string(), length, TakeoffUnit::LinearFoot);
+            }
+            item(CostCategory::Roofing, "Gutters".to_string(), topology.eave, TakeoffUnit::LinearFoot);
+            item(CostCategory::Roofing, "Downspouts".to_string(), topology.downspouts() as f64, TakeoffUnit::Each);
+        }
+        item(CostCategory::Exterior, "Exterior wall finish".to_string(), input.exterior_wall_sqft, TakeoffUnit::SquareFoot);
+
+        // Drywall on room walls, and ceilings not covered by a modeled ceiling
+        let drywall_sqft: f64 = input.rooms.iter()
+            .map(|r| r.wall_sqft + if r.ceiling_modeled { 0.0 } else { r.floor_sqft })
+            .sum();
+        item(CostCategory::Drywall, "Drywall".to_string(), drywall_sqft, TakeoffUnit::SquareFoot);
+        item(CostCategory::Drywall, "Drywall".to_string(), sheets(drywall_sqft), TakeoffUnit::Sheet);
+        item(CostCategory::Flooring, "Floor area".to_string(), input.total_floor_area, TakeoffUnit::SquareFoot);
+
+        let mut openings: Vec<OpeningScheduleEntry> = input.openings.into_iter()
+            .map(|o| OpeningScheduleEntry {
+                opening_type: o.opening_type,
+                catalog_code: o.catalog_code,
+                width: o.width,
+                height: o.height,
+                count: o.count,
+            })
+            .collect();
+        openings.sort_by(|a, b| {
+            (&a.opening_type, &a.catalog_code)
+                .cmp(&(&b.opening_type, &b.catalog_code))
+                .then(a.width.total_cmp(&b.width))
+                .then(a.height.total_cmp(&b.height))
+        });
+
+        Ok(QuantityTakeoff { level_id, studs, items, openings, unframed_walls })
+    }
+
+    /// The takeoff as CSV rows of category, description, quantity and unit:
+    /// studs, then the other items, then the door and window schedule
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("category,description,quantity,unit\n");
+        let mut row = |category: CostCategory, description: String, quantity: f64, unit: TakeoffUnit| {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                csv_field(category.display_name()),
+                csv_field(&description),
+                (quantity * 100.0).round() / 100.0,
+                unit.abbreviation()
+            ));
+        };
+        for stud in &self.studs {
+            let description = format!("{} studs @ {}\"", stud.lumber_size.nominal_name(), stud.length);
+            row(CostCategory::Framing, description, stud.count as f64, TakeoffUnit::Each);
+        }
+        for item in &self.items {
+            row(item.category, item.description.clone(), item.quantity, item.unit);
+        }
+        for opening in &self.openings {
+            let category = if opening.opening_type == "window" { CostCategory::Windows } else { CostCategory::Doors };
+            let mut description = format!("{} {}x{}", opening.opening_type.replace('_', " "), opening.width, opening.height);
+            if let Some(code) = &opening.catalog_code {
+                description.push_str(&format!(" ({})", code));
+            }
+            row(category, description, opening.count as f64, TakeoffUnit::Each);
+        }
+        csv
+    }
+}
+
+/// Whole 4' x 8' sheets to cover an area
+fn sheets(sqft: f64) -> f64 {
+    (sqft / SHEET_SQFT).ceil()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CodeRegion, OpeningType, Point2, Polygon2, RoomType, UnitSystem, WallLayer};
+
+    #[test]
+    fn test_quantity_takeoff() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(30.0, 20.0)).unwrap();
+        store.create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(20.0, 12.0)).unwrap();
+        let assembly_id = store.create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()]).unwrap();
+        let framed = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        store
+            .create_wall(level_id, assembly_id, Point2::new(20.0, 0.0), Point2::new(20.0, 12.0), 9.0)
+            .unwrap();
+        store.add_opening(framed, OpeningType::Window, 0.5, 3.0, 4.0, 3.0).unwrap();
+        store.generate_wall_framing(framed).unwrap();
+
+        let takeoff = QuantityTakeoff::from_level(&store, level_id).unwrap();
+        assert_eq!(takeoff.unframed_walls, 1);
+        assert!(!takeoff.studs.is_empty());
+        assert!(takeoff.studs.iter().all(|s| s.lumber_size == LumberSize::TwoBySix));
+        let find = |description: &str, unit| {
+            takeoff.items.iter().find(|i| i.description == description && i.unit == unit).unwrap().quantity
+        };
+        // Bottom plus double top plate along the 20' wall
+        assert!((find("2x6 plates", TakeoffUnit::LinearFoot) - 60.0).abs() < 1.0);
+        // 100' of exterior wall at 9' is 900 sqft, 29 sheets
+        assert_eq!(find("Wall sheathing", TakeoffUnit::Sheet), 29.0);
+        // Kitchen walls 64' x 9' plus a 240 sqft ceiling
+        assert_eq!(find("Drywall", TakeoffUnit::SquareFoot), 816.0);
+        assert_eq!(find("Drywall", TakeoffUnit::Sheet), 26.0);
+        assert_eq!(takeoff.openings.len(), 1);
+        assert_eq!(takeoff.openings[0].opening_type, "window");
+
+        let csv = takeoff.to_csv();
+        assert!(csv.starts_with("category,description,quantity,unit\n"));
+        assert!(csv.contains("Drywall,Drywall,26,sheets\n"));
+        assert!(csv.contains("Windows,window 3x4,1,ea\n"));
+
+        assert!(QuantityTakeoff::from_level(&store, LevelId::new()).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+// Access control - users, their API keys, and who may read or edit which
+// projects. Kept apart from the event logs: granting access isn't an edit
+// to the model, and undoing an edit must never revoke someone's access.
+// Keys are held only as digests the caller computes from the presented key,
+// so a copy of the store doesn't give away working keys.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ids::{ProjectId, UserId};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub id: UserId,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// Digest of the key, as hashed by the caller
+    pub digest: String,
+    pub user_id: UserId,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a user may do in a project; each role can do all the ones before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectRole {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+/// Why a request was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessError {
+    /// No key, or one that isn't recognized
+    Unauthenticated,
+    /// A known user without the role the request needs
+    Forbidden,
+}
+
+impl AccessError {
+    pub fn http_status(&self) -> u16 {
+        match self {
+            AccessError::Unauthenticated => 401,
+            AccessError::Forbidden => 403,
+        }
+    }
+}
+
+impl std::fmt::Display for AccessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccessError::Unauthenticated => write!(f, "Missing or unknown API key"),
+            AccessError::Forbidden => write!(f, "Not permitted in this project"),
+        }
+    }
+}
+
+impl std::error::Error for AccessError {}
+
+/// Users, keys and project grants
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessControl {
+    users: HashMap<UserId, User>,
+    /// Keyed by digest
+    keys: HashMap<String, ApiKey>,
+    grants: HashMap<ProjectId, HashMap<UserId, ProjectRole>>,
+}
+
+impl AccessControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_user(&mut self, name: impl Into<String>) -> UserId {
+        let user = User { id: UserId::new(), name: name.into() };
+        let user_id = user.id;
+        self.users.insert(user_id, user);
+        user_id
+    }
+
+    pub fn get_user(&self, user_id: UserId) -> Option<&User> {
+        self.users.get(&user_id)
+    }
+
+    /// Remove a user with their keys and grants. Fails while the user is
+    /// the last owner of a project; name another owner first.
+    pub fn remove_user(&mut self, user_id: UserId) -> Result<()> {
+        if !self.users.contains_key(&user_id) {
+            return Err(anyhow!("User not found: {:?}", user_id));
+        }
+        let owned = self.grants
+            .iter()
+            .find(|(_, grants)| is_last_owner(grants, user_id))
+            .map(|(project_id, _)| project_id);
+        if let Some(project_id) = owned {
+            return Err(anyhow!("User is the last owner of project: {:?}", project_id));
+        }
+        self.users.remove(&user_id);
+        self.keys.retain(|_, key| key.user_id != user_id);
+        for grants in self.grants.values_mut() {
+            grants.remove(&user_id);
+        }
+        Ok(())
+    }
+
+    /// Register a key for a user by its digest
+    pub fn add_key(&mut self, user_id: UserId, digest: impl Into<String>, label: impl Into<String>) -> Result<()> {
+        let digest = digest.into();
+        if !self.users.contains_key(&user_id) {
+            return Err(anyhow!("User not found: {:?}", user_id));
+        }
+        if digest.is_empty() {
+            return Err(anyhow!("Key digest must not be empty"));
+        }
+        if self.keys.contains_key(&digest) {
+            return Err(anyhow!("Key is already registered"));
+        }
+
+        let key = ApiKey { digest: digest.clone(), user_id, label: label.into(), created_at: Utc::now() };
+        self.keys.insert(digest, key);
+        Ok(())
+    }
+
+    pub fn revoke_key(&mut self, digest: &str) -> Result<()> {
+        self.keys.remove(digest)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("Key not found"))
+    }
+
+    /// A user's keys, oldest first
+    pub fn user_keys(&self, user_id: UserId) -> Vec<&ApiKey> {
+        let mut keys: Vec<&ApiKey> = self.keys.values().filter(|k| k.user_id == user_id).collect();
+        keys.sort_by_key(|k| k.created_at);
+        keys
+    }
+
+    /// The user a presented key's digest belongs to
+    pub fn authenticate(&self, digest: Option<&str>) -> Result<UserId, AccessError> {
+        digest
+            .and_then(|d| self.keys.get(d))
+            .map(|key| key.user_id)
+            .ok_or(AccessError::Unauthenticated)
+    }
+
+    /// Give a user a role in a project, replacing any role they had
+    pub fn grant(&mut self, project_id: ProjectId, user_id: UserId, role: ProjectRole) -> Result<()> {
+        if !self.users.contains_key(&user_id) {
+            return Err(anyhow!("User not found: {:?}", user_id));
+        }
+        self.grants.entry(project_id).or_default().insert(user_id, role);
+        Ok(())
+    }
+
+    /// Take a user's role in a project away. A project's last owner can't
+    /// be removed, or nobody could manage it.
+    pub fn revoke(&mut self, project_id: ProjectId, user_id: UserId) -> Result<()> {
+        let grants = self.grants.get_mut(&project_id)
+            .ok_or_else(|| anyhow!("No access granted in project: {:?}", project_id))?;
+        if !grants.contains_key(&user_id) {
+            return Err(anyhow!("User has no access to project: {:?}", user_id));
+        }
+        if is_last_owner(grants, user_id) {
+            return Err(anyhow!("Can't remove the project's last owner"));
+        }
+        grants.remove(&user_id);
+        Ok(())
+    }
+
+    pub fn role(&self, project_id: ProjectId, user_id: UserId) -> Option<ProjectRole> {
+        self.grants.get(&project_id)?.get(&user_id).copied()
+    }
+
+    /// Whether a user holds at least `needed` in a project
+    pub fn check(&self, user_id: UserId, project_id: ProjectId, needed: ProjectRole) -> Result<(), AccessError> {
+        match self.role(project_id, user_id) {
+            Some(role) if role >= needed => Ok(()),
+            _ => Err(AccessError::Forbidden),
+        }
+    }
+
+    /// Projects a user has any role in
+    pub fn user_projects(&self, user_id: UserId) -> Vec<ProjectId> {
+        self.grants
+            .iter()
+            .filter(|(_, grants)| grants.contains_key(&user_id))
+            .map(|(project_id, _)| *project_id)
+            .collect()
+    }
+
+    /// Drop every grant in a deleted project
+    pub fn remove_project(&mut self, project_id: ProjectId) {
+        self.grants.remove(&project_id);
+    }
+}
+
+/// Whether a user is the only owner among a project's grants
+fn is_last_owner(grants: &HashMap<UserId, ProjectRole>, user_id: UserId) -> bool {
+    grants.get(&user_id) == Some(&ProjectRole::Owner)
+        && grants.values().filter(|r| **r == ProjectRole::Owner).count() == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_and_roles() {
+        let mut access = AccessControl::new();
+        let alice = access.add_user("Alice");
+        let bob = access.add_user("Bob");
+        let project_id = ProjectId::new();
+
+        access.add_key(alice, "digest-a", "laptop").unwrap();
+        assert!(access.add_key(bob, "digest-a", "copy").is_err());
+        assert!(access.add_key(UserId::new(), "digest-x", "nobody").is_err());
+        assert_eq!(access.authenticate(Some("digest-a")), Ok(alice));
+        assert_eq!(access.authenticate(Some("digest-b")), Err(AccessError::Unauthenticated));
+        assert_eq!(access.authenticate(None).unwrap_err().http_status(), 401);
+
+        access.grant(project_id, alice, ProjectRole::Owner).unwrap();
+        access.grant(project_id, bob, ProjectRole::Viewer).unwrap();
+        assert!(access.check(bob, project_id, ProjectRole::Viewer).is_ok());
+        assert_eq!(access.check(bob, project_id, ProjectRole::Editor), Err(AccessError::Forbidden));
+        assert!(access.check(alice, project_id, ProjectRole::Editor).is_ok());
+        assert!(access.check(alice, ProjectId::new(), ProjectRole::Viewer).is_err());
+        assert_eq!(access.user_projects(bob), vec![project_id]);
+
+        // The last owner stays until another is named
+        assert!(access.revoke(project_id, alice).is_err());
+        access.grant(project_id, bob, ProjectRole::Owner).unwrap();
+        access.revoke(project_id, alice).unwrap();
+        assert_eq!(access.role(project_id, alice), None);
+
+        access.revoke_key("digest-a").unwrap();
+        assert!(access.authenticate(Some("digest-a")).is_err());
+
+        // Nor can the last owner be removed as a user
+        access.add_key(bob, "digest-b", "ci").unwrap();
+        assert!(access.remove_user(bob).is_err());
+        assert!(access.authenticate(Some("digest-b")).is_ok());
+        assert_eq!(access.role(project_id, bob), Some(ProjectRole::Owner));
+        access.grant(project_id, alice, ProjectRole::Owner).unwrap();
+        access.remove_user(bob).unwrap();
+        assert!(access.authenticate(Some("digest-b")).is_err());
+        assert!(access.user_projects(bob).is_empty());
+    }
+}
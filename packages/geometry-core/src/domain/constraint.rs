@@ -0,0 +1,69 @@
+// Named constraints - design intent stored with a project
+// Declared once and checked after every edit; the solver can move walls and
+// resize rooms to bring broken constraints back into line
+
+use serde::{Deserialize, Serialize};
+use super::ids::{ConstraintId, ProjectId, RoomId, WallId};
+
+/// What a constraint requires. Lengths in feet, areas in square feet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConstraintKind {
+    /// Room floor area at least `min_area`
+    RoomMinArea { room_id: RoomId, min_area: f64 },
+    /// Longer side of the room's bounding box at most `max_ratio` times
+    /// the shorter
+    RoomAspectRatio { room_id: RoomId, max_ratio: f64 },
+    /// Wall centerline on the named axis of its building's grid
+    WallOnGridAxis { wall_id: WallId, axis: String },
+    /// Parallel walls whose centerlines are `distance` apart
+    WallDistance { wall_a: WallId, wall_b: WallId, distance: f64 },
+}
+
+impl ConstraintKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RoomMinArea { .. } => "room_min_area",
+            Self::RoomAspectRatio { .. } => "room_aspect_ratio",
+            Self::WallOnGridAxis { .. } => "wall_on_grid_axis",
+            Self::WallDistance { .. } => "wall_distance",
+        }
+    }
+
+    /// Rooms the constraint refers to
+    pub fn room_ids(&self) -> Vec<RoomId> {
+        match self {
+            Self::RoomMinArea { room_id, .. } | Self::RoomAspectRatio { room_id, .. } => vec![*room_id],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Walls the constraint refers to
+    pub fn wall_ids(&self) -> Vec<WallId> {
+        match self {
+            Self::WallOnGridAxis { wall_id, .. } => vec![*wall_id],
+            Self::WallDistance { wall_a, wall_b, .. } => vec![*wall_a, *wall_b],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A named constraint saved in a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Constraint {
+    pub id: ConstraintId,
+    pub project_id: ProjectId,
+    pub name: String,
+    pub kind: ConstraintKind,
+}
+
+impl Constraint {
+    pub fn new(project_id: ProjectId, name: impl Into<String>, kind: ConstraintKind) -> Self {
+        Self {
+            id: ConstraintId::new(),
+            project_id,
+            name: name.into(),
+            kind,
+        }
+    }
+}
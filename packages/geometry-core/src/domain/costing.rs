@@ -67,10 +67,11 @@ define_cost_id!(CostEstimateId, "est");
 // ============================================================================
 
 /// Unit of measurement for pricing items
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum PricingUnit {
     /// Per individual component (windows, doors, trusses, fixtures)
+    #[default]
     PerComponent,
     /// Per square foot (roofing, siding, flooring)
     PerSquareFoot,
@@ -88,12 +89,6 @@ pub enum PricingUnit {
     Lump,
 }
 
-impl Default for PricingUnit {
-    fn default() -> Self {
-        PricingUnit::PerComponent
-    }
-}
-
 impl PricingUnit {
     /// Get display name for the unit
     pub fn display_name(&self) -> &'static str {
@@ -129,7 +124,7 @@ impl PricingUnit {
 // ============================================================================
 
 /// Major cost categories for construction budgeting
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum CostCategory {
     SiteWork,
@@ -150,15 +145,10 @@ pub enum CostCategory {
     Fixtures,
     Appliances,
     Landscaping,
+    #[default]
     Contingency,
 }
 
-impl Default for CostCategory {
-    fn default() -> Self {
-        CostCategory::Contingency
-    }
-}
-
 impl CostCategory {
     /// Get display name for the category
     pub fn display_name(&self) -> &'static str {
@@ -211,16 +201,107 @@ impl CostCategory {
     }
 }
 
+// ============================================================================
+// CSI Divisions
+// ============================================================================
+
+/// CSI MasterFormat divisions estimates are rolled up under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CsiDivision {
+    #[default]
+    GeneralRequirements,
+    Concrete,
+    Masonry,
+    WoodPlasticsComposites,
+    ThermalMoistureProtection,
+    Openings,
+    Finishes,
+    Equipment,
+    Furnishings,
+    Plumbing,
+    HVAC,
+    Electrical,
+    Earthwork,
+    ExteriorImprovements,
+}
+
+impl CsiDivision {
+    /// Two-digit MasterFormat division number
+    pub fn number(&self) -> &'static str {
+        match self {
+            CsiDivision::GeneralRequirements => "01",
+            CsiDivision::Concrete => "03",
+            CsiDivision::Masonry => "04",
+            CsiDivision::WoodPlasticsComposites => "06",
+            CsiDivision::ThermalMoistureProtection => "07",
+            CsiDivision::Openings => "08",
+            CsiDivision::Finishes => "09",
+            CsiDivision::Equipment => "11",
+            CsiDivision::Furnishings => "12",
+            CsiDivision::Plumbing => "22",
+            CsiDivision::HVAC => "23",
+            CsiDivision::Electrical => "26",
+            CsiDivision::Earthwork => "31",
+            CsiDivision::ExteriorImprovements => "32",
+        }
+    }
+
+    /// Get display name for the division
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CsiDivision::GeneralRequirements => "General Requirements",
+            CsiDivision::Concrete => "Concrete",
+            CsiDivision::Masonry => "Masonry",
+            CsiDivision::WoodPlasticsComposites => "Wood, Plastics, and Composites",
+            CsiDivision::ThermalMoistureProtection => "Thermal and Moisture Protection",
+            CsiDivision::Openings => "Openings",
+            CsiDivision::Finishes => "Finishes",
+            CsiDivision::Equipment => "Equipment",
+            CsiDivision::Furnishings => "Furnishings",
+            CsiDivision::Plumbing => "Plumbing",
+            CsiDivision::HVAC => "Heating, Ventilating, and Air Conditioning",
+            CsiDivision::Electrical => "Electrical",
+            CsiDivision::Earthwork => "Earthwork",
+            CsiDivision::ExteriorImprovements => "Exterior Improvements",
+        }
+    }
+}
+
+impl CostCategory {
+    /// Division a category's items fall under unless their material says otherwise
+    pub fn csi_division(&self) -> CsiDivision {
+        match self {
+            CostCategory::SiteWork => CsiDivision::Earthwork,
+            CostCategory::Foundation => CsiDivision::Concrete,
+            CostCategory::Framing | CostCategory::Trim => CsiDivision::WoodPlasticsComposites,
+            CostCategory::Roofing | CostCategory::Exterior | CostCategory::Insulation => {
+                CsiDivision::ThermalMoistureProtection
+            }
+            CostCategory::Windows | CostCategory::Doors => CsiDivision::Openings,
+            CostCategory::Drywall | CostCategory::Flooring | CostCategory::Painting => CsiDivision::Finishes,
+            CostCategory::Plumbing => CsiDivision::Plumbing,
+            CostCategory::Electrical => CsiDivision::Electrical,
+            CostCategory::HVAC => CsiDivision::HVAC,
+            CostCategory::Fixtures => CsiDivision::Furnishings,
+            CostCategory::Appliances => CsiDivision::Equipment,
+            CostCategory::Landscaping => CsiDivision::ExteriorImprovements,
+            CostCategory::Contingency => CsiDivision::GeneralRequirements,
+        }
+    }
+}
+
 // ============================================================================
 // Labor Types
 // ============================================================================
 
 /// Types of labor for construction activities
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum LaborType {
     // General
     /// Unskilled general labor
+    #[default]
     GeneralLabor,
     /// Skilled tradesperson (general)
     SkilledLabor,
@@ -229,6 +310,12 @@ pub enum LaborType {
     /// Framing carpentry work
     FramingCarpentry,
 
+    // Site work
+    /// Digging a pad down to grade
+    Excavation,
+    /// Placing and compacting fill to raise a pad
+    FillCompaction,
+
     // Concrete specific (detailed breakdown)
     /// Subgrade preparation: grading, compaction, gravel, vapor barrier
     ConcreteSubgradePrep,
@@ -242,6 +329,8 @@ pub enum LaborType {
     // Other trades
     /// Roofing installation
     RoofingInstall,
+    /// Hanging gutters and downspouts
+    GutterInstall,
     /// Siding installation
     SidingInstall,
     /// Drywall hanging and finishing
@@ -260,12 +349,6 @@ pub enum LaborType {
     HVACInstall,
 }
 
-impl Default for LaborType {
-    fn default() -> Self {
-        LaborType::GeneralLabor
-    }
-}
-
 impl LaborType {
     /// Get display name for the labor type
     pub fn display_name(&self) -> &'static str {
@@ -273,11 +356,14 @@ impl LaborType {
             LaborType::GeneralLabor => "General Labor",
             LaborType::SkilledLabor => "Skilled Labor",
             LaborType::FramingCarpentry => "Framing Carpentry",
+            LaborType::Excavation => "Excavation",
+            LaborType::FillCompaction => "Fill & Compaction",
             LaborType::ConcreteSubgradePrep => "Concrete Subgrade Prep",
             LaborType::ConcreteFormInstall => "Concrete Form Install",
             LaborType::ConcreteRebarInstall => "Concrete Rebar Install",
             LaborType::ConcretePlaceFinish => "Concrete Place & Finish",
             LaborType::RoofingInstall => "Roofing Install",
+            LaborType::GutterInstall => "Gutter Install",
             LaborType::SidingInstall => "Siding Install",
             LaborType::DrywallInstall => "Drywall Install",
             LaborType::PaintingLabor => "Painting",
@@ -295,11 +381,14 @@ impl LaborType {
             LaborType::GeneralLabor => CostCategory::SiteWork,
             LaborType::SkilledLabor => CostCategory::Framing,
             LaborType::FramingCarpentry => CostCategory::Framing,
+            LaborType::Excavation => CostCategory::SiteWork,
+            LaborType::FillCompaction => CostCategory::SiteWork,
             LaborType::ConcreteSubgradePrep => CostCategory::Foundation,
             LaborType::ConcreteFormInstall => CostCategory::Foundation,
             LaborType::ConcreteRebarInstall => CostCategory::Foundation,
             LaborType::ConcretePlaceFinish => CostCategory::Foundation,
             LaborType::RoofingInstall => CostCategory::Roofing,
+            LaborType::GutterInstall => CostCategory::Roofing,
             LaborType::SidingInstall => CostCategory::Exterior,
             LaborType::DrywallInstall => CostCategory::Drywall,
             LaborType::PaintingLabor => CostCategory::Painting,
@@ -328,7 +417,7 @@ impl LaborType {
 // ============================================================================
 
 /// Types of construction materials
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum MaterialType {
     // Concrete materials
@@ -343,8 +432,13 @@ pub enum MaterialType {
     /// Base gravel / crushed stone (per cubic yard)
     ConcreteGravel,
 
+    // Site work
+    /// Imported structural fill (per cubic yard)
+    FillSoil,
+
     // Framing lumber
     /// 2x4 dimensional lumber
+    #[default]
     Lumber2x4,
     /// 2x6 dimensional lumber
     Lumber2x6,
@@ -356,6 +450,8 @@ pub enum MaterialType {
     Lumber2x12,
     /// Laminated Veneer Lumber beam
     LVLBeam,
+    /// Structural post or column (per component)
+    StructuralPost,
     /// OSB or plywood sheathing
     Sheathing,
 
@@ -368,6 +464,16 @@ pub enum MaterialType {
     TileRoofing,
     /// Roofing felt / synthetic underlayment (per sqft)
     RoofingUnderlayment,
+    /// Metal drip edge along eaves and rakes (per linear foot)
+    DripEdge,
+    /// Ridge and hip cap shingles (per linear foot)
+    RidgeCap,
+    /// Valley flashing (per linear foot)
+    ValleyFlashing,
+    /// Seamless aluminum gutter (per linear foot)
+    Gutter,
+    /// Downspout with elbows and outlet (per component)
+    Downspout,
 
     // Exterior
     /// Vinyl siding (per sqft)
@@ -380,6 +486,10 @@ pub enum MaterialType {
     Brick,
     /// Natural or manufactured stone (per sqft)
     Stone,
+    /// Deck or porch boards (per linear foot)
+    DeckBoard,
+    /// Deck guard rail with balusters (per linear foot)
+    DeckRailing,
 
     // Openings
     /// Window unit (per component)
@@ -414,8 +524,22 @@ pub enum MaterialType {
     Truss,
     /// Light fixture (per component)
     LightFixture,
+    /// Duplex receptacle with box (per component)
+    Receptacle,
+    /// GFCI receptacle with box (per component)
+    GfciReceptacle,
+    /// Wall switch with box (per component)
+    LightSwitch,
     /// Plumbing fixture - sink, toilet, etc. (per component)
     PlumbingFixture,
+    /// Toilet with wax ring and supply (per component)
+    Toilet,
+    /// Lavatory or kitchen sink with faucet (per component)
+    Sink,
+    /// Bathtub with drain and valve (per component)
+    Bathtub,
+    /// Shower pan or stall with valve (per component)
+    Shower,
     /// Kitchen / bath cabinet (per component)
     Cabinet,
     /// Countertop - granite, quartz, laminate (per sqft)
@@ -424,12 +548,6 @@ pub enum MaterialType {
     Appliance,
 }
 
-impl Default for MaterialType {
-    fn default() -> Self {
-        MaterialType::Lumber2x4
-    }
-}
-
 impl MaterialType {
     /// Get display name for the material
     pub fn display_name(&self) -> &'static str {
@@ -445,16 +563,25 @@ impl MaterialType {
             MaterialType::Lumber2x10 => "2x10 Lumber",
             MaterialType::Lumber2x12 => "2x12 Lumber",
             MaterialType::LVLBeam => "LVL Beam",
+            MaterialType::StructuralPost => "Structural Post",
             MaterialType::Sheathing => "Sheathing",
             MaterialType::AsphaltShingles => "Asphalt Shingles",
             MaterialType::MetalRoofing => "Metal Roofing",
             MaterialType::TileRoofing => "Tile Roofing",
             MaterialType::RoofingUnderlayment => "Roofing Underlayment",
+            MaterialType::DripEdge => "Drip Edge",
+            MaterialType::RidgeCap => "Ridge Cap",
+            MaterialType::ValleyFlashing => "Valley Flashing",
+            MaterialType::Gutter => "Gutter",
+            MaterialType::Downspout => "Downspout",
             MaterialType::VinylSiding => "Vinyl Siding",
             MaterialType::HardieBoard => "Hardie Board",
             MaterialType::Stucco => "Stucco",
             MaterialType::Brick => "Brick",
             MaterialType::Stone => "Stone",
+            MaterialType::FillSoil => "Fill Soil",
+            MaterialType::DeckBoard => "Deck Board",
+            MaterialType::DeckRailing => "Deck Railing",
             MaterialType::WindowUnit => "Window",
             MaterialType::ExteriorDoor => "Exterior Door",
             MaterialType::InteriorDoor => "Interior Door",
@@ -469,7 +596,14 @@ impl MaterialType {
             MaterialType::Trim => "Trim",
             MaterialType::Truss => "Truss",
             MaterialType::LightFixture => "Light Fixture",
+            MaterialType::Receptacle => "Receptacle",
+            MaterialType::GfciReceptacle => "GFCI Receptacle",
+            MaterialType::LightSwitch => "Light Switch",
             MaterialType::PlumbingFixture => "Plumbing Fixture",
+            MaterialType::Toilet => "Toilet",
+            MaterialType::Sink => "Sink",
+            MaterialType::Bathtub => "Bathtub",
+            MaterialType::Shower => "Shower",
             MaterialType::Cabinet => "Cabinet",
             MaterialType::Countertop => "Countertop",
             MaterialType::Appliance => "Appliance",
@@ -480,13 +614,20 @@ impl MaterialType {
     pub fn typical_unit(&self) -> PricingUnit {
         match self {
             // Per cubic yard
-            MaterialType::ConcreteMix | MaterialType::ConcreteGravel => PricingUnit::PerCubicYard,
+            MaterialType::ConcreteMix | MaterialType::ConcreteGravel | MaterialType::FillSoil => PricingUnit::PerCubicYard,
 
             // Per pound
             MaterialType::ConcreteRebar => PricingUnit::PerPound,
 
             // Per linear foot
-            MaterialType::ConcreteForms | MaterialType::Trim => PricingUnit::PerLinearFoot,
+            MaterialType::ConcreteForms
+            | MaterialType::Trim
+            | MaterialType::DeckBoard
+            | MaterialType::DeckRailing
+            | MaterialType::DripEdge
+            | MaterialType::RidgeCap
+            | MaterialType::ValleyFlashing
+            | MaterialType::Gutter => PricingUnit::PerLinearFoot,
 
             // Per board
             MaterialType::Lumber2x4
@@ -501,9 +642,18 @@ impl MaterialType {
             | MaterialType::ExteriorDoor
             | MaterialType::InteriorDoor
             | MaterialType::GarageDoor
+            | MaterialType::Downspout
+            | MaterialType::StructuralPost
             | MaterialType::Truss
             | MaterialType::LightFixture
+            | MaterialType::Receptacle
+            | MaterialType::GfciReceptacle
+            | MaterialType::LightSwitch
             | MaterialType::PlumbingFixture
+            | MaterialType::Toilet
+            | MaterialType::Sink
+            | MaterialType::Bathtub
+            | MaterialType::Shower
             | MaterialType::Cabinet
             | MaterialType::Appliance => PricingUnit::PerComponent,
 
@@ -521,24 +671,34 @@ impl MaterialType {
             | MaterialType::ConcreteVaporBarrier
             | MaterialType::ConcreteGravel => CostCategory::Foundation,
 
+            MaterialType::FillSoil => CostCategory::SiteWork,
+
             MaterialType::Lumber2x4
             | MaterialType::Lumber2x6
             | MaterialType::Lumber2x8
             | MaterialType::Lumber2x10
             | MaterialType::Lumber2x12
             | MaterialType::LVLBeam
+            | MaterialType::StructuralPost
             | MaterialType::Sheathing => CostCategory::Framing,
 
             MaterialType::AsphaltShingles
             | MaterialType::MetalRoofing
             | MaterialType::TileRoofing
-            | MaterialType::RoofingUnderlayment => CostCategory::Roofing,
+            | MaterialType::RoofingUnderlayment
+            | MaterialType::DripEdge
+            | MaterialType::RidgeCap
+            | MaterialType::ValleyFlashing
+            | MaterialType::Gutter
+            | MaterialType::Downspout => CostCategory::Roofing,
 
             MaterialType::VinylSiding
             | MaterialType::HardieBoard
             | MaterialType::Stucco
             | MaterialType::Brick
-            | MaterialType::Stone => CostCategory::Exterior,
+            | MaterialType::Stone
+            | MaterialType::DeckBoard
+            | MaterialType::DeckRailing => CostCategory::Exterior,
 
             MaterialType::WindowUnit => CostCategory::Windows,
             MaterialType::ExteriorDoor | MaterialType::InteriorDoor | MaterialType::GarageDoor => {
@@ -555,8 +715,15 @@ impl MaterialType {
 
             MaterialType::Trim => CostCategory::Trim,
             MaterialType::Truss => CostCategory::Framing,
-            MaterialType::LightFixture => CostCategory::Electrical,
-            MaterialType::PlumbingFixture => CostCategory::Plumbing,
+            MaterialType::LightFixture
+            | MaterialType::Receptacle
+            | MaterialType::GfciReceptacle
+            | MaterialType::LightSwitch => CostCategory::Electrical,
+            MaterialType::PlumbingFixture
+            | MaterialType::Toilet
+            | MaterialType::Sink
+            | MaterialType::Bathtub
+            | MaterialType::Shower => CostCategory::Plumbing,
             MaterialType::Cabinet | MaterialType::Countertop => CostCategory::Fixtures,
             MaterialType::Appliance => CostCategory::Appliances,
         }
@@ -740,6 +907,67 @@ impl CostLineItem {
     pub fn is_material(&self) -> bool {
         self.material_type.is_some()
     }
+
+    /// Division the item is rolled up under: its category's, unless the
+    /// material belongs to another trade (brick in Exterior is masonry)
+    pub fn csi_division(&self) -> CsiDivision {
+        match self.material_type {
+            Some(MaterialType::Brick | MaterialType::Stone) => CsiDivision::Masonry,
+            Some(MaterialType::Stucco) => CsiDivision::Finishes,
+            Some(MaterialType::Cabinet) => CsiDivision::WoodPlasticsComposites,
+            Some(MaterialType::LightFixture) => CsiDivision::Electrical,
+            Some(MaterialType::PlumbingFixture) => CsiDivision::Plumbing,
+            _ => self.category.csi_division(),
+        }
+    }
+}
+
+// ============================================================================
+// Quantity Sources
+// ============================================================================
+
+/// Where a trade's quantities came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuantitySource {
+    /// Taken from modeled geometry (generated framing, a modeled roof or foundation)
+    Modeled,
+    /// Partly modeled, the rest estimated
+    Partial,
+    /// Estimated from areas and lengths with rule-of-thumb factors
+    Heuristic,
+}
+
+// ============================================================================
+// Division Rollups and Markups
+// ============================================================================
+
+/// Line items and subtotals under one CSI division
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DivisionSubtotal {
+    pub division: CsiDivision,
+    pub line_item_ids: Vec<CostLineItemId>,
+    pub material_total: f64,
+    pub labor_total: f64,
+    pub subtotal: f64,
+}
+
+/// Percentages added on top of direct cost. Each compounds on the ones
+/// before it: contingency, then overhead, then profit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Markups {
+    pub contingency_percent: f64,
+    pub overhead_percent: f64,
+    pub profit_percent: f64,
+}
+
+/// One markup applied to an estimate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkupLine {
+    pub name: String,
+    pub percent: f64,
+    pub amount: f64,
 }
 
 // ============================================================================
@@ -755,7 +983,22 @@ pub struct CostEstimate {
     pub subtotals: HashMap<CostCategory, f64>,
     pub labor_total: f64,
     pub material_total: f64,
+    /// Line items grouped by CSI division, in division order
+    #[serde(default)]
+    pub divisions: Vec<DivisionSubtotal>,
+    /// Sum of the line items, before markups
+    #[serde(default)]
+    pub direct_total: f64,
+    #[serde(default)]
+    pub markup_rates: Markups,
+    /// Markups applied, in the order they compound
+    #[serde(default)]
+    pub markups: Vec<MarkupLine>,
+    /// Direct total plus markups
     pub grand_total: f64,
+    /// Whether each trade was priced from modeled or estimated quantities
+    #[serde(default)]
+    pub quantity_sources: HashMap<CostCategory, QuantitySource>,
     /// ISO date string
     pub created_at: String,
     pub notes: Option<String>,
@@ -770,7 +1013,12 @@ impl CostEstimate {
             subtotals: HashMap::new(),
             labor_total: 0.0,
             material_total: 0.0,
+            divisions: Vec::new(),
+            direct_total: 0.0,
+            markup_rates: Markups::default(),
+            markups: Vec::new(),
             grand_total: 0.0,
+            quantity_sources: HashMap::new(),
             created_at: chrono::Utc::now().format("%Y-%m-%d").to_string(),
             notes: None,
         }
@@ -788,8 +1036,9 @@ impl CostEstimate {
             self.material_total += item.total;
         }
 
-        // Update grand total
-        self.grand_total += item.total;
+        self.add_to_division(&item);
+        self.direct_total += item.total;
+        self.apply_markups();
 
         self.line_items.push(item);
     }
@@ -797,11 +1046,13 @@ impl CostEstimate {
     /// Recalculate all totals from line items
     pub fn recalculate_totals(&mut self) {
         self.subtotals.clear();
+        self.divisions.clear();
         self.labor_total = 0.0;
         self.material_total = 0.0;
-        self.grand_total = 0.0;
+        self.direct_total = 0.0;
 
-        for item in &self.line_items {
+        let items = std::mem::take(&mut self.line_items);
+        for item in &items {
             *self.subtotals.entry(item.category).or_insert(0.0) += item.total;
 
             if item.is_labor() {
@@ -810,8 +1061,62 @@ impl CostEstimate {
                 self.material_total += item.total;
             }
 
-            self.grand_total += item.total;
+            self.add_to_division(item);
+            self.direct_total += item.total;
+        }
+        self.line_items = items;
+        self.apply_markups();
+    }
+
+    /// Set the markup percentages and update the grand total
+    pub fn set_markups(&mut self, rates: Markups) {
+        self.markup_rates = rates;
+        self.apply_markups();
+    }
+
+    fn add_to_division(&mut self, item: &CostLineItem) {
+        let division = item.csi_division();
+        let index = match self.divisions.binary_search_by_key(&division, |d| d.division) {
+            Ok(index) => index,
+            Err(index) => {
+                self.divisions.insert(index, DivisionSubtotal {
+                    division,
+                    line_item_ids: Vec::new(),
+                    material_total: 0.0,
+                    labor_total: 0.0,
+                    subtotal: 0.0,
+                });
+                index
+            }
+        };
+        let entry = &mut self.divisions[index];
+        entry.line_item_ids.push(item.id);
+        if item.is_labor() {
+            entry.labor_total += item.total;
+        } else if item.is_material() {
+            entry.material_total += item.total;
+        }
+        entry.subtotal += item.total;
+    }
+
+    /// Compound the markups on the direct total
+    fn apply_markups(&mut self) {
+        let rates = [
+            ("Contingency", self.markup_rates.contingency_percent),
+            ("Overhead", self.markup_rates.overhead_percent),
+            ("Profit", self.markup_rates.profit_percent),
+        ];
+        self.markups.clear();
+        let mut total = self.direct_total;
+        for (name, percent) in rates {
+            if percent == 0.0 {
+                continue;
+            }
+            let amount = total * percent / 100.0;
+            total += amount;
+            self.markups.push(MarkupLine { name: name.to_string(), percent, amount });
         }
+        self.grand_total = total;
     }
 
     /// Get line items for a specific category
@@ -832,6 +1137,14 @@ impl CostEstimate {
         self.line_items.iter().filter(|item| item.is_material()).collect()
     }
 
+    /// Get line items under a CSI division
+    pub fn items_by_division(&self, division: CsiDivision) -> Vec<&CostLineItem> {
+        self.line_items
+            .iter()
+            .filter(|item| item.csi_division() == division)
+            .collect()
+    }
+
     /// Get subtotal for a category
     pub fn category_subtotal(&self, category: CostCategory) -> f64 {
         self.subtotals.get(&category).copied().unwrap_or(0.0)
@@ -861,6 +1174,9 @@ impl CostEstimate {
 pub struct PriceTable {
     pub material_prices: HashMap<MaterialType, UnitPrice>,
     pub labor_rates: HashMap<LaborType, LaborRate>,
+    /// Pricing region this table belongs to (e.g. "us-tx"), None for the default table
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
 }
 
 impl PriceTable {
@@ -868,6 +1184,7 @@ impl PriceTable {
         Self {
             material_prices: HashMap::new(),
             labor_rates: HashMap::new(),
+            region: None,
         }
     }
 
@@ -882,22 +1199,31 @@ impl PriceTable {
             MaterialType::ConcreteForms,
             MaterialType::ConcreteVaporBarrier,
             MaterialType::ConcreteGravel,
+            MaterialType::FillSoil,
             MaterialType::Lumber2x4,
             MaterialType::Lumber2x6,
             MaterialType::Lumber2x8,
             MaterialType::Lumber2x10,
             MaterialType::Lumber2x12,
             MaterialType::LVLBeam,
+            MaterialType::StructuralPost,
             MaterialType::Sheathing,
             MaterialType::AsphaltShingles,
             MaterialType::MetalRoofing,
             MaterialType::TileRoofing,
             MaterialType::RoofingUnderlayment,
+            MaterialType::DripEdge,
+            MaterialType::RidgeCap,
+            MaterialType::ValleyFlashing,
+            MaterialType::Gutter,
+            MaterialType::Downspout,
             MaterialType::VinylSiding,
             MaterialType::HardieBoard,
             MaterialType::Stucco,
             MaterialType::Brick,
             MaterialType::Stone,
+            MaterialType::DeckBoard,
+            MaterialType::DeckRailing,
             MaterialType::WindowUnit,
             MaterialType::ExteriorDoor,
             MaterialType::InteriorDoor,
@@ -912,7 +1238,14 @@ impl PriceTable {
             MaterialType::Trim,
             MaterialType::Truss,
             MaterialType::LightFixture,
+            MaterialType::Receptacle,
+            MaterialType::GfciReceptacle,
+            MaterialType::LightSwitch,
             MaterialType::PlumbingFixture,
+            MaterialType::Toilet,
+            MaterialType::Sink,
+            MaterialType::Bathtub,
+            MaterialType::Shower,
             MaterialType::Cabinet,
             MaterialType::Countertop,
             MaterialType::Appliance,
@@ -930,11 +1263,14 @@ impl PriceTable {
             LaborType::GeneralLabor,
             LaborType::SkilledLabor,
             LaborType::FramingCarpentry,
+            LaborType::Excavation,
+            LaborType::FillCompaction,
             LaborType::ConcreteSubgradePrep,
             LaborType::ConcreteFormInstall,
             LaborType::ConcreteRebarInstall,
             LaborType::ConcretePlaceFinish,
             LaborType::RoofingInstall,
+            LaborType::GutterInstall,
             LaborType::SidingInstall,
             LaborType::DrywallInstall,
             LaborType::PaintingLabor,
@@ -1151,6 +1487,49 @@ mod tests {
         assert_eq!(estimate.grand_total, 2600.0);
     }
 
+    #[test]
+    fn test_cost_estimate_divisions_and_markups() {
+        let mut estimate = CostEstimate::new(LevelId::new());
+        estimate.add_line_item(CostLineItem::material(
+            CostCategory::Framing,
+            "Lumber",
+            MaterialType::Lumber2x6,
+            100.0,
+            PricingUnit::PerBoard,
+            8.0,
+        ));
+        estimate.add_line_item(CostLineItem::material(
+            CostCategory::Exterior,
+            "Brick veneer",
+            MaterialType::Brick,
+            100.0,
+            PricingUnit::PerSquareFoot,
+            2.0,
+        ));
+        estimate.add_line_item(CostLineItem::material(
+            CostCategory::Foundation,
+            "Concrete",
+            MaterialType::ConcreteMix,
+            5.0,
+            PricingUnit::PerCubicYard,
+            100.0,
+        ));
+
+        let numbers: Vec<&str> = estimate.divisions.iter().map(|d| d.division.number()).collect();
+        assert_eq!(numbers, vec!["03", "04", "06"]);
+        assert_eq!(estimate.divisions[1].subtotal, 200.0);
+        assert_eq!(estimate.items_by_division(CsiDivision::Masonry).len(), 1);
+        assert_eq!(estimate.direct_total, 1500.0);
+
+        // 10% contingency, then 10% overhead on that, then 10% profit
+        estimate.set_markups(Markups { contingency_percent: 10.0, overhead_percent: 10.0, profit_percent: 10.0 });
+        assert_eq!(estimate.markups.len(), 3);
+        assert!((estimate.grand_total - 1996.5).abs() < 1e-9);
+        estimate.recalculate_totals();
+        assert!((estimate.grand_total - 1996.5).abs() < 1e-9);
+        assert_eq!(estimate.direct_total, 1500.0);
+    }
+
     #[test]
     fn test_cost_estimate_cost_per_sqft() {
         let level_id = LevelId::new();
@@ -0,0 +1,188 @@
+// Decks and porches - open platforms built off a level's outline
+// A deck hangs from a ledger bolted along part of one footprint edge and
+// extends outward from it. It sits outside the footprint, so it is framed,
+// priced and counted apart from the level's floor. Joists are sized for the
+// span out from the ledger; a surface more than 30" above grade needs a
+// guard rail along its open sides (IRC R312.1.1).
+
+use serde::{Deserialize, Serialize};
+use super::framing::{FramingMember, LumberSize};
+use super::ids::{DeckId, LevelId};
+use super::spatial::{Point2, Polygon2};
+
+/// Joist spacing on center (inches)
+pub const DECK_JOIST_SPACING: f64 = 16.0;
+
+/// Deck surface below the level's floor, to keep water out of the door (feet)
+pub const DECK_STEP_DOWN: f64 = 1.5 / 12.0;
+
+/// Surface height above grade past which a guard rail is required (feet)
+pub const GUARD_REQUIRED_HEIGHT: f64 = 30.0 / 12.0;
+
+/// Longest span out from the ledger a deck may have (feet)
+pub const MAX_DECK_DEPTH: f64 = 18.0;
+
+/// Decking board laid flat: 5/4x6, 5.5" wide and 1" thick
+pub const DECKING: LumberSize = LumberSize::Custom { width: 5.5, depth: 1.0 };
+
+/// Whether the platform is a deck or a porch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeckKind {
+    Deck,
+    /// Usually covered and at the front door
+    Porch,
+}
+
+impl DeckKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Deck => "deck",
+            Self::Porch => "porch",
+        }
+    }
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "deck" => Some(Self::Deck),
+            "porch" | "stoop" => Some(Self::Porch),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Deck => "Deck",
+            Self::Porch => "Porch",
+        }
+    }
+}
+
+/// A deck or porch attached to a level's outline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deck {
+    pub id: DeckId,
+    pub level_id: LevelId,
+    pub kind: DeckKind,
+    /// Ledger line along the outline; the deck extends to its right,
+    /// looking from start to end
+    pub ledger_start: Point2,
+    pub ledger_end: Point2,
+    /// Distance out from the ledger (feet)
+    pub depth: f64,
+    /// Height of the surface above grade (feet)
+    pub height: f64,
+    pub joist_size: LumberSize,
+}
+
+impl Deck {
+    pub fn new(level_id: LevelId, kind: DeckKind, ledger_start: Point2, ledger_end: Point2, depth: f64, height: f64) -> Self {
+        Self {
+            id: DeckId::new(),
+            level_id,
+            kind,
+            ledger_start,
+            ledger_end,
+            depth,
+            height,
+            joist_size: Self::joist_size_for(depth),
+        }
+    }
+
+    /// Joist size for a span out from the ledger at 16" on center
+    pub fn joist_size_for(depth: f64) -> LumberSize {
+        if depth <= 8.0 {
+            LumberSize::TwoBySix
+        } else if depth <= 11.0 {
+            LumberSize::TwoByEight
+        } else if depth <= 14.0 {
+            LumberSize::TwoByTen
+        } else {
+            LumberSize::TwoByTwelve
+        }
+    }
+
+    /// Length along the ledger (feet)
+    pub fn width(&self) -> f64 {
+        self.ledger_start.distance_to(&self.ledger_end)
+    }
+
+    pub fn area(&self) -> f64 {
+        self.width() * self.depth
+    }
+
+    /// Plan point `along` feet down the ledger and `out` feet away from it
+    pub fn point(&self, along: f64, out: f64) -> Point2 {
+        let width = self.width();
+        let (dx, dy) = if width > 0.0 {
+            ((self.ledger_end.x - self.ledger_start.x) / width, (self.ledger_end.y - self.ledger_start.y) / width)
+        } else {
+            (1.0, 0.0)
+        };
+        Point2::new(
+            self.ledger_start.x + dx * along + dy * out,
+            self.ledger_start.y + dy * along - dx * out,
+        )
+    }
+
+    /// Plan outline of the deck surface
+    pub fn outline(&self) -> Polygon2 {
+        let width = self.width();
+        Polygon2::new(vec![
+            self.point(0.0, 0.0),
+            self.point(0.0, self.depth),
+            self.point(width, self.depth),
+            self.point(width, 0.0),
+        ])
+    }
+
+    pub fn needs_railing(&self) -> bool {
+        self.height > GUARD_REQUIRED_HEIGHT
+    }
+
+    /// Guard rail along the two sides and outer edge (feet), or none when
+    /// the deck is low enough to go without
+    pub fn railing_length(&self) -> f64 {
+        if self.needs_railing() { self.width() + 2.0 * self.depth } else { 0.0 }
+    }
+}
+
+/// Framing for a deck: ledger, rims, joists, decking and railing posts.
+/// Positions are in inches in plan, with z measured from the deck surface.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckFramingLayout {
+    pub deck_id: DeckId,
+    pub members: Vec<FramingMember>,
+    pub joist_count: u32,
+    /// Courses of decking across the joists
+    pub decking_courses: u32,
+    /// Linear feet of decking, before it's cut to stock lengths
+    pub decking_length: f64,
+    pub railing_posts: u32,
+    /// Board feet of framing lumber, decking and posts not included
+    pub total_board_feet: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deck_outline() {
+        // Ledger running west along the north side of a footprint: the deck
+        // extends north
+        let deck = Deck::new(LevelId::new(), DeckKind::Deck, Point2::new(20.0, 30.0), Point2::new(4.0, 30.0), 10.0, 3.0);
+        let outline = deck.outline();
+        assert!((outline.area() - 160.0).abs() < 1e-9);
+        assert!(outline.outer.iter().all(|p| p.y >= 30.0 - 1e-9 && p.y <= 40.0 + 1e-9));
+        assert_eq!(deck.joist_size, LumberSize::TwoByEight);
+        assert!(deck.needs_railing());
+        assert!((deck.railing_length() - 36.0).abs() < 1e-9);
+
+        let porch = Deck::new(LevelId::new(), DeckKind::Porch, Point2::new(0.0, 0.0), Point2::new(8.0, 0.0), 6.0, 1.5);
+        assert!(!porch.needs_railing());
+        assert_eq!(porch.railing_length(), 0.0);
+        assert_eq!(DeckKind::parse("Stoop"), Some(DeckKind::Porch));
+    }
+}
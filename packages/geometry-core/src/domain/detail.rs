@@ -0,0 +1,126 @@
+// Detail callouts - plan markers that point at a standard detail drawing
+// A permit set references details as "number/sheet" (e.g. "3/A-501"); the
+// callout carries that reference plus the element it describes.
+
+use serde::{Deserialize, Serialize};
+use super::ids::{DetailCalloutId, LevelId, OpeningId, WallId};
+use super::spatial::Point2;
+
+/// Which standard detail a callout references
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetailKind {
+    TypicalWallSection,
+    FoundationEdge,
+    WindowHead,
+    WindowJamb,
+    WindowSill,
+    DoorThreshold,
+    RoofEave,
+    Other(String),
+}
+
+impl DetailKind {
+    /// Parse from string
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "wall_section" | "typical_wall_section" => Self::TypicalWallSection,
+            "foundation_edge" | "foundation" => Self::FoundationEdge,
+            "window_head" | "head" => Self::WindowHead,
+            "window_jamb" | "jamb" => Self::WindowJamb,
+            "window_sill" | "sill" => Self::WindowSill,
+            "door_threshold" | "threshold" => Self::DoorThreshold,
+            "roof_eave" | "eave" => Self::RoofEave,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            Self::TypicalWallSection => "Typical Wall Section",
+            Self::FoundationEdge => "Foundation Edge",
+            Self::WindowHead => "Window Head",
+            Self::WindowJamb => "Window Jamb",
+            Self::WindowSill => "Window Sill",
+            Self::DoorThreshold => "Door Threshold",
+            Self::RoofEave => "Roof Eave",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+/// A detail drawing on a sheet of the drawing set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetailReference {
+    pub kind: DetailKind,
+    pub detail_number: String, // e.g., "3"
+    pub sheet: String,         // e.g., "A-501"
+}
+
+impl DetailReference {
+    pub fn new(kind: DetailKind, detail_number: impl Into<String>, sheet: impl Into<String>) -> Self {
+        Self {
+            kind,
+            detail_number: detail_number.into(),
+            sheet: sheet.into(),
+        }
+    }
+
+    /// Callout bubble text, e.g. "3/A-501"
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.detail_number, self.sheet)
+    }
+}
+
+/// A marker on a level plan linking a location to a detail drawing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailCallout {
+    pub id: DetailCalloutId,
+    pub level_id: LevelId,
+    pub location: Point2,
+    pub reference: DetailReference,
+    /// Element the detail describes, if any
+    pub wall_id: Option<WallId>,
+    pub opening_id: Option<OpeningId>,
+}
+
+impl DetailCallout {
+    pub fn new(level_id: LevelId, location: Point2, reference: DetailReference) -> Self {
+        Self {
+            id: DetailCalloutId::new(),
+            level_id,
+            location,
+            reference,
+            wall_id: None,
+            opening_id: None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        self.reference.label()
+    }
+}
+
+/// One row of a detail schedule: a referenced detail and where it's called out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailScheduleEntry {
+    pub label: String,
+    pub kind: DetailKind,
+    pub sheet: String,
+    pub detail_number: String,
+    pub callout_ids: Vec<DetailCalloutId>,
+    pub level_ids: Vec<LevelId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_label() {
+        let reference = DetailReference::new(DetailKind::parse("head"), "3", "A-501");
+        assert_eq!(reference.kind, DetailKind::WindowHead);
+        assert_eq!(reference.label(), "3/A-501");
+        assert_eq!(DetailKind::parse("Ridge Vent").display_name(), "ridge vent");
+    }
+}
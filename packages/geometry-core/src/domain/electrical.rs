@@ -0,0 +1,204 @@
+// Electrical rough-in - receptacles, switches and light fixtures
+// Devices sit at a plan point, usually on a wall face inside a room. The
+// auto-layout follows the IRC general-use receptacle rule (no point along a
+// wall line more than 6' from a receptacle, so receptacles at most 12'
+// apart), puts a switch beside each door and a fixture in each room.
+
+use serde::{Deserialize, Serialize};
+use super::costing::MaterialType;
+use super::ids::{DeviceId, LevelId, RoomId, WallId};
+use super::room::RoomType;
+use super::spatial::Point2;
+
+/// Farthest apart two receptacles along a wall line may be (feet)
+pub const RECEPTACLE_SPACING: f64 = 12.0;
+
+/// Shortest run of wall that needs a receptacle (feet)
+pub const MIN_WALL_SPACE: f64 = 2.0;
+
+/// How far from the door edge a switch goes (feet)
+pub const SWITCH_OFFSET: f64 = 0.5;
+
+/// What an electrical device is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ElectricalDeviceKind {
+    Receptacle,
+    /// Ground-fault protected receptacle, required in wet and garage areas
+    GfciReceptacle,
+    Switch,
+    LightFixture,
+}
+
+impl ElectricalDeviceKind {
+    pub const ALL: [Self; 4] = [Self::Receptacle, Self::GfciReceptacle, Self::Switch, Self::LightFixture];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Receptacle => "receptacle",
+            Self::GfciReceptacle => "gfci_receptacle",
+            Self::Switch => "switch",
+            Self::LightFixture => "light_fixture",
+        }
+    }
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "receptacle" | "outlet" => Some(Self::Receptacle),
+            "gfci_receptacle" | "gfci" => Some(Self::GfciReceptacle),
+            "switch" => Some(Self::Switch),
+            "light_fixture" | "fixture" | "light" => Some(Self::LightFixture),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Receptacle => "Receptacle",
+            Self::GfciReceptacle => "GFCI Receptacle",
+            Self::Switch => "Switch",
+            Self::LightFixture => "Light Fixture",
+        }
+    }
+
+    /// Height of the device above the floor (feet); fixtures go on the
+    /// ceiling, so they have none
+    pub fn mounting_height(&self) -> Option<f64> {
+        match self {
+            Self::Receptacle | Self::GfciReceptacle => Some(16.0 / 12.0),
+            Self::Switch => Some(4.0),
+            Self::LightFixture => None,
+        }
+    }
+
+    /// Price table material for the device
+    pub fn material(&self) -> MaterialType {
+        match self {
+            Self::Receptacle => MaterialType::Receptacle,
+            Self::GfciReceptacle => MaterialType::GfciReceptacle,
+            Self::Switch => MaterialType::LightSwitch,
+            Self::LightFixture => MaterialType::LightFixture,
+        }
+    }
+
+    /// Receptacle kind the auto-layout uses in a room, or None when the
+    /// room gets no wall receptacles
+    pub fn receptacle_for(room_type: &RoomType) -> Option<Self> {
+        match room_type {
+            RoomType::Closet | RoomType::Pantry => None,
+            RoomType::Kitchen | RoomType::Bathroom | RoomType::Laundry | RoomType::Garage | RoomType::Utility => {
+                Some(Self::GfciReceptacle)
+            }
+            _ => Some(Self::Receptacle),
+        }
+    }
+}
+
+/// A receptacle, switch or fixture placed on a level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElectricalDevice {
+    pub id: DeviceId,
+    pub level_id: LevelId,
+    pub kind: ElectricalDeviceKind,
+    pub location: Point2,
+    /// Wall the device is mounted in; the device goes with the wall
+    pub wall_id: Option<WallId>,
+    pub room_id: Option<RoomId>,
+    /// Placed by the auto-layout, and replaced when it runs again
+    pub auto_placed: bool,
+}
+
+impl ElectricalDevice {
+    pub fn new(level_id: LevelId, kind: ElectricalDeviceKind, location: Point2) -> Self {
+        Self {
+            id: DeviceId::new(),
+            level_id,
+            kind,
+            location,
+            wall_id: None,
+            room_id: None,
+            auto_placed: false,
+        }
+    }
+}
+
+/// Device counts by kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceCounts {
+    pub receptacle: u32,
+    pub gfci_receptacle: u32,
+    pub switch: u32,
+    pub light_fixture: u32,
+}
+
+impl DeviceCounts {
+    pub fn get(&self, kind: ElectricalDeviceKind) -> u32 {
+        match kind {
+            ElectricalDeviceKind::Receptacle => self.receptacle,
+            ElectricalDeviceKind::GfciReceptacle => self.gfci_receptacle,
+            ElectricalDeviceKind::Switch => self.switch,
+            ElectricalDeviceKind::LightFixture => self.light_fixture,
+        }
+    }
+
+    pub fn add(&mut self, kind: ElectricalDeviceKind, count: u32) {
+        match kind {
+            ElectricalDeviceKind::Receptacle => self.receptacle += count,
+            ElectricalDeviceKind::GfciReceptacle => self.gfci_receptacle += count,
+            ElectricalDeviceKind::Switch => self.switch += count,
+            ElectricalDeviceKind::LightFixture => self.light_fixture += count,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.receptacle + self.gfci_receptacle + self.switch + self.light_fixture
+    }
+}
+
+/// One row of a device schedule: the devices in a room, or on a level
+/// outside any room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceScheduleEntry {
+    pub level_id: LevelId,
+    pub level_name: String,
+    pub room_id: Option<RoomId>,
+    pub room_name: Option<String>,
+    pub counts: DeviceCounts,
+}
+
+/// Devices in a project by room, with project totals
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceSchedule {
+    pub entries: Vec<DeviceScheduleEntry>,
+    pub totals: DeviceCounts,
+}
+
+/// Outcome of an electrical auto-layout on a level
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ElectricalLayoutSummary {
+    pub device_ids: Vec<DeviceId>,
+    pub counts: DeviceCounts,
+    /// Auto-placed devices from an earlier run that were replaced
+    pub replaced: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_kinds() {
+        assert_eq!(ElectricalDeviceKind::parse("GFCI"), Some(ElectricalDeviceKind::GfciReceptacle));
+        assert_eq!(ElectricalDeviceKind::parse("dimmer"), None);
+        assert_eq!(ElectricalDeviceKind::receptacle_for(&RoomType::Bathroom), Some(ElectricalDeviceKind::GfciReceptacle));
+        assert_eq!(ElectricalDeviceKind::receptacle_for(&RoomType::Closet), None);
+        assert_eq!(ElectricalDeviceKind::LightFixture.mounting_height(), None);
+
+        let mut counts = DeviceCounts::default();
+        counts.add(ElectricalDeviceKind::Switch, 2);
+        counts.add(ElectricalDeviceKind::Receptacle, 5);
+        assert_eq!(counts.get(ElectricalDeviceKind::Switch), 2);
+        assert_eq!(counts.total(), 7);
+    }
+}
@@ -51,6 +51,23 @@ impl ErrorCode {
             ErrorCode::OperationNotSupported => "OPERATION_NOT_SUPPORTED",
         }
     }
+
+    /// HTTP status an API responds with for this kind of error
+    pub fn http_status(&self) -> u16 {
+        match self {
+            ErrorCode::EntityNotFound => 404,
+            ErrorCode::EntityAlreadyExists | ErrorCode::ConstraintViolation => 409,
+            ErrorCode::InvalidParameter | ErrorCode::MissingParameter | ErrorCode::ParameterOutOfRange => 400,
+            ErrorCode::InvalidEntityReference
+            | ErrorCode::InvalidGeometry
+            | ErrorCode::DegenerateGeometry
+            | ErrorCode::GeometryTooSmall
+            | ErrorCode::GeometryOutOfBounds
+            | ErrorCode::ValidationFailed
+            | ErrorCode::OperationNotSupported => 422,
+            ErrorCode::InternalError => 500,
+        }
+    }
 }
 
 /// Entity types for error context
@@ -121,6 +138,11 @@ pub struct StructuredError {
 }
 
 impl StructuredError {
+    /// HTTP status for the error's code
+    pub fn http_status(&self) -> u16 {
+        self.code.http_status()
+    }
+
     /// Create an entity not found error
     pub fn entity_not_found(entity_type: EntityType, id: impl std::fmt::Debug) -> Self {
         let id_str = format!("{:?}", id);
@@ -239,6 +261,34 @@ impl StructuredError {
         }
     }
 
+    /// Create an error for a project outside the one a script is bound to
+    pub fn outside_project_scope(scope: impl std::fmt::Debug, project_id: impl std::fmt::Debug) -> Self {
+        Self {
+            code: ErrorCode::InvalidEntityReference,
+            message: "Project is outside this script's scope".to_string(),
+            entity_type: Some(EntityType::Project),
+            entity_id: Some(format!("{:?}", project_id)),
+            field: None,
+            provided_value: None,
+            valid_range: Some(format!("{:?}", scope)),
+            suggestions: vec!["Use the `project` variable for the script's own project".to_string()],
+        }
+    }
+
+    /// Create an error for an operation the current context doesn't allow
+    pub fn operation_not_supported(message: impl Into<String>, suggestions: Vec<String>) -> Self {
+        Self {
+            code: ErrorCode::OperationNotSupported,
+            message: message.into(),
+            entity_type: None,
+            entity_id: None,
+            field: None,
+            provided_value: None,
+            valid_range: None,
+            suggestions,
+        }
+    }
+
     /// Create an entity already exists error
     pub fn entity_already_exists(entity_type: EntityType, context: impl Into<String>) -> Self {
         Self {
@@ -450,6 +500,15 @@ mod tests {
         assert!(err.valid_range.unwrap().contains("0 to 1"));
     }
 
+    #[test]
+    fn test_http_status() {
+        assert_eq!(StructuredError::entity_not_found(EntityType::Wall, "wall_1").http_status(), 404);
+        assert_eq!(StructuredError::unknown_unit_system("fathoms").http_status(), 400);
+        let err = StructuredError::from(anyhow::anyhow!("Invalid polygon: must be closed, non-degenerate"));
+        assert_eq!(err.http_status(), 422);
+        assert_eq!(ErrorCode::InternalError.http_status(), 500);
+    }
+
     #[test]
     fn test_structured_error_display() {
         let err = StructuredError::unknown_unit_system("fathoms");
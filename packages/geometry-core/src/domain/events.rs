@@ -7,11 +7,29 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+use super::costing::Markups;
 use super::ids::*;
-use super::project::{CodeRegion, UnitSystem};
-use super::spatial::{Polygon2, Point2};
+use super::project::{BuildingOccupancy, CodeRegion, UnitSystem, ZoningRules};
+use super::spatial::{Polygon2, Point2, Point3};
 use super::room::RoomType;
-use super::opening::OpeningType;
+use super::opening::{OpeningDetails, OpeningType};
+use super::filter::SavedFilter;
+use super::script::SavedScript;
+use super::constraint::Constraint;
+use super::detail::DetailCallout;
+use super::roof::Roof;
+use super::stair::Stair;
+use super::slab::{CeilingAssembly, FloorSlab, SlabLayer};
+use super::foundation::{Foundation, FoundationDimensions};
+use super::structural::{Beam, Post};
+use super::electrical::ElectricalDevice;
+use super::plumbing::PlumbingFixture;
+use super::hvac::HvacZone;
+use super::deck::Deck;
+use super::garage::Garage;
+use super::finish::{FinishSurface, RoomFinishes};
+use super::costing::MaterialType;
+use super::wall::{AssemblyRole, WallArc};
 
 /// Unique identifier for an event
 pub type EventId = u64;
@@ -50,6 +68,16 @@ pub enum EventKind {
         old_name: String,
         new_name: String,
     },
+    ProjectCodeRegionChanged {
+        old_region: CodeRegion,
+        new_region: CodeRegion,
+    },
+    ProjectPriceRegionSet {
+        region: Option<String>,
+    },
+    ProjectMarkupsSet {
+        markups: Markups,
+    },
 
     // Site events
     SiteCreated {
@@ -66,6 +94,20 @@ pub enum EventKind {
         left: f64,
         right: f64,
     },
+    SiteZoningSet {
+        site_id: SiteId,
+        zoning: ZoningRules,
+    },
+    /// Degrees clockwise from the plan's +y axis to true north
+    SiteNorthAngleSet {
+        site_id: SiteId,
+        north_angle: f64,
+    },
+    /// Survey points the terrain is triangulated from; empty clears it
+    SiteTerrainSet {
+        site_id: SiteId,
+        points: Vec<Point3>,
+    },
 
     // Building events
     BuildingAdded {
@@ -80,6 +122,14 @@ pub enum EventKind {
     BuildingRemoved {
         building_id: BuildingId,
     },
+    BuildingOccupancySet {
+        building_id: BuildingId,
+        occupancy: BuildingOccupancy,
+    },
+    BuildingPadElevationSet {
+        building_id: BuildingId,
+        pad_elevation: Option<f64>,
+    },
 
     // Level events
     LevelAdded {
@@ -114,6 +164,11 @@ pub enum EventKind {
         footprint_id: FootprintId,
         level_id: LevelId,
     },
+    AuxiliaryFootprintAdded {
+        footprint_id: FootprintId,
+        level_id: LevelId,
+        polygon: Polygon2,
+    },
 
     // Grid events
     GridCreated {
@@ -129,6 +184,17 @@ pub enum EventKind {
         wall_assembly_id: WallAssemblyId,
         name: String,
     },
+    /// A shared assembly's name or layers changed. Recorded in every
+    /// project that uses it so their revisions move; replay leaves the
+    /// assembly as it is.
+    WallAssemblyUpdated {
+        wall_assembly_id: WallAssemblyId,
+        name: String,
+    },
+    DefaultAssemblySet {
+        role: AssemblyRole,
+        assembly_id: Option<WallAssemblyId>,
+    },
 
     // Wall events
     WallCreated {
@@ -138,11 +204,24 @@ pub enum EventKind {
         start: Point2,
         end: Point2,
         height: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        arc: Option<WallArc>,
     },
     WallRemoved {
         wall_id: WallId,
         level_id: LevelId,
     },
+    WallMoved {
+        wall_id: WallId,
+        level_id: LevelId,
+        start: Point2,
+        end: Point2,
+    },
+    WallAssemblySet {
+        wall_id: WallId,
+        level_id: LevelId,
+        assembly_id: WallAssemblyId,
+    },
 
     // Room events
     RoomCreated {
@@ -150,22 +229,221 @@ pub enum EventKind {
         level_id: LevelId,
         room_type: RoomType,
         name: String,
+        boundary: Polygon2,
     },
     RoomRemoved {
         room_id: RoomId,
         level_id: LevelId,
     },
+    RoomConditioningSet {
+        room_id: RoomId,
+        conditioned: Option<bool>,
+    },
+    RoomFinishSet {
+        room_id: RoomId,
+        surface: FinishSurface,
+        material: MaterialType,
+    },
+    RoomFinishesSet {
+        room_id: RoomId,
+        finishes: RoomFinishes,
+    },
+    RoomBoundarySet {
+        room_id: RoomId,
+        boundary: Polygon2,
+        /// Change in floor area (square feet), positive when the room grew
+        #[serde(default)]
+        area_delta: f64,
+    },
+    RoomMetaUpdated {
+        room_id: RoomId,
+        name: String,
+        room_type: RoomType,
+    },
 
     // Opening events
     OpeningAdded {
         opening_id: OpeningId,
         wall_id: WallId,
         opening_type: OpeningType,
+        position: f64,
+        width: f64,
+        height: f64,
+        sill_height: f64,
+        /// Catalog unit and properties the opening was placed with
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<OpeningDetails>,
     },
     OpeningRemoved {
         opening_id: OpeningId,
         wall_id: WallId,
     },
+    OpeningMoved {
+        opening_id: OpeningId,
+        wall_id: WallId,
+        position: f64,
+    },
+    OpeningModified {
+        opening_id: OpeningId,
+        wall_id: WallId,
+        opening_type: OpeningType,
+        position: f64,
+        width: f64,
+        height: f64,
+        sill_height: f64,
+    },
+
+    // Saved filter events
+    FilterSaved {
+        filter: SavedFilter,
+    },
+    FilterRemoved {
+        filter_id: FilterId,
+    },
+
+    // Saved script events; saving again under a name replaces the source
+    ScriptSaved {
+        script: SavedScript,
+    },
+    ScriptRemoved {
+        script_id: ScriptId,
+    },
+
+    // Named constraint events
+    ConstraintAdded {
+        constraint: Constraint,
+    },
+    ConstraintRemoved {
+        constraint_id: ConstraintId,
+    },
+
+    // Detail callout events
+    DetailCalloutAdded {
+        callout: DetailCallout,
+    },
+    DetailCalloutRemoved {
+        callout_id: DetailCalloutId,
+    },
+
+    // Roof events
+    RoofCreated {
+        roof: Roof,
+    },
+    RoofRemoved {
+        roof_id: RoofId,
+    },
+
+    // Stair events
+    StairCreated {
+        stair: Stair,
+    },
+    StairRemoved {
+        stair_id: StairId,
+    },
+
+    // Floor and ceiling assembly events
+    FloorSlabCreated {
+        slab: FloorSlab,
+    },
+    FloorSlabLayersSet {
+        slab_id: FloorSlabId,
+        layers: Vec<SlabLayer>,
+    },
+    FloorSlabRemoved {
+        slab_id: FloorSlabId,
+    },
+    CeilingAssemblyCreated {
+        ceiling: CeilingAssembly,
+    },
+    CeilingAssemblyLayersSet {
+        ceiling_id: CeilingAssemblyId,
+        layers: Vec<SlabLayer>,
+    },
+    CeilingAssemblyRemoved {
+        ceiling_id: CeilingAssemblyId,
+    },
+
+    // Foundation events
+    FoundationCreated {
+        foundation: Foundation,
+    },
+    FoundationDimensionsSet {
+        foundation_id: FoundationId,
+        dimensions: FoundationDimensions,
+    },
+    FoundationRemoved {
+        foundation_id: FoundationId,
+    },
+
+    // Beam and post events
+    BeamCreated {
+        beam: Beam,
+    },
+    BeamRemoved {
+        beam_id: BeamId,
+    },
+    PostCreated {
+        post: Post,
+    },
+    PostRemoved {
+        post_id: PostId,
+    },
+
+    // Electrical device events
+    ElectricalDeviceAdded {
+        device: ElectricalDevice,
+    },
+    ElectricalDeviceRemoved {
+        device_id: DeviceId,
+    },
+    /// Auto-placed devices on the level replaced by a new layout
+    ElectricalLayoutApplied {
+        level_id: LevelId,
+        devices: Vec<ElectricalDevice>,
+    },
+
+    // Plumbing fixture events
+    FixtureAdded {
+        fixture: PlumbingFixture,
+    },
+    FixtureRemoved {
+        fixture_id: FixtureId,
+    },
+
+    // HVAC zone events
+    HvacZoneCreated {
+        zone: HvacZone,
+    },
+    HvacZoneRemoved {
+        zone_id: HVACZoneId,
+    },
+    /// Room moved into a zone, out of any zone it was in; None leaves it
+    /// unzoned
+    HvacZoneRoomAssigned {
+        room_id: RoomId,
+        zone_id: Option<HVACZoneId>,
+    },
+
+    // Deck and porch events
+    DeckAdded {
+        deck: Deck,
+    },
+    DeckRemoved {
+        deck_id: DeckId,
+    },
+
+    // Garage events
+    GarageConfigured {
+        garage: Garage,
+    },
+    GarageRemoved {
+        garage_id: GarageId,
+    },
+    GarageSlabSet {
+        garage_id: GarageId,
+        slab_thickness: f64,
+        slab_slope: f64,
+    },
 
     // Geometry cache events (internal, not for undo)
     SolidGenerated {
@@ -177,6 +455,56 @@ pub enum EventKind {
     },
 }
 
+impl EventKind {
+    /// The kind's serialized `type` tag, e.g. "wall_created"
+    pub fn type_name(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.get("type")?.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    /// The wall assembly the event puts a wall or project default on
+    pub fn wall_assembly_id(&self) -> Option<WallAssemblyId> {
+        match self {
+            EventKind::WallCreated { assembly_id, .. } | EventKind::WallAssemblySet { assembly_id, .. } => {
+                Some(*assembly_id)
+            }
+            EventKind::DefaultAssemblySet { assembly_id, .. } => *assembly_id,
+            _ => None,
+        }
+    }
+}
+
+/// Which page of a project's history to return
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct EventQuery {
+    /// Event `type` tags to include (e.g. "wall_created"), empty for all
+    pub kinds: Vec<String>,
+    /// Only events at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only events before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Newest first
+    pub descending: bool,
+    /// Continue after this event, the `next_cursor` of the previous page
+    pub cursor: Option<EventId>,
+    /// Page size, None for every remaining event
+    pub limit: Option<usize>,
+}
+
+/// One page of matching events
+#[derive(Debug, Clone, Serialize)]
+pub struct EventPage<'a> {
+    pub events: Vec<&'a Event>,
+    /// Events matching the filters, across all pages
+    pub total: usize,
+    /// Cursor for the next page, None on the last page
+    pub next_cursor: Option<EventId>,
+}
+
 /// What generated a solid (for cache invalidation)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -190,10 +518,15 @@ pub enum SolidSource {
 }
 
 /// Event log for a project
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EventLog {
     events: Vec<Event>,
     next_id: EventId,
+    /// Events removed by undo, most recent last (cleared by any new event)
+    undone: Vec<Event>,
+    /// History up to this event has been compacted and no longer holds
+    /// every intermediate state (0 = never compacted)
+    compacted_through: EventId,
 }
 
 impl EventLog {
@@ -201,6 +534,20 @@ impl EventLog {
         Self {
             events: Vec::new(),
             next_id: 1,
+            undone: Vec::new(),
+            compacted_through: 0,
+        }
+    }
+
+    /// Rebuild a log from previously recorded events (ordered by ID)
+    pub fn from_events(mut events: Vec<Event>) -> Self {
+        events.sort_by_key(|e| e.id);
+        let next_id = events.last().map(|e| e.id + 1).unwrap_or(1);
+        Self {
+            events,
+            next_id,
+            undone: Vec::new(),
+            compacted_through: 0,
         }
     }
 
@@ -208,13 +555,51 @@ impl EventLog {
         let id = self.next_id;
         self.next_id += 1;
         self.events.push(Event::new(id, project_id, kind));
+        self.undone.clear();
         id
     }
 
+    /// Whether there is an event to undo (project creation can't be undone)
+    pub fn can_undo(&self) -> bool {
+        self.events.len() > 1
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Move the most recent event onto the redo stack
+    pub fn undo(&mut self) -> Option<&Event> {
+        if !self.can_undo() {
+            return None;
+        }
+        let event = self.events.pop()?;
+        self.undone.push(event);
+        self.undone.last()
+    }
+
+    /// Restore the most recently undone event, keeping its original ID
+    pub fn redo(&mut self) -> Option<&Event> {
+        let event = self.undone.pop()?;
+        self.events.push(event);
+        self.events.last()
+    }
+
     pub fn events(&self) -> &[Event] {
         &self.events
     }
 
+    /// Drop every event after the first `len` (discards the redo stack)
+    pub fn truncate(&mut self, len: usize) {
+        self.events.truncate(len);
+        self.undone.clear();
+    }
+
+    /// ID of the most recent event (0 for an empty log)
+    pub fn last_id(&self) -> EventId {
+        self.events.last().map(|e| e.id).unwrap_or(0)
+    }
+
     pub fn len(&self) -> usize {
         self.events.len()
     }
@@ -223,6 +608,27 @@ impl EventLog {
         self.events.is_empty()
     }
 
+    /// Drop events up to and including `through` that `keep` rejects. IDs
+    /// of surviving events are unchanged; the redo stack is kept.
+    pub fn compact(&mut self, through: EventId, mut keep: impl FnMut(&Event) -> bool) {
+        self.events.retain(|e| e.id > through || keep(e));
+        self.compacted_through = self.compacted_through.max(through);
+    }
+
+    pub fn compacted_through(&self) -> EventId {
+        self.compacted_through
+    }
+
+    /// Events waiting on the redo stack
+    pub fn undone_len(&self) -> usize {
+        self.undone.len()
+    }
+
+    /// Events waiting on the redo stack, most recently undone last
+    pub fn undone(&self) -> &[Event] {
+        &self.undone
+    }
+
     /// Get events after a given ID (for sync)
     pub fn events_since(&self, after_id: EventId) -> Vec<&Event> {
         self.events.iter().filter(|e| e.id > after_id).collect()
@@ -232,6 +638,42 @@ impl EventLog {
     pub fn last_n(&self, n: usize) -> Vec<&Event> {
         self.events.iter().rev().take(n).collect()
     }
+
+    /// A page of the events matching `query`. Cursors are event IDs, so
+    /// pages stay put as new events are recorded.
+    pub fn query(&self, query: &EventQuery) -> EventPage<'_> {
+        let matches = |e: &&Event| {
+            query.since.is_none_or(|t| e.timestamp >= t)
+                && query.until.is_none_or(|t| e.timestamp < t)
+                && (query.kinds.is_empty() || query.kinds.contains(&e.kind.type_name()))
+        };
+        let matching: Vec<&Event> = if query.descending {
+            self.events.iter().rev().filter(matches).collect()
+        } else {
+            self.events.iter().filter(matches).collect()
+        };
+
+        let total = matching.len();
+        let start = match query.cursor {
+            Some(cursor) => matching
+                .iter()
+                .position(|e| if query.descending { e.id < cursor } else { e.id > cursor })
+                .unwrap_or(total),
+            None => 0,
+        };
+        let events: Vec<&Event> = matching[start..]
+            .iter()
+            .take(query.limit.unwrap_or(usize::MAX))
+            .copied()
+            .collect();
+        let next_cursor = if start + events.len() < total {
+            events.last().map(|e| e.id)
+        } else {
+            None
+        };
+
+        EventPage { events, total, next_cursor }
+    }
 }
 
 #[cfg(test)]
@@ -282,4 +724,85 @@ mod tests {
         let since_3 = log.events_since(3);
         assert_eq!(since_3.len(), 2); // events 4 and 5
     }
+
+    #[test]
+    fn test_query_events() {
+        let mut log = EventLog::new();
+        let project_id = ProjectId::new();
+        for i in 0..5 {
+            log.push(project_id, EventKind::BuildingAdded { building_id: BuildingId::new(), name: format!("B{}", i) });
+            log.push(
+                project_id,
+                EventKind::ProjectRenamed { old_name: format!("v{}", i), new_name: format!("v{}", i + 1) },
+            );
+        }
+
+        let query = EventQuery { kinds: vec!["project_renamed".to_string()], limit: Some(2), ..Default::default() };
+        let first = log.query(&query);
+        assert_eq!(first.total, 5);
+        assert_eq!(first.events.iter().map(|e| e.id).collect::<Vec<_>>(), vec![2, 4]);
+        assert_eq!(first.next_cursor, Some(4));
+
+        // Walk the rest of the pages by cursor
+        let mut ids = Vec::new();
+        let mut cursor = first.next_cursor;
+        while let Some(after) = cursor {
+            let page = log.query(&EventQuery { cursor: Some(after), ..query.clone() });
+            ids.extend(page.events.iter().map(|e| e.id));
+            cursor = page.next_cursor;
+        }
+        assert_eq!(ids, vec![6, 8, 10]);
+
+        let newest = log.query(&EventQuery { descending: true, limit: Some(3), ..Default::default() });
+        assert_eq!(newest.events.iter().map(|e| e.id).collect::<Vec<_>>(), vec![10, 9, 8]);
+        let older = log.query(&EventQuery { descending: true, cursor: newest.next_cursor, ..Default::default() });
+        assert_eq!(older.events.first().map(|e| e.id), Some(7));
+        assert_eq!(older.next_cursor, None);
+
+        let future = Utc::now() + chrono::Duration::hours(1);
+        assert_eq!(log.query(&EventQuery { since: Some(future), ..Default::default() }).total, 0);
+        assert_eq!(log.query(&EventQuery { until: Some(future), ..Default::default() }).total, 10);
+    }
+
+    #[test]
+    fn test_undo_redo_stack() {
+        let mut log = EventLog::new();
+        let project_id = ProjectId::new();
+
+        log.push(
+            project_id,
+            EventKind::ProjectCreated {
+                name: "Test".into(),
+                units: UnitSystem::Imperial,
+                code_region: CodeRegion::us_irc_2021(),
+            },
+        );
+        let id = log.push(
+            project_id,
+            EventKind::ProjectRenamed {
+                old_name: "Test".into(),
+                new_name: "Renamed".into(),
+            },
+        );
+
+        assert_eq!(log.undo().map(|e| e.id), Some(id));
+        assert_eq!(log.len(), 1);
+        // Project creation stays
+        assert!(log.undo().is_none());
+
+        assert_eq!(log.redo().map(|e| e.id), Some(id));
+        assert_eq!(log.len(), 2);
+        assert!(!log.can_redo());
+
+        // A new event discards the redo stack
+        log.undo();
+        log.push(
+            project_id,
+            EventKind::ProjectRenamed {
+                old_name: "Test".into(),
+                new_name: "Other".into(),
+            },
+        );
+        assert!(!log.can_redo());
+    }
 }
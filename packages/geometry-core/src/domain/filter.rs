@@ -0,0 +1,161 @@
+// Saved filters - named queries stored with a project
+// Defined once and evaluated by the UI, reports, and scripts alike
+
+use serde::{Deserialize, Serialize};
+use super::ids::{FilterId, LevelId, OpeningId, ProjectId, RoomId, WallId};
+use super::opening::OpeningType;
+use super::room::RoomType;
+
+/// Which kind of entity a filter selects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterTarget {
+    Walls,
+    Rooms,
+    Openings,
+}
+
+impl FilterTarget {
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "walls" | "wall" => Some(Self::Walls),
+            "rooms" | "room" => Some(Self::Rooms),
+            "openings" | "opening" => Some(Self::Openings),
+            _ => None,
+        }
+    }
+}
+
+/// A single predicate; all conditions in a filter must match.
+/// Min/max bounds are inclusive, lengths in feet and areas in square feet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FilterCondition {
+    /// Entity is on the given level (openings use their host wall's level)
+    OnLevel { level_id: LevelId },
+
+    // Wall conditions
+    LoadBearing { value: bool },
+    Insulated { value: bool },
+    AssemblyName { name: String },
+    MinLength { value: f64 },
+    MaxLength { value: f64 },
+
+    // Room conditions
+    RoomTypeIs { room_type: RoomType },
+    MinArea { value: f64 },
+    MaxArea { value: f64 },
+    Conditioned { value: bool },
+
+    // Opening conditions
+    OpeningTypeIs { opening_type: OpeningType },
+    MinWidth { value: f64 },
+    MaxWidth { value: f64 },
+}
+
+impl FilterCondition {
+    /// Whether this condition can be evaluated against the target
+    pub fn applies_to(&self, target: FilterTarget) -> bool {
+        match self {
+            Self::OnLevel { .. } => true,
+            Self::LoadBearing { .. }
+            | Self::Insulated { .. }
+            | Self::AssemblyName { .. }
+            | Self::MinLength { .. }
+            | Self::MaxLength { .. } => target == FilterTarget::Walls,
+            Self::RoomTypeIs { .. }
+            | Self::MinArea { .. }
+            | Self::MaxArea { .. }
+            | Self::Conditioned { .. } => target == FilterTarget::Rooms,
+            Self::OpeningTypeIs { .. }
+            | Self::MinWidth { .. }
+            | Self::MaxWidth { .. } => target == FilterTarget::Openings,
+        }
+    }
+}
+
+/// A named query saved in a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedFilter {
+    pub id: FilterId,
+    pub project_id: ProjectId,
+    pub name: String,
+    pub target: FilterTarget,
+    pub conditions: Vec<FilterCondition>,
+}
+
+impl SavedFilter {
+    pub fn new(
+        project_id: ProjectId,
+        name: impl Into<String>,
+        target: FilterTarget,
+        conditions: Vec<FilterCondition>,
+    ) -> Self {
+        Self {
+            id: FilterId::new(),
+            project_id,
+            name: name.into(),
+            target,
+            conditions,
+        }
+    }
+
+    /// Conditions that don't apply to the filter's target
+    pub fn invalid_conditions(&self) -> Vec<&FilterCondition> {
+        self.conditions
+            .iter()
+            .filter(|c| !c.applies_to(self.target))
+            .collect()
+    }
+}
+
+/// Entities matched by a filter (only the target's list is populated)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FilterResult {
+    pub walls: Vec<WallId>,
+    pub rooms: Vec<RoomId>,
+    pub openings: Vec<OpeningId>,
+}
+
+impl FilterResult {
+    pub fn len(&self) -> usize {
+        self.walls.len() + self.rooms.len() + self.openings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condition_targets() {
+        let filter = SavedFilter::new(
+            ProjectId::new(),
+            "Small rooms",
+            FilterTarget::Rooms,
+            vec![
+                FilterCondition::MaxArea { value: 100.0 },
+                FilterCondition::LoadBearing { value: true },
+            ],
+        );
+
+        let invalid = filter.invalid_conditions();
+        assert_eq!(invalid, vec![&FilterCondition::LoadBearing { value: true }]);
+        assert_eq!(FilterTarget::parse("Walls"), Some(FilterTarget::Walls));
+        assert_eq!(FilterTarget::parse("roofs"), None);
+    }
+
+    #[test]
+    fn test_condition_serialization() {
+        let condition = FilterCondition::MinLength { value: 12.0 };
+        let json = serde_json::to_string(&condition).unwrap();
+        assert_eq!(json, r#"{"type":"min_length","value":12.0}"#);
+        let parsed: FilterCondition = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, condition);
+    }
+}
@@ -0,0 +1,234 @@
+// Room finishes - the material on each finished surface of a room
+// Selections come from the MaterialType price catalog so every finish can
+// be priced from the same table as the rest of the estimate.
+
+use serde::{Deserialize, Serialize};
+use super::costing::{CostCategory, MaterialType};
+use super::ids::{LevelId, RoomId};
+use super::room::RoomType;
+
+/// A finished surface of a room
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishSurface {
+    Floor,
+    Wall,
+    Ceiling,
+    Base,
+}
+
+impl FinishSurface {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Floor => "floor",
+            Self::Wall => "wall",
+            Self::Ceiling => "ceiling",
+            Self::Base => "base",
+        }
+    }
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "floor" | "flooring" => Some(Self::Floor),
+            "wall" | "walls" => Some(Self::Wall),
+            "ceiling" => Some(Self::Ceiling),
+            "base" | "baseboard" => Some(Self::Base),
+            _ => None,
+        }
+    }
+
+    /// Catalog materials that can finish this surface. Drywall means the
+    /// board is left unfinished; ConcreteMix means an exposed slab.
+    pub fn allowed_materials(&self) -> &'static [MaterialType] {
+        match self {
+            Self::Floor => &[
+                MaterialType::Hardwood,
+                MaterialType::Tile,
+                MaterialType::Carpet,
+                MaterialType::LVP,
+                MaterialType::ConcreteMix,
+            ],
+            Self::Wall => &[MaterialType::Paint, MaterialType::Tile, MaterialType::Drywall],
+            Self::Ceiling => &[MaterialType::Paint, MaterialType::Drywall],
+            Self::Base => &[MaterialType::Trim, MaterialType::Tile],
+        }
+    }
+
+    pub fn allows(&self, material: MaterialType) -> bool {
+        self.allowed_materials().contains(&material)
+    }
+
+    /// Parse one of this surface's allowed materials by name
+    pub fn parse_material(&self, s: &str) -> Option<MaterialType> {
+        let s = s.to_lowercase();
+        self.allowed_materials().iter().copied().find(|m| material_name(*m) == s)
+    }
+
+    /// Estimate category for this surface's finish
+    pub fn cost_category(&self) -> CostCategory {
+        match self {
+            Self::Floor => CostCategory::Flooring,
+            Self::Wall | Self::Ceiling => CostCategory::Painting,
+            Self::Base => CostCategory::Trim,
+        }
+    }
+}
+
+/// Name of a finish material, as the wasm and script APIs spell it
+pub fn material_name(material: MaterialType) -> &'static str {
+    match material {
+        MaterialType::Hardwood => "hardwood",
+        MaterialType::Tile => "tile",
+        MaterialType::Carpet => "carpet",
+        MaterialType::LVP => "lvp",
+        MaterialType::ConcreteMix => "concrete_mix",
+        MaterialType::Paint => "paint",
+        MaterialType::Drywall => "drywall",
+        MaterialType::Trim => "trim",
+        _ => "",
+    }
+}
+
+/// Grade of the room's trim package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrimLevel {
+    /// Finger-jointed, paint grade
+    Basic,
+    #[default]
+    Standard,
+    /// Wider profiles, stain grade
+    Premium,
+}
+
+impl TrimLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Basic => "basic",
+            Self::Standard => "standard",
+            Self::Premium => "premium",
+        }
+    }
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "basic" => Some(Self::Basic),
+            "standard" => Some(Self::Standard),
+            "premium" => Some(Self::Premium),
+            _ => None,
+        }
+    }
+
+    /// Multiplier on the trim material price
+    pub fn price_factor(&self) -> f64 {
+        match self {
+            Self::Basic => 0.75,
+            Self::Standard => 1.0,
+            Self::Premium => 1.6,
+        }
+    }
+}
+
+/// Finish selections for every surface of a room
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomFinishes {
+    pub floor: MaterialType,
+    pub wall: MaterialType,
+    pub ceiling: MaterialType,
+    pub base: MaterialType,
+    #[serde(default)]
+    pub trim: TrimLevel,
+}
+
+impl RoomFinishes {
+    /// Typical finishes for a room type
+    pub fn for_room_type(room_type: &RoomType) -> Self {
+        let floor = match room_type {
+            RoomType::Kitchen | RoomType::Bathroom | RoomType::Laundry | RoomType::Mudroom => MaterialType::Tile,
+            RoomType::LivingRoom
+            | RoomType::DiningRoom
+            | RoomType::FamilyRoom
+            | RoomType::Bedroom
+            | RoomType::Office => MaterialType::Hardwood,
+            RoomType::Garage => MaterialType::ConcreteMix,
+            _ => MaterialType::LVP,
+        };
+        let (ceiling, base) = match room_type {
+            RoomType::Garage => (MaterialType::Drywall, MaterialType::Trim),
+            RoomType::Bathroom => (MaterialType::Paint, MaterialType::Tile),
+            _ => (MaterialType::Paint, MaterialType::Trim),
+        };
+        let trim = match room_type {
+            RoomType::Garage | RoomType::Utility | RoomType::Closet | RoomType::Pantry | RoomType::Laundry => TrimLevel::Basic,
+            RoomType::LivingRoom | RoomType::DiningRoom | RoomType::FamilyRoom | RoomType::Foyer => TrimLevel::Premium,
+            _ => TrimLevel::Standard,
+        };
+        Self {
+            floor,
+            wall: MaterialType::Paint,
+            ceiling,
+            base,
+            trim,
+        }
+    }
+
+    pub fn get(&self, surface: FinishSurface) -> MaterialType {
+        match surface {
+            FinishSurface::Floor => self.floor,
+            FinishSurface::Wall => self.wall,
+            FinishSurface::Ceiling => self.ceiling,
+            FinishSurface::Base => self.base,
+        }
+    }
+
+    pub fn set(&mut self, surface: FinishSurface, material: MaterialType) {
+        match surface {
+            FinishSurface::Floor => self.floor = material,
+            FinishSurface::Wall => self.wall = material,
+            FinishSurface::Ceiling => self.ceiling = material,
+            FinishSurface::Base => self.base = material,
+        }
+    }
+}
+
+/// One row of a room finish schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinishScheduleEntry {
+    pub room_id: RoomId,
+    pub level_id: LevelId,
+    pub room_name: String,
+    pub room_type: RoomType,
+    pub finishes: RoomFinishes,
+    pub floor_sqft: f64,
+    pub wall_sqft: f64,
+    pub ceiling_sqft: f64,
+    pub base_lf: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_finishes() {
+        let bath = RoomFinishes::for_room_type(&RoomType::Bathroom);
+        assert_eq!(bath.floor, MaterialType::Tile);
+        assert_eq!(bath.get(FinishSurface::Base), MaterialType::Tile);
+
+        // Every default is a valid selection for its surface
+        for room_type in [RoomType::Garage, RoomType::Bedroom, RoomType::Other("Den".into())] {
+            let finishes = RoomFinishes::for_room_type(&room_type);
+            for surface in [FinishSurface::Floor, FinishSurface::Wall, FinishSurface::Ceiling, FinishSurface::Base] {
+                assert!(surface.allows(finishes.get(surface)), "{:?} {:?}", room_type, surface);
+            }
+        }
+        assert!(!FinishSurface::Ceiling.allows(MaterialType::Carpet));
+
+        assert_eq!(FinishSurface::Floor.parse_material("LVP"), Some(MaterialType::LVP));
+        assert_eq!(FinishSurface::Ceiling.parse_material("tile"), None);
+        assert_eq!(RoomFinishes::for_room_type(&RoomType::Garage).trim, TrimLevel::Basic);
+        assert_eq!(bath.trim, TrimLevel::Standard);
+    }
+}
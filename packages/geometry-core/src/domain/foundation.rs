@@ -0,0 +1,275 @@
+// Foundations - the concrete under a building's lowest level
+// Plan geometry comes from the lowest level's footprint; the foundation
+// only holds its section dimensions. Footings run under the perimeter,
+// stem walls stand on the footings flush with the outside of the
+// footprint, and a slab fills the footprint inside them. Outlines are
+// assumed to have square corners when insetting by a wall thickness.
+
+use serde::{Deserialize, Serialize};
+use super::ids::{FoundationId, LevelId};
+use super::spatial::Polygon2;
+
+/// Weight of #4 bar (lb/ft), used in footings and stem walls
+const REBAR_4_WEIGHT: f64 = 0.668;
+/// Weight of #3 bar (lb/ft), used in slabs
+const REBAR_3_WEIGHT: f64 = 0.376;
+/// Slab bar spacing each way (feet, 18" o.c.)
+const SLAB_BAR_SPACING: f64 = 1.5;
+/// Stem wall bar spacing, both vertical and horizontal (feet, 24" o.c.)
+const STEM_BAR_SPACING: f64 = 2.0;
+/// Continuous bars along a footing
+const FOOTING_BARS: f64 = 2.0;
+/// Laps and waste on all rebar
+const REBAR_WASTE: f64 = 1.1;
+
+const CUBIC_FEET_PER_YARD: f64 = 27.0;
+
+/// Foundation system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FoundationType {
+    /// Slab poured on grade with a thickened edge footing
+    SlabOnGrade,
+    /// Short stem walls on footings over a vented crawlspace
+    Crawlspace,
+    /// Full-height walls on footings with a slab floor
+    Basement,
+}
+
+impl FoundationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SlabOnGrade => "slab_on_grade",
+            Self::Crawlspace => "crawlspace",
+            Self::Basement => "basement",
+        }
+    }
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "slab_on_grade" | "slab" => Some(Self::SlabOnGrade),
+            "crawlspace" | "crawl_space" | "crawl" => Some(Self::Crawlspace),
+            "basement" => Some(Self::Basement),
+            _ => None,
+        }
+    }
+}
+
+/// Section dimensions of a foundation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FoundationDimensions {
+    pub footing_width: f64,       // inches
+    pub footing_depth: f64,       // inches
+    pub stem_wall_height: f64,    // feet, top of footing to top of wall (0 = no stem wall)
+    pub stem_wall_thickness: f64, // inches
+    pub slab_thickness: f64,      // inches (0 = no slab)
+    pub gravel_depth: f64,        // inches of base under the slab
+}
+
+impl FoundationDimensions {
+    /// Typical residential dimensions for a foundation type
+    pub fn for_type(foundation_type: FoundationType) -> Self {
+        match foundation_type {
+            FoundationType::SlabOnGrade => Self {
+                footing_width: 12.0,
+                footing_depth: 12.0,
+                stem_wall_height: 0.0,
+                stem_wall_thickness: 0.0,
+                slab_thickness: 4.0,
+                gravel_depth: 4.0,
+            },
+            FoundationType::Crawlspace => Self {
+                footing_width: 16.0,
+                footing_depth: 8.0,
+                stem_wall_height: 3.0,
+                stem_wall_thickness: 8.0,
+                slab_thickness: 0.0,
+                gravel_depth: 0.0,
+            },
+            FoundationType::Basement => Self {
+                footing_width: 20.0,
+                footing_depth: 10.0,
+                stem_wall_height: 8.0,
+                stem_wall_thickness: 8.0,
+                slab_thickness: 4.0,
+                gravel_depth: 4.0,
+            },
+        }
+    }
+}
+
+/// Material quantities for a foundation under a given footprint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FoundationQuantities {
+    pub perimeter: f64,   // feet
+    pub slab_area: f64,   // square feet of slab (0 without a slab)
+    pub ground_area: f64, // square feet inside the foundation walls
+    pub footing_cy: f64,
+    pub stem_wall_cy: f64,
+    pub slab_cy: f64,
+    pub gravel_cy: f64,
+    /// Form contact area, in linear feet of 12" form board
+    pub form_lf: f64,
+    pub rebar_lbs: f64,
+    pub vapor_barrier_sqft: f64,
+}
+
+impl FoundationQuantities {
+    /// Total concrete to order
+    pub fn concrete_cy(&self) -> f64 {
+        self.footing_cy + self.stem_wall_cy + self.slab_cy
+    }
+
+    /// Quantities with `area` of the footprint poured separately, as a
+    /// garage slab is. The area gets no house slab, and no vapor retarder
+    /// (IRC R506.2.3 exempts garages).
+    pub fn without_slab(self, area: f64, dimensions: &FoundationDimensions) -> Self {
+        let slab_area = area.min(self.slab_area);
+        let slab_bars = 2.0 * slab_area / SLAB_BAR_SPACING;
+        Self {
+            slab_area: self.slab_area - slab_area,
+            slab_cy: (self.slab_cy - slab_area * dimensions.slab_thickness / 12.0 / CUBIC_FEET_PER_YARD).max(0.0),
+            gravel_cy: (self.gravel_cy - slab_area * dimensions.gravel_depth / 12.0 / CUBIC_FEET_PER_YARD).max(0.0),
+            rebar_lbs: (self.rebar_lbs - slab_bars * REBAR_3_WEIGHT * REBAR_WASTE).max(0.0),
+            vapor_barrier_sqft: (self.vapor_barrier_sqft - area).max(0.0),
+            ..self
+        }
+    }
+}
+
+/// The foundation under a building's lowest level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Foundation {
+    pub id: FoundationId,
+    pub level_id: LevelId,
+    pub foundation_type: FoundationType,
+    pub dimensions: FoundationDimensions,
+}
+
+impl Foundation {
+    pub fn new(level_id: LevelId, foundation_type: FoundationType) -> Self {
+        Self {
+            id: FoundationId::new(),
+            level_id,
+            foundation_type,
+            dimensions: FoundationDimensions::for_type(foundation_type),
+        }
+    }
+
+    /// Concrete, gravel, forms, rebar and vapor barrier for `footprint`
+    pub fn quantities(&self, footprint: &Polygon2) -> FoundationQuantities {
+        let d = &self.dimensions;
+        let area = footprint.area();
+        let perimeter = footprint.perimeter();
+        let footing_width = d.footing_width / 12.0;
+        let footing_depth = d.footing_depth / 12.0;
+        let wall_thickness = d.stem_wall_thickness / 12.0;
+        let slab_thickness = d.slab_thickness / 12.0;
+
+        let has_stem_wall = d.stem_wall_height > 0.0 && wall_thickness > 0.0;
+        let ground_area = area - ring_area(perimeter, if has_stem_wall { wall_thickness } else { 0.0 });
+        let slab_area = if slab_thickness > 0.0 { ground_area } else { 0.0 };
+
+        let (footing_length, footing_cf, stem_wall_cf, form_lf, stem_rebar_lf) = if has_stem_wall {
+            // Footing centered under the wall; both faces of each are formed
+            let centerline = perimeter - 4.0 * wall_thickness;
+            let rows = (d.stem_wall_height / STEM_BAR_SPACING).ceil();
+            let verticals = (centerline / STEM_BAR_SPACING).ceil() * d.stem_wall_height;
+            (
+                centerline,
+                centerline * footing_width * footing_depth,
+                ring_area(perimeter, wall_thickness) * d.stem_wall_height,
+                2.0 * centerline * footing_depth + 2.0 * perimeter * d.stem_wall_height,
+                rows * centerline + verticals,
+            )
+        } else {
+            // Thickened edge under the slab, trenched inside and formed
+            // only at the slab edge
+            (
+                perimeter - 4.0 * footing_width,
+                ring_area(perimeter, footing_width) * footing_depth,
+                0.0,
+                perimeter * (slab_thickness + footing_depth),
+                0.0,
+            )
+        };
+        let footing_bars = FOOTING_BARS * footing_length;
+        let slab_bars = 2.0 * slab_area / SLAB_BAR_SPACING;
+        let rebar_lbs = ((footing_bars + stem_rebar_lf) * REBAR_4_WEIGHT + slab_bars * REBAR_3_WEIGHT) * REBAR_WASTE;
+
+        FoundationQuantities {
+            perimeter,
+            slab_area,
+            ground_area,
+            footing_cy: footing_cf / CUBIC_FEET_PER_YARD,
+            stem_wall_cy: stem_wall_cf / CUBIC_FEET_PER_YARD,
+            slab_cy: slab_area * slab_thickness / CUBIC_FEET_PER_YARD,
+            gravel_cy: slab_area * d.gravel_depth / 12.0 / CUBIC_FEET_PER_YARD,
+            form_lf,
+            rebar_lbs,
+            // Under the slab, or as the crawlspace ground cover
+            vapor_barrier_sqft: ground_area,
+        }
+    }
+}
+
+/// Area of the band between a square-cornered outline and its inset by `depth`
+fn ring_area(perimeter: f64, depth: f64) -> f64 {
+    perimeter * depth - 4.0 * depth * depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slab_on_grade_quantities() {
+        let foundation = Foundation::new(LevelId::new(), FoundationType::SlabOnGrade);
+        let q = foundation.quantities(&Polygon2::rectangle(40.0, 30.0));
+
+        assert!((q.slab_area - 1200.0).abs() < 1e-10);
+        // 1200 sf x 4" = 400 cf; edge 1' x 1' around a 140' perimeter = 136 cf
+        assert!((q.slab_cy - 400.0 / 27.0).abs() < 1e-10);
+        assert!((q.footing_cy - 136.0 / 27.0).abs() < 1e-10);
+        assert_eq!(q.stem_wall_cy, 0.0);
+        assert!((q.gravel_cy - 400.0 / 27.0).abs() < 1e-10);
+        assert!((q.form_lf - 140.0 * 16.0 / 12.0).abs() < 1e-10);
+        assert!((q.vapor_barrier_sqft - 1200.0).abs() < 1e-10);
+
+        // A 20' x 20' garage poured on its own
+        let rest = q.without_slab(400.0, &foundation.dimensions);
+        assert!((rest.slab_cy - 800.0 * 4.0 / 12.0 / 27.0).abs() < 1e-10);
+        assert!((rest.vapor_barrier_sqft - 800.0).abs() < 1e-10);
+        assert_eq!(rest.footing_cy, q.footing_cy);
+        assert!(rest.rebar_lbs < q.rebar_lbs);
+    }
+
+    #[test]
+    fn test_basement_quantities() {
+        let foundation = Foundation::new(LevelId::new(), FoundationType::Basement);
+        let q = foundation.quantities(&Polygon2::rectangle(40.0, 30.0));
+
+        // 8" walls: inside 38.667 x 28.667
+        let inside = (40.0 - 16.0 / 12.0) * (30.0 - 16.0 / 12.0);
+        assert!((q.slab_area - inside).abs() < 1e-9);
+        assert!((q.stem_wall_cy - (1200.0 - inside) * 8.0 / 27.0).abs() < 1e-9);
+        // Footing along the wall centerline: (140 - 4 x 8/12) x 20" x 10"
+        let centerline = 140.0 - 4.0 * 8.0 / 12.0;
+        assert!((q.footing_cy - centerline * (20.0 / 12.0) * (10.0 / 12.0) / 27.0).abs() < 1e-9);
+        assert!((q.concrete_cy() - (q.footing_cy + q.stem_wall_cy + q.slab_cy)).abs() < 1e-12);
+        assert!(q.rebar_lbs > 0.0);
+    }
+
+    #[test]
+    fn test_crawlspace_has_no_slab() {
+        let foundation = Foundation::new(LevelId::new(), FoundationType::Crawlspace);
+        let q = foundation.quantities(&Polygon2::rectangle(40.0, 30.0));
+        assert_eq!(q.slab_cy, 0.0);
+        assert_eq!(q.gravel_cy, 0.0);
+        assert!(q.stem_wall_cy > 0.0);
+        // Ground cover still goes down over the crawlspace floor
+        assert!(q.vapor_barrier_sqft > 1100.0);
+        assert_eq!(FoundationType::parse("crawl"), Some(FoundationType::Crawlspace));
+    }
+}
@@ -2,17 +2,18 @@
 // Provides data structures for generating and managing wood/steel framing
 
 use serde::{Deserialize, Serialize};
-use super::ids::{FramingMemberId, FramingLayoutId, WallId, OpeningId};
+use super::ids::{FramingMemberId, FramingLayoutId, FloorFramingLayoutId, LevelId, WallId, OpeningId};
 use super::spatial::Point3;
 
 /// Standard lumber sizes (nominal dimensions)
 /// Actual dimensions are smaller due to milling
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum LumberSize {
     /// 2x4 nominal (1.5" x 3.5" actual)
     TwoByFour,
     /// 2x6 nominal (1.5" x 5.5" actual)
+    #[default]
     TwoBySix,
     /// 2x8 nominal (1.5" x 7.25" actual)
     TwoByEight,
@@ -67,12 +68,6 @@ impl LumberSize {
     }
 }
 
-impl Default for LumberSize {
-    fn default() -> Self {
-        LumberSize::TwoBySix
-    }
-}
-
 /// Type of framing member within a wall assembly
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -97,6 +92,28 @@ pub enum FramingMemberType {
     Sill,
     /// Horizontal blocking for fire stopping
     FireBlocking,
+    /// Extra stud at a corner, giving the other wall and the interior
+    /// finish something to nail to
+    CornerStud,
+    /// Stud flanking a partition that tees into the wall
+    PartitionBacking,
+    /// Row of blocking between studs part way up the wall (backing for
+    /// cabinets, rails, etc.)
+    MidHeightBlocking,
+    /// Floor joist
+    FloorJoist,
+    /// Rim board closing off the joist ends around the floor's edge
+    RimBoard,
+    /// Solid blocking between floor joists
+    FloorBlocking,
+    /// Sheet of subfloor sheathing laid over the joists
+    SubfloorSheet,
+    /// Board bolted to the building that a deck's joists hang from
+    Ledger,
+    /// Decking board laid across deck joists
+    DeckBoard,
+    /// Post carrying a deck's guard rail
+    RailingPost,
 }
 
 impl FramingMemberType {
@@ -113,6 +130,16 @@ impl FramingMemberType {
             FramingMemberType::CrippleStud => "Cripple Stud",
             FramingMemberType::Sill => "Sill",
             FramingMemberType::FireBlocking => "Fire Blocking",
+            FramingMemberType::CornerStud => "Corner Stud",
+            FramingMemberType::PartitionBacking => "Partition Backing",
+            FramingMemberType::MidHeightBlocking => "Mid-Height Blocking",
+            FramingMemberType::FloorJoist => "Floor Joist",
+            FramingMemberType::RimBoard => "Rim Board",
+            FramingMemberType::FloorBlocking => "Floor Blocking",
+            FramingMemberType::SubfloorSheet => "Subfloor Sheet",
+            FramingMemberType::Ledger => "Ledger",
+            FramingMemberType::DeckBoard => "Deck Board",
+            FramingMemberType::RailingPost => "Railing Post",
         }
     }
 
@@ -124,6 +151,9 @@ impl FramingMemberType {
                 | FramingMemberType::KingStud
                 | FramingMemberType::JackStud
                 | FramingMemberType::CrippleStud
+                | FramingMemberType::CornerStud
+                | FramingMemberType::PartitionBacking
+                | FramingMemberType::RailingPost
         )
     }
 
@@ -134,10 +164,11 @@ impl FramingMemberType {
 }
 
 /// Material type for framing members
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum FramingMaterial {
     /// Spruce-Pine-Fir (most common residential)
+    #[default]
     SPF,
     /// Douglas Fir (stronger, often for headers)
     DF,
@@ -170,17 +201,12 @@ impl FramingMaterial {
     }
 }
 
-impl Default for FramingMaterial {
-    fn default() -> Self {
-        FramingMaterial::SPF
-    }
-}
-
 /// Header type for openings based on span and load
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum HeaderType {
     /// Double 2x lumber (typical for short spans)
+    #[default]
     DoubleLumber,
     /// Triple 2x lumber (medium spans)
     TripleLumber,
@@ -207,6 +233,11 @@ impl HeaderType {
         }
     }
 
+    /// Built up from plies of dimensional lumber rather than ordered as one piece
+    pub fn is_built_up(&self) -> bool {
+        matches!(self, HeaderType::DoubleLumber | HeaderType::TripleLumber)
+    }
+
     /// Suggest header type based on span width (in inches)
     pub fn for_span(span: f64, is_load_bearing: bool) -> Self {
         if !is_load_bearing {
@@ -231,12 +262,47 @@ impl HeaderType {
     }
 }
 
-impl Default for HeaderType {
-    fn default() -> Self {
-        HeaderType::DoubleLumber
+/// Header chosen for an opening: plies of one lumber size, or an LVL beam
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeaderSize {
+    pub header_type: HeaderType,
+    /// Size of each ply
+    pub lumber_size: LumberSize,
+    pub plies: u8,
+}
+
+impl HeaderSize {
+    /// Depth of the header (inches)
+    pub fn depth(&self) -> f64 {
+        self.lumber_size.actual_dimensions().1
+    }
+
+    /// Thickness of all plies together (inches)
+    pub fn thickness(&self) -> f64 {
+        self.lumber_size.actual_dimensions().0 * self.plies as f64
+    }
+
+    /// Takeoff description, e.g. "2-2x10" or "2-1.75x11.875 LVL"
+    pub fn description(&self) -> String {
+        let name = format!("{}-{}", self.plies, self.lumber_size.nominal_name());
+        match self.header_type {
+            HeaderType::LVL => format!("{} LVL", name),
+            _ => name,
+        }
     }
 }
 
+/// The header a layout uses over one of its openings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderSelection {
+    pub opening_id: OpeningId,
+    /// Rough opening width the header spans (inches)
+    pub span: f64,
+    /// Length of each ply, bearing on the jack studs (inches)
+    pub length: f64,
+    pub size: HeaderSize,
+}
+
 /// An individual framing member (stud, plate, header, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FramingMember {
@@ -250,8 +316,8 @@ pub struct FramingMember {
     pub length: f64,
     /// Rotation around the vertical axis in radians (for horizontal members)
     pub rotation: f64,
-    /// The wall this member belongs to
-    pub wall_id: WallId,
+    /// The wall this member belongs to (None for floor framing)
+    pub wall_id: Option<WallId>,
     /// Optional: the opening this member is associated with (for jack studs, headers, etc.)
     pub opening_id: Option<OpeningId>,
 }
@@ -274,7 +340,28 @@ impl FramingMember {
             position,
             length,
             rotation,
-            wall_id,
+            wall_id: Some(wall_id),
+            opening_id: None,
+        }
+    }
+
+    /// Create a floor framing member, which belongs to no wall
+    pub fn floor(
+        member_type: FramingMemberType,
+        lumber_size: LumberSize,
+        position: Point3,
+        length: f64,
+        rotation: f64,
+    ) -> Self {
+        Self {
+            id: FramingMemberId::new(),
+            member_type,
+            lumber_size,
+            material: FramingMaterial::SPF,
+            position,
+            length,
+            rotation,
+            wall_id: None,
             opening_id: None,
         }
     }
@@ -371,6 +458,10 @@ pub struct RoughOpening {
     pub header_type: HeaderType,
     /// Whether a sill is required (windows yes, doors no)
     pub requires_sill: bool,
+    /// Vehicle door: framed to the door size, with a header that carries
+    /// the wall above whether or not the wall is bearing
+    #[serde(default)]
+    pub garage_door: bool,
 }
 
 impl RoughOpening {
@@ -389,6 +480,7 @@ impl RoughOpening {
             header_depth: 7.25, // Default to 2x8 header depth
             header_type: HeaderType::DoubleLumber,
             requires_sill: false,
+            garage_door: false,
         }
     }
 
@@ -413,6 +505,7 @@ impl RoughOpening {
             header_depth: 7.25,
             header_type: HeaderType::for_span(ro_width, is_load_bearing),
             requires_sill: true,
+            garage_door: false,
         }
     }
 
@@ -437,9 +530,40 @@ impl RoughOpening {
             header_depth: 7.25,
             header_type: HeaderType::for_span(ro_width, is_load_bearing),
             requires_sill: false,
+            garage_door: false,
+        }
+    }
+
+    /// Create a rough opening for a garage door. Sectional doors mount on
+    /// jambs set in the opening, so it is framed to the door size, with
+    /// doubled jacks under the long header.
+    pub fn for_garage_door(
+        opening_id: OpeningId,
+        width: f64,
+        height: f64,
+        position_along_wall: f64,
+    ) -> Self {
+        Self {
+            opening_id,
+            width,
+            height,
+            position_along_wall,
+            jack_stud_count: 2,
+            header_depth: 7.25,
+            header_type: HeaderType::for_span(width, true),
+            requires_sill: false,
+            garage_door: true,
         }
     }
 
+    /// Use a manufacturer's rough opening size in place of the allowance
+    pub fn with_size(mut self, width: f64, height: f64) -> Self {
+        self.width = width;
+        self.height = height;
+        self.jack_stud_count = if width > 48.0 || self.garage_door { 2 } else { 1 };
+        self
+    }
+
     /// Set header type
     pub fn with_header_type(mut self, header_type: HeaderType) -> Self {
         self.header_type = header_type;
@@ -451,6 +575,12 @@ impl RoughOpening {
         self.jack_stud_count = count;
         self
     }
+
+    /// Set header depth
+    pub fn with_header_depth(mut self, depth: f64) -> Self {
+        self.header_depth = depth;
+        self
+    }
 }
 
 /// Complete framing layout for a wall
@@ -471,6 +601,9 @@ pub struct FramingLayout {
     pub total_board_feet: f64,
     /// Total stud count (for material estimation)
     pub stud_count: u32,
+    /// Header sized for each opening
+    #[serde(default)]
+    pub headers: Vec<HeaderSelection>,
 }
 
 impl FramingLayout {
@@ -484,6 +617,7 @@ impl FramingLayout {
             lumber_size,
             total_board_feet: 0.0,
             stud_count: 0,
+            headers: Vec::new(),
         }
     }
 
@@ -531,12 +665,111 @@ impl FramingLayout {
                         | FramingMemberType::KingStud
                         | FramingMemberType::JackStud
                         | FramingMemberType::CrippleStud
+                        | FramingMemberType::CornerStud
+                        | FramingMemberType::PartitionBacking
                 )
             })
             .count() as u32;
     }
 }
 
+/// How the studs at a wall corner are arranged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CornerStyle {
+    /// End stud plus two more beside it
+    #[default]
+    ThreeStud,
+    /// End stud plus one turned flat, leaving room for insulation
+    California,
+}
+
+impl CornerStyle {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CornerStyle::ThreeStud => "Three-Stud Corner",
+            CornerStyle::California => "California Corner",
+        }
+    }
+}
+
+/// How a level's floor is framed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FloorFramingConfig {
+    /// Plan direction the joists run, radians from the +x axis
+    pub joist_direction: f64,
+    /// Joist spacing on-center (inches)
+    pub joist_spacing: f64,
+    /// Lumber size for joists, rim boards, and blocking
+    pub lumber_size: LumberSize,
+    /// Subfloor sheathing thickness (inches)
+    pub sheathing_thickness: f64,
+}
+
+impl Default for FloorFramingConfig {
+    fn default() -> Self {
+        // Default: 2x10 joists 16" OC running along x, 3/4" subfloor
+        Self {
+            joist_direction: 0.0,
+            joist_spacing: 16.0,
+            lumber_size: LumberSize::TwoByTen,
+            sheathing_thickness: 0.75,
+        }
+    }
+}
+
+/// Floor framing for a level: joists, rim boards, blocking, and subfloor
+/// sheets. Positions are in inches in plan, with z measured from the level
+/// elevation (the top of the subfloor).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FloorFramingLayout {
+    pub id: FloorFramingLayoutId,
+    pub level_id: LevelId,
+    pub config: FloorFramingConfig,
+    pub members: Vec<FramingMember>,
+    /// Board feet of lumber (sheathing not included)
+    pub total_board_feet: f64,
+    pub joist_count: u32,
+    /// Full 4x8 sheets bought, counting cut sheets as whole
+    pub sheathing_sheets: u32,
+}
+
+impl FloorFramingLayout {
+    pub fn new(level_id: LevelId, config: FloorFramingConfig) -> Self {
+        Self {
+            id: FloorFramingLayoutId::new(),
+            level_id,
+            config,
+            members: Vec::new(),
+            total_board_feet: 0.0,
+            joist_count: 0,
+            sheathing_sheets: 0,
+        }
+    }
+
+    /// Get all members of a specific type
+    pub fn members_of_type(&self, member_type: FramingMemberType) -> Vec<&FramingMember> {
+        self.members
+            .iter()
+            .filter(|m| m.member_type == member_type)
+            .collect()
+    }
+
+    /// Recalculate totals from members
+    pub fn recalculate_totals(&mut self) {
+        let count = |member_type| self.members.iter().filter(|m| m.member_type == member_type).count() as u32;
+        self.joist_count = count(FramingMemberType::FloorJoist);
+        self.sheathing_sheets = count(FramingMemberType::SubfloorSheet);
+        self.total_board_feet = self
+            .members
+            .iter()
+            .filter(|m| m.member_type != FramingMemberType::SubfloorSheet)
+            .map(|m| m.board_feet())
+            .sum();
+    }
+}
+
 /// Configuration for wall framing (to be added to Wall struct)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WallFramingConfig {
@@ -550,6 +783,16 @@ pub struct WallFramingConfig {
     pub is_load_bearing: bool,
     /// Whether fire blocking is required (typically every 10')
     pub fire_blocking_required: bool,
+    /// Corner stud arrangement where this wall runs through a corner
+    #[serde(default)]
+    pub corner_style: CornerStyle,
+    /// Heights of blocking rows above the bottom of the wall (inches)
+    #[serde(default)]
+    pub blocking_rows: Vec<f64>,
+    /// Width of floor and roof bearing on this wall, for sizing headers
+    /// (feet). None assumes a typical 28' wide building.
+    #[serde(default)]
+    pub tributary_width: Option<f64>,
 }
 
 impl WallFramingConfig {
@@ -565,6 +808,9 @@ impl WallFramingConfig {
             double_top_plate,
             is_load_bearing,
             fire_blocking_required: false,
+            corner_style: CornerStyle::ThreeStud,
+            blocking_rows: Vec::new(),
+            tributary_width: None,
         }
     }
 
@@ -576,6 +822,9 @@ impl WallFramingConfig {
             double_top_plate: true,
             is_load_bearing: true,
             fire_blocking_required: false,
+            corner_style: CornerStyle::ThreeStud,
+            blocking_rows: Vec::new(),
+            tributary_width: None,
         }
     }
 
@@ -587,6 +836,9 @@ impl WallFramingConfig {
             double_top_plate: false,
             is_load_bearing: false,
             fire_blocking_required: false,
+            corner_style: CornerStyle::ThreeStud,
+            blocking_rows: Vec::new(),
+            tributary_width: None,
         }
     }
 
@@ -598,6 +850,9 @@ impl WallFramingConfig {
             double_top_plate: true,
             is_load_bearing: true,
             fire_blocking_required: false,
+            corner_style: CornerStyle::ThreeStud,
+            blocking_rows: Vec::new(),
+            tributary_width: None,
         }
     }
 
@@ -606,6 +861,24 @@ impl WallFramingConfig {
         self.fire_blocking_required = required;
         self
     }
+
+    /// Set corner stud arrangement
+    pub fn with_corner_style(mut self, corner_style: CornerStyle) -> Self {
+        self.corner_style = corner_style;
+        self
+    }
+
+    /// Set blocking row heights (inches above the bottom of the wall)
+    pub fn with_blocking_rows(mut self, heights: Vec<f64>) -> Self {
+        self.blocking_rows = heights;
+        self
+    }
+
+    /// Set the tributary width headers are sized for (feet)
+    pub fn with_tributary_width(mut self, width: f64) -> Self {
+        self.tributary_width = Some(width);
+        self
+    }
 }
 
 impl Default for WallFramingConfig {
@@ -617,6 +890,9 @@ impl Default for WallFramingConfig {
             double_top_plate: true,
             is_load_bearing: true,
             fire_blocking_required: false,
+            corner_style: CornerStyle::ThreeStud,
+            blocking_rows: Vec::new(),
+            tributary_width: None,
         }
     }
 }
@@ -0,0 +1,100 @@
+// Garages - the slab, vehicle doors and separation a garage room needs
+// A garage room gets its own slab, poured thicker than the house slab and
+// reinforced for vehicle loads, sloped to drain toward the vehicle door
+// (IRC R309.1). Walls it shares with the dwelling take the fire separation
+// assembly (R302.6), and its vehicle doors are framed to the door size
+// with headers sized to carry the wall above.
+
+use serde::{Deserialize, Serialize};
+use super::ids::{GarageId, LevelId, RoomId};
+use super::spatial::Polygon2;
+
+/// Slab thickness for vehicle loads (inches)
+pub const GARAGE_SLAB_THICKNESS: f64 = 5.0;
+
+/// Fall toward the vehicle door (inches per foot)
+pub const GARAGE_SLAB_SLOPE: f64 = 0.125;
+
+/// Gravel base under the slab (inches)
+const GARAGE_GRAVEL_DEPTH: f64 = 4.0;
+/// Weight of #4 bar (lb/ft)
+const REBAR_4_WEIGHT: f64 = 0.668;
+/// Bar spacing each way (feet, 18" o.c.)
+const BAR_SPACING: f64 = 1.5;
+/// Laps and waste on the rebar
+const REBAR_WASTE: f64 = 1.1;
+
+const CUBIC_FEET_PER_YARD: f64 = 27.0;
+
+/// Slab settings for a garage room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Garage {
+    pub id: GarageId,
+    pub level_id: LevelId,
+    pub room_id: RoomId,
+    /// Inches
+    pub slab_thickness: f64,
+    /// Fall toward the vehicle door (inches per foot)
+    pub slab_slope: f64,
+}
+
+impl Garage {
+    pub fn new(level_id: LevelId, room_id: RoomId) -> Self {
+        Self {
+            id: GarageId::new(),
+            level_id,
+            room_id,
+            slab_thickness: GARAGE_SLAB_THICKNESS,
+            slab_slope: GARAGE_SLAB_SLOPE,
+        }
+    }
+
+    /// Quantities for the slab over `outline`, falling across `run` feet
+    /// from the back of the garage to the vehicle door
+    pub fn slab(&self, outline: &Polygon2, run: f64) -> GarageSlab {
+        let area = outline.area();
+        // #4 bars both ways across the slab
+        let bars = 2.0 * area / BAR_SPACING;
+        GarageSlab {
+            area,
+            concrete_cy: area * self.slab_thickness / 12.0 / CUBIC_FEET_PER_YARD,
+            gravel_cy: area * GARAGE_GRAVEL_DEPTH / 12.0 / CUBIC_FEET_PER_YARD,
+            rebar_lbs: bars * REBAR_4_WEIGHT * REBAR_WASTE,
+            run,
+            fall: run * self.slab_slope,
+        }
+    }
+}
+
+/// Material quantities and drainage of a garage slab
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GarageSlab {
+    /// Square feet
+    pub area: f64,
+    pub concrete_cy: f64,
+    pub gravel_cy: f64,
+    pub rebar_lbs: f64,
+    /// Distance the slab drains across to the vehicle door (feet); 0
+    /// without a door
+    pub run: f64,
+    /// Drop from the back of the slab to the door (inches)
+    pub fall: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_garage_slab() {
+        let garage = Garage::new(LevelId::new(), RoomId::new());
+        let slab = garage.slab(&Polygon2::rectangle(24.0, 22.0), 22.0);
+
+        // 528 sf at 5" = 220 cf
+        assert!((slab.concrete_cy - 220.0 / 27.0).abs() < 1e-10);
+        assert!((slab.gravel_cy - 176.0 / 27.0).abs() < 1e-10);
+        // 704' of #4 bar each way combined, plus 10%
+        assert!((slab.rebar_lbs - 704.0 * 0.668 * 1.1).abs() < 1e-9);
+        assert!((slab.fall - 2.75).abs() < 1e-10);
+    }
+}
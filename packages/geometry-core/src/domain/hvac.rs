@@ -0,0 +1,27 @@
+// HVAC zones - groups of rooms on one thermostat
+// A zone belongs to a building and lists the rooms it serves, on any of the
+// building's levels. Loads, registers and duct runs are estimated from the
+// model in analysis::hvac rather than stored.
+
+use serde::{Deserialize, Serialize};
+use super::ids::{BuildingId, HVACZoneId, RoomId};
+
+/// A named group of rooms served together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HvacZone {
+    pub id: HVACZoneId,
+    pub building_id: BuildingId,
+    pub name: String,
+    pub room_ids: Vec<RoomId>,
+}
+
+impl HvacZone {
+    pub fn new(building_id: BuildingId, name: impl Into<String>) -> Self {
+        Self {
+            id: HVACZoneId::new(),
+            building_id,
+            name: name.into(),
+            room_ids: Vec::new(),
+        }
+    }
+}
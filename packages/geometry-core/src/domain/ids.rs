@@ -9,6 +9,7 @@ use uuid::Uuid;
 macro_rules! define_id {
     ($name:ident) => {
         #[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
         #[serde(transparent)]
         pub struct $name(Uuid);
 
@@ -69,7 +70,15 @@ define_id!(WallId);
 define_id!(FloorSystemId);
 define_id!(RoofSystemId);
 define_id!(RoofSurfaceId);
+define_id!(RoofId);
+define_id!(StairId);
+define_id!(FloorSlabId);
+define_id!(CeilingAssemblyId);
+define_id!(FoundationId);
 define_id!(BeamId);
+define_id!(PostId);
+define_id!(DeckId);
+define_id!(GarageId);
 
 // Room/Opening IDs - Phase 3
 define_id!(RoomId);
@@ -105,6 +114,20 @@ define_id!(SolidId);
 // Framing IDs - Phase 7 (Wall Framing)
 define_id!(FramingMemberId);
 define_id!(FramingLayoutId);
+define_id!(FloorFramingLayoutId);
+
+// Project data IDs
+define_id!(FilterId);
+define_id!(ScriptId);
+define_id!(SnapshotId);
+define_id!(DetailCalloutId);
+define_id!(ConstraintId);
+
+// Access control IDs
+define_id!(UserId);
+
+// Background job IDs
+define_id!(JobId);
 
 #[cfg(test)]
 mod tests {
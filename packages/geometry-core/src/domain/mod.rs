@@ -8,29 +8,72 @@ pub mod events;
 pub mod wall;
 pub mod room;
 pub mod opening;
+pub mod opening_catalog;
 pub mod framing;
 pub mod error;
 pub mod costing;
+pub mod filter;
+pub mod script;
+pub mod constraint;
+pub mod detail;
+pub mod roof;
+pub mod finish;
+pub mod stair;
+pub mod slab;
+pub mod foundation;
+pub mod structural;
+pub mod electrical;
+pub mod plumbing;
+pub mod hvac;
+pub mod deck;
+pub mod garage;
+pub mod terrain;
+pub mod access;
 
 // Re-export commonly used types
 pub use ids::*;
-pub use spatial::{Point2, Point3, Vector3, Polygon2, Polyline3};
+pub use spatial::{MirrorAxis, Point2, Point3, Vector3, Polygon2, Polyline3};
 pub use project::{
-    Project, Site, Setbacks, Building, Level, Footprint,
+    Project, Site, Setbacks, ZoningRules, Building, Level, Footprint, FootprintRole,
     Grid, GridAxis, GridDirection,
-    UnitSystem, CodeRegion, BuildingStats,
+    UnitSystem, CodeRegion, BuildingStats, BuildingOccupancy, ProjectStats,
+    ProjectPage, ProjectQuery, ProjectSort,
 };
-pub use events::{Event, EventId, EventKind, EventLog, SolidSource};
-pub use wall::{WallLayer, WallAssembly, Wall};
+pub use events::{Event, EventId, EventKind, EventLog, EventPage, EventQuery, SolidSource};
+pub use wall::{WallLayer, WallAssembly, Wall, WallArc, WallJoin, AssemblyRole};
 pub use room::{RoomType, Room};
-pub use opening::{OpeningType, Opening, WindowProperties, DoorProperties};
+pub use opening::{OpeningType, Opening, OpeningDetails, OpeningEdit, WindowProperties, DoorProperties};
+pub use opening_catalog::{ClearOpening, WindowType, DoorType, OpeningCatalog};
 pub use framing::{
     LumberSize, FramingMemberType, FramingMaterial, HeaderType,
-    FramingMember, FramingLayout, RoughOpening, WallFramingConfig,
+    FramingMember, FramingLayout, RoughOpening, WallFramingConfig, CornerStyle, HeaderSize, HeaderSelection,
+    FloorFramingConfig, FloorFramingLayout,
 };
 pub use error::{StructuredError, ErrorCode, EntityType};
 pub use costing::{
     PricingUnit, CostCategory, LaborType, MaterialType,
     UnitPrice, LaborRate, CostLineItem, CostEstimate, PriceTable,
-    CostLineItemId, CostEstimateId,
+    CostLineItemId, CostEstimateId, CsiDivision, DivisionSubtotal, Markups, MarkupLine, QuantitySource,
 };
+pub use filter::{SavedFilter, FilterTarget, FilterCondition, FilterResult};
+pub use script::SavedScript;
+pub use constraint::{Constraint, ConstraintKind};
+pub use detail::{DetailKind, DetailReference, DetailCallout, DetailScheduleEntry};
+pub use roof::{RoofType, Roof, RoofPlan, RoofPlane, RoofTopology};
+pub use finish::{FinishSurface, RoomFinishes, TrimLevel, FinishScheduleEntry};
+pub use stair::{Stair, StairRun};
+pub use slab::{SlabLayer, SlabBoundary, FloorSlab, CeilingAssembly};
+pub use foundation::{FoundationType, FoundationDimensions, FoundationQuantities, Foundation};
+pub use structural::{MemberProfile, Beam, Post};
+pub use electrical::{
+    ElectricalDeviceKind, ElectricalDevice, DeviceCounts, DeviceScheduleEntry, DeviceSchedule,
+    ElectricalLayoutSummary,
+};
+pub use plumbing::{
+    PlumbingFixtureKind, PlumbingFixture, FixtureClearance, WetWallGroup, WetWallAnalysis,
+};
+pub use hvac::HvacZone;
+pub use deck::{DeckKind, Deck, DeckFramingLayout};
+pub use garage::{Garage, GarageSlab};
+pub use terrain::{Contour, Terrain};
+pub use access::{AccessControl, AccessError, ApiKey, ProjectRole, User};
@@ -15,6 +15,7 @@ pub enum OpeningType {
 
 impl OpeningType {
     /// Parse from string
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "window" => Self::Window,
@@ -23,6 +24,11 @@ impl OpeningType {
         }
     }
 
+    /// Whether this is a vehicle door, e.g. `Other("garage_door")`
+    pub fn is_garage_door(&self) -> bool {
+        matches!(self, Self::Other(name) if name.to_lowercase().contains("garage"))
+    }
+
     /// Get display name
     pub fn display_name(&self) -> String {
         match self {
@@ -102,6 +108,38 @@ impl DoorProperties {
     }
 }
 
+/// Changes to an opening's placement, size or type; fields left as None
+/// keep their current value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpeningEdit {
+    pub position: Option<f64>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub sill_height: Option<f64>,
+    pub opening_type: Option<OpeningType>,
+}
+
+/// What an opening carries beyond its size and placement: the catalog unit
+/// it was placed from and its window or door properties
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpeningDetails {
+    pub catalog_code: Option<String>,
+    pub rough_size: Option<(f64, f64)>,
+    pub window_properties: Option<WindowProperties>,
+    pub door_properties: Option<DoorProperties>,
+}
+
+impl OpeningDetails {
+    pub fn is_empty(&self) -> bool {
+        self.catalog_code.is_none()
+            && self.rough_size.is_none()
+            && self.window_properties.is_none()
+            && self.door_properties.is_none()
+    }
+}
+
 /// An opening (window, door, etc.) in a wall
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Opening {
@@ -114,6 +152,13 @@ pub struct Opening {
     pub sill_height: f64,           // Height from floor to bottom of opening
     pub window_properties: Option<WindowProperties>,
     pub door_properties: Option<DoorProperties>,
+    /// Catalog entry the opening was placed from
+    #[serde(default)]
+    pub catalog_code: Option<String>,
+    /// Rough opening (width, height) given by the unit's manufacturer, in
+    /// the same units as the opening; None uses the framing allowance
+    #[serde(default)]
+    pub rough_size: Option<(f64, f64)>,
 }
 
 impl Opening {
@@ -135,6 +180,8 @@ impl Opening {
             sill_height,
             window_properties: None,
             door_properties: None,
+            catalog_code: None,
+            rough_size: None,
         }
     }
 
@@ -157,6 +204,8 @@ impl Opening {
             sill_height,
             window_properties: Some(properties),
             door_properties: None,
+            catalog_code: None,
+            rough_size: None,
         }
     }
 
@@ -178,6 +227,8 @@ impl Opening {
             sill_height: 0.0,  // Doors typically start at floor level
             window_properties: None,
             door_properties: Some(properties),
+            catalog_code: None,
+            rough_size: None,
         }
     }
 
@@ -193,6 +244,24 @@ impl Opening {
         self
     }
 
+    /// The opening's catalog unit and properties
+    pub fn details(&self) -> OpeningDetails {
+        OpeningDetails {
+            catalog_code: self.catalog_code.clone(),
+            rough_size: self.rough_size,
+            window_properties: self.window_properties.clone(),
+            door_properties: self.door_properties.clone(),
+        }
+    }
+
+    /// Replace the opening's catalog unit and properties
+    pub fn set_details(&mut self, details: OpeningDetails) {
+        self.catalog_code = details.catalog_code;
+        self.rough_size = details.rough_size;
+        self.window_properties = details.window_properties;
+        self.door_properties = details.door_properties;
+    }
+
     /// Calculate the area of the opening
     pub fn area(&self) -> f64 {
         self.width * self.height
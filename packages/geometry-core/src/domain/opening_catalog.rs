@@ -0,0 +1,231 @@
+// Opening catalog - manufacturer window and door sizes
+// Units are named by the usual size code: feet and inches of width then
+// height, so a 3068 door is 3'0" x 6'8". Each entry carries the rough
+// opening the manufacturer calls for, its price, and for windows the clear
+// opening used to check emergency escape (egress).
+
+use serde::{Deserialize, Serialize};
+
+/// Smallest net clear opening for an egress window (sq ft), IRC R310.2.1
+pub const EGRESS_MIN_AREA: f64 = 5.7;
+/// Smallest clear opening width (inches)
+pub const EGRESS_MIN_WIDTH: f64 = 20.0;
+/// Smallest clear opening height (inches)
+pub const EGRESS_MIN_HEIGHT: f64 = 24.0;
+/// Highest the clear opening may start above the floor (inches), IRC R310.2.2
+pub const EGRESS_MAX_SILL: f64 = 44.0;
+
+/// Clear opening of an operable window when fully open (inches)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClearOpening {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ClearOpening {
+    /// Net clear area (sq ft)
+    pub fn area(&self) -> f64 {
+        self.width * self.height / 144.0
+    }
+}
+
+/// A window unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowType {
+    /// Size code, e.g. "3050"
+    pub code: String,
+    pub name: String,
+    /// Unit size (feet)
+    pub width: f64,
+    pub height: f64,
+    /// Rough opening called for (inches)
+    pub rough_width: f64,
+    pub rough_height: f64,
+    /// None for fixed units, which can't be used for escape
+    #[serde(default)]
+    pub clear_opening: Option<ClearOpening>,
+    #[serde(default = "default_u_value")]
+    pub u_value: f64,
+    #[serde(default = "default_shgc")]
+    pub shgc: f64,
+    #[serde(default)]
+    pub unit_cost: f64,
+}
+
+impl WindowType {
+    /// Whether the window qualifies as an emergency escape opening when
+    /// its sill sits `sill_height` (inches) above the floor
+    pub fn meets_egress(&self, sill_height: f64) -> bool {
+        self.clear_opening.is_some_and(|clear| {
+            clear.area() >= EGRESS_MIN_AREA
+                && clear.width >= EGRESS_MIN_WIDTH
+                && clear.height >= EGRESS_MIN_HEIGHT
+                && sill_height <= EGRESS_MAX_SILL
+        })
+    }
+}
+
+/// A door unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoorType {
+    /// Size code, e.g. "3068"
+    pub code: String,
+    pub name: String,
+    /// Door slab size (feet)
+    pub width: f64,
+    pub height: f64,
+    /// Rough opening called for (inches)
+    pub rough_width: f64,
+    pub rough_height: f64,
+    #[serde(default)]
+    pub is_exterior: bool,
+    /// Fire rating in minutes
+    #[serde(default)]
+    pub fire_rating: Option<u32>,
+    #[serde(default)]
+    pub unit_cost: f64,
+}
+
+fn default_u_value() -> f64 {
+    0.30
+}
+
+fn default_shgc() -> f64 {
+    0.30
+}
+
+/// Windows and doors openings can be placed from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpeningCatalog {
+    #[serde(default)]
+    pub windows: Vec<WindowType>,
+    #[serde(default)]
+    pub doors: Vec<DoorType>,
+}
+
+impl Default for OpeningCatalog {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl OpeningCatalog {
+    /// Common stock sizes with typical rough openings and prices
+    pub fn standard() -> Self {
+        let window = |code: &str, name: &str, width: f64, height: f64, clear: Option<(f64, f64)>, unit_cost: f64| WindowType {
+            code: code.to_string(),
+            name: name.to_string(),
+            // Units are made 1/2" under their nominal size, which is the rough opening
+            width: (width - 0.5) / 12.0,
+            height: (height - 0.5) / 12.0,
+            rough_width: width,
+            rough_height: height,
+            clear_opening: clear.map(|(width, height)| ClearOpening { width, height }),
+            u_value: default_u_value(),
+            shgc: default_shgc(),
+            unit_cost,
+        };
+        let door = |code: &str, name: &str, width: f64, height: f64, is_exterior: bool, unit_cost: f64| DoorType {
+            code: code.to_string(),
+            name: name.to_string(),
+            width: width / 12.0,
+            height: height / 12.0,
+            // Prehung units need 2" over the slab width and 2-1/2" over its height
+            rough_width: width + 2.0,
+            rough_height: height + 2.5,
+            is_exterior,
+            fire_rating: None,
+            unit_cost,
+        };
+
+        Self {
+            windows: vec![
+                window("2030", "2030 Single Hung", 24.0, 36.0, Some((19.5, 14.0)), 220.0),
+                window("3050", "3050 Single Hung", 36.0, 60.0, Some((31.5, 26.0)), 380.0),
+                window("3060", "3060 Single Hung", 36.0, 72.0, Some((31.5, 32.0)), 420.0),
+                window("2448C", "2448 Casement", 24.0, 48.0, Some((20.5, 43.0)), 450.0),
+                window("4040", "4040 Slider", 48.0, 48.0, Some((21.0, 44.0)), 400.0),
+                window("4040F", "4040 Picture", 48.0, 48.0, None, 300.0),
+            ],
+            doors: vec![
+                door("2468", "2468 Interior", 28.0, 80.0, false, 180.0),
+                door("2668", "2668 Interior", 30.0, 80.0, false, 190.0),
+                door("2868", "2868 Interior", 32.0, 80.0, false, 200.0),
+                door("3068", "3068 Exterior", 36.0, 80.0, true, 650.0),
+                door("3080", "3080 Exterior", 36.0, 96.0, true, 850.0),
+            ],
+        }
+    }
+
+    /// Parse a catalog from JSON: { "windows": [...], "doors": [...] }
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Add another catalog's entries, replacing any with the same code
+    pub fn merge(&mut self, other: OpeningCatalog) {
+        for window in other.windows {
+            self.windows.retain(|w| w.code != window.code);
+            self.windows.push(window);
+        }
+        for door in other.doors {
+            self.doors.retain(|d| d.code != door.code);
+            self.doors.push(door);
+        }
+    }
+
+    pub fn window(&self, code: &str) -> Option<&WindowType> {
+        self.windows.iter().find(|w| w.code == code)
+    }
+
+    pub fn door(&self, code: &str) -> Option<&DoorType> {
+        self.doors.iter().find(|d| d.code == code)
+    }
+
+    /// Unit cost of whichever window or door has the code
+    pub fn unit_cost(&self, code: &str) -> Option<f64> {
+        self.window(code)
+            .map(|w| w.unit_cost)
+            .or_else(|| self.door(code).map(|d| d.unit_cost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_egress() {
+        let catalog = OpeningCatalog::standard();
+
+        // A 3050 single hung falls just short of 5.7 sq ft; a 3060 clears it
+        assert!(!catalog.window("3050").unwrap().meets_egress(24.0));
+        assert!(catalog.window("3060").unwrap().meets_egress(24.0));
+        // ...unless its sill is too high to climb out
+        assert!(!catalog.window("3060").unwrap().meets_egress(48.0));
+        // Fixed glass never counts
+        assert!(!catalog.window("4040F").unwrap().meets_egress(24.0));
+    }
+
+    #[test]
+    fn test_import_json() {
+        let mut catalog = OpeningCatalog::standard();
+        let imported = OpeningCatalog::from_json(
+            r#"{"windows": [{"code": "3050", "name": "3050 Casement", "width": 2.958, "height": 4.958,
+                              "rough_width": 36.0, "rough_height": 60.0,
+                              "clear_opening": {"width": 30.0, "height": 56.0}, "unit_cost": 520.0}],
+                "doors": [{"code": "3068F", "name": "3068 20-min", "width": 3.0, "height": 6.667,
+                           "rough_width": 38.0, "rough_height": 82.5, "fire_rating": 20}]}"#,
+        )
+        .unwrap();
+        catalog.merge(imported);
+
+        let casement = catalog.window("3050").unwrap();
+        assert_eq!(casement.name, "3050 Casement");
+        assert!(casement.meets_egress(24.0));
+        assert!((casement.u_value - 0.30).abs() < 1e-9);
+        assert_eq!(catalog.windows.iter().filter(|w| w.code == "3050").count(), 1);
+        assert_eq!(catalog.door("3068F").unwrap().fire_rating, Some(20));
+        assert_eq!(catalog.unit_cost("3050"), Some(520.0));
+    }
+}
@@ -0,0 +1,274 @@
+// Plumbing fixtures - toilets, sinks, tubs and showers placed in rooms
+// A fixture sits with its back against a wall and faces into the room. Its
+// clear space follows IRC R307.1: 15" from a toilet's centerline to either
+// side, 21" clear in front of toilets, sinks and tubs and 24" in front of a
+// shower. Fixtures backed onto the same wall share a wet wall - one drain,
+// vent and supply rough-in instead of one each.
+
+use serde::{Deserialize, Serialize};
+use super::costing::MaterialType;
+use super::ids::{FixtureId, LevelId, RoomId, WallId};
+use super::spatial::Point2;
+
+/// What a plumbing fixture is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlumbingFixtureKind {
+    Toilet,
+    Sink,
+    Tub,
+    Shower,
+}
+
+impl PlumbingFixtureKind {
+    pub const ALL: [Self; 4] = [Self::Toilet, Self::Sink, Self::Tub, Self::Shower];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Toilet => "toilet",
+            Self::Sink => "sink",
+            Self::Tub => "tub",
+            Self::Shower => "shower",
+        }
+    }
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "toilet" | "water_closet" | "wc" => Some(Self::Toilet),
+            "sink" | "lavatory" | "lav" => Some(Self::Sink),
+            "tub" | "bathtub" | "bath" => Some(Self::Tub),
+            "shower" => Some(Self::Shower),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Toilet => "Toilet",
+            Self::Sink => "Sink",
+            Self::Tub => "Tub",
+            Self::Shower => "Shower",
+        }
+    }
+
+    /// Width along the wall (feet)
+    pub fn width(&self) -> f64 {
+        match self {
+            Self::Toilet => 20.0 / 12.0,
+            Self::Sink => 24.0 / 12.0,
+            Self::Tub => 60.0 / 12.0,
+            Self::Shower => 36.0 / 12.0,
+        }
+    }
+
+    /// Depth out from the wall (feet)
+    pub fn depth(&self) -> f64 {
+        match self {
+            Self::Toilet => 28.0 / 12.0,
+            Self::Sink => 20.0 / 12.0,
+            Self::Tub => 30.0 / 12.0,
+            Self::Shower => 36.0 / 12.0,
+        }
+    }
+
+    /// Clearance from the centerline to a side wall or fixture (feet)
+    pub fn side_clearance(&self) -> f64 {
+        match self {
+            Self::Toilet => 15.0 / 12.0,
+            _ => self.width() / 2.0,
+        }
+    }
+
+    /// Clear floor in front of the fixture (feet)
+    pub fn front_clearance(&self) -> f64 {
+        match self {
+            Self::Shower => 24.0 / 12.0,
+            _ => 21.0 / 12.0,
+        }
+    }
+
+    /// Price table material for the fixture
+    pub fn material(&self) -> MaterialType {
+        match self {
+            Self::Toilet => MaterialType::Toilet,
+            Self::Sink => MaterialType::Sink,
+            Self::Tub => MaterialType::Bathtub,
+            Self::Shower => MaterialType::Shower,
+        }
+    }
+}
+
+/// A fixture placed in a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlumbingFixture {
+    pub id: FixtureId,
+    pub level_id: LevelId,
+    pub room_id: RoomId,
+    pub kind: PlumbingFixtureKind,
+    /// Middle of the fixture's back edge, on the wall face
+    pub location: Point2,
+    /// Plan direction the fixture faces, in degrees from the +X axis
+    pub rotation: f64,
+}
+
+impl PlumbingFixture {
+    pub fn new(level_id: LevelId, room_id: RoomId, kind: PlumbingFixtureKind, location: Point2, rotation: f64) -> Self {
+        Self {
+            id: FixtureId::new(),
+            level_id,
+            room_id,
+            kind,
+            location,
+            rotation,
+        }
+    }
+
+    /// Plan point `across` feet to the fixture's left and `out` feet in
+    /// front of its back edge
+    fn local(&self, across: f64, out: f64) -> Point2 {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        Point2::new(
+            self.location.x + out * cos - across * sin,
+            self.location.y + out * sin + across * cos,
+        )
+    }
+
+    fn rectangle(&self, half_width: f64, depth: f64) -> Vec<Point2> {
+        vec![
+            self.local(-half_width, 0.0),
+            self.local(-half_width, depth),
+            self.local(half_width, depth),
+            self.local(half_width, 0.0),
+        ]
+    }
+
+    /// Outline of the fixture itself
+    pub fn footprint(&self) -> Vec<Point2> {
+        self.rectangle(self.kind.width() / 2.0, self.kind.depth())
+    }
+
+    /// Outline of the fixture and the clear floor it needs: its side
+    /// clearance through its depth and front clearance
+    pub fn clear_space(&self) -> Vec<Point2> {
+        let half_width = self.kind.side_clearance().max(self.kind.width() / 2.0);
+        self.rectangle(half_width, self.kind.depth() + self.kind.front_clearance())
+    }
+
+    /// Whether another fixture stands in this one's clear space. Clear
+    /// floor in front of two fixtures may overlap.
+    pub fn obstructed_by(&self, other: &PlumbingFixture) -> bool {
+        other.id != self.id && convex_overlap(&self.clear_space(), &other.footprint())
+    }
+}
+
+/// Whether two convex outlines overlap with some area, by separating axes
+fn convex_overlap(a: &[Point2], b: &[Point2]) -> bool {
+    const TOLERANCE: f64 = 1e-6;
+    for ring in [a, b] {
+        for i in 0..ring.len() {
+            let (p, q) = (ring[i], ring[(i + 1) % ring.len()]);
+            let (nx, ny) = (p.y - q.y, q.x - p.x);
+            let length = nx.hypot(ny);
+            if length < 1e-9 {
+                continue;
+            }
+            let span = |ring: &[Point2]| {
+                ring.iter()
+                    .map(|v| (v.x * nx + v.y * ny) / length)
+                    .fold((f64::MAX, f64::MIN), |(lo, hi), d| (lo.min(d), hi.max(d)))
+            };
+            let ((a_lo, a_hi), (b_lo, b_hi)) = (span(a), span(b));
+            if a_hi <= b_lo + TOLERANCE || b_hi <= a_lo + TOLERANCE {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// A fixture whose clear space isn't met
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureClearance {
+    pub fixture_id: FixtureId,
+    pub room_id: RoomId,
+    pub kind: PlumbingFixtureKind,
+    /// The clear space runs outside the room
+    pub outside_room: bool,
+    /// Fixtures standing in the clear space
+    pub obstructed_by: Vec<FixtureId>,
+}
+
+impl FixtureClearance {
+    pub fn message(&self) -> String {
+        let mut problems = Vec::new();
+        if self.outside_room {
+            problems.push("runs outside the room".to_string());
+        }
+        if !self.obstructed_by.is_empty() {
+            problems.push(format!("is blocked by {} fixture(s)", self.obstructed_by.len()));
+        }
+        format!("{} clear space {}", self.kind.display_name(), problems.join(" and "))
+    }
+}
+
+/// Fixtures backed onto one wall, or a fixture on no wall by itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WetWallGroup {
+    pub wall_id: Option<WallId>,
+    pub fixture_ids: Vec<FixtureId>,
+    /// Rooms the fixtures are in; two rooms for a back-to-back wall
+    pub room_ids: Vec<RoomId>,
+}
+
+impl WetWallGroup {
+    /// More than one fixture shares the rough-in
+    pub fn is_shared(&self) -> bool {
+        self.fixture_ids.len() > 1
+    }
+}
+
+/// How a level's fixtures group onto wet walls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WetWallAnalysis {
+    pub level_id: LevelId,
+    /// Largest groups first
+    pub groups: Vec<WetWallGroup>,
+    pub fixture_count: usize,
+    /// Fixtures on a wet wall with at least one other
+    pub shared_fixture_count: usize,
+}
+
+impl WetWallAnalysis {
+    /// Rough-ins needed, one per group
+    pub fn rough_in_count(&self) -> usize {
+        self.groups.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_outlines() {
+        let level_id = LevelId::new();
+        let room_id = RoomId::new();
+        // A toilet on the south wall facing north
+        let toilet = PlumbingFixture::new(level_id, room_id, PlumbingFixtureKind::Toilet, Point2::new(5.0, 0.0), 90.0);
+        let space = toilet.clear_space();
+        assert!(space.iter().all(|p| p.y > -1e-9 && p.y < 49.0 / 12.0 + 1e-9));
+        assert!(space.iter().any(|p| (p.x - 3.75).abs() < 1e-9));
+
+        // A sink 24" to the side stands in the 15" side clearance; 30" clears it
+        let sink = |x: f64| PlumbingFixture::new(level_id, room_id, PlumbingFixtureKind::Sink, Point2::new(x, 0.0), 90.0);
+        assert!(toilet.obstructed_by(&sink(7.0)));
+        assert!(!toilet.obstructed_by(&sink(7.5)));
+        assert!(!toilet.obstructed_by(&toilet));
+
+        // Facing fixtures' clear floor may overlap
+        let facing = PlumbingFixture::new(level_id, room_id, PlumbingFixtureKind::Sink, Point2::new(5.0, 7.0), 270.0);
+        assert!(!toilet.obstructed_by(&facing));
+        assert_eq!(PlumbingFixtureKind::parse("lavatory"), Some(PlumbingFixtureKind::Sink));
+    }
+}
@@ -5,22 +5,21 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::ids::*;
+use super::room::Room;
 use super::spatial::{Point2, Point3, Polygon2};
+use super::terrain::Terrain;
+use super::costing::Markups;
+use super::wall::AssemblyRole;
 
 /// Unit system for the project
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum UnitSystem {
+    #[default]
     Imperial,
     Metric,
 }
 
-impl Default for UnitSystem {
-    fn default() -> Self {
-        Self::Imperial
-    }
-}
-
 /// Building code region for compliance checks
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CodeRegion {
@@ -62,10 +61,52 @@ pub struct Project {
     pub code_region: CodeRegion,
     pub site_id: Option<SiteId>,
     pub building_ids: Vec<BuildingId>,
+    /// Assemblies picked for new walls when none is specified
+    #[serde(default)]
+    pub default_assemblies: HashMap<AssemblyRole, WallAssemblyId>,
+    /// Region whose price set estimates use, None for the default prices
+    #[serde(default)]
+    pub price_region: Option<String>,
+    /// Overhead, profit and contingency added to estimates
+    #[serde(default)]
+    pub markups: Markups,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub modified_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Field a project listing is sorted by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectSort {
+    #[default]
+    Name,
+    Created,
+    Modified,
+}
+
+/// Which page of the project list to return
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct ProjectQuery {
+    /// Only projects whose name contains this (case-insensitive)
+    pub name_contains: Option<String>,
+    pub sort: ProjectSort,
+    pub descending: bool,
+    pub offset: usize,
+    /// Page size, None for every remaining project
+    pub limit: Option<usize>,
+}
+
+/// One page of matching projects
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectPage<'a> {
+    pub projects: Vec<&'a Project>,
+    /// Projects matching the filter, across all pages
+    pub total: usize,
+}
+
 impl Project {
     pub fn new(name: impl Into<String>, units: UnitSystem, code_region: CodeRegion) -> Self {
         let now = chrono::Utc::now();
@@ -76,6 +117,9 @@ impl Project {
             code_region,
             site_id: None,
             building_ids: Vec::new(),
+            default_assemblies: HashMap::new(),
+            price_region: None,
+            markups: Markups::default(),
             created_at: now,
             modified_at: now,
         }
@@ -93,8 +137,13 @@ pub struct Site {
     pub project_id: ProjectId,
     pub boundary: Option<Polygon2>,
     pub setbacks: Setbacks,
+    #[serde(default)]
+    pub zoning: ZoningRules,
     pub north_angle: f64, // degrees from Y-axis, clockwise
     pub elevation: f64,   // ground elevation at reference point
+    /// Surveyed ground surface, None when the site is taken as flat
+    #[serde(default)]
+    pub terrain: Option<Terrain>,
 }
 
 impl Site {
@@ -104,8 +153,10 @@ impl Site {
             project_id,
             boundary: None,
             setbacks: Setbacks::default(),
+            zoning: ZoningRules::default(),
             north_angle: 0.0,
             elevation: 0.0,
+            terrain: None,
         }
     }
 }
@@ -131,6 +182,67 @@ impl Default for Setbacks {
     }
 }
 
+/// Zoning limits on the lot beyond setbacks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZoningRules {
+    /// Boundary edge facing the street (edge i runs from vertex i to i + 1).
+    /// The rear is the edge facing away from it; the rest are sides.
+    #[serde(default)]
+    pub front_edge: usize,
+    /// Largest fraction of the lot building footprints may cover
+    #[serde(default)]
+    pub max_lot_coverage: Option<f64>,
+    /// Tallest a building may stand above grade (feet)
+    #[serde(default)]
+    pub max_height: Option<f64>,
+}
+
+/// Code occupancy of a structure on the site
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildingOccupancy {
+    /// Primary dwelling
+    #[default]
+    Dwelling,
+    /// Accessory dwelling unit
+    Adu,
+    /// Detached garage
+    Garage,
+    /// Shed, workshop or other unconditioned accessory structure
+    Shed,
+}
+
+impl BuildingOccupancy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dwelling => "dwelling",
+            Self::Adu => "adu",
+            Self::Garage => "garage",
+            Self::Shed => "shed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "dwelling" | "house" | "primary" => Some(Self::Dwelling),
+            "adu" | "accessory_dwelling" => Some(Self::Adu),
+            "garage" | "detached_garage" => Some(Self::Garage),
+            "shed" | "workshop" | "accessory" => Some(Self::Shed),
+            _ => None,
+        }
+    }
+
+    /// Whether people live in it (habitable-room rules and finishes apply)
+    pub fn is_habitable(&self) -> bool {
+        matches!(self, Self::Dwelling | Self::Adu)
+    }
+
+    /// Anything other than the primary dwelling
+    pub fn is_accessory(&self) -> bool {
+        !matches!(self, Self::Dwelling)
+    }
+}
+
 /// A building within the project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Building {
@@ -139,6 +251,12 @@ pub struct Building {
     pub name: String,
     pub origin: Point3,     // building origin relative to site
     pub level_ids: Vec<LevelId>,
+    #[serde(default)]
+    pub occupancy: BuildingOccupancy,
+    /// Elevation the site is graded to under the building (feet, on the
+    /// terrain's datum); None leaves the ground as surveyed
+    #[serde(default)]
+    pub pad_elevation: Option<f64>,
 }
 
 impl Building {
@@ -149,6 +267,8 @@ impl Building {
             name: name.into(),
             origin: Point3::origin(),
             level_ids: Vec::new(),
+            occupancy: BuildingOccupancy::default(),
+            pad_elevation: None,
         }
     }
 
@@ -166,7 +286,11 @@ pub struct Level {
     pub name: String,
     pub elevation: f64,      // Z height of floor surface
     pub floor_to_floor: f64, // height to next level (or to ceiling if top)
+    /// Primary footprint: the main block of the floor plate
     pub footprint_id: Option<FootprintId>,
+    /// Wings, attached garages and other massing alongside the primary
+    #[serde(default)]
+    pub auxiliary_footprint_ids: Vec<FootprintId>,
     pub is_basement: bool,
     pub wall_ids: Vec<WallId>,
     pub room_ids: Vec<RoomId>,
@@ -186,6 +310,7 @@ impl Level {
             elevation,
             floor_to_floor,
             footprint_id: None,
+            auxiliary_footprint_ids: Vec::new(),
             is_basement: elevation < 0.0,
             wall_ids: Vec::new(),
             room_ids: Vec::new(),
@@ -196,6 +321,22 @@ impl Level {
     pub fn ceiling_elevation(&self) -> f64 {
         self.elevation + self.floor_to_floor
     }
+
+    /// Every footprint on this level, primary first
+    pub fn footprint_ids(&self) -> impl Iterator<Item = FootprintId> + '_ {
+        self.footprint_id.into_iter().chain(self.auxiliary_footprint_ids.iter().copied())
+    }
+}
+
+/// Part a footprint plays in a level's massing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FootprintRole {
+    /// The main block of the floor plate
+    #[default]
+    Primary,
+    /// A wing, attached garage or other block alongside the primary
+    Auxiliary,
 }
 
 /// A 2D footprint polygon for a level
@@ -204,6 +345,8 @@ pub struct Footprint {
     pub id: FootprintId,
     pub level_id: LevelId,
     pub polygon: Polygon2,
+    #[serde(default)]
+    pub role: FootprintRole,
     pub solid_id: Option<SolidId>, // cached massing solid
 }
 
@@ -213,10 +356,19 @@ impl Footprint {
             id: FootprintId::new(),
             level_id,
             polygon,
+            role: FootprintRole::Primary,
             solid_id: None,
         }
     }
 
+    /// A footprint that sits alongside a level's primary one
+    pub fn auxiliary(level_id: LevelId, polygon: Polygon2) -> Self {
+        Self {
+            role: FootprintRole::Auxiliary,
+            ..Self::new(level_id, polygon)
+        }
+    }
+
     pub fn area(&self) -> f64 {
         self.polygon.area()
     }
@@ -244,6 +396,43 @@ impl Grid {
             axes: Vec::new(),
         }
     }
+
+    pub fn axis(&self, name: &str) -> Option<&GridAxis> {
+        self.axes.iter().find(|a| a.name == name)
+    }
+
+    /// Where two named axes cross; None unless one runs each way
+    pub fn intersection(&self, a: &str, b: &str) -> Option<Point2> {
+        let (a, b) = (self.axis(a)?, self.axis(b)?);
+        match (a.direction, b.direction) {
+            (GridDirection::Vertical, GridDirection::Horizontal) => Some(Point2::new(a.offset, b.offset)),
+            (GridDirection::Horizontal, GridDirection::Vertical) => Some(Point2::new(b.offset, a.offset)),
+            _ => None,
+        }
+    }
+
+    /// Point named by a grid reference such as "A/3": the crossing of the
+    /// two axes, in either order
+    pub fn resolve(&self, reference: &str) -> Option<Point2> {
+        let (a, b) = reference.split_once('/')?;
+        self.intersection(a.trim(), b.trim())
+    }
+
+    /// Pull each coordinate onto the nearest axis running across it, if
+    /// one is within `tolerance`
+    pub fn snap(&self, point: Point2, tolerance: f64) -> Point2 {
+        let nearest = |direction: GridDirection, value: f64| {
+            self.axes
+                .iter()
+                .filter(|a| a.direction == direction && (a.offset - value).abs() <= tolerance)
+                .min_by(|a, b| (a.offset - value).abs().total_cmp(&(b.offset - value).abs()))
+                .map_or(value, |a| a.offset)
+        };
+        Point2::new(
+            nearest(GridDirection::Vertical, point.x),
+            nearest(GridDirection::Horizontal, point.y),
+        )
+    }
 }
 
 /// A single grid axis (line)
@@ -263,29 +452,113 @@ pub enum GridDirection {
 /// Aggregated statistics for a building
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildingStats {
+    pub building_id: BuildingId,
+    pub occupancy: BuildingOccupancy,
     pub total_area: f64,
+    /// Heated area: footprint less garages, porches and other unconditioned
+    /// rooms. Structures that aren't habitable have none.
+    #[serde(default)]
+    pub conditioned_area: f64,
+    #[serde(default)]
+    pub unconditioned_area: f64,
     pub level_count: usize,
     pub footprint_areas: HashMap<LevelId, f64>,
+    /// Decks and porches, outside the footprint and not in the total
+    #[serde(default)]
+    pub deck_area: f64,
 }
 
 impl BuildingStats {
-    pub fn compute(building: &Building, levels: &[&Level], footprints: &[&Footprint]) -> Self {
+    pub fn compute(
+        building: &Building,
+        levels: &[&Level],
+        footprints: &[&Footprint],
+        rooms: &[&Room],
+    ) -> Self {
         let mut footprint_areas = HashMap::new();
         let mut total_area = 0.0;
+        let mut conditioned_area = 0.0;
 
         for level in levels {
-            if let Some(fp) = footprints.iter().find(|f| f.level_id == level.id) {
-                let area = fp.area();
+            let mut level_footprints = footprints.iter().filter(|f| f.level_id == level.id).peekable();
+            if level_footprints.peek().is_some() {
+                let area: f64 = level_footprints.map(|f| f.area()).sum();
                 footprint_areas.insert(level.id, area);
                 total_area += area;
+
+                // Rooms of unknown type count as heated
+                let unconditioned: f64 = rooms
+                    .iter()
+                    .filter(|r| r.level_id == level.id && r.is_conditioned() == Some(false))
+                    .map(|r| r.area())
+                    .sum();
+                if building.occupancy.is_habitable() {
+                    conditioned_area += (area - unconditioned).max(0.0);
+                }
             }
         }
 
         Self {
+            building_id: building.id,
+            occupancy: building.occupancy,
             total_area,
+            conditioned_area,
+            unconditioned_area: total_area - conditioned_area,
             level_count: levels.len(),
             footprint_areas,
+            deck_area: 0.0,
+        }
+    }
+}
+
+/// Statistics across every structure in a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectStats {
+    pub buildings: Vec<BuildingStats>,
+    pub total_area: f64,
+    /// Heated area across every structure
+    #[serde(default)]
+    pub conditioned_area: f64,
+    /// Area of dwellings and ADUs
+    pub habitable_area: f64,
+    /// Area of structures other than the primary dwelling
+    pub accessory_area: f64,
+    /// Decks and porches across every structure
+    #[serde(default)]
+    pub deck_area: f64,
+}
+
+impl ProjectStats {
+    pub fn compute(buildings: Vec<BuildingStats>) -> Self {
+        let mut total_area = 0.0;
+        let mut conditioned_area = 0.0;
+        let mut habitable_area = 0.0;
+        let mut accessory_area = 0.0;
+        let mut deck_area = 0.0;
+        for stats in &buildings {
+            total_area += stats.total_area;
+            deck_area += stats.deck_area;
+            conditioned_area += stats.conditioned_area;
+            if stats.occupancy.is_habitable() {
+                habitable_area += stats.total_area;
+            }
+            if stats.occupancy.is_accessory() {
+                accessory_area += stats.total_area;
+            }
         }
+
+        Self {
+            buildings,
+            total_area,
+            conditioned_area,
+            habitable_area,
+            accessory_area,
+            deck_area,
+        }
+    }
+
+    pub fn building_count(&self) -> usize {
+        self.buildings.len()
     }
 }
 
@@ -314,4 +587,49 @@ mod tests {
         let footprint = Footprint::new(level_id, polygon);
         assert!((footprint.area() - 1200.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_grid_snap_and_resolve() {
+        let mut grid = Grid::new(BuildingId::new());
+        for (name, direction, offset) in [
+            ("A", GridDirection::Horizontal, 0.0),
+            ("B", GridDirection::Horizontal, 24.0),
+            ("1", GridDirection::Vertical, 0.0),
+            ("3", GridDirection::Vertical, 32.0),
+        ] {
+            grid.axes.push(GridAxis { name: name.into(), direction, offset });
+        }
+
+        assert_eq!(grid.resolve("A/3"), Some(Point2::new(32.0, 0.0)));
+        assert_eq!(grid.resolve("3 / B"), Some(Point2::new(32.0, 24.0)));
+        assert_eq!(grid.resolve("A/B"), None);
+        assert_eq!(grid.resolve("C/1"), None);
+
+        // Only coordinates within tolerance of an axis move
+        assert_eq!(grid.snap(Point2::new(31.6, 12.0), 0.5), Point2::new(32.0, 12.0));
+        assert_eq!(grid.snap(Point2::new(0.3, 23.8), 0.5), Point2::new(0.0, 24.0));
+    }
+
+    #[test]
+    fn test_project_stats_by_occupancy() {
+        let stats = |occupancy, total_area| BuildingStats {
+            building_id: BuildingId::new(),
+            occupancy,
+            total_area,
+            conditioned_area: 0.0,
+            unconditioned_area: total_area,
+            level_count: 1,
+            footprint_areas: HashMap::new(),
+            deck_area: 0.0,
+        };
+        let project = ProjectStats::compute(vec![
+            stats(BuildingOccupancy::Dwelling, 1800.0),
+            stats(BuildingOccupancy::Adu, 600.0),
+            stats(BuildingOccupancy::Garage, 480.0),
+        ]);
+        assert!((project.total_area - 2880.0).abs() < 1e-10);
+        assert!((project.habitable_area - 2400.0).abs() < 1e-10);
+        assert!((project.accessory_area - 1080.0).abs() < 1e-10);
+        assert_eq!(BuildingOccupancy::parse("ADU"), Some(BuildingOccupancy::Adu));
+    }
 }
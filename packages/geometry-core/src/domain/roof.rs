@@ -0,0 +1,277 @@
+// Roofs - one per level, sitting on top of the level's walls
+// Roof geometry is derived from the level footprint: the footprint's
+// bounding rectangle, grown by the overhang, is the roof plan. The ridge
+// runs along the long side of that rectangle. Each plane drains to the
+// gutter along its eave, with downspouts for its share of the plan area.
+
+use serde::{Deserialize, Serialize};
+use super::ids::{LevelId, RoofId};
+use super::spatial::{Point2, Polygon2};
+
+/// Roof form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoofType {
+    /// Two slopes meeting at a ridge, with vertical gable ends
+    Gable,
+    /// Slopes on all four sides
+    Hip,
+    /// A single slope rising across the short side
+    Shed,
+}
+
+impl RoofType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gable => "gable",
+            Self::Hip => "hip",
+            Self::Shed => "shed",
+        }
+    }
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "gable" => Some(Self::Gable),
+            "hip" => Some(Self::Hip),
+            "shed" | "lean_to" => Some(Self::Shed),
+            _ => None,
+        }
+    }
+}
+
+/// Axis-aligned roof plan: the footprint bounds plus overhang
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RoofPlan {
+    pub min: Point2,
+    pub max: Point2,
+}
+
+impl RoofPlan {
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn depth(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    pub fn area(&self) -> f64 {
+        self.width() * self.depth()
+    }
+
+    /// Whether the ridge runs along the x axis
+    pub fn ridge_along_x(&self) -> bool {
+        self.width() >= self.depth()
+    }
+
+    /// Length of the side the slopes run across
+    pub fn span(&self) -> f64 {
+        self.width().min(self.depth())
+    }
+}
+
+/// Plan area one downspout drains (sqft, a 2x3" downspout)
+pub const DOWNSPOUT_DRAINAGE_AREA: f64 = 600.0;
+/// Longest gutter run to one downspout (feet)
+pub const MAX_GUTTER_RUN: f64 = 40.0;
+
+/// One sloped plane of a roof and the eave it drains to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RoofPlane {
+    /// Horizontal area the plane covers, which sets its runoff (sqft)
+    pub plan_area: f64,
+    pub surface_area: f64,
+    /// Gutter along the low edge (feet)
+    pub eave_length: f64,
+    pub downspouts: u32,
+}
+
+impl RoofPlane {
+    fn new(plan_area: f64, eave_length: f64, slope_factor: f64) -> Self {
+        let by_area = (plan_area / DOWNSPOUT_DRAINAGE_AREA).ceil();
+        let by_run = (eave_length / MAX_GUTTER_RUN).ceil();
+        Self {
+            plan_area,
+            surface_area: plan_area * slope_factor,
+            eave_length,
+            downspouts: by_area.max(by_run).max(1.0) as u32,
+        }
+    }
+}
+
+/// Planes and edge lengths of a roof (feet)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoofTopology {
+    pub planes: Vec<RoofPlane>,
+    /// Low edges, guttered
+    pub eave: f64,
+    /// Sloped gable edges; a shed's high edge is trimmed like a rake
+    pub rake: f64,
+    pub ridge: f64,
+    pub hip: f64,
+    /// Always zero on a single rectangular plan
+    pub valley: f64,
+}
+
+impl RoofTopology {
+    pub fn downspouts(&self) -> u32 {
+        self.planes.iter().map(|p| p.downspouts).sum()
+    }
+}
+
+/// A roof over a level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Roof {
+    pub id: RoofId,
+    pub level_id: LevelId,
+    pub roof_type: RoofType,
+    pub pitch: f64,    // rise in inches per 12 inches of run
+    pub overhang: f64, // horizontal eave projection past the footprint (ft)
+}
+
+impl Roof {
+    pub fn new(level_id: LevelId, roof_type: RoofType, pitch: f64, overhang: f64) -> Self {
+        Self {
+            id: RoofId::new(),
+            level_id,
+            roof_type,
+            pitch,
+            overhang,
+        }
+    }
+
+    /// Rise per foot of run
+    pub fn slope(&self) -> f64 {
+        self.pitch / 12.0
+    }
+
+    /// Ratio of sloped surface area to plan area
+    pub fn slope_factor(&self) -> f64 {
+        (1.0 + self.slope() * self.slope()).sqrt()
+    }
+
+    /// Roof plan for a footprint; None for a degenerate footprint
+    pub fn plan(&self, footprint: &Polygon2) -> Option<RoofPlan> {
+        if footprint.outer.len() < 3 {
+            return None;
+        }
+        let min_x = footprint.outer.iter().map(|p| p.x).fold(f64::MAX, f64::min);
+        let max_x = footprint.outer.iter().map(|p| p.x).fold(f64::MIN, f64::max);
+        let min_y = footprint.outer.iter().map(|p| p.y).fold(f64::MAX, f64::min);
+        let max_y = footprint.outer.iter().map(|p| p.y).fold(f64::MIN, f64::max);
+        Some(RoofPlan {
+            min: Point2::new(min_x - self.overhang, min_y - self.overhang),
+            max: Point2::new(max_x + self.overhang, max_y + self.overhang),
+        })
+    }
+
+    /// Height of the ridge (or high edge of a shed) above the wall plate
+    pub fn ridge_height(&self, plan: &RoofPlan) -> f64 {
+        match self.roof_type {
+            RoofType::Gable | RoofType::Hip => plan.span() / 2.0 * self.slope(),
+            RoofType::Shed => plan.span() * self.slope(),
+        }
+    }
+
+    /// Planes and edges of the roof over a footprint; None for a degenerate
+    /// footprint
+    pub fn topology(&self, footprint: &Polygon2) -> Option<RoofTopology> {
+        let plan = self.plan(footprint)?;
+        let length = plan.width().max(plan.depth());
+        let span = plan.span();
+        let rise = self.ridge_height(&plan);
+        let sf = self.slope_factor();
+
+        let topology = match self.roof_type {
+            RoofType::Gable => RoofTopology {
+                planes: vec![RoofPlane::new(length * span / 2.0, length, sf); 2],
+                eave: 2.0 * length,
+                rake: 4.0 * (span / 2.0).hypot(rise),
+                ridge: length,
+                hip: 0.0,
+                valley: 0.0,
+            },
+            RoofType::Hip => {
+                // Sides are trapezoids and ends triangles, meeting in hips
+                // over the plan's corner diagonals
+                let side = RoofPlane::new((2.0 * length - span) / 2.0 * span / 2.0, length, sf);
+                let end = RoofPlane::new(span * span / 4.0, span, sf);
+                RoofTopology {
+                    planes: vec![side, side, end, end],
+                    eave: 2.0 * (length + span),
+                    rake: 0.0,
+                    ridge: length - span,
+                    hip: 4.0 * (span / 2.0 * std::f64::consts::SQRT_2).hypot(rise),
+                    valley: 0.0,
+                }
+            }
+            RoofType::Shed => RoofTopology {
+                planes: vec![RoofPlane::new(length * span, length, sf)],
+                eave: length,
+                rake: 2.0 * span.hypot(rise) + length,
+                ridge: 0.0,
+                hip: 0.0,
+                valley: 0.0,
+            },
+        };
+        Some(topology)
+    }
+
+    /// Sloped surface area (sqft). Every plane shares the same pitch, so
+    /// this is the plan area times the slope factor for all roof types.
+    pub fn surface_area(&self, footprint: &Polygon2) -> f64 {
+        self.plan(footprint)
+            .map(|plan| plan.area() * self.slope_factor())
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roof_dimensions() {
+        let footprint = Polygon2::rectangle(40.0, 24.0);
+        let roof = Roof::new(LevelId::new(), RoofType::Gable, 6.0, 1.0);
+
+        let plan = roof.plan(&footprint).unwrap();
+        assert!(plan.ridge_along_x());
+        assert_eq!(plan.span(), 26.0);
+        assert!((roof.ridge_height(&plan) - 6.5).abs() < 1e-10);
+        assert!((roof.surface_area(&footprint) - 42.0 * 26.0 * 1.25_f64.sqrt()).abs() < 1e-9);
+
+        let shed = Roof::new(LevelId::new(), RoofType::parse("Shed").unwrap(), 3.0, 0.0);
+        let plan = shed.plan(&footprint).unwrap();
+        assert!((shed.ridge_height(&plan) - 6.0).abs() < 1e-10);
+        assert_eq!(RoofType::parse("mansard"), None);
+    }
+
+    #[test]
+    fn test_roof_topology() {
+        let footprint = Polygon2::rectangle(40.0, 24.0);
+
+        // 42' x 26' plan at 6:12: 13' runs rising 6.5'
+        let gable = Roof::new(LevelId::new(), RoofType::Gable, 6.0, 1.0);
+        let topology = gable.topology(&footprint).unwrap();
+        assert_eq!(topology.eave, 84.0);
+        assert_eq!(topology.ridge, 42.0);
+        assert!((topology.rake - 4.0 * 13.0 * 1.25_f64.sqrt()).abs() < 1e-9);
+        // 546 sf draining to each 42' eave takes two downspouts a side
+        assert_eq!(topology.downspouts(), 4);
+        let area: f64 = topology.planes.iter().map(|p| p.surface_area).sum();
+        assert!((area - gable.surface_area(&footprint)).abs() < 1e-9);
+
+        let hip = Roof::new(LevelId::new(), RoofType::Hip, 6.0, 1.0);
+        let topology = hip.topology(&footprint).unwrap();
+        assert_eq!(topology.eave, 136.0);
+        assert_eq!(topology.ridge, 16.0);
+        assert_eq!(topology.rake, 0.0);
+        // Each hip runs 13' each way in plan and rises 6.5'
+        assert!((topology.hip - 4.0 * (338.0_f64 + 42.25).sqrt()).abs() < 1e-9);
+        let plan_area: f64 = topology.planes.iter().map(|p| p.plan_area).sum();
+        assert!((plan_area - 42.0 * 26.0).abs() < 1e-9);
+        assert_eq!(topology.downspouts(), 6);
+    }
+}
@@ -4,6 +4,8 @@
 use serde::{Deserialize, Serialize};
 use super::ids::{RoomId, LevelId, WallId};
 use super::spatial::Polygon2;
+use super::finish::{FinishSurface, RoomFinishes};
+use super::costing::MaterialType;
 
 /// Type of room - used for scheduling, code compliance, HVAC zoning
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,6 +31,7 @@ pub enum RoomType {
 
 impl RoomType {
     /// Parse from string
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "living_room" | "livingroom" | "living" => Self::LivingRoom,
@@ -71,6 +74,37 @@ impl RoomType {
             Self::Other(name) => name.clone(),
         }
     }
+
+    /// Whether this is a habitable space (living, sleeping, eating, cooking)
+    pub fn is_habitable(&self) -> bool {
+        matches!(
+            self,
+            Self::LivingRoom
+                | Self::Kitchen
+                | Self::Bedroom
+                | Self::DiningRoom
+                | Self::FamilyRoom
+                | Self::Office
+        )
+    }
+
+    /// Whether this room type is normally inside the conditioned envelope
+    /// Returns None for custom types that can't be classified by name
+    pub fn is_conditioned(&self) -> Option<bool> {
+        match self {
+            Self::Garage => Some(false),
+            Self::Other(name) => {
+                let name = name.to_lowercase();
+                let unconditioned = ["porch", "patio", "deck", "attic", "crawl", "shed", "carport"];
+                if unconditioned.iter().any(|k| name.contains(k)) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            _ => Some(true),
+        }
+    }
 }
 
 impl Default for RoomType {
@@ -90,6 +124,12 @@ pub struct Room {
     pub floor_finish: String,
     pub ceiling_height: Option<f64>, // None = use level's floor_to_floor
     pub bounding_wall_ids: Vec<WallId>,
+    /// Explicit conditioning override (None = derive from room type)
+    #[serde(default)]
+    pub conditioned: Option<bool>,
+    /// Finish selections (None = typical finishes for the room type)
+    #[serde(default)]
+    pub finishes: Option<RoomFinishes>,
 }
 
 impl Room {
@@ -108,6 +148,8 @@ impl Room {
             floor_finish: "Hardwood".to_string(),
             ceiling_height: None,
             bounding_wall_ids: Vec::new(),
+            conditioned: None,
+            finishes: None,
         }
     }
 
@@ -138,6 +180,30 @@ impl Room {
         self
     }
 
+    /// Mark the room as inside or outside the conditioned envelope
+    pub fn with_conditioned(mut self, conditioned: bool) -> Self {
+        self.conditioned = Some(conditioned);
+        self
+    }
+
+    /// Whether the room is conditioned - explicit override first, then room type.
+    /// None means the room can't be classified.
+    pub fn is_conditioned(&self) -> Option<bool> {
+        self.conditioned.or_else(|| self.room_type.is_conditioned())
+    }
+
+    /// Finishes in effect - explicit selections, else room type defaults
+    pub fn finishes(&self) -> RoomFinishes {
+        self.finishes.unwrap_or_else(|| RoomFinishes::for_room_type(&self.room_type))
+    }
+
+    /// Select the finish for one surface, keeping the others as they are
+    pub fn set_finish(&mut self, surface: FinishSurface, material: MaterialType) {
+        let mut finishes = self.finishes();
+        finishes.set(surface, material);
+        self.finishes = Some(finishes);
+    }
+
     /// Set the bounding walls for this room
     pub fn set_bounding_walls(&mut self, wall_ids: Vec<WallId>) {
         self.bounding_wall_ids = wall_ids;
@@ -147,7 +213,6 @@ impl Room {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::Point2;
 
     #[test]
     fn test_room_creation() {
@@ -200,4 +265,23 @@ mod tests {
         assert_eq!(room.ceiling_height, Some(12.0));
         assert_eq!(room.floor_finish, "Oak");
     }
+
+    #[test]
+    fn test_room_conditioning() {
+        let level_id = LevelId::new();
+        let boundary = Polygon2::rectangle(10.0, 10.0);
+
+        let bedroom = Room::new(level_id, RoomType::Bedroom, "Bed", boundary.clone());
+        assert_eq!(bedroom.is_conditioned(), Some(true));
+
+        let garage = Room::new(level_id, RoomType::Garage, "Garage", boundary.clone());
+        assert_eq!(garage.is_conditioned(), Some(false));
+
+        let porch = Room::new(level_id, RoomType::from_str("porch"), "Porch", boundary.clone());
+        assert_eq!(porch.is_conditioned(), Some(false));
+
+        let studio = Room::new(level_id, RoomType::from_str("studio"), "Studio", boundary);
+        assert_eq!(studio.is_conditioned(), None);
+        assert_eq!(studio.with_conditioned(true).is_conditioned(), Some(true));
+    }
 }
@@ -0,0 +1,27 @@
+// Saved scripts - named Rhai scripts stored with a project
+// Reusable parametric steps ("add standard bathroom") kept in the project's
+// history like any other edit, and run on demand against the model
+
+use serde::{Deserialize, Serialize};
+use super::ids::{ProjectId, ScriptId};
+
+/// A named script saved in a project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedScript {
+    pub id: ScriptId,
+    pub project_id: ProjectId,
+    pub name: String,
+    /// Rhai source
+    pub source: String,
+}
+
+impl SavedScript {
+    pub fn new(project_id: ProjectId, name: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            id: ScriptId::new(),
+            project_id,
+            name: name.into(),
+            source: source.into(),
+        }
+    }
+}
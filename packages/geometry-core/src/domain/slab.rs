@@ -0,0 +1,219 @@
+// Floor and ceiling assemblies - layered horizontal planes over a level
+// A floor slab's top sits at the level elevation; a ceiling's underside sits
+// at the ceiling height. Either covers the whole footprint or a single room.
+// The slab on grade under the lowest level is priced by the foundation, so
+// floor slabs model framed floors.
+
+use serde::{Deserialize, Serialize};
+use super::costing::{MaterialType, PricingUnit};
+use super::ids::{CeilingAssemblyId, FloorSlabId, LevelId, RoomId};
+
+/// Framing spacing assumed when counting joists (16" o.c.)
+const JOIST_SPACING: f64 = 16.0 / 12.0;
+/// Stock length joists are bought in (feet)
+const JOIST_STOCK_LENGTH: f64 = 12.0;
+
+/// A single layer of a floor or ceiling assembly, priced as its material
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlabLayer {
+    pub name: String,
+    pub material: MaterialType,
+    pub thickness: f64, // inches
+    /// Thermal resistance per inch; None uses the material's typical value
+    #[serde(default)]
+    pub r_value_per_inch: Option<f64>,
+}
+
+impl SlabLayer {
+    pub fn new(name: impl Into<String>, material: MaterialType, thickness: f64) -> Self {
+        Self {
+            name: name.into(),
+            material,
+            thickness,
+            r_value_per_inch: None,
+        }
+    }
+
+    pub fn with_r_value_per_inch(mut self, r_value_per_inch: f64) -> Self {
+        self.r_value_per_inch = Some(r_value_per_inch);
+        self
+    }
+
+    /// Thermal resistance of the layer through its full thickness. Joist
+    /// layers carry none: the insulation in the same cavity is counted.
+    pub fn r_value(&self) -> f64 {
+        let per_inch = self.r_value_per_inch.unwrap_or(match self.material {
+            MaterialType::Insulation => 3.1,
+            MaterialType::Drywall => 0.9,
+            MaterialType::Sheathing => 1.25,
+            MaterialType::ConcreteMix => 0.08,
+            MaterialType::Carpet => 2.0,
+            MaterialType::Hardwood => 0.9,
+            _ => 0.0,
+        });
+        self.thickness * per_inch
+    }
+
+    pub fn subfloor_3_4() -> Self {
+        Self::new("Subfloor 3/4\"", MaterialType::Sheathing, 0.75)
+    }
+
+    pub fn joist_2x10() -> Self {
+        Self::new("2x10 Floor Joists", MaterialType::Lumber2x10, 9.25)
+    }
+
+    pub fn gypsum_1_2() -> Self {
+        Self::new("Gypsum Board 1/2\"", MaterialType::Drywall, 0.5)
+    }
+
+    pub fn insulation_r38() -> Self {
+        Self::new("Fiberglass Insulation R38", MaterialType::Insulation, 10.25)
+            .with_r_value_per_inch(38.0 / 10.25)
+    }
+
+    /// Purchase quantity covering `area` square feet, in the material's unit
+    pub fn quantity(&self, area: f64) -> f64 {
+        match self.material.typical_unit() {
+            PricingUnit::PerCubicYard => area * self.thickness / 12.0 / 27.0,
+            PricingUnit::PerLinearFoot => area / JOIST_SPACING,
+            // Joists at 16" o.c. in 12' stock plus 10% waste
+            PricingUnit::PerBoard => (area / JOIST_SPACING / JOIST_STOCK_LENGTH * 1.1).ceil(),
+            _ => area,
+        }
+    }
+}
+
+/// Total thickness of a layer stack in feet
+fn layers_thickness(layers: &[SlabLayer]) -> f64 {
+    layers.iter().map(|l| l.thickness).sum::<f64>() / 12.0
+}
+
+/// Thermal resistance through a layer stack, layers in series
+fn layers_r_value(layers: &[SlabLayer]) -> f64 {
+    layers.iter().map(|l| l.r_value()).sum()
+}
+
+/// Plan extent of a floor or ceiling assembly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlabBoundary {
+    /// The whole level footprint
+    Level,
+    /// A single room's boundary
+    Room { room_id: RoomId },
+}
+
+/// A framed floor under a level or room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FloorSlab {
+    pub id: FloorSlabId,
+    pub level_id: LevelId,
+    pub boundary: SlabBoundary,
+    /// Layers from the walking surface down
+    pub layers: Vec<SlabLayer>,
+}
+
+impl FloorSlab {
+    pub fn new(level_id: LevelId, boundary: SlabBoundary, layers: Vec<SlabLayer>) -> Self {
+        Self {
+            id: FloorSlabId::new(),
+            level_id,
+            boundary,
+            layers,
+        }
+    }
+
+    /// Subfloor over 2x10 joists
+    pub fn framed_layers() -> Vec<SlabLayer> {
+        vec![SlabLayer::subfloor_3_4(), SlabLayer::joist_2x10()]
+    }
+
+    /// Thickness in feet, used when no slab has been modeled
+    pub fn default_thickness() -> f64 {
+        layers_thickness(&Self::framed_layers())
+    }
+
+    /// Thickness in feet
+    pub fn thickness(&self) -> f64 {
+        layers_thickness(&self.layers)
+    }
+
+    /// Thermal resistance through the floor, without air films
+    pub fn r_value(&self) -> f64 {
+        layers_r_value(&self.layers)
+    }
+
+    /// Whether the floor includes an insulation layer
+    pub fn is_insulated(&self) -> bool {
+        self.layers.iter().any(|l| l.material == MaterialType::Insulation)
+    }
+}
+
+/// A ceiling under the floor or roof above a level or room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CeilingAssembly {
+    pub id: CeilingAssemblyId,
+    pub level_id: LevelId,
+    pub boundary: SlabBoundary,
+    /// Layers from the room side up
+    pub layers: Vec<SlabLayer>,
+}
+
+impl CeilingAssembly {
+    pub fn new(level_id: LevelId, boundary: SlabBoundary, layers: Vec<SlabLayer>) -> Self {
+        Self {
+            id: CeilingAssemblyId::new(),
+            level_id,
+            boundary,
+            layers,
+        }
+    }
+
+    /// Drywall ceiling with attic insulation above
+    pub fn insulated_layers() -> Vec<SlabLayer> {
+        vec![SlabLayer::gypsum_1_2(), SlabLayer::insulation_r38()]
+    }
+
+    /// Thickness in feet
+    pub fn thickness(&self) -> f64 {
+        layers_thickness(&self.layers)
+    }
+
+    /// Thermal resistance through the ceiling, without air films
+    pub fn r_value(&self) -> f64 {
+        layers_r_value(&self.layers)
+    }
+
+    /// Whether the ceiling includes an insulation layer
+    pub fn is_insulated(&self) -> bool {
+        self.layers.iter().any(|l| l.material == MaterialType::Insulation)
+    }
+
+    /// Whether the ceiling includes a drywall layer
+    pub fn has_drywall(&self) -> bool {
+        self.layers.iter().any(|l| l.material == MaterialType::Drywall)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slab_thickness_and_quantities() {
+        let slab = FloorSlab::new(LevelId::new(), SlabBoundary::Level, FloorSlab::framed_layers());
+        assert!((slab.thickness() - 10.0 / 12.0).abs() < 1e-10);
+        assert!((FloorSlab::default_thickness() - slab.thickness()).abs() < 1e-10);
+
+        // 500 sqft: 375 lf of joists = 31.25 sticks, 35 with waste
+        assert_eq!(SlabLayer::joist_2x10().quantity(500.0), 35.0);
+        assert_eq!(SlabLayer::subfloor_3_4().quantity(480.0), 480.0);
+        let concrete = SlabLayer::new("Topping", MaterialType::ConcreteMix, 1.5);
+        assert!((concrete.quantity(216.0) - 1.0).abs() < 1e-10);
+
+        let ceiling = CeilingAssembly::new(LevelId::new(), SlabBoundary::Level, CeilingAssembly::insulated_layers());
+        assert!(ceiling.has_drywall());
+        assert!((ceiling.r_value() - 38.45).abs() < 1e-9);
+        assert_eq!(SlabLayer::joist_2x10().r_value(), 0.0);
+    }
+}
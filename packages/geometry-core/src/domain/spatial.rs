@@ -241,12 +241,154 @@ impl Polygon2 {
         self.clone()
     }
 
-    /// Split the polygon by a line defined by two points
-    /// Returns a tuple of (left_polygon, right_polygon)
-    /// TODO: Implement robust polygon splitting
-    pub fn split(&self, _p1: Point2, _p2: Point2) -> Option<(Self, Self)> {
-        // Placeholder: return None to indicate split failed/not implemented
-        None
+    /// Split the polygon by the infinite line through two points.
+    /// Returns a tuple of (left_polygon, right_polygon), looking from `p1`
+    /// towards `p2`. Returns None unless the line cuts the outline into
+    /// exactly two pieces; holes go with the piece they fall in, and one
+    /// the line passes through stops the split.
+    pub fn split(&self, p1: Point2, p2: Point2) -> Option<(Self, Self)> {
+        let (dx, dy) = (p2.x - p1.x, p2.y - p1.y);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length < 1e-10 || self.outer.len() < 3 {
+            return None;
+        }
+        // Signed distance from the line, positive to the left
+        let side = |p: &Point2| {
+            let d = (dx * (p.y - p1.y) - dy * (p.x - p1.x)) / length;
+            if d.abs() < 1e-9 { 0.0 } else { d }
+        };
+
+        // A single cut crosses the outline exactly twice
+        let signs: Vec<f64> = self.outer.iter().map(side).filter(|d| *d != 0.0).collect();
+        let crossings = (0..signs.len())
+            .filter(|&i| signs[i] * signs[(i + 1) % signs.len()] < 0.0)
+            .count();
+        if crossings != 2 {
+            return None;
+        }
+
+        // Keep the part of the ring on one side, cutting edges that cross
+        let clip = |keep: f64| -> Vec<Point2> {
+            let n = self.outer.len();
+            let mut ring = Vec::new();
+            for i in 0..n {
+                let (a, b) = (self.outer[i], self.outer[(i + 1) % n]);
+                let (da, db) = (side(&a) * keep, side(&b) * keep);
+                if da >= 0.0 {
+                    ring.push(a);
+                }
+                if da * db < 0.0 {
+                    let t = da / (da - db);
+                    ring.push(Point2::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y)));
+                }
+            }
+            ring.dedup_by(|p, q| p.distance_to(q) < 1e-9);
+            if ring.len() > 1 && ring[0].distance_to(&ring[ring.len() - 1]) < 1e-9 {
+                ring.pop();
+            }
+            ring
+        };
+
+        let mut left_holes = Vec::new();
+        let mut right_holes = Vec::new();
+        for hole in &self.holes {
+            let sides: Vec<f64> = hole.iter().map(side).collect();
+            if sides.iter().all(|d| *d >= 0.0) {
+                left_holes.push(hole.clone());
+            } else if sides.iter().all(|d| *d <= 0.0) {
+                right_holes.push(hole.clone());
+            } else {
+                return None;
+            }
+        }
+
+        let left = Self::with_holes(clip(1.0), left_holes);
+        let right = Self::with_holes(clip(-1.0), right_holes);
+        (left.is_valid() && right.is_valid()).then_some((left, right))
+    }
+
+    /// Move the outer edge from vertex `edge_index` to the next one along
+    /// its normal (positive = outward). Each end slides along the edge next
+    /// to it; where that edge runs the same way as the pushed one, a new
+    /// edge is added to join them. Returns None for an edge that doesn't
+    /// exist or has no length.
+    pub fn push_edge(&self, edge_index: usize, distance: f64) -> Option<Self> {
+        let n = self.outer.len();
+        if n < 3 || edge_index >= n {
+            return None;
+        }
+        let at = |i: usize| self.outer[i % n];
+        let (a, b) = (at(edge_index), at(edge_index + 1));
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length < 1e-10 {
+            return None;
+        }
+
+        // Outward is to the right of the edge for counterclockwise rings
+        let side = if self.signed_area() >= 0.0 { 1.0 } else { -1.0 };
+        let (nx, ny) = (side * dy / length, -side * dx / length);
+        let (ux, uy) = (dx / length, dy / length);
+
+        // Where the pushed edge's line crosses the line from `from` to
+        // `to`; None when they run the same way
+        let slide = |from: Point2, to: Point2, moved: Point2| -> Option<Point2> {
+            let (ex, ey) = (to.x - from.x, to.y - from.y);
+            let denom = ux * ey - uy * ex;
+            if denom.abs() < 1e-9 * (ex * ex + ey * ey).sqrt() {
+                return None;
+            }
+            let t = ((from.x - moved.x) * ey - (from.y - moved.y) * ex) / denom;
+            Some(Point2::new(moved.x + t * ux, moved.y + t * uy))
+        };
+        let moved_a = Point2::new(a.x + nx * distance, a.y + ny * distance);
+        let moved_b = Point2::new(b.x + nx * distance, b.y + ny * distance);
+
+        let new_a = match slide(at(edge_index + n - 1), a, moved_a) {
+            Some(p) => vec![p],
+            None => vec![a, moved_a],
+        };
+        let new_b = match slide(b, at(edge_index + 2), moved_b) {
+            Some(p) => vec![p],
+            None => vec![moved_b, b],
+        };
+
+        // Walk the ring starting just after the pushed edge
+        let mut outer: Vec<Point2> = (2..n).map(|k| at(edge_index + k)).collect();
+        outer.splice(0..0, new_b);
+        outer.extend(new_a);
+        outer.dedup_by(|p, q| p.distance_to(q) < 1e-9);
+        if outer.len() > 1 && outer[0].distance_to(&outer[outer.len() - 1]) < 1e-9 {
+            outer.pop();
+        }
+
+        Some(Self::with_holes(outer, self.holes.clone()))
+    }
+}
+
+/// Line a plan is mirrored across
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "direction", content = "offset", rename_all = "snake_case")]
+pub enum MirrorAxis {
+    /// The line x = offset; east and west swap
+    Vertical(f64),
+    /// The line y = offset; north and south swap
+    Horizontal(f64),
+}
+
+impl MirrorAxis {
+    pub fn reflect(&self, p: &Point2) -> Point2 {
+        match *self {
+            Self::Vertical(x) => Point2::new(2.0 * x - p.x, p.y),
+            Self::Horizontal(y) => Point2::new(p.x, 2.0 * y - p.y),
+        }
+    }
+
+    /// Mirror image of a polygon, with its rings reversed so the winding
+    /// stays counterclockwise outside and clockwise for holes
+    pub fn reflect_polygon(&self, polygon: &Polygon2) -> Polygon2 {
+        let ring = |points: &[Point2]| points.iter().rev().map(|p| self.reflect(p)).collect();
+        Polygon2::with_holes(ring(&polygon.outer), polygon.holes.iter().map(|h| ring(h)).collect())
     }
 }
 
@@ -306,4 +448,49 @@ mod tests {
         let degenerate = Polygon2::new(vec![Point2::new(0.0, 0.0), Point2::new(1.0, 0.0)]);
         assert!(!degenerate.is_valid());
     }
+
+    #[test]
+    fn test_push_edge() {
+        // Pushing the east side of a rectangle keeps it a rectangle
+        let rect = Polygon2::rectangle(40.0, 30.0);
+        let wider = rect.push_edge(1, 5.0).unwrap();
+        assert_eq!(wider.outer.len(), 4);
+        assert!((wider.area() - 1350.0).abs() < 1e-9);
+
+        // Pulling in part of the north side carves out an L
+        let mut notched = rect.clone();
+        notched.outer.insert(3, Point2::new(25.0, 30.0));
+        let l_shape = notched.push_edge(2, -15.0).unwrap();
+        assert_eq!(l_shape.outer.len(), 6);
+        assert!(l_shape.outer.contains(&Point2::new(40.0, 15.0)));
+        assert!(l_shape.outer.contains(&Point2::new(25.0, 15.0)));
+        assert!((l_shape.area() - 975.0).abs() < 1e-9);
+        assert!(l_shape.is_valid());
+
+        assert!(rect.push_edge(4, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_split() {
+        let rect = Polygon2::rectangle(40.0, 30.0);
+        let (left, right) = rect.split(Point2::new(10.0, -5.0), Point2::new(10.0, 35.0)).unwrap();
+        // Heading north, the west strip is on the left
+        assert!((left.area() - 300.0).abs() < 1e-9);
+        assert!((right.area() - 900.0).abs() < 1e-9);
+        assert!(left.outer.contains(&Point2::new(10.0, 0.0)));
+
+        // Cutting through both arms of a U would leave three pieces
+        let u_shape = Polygon2::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(30.0, 0.0),
+            Point2::new(30.0, 30.0),
+            Point2::new(20.0, 30.0),
+            Point2::new(20.0, 10.0),
+            Point2::new(10.0, 10.0),
+            Point2::new(10.0, 30.0),
+            Point2::new(0.0, 30.0),
+        ]);
+        assert!(u_shape.split(Point2::new(0.0, 20.0), Point2::new(30.0, 20.0)).is_none());
+        assert!(rect.split(Point2::new(0.0, 50.0), Point2::new(40.0, 50.0)).is_none());
+    }
 }
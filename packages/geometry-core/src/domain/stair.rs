@@ -0,0 +1,136 @@
+// Stairs - a straight flight connecting two levels of a building
+// The stair stores its configuration; the rise/run is solved from the
+// current level elevations so it stays correct when a level moves.
+
+use serde::{Deserialize, Serialize};
+use super::ids::{LevelId, StairId};
+use super::spatial::Point2;
+
+/// Tallest riser allowed (inches, IRC R311.7.5.1)
+pub const MAX_RISER_HEIGHT: f64 = 7.75;
+/// Shallowest tread allowed (inches, IRC R311.7.5.2)
+pub const MIN_TREAD_DEPTH: f64 = 10.0;
+/// Narrowest clear stair width allowed (feet, IRC R311.7.1)
+pub const MIN_STAIR_WIDTH: f64 = 3.0;
+
+/// Solved riser and tread layout for a flight
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StairRun {
+    pub riser_count: u32,
+    pub riser_height: f64, // inches
+    pub tread_depth: f64,  // inches
+    pub total_rise: f64,   // feet
+    pub total_run: f64,    // feet
+}
+
+impl StairRun {
+    /// Fewest equal risers no taller than `max_riser` (inches) that climb
+    /// `total_rise` (feet). Returns None when there is nothing to climb.
+    pub fn solve(total_rise: f64, max_riser: f64, tread_depth: f64) -> Option<Self> {
+        if total_rise <= 0.0 || max_riser <= 0.0 {
+            return None;
+        }
+        let rise_in = total_rise * 12.0;
+        // Tolerance keeps an exact multiple from rounding up to an extra riser
+        let riser_count = ((rise_in / max_riser) - 1e-9).ceil().max(1.0) as u32;
+        let tread_count = riser_count - 1;
+
+        Some(Self {
+            riser_count,
+            riser_height: rise_in / riser_count as f64,
+            tread_depth,
+            total_rise,
+            total_run: tread_count as f64 * tread_depth / 12.0,
+        })
+    }
+
+    /// Treads between the bottom floor and the top landing
+    pub fn tread_count(&self) -> u32 {
+        self.riser_count.saturating_sub(1)
+    }
+
+    /// Slope of the flight in degrees
+    pub fn angle_degrees(&self) -> f64 {
+        self.riser_height.atan2(self.tread_depth).to_degrees()
+    }
+}
+
+/// A straight stair from one level up to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stair {
+    pub id: StairId,
+    pub from_level_id: LevelId,
+    pub to_level_id: LevelId,
+    /// Plan point at the center of the bottom riser
+    pub start: Point2,
+    /// Plan direction of travel going up, in degrees from the +X axis
+    pub direction: f64,
+    pub width: f64,            // feet
+    pub max_riser_height: f64, // inches
+    pub tread_depth: f64,      // inches
+}
+
+impl Stair {
+    pub fn new(
+        from_level_id: LevelId,
+        to_level_id: LevelId,
+        start: Point2,
+        direction: f64,
+        width: f64,
+        max_riser_height: f64,
+        tread_depth: f64,
+    ) -> Self {
+        Self {
+            id: StairId::new(),
+            from_level_id,
+            to_level_id,
+            start,
+            direction,
+            width,
+            max_riser_height,
+            tread_depth,
+        }
+    }
+
+    /// Solve the flight for the height between the two levels (feet)
+    pub fn solve(&self, total_rise: f64) -> Option<StairRun> {
+        StairRun::solve(total_rise, self.max_riser_height, self.tread_depth)
+    }
+
+    /// Unit vector of travel in plan
+    pub fn direction_vector(&self) -> (f64, f64) {
+        let radians = self.direction.to_radians();
+        (radians.cos(), radians.sin())
+    }
+
+    /// Plan point at the center of the top riser
+    pub fn end(&self, run: &StairRun) -> Point2 {
+        let (dx, dy) = self.direction_vector();
+        Point2::new(
+            self.start.x + dx * run.total_run,
+            self.start.y + dy * run.total_run,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_stair_run() {
+        // 9' floor to floor at 7.75" max: 108 / 7.75 = 13.9 -> 14 risers
+        let run = StairRun::solve(9.0, MAX_RISER_HEIGHT, MIN_TREAD_DEPTH).unwrap();
+        assert_eq!(run.riser_count, 14);
+        assert_eq!(run.tread_count(), 13);
+        assert!(run.riser_height <= MAX_RISER_HEIGHT);
+        assert!((run.riser_height * 14.0 - 108.0).abs() < 1e-9);
+        assert!((run.total_run - 13.0 * 10.0 / 12.0).abs() < 1e-9);
+
+        // An exact multiple doesn't gain an extra riser
+        let run = StairRun::solve(10.0, 7.5, 11.0).unwrap();
+        assert_eq!(run.riser_count, 16);
+
+        assert!(StairRun::solve(0.0, MAX_RISER_HEIGHT, MIN_TREAD_DEPTH).is_none());
+    }
+}
@@ -0,0 +1,154 @@
+// Beams and posts - structural members placed directly in the model
+// Where an open plan leaves a bearing line without a wall under it, a beam
+// carries the load across and posts carry its ends down. Beams sit flush with
+// the framing above the level, so their top is at the level's floor-to-floor
+// height; posts stand on the level's floor.
+
+use serde::{Deserialize, Serialize};
+use super::framing::{FramingMaterial, LumberSize};
+use super::ids::{BeamId, LevelId, PostId};
+use super::spatial::Point2;
+
+/// Cross-section of a beam or post: plies of one member size side by side
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MemberProfile {
+    /// Size of each ply
+    pub lumber_size: LumberSize,
+    pub plies: u8,
+}
+
+impl MemberProfile {
+    pub fn new(lumber_size: LumberSize, plies: u8) -> Self {
+        Self { lumber_size, plies: plies.max(1) }
+    }
+
+    /// Thickness of all plies together (inches)
+    pub fn width(&self) -> f64 {
+        self.lumber_size.actual_dimensions().0 * self.plies as f64
+    }
+
+    /// Depth of the member (inches)
+    pub fn depth(&self) -> f64 {
+        self.lumber_size.actual_dimensions().1
+    }
+
+    /// Takeoff description, e.g. "3-2x10" or "1-4x4"
+    pub fn description(&self) -> String {
+        format!("{}-{}", self.plies, self.lumber_size.nominal_name())
+    }
+}
+
+/// A horizontal beam spanning between two plan points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Beam {
+    pub id: BeamId,
+    pub level_id: LevelId,
+    pub start: Point2,
+    pub end: Point2,
+    pub profile: MemberProfile,
+    pub material: FramingMaterial,
+    /// Top of the beam above the level's floor (feet)
+    pub top_height: f64,
+}
+
+impl Beam {
+    pub fn new(
+        level_id: LevelId,
+        start: Point2,
+        end: Point2,
+        profile: MemberProfile,
+        material: FramingMaterial,
+        top_height: f64,
+    ) -> Self {
+        Self {
+            id: BeamId::new(),
+            level_id,
+            start,
+            end,
+            profile,
+            material,
+            top_height,
+        }
+    }
+
+    /// Span in plan (feet)
+    pub fn length(&self) -> f64 {
+        self.start.distance_to(&self.end)
+    }
+
+    /// Bottom of the beam above the level's floor (feet)
+    pub fn bottom_height(&self) -> f64 {
+        self.top_height - self.profile.depth() / 12.0
+    }
+
+    /// Whether a plan point lies under the beam, within half its width
+    pub fn is_over(&self, point: &Point2) -> bool {
+        let length = self.length();
+        if length < 1e-10 {
+            return false;
+        }
+        let ux = (self.end.x - self.start.x) / length;
+        let uy = (self.end.y - self.start.y) / length;
+        let along = (point.x - self.start.x) * ux + (point.y - self.start.y) * uy;
+        let across = ((point.x - self.start.x) * uy - (point.y - self.start.y) * ux).abs();
+        (0.0..=length).contains(&along) && across <= self.profile.width() / 24.0 + 1e-9
+    }
+}
+
+/// A vertical post standing on a level's floor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Post {
+    pub id: PostId,
+    pub level_id: LevelId,
+    /// Plan point at the center of the post
+    pub location: Point2,
+    pub profile: MemberProfile,
+    pub material: FramingMaterial,
+    /// Height from the floor to the top of the post (feet)
+    pub height: f64,
+}
+
+impl Post {
+    pub fn new(
+        level_id: LevelId,
+        location: Point2,
+        profile: MemberProfile,
+        material: FramingMaterial,
+        height: f64,
+    ) -> Self {
+        Self {
+            id: PostId::new(),
+            level_id,
+            location,
+            profile,
+            material,
+            height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beam_geometry() {
+        let profile = MemberProfile::new(LumberSize::TwoByTwelve, 3);
+        assert_eq!(profile.description(), "3-2x12");
+        assert!((profile.width() - 4.5).abs() < 1e-9);
+
+        let beam = Beam::new(
+            LevelId::new(),
+            Point2::new(0.0, 10.0),
+            Point2::new(16.0, 10.0),
+            profile,
+            FramingMaterial::DF,
+            9.0,
+        );
+        assert!((beam.length() - 16.0).abs() < 1e-9);
+        assert!((beam.bottom_height() - (9.0 - 11.25 / 12.0)).abs() < 1e-9);
+        assert!(beam.is_over(&Point2::new(16.0, 10.1)));
+        assert!(!beam.is_over(&Point2::new(8.0, 10.5)));
+        assert!(!beam.is_over(&Point2::new(17.0, 10.0)));
+    }
+}
@@ -0,0 +1,214 @@
+// Terrain - the ground surface of a site from surveyed XYZ points
+// Points are triangulated into a TIN (Delaunay, so each triangle is as
+// close to equilateral as the points allow), and elevations between them
+// are interpolated linearly across their triangle. Plan coordinates are
+// the site's, elevations in feet on the same datum as pad elevations.
+
+use serde::{Deserialize, Serialize};
+use super::spatial::{Point2, Point3};
+
+/// Points closer than this in plan are the same survey point (feet)
+const DUPLICATE_TOLERANCE: f64 = 1e-6;
+
+/// Triangulated ground surface
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Terrain {
+    pub points: Vec<Point3>,
+    /// Counter-clockwise triples of indices into `points`
+    pub triangles: Vec<[usize; 3]>,
+}
+
+/// Lines of equal elevation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contour {
+    pub elevation: f64,
+    /// Unordered pieces of the line, one per triangle it crosses
+    pub segments: Vec<[Point2; 2]>,
+}
+
+impl Terrain {
+    /// Triangulate survey points. None unless there are three points not
+    /// on one line, or if two points share a plan position.
+    pub fn triangulate(points: Vec<Point3>) -> Option<Self> {
+        if points.len() < 3 || points.iter().any(|p| !(p.x.is_finite() && p.y.is_finite() && p.z.is_finite())) {
+            return None;
+        }
+        for (i, a) in points.iter().enumerate() {
+            if points[i + 1..].iter().any(|b| plan(a).distance_to(&plan(b)) < DUPLICATE_TOLERANCE) {
+                return None;
+            }
+        }
+
+        // Bowyer-Watson: start inside a triangle enclosing every point,
+        // then add points one at a time, re-triangulating the cavity of
+        // triangles whose circumcircles they fall in
+        let (min_x, max_x) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.x), hi.max(p.x)));
+        let (min_y, max_y) = points.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+        let size = (max_x - min_x).max(max_y - min_y).max(1.0);
+        let (cx, cy) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+        let mut vertices: Vec<Point2> = points.iter().map(plan).collect();
+        let n = vertices.len();
+        vertices.push(Point2::new(cx - 20.0 * size, cy - 10.0 * size));
+        vertices.push(Point2::new(cx + 20.0 * size, cy - 10.0 * size));
+        vertices.push(Point2::new(cx, cy + 20.0 * size));
+
+        let mut triangles: Vec<[usize; 3]> = vec![[n, n + 1, n + 2]];
+        for i in 0..n {
+            let p = vertices[i];
+            let (bad, good): (Vec<[usize; 3]>, Vec<[usize; 3]>) = triangles
+                .into_iter()
+                .partition(|t| in_circumcircle(&vertices, t, &p));
+
+            // Edges of the cavity are those only one bad triangle has
+            let mut edges: Vec<(usize, usize)> = Vec::new();
+            for t in &bad {
+                for k in 0..3 {
+                    let edge = (t[k], t[(k + 1) % 3]);
+                    match edges.iter().position(|&(a, b)| (a, b) == (edge.1, edge.0)) {
+                        Some(shared) => {
+                            edges.swap_remove(shared);
+                        }
+                        None => edges.push(edge),
+                    }
+                }
+            }
+            triangles = good;
+            triangles.extend(edges.into_iter().map(|(a, b)| [a, b, i]));
+        }
+        triangles.retain(|t| t.iter().all(|&v| v < n));
+
+        if triangles.is_empty() {
+            return None;
+        }
+        Some(Self { points, triangles })
+    }
+
+    /// Ground elevation at a plan point, None outside the surveyed area
+    pub fn elevation_at(&self, point: &Point2) -> Option<f64> {
+        self.triangles.iter().find_map(|t| {
+            let [a, b, c] = t.map(|i| self.points[i]);
+            let area = cross(&plan(&a), &plan(&b), &plan(&c));
+            let wa = cross(point, &plan(&b), &plan(&c)) / area;
+            let wb = cross(&plan(&a), point, &plan(&c)) / area;
+            let wc = 1.0 - wa - wb;
+            let eps = -1e-9;
+            (wa >= eps && wb >= eps && wc >= eps).then_some(wa * a.z + wb * b.z + wc * c.z)
+        })
+    }
+
+    /// Lowest and highest surveyed elevations
+    pub fn elevation_range(&self) -> (f64, f64) {
+        self.points
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.z), hi.max(p.z)))
+    }
+
+    /// Contours at each multiple of `interval` feet within the surveyed
+    /// elevations
+    pub fn contours(&self, interval: f64) -> Vec<Contour> {
+        if !interval.is_finite() || interval <= 0.0 {
+            return Vec::new();
+        }
+        let (low, high) = self.elevation_range();
+        let mut contours = Vec::new();
+        let mut step = (low / interval).ceil();
+        while step * interval <= high {
+            let elevation = step * interval;
+            let segments: Vec<[Point2; 2]> = self.triangles
+                .iter()
+                .filter_map(|t| {
+                    let corners = t.map(|i| self.points[i]);
+                    // A corner exactly on the contour counts as above it
+                    let crossings: Vec<Point2> = (0..3)
+                        .filter_map(|k| {
+                            let (a, b) = (corners[k], corners[(k + 1) % 3]);
+                            ((a.z >= elevation) != (b.z >= elevation)).then(|| {
+                                let t = (elevation - a.z) / (b.z - a.z);
+                                Point2::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+                            })
+                        })
+                        .collect();
+                    // Touching a single corner isn't a crossing
+                    (crossings.len() == 2 && crossings[0].distance_to(&crossings[1]) > DUPLICATE_TOLERANCE)
+                        .then(|| [crossings[0], crossings[1]])
+                })
+                .collect();
+            if !segments.is_empty() {
+                contours.push(Contour { elevation, segments });
+            }
+            step += 1.0;
+        }
+        contours
+    }
+}
+
+fn plan(p: &Point3) -> Point2 {
+    Point2::new(p.x, p.y)
+}
+
+/// Twice the signed area of triangle abc, positive when counter-clockwise
+fn cross(a: &Point2, b: &Point2, c: &Point2) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Whether `p` lies inside the circle through a triangle's corners
+fn in_circumcircle(vertices: &[Point2], t: &[usize; 3], p: &Point2) -> bool {
+    let [a, b, c] = t.map(|i| vertices[i]);
+    let (ax, ay) = (a.x - p.x, a.y - p.y);
+    let (bx, by) = (b.x - p.x, b.y - p.y);
+    let (cx, cy) = (c.x - p.x, c.y - p.y);
+    let det = (ax * ax + ay * ay) * (bx * cy - cx * by)
+        - (bx * bx + by * by) * (ax * cy - cx * ay)
+        + (cx * cx + cy * cy) * (ax * by - bx * ay);
+    // The determinant's sign flips with the triangle's winding
+    if cross(&a, &b, &c) > 0.0 { det > 0.0 } else { det < 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Grid of points on the plane z = 100 + x / 10
+    fn sloped_grid() -> Vec<Point3> {
+        (0..=4)
+            .flat_map(|i| (0..=3).map(move |j| (i as f64 * 10.0, j as f64 * 10.0)))
+            .map(|(x, y)| Point3::new(x, y, 100.0 + x / 10.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_triangulate_grid() {
+        let terrain = Terrain::triangulate(sloped_grid()).unwrap();
+        // A 4 x 3 grid of cells, two triangles each
+        assert_eq!(terrain.triangles.len(), 24);
+        assert!(terrain.triangles.iter().all(|t| {
+            let [a, b, c] = t.map(|i| plan(&terrain.points[i]));
+            cross(&a, &b, &c) > 0.0
+        }));
+
+        let z = terrain.elevation_at(&Point2::new(25.0, 12.5)).unwrap();
+        assert!((z - 102.5).abs() < 1e-9);
+        assert!(terrain.elevation_at(&Point2::new(45.0, 5.0)).is_none());
+
+        assert!(Terrain::triangulate(vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 0.0)]).is_none());
+        assert!(Terrain::triangulate(vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(2.0, 2.0, 1.0),
+        ]).is_none());
+    }
+
+    #[test]
+    fn test_contours() {
+        let terrain = Terrain::triangulate(sloped_grid()).unwrap();
+        let contours = terrain.contours(1.0);
+        // One line per foot, above the low edge at 100'
+        assert_eq!(contours.iter().map(|c| c.elevation).collect::<Vec<_>>(), vec![101.0, 102.0, 103.0, 104.0]);
+
+        // The 102' contour runs north-south along x = 20
+        let line = &contours[1];
+        assert!(line.segments.iter().flatten().all(|p| (p.x - 20.0).abs() < 1e-9));
+        let length: f64 = line.segments.iter().map(|[a, b]| a.distance_to(b)).sum();
+        assert!((length - 30.0).abs() < 1e-9);
+    }
+}
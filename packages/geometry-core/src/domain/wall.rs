@@ -1,5 +1,6 @@
 // Wall structures - assemblies and individual wall elements
-// Walls are defined by centerline with layers offset from center
+// Walls are defined by centerline with layers offset from center. A curved
+// wall's centerline is a circular arc between its start and end points.
 
 use serde::{Deserialize, Serialize};
 use super::ids::*;
@@ -11,7 +12,14 @@ use super::framing::WallFramingConfig;
 pub struct WallLayer {
     pub material: String,
     pub thickness: f64,      // in current units
+    #[serde(default)]
     pub is_structural: bool,
+    /// Layer is part of the thermal insulation (counts toward the envelope)
+    #[serde(default)]
+    pub is_insulation: bool,
+    /// Thermal resistance per inch of thickness (h·ft²·°F/Btu per inch)
+    #[serde(default)]
+    pub r_value_per_inch: f64,
 }
 
 impl WallLayer {
@@ -20,25 +28,96 @@ impl WallLayer {
             material: material.into(),
             thickness,
             is_structural,
+            is_insulation: false,
+            r_value_per_inch: 0.0,
+        }
+    }
+
+    pub fn with_r_value_per_inch(mut self, r_value_per_inch: f64) -> Self {
+        self.r_value_per_inch = r_value_per_inch;
+        self
+    }
+
+    /// Thermal resistance of the layer through its full thickness
+    pub fn r_value(&self) -> f64 {
+        self.thickness * self.r_value_per_inch
+    }
+
+    /// Non-structural insulation layer
+    pub fn insulation(material: impl Into<String>, thickness: f64) -> Self {
+        Self {
+            is_insulation: true,
+            ..Self::new(material, thickness, false)
         }
     }
 
     /// Common layer presets (thicknesses in inches for Imperial)
+    /// Stud layers carry no R-value: the insulation beside them in the
+    /// same cavity is counted instead.
     pub fn gypsum_5_8() -> Self {
-        Self::new("Gypsum Board 5/8\"", 0.625, false)
+        Self::new("Gypsum Board 5/8\"", 0.625, false).with_r_value_per_inch(0.9)
     }
 
     pub fn osb_7_16() -> Self {
-        Self::new("OSB 7/16\"", 0.4375, true)
+        Self::new("OSB 7/16\"", 0.4375, true).with_r_value_per_inch(1.42)
     }
 
     pub fn fiberglass_r19() -> Self {
-        Self::new("Fiberglass Insulation R19", 6.25, false)
+        Self::insulation("Fiberglass Insulation R19", 6.25).with_r_value_per_inch(19.0 / 6.25)
     }
 
     pub fn stud_2x6() -> Self {
         Self::new("2x6 Wood Stud", 5.5, true)
     }
+
+    pub fn stud_2x4() -> Self {
+        Self::new("2x4 Wood Stud", 3.5, true)
+    }
+
+    /// Fire-rated gypsum for separation walls
+    pub fn gypsum_type_x_5_8() -> Self {
+        Self::new("Type X Gypsum Board 5/8\"", 0.625, false).with_r_value_per_inch(0.9)
+    }
+
+    /// Backer for tiled and wet walls
+    pub fn cement_board_1_2() -> Self {
+        Self::new("Cement Board 1/2\"", 0.5, false).with_r_value_per_inch(0.52)
+    }
+}
+
+/// What a wall separates, used to pick an assembly when none is given
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssemblyRole {
+    /// On the building perimeter
+    Exterior,
+    /// Between a garage and the dwelling
+    FireSeparation,
+    /// Bounding a bathroom or laundry
+    WetArea,
+    /// Any other interior wall
+    Interior,
+}
+
+impl AssemblyRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Exterior => "exterior",
+            Self::FireSeparation => "fire_separation",
+            Self::WetArea => "wet_area",
+            Self::Interior => "interior",
+        }
+    }
+
+    /// Built-in assembly used when the project has no default for this role
+    pub fn preset(&self) -> WallAssembly {
+        match self {
+            Self::Exterior => WallAssembly::exterior_2x6(),
+            Self::FireSeparation => WallAssembly::fire_separation(),
+            Self::WetArea => WallAssembly::wet_area_partition(),
+            Self::Interior => WallAssembly::interior_partition(),
+        }
+    }
 }
 
 /// A wall assembly defining the complete layer stack
@@ -80,16 +159,53 @@ impl WallAssembly {
             "Interior Partition",
             vec![
                 WallLayer::gypsum_5_8(),
-                WallLayer::new("2x4 Wood Stud", 3.5, true),
+                WallLayer::stud_2x4(),
                 WallLayer::gypsum_5_8(),
             ],
         )
     }
 
-    /// Calculate R-value if layers have thermal properties (stub for future)
-    pub fn r_value(&self) -> f64 {
-        // Placeholder - would sum R-values of layers
-        0.0
+    /// Garage/dwelling separation with Type X gypsum on both faces
+    pub fn fire_separation() -> Self {
+        Self::new(
+            "Fire Separation Wall",
+            vec![
+                WallLayer::gypsum_type_x_5_8(),
+                WallLayer::stud_2x4(),
+                WallLayer::gypsum_type_x_5_8(),
+            ],
+        )
+    }
+
+    /// Partition with a cement board face for bathrooms and laundries
+    pub fn wet_area_partition() -> Self {
+        Self::new(
+            "Wet Area Partition",
+            vec![
+                WallLayer::cement_board_1_2(),
+                WallLayer::stud_2x4(),
+                WallLayer::gypsum_5_8(),
+            ],
+        )
+    }
+
+    /// Whether a face carries the 1/2" gypsum board a garage/dwelling
+    /// separation needs (IRC R302.6)
+    pub fn separates_garage(&self) -> bool {
+        self.layers
+            .iter()
+            .any(|l| l.material.to_lowercase().contains("gypsum") && l.thickness >= 0.5 - 1e-9)
+    }
+
+    /// Whether any layer in the stack is insulation
+    pub fn is_insulated(&self) -> bool {
+        self.layers.iter().any(|l| l.is_insulation)
+    }
+
+    /// Thermal resistance through the assembly, with the layers in series.
+    /// Air films at the faces are not included.
+    pub fn total_r_value(&self) -> f64 {
+        self.layers.iter().map(|l| l.r_value()).sum()
     }
 
     /// Get structural depth (thickness of structural layers)
@@ -103,6 +219,45 @@ impl WallAssembly {
     }
 }
 
+/// Circular arc followed by a curved wall's centerline
+/// The arc takes the shorter way around from start to end. A positive
+/// radius bows the wall out to its left (see `Wall::perpendicular`), a
+/// negative one to its right.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WallArc {
+    pub radius: f64,
+}
+
+impl WallArc {
+    /// Whether the arc can span a chord of this length
+    pub fn fits_chord(&self, chord: f64) -> bool {
+        self.radius.is_finite() && 2.0 * self.radius.abs() >= chord
+    }
+}
+
+/// How one end of a wall meets the rest of the wall network
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WallJoin {
+    /// Stands free, cut square
+    #[default]
+    Free,
+    /// Meets an end of another wall; both ends are mitered
+    Corner { wall: WallId },
+    /// Stops against the side of another wall, which runs through
+    Tee { wall: WallId },
+}
+
+impl WallJoin {
+    /// The other wall at this join, if any
+    pub fn wall(&self) -> Option<WallId> {
+        match self {
+            WallJoin::Free => None,
+            WallJoin::Corner { wall } | WallJoin::Tee { wall } => Some(*wall),
+        }
+    }
+}
+
 /// An individual wall instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Wall {
@@ -113,6 +268,15 @@ pub struct Wall {
     pub end: Point2,    // centerline end point
     pub height: f64,    // usually matches floor_to_floor
     pub base_offset: f64, // offset from level elevation
+    /// Centerline arc for a curved wall (None = straight)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arc: Option<WallArc>,
+    /// How the start meets other walls, kept current by the store
+    #[serde(default)]
+    pub start_join: WallJoin,
+    /// How the end meets other walls, kept current by the store
+    #[serde(default)]
+    pub end_join: WallJoin,
     /// Framing configuration for this wall
     pub framing_config: WallFramingConfig,
     /// Reference to generated framing layout (if generated)
@@ -135,6 +299,9 @@ impl Wall {
             end,
             height,
             base_offset: 0.0,
+            arc: None,
+            start_join: WallJoin::Free,
+            end_join: WallJoin::Free,
             framing_config: WallFramingConfig::default(),
             framing_layout_id: None,
         }
@@ -145,6 +312,16 @@ impl Wall {
         self
     }
 
+    /// Curve the centerline along an arc of `radius` (signed, see `WallArc`)
+    pub fn with_arc(mut self, radius: f64) -> Self {
+        self.arc = Some(WallArc { radius });
+        self
+    }
+
+    pub fn is_curved(&self) -> bool {
+        self.arc.is_some()
+    }
+
     /// Set framing configuration
     pub fn with_framing_config(mut self, config: WallFramingConfig) -> Self {
         self.framing_config = config;
@@ -157,16 +334,24 @@ impl Wall {
         self
     }
 
-    /// Get the wall length (horizontal distance)
-    pub fn length(&self) -> f64 {
+    /// Straight-line distance from start to end
+    pub fn chord_length(&self) -> f64 {
         self.start.distance_to(&self.end)
     }
 
-    /// Get the centerline direction vector (normalized)
+    /// Get the wall length along its centerline (arc length for curved walls)
+    pub fn length(&self) -> f64 {
+        match self.arc_geometry() {
+            Some((_, radius, sweep)) => radius.abs() * sweep,
+            None => self.chord_length(),
+        }
+    }
+
+    /// Get the chord direction vector from start to end (normalized)
     pub fn direction(&self) -> (f64, f64) {
         let dx = self.end.x - self.start.x;
         let dy = self.end.y - self.start.y;
-        let len = self.length();
+        let len = self.chord_length();
         if len > 1e-10 {
             (dx / len, dy / len)
         } else {
@@ -187,10 +372,107 @@ impl Wall {
 
     /// Get the centerline midpoint
     pub fn midpoint(&self) -> Point2 {
-        Point2::new(
-            (self.start.x + self.end.x) / 2.0,
-            (self.start.y + self.end.y) / 2.0,
-        )
+        self.point_at(self.length() / 2.0)
+    }
+
+    /// Center, signed radius and sweep angle (radians) of a curved wall's arc
+    pub fn arc_geometry(&self) -> Option<(Point2, f64, f64)> {
+        let arc = self.arc?;
+        let half_chord = self.chord_length() / 2.0;
+        if half_chord < 1e-10 || !arc.fits_chord(2.0 * half_chord) {
+            return None;
+        }
+        let radius = arc.radius;
+        // The center sits across the chord from the bulge
+        let apothem = (radius * radius - half_chord * half_chord).max(0.0).sqrt();
+        let (px, py) = self.perpendicular();
+        let mid = Point2::new((self.start.x + self.end.x) / 2.0, (self.start.y + self.end.y) / 2.0);
+        let side = -radius.signum();
+        let center = Point2::new(mid.x + side * px * apothem, mid.y + side * py * apothem);
+        let sweep = 2.0 * (half_chord / radius.abs()).min(1.0).asin();
+        Some((center, radius, sweep))
+    }
+
+    /// Point on the centerline `distance` from the start, measured along it
+    pub fn point_at(&self, distance: f64) -> Point2 {
+        match self.arc_geometry() {
+            Some((center, radius, _)) => {
+                // Bowing left means turning right, clockwise about the center
+                let angle = (self.start.y - center.y).atan2(self.start.x - center.x) - distance / radius;
+                Point2::new(
+                    center.x + radius.abs() * angle.cos(),
+                    center.y + radius.abs() * angle.sin(),
+                )
+            }
+            None => {
+                let (dx, dy) = self.direction();
+                Point2::new(self.start.x + dx * distance, self.start.y + dy * distance)
+            }
+        }
+    }
+
+    /// Unit direction of travel along the centerline `distance` from the start
+    pub fn tangent_at(&self, distance: f64) -> (f64, f64) {
+        match self.arc_geometry() {
+            Some((center, radius, _)) => {
+                let p = self.point_at(distance);
+                let (rx, ry) = ((p.x - center.x) / radius.abs(), (p.y - center.y) / radius.abs());
+                let s = radius.signum();
+                (s * ry, -s * rx)
+            }
+            None => self.direction(),
+        }
+    }
+
+    /// Plan angle (radians from +x) of the centerline `distance` from the start
+    pub fn rotation_at(&self, distance: f64) -> f64 {
+        let (dx, dy) = self.tangent_at(distance);
+        dy.atan2(dx)
+    }
+
+    /// Distance along the centerline of the point on it nearest `p`, and
+    /// how far `p` lies to the left of it (negative to the right). The
+    /// distance falls outside 0..length when `p` is beyond an end.
+    pub fn project(&self, p: &Point2) -> (f64, f64) {
+        match self.arc_geometry() {
+            Some((center, radius, _)) => {
+                let s = radius.signum();
+                let a0 = (self.start.y - center.y).atan2(self.start.x - center.x);
+                let ap = (p.y - center.y).atan2(p.x - center.x);
+                let mut turned = (a0 - ap) * s;
+                while turned <= -std::f64::consts::PI {
+                    turned += 2.0 * std::f64::consts::PI;
+                }
+                while turned > std::f64::consts::PI {
+                    turned -= 2.0 * std::f64::consts::PI;
+                }
+                (turned * radius.abs(), s * (center.distance_to(p) - radius.abs()))
+            }
+            None => {
+                let (dx, dy) = self.direction();
+                let (px, py) = (p.x - self.start.x, p.y - self.start.y);
+                (px * dx + py * dy, dx * py - dy * px)
+            }
+        }
+    }
+
+    /// Centerline as a polyline: the two endpoints for a straight wall, or
+    /// points along the arc no more than `max_angle` radians apart
+    pub fn centerline_points(&self, max_angle: f64) -> Vec<Point2> {
+        match self.arc_geometry() {
+            Some((_, _, sweep)) => {
+                let segments = (sweep / max_angle.max(1e-3) - 1e-9).ceil().max(1.0) as usize;
+                let length = self.length();
+                (0..=segments)
+                    .map(|i| match i {
+                        0 => self.start,
+                        i if i == segments => self.end,
+                        i => self.point_at(length * i as f64 / segments as f64),
+                    })
+                    .collect()
+            }
+            None => vec![self.start, self.end],
+        }
     }
 
     /// Check if this wall shares an endpoint with another wall
@@ -213,6 +495,39 @@ mod tests {
         assert!(assembly.layers.len() == 4);
     }
 
+    #[test]
+    fn test_wall_assembly_insulation() {
+        assert!(WallAssembly::exterior_2x6().is_insulated());
+        assert!(!WallAssembly::interior_partition().is_insulated());
+
+        // Only the explicit flag counts, not the material name
+        let siding = WallAssembly::new(
+            "Board and Batten",
+            vec![WallLayer::new("Board and Batten Siding", 0.75, false)],
+        );
+        assert!(!siding.is_insulated());
+        let blown = WallAssembly::new("Blown", vec![WallLayer::insulation("R-21 Blown-In", 5.5)]);
+        assert!(blown.is_insulated());
+    }
+
+    #[test]
+    fn test_wall_assembly_r_value() {
+        // Drywall, R-19 batts, and OSB; the studs add nothing in series
+        let r = WallAssembly::exterior_2x6().total_r_value();
+        assert!((r - (0.625 * 0.9 + 19.0 + 0.4375 * 1.42)).abs() < 1e-9);
+
+        // Layers authored as JSON leave out what they don't need
+        let layers: Vec<WallLayer> = serde_json::from_str(
+            r#"[{"material": "XPS Foam", "thickness": 2.0, "r_value_per_inch": 5.0, "is_insulation": true},
+                {"material": "Brick Veneer", "thickness": 3.5, "r_value_per_inch": 0.11}]"#,
+        )
+        .unwrap();
+        assert!(!layers[0].is_structural);
+        let assembly = WallAssembly::new("Brick on Foam", layers);
+        assert!((assembly.total_r_value() - 10.385).abs() < 1e-9);
+        assert!(assembly.is_insulated());
+    }
+
     #[test]
     fn test_wall_length() {
         let wall = Wall::new(
@@ -265,6 +580,45 @@ mod tests {
         assert!((py - 1.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_curved_wall_geometry() {
+        // Half circle of radius 5 bowing to the left (+y) of a wall along x
+        let wall = Wall::new(
+            WallAssemblyId::new(),
+            LevelId::new(),
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+            9.0,
+        )
+        .with_arc(5.0);
+        let (center, _, sweep) = wall.arc_geometry().unwrap();
+        assert!(center.distance_to(&Point2::new(5.0, 0.0)) < 1e-10);
+        assert!((sweep - std::f64::consts::PI).abs() < 1e-10);
+        assert!((wall.length() - 5.0 * std::f64::consts::PI).abs() < 1e-10);
+        assert!((wall.area() - 45.0 * std::f64::consts::PI).abs() < 1e-9);
+        assert!(wall.midpoint().distance_to(&Point2::new(5.0, 5.0)) < 1e-10);
+        assert!(wall.point_at(wall.length()).distance_to(&wall.end) < 1e-10);
+
+        // Heading up at the start, along +x at the top, down at the end
+        let (tx, ty) = wall.tangent_at(0.0);
+        assert!(tx.abs() < 1e-10 && (ty - 1.0).abs() < 1e-10);
+        assert!(wall.rotation_at(wall.length() / 2.0).abs() < 1e-10);
+
+        // Outside the bulge is to the wall's left
+        let (along, offset) = wall.project(&Point2::new(5.0, 6.0));
+        assert!((along - wall.length() / 2.0).abs() < 1e-10);
+        assert!((offset - 1.0).abs() < 1e-10);
+
+        // A negative radius bows the other way; too small a radius is a straight wall
+        let flipped = wall.clone().with_arc(-5.0);
+        assert!(flipped.midpoint().distance_to(&Point2::new(5.0, -5.0)) < 1e-10);
+        assert!((flipped.project(&Point2::new(5.0, -6.0)).1 + 1.0).abs() < 1e-10);
+        let shallow = wall.clone().with_arc(10.0);
+        assert!(shallow.length() > 10.0 && shallow.length() < 5.0 * std::f64::consts::PI);
+        assert_eq!(shallow.centerline_points(0.1).len(), 12);
+        assert!(wall.clone().with_arc(4.0).arc_geometry().is_none());
+    }
+
     #[test]
     fn test_wall_connection() {
         let wall1 = Wall::new(
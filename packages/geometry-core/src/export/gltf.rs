@@ -0,0 +1,482 @@
+// glTF export - a level or building as one binary glTF (GLB) scene
+// Each wall, slab, ceiling, roof, framing member, beam, post and stair part
+// becomes a named node under its level, drawn with the material slot of
+// its kind. Vertices stay in plan feet with z up; the root node converts
+// to glTF's meters with y up.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::domain::deck::DECK_STEP_DOWN;
+use crate::domain::{BuildingId, FramingMember, Level, LevelId, Point3};
+use crate::geometry::{
+    create_oriented_box, extrude_polygon, generate_beam_solid, generate_post_solid, generate_roof_solid,
+    generate_stair_solids, generate_wall_solid, solid_to_mesh, MeshData, MeshQuality,
+};
+use crate::store::Store;
+
+const FEET_TO_METERS: f32 = 0.3048;
+/// Tessellation tolerance (feet) for building parts and for framing lumber
+const TOLERANCE: f64 = 0.1;
+const FRAMING_TOLERANCE: f64 = 0.05;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Material an exported mesh is drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaterialSlot {
+    Wall,
+    Floor,
+    Ceiling,
+    Roof,
+    Framing,
+    Structural,
+    Stair,
+}
+
+impl MaterialSlot {
+    /// Every slot, in material index order
+    pub const ALL: [Self; 7] = [
+        Self::Wall,
+        Self::Floor,
+        Self::Ceiling,
+        Self::Roof,
+        Self::Framing,
+        Self::Structural,
+        Self::Stair,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Wall => "wall",
+            Self::Floor => "floor",
+            Self::Ceiling => "ceiling",
+            Self::Roof => "roof",
+            Self::Framing => "framing",
+            Self::Structural => "structural",
+            Self::Stair => "stair",
+        }
+    }
+
+    fn base_color(&self) -> [f32; 4] {
+        match self {
+            Self::Wall => [0.92, 0.90, 0.86, 1.0],
+            Self::Floor => [0.76, 0.64, 0.48, 1.0],
+            Self::Ceiling => [0.97, 0.97, 0.97, 1.0],
+            Self::Roof => [0.36, 0.38, 0.42, 1.0],
+            Self::Framing => [0.87, 0.72, 0.50, 1.0],
+            Self::Structural => [0.70, 0.52, 0.32, 1.0],
+            Self::Stair => [0.65, 0.50, 0.35, 1.0],
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|s| s == self).unwrap_or(0)
+    }
+}
+
+/// Node in the exported scene: a group, or one entity's mesh
+struct SceneNode {
+    name: String,
+    extras: Option<Value>,
+    mesh: Option<(MeshData, MaterialSlot)>,
+    children: Vec<SceneNode>,
+}
+
+impl SceneNode {
+    fn group(name: impl Into<String>) -> Self {
+        Self { name: name.into(), extras: None, mesh: None, children: Vec::new() }
+    }
+
+    fn entity(name: String, kind: &str, id: String, mesh: MeshData, slot: MaterialSlot) -> Self {
+        Self {
+            name,
+            extras: Some(json!({ "kind": kind, "id": id })),
+            mesh: Some((mesh, slot)),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Export one level as a GLB file
+pub fn export_level_glb(store: &Store, level_id: LevelId) -> Result<Vec<u8>> {
+    let level = store.get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+    let root = level_node(store, level);
+    Ok(write_glb(&level.name, root))
+}
+
+/// Export every level of a building as a GLB file
+pub fn export_building_glb(store: &Store, building_id: BuildingId) -> Result<Vec<u8>> {
+    let building = store.get_building(building_id)
+        .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+    let mut root = SceneNode::group(building.name.clone());
+    for level in store.get_building_levels_by_elevation(building_id) {
+        root.children.push(level_node(store, level));
+    }
+    Ok(write_glb(&building.name, root))
+}
+
+/// A level as a group with one node per entity
+fn level_node(store: &Store, level: &Level) -> SceneNode {
+    let mut node = SceneNode::group(level.name.clone());
+    node.children = level_parts(store, level, true, MeshQuality::High)
+        .into_iter()
+        .map(|part| SceneNode::entity(part.name, part.kind, part.id, part.mesh, part.slot))
+        .collect();
+    node
+}
+
+/// One entity's mesh within an exported level
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelPart {
+    pub name: String,
+    /// What the entity is, e.g. "wall", "roof" or "framing_member"
+    pub kind: &'static str,
+    pub id: String,
+    pub mesh: MeshData,
+    pub slot: MaterialSlot,
+}
+
+/// Meshes for every part of a level. Parts whose solid can't be built are
+/// left out rather than failing the export. Without `walls`, wall solids
+/// are skipped (their framing is kept) for callers that mesh walls apart.
+/// Wall and floor framing is left out at qualities that exclude it.
+pub(crate) fn level_parts(store: &Store, level: &Level, walls: bool, quality: MeshQuality) -> Vec<LevelPart> {
+    let mut parts = Vec::new();
+    let mut add = |name: String, kind: &'static str, id: String, mesh: Option<MeshData>, slot: MaterialSlot| {
+        if let Some(mesh) = mesh.filter(|m| !m.indices.is_empty()) {
+            parts.push(LevelPart { name, kind, id, mesh, slot });
+        }
+    };
+
+    let network = store.wall_network(level.id);
+    for wall in store.get_level_walls(level.id) {
+        if walls {
+            let openings = store.get_wall_openings(wall.id);
+            let mesh = generate_wall_solid(wall, store.wall_thickness(wall), level.elevation, &openings, &network.cuts(wall.id))
+                .and_then(|solid| quality.mesh(&solid, TOLERANCE))
+                .ok();
+            add(format!("Wall {}", wall.id), "wall", wall.id.to_string(), mesh, MaterialSlot::Wall);
+        }
+
+        if let Some(layout) = store.get_wall_framing_layout(wall.id).filter(|_| quality.includes_framing()) {
+            for member in &layout.members {
+                let mesh = member_mesh(member, level.elevation + wall.base_offset);
+                add(
+                    format!("{} {}", member.member_type.display_name(), member.id),
+                    "framing_member",
+                    member.id.to_string(),
+                    mesh,
+                    MaterialSlot::Framing,
+                );
+            }
+        }
+    }
+
+    for slab in store.get_level_floor_slabs(level.id) {
+        let thickness = slab.thickness();
+        let mesh = store.slab_outline(level.id, slab.boundary)
+            .and_then(|outline| extrude_polygon(outline, level.elevation - thickness, thickness).ok())
+            .and_then(|solid| quality.mesh(&solid, TOLERANCE).ok());
+        add(format!("Floor slab {}", slab.id), "floor_slab", slab.id.to_string(), mesh, MaterialSlot::Floor);
+    }
+    for ceiling in store.get_level_ceilings(level.id) {
+        let mesh = store.ceiling_elevation(ceiling.id)
+            .zip(store.slab_outline(level.id, ceiling.boundary))
+            .and_then(|(bottom, outline)| extrude_polygon(outline, bottom, ceiling.thickness()).ok())
+            .and_then(|solid| quality.mesh(&solid, TOLERANCE).ok());
+        add(format!("Ceiling {}", ceiling.id), "ceiling", ceiling.id.to_string(), mesh, MaterialSlot::Ceiling);
+    }
+
+    if let Some(layout) = store.get_floor_framing_layout(level.id).filter(|_| quality.includes_framing()) {
+        for member in &layout.members {
+            add(
+                format!("{} {}", member.member_type.display_name(), member.id),
+                "framing_member",
+                member.id.to_string(),
+                member_mesh(member, level.elevation),
+                MaterialSlot::Framing,
+            );
+        }
+    }
+
+    if let (Some(roof), Some(footprint)) = (store.get_level_roof(level.id), store.get_level_footprint(level.id)) {
+        let mesh = generate_roof_solid(roof, &footprint.polygon, level.ceiling_elevation())
+            .and_then(|solid| quality.mesh(&solid, TOLERANCE))
+            .ok();
+        add(format!("Roof {}", roof.id), "roof", roof.id.to_string(), mesh, MaterialSlot::Roof);
+    }
+
+    for beam in store.get_level_beams(level.id) {
+        let mesh = generate_beam_solid(beam, level.elevation)
+            .and_then(|solid| quality.mesh(&solid, FRAMING_TOLERANCE))
+            .ok();
+        add(format!("Beam {}", beam.id), "beam", beam.id.to_string(), mesh, MaterialSlot::Structural);
+    }
+    for post in store.get_level_posts(level.id) {
+        let mesh = generate_post_solid(post, level.elevation)
+            .and_then(|solid| quality.mesh(&solid, FRAMING_TOLERANCE))
+            .ok();
+        add(format!("Post {}", post.id), "post", post.id.to_string(), mesh, MaterialSlot::Structural);
+    }
+
+    for deck in store.get_level_decks(level.id) {
+        let Ok(layout) = store.get_deck_framing(deck.id) else {
+            continue;
+        };
+        for member in &layout.members {
+            add(
+                format!("{} {} {}", deck.kind.display_name(), member.member_type.display_name(), member.id),
+                "deck",
+                deck.id.to_string(),
+                member_mesh(member, level.elevation - DECK_STEP_DOWN),
+                MaterialSlot::Framing,
+            );
+        }
+    }
+
+    for stair in store.get_level_stairs(level.id) {
+        let Ok(solids) = store.get_stair_run(stair.id)
+            .and_then(|run| generate_stair_solids(stair, &run, level.elevation))
+        else {
+            continue;
+        };
+        let treads = solids.treads.iter().enumerate().map(|(i, s)| (format!("tread {}", i + 1), s));
+        let stringers = solids.stringers.iter().enumerate().map(|(i, s)| (format!("stringer {}", i + 1), s));
+        for (part, solid) in treads.chain(stringers) {
+            let mesh = quality.mesh(solid, TOLERANCE).ok();
+            add(format!("Stair {} {}", stair.id, part), "stair", stair.id.to_string(), mesh, MaterialSlot::Stair);
+        }
+    }
+
+    parts
+}
+
+/// Lumber as a box: vertical members stand `length` tall, the rest lie
+/// along their rotation
+fn member_mesh(member: &FramingMember, base_z: f64) -> Option<MeshData> {
+    let (width, depth) = member.lumber_size.actual_dimensions();
+    let (width, depth, length) = (width / 12.0, depth / 12.0, member.length / 12.0);
+    let origin = Point3::new(member.position.x / 12.0, member.position.y / 12.0, base_z + member.position.z / 12.0);
+    let solid = if member.member_type.is_vertical() {
+        create_oriented_box(width, depth, length, &origin, member.rotation)
+    } else {
+        create_oriented_box(length, width, depth, &origin, member.rotation)
+    };
+    solid.and_then(|s| solid_to_mesh(&s, FRAMING_TOLERANCE)).ok()
+}
+
+/// glTF JSON and binary buffer, filled in as nodes are added
+#[derive(Default)]
+struct GltfWriter {
+    nodes: Vec<Value>,
+    meshes: Vec<Value>,
+    accessors: Vec<Value>,
+    buffer_views: Vec<Value>,
+    buffer: Vec<u8>,
+}
+
+impl GltfWriter {
+    /// Add a node and its children; returns the node's index
+    fn add_node(&mut self, node: SceneNode) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(json!({ "name": node.name }));
+
+        if let Some(extras) = node.extras {
+            self.nodes[index]["extras"] = extras;
+        }
+        if let Some((mesh, slot)) = node.mesh {
+            let mesh_index = self.add_mesh(&node.name, mesh, slot);
+            self.nodes[index]["mesh"] = json!(mesh_index);
+        }
+        let children: Vec<usize> = node.children.into_iter().map(|child| self.add_node(child)).collect();
+        if !children.is_empty() {
+            self.nodes[index]["children"] = json!(children);
+        }
+        index
+    }
+
+    fn add_mesh(&mut self, name: &str, mesh: MeshData, slot: MaterialSlot) -> usize {
+        let count = mesh.vertex_count();
+        let (mut min, mut max) = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+        for p in mesh.positions.chunks_exact(3) {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+            }
+        }
+
+        let position = self.add_accessor(floats(&mesh.positions), ARRAY_BUFFER, FLOAT, count, "VEC3");
+        self.accessors[position]["min"] = json!(min);
+        self.accessors[position]["max"] = json!(max);
+        let normal = self.add_accessor(floats(&mesh.normals), ARRAY_BUFFER, FLOAT, count, "VEC3");
+        let indices: Vec<u8> = mesh.indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let indices = self.add_accessor(indices, ELEMENT_ARRAY_BUFFER, UNSIGNED_INT, mesh.indices.len(), "SCALAR");
+
+        self.meshes.push(json!({
+            "name": name,
+            "primitives": [{
+                "attributes": { "POSITION": position, "NORMAL": normal },
+                "indices": indices,
+                "material": slot.index(),
+                "mode": 4,
+            }],
+        }));
+        self.meshes.len() - 1
+    }
+
+    fn add_accessor(&mut self, bytes: Vec<u8>, target: u32, component_type: u32, count: usize, kind: &str) -> usize {
+        self.buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+            "target": target,
+        }));
+        self.buffer.extend(bytes);
+        self.accessors.push(json!({
+            "bufferView": self.buffer_views.len() - 1,
+            "componentType": component_type,
+            "count": count,
+            "type": kind,
+        }));
+        self.accessors.len() - 1
+    }
+}
+
+fn floats(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Assemble the scene under a root that turns plan feet (z up) into glTF
+/// meters (y up), and pack it as GLB
+fn write_glb(scene_name: &str, root: SceneNode) -> Vec<u8> {
+    let mut writer = GltfWriter::default();
+    let root_index = writer.add_node(root);
+    let s = FEET_TO_METERS;
+    writer.nodes[root_index]["matrix"] = json!([
+        s, 0.0, 0.0, 0.0,
+        0.0, 0.0, -s, 0.0,
+        0.0, s, 0.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    ]);
+
+    let materials: Vec<Value> = MaterialSlot::ALL
+        .iter()
+        .map(|slot| json!({
+            "name": slot.name(),
+            "pbrMetallicRoughness": {
+                "baseColorFactor": slot.base_color(),
+                "metallicFactor": 0.0,
+                "roughnessFactor": 0.9,
+            },
+        }))
+        .collect();
+
+    let mut gltf = json!({
+        "asset": { "version": "2.0", "generator": "geometry-core" },
+        "scene": 0,
+        "scenes": [{ "name": scene_name, "nodes": [root_index] }],
+        "nodes": writer.nodes,
+        "materials": materials,
+    });
+    if !writer.meshes.is_empty() {
+        gltf["meshes"] = json!(writer.meshes);
+        gltf["accessors"] = json!(writer.accessors);
+        gltf["bufferViews"] = json!(writer.buffer_views);
+        gltf["buffers"] = json!([{ "byteLength": writer.buffer.len() }]);
+    }
+
+    // Chunks are padded to four bytes: JSON with spaces, binary with zeros
+    let mut json_chunk = gltf.to_string().into_bytes();
+    json_chunk.resize(json_chunk.len().next_multiple_of(4), b' ');
+    let mut bin_chunk = writer.buffer;
+    bin_chunk.resize(bin_chunk.len().next_multiple_of(4), 0);
+
+    let has_bin = !bin_chunk.is_empty();
+    let total = 12 + 8 + json_chunk.len() + if has_bin { 8 + bin_chunk.len() } else { 0 };
+    let mut glb = Vec::with_capacity(total);
+    glb.extend(GLB_MAGIC.to_le_bytes());
+    glb.extend(2u32.to_le_bytes());
+    glb.extend((total as u32).to_le_bytes());
+    glb.extend((json_chunk.len() as u32).to_le_bytes());
+    glb.extend(CHUNK_JSON.to_le_bytes());
+    glb.extend(json_chunk);
+    if has_bin {
+        glb.extend((bin_chunk.len() as u32).to_le_bytes());
+        glb.extend(CHUNK_BIN.to_le_bytes());
+        glb.extend(bin_chunk);
+    }
+    glb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    /// Split a GLB into its JSON document and binary chunk length
+    fn read_glb(glb: &[u8]) -> (Value, usize) {
+        let word = |at: usize| u32::from_le_bytes(glb[at..at + 4].try_into().unwrap()) as usize;
+        assert_eq!(word(0) as u32, GLB_MAGIC);
+        assert_eq!(word(8), glb.len());
+        let json_len = word(12);
+        let gltf = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+        let bin_len = if glb.len() > 20 + json_len { word(20 + json_len) } else { 0 };
+        (gltf, bin_len)
+    }
+
+    #[test]
+    fn test_export_building_glb() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let first = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.add_level(building_id, "Second Floor", 9.0, 8.0).unwrap();
+        store.set_level_footprint(first, Polygon2::rectangle(30.0, 20.0)).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall = store
+            .create_wall(first, assembly_id, Point2::new(0.0, 0.0), Point2::new(30.0, 0.0), 9.0)
+            .unwrap();
+        store.add_opening(wall, OpeningType::Window, 0.5, 4.0, 4.0, 3.0).unwrap();
+        store.generate_wall_framing(wall).unwrap();
+        store.create_floor_slab(first, SlabBoundary::Level, FloorSlab::framed_layers()).unwrap();
+
+        let glb = export_building_glb(&store, building_id).unwrap();
+        let (gltf, bin_len) = read_glb(&glb);
+        assert_eq!(gltf["buffers"][0]["byteLength"].as_u64().unwrap() as usize, bin_len);
+        assert_eq!(gltf["materials"].as_array().unwrap().len(), MaterialSlot::ALL.len());
+
+        // Root building node, then levels in elevation order
+        let nodes = gltf["nodes"].as_array().unwrap();
+        assert_eq!(nodes[0]["name"], "Main");
+        let levels = nodes[0]["children"].as_array().unwrap();
+        assert_eq!(nodes[levels[0].as_u64().unwrap() as usize]["name"], "First Floor");
+
+        let named = |prefix: &str| nodes.iter().filter(|n| n["name"].as_str().unwrap().starts_with(prefix)).count();
+        assert_eq!(named("Wall "), 1);
+        assert_eq!(named("Floor slab "), 1);
+        assert!(named("Stud ") > 0);
+        let wall_node = nodes.iter().find(|n| n["extras"]["kind"] == "wall").unwrap();
+        assert_eq!(wall_node["extras"]["id"], wall.to_string());
+        let wall_mesh = &gltf["meshes"][wall_node["mesh"].as_u64().unwrap() as usize];
+        assert_eq!(wall_mesh["primitives"][0]["material"], MaterialSlot::Wall.index());
+
+        let glb = export_level_glb(&store, first).unwrap();
+        let (gltf, _) = read_glb(&glb);
+        assert_eq!(gltf["scenes"][0]["name"], "First Floor");
+        assert!(export_level_glb(&store, LevelId::new()).is_err());
+    }
+}
@@ -0,0 +1,474 @@
+// IFC export - a project as an IFC4 STEP physical file (.ifc)
+// Spatial structure is project > site > building > storey. Walls are
+// IfcWall bodies with their assembly as a material layer set, rooms are
+// IfcSpace extrusions, and openings are IfcOpeningElement voids filled by
+// an IfcDoor or IfcWindow. Plan feet are written as meters.
+
+use anyhow::{anyhow, Result};
+use uuid::Uuid;
+
+use crate::domain::{Building, Level, OpeningType, Point2, ProjectId, Wall, WallAssembly};
+use crate::store::Store;
+
+const FEET_TO_METERS: f64 = 0.3048;
+const INCHES_TO_METERS: f64 = 0.0254;
+/// Depth of door and window bodies within their opening (feet)
+const FILL_THICKNESS: f64 = 0.15;
+
+/// Write a project and all its buildings as IFC4 SPF text
+pub fn export_project_ifc(store: &Store, project_id: ProjectId) -> Result<String> {
+    let project = store.get_project(project_id)
+        .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+
+    let mut ifc = IfcWriter::default();
+    let origin = ifc.add("IFCCARTESIANPOINT((0.,0.,0.))".to_string());
+    let z_axis = ifc.add("IFCDIRECTION((0.,0.,1.))".to_string());
+    let world = ifc.add(format!("IFCAXIS2PLACEMENT3D(#{},$,$)", origin));
+    let context = ifc.add(format!("IFCGEOMETRICREPRESENTATIONCONTEXT($,'Model',3,1.E-05,#{},$)", world));
+    let body = ifc.add(format!(
+        "IFCGEOMETRICREPRESENTATIONSUBCONTEXT('Body','Model',*,*,*,*,#{},$,.MODEL_VIEW.,$)",
+        context
+    ));
+    let units = [
+        ifc.add("IFCSIUNIT(*,.LENGTHUNIT.,$,.METRE.)".to_string()),
+        ifc.add("IFCSIUNIT(*,.AREAUNIT.,$,.SQUARE_METRE.)".to_string()),
+        ifc.add("IFCSIUNIT(*,.VOLUMEUNIT.,$,.CUBIC_METRE.)".to_string()),
+        ifc.add("IFCSIUNIT(*,.PLANEANGLEUNIT.,$,.RADIAN.)".to_string()),
+    ];
+    let units = ifc.add(format!("IFCUNITASSIGNMENT({})", refs(&units)));
+    let ifc_project = ifc.add(format!(
+        "IFCPROJECT('{}',$,{},$,$,$,$,(#{}),#{})",
+        global_id(project.id.as_uuid()),
+        text(&project.name),
+        context,
+        units
+    ));
+
+    let site_placement = ifc.add(format!("IFCLOCALPLACEMENT($,#{})", world));
+    let site = ifc.add(format!(
+        "IFCSITE('{}',$,'Site',$,$,#{},$,$,.ELEMENT.,$,$,$,$,$)",
+        new_global_id(),
+        site_placement
+    ));
+    ifc.aggregate(ifc_project, &[site]);
+
+    let mut ctx = ExportContext { ifc, z_axis, body };
+    let mut buildings = Vec::new();
+    for building in project.building_ids.iter().filter_map(|id| store.get_building(*id)) {
+        buildings.push(ctx.building(store, building, site_placement));
+    }
+    ctx.ifc.aggregate(site, &buildings);
+
+    let timestamp = project.modified_at.format("%Y-%m-%dT%H:%M:%S");
+    Ok(ctx.ifc.finish(&project.name, &timestamp.to_string()))
+}
+
+/// Entity lines of the DATA section, numbered as they are added
+#[derive(Default)]
+struct IfcWriter {
+    lines: Vec<String>,
+}
+
+impl IfcWriter {
+    /// Add an entity; returns its instance number
+    fn add(&mut self, entity: String) -> usize {
+        self.lines.push(entity);
+        self.lines.len()
+    }
+
+    fn point2(&mut self, x: f64, y: f64) -> usize {
+        self.add(format!("IFCCARTESIANPOINT(({},{}))", real(x), real(y)))
+    }
+
+    fn point3(&mut self, x: f64, y: f64, z: f64) -> usize {
+        self.add(format!("IFCCARTESIANPOINT(({},{},{}))", real(x), real(y), real(z)))
+    }
+
+    /// Placement at a point in meters, relative to `parent` (None = world),
+    /// with its x axis turned to `x_dir` when given
+    fn placement(&mut self, parent: Option<usize>, at: (f64, f64, f64), x_dir: Option<(f64, f64)>, z_axis: usize) -> usize {
+        let point = self.point3(at.0, at.1, at.2);
+        let axes = match x_dir {
+            Some((x, y)) => {
+                let direction = self.add(format!("IFCDIRECTION(({},{},0.))", real(x), real(y)));
+                format!("#{},#{}", z_axis, direction)
+            }
+            None => "$,$".to_string(),
+        };
+        let axis = self.add(format!("IFCAXIS2PLACEMENT3D(#{},{})", point, axes));
+        let parent = parent.map(|p| format!("#{}", p)).unwrap_or_else(|| "$".to_string());
+        self.add(format!("IFCLOCALPLACEMENT({},#{})", parent, axis))
+    }
+
+    fn aggregate(&mut self, relating: usize, related: &[usize]) {
+        if !related.is_empty() {
+            self.add(format!("IFCRELAGGREGATES('{}',$,$,$,#{},{})", new_global_id(), relating, refs(related)));
+        }
+    }
+
+    fn finish(self, name: &str, timestamp: &str) -> String {
+        let mut out = String::from("ISO-10303-21;\nHEADER;\n");
+        out.push_str("FILE_DESCRIPTION(('ViewDefinition [ReferenceView]'),'2;1');\n");
+        out.push_str(&format!(
+            "FILE_NAME({},'{}',(''),(''),'geometry-core','geometry-core','');\n",
+            text(&format!("{}.ifc", name)),
+            timestamp
+        ));
+        out.push_str("FILE_SCHEMA(('IFC4'));\nENDSEC;\nDATA;\n");
+        for (i, line) in self.lines.iter().enumerate() {
+            out.push_str(&format!("#{}={};\n", i + 1, line));
+        }
+        out.push_str("ENDSEC;\nEND-ISO-10303-21;\n");
+        out
+    }
+}
+
+/// Writer plus the shared context entities every product refers to
+struct ExportContext {
+    ifc: IfcWriter,
+    z_axis: usize,
+    body: usize,
+}
+
+impl ExportContext {
+    fn building(&mut self, store: &Store, building: &Building, site_placement: usize) -> usize {
+        let o = &building.origin;
+        let placement = self.ifc.placement(
+            Some(site_placement),
+            (o.x * FEET_TO_METERS, o.y * FEET_TO_METERS, o.z * FEET_TO_METERS),
+            None,
+            self.z_axis,
+        );
+        let ifc_building = self.ifc.add(format!(
+            "IFCBUILDING('{}',$,{},$,$,#{},$,$,.ELEMENT.,$,$,$)",
+            new_global_id(),
+            text(&building.name),
+            placement
+        ));
+        let storeys: Vec<usize> = store
+            .get_building_levels_by_elevation(building.id)
+            .into_iter()
+            .map(|level| self.storey(store, level, placement))
+            .collect();
+        self.ifc.aggregate(ifc_building, &storeys);
+        ifc_building
+    }
+
+    fn storey(&mut self, store: &Store, level: &Level, building_placement: usize) -> usize {
+        let elevation = level.elevation * FEET_TO_METERS;
+        let placement = self.ifc.placement(Some(building_placement), (0.0, 0.0, elevation), None, self.z_axis);
+        let storey = self.ifc.add(format!(
+            "IFCBUILDINGSTOREY('{}',$,{},$,$,#{},$,$,.ELEMENT.,{})",
+            global_id(level.id.as_uuid()),
+            text(&level.name),
+            placement,
+            real(elevation)
+        ));
+
+        let mut contained = Vec::new();
+        for wall in store.get_level_walls(level.id) {
+            contained.extend(self.wall(store, wall, placement));
+        }
+        if !contained.is_empty() {
+            self.ifc.add(format!(
+                "IFCRELCONTAINEDINSPATIALSTRUCTURE('{}',$,$,$,{},#{})",
+                new_global_id(),
+                refs(&contained),
+                storey
+            ));
+        }
+
+        let spaces: Vec<usize> = store
+            .get_level_rooms(level.id)
+            .into_iter()
+            .map(|room| {
+                let height = room.ceiling_height.unwrap_or(level.floor_to_floor);
+                let solid = self.extruded_polygon(&room.boundary.outer, &room.boundary.holes, height);
+                let shape = self.shape(solid);
+                let placement = self.ifc.placement(Some(placement), (0.0, 0.0, 0.0), None, self.z_axis);
+                self.ifc.add(format!(
+                    "IFCSPACE('{}',$,{},$,{},#{},#{},{},.ELEMENT.,.SPACE.,$)",
+                    global_id(room.id.as_uuid()),
+                    text(&room.name),
+                    text(&room.room_type.display_name()),
+                    placement,
+                    shape,
+                    text(&room.room_type.display_name())
+                ))
+            })
+            .collect();
+        self.ifc.aggregate(storey, &spaces);
+        storey
+    }
+
+    /// A wall with its material layers, openings and fills; returns the
+    /// elements contained directly in the storey. Curved walls are written
+    /// along their chord.
+    fn wall(&mut self, store: &Store, wall: &Wall, storey_placement: usize) -> Vec<usize> {
+        let length = wall.chord_length();
+        if length <= 1e-9 {
+            return Vec::new();
+        }
+        let thickness = store.wall_thickness(wall);
+        let placement = self.ifc.placement(
+            Some(storey_placement),
+            (wall.start.x * FEET_TO_METERS, wall.start.y * FEET_TO_METERS, wall.base_offset * FEET_TO_METERS),
+            Some(wall.direction()),
+            self.z_axis,
+        );
+        let solid = self.extruded_rectangle(length / 2.0, 0.0, length, thickness, wall.height);
+        let shape = self.shape(solid);
+        let ifc_wall = self.ifc.add(format!(
+            "IFCWALL('{}',$,{},$,$,#{},#{},$,.SOLIDWALL.)",
+            global_id(wall.id.as_uuid()),
+            text(&format!("Wall {}", wall.id)),
+            placement,
+            shape
+        ));
+        if let Some(assembly) = store.get_wall_assembly(wall.assembly_id) {
+            self.layer_set(assembly, ifc_wall);
+        }
+
+        let mut elements = vec![ifc_wall];
+        for opening in store.get_wall_openings(wall.id) {
+            let left = opening.position_along_wall * length - opening.width / 2.0;
+            let opening_placement = self.ifc.placement(
+                Some(placement),
+                (left * FEET_TO_METERS, 0.0, opening.sill_height * FEET_TO_METERS),
+                None,
+                self.z_axis,
+            );
+            // Cut a little past both faces so the void goes clean through
+            let solid = self.extruded_rectangle(opening.width / 2.0, 0.0, opening.width, thickness + 0.1, opening.height);
+            let shape = self.shape(solid);
+            let ifc_opening = self.ifc.add(format!(
+                "IFCOPENINGELEMENT('{}',$,{},$,$,#{},#{},$,.OPENING.)",
+                global_id(opening.id.as_uuid()),
+                text(&format!("Opening {}", opening.id)),
+                opening_placement,
+                shape
+            ));
+            self.ifc.add(format!("IFCRELVOIDSELEMENT('{}',$,$,$,#{},#{})", new_global_id(), ifc_wall, ifc_opening));
+
+            let entity = match opening.opening_type {
+                OpeningType::Door => "IFCDOOR",
+                OpeningType::Window => "IFCWINDOW",
+                OpeningType::Other(_) => continue,
+            };
+            let solid = self.extruded_rectangle(opening.width / 2.0, 0.0, opening.width, FILL_THICKNESS, opening.height);
+            let shape = self.shape(solid);
+            let fill_placement = self.ifc.placement(Some(opening_placement), (0.0, 0.0, 0.0), None, self.z_axis);
+            let predefined = if entity == "IFCDOOR" { ".DOOR.,.NOTDEFINED." } else { ".WINDOW.,.NOTDEFINED." };
+            let fill = self.ifc.add(format!(
+                "{}('{}',$,{},$,$,#{},#{},$,{},{},{},$)",
+                entity,
+                new_global_id(),
+                text(&opening.opening_type.display_name()),
+                fill_placement,
+                shape,
+                real(opening.height * FEET_TO_METERS),
+                real(opening.width * FEET_TO_METERS),
+                predefined
+            ));
+            self.ifc.add(format!("IFCRELFILLSELEMENT('{}',$,$,$,#{},#{})", new_global_id(), ifc_opening, fill));
+            elements.push(fill);
+        }
+        elements
+    }
+
+    /// Assembly layers as a layer set used across the wall's thickness,
+    /// centered on its axis
+    fn layer_set(&mut self, assembly: &WallAssembly, ifc_wall: usize) {
+        let layers: Vec<usize> = assembly
+            .layers
+            .iter()
+            .map(|layer| {
+                let material = self.ifc.add(format!("IFCMATERIAL({},$,$)", text(&layer.material)));
+                self.ifc.add(format!(
+                    "IFCMATERIALLAYER(#{},{},.F.,{},$,$,$)",
+                    material,
+                    real(layer.thickness * INCHES_TO_METERS),
+                    text(&layer.material)
+                ))
+            })
+            .collect();
+        let set = self.ifc.add(format!("IFCMATERIALLAYERSET({},{},$)", refs(&layers), text(&assembly.name)));
+        let offset = -assembly.total_thickness * INCHES_TO_METERS / 2.0;
+        let usage = self.ifc.add(format!("IFCMATERIALLAYERSETUSAGE(#{},.AXIS2.,.POSITIVE.,{},$)", set, real(offset)));
+        self.ifc.add(format!("IFCRELASSOCIATESMATERIAL('{}',$,$,$,(#{}),#{})", new_global_id(), ifc_wall, usage));
+    }
+
+    /// Rectangle centered at (cx, cy) feet, extruded up `height` feet
+    fn extruded_rectangle(&mut self, cx: f64, cy: f64, x_dim: f64, y_dim: f64, height: f64) -> usize {
+        let center = self.ifc.point2(cx * FEET_TO_METERS, cy * FEET_TO_METERS);
+        let position = self.ifc.add(format!("IFCAXIS2PLACEMENT2D(#{},$)", center));
+        let profile = self.ifc.add(format!(
+            "IFCRECTANGLEPROFILEDEF(.AREA.,$,#{},{},{})",
+            position,
+            real(x_dim * FEET_TO_METERS),
+            real(y_dim * FEET_TO_METERS)
+        ));
+        self.extrude(profile, height)
+    }
+
+    /// Plan polygon (feet) with holes, extruded up `height` feet
+    fn extruded_polygon(&mut self, outer: &[Point2], holes: &[Vec<Point2>], height: f64) -> usize {
+        let outer = self.polyline(outer);
+        let profile = if holes.is_empty() {
+            self.ifc.add(format!("IFCARBITRARYCLOSEDPROFILEDEF(.AREA.,$,#{})", outer))
+        } else {
+            let inner: Vec<usize> = holes.iter().map(|hole| self.polyline(hole)).collect();
+            self.ifc.add(format!("IFCARBITRARYPROFILEDEFWITHVOIDS(.AREA.,$,#{},{})", outer, refs(&inner)))
+        };
+        self.extrude(profile, height)
+    }
+
+    /// Closed polyline: IFC repeats the first point at the end
+    fn polyline(&mut self, points: &[Point2]) -> usize {
+        let mut ids: Vec<usize> = points
+            .iter()
+            .map(|p| self.ifc.point2(p.x * FEET_TO_METERS, p.y * FEET_TO_METERS))
+            .collect();
+        if let Some(&first) = ids.first() {
+            ids.push(first);
+        }
+        self.ifc.add(format!("IFCPOLYLINE({})", refs(&ids)))
+    }
+
+    fn extrude(&mut self, profile: usize, height: f64) -> usize {
+        let origin = self.ifc.point3(0.0, 0.0, 0.0);
+        let position = self.ifc.add(format!("IFCAXIS2PLACEMENT3D(#{},$,$)", origin));
+        self.ifc.add(format!(
+            "IFCEXTRUDEDAREASOLID(#{},#{},#{},{})",
+            profile,
+            position,
+            self.z_axis,
+            real(height * FEET_TO_METERS)
+        ))
+    }
+
+    fn shape(&mut self, solid: usize) -> usize {
+        let representation = self.ifc.add(format!(
+            "IFCSHAPEREPRESENTATION(#{},'Body','SweptSolid',(#{}))",
+            self.body,
+            solid
+        ));
+        self.ifc.add(format!("IFCPRODUCTDEFINITIONSHAPE($,$,(#{}))", representation))
+    }
+}
+
+/// `(#1,#2,...)`
+fn refs(ids: &[usize]) -> String {
+    let items: Vec<String> = ids.iter().map(|id| format!("#{}", id)).collect();
+    format!("({})", items.join(","))
+}
+
+/// SPF real: always has a decimal point, no exponent, trailing zeros trimmed
+fn real(value: f64) -> String {
+    let s = format!("{:.6}", value);
+    let s = s.trim_end_matches('0');
+    if s == "-0." { "0.".to_string() } else { s.to_string() }
+}
+
+/// SPF string literal: quotes doubled, backslashes doubled, anything
+/// outside printable ASCII as \X2\ UTF-16 hex
+fn text(value: &str) -> String {
+    let mut out = String::from("'");
+    for c in value.chars() {
+        match c {
+            '\'' => out.push_str("''"),
+            '\\' => out.push_str("\\\\"),
+            ' '..='~' => out.push(c),
+            _ => {
+                let mut units = [0u16; 2];
+                let hex: String = c.encode_utf16(&mut units).iter().map(|u| format!("{:04X}", u)).collect();
+                out.push_str(&format!("\\X2\\{}\\X0\\", hex));
+            }
+        }
+    }
+    out.push('\'');
+    out
+}
+
+const GUID_CHARS: &[u8; 64] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz_$";
+
+/// IFC compressed GUID: the 128 bits as 22 base-64 characters, the first
+/// holding the top two bits
+fn global_id(uuid: &Uuid) -> String {
+    let n = uuid.as_u128();
+    (0..22)
+        .map(|i| GUID_CHARS[((n >> (6 * (21 - i))) & 63) as usize] as char)
+        .collect()
+}
+
+/// GUID for entities with no domain id (relationships, site, building)
+fn new_global_id() -> String {
+    global_id(&Uuid::new_v4())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    #[test]
+    fn test_export_project_ifc() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Smith's House", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("2x6 Exterior", vec![WallLayer::osb_7_16(), WallLayer::stud_2x6()])
+            .unwrap();
+        let wall = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        store.add_opening(wall, OpeningType::Door, 0.25, 3.0, 6.8, 0.0).unwrap();
+        store.add_opening(wall, OpeningType::Window, 0.75, 4.0, 4.0, 3.0).unwrap();
+        store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(20.0, 12.0))
+            .unwrap();
+
+        let ifc = export_project_ifc(&store, project_id).unwrap();
+        assert!(ifc.starts_with("ISO-10303-21;"));
+        assert!(ifc.contains("FILE_SCHEMA(('IFC4'));"));
+        assert!(ifc.contains("IFCPROJECT("));
+        assert!(ifc.contains("'Smith''s House'"));
+        assert!(ifc.contains("IFCBUILDINGSTOREY("));
+        assert!(ifc.contains(&format!("IFCWALL('{}'", global_id(wall.as_uuid()))));
+        assert_eq!(ifc.matches("IFCOPENINGELEMENT(").count(), 2);
+        assert_eq!(ifc.matches("IFCRELVOIDSELEMENT(").count(), 2);
+        assert_eq!(ifc.matches("IFCRELFILLSELEMENT(").count(), 2);
+        assert_eq!(ifc.matches("IFCDOOR(").count(), 1);
+        assert_eq!(ifc.matches("IFCWINDOW(").count(), 1);
+        assert_eq!(ifc.matches("IFCSPACE(").count(), 1);
+        assert_eq!(ifc.matches("IFCMATERIALLAYER(").count(), 2);
+        assert!(ifc.contains("IFCMATERIALLAYERSET("));
+        assert!(ifc.contains("'2x6 Exterior'"));
+
+        // Every reference points at an entity that exists
+        let count = ifc.lines().filter(|l| l.starts_with('#')).count();
+        for line in ifc.lines().filter(|l| l.starts_with('#')) {
+            let body = &line[line.find('=').unwrap()..];
+            for part in body.split('#').skip(1) {
+                let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+                let id: usize = digits.parse().unwrap();
+                assert!(id >= 1 && id <= count, "dangling #{} in {}", id, line);
+            }
+        }
+
+        assert!(export_project_ifc(&store, ProjectId::new()).is_err());
+    }
+
+    #[test]
+    fn test_ifc_encoding() {
+        assert_eq!(real(1.0), "1.");
+        assert_eq!(real(-0.0000001), "0.");
+        assert_eq!(real(0.3048), "0.3048");
+        assert_eq!(text("Café \\ 'A'"), "'Caf\\X2\\00E9\\X0\\ \\\\ ''A'''");
+        assert_eq!(global_id(&Uuid::nil()), "0000000000000000000000");
+        assert_eq!(global_id(&Uuid::from_u128(u128::MAX)), "3$$$$$$$$$$$$$$$$$$$$$");
+    }
+}
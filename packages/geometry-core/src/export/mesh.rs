@@ -0,0 +1,348 @@
+// Mesh export - every part of a level merged into one mesh, for writing
+// as OBJ or STL, and the wall, room and shell meshes a viewer draws a level
+// with. Coordinates stay in plan feet with z up.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::gltf::{level_parts, LevelPart};
+use crate::domain::{BuildingId, LevelId, Opening, RoomId, WallId};
+use crate::geometry::{extrude_polygon, extrude_polygon_shell, generate_wall_solid, MeshData, MeshQuality};
+use crate::store::Store;
+
+/// Tessellation tolerance (feet) for viewer meshes
+const TOLERANCE: f64 = 0.1;
+
+/// Cleanup applied to an exported mesh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeshExportOptions {
+    /// Merge coincident vertices so the mesh is connected (also recomputes
+    /// normals)
+    pub weld: bool,
+    /// Distance in feet under which vertices are merged
+    pub weld_tolerance: f64,
+    /// Recompute smooth normals without welding
+    pub recompute_normals: bool,
+    /// Tessellation detail; below high, framing is left out
+    pub quality: MeshQuality,
+}
+
+impl Default for MeshExportOptions {
+    fn default() -> Self {
+        Self {
+            weld: false,
+            weld_tolerance: 0.001,
+            recompute_normals: false,
+            quality: MeshQuality::High,
+        }
+    }
+}
+
+/// Walls, slabs, roof, framing and stairs of a level as one mesh
+pub fn export_level_mesh(store: &Store, level_id: LevelId, options: &MeshExportOptions) -> Result<MeshData> {
+    let level = store.get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+    let mut mesh = MeshData::default();
+    for part in level_parts(store, level, true, options.quality) {
+        mesh.append(part.mesh);
+    }
+    if options.weld {
+        mesh = mesh.welded(options.weld_tolerance);
+    } else if options.recompute_normals {
+        mesh.recompute_normals();
+    }
+    Ok(mesh)
+}
+
+/// A wall mitered at corners and trimmed at tees, openings cut through
+#[derive(Debug, Clone, Serialize)]
+pub struct WallMesh {
+    pub wall_id: WallId,
+    pub thickness: f64,
+    pub mesh: MeshData,
+}
+
+/// A room's floor plate: the modeled slab's thickness, else a framed floor,
+/// with its top at the level elevation
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomMesh {
+    pub room_id: RoomId,
+    pub mesh: MeshData,
+}
+
+/// Footprint shell and room floor plates of a level
+#[derive(Debug, Clone, Serialize)]
+pub struct CombinedLevelMesh {
+    /// Every footprint extruded as a shell, None if the level has none
+    pub shell: Option<MeshData>,
+    /// Why a footprint's shell couldn't be built (the shell is then None)
+    pub shell_error: Option<String>,
+    pub rooms: Vec<RoomMesh>,
+}
+
+/// Meshes for each wall on a level. Walls whose solid can't be built are
+/// left out.
+pub fn level_wall_meshes(store: &Store, level_id: LevelId, quality: MeshQuality) -> Result<Vec<WallMesh>> {
+    let level = store.get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+    let network = store.wall_network(level_id);
+    Ok(store.get_level_walls(level_id)
+        .into_iter()
+        .filter_map(|wall| {
+            let thickness = store.wall_thickness(wall);
+            let openings = store.get_wall_openings(wall.id);
+            let solid = generate_wall_solid(wall, thickness, level.elevation, &openings, &network.cuts(wall.id)).ok()?;
+            let mesh = quality.mesh(&solid, TOLERANCE).ok()?;
+            Some(WallMesh { wall_id: wall.id, thickness, mesh })
+        })
+        .collect())
+}
+
+/// Floor plates for each room on a level. Rooms with invalid boundaries,
+/// or that fail to extrude, are left out.
+pub fn level_room_meshes(store: &Store, level_id: LevelId, quality: MeshQuality) -> Result<Vec<RoomMesh>> {
+    let level = store.get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+    Ok(store.get_level_rooms(level_id)
+        .into_iter()
+        .filter(|room| room.boundary.is_valid())
+        .filter_map(|room| {
+            let floor_thickness = store.room_floor_thickness(room.id);
+            let solid = extrude_polygon(&room.boundary, level.elevation - floor_thickness, floor_thickness).ok()?;
+            let mesh = quality.mesh(&solid, TOLERANCE).ok()?;
+            Some(RoomMesh { room_id: room.id, mesh })
+        })
+        .collect())
+}
+
+/// The level's footprints as a shell of walls `wall_thickness` thick, with
+/// its room floor plates
+pub fn level_combined_mesh(
+    store: &Store,
+    level_id: LevelId,
+    wall_thickness: f64,
+    quality: MeshQuality,
+) -> Result<CombinedLevelMesh> {
+    let level = store.get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+    let footprints = store.get_level_footprints(level_id);
+    let mut shell = (!footprints.is_empty()).then(MeshData::default);
+    let mut shell_error = None;
+    for footprint in footprints {
+        match extrude_polygon_shell(&footprint.polygon, level.elevation, level.floor_to_floor, wall_thickness) {
+            Ok(solid) => {
+                if let (Some(shell), Ok(mesh)) = (shell.as_mut(), quality.mesh(&solid, TOLERANCE)) {
+                    shell.append(mesh);
+                }
+            }
+            Err(e) => shell_error = Some(e.to_string()),
+        }
+    }
+    if shell_error.is_some() {
+        shell = None;
+    }
+
+    Ok(CombinedLevelMesh {
+        shell,
+        shell_error,
+        rooms: level_room_meshes(store, level_id, quality)?,
+    })
+}
+
+/// Everything a viewer draws for one level
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelScene {
+    pub level_id: LevelId,
+    pub name: String,
+    pub elevation: f64,
+    pub height: f64,
+    /// Detail the level was meshed at
+    pub quality: MeshQuality,
+    pub shell: Option<MeshData>,
+    pub shell_error: Option<String>,
+    pub walls: Vec<WallMesh>,
+    pub rooms: Vec<RoomMesh>,
+    /// Openings already cut through the wall meshes, for labels and picking
+    pub openings: Vec<Opening>,
+    /// Slabs, ceilings, roof, framing as last generated, beams, posts,
+    /// decks and stairs
+    pub parts: Vec<LevelPart>,
+}
+
+/// Every level of a building, lowest first
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildingScene {
+    pub building_id: BuildingId,
+    pub name: String,
+    pub levels: Vec<LevelScene>,
+}
+
+/// A building's full scene in one pass, so a viewer can rebuild it without
+/// asking for each level's walls, rooms and parts separately. With a
+/// `focus` level, that level is meshed at `quality` and the others one
+/// step coarser for each level they are away from it.
+pub fn building_scene(
+    store: &Store,
+    building_id: BuildingId,
+    wall_thickness: f64,
+    quality: MeshQuality,
+    focus: Option<LevelId>,
+) -> Result<BuildingScene> {
+    let building = store.get_building(building_id)
+        .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+
+    let levels = store.get_building_levels_by_elevation(building_id);
+    let focus_index = focus.and_then(|id| levels.iter().position(|l| l.id == id));
+    let levels = levels
+        .into_iter()
+        .enumerate()
+        .map(|(i, level)| {
+            let quality = quality.coarsened(focus_index.map_or(0, |f| f.abs_diff(i)));
+            let combined = level_combined_mesh(store, level.id, wall_thickness, quality)?;
+            Ok(LevelScene {
+                level_id: level.id,
+                name: level.name.clone(),
+                elevation: level.elevation,
+                height: level.floor_to_floor,
+                quality,
+                shell: combined.shell,
+                shell_error: combined.shell_error,
+                walls: level_wall_meshes(store, level.id, quality)?,
+                rooms: combined.rooms,
+                openings: store.get_level_walls(level.id)
+                    .into_iter()
+                    .flat_map(|wall| store.get_wall_openings(wall.id))
+                    .cloned()
+                    .collect(),
+                parts: level_parts(store, level, false, quality),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(BuildingScene { building_id, name: building.name.clone(), levels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    #[test]
+    fn test_export_level_mesh() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 10.0), Point2::new(20.0, 10.0), 9.0)
+            .unwrap();
+
+        let raw = export_level_mesh(&store, level_id, &MeshExportOptions::default()).unwrap();
+        let welded = export_level_mesh(&store, level_id, &MeshExportOptions { weld: true, ..Default::default() }).unwrap();
+        assert!(welded.vertex_count() < raw.vertex_count());
+        assert_eq!(welded.normals.len(), welded.positions.len());
+        // Two separate 20' x 9' walls, each 5.5" thick
+        let expected = 2.0 * 20.0 * 9.0 * 5.5 / 12.0;
+        assert!((welded.volume() - expected).abs() < 1e-3);
+        assert_eq!(welded.to_stl().len(), 84 + welded.triangle_count() * 50);
+
+        assert!(export_level_mesh(&store, LevelId::new(), &MeshExportOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_level_viewer_meshes() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+
+        let walls = level_wall_meshes(&store, level_id, MeshQuality::High).unwrap();
+        assert_eq!(walls.len(), 1);
+        assert_eq!(walls[0].wall_id, wall_id);
+        assert!((walls[0].thickness - 5.5 / 12.0).abs() < 1e-9);
+
+        let rooms = level_room_meshes(&store, level_id, MeshQuality::High).unwrap();
+        assert_eq!(rooms.len(), 1);
+        assert_eq!(rooms[0].room_id, room_id);
+        assert!(rooms[0].mesh.triangle_count() > 0);
+
+        // No footprint yet, so no shell
+        let combined = level_combined_mesh(&store, level_id, 0.5, MeshQuality::High).unwrap();
+        assert!(combined.shell.is_none() && combined.shell_error.is_none());
+        assert_eq!(combined.rooms.len(), 1);
+
+        store.set_level_footprint(level_id, Polygon2::rectangle(30.0, 20.0)).unwrap();
+        let combined = level_combined_mesh(&store, level_id, 0.5, MeshQuality::High).unwrap();
+        assert!(combined.shell.is_some_and(|shell| shell.triangle_count() > 0));
+
+        assert!(level_wall_meshes(&store, LevelId::new(), MeshQuality::High).is_err());
+    }
+
+    #[test]
+    fn test_building_scene() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let upper_id = store.add_level(building_id, "Second Floor", 9.0, 9.0).unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(30.0, 20.0)).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let opening_id = store.add_opening(wall_id, OpeningType::Window, 0.5, 3.0, 4.0, 3.0).unwrap();
+        store.generate_wall_framing(wall_id).unwrap();
+        store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+
+        let scene = building_scene(&store, building_id, 0.5, MeshQuality::High, None).unwrap();
+        assert_eq!(scene.levels.iter().map(|l| l.level_id).collect::<Vec<_>>(), vec![level_id, upper_id]);
+        let first = &scene.levels[0];
+        assert!(first.shell.is_some());
+        assert_eq!(first.walls.len(), 1);
+        assert_eq!(first.rooms.len(), 1);
+        assert_eq!(first.openings.iter().map(|o| o.id).collect::<Vec<_>>(), vec![opening_id]);
+        // Walls are meshed once, in `walls`; their framing is a part
+        assert!(first.parts.iter().all(|p| p.kind != "wall"));
+        assert!(first.parts.iter().any(|p| p.kind == "framing_member"));
+        assert!(scene.levels[1].walls.is_empty() && scene.levels[1].shell.is_none());
+
+        // Focused on the upper floor, the first floor is a step coarser
+        let scene = building_scene(&store, building_id, 0.5, MeshQuality::Medium, Some(upper_id)).unwrap();
+        assert_eq!(scene.levels[1].quality, MeshQuality::Medium);
+        assert_eq!(scene.levels[0].quality, MeshQuality::Low);
+        assert!(scene.levels[0].parts.iter().all(|p| p.kind != "framing_member"));
+        assert_eq!(scene.levels[0].walls.len(), 1);
+
+        assert!(building_scene(&store, BuildingId::new(), 0.5, MeshQuality::High, None).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+// Export - building models written out for other tools
+
+pub mod gltf;
+pub mod ifc;
+pub mod mesh;
+pub mod pick;
+
+pub use gltf::{export_building_glb, export_level_glb, LevelPart, MaterialSlot};
+pub use ifc::export_project_ifc;
+pub use mesh::{
+    building_scene, export_level_mesh, level_combined_mesh, level_room_meshes, level_wall_meshes, BuildingScene,
+    CombinedLevelMesh, LevelScene, MeshExportOptions, RoomMesh, WallMesh,
+};
+pub use pick::{pick_entity, LevelPickIndex, PickHit, PickTarget};
@@ -0,0 +1,130 @@
+// Picking - which wall or room a ray from the viewer's camera hits, tested
+// against the same meshes the viewer draws so selection matches what is
+// on screen. Coordinates are plan feet with z up, as for the meshes.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::mesh::{level_room_meshes, level_wall_meshes};
+use crate::domain::{LevelId, Point3, RoomId, Vector3, WallId};
+use crate::geometry::{MeshData, MeshQuality, TriangleBvh};
+use crate::store::Store;
+
+/// An entity that can be picked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum PickTarget {
+    Wall(WallId),
+    Room(RoomId),
+}
+
+impl PickTarget {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PickTarget::Wall(_) => "wall",
+            PickTarget::Room(_) => "room",
+        }
+    }
+
+    pub fn id(&self) -> String {
+        match self {
+            PickTarget::Wall(id) => id.to_string(),
+            PickTarget::Room(id) => id.to_string(),
+        }
+    }
+}
+
+/// The entity a ray hit first
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PickHit {
+    pub target: PickTarget,
+    /// Distance along the ray, in units of the direction's length
+    pub distance: f64,
+    pub point: Point3,
+    /// Normal of the face hit, turned toward the ray's origin
+    pub normal: Vector3,
+}
+
+/// A level's wall and room meshes indexed for raycasts. Build it once per
+/// level revision and reuse it for every pick until the level changes.
+#[derive(Debug, Clone, Default)]
+pub struct LevelPickIndex {
+    targets: Vec<PickTarget>,
+    bvh: TriangleBvh,
+}
+
+impl LevelPickIndex {
+    pub fn build(store: &Store, level_id: LevelId) -> Result<Self> {
+        let mut targets = Vec::new();
+        let mut meshes: Vec<MeshData> = Vec::new();
+        for wall in level_wall_meshes(store, level_id, MeshQuality::High)? {
+            targets.push(PickTarget::Wall(wall.wall_id));
+            meshes.push(wall.mesh);
+        }
+        for room in level_room_meshes(store, level_id, MeshQuality::High)? {
+            targets.push(PickTarget::Room(room.room_id));
+            meshes.push(room.mesh);
+        }
+        let bvh = TriangleBvh::build(meshes.iter().enumerate());
+        Ok(Self { targets, bvh })
+    }
+
+    /// First wall or room hit by the ray from `origin` along `direction`
+    pub fn pick(&self, origin: Point3, direction: Vector3) -> Option<PickHit> {
+        let hit = self.bvh.raycast([origin.x, origin.y, origin.z], [direction.x, direction.y, direction.z])?;
+        Some(PickHit {
+            target: self.targets[hit.owner],
+            distance: hit.distance,
+            point: Point3::new(hit.point[0], hit.point[1], hit.point[2]),
+            normal: Vector3::new(hit.normal[0], hit.normal[1], hit.normal[2]),
+        })
+    }
+}
+
+/// First wall or room on a level hit by a ray. Builds the index for a
+/// single pick; keep a LevelPickIndex to pick repeatedly.
+pub fn pick_entity(store: &Store, level_id: LevelId, origin: Point3, direction: Vector3) -> Result<Option<PickHit>> {
+    Ok(LevelPickIndex::build(store, level_id)?.pick(origin, direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    #[test]
+    fn test_pick_entity() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+        let index = LevelPickIndex::build(&store, level_id).unwrap();
+
+        // Looking down into the room lands on its floor plate
+        let hit = index.pick(Point3::new(6.0, 5.0, 20.0), Vector3::down()).unwrap();
+        assert_eq!(hit.target, PickTarget::Room(room_id));
+        assert!(hit.point.z.abs() < 1e-3);
+        assert!((hit.normal.z - 1.0).abs() < 1e-6);
+
+        // Looking across the room at the wall hits its inner face
+        let hit = index.pick(Point3::new(10.0, 8.0, 4.0), Vector3::new(0.0, -1.0, 0.0)).unwrap();
+        assert_eq!(hit.target, PickTarget::Wall(wall_id));
+        assert!(hit.normal.y > 0.99);
+        assert_eq!(hit.target.kind(), "wall");
+
+        // Beside the building there's nothing to hit
+        assert!(index.pick(Point3::new(40.0, 5.0, 20.0), Vector3::down()).is_none());
+        assert!(pick_entity(&store, LevelId::new(), Point3::origin(), Vector3::down()).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+// Cut list optimizer - bins framing members into stock lumber lengths
+// Pieces of each lumber size are packed first-fit-decreasing into boards of
+// the longest stock length, then each board is swapped for the shortest stock
+// length that still holds its cuts. Pieces longer than any stock board (long
+// plates) are spliced from full-length boards plus a remainder.
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    Beam, FramingLayout, FramingMaterial, FramingMemberType, LevelId, LumberSize, MemberProfile, Post, WallId,
+};
+
+/// Stock lengths lumber is bought in (inches): 8', 10', 12', and 16'
+pub const STOCK_LENGTHS: [f64; 4] = [96.0, 120.0, 144.0, 192.0];
+
+/// Material lost to the saw blade on each cut (inches)
+const KERF: f64 = 0.125;
+
+/// One stock board and the pieces cut from it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockBoard {
+    pub lumber_size: LumberSize,
+    /// Length bought (inches)
+    pub stock_length: f64,
+    /// Lengths of the pieces cut from the board (inches)
+    pub cuts: Vec<f64>,
+}
+
+impl StockBoard {
+    /// Length of board used by the cuts and the kerfs between them
+    pub fn used_length(&self) -> f64 {
+        self.cuts.iter().sum::<f64>() + KERF * self.cuts.len().saturating_sub(1) as f64
+    }
+
+    /// Length left over after cutting
+    pub fn offcut(&self) -> f64 {
+        self.stock_length - self.used_length()
+    }
+}
+
+/// How many boards of one size and length to buy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockOrder {
+    pub lumber_size: LumberSize,
+    pub stock_length: f64,
+    pub count: u32,
+}
+
+/// Stock boards to buy for a set of framing members, and how to cut them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CutList {
+    pub boards: Vec<StockBoard>,
+    /// Boards grouped by size and length, for ordering
+    pub orders: Vec<StockOrder>,
+    /// Total length of stock bought (inches)
+    pub stock_length: f64,
+    /// Total length of the pieces cut from it (inches)
+    pub cut_length: f64,
+    /// Share of the stock that ends up as offcuts and sawdust
+    pub waste_percent: f64,
+}
+
+impl CutList {
+    /// Optimize the cut list for a set of framing layouts
+    ///
+    /// Built-up headers are cut ply by ply; engineered headers (LVL and the
+    /// like) are ordered to length and left out, as is anything with a
+    /// custom cross-section.
+    pub fn from_layouts<'a>(layouts: impl IntoIterator<Item = &'a FramingLayout>) -> Self {
+        Self::optimize(layout_pieces(layouts))
+    }
+
+    /// Optimize the cut list for framing layouts together with beams and
+    /// posts. Sawn beams and posts are cut ply by ply like headers; LVL,
+    /// PSL and steel members are ordered to length and left out.
+    pub fn from_members<'a>(
+        layouts: impl IntoIterator<Item = &'a FramingLayout>,
+        beams: &[&Beam],
+        posts: &[&Post],
+    ) -> Self {
+        let mut pieces = layout_pieces(layouts);
+        let sawn = |profile: &MemberProfile, material: FramingMaterial| {
+            !material.is_engineered() && material != FramingMaterial::Steel && is_stock(&profile.lumber_size)
+        };
+        for beam in beams.iter().filter(|b| sawn(&b.profile, b.material)) {
+            pieces.extend((0..beam.profile.plies).map(|_| (beam.profile.lumber_size, beam.length() * 12.0)));
+        }
+        for post in posts.iter().filter(|p| sawn(&p.profile, p.material)) {
+            pieces.extend((0..post.profile.plies).map(|_| (post.profile.lumber_size, post.height * 12.0)));
+        }
+        Self::optimize(pieces)
+    }
+
+    /// Pack pieces (lumber size, length in inches) into stock boards
+    pub fn optimize(pieces: Vec<(LumberSize, f64)>) -> Self {
+        let longest = STOCK_LENGTHS[STOCK_LENGTHS.len() - 1];
+
+        // Group by size, splicing anything longer than the longest board
+        let mut by_size: Vec<(LumberSize, Vec<f64>)> = Vec::new();
+        for (lumber_size, length) in pieces {
+            if length <= 0.0 {
+                continue;
+            }
+            let lengths = match by_size.iter_mut().find(|(size, _)| *size == lumber_size) {
+                Some((_, lengths)) => lengths,
+                None => {
+                    by_size.push((lumber_size, Vec::new()));
+                    &mut by_size.last_mut().expect("just pushed").1
+                }
+            };
+            let full = (length / longest).floor();
+            let remainder = length - full * longest;
+            lengths.extend((0..full as usize).map(|_| longest));
+            if remainder > KERF {
+                lengths.push(remainder);
+            }
+        }
+        by_size.sort_by_key(|(size, _)| size.nominal_name());
+
+        let mut boards = Vec::new();
+        for (lumber_size, mut lengths) in by_size {
+            lengths.sort_by(|a, b| b.total_cmp(a));
+            let mut packed: Vec<StockBoard> = Vec::new();
+            for length in lengths {
+                let fits = |board: &&mut StockBoard| board.used_length() + KERF + length <= longest + 1e-9;
+                match packed.iter_mut().find(|b| fits(b)) {
+                    Some(board) => board.cuts.push(length),
+                    None => packed.push(StockBoard { lumber_size, stock_length: longest, cuts: vec![length] }),
+                }
+            }
+            for board in &mut packed {
+                let used = board.used_length();
+                board.stock_length = STOCK_LENGTHS
+                    .iter()
+                    .copied()
+                    .find(|&stock| used <= stock + 1e-9)
+                    .unwrap_or(longest);
+            }
+            boards.extend(packed);
+        }
+
+        let mut orders: Vec<StockOrder> = Vec::new();
+        for board in &boards {
+            match orders
+                .iter_mut()
+                .find(|o| o.lumber_size == board.lumber_size && o.stock_length == board.stock_length)
+            {
+                Some(order) => order.count += 1,
+                None => orders.push(StockOrder {
+                    lumber_size: board.lumber_size,
+                    stock_length: board.stock_length,
+                    count: 1,
+                }),
+            }
+        }
+        orders.sort_by(|a, b| {
+            a.lumber_size.nominal_name().cmp(&b.lumber_size.nominal_name())
+                .then(a.stock_length.total_cmp(&b.stock_length))
+        });
+
+        let stock_length: f64 = boards.iter().map(|b| b.stock_length).sum();
+        let cut_length: f64 = boards.iter().flat_map(|b| &b.cuts).sum();
+        let waste_percent = if stock_length > 0.0 {
+            (stock_length - cut_length) / stock_length * 100.0
+        } else {
+            0.0
+        };
+
+        Self { boards, orders, stock_length, cut_length, waste_percent }
+    }
+
+    /// The stock order as CSV: size, length in feet, count
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("lumber_size,stock_length_ft,count\n");
+        for order in &self.orders {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                order.lumber_size.nominal_name(),
+                order.stock_length / 12.0,
+                order.count
+            ));
+        }
+        csv
+    }
+}
+
+/// A wall's own cut list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallCutList {
+    pub wall_id: WallId,
+    pub cut_list: CutList,
+}
+
+/// Cut lists for each framed wall on a level, and for the level as a
+/// whole. The level's list packs all walls together with the level's beams
+/// and posts, so offcuts from one wall can serve another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelCutList {
+    pub level_id: LevelId,
+    pub walls: Vec<WallCutList>,
+    pub total: CutList,
+}
+
+/// Stock pieces (lumber size, length in inches) in a set of framing layouts
+fn layout_pieces<'a>(layouts: impl IntoIterator<Item = &'a FramingLayout>) -> Vec<(LumberSize, f64)> {
+    let mut pieces = Vec::new();
+    for layout in layouts {
+        for member in &layout.members {
+            if member.member_type != FramingMemberType::Header && is_stock(&member.lumber_size) {
+                pieces.push((member.lumber_size, member.length));
+            }
+        }
+        for header in &layout.headers {
+            if header.size.header_type.is_built_up() && is_stock(&header.size.lumber_size) {
+                pieces.extend((0..header.size.plies).map(|_| (header.size.lumber_size, header.length)));
+            }
+        }
+    }
+    pieces
+}
+
+fn is_stock(lumber_size: &LumberSize) -> bool {
+    !matches!(lumber_size, LumberSize::Custom { .. })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_fit_decreasing() {
+        // Two 92-5/8" studs come out of each 16' board, and the three 40"
+        // cripples share a board cut down to 12'
+        let mut pieces = vec![(LumberSize::TwoBySix, 92.625); 6];
+        pieces.extend(vec![(LumberSize::TwoBySix, 40.0); 3]);
+        let cut_list = CutList::optimize(pieces);
+
+        assert_eq!(cut_list.boards.len(), 4);
+        assert_eq!(cut_list.boards[0].cuts.len(), 2);
+        assert_eq!(cut_list.boards[3].cuts, vec![40.0; 3]);
+        let counts: Vec<(f64, u32)> = cut_list.orders.iter().map(|o| (o.stock_length, o.count)).collect();
+        assert_eq!(counts, vec![(144.0, 1), (192.0, 3)]);
+        let expected = (720.0 - (6.0 * 92.625 + 120.0)) / 720.0 * 100.0;
+        assert!((cut_list.waste_percent - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_long_pieces_are_spliced() {
+        // A 20' plate takes a 16' board and a 4' remainder, which shares a
+        // board with the 5' piece
+        let cut_list = CutList::optimize(vec![(LumberSize::TwoByFour, 240.0), (LumberSize::TwoByFour, 60.0)]);
+
+        assert_eq!(cut_list.boards.len(), 2);
+        assert!((cut_list.cut_length - 300.0).abs() < 1e-9);
+        assert_eq!(cut_list.boards[1].cuts, vec![60.0, 48.0]);
+        assert_eq!(cut_list.boards[1].stock_length, 120.0);
+        assert_eq!(cut_list.to_csv(), "lumber_size,stock_length_ft,count\n2x4,10,1\n2x4,16,1\n");
+    }
+}
@@ -0,0 +1,198 @@
+// Deck framing generator - ledger, rims, joists, decking and railing posts
+// Works in the deck's frame: u runs along the ledger and v out from it. The
+// ledger lines the building side and a rim board the outer edge; the joists
+// hang between them at 16" on center, with a rim on each end. Decking runs
+// along the ledger across the joists, and when the deck is high enough to
+// need a guard, 4x4 posts stand inside the rims along the open sides at no
+// more than 6' apart.
+//
+// Member positions are in inches, placed as in floor framing: a member's
+// position is the corner where it starts, it runs `length` along its
+// rotation, its width extends to the left of that direction, and its depth
+// rises from z. z is measured from the deck surface.
+
+use crate::domain::deck::{DECKING, DECK_JOIST_SPACING};
+use crate::domain::{Deck, DeckFramingLayout, FramingMember, FramingMemberType, LumberSize, Point2, Point3};
+use super::generator::FramingError;
+
+/// Gap between decking boards (inches)
+const DECKING_GAP: f64 = 0.25;
+/// Guard rail height above the surface (inches)
+const RAILING_HEIGHT: f64 = 36.0;
+/// Farthest apart railing posts may stand (inches)
+const RAILING_POST_SPACING: f64 = 72.0;
+const RAILING_POST: LumberSize = LumberSize::FourByFour;
+/// Pieces shorter than this are dropped (inches)
+const MIN_PIECE: f64 = 0.5;
+
+/// Deck framing generator
+pub struct DeckFramingGenerator;
+
+impl DeckFramingGenerator {
+    pub fn generate(deck: &Deck) -> Result<DeckFramingLayout, FramingError> {
+        let (width, depth) = (deck.width() * 12.0, deck.depth * 12.0);
+        if width < 12.0 || depth < 12.0 {
+            return Err(FramingError::InvalidConfig {
+                message: "Deck must be at least 1' wide and 1' deep".to_string(),
+            });
+        }
+
+        let frame = DeckFrame::new(deck);
+        let joist = deck.joist_size;
+        let thickness = joist.actual_dimensions().0;
+        let decking = DECKING.actual_dimensions();
+        let joist_z = -decking.1 - joist.actual_dimensions().1;
+        let mut members = Vec::new();
+
+        // Ledger and outer rim along the deck, end rims between them
+        members.push(frame.along(FramingMemberType::Ledger, joist, 0.0, thickness, joist_z, width));
+        members.push(frame.along(FramingMemberType::RimBoard, joist, 0.0, depth, joist_z, width));
+        for u in [0.0, width - thickness] {
+            members.push(frame.out(FramingMemberType::RimBoard, joist, u, thickness, joist_z, depth - 2.0 * thickness));
+        }
+
+        // Joists between the end rims
+        let mut u = DECK_JOIST_SPACING;
+        while u < width - thickness - MIN_PIECE {
+            members.push(frame.out(FramingMemberType::FloorJoist, joist, u - thickness / 2.0, thickness, joist_z, depth - 2.0 * thickness));
+            u += DECK_JOIST_SPACING;
+        }
+
+        // Decking courses from the building out, the last one ripped to fit
+        let mut courses = 0;
+        let mut v = 0.0;
+        while v < depth - MIN_PIECE {
+            let board_width = decking.0.min(depth - v);
+            let board = LumberSize::Custom { width: board_width, depth: decking.1 };
+            members.push(frame.along(FramingMemberType::DeckBoard, board, 0.0, v + board_width, -decking.1, width));
+            courses += 1;
+            v += decking.0 + DECKING_GAP;
+        }
+
+        // Posts inside the rims, from the house out and around the open sides
+        let mut railing_posts = 0;
+        if deck.needs_railing() {
+            let half = RAILING_POST.actual_dimensions().0 / 2.0;
+            let inset = thickness + half;
+            let corners = [
+                (inset, inset),
+                (inset, depth - inset),
+                (width - inset, depth - inset),
+                (width - inset, inset),
+            ];
+            let mut centers = vec![corners[0]];
+            for pair in corners.windows(2) {
+                let ((u0, v0), (u1, v1)) = (pair[0], pair[1]);
+                let spans = ((u1 - u0).hypot(v1 - v0) / RAILING_POST_SPACING).ceil().max(1.0) as usize;
+                centers.extend((1..=spans).map(|i| {
+                    let t = i as f64 / spans as f64;
+                    (u0 + (u1 - u0) * t, v0 + (v1 - v0) * t)
+                }));
+            }
+            for (u, v) in centers {
+                members.push(frame.post(RAILING_POST, u - half, v + half, joist_z, RAILING_HEIGHT - joist_z));
+                railing_posts += 1;
+            }
+        }
+
+        let joist_count = members.iter().filter(|m| m.member_type == FramingMemberType::FloorJoist).count() as u32;
+        let total_board_feet = members
+            .iter()
+            .filter(|m| matches!(m.member_type, FramingMemberType::Ledger | FramingMemberType::RimBoard | FramingMemberType::FloorJoist))
+            .map(|m| m.board_feet())
+            .sum();
+
+        Ok(DeckFramingLayout {
+            deck_id: deck.id,
+            members,
+            joist_count,
+            decking_courses: courses,
+            decking_length: courses as f64 * width / 12.0,
+            railing_posts,
+            total_board_feet,
+        })
+    }
+}
+
+/// Plan frame of a deck in inches: u along the ledger, v out from it
+struct DeckFrame {
+    origin: Point2,
+    /// Unit direction along the ledger
+    along: (f64, f64),
+}
+
+impl DeckFrame {
+    fn new(deck: &Deck) -> Self {
+        let start = deck.point(0.0, 0.0);
+        let end = deck.point(1.0, 0.0);
+        Self {
+            origin: Point2::new(start.x * 12.0, start.y * 12.0),
+            along: (end.x - start.x, end.y - start.y),
+        }
+    }
+
+    fn to_plan(&self, u: f64, v: f64, z: f64) -> Point3 {
+        let (dx, dy) = self.along;
+        Point3::new(self.origin.x + dx * u + dy * v, self.origin.y + dy * u - dx * v, z)
+    }
+
+    /// Member running along the ledger; its width extends back toward the
+    /// building from `v`
+    fn along(&self, member_type: FramingMemberType, size: LumberSize, u: f64, v: f64, z: f64, length: f64) -> FramingMember {
+        let rotation = self.along.1.atan2(self.along.0);
+        FramingMember::floor(member_type, size, self.to_plan(u, v, z), length, rotation)
+    }
+
+    /// Member running out from the ledger, starting at `v`; its width
+    /// extends along the ledger from `u`
+    fn out(&self, member_type: FramingMemberType, size: LumberSize, u: f64, v: f64, z: f64, length: f64) -> FramingMember {
+        let rotation = (-self.along.0).atan2(self.along.1);
+        FramingMember::floor(member_type, size, self.to_plan(u, v, z), length, rotation)
+    }
+
+    /// Post standing `length` tall with its corner at (u, v), its faces
+    /// along the ledger and back toward the building
+    fn post(&self, size: LumberSize, u: f64, v: f64, z: f64, length: f64) -> FramingMember {
+        let rotation = self.along.1.atan2(self.along.0);
+        FramingMember::floor(FramingMemberType::RailingPost, size, self.to_plan(u, v, z), length, rotation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DeckKind, LevelId};
+
+    #[test]
+    fn test_deck_framing() {
+        // 16' x 10' deck off the north side, 4' above grade
+        let deck = Deck::new(LevelId::new(), DeckKind::Deck, Point2::new(16.0, 30.0), Point2::new(0.0, 30.0), 10.0, 4.0);
+        let layout = DeckFramingGenerator::generate(&deck).unwrap();
+
+        // Joists every 16" between the end rims: 11 along 192"
+        assert_eq!(layout.joist_count, 11);
+        let joists: Vec<&FramingMember> = layout.members.iter().filter(|m| m.member_type == FramingMemberType::FloorJoist).collect();
+        assert!(joists.iter().all(|j| (j.length - 117.0).abs() < 1e-9));
+        assert!(joists.iter().all(|j| (j.position.z + 8.25).abs() < 1e-9));
+        // Every joist runs north from just off the ledger
+        assert!(joists.iter().all(|j| (j.position.y - (360.0 + 1.5)).abs() < 1e-9));
+        assert!(joists.iter().all(|j| (j.rotation - std::f64::consts::FRAC_PI_2).abs() < 1e-9));
+
+        // 120" of decking at 5.75" a course
+        assert_eq!(layout.decking_courses, 21);
+        assert!((layout.decking_length - 21.0 * 16.0).abs() < 1e-9);
+
+        // Posts at the four corners, split into 6' bays along the 10' sides
+        // and 16' outer edge
+        assert_eq!(layout.railing_posts, 4 + 2 + 2);
+        let post = layout.members.iter().find(|m| m.member_type == FramingMemberType::RailingPost).unwrap();
+        assert!((post.position.z + post.length - RAILING_HEIGHT).abs() < 1e-9);
+        assert!(layout.total_board_feet > 0.0);
+
+        // A low porch gets no railing
+        let porch = Deck::new(LevelId::new(), DeckKind::Porch, Point2::new(0.0, 0.0), Point2::new(8.0, 0.0), 5.0, 1.0);
+        assert_eq!(DeckFramingGenerator::generate(&porch).unwrap().railing_posts, 0);
+        let sliver = Deck::new(LevelId::new(), DeckKind::Deck, Point2::new(0.0, 0.0), Point2::new(0.5, 0.0), 5.0, 1.0);
+        assert!(DeckFramingGenerator::generate(&sliver).is_err());
+    }
+}
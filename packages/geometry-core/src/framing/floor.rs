@@ -0,0 +1,346 @@
+// Floor framing generator - joists, rim boards, blocking, and subfloor
+// Works in a frame aligned with the joists: u runs along them and v across.
+// Joists sit at the configured spacing between the rim boards that line the
+// footprint's edges, blocking rows fall every 8' along the span, and 4x8
+// subfloor sheets run across the joists with staggered end joints.
+//
+// Member positions are in inches. A member's position is the corner where it
+// starts; it runs `length` along its rotation, its width extends to the left
+// of that direction, and its depth rises from z. z is measured from the level
+// elevation, which is the top of the subfloor.
+
+use crate::domain::{
+    FloorFramingConfig, FloorFramingLayout, FramingMember, FramingMemberType,
+    LevelId, LumberSize, Point2, Point3, Polygon2,
+};
+use super::generator::FramingError;
+
+/// Subfloor sheet size (inches); the long side runs across the joists
+const SHEET_LENGTH: f64 = 96.0;
+const SHEET_WIDTH: f64 = 48.0;
+/// Distance between rows of blocking along the joist span (inches)
+const BLOCKING_INTERVAL: f64 = 96.0;
+/// Pieces shorter than this are dropped (inches)
+const MIN_PIECE: f64 = 0.5;
+
+/// Floor framing generator
+pub struct FloorFramingGenerator;
+
+impl FloorFramingGenerator {
+    /// Frame a floor over a footprint given in inches
+    pub fn generate(
+        level_id: LevelId,
+        footprint: &Polygon2,
+        config: &FloorFramingConfig,
+    ) -> Result<FloorFramingLayout, FramingError> {
+        if !config.joist_spacing.is_finite() || config.joist_spacing <= 0.0 {
+            return Err(FramingError::InvalidConfig {
+                message: "Joist spacing must be positive".to_string(),
+            });
+        }
+        if footprint.outer.len() < 3 || footprint.area() <= 0.0 {
+            return Err(FramingError::InvalidConfig {
+                message: "Floor footprint must enclose an area".to_string(),
+            });
+        }
+
+        let frame = JoistFrame::new(config.joist_direction);
+        let rings = plan_rings(footprint);
+        let local: Vec<Vec<Point2>> = rings
+            .iter()
+            .map(|ring| ring.iter().map(|p| frame.to_local(p)).collect())
+            .collect();
+
+        let mut layout = FloorFramingLayout::new(level_id, config.clone());
+        layout.members.extend(Self::generate_rim_boards(&rings, config));
+        let joist_lines = Self::generate_joists(&local, &frame, config, &mut layout.members);
+        layout.members.extend(Self::generate_blocking(&local, &frame, &joist_lines, config));
+        layout.members.extend(Self::generate_sheathing(&local, &frame, config));
+
+        layout.recalculate_totals();
+        Ok(layout)
+    }
+
+    /// Rim boards along every edge, set just inside the footprint
+    fn generate_rim_boards(rings: &[Vec<Point2>], config: &FloorFramingConfig) -> Vec<FramingMember> {
+        let z = joist_bottom(config);
+        rings
+            .iter()
+            .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+            .filter(|(a, b)| a.distance_to(b) >= MIN_PIECE)
+            .map(|(a, b)| {
+                FramingMember::floor(
+                    FramingMemberType::RimBoard,
+                    config.lumber_size,
+                    Point3::new(a.x, a.y, z),
+                    a.distance_to(&b),
+                    (b.y - a.y).atan2(b.x - a.x),
+                )
+            })
+            .collect()
+    }
+
+    /// Joists at each spacing across the floor, butting the rim boards.
+    /// Returns the v of every line blocking spans between, including the
+    /// rim-bounded edges.
+    fn generate_joists(
+        local: &[Vec<Point2>],
+        frame: &JoistFrame,
+        config: &FloorFramingConfig,
+        members: &mut Vec<FramingMember>,
+    ) -> Vec<f64> {
+        let (min, max) = bounds(local);
+        let thickness = config.lumber_size.actual_dimensions().0;
+        let z = joist_bottom(config);
+
+        let mut lines = vec![min.y];
+        let mut v = min.y + config.joist_spacing;
+        while v < max.y - MIN_PIECE {
+            for (u0, u1) in scanline(local, v) {
+                let (start, end) = (u0 + thickness, u1 - thickness);
+                if end - start >= MIN_PIECE {
+                    let corner = frame.to_plan(&Point2::new(start, v - thickness / 2.0));
+                    members.push(FramingMember::floor(
+                        FramingMemberType::FloorJoist,
+                        config.lumber_size,
+                        Point3::new(corner.x, corner.y, z),
+                        end - start,
+                        frame.angle,
+                    ));
+                }
+            }
+            lines.push(v);
+            v += config.joist_spacing;
+        }
+        lines.push(max.y);
+        lines
+    }
+
+    /// Rows of blocking between neighbouring joists every `BLOCKING_INTERVAL`
+    fn generate_blocking(
+        local: &[Vec<Point2>],
+        frame: &JoistFrame,
+        joist_lines: &[f64],
+        config: &FloorFramingConfig,
+    ) -> Vec<FramingMember> {
+        let (min, max) = bounds(local);
+        let thickness = config.lumber_size.actual_dimensions().0;
+        let z = joist_bottom(config);
+
+        let mut blocking = Vec::new();
+        let mut u = min.x + BLOCKING_INTERVAL;
+        while u < max.x - MIN_PIECE {
+            for pair in joist_lines.windows(2) {
+                let (v0, v1) = (pair[0] + thickness / 2.0, pair[1] - thickness / 2.0);
+                if v1 - v0 < MIN_PIECE || !contains(local, &Point2::new(u, (v0 + v1) / 2.0)) {
+                    continue;
+                }
+                // Runs across the joists, so its width lies toward -u
+                let corner = frame.to_plan(&Point2::new(u + thickness / 2.0, v0));
+                blocking.push(FramingMember::floor(
+                    FramingMemberType::FloorBlocking,
+                    config.lumber_size,
+                    Point3::new(corner.x, corner.y, z),
+                    v1 - v0,
+                    frame.angle + std::f64::consts::FRAC_PI_2,
+                ));
+            }
+            u += BLOCKING_INTERVAL;
+        }
+        blocking
+    }
+
+    /// 4x8 sheets across the joists, every other row shifted half a sheet so
+    /// end joints don't line up. Sheets are cut to the footprint's extents;
+    /// any sheet that touches the footprint is counted.
+    fn generate_sheathing(local: &[Vec<Point2>], frame: &JoistFrame, config: &FloorFramingConfig) -> Vec<FramingMember> {
+        let (min, max) = bounds(local);
+        let z = -config.sheathing_thickness;
+
+        let mut sheets = Vec::new();
+        let mut u0 = min.x;
+        let mut row = 0;
+        while u0 < max.x - MIN_PIECE {
+            let u1 = (u0 + SHEET_WIDTH).min(max.x);
+            let stagger = if row % 2 == 1 { SHEET_LENGTH / 2.0 } else { 0.0 };
+            let mut v0 = min.y - stagger;
+            while v0 < max.y - MIN_PIECE {
+                let (a, b) = (v0.max(min.y), (v0 + SHEET_LENGTH).min(max.y));
+                if b - a >= MIN_PIECE && overlaps(local, Point2::new(u0, a), Point2::new(u1, b)) {
+                    let corner = frame.to_plan(&Point2::new(u1, a));
+                    sheets.push(FramingMember::floor(
+                        FramingMemberType::SubfloorSheet,
+                        LumberSize::Custom { width: u1 - u0, depth: config.sheathing_thickness },
+                        Point3::new(corner.x, corner.y, z),
+                        b - a,
+                        frame.angle + std::f64::consts::FRAC_PI_2,
+                    ));
+                }
+                v0 += SHEET_LENGTH;
+            }
+            u0 += SHEET_WIDTH;
+            row += 1;
+        }
+        sheets
+    }
+}
+
+/// Plan axes aligned with the joists
+struct JoistFrame {
+    angle: f64,
+    cos: f64,
+    sin: f64,
+}
+
+impl JoistFrame {
+    fn new(angle: f64) -> Self {
+        Self { angle, cos: angle.cos(), sin: angle.sin() }
+    }
+
+    fn to_local(&self, p: &Point2) -> Point2 {
+        Point2::new(p.x * self.cos + p.y * self.sin, -p.x * self.sin + p.y * self.cos)
+    }
+
+    fn to_plan(&self, p: &Point2) -> Point2 {
+        Point2::new(p.x * self.cos - p.y * self.sin, p.x * self.sin + p.y * self.cos)
+    }
+}
+
+/// Bottom of the joists: they carry the subfloor, whose top is the level
+fn joist_bottom(config: &FloorFramingConfig) -> f64 {
+    -(config.sheathing_thickness + config.lumber_size.actual_dimensions().1)
+}
+
+/// The footprint's rings wound so the floor lies to the left of each edge:
+/// outer counterclockwise, holes clockwise
+fn plan_rings(footprint: &Polygon2) -> Vec<Vec<Point2>> {
+    let wound = |ring: &Vec<Point2>, ccw: bool| {
+        let mut ring = ring.clone();
+        if (Polygon2::new(ring.clone()).signed_area() > 0.0) != ccw {
+            ring.reverse();
+        }
+        ring
+    };
+    std::iter::once(wound(&footprint.outer, true))
+        .chain(footprint.holes.iter().map(|hole| wound(hole, false)))
+        .collect()
+}
+
+fn bounds(rings: &[Vec<Point2>]) -> (Point2, Point2) {
+    let points = rings.iter().flatten();
+    let min = points.clone().fold(Point2::new(f64::INFINITY, f64::INFINITY), |m, p| Point2::new(m.x.min(p.x), m.y.min(p.y)));
+    let max = points.fold(Point2::new(f64::NEG_INFINITY, f64::NEG_INFINITY), |m, p| Point2::new(m.x.max(p.x), m.y.max(p.y)));
+    (min, max)
+}
+
+/// Spans of the line at `v` that lie inside the rings (even-odd)
+fn scanline(rings: &[Vec<Point2>], v: f64) -> Vec<(f64, f64)> {
+    let mut crossings: Vec<f64> = rings
+        .iter()
+        .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+        .filter(|(a, b)| (a.y > v) != (b.y > v))
+        .map(|(a, b)| a.x + (v - a.y) / (b.y - a.y) * (b.x - a.x))
+        .collect();
+    crossings.sort_by(f64::total_cmp);
+    crossings.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+fn contains(rings: &[Vec<Point2>], point: &Point2) -> bool {
+    scanline(rings, point.y).iter().any(|&(u0, u1)| point.x > u0 && point.x < u1)
+}
+
+/// Whether the rectangle from `min` to `max` overlaps the floor by more
+/// than a sliver along its edges
+fn overlaps(rings: &[Vec<Point2>], min: Point2, max: Point2) -> bool {
+    let min = Point2::new(min.x + MIN_PIECE, min.y + MIN_PIECE);
+    let max = Point2::new(max.x - MIN_PIECE, max.y - MIN_PIECE);
+    let corners = [min, Point2::new(max.x, min.y), max, Point2::new(min.x, max.y)];
+    let center = Point2::new((min.x + max.x) / 2.0, (min.y + max.y) / 2.0);
+    if corners.iter().chain([&center]).any(|p| contains(rings, p)) {
+        return true;
+    }
+    let inside_rect = |p: &Point2| p.x > min.x && p.x < max.x && p.y > min.y && p.y < max.y;
+    if rings.iter().flatten().any(inside_rect) {
+        return true;
+    }
+    rings
+        .iter()
+        .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+        .any(|(a, b)| (0..4).any(|i| segments_cross(a, b, corners[i], corners[(i + 1) % 4])))
+}
+
+fn segments_cross(a: Point2, b: Point2, c: Point2, d: Point2) -> bool {
+    let side = |p: Point2, q: Point2, r: Point2| (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x);
+    side(a, b, c) * side(a, b, d) < 0.0 && side(c, d, a) * side(c, d, b) < 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rectangular_floor() {
+        // 20' x 12' floor, 2x10 joists 16" OC along the 20' side
+        let footprint = Polygon2::rectangle(240.0, 144.0);
+        let layout = FloorFramingGenerator::generate(LevelId::new(), &footprint, &FloorFramingConfig::default()).unwrap();
+
+        assert_eq!(layout.joist_count, 8);
+        assert_eq!(layout.members_of_type(FramingMemberType::RimBoard).len(), 4);
+        let joist = layout.members_of_type(FramingMemberType::FloorJoist)[0];
+        assert!((joist.length - 237.0).abs() < 1e-9);
+        assert!((joist.position.z + 10.0).abs() < 1e-9);
+
+        // Two rows of blocking across the nine bays
+        assert_eq!(layout.members_of_type(FramingMemberType::FloorBlocking).len(), 18);
+
+        // 7.5 sheets of area, but staggered joints leave ten to buy
+        assert_eq!(layout.sheathing_sheets, 10);
+        let sheet_area: f64 = layout
+            .members_of_type(FramingMemberType::SubfloorSheet)
+            .iter()
+            .map(|s| s.length * s.lumber_size.actual_dimensions().0)
+            .sum();
+        assert!((sheet_area - 240.0 * 144.0).abs() < 1e-6);
+
+        // Board feet cover the lumber only
+        let lumber: f64 = layout
+            .members
+            .iter()
+            .filter(|m| m.member_type != FramingMemberType::SubfloorSheet)
+            .map(|m| m.board_feet())
+            .sum();
+        assert!((layout.total_board_feet - lumber).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_joist_direction_and_shape() {
+        // L-shaped floor, joists running along y: the notch shortens the
+        // joists over it rather than dropping them
+        let footprint = Polygon2::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(192.0, 0.0),
+            Point2::new(192.0, 96.0),
+            Point2::new(100.0, 96.0),
+            Point2::new(100.0, 192.0),
+            Point2::new(0.0, 192.0),
+        ]);
+        let config = FloorFramingConfig {
+            joist_direction: std::f64::consts::FRAC_PI_2,
+            ..Default::default()
+        };
+        let layout = FloorFramingGenerator::generate(LevelId::new(), &footprint, &config).unwrap();
+
+        assert_eq!(layout.joist_count, 11);
+        let joists = layout.members_of_type(FramingMemberType::FloorJoist);
+        let short = joists.iter().filter(|j| (j.length - 93.0).abs() < 1e-9).count();
+        assert_eq!(short, 5);
+        assert!(joists.iter().all(|j| (j.rotation - std::f64::consts::FRAC_PI_2).abs() < 1e-9));
+        assert_eq!(layout.members_of_type(FramingMemberType::RimBoard).len(), 6);
+
+        // Sheets that would fall wholly in the notch are left out
+        assert_eq!(layout.sheathing_sheets, 9);
+
+        let bad = FloorFramingConfig { joist_spacing: 0.0, ..Default::default() };
+        assert!(FloorFramingGenerator::generate(LevelId::new(), &footprint, &bad).is_err());
+    }
+}
@@ -2,11 +2,12 @@
 // Implements standard residential framing per IRC conventions
 
 use crate::domain::{
-    Wall, WallAssembly, Opening, OpeningType,
-    FramingMember, FramingMemberType, FramingLayout,
+    Wall, WallAssembly, WallJoin, Opening, OpeningType,
+    FramingMember, FramingMemberType, FramingLayout, CornerStyle,
     RoughOpening, WallFramingConfig, LumberSize, HeaderType, FramingMaterial,
-    Point3, WallId, OpeningId,
+    Point2, Point3, WallId, OpeningId, HeaderSelection, HeaderSize,
 };
+use super::headers::{size_header, DEFAULT_TRIBUTARY_WIDTH};
 
 /// Error type for framing generation
 #[derive(Debug, Clone)]
@@ -47,6 +48,22 @@ impl std::fmt::Display for FramingError {
 
 impl std::error::Error for FramingError {}
 
+/// Largest arc one plate piece follows on a curved wall (15 degrees)
+pub const CURVED_PLATE_MAX_SWEEP: f64 = std::f64::consts::PI / 12.0;
+
+/// Fire blocking rows are needed at least this often up a tall wall
+/// (inches, IRC R602.8)
+const FIRE_BLOCK_INTERVAL: f64 = 120.0;
+
+/// A partition that tees into the side of the wall being framed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PartitionJunction {
+    /// Distance along the wall to the partition's centerline (inches)
+    pub position: f64,
+    /// Depth of the partition's studs (inches)
+    pub thickness: f64,
+}
+
 /// Framing generator for producing wall framing layouts
 pub struct FramingGenerator;
 
@@ -61,9 +78,20 @@ impl FramingGenerator {
     /// # Returns
     /// A complete `FramingLayout` with all members, or an error
     pub fn generate_wall_framing(
+        wall: &Wall,
+        assembly: &WallAssembly,
+        openings: &[Opening],
+    ) -> Result<FramingLayout, FramingError> {
+        Self::generate_wall_framing_with_partitions(wall, assembly, openings, &[])
+    }
+
+    /// Generate framing for a wall that has partitions teeing into it,
+    /// adding backing studs on either side of each partition
+    pub fn generate_wall_framing_with_partitions(
         wall: &Wall,
         _assembly: &WallAssembly,
         openings: &[Opening],
+        partitions: &[PartitionJunction],
     ) -> Result<FramingLayout, FramingError> {
         let config = &wall.framing_config;
         let wall_length = wall.length();
@@ -93,12 +121,8 @@ impl FramingGenerator {
         let mut layout = FramingLayout::new(wall.id, config.stud_spacing, config.lumber_size);
         layout.double_top_plate = config.double_top_plate;
 
-        // Get wall direction for positioning
-        let (dir_x, dir_y) = wall.direction();
-        let wall_rotation = dir_y.atan2(dir_x);
-
         // 1. Generate plates (bottom, top, double-top if configured)
-        let plates = Self::generate_plates(wall, config, wall_rotation);
+        let plates = Self::generate_plates(wall, config);
         for plate in plates {
             layout.add_member(plate);
         }
@@ -111,16 +135,41 @@ impl FramingGenerator {
 
         // 3. Generate opening framing (kings, jacks, headers, sills, cripples)
         for ro in &rough_openings {
-            let opening_members = Self::generate_opening_framing(wall, ro, config);
+            let (opening_members, header) = Self::generate_opening_framing(wall, ro, config);
             for member in opening_members {
                 layout.add_member(member);
             }
+            layout.headers.push(header);
+        }
+
+        // 4. Corner studs where this wall runs through a corner
+        for member in Self::generate_corner_studs(wall, config) {
+            layout.add_member(member);
         }
 
-        // 4. Generate blocking (fire stops at 10' intervals if required)
+        // 5. Backing where partitions tee in
+        for member in Self::generate_partition_backing(wall, config, partitions, &rough_openings) {
+            layout.add_member(member);
+        }
+
+        // 6. Blocking rows between the full-height studs: requested rows,
+        // then fire blocking at 10' intervals up tall walls
+        let bays = Self::stud_bays(wall, config, &layout);
+        let mut rows: Vec<_> = config
+            .blocking_rows
+            .iter()
+            .map(|&height| (FramingMemberType::MidHeightBlocking, height))
+            .collect();
         if config.fire_blocking_required {
-            let blocking = Self::generate_fire_blocking(wall, config, &rough_openings, wall_rotation);
-            for block in blocking {
+            let (_, lumber_depth) = config.lumber_size.actual_dimensions();
+            let mut height = lumber_depth + FIRE_BLOCK_INTERVAL;
+            while height < wall_height - Self::top_plate_depth(config) {
+                rows.push((FramingMemberType::FireBlocking, height));
+                height += FIRE_BLOCK_INTERVAL;
+            }
+        }
+        for (member_type, height) in rows {
+            for block in Self::generate_blocking_row(wall, config, &bays, &rough_openings, member_type, height) {
                 layout.add_member(block);
             }
         }
@@ -132,54 +181,54 @@ impl FramingGenerator {
     }
 
     /// Generate plates (bottom plate, top plate, optional double top plate)
-    fn generate_plates(wall: &Wall, config: &WallFramingConfig, rotation: f64) -> Vec<FramingMember> {
+    /// Curved walls get each plate as a run of straight pieces along the arc
+    fn generate_plates(wall: &Wall, config: &WallFramingConfig) -> Vec<FramingMember> {
         let mut plates = Vec::new();
-        let wall_length = wall.length();
         let (_, lumber_depth) = config.lumber_size.actual_dimensions();
 
-        // Bottom plate - at floor level
-        let bottom_plate = FramingMember::new(
-            FramingMemberType::BottomPlate,
-            config.lumber_size,
-            FramingMaterial::SPF,
-            Point3::new(wall.start.x, wall.start.y, wall.base_offset),
-            wall_length,
-            rotation,
-            wall.id,
-        );
-        plates.push(bottom_plate);
-
-        // Top plate - at wall height minus one plate thickness
+        // Bottom plate at floor level, top plate at wall height minus one
+        // plate thickness, and a double top plate under it on load-bearing walls
         let top_plate_z = wall.base_offset + wall.height - lumber_depth;
-        let top_plate = FramingMember::new(
-            FramingMemberType::TopPlate,
-            config.lumber_size,
-            FramingMaterial::SPF,
-            Point3::new(wall.start.x, wall.start.y, top_plate_z),
-            wall_length,
-            rotation,
-            wall.id,
-        );
-        plates.push(top_plate);
-
-        // Double top plate if configured (load-bearing walls)
+        let mut courses = vec![
+            (FramingMemberType::BottomPlate, wall.base_offset),
+            (FramingMemberType::TopPlate, top_plate_z),
+        ];
         if config.double_top_plate {
-            let double_top_z = top_plate_z - lumber_depth;
-            let double_top = FramingMember::new(
-                FramingMemberType::DoubleTopPlate,
-                config.lumber_size,
-                FramingMaterial::SPF,
-                Point3::new(wall.start.x, wall.start.y, double_top_z),
-                wall_length,
-                rotation,
-                wall.id,
-            );
-            plates.push(double_top);
+            courses.push((FramingMemberType::DoubleTopPlate, top_plate_z - lumber_depth));
+        }
+
+        for (member_type, z) in courses {
+            for piece in wall.centerline_points(CURVED_PLATE_MAX_SWEEP).windows(2) {
+                let (start, end) = (piece[0], piece[1]);
+                plates.push(FramingMember::new(
+                    member_type,
+                    config.lumber_size,
+                    FramingMaterial::SPF,
+                    Point3::new(start.x, start.y, z),
+                    start.distance_to(&end),
+                    (end.y - start.y).atan2(end.x - start.x),
+                    wall.id,
+                ));
+            }
         }
 
         plates
     }
 
+    /// Point on the wall centerline `position` along it, at height `z`
+    fn point_along(wall: &Wall, position: f64, z: f64) -> Point3 {
+        let p = wall.point_at(position);
+        Point3::new(p.x, p.y, z)
+    }
+
+    /// Plan rotation of a straight member running between two positions
+    /// along the wall (the wall's own rotation when it is straight)
+    fn rotation_between(wall: &Wall, from: f64, to: f64) -> f64 {
+        let a = wall.point_at(from);
+        let b = wall.point_at(to);
+        (b.y - a.y).atan2(b.x - a.x)
+    }
+
     /// Generate studs at on-center spacing, skipping opening areas
     fn generate_studs(
         wall: &Wall,
@@ -188,7 +237,6 @@ impl FramingGenerator {
     ) -> Vec<FramingMember> {
         let mut studs = Vec::new();
         let wall_length = wall.length();
-        let (dir_x, dir_y) = wall.direction();
         let (lumber_width, lumber_depth) = config.lumber_size.actual_dimensions();
 
         // Calculate stud height (between plates)
@@ -208,15 +256,11 @@ impl FramingGenerator {
             let in_opening = Self::position_in_opening(position, lumber_width, rough_openings);
 
             if !in_opening {
-                // Calculate 3D position along wall
-                let x = wall.start.x + dir_x * position;
-                let y = wall.start.y + dir_y * position;
-
                 let stud = FramingMember::new(
                     FramingMemberType::Stud,
                     config.lumber_size,
                     FramingMaterial::SPF,
-                    Point3::new(x, y, stud_z),
+                    Self::point_along(wall, position, stud_z),
                     stud_height,
                     0.0, // Studs are vertical, no rotation needed
                     wall.id,
@@ -233,14 +277,11 @@ impl FramingGenerator {
             let last_stud_position = ((wall_length - lumber_width) / config.stud_spacing).floor()
                 * config.stud_spacing;
             if (wall_length - lumber_width - last_stud_position).abs() > lumber_width {
-                let x = wall.start.x + dir_x * (wall_length - lumber_width);
-                let y = wall.start.y + dir_y * (wall_length - lumber_width);
-
                 let end_stud = FramingMember::new(
                     FramingMemberType::Stud,
                     config.lumber_size,
                     FramingMaterial::SPF,
-                    Point3::new(x, y, stud_z),
+                    Self::point_along(wall, wall_length - lumber_width, stud_z),
                     stud_height,
                     0.0,
                     wall.id,
@@ -252,15 +293,14 @@ impl FramingGenerator {
         studs
     }
 
-    /// Generate all framing for a single opening (kings, jacks, header, sill, cripples)
+    /// Generate all framing for a single opening (kings, jacks, header, sill,
+    /// cripples), along with the header chosen for it
     fn generate_opening_framing(
         wall: &Wall,
         ro: &RoughOpening,
         config: &WallFramingConfig,
-    ) -> Vec<FramingMember> {
+    ) -> (Vec<FramingMember>, HeaderSelection) {
         let mut members = Vec::new();
-        let (dir_x, dir_y) = wall.direction();
-        let wall_rotation = dir_y.atan2(dir_x);
         let (lumber_width, lumber_depth) = config.lumber_size.actual_dimensions();
 
         // Calculate positions
@@ -281,11 +321,7 @@ impl FramingGenerator {
             FramingMemberType::KingStud,
             config.lumber_size,
             FramingMaterial::SPF,
-            Point3::new(
-                wall.start.x + dir_x * king_left_pos,
-                wall.start.y + dir_y * king_left_pos,
-                stud_z,
-            ),
+            Self::point_along(wall, king_left_pos, stud_z),
             full_stud_height,
             0.0,
             wall.id,
@@ -297,11 +333,7 @@ impl FramingGenerator {
             FramingMemberType::KingStud,
             config.lumber_size,
             FramingMaterial::SPF,
-            Point3::new(
-                wall.start.x + dir_x * king_right_pos,
-                wall.start.y + dir_y * king_right_pos,
-                stud_z,
-            ),
+            Self::point_along(wall, king_right_pos, stud_z),
             full_stud_height,
             0.0,
             wall.id,
@@ -322,11 +354,7 @@ impl FramingGenerator {
                 FramingMemberType::JackStud,
                 config.lumber_size,
                 FramingMaterial::SPF,
-                Point3::new(
-                    wall.start.x + dir_x * (king_left_pos + offset),
-                    wall.start.y + dir_y * (king_left_pos + offset),
-                    stud_z,
-                ),
+                Self::point_along(wall, king_left_pos + offset, stud_z),
                 jack_height,
                 0.0,
                 wall.id,
@@ -339,11 +367,7 @@ impl FramingGenerator {
                 FramingMemberType::JackStud,
                 config.lumber_size,
                 FramingMaterial::SPF,
-                Point3::new(
-                    wall.start.x + dir_x * (king_right_pos - offset),
-                    wall.start.y + dir_y * (king_right_pos - offset),
-                    stud_z,
-                ),
+                Self::point_along(wall, king_right_pos - offset, stud_z),
                 jack_height,
                 0.0,
                 wall.id,
@@ -352,23 +376,29 @@ impl FramingGenerator {
             members.push(jack_right);
         }
 
-        // Header (spans between jack studs)
-        let header_lumber_size = Self::size_header_lumber(ro.width, config.is_load_bearing);
+        // Header (spans between jack studs), one member as thick as its plies
+        let header_size = Self::header_size(ro, config);
         let header_length = ro.width + (ro.jack_stud_count as f64 * lumber_width * 2.0);
-        let header_x = wall.start.x + dir_x * (king_left_pos + lumber_width);
-        let header_y = wall.start.y + dir_y * (king_left_pos + lumber_width);
+        let header_start = king_left_pos + lumber_width;
+        let header_rotation = Self::rotation_between(wall, header_start, header_start + header_length);
 
         let header = FramingMember::new(
             FramingMemberType::Header,
-            header_lumber_size,
-            Self::header_material(ro.header_type),
-            Point3::new(header_x, header_y, header_bottom_z),
+            LumberSize::Custom { width: header_size.thickness(), depth: header_size.depth() },
+            Self::header_material(header_size.header_type),
+            Self::point_along(wall, header_start, header_bottom_z),
             header_length,
-            wall_rotation,
+            header_rotation,
             wall.id,
         )
         .with_opening(ro.opening_id);
         members.push(header);
+        let selection = HeaderSelection {
+            opening_id: ro.opening_id,
+            span: ro.width,
+            length: header_length,
+            size: header_size,
+        };
 
         // Sill plate (for windows, below the opening)
         if ro.requires_sill {
@@ -381,9 +411,9 @@ impl FramingGenerator {
                 FramingMemberType::Sill,
                 config.lumber_size,
                 FramingMaterial::SPF,
-                Point3::new(header_x, header_y, sill_z),
+                Self::point_along(wall, header_start, sill_z),
                 header_length,
-                wall_rotation,
+                header_rotation,
                 wall.id,
             )
             .with_opening(ro.opening_id);
@@ -411,7 +441,7 @@ impl FramingGenerator {
             }
         }
 
-        members
+        (members, selection)
     }
 
     /// Generate cripple studs above header or below sill
@@ -424,7 +454,6 @@ impl FramingGenerator {
         _below_sill: bool,
     ) -> Vec<FramingMember> {
         let mut cripples = Vec::new();
-        let (dir_x, dir_y) = wall.direction();
         let (lumber_width, _) = config.lumber_size.actual_dimensions();
 
         let ro_left = ro.position_along_wall - ro.width / 2.0;
@@ -437,11 +466,7 @@ impl FramingGenerator {
                 FramingMemberType::CrippleStud,
                 config.lumber_size,
                 FramingMaterial::SPF,
-                Point3::new(
-                    wall.start.x + dir_x * pos,
-                    wall.start.y + dir_y * pos,
-                    z_position,
-                ),
+                Self::point_along(wall, pos, z_position),
                 height,
                 0.0,
                 wall.id,
@@ -455,70 +480,175 @@ impl FramingGenerator {
         cripples
     }
 
-    /// Generate fire blocking at 10' intervals
-    fn generate_fire_blocking(
+    /// Whether a corner's studs belong to this wall. One wall runs through
+    /// each corner and carries them; the other butts into it. The wall
+    /// with the lower ID runs through, so both sides agree.
+    fn runs_through(wall: &Wall, join: WallJoin) -> bool {
+        match join {
+            WallJoin::Corner { wall: other } => wall.id.as_uuid() < other.as_uuid(),
+            _ => false,
+        }
+    }
+
+    /// Generate the extra studs at each corner this wall runs through: two
+    /// beside the end stud for a three-stud corner, or one turned flat for
+    /// a California corner
+    fn generate_corner_studs(wall: &Wall, config: &WallFramingConfig) -> Vec<FramingMember> {
+        let mut studs = Vec::new();
+        let wall_length = wall.length();
+        let (lumber_width, lumber_depth) = config.lumber_size.actual_dimensions();
+        let stud_height = wall.height - Self::top_plate_depth(config) - lumber_depth;
+        let stud_z = wall.base_offset + lumber_depth;
+
+        for (join, at_start) in [(wall.start_join, true), (wall.end_join, false)] {
+            if !Self::runs_through(wall, join) {
+                continue;
+            }
+            let count = match config.corner_style {
+                CornerStyle::ThreeStud => 2,
+                CornerStyle::California => 1,
+            };
+            for i in 0..count {
+                // Side by side inward from the end stud
+                let position = if at_start {
+                    (i as f64 + 1.0) * lumber_width
+                } else {
+                    wall_length - (i as f64 + 2.0) * lumber_width
+                };
+                // A California corner's stud is turned flat, across the wall
+                let rotation = match config.corner_style {
+                    CornerStyle::ThreeStud => 0.0,
+                    CornerStyle::California => {
+                        Self::rotation_between(wall, position, position + lumber_width)
+                            + std::f64::consts::FRAC_PI_2
+                    }
+                };
+                studs.push(FramingMember::new(
+                    FramingMemberType::CornerStud,
+                    config.lumber_size,
+                    FramingMaterial::SPF,
+                    Self::point_along(wall, position, stud_z),
+                    stud_height,
+                    rotation,
+                    wall.id,
+                ));
+            }
+        }
+
+        studs
+    }
+
+    /// Generate a pair of backing studs flanking each partition that tees
+    /// into the wall, so the partition's end stud has something to nail to
+    fn generate_partition_backing(
         wall: &Wall,
         config: &WallFramingConfig,
+        partitions: &[PartitionJunction],
         rough_openings: &[RoughOpening],
-        rotation: f64,
     ) -> Vec<FramingMember> {
-        let mut blocking = Vec::new();
+        let mut backing = Vec::new();
         let wall_length = wall.length();
-        let (dir_x, dir_y) = wall.direction();
-        let (_, lumber_depth) = config.lumber_size.actual_dimensions();
-
-        // Fire blocking typically required every 10 feet (120 inches)
-        const FIRE_BLOCK_INTERVAL: f64 = 120.0;
+        let (lumber_width, lumber_depth) = config.lumber_size.actual_dimensions();
+        let stud_height = wall.height - Self::top_plate_depth(config) - lumber_depth;
+        let stud_z = wall.base_offset + lumber_depth;
 
-        // Calculate mid-height position for blocking
-        let plate_count = if config.double_top_plate { 3.0 } else { 2.0 };
-        let stud_height = wall.height - (plate_count * lumber_depth);
-        let blocking_z = wall.base_offset + lumber_depth + stud_height / 2.0;
-
-        // Generate blocking between studs
-        let mut pos = 0.0;
-        while pos < wall_length {
-            // Find the next segment that needs blocking
-            let segment_start = pos;
-            let segment_end = (pos + FIRE_BLOCK_INTERVAL).min(wall_length);
-
-            // Skip if entirely within an opening
-            if !Self::segment_entirely_in_opening(segment_start, segment_end, rough_openings) {
-                // Place blocking between stud positions
-                let mut block_pos = segment_start;
-                while block_pos < segment_end {
-                    let next_stud = ((block_pos / config.stud_spacing).floor() + 1.0)
-                        * config.stud_spacing;
-
-                    if next_stud < segment_end {
-                        let block_length = config.stud_spacing;
-
-                        // Check this specific block isn't in an opening
-                        if !Self::position_in_opening(block_pos, block_length, rough_openings) {
-                            let block = FramingMember::new(
-                                FramingMemberType::FireBlocking,
-                                config.lumber_size,
-                                FramingMaterial::SPF,
-                                Point3::new(
-                                    wall.start.x + dir_x * block_pos,
-                                    wall.start.y + dir_y * block_pos,
-                                    blocking_z,
-                                ),
-                                block_length,
-                                rotation,
-                                wall.id,
-                            );
-                            blocking.push(block);
-                        }
-                    }
-                    block_pos = next_stud;
+        for partition in partitions {
+            let positions = [
+                partition.position - partition.thickness / 2.0 - lumber_width,
+                partition.position + partition.thickness / 2.0,
+            ];
+            for position in positions {
+                let position = position.clamp(0.0, wall_length - lumber_width);
+                if Self::position_in_opening(position, lumber_width, rough_openings) {
+                    continue;
                 }
+                backing.push(FramingMember::new(
+                    FramingMemberType::PartitionBacking,
+                    config.lumber_size,
+                    FramingMaterial::SPF,
+                    Self::point_along(wall, position, stud_z),
+                    stud_height,
+                    0.0,
+                    wall.id,
+                ));
             }
+        }
+
+        backing
+    }
 
-            pos += FIRE_BLOCK_INTERVAL;
+    /// Spans between neighbouring full-height studs, as (start, end) along
+    /// the wall. Blocking fills these.
+    fn stud_bays(wall: &Wall, config: &WallFramingConfig, layout: &FramingLayout) -> Vec<(f64, f64)> {
+        let (lumber_width, _) = config.lumber_size.actual_dimensions();
+        let mut positions: Vec<f64> = layout
+            .members
+            .iter()
+            .filter(|m| {
+                matches!(
+                    m.member_type,
+                    FramingMemberType::Stud
+                        | FramingMemberType::KingStud
+                        | FramingMemberType::CornerStud
+                        | FramingMemberType::PartitionBacking
+                )
+            })
+            .map(|m| wall.project(&Point2::new(m.position.x, m.position.y)).0)
+            .collect();
+        positions.sort_by(f64::total_cmp);
+
+        positions
+            .windows(2)
+            .map(|pair| (pair[0] + lumber_width, pair[1]))
+            .filter(|(start, end)| end - start > 1e-6)
+            .collect()
+    }
+
+    /// Generate one row of blocking `height` above the bottom of the wall,
+    /// a block in each stud bay the row crosses clear of openings
+    fn generate_blocking_row(
+        wall: &Wall,
+        config: &WallFramingConfig,
+        bays: &[(f64, f64)],
+        rough_openings: &[RoughOpening],
+        member_type: FramingMemberType,
+        height: f64,
+    ) -> Vec<FramingMember> {
+        let (_, lumber_depth) = config.lumber_size.actual_dimensions();
+        let stud_top = wall.height - Self::top_plate_depth(config);
+        if height < lumber_depth || height >= stud_top {
+            return Vec::new();
         }
 
-        blocking
+        bays.iter()
+            .filter(|(start, end)| {
+                // Openings hang from the header, so they block everything
+                // from the bottom of the rough opening up
+                !rough_openings.iter().any(|ro| {
+                    let ro_left = ro.position_along_wall - ro.width / 2.0;
+                    let ro_right = ro.position_along_wall + ro.width / 2.0;
+                    let ro_bottom = stud_top - ro.header_depth - ro.height;
+                    *start < ro_right && *end > ro_left && height >= ro_bottom
+                })
+            })
+            .map(|&(start, end)| {
+                FramingMember::new(
+                    member_type,
+                    config.lumber_size,
+                    FramingMaterial::SPF,
+                    Self::point_along(wall, start, wall.base_offset + height),
+                    end - start,
+                    Self::rotation_between(wall, start, end),
+                    wall.id,
+                )
+            })
+            .collect()
+    }
+
+    /// Depth of the top plate(s)
+    fn top_plate_depth(config: &WallFramingConfig) -> f64 {
+        let (_, lumber_depth) = config.lumber_size.actual_dimensions();
+        if config.double_top_plate { 2.0 * lumber_depth } else { lumber_depth }
     }
 
     /// Compute rough opening from an Opening, applying tolerances
@@ -548,6 +678,12 @@ impl FramingGenerator {
                 position_along_wall,
                 config.is_load_bearing,
             ),
+            OpeningType::Other(_) if opening.opening_type.is_garage_door() => RoughOpening::for_garage_door(
+                opening.id,
+                opening.width,
+                opening.height,
+                position_along_wall,
+            ),
             OpeningType::Other(_) => {
                 // Generic opening - treat like a door
                 RoughOpening::for_door(
@@ -560,6 +696,16 @@ impl FramingGenerator {
             }
         };
 
+        // A catalog unit calls for its own rough opening
+        let ro = match opening.rough_size {
+            Some((width, height)) => ro.with_size(width, height),
+            None => ro,
+        };
+
+        // Size the header for the rough opening's span
+        let header = Self::header_size(&ro, config);
+        let ro = ro.with_header_type(header.header_type).with_header_depth(header.depth());
+
         // Validate rough opening fits in wall
         let ro_left = ro.position_along_wall - ro.width / 2.0;
         let ro_right = ro.position_along_wall + ro.width / 2.0;
@@ -649,57 +795,17 @@ impl FramingGenerator {
         false
     }
 
-    /// Check if a segment is entirely within an opening
-    fn segment_entirely_in_opening(
-        start: f64,
-        end: f64,
-        rough_openings: &[RoughOpening],
-    ) -> bool {
-        for ro in rough_openings {
-            let ro_left = ro.position_along_wall - ro.width / 2.0;
-            let ro_right = ro.position_along_wall + ro.width / 2.0;
-
-            if start >= ro_left && end <= ro_right {
-                return true;
-            }
-        }
-        false
-    }
-
     /// Size header based on span and load-bearing status
     /// Follows simplified IRC Table R602.7 guidelines
     pub fn size_header(span: f64, is_load_bearing: bool) -> HeaderType {
         HeaderType::for_span(span, is_load_bearing)
     }
 
-    /// Get the lumber size for a header based on span
-    fn size_header_lumber(span: f64, is_load_bearing: bool) -> LumberSize {
-        if !is_load_bearing {
-            // Non-load-bearing can use smaller headers
-            if span <= 48.0 {
-                LumberSize::TwoBySix
-            } else {
-                LumberSize::TwoByEight
-            }
-        } else {
-            // Load-bearing: size per IRC Table R602.7
-            if span <= 48.0 {
-                LumberSize::TwoBySix
-            } else if span <= 72.0 {
-                LumberSize::TwoByEight
-            } else if span <= 96.0 {
-                LumberSize::TwoByTen
-            } else if span <= 120.0 {
-                LumberSize::TwoByTwelve
-            } else {
-                // > 10': Need engineered lumber (LVL)
-                // Use custom size to represent LVL dimensions
-                LumberSize::Custom {
-                    width: 1.75,  // LVL width
-                    depth: 11.875, // 1-3/4" x 11-7/8" LVL
-                }
-            }
-        }
+    /// Header for a rough opening in this wall, per its load and tributary
+    /// width; garage door headers are sized as bearing regardless
+    fn header_size(ro: &RoughOpening, config: &WallFramingConfig) -> HeaderSize {
+        let tributary_width = config.tributary_width.unwrap_or(DEFAULT_TRIBUTARY_WIDTH);
+        size_header(ro.width, config.is_load_bearing || ro.garage_door, tributary_width)
     }
 
     /// Get the material for a header type
@@ -940,5 +1046,166 @@ mod tests {
         assert_eq!(double_tops.len(), 0);
     }
 
+    #[test]
+    fn test_curved_wall_framing() {
+        // Quarter circle of 120" radius, bowing to the wall's left
+        let chord = 120.0 * std::f64::consts::SQRT_2;
+        let wall = create_test_wall(chord, 96.0).with_arc(120.0);
+        let assembly = create_test_assembly();
+
+        let layout = FramingGenerator::generate_wall_framing(&wall, &assembly, &[]).unwrap();
+        let (center, _, _) = wall.arc_geometry().unwrap();
+
+        // Studs stand on the arc, spaced along it rather than the chord
+        let studs: Vec<_> = layout
+            .members
+            .iter()
+            .filter(|m| m.member_type == FramingMemberType::Stud)
+            .collect();
+        assert!(studs.len() as f64 >= wall.length() / 16.0);
+        for stud in &studs {
+            let r = Point2::new(stud.position.x, stud.position.y).distance_to(&center);
+            assert!((r - 120.0).abs() < 1e-9);
+        }
+
+        // Each plate is cut in pieces of at most 15 degrees of arc
+        let bottom: Vec<_> = layout
+            .members
+            .iter()
+            .filter(|m| m.member_type == FramingMemberType::BottomPlate)
+            .collect();
+        assert_eq!(bottom.len(), 6);
+        let total: f64 = bottom.iter().map(|m| m.length).sum();
+        assert!(total < wall.length() && total > wall.length() * 0.99);
+        assert!(bottom[0].rotation > bottom[5].rotation);
+    }
+
+    #[test]
+    fn test_corner_studs() {
+        // Two walls meeting at a corner; only the one that runs through
+        // carries the corner studs
+        let mut a = create_test_wall(120.0, 96.0);
+        let mut b = create_test_wall(120.0, 96.0);
+        if a.id.as_uuid() > b.id.as_uuid() {
+            std::mem::swap(&mut a, &mut b);
+        }
+        a.end_join = WallJoin::Corner { wall: b.id };
+        b.start_join = WallJoin::Corner { wall: a.id };
+        let assembly = create_test_assembly();
+
+        let corner_studs = |wall: &Wall| {
+            FramingGenerator::generate_wall_framing(wall, &assembly, &[])
+                .unwrap()
+                .members_of_type(FramingMemberType::CornerStud)
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let studs = corner_studs(&a);
+        assert_eq!(studs.len(), 2);
+        assert!(studs.iter().all(|m| m.position.x > 110.0 && m.rotation == 0.0));
+        assert!(corner_studs(&b).is_empty());
+
+        a.framing_config = a.framing_config.clone().with_corner_style(CornerStyle::California);
+        let studs = corner_studs(&a);
+        assert_eq!(studs.len(), 1);
+        assert!((studs[0].rotation - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_partition_backing() {
+        let wall = create_test_wall(144.0, 96.0);
+        let assembly = create_test_assembly();
+        let partition = PartitionJunction { position: 60.0, thickness: 3.5 };
+
+        let layout = FramingGenerator::generate_wall_framing_with_partitions(&wall, &assembly, &[], &[partition])
+            .unwrap();
+        let mut backing: Vec<f64> = layout
+            .members_of_type(FramingMemberType::PartitionBacking)
+            .iter()
+            .map(|m| m.position.x)
+            .collect();
+        backing.sort_by(f64::total_cmp);
+        assert_eq!(backing, vec![56.75, 61.75]);
+    }
+
+    #[test]
+    fn test_blocking_rows() {
+        // 12' tall wall with a window and backing rows below and beside it
+        let mut wall = create_test_wall(144.0, 144.0);
+        wall.framing_config = WallFramingConfig::exterior()
+            .with_fire_blocking(true)
+            .with_blocking_rows(vec![24.0, 96.0]);
+        let assembly = create_test_assembly();
+        let window = Opening::window(wall.id, 0.5, 36.0, 48.0, 36.0, WindowProperties::double_pane());
+
+        let layout = FramingGenerator::generate_wall_framing(&wall, &assembly, &[window]).unwrap();
+
+        // One fire blocking row 10' up, across every bay but the window's
+        // (openings hang from the header, which starts just above the row)
+        let fire = layout.members_of_type(FramingMemberType::FireBlocking);
+        assert!(!fire.is_empty());
+        assert!(fire.iter().all(|m| (m.position.z - 125.5).abs() < 1e-9));
+        let covered: f64 = fire.iter().map(|m| m.length).sum();
+        let studs = layout
+            .members
+            .iter()
+            .filter(|m| matches!(m.member_type, FramingMemberType::Stud | FramingMemberType::KingStud))
+            .count();
+        assert!((covered + studs as f64 * 1.5 + 37.0 - 144.0).abs() < 1e-9);
+
+        // The low row runs under the window; the high one stops at it
+        let rows = layout.members_of_type(FramingMemberType::MidHeightBlocking);
+        let (low, high): (Vec<&FramingMember>, Vec<_>) = rows.into_iter().partition(|m| m.position.z < 50.0);
+        assert_eq!(low.len(), high.len() + 1);
+        assert!(high.iter().all(|m| m.position.x + m.length <= 53.5 || m.position.x >= 90.5));
+    }
+
+    #[test]
+    fn test_headers_sized_per_opening() {
+        // 6' door in a bearing wall, under a 28' and then a 20' wide load
+        let mut wall = create_test_wall(192.0, 96.0);
+        let assembly = create_test_assembly();
+        let door = Opening::door(wall.id, 0.5, 72.0, 80.0, DoorProperties::exterior());
+
+        let layout = FramingGenerator::generate_wall_framing(&wall, &assembly, std::slice::from_ref(&door)).unwrap();
+        assert_eq!(layout.headers.len(), 1);
+        let selection = &layout.headers[0];
+        assert_eq!(selection.opening_id, door.id);
+        assert_eq!(selection.size.description(), "2-2x10");
+
+        // The header member carries both plies, and the jacks stop under it
+        let header = &layout.members_of_type(FramingMemberType::Header)[0];
+        assert_eq!(header.cross_section(), (3.0, 9.25));
+        let king = &layout.members_of_type(FramingMemberType::KingStud)[0];
+        let jack = &layout.members_of_type(FramingMemberType::JackStud)[0];
+        assert!((king.length - jack.length - 9.25).abs() < 1e-9);
+
+        wall.framing_config = wall.framing_config.clone().with_tributary_width(20.0);
+        let layout = FramingGenerator::generate_wall_framing(&wall, &assembly, &[door]).unwrap();
+        assert_eq!(layout.headers[0].size.description(), "2-2x8");
+    }
+
+    #[test]
+    fn test_garage_door_framing() {
+        // 9' garage door in a nonbearing wall
+        let mut wall = create_test_wall(192.0, 96.0);
+        wall.framing_config = WallFramingConfig::interior_partition();
+        let assembly = create_test_assembly();
+        let door = Opening::new(wall.id, OpeningType::Other("garage_door".to_string()), 0.5, 108.0, 84.0, 0.0);
+
+        let ro = FramingGenerator::compute_rough_opening(&door, &wall, &wall.framing_config).unwrap();
+        assert!(ro.garage_door);
+        assert_eq!((ro.width, ro.height), (108.0, 84.0));
+        let layout = FramingGenerator::generate_wall_framing(&wall, &assembly, &[door]).unwrap();
+        assert_eq!(layout.members_of_type(FramingMemberType::JackStud).len(), 4);
+
+        // Sized for the load above, not as a partition header
+        let partition = size_header(108.0, false, DEFAULT_TRIBUTARY_WIDTH);
+        let bearing = size_header(108.0, true, DEFAULT_TRIBUTARY_WIDTH);
+        assert_eq!(layout.headers[0].size.description(), bearing.description());
+        assert_ne!(bearing.description(), partition.description());
+    }
+
     use crate::domain::{DoorProperties, WindowProperties};
 }
@@ -0,0 +1,136 @@
+// Header sizing - picks the lightest header that spans an opening
+// Built-up lumber headers follow IRC Table R602.7(1) for walls carrying a
+// roof and ceiling (30 psf ground snow). Spans past the table get an LVL
+// beam sized by rule of thumb until an engineered schedule replaces it.
+
+use crate::domain::{HeaderSize, HeaderType, LumberSize};
+
+/// Building widths the span table is given for (feet)
+const TABLE_WIDTHS: [f64; 3] = [20.0, 28.0, 36.0];
+
+/// Tributary width assumed when a wall doesn't give one (feet)
+pub const DEFAULT_TRIBUTARY_WIDTH: f64 = 28.0;
+
+/// Openings doubled 2x4s may span in a nonbearing wall (inches); IRC
+/// R602.7.2 allows even a single flat 2x4 this far
+const NONBEARING_2X4_SPAN: f64 = 96.0;
+
+/// LVL plies are 1-3/4" thick
+const LVL_PLY_WIDTH: f64 = 1.75;
+/// Stock LVL depths (inches)
+const LVL_DEPTHS: [f64; 5] = [9.25, 11.875, 14.0, 16.0, 18.0];
+
+/// Built-up headers from the span table, lightest (least wood) first,
+/// with their allowable spans (inches) for each of `TABLE_WIDTHS`
+const SPAN_TABLE: [(u8, LumberSize, [f64; 3]); 11] = [
+    (2, LumberSize::TwoByFour, [42.0, 38.0, 34.0]),
+    (2, LumberSize::TwoBySix, [65.0, 56.0, 50.0]),
+    (2, LumberSize::TwoByEight, [82.0, 71.0, 64.0]),
+    (2, LumberSize::TwoByTen, [101.0, 87.0, 78.0]),
+    (3, LumberSize::TwoByEight, [100.0, 89.0, 80.0]),
+    (2, LumberSize::TwoByTwelve, [117.0, 101.0, 90.0]),
+    (3, LumberSize::TwoByTen, [126.0, 109.0, 98.0]),
+    (4, LumberSize::TwoByEight, [110.0, 100.0, 92.0]),
+    (3, LumberSize::TwoByTwelve, [146.0, 127.0, 113.0]),
+    (4, LumberSize::TwoByTen, [140.0, 126.0, 113.0]),
+    (4, LumberSize::TwoByTwelve, [169.0, 146.0, 131.0]),
+];
+
+/// Size the header over an opening
+///
+/// # Arguments
+/// * `span` - Rough opening width (inches)
+/// * `is_load_bearing` - Whether the wall carries floor or roof load
+/// * `tributary_width` - Width of floor and roof bearing on the wall (feet)
+pub fn size_header(span: f64, is_load_bearing: bool, tributary_width: f64) -> HeaderSize {
+    if !is_load_bearing && span <= NONBEARING_2X4_SPAN {
+        return built_up(2, LumberSize::TwoByFour);
+    }
+
+    // Nonbearing walls carry nothing, so the lightest column applies;
+    // otherwise the first column at least as wide as the load
+    let column = if is_load_bearing {
+        TABLE_WIDTHS.iter().position(|&w| tributary_width <= w)
+    } else {
+        Some(0)
+    };
+    if let Some(column) = column {
+        if let Some(&(plies, lumber, _)) = SPAN_TABLE.iter().find(|(_, _, spans)| span <= spans[column]) {
+            return built_up(plies, lumber);
+        }
+    }
+
+    size_lvl(span, tributary_width.max(TABLE_WIDTHS[0]))
+}
+
+fn built_up(plies: u8, lumber_size: LumberSize) -> HeaderSize {
+    HeaderSize {
+        header_type: if plies == 2 { HeaderType::DoubleLumber } else { HeaderType::TripleLumber },
+        lumber_size,
+        plies,
+    }
+}
+
+/// An LVL beam spans about a foot per inch of depth with two plies at
+/// 28' of tributary width, less as the load widens; a third ply adds a
+/// fifth again
+fn size_lvl(span: f64, tributary_width: f64) -> HeaderSize {
+    let reach = (DEFAULT_TRIBUTARY_WIDTH / tributary_width).sqrt();
+    let (plies, depth) = [(2u8, 1.0), (3, 1.2)]
+        .iter()
+        .flat_map(|&(plies, factor)| LVL_DEPTHS.iter().map(move |&depth| (plies, depth, factor)))
+        .find(|&(_, depth, factor)| span <= depth * 12.0 * reach * factor)
+        .map(|(plies, depth, _)| (plies, depth))
+        .unwrap_or((3, LVL_DEPTHS[LVL_DEPTHS.len() - 1]));
+
+    HeaderSize {
+        header_type: HeaderType::LVL,
+        lumber_size: LumberSize::Custom { width: LVL_PLY_WIDTH, depth },
+        plies,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_header_from_span_table() {
+        // 3' door in a bearing wall of a 28' wide house, then a 3'-6" one
+        let size = size_header(38.0, true, 28.0);
+        assert_eq!((size.plies, size.lumber_size), (2, LumberSize::TwoByFour));
+        let size = size_header(44.0, true, 28.0);
+        assert_eq!((size.plies, size.lumber_size), (2, LumberSize::TwoBySix));
+
+        // A wider load needs a deeper header over the same span
+        let size = size_header(60.0, true, 20.0);
+        assert_eq!((size.plies, size.lumber_size), (2, LumberSize::TwoBySix));
+        let size = size_header(60.0, true, 36.0);
+        assert_eq!((size.plies, size.lumber_size), (2, LumberSize::TwoByEight));
+
+        // 6' slider, then a 9' one that needs a third ply
+        let size = size_header(74.0, true, 28.0);
+        assert_eq!((size.plies, size.lumber_size), (2, LumberSize::TwoByTen));
+        let size = size_header(105.0, true, 28.0);
+        assert_eq!((size.plies, size.lumber_size), (3, LumberSize::TwoByTen));
+        assert_eq!(size.header_type, HeaderType::TripleLumber);
+        assert_eq!(size.description(), "3-2x10");
+
+        // Nonbearing walls get by with doubled 2x4s up to 8'
+        let size = size_header(90.0, false, 28.0);
+        assert_eq!((size.plies, size.lumber_size), (2, LumberSize::TwoByFour));
+    }
+
+    #[test]
+    fn test_size_header_beyond_table() {
+        // 16' garage door opening
+        let size = size_header(194.0, true, 28.0);
+        assert_eq!(size.header_type, HeaderType::LVL);
+        assert_eq!(size.plies, 2);
+        assert!((size.depth() - 18.0).abs() < 1e-9);
+        assert!((size.thickness() - 3.5).abs() < 1e-9);
+
+        // Wider than the table covers
+        assert_eq!(size_header(40.0, true, 40.0).header_type, HeaderType::LVL);
+    }
+}
@@ -1,8 +1,16 @@
-// Framing generation module - algorithms for wall framing layout
-// Generates studs, plates, headers, and other framing members
+// Framing generation module - algorithms for wall, floor and deck framing layout
+// Generates studs, plates, headers, joists, and other framing members
 
+pub mod cut_list;
+pub mod deck;
+pub mod floor;
 pub mod generator;
+pub mod headers;
 pub mod regeneration;
 
-pub use generator::FramingGenerator;
+pub use generator::{FramingGenerator, FramingError, PartitionJunction, CURVED_PLATE_MAX_SWEEP};
+pub use cut_list::{CutList, LevelCutList, StockBoard, StockOrder, WallCutList, STOCK_LENGTHS};
+pub use deck::DeckFramingGenerator;
+pub use floor::FloorFramingGenerator;
+pub use headers::size_header;
 pub use regeneration::RegenerationManager;
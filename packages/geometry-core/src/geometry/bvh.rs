@@ -0,0 +1,283 @@
+// Bounding volume hierarchy over mesh triangles
+// Finds the nearest triangle a ray hits across many meshes without testing
+// each one: triangles are split at the median of their longest axis until
+// a few remain per leaf, and a ray only descends into boxes it crosses.
+
+use super::MeshData;
+
+/// Triangles per leaf
+const LEAF_SIZE: usize = 4;
+
+type Vec3 = [f64; 3];
+
+#[derive(Debug, Clone, Copy)]
+struct Triangle {
+    vertices: [Vec3; 3],
+    /// Which of the input meshes the triangle came from
+    owner: usize,
+}
+
+impl Triangle {
+    fn centroid(&self) -> Vec3 {
+        let [a, b, c] = self.vertices;
+        [(a[0] + b[0] + c[0]) / 3.0, (a[1] + b[1] + c[1]) / 3.0, (a[2] + b[2] + c[2]) / 3.0]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    min: Vec3,
+    max: Vec3,
+}
+
+impl Bounds {
+    fn empty() -> Self {
+        Self { min: [f64::MAX; 3], max: [f64::MIN; 3] }
+    }
+
+    fn grow(&mut self, p: Vec3) {
+        for (axis, &v) in p.iter().enumerate() {
+            self.min[axis] = self.min[axis].min(v);
+            self.max[axis] = self.max[axis].max(v);
+        }
+    }
+
+    fn longest_axis(&self) -> usize {
+        let size = [self.max[0] - self.min[0], self.max[1] - self.min[1], self.max[2] - self.min[2]];
+        if size[0] >= size[1] && size[0] >= size[2] {
+            0
+        } else if size[1] >= size[2] {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Distance along the ray where it enters the box, if it does before
+    /// `limit`
+    fn entry(&self, origin: Vec3, inv_dir: Vec3, limit: f64) -> Option<f64> {
+        let (mut near, mut far) = (0.0f64, limit);
+        for axis in 0..3 {
+            let t1 = (self.min[axis] - origin[axis]) * inv_dir[axis];
+            let t2 = (self.max[axis] - origin[axis]) * inv_dir[axis];
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+        }
+        (near <= far).then_some(near)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf { bounds: Bounds, start: usize, end: usize },
+    Branch { bounds: Bounds, left: usize, right: usize },
+}
+
+impl Node {
+    fn bounds(&self) -> &Bounds {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Branch { bounds, .. } => bounds,
+        }
+    }
+}
+
+/// Where a ray first hits a triangle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// Index of the mesh the triangle belongs to, as given to `build`
+    pub owner: usize,
+    /// Distance along the ray, in units of the direction's length
+    pub distance: f64,
+    pub point: Vec3,
+    /// Unit normal of the triangle, turned to face the ray's origin
+    pub normal: Vec3,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TriangleBvh {
+    triangles: Vec<Triangle>,
+    nodes: Vec<Node>,
+}
+
+impl TriangleBvh {
+    /// Index the triangles of each (owner, mesh) pair
+    pub fn build<'a>(meshes: impl IntoIterator<Item = (usize, &'a MeshData)>) -> Self {
+        let mut triangles = Vec::new();
+        for (owner, mesh) in meshes {
+            let point = |i: u32| {
+                let i = i as usize * 3;
+                [mesh.positions[i] as f64, mesh.positions[i + 1] as f64, mesh.positions[i + 2] as f64]
+            };
+            for tri in mesh.indices.chunks_exact(3) {
+                triangles.push(Triangle { vertices: [point(tri[0]), point(tri[1]), point(tri[2])], owner });
+            }
+        }
+
+        let mut bvh = Self { triangles, nodes: Vec::new() };
+        if !bvh.triangles.is_empty() {
+            bvh.split(0, bvh.triangles.len());
+        }
+        bvh
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Nearest triangle hit by the ray from `origin` along `direction`
+    pub fn raycast(&self, origin: Vec3, direction: Vec3) -> Option<RayHit> {
+        if self.nodes.is_empty() || direction == [0.0; 3] {
+            return None;
+        }
+        let inv_dir = [1.0 / direction[0], 1.0 / direction[1], 1.0 / direction[2]];
+
+        let mut best: Option<(f64, usize)> = None;
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let limit = best.map_or(f64::MAX, |(t, _)| t);
+            if self.nodes[index].bounds().entry(origin, inv_dir, limit).is_none() {
+                continue;
+            }
+            match self.nodes[index] {
+                Node::Leaf { start, end, .. } => {
+                    for (i, triangle) in self.triangles[start..end].iter().enumerate() {
+                        if let Some(t) = intersect(triangle, origin, direction) {
+                            if best.is_none_or(|(best_t, _)| t < best_t) {
+                                best = Some((t, start + i));
+                            }
+                        }
+                    }
+                }
+                Node::Branch { left, right, .. } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+            }
+        }
+
+        best.map(|(distance, index)| {
+            let triangle = &self.triangles[index];
+            let [a, b, c] = triangle.vertices;
+            let mut normal = normalize(cross(sub(b, a), sub(c, a)));
+            if dot(normal, direction) > 0.0 {
+                normal = [-normal[0], -normal[1], -normal[2]];
+            }
+            RayHit {
+                owner: triangle.owner,
+                distance,
+                point: [
+                    origin[0] + direction[0] * distance,
+                    origin[1] + direction[1] * distance,
+                    origin[2] + direction[2] * distance,
+                ],
+                normal,
+            }
+        })
+    }
+
+    /// Build the node over `triangles[start..end]`, returning its index
+    fn split(&mut self, start: usize, end: usize) -> usize {
+        let mut bounds = Bounds::empty();
+        let mut centroids = Bounds::empty();
+        for triangle in &self.triangles[start..end] {
+            triangle.vertices.iter().for_each(|&v| bounds.grow(v));
+            centroids.grow(triangle.centroid());
+        }
+
+        let index = self.nodes.len();
+        if end - start <= LEAF_SIZE {
+            self.nodes.push(Node::Leaf { bounds, start, end });
+            return index;
+        }
+
+        let axis = centroids.longest_axis();
+        let mid = (start + end) / 2;
+        self.triangles[start..end].select_nth_unstable_by(mid - start, |a, b| {
+            a.centroid()[axis].total_cmp(&b.centroid()[axis])
+        });
+        // Reserve the slot; children are pushed after it
+        self.nodes.push(Node::Leaf { bounds, start, end });
+        let left = self.split(start, mid);
+        let right = self.split(mid, end);
+        self.nodes[index] = Node::Branch { bounds, left, right };
+        index
+    }
+}
+
+/// Möller–Trumbore ray/triangle intersection, hits from either side
+fn intersect(triangle: &Triangle, origin: Vec3, direction: Vec3) -> Option<f64> {
+    const EPSILON: f64 = 1e-9;
+    let [a, b, c] = triangle.vertices;
+    let (e1, e2) = (sub(b, a), sub(c, a));
+    let p = cross(direction, e2);
+    let det = dot(e1, p);
+    if det.abs() < EPSILON {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = sub(origin, a);
+    let u = dot(s, p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = cross(s, e1);
+    let v = dot(direction, q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = dot(e2, q) * inv_det;
+    (t > EPSILON).then_some(t)
+}
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn normalize(v: Vec3) -> Vec3 {
+    let len = dot(v, v).sqrt();
+    if len > 1e-12 { [v[0] / len, v[1] / len, v[2] / len] } else { v }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Point3;
+    use crate::geometry::{create_box, solid_to_mesh};
+
+    fn box_mesh(x: f64, y: f64, size: f64) -> MeshData {
+        let solid = create_box(size, size, size, &Point3::new(x, y, 0.0)).unwrap();
+        solid_to_mesh(&solid, 0.1).unwrap()
+    }
+
+    #[test]
+    fn test_raycast_nearest_mesh() {
+        let meshes: Vec<MeshData> = (0..20).map(|i| box_mesh(i as f64 * 5.0, 0.0, 2.0)).collect();
+        let bvh = TriangleBvh::build(meshes.iter().enumerate());
+        assert_eq!(bvh.triangle_count(), meshes.iter().map(|m| m.triangle_count()).sum::<usize>());
+
+        // Straight down onto box 7, whose top is at z = 2
+        let hit = bvh.raycast([36.0, 1.0, 10.0], [0.0, 0.0, -1.0]).unwrap();
+        assert_eq!(hit.owner, 7);
+        assert!((hit.distance - 8.0).abs() < 1e-6);
+        assert!((hit.normal[2] - 1.0).abs() < 1e-6);
+
+        // Along the row from the -x side: the first box is hit, facing back
+        let hit = bvh.raycast([-10.0, 1.0, 1.0], [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(hit.owner, 0);
+        assert!((hit.point[0] - 0.0).abs() < 1e-6);
+        assert!((hit.normal[0] + 1.0).abs() < 1e-6);
+
+        // Between the boxes, and pointing away
+        assert!(bvh.raycast([3.5, 1.0, 10.0], [0.0, 0.0, -1.0]).is_none());
+        assert!(bvh.raycast([36.0, 1.0, 10.0], [0.0, 0.0, 1.0]).is_none());
+        assert!(TriangleBvh::default().raycast([0.0; 3], [1.0, 0.0, 0.0]).is_none());
+    }
+}
@@ -0,0 +1,517 @@
+// Boolean operations on solids
+// Operands are tessellated into planar polygons and combined with BSP
+// trees (each solid's polygons are clipped against the other's tree). The
+// surviving polygons are welded back into a closed Truck solid. Curved
+// faces come back as facets at the tessellation tolerance.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use truck_meshalgo::prelude::*;
+use truck_modeling::{Shell, Solid};
+use truck_modeling::Point3 as TruckPoint3;
+
+use super::polyhedron_shell;
+
+/// Tessellation tolerance for curved faces
+const TESSELLATION_TOLERANCE: f64 = 0.01;
+/// Distance within which a point is on a plane, and two points coincide
+const EPSILON: f64 = 1e-6;
+
+/// Everything inside either solid
+pub fn union_solids(a: &Solid, b: &Solid) -> Result<Solid> {
+    let mut a = Node::new(polygons_of(a)?);
+    let mut b = Node::new(polygons_of(b)?);
+    a.clip_to(&b);
+    b.clip_to(&a);
+    b.invert();
+    b.clip_to(&a);
+    b.invert();
+    a.build(b.all_polygons());
+    solid_from_polygons(a.all_polygons())
+}
+
+/// Everything inside `a` and outside `tool`
+pub fn subtract_solid(a: &Solid, tool: &Solid) -> Result<Solid> {
+    let mut a = Node::new(polygons_of(a)?);
+    let mut b = Node::new(polygons_of(tool)?);
+    a.invert();
+    a.clip_to(&b);
+    b.clip_to(&a);
+    b.invert();
+    b.clip_to(&a);
+    b.invert();
+    a.build(b.all_polygons());
+    a.invert();
+    solid_from_polygons(a.all_polygons())
+}
+
+/// Everything inside both solids
+pub fn intersect_solids(a: &Solid, b: &Solid) -> Result<Solid> {
+    let mut a = Node::new(polygons_of(a)?);
+    let mut b = Node::new(polygons_of(b)?);
+    a.invert();
+    b.clip_to(&a);
+    b.invert();
+    a.clip_to(&b);
+    b.clip_to(&a);
+    a.build(b.all_polygons());
+    a.invert();
+    solid_from_polygons(a.all_polygons())
+}
+
+type Vec3 = [f64; 3];
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn lerp(a: Vec3, b: Vec3, t: f64) -> Vec3 {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Plane {
+    normal: Vec3,
+    w: f64,
+}
+
+impl Plane {
+    fn from_points(a: Vec3, b: Vec3, c: Vec3) -> Option<Self> {
+        let n = cross(sub(b, a), sub(c, a));
+        let len = dot(n, n).sqrt();
+        if len < EPSILON * EPSILON {
+            return None;
+        }
+        let normal = [n[0] / len, n[1] / len, n[2] / len];
+        Some(Self { normal, w: dot(normal, a) })
+    }
+
+    fn flip(&mut self) {
+        self.normal = [-self.normal[0], -self.normal[1], -self.normal[2]];
+        self.w = -self.w;
+    }
+
+    /// Split a polygon by this plane into the four lists
+    fn split(
+        &self,
+        polygon: Polygon,
+        coplanar_front: &mut Vec<Polygon>,
+        coplanar_back: &mut Vec<Polygon>,
+        front: &mut Vec<Polygon>,
+        back: &mut Vec<Polygon>,
+    ) {
+        const COPLANAR: u8 = 0;
+        const FRONT: u8 = 1;
+        const BACK: u8 = 2;
+        const SPANNING: u8 = 3;
+
+        let mut kind = COPLANAR;
+        let kinds: Vec<u8> = polygon.vertices
+            .iter()
+            .map(|v| {
+                let t = dot(self.normal, *v) - self.w;
+                let k = if t < -EPSILON { BACK } else if t > EPSILON { FRONT } else { COPLANAR };
+                kind |= k;
+                k
+            })
+            .collect();
+
+        match kind {
+            COPLANAR => {
+                if dot(self.normal, polygon.plane.normal) > 0.0 {
+                    coplanar_front.push(polygon);
+                } else {
+                    coplanar_back.push(polygon);
+                }
+            }
+            FRONT => front.push(polygon),
+            BACK => back.push(polygon),
+            _ => {
+                let n = polygon.vertices.len();
+                let mut f = Vec::new();
+                let mut b = Vec::new();
+                for i in 0..n {
+                    let j = (i + 1) % n;
+                    let (ki, kj) = (kinds[i], kinds[j]);
+                    let (vi, vj) = (polygon.vertices[i], polygon.vertices[j]);
+                    if ki != BACK {
+                        f.push(vi);
+                    }
+                    if ki != FRONT {
+                        b.push(vi);
+                    }
+                    if (ki | kj) == SPANNING {
+                        let t = (self.w - dot(self.normal, vi)) / dot(self.normal, sub(vj, vi));
+                        let v = lerp(vi, vj, t);
+                        f.push(v);
+                        b.push(v);
+                    }
+                }
+                if f.len() >= 3 {
+                    front.push(Polygon { vertices: f, plane: polygon.plane });
+                }
+                if b.len() >= 3 {
+                    back.push(Polygon { vertices: b, plane: polygon.plane });
+                }
+            }
+        }
+    }
+}
+
+/// A convex planar polygon, counterclockwise seen from outside
+#[derive(Debug, Clone)]
+struct Polygon {
+    vertices: Vec<Vec3>,
+    plane: Plane,
+}
+
+impl Polygon {
+    fn new(vertices: Vec<Vec3>) -> Option<Self> {
+        let plane = Plane::from_points(vertices[0], vertices[1], vertices[2])?;
+        Some(Self { vertices, plane })
+    }
+
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        self.plane.flip();
+    }
+}
+
+/// BSP tree node: a splitting plane, the polygons on it, and subtrees
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<Box<Node>>,
+    back: Option<Box<Node>>,
+    polygons: Vec<Polygon>,
+}
+
+impl Node {
+    fn new(polygons: Vec<Polygon>) -> Self {
+        let mut node = Self::default();
+        node.build(polygons);
+        node
+    }
+
+    /// Swap inside and outside
+    fn invert(&mut self) {
+        for polygon in &mut self.polygons {
+            polygon.flip();
+        }
+        if let Some(plane) = &mut self.plane {
+            plane.flip();
+        }
+        if let Some(front) = &mut self.front {
+            front.invert();
+        }
+        if let Some(back) = &mut self.back {
+            back.invert();
+        }
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// Remove the parts of `polygons` inside this tree
+    fn clip_polygons(&self, polygons: Vec<Polygon>) -> Vec<Polygon> {
+        let Some(plane) = self.plane else {
+            return polygons;
+        };
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        for polygon in polygons {
+            let mut coplanar_front = Vec::new();
+            let mut coplanar_back = Vec::new();
+            plane.split(polygon, &mut coplanar_front, &mut coplanar_back, &mut front, &mut back);
+            front.append(&mut coplanar_front);
+            back.append(&mut coplanar_back);
+        }
+        let mut front = match &self.front {
+            Some(node) => node.clip_polygons(front),
+            None => front,
+        };
+        let back = match &self.back {
+            Some(node) => node.clip_polygons(back),
+            None => Vec::new(),
+        };
+        front.extend(back);
+        front
+    }
+
+    /// Remove the parts of this tree's polygons inside `other`
+    fn clip_to(&mut self, other: &Node) {
+        self.polygons = other.clip_polygons(std::mem::take(&mut self.polygons));
+        if let Some(front) = &mut self.front {
+            front.clip_to(other);
+        }
+        if let Some(back) = &mut self.back {
+            back.clip_to(other);
+        }
+    }
+
+    fn all_polygons(&self) -> Vec<Polygon> {
+        let mut polygons = self.polygons.clone();
+        if let Some(front) = &self.front {
+            polygons.extend(front.all_polygons());
+        }
+        if let Some(back) = &self.back {
+            polygons.extend(back.all_polygons());
+        }
+        polygons
+    }
+
+    fn build(&mut self, polygons: Vec<Polygon>) {
+        if polygons.is_empty() {
+            return;
+        }
+        let plane = *self.plane.get_or_insert(polygons[0].plane);
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        for polygon in polygons {
+            let mut coplanar_front = Vec::new();
+            let mut coplanar_back = Vec::new();
+            plane.split(polygon, &mut coplanar_front, &mut coplanar_back, &mut front, &mut back);
+            self.polygons.append(&mut coplanar_front);
+            self.polygons.append(&mut coplanar_back);
+        }
+        if !front.is_empty() {
+            self.front.get_or_insert_with(Default::default).build(front);
+        }
+        if !back.is_empty() {
+            self.back.get_or_insert_with(Default::default).build(back);
+        }
+    }
+}
+
+/// Tessellate a solid into outward-facing triangles
+fn polygons_of(solid: &Solid) -> Result<Vec<Polygon>> {
+    let mesh = solid.triangulation(TESSELLATION_TOLERANCE).to_polygon();
+    let positions: Vec<Vec3> = mesh.positions().iter().map(|p| [p.x, p.y, p.z]).collect();
+
+    let mut polygons = Vec::new();
+    for face in mesh.face_iter() {
+        for i in 1..face.len().saturating_sub(1) {
+            let tri = vec![positions[face[0].pos], positions[face[i].pos], positions[face[i + 1].pos]];
+            polygons.extend(Polygon::new(tri));
+        }
+    }
+    if polygons.is_empty() {
+        return Err(anyhow!("Solid has no faces"));
+    }
+
+    // Faces may come back inside out; a closed surface facing outward
+    // encloses a positive volume
+    let volume: f64 = polygons.iter()
+        .map(|p| dot(p.vertices[0], cross(p.vertices[1], p.vertices[2])))
+        .sum();
+    if volume < 0.0 {
+        for polygon in &mut polygons {
+            polygon.flip();
+        }
+    }
+    Ok(polygons)
+}
+
+/// Weld polygons back into a closed solid, one shell per connected piece
+fn solid_from_polygons(polygons: Vec<Polygon>) -> Result<Solid> {
+    // Merge coincident vertices
+    let mut points: Vec<Vec3> = Vec::new();
+    let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    let cell = |p: Vec3| p.map(|c| (c / (EPSILON * 10.0)).floor() as i64);
+    let mut weld = |p: Vec3| -> usize {
+        let c = cell(p);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if let Some(ids) = grid.get(&[c[0] + dx, c[1] + dy, c[2] + dz]) {
+                        if let Some(&id) = ids.iter().find(|&&id| {
+                            let d = sub(points[id], p);
+                            dot(d, d) < EPSILON * EPSILON * 100.0
+                        }) {
+                            return id;
+                        }
+                    }
+                }
+            }
+        }
+        points.push(p);
+        grid.entry(c).or_default().push(points.len() - 1);
+        points.len() - 1
+    };
+
+    let mut faces: Vec<Vec<usize>> = Vec::new();
+    for polygon in &polygons {
+        let mut face: Vec<usize> = Vec::new();
+        for v in &polygon.vertices {
+            let id = weld(*v);
+            if face.last() != Some(&id) {
+                face.push(id);
+            }
+        }
+        while face.len() > 1 && face.first() == face.last() {
+            face.pop();
+        }
+        if face.len() >= 3 {
+            faces.push(face);
+        }
+    }
+    let faces: Vec<Vec<usize>> = faces
+        .into_iter()
+        .filter(|f| face_area(&points, f) > EPSILON * EPSILON)
+        .collect();
+    if faces.is_empty() {
+        return Err(anyhow!("Boolean operation produced an empty solid"));
+    }
+
+    // Split edges at vertices of neighbouring faces lying on them, so every
+    // edge is shared by exactly two faces
+    let faces: Vec<Vec<usize>> = faces
+        .iter()
+        .map(|face| {
+            let mut split = Vec::with_capacity(face.len());
+            for (i, &a) in face.iter().enumerate() {
+                let b = face[(i + 1) % face.len()];
+                split.push(a);
+                let (pa, pb) = (points[a], points[b]);
+                let ab = sub(pb, pa);
+                let len2 = dot(ab, ab);
+                let mut on_edge: Vec<(f64, usize)> = (0..points.len())
+                    .filter(|&v| v != a && v != b)
+                    .filter_map(|v| {
+                        let t = dot(sub(points[v], pa), ab) / len2;
+                        if t <= 0.0 || t >= 1.0 {
+                            return None;
+                        }
+                        let d = sub(points[v], lerp(pa, pb, t));
+                        (dot(d, d) < EPSILON * EPSILON * 100.0).then_some((t, v))
+                    })
+                    .collect();
+                on_edge.sort_by(|x, y| x.0.total_cmp(&y.0));
+                split.extend(on_edge.into_iter().map(|(_, v)| v));
+            }
+            split
+        })
+        .collect();
+
+    // Group faces into connected pieces
+    let mut parent: Vec<usize> = (0..faces.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+    let mut owner: HashMap<usize, usize> = HashMap::new();
+    for (i, face) in faces.iter().enumerate() {
+        for v in face {
+            if let Some(&j) = owner.get(v) {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                parent[ri] = rj;
+            } else {
+                owner.insert(*v, i);
+            }
+        }
+    }
+    let mut pieces: HashMap<usize, Vec<Vec<usize>>> = HashMap::new();
+    for (i, face) in faces.into_iter().enumerate() {
+        let root = find(&mut parent, i);
+        pieces.entry(root).or_default().push(face);
+    }
+
+    let truck_points: Vec<TruckPoint3> = points.iter().map(|p| TruckPoint3::new(p[0], p[1], p[2])).collect();
+    let shells = pieces
+        .into_values()
+        .map(|faces| polyhedron_shell(&truck_points, &faces))
+        .collect::<Result<Vec<Shell>>>()?;
+    Solid::try_new(shells).map_err(|e| anyhow!("Boolean result is not a closed solid: {:?}", e))
+}
+
+/// Area of a planar face given as indices into `points`
+fn face_area(points: &[Vec3], face: &[usize]) -> f64 {
+    let origin = points[face[0]];
+    let mut n = [0.0; 3];
+    for i in 1..face.len() - 1 {
+        let c = cross(sub(points[face[i]], origin), sub(points[face[i + 1]], origin));
+        n = [n[0] + c[0], n[1] + c[1], n[2] + c[2]];
+    }
+    dot(n, n).sqrt() / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Point3, Polygon2};
+    use crate::geometry::{create_box, extrude_polygon, solid_to_mesh};
+
+    fn volume(solid: &Solid) -> f64 {
+        solid_to_mesh(solid, 0.01).unwrap().volume()
+    }
+
+    fn cube(x: f64, y: f64, z: f64, size: f64) -> Solid {
+        create_box(size, size, size, &Point3::new(x, y, z)).unwrap()
+    }
+
+    #[test]
+    fn test_overlapping_cubes() {
+        let a = cube(0.0, 0.0, 0.0, 2.0);
+        let b = cube(1.0, 1.0, 1.0, 2.0);
+        assert!((volume(&a) - 8.0).abs() < 1e-3);
+
+        assert!((volume(&union_solids(&a, &b).unwrap()) - 15.0).abs() < 1e-3);
+        assert!((volume(&subtract_solid(&a, &b).unwrap()) - 7.0).abs() < 1e-3);
+        assert!((volume(&intersect_solids(&a, &b).unwrap()) - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_disjoint_and_empty_results() {
+        let a = cube(0.0, 0.0, 0.0, 1.0);
+        let b = cube(5.0, 0.0, 0.0, 1.0);
+        let both = union_solids(&a, &b).unwrap();
+        assert_eq!(both.boundaries().len(), 2);
+        assert!((volume(&both) - 2.0).abs() < 1e-3);
+        assert!(intersect_solids(&a, &b).is_err());
+    }
+
+    #[test]
+    fn test_cut_opening_through_wall() {
+        // 10' x 6" x 8' wall with a 3' x 4' window at a 3' sill
+        let wall = create_box(10.0, 0.5, 8.0, &Point3::new(0.0, 0.0, 0.0)).unwrap();
+        let window = create_box(3.0, 1.5, 4.0, &Point3::new(3.5, -0.5, 3.0)).unwrap();
+        let cut = subtract_solid(&wall, &window).unwrap();
+        assert!((volume(&cut) - (40.0 - 12.0 * 0.5)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_merged_wings() {
+        // Two overlapping wings form an L-shaped massing
+        let main = extrude_polygon(&Polygon2::rectangle(40.0, 20.0), 0.0, 9.0).unwrap();
+        let wing = extrude_polygon(
+            &Polygon2::new(vec![
+                crate::domain::Point2::new(30.0, 10.0),
+                crate::domain::Point2::new(40.0, 10.0),
+                crate::domain::Point2::new(40.0, 40.0),
+                crate::domain::Point2::new(30.0, 40.0),
+            ]),
+            0.0,
+            9.0,
+        )
+        .unwrap();
+        let merged = union_solids(&main, &wing).unwrap();
+        assert_eq!(merged.boundaries().len(), 1);
+        assert!((volume(&merged) - (800.0 + 200.0) * 9.0).abs() < 1e-2);
+    }
+}
@@ -0,0 +1,118 @@
+// Level of detail for viewer meshes
+// A multi-level model meshed at full detail can run to hundreds of
+// thousands of triangles. Lower qualities tessellate curved faces more
+// coarsely; the lowest also decimates each mesh and leaves framing members
+// out, for levels far from the camera.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use truck_modeling::Solid;
+
+use super::{solid_to_mesh, MeshData};
+
+/// How much detail to mesh with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeshQuality {
+    #[default]
+    High,
+    Medium,
+    Low,
+}
+
+impl MeshQuality {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "high" | "full" => Some(Self::High),
+            "medium" | "med" => Some(Self::Medium),
+            "low" | "preview" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    /// Tessellation tolerance for a part meshed at `base` in full detail
+    pub fn tolerance(&self, base: f64) -> f64 {
+        match self {
+            Self::High => base,
+            Self::Medium => base * 2.5,
+            Self::Low => base * 5.0,
+        }
+    }
+
+    /// Grid cell (feet) of the decimation pass, None to skip it
+    pub fn decimation_cell(&self) -> Option<f64> {
+        match self {
+            Self::Low => Some(0.25),
+            _ => None,
+        }
+    }
+
+    /// Whether studs, plates, joists and other framing members are meshed
+    pub fn includes_framing(&self) -> bool {
+        *self != Self::Low
+    }
+
+    /// The quality `steps` levels coarser, stopping at Low
+    pub fn coarsened(&self, steps: usize) -> Self {
+        match (*self, steps) {
+            (quality, 0) => quality,
+            (Self::High, 1) => Self::Medium,
+            _ => Self::Low,
+        }
+    }
+
+    /// Mesh a solid at this quality; `base_tolerance` is the tolerance the
+    /// part is tessellated at in full detail
+    pub fn mesh(&self, solid: &Solid, base_tolerance: f64) -> Result<MeshData> {
+        let mesh = solid_to_mesh(solid, self.tolerance(base_tolerance))?;
+        Ok(self.simplify(mesh))
+    }
+
+    /// Apply the decimation pass, if this quality has one
+    pub fn simplify(&self, mesh: MeshData) -> MeshData {
+        match self.decimation_cell() {
+            Some(cell) => mesh.decimated(cell),
+            None => mesh,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Polygon2;
+    use crate::geometry::extrude_polygon;
+
+    #[test]
+    fn test_mesh_quality() {
+        assert_eq!(MeshQuality::parse("LOW"), Some(MeshQuality::Low));
+        assert_eq!(MeshQuality::parse("ultra"), None);
+        assert_eq!(MeshQuality::High.coarsened(1), MeshQuality::Medium);
+        assert_eq!(MeshQuality::Medium.coarsened(1), MeshQuality::Low);
+        assert_eq!(MeshQuality::High.coarsened(3), MeshQuality::Low);
+        assert_eq!(MeshQuality::Medium.coarsened(0), MeshQuality::Medium);
+
+        // A round column: fewer triangles as quality drops
+        let circle = Polygon2::new(
+            (0..64)
+                .map(|i| {
+                    let a = i as f64 / 64.0 * std::f64::consts::TAU;
+                    crate::domain::Point2::new(a.cos(), a.sin())
+                })
+                .collect(),
+        );
+        let solid = extrude_polygon(&circle, 0.0, 9.0).unwrap();
+        let high = MeshQuality::High.mesh(&solid, 0.1).unwrap();
+        let low = MeshQuality::Low.mesh(&solid, 0.1).unwrap();
+        assert!(low.triangle_count() < high.triangle_count());
+        assert!(low.triangle_count() > 0);
+    }
+}
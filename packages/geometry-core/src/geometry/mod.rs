@@ -1,19 +1,38 @@
 // Geometry operations using Truck B-Rep kernel
 // Provides solid generation, meshing, and collision detection utilities
 
+pub mod bvh;
+pub mod csg;
+pub mod lod;
+pub mod planar_graph;
 pub mod polygon_ops;
+pub mod roof;
+pub mod stair;
+pub mod structural;
+pub mod wall;
+pub mod wall_joins;
 
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 
-use truck_modeling::{builder, Solid, Vertex, Wire};
+use truck_modeling::{builder, Edge, Shell, Solid, Vertex, Wire};
 use truck_modeling::Point3 as TruckPoint3;
 use truck_modeling::Vector3 as TruckVector3;
 use truck_meshalgo::prelude::*;
 
 use crate::domain::{Polygon2, SolidId, Level, Footprint};
-use polygon_ops::offset_polygon;
+use polygon_ops::{offset_polygon_with, OffsetOptions};
+
+pub use bvh::{RayHit, TriangleBvh};
+pub use csg::{intersect_solids, subtract_solid, union_solids};
+pub use lod::MeshQuality;
+pub use planar_graph::{GraphEdge, GraphFace, PlanarGraph};
+pub use roof::generate_roof_solid;
+pub use stair::{generate_stair_solids, StairSolids};
+pub use structural::{generate_beam_solid, generate_post_solid};
+pub use wall::generate_wall_solid;
+pub use wall_joins::{WallEndCuts, WallNetwork};
 
 /// Cache for generated Truck solids
 pub type SolidCache = Arc<RwLock<HashMap<SolidId, Solid>>>;
@@ -23,8 +42,14 @@ pub fn new_solid_cache() -> SolidCache {
     Arc::new(RwLock::new(HashMap::new()))
 }
 
+/// Leading bytes of a packed mesh
+const PACKED_MESH_MAGIC: &[u8; 4] = b"GMSH";
+const PACKED_MESH_VERSION: u32 = 1;
+/// Magic, version, then the lengths of the three arrays
+const PACKED_MESH_HEADER: usize = 20;
+
 /// Mesh data ready for frontend rendering (Three.js compatible)
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct MeshData {
     pub positions: Vec<f32>,   // flattened [x, y, z, x, y, z, ...]
     pub normals: Vec<f32>,     // flattened [nx, ny, nz, ...]
@@ -39,6 +64,246 @@ impl MeshData {
     pub fn triangle_count(&self) -> usize {
         self.indices.len() / 3
     }
+
+    /// Add another mesh's triangles to this one
+    pub fn append(&mut self, other: MeshData) {
+        let base = self.vertex_count() as u32;
+        self.positions.extend(other.positions);
+        self.normals.extend(other.normals);
+        self.indices.extend(other.indices.into_iter().map(|i| i + base));
+    }
+
+    /// Enclosed volume of a closed mesh with outward-facing triangles
+    pub fn volume(&self) -> f64 {
+        let point = |i: u32| {
+            let i = i as usize * 3;
+            [
+                self.positions[i] as f64,
+                self.positions[i + 1] as f64,
+                self.positions[i + 2] as f64,
+            ]
+        };
+        self.indices
+            .chunks_exact(3)
+            .map(|tri| {
+                let (a, b, c) = (point(tri[0]), point(tri[1]), point(tri[2]));
+                a[0] * (b[1] * c[2] - b[2] * c[1])
+                    + a[1] * (b[2] * c[0] - b[0] * c[2])
+                    + a[2] * (b[0] * c[1] - b[1] * c[0])
+            })
+            .sum::<f64>()
+            / 6.0
+    }
+
+    /// Merge vertices closer than `tolerance`, dropping triangles that
+    /// collapse. Normals are recomputed since merged vertices no longer
+    /// share one.
+    pub fn welded(&self, tolerance: f64) -> MeshData {
+        let cell = tolerance.max(1e-9);
+        let mut lookup: HashMap<(i64, i64, i64), u32> = HashMap::new();
+        let mut remap = Vec::with_capacity(self.vertex_count());
+        let mut positions = Vec::new();
+        for p in self.positions.chunks_exact(3) {
+            let key = (
+                (p[0] as f64 / cell).round() as i64,
+                (p[1] as f64 / cell).round() as i64,
+                (p[2] as f64 / cell).round() as i64,
+            );
+            let index = *lookup.entry(key).or_insert_with(|| {
+                positions.extend_from_slice(p);
+                (positions.len() / 3 - 1) as u32
+            });
+            remap.push(index);
+        }
+
+        let indices = self.indices
+            .chunks_exact(3)
+            .map(|tri| [remap[tri[0] as usize], remap[tri[1] as usize], remap[tri[2] as usize]])
+            .filter(|[a, b, c]| a != b && b != c && a != c)
+            .flatten()
+            .collect();
+        let mut mesh = MeshData { positions, normals: Vec::new(), indices };
+        mesh.recompute_normals();
+        mesh
+    }
+
+    /// Vertex-clustering simplification for meshes seen from afar. Corners
+    /// within the same `cell` (feet) on faces facing about the same way are
+    /// merged, so detail smaller than a cell collapses while hard edges
+    /// keep their flat normals. Triangles that collapse are dropped.
+    pub fn decimated(&self, cell: f64) -> MeshData {
+        let cell = cell.max(1e-9);
+        let mut lookup: HashMap<[i64; 6], u32> = HashMap::new();
+        let mut mesh = MeshData::default();
+        for tri in self.indices.chunks_exact(3) {
+            let n = self.face_normal(tri);
+            let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            if len < 1e-12 {
+                continue;
+            }
+            let n = [n[0] / len, n[1] / len, n[2] / len];
+            let mut corners = [0u32; 3];
+            for (corner, &i) in corners.iter_mut().zip(tri) {
+                let p = &self.positions[i as usize * 3..i as usize * 3 + 3];
+                // Normals are bucketed about 15 degrees apart
+                let key = [
+                    (p[0] as f64 / cell).round() as i64,
+                    (p[1] as f64 / cell).round() as i64,
+                    (p[2] as f64 / cell).round() as i64,
+                    (n[0] * 4.0).round() as i64,
+                    (n[1] * 4.0).round() as i64,
+                    (n[2] * 4.0).round() as i64,
+                ];
+                *corner = *lookup.entry(key).or_insert_with(|| {
+                    mesh.positions.extend_from_slice(p);
+                    mesh.normals.extend(n.map(|c| c as f32));
+                    (mesh.positions.len() / 3 - 1) as u32
+                });
+            }
+            let [a, b, c] = corners;
+            if a != b && b != c && a != c {
+                mesh.indices.extend(corners);
+            }
+        }
+        mesh
+    }
+
+    /// Replace the normals with area-weighted averages of the normals of
+    /// the triangles around each vertex
+    pub fn recompute_normals(&mut self) {
+        let mut sums = vec![[0.0f64; 3]; self.vertex_count()];
+        for tri in self.indices.chunks_exact(3) {
+            let n = self.face_normal(tri);
+            for &i in tri {
+                for axis in 0..3 {
+                    sums[i as usize][axis] += n[axis];
+                }
+            }
+        }
+        self.normals = sums
+            .iter()
+            .flat_map(|n| {
+                let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+                if len > 1e-12 {
+                    [(n[0] / len) as f32, (n[1] / len) as f32, (n[2] / len) as f32]
+                } else {
+                    [0.0, 0.0, 1.0]
+                }
+            })
+            .collect();
+    }
+
+    /// Triangle normal scaled by twice its area
+    fn face_normal(&self, tri: &[u32]) -> [f64; 3] {
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            [self.positions[i] as f64, self.positions[i + 1] as f64, self.positions[i + 2] as f64]
+        };
+        let (a, b, c) = (p(tri[0]), p(tri[1]), p(tri[2]));
+        let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+        [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]]
+    }
+
+    /// Wavefront OBJ text; vertex normals are written when there is one
+    /// per vertex
+    pub fn to_obj(&self) -> String {
+        use std::fmt::Write;
+
+        let mut obj = String::new();
+        for p in self.positions.chunks_exact(3) {
+            let _ = writeln!(obj, "v {} {} {}", p[0], p[1], p[2]);
+        }
+        let with_normals = self.normals.len() == self.positions.len();
+        if with_normals {
+            for n in self.normals.chunks_exact(3) {
+                let _ = writeln!(obj, "vn {} {} {}", n[0], n[1], n[2]);
+            }
+        }
+        for tri in self.indices.chunks_exact(3) {
+            let (a, b, c) = (tri[0] + 1, tri[1] + 1, tri[2] + 1);
+            if with_normals {
+                let _ = writeln!(obj, "f {a}//{a} {b}//{b} {c}//{c}");
+            } else {
+                let _ = writeln!(obj, "f {a} {b} {c}");
+            }
+        }
+        obj
+    }
+
+    /// Binary STL with a facet normal computed for each triangle
+    pub fn to_stl(&self) -> Vec<u8> {
+        let mut stl = Vec::with_capacity(84 + self.triangle_count() * 50);
+        let mut header = [0u8; 80];
+        let label = b"geometry-core mesh";
+        header[..label.len()].copy_from_slice(label);
+        stl.extend_from_slice(&header);
+        stl.extend((self.triangle_count() as u32).to_le_bytes());
+        for tri in self.indices.chunks_exact(3) {
+            let n = self.face_normal(tri);
+            let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt().max(1e-12);
+            for value in n {
+                stl.extend(((value / len) as f32).to_le_bytes());
+            }
+            for &i in tri {
+                let i = i as usize * 3;
+                for value in &self.positions[i..i + 3] {
+                    stl.extend(value.to_le_bytes());
+                }
+            }
+            stl.extend(0u16.to_le_bytes());
+        }
+        stl
+    }
+
+    /// Compact binary form for sending over the wire: a 20-byte header
+    /// ("GMSH", version, then the position, normal and index counts as u32s)
+    /// followed by the positions and normals as f32s and the indices as
+    /// u32s, all little-endian. Each array starts 4-byte aligned, so a
+    /// client can view it as a typed array without copying.
+    pub fn to_packed(&self) -> Vec<u8> {
+        let len = self.positions.len() + self.normals.len() + self.indices.len();
+        let mut bytes = Vec::with_capacity(PACKED_MESH_HEADER + 4 * len);
+        bytes.extend_from_slice(PACKED_MESH_MAGIC);
+        bytes.extend(PACKED_MESH_VERSION.to_le_bytes());
+        for count in [self.positions.len(), self.normals.len(), self.indices.len()] {
+            bytes.extend((count as u32).to_le_bytes());
+        }
+        for value in self.positions.iter().chain(&self.normals) {
+            bytes.extend(value.to_le_bytes());
+        }
+        for index in &self.indices {
+            bytes.extend(index.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Read a mesh written by `to_packed`
+    pub fn from_packed(bytes: &[u8]) -> Result<Self> {
+        let word = |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        if bytes.len() < PACKED_MESH_HEADER || &bytes[..4] != PACKED_MESH_MAGIC {
+            return Err(anyhow!("Not a packed mesh"));
+        }
+        if word(4) != PACKED_MESH_VERSION {
+            return Err(anyhow!("Unsupported packed mesh version: {}", word(4)));
+        }
+        let counts = [word(8) as usize, word(12) as usize, word(16) as usize];
+        // Summed as u64 so a corrupt header can't overflow on 32-bit targets
+        let expected = PACKED_MESH_HEADER as u64 + 4 * counts.iter().map(|&c| c as u64).sum::<u64>();
+        if bytes.len() as u64 != expected {
+            return Err(anyhow!("Packed mesh length doesn't match its header"));
+        }
+
+        let mut offset = PACKED_MESH_HEADER;
+        let mut words = |count: usize| {
+            let start = offset;
+            offset += 4 * count;
+            (start..offset).step_by(4).map(word).collect::<Vec<u32>>()
+        };
+        let positions = words(counts[0]).into_iter().map(f32::from_bits).collect();
+        let normals = words(counts[1]).into_iter().map(f32::from_bits).collect();
+        let indices = words(counts[2]);
+        Ok(Self { positions, normals, indices })
+    }
 }
 
 /// Create a wire from a sequence of 2D points at a given Z elevation
@@ -55,7 +320,7 @@ fn points_to_wire(points: &[crate::domain::Point2], z: f64) -> Wire {
         edges.push(edge);
     }
 
-    Wire::from_iter(edges.into_iter())
+    Wire::from_iter(edges)
 }
 
 /// Extrude a 2D polygon into a 3D solid (for massing/footprint visualization)
@@ -98,6 +363,8 @@ pub fn extrude_polygon(polygon: &Polygon2, base_z: f64, height: f64) -> Result<S
 /// 2. Creating inner boundary by offsetting inward by wall_thickness
 /// 3. Extruding the ring between them
 ///
+/// Fails if the walls would fill the whole polygon.
+///
 /// # Arguments
 /// * `polygon` - The outer boundary polygon
 /// * `base_z` - Base elevation
@@ -124,60 +391,37 @@ pub fn extrude_polygon_shell(
         return Err(anyhow!("Wall thickness must be positive"));
     }
 
-    // Calculate minimum dimension of the polygon to validate wall thickness
-    // Use a simple bounding box approximation
-    let min_x = polygon.outer.iter().map(|p| p.x).fold(f64::MAX, f64::min);
-    let max_x = polygon.outer.iter().map(|p| p.x).fold(f64::MIN, f64::max);
-    let min_y = polygon.outer.iter().map(|p| p.y).fold(f64::MAX, f64::min);
-    let max_y = polygon.outer.iter().map(|p| p.y).fold(f64::MIN, f64::max);
-    let min_dimension = (max_x - min_x).min(max_y - min_y);
-
-    // Wall thickness must be less than half the minimum dimension
-    // otherwise the inner walls would overlap
-    if wall_thickness >= min_dimension / 2.0 {
+    // Inner faces of the walls; a thin neck may split the interior into
+    // several rooms, and footprint holes grow by the wall thickness
+    let inset = offset_polygon_with(polygon, -wall_thickness, &OffsetOptions::default())?;
+    if inset.is_empty() {
         return Err(anyhow!(
-            "Wall thickness ({:.3}) must be less than half the minimum polygon dimension ({:.3}). \
-             Maximum allowed: {:.3}",
-            wall_thickness,
-            min_dimension,
-            min_dimension / 2.0 - 0.001
+            "Wall thickness ({:.3}) leaves no interior inside the polygon",
+            wall_thickness
         ));
     }
 
-    // Create inner boundary by offsetting inward
-    // Note: offset_polygon has inverted normal direction, so positive distance = inward
-    // for counterclockwise polygons. We use positive wall_thickness here.
-    let inner_result = offset_polygon(polygon, wall_thickness);
-
-    match inner_result {
-        Ok(inner_polygon) => {
-            // Validate that the inner polygon is usable
-            if inner_polygon.outer.len() < 3 || !inner_polygon.is_valid() {
-                // Fall back to solid extrusion if inner polygon is degenerate
-                eprintln!(
-                    "[geometry-core] Warning: Inner polygon degenerate after offset, falling back to solid extrusion"
-                );
-                return extrude_polygon(polygon, base_z, height);
-            }
-
-            // Create shell polygon: outer boundary with inner as a hole
-            let shell_polygon = Polygon2::with_holes(
-                polygon.outer.clone(),
-                vec![inner_polygon.outer],
-            );
-
-            // Extrude the shell polygon (Truck handles holes via multiple wires)
-            extrude_polygon(&shell_polygon, base_z, height)
-        }
-        Err(e) => {
-            // Fall back to solid extrusion with warning
-            eprintln!(
-                "[geometry-core] Warning: Failed to create inner offset for shell: {}. Falling back to solid extrusion.",
-                e
-            );
-            extrude_polygon(polygon, base_z, height)
+    // The wall band is the footprint minus the inset: inset outlines become
+    // holes, and inset holes become outlines around the footprint's holes
+    let oriented = |ring: &[crate::domain::Point2], counterclockwise: bool| {
+        let mut ring = ring.to_vec();
+        if (Polygon2::new(ring.clone()).signed_area() > 0.0) != counterclockwise {
+            ring.reverse();
         }
+        ring
+    };
+    let mut rings = vec![oriented(&polygon.outer, true)];
+    rings.extend(polygon.holes.iter().map(|hole| oriented(hole, false)));
+    for piece in &inset {
+        rings.push(oriented(&piece.outer, false));
+        rings.extend(piece.holes.iter().map(|hole| oriented(hole, true)));
+    }
+
+    let mut shells = Vec::new();
+    for band in polygon_ops::assemble_polygons(rings) {
+        shells.extend(extrude_polygon(&band, base_z, height)?.into_boundaries());
     }
+    Solid::try_new(shells).map_err(|e| anyhow!("Failed to assemble shell walls: {:?}", e))
 }
 
 /// Create a simple box solid
@@ -196,7 +440,7 @@ pub fn create_box(width: f64, depth: f64, height: f64, origin: &crate::domain::P
     let edge2 = builder::line(&v2, &v3);
     let edge3 = builder::line(&v3, &v0);
 
-    let wire = Wire::from_iter(vec![edge0, edge1, edge2, edge3].into_iter());
+    let wire = Wire::from_iter(vec![edge0, edge1, edge2, edge3]);
     let bottom = builder::try_attach_plane(&[wire])
         .map_err(|e| anyhow!("Failed to create base face: {:?}", e))?;
 
@@ -204,6 +448,70 @@ pub fn create_box(width: f64, depth: f64, height: f64, origin: &crate::domain::P
     Ok(solid)
 }
 
+/// Create a box turned in plan: it runs `length` from the origin along
+/// `rotation` (radians from +x), `width` to the left of that, and `height` up
+pub fn create_oriented_box(
+    length: f64,
+    width: f64,
+    height: f64,
+    origin: &crate::domain::Point3,
+    rotation: f64,
+) -> Result<Solid> {
+    if length <= 0.0 || width <= 0.0 || height <= 0.0 {
+        return Err(anyhow!("All dimensions must be positive"));
+    }
+
+    let (along, left) = (
+        TruckVector3::new(rotation.cos(), rotation.sin(), 0.0),
+        TruckVector3::new(-rotation.sin(), rotation.cos(), 0.0),
+    );
+    let p0 = TruckPoint3::new(origin.x, origin.y, origin.z);
+    let corners = [p0, p0 + along * length, p0 + along * length + left * width, p0 + left * width];
+    let vertices: Vec<Vertex> = corners.iter().map(|p| builder::vertex(*p)).collect();
+
+    let wire = Wire::from_iter((0..4).map(|i| builder::line(&vertices[i], &vertices[(i + 1) % 4])));
+    let bottom = builder::try_attach_plane(&[wire])
+        .map_err(|e| anyhow!("Failed to create base face: {:?}", e))?;
+
+    Ok(builder::tsweep(&bottom, TruckVector3::new(0.0, 0.0, height)))
+}
+
+/// Build a closed solid from planar faces given as counterclockwise vertex
+/// loops. Each edge is created once and shared by its two faces.
+pub(crate) fn polyhedron(points: &[TruckPoint3], faces: &[Vec<usize>]) -> Result<Solid> {
+    Solid::try_new(vec![polyhedron_shell(points, faces)?])
+        .map_err(|e| anyhow!("Faces do not close a solid: {:?}", e))
+}
+
+/// The shell of a polyhedron, for solids made of several pieces
+pub(crate) fn polyhedron_shell(points: &[TruckPoint3], faces: &[Vec<usize>]) -> Result<Shell> {
+    let vertices: Vec<Vertex> = points.iter().map(|p| builder::vertex(*p)).collect();
+    let mut edges: HashMap<(usize, usize), Edge> = HashMap::new();
+
+    let mut shell_faces = Vec::with_capacity(faces.len());
+    for face in faces {
+        let mut wire = Wire::new();
+        for (i, &a) in face.iter().enumerate() {
+            let b = face[(i + 1) % face.len()];
+            let edge = if a < b {
+                edges.entry((a, b))
+                    .or_insert_with(|| builder::line(&vertices[a], &vertices[b]))
+                    .clone()
+            } else {
+                edges.entry((b, a))
+                    .or_insert_with(|| builder::line(&vertices[b], &vertices[a]))
+                    .inverse()
+            };
+            wire.push_back(edge);
+        }
+        let face = builder::try_attach_plane(&[wire])
+            .map_err(|e| anyhow!("Failed to create face: {:?}", e))?;
+        shell_faces.push(face);
+    }
+
+    Ok(Shell::from(shell_faces))
+}
+
 /// Convert a Truck Solid to mesh data for frontend rendering
 pub fn solid_to_mesh(solid: &Solid, tolerance: f64) -> Result<MeshData> {
     // Tessellate the solid
@@ -333,6 +641,14 @@ pub fn generate_footprint_massing(
     Ok(solid_id)
 }
 
+/// Evict cached solids not in `live`, returning how many were dropped
+pub fn prune_solid_cache(cache: &SolidCache, live: &HashSet<SolidId>) -> usize {
+    let mut cache_write = cache.write().unwrap();
+    let before = cache_write.len();
+    cache_write.retain(|id, _| live.contains(id));
+    before - cache_write.len()
+}
+
 /// Get mesh for a solid ID from cache
 pub fn get_mesh_for_solid(
     solid_id: SolidId,
@@ -379,7 +695,7 @@ pub fn validate_polygon_geometry(polygon: &Polygon2) -> Result<()> {
         edges.push(edge);
     }
 
-    let wire = Wire::from_iter(edges.into_iter());
+    let wire = Wire::from_iter(edges);
 
     // Try to create a face - this will fail for invalid geometry
     builder::try_attach_plane(&[wire])
@@ -415,6 +731,18 @@ mod tests {
         assert!((bbox.size()[2] - 15.0).abs() < 0.1);
     }
 
+    #[test]
+    fn test_create_oriented_box() {
+        // Turned a quarter: the length runs up y and the width toward -x
+        let origin = Point3::new(1.0, 2.0, 0.0);
+        let solid = create_oriented_box(10.0, 2.0, 3.0, &origin, std::f64::consts::FRAC_PI_2).unwrap();
+
+        let bbox = BoundingBox::from_solid(&solid);
+        assert!((bbox.size()[0] - 2.0).abs() < 0.1);
+        assert!((bbox.size()[1] - 10.0).abs() < 0.1);
+        assert!((bbox.min[0] + 1.0).abs() < 0.1);
+    }
+
     #[test]
     fn test_solid_to_mesh() {
         let polygon = Polygon2::rectangle(10.0, 10.0);
@@ -426,6 +754,66 @@ mod tests {
         assert_eq!(mesh.positions.len(), mesh.normals.len());
     }
 
+    #[test]
+    fn test_mesh_weld_obj_stl() {
+        let solid = create_box(2.0, 3.0, 4.0, &crate::domain::Point3::new(0.0, 0.0, 0.0)).unwrap();
+        let mesh = solid_to_mesh(&solid, 0.1).unwrap();
+
+        // A box welds down to its eight corners without losing volume
+        let welded = mesh.welded(1e-4);
+        assert_eq!(welded.vertex_count(), 8);
+        assert_eq!(welded.triangle_count(), 12);
+        assert_eq!(welded.normals.len(), welded.positions.len());
+        assert!((welded.volume() - 24.0).abs() < 1e-6);
+
+        let obj = welded.to_obj();
+        assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), 8);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("vn ")).count(), 8);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), 12);
+        assert!(obj.contains("//"));
+
+        let stl = welded.to_stl();
+        assert_eq!(stl.len(), 84 + 12 * 50);
+        assert_eq!(u32::from_le_bytes(stl[80..84].try_into().unwrap()), 12);
+    }
+
+    #[test]
+    fn test_mesh_decimated() {
+        // A 0.1' deep panel in a 0.5' grid: its four narrow sides collapse,
+        // leaving the two broad faces, which never merge as they face apart
+        let origin = crate::domain::Point3::new(0.0, 0.0, 0.0);
+        let thin = solid_to_mesh(&create_box(4.0, 0.1, 4.0, &origin).unwrap(), 0.1).unwrap();
+        assert_eq!(thin.decimated(0.5).triangle_count(), 4);
+
+        // A 4' box has nothing to lose
+
+        let mesh = solid_to_mesh(&create_box(4.0, 4.0, 4.0, &origin).unwrap(), 0.1).unwrap();
+        let decimated = mesh.decimated(0.5);
+        assert_eq!(decimated.triangle_count(), 12);
+        // Four corners per face, each face's own normal
+        assert_eq!(decimated.vertex_count(), 24);
+        assert_eq!(decimated.normals.len(), decimated.positions.len());
+        assert!((decimated.volume() - 64.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mesh_packed_round_trip() {
+        let solid = create_box(2.0, 3.0, 4.0, &crate::domain::Point3::new(0.0, 0.0, 0.0)).unwrap();
+        let mesh = solid_to_mesh(&solid, 0.1).unwrap().welded(1e-4);
+
+        let packed = mesh.to_packed();
+        // Eight corners with normals, twelve triangles
+        assert_eq!(packed.len(), 20 + 4 * (24 + 24 + 36));
+        let read = MeshData::from_packed(&packed).unwrap();
+        assert_eq!(read.positions, mesh.positions);
+        assert_eq!(read.normals, mesh.normals);
+        assert_eq!(read.indices, mesh.indices);
+
+        assert!(MeshData::from_packed(&packed[..packed.len() - 4]).is_err());
+        assert!(MeshData::from_packed(b"STL").is_err());
+        assert!(MeshData::from_packed(&MeshData::default().to_packed()).unwrap().indices.is_empty());
+    }
+
     #[test]
     fn test_extrude_polygon_shell() {
         // Create a 30x40 foot rectangle
@@ -479,6 +867,27 @@ mod tests {
         assert!(result.is_ok(), "Should succeed when wall thickness < half min dimension");
     }
 
+    #[test]
+    fn test_extrude_polygon_shell_around_courtyard() {
+        // 10x10 footprint around a 2x2 courtyard: one band of walls at the
+        // outside and another lining the courtyard
+        let courtyard = vec![
+            crate::domain::Point2::new(4.0, 4.0),
+            crate::domain::Point2::new(6.0, 4.0),
+            crate::domain::Point2::new(6.0, 6.0),
+            crate::domain::Point2::new(4.0, 6.0),
+        ];
+        let polygon = Polygon2::with_holes(Polygon2::rectangle(10.0, 10.0).outer, vec![courtyard]);
+
+        let shell = extrude_polygon_shell(&polygon, 0.0, 9.0, 1.0).unwrap();
+        assert_eq!(shell.boundaries().len(), 2);
+        let bbox = BoundingBox::from_solid(&shell);
+        assert!((bbox.size()[0] - 10.0).abs() < 0.1);
+
+        // Walls thick enough to meet the courtyard lining fill everything
+        assert!(extrude_polygon_shell(&polygon, 0.0, 9.0, 2.0).is_err());
+    }
+
     #[test]
     fn test_extrude_polygon_shell_invalid_params() {
         let polygon = Polygon2::rectangle(10.0, 10.0);
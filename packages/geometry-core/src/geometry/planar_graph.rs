@@ -0,0 +1,334 @@
+// Planar graphs of plan segments
+// Wall centerlines and room edges are treated as a planar graph: segments
+// are split wherever they cross or touch, ends within a tolerance are
+// welded, and the enclosed faces are traced. Rooms are derived from the
+// faces of the wall network; walls are derived from the edges rooms share.
+
+use crate::domain::{Point2, Polygon2};
+use super::polygon_ops::{remove_collinear, ring_area, segment_contacts, winding_number};
+
+/// An edge between two vertices, remembering which input segments it
+/// came from (overlapping segments share an edge)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphEdge {
+    pub start: usize,
+    pub end: usize,
+    pub sources: Vec<usize>,
+}
+
+/// An enclosed face, counterclockwise, with any islands inside it as
+/// holes, and the input segments along its boundary
+#[derive(Debug, Clone)]
+pub struct GraphFace {
+    pub polygon: Polygon2,
+    pub sources: Vec<usize>,
+}
+
+/// Segments split at every crossing, with welded vertices
+#[derive(Debug, Clone, Default)]
+pub struct PlanarGraph {
+    vertices: Vec<Point2>,
+    edges: Vec<GraphEdge>,
+}
+
+impl PlanarGraph {
+    /// Build a graph from segments. Ends within `tolerance` of each other,
+    /// or of another segment, are joined.
+    pub fn from_segments(segments: &[(Point2, Point2)], tolerance: f64) -> Self {
+        let mut cuts: Vec<Vec<(f64, Point2)>> = segments.iter().map(|&(a, b)| vec![(0.0, a), (1.0, b)]).collect();
+        for i in 0..segments.len() {
+            for j in (i + 1)..segments.len() {
+                for (ti, tj, p) in segment_contacts(&segments[i], &segments[j], tolerance) {
+                    cuts[i].push((ti, p));
+                    cuts[j].push((tj, p));
+                }
+            }
+        }
+
+        let mut graph = Self::default();
+        for (source, mut cut) in cuts.into_iter().enumerate() {
+            if segments[source].0.distance_to(&segments[source].1) <= tolerance {
+                continue;
+            }
+            cut.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let ids: Vec<usize> = cut.into_iter().map(|(_, p)| graph.vertex(p, tolerance)).collect();
+            for pair in ids.windows(2).filter(|w| w[0] != w[1]) {
+                let (start, end) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+                match graph.edges.iter_mut().find(|e| e.start == start && e.end == end) {
+                    Some(edge) if !edge.sources.contains(&source) => edge.sources.push(source),
+                    Some(_) => {}
+                    None => graph.edges.push(GraphEdge { start, end, sources: vec![source] }),
+                }
+            }
+        }
+        graph
+    }
+
+    pub fn vertices(&self) -> &[Point2] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    /// Enclosed faces, largest first. Dangling edges enclose nothing and
+    /// are ignored; a separate network inside a face becomes its hole.
+    pub fn faces(&self) -> Vec<GraphFace> {
+        // Strip dangling edges until every vertex closes a loop
+        let mut live = vec![true; self.edges.len()];
+        loop {
+            let mut degree = vec![0; self.vertices.len()];
+            for (edge, _) in self.edges.iter().zip(&live).filter(|(_, live)| **live) {
+                degree[edge.start] += 1;
+                degree[edge.end] += 1;
+            }
+            let mut pruned = false;
+            for (e, edge) in self.edges.iter().enumerate() {
+                if live[e] && (degree[edge.start] < 2 || degree[edge.end] < 2) {
+                    live[e] = false;
+                    pruned = true;
+                }
+            }
+            if !pruned {
+                break;
+            }
+        }
+
+        // Half-edge 2e runs start to end, 2e+1 back; each leaves its
+        // vertex at an angle
+        let angle = |from: usize, to: usize| {
+            let (a, b) = (self.vertices[from], self.vertices[to]);
+            (b.y - a.y).atan2(b.x - a.x)
+        };
+        let ends = |h: usize| {
+            let edge = &self.edges[h / 2];
+            if h.is_multiple_of(2) { (edge.start, edge.end) } else { (edge.end, edge.start) }
+        };
+        let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); self.vertices.len()];
+        for h in (0..self.edges.len() * 2).filter(|h| live[h / 2]) {
+            outgoing[ends(h).0].push(h);
+        }
+        for hs in &mut outgoing {
+            hs.sort_by(|&a, &b| {
+                let (a0, a1) = ends(a);
+                let (b0, b1) = ends(b);
+                angle(a0, a1).total_cmp(&angle(b0, b1))
+            });
+        }
+
+        // Trace each loop with the face on its left: arriving at a vertex,
+        // leave by the next edge clockwise from the one arrived along
+        let mut used = vec![false; self.edges.len() * 2];
+        let mut loops: Vec<(Vec<Point2>, Vec<usize>)> = Vec::new();
+        for first in (0..self.edges.len() * 2).filter(|h| live[h / 2]) {
+            if used[first] {
+                continue;
+            }
+            let mut ring = Vec::new();
+            let mut sources = Vec::new();
+            let mut h = first;
+            while !used[h] {
+                used[h] = true;
+                let (from, to) = ends(h);
+                ring.push(self.vertices[from]);
+                sources.extend(self.edges[h / 2].sources.iter().copied());
+                let around = &outgoing[to];
+                let back = around.iter().position(|&o| o == (h ^ 1)).unwrap_or(0);
+                h = around[(back + around.len() - 1) % around.len()];
+            }
+            loops.push((remove_collinear(ring), sources));
+        }
+
+        // Counterclockwise loops are faces; clockwise ones are the outlines
+        // of networks, which are holes in whatever face surrounds them
+        let (mut faces, outlines): (Vec<_>, Vec<_>) = loops
+            .into_iter()
+            .filter(|(ring, _)| ring.len() >= 3)
+            .partition(|(ring, _)| ring_area(ring) > 0.0);
+        faces.sort_by(|a, b| ring_area(&b.0).total_cmp(&ring_area(&a.0)));
+        let mut faces: Vec<GraphFace> = faces
+            .into_iter()
+            .map(|(ring, sources)| GraphFace { polygon: Polygon2::new(ring), sources })
+            .collect();
+
+        for (ring, sources) in outlines {
+            let (a, b) = (ring[0], ring[1]);
+            let len = a.distance_to(&b);
+            let probe = Point2::new(
+                (a.x + b.x) / 2.0 - (b.y - a.y) / len * 1e-7,
+                (a.y + b.y) / 2.0 + (b.x - a.x) / len * 1e-7,
+            );
+            let owner = faces
+                .iter_mut()
+                .filter(|face| winding_number(std::slice::from_ref(&face.polygon.outer), &probe) != 0)
+                .min_by(|x, y| x.polygon.signed_area().total_cmp(&y.polygon.signed_area()));
+            if let Some(face) = owner {
+                face.polygon.holes.push(ring);
+                face.sources.extend(sources);
+            }
+        }
+
+        for face in &mut faces {
+            face.sources.sort_unstable();
+            face.sources.dedup();
+        }
+        faces
+    }
+
+    /// Index of the vertex at `p`, welding it to any within `tolerance`
+    fn vertex(&mut self, p: Point2, tolerance: f64) -> usize {
+        match self.vertices.iter().position(|v| v.distance_to(&p) <= tolerance) {
+            Some(id) => id,
+            None => {
+                self.vertices.push(p);
+                self.vertices.len() - 1
+            }
+        }
+    }
+}
+
+/// Longest stretch of outline two polygons share, running along an edge
+/// of `a`. Edges count as shared when both ends of one lie within
+/// `tolerance` of the other's line.
+pub fn shared_edge(a: &Polygon2, b: &Polygon2, tolerance: f64) -> Option<(Point2, Point2)> {
+    shared_segments(a, b, tolerance)
+        .into_iter()
+        .fold(None, |best: Option<(Point2, Point2)>, (start, end)| match best {
+            Some((s, e)) if s.distance_to(&e) >= start.distance_to(&end) => best,
+            _ => Some((start, end)),
+        })
+}
+
+/// Every stretch of outline two polygons share, running along the edges
+/// of `a`, longer than `tolerance`
+pub fn shared_segments(a: &Polygon2, b: &Polygon2, tolerance: f64) -> Vec<(Point2, Point2)> {
+    let edges = |ring: &[Point2]| {
+        (0..ring.len())
+            .map(|i| (ring[i], ring[(i + 1) % ring.len()]))
+            .collect::<Vec<_>>()
+    };
+
+    let mut segments = Vec::new();
+    for (p, q) in edges(&a.outer) {
+        let len = p.distance_to(&q);
+        if len <= tolerance {
+            continue;
+        }
+        let (dx, dy) = ((q.x - p.x) / len, (q.y - p.y) / len);
+        // Distance along and off the line of this edge
+        let along = |r: &Point2| (r.x - p.x) * dx + (r.y - p.y) * dy;
+        let off = |r: &Point2| ((r.y - p.y) * dx - (r.x - p.x) * dy).abs();
+
+        for (r, s) in edges(&b.outer) {
+            if off(&r) > tolerance || off(&s) > tolerance {
+                continue;
+            }
+            let lo = along(&r).min(along(&s)).max(0.0);
+            let hi = along(&r).max(along(&s)).min(len);
+            if hi - lo > tolerance {
+                let at = |t: f64| Point2::new(p.x + dx * t, p.y + dy * t);
+                segments.push((at(lo), at(hi)));
+            }
+        }
+    }
+    segments
+}
+
+/// A point strictly inside a polygon (and outside its holes), found on a
+/// horizontal line through the middle of its vertical extent
+pub fn interior_point(polygon: &Polygon2) -> Option<Point2> {
+    let rings: Vec<&Vec<Point2>> = std::iter::once(&polygon.outer).chain(&polygon.holes).collect();
+    let mut ys: Vec<f64> = rings.iter().flat_map(|ring| ring.iter().map(|p| p.y)).collect();
+    ys.sort_by(|a, b| a.total_cmp(b));
+    ys.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+
+    // Scan between consecutive vertex heights, widest band first, so the
+    // line never passes through a vertex
+    let mut bands: Vec<(f64, f64)> = ys.windows(2).map(|w| (w[0], w[1])).collect();
+    bands.sort_by(|a, b| (b.1 - b.0).total_cmp(&(a.1 - a.0)));
+    for (y0, y1) in bands {
+        let y = (y0 + y1) / 2.0;
+        let mut xs: Vec<f64> = rings
+            .iter()
+            .flat_map(|ring| {
+                (0..ring.len()).filter_map(move |i| {
+                    let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+                    ((a.y < y) != (b.y < y)).then(|| a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x))
+                })
+            })
+            .collect();
+        xs.sort_by(|a, b| a.total_cmp(b));
+        let widest = xs
+            .chunks_exact(2)
+            .max_by(|a, b| (a[1] - a[0]).total_cmp(&(b[1] - b[0])));
+        if let Some(span) = widest {
+            return Some(Point2::new((span[0] + span[1]) / 2.0, y));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: f64, y: f64) -> Point2 {
+        Point2::new(x, y)
+    }
+
+    #[test]
+    fn test_faces_of_split_rectangle() {
+        // 20x10 box split by a wall at x = 8, with a stub wall left dangling
+        let segments = [
+            (p(0.0, 0.0), p(20.0, 0.0)),
+            (p(20.0, 0.0), p(20.0, 10.0)),
+            (p(20.0, 10.0), p(0.0, 10.0)),
+            (p(0.0, 10.0), p(0.0, 0.0)),
+            (p(8.0, 0.0), p(8.0, 10.0)),
+            (p(14.0, 10.0), p(14.0, 6.0)),
+        ];
+        let graph = PlanarGraph::from_segments(&segments, 0.01);
+        let faces = graph.faces();
+
+        assert_eq!(faces.len(), 2);
+        assert!((faces[0].polygon.area() - 120.0).abs() < 1e-9);
+        assert!((faces[1].polygon.area() - 80.0).abs() < 1e-9);
+        assert!(faces.iter().all(|f| f.polygon.signed_area() > 0.0));
+        assert_eq!(faces[0].sources, vec![0, 1, 2, 4]);
+        // The right room is a plain rectangle once split points are dropped
+        assert_eq!(faces[0].polygon.outer.len(), 4);
+    }
+
+    #[test]
+    fn test_island_becomes_hole() {
+        let square = |x0: f64, y0: f64, size: f64| {
+            let c = [p(x0, y0), p(x0 + size, y0), p(x0 + size, y0 + size), p(x0, y0 + size)];
+            (0..4).map(move |i| (c[i], c[(i + 1) % 4])).collect::<Vec<_>>()
+        };
+        let mut segments = square(0.0, 0.0, 10.0);
+        segments.extend(square(4.0, 4.0, 2.0));
+        let faces = PlanarGraph::from_segments(&segments, 0.01).faces();
+
+        assert_eq!(faces.len(), 2);
+        assert_eq!(faces[0].polygon.holes.len(), 1);
+        assert!((faces[0].polygon.area() - 96.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shared_edge_and_interior_point() {
+        let a = Polygon2::rectangle(10.0, 10.0);
+        let b = Polygon2::new(vec![p(10.0, 2.0), p(16.0, 2.0), p(16.0, 14.0), p(10.0, 14.0)]);
+        let (start, end) = shared_edge(&a, &b, 0.1).unwrap();
+        assert!(start.distance_to(&p(10.0, 2.0)) < 1e-9 && end.distance_to(&p(10.0, 10.0)) < 1e-9);
+        let apart = Polygon2::new(vec![p(20.0, 0.0), p(25.0, 0.0), p(25.0, 5.0), p(20.0, 5.0)]);
+        assert!(shared_edge(&a, &apart, 0.1).is_none());
+
+        // U shape: the centroid falls in the notch, the interior point does not
+        let u = Polygon2::new(vec![
+            p(0.0, 0.0), p(9.0, 0.0), p(9.0, 9.0), p(6.0, 9.0), p(6.0, 3.0), p(3.0, 3.0), p(3.0, 9.0), p(0.0, 9.0),
+        ]);
+        let inside = interior_point(&u).unwrap();
+        assert_ne!(winding_number(std::slice::from_ref(&u.outer), &inside), 0);
+    }
+}
@@ -4,92 +4,423 @@
 use anyhow::{anyhow, Result};
 use crate::domain::{Point2, Polygon2};
 
-/// Offset a polygon inward (negative distance) or outward (positive distance)
-/// This creates a parallel offset of the polygon boundary
+/// Distance below which two points are the same point
+const POINT_TOLERANCE: f64 = 1e-9;
+/// How far to either side of an edge the fill is sampled during cleanup
+const PROBE_DISTANCE: f64 = 1e-7;
+
+/// How offset edges are joined at corners where they pull apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetJoin {
+    /// Extend both edges to a point, beveling corners sharper than the miter limit
+    Miter,
+    /// Cut the corner off with a straight edge
+    Bevel,
+    /// Round the corner with an arc about the original vertex
+    Round,
+}
+
+/// Options for `offset_polygon_with`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffsetOptions {
+    pub join: OffsetJoin,
+    /// Longest miter allowed, as a multiple of the offset distance
+    pub miter_limit: f64,
+    /// Largest gap between a round join and the true arc
+    pub arc_tolerance: f64,
+}
+
+impl Default for OffsetOptions {
+    fn default() -> Self {
+        Self {
+            join: OffsetJoin::Miter,
+            miter_limit: 4.0,
+            arc_tolerance: 0.01,
+        }
+    }
+}
+
+/// Offset a polygon outward (positive distance) or inward (negative
+/// distance) with mitered corners. Fails if the offset leaves nothing or
+/// splits the polygon into several pieces; use `offset_polygon_with` to get
+/// every piece.
 pub fn offset_polygon(polygon: &Polygon2, distance: f64) -> Result<Polygon2> {
+    let mut pieces = offset_polygon_with(polygon, distance, &OffsetOptions::default())?;
+    match pieces.len() {
+        0 => Err(anyhow!("Offset of {} leaves nothing of the polygon", distance)),
+        1 => Ok(pieces.remove(0)),
+        n => Err(anyhow!("Offset of {} splits the polygon into {} pieces", distance, n)),
+    }
+}
+
+/// Offset a polygon and its holes outward (positive distance) or inward
+/// (negative distance). Each ring is offset edge by edge with the chosen
+/// join at corners, then overlaps and self-intersections are resolved by
+/// keeping only the boundary of the area the offset rings cover. Returns
+/// every resulting piece, largest first; an inward offset that consumes
+/// the polygon returns none.
+pub fn offset_polygon_with(polygon: &Polygon2, distance: f64, options: &OffsetOptions) -> Result<Vec<Polygon2>> {
     if polygon.outer.len() < 3 {
         return Err(anyhow!("Polygon must have at least 3 vertices"));
     }
+    if !distance.is_finite() {
+        return Err(anyhow!("Offset distance must be finite"));
+    }
+    if options.join == OffsetJoin::Miter && options.miter_limit < 1.0 {
+        return Err(anyhow!("Miter limit must be at least 1"));
+    }
+    if options.join == OffsetJoin::Round && options.arc_tolerance <= 0.0 {
+        return Err(anyhow!("Arc tolerance must be positive"));
+    }
 
-    // For now, implement a simple perpendicular offset for each edge
-    // This is a simplified version - a production implementation would use
-    // a library like geo-types or a computational geometry library
+    // Outer ring counterclockwise and holes clockwise, so the right-hand
+    // normal of every edge points away from the polygon
+    let mut rings = Vec::with_capacity(1 + polygon.holes.len());
+    if let Some(outer) = clean_ring(&polygon.outer, true) {
+        rings.push(outer);
+    } else {
+        return Err(anyhow!("Polygon outline has no area"));
+    }
+    rings.extend(polygon.holes.iter().filter_map(|hole| clean_ring(hole, false)));
 
-    let mut new_points = Vec::with_capacity(polygon.outer.len());
-    let n = polygon.outer.len();
+    let raw: Vec<Vec<Point2>> = rings.iter().map(|ring| offset_ring(ring, distance, options)).collect();
+    Ok(assemble_polygons(positive_fill(&raw)))
+}
 
-    for i in 0..n {
-        let prev = &polygon.outer[(i + n - 1) % n];
-        let curr = &polygon.outer[i];
-        let next = &polygon.outer[(i + 1) % n];
+/// Drop repeated and collinear points and orient a ring; None if nothing
+/// with area is left
+fn clean_ring(ring: &[Point2], counterclockwise: bool) -> Option<Vec<Point2>> {
+    let mut points: Vec<Point2> = Vec::with_capacity(ring.len());
+    for p in ring {
+        if points.last().is_none_or(|last| last.distance_to(p) > POINT_TOLERANCE) {
+            points.push(*p);
+        }
+    }
+    while points.len() > 1 && points[0].distance_to(&points[points.len() - 1]) <= POINT_TOLERANCE {
+        points.pop();
+    }
+    let mut points = remove_collinear(points);
+    if points.len() < 3 || ring_area(&points).abs() <= POINT_TOLERANCE {
+        return None;
+    }
+    if (ring_area(&points) > 0.0) != counterclockwise {
+        points.reverse();
+    }
+    Some(points)
+}
 
-        // Calculate perpendicular offset
-        let offset_point = compute_offset_point(prev, curr, next, distance);
-        new_points.push(offset_point);
+/// Remove points lying on the straight line between their neighbours
+pub(crate) fn remove_collinear(mut points: Vec<Point2>) -> Vec<Point2> {
+    let mut i = 0;
+    while points.len() >= 3 && i < points.len() {
+        let n = points.len();
+        let (a, b, c) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+        let cross = (b.x - a.x) * (c.y - b.y) - (b.y - a.y) * (c.x - b.x);
+        let dot = (b.x - a.x) * (c.x - b.x) + (b.y - a.y) * (c.y - b.y);
+        let scale = a.distance_to(&b) * b.distance_to(&c);
+        if scale <= POINT_TOLERANCE || (cross.abs() <= POINT_TOLERANCE * scale && dot > 0.0) {
+            points.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
     }
+    points
+}
 
-    // Filter out points that are too close together
-    let filtered_points: Vec<Point2> = new_points
-        .iter()
-        .enumerate()
-        .filter(|(i, p)| {
-            let next_i = (*i + 1) % new_points.len();
-            p.distance_to(&new_points[next_i]) > 1e-6
+/// Shoelace area of a ring, positive when counterclockwise
+pub(crate) fn ring_area(ring: &[Point2]) -> f64 {
+    let n = ring.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (ring[i], ring[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
         })
-        .map(|(_, p)| *p)
-        .collect();
+        .sum::<f64>()
+        / 2.0
+}
 
-    if filtered_points.len() < 3 {
-        return Err(anyhow!("Offset operation resulted in degenerate polygon"));
-    }
+/// Unit normal to the right of travel from a to b
+fn right_normal(a: &Point2, b: &Point2) -> (f64, f64) {
+    let len = a.distance_to(b);
+    ((b.y - a.y) / len, -(b.x - a.x) / len)
+}
+
+/// Move every edge of a cleaned ring `distance` along its right-hand
+/// normal and join consecutive edges. Where offset edges cross, both are
+/// run through the original vertex; the loops this leaves behind are
+/// removed by `positive_fill`.
+fn offset_ring(ring: &[Point2], distance: f64, options: &OffsetOptions) -> Vec<Point2> {
+    let n = ring.len();
+    let mut out = Vec::with_capacity(n * 2);
+    for i in 0..n {
+        let (prev, p, next) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+        let n1 = right_normal(&prev, &p);
+        let n2 = right_normal(&p, &next);
+        let at = |(nx, ny): (f64, f64), d: f64| Point2::new(p.x + nx * d, p.y + ny * d);
+        let cross = n1.0 * n2.1 - n1.1 * n2.0;
+        let dot = n1.0 * n2.0 + n1.1 * n2.1;
+
+        if distance == 0.0 || (cross.abs() < 1e-12 && dot > 0.0) {
+            out.push(at(n1, distance));
+            continue;
+        }
+        // Edges pull apart where the ring turns toward the offset side, or
+        // doubles back on itself
+        let gap = cross * distance > 0.0 || (cross.abs() < 1e-12 && dot < 0.0);
+        if !gap {
+            out.push(at(n1, distance));
+            out.push(p);
+            out.push(at(n2, distance));
+            continue;
+        }
 
-    Ok(Polygon2::new(filtered_points))
+        match options.join {
+            OffsetJoin::Miter if 2.0 / (1.0 + dot) <= options.miter_limit * options.miter_limit => {
+                let scale = distance / (1.0 + dot);
+                out.push(Point2::new(p.x + (n1.0 + n2.0) * scale, p.y + (n1.1 + n2.1) * scale));
+            }
+            OffsetJoin::Round => {
+                let radius = distance.abs();
+                let step = if options.arc_tolerance >= radius {
+                    std::f64::consts::FRAC_PI_2
+                } else {
+                    2.0 * (1.0 - options.arc_tolerance / radius).acos()
+                };
+                let angle = if cross.abs() < 1e-12 {
+                    std::f64::consts::PI * distance.signum()
+                } else {
+                    cross.atan2(dot)
+                };
+                let steps = (angle.abs() / step).ceil().max(1.0) as usize;
+                for k in 0..=steps {
+                    let theta = angle * k as f64 / steps as f64;
+                    let (sin, cos) = theta.sin_cos();
+                    out.push(at((n1.0 * cos - n1.1 * sin, n1.0 * sin + n1.1 * cos), distance));
+                }
+            }
+            _ => {
+                out.push(at(n1, distance));
+                out.push(at(n2, distance));
+            }
+        }
+    }
+    out
 }
 
-/// Compute offset point at a vertex given previous, current, and next points
-fn compute_offset_point(prev: &Point2, curr: &Point2, next: &Point2, distance: f64) -> Point2 {
-    // Calculate edge vectors
-    let v1_x = curr.x - prev.x;
-    let v1_y = curr.y - prev.y;
-    let v1_len = (v1_x * v1_x + v1_y * v1_y).sqrt();
+/// Boundary of the area covered by the rings with a positive winding
+/// number, as rings with that area on their left
+fn positive_fill(rings: &[Vec<Point2>]) -> Vec<Vec<Point2>> {
+    let segments: Vec<(Point2, Point2)> = rings
+        .iter()
+        .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+        .filter(|(a, b)| a.distance_to(b) > POINT_TOLERANCE)
+        .collect();
 
-    let v2_x = next.x - curr.x;
-    let v2_y = next.y - curr.y;
-    let v2_len = (v2_x * v2_x + v2_y * v2_y).sqrt();
+    // Split every segment wherever another crosses or touches it
+    let mut cuts: Vec<Vec<(f64, Point2)>> = segments.iter().map(|&(a, b)| vec![(0.0, a), (1.0, b)]).collect();
+    for i in 0..segments.len() {
+        for j in (i + 1)..segments.len() {
+            for (ti, tj, p) in segment_contacts(&segments[i], &segments[j], POINT_TOLERANCE) {
+                cuts[i].push((ti, p));
+                cuts[j].push((tj, p));
+            }
+        }
+    }
 
-    if v1_len < 1e-10 || v2_len < 1e-10 {
-        return *curr;
+    // Weld split points into shared vertices
+    let mut vertices: Vec<Point2> = Vec::new();
+    let mut vertex_of = |p: Point2| -> usize {
+        match vertices.iter().position(|v| v.distance_to(&p) <= POINT_TOLERANCE * 10.0) {
+            Some(id) => id,
+            None => {
+                vertices.push(p);
+                vertices.len() - 1
+            }
+        }
+    };
+    let mut pieces: Vec<(usize, usize)> = Vec::new();
+    for mut cut in cuts {
+        cut.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let ids: Vec<usize> = cut.into_iter().map(|(_, p)| vertex_of(p)).collect();
+        pieces.extend(ids.windows(2).filter(|w| w[0] != w[1]).map(|w| (w[0], w[1])));
+    }
+
+    // Keep pieces with covered area on exactly one side
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for (u, v) in pieces {
+        let (a, b) = (vertices[u], vertices[v]);
+        let len = a.distance_to(&b);
+        let (lx, ly) = (-(b.y - a.y) / len * PROBE_DISTANCE, (b.x - a.x) / len * PROBE_DISTANCE);
+        let (mx, my) = ((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+        let left = winding_number(rings, &Point2::new(mx + lx, my + ly)) > 0;
+        let right = winding_number(rings, &Point2::new(mx - lx, my - ly)) > 0;
+        let edge = match (left, right) {
+            (true, false) => (u, v),
+            (false, true) => (v, u),
+            _ => continue,
+        };
+        if !edges.contains(&edge) {
+            edges.push(edge);
+        }
     }
 
-    // Normalize
-    let n1_x = -v1_y / v1_len;
-    let n1_y = v1_x / v1_len;
-    let n2_x = -v2_y / v2_len;
-    let n2_y = v2_x / v2_len;
+    // Chain edges into rings, taking the sharpest right turn where several
+    // leave one vertex so rings touching at a point stay separate
+    let mut used = vec![false; edges.len()];
+    let mut result = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+        let mut ring = vec![edges[first].0];
+        let mut current = first;
+        loop {
+            let (u, v) = edges[current];
+            if v == edges[first].0 {
+                break;
+            }
+            let (a, b) = (vertices[u], vertices[v]);
+            let heading = (b.y - a.y).atan2(b.x - a.x);
+            let turn = |e: usize| {
+                let c = vertices[edges[e].1];
+                let mut t = (c.y - b.y).atan2(c.x - b.x) - heading;
+                while t <= -std::f64::consts::PI {
+                    t += 2.0 * std::f64::consts::PI;
+                }
+                while t > std::f64::consts::PI {
+                    t -= 2.0 * std::f64::consts::PI;
+                }
+                t
+            };
+            let next = (0..edges.len())
+                .filter(|&e| !used[e] && edges[e].0 == v)
+                .min_by(|&x, &y| turn(x).total_cmp(&turn(y)));
+            match next {
+                Some(e) => {
+                    used[e] = true;
+                    ring.push(v);
+                    current = e;
+                }
+                None => {
+                    ring.clear();
+                    break;
+                }
+            }
+        }
+        let ring = remove_collinear(ring.into_iter().map(|id| vertices[id]).collect());
+        if ring.len() >= 3 && ring_area(&ring).abs() > POINT_TOLERANCE {
+            result.push(ring);
+        }
+    }
+    result
+}
 
-    // Average normal (bisector)
-    let avg_nx = (n1_x + n2_x) / 2.0;
-    let avg_ny = (n1_y + n2_y) / 2.0;
-    let avg_len = (avg_nx * avg_nx + avg_ny * avg_ny).sqrt();
+/// Points where two segments meet, as (parameter on a, parameter on b,
+/// point). Collinear overlaps give the ends of the shared stretch. Ends
+/// within `tolerance` of the other segment touch it and keep their
+/// position exactly.
+pub(crate) fn segment_contacts(a: &(Point2, Point2), b: &(Point2, Point2), tolerance: f64) -> Vec<(f64, f64, Point2)> {
+    let (p, r) = (a.0, (a.1.x - a.0.x, a.1.y - a.0.y));
+    let (q, s) = (b.0, (b.1.x - b.0.x, b.1.y - b.0.y));
+    let cross = |u: (f64, f64), v: (f64, f64)| u.0 * v.1 - u.1 * v.0;
+    let dot = |u: (f64, f64), v: (f64, f64)| u.0 * v.0 + u.1 * v.1;
+    let qp = (q.x - p.x, q.y - p.y);
+    let denom = cross(r, s);
+    let (rr, ss) = (dot(r, r), dot(s, s));
+
+    // Parameter of a point along a segment, if it lies on it
+    let on = |point: &Point2, start: &Point2, dir: (f64, f64), len2: f64| {
+        let t = dot((point.x - start.x, point.y - start.y), dir) / len2;
+        let foot = Point2::new(start.x + dir.0 * t, start.y + dir.1 * t);
+        let slack = tolerance / len2.sqrt();
+        ((-slack..=1.0 + slack).contains(&t) && foot.distance_to(point) <= tolerance).then_some(t.clamp(0.0, 1.0))
+    };
 
-    if avg_len < 1e-10 {
-        return *curr;
+    if denom.abs() <= 1e-12 * (rr * ss).sqrt() {
+        // Parallel: only endpoints lying on the other segment matter
+        let mut contacts = Vec::new();
+        for end in [b.0, b.1] {
+            if let (Some(t), Some(u)) = (on(&end, &p, r, rr), on(&end, &q, s, ss)) {
+                contacts.push((t, u, end));
+            }
+        }
+        for end in [a.0, a.1] {
+            if let (Some(t), Some(u)) = (on(&end, &p, r, rr), on(&end, &q, s, ss)) {
+                contacts.push((t, u, end));
+            }
+        }
+        return contacts;
     }
 
-    // Calculate offset distance based on angle
-    let dot = n1_x * n2_x + n1_y * n2_y;
-    let angle_factor = if dot.abs() > 0.999 {
-        1.0
+    let t = cross(qp, s) / denom;
+    let u = cross(qp, r) / denom;
+    let slack_t = tolerance / rr.sqrt();
+    let slack_u = tolerance / ss.sqrt();
+    if t < -slack_t || t > 1.0 + slack_t || u < -slack_u || u > 1.0 + slack_u {
+        return Vec::new();
+    }
+    // Snap to an existing endpoint so touching segments share it exactly
+    let point = if u <= slack_u {
+        b.0
+    } else if u >= 1.0 - slack_u {
+        b.1
+    } else if t <= slack_t {
+        a.0
+    } else if t >= 1.0 - slack_t {
+        a.1
     } else {
-        1.0 / (1.0 + dot).sqrt()
+        Point2::new(p.x + r.0 * t, p.y + r.1 * t)
     };
+    vec![(t.clamp(0.0, 1.0), u.clamp(0.0, 1.0), point)]
+}
 
-    let offset_dist = distance * angle_factor;
+/// Sum over the rings of how many times each winds counterclockwise around a point
+pub(crate) fn winding_number(rings: &[Vec<Point2>], point: &Point2) -> i32 {
+    let mut winding = 0;
+    for ring in rings {
+        for i in 0..ring.len() {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            let side = (b.x - a.x) * (point.y - a.y) - (point.x - a.x) * (b.y - a.y);
+            if a.y <= point.y {
+                if b.y > point.y && side > 0.0 {
+                    winding += 1;
+                }
+            } else if b.y <= point.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+    winding
+}
 
-    Point2::new(
-        curr.x + (avg_nx / avg_len) * offset_dist,
-        curr.y + (avg_ny / avg_len) * offset_dist,
-    )
+/// Group boundary rings into polygons: counterclockwise rings are outlines
+/// and clockwise rings are holes of the smallest outline around them.
+/// Largest polygon first.
+pub(crate) fn assemble_polygons(rings: Vec<Vec<Point2>>) -> Vec<Polygon2> {
+    let (outers, holes): (Vec<Vec<Point2>>, Vec<Vec<Point2>>) =
+        rings.into_iter().partition(|ring| ring_area(ring) > 0.0);
+    let mut polygons: Vec<Polygon2> = outers.into_iter().map(Polygon2::new).collect();
+    polygons.sort_by(|a, b| b.signed_area().total_cmp(&a.signed_area()));
+
+    for hole in holes {
+        // A point just inside the material beside the hole's first edge
+        let (a, b) = (hole[0], hole[1]);
+        let len = a.distance_to(&b);
+        let probe = Point2::new(
+            (a.x + b.x) / 2.0 - (b.y - a.y) / len * PROBE_DISTANCE,
+            (a.y + b.y) / 2.0 + (b.x - a.x) / len * PROBE_DISTANCE,
+        );
+        let owner = polygons
+            .iter_mut()
+            .filter(|polygon| winding_number(std::slice::from_ref(&polygon.outer), &probe) != 0)
+            .min_by(|x, y| x.signed_area().total_cmp(&y.signed_area()));
+        if let Some(polygon) = owner {
+            polygon.holes.push(hole);
+        }
+    }
+    polygons
 }
 
 /// Union two polygons (simplified implementation)
@@ -232,7 +563,7 @@ fn line_segment_intersection(
     let u = -((x1 - x2) * (y1 - y3) - (y1 - y2) * (x1 - x3)) / denom;
 
     // Check if intersection is within both segments
-    if t >= 0.0 && t <= 1.0 && u >= 0.0 && u <= 1.0 {
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
         Some(Point2::new(x1 + t * (x2 - x1), y1 + t * (y2 - y1)))
     } else {
         None
@@ -290,6 +621,118 @@ mod tests {
         assert!(offset.area() < polygon.area());
     }
 
+    #[test]
+    fn test_offset_concave_l_shape() {
+        // 10x10 square with a 5x5 notch cut from the top right
+        let polygon = Polygon2::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(10.0, 5.0),
+            Point2::new(5.0, 5.0),
+            Point2::new(5.0, 10.0),
+            Point2::new(0.0, 10.0),
+        ]);
+
+        // Inset L has arms 8 long and 3 wide: 8*3 + 3*5 = 39
+        let inset = offset_polygon(&polygon, -1.0).unwrap();
+        assert!((inset.area() - 39.0).abs() < 1e-6, "got {}", inset.area());
+        assert_eq!(inset.outer.len(), 6);
+
+        // Mitered outset: 12x12 less a 5x5 notch = 119
+        let outset = offset_polygon(&polygon, 1.0).unwrap();
+        assert!((outset.area() - 119.0).abs() < 1e-6, "got {}", outset.area());
+    }
+
+    #[test]
+    fn test_offset_join_styles() {
+        let polygon = Polygon2::rectangle(10.0, 10.0);
+        let area = |join| {
+            let options = OffsetOptions { join, ..OffsetOptions::default() };
+            let pieces = offset_polygon_with(&polygon, 1.0, &options).unwrap();
+            assert_eq!(pieces.len(), 1);
+            pieces[0].area()
+        };
+
+        // Miter keeps square corners, bevel clips them, round sits between
+        let miter = area(OffsetJoin::Miter);
+        let bevel = area(OffsetJoin::Bevel);
+        let round = area(OffsetJoin::Round);
+        assert!((miter - 144.0).abs() < 1e-6);
+        assert!((bevel - 142.0).abs() < 1e-6);
+        assert!(round > bevel && round < miter);
+        assert!((round - (140.0 + std::f64::consts::PI)).abs() < 0.05);
+
+        // A miter limit of 1 bevels every corner
+        let options = OffsetOptions { miter_limit: 1.0, ..OffsetOptions::default() };
+        let limited = offset_polygon_with(&polygon, 1.0, &options).unwrap();
+        assert!((limited[0].area() - 142.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_offset_with_hole() {
+        let hole = vec![
+            Point2::new(4.0, 4.0),
+            Point2::new(6.0, 4.0),
+            Point2::new(6.0, 6.0),
+            Point2::new(4.0, 6.0),
+        ];
+        let polygon = Polygon2::with_holes(Polygon2::rectangle(10.0, 10.0).outer, vec![hole]);
+
+        // Shrinking grows the hole: 8x8 outline less a 4x4 hole
+        let inset = offset_polygon(&polygon, -1.0).unwrap();
+        assert_eq!(inset.holes.len(), 1);
+        assert!((inset.area() - 48.0).abs() < 1e-6, "got {}", inset.area());
+
+        // Growing far enough closes the hole
+        let outset = offset_polygon(&polygon, 1.5).unwrap();
+        assert!(outset.holes.is_empty());
+        assert!((outset.area() - 169.0).abs() < 1e-6, "got {}", outset.area());
+    }
+
+    #[test]
+    fn test_offset_collapse_and_split() {
+        let polygon = Polygon2::rectangle(4.0, 4.0);
+        assert!(offset_polygon_with(&polygon, -2.5, &OffsetOptions::default()).unwrap().is_empty());
+        assert!(offset_polygon(&polygon, -2.5).is_err());
+
+        // Two 4x4 rooms joined by a 1 wide corridor
+        let dumbbell = Polygon2::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(4.0, 0.0),
+            Point2::new(4.0, 1.5),
+            Point2::new(8.0, 1.5),
+            Point2::new(8.0, 0.0),
+            Point2::new(12.0, 0.0),
+            Point2::new(12.0, 4.0),
+            Point2::new(8.0, 4.0),
+            Point2::new(8.0, 2.5),
+            Point2::new(4.0, 2.5),
+            Point2::new(4.0, 4.0),
+            Point2::new(0.0, 4.0),
+        ]);
+        let pieces = offset_polygon_with(&dumbbell, -1.0, &OffsetOptions::default()).unwrap();
+        assert_eq!(pieces.len(), 2);
+        for piece in &pieces {
+            assert!((piece.area() - 4.0).abs() < 1e-6, "got {}", piece.area());
+        }
+        assert!(offset_polygon(&dumbbell, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_offset_cleans_self_intersection() {
+        // Bow tie crossing at (24/7, 24/7): only the larger, counterclockwise
+        // lobe encloses positive area
+        let bow_tie = Polygon2::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(6.0, 6.0),
+            Point2::new(6.0, 3.0),
+            Point2::new(0.0, 4.0),
+        ]);
+        let pieces = offset_polygon_with(&bow_tie, 0.0, &OffsetOptions::default()).unwrap();
+        assert_eq!(pieces.len(), 1);
+        assert!((pieces[0].area() - 48.0 / 7.0).abs() < 1e-6, "got {}", pieces[0].area());
+    }
+
     #[test]
     fn test_split_polygon_by_line() {
         // Create a simple rectangle
@@ -0,0 +1,118 @@
+// Roof solid generation
+// Gable, hip and shed roofs share one topology over the rectangular roof
+// plan: four eave corners plus two ridge points (which meet at an apex for
+// a hip roof over a square plan).
+
+use anyhow::{anyhow, Result};
+
+use truck_modeling::Solid;
+use truck_modeling::Point3 as TruckPoint3;
+
+use crate::domain::{Polygon2, Roof, RoofType};
+use super::polyhedron;
+
+/// Build the roof solid for a footprint, sitting on the wall plate at `plate_z`
+pub fn generate_roof_solid(roof: &Roof, footprint: &Polygon2, plate_z: f64) -> Result<Solid> {
+    let plan = roof.plan(footprint)
+        .ok_or_else(|| anyhow!("Footprint must have at least 3 vertices"))?;
+    if plan.width() <= 0.0 || plan.depth() <= 0.0 {
+        return Err(anyhow!("Roof plan has zero area"));
+    }
+
+    // Work in (u along the ridge, v across it), then map back to plan axes
+    let length = plan.width().max(plan.depth());
+    let span = plan.span();
+    let rise = roof.ridge_height(&plan);
+    let top = plate_z + rise;
+
+    let (ridge_start, ridge_end, ridge_v) = match roof.roof_type {
+        RoofType::Gable => (0.0, length, span / 2.0),
+        RoofType::Hip => (span / 2.0, length - span / 2.0, span / 2.0),
+        RoofType::Shed => (0.0, length, span),
+    };
+
+    let mut points = vec![
+        (0.0, 0.0, plate_z),
+        (length, 0.0, plate_z),
+        (length, span, plate_z),
+        (0.0, span, plate_z),
+        (ridge_start, ridge_v, top),
+    ];
+    // Faces are wound counterclockwise seen from outside
+    let faces: Vec<Vec<usize>> = if ridge_end - ridge_start > 1e-9 {
+        points.push((ridge_end, ridge_v, top));
+        vec![
+            vec![0, 3, 2, 1], // underside
+            vec![0, 1, 5, 4], // low-v slope
+            vec![2, 3, 4, 5], // high-v slope (or shed wall)
+            vec![0, 4, 3],    // u = 0 end
+            vec![1, 2, 5],    // u = length end
+        ]
+    } else {
+        // Hip roof over a square plan is a pyramid
+        vec![
+            vec![0, 3, 2, 1],
+            vec![0, 1, 4],
+            vec![2, 3, 4],
+            vec![0, 4, 3],
+            vec![1, 2, 4],
+        ]
+    };
+
+    let along_x = plan.ridge_along_x();
+    let points: Vec<TruckPoint3> = points
+        .into_iter()
+        .map(|(u, v, z)| {
+            if along_x {
+                TruckPoint3::new(plan.min.x + u, plan.min.y + v, z)
+            } else {
+                TruckPoint3::new(plan.min.x + v, plan.min.y + u, z)
+            }
+        })
+        .collect();
+
+    // Swapping axes mirrors the solid, which reverses the winding
+    let faces = if along_x {
+        faces
+    } else {
+        faces.into_iter().map(|f| f.into_iter().rev().collect()).collect()
+    };
+
+    polyhedron(&points, &faces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::LevelId;
+    use crate::geometry::{solid_to_mesh, BoundingBox};
+
+    #[test]
+    fn test_roof_solids() {
+        let footprint = Polygon2::rectangle(40.0, 24.0);
+        for roof_type in [RoofType::Gable, RoofType::Hip, RoofType::Shed] {
+            let roof = Roof::new(LevelId::new(), roof_type, 6.0, 1.0);
+            let solid = generate_roof_solid(&roof, &footprint, 9.0).unwrap();
+
+            let bbox = BoundingBox::from_solid(&solid);
+            assert!((bbox.size()[0] - 42.0).abs() < 0.1, "{:?} width", roof_type);
+            assert!((bbox.size()[1] - 26.0).abs() < 0.1, "{:?} depth", roof_type);
+            let plan = roof.plan(&footprint).unwrap();
+            assert!((bbox.max[2] - (9.0 + roof.ridge_height(&plan))).abs() < 0.1, "{:?} ridge", roof_type);
+
+            let mesh = solid_to_mesh(&solid, 0.1).unwrap();
+            assert!(mesh.triangle_count() >= 6);
+        }
+    }
+
+    #[test]
+    fn test_hip_roof_over_square_plan() {
+        // A deep, narrow plan puts the ridge along y; a square one makes a pyramid
+        let roof = Roof::new(LevelId::new(), RoofType::Hip, 8.0, 0.0);
+        let deep = generate_roof_solid(&roof, &Polygon2::rectangle(20.0, 36.0), 0.0).unwrap();
+        assert!((BoundingBox::from_solid(&deep).max[2] - 20.0 / 3.0).abs() < 0.1);
+
+        let square = generate_roof_solid(&roof, &Polygon2::rectangle(30.0, 30.0), 0.0).unwrap();
+        assert!((BoundingBox::from_solid(&square).max[2] - 10.0).abs() < 0.1);
+    }
+}
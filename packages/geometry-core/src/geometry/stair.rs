@@ -0,0 +1,141 @@
+// Stair solid generation
+// Each part is a prism: a profile drawn in the vertical plane of travel
+// (u along the flight, z up) extruded across the stair width (v).
+
+use anyhow::{anyhow, Result};
+
+use truck_modeling::Solid;
+use truck_modeling::Point3 as TruckPoint3;
+
+use crate::domain::{Stair, StairRun};
+use super::polyhedron;
+
+/// Tread thickness (feet, 1")
+const TREAD_THICKNESS: f64 = 1.0 / 12.0;
+/// Stringer thickness across the flight (feet, 1-1/2" for a 2x12)
+const STRINGER_THICKNESS: f64 = 1.5 / 12.0;
+/// Stringer depth measured square to the slope (feet, 11-1/4" for a 2x12)
+const STRINGER_DEPTH: f64 = 11.25 / 12.0;
+
+/// Solids making up a stair flight
+pub struct StairSolids {
+    pub treads: Vec<Solid>,
+    /// One stringer on each side of the flight
+    pub stringers: Vec<Solid>,
+}
+
+/// Build the treads and stringers of a flight starting at floor elevation `base_z`
+pub fn generate_stair_solids(stair: &Stair, run: &StairRun, base_z: f64) -> Result<StairSolids> {
+    if stair.width <= 2.0 * STRINGER_THICKNESS {
+        return Err(anyhow!("Stair is too narrow for its stringers"));
+    }
+
+    let rise = run.riser_height / 12.0;
+    let going = run.tread_depth / 12.0;
+    let half = stair.width / 2.0;
+    let inner = half - STRINGER_THICKNESS;
+
+    // Tread i spans one going and sits at the top of riser i
+    let mut treads = Vec::with_capacity(run.tread_count() as usize);
+    for i in 1..=run.tread_count() {
+        let u0 = (i - 1) as f64 * going;
+        let top = i as f64 * rise;
+        let profile = [
+            (u0, top - TREAD_THICKNESS),
+            (u0 + going, top - TREAD_THICKNESS),
+            (u0 + going, top),
+            (u0, top),
+        ];
+        treads.push(flight_prism(stair, base_z, &profile, -inner, inner)?);
+    }
+
+    let mut stringers = Vec::new();
+    if run.tread_count() > 0 {
+        // Top edge follows the tread nosings; the bottom edge is cut level
+        // where it meets the floor
+        let slope = rise / going;
+        let plumb_depth = STRINGER_DEPTH * (1.0 + slope * slope).sqrt();
+        let length = run.total_run;
+        let top = run.total_rise;
+        let toe = ((plumb_depth - rise) / slope).clamp(0.0, length);
+        let profile = [
+            (0.0, 0.0),
+            (toe, 0.0),
+            (length, top - plumb_depth),
+            (length, top),
+            (0.0, rise),
+        ];
+        stringers.push(flight_prism(stair, base_z, &profile, -half, -inner)?);
+        stringers.push(flight_prism(stair, base_z, &profile, inner, half)?);
+    }
+
+    Ok(StairSolids { treads, stringers })
+}
+
+/// Extrude a (u, z) profile, counterclockwise with u to the right, from v0
+/// to v1 and place it along the stair
+fn flight_prism(stair: &Stair, base_z: f64, profile: &[(f64, f64)], v0: f64, v1: f64) -> Result<Solid> {
+    let (dx, dy) = stair.direction_vector();
+    let place = |u: f64, v: f64, z: f64| {
+        TruckPoint3::new(
+            stair.start.x + u * dx - v * dy,
+            stair.start.y + u * dy + v * dx,
+            base_z + z,
+        )
+    };
+
+    let n = profile.len();
+    let points: Vec<TruckPoint3> = profile
+        .iter()
+        .map(|&(u, z)| place(u, v0, z))
+        .chain(profile.iter().map(|&(u, z)| place(u, v1, z)))
+        .collect();
+
+    // The v0 cap faces -v, where the profile reads counterclockwise
+    let mut faces = vec![(0..n).collect::<Vec<_>>(), (n..2 * n).rev().collect()];
+    for a in 0..n {
+        let b = (a + 1) % n;
+        faces.push(vec![b, a, a + n, b + n]);
+    }
+
+    polyhedron(&points, &faces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LevelId, Point2};
+    use crate::geometry::{solid_to_mesh, BoundingBox};
+
+    #[test]
+    fn test_stair_solids() {
+        let stair = Stair::new(LevelId::new(), LevelId::new(), Point2::new(0.0, 0.0), 0.0, 3.0, 7.75, 10.0);
+        let run = stair.solve(9.0).unwrap();
+        let solids = generate_stair_solids(&stair, &run, 0.0).unwrap();
+        assert_eq!(solids.treads.len(), 13);
+        assert_eq!(solids.stringers.len(), 2);
+
+        let top_tread = BoundingBox::from_solid(solids.treads.last().unwrap());
+        assert!((top_tread.max[2] - 13.0 * run.riser_height / 12.0).abs() < 1e-6);
+        assert!((top_tread.max[0] - run.total_run).abs() < 1e-6);
+
+        let stringer = BoundingBox::from_solid(&solids.stringers[0]);
+        assert!(stringer.min[2].abs() < 1e-6);
+        assert!((stringer.max[2] - 9.0).abs() < 1e-6);
+        assert!((stringer.min[1] + 1.5).abs() < 1e-6);
+
+        let mesh = solid_to_mesh(&solids.stringers[1], 0.1).unwrap();
+        assert!(mesh.triangle_count() >= 10);
+    }
+
+    #[test]
+    fn test_stair_direction() {
+        // Climbing toward -Y puts the flight below the start point in plan
+        let stair = Stair::new(LevelId::new(), LevelId::new(), Point2::new(5.0, 20.0), 270.0, 3.0, 7.5, 11.0);
+        let run = stair.solve(10.0).unwrap();
+        let solids = generate_stair_solids(&stair, &run, 10.0).unwrap();
+        let bbox = BoundingBox::from_solid(solids.treads.last().unwrap());
+        assert!((bbox.min[1] - (20.0 - run.total_run)).abs() < 1e-6);
+        assert!((bbox.max[2] - (10.0 + 10.0 - 7.5 / 12.0)).abs() < 1e-6);
+    }
+}
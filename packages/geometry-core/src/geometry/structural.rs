@@ -0,0 +1,56 @@
+// Beam and post solid generation
+// Both are plain boxes: a beam runs along its plan line centered on it,
+// hanging down from its top height; a post is centered on its plan point.
+
+use anyhow::Result;
+
+use truck_modeling::Solid;
+
+use crate::domain::{Beam, Point3, Post};
+use super::create_oriented_box;
+
+/// Solid for a beam on a level whose floor is at `base_z`
+pub fn generate_beam_solid(beam: &Beam, base_z: f64) -> Result<Solid> {
+    let width = beam.profile.width() / 12.0;
+    let rotation = (beam.end.y - beam.start.y).atan2(beam.end.x - beam.start.x);
+    // Shift right of the line by half the width so the box is centered on it
+    let origin = Point3::new(
+        beam.start.x + rotation.sin() * width / 2.0,
+        beam.start.y - rotation.cos() * width / 2.0,
+        base_z + beam.bottom_height(),
+    );
+    create_oriented_box(beam.length(), width, beam.profile.depth() / 12.0, &origin, rotation)
+}
+
+/// Solid for a post on a level whose floor is at `base_z`. The post's deep
+/// side runs along X.
+pub fn generate_post_solid(post: &Post, base_z: f64) -> Result<Solid> {
+    let (width, depth) = (post.profile.width() / 12.0, post.profile.depth() / 12.0);
+    let origin = Point3::new(post.location.x - depth / 2.0, post.location.y - width / 2.0, base_z);
+    create_oriented_box(depth, width, post.height, &origin, 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{FramingMaterial, LevelId, LumberSize, MemberProfile, Point2};
+    use crate::geometry::BoundingBox;
+
+    #[test]
+    fn test_beam_and_post_solids() {
+        let profile = MemberProfile::new(LumberSize::TwoByTwelve, 2);
+        let beam = Beam::new(LevelId::new(), Point2::new(4.0, 0.0), Point2::new(4.0, 12.0), profile, FramingMaterial::DF, 9.0);
+        let bbox = BoundingBox::from_solid(&generate_beam_solid(&beam, 10.0).unwrap());
+        assert!((bbox.min[0] - (4.0 - 1.5 / 12.0)).abs() < 1e-6);
+        assert!((bbox.max[0] - (4.0 + 1.5 / 12.0)).abs() < 1e-6);
+        assert!((bbox.max[1] - 12.0).abs() < 1e-6);
+        assert!((bbox.max[2] - 19.0).abs() < 1e-6);
+        assert!((bbox.min[2] - (19.0 - 11.25 / 12.0)).abs() < 1e-6);
+
+        let post = Post::new(LevelId::new(), Point2::new(4.0, 12.0), MemberProfile::new(LumberSize::FourByFour, 1), FramingMaterial::DF, 8.0);
+        let bbox = BoundingBox::from_solid(&generate_post_solid(&post, 10.0).unwrap());
+        assert!((bbox.min[0] - (4.0 - 1.75 / 12.0)).abs() < 1e-6);
+        assert!((bbox.max[1] - (12.0 + 1.75 / 12.0)).abs() < 1e-6);
+        assert!((bbox.max[2] - 18.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,264 @@
+// Wall solid generation
+// A wall is a prism along its centerline: u runs from start to end (along
+// the arc for curved walls), v across the thickness (positive to the wall's
+// left) and z up from the wall base. Openings are cut out as prisms running
+// through both faces. The ends are cut to meet neighbouring walls as
+// resolved by `WallNetwork`.
+
+use anyhow::{anyhow, Result};
+
+use truck_modeling::Solid;
+use truck_modeling::Point3 as TruckPoint3;
+
+use crate::domain::{Opening, Wall};
+use super::{polyhedron, subtract_solid, WallEndCuts};
+
+/// How far an opening's cutter runs past the wall faces, and past the
+/// wall's ends, base or top when the opening reaches them (feet)
+const CUT_OVERRUN: f64 = 0.1;
+/// Largest arc covered by one facet of a curved wall (5 degrees)
+const ARC_SEGMENT_ANGLE: f64 = std::f64::consts::PI / 36.0;
+/// Facet edges closer together than this along the wall are merged (feet)
+const STATION_TOLERANCE: f64 = 1e-3;
+
+/// Build a wall `thickness` feet thick on a level at elevation `base_z`, with
+/// its ends cut to its joins and its openings cut through it. Openings are
+/// clipped to the wall; any that miss it entirely are skipped.
+pub fn generate_wall_solid(
+    wall: &Wall,
+    thickness: f64,
+    base_z: f64,
+    openings: &[&Opening],
+    ends: &WallEndCuts,
+) -> Result<Solid> {
+    let length = wall.length();
+    if length <= 0.0 {
+        return Err(anyhow!("Wall has zero length"));
+    }
+    if thickness <= 0.0 || wall.height <= 0.0 {
+        return Err(anyhow!("Wall thickness and height must be positive"));
+    }
+
+    // Run each cutter past any wall face it meets so no cut is coplanar
+    // with the wall's own faces, including ends extended to a join
+    let reach = |faces: [f64; 2]| CUT_OVERRUN + faces[0].max(faces[1]).max(0.0);
+    let extend = |lo: f64, hi: f64, min: f64, max: f64, overrun: [f64; 2]| {
+        [
+            if lo <= min + STATION_TOLERANCE { min - overrun[0] } else { lo },
+            if hi >= max - STATION_TOLERANCE { max + overrun[1] } else { hi },
+        ]
+    };
+    let cuts: Vec<(&Opening, [f64; 2], [f64; 2])> = openings
+        .iter()
+        .filter_map(|opening| {
+            let center = opening.position_along_wall * length;
+            let u = [center - opening.width / 2.0, center + opening.width / 2.0];
+            let z = [opening.sill_height, opening.sill_height + opening.height];
+            if u[1] <= 0.0 || u[0] >= length || z[1] <= 0.0 || z[0] >= wall.height {
+                return None;
+            }
+            Some((
+                *opening,
+                extend(u[0], u[1], 0.0, length, [reach(ends.start), reach(ends.end)]),
+                extend(z[0], z[1], 0.0, wall.height, [CUT_OVERRUN; 2]),
+            ))
+        })
+        .collect();
+
+    // Curved walls are faceted, with facet edges at every opening jamb so
+    // the cutters' sides line up with the wall's
+    let mut stations = vec![0.0, length];
+    if let Some((_, _, sweep)) = wall.arc_geometry() {
+        let segments = (sweep / ARC_SEGMENT_ANGLE - 1e-9).ceil().max(1.0) as usize;
+        stations.extend((1..segments).map(|i| length * i as f64 / segments as f64));
+        stations.extend(cuts.iter().flat_map(|(_, u, _)| *u).filter(|u| *u > 0.0 && *u < length));
+    }
+    stations.sort_by(|a, b| a.total_cmp(b));
+    stations.dedup_by(|a, b| (*a - *b).abs() < STATION_TOLERANCE);
+
+    let half = thickness / 2.0;
+    let mut solid = wall_prism(wall, base_z, &stations, [-half, half], [0.0, wall.height], ends)?;
+
+    for (opening, u, z) in cuts {
+        let snap = |x: f64| stations.iter().copied().find(|s| (x - s).abs() < STATION_TOLERANCE).unwrap_or(x);
+        let u = [snap(u[0]), snap(u[1])];
+        let inside = stations
+            .iter()
+            .copied()
+            .filter(|s| *s > u[0] + STATION_TOLERANCE && *s < u[1] - STATION_TOLERANCE);
+        let cutter_stations: Vec<f64> = std::iter::once(u[0]).chain(inside).chain(std::iter::once(u[1])).collect();
+        let cutter = wall_prism(
+            wall,
+            base_z,
+            &cutter_stations,
+            [-half - CUT_OVERRUN, half + CUT_OVERRUN],
+            z,
+            &WallEndCuts::default(),
+        )?;
+        solid = subtract_solid(&solid, &cutter)
+            .map_err(|e| anyhow!("Failed to cut opening {:?}: {}", opening.id, e))?;
+    }
+
+    Ok(solid)
+}
+
+/// Prism over `stations` along the wall, spanning `v` and `z` in the
+/// wall's frame: a box on a straight wall, a faceted band on a curved one.
+/// The first and last stations' corners slide along the wall by `ends`.
+fn wall_prism(
+    wall: &Wall,
+    base_z: f64,
+    stations: &[f64],
+    v: [f64; 2],
+    z: [f64; 2],
+    ends: &WallEndCuts,
+) -> Result<Solid> {
+    let last = stations.len() - 1;
+    let place = |station: usize, side: usize, z: f64| {
+        let u = stations[station];
+        let p = wall.point_at(u);
+        let (tx, ty) = wall.tangent_at(u);
+        let slide = match station {
+            0 => -ends.start[side],
+            s if s == last => ends.end[side],
+            _ => 0.0,
+        };
+        TruckPoint3::new(
+            p.x - v[side] * ty + tx * slide,
+            p.y + v[side] * tx + ty * slide,
+            base_z + wall.base_offset + z,
+        )
+    };
+
+    // Plan outline: along the v0 side, then back along the v1 side, which
+    // reads counterclockwise from above
+    let outline: Vec<(usize, usize)> = (0..stations.len())
+        .map(|s| (s, 0))
+        .chain((0..stations.len()).rev().map(|s| (s, 1)))
+        .collect();
+
+    let n = outline.len();
+    let points: Vec<TruckPoint3> = z
+        .iter()
+        .flat_map(|&z| outline.iter().map(move |&(station, side)| place(station, side, z)))
+        .collect();
+
+    // The bottom loop is reversed to face down
+    let mut faces = vec![(0..n).rev().collect::<Vec<_>>(), (n..2 * n).collect()];
+    for a in 0..n {
+        let b = (a + 1) % n;
+        faces.push(vec![a, b, b + n, a + n]);
+    }
+
+    polyhedron(&points, &faces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LevelId, OpeningType, Point2, WallAssemblyId};
+    use crate::geometry::{solid_to_mesh, BoundingBox};
+
+    fn wall() -> Wall {
+        Wall::new(WallAssemblyId::new(), LevelId::new(), Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), 8.0)
+    }
+
+    #[test]
+    fn test_window_cuts_through_wall() {
+        let wall = wall();
+        let window = Opening::new(wall.id, OpeningType::Window, 0.3, 3.0, 4.0, 3.0);
+        let solid = generate_wall_solid(&wall, 0.5, 0.0, &[&window], &WallEndCuts::default()).unwrap();
+
+        let mesh = solid_to_mesh(&solid, 0.1).unwrap();
+        assert!((mesh.volume() - (80.0 - 12.0) * 0.5).abs() < 1e-6);
+
+        // The hole is centered 3' along the wall, between 3' and 7' up: a
+        // ray through it along +y hits no triangle
+        let hits = mesh.indices.chunks(3).filter(|t| {
+            let p = |i: u32| {
+                let i = i as usize * 3;
+                (mesh.positions[i] as f64, mesh.positions[i + 2] as f64)
+            };
+            let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+            let side = |p: (f64, f64), q: (f64, f64)| (q.0 - p.0) * (5.0 - p.1) - (q.1 - p.1) * (3.0 - p.0);
+            let (s0, s1, s2) = (side(a, b), side(b, c), side(c, a));
+            (s0 > 0.0 && s1 > 0.0 && s2 > 0.0) || (s0 < 0.0 && s1 < 0.0 && s2 < 0.0)
+        });
+        assert_eq!(hits.count(), 0);
+    }
+
+    #[test]
+    fn test_door_and_clipped_openings() {
+        let wall = wall().with_base_offset(1.0);
+        // A door from the floor, a window running off the wall's end and one
+        // entirely above the top plate
+        let door = Opening::new(wall.id, OpeningType::Door, 0.2, 3.0, 6.75, 0.0);
+        let window = Opening::new(wall.id, OpeningType::Window, 1.0, 4.0, 3.0, 4.0);
+        let above = Opening::new(wall.id, OpeningType::Window, 0.5, 2.0, 2.0, 9.0);
+        let solid = generate_wall_solid(&wall, 0.5, 10.0, &[&door, &window, &above], &WallEndCuts::default()).unwrap();
+
+        let mesh = solid_to_mesh(&solid, 0.1).unwrap();
+        let removed = 3.0 * 6.75 + 2.0 * 3.0;
+        assert!((mesh.volume() - (80.0 - removed) * 0.5).abs() < 1e-6);
+
+        let bounds = BoundingBox::from_solid(&solid);
+        assert!((bounds.min[2] - 11.0).abs() < 1e-9);
+        assert!((bounds.max[2] - 19.0).abs() < 1e-9);
+        assert!((bounds.max[0] - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_curved_wall_with_window() {
+        // Half circle of radius 5 bowing toward +y
+        let wall = wall().with_arc(5.0);
+        let plain = generate_wall_solid(&wall, 0.5, 0.0, &[], &WallEndCuts::default()).unwrap();
+        let bounds = BoundingBox::from_solid(&plain);
+        assert!((bounds.max[1] - 5.25).abs() < 1e-6);
+        assert!((bounds.min[0] + 0.25).abs() < 1e-6);
+
+        // Faceted, so slightly under the true annulus volume
+        let annulus = std::f64::consts::PI / 2.0 * (5.25 * 5.25 - 4.75 * 4.75) * 8.0;
+        let volume = solid_to_mesh(&plain, 0.1).unwrap().volume();
+        assert!(volume < annulus && volume > annulus * 0.99);
+
+        // A window 2' along the arc at the crown takes out 2' x 3' of wall
+        let window = Opening::new(wall.id, OpeningType::Window, 0.5, 2.0, 3.0, 3.0);
+        let cut = generate_wall_solid(&wall, 0.5, 0.0, &[&window], &WallEndCuts::default()).unwrap();
+        let removed = volume - solid_to_mesh(&cut, 0.1).unwrap().volume();
+        assert!((removed - 2.0 * 3.0 * 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_wall_without_openings() {
+        let wall = Wall::new(WallAssemblyId::new(), LevelId::new(), Point2::new(0.0, 0.0), Point2::new(0.0, 12.0), 9.0);
+        let solid = generate_wall_solid(&wall, 0.5, 0.0, &[], &WallEndCuts::default()).unwrap();
+        let bounds = BoundingBox::from_solid(&solid);
+        assert!((bounds.min[0] + 0.25).abs() < 1e-9);
+        assert!((bounds.max[0] - 0.25).abs() < 1e-9);
+        assert!((bounds.max[1] - 12.0).abs() < 1e-9);
+        let mesh = solid_to_mesh(&solid, 0.1).unwrap();
+        assert!((mesh.volume() - 12.0 * 9.0 * 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mitered_corner_with_door_at_end() {
+        let a = wall();
+        let b = Wall::new(WallAssemblyId::new(), LevelId::new(), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0), 8.0);
+        let network = crate::geometry::WallNetwork::solve(&[(&a, 0.5), (&b, 0.5)]);
+
+        // The outside face reaches the corner, and the miter keeps the
+        // wall's volume: the corner is shared without overlap
+        let solid = generate_wall_solid(&a, 0.5, 0.0, &[], &network.cuts(a.id)).unwrap();
+        let bounds = BoundingBox::from_solid(&solid);
+        assert!((bounds.max[0] - 10.25).abs() < 1e-9);
+        assert!((solid_to_mesh(&solid, 0.1).unwrap().volume() - 40.0).abs() < 1e-9);
+
+        // A door running into the corner cuts out to the mitered face
+        let door = Opening::new(a.id, OpeningType::Door, 0.9, 2.0, 7.0, 0.0);
+        let solid = generate_wall_solid(&a, 0.5, 0.0, &[&door], &network.cuts(a.id)).unwrap();
+        let bounds = BoundingBox::from_solid(&solid);
+        assert!((bounds.max[0] - 10.25).abs() < 1e-9);
+        let removed = 40.0 - solid_to_mesh(&solid, 0.1).unwrap().volume();
+        assert!((removed - 2.0 * 0.5 * 7.0).abs() < 1e-6, "removed {}", removed);
+    }
+}
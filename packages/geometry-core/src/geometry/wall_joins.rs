@@ -0,0 +1,267 @@
+// Wall join resolution
+// The walls on a level form a network. Ends that meet form a corner and
+// are mitered against each other; an end that stops against the side of
+// another wall forms a tee and is trimmed to that wall's face. Where three
+// or more ends meet, the two most nearly in line form the corner and the
+// rest tee into it. Joins are resolved in plan from each wall's centerline
+// and thickness.
+
+use std::collections::HashMap;
+
+use crate::domain::{Point2, Wall, WallId, WallJoin};
+
+/// Wall ends closer together than this meet (feet)
+const JOIN_TOLERANCE: f64 = 0.01;
+/// Longest miter, as a multiple of the wall's thickness; the faces of
+/// sharper corners stop at this length
+const MITER_LIMIT: f64 = 4.0;
+
+/// How far each face of a wall runs past the ends of its centerline,
+/// measured outward along the wall (negative when trimmed back). Faces are
+/// indexed by side: `[right, left]` looking from start to end.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WallEndCuts {
+    pub start: [f64; 2],
+    pub end: [f64; 2],
+}
+
+/// Resolved joins between the walls of one level
+#[derive(Debug, Clone, Default)]
+pub struct WallNetwork {
+    joins: HashMap<WallId, [WallJoin; 2]>,
+    cuts: HashMap<WallId, WallEndCuts>,
+}
+
+/// One end of a wall in plan
+struct WallEnd {
+    wall: usize,
+    at_end: bool,
+    point: Point2,
+    /// Unit direction out of the wall past this end
+    out: (f64, f64),
+    half: f64,
+}
+
+impl WallEnd {
+    /// Normal to the left of `out`
+    fn left(&self) -> (f64, f64) {
+        (-self.out.1, self.out.0)
+    }
+
+    /// Point on the face `side` (+1 left of `out`, -1 right) at this end
+    fn face_point(&self, side: f64) -> Point2 {
+        let (lx, ly) = self.left();
+        Point2::new(self.point.x + lx * self.half * side, self.point.y + ly * self.half * side)
+    }
+
+    /// Index into `WallEndCuts` of the face `side` of `out`
+    fn face_index(&self, side: f64) -> usize {
+        // Looking out past the start, the wall's right side is on the left
+        match (self.at_end, side > 0.0) {
+            (true, true) | (false, false) => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl WallNetwork {
+    /// Resolve joins between walls, each given with its thickness in feet
+    pub fn solve(walls: &[(&Wall, f64)]) -> Self {
+        let mut network = Self::default();
+        let mut ends = Vec::with_capacity(walls.len() * 2);
+        for (i, (wall, thickness)) in walls.iter().enumerate() {
+            network.joins.insert(wall.id, [WallJoin::Free; 2]);
+            network.cuts.insert(wall.id, WallEndCuts::default());
+            let length = wall.length();
+            if length <= JOIN_TOLERANCE {
+                continue;
+            }
+            let (sx, sy) = wall.tangent_at(0.0);
+            let (ex, ey) = wall.tangent_at(length);
+            ends.push(WallEnd { wall: i, at_end: false, point: wall.start, out: (-sx, -sy), half: thickness / 2.0 });
+            ends.push(WallEnd { wall: i, at_end: true, point: wall.end, out: (ex, ey), half: thickness / 2.0 });
+        }
+
+        // Group ends that meet
+        let mut nodes: Vec<Vec<usize>> = Vec::new();
+        for (i, end) in ends.iter().enumerate() {
+            match nodes.iter_mut().find(|node| ends[node[0]].point.distance_to(&end.point) <= JOIN_TOLERANCE) {
+                Some(node) => node.push(i),
+                None => nodes.push(vec![i]),
+            }
+        }
+
+        for node in nodes {
+            if node.len() == 1 {
+                network.join_to_side(walls, &ends[node[0]]);
+                continue;
+            }
+
+            // The two ends most nearly in line form the corner
+            let mut pair = (node[0], node[1]);
+            let mut best = f64::INFINITY;
+            for (k, &a) in node.iter().enumerate() {
+                for &b in &node[k + 1..] {
+                    let dot = ends[a].out.0 * ends[b].out.0 + ends[a].out.1 * ends[b].out.1;
+                    if dot < best {
+                        best = dot;
+                        pair = (a, b);
+                    }
+                }
+            }
+            let (a, b) = (&ends[pair.0], &ends[pair.1]);
+            network.set_join(walls, a, WallJoin::Corner { wall: walls[b.wall].0.id });
+            network.set_join(walls, b, WallJoin::Corner { wall: walls[a.wall].0.id });
+            // Walking in along one wall and out along the other keeps the
+            // same face on the left
+            for side in [1.0, -1.0] {
+                let (qa, qb) = (a.face_point(side), b.face_point(-side));
+                if let Some((ta, tb)) = line_intersection(&qa, a.out, &qb, b.out) {
+                    network.set_cut(walls, a, side, ta);
+                    network.set_cut(walls, b, -side, tb);
+                }
+            }
+
+            // Any others stop against the corner's first wall
+            for &stem in node.iter().filter(|&&e| e != pair.0 && e != pair.1) {
+                let (stem, host) = (&ends[stem], &ends[pair.0]);
+                let side = host.left().0 * -stem.out.0 + host.left().1 * -stem.out.1;
+                network.tee(walls, stem, walls[host.wall].0.id, &host.face_point(side.signum()), host.out);
+            }
+        }
+
+        network
+    }
+
+    /// Joins at the start and end of a wall
+    pub fn joins(&self, wall_id: WallId) -> [WallJoin; 2] {
+        self.joins.get(&wall_id).copied().unwrap_or_default()
+    }
+
+    /// Face extensions at each end of a wall
+    pub fn cuts(&self, wall_id: WallId) -> WallEndCuts {
+        self.cuts.get(&wall_id).copied().unwrap_or_default()
+    }
+
+    /// Tee a lone end into the side of the nearest wall it touches
+    fn join_to_side(&mut self, walls: &[(&Wall, f64)], end: &WallEnd) {
+        let host = walls
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != end.wall)
+            .filter_map(|(_, (wall, thickness))| {
+                let (along, offset) = wall.project(&end.point);
+                let inside = along > JOIN_TOLERANCE && along < wall.length() - JOIN_TOLERANCE;
+                (inside && offset.abs() <= thickness / 2.0 + JOIN_TOLERANCE).then_some((*wall, *thickness, along, offset))
+            })
+            .min_by(|a, b| a.3.abs().total_cmp(&b.3.abs()));
+
+        if let Some((wall, thickness, along, _)) = host {
+            // The face on the side the stem comes from
+            let p = wall.point_at(along);
+            let (tx, ty) = wall.tangent_at(along);
+            let side = (-ty * -end.out.0 + tx * -end.out.1).signum();
+            let face = Point2::new(p.x - ty * thickness / 2.0 * side, p.y + tx * thickness / 2.0 * side);
+            self.tee(walls, end, wall.id, &face, (tx, ty));
+        }
+    }
+
+    /// Trim both faces of a stem end to a host face line
+    fn tee(&mut self, walls: &[(&Wall, f64)], stem: &WallEnd, host: WallId, face: &Point2, direction: (f64, f64)) {
+        self.set_join(walls, stem, WallJoin::Tee { wall: host });
+        for side in [1.0, -1.0] {
+            if let Some((t, _)) = line_intersection(&stem.face_point(side), stem.out, face, direction) {
+                self.set_cut(walls, stem, side, t);
+            }
+        }
+    }
+
+    fn set_join(&mut self, walls: &[(&Wall, f64)], end: &WallEnd, join: WallJoin) {
+        if let Some(joins) = self.joins.get_mut(&walls[end.wall].0.id) {
+            joins[end.at_end as usize] = join;
+        }
+    }
+
+    fn set_cut(&mut self, walls: &[(&Wall, f64)], end: &WallEnd, side: f64, extension: f64) {
+        let limit = MITER_LIMIT * 2.0 * end.half;
+        if let Some(cuts) = self.cuts.get_mut(&walls[end.wall].0.id) {
+            let faces = if end.at_end { &mut cuts.end } else { &mut cuts.start };
+            faces[end.face_index(side)] = extension.clamp(-limit, limit);
+        }
+    }
+}
+
+/// Parameters along each line where `p + t*d` meets `q + s*e`, or None if
+/// they are parallel
+fn line_intersection(p: &Point2, d: (f64, f64), q: &Point2, e: (f64, f64)) -> Option<(f64, f64)> {
+    let denom = d.0 * e.1 - d.1 * e.0;
+    if denom.abs() < 1e-9 {
+        return None;
+    }
+    let (wx, wy) = (q.x - p.x, q.y - p.y);
+    Some(((wx * e.1 - wy * e.0) / denom, (wx * d.1 - wy * d.0) / denom))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LevelId, WallAssemblyId};
+
+    fn wall(x0: f64, y0: f64, x1: f64, y1: f64) -> Wall {
+        Wall::new(WallAssemblyId::new(), LevelId::new(), Point2::new(x0, y0), Point2::new(x1, y1), 8.0)
+    }
+
+    #[test]
+    fn test_corner_is_mitered() {
+        // L corner at (10, 0): along x, then up y, 0.5 thick
+        let a = wall(0.0, 0.0, 10.0, 0.0);
+        let b = wall(10.0, 0.0, 10.0, 10.0);
+        let network = WallNetwork::solve(&[(&a, 0.5), (&b, 0.5)]);
+
+        assert_eq!(network.joins(a.id), [WallJoin::Free, WallJoin::Corner { wall: b.id }]);
+        assert_eq!(network.joins(b.id), [WallJoin::Corner { wall: a.id }, WallJoin::Free]);
+
+        // Outside (right) faces run out to the corner, inside faces stop short
+        let cuts = network.cuts(a.id);
+        assert_eq!(cuts.start, [0.0, 0.0]);
+        assert!((cuts.end[0] - 0.25).abs() < 1e-10 && (cuts.end[1] + 0.25).abs() < 1e-10);
+        let cuts = network.cuts(b.id);
+        assert!((cuts.start[0] - 0.25).abs() < 1e-10 && (cuts.start[1] + 0.25).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_tee_stops_at_face() {
+        // Partition running up from the middle of a 1' thick wall
+        let host = wall(0.0, 0.0, 10.0, 0.0);
+        let stem = wall(5.0, 0.0, 5.0, 6.0);
+        let network = WallNetwork::solve(&[(&host, 1.0), (&stem, 0.5)]);
+
+        assert_eq!(network.joins(host.id), [WallJoin::Free; 2]);
+        assert_eq!(network.joins(stem.id)[0], WallJoin::Tee { wall: host.id });
+        assert_eq!(network.cuts(host.id), WallEndCuts::default());
+        let cuts = network.cuts(stem.id);
+        assert!((cuts.start[0] + 0.5).abs() < 1e-10 && (cuts.start[1] + 0.5).abs() < 1e-10);
+
+        // A stem drawn to the host's face joins the same way
+        let stem = wall(5.0, 0.5, 5.0, 6.0);
+        let network = WallNetwork::solve(&[(&host, 1.0), (&stem, 0.5)]);
+        assert_eq!(network.joins(stem.id)[0], WallJoin::Tee { wall: host.id });
+        assert!(network.cuts(stem.id).start[0].abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_three_way_junction() {
+        // Two walls in line with a third meeting them from below
+        let left = wall(0.0, 0.0, 5.0, 0.0);
+        let right = wall(5.0, 0.0, 10.0, 0.0);
+        let stem = wall(5.0, -6.0, 5.0, 0.0);
+        let network = WallNetwork::solve(&[(&left, 0.5), (&right, 0.5), (&stem, 0.5)]);
+
+        assert_eq!(network.joins(left.id)[1], WallJoin::Corner { wall: right.id });
+        assert_eq!(network.joins(right.id)[0], WallJoin::Corner { wall: left.id });
+        assert_eq!(network.joins(stem.id)[1], WallJoin::Tee { wall: left.id });
+        assert_eq!(network.cuts(left.id).end, [0.0, 0.0]);
+        let cuts = network.cuts(stem.id);
+        assert!((cuts.end[0] + 0.25).abs() < 1e-10 && (cuts.end[1] + 0.25).abs() < 1e-10);
+    }
+}
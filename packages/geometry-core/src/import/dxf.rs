@@ -0,0 +1,290 @@
+// DXF import - footprints and walls from a plan drawing
+// Closed polylines become footprints, and lines or open polylines on any
+// layer whose name contains "WALL" become wall centerlines (one wall per
+// segment) at the level's floor-to-floor height. Closed polylines on wall
+// layers are wall outlines, which can't be turned back into centerlines,
+// so they are skipped. Arcs and polyline bulges are not read. Drawing
+// units come from $INSUNITS; unitless drawings are taken as feet.
+
+use anyhow::{anyhow, Result};
+
+use super::{add_footprints, ring_polygon, ImportSummary};
+use crate::domain::{LevelId, Point2};
+use crate::store::Store;
+
+/// An entity's type and its group code / value pairs
+struct Entity<'a> {
+    kind: &'a str,
+    pairs: Vec<(i32, &'a str)>,
+}
+
+impl<'a> Entity<'a> {
+    fn value(&self, code: i32) -> Option<&'a str> {
+        self.pairs.iter().find(|(c, _)| *c == code).map(|(_, v)| *v)
+    }
+
+    fn number(&self, code: i32) -> f64 {
+        self.value(code).and_then(|v| v.parse().ok()).unwrap_or(0.0)
+    }
+
+    fn layer(&self) -> &'a str {
+        self.value(8).unwrap_or("0")
+    }
+
+    /// Points from each 10/20 pair, in order
+    fn points(&self) -> Vec<Point2> {
+        let mut points = Vec::new();
+        let mut x = None;
+        for (code, value) in &self.pairs {
+            match code {
+                10 => x = value.parse::<f64>().ok(),
+                20 => {
+                    if let (Some(px), Ok(py)) = (x.take(), value.parse::<f64>()) {
+                        points.push(Point2::new(px, py));
+                    }
+                }
+                _ => {}
+            }
+        }
+        points
+    }
+}
+
+/// A polyline gathered from LWPOLYLINE or POLYLINE/VERTEX entities
+struct Polyline<'a> {
+    layer: &'a str,
+    points: Vec<Point2>,
+    closed: bool,
+}
+
+/// Import footprints and walls from DXF text onto a level
+pub fn import_dxf(store: &mut Store, level_id: LevelId, source: &str) -> Result<ImportSummary> {
+    let level = store.get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+    let height = level.floor_to_floor;
+
+    let pairs = read_pairs(source)?;
+    let mut summary = ImportSummary::default();
+    let scale = match header_value(&pairs, "$INSUNITS") {
+        None | Some(0) | Some(2) => 1.0,
+        Some(1) => 1.0 / 12.0,
+        Some(4) => 1.0 / 304.8,
+        Some(5) => 1.0 / 30.48,
+        Some(6) => 1.0 / 0.3048,
+        Some(units) => {
+            summary.warnings.push(format!("Unsupported $INSUNITS {}, reading the drawing as feet", units));
+            1.0
+        }
+    };
+    let scaled = |points: Vec<Point2>| -> Vec<Point2> {
+        points.into_iter().map(|p| Point2::new(p.x * scale, p.y * scale)).collect()
+    };
+
+    let entities = read_entities(&pairs);
+    let mut lines: Vec<(Point2, Point2)> = Vec::new();
+    let mut polylines: Vec<Polyline> = Vec::new();
+    let mut i = 0;
+    while i < entities.len() {
+        let entity = &entities[i];
+        match entity.kind {
+            "LINE" => {
+                if is_wall_layer(entity.layer()) {
+                    lines.push((
+                        Point2::new(entity.number(10) * scale, entity.number(20) * scale),
+                        Point2::new(entity.number(11) * scale, entity.number(21) * scale),
+                    ));
+                }
+            }
+            "LWPOLYLINE" => polylines.push(Polyline {
+                layer: entity.layer(),
+                points: scaled(entity.points()),
+                closed: entity.number(70) as i64 & 1 == 1,
+            }),
+            "POLYLINE" => {
+                let mut points = Vec::new();
+                while let Some(vertex) = entities.get(i + 1).filter(|e| e.kind == "VERTEX") {
+                    points.push(Point2::new(vertex.number(10), vertex.number(20)));
+                    i += 1;
+                }
+                polylines.push(Polyline {
+                    layer: entity.layer(),
+                    points: scaled(points),
+                    closed: entity.number(70) as i64 & 1 == 1,
+                });
+            }
+            "VERTEX" | "SEQEND" => {}
+            kind => summary.warnings.push(format!("Skipped {} on layer {}", kind, entity.layer())),
+        }
+        i += 1;
+    }
+
+    let mut footprints = Vec::new();
+    for polyline in polylines {
+        match (is_wall_layer(polyline.layer), polyline.closed) {
+            (true, true) => summary.warnings.push(format!(
+                "Skipped closed polyline on wall layer {}: draw walls as centerlines",
+                polyline.layer
+            )),
+            (true, false) => lines.extend(polyline.points.windows(2).map(|w| (w[0], w[1]))),
+            (false, true) => match ring_polygon(&polyline.points) {
+                Some(polygon) => footprints.push(polygon),
+                None => summary.warnings.push(format!("Skipped degenerate polyline on layer {}", polyline.layer)),
+            },
+            (false, false) => summary.warnings.push(format!("Skipped open polyline on layer {}", polyline.layer)),
+        }
+    }
+
+    store.transaction(|store| {
+        // Footprints go in first so wall assemblies are picked against them
+        add_footprints(store, level_id, footprints, &mut summary)?;
+        for (start, end) in lines {
+            if start.distance_to(&end) < 1e-6 {
+                summary.warnings.push("Skipped zero-length wall line".to_string());
+                continue;
+            }
+            summary.wall_ids.push(store.create_wall_auto(level_id, start, end, height)?);
+        }
+        Ok(())
+    })?;
+    Ok(summary)
+}
+
+fn is_wall_layer(layer: &str) -> bool {
+    layer.to_uppercase().contains("WALL")
+}
+
+/// Group code / value pairs, values trimmed
+fn read_pairs(source: &str) -> Result<Vec<(i32, &str)>> {
+    let lines: Vec<&str> = source.lines().collect();
+    lines
+        .chunks(2)
+        .filter(|chunk| !(chunk.len() == 1 && chunk[0].trim().is_empty()))
+        .map(|chunk| {
+            let code = chunk[0].trim();
+            let value = chunk.get(1).ok_or_else(|| anyhow!("DXF ends after group code {}", code))?;
+            let code = code.parse().map_err(|_| anyhow!("Invalid DXF group code: {:?}", code))?;
+            Ok((code, value.trim()))
+        })
+        .collect()
+}
+
+/// Integer value of a header variable
+fn header_value(pairs: &[(i32, &str)], name: &str) -> Option<i64> {
+    let at = pairs.iter().position(|&(code, value)| code == 9 && value == name)?;
+    pairs.get(at + 1).and_then(|(_, value)| value.parse().ok())
+}
+
+/// Entities of the ENTITIES section
+fn read_entities<'a>(pairs: &[(i32, &'a str)]) -> Vec<Entity<'a>> {
+    let start = pairs
+        .windows(2)
+        .position(|w| w[0] == (0, "SECTION") && w[1] == (2, "ENTITIES"));
+    let Some(start) = start else {
+        return Vec::new();
+    };
+    let mut entities: Vec<Entity<'a>> = Vec::new();
+    for &(code, value) in &pairs[start + 2..] {
+        if code == 0 {
+            if value == "ENDSEC" {
+                break;
+            }
+            entities.push(Entity { kind: value, pairs: Vec::new() });
+        } else if let Some(entity) = entities.last_mut() {
+            entity.pairs.push((code, value));
+        }
+    }
+    entities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    /// DXF text from (kind, pairs) entities
+    fn dxf(insunits: i32, entities: &[(&str, Vec<(i32, String)>)]) -> String {
+        let mut out = format!("0\nSECTION\n2\nHEADER\n9\n$INSUNITS\n70\n{}\n0\nENDSEC\n0\nSECTION\n2\nENTITIES\n", insunits);
+        for (kind, pairs) in entities {
+            out.push_str(&format!("0\n{}\n", kind));
+            for (code, value) in pairs {
+                out.push_str(&format!("{}\n{}\n", code, value));
+            }
+        }
+        out.push_str("0\nENDSEC\n0\nEOF\n");
+        out
+    }
+
+    fn points(layer: &str, closed: bool, ring: &[(f64, f64)]) -> Vec<(i32, String)> {
+        let mut pairs = vec![(8, layer.to_string()), (90, ring.len().to_string()), (70, (closed as i32).to_string())];
+        for (x, y) in ring {
+            pairs.push((10, x.to_string()));
+            pairs.push((20, y.to_string()));
+        }
+        pairs
+    }
+
+    #[test]
+    fn test_import_dxf() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+
+        // Inches: a clockwise 30' x 20' house with a 10' x 8' porch, two
+        // walls as a line and an open polyline, and a stray circle
+        let source = dxf(1, &[
+            ("LWPOLYLINE", points("OUTLINE", true, &[(0.0, 0.0), (0.0, 240.0), (360.0, 240.0), (360.0, 0.0)])),
+            ("LWPOLYLINE", points("OUTLINE", true, &[(360.0, 0.0), (480.0, 0.0), (480.0, 96.0), (360.0, 96.0), (360.0, 0.0)])),
+            ("LINE", vec![(8, "A-WALL".into()), (10, "0".into()), (20, "0".into()), (11, "360".into()), (21, "0".into())]),
+            ("LWPOLYLINE", points("A-WALL", false, &[(360.0, 0.0), (360.0, 240.0), (0.0, 240.0)])),
+            ("LWPOLYLINE", points("A-WALL", true, &[(0.0, 0.0), (6.0, 0.0), (6.0, 6.0)])),
+            ("CIRCLE", vec![(8, "0".into()), (10, "0".into()), (20, "0".into()), (40, "1".into())]),
+        ]);
+        let summary = import_dxf(&mut store, level_id, &source).unwrap();
+
+        assert_eq!(summary.footprint_ids.len(), 2);
+        let primary = store.get_level_footprint(level_id).unwrap();
+        assert!((primary.polygon.area() - 600.0).abs() < 1e-9);
+        assert!(primary.polygon.signed_area() > 0.0);
+        assert_eq!(store.get_level_footprints(level_id).len(), 2);
+
+        assert_eq!(summary.wall_ids.len(), 3);
+        let wall = store.get_wall(summary.wall_ids[0]).unwrap();
+        assert!((wall.end.x - 30.0).abs() < 1e-9);
+        assert_eq!(wall.height, 9.0);
+        assert_eq!(summary.warnings.len(), 2);
+
+        assert!(import_dxf(&mut store, LevelId::new(), &source).is_err());
+        assert!(import_dxf(&mut store, level_id, "0\nSECTION\nbad").is_err());
+    }
+
+    #[test]
+    fn test_plan_dxf_round_trip_skips_wall_outlines() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(20.0, 12.0))
+            .unwrap();
+        let exported = crate::plan2d::render_plan_dxf(&store, level_id).unwrap();
+
+        let target = store.add_level(building_id, "Second Floor", 9.0, 8.0).unwrap();
+        let summary = import_dxf(&mut store, target, &exported).unwrap();
+        // The room outline comes back as a footprint; wall outlines don't
+        assert_eq!(summary.footprint_ids.len(), 1);
+        let footprint = store.get_level_footprint(target).unwrap();
+        assert!((footprint.polygon.area() - 240.0).abs() < 1e-6);
+        assert!(summary.wall_ids.is_empty());
+    }
+}
@@ -0,0 +1,804 @@
+// IFC import - storeys, walls, slabs and spaces from an IFC file (SPF text)
+// Each IfcBuildingStorey becomes a level of one new building. Walls are
+// read from their 'Axis' polyline, or else the length of a rectangular
+// 'Body' extrusion; their material layer set becomes a wall assembly.
+// Slab and space extrusion profiles become footprints and rooms.
+// Placements are taken as plan rotations only (z up), which covers
+// what authoring tools write for walls, slabs and spaces.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use super::{add_footprints, ring_polygon, ImportSummary};
+use crate::domain::{LevelId, Point2, Polygon2, ProjectId, RoomType, WallAssemblyId, WallLayer};
+use crate::store::Store;
+
+const METERS_TO_FEET: f64 = 1.0 / 0.3048;
+/// Floor-to-floor for the top storey when nothing on it says otherwise
+const DEFAULT_STOREY_HEIGHT: f64 = 9.0;
+
+/// Layer set name and its (material name, thickness in inches) layers
+type LayerSet = (Option<String>, Vec<(String, f64)>);
+
+/// Attribute value in an SPF entity
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Ref(usize),
+    Number(f64),
+    Str(String),
+    Enum(String),
+    List(Vec<Value>),
+    /// `IFCLABEL('x')` and other typed values
+    Typed(String, Box<Value>),
+    Null,
+}
+
+impl Value {
+    fn as_ref(&self) -> Option<usize> {
+        match self {
+            Value::Ref(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            Value::Typed(_, inner) => inner.as_number(),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            Value::Typed(_, inner) => inner.as_str(),
+            _ => None,
+        }
+    }
+
+    fn as_enum(&self) -> Option<&str> {
+        match self {
+            Value::Enum(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_list(&self) -> &[Value] {
+        match self {
+            Value::List(items) => items,
+            _ => &[],
+        }
+    }
+}
+
+/// Entity name (upper case) and attributes
+#[derive(Debug)]
+struct Record {
+    name: String,
+    args: Vec<Value>,
+}
+
+impl Record {
+    fn arg(&self, index: usize) -> &Value {
+        self.args.get(index).unwrap_or(&Value::Null)
+    }
+}
+
+/// Plan placement: translation, elevation and rotation
+#[derive(Debug, Clone, Copy)]
+struct Transform {
+    x: f64,
+    y: f64,
+    z: f64,
+    cos: f64,
+    sin: f64,
+}
+
+impl Transform {
+    const IDENTITY: Self = Self { x: 0.0, y: 0.0, z: 0.0, cos: 1.0, sin: 0.0 };
+
+    fn apply(&self, p: Point2) -> Point2 {
+        Point2::new(self.x + self.cos * p.x - self.sin * p.y, self.y + self.sin * p.x + self.cos * p.y)
+    }
+
+    /// `local` expressed in this transform's parent frame
+    fn then(&self, local: &Transform) -> Transform {
+        let origin = self.apply(Point2::new(local.x, local.y));
+        Transform {
+            x: origin.x,
+            y: origin.y,
+            z: self.z + local.z,
+            cos: self.cos * local.cos - self.sin * local.sin,
+            sin: self.sin * local.cos + self.cos * local.sin,
+        }
+    }
+}
+
+/// Parsed DATA section with the file's length unit
+struct IfcModel {
+    records: HashMap<usize, Record>,
+    /// Feet per file length unit
+    scale: f64,
+}
+
+impl IfcModel {
+    fn get(&self, id: usize) -> Option<&Record> {
+        self.records.get(&id)
+    }
+
+    fn get_ref(&self, value: &Value) -> Option<&Record> {
+        value.as_ref().and_then(|id| self.get(id))
+    }
+
+    fn all<'a>(&'a self, names: &'a [&str]) -> impl Iterator<Item = (usize, &'a Record)> + 'a {
+        let mut ids: Vec<usize> = self.records
+            .iter()
+            .filter(|(_, r)| names.contains(&r.name.as_str()))
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids.into_iter().map(|id| (id, &self.records[&id]))
+    }
+
+    /// Cartesian point's coordinates in feet
+    fn point(&self, value: &Value) -> Option<(f64, f64, f64)> {
+        let record = self.get_ref(value).filter(|r| r.name == "IFCCARTESIANPOINT")?;
+        let coords: Vec<f64> = record.arg(0).as_list().iter().filter_map(Value::as_number).collect();
+        let c = |i: usize| coords.get(i).copied().unwrap_or(0.0) * self.scale;
+        Some((c(0), c(1), c(2)))
+    }
+
+    /// Direction as a unit plan vector
+    fn direction(&self, value: &Value) -> Option<(f64, f64)> {
+        let record = self.get_ref(value).filter(|r| r.name == "IFCDIRECTION")?;
+        let ratios: Vec<f64> = record.arg(0).as_list().iter().filter_map(Value::as_number).collect();
+        let (x, y) = (ratios.first().copied()?, ratios.get(1).copied().unwrap_or(0.0));
+        let len = (x * x + y * y).sqrt();
+        (len > 1e-12).then(|| (x / len, y / len))
+    }
+
+    /// IfcAxis2Placement2D/3D as a transform
+    fn axis_placement(&self, value: &Value) -> Transform {
+        let Some(record) = self.get_ref(value) else {
+            return Transform::IDENTITY;
+        };
+        let (x, y, z) = self.point(record.arg(0)).unwrap_or((0.0, 0.0, 0.0));
+        let ref_direction = if record.name == "IFCAXIS2PLACEMENT2D" { record.arg(1) } else { record.arg(2) };
+        let (cos, sin) = self.direction(ref_direction).unwrap_or((1.0, 0.0));
+        Transform { x, y, z, cos, sin }
+    }
+
+    /// World transform of an IfcLocalPlacement chain
+    fn placement(&self, value: &Value) -> Transform {
+        let Some(record) = self.get_ref(value).filter(|r| r.name == "IFCLOCALPLACEMENT") else {
+            return Transform::IDENTITY;
+        };
+        self.placement(record.arg(0)).then(&self.axis_placement(record.arg(1)))
+    }
+
+    /// Items of a product's representation with the given identifier
+    fn representation_items(&self, product: &Record, identifier: &str) -> Vec<&Record> {
+        let Some(shape) = self.get_ref(product.arg(6)) else {
+            return Vec::new();
+        };
+        shape
+            .arg(2)
+            .as_list()
+            .iter()
+            .filter_map(|r| self.get_ref(r))
+            .filter(|r| r.arg(1).as_str() == Some(identifier))
+            .flat_map(|r| r.arg(3).as_list().iter().filter_map(|item| self.get_ref(item)))
+            .collect()
+    }
+
+    /// First swept solid in a product's body: profile record, the solid's
+    /// position and its depth in feet
+    fn extrusion(&self, product: &Record) -> Option<(&Record, Transform, f64)> {
+        let solid = self
+            .representation_items(product, "Body")
+            .into_iter()
+            .find(|r| r.name == "IFCEXTRUDEDAREASOLID")?;
+        let profile = self.get_ref(solid.arg(0))?;
+        let depth = solid.arg(3).as_number()? * self.scale;
+        Some((profile, self.axis_placement(solid.arg(1)), depth))
+    }
+
+    /// Polyline as points in feet
+    fn polyline(&self, value: &Value) -> Option<Vec<Point2>> {
+        self.get_ref(value).and_then(|record| self.polyline_points(record))
+    }
+
+    fn polyline_points(&self, record: &Record) -> Option<Vec<Point2>> {
+        if record.name != "IFCPOLYLINE" {
+            return None;
+        }
+        record
+            .arg(0)
+            .as_list()
+            .iter()
+            .map(|p| self.point(p).map(|(x, y, _)| Point2::new(x, y)))
+            .collect()
+    }
+
+    /// Profile outline and holes in its own coordinates
+    fn profile(&self, profile: &Record) -> Option<(Vec<Point2>, Vec<Vec<Point2>>)> {
+        match profile.name.as_str() {
+            "IFCARBITRARYCLOSEDPROFILEDEF" => Some((self.polyline(profile.arg(2))?, Vec::new())),
+            "IFCARBITRARYPROFILEDEFWITHVOIDS" => {
+                let holes = profile.arg(3).as_list().iter().filter_map(|c| self.polyline(c)).collect();
+                Some((self.polyline(profile.arg(2))?, holes))
+            }
+            "IFCRECTANGLEPROFILEDEF" => {
+                let position = self.axis_placement(profile.arg(2));
+                let (hx, hy) = (
+                    profile.arg(3).as_number()? * self.scale / 2.0,
+                    profile.arg(4).as_number()? * self.scale / 2.0,
+                );
+                let corners = [(-hx, -hy), (hx, -hy), (hx, hy), (-hx, hy)];
+                Some((corners.iter().map(|&(x, y)| position.apply(Point2::new(x, y))).collect(), Vec::new()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Plan polygon and height of a product's body extrusion
+    fn extruded_polygon(&self, product: &Record) -> Option<(Polygon2, f64)> {
+        let (profile, position, depth) = self.extrusion(product)?;
+        let (outer, holes) = self.profile(profile)?;
+        let world = self.placement(product.arg(5)).then(&position);
+        let to_world = |ring: Vec<Point2>| -> Vec<Point2> { ring.into_iter().map(|p| world.apply(p)).collect() };
+        let mut polygon = ring_polygon(&to_world(outer))?;
+        polygon.holes = holes
+            .into_iter()
+            .filter_map(|hole| ring_polygon(&to_world(hole)))
+            .map(|mut hole| {
+                hole.outer.reverse();
+                hole.outer
+            })
+            .collect();
+        Some((polygon, depth))
+    }
+
+    /// Wall centerline and height
+    fn wall_line(&self, wall: &Record) -> Option<(Point2, Point2, Option<f64>)> {
+        let placement = self.placement(wall.arg(5));
+        let extrusion = self.extrusion(wall);
+        let height = extrusion.map(|(_, _, depth)| depth);
+
+        let axis = self
+            .representation_items(wall, "Axis")
+            .into_iter()
+            .find_map(|item| self.polyline_points(item));
+        if let Some(points) = axis.filter(|p| p.len() >= 2) {
+            return Some((placement.apply(points[0]), placement.apply(points[points.len() - 1]), height));
+        }
+
+        // No axis: a rectangular body runs along its profile's x axis
+        let (profile, position, depth) = extrusion?;
+        if profile.name != "IFCRECTANGLEPROFILEDEF" {
+            return None;
+        }
+        let local = self.axis_placement(profile.arg(2));
+        let half = profile.arg(3).as_number()? * self.scale / 2.0;
+        let world = placement.then(&position).then(&local);
+        Some((world.apply(Point2::new(-half, 0.0)), world.apply(Point2::new(half, 0.0)), Some(depth)))
+    }
+
+    /// Material layers (name, thickness in inches) and set name for each
+    /// element with a layer set
+    fn layer_sets(&self) -> HashMap<usize, LayerSet> {
+        let mut sets = HashMap::new();
+        for (_, rel) in self.all(&["IFCRELASSOCIATESMATERIAL"]) {
+            let Some(mut material) = self.get_ref(rel.arg(5)) else {
+                continue;
+            };
+            if material.name == "IFCMATERIALLAYERSETUSAGE" {
+                match self.get_ref(material.arg(0)) {
+                    Some(set) => material = set,
+                    None => continue,
+                }
+            }
+            if material.name != "IFCMATERIALLAYERSET" {
+                continue;
+            }
+            let layers: Vec<(String, f64)> = material
+                .arg(0)
+                .as_list()
+                .iter()
+                .filter_map(|l| self.get_ref(l))
+                .filter_map(|layer| {
+                    let name = self
+                        .get_ref(layer.arg(0))
+                        .and_then(|m| m.arg(0).as_str())
+                        .unwrap_or("Layer")
+                        .to_string();
+                    Some((name, layer.arg(1).as_number()? * self.scale * 12.0))
+                })
+                .filter(|(_, thickness)| *thickness > 0.0)
+                .collect();
+            let name = material.arg(1).as_str().map(str::to_string);
+            for object in rel.arg(4).as_list().iter().filter_map(Value::as_ref) {
+                sets.insert(object, (name.clone(), layers.clone()));
+            }
+        }
+        sets
+    }
+}
+
+/// Import an IFC file's storeys, walls, slabs and spaces into a project as
+/// a new building
+pub fn import_ifc(store: &mut Store, project_id: ProjectId, source: &str) -> Result<ImportSummary> {
+    store.get_project(project_id)
+        .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+    let model = parse_model(source)?;
+    let mut summary = ImportSummary::default();
+
+    // Storeys and what each one holds
+    let mut storeys: Vec<(usize, String, f64)> = model
+        .all(&["IFCBUILDINGSTOREY"])
+        .map(|(id, storey)| {
+            let name = storey.arg(2).as_str().unwrap_or("Level").to_string();
+            let elevation = storey
+                .arg(9)
+                .as_number()
+                .map(|e| e * model.scale)
+                .unwrap_or_else(|| model.placement(storey.arg(5)).z);
+            (id, name, elevation)
+        })
+        .collect();
+    if storeys.is_empty() {
+        return Err(anyhow!("IFC file has no building storeys"));
+    }
+    storeys.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+    let mut storey_of: HashMap<usize, usize> = HashMap::new();
+    for (_, rel) in model.all(&["IFCRELCONTAINEDINSPATIALSTRUCTURE"]) {
+        if let Some(storey) = rel.arg(5).as_ref() {
+            for element in rel.arg(4).as_list().iter().filter_map(Value::as_ref) {
+                storey_of.insert(element, storey);
+            }
+        }
+    }
+    for (_, rel) in model.all(&["IFCRELAGGREGATES"]) {
+        if let Some(parent) = rel.arg(4).as_ref() {
+            for part in rel.arg(5).as_list().iter().filter_map(Value::as_ref) {
+                storey_of.entry(part).or_insert(parent);
+            }
+        }
+    }
+
+    let building_name = model
+        .all(&["IFCBUILDING"])
+        .find_map(|(_, b)| b.arg(2).as_str().map(str::to_string))
+        .unwrap_or_else(|| "Imported Building".to_string());
+    let layer_sets = model.layer_sets();
+
+    store.transaction(|store| {
+        let building_id = store.add_building(project_id, building_name)?;
+        let mut levels: HashMap<usize, LevelId> = HashMap::new();
+        for (i, (id, name, elevation)) in storeys.iter().enumerate() {
+            let wall_height = model
+                .all(&["IFCWALL", "IFCWALLSTANDARDCASE"])
+                .filter(|(wall, _)| storey_of.get(wall) == Some(id))
+                .filter_map(|(_, wall)| model.wall_line(wall).and_then(|(_, _, h)| h))
+                .fold(None, |max: Option<f64>, h| Some(max.map_or(h, |m| m.max(h))));
+            let floor_to_floor = match storeys.get(i + 1) {
+                Some(next) if next.2 > *elevation => next.2 - elevation,
+                _ => wall_height.unwrap_or(DEFAULT_STOREY_HEIGHT),
+            };
+            let level_id = store.add_level(building_id, name.clone(), *elevation, floor_to_floor)?;
+            levels.insert(*id, level_id);
+            summary.level_ids.push(level_id);
+        }
+        let level_for = |element: usize| storey_of.get(&element).and_then(|s| levels.get(s)).copied();
+
+        // Slabs as footprints, grouped by level
+        let mut footprints: HashMap<LevelId, Vec<Polygon2>> = HashMap::new();
+        for (id, slab) in model.all(&["IFCSLAB"]) {
+            if slab.arg(8).as_enum() == Some("ROOF") {
+                continue;
+            }
+            match (level_for(id), model.extruded_polygon(slab)) {
+                (Some(level_id), Some((mut polygon, _))) => {
+                    polygon.holes.clear();
+                    footprints.entry(level_id).or_default().push(polygon);
+                }
+                _ => summary.warnings.push(format!("Skipped slab #{}: no storey or extruded profile", id)),
+            }
+        }
+        for level_id in &summary.level_ids.clone() {
+            if let Some(polygons) = footprints.remove(level_id) {
+                add_footprints(store, *level_id, polygons, &mut summary)?;
+            }
+        }
+
+        let mut assemblies: HashMap<String, WallAssemblyId> = HashMap::new();
+        for (id, wall) in model.all(&["IFCWALL", "IFCWALLSTANDARDCASE"]) {
+            let (Some(level_id), Some((start, end, height))) = (level_for(id), model.wall_line(wall)) else {
+                summary.warnings.push(format!("Skipped wall #{}: no storey or axis", id));
+                continue;
+            };
+            if start.distance_to(&end) < 1e-6 {
+                summary.warnings.push(format!("Skipped wall #{}: zero length", id));
+                continue;
+            }
+            let height = height.unwrap_or_else(|| store.get_level(level_id).map_or(DEFAULT_STOREY_HEIGHT, |l| l.floor_to_floor));
+            let wall_id = match layer_sets.get(&id).filter(|(_, layers)| !layers.is_empty()) {
+                Some((name, layers)) => {
+                    let assembly_id = imported_assembly(store, &mut assemblies, name.as_deref(), layers)?;
+                    store.create_wall(level_id, assembly_id, start, end, height)?
+                }
+                None => store.create_wall_auto(level_id, start, end, height)?,
+            };
+            summary.wall_ids.push(wall_id);
+        }
+
+        for (id, space) in model.all(&["IFCSPACE"]) {
+            let (Some(level_id), Some((polygon, _))) = (level_for(id), model.extruded_polygon(space)) else {
+                summary.warnings.push(format!("Skipped space #{}: no storey or extruded profile", id));
+                continue;
+            };
+            let name = space.arg(2).as_str().unwrap_or("Room").to_string();
+            let kind = space.arg(7).as_str().or(space.arg(4).as_str()).unwrap_or(&name);
+            let room_type = match RoomType::from_str(&kind.replace(' ', "_")) {
+                RoomType::Other(_) => RoomType::Other(kind.to_string()),
+                room_type => room_type,
+            };
+            summary.room_ids.push(store.create_room(level_id, room_type, name, polygon)?);
+        }
+        Ok(())
+    })?;
+    Ok(summary)
+}
+
+/// Assembly for an IFC layer set, shared by walls with the same set and
+/// reusing a store assembly with the same name and layers
+fn imported_assembly(
+    store: &mut Store,
+    created: &mut HashMap<String, WallAssemblyId>,
+    name: Option<&str>,
+    layers: &[(String, f64)],
+) -> Result<WallAssemblyId> {
+    let total: f64 = layers.iter().map(|(_, t)| t).sum();
+    let name = name.map(str::to_string).unwrap_or_else(|| format!("Imported {:.2}\" wall", total));
+    if let Some(id) = created.get(&name) {
+        return Ok(*id);
+    }
+
+    let existing = store.list_wall_assemblies().into_iter().find(|a| {
+        a.name == name
+            && a.layers.len() == layers.len()
+            && a.layers.iter().zip(layers).all(|(l, (m, t))| l.material == *m && (l.thickness - t).abs() < 1e-3)
+    });
+    let id = match existing {
+        Some(assembly) => assembly.id,
+        None => {
+            // The thickest layer is taken as the structural one
+            let thickest = layers
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1 .1.total_cmp(&b.1 .1))
+                .map(|(i, _)| i);
+            let layers = layers
+                .iter()
+                .enumerate()
+                .map(|(i, (material, thickness))| WallLayer::new(material.clone(), *thickness, Some(i) == thickest))
+                .collect();
+            store.create_wall_assembly(name.clone(), layers)?
+        }
+    };
+    created.insert(name, id);
+    Ok(id)
+}
+
+/// Parse the DATA section and work out the length unit
+fn parse_model(source: &str) -> Result<IfcModel> {
+    let data_start = source
+        .find("DATA;")
+        .ok_or_else(|| anyhow!("Not an IFC file: no DATA section"))?;
+    let mut records = HashMap::new();
+    for statement in split_statements(&source[data_start + 5..]) {
+        let statement = statement.trim();
+        if statement.is_empty() || statement == "ENDSEC" {
+            break;
+        }
+        let Some(rest) = statement.strip_prefix('#') else {
+            continue;
+        };
+        let (id, body) = rest
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Invalid IFC entity: {}", statement))?;
+        let id: usize = id.trim().parse().map_err(|_| anyhow!("Invalid IFC entity id: {}", id))?;
+        let body = body.trim();
+        let open = body.find('(').ok_or_else(|| anyhow!("Invalid IFC entity: {}", statement))?;
+        let mut parser = Parser { chars: body[open..].chars().collect(), at: 0 };
+        let args = match parser.value()? {
+            Value::List(args) => args,
+            _ => return Err(anyhow!("Invalid IFC entity: {}", statement)),
+        };
+        records.insert(id, Record { name: body[..open].trim().to_uppercase(), args });
+    }
+
+    let mut model = IfcModel { records, scale: METERS_TO_FEET };
+    model.scale = length_unit(&model).unwrap_or(METERS_TO_FEET);
+    Ok(model)
+}
+
+/// Feet per length unit, from the project's unit assignment
+fn length_unit(model: &IfcModel) -> Option<f64> {
+    model.all(&["IFCSIUNIT", "IFCCONVERSIONBASEDUNIT"]).find_map(|(_, unit)| {
+        if unit.arg(1).as_enum() != Some("LENGTHUNIT") {
+            return None;
+        }
+        if unit.name == "IFCSIUNIT" {
+            let prefix = match unit.arg(2).as_enum() {
+                Some("MILLI") => 0.001,
+                Some("CENTI") => 0.01,
+                Some("DECI") => 0.1,
+                Some("KILO") => 1000.0,
+                _ => 1.0,
+            };
+            return Some(prefix * METERS_TO_FEET);
+        }
+        match unit.arg(2).as_str()?.to_uppercase().as_str() {
+            "FOOT" | "FEET" => Some(1.0),
+            "INCH" => Some(1.0 / 12.0),
+            _ => None,
+        }
+    })
+}
+
+/// Statements split on semicolons outside string literals
+fn split_statements(data: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let (mut start, mut in_string) = (0, false);
+    for (i, c) in data.char_indices() {
+        match c {
+            '\'' => in_string = !in_string,
+            ';' if !in_string => {
+                statements.push(&data[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    statements
+}
+
+/// Recursive-descent reader for SPF attribute values
+struct Parser {
+    chars: Vec<char>,
+    at: usize,
+}
+
+impl Parser {
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.get(self.at).is_some_and(|c| c.is_whitespace()) {
+            self.at += 1;
+        }
+        self.chars.get(self.at).copied()
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        let c = self.peek().ok_or_else(|| anyhow!("Unexpected end of IFC entity"))?;
+        match c {
+            '(' => {
+                self.at += 1;
+                let mut items = Vec::new();
+                if self.peek() == Some(')') {
+                    self.at += 1;
+                    return Ok(Value::List(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    match self.peek() {
+                        Some(',') => self.at += 1,
+                        Some(')') => {
+                            self.at += 1;
+                            return Ok(Value::List(items));
+                        }
+                        _ => return Err(anyhow!("Expected ',' or ')' in IFC entity")),
+                    }
+                }
+            }
+            '$' | '*' => {
+                self.at += 1;
+                Ok(Value::Null)
+            }
+            '#' => {
+                self.at += 1;
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                Ok(Value::Ref(digits.parse().map_err(|_| anyhow!("Invalid IFC reference"))?))
+            }
+            '\'' => {
+                self.at += 1;
+                let mut s = String::new();
+                loop {
+                    match self.chars.get(self.at) {
+                        None => return Err(anyhow!("Unterminated IFC string")),
+                        Some('\'') if self.chars.get(self.at + 1) == Some(&'\'') => {
+                            s.push('\'');
+                            self.at += 2;
+                        }
+                        Some('\'') => {
+                            self.at += 1;
+                            break;
+                        }
+                        Some(&c) => {
+                            s.push(c);
+                            self.at += 1;
+                        }
+                    }
+                }
+                Ok(Value::Str(decode(&s)))
+            }
+            '.' => {
+                self.at += 1;
+                let name = self.take_while(|c| c != '.');
+                self.at += 1;
+                Ok(Value::Enum(name))
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '+' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+                Ok(Value::Number(number.parse().map_err(|_| anyhow!("Invalid IFC number: {}", number))?))
+            }
+            c if c.is_ascii_alphabetic() => {
+                let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+                let inner = self.value()?;
+                let inner = match inner {
+                    Value::List(mut items) if items.len() == 1 => items.remove(0),
+                    other => other,
+                };
+                Ok(Value::Typed(name.to_uppercase(), Box::new(inner)))
+            }
+            c => Err(anyhow!("Unexpected character in IFC entity: {:?}", c)),
+        }
+    }
+
+    fn take_while(&mut self, keep: impl Fn(char) -> bool) -> String {
+        let start = self.at;
+        while self.chars.get(self.at).is_some_and(|&c| keep(c)) {
+            self.at += 1;
+        }
+        self.chars[start..self.at].iter().collect()
+    }
+}
+
+/// Undo SPF string escapes: doubled backslashes and \X2\ UTF-16 runs
+fn decode(s: &str) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(at) = rest.find('\\') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        if let Some(tail) = rest.strip_prefix("\\\\") {
+            out.push('\\');
+            rest = tail;
+        } else if let Some((hex, tail)) = rest.strip_prefix("\\X2\\").and_then(|t| t.split_once("\\X0\\")) {
+            let units: Vec<u16> = hex
+                .as_bytes()
+                .chunks(4)
+                .filter_map(|c| u16::from_str_radix(std::str::from_utf8(c).ok()?, 16).ok())
+                .collect();
+            out.push_str(&String::from_utf16_lossy(&units));
+            rest = tail;
+        } else {
+            out.push('\\');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+    use crate::export::export_project_ifc;
+
+    #[test]
+    fn test_ifc_round_trip() {
+        let mut store = Store::new();
+        let source_project = store
+            .create_project("Source", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(source_project, "Main").unwrap();
+        let first = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.add_level(building_id, "Second Floor", 9.0, 8.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("2x6 Exterior", vec![WallLayer::osb_7_16(), WallLayer::stud_2x6()])
+            .unwrap();
+        store
+            .create_wall(first, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        store
+            .create_wall(first, assembly_id, Point2::new(20.0, 0.0), Point2::new(20.0, 12.0), 9.0)
+            .unwrap();
+        store
+            .create_room(first, RoomType::Kitchen, "Café", Polygon2::rectangle(20.0, 12.0))
+            .unwrap();
+        let ifc = export_project_ifc(&store, source_project).unwrap();
+
+        let target = store
+            .create_project("Target", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let summary = import_ifc(&mut store, target, &ifc).unwrap();
+        assert_eq!(summary.level_ids.len(), 2);
+        let level = store.get_level(summary.level_ids[1]).unwrap();
+        assert_eq!(level.name, "Second Floor");
+        assert!((level.elevation - 9.0).abs() < 1e-6);
+
+        assert_eq!(summary.wall_ids.len(), 2);
+        let walls: Vec<&Wall> = summary.wall_ids.iter().map(|id| store.get_wall(*id).unwrap()).collect();
+        let east = walls.iter().find(|w| (w.start.x - 20.0).abs() < 1e-6).unwrap();
+        assert!(east.start.y.abs() < 1e-6 && (east.end.y - 12.0).abs() < 1e-6);
+        assert!((east.height - 9.0).abs() < 1e-6);
+        // Both walls share the assembly, found again by name and layers
+        assert!(walls.iter().all(|w| w.assembly_id == assembly_id));
+
+        assert_eq!(summary.room_ids.len(), 1);
+        let room = store.get_room(summary.room_ids[0]).unwrap();
+        assert_eq!(room.name, "Café");
+        assert_eq!(room.room_type, RoomType::Kitchen);
+        assert!((room.area() - 240.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_import_ifc_slabs_and_units() {
+        // Millimeters, a wall with only an axis and a layer set, and a slab
+        let ifc = "ISO-10303-21;\nHEADER;\nENDSEC;\nDATA;\n\
+            #1=IFCSIUNIT(*,.LENGTHUNIT.,.MILLI.,.METRE.);\n\
+            #2=IFCBUILDING('b',$,'Shop',$,$,$,$,$,.ELEMENT.,$,$,$);\n\
+            #3=IFCBUILDINGSTOREY('s',$,'Ground',$,$,$,$,$,.ELEMENT.,0.);\n\
+            #10=IFCCARTESIANPOINT((0.,0.));\n\
+            #11=IFCCARTESIANPOINT((6096.,0.));\n\
+            #12=IFCPOLYLINE((#10,#11));\n\
+            #13=IFCSHAPEREPRESENTATION($,'Axis','Curve2D',(#12));\n\
+            #14=IFCPRODUCTDEFINITIONSHAPE($,$,(#13));\n\
+            #15=IFCWALL('w',$,'Wall',$,$,$,#14,$,$);\n\
+            #20=IFCMATERIAL('Concrete',$,$);\n\
+            #21=IFCMATERIALLAYER(#20,203.2,.F.,$,$,$,$);\n\
+            #22=IFCMATERIALLAYERSET((#21),$,$);\n\
+            #23=IFCRELASSOCIATESMATERIAL('r',$,$,$,(#15),#22);\n\
+            #30=IFCCARTESIANPOINT((0.,0.));\n\
+            #31=IFCCARTESIANPOINT((6096.,0.));\n\
+            #32=IFCCARTESIANPOINT((6096.,3048.));\n\
+            #33=IFCCARTESIANPOINT((0.,3048.));\n\
+            #34=IFCPOLYLINE((#30,#33,#32,#31,#30));\n\
+            #35=IFCARBITRARYCLOSEDPROFILEDEF(.AREA.,$,#34);\n\
+            #36=IFCEXTRUDEDAREASOLID(#35,$,$,200.);\n\
+            #37=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#36));\n\
+            #38=IFCPRODUCTDEFINITIONSHAPE($,$,(#37));\n\
+            #39=IFCSLAB('f',$,'Slab',$,$,$,#38,$,.FLOOR.);\n\
+            #40=IFCRELCONTAINEDINSPATIALSTRUCTURE('c',$,$,$,(#15,#39),#3);\n\
+            ENDSEC;\nEND-ISO-10303-21;\n";
+
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let summary = import_ifc(&mut store, project_id, ifc).unwrap();
+        assert_eq!(summary.level_ids.len(), 1);
+        assert_eq!(summary.footprint_ids.len(), 1);
+        let footprint = store.get_level_footprint(summary.level_ids[0]).unwrap();
+        assert!((footprint.polygon.area() - 200.0).abs() < 1e-6);
+
+        let wall = store.get_wall(summary.wall_ids[0]).unwrap();
+        assert!((wall.end.x - 20.0).abs() < 1e-6);
+        assert_eq!(wall.height, DEFAULT_STOREY_HEIGHT);
+        let assembly = store.get_wall_assembly(wall.assembly_id).unwrap();
+        assert_eq!(assembly.layers[0].material, "Concrete");
+        assert!((assembly.total_thickness - 8.0).abs() < 1e-6);
+
+        assert!(import_ifc(&mut store, project_id, "not ifc").is_err());
+        assert!(import_ifc(&mut store, ProjectId::new(), ifc).is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("Caf\\X2\\00E9\\X0\\ \\\\"), "Café \\");
+    }
+}
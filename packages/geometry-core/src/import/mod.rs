@@ -0,0 +1,71 @@
+// Import - existing plans and models read into the store
+// Importers only add entities; anything they can't interpret is skipped
+// and reported as a warning. Each import runs in one transaction, so a
+// failure leaves the store as it was.
+
+pub mod dxf;
+pub mod ifc;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{FootprintId, LevelId, Point2, Polygon2, RoomId, WallId};
+use crate::store::Store;
+
+pub use dxf::import_dxf;
+pub use ifc::import_ifc;
+
+/// Vertices closer than this (feet) are treated as one
+const DUPLICATE_TOLERANCE: f64 = 1e-6;
+
+/// What an import added to the store
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub level_ids: Vec<LevelId>,
+    pub footprint_ids: Vec<FootprintId>,
+    pub wall_ids: Vec<WallId>,
+    pub room_ids: Vec<RoomId>,
+    /// Entities that were read but not imported, with the reason
+    pub warnings: Vec<String>,
+}
+
+/// Ring as a counterclockwise polygon, without repeated or closing
+/// vertices. None if fewer than three vertices remain.
+fn ring_polygon(points: &[Point2]) -> Option<Polygon2> {
+    let mut ring: Vec<Point2> = Vec::with_capacity(points.len());
+    for p in points {
+        if ring.last().is_none_or(|last| last.distance_to(p) > DUPLICATE_TOLERANCE) {
+            ring.push(*p);
+        }
+    }
+    while ring.len() > 1 && ring[0].distance_to(&ring[ring.len() - 1]) <= DUPLICATE_TOLERANCE {
+        ring.pop();
+    }
+    if ring.len() < 3 {
+        return None;
+    }
+    let mut polygon = Polygon2::new(ring);
+    if polygon.signed_area() < 0.0 {
+        polygon.outer.reverse();
+    }
+    Some(polygon)
+}
+
+/// Footprints for a level, largest first: it becomes the primary unless the
+/// level already has one, and the rest are auxiliary
+fn add_footprints(store: &mut Store, level_id: LevelId, mut polygons: Vec<Polygon2>, summary: &mut ImportSummary) -> Result<()> {
+    polygons.sort_by(|a, b| b.area().total_cmp(&a.area()));
+    for polygon in polygons {
+        if !polygon.is_valid() {
+            summary.warnings.push("Skipped a footprint that is not a simple polygon".to_string());
+            continue;
+        }
+        let id = if store.get_level_footprint(level_id).is_none() {
+            store.set_level_footprint(level_id, polygon)?
+        } else {
+            store.add_auxiliary_footprint(level_id, polygon)?
+        };
+        summary.footprint_ids.push(id);
+    }
+    Ok(())
+}
@@ -0,0 +1,273 @@
+// Background jobs for heavy operations
+// Meshing a whole building, running compliance or exporting IFC can take
+// seconds. A server submits the work as a job and answers at once with its
+// ID; the work reports progress through its JobHandle and clients poll the
+// job's status until it finishes.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::JobId;
+use crate::rhai_api::CancellationToken;
+
+/// Where a job is in its lifecycle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Succeeded { result: serde_json::Value },
+    Failed { error: String },
+    Cancelled,
+}
+
+impl JobState {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobState::Queued | JobState::Running)
+    }
+}
+
+/// A job as a client polling it sees it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatus {
+    pub id: JobId,
+    /// What the job does, e.g. "mesh_building" or "export_ifc"
+    pub kind: String,
+    #[serde(flatten)]
+    pub state: JobState,
+    /// Fraction done, 0.0 to 1.0
+    pub progress: f64,
+    /// What the job is working on now
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+struct JobEntry {
+    status: JobStatus,
+    cancel: CancellationToken,
+}
+
+type Jobs = Arc<Mutex<HashMap<JobId, JobEntry>>>;
+
+/// Every submitted job and its status. Clones share the same jobs.
+#[derive(Debug, Clone, Default)]
+pub struct JobQueue {
+    jobs: Jobs,
+}
+
+/// The running side of a job: reports progress and the outcome
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    id: JobId,
+    jobs: Jobs,
+    cancel: CancellationToken,
+}
+
+impl JobQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a queued job. The caller runs the work however it likes
+    /// (a blocking thread pool, say) and reports through the handle.
+    pub fn submit(&self, kind: impl Into<String>) -> JobHandle {
+        let id = JobId::new();
+        let cancel = CancellationToken::new();
+        let status = JobStatus {
+            id,
+            kind: kind.into(),
+            state: JobState::Queued,
+            progress: 0.0,
+            message: None,
+            created_at: Utc::now(),
+            finished_at: None,
+        };
+        self.jobs.lock().unwrap().insert(id, JobEntry { status, cancel: cancel.clone() });
+        JobHandle { id, jobs: self.jobs.clone(), cancel }
+    }
+
+    /// Submit a job and run it on its own thread. The job succeeds with
+    /// whatever `work` returns, unless it was cancelled first.
+    pub fn spawn<F>(&self, kind: impl Into<String>, work: F) -> JobId
+    where
+        F: FnOnce(&JobHandle) -> Result<serde_json::Value> + Send + 'static,
+    {
+        let handle = self.submit(kind);
+        let id = handle.id;
+        std::thread::spawn(move || {
+            handle.start();
+            match work(&handle) {
+                Ok(result) => handle.succeed(result),
+                Err(e) => handle.fail(e.to_string()),
+            }
+        });
+        id
+    }
+
+    pub fn status(&self, id: JobId) -> Option<JobStatus> {
+        self.jobs.lock().unwrap().get(&id).map(|entry| entry.status.clone())
+    }
+
+    /// Every job, oldest first
+    pub fn list(&self) -> Vec<JobStatus> {
+        let mut jobs: Vec<JobStatus> = self.jobs.lock().unwrap().values().map(|e| e.status.clone()).collect();
+        jobs.sort_by_key(|job| job.created_at);
+        jobs
+    }
+
+    /// Ask a job to stop. It is marked cancelled at once; the work sees the
+    /// token and should return early. False if the job is unknown or done.
+    pub fn cancel(&self, id: JobId) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        match jobs.get_mut(&id) {
+            Some(entry) if !entry.status.state.is_finished() => {
+                entry.cancel.cancel();
+                entry.status.state = JobState::Cancelled;
+                entry.status.finished_at = Some(Utc::now());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drop finished jobs that ended before `before`, returning how many
+    pub fn remove_finished(&self, before: DateTime<Utc>) -> usize {
+        let mut jobs = self.jobs.lock().unwrap();
+        let count = jobs.len();
+        jobs.retain(|_, entry| entry.status.finished_at.is_none_or(|t| t >= before));
+        count - jobs.len()
+    }
+}
+
+impl JobHandle {
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    /// Token to pass on to cancellable work, e.g. a script's ExecutionLimits
+    pub fn cancellation(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    pub fn start(&self) {
+        self.update(|status| status.state = JobState::Running);
+    }
+
+    /// Report progress; `fraction` is clamped to 0.0..=1.0
+    pub fn progress(&self, fraction: f64, message: impl Into<String>) {
+        let message = message.into();
+        self.update(|status| {
+            status.progress = fraction.clamp(0.0, 1.0);
+            status.message = Some(message);
+        });
+    }
+
+    pub fn succeed(&self, result: serde_json::Value) {
+        self.finish(JobState::Succeeded { result });
+    }
+
+    pub fn fail(&self, error: impl Into<String>) {
+        self.finish(JobState::Failed { error: error.into() });
+    }
+
+    fn finish(&self, state: JobState) {
+        let succeeded = matches!(state, JobState::Succeeded { .. });
+        self.update(|status| {
+            if succeeded {
+                status.progress = 1.0;
+            }
+            status.state = state;
+            status.finished_at = Some(Utc::now());
+        });
+    }
+
+    /// Apply `change` unless the job has already finished or been cancelled
+    fn update(&self, change: impl FnOnce(&mut JobStatus)) {
+        if let Some(entry) = self.jobs.lock().unwrap().get_mut(&self.id) {
+            if !entry.status.state.is_finished() {
+                change(&mut entry.status);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    fn wait_for(queue: &JobQueue, id: JobId) -> JobStatus {
+        for _ in 0..500 {
+            let status = queue.status(id).unwrap();
+            if status.state.is_finished() {
+                return status;
+            }
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        panic!("job {} did not finish", id);
+    }
+
+    #[test]
+    fn test_job_lifecycle() {
+        let queue = JobQueue::new();
+        let (go, wait) = channel::<()>();
+        let id = queue.spawn("mesh_building", move |job| {
+            job.progress(0.5, "level 1 of 2");
+            wait.recv().unwrap();
+            Ok(serde_json::json!({ "triangles": 1200 }))
+        });
+
+        // Halfway through, the job shows its progress
+        for _ in 0..500 {
+            if queue.status(id).unwrap().progress > 0.0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        let status = queue.status(id).unwrap();
+        assert_eq!(status.state, JobState::Running);
+        assert_eq!(status.message.as_deref(), Some("level 1 of 2"));
+
+        go.send(()).unwrap();
+        let status = wait_for(&queue, id);
+        assert_eq!(status.state, JobState::Succeeded { result: serde_json::json!({ "triangles": 1200 }) });
+        assert_eq!(status.progress, 1.0);
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["state"], "succeeded");
+        assert_eq!(json["kind"], "mesh_building");
+
+        let failed = queue.spawn("export_ifc", |_| Err(anyhow!("no buildings")));
+        assert_eq!(wait_for(&queue, failed).state, JobState::Failed { error: "no buildings".to_string() });
+        assert_eq!(queue.list().len(), 2);
+        assert_eq!(queue.remove_finished(Utc::now() + chrono::Duration::seconds(1)), 2);
+        assert!(queue.list().is_empty());
+    }
+
+    #[test]
+    fn test_cancel_job() {
+        let queue = JobQueue::new();
+        let job = queue.submit("compliance");
+        let token = job.cancellation();
+        job.start();
+
+        assert!(queue.cancel(job.id()));
+        assert!(token.is_cancelled());
+        // The work's late result doesn't overwrite the cancellation
+        job.succeed(serde_json::Value::Null);
+        assert_eq!(queue.status(job.id()).unwrap().state, JobState::Cancelled);
+        assert!(!queue.cancel(job.id()));
+        assert!(!queue.cancel(JobId::new()));
+    }
+}
@@ -4,8 +4,39 @@ pub mod geometry;
 pub mod store;
 pub mod rhai_api;
 pub mod costing;
+pub mod analysis;
+pub mod sequencing;
+pub mod compliance;
+pub mod constraints;
+pub mod templates;
+pub mod plan2d;
+pub mod export;
+pub mod import;
+pub mod metrics;
+pub mod jobs;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 
 pub use domain::*;
 pub use framing::{FramingGenerator, RegenerationManager};
 pub use store::SharedStore;
-pub use costing::{CostCalculator, CostInput, RoomCostInput, OpeningCostInput};
+pub use costing::{CostCalculator, CostInput, RoomCostInput, OpeningCostInput, EstimateSection, ProjectEstimate};
+pub use analysis::{
+    analyze_egress, analyze_envelope, check_bearing_continuity, BearingIssue, BearingIssueKind, BearingReport,
+    BearingSupport, EgressIssue, EgressIssueKind, EgressReport, EnvelopeIssue, EnvelopeIssueKind, EnvelopeReport,
+    check_zoning, LotSide, SetbackViolation, ZoningReport,
+    analyze_daylight, DaylightReport, RoomDaylight,
+    circulation_graph, rooms_unreachable_from_entry, CirculationGraph, CirculationPath, Passage, PassageKind,
+    calculate_energy, EnergyComponent, EnergyComponentKind, EnergySummary, InsulationSummary, LevelEnergy,
+    solar_report, FacadeSolar, Facing, SolarReport, WindowSolar,
+};
+pub use constraints::{
+    check_constraints, check_mutation, solve_constraints, ConstraintChanges, ConstraintReport, ConstraintStatus,
+    SolveReport,
+};
+pub use templates::{instantiate_template, BuildingTemplate, TemplateInstance, TemplateParams};
+pub use plan2d::{render_plan_dxf, render_plan_svg, PlanSvgOptions};
+pub use export::{export_building_glb, export_level_glb, export_level_mesh, export_project_ifc, MaterialSlot, MeshExportOptions};
+pub use import::{import_dxf, import_ifc, ImportSummary};
+pub use sequencing::{sequence_building, ConstructionPhase, ConstructionSequence, SequenceTiming};
+pub use compliance::{check_compliance, switch_code_region, CodeRequirements, ComplianceReport, ComplianceRule, ComplianceViolation, CodeRegionChange, RuleContext, RuleRegistry, Severity};
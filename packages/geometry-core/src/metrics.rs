@@ -0,0 +1,208 @@
+// Operational metrics
+// Request counts and latencies, mesh generation timings and script
+// execution stats, rendered in the Prometheus text format for a server's
+// /metrics endpoint. Recording takes &self so one Metrics can be shared by
+// every request handler.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use crate::rhai_api::ScriptResult;
+use crate::store::MemoryStats;
+
+/// Latency histogram bucket bounds, in seconds
+const BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Counts of observations at or under each bucket bound, plus their sum
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum_seconds: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration_ms: f64) {
+        let seconds = duration_ms / 1000.0;
+        for (bound, bucket) in BUCKETS.iter().zip(self.buckets.iter_mut()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum_seconds += seconds;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        for (bound, bucket) in BUCKETS.iter().zip(self.buckets.iter()) {
+            let _ = writeln!(out, "{}_bucket{{{}{}le=\"{}\"}} {}", name, labels, sep, bound, bucket);
+        }
+        let _ = writeln!(out, "{}_bucket{{{}{}le=\"+Inf\"}} {}", name, labels, sep, self.count);
+        let braced = if labels.is_empty() { String::new() } else { format!("{{{}}}", labels) };
+        let _ = writeln!(out, "{}_sum{} {}", name, braced, self.sum_seconds);
+        let _ = writeln!(out, "{}_count{} {}", name, braced, self.count);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Recorded {
+    /// Keyed by (method, route, status)
+    requests: BTreeMap<(String, String, u16), u64>,
+    /// Keyed by (method, route)
+    request_latency: BTreeMap<(String, String), Histogram>,
+    /// Keyed by what was meshed, e.g. "level" or "building"
+    mesh_latency: BTreeMap<String, Histogram>,
+    /// Keyed by outcome: "success", "error", "timeout" or "cancelled"
+    scripts: BTreeMap<&'static str, u64>,
+    script_latency: Histogram,
+    script_events: u64,
+}
+
+/// Shared, thread-safe metrics registry
+#[derive(Debug, Default)]
+pub struct Metrics {
+    recorded: Mutex<Recorded>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a handled request. `route` should be the route pattern
+    /// (e.g. "/api/v1/walls/:id"), not the path, to keep label counts bounded.
+    pub fn record_request(&self, method: &str, route: &str, status: u16, duration_ms: f64) {
+        let mut recorded = self.recorded.lock().unwrap();
+        *recorded.requests.entry((method.to_string(), route.to_string(), status)).or_default() += 1;
+        recorded
+            .request_latency
+            .entry((method.to_string(), route.to_string()))
+            .or_default()
+            .observe(duration_ms);
+    }
+
+    /// Record how long generating a mesh took
+    pub fn record_mesh(&self, kind: &str, duration_ms: f64) {
+        let mut recorded = self.recorded.lock().unwrap();
+        recorded.mesh_latency.entry(kind.to_string()).or_default().observe(duration_ms);
+    }
+
+    /// Record a finished script execution
+    pub fn record_script(&self, result: &ScriptResult) {
+        let outcome = match (result.success, result.aborted) {
+            (true, _) => "success",
+            (false, Some(abort)) => abort.as_str(),
+            (false, None) => "error",
+        };
+        let mut recorded = self.recorded.lock().unwrap();
+        *recorded.scripts.entry(outcome).or_default() += 1;
+        recorded.script_latency.observe(result.duration_ms);
+        recorded.script_events += result.events_generated as u64;
+    }
+
+    /// Everything recorded so far plus the store's current sizes, in the
+    /// Prometheus text exposition format
+    pub fn render(&self, stats: &MemoryStats) -> String {
+        let recorded = self.recorded.lock().unwrap();
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP cad_requests_total Requests handled, by route and status");
+        let _ = writeln!(out, "# TYPE cad_requests_total counter");
+        for ((method, route, status), count) in &recorded.requests {
+            let _ = writeln!(
+                out,
+                "cad_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                method, escape(route), status, count
+            );
+        }
+
+        let _ = writeln!(out, "# HELP cad_request_duration_seconds Request latency, by route");
+        let _ = writeln!(out, "# TYPE cad_request_duration_seconds histogram");
+        for ((method, route), histogram) in &recorded.request_latency {
+            let labels = format!("method=\"{}\",route=\"{}\"", method, escape(route));
+            histogram.render(&mut out, "cad_request_duration_seconds", &labels);
+        }
+
+        let _ = writeln!(out, "# HELP cad_mesh_duration_seconds Mesh generation time, by what was meshed");
+        let _ = writeln!(out, "# TYPE cad_mesh_duration_seconds histogram");
+        for (kind, histogram) in &recorded.mesh_latency {
+            histogram.render(&mut out, "cad_mesh_duration_seconds", &format!("kind=\"{}\"", escape(kind)));
+        }
+
+        let _ = writeln!(out, "# HELP cad_scripts_total Rhai scripts run, by outcome");
+        let _ = writeln!(out, "# TYPE cad_scripts_total counter");
+        for (outcome, count) in &recorded.scripts {
+            let _ = writeln!(out, "cad_scripts_total{{outcome=\"{}\"}} {}", outcome, count);
+        }
+        let _ = writeln!(out, "# HELP cad_script_duration_seconds Rhai script run time");
+        let _ = writeln!(out, "# TYPE cad_script_duration_seconds histogram");
+        recorded.script_latency.render(&mut out, "cad_script_duration_seconds", "");
+        let _ = writeln!(out, "# HELP cad_script_events_total Events recorded by Rhai scripts");
+        let _ = writeln!(out, "# TYPE cad_script_events_total counter");
+        let _ = writeln!(out, "cad_script_events_total {}", recorded.script_events);
+
+        let gauges = [
+            ("cad_store_projects", "Projects in the store", stats.projects),
+            ("cad_store_entities", "Entities in the store", stats.entities),
+            ("cad_store_events", "Events across all event logs", stats.events),
+            ("cad_store_undone_events", "Undone events held for redo", stats.undone_events),
+            ("cad_store_event_log_bytes", "Serialized size of every event log", stats.event_log_bytes),
+            ("cad_store_snapshots", "Snapshots held", stats.snapshots),
+            ("cad_store_subscribers", "Live event subscribers", stats.subscribers),
+        ];
+        for (name, help, value) in gauges {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} gauge", name);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+
+        out
+    }
+}
+
+/// Escape a label value
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+    use crate::rhai_api::{create_engine, execute_script};
+    use crate::store::new_shared_store;
+
+    #[test]
+    fn test_render_metrics() {
+        let metrics = Metrics::new();
+        metrics.record_request("GET", "/api/v1/projects", 200, 3.0);
+        metrics.record_request("GET", "/api/v1/projects", 200, 40.0);
+        metrics.record_request("GET", "/api/v1/projects/:id", 404, 1.0);
+        metrics.record_mesh("level", 120.0);
+
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+        metrics.record_script(&execute_script(&engine, "1 + 1", store.clone()));
+        metrics.record_script(&execute_script(&engine, "undefined_fn()", store.clone()));
+        store.write().unwrap()
+            .create_project("Metrics", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+
+        let text = metrics.render(&store.read().unwrap().memory_stats());
+        assert!(text.contains("cad_requests_total{method=\"GET\",route=\"/api/v1/projects\",status=\"200\"} 2"));
+        assert!(text.contains("cad_requests_total{method=\"GET\",route=\"/api/v1/projects/:id\",status=\"404\"} 1"));
+        assert!(text.contains(
+            "cad_request_duration_seconds_bucket{method=\"GET\",route=\"/api/v1/projects\",le=\"0.005\"} 1"
+        ));
+        assert!(text.contains(
+            "cad_request_duration_seconds_bucket{method=\"GET\",route=\"/api/v1/projects\",le=\"+Inf\"} 2"
+        ));
+        assert!(text.contains("cad_mesh_duration_seconds_count{kind=\"level\"} 1"));
+        assert!(text.contains("cad_scripts_total{outcome=\"success\"} 1"));
+        assert!(text.contains("cad_scripts_total{outcome=\"error\"} 1"));
+        assert!(text.contains("cad_script_duration_seconds_count 2"));
+        assert!(text.contains("cad_store_projects 1"));
+    }
+}
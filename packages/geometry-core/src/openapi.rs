@@ -0,0 +1,40 @@
+// OpenAPI schemas for the core types a server sends and receives
+// Built with the `openapi` feature. A server merges CoreApiDoc into the
+// document for its own handlers, so the frontend can generate its client
+// from one schema instead of hand-maintaining request shapes.
+
+use utoipa::OpenApi;
+
+use crate::domain::{EventQuery, ProjectQuery, ProjectRole, ProjectSort};
+use crate::store::{ChangeSet, CompactionReport, MemoryStats};
+
+/// Component schemas for the core's request and response types
+#[derive(OpenApi)]
+#[openapi(components(schemas(
+    ChangeSet,
+    CompactionReport,
+    EventQuery,
+    MemoryStats,
+    ProjectQuery,
+    ProjectRole,
+    ProjectSort,
+)))]
+pub struct CoreApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_core_schemas() {
+        let doc = CoreApiDoc::openapi();
+        let schemas = doc.components.expect("components").schemas;
+        for name in ["ChangeSet", "EventQuery", "ProjectQuery", "ProjectRole", "MemoryStats"] {
+            assert!(schemas.contains_key(name), "missing schema {}", name);
+        }
+
+        let json = CoreApiDoc::openapi().to_json().unwrap();
+        assert!(json.contains("full_resync"));
+        assert!(json.contains("\"format\":\"uuid\""));
+    }
+}
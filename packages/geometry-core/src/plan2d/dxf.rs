@@ -0,0 +1,343 @@
+// DXF floor plans - the same drawing as the SVG plan, as AutoCAD R12 DXF
+// Coordinates are written in inches (architectural units) with +y north.
+// Each kind of linework sits on its own layer so it can be frozen or
+// restyled in CAD; dimensions are plain lines and text rather than
+// associative DIMENSION entities.
+
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use super::{building_extents, feet_inches, grid_line, sheet_extents, wall_outline, ARC_SEGMENTS};
+use crate::domain::{LevelId, Opening, OpeningType, Point2, Wall};
+use crate::store::Store;
+
+/// Layer name, ACI color
+const LAYERS: [(&str, u8); 5] = [
+    (WALLS, 7),
+    (OPENINGS, 4),
+    (ROOMS, 8),
+    (GRID, 1),
+    (DIMENSIONS, 2),
+];
+const WALLS: &str = "WALLS";
+const OPENINGS: &str = "OPENINGS";
+const ROOMS: &str = "ROOMS";
+const GRID: &str = "GRID";
+const DIMENSIONS: &str = "DIMENSIONS";
+
+/// How far grids and dimensions sit outside the building (feet)
+const MARGIN: f64 = 3.0;
+
+/// Render a level's floor plan as DXF text
+pub fn render_plan_dxf(store: &Store, level_id: LevelId) -> Result<String> {
+    let level = store.get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+    let building = building_extents(store, level_id);
+    let grid = store.get_grid(level.building_id);
+    let sheet = sheet_extents(building, grid);
+
+    let mut dxf = DxfWriter::default();
+
+    for room in store.get_level_rooms(level_id) {
+        dxf.polyline(ROOMS, &room.boundary.outer, true);
+        for hole in &room.boundary.holes {
+            dxf.polyline(ROOMS, hole, true);
+        }
+        let c = room.boundary.centroid();
+        dxf.text(ROOMS, &c, 0.8, 0.0, &room.name);
+        dxf.text(ROOMS, &Point2::new(c.x, c.y - 0.9), 0.6, 0.0, &format!("{:.0} sq ft", room.area()));
+    }
+
+    for wall in store.get_level_walls(level_id) {
+        let half = store.wall_thickness(wall) / 2.0;
+        let length = wall.length();
+        let mut openings: Vec<&Opening> = store.get_wall_openings(wall.id);
+        openings.sort_by(|a, b| a.position_along_wall.total_cmp(&b.position_along_wall));
+        let mut from = 0.0;
+        for opening in &openings {
+            let center = opening.position_along_wall * length;
+            let (d0, d1) = ((center - opening.width / 2.0).max(0.0), (center + opening.width / 2.0).min(length));
+            if d0 > from {
+                dxf.wall_run(wall, from, d0, half);
+            }
+            from = from.max(d1);
+            dxf.opening(wall, opening, d0, d1, half);
+        }
+        if length > from {
+            dxf.wall_run(wall, from, length, half);
+        }
+    }
+
+    if let Some(grid) = grid {
+        for axis in &grid.axes {
+            let (start, end) = grid_line(axis, &sheet, MARGIN);
+            dxf.line(GRID, &start, &end);
+            dxf.circle(GRID, &start, 0.7);
+            dxf.text(GRID, &start, 0.7, 0.0, &axis.name);
+        }
+    }
+
+    if building.max.x > building.min.x {
+        let below = building.min.y - MARGIN / 2.0;
+        dxf.dimension(&Point2::new(building.min.x, below), &Point2::new(building.max.x, below));
+        let left = building.min.x - MARGIN / 2.0;
+        dxf.dimension(&Point2::new(left, building.min.y), &Point2::new(left, building.max.y));
+    }
+
+    Ok(dxf.finish())
+}
+
+/// ENTITIES section, written as group code / value pairs
+#[derive(Default)]
+struct DxfWriter {
+    entities: String,
+}
+
+impl DxfWriter {
+    fn pair(&mut self, code: u16, value: &str) {
+        let _ = writeln!(self.entities, "{:>3}\n{}", code, value);
+    }
+
+    /// Plan point in feet, written as inches
+    fn point(&mut self, code: u16, p: &Point2) {
+        self.pair(code, &num(p.x * 12.0));
+        self.pair(code + 10, &num(p.y * 12.0));
+        self.pair(code + 20, "0.0");
+    }
+
+    fn entity(&mut self, kind: &str, layer: &str) {
+        self.pair(0, kind);
+        self.pair(8, layer);
+    }
+
+    fn line(&mut self, layer: &str, a: &Point2, b: &Point2) {
+        self.entity("LINE", layer);
+        self.point(10, a);
+        self.point(11, b);
+    }
+
+    fn polyline(&mut self, layer: &str, points: &[Point2], closed: bool) {
+        self.entity("POLYLINE", layer);
+        self.pair(66, "1");
+        self.point(10, &Point2::origin());
+        self.pair(70, if closed { "1" } else { "0" });
+        for p in points {
+            self.entity("VERTEX", layer);
+            self.point(10, p);
+        }
+        self.entity("SEQEND", layer);
+    }
+
+    fn circle(&mut self, layer: &str, center: &Point2, radius: f64) {
+        self.entity("CIRCLE", layer);
+        self.point(10, center);
+        self.pair(40, &num(radius * 12.0));
+    }
+
+    /// Arc drawn counterclockwise between two angles in degrees
+    fn arc(&mut self, layer: &str, center: &Point2, radius: f64, start: f64, end: f64) {
+        self.entity("ARC", layer);
+        self.point(10, center);
+        self.pair(40, &num(radius * 12.0));
+        self.pair(50, &num(start));
+        self.pair(51, &num(end));
+    }
+
+    /// Text centered on a point; height in feet, rotation in degrees
+    fn text(&mut self, layer: &str, at: &Point2, height: f64, rotation: f64, value: &str) {
+        self.entity("TEXT", layer);
+        self.point(10, at);
+        self.pair(40, &num(height * 12.0));
+        self.pair(1, &encode(value));
+        if rotation != 0.0 {
+            self.pair(50, &num(rotation));
+        }
+        self.pair(72, "1");
+        self.pair(73, "2");
+        self.point(11, at);
+    }
+
+    /// Closed outline of a run of wall
+    fn wall_run(&mut self, wall: &Wall, d0: f64, d1: f64, half: f64) {
+        let faces = wall_outline(wall, d0, d1, half);
+        let ring: Vec<Point2> = faces.iter().map(|(l, _)| *l)
+            .chain(faces.iter().rev().map(|(_, r)| *r))
+            .collect();
+        self.polyline(WALLS, &ring, true);
+    }
+
+    /// Jambs at both ends of the gap, plus a door leaf and swing or a
+    /// window's glazing line
+    fn opening(&mut self, wall: &Wall, opening: &Opening, d0: f64, d1: f64, half: f64) {
+        let faces = wall_outline(wall, d0, d1, half);
+        let (Some(&first), Some(&last)) = (faces.first(), faces.last()) else {
+            return;
+        };
+        self.line(OPENINGS, &first.0, &first.1);
+        self.line(OPENINGS, &last.0, &last.1);
+        match opening.opening_type {
+            OpeningType::Door => {
+                let hinge = wall.point_at(d0);
+                let strike = wall.point_at(d1);
+                let (tx, ty) = wall.tangent_at(d0);
+                let leaf = d1 - d0;
+                let open = Point2::new(hinge.x - ty * leaf, hinge.y + tx * leaf);
+                self.line(OPENINGS, &hinge, &open);
+                let angle = |p: &Point2| (p.y - hinge.y).atan2(p.x - hinge.x).to_degrees();
+                self.arc(OPENINGS, &hinge, leaf, angle(&strike), angle(&open));
+            }
+            OpeningType::Window => {
+                let steps = if wall.is_curved() { ARC_SEGMENTS } else { 1 };
+                let glass: Vec<Point2> = (0..=steps)
+                    .map(|i| wall.point_at(d0 + (d1 - d0) * i as f64 / steps as f64))
+                    .collect();
+                self.polyline(OPENINGS, &glass, false);
+            }
+            OpeningType::Other(_) => {}
+        }
+    }
+
+    /// Dimension string between two points: line, end ticks and length
+    fn dimension(&mut self, a: &Point2, b: &Point2) {
+        let length = a.distance_to(b);
+        let (ux, uy) = ((b.x - a.x) / length, (b.y - a.y) / length);
+        let tick = 0.35;
+        self.line(DIMENSIONS, a, b);
+        for p in [a, b] {
+            self.line(
+                DIMENSIONS,
+                &Point2::new(p.x - (ux - uy) * tick, p.y - (uy + ux) * tick),
+                &Point2::new(p.x + (ux - uy) * tick, p.y + (uy + ux) * tick),
+            );
+        }
+        // Text sits just above the line, reading along it
+        let mid = Point2::new((a.x + b.x) / 2.0 - uy * 0.5, (a.y + b.y) / 2.0 + ux * 0.5);
+        self.text(DIMENSIONS, &mid, 0.7, uy.atan2(ux).to_degrees(), &feet_inches(length));
+    }
+
+    /// Wrap the entities with a header and layer table
+    fn finish(self) -> String {
+        let mut out = String::new();
+        let mut pair = |code: u16, value: &str| {
+            let _ = writeln!(out, "{:>3}\n{}", code, value);
+        };
+        pair(0, "SECTION");
+        pair(2, "HEADER");
+        pair(9, "$ACADVER");
+        pair(1, "AC1009");
+        // Architectural units, drawn in inches
+        pair(9, "$INSUNITS");
+        pair(70, "1");
+        pair(9, "$LUNITS");
+        pair(70, "4");
+        pair(0, "ENDSEC");
+
+        pair(0, "SECTION");
+        pair(2, "TABLES");
+        pair(0, "TABLE");
+        pair(2, "LTYPE");
+        pair(70, "1");
+        pair(0, "LTYPE");
+        pair(2, "CONTINUOUS");
+        pair(70, "0");
+        pair(3, "Solid line");
+        pair(72, "65");
+        pair(73, "0");
+        pair(40, "0.0");
+        pair(0, "ENDTAB");
+        pair(0, "TABLE");
+        pair(2, "LAYER");
+        pair(70, &LAYERS.len().to_string());
+        for (name, color) in LAYERS {
+            pair(0, "LAYER");
+            pair(2, name);
+            pair(70, "0");
+            pair(62, &color.to_string());
+            pair(6, "CONTINUOUS");
+        }
+        pair(0, "ENDTAB");
+        pair(0, "ENDSEC");
+
+        pair(0, "SECTION");
+        pair(2, "ENTITIES");
+        out.push_str(&self.entities);
+        let mut pair = |code: u16, value: &str| {
+            let _ = writeln!(out, "{:>3}\n{}", code, value);
+        };
+        pair(0, "ENDSEC");
+        pair(0, "EOF");
+        out
+    }
+}
+
+/// Value to at most four decimals, without trailing zeros but keeping
+/// one decimal place so readers treat it as a real
+fn num(value: f64) -> String {
+    let s = format!("{:.4}", value);
+    let s = s.trim_end_matches('0');
+    let s = if s.ends_with('.') { format!("{}0", s) } else { s.to_string() };
+    if s == "-0.0" { "0.0".to_string() } else { s }
+}
+
+/// Text value: non-ASCII characters as \U+XXXX escapes, line breaks dropped
+fn encode(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| *c != '\n' && *c != '\r')
+        .map(|c| if c.is_ascii() { c.to_string() } else { format!("\\U+{:04X}", c as u32) })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    #[test]
+    fn test_render_plan_dxf() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::new("Stud", 6.0, true)])
+            .unwrap();
+        let corners = [(0.0, 0.0), (32.5, 0.0), (32.5, 24.0), (0.0, 24.0)];
+        let mut walls = Vec::new();
+        for i in 0..4 {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            walls.push(store
+                .create_wall(level_id, assembly_id, Point2::new(a.0, a.1), Point2::new(b.0, b.1), 9.0)
+                .unwrap());
+        }
+        store.add_opening(walls[0], OpeningType::Door, 0.5, 3.0, 6.8, 0.0).unwrap();
+        store.add_opening(walls[1], OpeningType::Window, 0.5, 4.0, 4.0, 3.0).unwrap();
+        store
+            .create_room(level_id, RoomType::Kitchen, "Café", Polygon2::new(corners.iter().map(|c| Point2::new(c.0, c.1)).collect()))
+            .unwrap();
+        store.create_grid(building_id).unwrap();
+        store
+            .add_grid_axis(building_id, GridAxis { name: "A".into(), direction: GridDirection::Horizontal, offset: 0.0 })
+            .unwrap();
+
+        let dxf = render_plan_dxf(&store, level_id).unwrap();
+        assert!(dxf.trim_end().ends_with("EOF"));
+        for (name, _) in LAYERS {
+            assert!(dxf.contains(&format!("  2\n{}\n", name)), "missing layer {}", name);
+        }
+        // Four walls, the door and window each split theirs in two
+        assert_eq!(dxf.matches("POLYLINE\n  8\nWALLS\n").count(), 6);
+        assert_eq!(dxf.matches("ARC\n  8\nOPENINGS\n").count(), 1);
+        assert_eq!(dxf.matches("CIRCLE\n  8\nGRID\n").count(), 1);
+        assert!(dxf.contains("Caf\\U+00E9"));
+        assert!(dxf.contains("780 sq ft"));
+        assert!(dxf.contains("33'-0\"") && dxf.contains("24'-6\""));
+        // South wall's outside face, in inches
+        assert!(dxf.contains(" 10\n-3.0\n"));
+
+        assert!(render_plan_dxf(&store, LevelId::new()).is_err());
+    }
+}
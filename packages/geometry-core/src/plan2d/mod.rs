@@ -0,0 +1,555 @@
+// 2D floor plans - renders a level as an SVG drawing
+// Plan coordinates are feet with +y north; the SVG flips y so north is up.
+// Walls are drawn at their assembly thickness with gaps at openings, doors
+// with a leaf and swing arc, windows with a glazing line and other openings
+// as bare jambs. Doors carry no handing, so every leaf is hinged on the jamb
+// nearer the wall's start and swings to the wall's left. Detail callouts are
+// split bubbles, detail number over sheet, on a leader up and to the right
+// of the point they mark.
+
+pub mod dxf;
+
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    DetailCallout, ElectricalDevice, ElectricalDeviceKind, Grid, GridAxis, GridDirection, LevelId, Opening, OpeningType,
+    Point2, Wall,
+};
+use crate::store::Store;
+
+pub use dxf::render_plan_dxf;
+
+/// Segments per arc when tracing curved walls
+const ARC_SEGMENTS: usize = 16;
+/// Distance (feet, along each axis) from a callout's point to its bubble
+const CALLOUT_OFFSET: f64 = 2.0;
+const CALLOUT_RADIUS: f64 = 0.8;
+
+/// Drawing options for a plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanSvgOptions {
+    /// Pixels per foot for the SVG's width and height
+    pub scale: f64,
+    /// White space around the drawing (feet)
+    pub margin: f64,
+    pub show_rooms: bool,
+    pub show_grid: bool,
+    pub show_dimensions: bool,
+    pub show_electrical: bool,
+    pub show_details: bool,
+}
+
+impl Default for PlanSvgOptions {
+    fn default() -> Self {
+        Self {
+            scale: 24.0,
+            margin: 6.0,
+            show_rooms: true,
+            show_grid: true,
+            show_dimensions: true,
+            show_electrical: true,
+            show_details: true,
+        }
+    }
+}
+
+/// Plan extents, grown point by point
+#[derive(Debug, Clone, Copy)]
+struct Extents {
+    min: Point2,
+    max: Point2,
+}
+
+impl Extents {
+    fn empty() -> Self {
+        Self {
+            min: Point2::new(f64::INFINITY, f64::INFINITY),
+            max: Point2::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    fn include(&mut self, p: &Point2) {
+        self.min = Point2::new(self.min.x.min(p.x), self.min.y.min(p.y));
+        self.max = Point2::new(self.max.x.max(p.x), self.max.y.max(p.y));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min.x > self.max.x
+    }
+}
+
+/// Render a level's floor plan as an SVG document
+pub fn render_plan_svg(store: &Store, level_id: LevelId, options: &PlanSvgOptions) -> Result<String> {
+    let level = store.get_level(level_id)
+        .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+    let walls = store.get_level_walls(level_id);
+    let rooms = store.get_level_rooms(level_id);
+    let decks = store.get_level_decks(level_id);
+    let callouts = if options.show_details { store.get_level_detail_callouts(level_id) } else { Vec::new() };
+    let grid = store.get_grid(level.building_id).filter(|_| options.show_grid);
+
+    // Dimensions measure the building itself; grids and decks only widen the sheet
+    let building = building_extents(store, level_id);
+    let mut sheet = sheet_extents(building, grid);
+    for deck in &decks {
+        deck.outline().outer.iter().for_each(|p| sheet.include(p));
+    }
+    for callout in &callouts {
+        let reach = CALLOUT_OFFSET + CALLOUT_RADIUS;
+        sheet.include(&callout.location);
+        sheet.include(&Point2::new(callout.location.x + reach, callout.location.y + reach));
+    }
+    let pad = options.margin.max(0.0);
+    let (x0, y0) = (sheet.min.x - pad, -(sheet.max.y + pad));
+    let (width, height) = (sheet.max.x - sheet.min.x + 2.0 * pad, sheet.max.y - sheet.min.y + 2.0 * pad);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+        num(x0), num(y0), num(width), num(height),
+        num(width * options.scale), num(height * options.scale),
+    );
+    let _ = writeln!(svg, "<title>{}</title>", escape(&level.name));
+
+    if let Some(grid) = grid {
+        let _ = writeln!(svg, r##"<g id="grid" stroke="#9aa5b1" stroke-width="0.04" stroke-dasharray="0.6 0.2 0.1 0.2" fill="none">"##);
+        let mut labels = String::new();
+        for axis in &grid.axes {
+            let (start, end) = grid_line(axis, &sheet, pad / 2.0);
+            let _ = writeln!(svg, "{}", line(&start, &end));
+            let _ = writeln!(
+                labels,
+                r#"<circle cx="{}" cy="{}" r="0.7"/><text x="{}" y="{}">{}</text>"#,
+                num(start.x), num(-start.y), num(start.x), num(-start.y + 0.25), escape(&axis.name),
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+        let _ = writeln!(
+            svg,
+            r##"<g id="grid-labels" stroke="#9aa5b1" stroke-width="0.04" fill="#ffffff" font-size="0.7" text-anchor="middle">"##
+        );
+        svg.push_str(&labels.replace("<text ", r##"<text fill="#52606d" stroke="none" "##));
+        let _ = writeln!(svg, "</g>");
+    }
+
+    if options.show_rooms {
+        let _ = writeln!(svg, r##"<g id="rooms" fill="#f5f7fa" stroke="#cbd2d9" stroke-width="0.03">"##);
+        for room in &rooms {
+            let _ = writeln!(svg, r#"<polygon data-room-id="{}" points="{}"/>"#, room.id, points(&room.boundary.outer));
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    if !decks.is_empty() {
+        let _ = writeln!(svg, r##"<g id="decks" fill="#fdf6e3" stroke="#8d6e63" stroke-width="0.04">"##);
+        for deck in decks {
+            let _ = writeln!(
+                svg,
+                r#"<polygon data-deck-id="{}" data-kind="{}" points="{}"/>"#,
+                deck.id, deck.kind.as_str(), points(&deck.outline().outer),
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    let _ = writeln!(svg, r##"<g id="walls" fill="#323f4b" stroke="none">"##);
+    let mut openings_svg = String::new();
+    for wall in &walls {
+        let half = store.wall_thickness(wall) / 2.0;
+        let length = wall.length();
+
+        // Solid runs of wall between the openings
+        let mut openings: Vec<&Opening> = store.get_wall_openings(wall.id);
+        openings.sort_by(|a, b| a.position_along_wall.total_cmp(&b.position_along_wall));
+        let mut from = 0.0;
+        for opening in &openings {
+            let center = opening.position_along_wall * length;
+            let (d0, d1) = ((center - opening.width / 2.0).max(0.0), (center + opening.width / 2.0).min(length));
+            if d0 > from {
+                let _ = writeln!(svg, r#"<polygon data-wall-id="{}" points="{}"/>"#, wall.id, outline_points(wall, from, d0, half));
+            }
+            from = from.max(d1);
+            openings_svg.push_str(&opening_symbol(wall, opening, d0, d1, half));
+        }
+        if length > from {
+            let _ = writeln!(svg, r#"<polygon data-wall-id="{}" points="{}"/>"#, wall.id, outline_points(wall, from, length, half));
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+    let _ = writeln!(svg, r##"<g id="openings" fill="none" stroke="#323f4b" stroke-width="0.04">"##);
+    svg.push_str(&openings_svg);
+    let _ = writeln!(svg, "</g>");
+
+    if options.show_rooms {
+        let _ = writeln!(svg, r##"<g id="room-labels" fill="#323f4b" font-size="0.8" text-anchor="middle">"##);
+        for room in &rooms {
+            let c = room.boundary.centroid();
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}">{}</text><text x="{}" y="{}" font-size="0.6">{:.0} sq ft</text>"#,
+                num(c.x), num(-c.y), escape(&room.name), num(c.x), num(-c.y + 0.9), room.area(),
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    if options.show_electrical {
+        let devices = store.get_level_electrical_devices(level_id);
+        if !devices.is_empty() {
+            let _ = writeln!(
+                svg,
+                r##"<g id="electrical" fill="#ffffff" stroke="#c05621" stroke-width="0.04" font-size="0.5" text-anchor="middle">"##
+            );
+            for device in devices {
+                svg.push_str(&device_symbol(device));
+            }
+            let _ = writeln!(svg, "</g>");
+        }
+    }
+
+    if !callouts.is_empty() {
+        let _ = writeln!(
+            svg,
+            r##"<g id="details" fill="#ffffff" stroke="#323f4b" stroke-width="0.04" font-size="0.5" text-anchor="middle">"##
+        );
+        for callout in callouts {
+            svg.push_str(&callout_symbol(callout));
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    if options.show_dimensions && building.max.x > building.min.x {
+        let _ = writeln!(
+            svg,
+            r##"<g id="dimensions" stroke="#52606d" stroke-width="0.03" fill="#52606d" font-size="0.7" text-anchor="middle">"##
+        );
+        let offset = (pad / 2.0).max(1.5);
+        let below = building.min.y - offset;
+        svg.push_str(&dimension(
+            &Point2::new(building.min.x, below),
+            &Point2::new(building.max.x, below),
+        ));
+        let left = building.min.x - offset;
+        svg.push_str(&dimension(
+            &Point2::new(left, building.min.y),
+            &Point2::new(left, building.max.y),
+        ));
+        let _ = writeln!(svg, "</g>");
+    }
+
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+/// Plan symbol for an electrical device: a circle with two prongs for a
+/// receptacle (marked GFI when ground-fault protected), an S for a switch
+/// and a crossed circle for a ceiling fixture
+fn device_symbol(device: &ElectricalDevice) -> String {
+    let p = device.location;
+    let circle = |r: f64| format!(r#"<circle cx="{}" cy="{}" r="{}"/>"#, num(p.x), num(-p.y), num(r));
+    let text = |label: &str, dy: f64| {
+        format!(r##"<text x="{}" y="{}" fill="#c05621" stroke="none">{}</text>"##, num(p.x), num(-p.y + dy), label)
+    };
+    let offset = |dx: f64, dy: f64| Point2::new(p.x + dx, p.y + dy);
+    let body = match device.kind {
+        ElectricalDeviceKind::Receptacle | ElectricalDeviceKind::GfciReceptacle => {
+            let mut body = circle(0.3);
+            body.push_str(&line(&offset(-0.1, -0.12), &offset(-0.1, 0.12)));
+            body.push_str(&line(&offset(0.1, -0.12), &offset(0.1, 0.12)));
+            if device.kind == ElectricalDeviceKind::GfciReceptacle {
+                body.push_str(&text("GFI", -0.45));
+            }
+            body
+        }
+        ElectricalDeviceKind::Switch => text("S", 0.18),
+        ElectricalDeviceKind::LightFixture => {
+            let r = 0.4 * std::f64::consts::FRAC_1_SQRT_2;
+            let mut body = circle(0.4);
+            body.push_str(&line(&offset(-r, -r), &offset(r, r)));
+            body.push_str(&line(&offset(-r, r), &offset(r, -r)));
+            body
+        }
+    };
+    format!("<g data-device-id=\"{}\">{}</g>\n", device.id, body)
+}
+
+/// Detail callout: a leader from the marked point to a bubble split by a
+/// bar, with the detail number above and the sheet below
+fn callout_symbol(callout: &DetailCallout) -> String {
+    let p = callout.location;
+    let c = Point2::new(p.x + CALLOUT_OFFSET, p.y + CALLOUT_OFFSET);
+    let r = CALLOUT_RADIUS;
+    // The leader stops at the bubble's edge
+    let edge = r * std::f64::consts::FRAC_1_SQRT_2;
+    let text = |label: &str, dy: f64| {
+        format!(r##"<text x="{}" y="{}" fill="#323f4b" stroke="none">{}</text>"##, num(c.x), num(-c.y + dy), escape(label))
+    };
+    format!(
+        "<g data-callout-id=\"{}\"><title>{}</title>{}<circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>{}{}{}</g>\n",
+        callout.id,
+        escape(&callout.label()),
+        line(&p, &Point2::new(c.x - edge, c.y - edge)),
+        num(c.x), num(-c.y), num(r),
+        line(&Point2::new(c.x - r, c.y), &Point2::new(c.x + r, c.y)),
+        text(&callout.reference.detail_number, -0.15),
+        text(&callout.reference.sheet, 0.5),
+    )
+}
+
+/// Extents of a level's footprints, wall faces and rooms
+fn building_extents(store: &Store, level_id: LevelId) -> Extents {
+    let mut building = Extents::empty();
+    for footprint in store.get_level_footprints(level_id) {
+        footprint.polygon.outer.iter().for_each(|p| building.include(p));
+    }
+    for wall in store.get_level_walls(level_id) {
+        let half = store.wall_thickness(wall) / 2.0;
+        for (left, right) in wall_outline(wall, 0.0, wall.length(), half) {
+            building.include(&left);
+            building.include(&right);
+        }
+    }
+    for room in store.get_level_rooms(level_id) {
+        room.boundary.outer.iter().for_each(|p| building.include(p));
+    }
+    if building.is_empty() {
+        building.include(&Point2::origin());
+    }
+    building
+}
+
+/// Building extents widened to take in every grid axis
+fn sheet_extents(building: Extents, grid: Option<&Grid>) -> Extents {
+    let mut sheet = building;
+    for axis in grid.map(|g| g.axes.as_slice()).unwrap_or_default() {
+        match axis.direction {
+            GridDirection::Horizontal => sheet.include(&Point2::new(building.min.x, axis.offset)),
+            GridDirection::Vertical => sheet.include(&Point2::new(axis.offset, building.min.y)),
+        }
+    }
+    sheet
+}
+
+/// Grid axis drawn across the sheet, running `reach` past it at both ends;
+/// the bubble goes at the start
+fn grid_line(axis: &GridAxis, sheet: &Extents, reach: f64) -> (Point2, Point2) {
+    match axis.direction {
+        GridDirection::Horizontal => (
+            Point2::new(sheet.min.x - reach, axis.offset),
+            Point2::new(sheet.max.x + reach, axis.offset),
+        ),
+        GridDirection::Vertical => (
+            Point2::new(axis.offset, sheet.min.y - reach),
+            Point2::new(axis.offset, sheet.max.y + reach),
+        ),
+    }
+}
+
+/// Pairs of (left, right) face points along a wall from `d0` to `d1`
+fn wall_outline(wall: &Wall, d0: f64, d1: f64, half: f64) -> Vec<(Point2, Point2)> {
+    let steps = if wall.is_curved() { ARC_SEGMENTS } else { 1 };
+    (0..=steps)
+        .map(|i| {
+            let d = d0 + (d1 - d0) * i as f64 / steps as f64;
+            let p = wall.point_at(d);
+            let (tx, ty) = wall.tangent_at(d);
+            (
+                Point2::new(p.x - ty * half, p.y + tx * half),
+                Point2::new(p.x + ty * half, p.y - tx * half),
+            )
+        })
+        .collect()
+}
+
+/// Closed outline of a run of wall, as SVG polygon points
+fn outline_points(wall: &Wall, d0: f64, d1: f64, half: f64) -> String {
+    let faces = wall_outline(wall, d0, d1, half);
+    let ring: Vec<Point2> = faces.iter().map(|(l, _)| *l)
+        .chain(faces.iter().rev().map(|(_, r)| *r))
+        .collect();
+    points(&ring)
+}
+
+/// Door leaf and swing, or window jambs and glazing, in an opening's gap
+fn opening_symbol(wall: &Wall, opening: &Opening, d0: f64, d1: f64, half: f64) -> String {
+    let faces = wall_outline(wall, d0, d1, half);
+    let (Some(first), Some(last)) = (faces.first(), faces.last()) else {
+        return String::new();
+    };
+    let mut out = String::new();
+    match opening.opening_type {
+        OpeningType::Door => {
+            let hinge = wall.point_at(d0);
+            let strike = wall.point_at(d1);
+            let (tx, ty) = wall.tangent_at(d0);
+            let leaf = d1 - d0;
+            let open = Point2::new(hinge.x - ty * leaf, hinge.y + tx * leaf);
+            // Sweep direction as seen with the SVG's y axis pointing down
+            let cross = (open.x - hinge.x) * (hinge.y - strike.y) - (hinge.y - open.y) * (strike.x - hinge.x);
+            let sweep = if cross > 0.0 { 1 } else { 0 };
+            let _ = writeln!(
+                out,
+                r#"<path data-opening-id="{}" d="M {} {} L {} {} A {} {} 0 0 {} {} {}"/>"#,
+                opening.id,
+                num(hinge.x), num(-hinge.y), num(open.x), num(-open.y),
+                num(leaf), num(leaf), sweep, num(strike.x), num(-strike.y),
+            );
+        }
+        OpeningType::Window => {
+            let steps = if wall.is_curved() { ARC_SEGMENTS } else { 1 };
+            let glass: Vec<Point2> = (0..=steps)
+                .map(|i| wall.point_at(d0 + (d1 - d0) * i as f64 / steps as f64))
+                .collect();
+            let _ = writeln!(
+                out,
+                r#"<g data-opening-id="{}">{}{}<polyline points="{}"/></g>"#,
+                opening.id,
+                line(&first.0, &first.1),
+                line(&last.0, &last.1),
+                points(&glass),
+            );
+        }
+        OpeningType::Other(_) => {
+            let _ = writeln!(
+                out,
+                r#"<g data-opening-id="{}">{}{}</g>"#,
+                opening.id,
+                line(&first.0, &first.1),
+                line(&last.0, &last.1),
+            );
+        }
+    }
+    out
+}
+
+/// Dimension string between two points: line, end ticks and length
+fn dimension(a: &Point2, b: &Point2) -> String {
+    let length = a.distance_to(b);
+    let (ux, uy) = ((b.x - a.x) / length, (b.y - a.y) / length);
+    let tick = 0.35;
+    let tick_at = |p: &Point2| {
+        line(
+            &Point2::new(p.x - (ux - uy) * tick, p.y - (uy + ux) * tick),
+            &Point2::new(p.x + (ux - uy) * tick, p.y + (uy + ux) * tick),
+        )
+    };
+    let mid = Point2::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+    let angle = -uy.atan2(ux).to_degrees();
+    format!(
+        "{}{}{}<text x=\"{}\" y=\"{}\" stroke=\"none\" transform=\"rotate({} {} {})\">{}</text>\n",
+        line(a, b), tick_at(a), tick_at(b),
+        num(mid.x), num(-mid.y - 0.25), num(angle), num(mid.x), num(-mid.y),
+        feet_inches(length),
+    )
+}
+
+/// Length as feet and inches to the nearest inch, e.g. 32'-6"
+fn feet_inches(feet: f64) -> String {
+    let inches = (feet * 12.0).round() as i64;
+    format!("{}'-{}\"", inches / 12, inches % 12)
+}
+
+fn line(a: &Point2, b: &Point2) -> String {
+    format!(
+        r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+        num(a.x), num(-a.y), num(b.x), num(-b.y),
+    )
+}
+
+fn points(ring: &[Point2]) -> String {
+    ring.iter()
+        .map(|p| format!("{},{}", num(p.x), num(-p.y)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Coordinate to at most four decimals, without trailing zeros
+fn num(value: f64) -> String {
+    let s = format!("{:.4}", value);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0".to_string() } else { s.to_string() }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    #[test]
+    fn test_render_plan_svg() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::new("Stud", 6.0, true)])
+            .unwrap();
+        let corners = [(0.0, 0.0), (32.5, 0.0), (32.5, 24.0), (0.0, 24.0)];
+        let mut walls = Vec::new();
+        for i in 0..4 {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            walls.push(store
+                .create_wall(level_id, assembly_id, Point2::new(a.0, a.1), Point2::new(b.0, b.1), 9.0)
+                .unwrap());
+        }
+        store.add_opening(walls[0], OpeningType::Door, 0.5, 3.0, 6.8, 0.0).unwrap();
+        store.add_opening(walls[1], OpeningType::Window, 0.5, 4.0, 4.0, 3.0).unwrap();
+        store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen & Dining", Polygon2::new(corners.iter().map(|c| Point2::new(c.0, c.1)).collect()))
+            .unwrap();
+        store.create_grid(building_id).unwrap();
+        store
+            .add_grid_axis(building_id, GridAxis { name: "A".into(), direction: GridDirection::Horizontal, offset: 0.0 })
+            .unwrap();
+
+        let svg = render_plan_svg(&store, level_id, &PlanSvgOptions::default()).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        // The door splits the south wall in two
+        assert_eq!(svg.matches(&format!("data-wall-id=\"{}\"", walls[0])).count(), 2);
+        assert_eq!(svg.matches("<path data-opening-id").count(), 1);
+        assert!(svg.contains("Kitchen &amp; Dining"));
+        assert!(svg.contains("780 sq ft"));
+        // Overall dimensions run to the outside faces of the walls
+        assert!(svg.contains("33'-0\"") && svg.contains("24'-6\""));
+        assert!(svg.contains(">A</text>"));
+
+        // Devices get markers of their own, hidden with the electrical layer
+        store.auto_layout_electrical(level_id).unwrap();
+        let svg = render_plan_svg(&store, level_id, &PlanSvgOptions::default()).unwrap();
+        let devices = store.get_level_electrical_devices(level_id).len();
+        assert_eq!(svg.matches("data-device-id").count(), devices);
+        assert!(svg.contains(">GFI</text>") && svg.contains(">S</text>"));
+
+        // Detail callouts get a bubble and leader labelled with their reference
+        let reference = DetailReference::new(DetailKind::TypicalWallSection, "3", "A-501");
+        let callout_id = store.add_wall_detail_callout(walls[2], reference).unwrap();
+        let svg = render_plan_svg(&store, level_id, &PlanSvgOptions::default()).unwrap();
+        assert!(svg.contains(&format!("data-callout-id=\"{}\"", callout_id)));
+        assert!(svg.contains("<title>3/A-501</title>"));
+        assert!(svg.contains(">3</text>") && svg.contains(">A-501</text>"));
+        let hidden = PlanSvgOptions { show_details: false, ..Default::default() };
+        assert!(!render_plan_svg(&store, level_id, &hidden).unwrap().contains("data-callout-id"));
+
+        let bare = PlanSvgOptions { show_grid: false, show_dimensions: false, ..Default::default() };
+        let svg = render_plan_svg(&store, level_id, &bare).unwrap();
+        assert!(!svg.contains("id=\"grid\"") && !svg.contains("id=\"dimensions\""));
+        let plain = PlanSvgOptions { show_electrical: false, ..Default::default() };
+        assert!(!render_plan_svg(&store, level_id, &plain).unwrap().contains("id=\"electrical\""));
+        assert!(render_plan_svg(&store, LevelId::new(), &bare).is_err());
+    }
+}
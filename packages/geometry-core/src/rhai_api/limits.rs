@@ -0,0 +1,117 @@
+// Wall-clock timeouts and cancellation for script execution
+// The operation limit bounds how much work a script does but not how long
+// it takes, since one call into the store can be slow. A running script is
+// checked against its deadline and its cancellation token from Rhai's
+// progress callback, and stopped between operations if either has passed.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rhai::{Dynamic, Engine};
+use serde::{Deserialize, Serialize};
+
+/// Operations between clock reads
+const CHECK_INTERVAL: u64 = 1_000;
+
+/// Handle for stopping a running script, from another thread or a host callback
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits on one script execution beyond the engine's operation count
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionLimits {
+    pub timeout: Option<Duration>,
+    pub cancel: Option<CancellationToken>,
+}
+
+impl ExecutionLimits {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self { timeout: Some(timeout), cancel: None }
+    }
+}
+
+/// Why a script was stopped before it finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptAbort {
+    Timeout,
+    Cancelled,
+}
+
+impl ScriptAbort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScriptAbort::Timeout => "timeout",
+            ScriptAbort::Cancelled => "cancelled",
+        }
+    }
+
+    /// The abort a terminated script was stopped for
+    pub(super) fn from_token(token: &Dynamic) -> Option<Self> {
+        match token.clone().into_string().ok()?.as_str() {
+            "timeout" => Some(ScriptAbort::Timeout),
+            "cancelled" => Some(ScriptAbort::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+struct ActiveLimits {
+    deadline: Option<DateTime<Utc>>,
+    cancel: Option<CancellationToken>,
+}
+
+thread_local! {
+    static ACTIVE_LIMITS: RefCell<Option<ActiveLimits>> = const { RefCell::new(None) };
+}
+
+/// Stop scripts that run past their limits
+pub(super) fn register_limits(engine: &mut Engine) {
+    engine.on_progress(|operations| {
+        if operations % CHECK_INTERVAL != 0 {
+            return None;
+        }
+        ACTIVE_LIMITS.with(|limits| {
+            let limits = limits.borrow();
+            let limits = limits.as_ref()?;
+            if limits.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+                return Some(Dynamic::from(ScriptAbort::Cancelled.as_str()));
+            }
+            if limits.deadline.is_some_and(|d| Utc::now() >= d) {
+                return Some(Dynamic::from(ScriptAbort::Timeout.as_str()));
+            }
+            None
+        })
+    });
+}
+
+/// Run `f` under `limits`, restoring whatever limits were active before
+pub(super) fn limited<T>(limits: &ExecutionLimits, f: impl FnOnce() -> T) -> T {
+    let active = ActiveLimits {
+        deadline: limits.timeout
+            .and_then(|t| chrono::Duration::from_std(t).ok())
+            .map(|t| Utc::now() + t),
+        cancel: limits.cancel.clone(),
+    };
+    let outer = ACTIVE_LIMITS.with(|l| l.replace(Some(active)));
+    let value = f();
+    ACTIVE_LIMITS.with(|l| l.replace(outer));
+    value
+}
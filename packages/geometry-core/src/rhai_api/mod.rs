@@ -2,13 +2,24 @@
 // Exposes safe, sandboxed functions for AI and human scripts to drive modeling
 // Updated to use StructuredError for observable feedback loops with Gemini
 
-use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope, AST};
-use std::sync::Arc;
+use std::cell::Cell;
+
+use rhai::{Dynamic, Engine, EvalAltResult, Map, NativeCallContext, Scope, AST};
 use anyhow::{anyhow, Result};
 
+use crate::analysis::{check_bearing_continuity, BearingIssue};
+use crate::compliance::{check_compliance, switch_code_region, ComplianceViolation};
+use crate::costing::CostCalculator;
 use crate::domain::*;
 use crate::domain::error::{StructuredError, EntityType};
-use crate::store::SharedStore;
+use crate::store::{RoomAdjacency, SharedStore, Store};
+use crate::templates::{instantiate_template, BuildingTemplate, TemplateInstance, TemplateParams};
+
+mod limits;
+mod trace;
+
+pub use limits::{CancellationToken, ExecutionLimits, ScriptAbort};
+pub use trace::{dynamic_to_json, TracedCall};
 
 /// Convert a StructuredError to a Rhai EvalAltResult
 /// The error message is JSON-formatted for Gemini to parse
@@ -22,11 +33,46 @@ pub struct ScriptResult {
     pub success: bool,
     pub return_value: Option<Dynamic>,
     pub error: Option<String>,
+    /// Events recorded, including those of a script that failed or was
+    /// aborted partway
     pub events_generated: usize,
+    /// Domain functions called, in order, up to the failing one
+    pub trace: Vec<TracedCall>,
+    /// Set when the script was stopped by a timeout or cancellation
+    pub aborted: Option<ScriptAbort>,
+    /// Wall-clock time the script ran for
+    pub duration_ms: f64,
+}
+
+impl ScriptResult {
+    /// The result as JSON, with entity handles written as their IDs
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "success": self.success,
+            "value": self.return_value.as_ref().map(dynamic_to_json),
+            "error": self.error,
+            "events_generated": self.events_generated,
+            "trace": self.trace,
+            "aborted": self.aborted,
+            "duration_ms": self.duration_ms,
+        })
+    }
 }
 
 /// Create a sandboxed Rhai engine with domain functions registered
 pub fn create_engine(store: SharedStore) -> Engine {
+    build_engine(store, None)
+}
+
+/// Create an engine whose scripts can only read and edit one project.
+/// Entity handles can't be forged in a script, so binding the functions
+/// that name a project (and the ones that list or create projects) keeps
+/// every handle a script can reach inside the project.
+pub fn create_project_engine(store: SharedStore, project_id: ProjectId) -> Engine {
+    build_engine(store, Some(project_id))
+}
+
+fn build_engine(store: SharedStore, scope: Option<ProjectId>) -> Engine {
     let mut engine = Engine::new();
 
     // Disable potentially dangerous operations
@@ -37,6 +83,9 @@ pub fn create_engine(store: SharedStore) -> Engine {
     engine.set_max_array_size(1_000);
     engine.set_max_map_size(500);
 
+    // Wall-clock timeouts and cancellation, checked as the script runs
+    limits::register_limits(&mut engine);
+
     // Register custom types for IDs (as opaque handles)
     engine.register_type_with_name::<ProjectId>("ProjectId");
     engine.register_type_with_name::<BuildingId>("BuildingId");
@@ -47,17 +96,41 @@ pub fn create_engine(store: SharedStore) -> Engine {
     engine.register_type_with_name::<WallAssemblyId>("WallAssemblyId");
     engine.register_type_with_name::<RoomId>("RoomId");
     engine.register_type_with_name::<OpeningId>("OpeningId");
+    engine.register_type_with_name::<FilterId>("FilterId");
+    engine.register_type_with_name::<ScriptId>("ScriptId");
+    engine.register_type_with_name::<SnapshotId>("SnapshotId");
+    engine.register_type_with_name::<DetailCalloutId>("DetailCalloutId");
+    engine.register_type_with_name::<RoofId>("RoofId");
+    engine.register_type_with_name::<FoundationId>("FoundationId");
+    engine.register_type_with_name::<StairId>("StairId");
+    engine.register_type_with_name::<FixtureId>("FixtureId");
 
     // Register domain functions
-    register_project_functions(&mut engine, store.clone());
-    register_building_functions(&mut engine, store.clone());
+    register_project_functions(&mut engine, store.clone(), scope);
+    register_building_functions(&mut engine, store.clone(), scope);
     register_level_functions(&mut engine, store.clone());
     register_footprint_functions(&mut engine, store.clone());
     register_grid_functions(&mut engine, store.clone());
     register_wall_functions(&mut engine, store.clone());
     register_room_functions(&mut engine, store.clone());
     register_opening_functions(&mut engine, store.clone());
-    register_query_functions(&mut engine, store.clone());
+    register_query_functions(&mut engine, store.clone(), scope);
+    register_filter_functions(&mut engine, store.clone(), scope);
+    register_script_functions(&mut engine, store.clone(), scope);
+    register_snapshot_functions(&mut engine, store.clone(), scope);
+    register_detail_functions(&mut engine, store.clone(), scope);
+    register_roof_functions(&mut engine, store.clone());
+    register_foundation_functions(&mut engine, store.clone());
+    register_plumbing_functions(&mut engine, store.clone());
+    register_template_functions(&mut engine, store.clone(), scope);
+    register_costing_functions(&mut engine, store.clone());
+    register_compliance_functions(&mut engine, store.clone());
+
+    // Trace calls to everything registered above
+    trace::register_call_trace(&mut engine);
+
+    // Unit helpers are too common in scripts to be worth tracing
+    register_unit_functions(&mut engine);
 
     engine
 }
@@ -67,45 +140,159 @@ pub fn execute_script(
     engine: &Engine,
     script: &str,
     store: SharedStore,
+) -> ScriptResult {
+    execute_in_scope(engine, script, &mut Scope::new(), store, &ExecutionLimits::default())
+}
+
+/// Execute a Rhai script, stopping it at a timeout or when cancelled
+pub fn execute_script_with_limits(
+    engine: &Engine,
+    script: &str,
+    store: SharedStore,
+    limits: &ExecutionLimits,
+) -> ScriptResult {
+    execute_in_scope(engine, script, &mut Scope::new(), store, limits)
+}
+
+fn execute_in_scope(
+    engine: &Engine,
+    script: &str,
+    scope: &mut Scope,
+    store: SharedStore,
+    limits: &ExecutionLimits,
 ) -> ScriptResult {
     let events_before = {
         let store_read = store.read().unwrap();
         store_read.event_logs.values().map(|l| l.len()).sum::<usize>()
     };
 
-    let result = engine.eval::<Dynamic>(script);
+    // chrono rather than Instant, which panics on wasm32
+    let started = chrono::Utc::now();
+    let (result, trace) = limits::limited(limits, || {
+        trace::traced(|| engine.eval_with_scope::<Dynamic>(scope, script))
+    });
+    let duration_ms = (chrono::Utc::now() - started).num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0;
 
     let events_after = {
         let store_read = store.read().unwrap();
         store_read.event_logs.values().map(|l| l.len()).sum::<usize>()
     };
 
+    // Restoring a snapshot can shrink the logs
+    let events_generated = events_after.saturating_sub(events_before);
     match result {
         Ok(value) => ScriptResult {
             success: true,
             return_value: Some(value),
             error: None,
-            events_generated: events_after - events_before,
+            events_generated,
+            trace,
+            aborted: None,
+            duration_ms,
         },
         Err(e) => ScriptResult {
             success: false,
             return_value: None,
+            aborted: match &*e {
+                EvalAltResult::ErrorTerminated(token, _) => ScriptAbort::from_token(token),
+                _ => None,
+            },
             error: Some(e.to_string()),
-            events_generated: 0,
+            events_generated,
+            trace,
+            duration_ms,
         },
     }
 }
 
+/// Execute a script in a project, which it sees as `project`. Pair with an
+/// engine from `create_project_engine` to keep it inside the project.
+pub fn execute_in_project(
+    engine: &Engine,
+    script: &str,
+    store: SharedStore,
+    project_id: ProjectId,
+    limits: &ExecutionLimits,
+) -> ScriptResult {
+    execute_in_scope(engine, script, &mut saved_script_scope(project_id, Map::new()), store, limits)
+}
+
+/// Run a script saved in a project. The script sees the project as
+/// `project` and the parameters as `params`.
+pub fn execute_saved_script(
+    engine: &Engine,
+    store: SharedStore,
+    project_id: ProjectId,
+    name: &str,
+    params: Map,
+    limits: &ExecutionLimits,
+) -> Result<ScriptResult> {
+    let source = store.read().unwrap()
+        .find_script(project_id, name)
+        .map(|s| s.source.clone())
+        .ok_or_else(|| anyhow!("No saved script named {}", name))?;
+    Ok(execute_in_scope(engine, &source, &mut saved_script_scope(project_id, params), store, limits))
+}
+
+fn saved_script_scope(project_id: ProjectId, params: Map) -> Scope<'static> {
+    let mut scope = Scope::new();
+    scope.push_constant("project", project_id);
+    scope.push_constant("params", params);
+    scope
+}
+
 /// Compile a script without executing (for validation)
 pub fn compile_script(engine: &Engine, script: &str) -> Result<AST> {
     engine.compile(script).map_err(|e| anyhow!("Compilation error: {}", e))
 }
 
+// ========== Unit Functions ==========
+
+const FEET_PER_METER: f64 = 1.0 / 0.3048;
+
+/// Length helpers that convert to feet, the model's length unit whatever
+/// the project's display units, e.g. `ft(12) + inches(6)` or `m(3.2)`
+fn register_unit_functions(engine: &mut Engine) {
+    let units = [
+        ("ft", 1.0),
+        ("feet", 1.0),
+        ("inches", 1.0 / 12.0),
+        ("m", FEET_PER_METER),
+        ("cm", FEET_PER_METER / 100.0),
+        ("mm", FEET_PER_METER / 1000.0),
+    ];
+    for (name, feet) in units {
+        engine.register_fn(name, move |value: Dynamic| -> Result<f64, Box<EvalAltResult>> {
+            Ok(number_arg("value", &value)? * feet)
+        });
+    }
+}
+
 // ========== Project Functions ==========
 
-fn register_project_functions(engine: &mut Engine, store: SharedStore) {
+/// Reject a project other than the one a scoped engine is bound to
+fn check_scope(scope: Option<ProjectId>, project_id: ProjectId) -> Result<(), Box<EvalAltResult>> {
+    if in_scope(scope, project_id) {
+        Ok(())
+    } else {
+        Err(structured_err(StructuredError::outside_project_scope(scope, project_id)))
+    }
+}
+
+fn in_scope(scope: Option<ProjectId>, project_id: ProjectId) -> bool {
+    scope.is_none_or(|bound| bound == project_id)
+}
+
+fn register_project_functions(engine: &mut Engine, store: SharedStore, scope: Option<ProjectId>) {
     let s = store.clone();
     engine.register_fn("create_project", move |name: &str, units: &str, code_region: &str| -> Result<ProjectId, Box<EvalAltResult>> {
+        if scope.is_some() {
+            return Err(structured_err(StructuredError::operation_not_supported(
+                "Scripts scoped to a project can't create projects",
+                vec!["Use the `project` variable for the script's own project".to_string()],
+            )));
+        }
+
         let units = match units.to_lowercase().as_str() {
             "imperial" | "us" | "feet" => UnitSystem::Imperial,
             "metric" | "si" | "meters" => UnitSystem::Metric,
@@ -121,6 +308,7 @@ fn register_project_functions(engine: &mut Engine, store: SharedStore) {
 
     let s = store.clone();
     engine.register_fn("get_project_name", move |id: ProjectId| -> Result<String, Box<EvalAltResult>> {
+        check_scope(scope, id)?;
         let store = s.read().unwrap();
         store.get_project(id)
             .map(|p| p.name.clone())
@@ -131,16 +319,39 @@ fn register_project_functions(engine: &mut Engine, store: SharedStore) {
     engine.register_fn("list_project_ids", move || -> Vec<Dynamic> {
         let store = s.read().unwrap();
         store.projects.keys()
+            .filter(|id| in_scope(scope, **id))
             .map(|id| Dynamic::from(*id))
             .collect()
     });
+
+    let s = store.clone();
+    engine.register_fn("set_project_code_region", move |project_id: ProjectId, code_region: &str| -> Result<Map, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        let code_region = parse_code_region(code_region)?;
+
+        let mut store = s.write().unwrap();
+        let change = switch_code_region(&mut store, project_id, code_region)
+            .map_err(|e| structured_err(StructuredError::from(e)))?;
+
+        let violations = |list: &[ComplianceViolation]| -> Dynamic {
+            Dynamic::from_array(list.iter().map(violation_to_map).map(Dynamic::from).collect())
+        };
+        let mut map = Map::new();
+        map.insert("old_region".into(), Dynamic::from(format!("{}_{}", change.old_region.code, change.old_region.year)));
+        map.insert("new_region".into(), Dynamic::from(format!("{}_{}", change.new_region.code, change.new_region.year)));
+        map.insert("newly_violated".into(), violations(&change.newly_violated));
+        map.insert("resolved".into(), violations(&change.resolved));
+        map.insert("compliant".into(), Dynamic::from(change.report.is_compliant()));
+        Ok(map)
+    });
 }
 
 // ========== Building Functions ==========
 
-fn register_building_functions(engine: &mut Engine, store: SharedStore) {
+fn register_building_functions(engine: &mut Engine, store: SharedStore, scope: Option<ProjectId>) {
     let s = store.clone();
     engine.register_fn("add_building", move |project_id: ProjectId, name: &str| -> Result<BuildingId, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
         let mut store = s.write().unwrap();
         store.add_building(project_id, name)
             .map_err(|e| structured_err(StructuredError::from(e)))
@@ -175,7 +386,9 @@ fn register_building_functions(engine: &mut Engine, store: SharedStore) {
 
 fn register_level_functions(engine: &mut Engine, store: SharedStore) {
     let s = store.clone();
-    engine.register_fn("add_level", move |building_id: BuildingId, name: &str, elevation: f64, floor_to_floor: f64| -> Result<LevelId, Box<EvalAltResult>> {
+    engine.register_fn("add_level", move |building_id: BuildingId, name: &str, elevation: Dynamic, floor_to_floor: Dynamic| -> Result<LevelId, Box<EvalAltResult>> {
+        let elevation = number_arg("elevation", &elevation)?;
+        let floor_to_floor = number_arg("floor_to_floor", &floor_to_floor)?;
         // Validate floor_to_floor height
         if floor_to_floor <= 0.0 {
             return Err(structured_err(StructuredError::parameter_out_of_range(
@@ -221,6 +434,28 @@ fn register_level_functions(engine: &mut Engine, store: SharedStore) {
         store.remove_level(id)
             .map_err(|e| structured_err(StructuredError::from(e)))
     });
+
+    let s = store.clone();
+    engine.register_fn("duplicate_level", move |id: LevelId, elevation: Dynamic| -> Result<LevelId, Box<EvalAltResult>> {
+        let elevation = number_arg("elevation", &elevation)?;
+        let mut store = s.write().unwrap();
+        store.duplicate_level(id, elevation)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("mirror_level", move |id: LevelId, direction: &str, offset: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let offset = number_arg("offset", &offset)?;
+        let axis = match direction.to_lowercase().as_str() {
+            "horizontal" | "h" | "x" => MirrorAxis::Horizontal(offset),
+            "vertical" | "v" | "y" => MirrorAxis::Vertical(offset),
+            _ => return Err(structured_err(StructuredError::unknown_grid_direction(direction))),
+        };
+
+        let mut store = s.write().unwrap();
+        store.mirror_level(id, axis)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
 }
 
 // ========== Footprint Functions ==========
@@ -238,7 +473,9 @@ fn register_footprint_functions(engine: &mut Engine, store: SharedStore) {
 
     // Set footprint from explicit width/depth (rectangle)
     let s = store.clone();
-    engine.register_fn("set_level_footprint_rect", move |level_id: LevelId, width: f64, depth: f64| -> Result<FootprintId, Box<EvalAltResult>> {
+    engine.register_fn("set_level_footprint_rect", move |level_id: LevelId, width: Dynamic, depth: Dynamic| -> Result<FootprintId, Box<EvalAltResult>> {
+        let width = number_arg("width", &width)?;
+        let depth = number_arg("depth", &depth)?;
         if width <= 0.0 {
             return Err(structured_err(StructuredError::parameter_out_of_range(
                 "width",
@@ -280,11 +517,98 @@ fn register_footprint_functions(engine: &mut Engine, store: SharedStore) {
     });
 
     let s = store.clone();
-    engine.register_fn("offset_footprint", move |footprint_id: FootprintId, distance: f64| -> Result<(), Box<EvalAltResult>> {
+    engine.register_fn("offset_footprint", move |footprint_id: FootprintId, distance: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let distance = number_arg("distance", &distance)?;
         let mut store = s.write().unwrap();
         store.offset_footprint(footprint_id, distance)
             .map_err(|e| structured_err(StructuredError::from(e)))
     });
+
+    // Sketch edits: vertices and edges are numbered in outline order, edge
+    // i running from vertex i to vertex i + 1
+    let s = store.clone();
+    engine.register_fn("get_footprint_vertices", move |footprint_id: FootprintId| -> Result<rhai::Array, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        let footprint = store.get_footprint(footprint_id)
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Footprint, footprint_id)))?;
+        Ok(footprint.polygon.outer
+            .iter()
+            .map(|p| Dynamic::from_array(vec![Dynamic::from(p.x), Dynamic::from(p.y)]))
+            .collect())
+    });
+
+    let s = store.clone();
+    engine.register_fn("insert_vertex", move |footprint_id: FootprintId, edge_index: i64, point: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let edge_index = index_arg("edge_index", edge_index)?;
+        let point = array_to_point(point)?;
+        let mut store = s.write().unwrap();
+        store.insert_footprint_vertex(footprint_id, edge_index, point)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("move_vertex", move |footprint_id: FootprintId, vertex_index: i64, point: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let vertex_index = index_arg("vertex_index", vertex_index)?;
+        let point = array_to_point(point)?;
+        let mut store = s.write().unwrap();
+        store.move_footprint_vertex(footprint_id, vertex_index, point)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("remove_vertex", move |footprint_id: FootprintId, vertex_index: i64| -> Result<(), Box<EvalAltResult>> {
+        let vertex_index = index_arg("vertex_index", vertex_index)?;
+        let mut store = s.write().unwrap();
+        store.remove_footprint_vertex(footprint_id, vertex_index)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Positive distances push the edge out, negative ones pull it in
+    let s = store.clone();
+    engine.register_fn("push_edge", move |footprint_id: FootprintId, edge_index: i64, distance: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let distance = number_arg("distance", &distance)?;
+        let edge_index = index_arg("edge_index", edge_index)?;
+        let mut store = s.write().unwrap();
+        store.push_footprint_edge(footprint_id, edge_index, distance)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Wings and attached garages sit alongside the level's primary footprint
+    let s = store.clone();
+    engine.register_fn("add_auxiliary_footprint", move |level_id: LevelId, points: rhai::Array| -> Result<FootprintId, Box<EvalAltResult>> {
+        let polygon = array_to_polygon(points)?;
+
+        let mut store = s.write().unwrap();
+        store.add_auxiliary_footprint(level_id, polygon)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("get_level_footprints", move |level_id: LevelId| -> rhai::Array {
+        let store = s.read().unwrap();
+        store.get_level_footprints(level_id)
+            .iter()
+            .map(|f| Dynamic::from(f.id))
+            .collect()
+    });
+
+    let s = store.clone();
+    engine.register_fn("remove_footprint", move |footprint_id: FootprintId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.remove_footprint(footprint_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Returns [kept, new]: the larger piece keeps the footprint's id
+    let s = store.clone();
+    engine.register_fn("split_footprint", move |footprint_id: FootprintId, p1: Dynamic, p2: Dynamic| -> Result<rhai::Array, Box<EvalAltResult>> {
+        let p1 = array_to_point(p1)?;
+        let p2 = array_to_point(p2)?;
+        let mut store = s.write().unwrap();
+        store.split_footprint(footprint_id, p1, p2)
+            .map(|(kept, new)| vec![Dynamic::from(kept), Dynamic::from(new)])
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
 }
 
 // ========== Grid Functions ==========
@@ -298,7 +622,8 @@ fn register_grid_functions(engine: &mut Engine, store: SharedStore) {
     });
 
     let s = store.clone();
-    engine.register_fn("add_grid_axis", move |building_id: BuildingId, name: &str, direction: &str, offset: f64| -> Result<(), Box<EvalAltResult>> {
+    engine.register_fn("add_grid_axis", move |building_id: BuildingId, name: &str, direction: &str, offset: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let offset = number_arg("offset", &offset)?;
         let dir = match direction.to_lowercase().as_str() {
             "horizontal" | "h" | "x" => GridDirection::Horizontal,
             "vertical" | "v" | "y" => GridDirection::Vertical,
@@ -315,6 +640,26 @@ fn register_grid_functions(engine: &mut Engine, store: SharedStore) {
         store.add_grid_axis(building_id, axis)
             .map_err(|e| structured_err(StructuredError::from(e)))
     });
+
+    let s = store.clone();
+    engine.register_fn("snap_point_to_grid", move |building_id: BuildingId, point: Dynamic, tolerance: Dynamic| -> Result<rhai::Array, Box<EvalAltResult>> {
+        let tolerance = number_arg("tolerance", &tolerance)?;
+        let point = array_to_point(point)?;
+        let store = s.read().unwrap();
+        store.snap_point_to_grid(building_id, point, tolerance)
+            .map(|p| vec![Dynamic::from(p.x), Dynamic::from(p.y)])
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Walls and rooms also take grid references like "A/3" in place of
+    // coordinates; this resolves one on its own
+    let s = store.clone();
+    engine.register_fn("grid_point", move |level_id: LevelId, reference: &str| -> Result<rhai::Array, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        store.resolve_grid_point(level_id, reference)
+            .map(|p| vec![Dynamic::from(p.x), Dynamic::from(p.y)])
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
 }
 
 // ========== Wall Functions ==========
@@ -328,11 +673,33 @@ fn register_wall_functions(engine: &mut Engine, store: SharedStore) {
             .map_err(|e| structured_err(StructuredError::from(e)))
     });
 
+    // Layers are maps: #{material: "XPS Foam", thickness: 2.0, r_value_per_inch: 5.0,
+    // is_structural: false, is_insulation: true}; the flags and R-value are optional
+    let s = store.clone();
+    engine.register_fn("create_wall_assembly_with_layers", move |name: &str, layers: rhai::Array| -> Result<WallAssemblyId, Box<EvalAltResult>> {
+        let layers: Vec<WallLayer> = rhai::serde::from_dynamic(&Dynamic::from(layers))
+            .map_err(|e| structured_err(StructuredError::invalid_parameter(
+                "layers",
+                format!("Invalid layer: {}", e),
+                name,
+                Some("maps with material, thickness, and optional r_value_per_inch, is_structural, is_insulation".to_string()),
+            )))?;
+        let mut store = s.write().unwrap();
+        store.create_wall_assembly(name, layers)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
     let s = store.clone();
-    engine.register_fn("create_wall", move |level_id: LevelId, assembly_id: WallAssemblyId, start: Dynamic, end: Dynamic, height: f64| -> Result<WallId, Box<EvalAltResult>> {
-        let start_pt = array_to_point(start)?;
-        let end_pt = array_to_point(end)?;
+    engine.register_fn("total_r_value", move |id: WallAssemblyId| -> Result<f64, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        store.get_wall_assembly(id)
+            .map(|a| a.total_r_value())
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::WallAssembly, id)))
+    });
 
+    let s = store.clone();
+    engine.register_fn("create_wall", move |level_id: LevelId, assembly_id: WallAssemblyId, start: Dynamic, end: Dynamic, height: Dynamic| -> Result<WallId, Box<EvalAltResult>> {
+        let height = number_arg("height", &height)?;
         // Validate height
         if height <= 0.0 {
             return Err(structured_err(StructuredError::parameter_out_of_range(
@@ -344,10 +711,86 @@ fn register_wall_functions(engine: &mut Engine, store: SharedStore) {
         }
 
         let mut store = s.write().unwrap();
+        let start_pt = level_point_arg(&store, level_id, start)?;
+        let end_pt = level_point_arg(&store, level_id, end)?;
         store.create_wall(level_id, assembly_id, start_pt, end_pt, height)
             .map_err(|e| structured_err(StructuredError::from(e)))
     });
 
+    // Curved wall: the centerline arcs from start to end (positive radius
+    // bows to the wall's left)
+    let s = store.clone();
+    engine.register_fn("create_curved_wall", move |level_id: LevelId, assembly_id: WallAssemblyId, start: Dynamic, end: Dynamic, radius: Dynamic, height: Dynamic| -> Result<WallId, Box<EvalAltResult>> {
+        let radius = number_arg("radius", &radius)?;
+        let height = number_arg("height", &height)?;
+        if height <= 0.0 {
+            return Err(structured_err(StructuredError::parameter_out_of_range(
+                "height",
+                height,
+                Some(0.0),
+                None,
+            )));
+        }
+
+        let mut store = s.write().unwrap();
+        let start_pt = level_point_arg(&store, level_id, start)?;
+        let end_pt = level_point_arg(&store, level_id, end)?;
+        store.create_curved_wall(level_id, assembly_id, start_pt, end_pt, radius, height)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Without an assembly, pick one from the wall's context
+    let s = store.clone();
+    engine.register_fn("create_wall", move |level_id: LevelId, start: Dynamic, end: Dynamic, height: Dynamic| -> Result<WallId, Box<EvalAltResult>> {
+        let height = number_arg("height", &height)?;
+        if height <= 0.0 {
+            return Err(structured_err(StructuredError::parameter_out_of_range(
+                "height",
+                height,
+                Some(0.0),
+                None,
+            )));
+        }
+
+        let mut store = s.write().unwrap();
+        let start_pt = level_point_arg(&store, level_id, start)?;
+        let end_pt = level_point_arg(&store, level_id, end)?;
+        store.create_wall_auto(level_id, start_pt, end_pt, height)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Frame a wall, replacing any framing it had
+    let s = store.clone();
+    engine.register_fn("generate_wall_framing", move |wall_id: WallId| -> Result<Map, Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        let layout_id = store.generate_wall_framing(wall_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))?;
+        let layout = store.get_framing_layout(layout_id)
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Wall, wall_id)))?;
+
+        let mut map = Map::new();
+        map.insert("stud_count".into(), Dynamic::from(layout.stud_count as i64));
+        map.insert("member_count".into(), Dynamic::from(layout.members.len() as i64));
+        map.insert("board_feet".into(), Dynamic::from(layout.total_board_feet));
+        Ok(map)
+    });
+
+    // Framing quantities for a wall, framed if it has none and regenerated
+    // only if stale
+    let s = store.clone();
+    engine.register_fn("get_framing_summary", move |wall_id: WallId| -> Result<Map, Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        let framed = store.refresh_wall_framing(wall_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))?;
+        if framed.is_none() {
+            store.generate_wall_framing(wall_id)
+                .map_err(|e| structured_err(StructuredError::from(e)))?;
+        }
+        let layout = store.get_wall_framing_layout(wall_id)
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Wall, wall_id)))?;
+        Ok(framing_summary_to_map(layout))
+    });
+
     let s = store.clone();
     engine.register_fn("get_wall_assembly", move |id: WallId| -> Result<WallAssemblyId, Box<EvalAltResult>> {
         let store = s.read().unwrap();
@@ -355,6 +798,74 @@ fn register_wall_functions(engine: &mut Engine, store: SharedStore) {
             .map(|w| w.assembly_id)
             .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Wall, id)))
     });
+
+    let s = store.clone();
+    engine.register_fn("move_wall", move |wall_id: WallId, dx: Dynamic, dy: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let dx = number_arg("dx", &dx)?;
+        let dy = number_arg("dy", &dy)?;
+        let mut store = s.write().unwrap();
+        store.move_wall(wall_id, dx, dy)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Endpoints take coordinates or grid references, like create_wall
+    let s = store.clone();
+    engine.register_fn("set_wall_endpoints", move |wall_id: WallId, start: Dynamic, end: Dynamic| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        let level_id = store.get_wall(wall_id)
+            .map(|w| w.level_id)
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Wall, wall_id)))?;
+        let start_pt = level_point_arg(&store, level_id, start)?;
+        let end_pt = level_point_arg(&store, level_id, end)?;
+        store.set_wall_endpoints(wall_id, start_pt, end_pt)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("set_wall_assembly", move |wall_id: WallId, assembly_id: WallAssemblyId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.set_wall_assembly(wall_id, assembly_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Takes the wall's openings with it
+    let s = store.clone();
+    engine.register_fn("remove_wall", move |wall_id: WallId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.remove_wall(wall_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Split at a fraction along the wall; returns the new second half
+    let s = store.clone();
+    engine.register_fn("split_wall", move |wall_id: WallId, t: Dynamic| -> Result<WallId, Box<EvalAltResult>> {
+        let t = number_arg("t", &t)?;
+        if !(t > 0.0 && t < 1.0) {
+            return Err(structured_err(StructuredError::invalid_parameter(
+                "t",
+                "Split point must be strictly between 0.0 and 1.0",
+                t.to_string(),
+                Some("0.0 < t < 1.0".to_string()),
+            )));
+        }
+        let mut store = s.write().unwrap();
+        store.split_wall(wall_id, t)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("merge_walls", move |a: WallId, b: WallId| -> Result<WallId, Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.merge_walls(a, b)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("trim_extend_to", move |wall_id: WallId, other_id: WallId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.trim_extend_to(wall_id, other_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
 }
 
 // ========== Room Functions ==========
@@ -362,31 +873,93 @@ fn register_wall_functions(engine: &mut Engine, store: SharedStore) {
 fn register_room_functions(engine: &mut Engine, store: SharedStore) {
     let s = store.clone();
     engine.register_fn("create_room", move |level_id: LevelId, room_type_str: &str, name: &str, points: rhai::Array| -> Result<RoomId, Box<EvalAltResult>> {
+        let room_type = parse_room_type(room_type_str);
+
+        let mut store = s.write().unwrap();
+        let polygon = level_polygon_arg(&store, level_id, points)?;
+        store.create_room(level_id, room_type, name, polygon)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Reshape a room; returns the change in floor area
+    let s = store.clone();
+    engine.register_fn("update_room_boundary", move |room_id: RoomId, points: rhai::Array| -> Result<f64, Box<EvalAltResult>> {
         let polygon = array_to_polygon(points)?;
+        let mut store = s.write().unwrap();
+        store.update_room_boundary(room_id, polygon.outer)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
 
-        // Parse room type - allow custom types but log standard ones
-        let room_type = match room_type_str.to_lowercase().as_str() {
-            "living" | "livingroom" | "living_room" => RoomType::LivingRoom,
-            "kitchen" => RoomType::Kitchen,
-            "bedroom" => RoomType::Bedroom,
-            "bathroom" | "bath" => RoomType::Bathroom,
-            "garage" => RoomType::Garage,
-            "utility" | "mechanical" => RoomType::Utility,
-            "circulation" | "hallway" | "hall" | "corridor" => RoomType::Hallway,
-            other => RoomType::Other(other.to_string()),
-        };
+    let s = store.clone();
+    engine.register_fn("update_room_meta", move |room_id: RoomId, name: &str, room_type_str: &str| -> Result<(), Box<EvalAltResult>> {
+        let room_type = parse_room_type(room_type_str);
+        let mut store = s.write().unwrap();
+        store.update_room_meta(room_id, name, room_type)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("remove_room", move |room_id: RoomId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.remove_room(room_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
 
+    // Change finish selections by surface name, e.g. #{ floor: "carpet", trim: "premium" }
+    let s = store.clone();
+    engine.register_fn("set_room_finishes", move |room_id: RoomId, finishes: Map| -> Result<(), Box<EvalAltResult>> {
+        let mut selections = Vec::new();
+        for (key, value) in &finishes {
+            let value = value.clone().into_string()
+                .map_err(|kind| structured_err(StructuredError::invalid_parameter(
+                    key.as_str(),
+                    "Finish selections are names",
+                    kind,
+                    Some("a material name such as \"carpet\", or basic, standard or premium for trim".to_string()),
+                )))?;
+            selections.push((key.to_string(), value));
+        }
+        let selections: Vec<(&str, &str)> = selections.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
         let mut store = s.write().unwrap();
-        store.create_room(level_id, room_type, name, polygon)
+        store.update_room_finishes(room_id, &selections)
+            .map(|_| ())
             .map_err(|e| structured_err(StructuredError::from(e)))
     });
+
+    let s = store.clone();
+    engine.register_fn("derive_rooms_from_walls", move |level_id: LevelId| -> Result<Map, Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        let derivation = store.derive_rooms_from_walls(level_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))?;
+
+        let ids = |ids: &[RoomId]| -> Dynamic {
+            Dynamic::from_array(ids.iter().map(|id| Dynamic::from(*id)).collect())
+        };
+        let mut map = Map::new();
+        map.insert("created".into(), ids(&derivation.created));
+        map.insert("updated".into(), ids(&derivation.updated));
+        map.insert("unenclosed".into(), ids(&derivation.unenclosed));
+        Ok(map)
+    });
+
+    let s = store.clone();
+    engine.register_fn("get_room_adjacencies", move |level_id: LevelId| -> Result<Vec<Dynamic>, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        let graph = store.room_adjacency(level_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))?;
+        Ok(graph.adjacencies.iter().map(adjacency_to_map).map(Dynamic::from).collect())
+    });
 }
 
 // ========== Opening Functions ==========
 
 fn register_opening_functions(engine: &mut Engine, store: SharedStore) {
     let s = store.clone();
-    engine.register_fn("add_opening", move |wall_id: WallId, type_str: &str, position: f64, width: f64, height: f64, sill: f64| -> Result<OpeningId, Box<EvalAltResult>> {
+    engine.register_fn("add_opening", move |wall_id: WallId, type_str: &str, position: Dynamic, width: Dynamic, height: Dynamic, sill: Dynamic| -> Result<OpeningId, Box<EvalAltResult>> {
+        let position = number_arg("position", &position)?;
+        let width = number_arg("width", &width)?;
+        let height = number_arg("height", &height)?;
+        let sill = number_arg("sill", &sill)?;
         // Parse opening type
         let opening_type = match type_str.to_lowercase().as_str() {
             "window" => OpeningType::Window,
@@ -395,7 +968,7 @@ fn register_opening_functions(engine: &mut Engine, store: SharedStore) {
         };
 
         // Validate position (0.0 to 1.0)
-        if position < 0.0 || position > 1.0 {
+        if !(0.0..=1.0).contains(&position) {
             return Err(structured_err(StructuredError::parameter_out_of_range(
                 "position",
                 position,
@@ -438,322 +1011,2001 @@ fn register_opening_functions(engine: &mut Engine, store: SharedStore) {
         store.add_opening(wall_id, opening_type, position, width, height, sill)
             .map_err(|e| structured_err(StructuredError::from(e)))
     });
-}
-
-// ========== Query Functions ==========
 
-fn register_query_functions(engine: &mut Engine, store: SharedStore) {
     let s = store.clone();
-    engine.register_fn("get_building_stats", move |building_id: BuildingId| -> Result<Map, Box<EvalAltResult>> {
+    engine.register_fn("add_catalog_opening", move |wall_id: WallId, code: &str, position: Dynamic, sill: Dynamic| -> Result<OpeningId, Box<EvalAltResult>> {
+        let position = number_arg("position", &position)?;
+        let sill = number_arg("sill", &sill)?;
+        if !(0.0..=1.0).contains(&position) {
+            return Err(structured_err(StructuredError::parameter_out_of_range(
+                "position",
+                position,
+                Some(0.0),
+                Some(1.0),
+            )));
+        }
+
+        let mut store = s.write().unwrap();
+        store.add_catalog_opening(wall_id, code, position, sill)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Move, resize or retype an opening in place:
+    // #{position, width, height, sill_height, type}, any subset
+    let s = store.clone();
+    engine.register_fn("update_opening", move |opening_id: OpeningId, changes: Map| -> Result<(), Box<EvalAltResult>> {
+        let edit = map_to_opening_edit(&changes)?;
+        let mut store = s.write().unwrap();
+        store.update_opening(opening_id, edit)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("remove_opening", move |opening_id: OpeningId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.remove_opening(opening_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+}
+
+// ========== Query Functions ==========
+
+fn register_query_functions(engine: &mut Engine, store: SharedStore, scope: Option<ProjectId>) {
+    let s = store.clone();
+    engine.register_fn("get_building_stats", move |building_id: BuildingId| -> Result<Map, Box<EvalAltResult>> {
         let store = s.read().unwrap();
         let stats = store.get_building_stats(building_id)
             .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Building, building_id)))?;
 
         let mut map = Map::new();
         map.insert("total_area".into(), Dynamic::from(stats.total_area));
+        map.insert("conditioned_area".into(), Dynamic::from(stats.conditioned_area));
+        map.insert("unconditioned_area".into(), Dynamic::from(stats.unconditioned_area));
         map.insert("level_count".into(), Dynamic::from(stats.level_count as i64));
         Ok(map)
     });
 
+    let s = store.clone();
+    engine.register_fn("check_bearing_continuity", move |building_id: BuildingId| -> Result<Map, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        let report = check_bearing_continuity(&store, building_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))?;
+
+        let mut map = Map::new();
+        map.insert("continuous".into(), Dynamic::from(report.is_continuous()));
+        map.insert(
+            "issues".into(),
+            Dynamic::from_array(report.issues.iter().map(bearing_issue_to_map).map(Dynamic::from).collect()),
+        );
+        Ok(map)
+    });
+
     let s = store.clone();
     engine.register_fn("get_event_count", move |project_id: ProjectId| -> Result<i64, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
         let store = s.read().unwrap();
         store.get_event_log(project_id)
             .map(|l| l.len() as i64)
             .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Project, project_id)))
     });
+
+    // Listings return ids in a stable order, empty for an unknown parent
+    let s = store.clone();
+    engine.register_fn("get_project_buildings", move |project_id: ProjectId| -> rhai::Array {
+        if !in_scope(scope, project_id) {
+            return Vec::new();
+        }
+        let store = s.read().unwrap();
+        store.get_project(project_id)
+            .map(|p| p.building_ids.iter().map(|id| Dynamic::from(*id)).collect())
+            .unwrap_or_default()
+    });
+
+    let s = store.clone();
+    engine.register_fn("get_level_walls", move |level_id: LevelId| -> rhai::Array {
+        let store = s.read().unwrap();
+        let mut walls = store.get_level_walls(level_id);
+        walls.sort_by_key(|w| w.id.to_string());
+        walls.iter().map(|w| Dynamic::from(w.id)).collect()
+    });
+
+    let s = store.clone();
+    engine.register_fn("get_level_rooms", move |level_id: LevelId| -> rhai::Array {
+        let store = s.read().unwrap();
+        let mut rooms = store.get_level_rooms(level_id);
+        rooms.sort_by_key(|r| r.id.to_string());
+        rooms.iter().map(|r| Dynamic::from(r.id)).collect()
+    });
+
+    let s = store.clone();
+    engine.register_fn("get_wall_openings", move |wall_id: WallId| -> rhai::Array {
+        let store = s.read().unwrap();
+        let mut openings = store.get_wall_openings(wall_id);
+        openings.sort_by(|a, b| a.position_along_wall.total_cmp(&b.position_along_wall));
+        openings.iter().map(|o| Dynamic::from(o.id)).collect()
+    });
+
+    let s = store.clone();
+    engine.register_fn("list_wall_assemblies", move || -> rhai::Array {
+        let store = s.read().unwrap();
+        let mut assemblies = store.list_wall_assemblies();
+        assemblies.sort_by(|a, b| a.name.cmp(&b.name));
+        assemblies.into_iter().map(|a| Dynamic::from(assembly_to_map(a))).collect()
+    });
+
+    // Entity details as maps, e.g. get_room(id).area
+    let s = store.clone();
+    engine.register_fn("get_wall", move |wall_id: WallId| -> Result<Map, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        store.get_wall(wall_id)
+            .map(wall_to_map)
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Wall, wall_id)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("get_room", move |room_id: RoomId| -> Result<Map, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        store.get_room(room_id)
+            .map(room_to_map)
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Room, room_id)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("get_room_area", move |room_id: RoomId| -> Result<f64, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        store.get_room(room_id)
+            .map(|r| r.area())
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Room, room_id)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("get_opening", move |opening_id: OpeningId| -> Result<Map, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        store.get_opening(opening_id)
+            .map(opening_to_map)
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Opening, opening_id)))
+    });
+
+    // The roof or foundation of a level, or () if it has none
+    let s = store.clone();
+    engine.register_fn("get_level_roof", move |level_id: LevelId| -> Dynamic {
+        let store = s.read().unwrap();
+        let Some(roof) = store.get_level_roof(level_id) else {
+            return Dynamic::UNIT;
+        };
+        let mut map = Map::new();
+        map.insert("id".into(), Dynamic::from(roof.id));
+        map.insert("roof_type".into(), Dynamic::from(roof.roof_type.as_str().to_string()));
+        map.insert("pitch".into(), Dynamic::from(roof.pitch));
+        map.insert("overhang".into(), Dynamic::from(roof.overhang));
+        if let Some(footprint) = store.get_level_footprint(level_id) {
+            map.insert("surface_area".into(), Dynamic::from(roof.surface_area(&footprint.polygon)));
+        }
+        Dynamic::from(map)
+    });
+
+    let s = store.clone();
+    engine.register_fn("get_level_foundation", move |level_id: LevelId| -> Dynamic {
+        let store = s.read().unwrap();
+        let Some(foundation) = store.get_level_foundation(level_id) else {
+            return Dynamic::UNIT;
+        };
+        let mut map = Map::new();
+        map.insert("id".into(), Dynamic::from(foundation.id));
+        map.insert("foundation_type".into(), Dynamic::from(foundation.foundation_type.as_str().to_string()));
+        Dynamic::from(map)
+    });
 }
 
-// ========== Helper Functions ==========
+// ========== Saved Filter Functions ==========
 
-fn parse_code_region(s: &str) -> Result<CodeRegion, Box<EvalAltResult>> {
-    // Parse strings like "US_IRC_2021" or "US_IBC_2018"
-    let parts: Vec<&str> = s.split('_').collect();
-    if parts.len() >= 3 {
-        let code = parts[1].to_string();
-        let year = parts[2].parse::<u16>()
-            .map_err(|_| structured_err(StructuredError::unknown_code_region(s)))?;
-        Ok(CodeRegion::new(code, year))
-    } else if s.to_lowercase().contains("irc") {
-        Ok(CodeRegion::us_irc_2021())
-    } else if s.to_lowercase().contains("ibc") {
-        Ok(CodeRegion::us_ibc_2021())
-    } else {
-        Err(structured_err(StructuredError::unknown_code_region(s)))
-    }
+fn register_filter_functions(engine: &mut Engine, store: SharedStore, scope: Option<ProjectId>) {
+    let s = store.clone();
+    engine.register_fn("save_filter", move |project_id: ProjectId, name: &str, target: &str, conditions: Map| -> Result<FilterId, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        let target = FilterTarget::parse(target)
+            .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                "target",
+                "Unknown filter target",
+                target,
+                Some("walls, rooms, or openings".to_string()),
+            )))?;
+        let conditions = map_to_conditions(&conditions)?;
+
+        let mut store = s.write().unwrap();
+        store.save_filter(project_id, name, target, conditions)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("evaluate_filter", move |project_id: ProjectId, name: &str| -> Result<Vec<Dynamic>, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        let store = s.read().unwrap();
+        let result = store.evaluate_named_filter(project_id, name)
+            .map_err(|e| structured_err(StructuredError::from(e)))?;
+
+        Ok(result.walls.into_iter().map(Dynamic::from)
+            .chain(result.rooms.into_iter().map(Dynamic::from))
+            .chain(result.openings.into_iter().map(Dynamic::from))
+            .collect())
+    });
+
+    let s = store.clone();
+    engine.register_fn("list_filters", move |project_id: ProjectId| -> Vec<Dynamic> {
+        if !in_scope(scope, project_id) {
+            return Vec::new();
+        }
+        let store = s.read().unwrap();
+        store.get_project_filters(project_id)
+            .iter()
+            .map(|f| Dynamic::from(f.name.clone()))
+            .collect()
+    });
+
+    let s = store.clone();
+    engine.register_fn("remove_filter", move |project_id: ProjectId, name: &str| -> Result<(), Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        let mut store = s.write().unwrap();
+        let filter_id = store.find_filter(project_id, name)
+            .map(|f| f.id)
+            .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                "name",
+                "No saved filter with this name",
+                name,
+                None,
+            )))?;
+        store.remove_filter(filter_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
 }
 
-fn extract_number(val: &Dynamic) -> Option<f64> {
-    // Try as float first
-    if let Ok(f) = val.as_float() {
-        return Some(f);
-    }
-    // Then try as int
-    if let Ok(i) = val.as_int() {
-        return Some(i as f64);
+// ========== Script Library Functions ==========
+
+/// Saved scripts may run other saved scripts this many deep
+const MAX_SCRIPT_NESTING: usize = 8;
+
+thread_local! {
+    static SCRIPT_NESTING: Cell<usize> = const { Cell::new(0) };
+}
+
+fn run_saved_script(
+    context: &NativeCallContext,
+    store: &SharedStore,
+    project_id: ProjectId,
+    name: &str,
+    params: Map,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    let source = store.read().unwrap()
+        .find_script(project_id, name)
+        .map(|s| s.source.clone())
+        .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+            "name",
+            "No saved script with this name",
+            name,
+            None,
+        )))?;
+
+    let depth = SCRIPT_NESTING.with(|n| n.get());
+    if depth >= MAX_SCRIPT_NESTING {
+        return Err(structured_err(StructuredError::invalid_parameter(
+            "name",
+            format!("Saved scripts nest more than {} deep", MAX_SCRIPT_NESTING),
+            name,
+            None,
+        )));
     }
-    None
+    SCRIPT_NESTING.with(|n| n.set(depth + 1));
+    let result = context.engine()
+        .eval_with_scope::<Dynamic>(&mut saved_script_scope(project_id, params), &source);
+    SCRIPT_NESTING.with(|n| n.set(depth));
+    result
 }
 
+fn register_script_functions(engine: &mut Engine, store: SharedStore, scope: Option<ProjectId>) {
+    // Scripts are compiled before saving so a broken one never lands in the library
+    let s = store.clone();
+    engine.register_fn("save_script", move |context: NativeCallContext, project_id: ProjectId, name: &str, source: &str| -> Result<ScriptId, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        context.engine().compile(source).map_err(|e| structured_err(StructuredError::invalid_parameter(
+            "source",
+            format!("Script does not compile: {}", e),
+            name,
+            None,
+        )))?;
 
+        let mut store = s.write().unwrap();
+        store.save_script(project_id, name, source)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
 
-fn array_to_point(val: Dynamic) -> Result<Point2, Box<EvalAltResult>> {
-    // Try as array first [x, y]
-    if let Some(pair) = val.clone().try_cast::<rhai::Array>() {
-        if pair.len() == 2 {
-            let x = extract_number(&pair[0])
-                .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
-                    "x",
-                    "Expected number for x coordinate",
-                    format!("{:?}", pair[0]),
-                    Some("numeric value".to_string()),
-                )))?;
-            let y = extract_number(&pair[1])
-                .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
-                    "y",
-                    "Expected number for y coordinate",
-                    format!("{:?}", pair[1]),
-                    Some("numeric value".to_string()),
-                )))?;
-            return Ok(Point2::new(x, y));
+    // Runs in the calling engine and returns the saved script's value
+    let s = store.clone();
+    engine.register_fn("run_saved_script", move |context: NativeCallContext, project_id: ProjectId, name: &str, params: Map| -> Result<Dynamic, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        run_saved_script(&context, &s, project_id, name, params)
+    });
+
+    let s = store.clone();
+    engine.register_fn("run_saved_script", move |context: NativeCallContext, project_id: ProjectId, name: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        run_saved_script(&context, &s, project_id, name, Map::new())
+    });
+
+    let s = store.clone();
+    engine.register_fn("list_scripts", move |project_id: ProjectId| -> Vec<Dynamic> {
+        if !in_scope(scope, project_id) {
+            return Vec::new();
         }
-    }
+        let store = s.read().unwrap();
+        store.get_project_scripts(project_id)
+            .iter()
+            .map(|script| Dynamic::from(script.name.clone()))
+            .collect()
+    });
 
-    // Try as map with x/y keys
-    if let Some(map) = val.clone().try_cast::<Map>() {
-        let x = map.get("x")
-            .and_then(|v| extract_number(v))
-            .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
-                "x",
-                "Expected 'x' key with numeric value in point object",
-                "missing or non-numeric",
-                Some("{x: number, y: number}".to_string()),
-            )))?;
-        let y = map.get("y")
-            .and_then(|v| extract_number(v))
+    let s = store.clone();
+    engine.register_fn("remove_script", move |project_id: ProjectId, name: &str| -> Result<(), Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        let mut store = s.write().unwrap();
+        let script_id = store.find_script(project_id, name)
+            .map(|script| script.id)
             .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
-                "y",
-                "Expected 'y' key with numeric value in point object",
-                "missing or non-numeric",
-                Some("{x: number, y: number}".to_string()),
+                "name",
+                "No saved script with this name",
+                name,
+                None,
             )))?;
-        return Ok(Point2::new(x, y));
-    }
+        store.remove_script(script_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+}
 
-    Err(structured_err(StructuredError::invalid_geometry(
-        "Invalid point format",
-        vec![
-            "Use array format: [x, y]".to_string(),
-            "Or object format: {x: number, y: number}".to_string(),
-        ],
-    )))
+// ========== Snapshot Functions ==========
+
+fn register_snapshot_functions(engine: &mut Engine, store: SharedStore, scope: Option<ProjectId>) {
+    let s = store.clone();
+    engine.register_fn("snapshot", move |project_id: ProjectId| -> Result<SnapshotId, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        let mut store = s.write().unwrap();
+        store.create_snapshot(project_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    // Without an argument, checkpoint the bound project, or the only
+    // project in the store
+    let s = store.clone();
+    engine.register_fn("snapshot", move || -> Result<SnapshotId, Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        if let Some(project_id) = scope {
+            return store.create_snapshot(project_id)
+                .map_err(|e| structured_err(StructuredError::from(e)));
+        }
+        let project_ids: Vec<ProjectId> = store.projects.keys().copied().collect();
+        let [project_id] = project_ids.as_slice() else {
+            return Err(structured_err(StructuredError::invalid_parameter(
+                "project",
+                "snapshot() needs exactly one project in the store",
+                format!("{} projects", project_ids.len()),
+                Some("call snapshot(project) instead".to_string()),
+            )));
+        };
+        store.create_snapshot(*project_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("restore", move |snapshot_id: SnapshotId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        if let Some(snapshot) = store.snapshots.get(&snapshot_id) {
+            check_scope(scope, snapshot.project_id)?;
+        }
+        store.restore_snapshot(snapshot_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
 }
 
-fn array_to_polygon(arr: rhai::Array) -> Result<Polygon2, Box<EvalAltResult>> {
-    let mut points = Vec::with_capacity(arr.len());
+// ========== Detail Callout Functions ==========
+
+fn register_detail_functions(engine: &mut Engine, store: SharedStore, scope: Option<ProjectId>) {
+    let s = store.clone();
+    engine.register_fn("add_detail_callout", move |level_id: LevelId, location: rhai::Array, kind: &str, number: &str, sheet: &str| -> Result<DetailCalloutId, Box<EvalAltResult>> {
+        let location = array_to_point(Dynamic::from_array(location))?;
+        let reference = DetailReference::new(DetailKind::parse(kind), number, sheet);
+
+        let mut store = s.write().unwrap();
+        store.add_detail_callout(level_id, location, reference)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("add_detail_callout", move |wall_id: WallId, kind: &str, number: &str, sheet: &str| -> Result<DetailCalloutId, Box<EvalAltResult>> {
+        let reference = DetailReference::new(DetailKind::parse(kind), number, sheet);
+
+        let mut store = s.write().unwrap();
+        store.add_wall_detail_callout(wall_id, reference)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("add_detail_callout", move |opening_id: OpeningId, kind: &str, number: &str, sheet: &str| -> Result<DetailCalloutId, Box<EvalAltResult>> {
+        let reference = DetailReference::new(DetailKind::parse(kind), number, sheet);
+
+        let mut store = s.write().unwrap();
+        store.add_opening_detail_callout(opening_id, reference)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("remove_detail_callout", move |callout_id: DetailCalloutId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.remove_detail_callout(callout_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("detail_schedule", move |project_id: ProjectId| -> Vec<Dynamic> {
+        if !in_scope(scope, project_id) {
+            return Vec::new();
+        }
+        let store = s.read().unwrap();
+        store.detail_schedule(project_id)
+            .into_iter()
+            .map(|entry| Dynamic::from(entry.label))
+            .collect()
+    });
+}
+
+// ========== Roof Functions ==========
+
+fn register_roof_functions(engine: &mut Engine, store: SharedStore) {
+    let s = store.clone();
+    engine.register_fn("create_roof", move |level_id: LevelId, roof_type: &str, pitch: Dynamic, overhang: Dynamic| -> Result<RoofId, Box<EvalAltResult>> {
+        let pitch = number_arg("pitch", &pitch)?;
+        let overhang = number_arg("overhang", &overhang)?;
+        let roof_type = RoofType::parse(roof_type).ok_or_else(|| {
+            structured_err(StructuredError::invalid_parameter(
+                "roof_type",
+                "Unknown roof type",
+                roof_type,
+                Some("gable, hip, shed".to_string()),
+            ))
+        })?;
+
+        let mut store = s.write().unwrap();
+        store.create_roof(level_id, roof_type, pitch, overhang)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("remove_roof", move |roof_id: RoofId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.remove_roof(roof_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+}
+
+// ========== Foundation Functions ==========
+
+fn register_foundation_functions(engine: &mut Engine, store: SharedStore) {
+    let s = store.clone();
+    engine.register_fn("create_foundation", move |level_id: LevelId, foundation_type: &str| -> Result<FoundationId, Box<EvalAltResult>> {
+        let foundation_type = FoundationType::parse(foundation_type).ok_or_else(|| {
+            structured_err(StructuredError::invalid_parameter(
+                "foundation_type",
+                "Unknown foundation type",
+                foundation_type,
+                Some("slab_on_grade, crawlspace, basement".to_string()),
+            ))
+        })?;
+
+        let mut store = s.write().unwrap();
+        store.create_foundation(level_id, foundation_type)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("remove_foundation", move |foundation_id: FoundationId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.remove_foundation(foundation_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+}
+
+// ========== Plumbing Functions ==========
+
+fn register_plumbing_functions(engine: &mut Engine, store: SharedStore) {
+    let s = store.clone();
+    engine.register_fn("add_fixture", move |room_id: RoomId, kind: &str, location: rhai::Array, rotation: Dynamic| -> Result<FixtureId, Box<EvalAltResult>> {
+        let rotation = number_arg("rotation", &rotation)?;
+        let kind = PlumbingFixtureKind::parse(kind).ok_or_else(|| {
+            structured_err(StructuredError::invalid_parameter(
+                "kind",
+                "Unknown fixture kind",
+                kind,
+                Some("toilet, sink, tub, shower".to_string()),
+            ))
+        })?;
+        let location = array_to_point(Dynamic::from_array(location))?;
+
+        let mut store = s.write().unwrap();
+        store.add_fixture(room_id, kind, location, rotation)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("remove_fixture", move |fixture_id: FixtureId| -> Result<(), Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        store.remove_fixture(fixture_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("wet_walls", move |level_id: LevelId| -> Map {
+        let store = s.read().unwrap();
+        let analysis = store.wet_walls(level_id);
+        let mut map = Map::new();
+        map.insert("fixtures".into(), Dynamic::from(analysis.fixture_count as i64));
+        map.insert("shared_fixtures".into(), Dynamic::from(analysis.shared_fixture_count as i64));
+        map.insert("rough_ins".into(), Dynamic::from(analysis.rough_in_count() as i64));
+        map
+    });
+}
+
+// ========== Costing Functions ==========
+
+fn register_costing_functions(engine: &mut Engine, store: SharedStore) {
+    // Priced at default rates, or the project's regional prices and markups
+    let s = store.clone();
+    engine.register_fn("generate_cost_estimate", move |level_id: LevelId| -> Result<Map, Box<EvalAltResult>> {
+        let mut store = s.write().unwrap();
+        if store.level_project_id(level_id).is_none() {
+            return Err(structured_err(StructuredError::entity_not_found(EntityType::Level, level_id)));
+        }
+
+        let estimate = CostCalculator::with_defaults().estimate_level(&mut store, level_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))?;
+        Ok(estimate_to_map(&estimate))
+    });
+}
+
+// ========== Compliance Functions ==========
+
+fn register_compliance_functions(engine: &mut Engine, store: SharedStore) {
+    // Violations on the level, plus those that apply to the whole project
+    let s = store.clone();
+    engine.register_fn("run_compliance_checks", move |level_id: LevelId| -> Result<Map, Box<EvalAltResult>> {
+        let store = s.read().unwrap();
+        let project_id = store.level_project_id(level_id)
+            .ok_or_else(|| structured_err(StructuredError::entity_not_found(EntityType::Level, level_id)))?;
+        let report = check_compliance(&store, project_id)
+            .map_err(|e| structured_err(StructuredError::from(e)))?;
+
+        let violations: rhai::Array = report.violations
+            .iter()
+            .filter(|v| v.level_id.is_none_or(|id| id == level_id))
+            .map(violation_to_map)
+            .map(Dynamic::from)
+            .collect();
+        let mut map = Map::new();
+        map.insert("compliant".into(), Dynamic::from(violations.is_empty()));
+        map.insert(
+            "checked".into(),
+            Dynamic::from_array(report.checked.iter().map(|r| Dynamic::from(r.as_str().to_string())).collect()),
+        );
+        map.insert("violations".into(), Dynamic::from_array(violations));
+        Ok(map)
+    });
+}
+
+// ========== Template Functions ==========
+
+fn register_template_functions(engine: &mut Engine, store: SharedStore, scope: Option<ProjectId>) {
+    let s = store.clone();
+    engine.register_fn("instantiate_template", move |project_id: ProjectId, template: &str, params: Map| -> Result<Map, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        let template = parse_template(template)?;
+        let params = map_to_template_params(&params)?;
+
+        let mut store = s.write().unwrap();
+        instantiate_template(&mut store, project_id, template, &params)
+            .map(|instance| template_instance_to_map(&instance))
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    let s = store.clone();
+    engine.register_fn("instantiate_template", move |project_id: ProjectId, template: &str| -> Result<Map, Box<EvalAltResult>> {
+        check_scope(scope, project_id)?;
+        let template = parse_template(template)?;
+
+        let mut store = s.write().unwrap();
+        instantiate_template(&mut store, project_id, template, &TemplateParams::default())
+            .map(|instance| template_instance_to_map(&instance))
+            .map_err(|e| structured_err(StructuredError::from(e)))
+    });
+
+    engine.register_fn("list_templates", || -> Vec<Dynamic> {
+        BuildingTemplate::all()
+            .iter()
+            .map(|t| Dynamic::from(t.as_str().to_string()))
+            .collect()
+    });
+}
+
+// ========== Helper Functions ==========
+
+fn parse_code_region(s: &str) -> Result<CodeRegion, Box<EvalAltResult>> {
+    // Parse strings like "US_IRC_2021" or "US_IBC_2018"
+    let parts: Vec<&str> = s.split('_').collect();
+    if parts.len() >= 3 {
+        let code = parts[1].to_string();
+        let year = parts[2].parse::<u16>()
+            .map_err(|_| structured_err(StructuredError::unknown_code_region(s)))?;
+        Ok(CodeRegion::new(code, year))
+    } else if s.to_lowercase().contains("irc") {
+        Ok(CodeRegion::us_irc_2021())
+    } else if s.to_lowercase().contains("ibc") {
+        Ok(CodeRegion::us_ibc_2021())
+    } else {
+        Err(structured_err(StructuredError::unknown_code_region(s)))
+    }
+}
+
+fn framing_summary_to_map(layout: &FramingLayout) -> Map {
+    let mut breakdown = Map::new();
+    for member in &layout.members {
+        let count = breakdown
+            .entry(member.member_type.display_name().into())
+            .or_insert_with(|| Dynamic::from(0_i64));
+        *count = Dynamic::from(count.as_int().unwrap_or(0) + 1);
+    }
+    let headers = layout.headers
+        .iter()
+        .map(|h| {
+            let mut map = Map::new();
+            map.insert("opening_id".into(), Dynamic::from(h.opening_id));
+            map.insert("span".into(), Dynamic::from(h.span));
+            map.insert("size".into(), Dynamic::from(h.size.description()));
+            Dynamic::from(map)
+        })
+        .collect();
+
+    let mut map = Map::new();
+    map.insert("stud_count".into(), Dynamic::from(layout.stud_count as i64));
+    map.insert("member_count".into(), Dynamic::from(layout.members.len() as i64));
+    map.insert("board_feet".into(), Dynamic::from(layout.total_board_feet));
+    map.insert("stud_spacing".into(), Dynamic::from(layout.stud_spacing));
+    map.insert("lumber_size".into(), Dynamic::from(layout.lumber_size.nominal_name().to_string()));
+    map.insert("double_top_plate".into(), Dynamic::from(layout.double_top_plate));
+    map.insert("members".into(), Dynamic::from(breakdown));
+    map.insert("headers".into(), Dynamic::from_array(headers));
+    map
+}
+
+fn estimate_to_map(estimate: &CostEstimate) -> Map {
+    let mut subtotals = Map::new();
+    for (category, total) in &estimate.subtotals {
+        subtotals.insert(category.display_name().into(), Dynamic::from(*total));
+    }
+    let line_items = estimate.line_items
+        .iter()
+        .map(|item| {
+            let mut map = Map::new();
+            map.insert("category".into(), Dynamic::from(item.category.display_name().to_string()));
+            map.insert("description".into(), Dynamic::from(item.description.clone()));
+            map.insert("quantity".into(), Dynamic::from(item.quantity));
+            map.insert("unit".into(), Dynamic::from(item.unit.display_name().to_string()));
+            map.insert("unit_price".into(), Dynamic::from(item.unit_price));
+            map.insert("total".into(), Dynamic::from(item.total));
+            Dynamic::from(map)
+        })
+        .collect();
+
+    let mut map = Map::new();
+    map.insert("level_id".into(), Dynamic::from(estimate.level_id));
+    map.insert("line_items".into(), Dynamic::from_array(line_items));
+    map.insert("subtotals".into(), Dynamic::from(subtotals));
+    map.insert("labor_total".into(), Dynamic::from(estimate.labor_total));
+    map.insert("material_total".into(), Dynamic::from(estimate.material_total));
+    map.insert("direct_total".into(), Dynamic::from(estimate.direct_total));
+    map.insert("grand_total".into(), Dynamic::from(estimate.grand_total));
+    map
+}
+
+fn violation_to_map(violation: &ComplianceViolation) -> Map {
+    let mut map = Map::new();
+    map.insert("rule".into(), Dynamic::from(violation.rule.as_str().to_string()));
+    map.insert("section".into(), Dynamic::from(violation.section.clone()));
+    map.insert("message".into(), Dynamic::from(violation.message.clone()));
+    map.insert("severity".into(), Dynamic::from(violation.severity.as_str().to_string()));
+    map.insert(
+        "room_ids".into(),
+        Dynamic::from_array(violation.room_ids.iter().map(|id| Dynamic::from(*id)).collect()),
+    );
+    map
+}
+
+fn adjacency_to_map(adjacency: &RoomAdjacency) -> Map {
+    let point = |p: &Point2| Dynamic::from_array(vec![Dynamic::from(p.x), Dynamic::from(p.y)]);
+    let mut map = Map::new();
+    map.insert("room_a".into(), Dynamic::from(adjacency.room_a));
+    map.insert("room_b".into(), Dynamic::from(adjacency.room_b));
+    map.insert("length".into(), Dynamic::from(adjacency.length));
+    map.insert(
+        "segments".into(),
+        Dynamic::from_array(
+            adjacency.segments
+                .iter()
+                .map(|seg| Dynamic::from_array(vec![point(&seg.start), point(&seg.end)]))
+                .collect(),
+        ),
+    );
+    map
+}
+
+fn point_to_array(p: &Point2) -> Dynamic {
+    Dynamic::from_array(vec![Dynamic::from(p.x), Dynamic::from(p.y)])
+}
+
+fn wall_to_map(wall: &Wall) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), Dynamic::from(wall.id));
+    map.insert("level_id".into(), Dynamic::from(wall.level_id));
+    map.insert("assembly_id".into(), Dynamic::from(wall.assembly_id));
+    map.insert("start".into(), point_to_array(&wall.start));
+    map.insert("end".into(), point_to_array(&wall.end));
+    map.insert("height".into(), Dynamic::from(wall.height));
+    map.insert("length".into(), Dynamic::from(wall.length()));
+    map.insert("curved".into(), Dynamic::from(wall.arc.is_some()));
+    map
+}
+
+fn room_to_map(room: &Room) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), Dynamic::from(room.id));
+    map.insert("level_id".into(), Dynamic::from(room.level_id));
+    map.insert("name".into(), Dynamic::from(room.name.clone()));
+    // In the form create_room takes, e.g. "living_room"
+    let room_type = match &room.room_type {
+        RoomType::Other(name) => name.clone(),
+        other => other.display_name().to_lowercase().replace(' ', "_"),
+    };
+    map.insert("room_type".into(), Dynamic::from(room_type));
+    map.insert("area".into(), Dynamic::from(room.area()));
+    map.insert("perimeter".into(), Dynamic::from(room.perimeter()));
+    map.insert(
+        "boundary".into(),
+        Dynamic::from_array(room.boundary.outer.iter().map(point_to_array).collect()),
+    );
+    map
+}
+
+fn opening_to_map(opening: &Opening) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), Dynamic::from(opening.id));
+    map.insert("wall_id".into(), Dynamic::from(opening.wall_id));
+    let opening_type = match &opening.opening_type {
+        OpeningType::Other(name) => name.clone(),
+        other => other.display_name().to_lowercase(),
+    };
+    map.insert("type".into(), Dynamic::from(opening_type));
+    map.insert("position".into(), Dynamic::from(opening.position_along_wall));
+    map.insert("width".into(), Dynamic::from(opening.width));
+    map.insert("height".into(), Dynamic::from(opening.height));
+    map.insert("sill_height".into(), Dynamic::from(opening.sill_height));
+    map.insert(
+        "catalog_code".into(),
+        opening.catalog_code.clone().map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+    );
+    map
+}
+
+fn assembly_to_map(assembly: &WallAssembly) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), Dynamic::from(assembly.id));
+    map.insert("name".into(), Dynamic::from(assembly.name.clone()));
+    map.insert("thickness".into(), Dynamic::from(assembly.total_thickness));
+    map.insert("r_value".into(), Dynamic::from(assembly.total_r_value()));
+    map.insert("insulated".into(), Dynamic::from(assembly.is_insulated()));
+    map
+}
+
+fn bearing_issue_to_map(issue: &BearingIssue) -> Map {
+    let mut map = Map::new();
+    map.insert("kind".into(), Dynamic::from(issue.kind.as_str().to_string()));
+    map.insert("message".into(), Dynamic::from(issue.message.clone()));
+    map.insert("wall_id".into(), Dynamic::from(issue.wall_id));
+    map.insert("length".into(), Dynamic::from(issue.length));
+    map.insert(
+        "supporting_wall_ids".into(),
+        Dynamic::from_array(issue.supporting_wall_ids.iter().map(|id| Dynamic::from(*id)).collect()),
+    );
+    map
+}
+
+/// Number argument, so `30` works where `30.0` is expected
+fn number_arg(field: &str, val: &Dynamic) -> Result<f64, Box<EvalAltResult>> {
+    extract_number(val).ok_or_else(|| {
+        structured_err(StructuredError::invalid_parameter(
+            field,
+            "Expected a number",
+            val.type_name(),
+            None,
+        ))
+    })
+}
+
+fn extract_number(val: &Dynamic) -> Option<f64> {
+    // Try as float first
+    if let Ok(f) = val.as_float() {
+        return Some(f);
+    }
+    // Then try as int
+    if let Ok(i) = val.as_int() {
+        return Some(i as f64);
+    }
+    None
+}
+
+
+
+/// Convert a map like #{load_bearing: true, max_area: 100.0} to filter conditions
+fn parse_template(template: &str) -> Result<BuildingTemplate, Box<EvalAltResult>> {
+    BuildingTemplate::parse(template).ok_or_else(|| {
+        structured_err(StructuredError::invalid_parameter(
+            "template",
+            "Unknown building template",
+            template,
+            Some("ranch, two_story_colonial".to_string()),
+        ))
+    })
+}
+
+fn map_to_template_params(map: &Map) -> Result<TemplateParams, Box<EvalAltResult>> {
+    let mut params = TemplateParams::default();
+
+    for (key, val) in map {
+        let key = key.as_str();
+        let number = || extract_number(val)
+            .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                key,
+                "Expected numeric value",
+                format!("{:?}", val),
+                Some("numeric value".to_string()),
+            )));
+        let count = || val.as_int().ok().and_then(|n| u32::try_from(n).ok())
+            .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                key,
+                "Expected a whole number",
+                format!("{:?}", val),
+                Some("integer of 0 or more".to_string()),
+            )));
+
+        match key {
+            "stories" => params.stories = Some(count()?),
+            "width" => params.width = Some(number()?),
+            "depth" => params.depth = Some(number()?),
+            "garage" => {
+                params.garage = Some(val.as_bool().map_err(|_| structured_err(StructuredError::invalid_parameter(
+                    key,
+                    "Expected boolean value",
+                    format!("{:?}", val),
+                    Some("true or false".to_string()),
+                )))?);
+            }
+            "bedrooms" => params.bedrooms = Some(count()?),
+            "story_height" => params.story_height = Some(number()?),
+            _ => {
+                return Err(structured_err(StructuredError::invalid_parameter(
+                    key,
+                    "Unknown template parameter",
+                    key,
+                    Some("stories, width, depth, garage, bedrooms, story_height".to_string()),
+                )));
+            }
+        }
+    }
+
+    Ok(params)
+}
+
+/// Parse a room type, keeping unknown names as custom types
+fn parse_room_type(room_type_str: &str) -> RoomType {
+    match room_type_str.to_lowercase().as_str() {
+        "living" | "livingroom" | "living_room" => RoomType::LivingRoom,
+        "kitchen" => RoomType::Kitchen,
+        "bedroom" => RoomType::Bedroom,
+        "bathroom" | "bath" => RoomType::Bathroom,
+        "garage" => RoomType::Garage,
+        "utility" | "mechanical" => RoomType::Utility,
+        "circulation" | "hallway" | "hall" | "corridor" => RoomType::Hallway,
+        other => RoomType::Other(other.to_string()),
+    }
+}
+
+fn map_to_opening_edit(map: &Map) -> Result<OpeningEdit, Box<EvalAltResult>> {
+    let mut edit = OpeningEdit::default();
+
+    for (key, val) in map {
+        let key = key.as_str();
+        let number = || extract_number(val)
+            .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                key,
+                "Expected numeric value",
+                format!("{:?}", val),
+                Some("numeric value".to_string()),
+            )));
+
+        match key {
+            "position" => edit.position = Some(number()?),
+            "width" => edit.width = Some(number()?),
+            "height" => edit.height = Some(number()?),
+            "sill_height" => edit.sill_height = Some(number()?),
+            "type" | "opening_type" => {
+                let type_str = val.clone().into_string().unwrap_or_default();
+                edit.opening_type = Some(match type_str.to_lowercase().as_str() {
+                    "window" => OpeningType::Window,
+                    "door" => OpeningType::Door,
+                    _ => return Err(structured_err(StructuredError::unknown_opening_type(&type_str))),
+                });
+            }
+            _ => {
+                return Err(structured_err(StructuredError::invalid_parameter(
+                    key,
+                    "Unknown opening field",
+                    key,
+                    Some("position, width, height, sill_height, type".to_string()),
+                )));
+            }
+        }
+    }
+
+    Ok(edit)
+}
+
+fn template_instance_to_map(instance: &TemplateInstance) -> Map {
+    fn ids<T: Clone + Send + Sync + 'static>(ids: &[T]) -> Dynamic {
+        Dynamic::from_array(ids.iter().cloned().map(Dynamic::from).collect())
+    }
+
+    let mut map = Map::new();
+    map.insert("template".into(), Dynamic::from(instance.template.as_str().to_string()));
+    map.insert("building".into(), Dynamic::from(instance.building_id));
+    map.insert("levels".into(), ids(&instance.level_ids));
+    map.insert("rooms".into(), ids(&instance.room_ids));
+    map.insert("walls".into(), ids(&instance.wall_ids));
+    map.insert("openings".into(), ids(&instance.opening_ids));
+    map.insert("stairs".into(), ids(&instance.stair_ids));
+    map
+}
+
+fn map_to_conditions(map: &Map) -> Result<Vec<FilterCondition>, Box<EvalAltResult>> {
+    let mut conditions = Vec::with_capacity(map.len());
+
+    for (key, val) in map {
+        let key = key.as_str();
+        let number = || extract_number(val)
+            .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                key,
+                "Expected numeric value",
+                format!("{:?}", val),
+                Some("numeric value".to_string()),
+            )));
+        let flag = || val.as_bool()
+            .map_err(|_| structured_err(StructuredError::invalid_parameter(
+                key,
+                "Expected boolean value",
+                format!("{:?}", val),
+                Some("true or false".to_string()),
+            )));
+
+        let condition = match key {
+            "level" => {
+                let level_id = val.clone().try_cast::<LevelId>()
+                    .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                        key,
+                        "Expected a level handle",
+                        format!("{:?}", val),
+                        Some("LevelId".to_string()),
+                    )))?;
+                FilterCondition::OnLevel { level_id }
+            }
+            "load_bearing" => FilterCondition::LoadBearing { value: flag()? },
+            "insulated" => FilterCondition::Insulated { value: flag()? },
+            "assembly" => FilterCondition::AssemblyName { name: val.to_string() },
+            "min_length" => FilterCondition::MinLength { value: number()? },
+            "max_length" => FilterCondition::MaxLength { value: number()? },
+            "room_type" => FilterCondition::RoomTypeIs { room_type: RoomType::from_str(&val.to_string()) },
+            "min_area" => FilterCondition::MinArea { value: number()? },
+            "max_area" => FilterCondition::MaxArea { value: number()? },
+            "conditioned" => FilterCondition::Conditioned { value: flag()? },
+            "opening_type" => FilterCondition::OpeningTypeIs { opening_type: OpeningType::from_str(&val.to_string()) },
+            "min_width" => FilterCondition::MinWidth { value: number()? },
+            "max_width" => FilterCondition::MaxWidth { value: number()? },
+            _ => {
+                return Err(structured_err(StructuredError::invalid_parameter(
+                    key,
+                    "Unknown filter condition",
+                    format!("{:?}", val),
+                    Some("level, load_bearing, insulated, assembly, min_length, max_length, room_type, min_area, max_area, conditioned, opening_type, min_width, max_width".to_string()),
+                )));
+            }
+        };
+        conditions.push(condition);
+    }
+
+    Ok(conditions)
+}
+
+fn index_arg(field: &str, value: i64) -> Result<usize, Box<EvalAltResult>> {
+    usize::try_from(value).map_err(|_| structured_err(StructuredError::parameter_out_of_range(
+        field,
+        value as f64,
+        Some(0.0),
+        None,
+    )))
+}
+
+fn array_to_point(val: Dynamic) -> Result<Point2, Box<EvalAltResult>> {
+    // Try as array first [x, y]
+    if let Some(pair) = val.clone().try_cast::<rhai::Array>() {
+        if pair.len() == 2 {
+            let x = extract_number(&pair[0])
+                .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                    "x",
+                    "Expected number for x coordinate",
+                    format!("{:?}", pair[0]),
+                    Some("numeric value".to_string()),
+                )))?;
+            let y = extract_number(&pair[1])
+                .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                    "y",
+                    "Expected number for y coordinate",
+                    format!("{:?}", pair[1]),
+                    Some("numeric value".to_string()),
+                )))?;
+            return Ok(Point2::new(x, y));
+        }
+    }
+
+    // Try as map with x/y keys
+    if let Some(map) = val.clone().try_cast::<Map>() {
+        let x = map.get("x")
+            .and_then(extract_number)
+            .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                "x",
+                "Expected 'x' key with numeric value in point object",
+                "missing or non-numeric",
+                Some("{x: number, y: number}".to_string()),
+            )))?;
+        let y = map.get("y")
+            .and_then(extract_number)
+            .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                "y",
+                "Expected 'y' key with numeric value in point object",
+                "missing or non-numeric",
+                Some("{x: number, y: number}".to_string()),
+            )))?;
+        return Ok(Point2::new(x, y));
+    }
+
+    Err(structured_err(StructuredError::invalid_geometry(
+        "Invalid point format",
+        vec![
+            "Use array format: [x, y]".to_string(),
+            "Or object format: {x: number, y: number}".to_string(),
+        ],
+    )))
+}
+
+/// A point given as coordinates or as a grid reference like "A/3" on the
+/// level's building grid
+fn level_point_arg(store: &Store, level_id: LevelId, val: Dynamic) -> Result<Point2, Box<EvalAltResult>> {
+    match val.clone().into_immutable_string() {
+        Ok(reference) => store.resolve_grid_point(level_id, &reference)
+            .map_err(|e| structured_err(StructuredError::from(e))),
+        Err(_) => array_to_point(val),
+    }
+}
+
+/// Polygon whose points may be grid references, as for `level_point_arg`
+fn level_polygon_arg(store: &Store, level_id: LevelId, arr: rhai::Array) -> Result<Polygon2, Box<EvalAltResult>> {
+    let points = arr
+        .into_iter()
+        .map(|item| match item.clone().into_immutable_string() {
+            Ok(reference) => {
+                let p = store.resolve_grid_point(level_id, &reference)
+                    .map_err(|e| structured_err(StructuredError::from(e)))?;
+                Ok(Dynamic::from_array(vec![Dynamic::from(p.x), Dynamic::from(p.y)]))
+            }
+            Err(_) => Ok(item),
+        })
+        .collect::<Result<rhai::Array, Box<EvalAltResult>>>()?;
+    array_to_polygon(points)
+}
+
+fn array_to_polygon(arr: rhai::Array) -> Result<Polygon2, Box<EvalAltResult>> {
+    let mut points = Vec::with_capacity(arr.len());
+
+    for (idx, item) in arr.iter().enumerate() {
+        // Try as array first [x, y]
+        if let Some(pair) = item.clone().try_cast::<rhai::Array>() {
+            if pair.len() == 2 {
+                let x = extract_number(&pair[0])
+                    .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                        format!("points[{}].x", idx),
+                        "Expected number for x coordinate",
+                        format!("{:?}", pair[0]),
+                        Some("numeric value".to_string()),
+                    )))?;
+                let y = extract_number(&pair[1])
+                    .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                        format!("points[{}].y", idx),
+                        "Expected number for y coordinate",
+                        format!("{:?}", pair[1]),
+                        Some("numeric value".to_string()),
+                    )))?;
+                points.push(Point2::new(x, y));
+                continue;
+            }
+        }
+
+        // Try as map with x/y keys
+        if let Some(map) = item.clone().try_cast::<Map>() {
+            let x = map.get("x")
+                .and_then(extract_number)
+                .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                    format!("points[{}].x", idx),
+                    "Expected 'x' key with numeric value",
+                    "missing or non-numeric",
+                    Some("{x: number, y: number}".to_string()),
+                )))?;
+            let y = map.get("y")
+                .and_then(extract_number)
+                .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
+                    format!("points[{}].y", idx),
+                    "Expected 'y' key with numeric value",
+                    "missing or non-numeric",
+                    Some("{x: number, y: number}".to_string()),
+                )))?;
+            points.push(Point2::new(x, y));
+            continue;
+        }
+
+        return Err(structured_err(StructuredError::invalid_geometry(
+            format!("Invalid point format at index {}", idx),
+            vec![
+                "Use array format: [x, y]".to_string(),
+                "Or object format: {x: number, y: number}".to_string(),
+            ],
+        )));
+    }
+
+    if points.len() < 3 {
+        return Err(structured_err(StructuredError::degenerate_geometry(
+            EntityType::Footprint,
+            format!("Polygon requires at least 3 points, got {}", points.len()),
+        )));
+    }
+
+    Ok(Polygon2::new(points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::new_shared_store;
+
+    #[test]
+    fn test_create_project_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Test House", "imperial", "US_IRC_2021");
+            project
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        assert!(result.events_generated > 0);
+    }
+
+    #[test]
+    fn test_full_building_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Scandi House", "imperial", "US_IRC_2021");
+            let bldg = add_building(project, "Main");
+            let main = add_level(bldg, "Main Floor", 0.0, 9.0);
+            let upper = add_level(bldg, "Upper Floor", 9.0, 8.0);
+
+            set_level_footprint_rect(main, 40.0, 30.0);
+            set_level_footprint_rect(upper, 40.0, 30.0);
+
+            let stats = get_building_stats(bldg);
+            stats.total_area
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+
+        if let Some(value) = result.return_value {
+            let area = value.as_float().unwrap();
+            assert!((area - 2400.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_polygon_footprint_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("L-Shape", "imperial", "US_IRC_2021");
+            let bldg = add_building(project, "Main");
+            let main = add_level(bldg, "Main", 0.0, 9.0);
+
+            // L-shaped footprint
+            let footprint = [
+                [0.0, 0.0],
+                [40.0, 0.0],
+                [40.0, 20.0],
+                [20.0, 20.0],
+                [20.0, 30.0],
+                [0.0, 30.0]
+            ];
+            set_level_footprint(main, footprint);
+
+            get_footprint_area(main)
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+
+        // L-shape area: (40*20) + (20*10) = 800 + 200 = 1000
+        if let Some(value) = result.return_value {
+            let area = value.as_float().unwrap();
+            assert!((area - 1000.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_integer_and_unit_args() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Units", "imperial", "US_IRC_2021");
+            let bldg = add_building(project, "Main");
+            let main = add_level(bldg, "Main", 0, 9);
+            set_level_footprint_rect(main, ft(30) + inches(6), m(3.048));
+            let wall = create_wall(main, [0, 0], [ft(20), 0], 9);
+            [get_footprint_area(main), mm(304.8), wall]
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+
+        let values = result.return_value.unwrap().into_array().unwrap();
+        assert!((values[0].as_float().unwrap() - 305.0).abs() < 1e-9);
+        assert!((values[1].as_float().unwrap() - 1.0).abs() < 1e-9);
+        let wall = store.read().unwrap().get_wall(values[2].clone().cast::<WallId>()).unwrap().length();
+        assert!((wall - 20.0).abs() < 1e-9);
+        // Helpers are left out of the call trace
+        assert!(result.trace.iter().all(|call| call.function != "ft"));
+
+        let result = execute_script(&engine, r#"ft("ten")"#, store.clone());
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_script_error_handling() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            // Try to add a building to a non-existent project
+            let fake_project = create_project("Test", "imperial", "IRC");
+            add_level(fake_project, "Test", 0.0, 9.0)
+        "#;
+
+        // This should fail because we're adding a level directly to a project (wrong type)
+        let result = execute_script(&engine, script, store.clone());
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_check_bearing_continuity() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Two Story", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let first = add_level(bldg, "First", 0.0, 10.0);
+            let second = add_level(bldg, "Second", 10.0, 9.0);
+            let assembly = create_wall_assembly("Basic Wall");
+
+            create_wall(first, assembly, [0.0, 0.0], [12.0, 0.0], 9.0);
+            create_wall(second, assembly, [0.0, 0.0], [20.0, 0.0], 8.0);
+
+            let report = check_bearing_continuity(bldg);
+            if report.continuous || report.issues.len() != 1 {
+                throw "expected one unsupported span";
+            }
+            report.issues[0].length
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        let length = result.return_value.unwrap().as_float().unwrap();
+        assert!((length - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_get_room_adjacencies_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Test", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let first = add_level(bldg, "First", 0.0, 9.0);
+            create_room(first, "kitchen", "Kitchen", [[0.0, 0.0], [12.0, 0.0], [12.0, 10.0], [0.0, 10.0]]);
+            create_room(first, "living", "Living", [[12.0, 0.0], [26.0, 0.0], [26.0, 14.0], [12.0, 14.0]]);
+
+            let adjacencies = get_room_adjacencies(first);
+            if adjacencies.len() != 1 || adjacencies[0].segments.len() != 1 {
+                throw "expected the kitchen and living room to be adjacent";
+            }
+            adjacencies[0].length
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        let length = result.return_value.unwrap().as_float().unwrap();
+        assert!((length - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_instantiate_template_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Starter", "imperial", "US_IRC_2021");
+            let model = instantiate_template(project, "two_story_colonial",
+                #{ stories: 2, width: 40, depth: 30, garage: true, bedrooms: 3 });
+            if model.levels.len() != 2 || model.stairs.len() != 1 {
+                throw "expected two levels joined by a stair";
+            }
+            model.rooms.len()
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        // Eight rooms with the garage downstairs; three bedrooms, a bath and
+        // the hall upstairs
+        assert_eq!(result.return_value.unwrap().as_int().unwrap(), 13);
+
+        let script = r#"
+            let project = create_project("Starter", "imperial", "US_IRC_2021");
+            instantiate_template(project, "ranch", #{ basements: 1 })
+        "#;
+        assert!(!execute_script(&engine, script, store.clone()).success);
+    }
+
+    #[test]
+    fn test_max_operations_limit() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        // This should hit the operations limit
+        let script = r#"
+            let x = 0;
+            loop {
+                x += 1;
+            }
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("operations"));
+    }
+
+    #[test]
+    fn test_phase2_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Phase2 House", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 10.0);
+            
+            let assembly = create_wall_assembly("Basic Wall");
+            
+            let p1 = [0.0, 0.0];
+            let p2 = [10.0, 0.0];
+            let wall = create_wall(level, assembly, p1, p2, 10.0);
+            
+            add_opening(wall, "window", 0.5, 3.0, 4.0, 2.0);
+            add_catalog_opening(wall, "3068", 0.2, 0.0);
+            
+            let room_poly = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+            let room = create_room(level, "living", "Living Room", room_poly);
+            set_room_finishes(room, #{ floor: "carpet", trim: "basic" });
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+    }
+
+    #[test]
+    fn test_saved_filter_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Filters", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+
+            create_room(level, "closet", "Closet", [[0.0, 0.0], [6.0, 0.0], [6.0, 5.0], [0.0, 5.0]]);
+            create_room(level, "living", "Living", [[0.0, 0.0], [20.0, 0.0], [20.0, 15.0], [0.0, 15.0]]);
+
+            save_filter(project, "Small rooms", "rooms", #{max_area: 100});
+            evaluate_filter(project, "small rooms").len()
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        assert_eq!(result.return_value.unwrap().as_int().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_restore_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Trial", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+
+            let checkpoint = snapshot();
+            add_level(bldg, "L2", 9.0, 9.0);
+            add_level(bldg, "L3", 18.0, 9.0);
+            restore(checkpoint);
+
+            get_building_levels(bldg).len()
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        assert_eq!(result.return_value.unwrap().as_int().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_detail_callout_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Permit Set", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            add_detail_callout(level, [0.0, 0.0], "foundation_edge", "2", "S-301");
+            add_detail_callout(level, [10.0, 0.0], "wall_section", "1", "A-501");
+            add_detail_callout(level, [20.0, 0.0], "wall_section", "1", "A-501");
+
+            detail_schedule(project)
+        "#;
 
-    for (idx, item) in arr.iter().enumerate() {
-        // Try as array first [x, y]
-        if let Some(pair) = item.clone().try_cast::<rhai::Array>() {
-            if pair.len() == 2 {
-                let x = extract_number(&pair[0])
-                    .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
-                        format!("points[{}].x", idx),
-                        "Expected number for x coordinate",
-                        format!("{:?}", pair[0]),
-                        Some("numeric value".to_string()),
-                    )))?;
-                let y = extract_number(&pair[1])
-                    .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
-                        format!("points[{}].y", idx),
-                        "Expected number for y coordinate",
-                        format!("{:?}", pair[1]),
-                        Some("numeric value".to_string()),
-                    )))?;
-                points.push(Point2::new(x, y));
-                continue;
-            }
-        }
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        let labels: Vec<String> = result.return_value.unwrap()
+            .into_array().unwrap()
+            .into_iter()
+            .map(|d| d.into_string().unwrap())
+            .collect();
+        assert_eq!(labels, vec!["1/A-501", "2/S-301"]);
+    }
 
-        // Try as map with x/y keys
-        if let Some(map) = item.clone().try_cast::<Map>() {
-            let x = map.get("x")
-                .and_then(|v| extract_number(v))
-                .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
-                    format!("points[{}].x", idx),
-                    "Expected 'x' key with numeric value",
-                    "missing or non-numeric",
-                    Some("{x: number, y: number}".to_string()),
-                )))?;
-            let y = map.get("y")
-                .and_then(|v| extract_number(v))
-                .ok_or_else(|| structured_err(StructuredError::invalid_parameter(
-                    format!("points[{}].y", idx),
-                    "Expected 'y' key with numeric value",
-                    "missing or non-numeric",
-                    Some("{x: number, y: number}".to_string()),
-                )))?;
-            points.push(Point2::new(x, y));
-            continue;
-        }
+    #[test]
+    fn test_create_roof_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
 
-        return Err(structured_err(StructuredError::invalid_geometry(
-            format!("Invalid point format at index {}", idx),
-            vec![
-                "Use array format: [x, y]".to_string(),
-                "Or object format: {x: number, y: number}".to_string(),
-            ],
-        )));
+        let script = r#"
+            let project = create_project("Cabin", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            create_roof(level, "gable", 6.0, 1.5)
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        let roof_id = result.return_value.unwrap().cast::<RoofId>();
+        assert_eq!(store.read().unwrap().get_roof(roof_id).unwrap().roof_type, RoofType::Gable);
+
+        let result = execute_script(&engine, r#"create_roof(add_level(add_building(create_project("X", "imperial", "IRC"), "B"), "L", 0.0, 9.0), "dome", 6.0, 1.0)"#, store);
+        assert!(!result.success);
     }
 
-    if points.len() < 3 {
-        return Err(structured_err(StructuredError::degenerate_geometry(
-            EntityType::Footprint,
-            format!("Polygon requires at least 3 points, got {}", points.len()),
-        )));
+    #[test]
+    fn test_set_project_code_region_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Retrofit", "imperial", "US_IRC_2021");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            create_room(level, "bedroom", "Bedroom", [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]);
+
+            let change = set_project_code_region(project, "US_IRC_2012");
+            change.newly_violated[0].rule
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        assert_eq!(result.return_value.unwrap().into_string().unwrap(), "largest_room_area");
     }
 
-    Ok(Polygon2::new(points))
-}
+    #[test]
+    fn test_generate_wall_framing_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::store::new_shared_store;
+        let script = r#"
+            let project = create_project("Shed", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            let assembly = create_wall_assembly("Stud Wall");
+            let wall = create_wall(level, assembly, [0.0, 0.0], [12.0, 0.0], 9.0);
+
+            generate_wall_framing(wall).stud_count
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        // 12' at 16" OC: studs at 0" through 128", plus the end stud
+        assert_eq!(result.return_value.unwrap().as_int().unwrap(), 10);
+    }
 
     #[test]
-    fn test_create_project_script() {
+    fn test_add_fixture_script() {
         let store = new_shared_store();
         let engine = create_engine(store.clone());
 
         let script = r#"
-            let project = create_project("Test House", "imperial", "US_IRC_2021");
-            project
+            let project = create_project("Bath", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            let bath = create_room(level, "bathroom", "Bath", [[0.0, 0.0], [8.0, 0.0], [8.0, 6.0], [0.0, 6.0]]);
+
+            add_fixture(bath, "toilet", [2.0, 0.0], 90.0);
+            add_fixture(bath, "sink", [5.0, 0.0], 90.0);
+            wet_walls(level).rough_ins
         "#;
 
         let result = execute_script(&engine, script, store.clone());
         assert!(result.success, "Script failed: {:?}", result.error);
-        assert!(result.events_generated > 0);
+        // No walls are modeled, so each fixture needs its own rough-in
+        assert_eq!(result.return_value.unwrap().as_int().unwrap(), 2);
     }
 
     #[test]
-    fn test_full_building_script() {
+    fn test_footprint_sketch_script() {
         let store = new_shared_store();
         let engine = create_engine(store.clone());
 
         let script = r#"
-            let project = create_project("Scandi House", "imperial", "US_IRC_2021");
+            let project = create_project("Massing", "imperial", "IRC");
             let bldg = add_building(project, "Main");
-            let main = add_level(bldg, "Main Floor", 0.0, 9.0);
-            let upper = add_level(bldg, "Upper Floor", 9.0, 8.0);
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            let footprint = set_level_footprint_rect(level, 40.0, 30.0);
 
-            set_level_footprint_rect(main, 40.0, 30.0);
-            set_level_footprint_rect(upper, 40.0, 30.0);
+            insert_vertex(footprint, 2, [25.0, 30.0]);
+            push_edge(footprint, 2, -15.0);
+            get_footprint_vertices(footprint).len()
+        "#;
 
-            let stats = get_building_stats(bldg);
-            stats.total_area
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        assert_eq!(result.return_value.unwrap().as_int().unwrap(), 6);
+    }
+
+    #[test]
+    fn test_grid_placement_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Grid", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            create_grid(bldg);
+            add_grid_axis(bldg, "A", "horizontal", 0.0);
+            add_grid_axis(bldg, "B", "horizontal", 24.0);
+            add_grid_axis(bldg, "1", "vertical", 0.0);
+            add_grid_axis(bldg, "3", "vertical", 32.0);
+
+            let assembly = create_wall_assembly("Ext");
+            create_wall(level, assembly, "A/1", "A/3", 9.0);
+            create_room(level, "living", "Great Room", ["A/1", "A/3", "B/3", [0.0, 24.0]]);
+            let snapped = snap_point_to_grid(bldg, [31.7, 12.0], 0.5);
+            snapped[0]
         "#;
 
         let result = execute_script(&engine, script, store.clone());
         assert!(result.success, "Script failed: {:?}", result.error);
+        assert_eq!(result.return_value.unwrap().as_float().unwrap(), 32.0);
 
-        if let Some(value) = result.return_value {
-            let area = value.as_float().unwrap();
-            assert!((area - 2400.0).abs() < 1e-10);
-        }
+        let store = store.read().unwrap();
+        let wall = store.walls.values().next().unwrap();
+        assert_eq!(wall.end, Point2::new(32.0, 0.0));
+        let room = store.rooms.values().next().unwrap();
+        assert!((room.area() - 768.0).abs() < 1e-9);
     }
 
     #[test]
-    fn test_polygon_footprint_script() {
+    fn test_multiple_footprints_script() {
         let store = new_shared_store();
         let engine = create_engine(store.clone());
 
         let script = r#"
-            let project = create_project("L-Shape", "imperial", "US_IRC_2021");
+            let project = create_project("Wings", "imperial", "IRC");
             let bldg = add_building(project, "Main");
-            let main = add_level(bldg, "Main", 0.0, 9.0);
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            let main = set_level_footprint_rect(level, 40.0, 30.0);
+            add_auxiliary_footprint(level, [[40.0, 0.0], [62.0, 0.0], [62.0, 22.0], [40.0, 22.0]]);
 
-            // L-shaped footprint
-            let footprint = [
-                [0.0, 0.0],
-                [40.0, 0.0],
-                [40.0, 20.0],
-                [20.0, 20.0],
-                [20.0, 30.0],
-                [0.0, 30.0]
-            ];
-            set_level_footprint(main, footprint);
+            let pieces = split_footprint(main, [30.0, -1.0], [30.0, 31.0]);
+            get_level_footprints(level).len()
+        "#;
 
-            get_footprint_area(main)
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        assert_eq!(result.return_value.unwrap().as_int().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_update_opening_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Shed", "imperial", "IRC");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            let assembly = create_wall_assembly("Stud Wall");
+            let wall = create_wall(level, assembly, [0.0, 0.0], [12.0, 0.0], 9.0);
+            let window = add_opening(wall, "window", 0.5, 3.0, 4.0, 3.0);
+
+            update_opening(window, #{ position: 0.25, type: "door", sill_height: 0 });
+            window
         "#;
 
         let result = execute_script(&engine, script, store.clone());
         assert!(result.success, "Script failed: {:?}", result.error);
+        let window = result.return_value.unwrap().try_cast::<OpeningId>().unwrap();
+        let store = store.read().unwrap();
+        let opening = store.get_opening(window).unwrap();
+        assert_eq!(opening.position_along_wall, 0.25);
+        assert_eq!(opening.opening_type, OpeningType::Door);
+        assert_eq!(opening.sill_height, 0.0);
+    }
 
-        // L-shape area: (40*20) + (20*10) = 800 + 200 = 1000
-        if let Some(value) = result.return_value {
-            let area = value.as_float().unwrap();
-            assert!((area - 1000.0).abs() < 1e-10);
-        }
+    #[test]
+    fn test_create_wall_assembly_with_layers_script() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let assembly = create_wall_assembly_with_layers("Foam Sheathed", [
+                #{material: "Gypsum Board", thickness: 0.5, r_value_per_inch: 0.9},
+                #{material: "2x4 Stud", thickness: 3.5, is_structural: true},
+                #{material: "Polyiso", thickness: 1.0, r_value_per_inch: 6.0, is_insulation: true}
+            ]);
+            total_r_value(assembly)
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        let r = result.return_value.unwrap().as_float().unwrap();
+        assert!((r - 6.45).abs() < 1e-9);
     }
 
     #[test]
-    fn test_script_error_handling() {
+    fn test_query_functions_script() {
         let store = new_shared_store();
         let engine = create_engine(store.clone());
 
         let script = r#"
-            // Try to add a building to a non-existent project
-            let fake_project = create_project("Test", "imperial", "IRC");
-            add_level(fake_project, "Test", 0.0, 9.0)
+            let project = create_project("Query", "imperial", "US_IRC_2021");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            let assembly = create_wall_assembly("2x4 Interior");
+            let wall = create_wall(level, assembly, [0.0, 0.0], [20.0, 0.0], 9.0);
+            create_wall(level, assembly, [20.0, 0.0], [20.0, 12.0], 9.0);
+            add_opening(wall, "window", 0.75, 3.0, 4.0, 3.0);
+            add_opening(wall, "door", 0.25, 3.0, 6.8, 0.0);
+            create_room(level, "living_room", "Living", [[0.0, 0.0], [20.0, 0.0], [20.0, 12.0], [0.0, 12.0]]);
+
+            let total = 0.0;
+            for room in get_level_rooms(level) {
+                total += get_room_area(room);
+            }
+            let first = get_opening(get_wall_openings(wall)[0]);
+            let room = get_room(get_level_rooms(level)[0]);
+            #{
+                buildings: get_project_buildings(project).len(),
+                walls: get_level_walls(level).len(),
+                total: total,
+                first_type: first.type,
+                room_type: room.room_type,
+                wall_length: get_wall(wall).length,
+                assemblies: list_wall_assemblies().len(),
+                roof: get_level_roof(level) == ()
+            }
         "#;
 
-        // This should fail because we're adding a level directly to a project (wrong type)
         let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        let map = result.return_value.unwrap().cast::<Map>();
+        assert_eq!(map["buildings"].as_int().unwrap(), 1);
+        assert_eq!(map["walls"].as_int().unwrap(), 2);
+        assert!((map["total"].as_float().unwrap() - 240.0).abs() < 1e-9);
+        // Openings come back in order along the wall
+        assert_eq!(map["first_type"].clone().into_string().unwrap(), "door");
+        assert_eq!(map["room_type"].clone().into_string().unwrap(), "living_room");
+        assert!((map["wall_length"].as_float().unwrap() - 20.0).abs() < 1e-9);
+        assert!(map["assemblies"].as_int().unwrap() >= 1);
+        assert!(map["roof"].as_bool().unwrap());
+
+        let result = execute_script(&engine, r#"get_wall(get_level_walls(add_level(add_building(create_project("X", "imperial", "IRC"), "B"), "L", 0.0, 9.0))[0])"#, store);
         assert!(!result.success);
     }
 
     #[test]
-    fn test_max_operations_limit() {
+    fn test_edit_and_remove_script() {
         let store = new_shared_store();
         let engine = create_engine(store.clone());
 
-        // This should hit the operations limit
         let script = r#"
-            let x = 0;
-            loop {
-                x += 1;
+            let project = create_project("Edits", "imperial", "US_IRC_2021");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            let interior = create_wall_assembly("2x4 Interior");
+            let exterior = create_wall_assembly("2x6 Exterior");
+            let keep = create_wall(level, interior, [0.0, 0.0], [20.0, 0.0], 9.0);
+            let drop = create_wall(level, interior, [20.0, 0.0], [20.0, 12.0], 9.0);
+            add_opening(drop, "window", 0.5, 3.0, 4.0, 3.0);
+            let door = add_opening(keep, "door", 0.25, 3.0, 6.8, 0.0);
+            let room = create_room(level, "bedroom", "Bed", [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]);
+
+            set_wall_endpoints(keep, [0.0, 0.0], [24.0, 0.0]);
+            set_wall_assembly(keep, exterior);
+            remove_wall(drop);
+            remove_opening(door);
+            remove_room(room);
+            #{
+                walls: get_level_walls(level).len(),
+                openings: get_wall_openings(keep).len(),
+                rooms: get_level_rooms(level).len(),
+                length: get_wall(keep).length,
+                assembly: get_wall(keep).assembly_id,
+                exterior: exterior
             }
         "#;
 
         let result = execute_script(&engine, script, store.clone());
-        assert!(!result.success);
-        assert!(result.error.unwrap().contains("operations"));
+        assert!(result.success, "Script failed: {:?}", result.error);
+        let map = result.return_value.unwrap().cast::<Map>();
+        assert_eq!(map["walls"].as_int().unwrap(), 1);
+        assert_eq!(map["openings"].as_int().unwrap(), 0);
+        assert_eq!(map["rooms"].as_int().unwrap(), 0);
+        assert!((map["length"].as_float().unwrap() - 24.0).abs() < 1e-9);
+        assert_eq!(map["assembly"].clone().cast::<WallAssemblyId>(), map["exterior"].clone().cast::<WallAssemblyId>());
+        // The removed wall's window went with it
+        assert!(store.read().unwrap().openings.is_empty());
     }
 
     #[test]
-    fn test_phase2_script() {
+    fn test_frame_price_and_check_script() {
         let store = new_shared_store();
         let engine = create_engine(store.clone());
 
         let script = r#"
-            let project = create_project("Phase2 House", "imperial", "IRC");
+            let project = create_project("Studio", "imperial", "US_IRC_2021");
             let bldg = add_building(project, "Main");
-            let level = add_level(bldg, "L1", 0.0, 10.0);
-            
-            let assembly = create_wall_assembly("Basic Wall");
-            
-            let p1 = [0.0, 0.0];
-            let p2 = [10.0, 0.0];
-            let wall = create_wall(level, assembly, p1, p2, 10.0);
-            
-            add_opening(wall, "window", 0.5, 3.0, 4.0, 2.0);
-            
-            let room_poly = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
-            create_room(level, "living", "Living Room", room_poly);
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            set_level_footprint(level, [[0.0, 0.0], [12.0, 0.0], [12.0, 10.0], [0.0, 10.0]]);
+            let assembly = create_wall_assembly("Stud Wall");
+            let wall = create_wall(level, assembly, [0.0, 0.0], [12.0, 0.0], 9.0);
+            add_opening(wall, "window", 0.5, 3.0, 4.0, 3.0);
+            create_room(level, "closet", "Closet", [[0.0, 0.0], [12.0, 0.0], [12.0, 10.0], [0.0, 10.0]]);
+
+            let framing = get_framing_summary(wall);
+            let estimate = generate_cost_estimate(level);
+            let compliance = run_compliance_checks(level);
+            #{
+                studs: framing.stud_count,
+                headers: framing.headers.len(),
+                total: estimate.grand_total,
+                items: estimate.line_items.len(),
+                checked: compliance.checked.len(),
+                compliant: compliance.compliant
+            }
         "#;
 
         let result = execute_script(&engine, script, store.clone());
         assert!(result.success, "Script failed: {:?}", result.error);
+        let map = result.return_value.unwrap().cast::<Map>();
+        assert!(map["studs"].as_int().unwrap() > 0);
+        assert_eq!(map["headers"].as_int().unwrap(), 1);
+        // Default prices are $0 until a price table is loaded, but every
+        // quantity is still itemized
+        assert_eq!(map["total"].as_float().unwrap(), 0.0);
+        assert!(map["items"].as_int().unwrap() > 0);
+        assert!(map["checked"].as_int().unwrap() > 0);
+        assert!(map["compliant"].as_bool().unwrap());
+    }
+
+    #[test]
+    fn test_saved_script_library() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Library", "imperial", "US_IRC_2021");
+            let bldg = add_building(project, "Main");
+            add_level(bldg, "L1", 0.0, 9.0);
+            save_script(project, "Closet", `
+                let level = get_building_levels(get_project_buildings(project)[0])[0];
+                let w = if "width" in params { params.width } else { 4.0 };
+                create_room(level, "closet", "Closet", [[0.0, 0.0], [w, 0.0], [w, 3.0], [0.0, 3.0]]);
+                get_room_area(get_level_rooms(level)[0])
+            `);
+            save_script(project, "Runaway", `run_saved_script(project, "Runaway")`);
+            let area = run_saved_script(project, "Closet", #{ width: 6.0 });
+            #{ project: project, area: area }
+        "#;
+        let result = execute_script(&engine, script, store.clone());
+        assert!(result.success, "Script failed: {:?}", result.error);
+        let map = result.return_value.unwrap().cast::<Map>();
+        let project_id = map["project"].clone().cast::<ProjectId>();
+        assert!((map["area"].as_float().unwrap() - 18.0).abs() < 1e-9);
+
+        // Hosts run saved scripts by name too
+        let result = execute_saved_script(&engine, store.clone(), project_id, "closet", Map::new(), &ExecutionLimits::default()).unwrap();
+        assert!(result.success, "Script failed: {:?}", result.error);
+        assert_eq!(store.read().unwrap().rooms.len(), 2);
+        assert!(execute_saved_script(&engine, store.clone(), project_id, "Missing", Map::new(), &ExecutionLimits::default()).is_err());
+
+        let result = execute_saved_script(&engine, store.clone(), project_id, "Runaway", Map::new(), &ExecutionLimits::default()).unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("nest"));
+
+        let mut scope = saved_script_scope(project_id, Map::new());
+        let result = execute_in_scope(
+            &engine,
+            r#"save_script(project, "Broken", "let = ;")"#,
+            &mut scope,
+            store.clone(),
+            &ExecutionLimits::default(),
+        );
+        assert!(!result.success);
+        assert_eq!(store.read().unwrap().get_project_scripts(project_id).len(), 2);
+    }
+
+    #[test]
+    fn test_script_call_trace() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        let script = r#"
+            let project = create_project("Traced", "imperial", "US_IRC_2021");
+            let bldg = add_building(project, "Main");
+            let level = add_level(bldg, "L1", 0.0, 9.0);
+            let total = 0;
+            for name in ["A", "B"] {
+                total += name.len();
+            }
+            add_level(bldg, "L2", 9.0, 0.0);
+        "#;
+
+        let result = execute_script(&engine, script, store.clone());
+        assert!(!result.success);
+
+        // Only domain calls are traced, not loops, operators or string methods
+        let functions: Vec<&str> = result.trace.iter().map(|c| c.function.as_str()).collect();
+        assert_eq!(functions, vec!["create_project", "add_building", "add_level", "add_level"]);
+
+        let created = &result.trace[1];
+        assert_eq!(created.args[1], serde_json::json!("Main"));
+        let building_id = created.result.clone().unwrap();
+        assert_eq!(result.trace[2].args[0], building_id);
+        assert_eq!(result.trace[2].line, Some(4));
+
+        // The failing call carries its structured error
+        let failed = result.trace.last().unwrap();
+        assert!(failed.result.is_none());
+        assert!(failed.error.as_ref().unwrap()["code"].is_string());
+
+        let json = result.to_json();
+        assert_eq!(json["success"], serde_json::json!(false));
+        assert_eq!(json["trace"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_script_timeout_and_cancel() {
+        let store = new_shared_store();
+        let engine = create_engine(store.clone());
+
+        // Runs well past the timeout but inside the operation limit
+        let script = r#"
+            let project = create_project("Slow", "imperial", "US_IRC_2021");
+            add_building(project, "Main");
+            let n = 0;
+            loop {
+                n += get_event_count(project);
+                if n > 1_000_000_000 { break; }
+            }
+        "#;
+        let limits = ExecutionLimits::with_timeout(std::time::Duration::ZERO);
+        let result = execute_script_with_limits(&engine, script, store.clone(), &limits);
+        assert!(!result.success);
+        assert_eq!(result.aborted, Some(ScriptAbort::Timeout));
+        // Both edits before the abort are reported
+        assert_eq!(result.events_generated, 2);
+        assert_eq!(result.to_json()["aborted"], serde_json::json!("timeout"));
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let limits = ExecutionLimits { timeout: None, cancel: Some(cancel) };
+        let result = execute_script_with_limits(&engine, script, store.clone(), &limits);
+        assert_eq!(result.aborted, Some(ScriptAbort::Cancelled));
+
+        // Without limits the operation cap still applies, and isn't an abort
+        let result = execute_script(&engine, script, store.clone());
+        assert!(!result.success);
+        assert_eq!(result.aborted, None);
+    }
+    #[test]
+    fn test_project_scoped_engine() {
+        let store = new_shared_store();
+        let (own, other) = {
+            let mut s = store.write().unwrap();
+            let own = s.create_project("Own", UnitSystem::Imperial, CodeRegion::us_irc_2021()).unwrap();
+            let other = s.create_project("Other", UnitSystem::Imperial, CodeRegion::us_irc_2021()).unwrap();
+            (own, other)
+        };
+        let engine = create_project_engine(store.clone(), own);
+
+        let script = r#"
+            add_building(project, "Main");
+            snapshot();
+            list_project_ids().len()
+        "#;
+        let result = execute_in_project(&engine, script, store.clone(), own, &ExecutionLimits::default());
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.return_value.unwrap().as_int().unwrap(), 1);
+        assert_eq!(store.read().unwrap().get_project(own).unwrap().building_ids.len(), 1);
+
+        let result = execute_in_project(&engine, r#"create_project("New", "imperial", "US_IRC_2021")"#, store.clone(), own, &ExecutionLimits::default());
+        assert!(!result.success);
+        assert_eq!(store.read().unwrap().projects.len(), 2);
+
+        // A handle to another project that leaks in is still refused
+        let mut scope = Scope::new();
+        scope.push_constant("other", other);
+        assert!(engine.eval_with_scope::<BuildingId>(&mut scope, r#"add_building(other, "Main")"#).is_err());
+        assert!(engine.eval_with_scope::<rhai::Array>(&mut scope, "get_project_buildings(other)").unwrap().is_empty());
+        assert!(store.read().unwrap().get_project(other).unwrap().building_ids.is_empty());
     }
 }
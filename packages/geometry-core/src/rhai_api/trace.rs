@@ -0,0 +1,134 @@
+// Call tracing for script execution
+// Each domain function a script calls is recorded with its arguments and
+// what it returned or why it failed, so the caller gets feedback on every
+// step rather than only the script's final value. Calls are observed through
+// Rhai's debugger hook, which sees native calls as they return.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use rhai::debugger::DebuggerCommand;
+use rhai::{Dynamic, Engine, EvalAltResult, Map};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::domain::*;
+
+/// Calls recorded per execution, beyond which the trace is cut short
+const MAX_TRACED_CALLS: usize = 1_000;
+
+/// One domain function call made by a script
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TracedCall {
+    pub function: String,
+    pub args: Vec<Value>,
+    /// Returned value, None if the call failed
+    pub result: Option<Value>,
+    /// Structured error the call failed with
+    pub error: Option<Value>,
+    /// Script line of the call
+    pub line: Option<usize>,
+}
+
+thread_local! {
+    static CALL_TRACE: RefCell<Vec<TracedCall>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Record calls to every function registered on the engine so far. Built-in
+/// operators and standard library calls are left out.
+pub(super) fn register_call_trace(engine: &mut Engine) {
+    let domain_functions: HashSet<String> = engine
+        .gen_fn_signatures(false)
+        .iter()
+        .filter_map(|signature| signature.split('(').next())
+        .map(str::to_string)
+        .collect();
+
+    // Rhai flags the debugger API deprecated only to mark it as unstable
+    #[allow(deprecated)]
+    engine.register_debugger(
+        |_, debugger| debugger,
+        move |context, event, _node, _source, pos| {
+            use rhai::debugger::DebuggerEvent;
+
+            let (result, error) = match event {
+                DebuggerEvent::FunctionExitWithValue(value) => (Some(dynamic_to_json(value)), None),
+                DebuggerEvent::FunctionExitWithError(err) => (None, Some(error_to_json(err))),
+                _ => return Ok(DebuggerCommand::StepInto),
+            };
+            let Some(frame) = context.global_runtime_state().debugger().call_stack().last() else {
+                return Ok(DebuggerCommand::StepInto);
+            };
+            if !domain_functions.contains(frame.fn_name.as_str()) {
+                return Ok(DebuggerCommand::StepInto);
+            }
+
+            let call = TracedCall {
+                function: frame.fn_name.to_string(),
+                args: frame.args.iter().map(dynamic_to_json).collect(),
+                result,
+                error,
+                line: pos.line(),
+            };
+            CALL_TRACE.with(|trace| {
+                let mut trace = trace.borrow_mut();
+                if trace.len() < MAX_TRACED_CALLS {
+                    trace.push(call);
+                }
+            });
+            Ok(DebuggerCommand::StepInto)
+        },
+    );
+}
+
+/// Run `f` with an empty trace, returning what it recorded
+pub(super) fn traced<T>(f: impl FnOnce() -> T) -> (T, Vec<TracedCall>) {
+    let outer = CALL_TRACE.with(|trace| trace.take());
+    let value = f();
+    let calls = CALL_TRACE.with(|trace| trace.replace(outer));
+    (value, calls)
+}
+
+/// JSON for a script value, with entity handles written as their IDs
+pub fn dynamic_to_json(value: &Dynamic) -> Value {
+    macro_rules! ids {
+        ($($id:ty),*) => {
+            $(
+                if let Some(id) = value.read_lock::<$id>() {
+                    return Value::String(id.to_string());
+                }
+            )*
+        };
+    }
+    ids!(
+        ProjectId, BuildingId, LevelId, FootprintId, SiteId, WallId, WallAssemblyId, RoomId, OpeningId,
+        FilterId, ScriptId, SnapshotId, DetailCalloutId, RoofId, FoundationId, StairId, FixtureId
+    );
+
+    if value.is_unit() {
+        Value::Null
+    } else if let Ok(b) = value.as_bool() {
+        Value::Bool(b)
+    } else if let Ok(i) = value.as_int() {
+        Value::from(i)
+    } else if let Ok(f) = value.as_float() {
+        serde_json::Number::from_f64(f).map_or(Value::Null, Value::Number)
+    } else if value.is_string() {
+        Value::String(value.to_string())
+    } else if let Some(array) = value.read_lock::<rhai::Array>() {
+        Value::Array(array.iter().map(dynamic_to_json).collect())
+    } else if let Some(map) = value.read_lock::<Map>() {
+        Value::Object(map.iter().map(|(k, v)| (k.to_string(), dynamic_to_json(v))).collect())
+    } else {
+        Value::String(value.type_name().to_string())
+    }
+}
+
+/// Structured errors come back as their JSON; anything else as its message
+pub(super) fn error_to_json(err: &EvalAltResult) -> Value {
+    let message = match err {
+        EvalAltResult::ErrorRuntime(value, _) if value.is_string() => value.to_string(),
+        other => other.to_string(),
+    };
+    serde_json::from_str(&message).unwrap_or(Value::String(message))
+}
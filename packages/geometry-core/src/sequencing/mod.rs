@@ -0,0 +1,407 @@
+// Construction sequencing - orders building entities by construction phase
+// Produces a timed manifest the frontend can use to animate the build
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{BuildingId, FootprintId, Level, LevelId, OpeningId, RoomId, WallId};
+use crate::store::Store;
+
+/// Construction phases in build order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstructionPhase {
+    Foundation,
+    FloorFraming,
+    WallFraming,
+    Roof,
+    Openings,
+    Finishes,
+}
+
+impl ConstructionPhase {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Foundation => "Foundation",
+            Self::FloorFraming => "Floor Framing",
+            Self::WallFraming => "Wall Framing",
+            Self::Roof => "Roof",
+            Self::Openings => "Openings",
+            Self::Finishes => "Finishes",
+        }
+    }
+
+    /// All phases in construction order
+    pub fn all_ordered() -> Vec<Self> {
+        vec![
+            Self::Foundation,
+            Self::FloorFraming,
+            Self::WallFraming,
+            Self::Roof,
+            Self::Openings,
+            Self::Finishes,
+        ]
+    }
+}
+
+/// Entity that appears in a sequence step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "id", rename_all = "snake_case")]
+pub enum SequencedEntity {
+    Footprint(FootprintId),
+    Wall(WallId),
+    Opening(OpeningId),
+    Room(RoomId),
+}
+
+/// Animation duration (seconds) per step for each phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceTiming {
+    pub foundation: f64,
+    pub floor_framing: f64,
+    pub wall: f64,
+    pub roof: f64,
+    pub opening: f64,
+    pub finish: f64,
+}
+
+impl Default for SequenceTiming {
+    fn default() -> Self {
+        Self {
+            foundation: 2.0,
+            floor_framing: 1.5,
+            wall: 0.5,
+            roof: 2.0,
+            opening: 0.25,
+            finish: 0.5,
+        }
+    }
+}
+
+impl SequenceTiming {
+    pub fn duration_for(&self, phase: ConstructionPhase) -> f64 {
+        match phase {
+            ConstructionPhase::Foundation => self.foundation,
+            ConstructionPhase::FloorFraming => self.floor_framing,
+            ConstructionPhase::WallFraming => self.wall,
+            ConstructionPhase::Roof => self.roof,
+            ConstructionPhase::Openings => self.opening,
+            ConstructionPhase::Finishes => self.finish,
+        }
+    }
+}
+
+/// A single timed step in the construction sequence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceStep {
+    pub index: usize,
+    pub phase: ConstructionPhase,
+    pub level_id: LevelId,
+    pub entity: SequencedEntity,
+    pub label: String,
+    pub start_time: f64, // seconds from start of sequence
+    pub duration: f64,   // seconds
+}
+
+impl SequenceStep {
+    pub fn end_time(&self) -> f64 {
+        self.start_time + self.duration
+    }
+}
+
+/// Ordered construction manifest for a building
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstructionSequence {
+    pub building_id: BuildingId,
+    pub steps: Vec<SequenceStep>,
+    pub total_duration: f64,
+}
+
+impl ConstructionSequence {
+    fn new(building_id: BuildingId) -> Self {
+        Self {
+            building_id,
+            steps: Vec::new(),
+            total_duration: 0.0,
+        }
+    }
+
+    fn push(
+        &mut self,
+        phase: ConstructionPhase,
+        level_id: LevelId,
+        entity: SequencedEntity,
+        label: String,
+        duration: f64,
+    ) {
+        self.steps.push(SequenceStep {
+            index: self.steps.len(),
+            phase,
+            level_id,
+            entity,
+            label,
+            start_time: self.total_duration,
+            duration,
+        });
+        self.total_duration += duration;
+    }
+
+    /// Get steps belonging to a phase
+    pub fn steps_in_phase(&self, phase: ConstructionPhase) -> Vec<&SequenceStep> {
+        self.steps.iter().filter(|s| s.phase == phase).collect()
+    }
+
+    /// Time at which a phase begins, if it has any steps
+    pub fn phase_start(&self, phase: ConstructionPhase) -> Option<f64> {
+        self.steps
+            .iter()
+            .find(|s| s.phase == phase)
+            .map(|s| s.start_time)
+    }
+}
+
+/// Build the construction sequence for a building
+///
+/// Order: foundation under the lowest level, then floor framing and walls level
+/// by level from the bottom up, the roof over the top level, then openings and
+/// room finishes level by level.
+pub fn sequence_building(
+    store: &Store,
+    building_id: BuildingId,
+    timing: &SequenceTiming,
+) -> Result<ConstructionSequence> {
+    store
+        .get_building(building_id)
+        .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+
+    let mut levels: Vec<&Level> = store.get_building_levels(building_id);
+    levels.sort_by(|a, b| a.elevation.total_cmp(&b.elevation));
+
+    let mut sequence = ConstructionSequence::new(building_id);
+
+    // Foundation under the lowest level with a footprint
+    if let Some((level, footprint_id)) = levels
+        .iter()
+        .find_map(|l| l.footprint_id.map(|fid| (*l, fid)))
+    {
+        sequence.push(
+            ConstructionPhase::Foundation,
+            level.id,
+            SequencedEntity::Footprint(footprint_id),
+            format!("{} foundation", level.name),
+            timing.foundation,
+        );
+    }
+
+    // Structure, bottom up
+    for level in &levels {
+        if let Some(footprint_id) = level.footprint_id {
+            sequence.push(
+                ConstructionPhase::FloorFraming,
+                level.id,
+                SequencedEntity::Footprint(footprint_id),
+                format!("{} floor framing", level.name),
+                timing.floor_framing,
+            );
+        }
+
+        let mut wall_number = 0;
+        for wall_id in &level.wall_ids {
+            if store.get_wall(*wall_id).is_none() {
+                continue;
+            }
+            wall_number += 1;
+            sequence.push(
+                ConstructionPhase::WallFraming,
+                level.id,
+                SequencedEntity::Wall(*wall_id),
+                format!("{} wall {}", level.name, wall_number),
+                timing.wall,
+            );
+        }
+    }
+
+    // Roof over the top level with a footprint
+    if let Some((level, footprint_id)) = levels
+        .iter()
+        .rev()
+        .find_map(|l| l.footprint_id.map(|fid| (*l, fid)))
+    {
+        sequence.push(
+            ConstructionPhase::Roof,
+            level.id,
+            SequencedEntity::Footprint(footprint_id),
+            format!("Roof over {}", level.name),
+            timing.roof,
+        );
+    }
+
+    // Openings, in wall order then position along each wall
+    for level in &levels {
+        for wall_id in &level.wall_ids {
+            let mut openings = store.get_wall_openings(*wall_id);
+            openings.sort_by(|a, b| a.position_along_wall.total_cmp(&b.position_along_wall));
+            for opening in openings {
+                sequence.push(
+                    ConstructionPhase::Openings,
+                    level.id,
+                    SequencedEntity::Opening(opening.id),
+                    format!("{} {}", level.name, opening.opening_type.display_name()),
+                    timing.opening,
+                );
+            }
+        }
+    }
+
+    // Finishes, room by room
+    for level in &levels {
+        for room_id in &level.room_ids {
+            if let Some(room) = store.get_room(*room_id) {
+                sequence.push(
+                    ConstructionPhase::Finishes,
+                    level.id,
+                    SequencedEntity::Room(room.id),
+                    format!("{} finishes", room.name),
+                    timing.finish,
+                );
+            }
+        }
+    }
+
+    Ok(sequence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::*;
+
+    fn two_story() -> (Store, BuildingId, LevelId, LevelId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        // Added out of elevation order on purpose
+        let upper = store.add_level(building_id, "Second", 9.0, 8.0).unwrap();
+        let lower = store.add_level(building_id, "First", 0.0, 9.0).unwrap();
+        store.set_level_footprint(lower, Polygon2::rectangle(30.0, 20.0)).unwrap();
+        store.set_level_footprint(upper, Polygon2::rectangle(30.0, 20.0)).unwrap();
+
+        let assembly = store
+            .create_wall_assembly("Ext", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        for level in [lower, upper] {
+            let wall = store
+                .create_wall(level, assembly, Point2::new(0.0, 0.0), Point2::new(30.0, 0.0), 8.0)
+                .unwrap();
+            store
+                .add_opening(wall, OpeningType::Window, 0.5, 3.0, 4.0, 3.0)
+                .unwrap();
+            store
+                .create_room(level, RoomType::Bedroom, "Bed", Polygon2::rectangle(10.0, 10.0))
+                .unwrap();
+        }
+
+        (store, building_id, lower, upper)
+    }
+
+    #[test]
+    fn test_phase_ordering() {
+        let (store, building_id, _, _) = two_story();
+        let sequence = sequence_building(&store, building_id, &SequenceTiming::default()).unwrap();
+
+        // 1 foundation + 2 floors + 2 walls + 1 roof + 2 openings + 2 rooms
+        assert_eq!(sequence.steps.len(), 10);
+
+        let phases: Vec<ConstructionPhase> = sequence.steps.iter().map(|s| s.phase).collect();
+        let first_roof = phases.iter().position(|p| *p == ConstructionPhase::Roof).unwrap();
+        assert!(phases[..first_roof]
+            .iter()
+            .all(|p| *p <= ConstructionPhase::WallFraming));
+        assert!(phases[first_roof + 1..]
+            .iter()
+            .all(|p| *p >= ConstructionPhase::Openings));
+        assert_eq!(phases[0], ConstructionPhase::Foundation);
+        assert_eq!(*phases.last().unwrap(), ConstructionPhase::Finishes);
+    }
+
+    #[test]
+    fn test_levels_built_bottom_up() {
+        let (store, building_id, lower, upper) = two_story();
+        let sequence = sequence_building(&store, building_id, &SequenceTiming::default()).unwrap();
+
+        assert_eq!(sequence.steps[0].level_id, lower);
+        let walls = sequence.steps_in_phase(ConstructionPhase::WallFraming);
+        assert_eq!(walls[0].level_id, lower);
+        assert_eq!(walls[1].level_id, upper);
+        assert_eq!(sequence.steps_in_phase(ConstructionPhase::Roof)[0].level_id, upper);
+    }
+
+    #[test]
+    fn test_timeline_is_contiguous() {
+        let (store, building_id, _, _) = two_story();
+        let timing = SequenceTiming::default();
+        let sequence = sequence_building(&store, building_id, &timing).unwrap();
+
+        for pair in sequence.steps.windows(2) {
+            assert!((pair[0].end_time() - pair[1].start_time).abs() < 1e-9);
+        }
+        let expected: f64 = sequence.steps.iter().map(|s| timing.duration_for(s.phase)).sum();
+        assert!((sequence.total_duration - expected).abs() < 1e-9);
+        assert_eq!(sequence.phase_start(ConstructionPhase::Foundation), Some(0.0));
+    }
+
+    #[test]
+    fn test_missing_building() {
+        let store = Store::new();
+        assert!(sequence_building(&store, BuildingId::new(), &SequenceTiming::default()).is_err());
+    }
+
+    #[test]
+    fn test_building_without_levels() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Shed").unwrap();
+
+        let sequence = sequence_building(&store, building_id, &SequenceTiming::default()).unwrap();
+        assert!(sequence.steps.is_empty());
+        assert_eq!(sequence.total_duration, 0.0);
+        assert_eq!(sequence.phase_start(ConstructionPhase::Foundation), None);
+    }
+
+    #[test]
+    fn test_level_without_footprint() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level = store.add_level(building_id, "First", 0.0, 9.0).unwrap();
+        let assembly = store
+            .create_wall_assembly("Ext", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        store
+            .create_wall(level, assembly, Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), 8.0)
+            .unwrap();
+
+        let sequence = sequence_building(&store, building_id, &SequenceTiming::default()).unwrap();
+        // No foundation, floor, or roof without a footprint - just the wall
+        assert_eq!(sequence.steps.len(), 1);
+        assert_eq!(sequence.steps[0].phase, ConstructionPhase::WallFraming);
+        assert_eq!(sequence.steps[0].label, "First wall 1");
+    }
+
+    #[test]
+    fn test_wall_labels_skip_missing_walls() {
+        let (mut store, building_id, lower, _) = two_story();
+        // A stale ID in the level's wall list must not leave a gap in numbering
+        store.get_level_mut(lower).unwrap().wall_ids.insert(0, WallId::new());
+
+        let sequence = sequence_building(&store, building_id, &SequenceTiming::default()).unwrap();
+        let walls = sequence.steps_in_phase(ConstructionPhase::WallFraming);
+        assert_eq!(walls[0].label, "First wall 1");
+    }
+}
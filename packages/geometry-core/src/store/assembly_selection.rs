@@ -0,0 +1,262 @@
+// Rules-driven wall assembly selection
+// Walls created without an explicit assembly get one from their context:
+// perimeter walls are exterior, garage/dwelling walls are fire separations,
+// bathroom and laundry walls are wet-area partitions, the rest are interior.
+
+use anyhow::{anyhow, Result};
+
+use crate::analysis::envelope::{collinear_overlap, polygon_edges};
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Assembly Selection ==========
+
+    /// Set (or clear with None) the project's assembly for a wall role
+    pub fn set_default_assembly(
+        &mut self,
+        project_id: ProjectId,
+        role: AssemblyRole,
+        assembly_id: Option<WallAssemblyId>,
+    ) -> Result<()> {
+        if let Some(id) = assembly_id {
+            if !self.wall_assemblies.contains_key(&id) {
+                return Err(anyhow!("Wall assembly not found: {:?}", id));
+            }
+        }
+        self.assign_default_assembly(project_id, role, assembly_id)
+    }
+
+    /// Set a role's assembly without checking the assembly exists, for
+    /// replaying logs into a store that doesn't hold it
+    pub(super) fn assign_default_assembly(
+        &mut self,
+        project_id: ProjectId,
+        role: AssemblyRole,
+        assembly_id: Option<WallAssemblyId>,
+    ) -> Result<()> {
+        let project = self.projects.get_mut(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+        match assembly_id {
+            Some(id) => project.default_assemblies.insert(role, id),
+            None => project.default_assemblies.remove(&role),
+        };
+        project.touch();
+
+        self.record_event(project_id, EventKind::DefaultAssemblySet { role, assembly_id });
+
+        Ok(())
+    }
+
+    /// Classify a wall segment by the footprint and rooms it runs along
+    pub fn wall_assembly_role(
+        &self,
+        level_id: LevelId,
+        start: Point2,
+        end: Point2,
+    ) -> Result<AssemblyRole> {
+        if !self.levels.contains_key(&level_id) {
+            return Err(anyhow!("Level not found: {:?}", level_id));
+        }
+
+        let runs_along = |polygon: &Polygon2| {
+            polygon_edges(polygon)
+                .iter()
+                .any(|(a, b)| collinear_overlap(&start, &end, a, b).is_some())
+        };
+
+        let footprint = self.get_level_footprint(level_id);
+        let rooms: Vec<&Room> = self.get_level_rooms(level_id)
+            .into_iter()
+            .filter(|r| runs_along(&r.boundary))
+            .collect();
+
+        // Without a footprint, a wall bounding a single room is on the perimeter
+        let on_perimeter = match footprint {
+            Some(f) => runs_along(&f.polygon),
+            None => rooms.len() == 1,
+        };
+        if on_perimeter {
+            return Ok(AssemblyRole::Exterior);
+        }
+
+        let garage = rooms.iter().any(|r| r.room_type == RoomType::Garage);
+        let dwelling = rooms
+            .iter()
+            .any(|r| r.room_type != RoomType::Garage && r.is_conditioned() == Some(true));
+        if garage && dwelling {
+            return Ok(AssemblyRole::FireSeparation);
+        }
+
+        if rooms.iter().any(|r| matches!(r.room_type, RoomType::Bathroom | RoomType::Laundry)) {
+            return Ok(AssemblyRole::WetArea);
+        }
+
+        Ok(AssemblyRole::Interior)
+    }
+
+    /// The project's assembly for a role, falling back to the built-in
+    /// preset (created on first use and reused by name after that)
+    pub fn assembly_for_role(
+        &mut self,
+        project_id: ProjectId,
+        role: AssemblyRole,
+    ) -> Result<WallAssemblyId> {
+        let project = self.projects.get(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+
+        if let Some(id) = project.default_assemblies.get(&role) {
+            if self.wall_assemblies.contains_key(id) {
+                return Ok(*id);
+            }
+        }
+
+        let preset = role.preset();
+        let existing = self.wall_assemblies
+            .values()
+            .find(|a| a.name == preset.name)
+            .map(|a| a.id);
+
+        match existing {
+            Some(id) => Ok(id),
+            None => self.create_wall_assembly(preset.name, preset.layers),
+        }
+    }
+
+    /// Pick the assembly for a new wall from its context
+    pub fn select_wall_assembly(
+        &mut self,
+        level_id: LevelId,
+        start: Point2,
+        end: Point2,
+    ) -> Result<WallAssemblyId> {
+        let role = self.wall_assembly_role(level_id, start, end)?;
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Building not found for level"))?;
+        self.assembly_for_role(project_id, role)
+    }
+
+    /// Create a wall whose assembly is chosen by select_wall_assembly
+    pub fn create_wall_auto(
+        &mut self,
+        level_id: LevelId,
+        start: Point2,
+        end: Point2,
+        height: f64,
+    ) -> Result<WallId> {
+        let assembly_id = self.select_wall_assembly(level_id, start, end)?;
+        self.create_wall(level_id, assembly_id, start, end, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 30x20 footprint with a garage on the left, a bath in the middle and a
+    /// bedroom and living room on the right
+    fn setup() -> (Store, ProjectId, LevelId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(30.0, 20.0)).unwrap();
+
+        let rect = |x0: f64, y0: f64, x1: f64, y1: f64| {
+            Polygon2::new(vec![
+                Point2::new(x0, y0),
+                Point2::new(x1, y0),
+                Point2::new(x1, y1),
+                Point2::new(x0, y1),
+            ])
+        };
+        store.create_room(level_id, RoomType::Garage, "Garage", rect(0.0, 0.0, 10.0, 20.0)).unwrap();
+        store.create_room(level_id, RoomType::Bathroom, "Bath", rect(10.0, 0.0, 20.0, 10.0)).unwrap();
+        store.create_room(level_id, RoomType::Bedroom, "Bedroom", rect(20.0, 0.0, 30.0, 10.0)).unwrap();
+        store.create_room(level_id, RoomType::LivingRoom, "Living", rect(10.0, 10.0, 30.0, 20.0)).unwrap();
+
+        (store, project_id, level_id)
+    }
+
+    #[test]
+    fn test_roles_by_context() {
+        let (store, _, level_id) = setup();
+        let role = |x0, y0, x1, y1| {
+            store
+                .wall_assembly_role(level_id, Point2::new(x0, y0), Point2::new(x1, y1))
+                .unwrap()
+        };
+
+        assert_eq!(role(0.0, 0.0, 30.0, 0.0), AssemblyRole::Exterior);
+        assert_eq!(role(10.0, 10.0, 10.0, 20.0), AssemblyRole::FireSeparation);
+        assert_eq!(role(20.0, 0.0, 20.0, 10.0), AssemblyRole::WetArea);
+        assert_eq!(role(20.0, 10.0, 30.0, 10.0), AssemblyRole::Interior);
+    }
+
+    #[test]
+    fn test_create_wall_auto() {
+        let (mut store, project_id, level_id) = setup();
+
+        let exterior = store
+            .create_wall_auto(level_id, Point2::new(0.0, 20.0), Point2::new(30.0, 20.0), 9.0)
+            .unwrap();
+        let separation = store
+            .create_wall_auto(level_id, Point2::new(10.0, 0.0), Point2::new(10.0, 20.0), 9.0)
+            .unwrap();
+        let assembly = |store: &Store, wall_id| {
+            let wall = store.get_wall(wall_id).unwrap();
+            store.get_wall_assembly(wall.assembly_id).unwrap().name.clone()
+        };
+        assert_eq!(assembly(&store, exterior), "Exterior 2x6 Wall");
+        assert_eq!(assembly(&store, separation), "Fire Separation Wall");
+
+        // Presets are reused rather than duplicated
+        let again = store
+            .create_wall_auto(level_id, Point2::new(0.0, 0.0), Point2::new(30.0, 0.0), 9.0)
+            .unwrap();
+        assert_eq!(
+            store.get_wall(again).unwrap().assembly_id,
+            store.get_wall(exterior).unwrap().assembly_id
+        );
+
+        // The project default wins over the preset
+        let custom = store
+            .create_wall_assembly("Exterior 2x8", vec![WallLayer::new("2x8 Wood Stud", 7.25, true)])
+            .unwrap();
+        store.set_default_assembly(project_id, AssemblyRole::Exterior, Some(custom)).unwrap();
+        let wall_id = store
+            .create_wall_auto(level_id, Point2::new(30.0, 0.0), Point2::new(30.0, 20.0), 9.0)
+            .unwrap();
+        assert_eq!(store.get_wall(wall_id).unwrap().assembly_id, custom);
+    }
+
+    #[test]
+    fn test_default_assembly_validation() {
+        let (mut store, project_id, _) = setup();
+        let result = store.set_default_assembly(
+            project_id,
+            AssemblyRole::Interior,
+            Some(WallAssemblyId::new()),
+        );
+        assert!(result.is_err());
+        assert!(store.set_default_assembly(project_id, AssemblyRole::Interior, None).is_ok());
+    }
+
+    #[test]
+    fn test_default_assembly_replays() {
+        let (mut store, project_id, _) = setup();
+        let synced = store.project_revision(project_id).unwrap();
+        let custom = store
+            .create_wall_assembly("Exterior 2x8", vec![WallLayer::new("2x8 Wood Stud", 7.25, true)])
+            .unwrap();
+        store.set_default_assembly(project_id, AssemblyRole::Exterior, Some(custom)).unwrap();
+
+        // Replayed stores don't hold the assembly, only the log's reference
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        let defaults = &rebuilt.get_project(project_id).unwrap().default_assemblies;
+        assert_eq!(defaults.get(&AssemblyRole::Exterior), Some(&custom));
+        assert!(store.changes_since(project_id, synced).is_ok());
+    }
+}
@@ -0,0 +1,80 @@
+// Event broadcast for live subscribers
+// Every recorded event is sent to each subscriber's channel so a server can
+// forward it (e.g. over a WebSocket) and clients can invalidate incrementally.
+// Events recorded inside a transaction are held until it commits.
+
+use std::sync::mpsc::{channel, Receiver};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Event Subscription ==========
+
+    /// Receive every event recorded from now on, across all projects.
+    /// Dropping the receiver unsubscribes.
+    pub fn subscribe(&mut self) -> Receiver<Event> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Send an event to subscribers, or hold it while a transaction is open
+    pub(super) fn broadcast(&mut self, event: Event) {
+        if let Some(held) = &mut self.held_events {
+            held.push(event);
+            return;
+        }
+        self.subscribers.retain(|s| s.send(event.clone()).is_ok());
+    }
+
+    /// Send the events held by a committed transaction
+    pub(super) fn release_held_events(&mut self) {
+        for event in self.held_events.take().unwrap_or_default() {
+            self.broadcast(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_subscribers_receive_events() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let events = store.subscribe();
+        let dropped = store.subscribe();
+        drop(dropped);
+
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.project_id, project_id);
+        assert!(matches!(event.kind, EventKind::BuildingAdded { building_id: id, .. } if id == building_id));
+        assert_eq!(store.subscriber_count(), 1);
+
+        // Rolled-back transactions send nothing; committed ones send on commit
+        let _ = store.transaction(|tx| {
+            tx.add_level(building_id, "L1", 0.0, 9.0)?;
+            Err::<(), _>(anyhow!("abort"))
+        });
+        assert!(events.try_recv().is_err());
+
+        store
+            .transaction(|tx| {
+                tx.add_level(building_id, "L1", 0.0, 9.0)?;
+                assert!(events.try_recv().is_err());
+                Ok(())
+            })
+            .unwrap();
+        assert!(matches!(events.try_recv().unwrap().kind, EventKind::LevelAdded { .. }));
+    }
+}
@@ -0,0 +1,479 @@
+// Entity-level change sets for incremental sync
+// A client that last synced at event N asks what changed since then. The
+// project as of event N is rebuilt by replay and compared with the current
+// state, so cascaded deletes (openings of a removed wall) and undone events
+// are accounted for without special cases.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::*;
+use super::Store;
+
+/// Created, modified and deleted IDs of one entity type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EntityChanges<T> {
+    pub created: Vec<T>,
+    pub modified: Vec<T>,
+    pub deleted: Vec<T>,
+}
+
+impl<T> Default for EntityChanges<T> {
+    fn default() -> Self {
+        Self {
+            created: Vec::new(),
+            modified: Vec::new(),
+            deleted: Vec::new(),
+        }
+    }
+}
+
+impl<T> EntityChanges<T> {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+}
+
+impl<T: Copy + Eq + Hash + ToString> EntityChanges<T> {
+    fn diff(before: &HashSet<T>, after: &HashSet<T>, touched: &HashSet<T>) -> Self {
+        let sorted = |ids: Vec<T>| {
+            let mut ids = ids;
+            ids.sort_by_key(|id| id.to_string());
+            ids
+        };
+        Self {
+            created: sorted(after.difference(before).copied().collect()),
+            modified: sorted(before.intersection(after).filter(|id| touched.contains(id)).copied().collect()),
+            deleted: sorted(before.difference(after).copied().collect()),
+        }
+    }
+}
+
+/// Everything that changed in a project between two revisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChangeSet {
+    pub from: EventId,
+    pub to: EventId,
+    /// The starting revision is no longer in the history (undone or
+    /// rewritten); every current entity is listed as created and the client
+    /// should rebuild from scratch
+    pub full_resync: bool,
+    pub buildings: EntityChanges<BuildingId>,
+    pub levels: EntityChanges<LevelId>,
+    pub footprints: EntityChanges<FootprintId>,
+    pub walls: EntityChanges<WallId>,
+    pub rooms: EntityChanges<RoomId>,
+    pub openings: EntityChanges<OpeningId>,
+    pub detail_callouts: EntityChanges<DetailCalloutId>,
+    pub roofs: EntityChanges<RoofId>,
+    pub stairs: EntityChanges<StairId>,
+    pub floor_slabs: EntityChanges<FloorSlabId>,
+    pub ceilings: EntityChanges<CeilingAssemblyId>,
+    pub foundations: EntityChanges<FoundationId>,
+    pub beams: EntityChanges<BeamId>,
+    pub posts: EntityChanges<PostId>,
+    pub electrical_devices: EntityChanges<DeviceId>,
+    pub plumbing_fixtures: EntityChanges<FixtureId>,
+    pub hvac_zones: EntityChanges<HVACZoneId>,
+    pub decks: EntityChanges<DeckId>,
+    pub garages: EntityChanges<GarageId>,
+}
+
+impl ChangeSet {
+    pub fn is_empty(&self) -> bool {
+        !self.full_resync
+            && self.buildings.is_empty()
+            && self.levels.is_empty()
+            && self.footprints.is_empty()
+            && self.walls.is_empty()
+            && self.rooms.is_empty()
+            && self.openings.is_empty()
+            && self.detail_callouts.is_empty()
+            && self.roofs.is_empty()
+            && self.stairs.is_empty()
+            && self.floor_slabs.is_empty()
+            && self.ceilings.is_empty()
+            && self.foundations.is_empty()
+            && self.beams.is_empty()
+            && self.posts.is_empty()
+            && self.electrical_devices.is_empty()
+            && self.plumbing_fixtures.is_empty()
+            && self.hvac_zones.is_empty()
+            && self.decks.is_empty()
+            && self.garages.is_empty()
+    }
+}
+
+/// IDs of a project's entities in one store
+#[derive(Default)]
+struct EntitySets {
+    buildings: HashSet<BuildingId>,
+    levels: HashSet<LevelId>,
+    footprints: HashSet<FootprintId>,
+    walls: HashSet<WallId>,
+    rooms: HashSet<RoomId>,
+    openings: HashSet<OpeningId>,
+    detail_callouts: HashSet<DetailCalloutId>,
+    roofs: HashSet<RoofId>,
+    stairs: HashSet<StairId>,
+    floor_slabs: HashSet<FloorSlabId>,
+    ceilings: HashSet<CeilingAssemblyId>,
+    foundations: HashSet<FoundationId>,
+    beams: HashSet<BeamId>,
+    posts: HashSet<PostId>,
+    electrical_devices: HashSet<DeviceId>,
+    plumbing_fixtures: HashSet<FixtureId>,
+    hvac_zones: HashSet<HVACZoneId>,
+    decks: HashSet<DeckId>,
+    garages: HashSet<GarageId>,
+}
+
+impl EntitySets {
+    fn collect(store: &Store, project_id: ProjectId) -> Self {
+        let mut sets = Self::default();
+        let Some(project) = store.projects.get(&project_id) else {
+            return sets;
+        };
+
+        for building_id in &project.building_ids {
+            sets.buildings.insert(*building_id);
+            for level in store.get_building_levels(*building_id) {
+                sets.levels.insert(level.id);
+                sets.footprints.extend(level.footprint_ids());
+                sets.walls.extend(level.wall_ids.iter().copied());
+                sets.rooms.extend(level.room_ids.iter().copied());
+            }
+        }
+        sets.openings = store.openings
+            .values()
+            .filter(|o| sets.walls.contains(&o.wall_id))
+            .map(|o| o.id)
+            .collect();
+        sets.detail_callouts = store.detail_callouts
+            .values()
+            .filter(|c| sets.levels.contains(&c.level_id))
+            .map(|c| c.id)
+            .collect();
+        sets.roofs = store.roofs
+            .values()
+            .filter(|r| sets.levels.contains(&r.level_id))
+            .map(|r| r.id)
+            .collect();
+        sets.stairs = store.stairs
+            .values()
+            .filter(|s| sets.levels.contains(&s.from_level_id))
+            .map(|s| s.id)
+            .collect();
+        sets.floor_slabs = store.floor_slabs
+            .values()
+            .filter(|s| sets.levels.contains(&s.level_id))
+            .map(|s| s.id)
+            .collect();
+        sets.ceilings = store.ceilings
+            .values()
+            .filter(|c| sets.levels.contains(&c.level_id))
+            .map(|c| c.id)
+            .collect();
+        sets.foundations = store.foundations
+            .values()
+            .filter(|f| sets.levels.contains(&f.level_id))
+            .map(|f| f.id)
+            .collect();
+        sets.beams = store.beams
+            .values()
+            .filter(|b| sets.levels.contains(&b.level_id))
+            .map(|b| b.id)
+            .collect();
+        sets.posts = store.posts
+            .values()
+            .filter(|p| sets.levels.contains(&p.level_id))
+            .map(|p| p.id)
+            .collect();
+        sets.electrical_devices = store.electrical_devices
+            .values()
+            .filter(|d| sets.levels.contains(&d.level_id))
+            .map(|d| d.id)
+            .collect();
+        sets.plumbing_fixtures = store.plumbing_fixtures
+            .values()
+            .filter(|f| sets.levels.contains(&f.level_id))
+            .map(|f| f.id)
+            .collect();
+        sets.hvac_zones = store.hvac_zones
+            .values()
+            .filter(|z| sets.buildings.contains(&z.building_id))
+            .map(|z| z.id)
+            .collect();
+        sets.decks = store.decks
+            .values()
+            .filter(|d| sets.levels.contains(&d.level_id))
+            .map(|d| d.id)
+            .collect();
+        sets.garages = store.garages
+            .values()
+            .filter(|g| sets.levels.contains(&g.level_id))
+            .map(|g| g.id)
+            .collect();
+        sets
+    }
+}
+
+impl Store {
+    // ========== Change Tracking ==========
+
+    /// Entities created, modified or deleted since `after_id` (0 for all).
+    /// Walls count as modified when their openings change (a rehosted
+    /// opening touches both hosts) or a wall is
+    /// added to or removed from their level, since their meshes are cut by
+    /// openings and joins.
+    pub fn changes_since(&self, project_id: ProjectId, after_id: EventId) -> Result<ChangeSet> {
+        let log = self.event_logs.get(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+
+        // Compacted history can't reproduce the state at an older revision
+        let full_resync = after_id != 0
+            && (after_id < log.compacted_through() || !log.events().iter().any(|e| e.id == after_id));
+
+        // Which wall each opening sat on, so a rehost touches its old host
+        let mut hosts: HashMap<OpeningId, WallId> = HashMap::new();
+        let before = if after_id == 0 || full_resync {
+            EntitySets::default()
+        } else {
+            let prefix: Vec<Event> = log.events()
+                .iter()
+                .take_while(|e| e.id <= after_id)
+                .cloned()
+                .collect();
+            let store = Store::rebuild_from_events(&EventLog::from_events(prefix))?;
+            hosts.extend(store.openings.values().map(|o| (o.id, o.wall_id)));
+            EntitySets::collect(&store, project_id)
+        };
+        let after = EntitySets::collect(self, project_id);
+
+        let mut touched = EntitySets::default();
+        let window = if full_resync { Vec::new() } else { log.events_since(after_id) };
+        for event in window {
+            match &event.kind {
+                EventKind::BuildingRenamed { building_id, .. }
+                | EventKind::BuildingOccupancySet { building_id, .. }
+                | EventKind::BuildingPadElevationSet { building_id, .. }
+                | EventKind::GridCreated { building_id, .. }
+                | EventKind::GridAxisAdded { building_id, .. } => {
+                    touched.buildings.insert(*building_id);
+                }
+                EventKind::LevelModified { level_id, .. } => {
+                    touched.levels.insert(*level_id);
+                    // Stair flights are solved from level elevations
+                    touched.stairs.extend(
+                        self.stairs
+                            .values()
+                            .filter(|s| s.from_level_id == *level_id || s.to_level_id == *level_id)
+                            .map(|s| s.id),
+                    );
+                }
+                EventKind::FootprintSet { footprint_id, level_id, .. }
+                | EventKind::AuxiliaryFootprintAdded { footprint_id, level_id, .. }
+                | EventKind::FootprintRemoved { footprint_id, level_id } => {
+                    touched.footprints.insert(*footprint_id);
+                    touched.levels.insert(*level_id);
+                }
+                EventKind::FootprintModified { footprint_id, .. } => {
+                    touched.footprints.insert(*footprint_id);
+                }
+                EventKind::RoomConditioningSet { room_id, .. }
+                | EventKind::RoomFinishSet { room_id, .. }
+                | EventKind::RoomFinishesSet { room_id, .. }
+                | EventKind::RoomBoundarySet { room_id, .. }
+                | EventKind::RoomMetaUpdated { room_id, .. } => {
+                    touched.rooms.insert(*room_id);
+                }
+                EventKind::FloorSlabLayersSet { slab_id, .. } => {
+                    touched.floor_slabs.insert(*slab_id);
+                }
+                EventKind::CeilingAssemblyLayersSet { ceiling_id, .. } => {
+                    touched.ceilings.insert(*ceiling_id);
+                }
+                EventKind::FoundationDimensionsSet { foundation_id, .. } => {
+                    touched.foundations.insert(*foundation_id);
+                }
+                EventKind::GarageSlabSet { garage_id, .. } => {
+                    touched.garages.insert(*garage_id);
+                }
+                EventKind::OpeningAdded { opening_id, wall_id, .. }
+                | EventKind::OpeningRemoved { opening_id, wall_id }
+                | EventKind::OpeningModified { opening_id, wall_id, .. } => {
+                    hosts.insert(*opening_id, *wall_id);
+                    touched.openings.insert(*opening_id);
+                    touched.walls.insert(*wall_id);
+                }
+                EventKind::OpeningMoved { opening_id, wall_id, .. } => {
+                    // A rehost cuts the old host's mesh as well as the new one's
+                    if let Some(old_wall_id) = hosts.insert(*opening_id, *wall_id) {
+                        touched.walls.insert(old_wall_id);
+                    }
+                    touched.openings.insert(*opening_id);
+                    touched.walls.insert(*wall_id);
+                }
+                EventKind::WallCreated { level_id, .. }
+                | EventKind::WallRemoved { level_id, .. }
+                | EventKind::WallMoved { level_id, .. }
+                | EventKind::WallAssemblySet { level_id, .. } => {
+                    // Joins are resolved again across the level
+                    touched.walls.extend(self.get_level_walls(*level_id).iter().map(|w| w.id));
+                }
+                EventKind::WallAssemblyUpdated { wall_assembly_id, .. } => {
+                    // Thicker or thinner walls move their neighbours' joins
+                    let levels: HashSet<LevelId> = self.walls
+                        .values()
+                        .filter(|w| w.assembly_id == *wall_assembly_id)
+                        .map(|w| w.level_id)
+                        .collect();
+                    for level_id in levels {
+                        touched.walls.extend(self.get_level_walls(level_id).iter().map(|w| w.id));
+                    }
+                }
+                EventKind::HvacZoneRoomAssigned { .. } => {
+                    // The room may have left any of the project's zones
+                    touched.hvac_zones.extend(after.hvac_zones.iter().copied());
+                }
+                _ => {}
+            }
+        }
+
+        Ok(ChangeSet {
+            from: after_id,
+            to: log.last_id(),
+            full_resync,
+            buildings: EntityChanges::diff(&before.buildings, &after.buildings, &touched.buildings),
+            levels: EntityChanges::diff(&before.levels, &after.levels, &touched.levels),
+            footprints: EntityChanges::diff(&before.footprints, &after.footprints, &touched.footprints),
+            walls: EntityChanges::diff(&before.walls, &after.walls, &touched.walls),
+            rooms: EntityChanges::diff(&before.rooms, &after.rooms, &touched.rooms),
+            openings: EntityChanges::diff(&before.openings, &after.openings, &touched.openings),
+            detail_callouts: EntityChanges::diff(
+                &before.detail_callouts,
+                &after.detail_callouts,
+                &touched.detail_callouts,
+            ),
+            roofs: EntityChanges::diff(&before.roofs, &after.roofs, &touched.roofs),
+            stairs: EntityChanges::diff(&before.stairs, &after.stairs, &touched.stairs),
+            floor_slabs: EntityChanges::diff(&before.floor_slabs, &after.floor_slabs, &touched.floor_slabs),
+            ceilings: EntityChanges::diff(&before.ceilings, &after.ceilings, &touched.ceilings),
+            foundations: EntityChanges::diff(&before.foundations, &after.foundations, &touched.foundations),
+            beams: EntityChanges::diff(&before.beams, &after.beams, &touched.beams),
+            posts: EntityChanges::diff(&before.posts, &after.posts, &touched.posts),
+            electrical_devices: EntityChanges::diff(
+                &before.electrical_devices,
+                &after.electrical_devices,
+                &touched.electrical_devices,
+            ),
+            plumbing_fixtures: EntityChanges::diff(
+                &before.plumbing_fixtures,
+                &after.plumbing_fixtures,
+                &touched.plumbing_fixtures,
+            ),
+            hvac_zones: EntityChanges::diff(&before.hvac_zones, &after.hvac_zones, &touched.hvac_zones),
+            decks: EntityChanges::diff(&before.decks, &after.decks, &touched.decks),
+            garages: EntityChanges::diff(&before.garages, &after.garages, &touched.garages),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_since() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let kept = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let doomed = store
+            .create_wall(level_id, assembly_id, Point2::new(20.0, 0.0), Point2::new(20.0, 20.0), 9.0)
+            .unwrap();
+        let doomed_window = store
+            .add_opening(doomed, OpeningType::Window, 0.5, 3.0, 4.0, 3.0)
+            .unwrap();
+        let synced = store.project_revision(project_id).unwrap();
+
+        let window = store.add_opening(kept, OpeningType::Window, 0.5, 3.0, 4.0, 3.0).unwrap();
+        store.remove_wall(doomed).unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+
+        let changes = store.changes_since(project_id, synced).unwrap();
+        assert!(!changes.full_resync);
+        assert_eq!(changes.walls.modified, vec![kept]);
+        assert_eq!(changes.walls.deleted, vec![doomed]);
+        assert_eq!(changes.openings.created, vec![window]);
+        assert_eq!(changes.openings.deleted, vec![doomed_window]);
+        assert_eq!(changes.rooms.created, vec![room_id]);
+        assert!(changes.levels.is_empty());
+
+        // Nothing changed since the latest revision
+        let latest = store.project_revision(project_id).unwrap();
+        assert!(store.changes_since(project_id, latest).unwrap().is_empty());
+
+        // After undoing past the client's revision, it must resync
+        store.undo(project_id).unwrap();
+        let resync = store.changes_since(project_id, latest).unwrap();
+        assert!(resync.full_resync);
+        assert_eq!(resync.walls.created, vec![kept]);
+    }
+    #[test]
+    fn test_opening_changes() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let south = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let east = store
+            .create_wall(level_id, assembly_id, Point2::new(20.0, 0.0), Point2::new(20.0, 20.0), 9.0)
+            .unwrap();
+        let window = store.add_opening(south, OpeningType::Window, 0.5, 3.0, 4.0, 3.0).unwrap();
+
+        // Resize
+        let synced = store.project_revision(project_id).unwrap();
+        store.update_opening(window, OpeningEdit { width: Some(4.0), ..Default::default() }).unwrap();
+        let changes = store.changes_since(project_id, synced).unwrap();
+        assert_eq!(changes.openings.modified, vec![window]);
+        assert_eq!(changes.walls.modified, vec![south]);
+
+        // Move along the same wall
+        let synced = store.project_revision(project_id).unwrap();
+        store.set_opening_position(window, 0.25).unwrap();
+        let changes = store.changes_since(project_id, synced).unwrap();
+        assert_eq!(changes.openings.modified, vec![window]);
+        assert_eq!(changes.walls.modified, vec![south]);
+
+        // Rehost onto another wall touches both hosts
+        let synced = store.project_revision(project_id).unwrap();
+        store.rehost_opening(window, east, 0.5).unwrap();
+        let changes = store.changes_since(project_id, synced).unwrap();
+        assert_eq!(changes.openings.modified, vec![window]);
+        let mut expected = vec![south, east];
+        expected.sort_by_key(|id| id.to_string());
+        assert_eq!(changes.walls.modified, expected);
+    }
+}
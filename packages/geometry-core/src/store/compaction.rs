@@ -0,0 +1,645 @@
+// Event log compaction and memory accounting for long editing sessions
+// Old history is rewritten without events that no longer shape the current
+// state: setters overwritten by a later setter, and entities that were
+// created and removed again (with everything that referenced them). Recent
+// events are left alone so undo keeps full granularity there. A compaction
+// only takes effect if replaying the rewritten log gives the same project.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::*;
+use super::Store;
+
+/// When and how much history to compact
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruningPolicy {
+    /// Compact a project's log after this many new events (None = only on request)
+    pub compact_every: Option<usize>,
+    /// Most recent events never compacted
+    pub keep_recent: usize,
+}
+
+impl Default for PruningPolicy {
+    fn default() -> Self {
+        Self {
+            compact_every: None,
+            keep_recent: 500,
+        }
+    }
+}
+
+/// Outcome of compacting one project's log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CompactionReport {
+    pub events_before: usize,
+    pub events_after: usize,
+    /// Snapshots pointing into the rewritten history, now discarded
+    pub snapshots_dropped: usize,
+}
+
+/// Sizes of everything a store holds on to, for tracking growth
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MemoryStats {
+    pub projects: usize,
+    pub entities: usize,
+    pub events: usize,
+    pub undone_events: usize,
+    /// Serialized size of every event log, a proxy for its heap use
+    pub event_log_bytes: usize,
+    pub snapshots: usize,
+    pub subscribers: usize,
+}
+
+/// How an event relates to an entity's lifecycle
+#[derive(Default)]
+struct EntityRefs {
+    created: Option<String>,
+    removed: Option<String>,
+    /// Entities the event needs to exist when it is replayed
+    uses: Vec<String>,
+}
+
+impl Store {
+    // ========== History Compaction ==========
+
+    pub fn pruning_policy(&self) -> PruningPolicy {
+        self.pruning_policy
+    }
+
+    pub fn set_pruning_policy(&mut self, policy: PruningPolicy) {
+        self.pruning_policy = policy;
+    }
+
+    /// Compact a project's log up to its `keep_recent` newest events
+    pub fn compact_event_log(&mut self, project_id: ProjectId, keep_recent: usize) -> Result<CompactionReport> {
+        let log = self.event_logs.get(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+        self.last_compaction.insert(project_id, log.last_id());
+
+        let events = log.events();
+        let events_before = events.len();
+        let unchanged = CompactionReport {
+            events_before,
+            events_after: events_before,
+            snapshots_dropped: 0,
+        };
+        // Project creation always stays, as does the newest event
+        let boundary = events_before.saturating_sub(keep_recent.max(1));
+        if boundary <= 1 {
+            return Ok(unchanged);
+        }
+        let through = events[boundary - 1].id;
+
+        let dropped = droppable_events(&events[1..boundary]);
+        if dropped.is_empty() {
+            return Ok(unchanged);
+        }
+
+        let mut compacted = log.clone();
+        compacted.compact(through, |e| !dropped.contains(&e.id));
+        let expected = project_state(&Store::rebuild_from_events(log)?);
+        let actual = Store::rebuild_from_events(&compacted)
+            .map(|store| project_state(&store))
+            .ok();
+        if actual.as_ref() != Some(&expected) {
+            return Ok(unchanged);
+        }
+
+        let events_after = compacted.len();
+        self.event_logs.insert(project_id, compacted);
+
+        // Snapshots inside the rewritten history can't be restored exactly;
+        // later ones move back by the number of events removed
+        let removed = events_before - events_after;
+        let before = self.snapshots.len();
+        self.snapshots.retain(|_, s| s.project_id != project_id || s.last_event_id > through);
+        for snapshot in self.snapshots.values_mut().filter(|s| s.project_id == project_id) {
+            snapshot.event_count -= removed;
+        }
+
+        Ok(CompactionReport {
+            events_before,
+            events_after,
+            snapshots_dropped: before - self.snapshots.len(),
+        })
+    }
+
+    /// Compact a project's log if the pruning policy says it is due
+    pub(super) fn maybe_compact(&mut self, project_id: ProjectId) {
+        let Some(every) = self.pruning_policy.compact_every else {
+            return;
+        };
+        // A transaction restores a copy of the store on failure, so wait
+        // until it is over
+        if self.replaying || self.held_events.is_some() {
+            return;
+        }
+        let Some(log) = self.event_logs.get(&project_id) else {
+            return;
+        };
+        let last = self.last_compaction.get(&project_id).copied().unwrap_or(0);
+        if log.last_id().saturating_sub(last) < every as EventId {
+            return;
+        }
+        let _ = self.compact_event_log(project_id, self.pruning_policy.keep_recent);
+    }
+
+    // ========== Memory Accounting ==========
+
+    pub fn memory_stats(&self) -> MemoryStats {
+        let entities = self.sites.len()
+            + self.buildings.len()
+            + self.levels.len()
+            + self.footprints.len()
+            + self.grids.len()
+            + self.wall_assemblies.len()
+            + self.walls.len()
+            + self.rooms.len()
+            + self.openings.len()
+            + self.framing_layouts.len()
+            + self.floor_framing_layouts.len()
+            + self.saved_filters.len()
+            + self.saved_scripts.len()
+            + self.constraints.len()
+            + self.detail_callouts.len()
+            + self.roofs.len()
+            + self.stairs.len()
+            + self.floor_slabs.len()
+            + self.ceilings.len()
+            + self.foundations.len()
+            + self.beams.len()
+            + self.posts.len()
+            + self.electrical_devices.len()
+            + self.plumbing_fixtures.len()
+            + self.hvac_zones.len()
+            + self.decks.len()
+            + self.garages.len();
+
+        let logs = self.event_logs.values();
+        MemoryStats {
+            projects: self.projects.len(),
+            entities,
+            events: logs.clone().map(|l| l.len()).sum(),
+            undone_events: logs.clone().map(|l| l.undone_len()).sum(),
+            event_log_bytes: logs
+                .flat_map(|l| l.events())
+                .map(|e| serde_json::to_vec(e).map(|b| b.len()).unwrap_or(0))
+                .sum(),
+            snapshots: self.snapshots.len(),
+            subscribers: self.subscriber_count(),
+        }
+    }
+
+    /// Solids still referenced by the model; anything else in a solid
+    /// cache can be evicted
+    pub fn live_solid_ids(&self) -> HashSet<SolidId> {
+        self.footprints.values().filter_map(|fp| fp.solid_id).collect()
+    }
+}
+
+/// IDs of events in `events` that don't contribute to the final state
+fn droppable_events(events: &[Event]) -> HashSet<EventId> {
+    let mut dropped = HashSet::new();
+
+    // Setters overwritten by a later setter of the same thing
+    let mut seen = HashSet::new();
+    for event in events.iter().rev() {
+        if let Some(key) = setter_key(&event.kind) {
+            if !seen.insert(key) {
+                dropped.insert(event.id);
+            }
+        }
+    }
+
+    // Entities created and later removed, plus footprints replaced by a
+    // later footprint on the same level
+    let refs: Vec<EntityRefs> = events.iter().map(|e| entity_refs(&e.kind)).collect();
+    let created: HashSet<&String> = refs.iter().filter_map(|r| r.created.as_ref()).collect();
+    let mut gone: HashSet<String> = refs.iter()
+        .filter_map(|r| r.removed.clone())
+        .filter(|key| created.contains(key))
+        .collect();
+    let mut latest_footprint: HashMap<LevelId, FootprintId> = HashMap::new();
+    for event in events {
+        if let EventKind::FootprintSet { footprint_id, level_id, .. } = &event.kind {
+            if let Some(old) = latest_footprint.insert(*level_id, *footprint_id) {
+                gone.insert(format!("footprint:{}", old));
+            }
+        }
+    }
+
+    // Entities created against a vanished entity vanish with it
+    loop {
+        let before = gone.len();
+        for r in &refs {
+            if let Some(key) = &r.created {
+                if r.uses.iter().any(|u| gone.contains(u)) {
+                    gone.insert(key.clone());
+                }
+            }
+        }
+        if gone.len() == before {
+            break;
+        }
+    }
+
+    for (event, r) in events.iter().zip(&refs) {
+        let touches_gone = [&r.created, &r.removed]
+            .into_iter()
+            .flatten()
+            .chain(&r.uses)
+            .any(|key| gone.contains(key));
+        if touches_gone {
+            dropped.insert(event.id);
+        }
+    }
+
+    dropped
+}
+
+/// What a setter event writes, when a later write of the same key makes it moot
+fn setter_key(kind: &EventKind) -> Option<String> {
+    Some(match kind {
+        EventKind::ProjectRenamed { .. } => "project_name".to_string(),
+        EventKind::ProjectCodeRegionChanged { .. } => "code_region".to_string(),
+        EventKind::ProjectPriceRegionSet { .. } => "price_region".to_string(),
+        EventKind::ProjectMarkupsSet { .. } => "markups".to_string(),
+        EventKind::SiteBoundarySet { site_id, .. } => format!("site_boundary:{}", site_id),
+        EventKind::SiteSetbacksUpdated { site_id, .. } => format!("site_setbacks:{}", site_id),
+        EventKind::SiteZoningSet { site_id, .. } => format!("site_zoning:{}", site_id),
+        EventKind::SiteNorthAngleSet { site_id, .. } => format!("site_north:{}", site_id),
+        EventKind::SiteTerrainSet { site_id, .. } => format!("site_terrain:{}", site_id),
+        EventKind::BuildingRenamed { building_id, .. } => format!("building_name:{}", building_id),
+        EventKind::BuildingOccupancySet { building_id, .. } => format!("occupancy:{}", building_id),
+        EventKind::BuildingPadElevationSet { building_id, .. } => format!("pad_elevation:{}", building_id),
+        EventKind::FootprintModified { footprint_id, .. } => format!("footprint_polygon:{}", footprint_id),
+        EventKind::DefaultAssemblySet { role, .. } => format!("default_assembly:{:?}", role),
+        EventKind::WallAssemblyUpdated { wall_assembly_id, .. } => format!("assembly_update:{}", wall_assembly_id),
+        EventKind::RoomConditioningSet { room_id, .. } => format!("conditioned:{}", room_id),
+        EventKind::RoomFinishSet { room_id, surface, .. } => format!("finish:{}:{}", room_id, surface.as_str()),
+        EventKind::RoomFinishesSet { room_id, .. } => format!("finishes:{}", room_id),
+        EventKind::RoomBoundarySet { room_id, .. } => format!("room_boundary:{}", room_id),
+        EventKind::RoomMetaUpdated { room_id, .. } => format!("room_meta:{}", room_id),
+        EventKind::WallMoved { wall_id, .. } => format!("wall_position:{}", wall_id),
+        EventKind::WallAssemblySet { wall_id, .. } => format!("wall_assembly:{}", wall_id),
+        EventKind::OpeningMoved { opening_id, .. } => format!("opening_position:{}", opening_id),
+        EventKind::OpeningModified { opening_id, .. } => format!("opening:{}", opening_id),
+        EventKind::FloorSlabLayersSet { slab_id, .. } => format!("slab_layers:{}", slab_id),
+        EventKind::CeilingAssemblyLayersSet { ceiling_id, .. } => format!("ceiling_layers:{}", ceiling_id),
+        EventKind::FoundationDimensionsSet { foundation_id, .. } => format!("foundation_dimensions:{}", foundation_id),
+        EventKind::HvacZoneRoomAssigned { room_id, .. } => format!("hvac_zone_room:{}", room_id),
+        EventKind::GarageSlabSet { garage_id, .. } => format!("garage_slab:{}", garage_id),
+        EventKind::ScriptSaved { script } => format!("script_source:{}", script.id),
+        _ => return None,
+    })
+}
+
+fn entity_refs(kind: &EventKind) -> EntityRefs {
+    let key = |prefix: &str, id: &dyn std::fmt::Display| format!("{}:{}", prefix, id);
+    let slab_uses = |boundary: &SlabBoundary| match boundary {
+        SlabBoundary::Room { room_id } => vec![key("room", room_id)],
+        SlabBoundary::Level => Vec::new(),
+    };
+
+    match kind {
+        EventKind::FootprintSet { footprint_id, .. } => EntityRefs {
+            created: Some(key("footprint", footprint_id)),
+            ..Default::default()
+        },
+        EventKind::AuxiliaryFootprintAdded { footprint_id, .. } => EntityRefs {
+            created: Some(key("footprint", footprint_id)),
+            ..Default::default()
+        },
+        EventKind::FootprintModified { footprint_id, .. } => EntityRefs {
+            uses: vec![key("footprint", footprint_id)],
+            ..Default::default()
+        },
+        EventKind::FootprintRemoved { footprint_id, .. } => EntityRefs {
+            removed: Some(key("footprint", footprint_id)),
+            ..Default::default()
+        },
+        EventKind::WallCreated { wall_id, .. } => EntityRefs {
+            created: Some(key("wall", wall_id)),
+            ..Default::default()
+        },
+        EventKind::WallRemoved { wall_id, .. } => EntityRefs {
+            removed: Some(key("wall", wall_id)),
+            ..Default::default()
+        },
+        EventKind::WallMoved { wall_id, .. } | EventKind::WallAssemblySet { wall_id, .. } => EntityRefs {
+            uses: vec![key("wall", wall_id)],
+            ..Default::default()
+        },
+        EventKind::RoomCreated { room_id, .. } => EntityRefs {
+            created: Some(key("room", room_id)),
+            ..Default::default()
+        },
+        EventKind::RoomRemoved { room_id, .. } => EntityRefs {
+            removed: Some(key("room", room_id)),
+            ..Default::default()
+        },
+        EventKind::RoomConditioningSet { room_id, .. }
+        | EventKind::RoomFinishSet { room_id, .. }
+        | EventKind::RoomFinishesSet { room_id, .. }
+        | EventKind::RoomBoundarySet { room_id, .. }
+        | EventKind::RoomMetaUpdated { room_id, .. } => EntityRefs {
+            uses: vec![key("room", room_id)],
+            ..Default::default()
+        },
+        EventKind::OpeningAdded { opening_id, wall_id, .. } => EntityRefs {
+            created: Some(key("opening", opening_id)),
+            uses: vec![key("wall", wall_id)],
+            ..Default::default()
+        },
+        EventKind::OpeningRemoved { opening_id, wall_id } => EntityRefs {
+            removed: Some(key("opening", opening_id)),
+            uses: vec![key("wall", wall_id)],
+            ..Default::default()
+        },
+        EventKind::OpeningMoved { opening_id, wall_id, .. }
+        | EventKind::OpeningModified { opening_id, wall_id, .. } => EntityRefs {
+            uses: vec![key("opening", opening_id), key("wall", wall_id)],
+            ..Default::default()
+        },
+        EventKind::FilterSaved { filter } => EntityRefs {
+            created: Some(key("filter", &filter.id)),
+            ..Default::default()
+        },
+        EventKind::FilterRemoved { filter_id } => EntityRefs {
+            removed: Some(key("filter", filter_id)),
+            ..Default::default()
+        },
+        EventKind::ScriptSaved { script } => EntityRefs {
+            created: Some(key("script", &script.id)),
+            ..Default::default()
+        },
+        EventKind::ScriptRemoved { script_id } => EntityRefs {
+            removed: Some(key("script", script_id)),
+            ..Default::default()
+        },
+        EventKind::ConstraintAdded { constraint } => EntityRefs {
+            created: Some(key("constraint", &constraint.id)),
+            ..Default::default()
+        },
+        EventKind::ConstraintRemoved { constraint_id } => EntityRefs {
+            removed: Some(key("constraint", constraint_id)),
+            ..Default::default()
+        },
+        EventKind::DetailCalloutAdded { callout } => EntityRefs {
+            created: Some(key("callout", &callout.id)),
+            ..Default::default()
+        },
+        EventKind::DetailCalloutRemoved { callout_id } => EntityRefs {
+            removed: Some(key("callout", callout_id)),
+            ..Default::default()
+        },
+        EventKind::RoofCreated { roof } => EntityRefs {
+            created: Some(key("roof", &roof.id)),
+            ..Default::default()
+        },
+        EventKind::RoofRemoved { roof_id } => EntityRefs {
+            removed: Some(key("roof", roof_id)),
+            ..Default::default()
+        },
+        EventKind::StairCreated { stair } => EntityRefs {
+            created: Some(key("stair", &stair.id)),
+            ..Default::default()
+        },
+        EventKind::StairRemoved { stair_id } => EntityRefs {
+            removed: Some(key("stair", stair_id)),
+            ..Default::default()
+        },
+        EventKind::FloorSlabCreated { slab } => EntityRefs {
+            created: Some(key("slab", &slab.id)),
+            uses: slab_uses(&slab.boundary),
+            ..Default::default()
+        },
+        EventKind::FloorSlabLayersSet { slab_id, .. } => EntityRefs {
+            uses: vec![key("slab", slab_id)],
+            ..Default::default()
+        },
+        EventKind::FloorSlabRemoved { slab_id } => EntityRefs {
+            removed: Some(key("slab", slab_id)),
+            ..Default::default()
+        },
+        EventKind::CeilingAssemblyCreated { ceiling } => EntityRefs {
+            created: Some(key("ceiling", &ceiling.id)),
+            uses: slab_uses(&ceiling.boundary),
+            ..Default::default()
+        },
+        EventKind::CeilingAssemblyLayersSet { ceiling_id, .. } => EntityRefs {
+            uses: vec![key("ceiling", ceiling_id)],
+            ..Default::default()
+        },
+        EventKind::CeilingAssemblyRemoved { ceiling_id } => EntityRefs {
+            removed: Some(key("ceiling", ceiling_id)),
+            ..Default::default()
+        },
+        EventKind::FoundationCreated { foundation } => EntityRefs {
+            created: Some(key("foundation", &foundation.id)),
+            ..Default::default()
+        },
+        EventKind::FoundationDimensionsSet { foundation_id, .. } => EntityRefs {
+            uses: vec![key("foundation", foundation_id)],
+            ..Default::default()
+        },
+        EventKind::FoundationRemoved { foundation_id } => EntityRefs {
+            removed: Some(key("foundation", foundation_id)),
+            ..Default::default()
+        },
+        EventKind::BeamCreated { beam } => EntityRefs {
+            created: Some(key("beam", &beam.id)),
+            ..Default::default()
+        },
+        EventKind::BeamRemoved { beam_id } => EntityRefs {
+            removed: Some(key("beam", beam_id)),
+            ..Default::default()
+        },
+        EventKind::PostCreated { post } => EntityRefs {
+            created: Some(key("post", &post.id)),
+            ..Default::default()
+        },
+        EventKind::PostRemoved { post_id } => EntityRefs {
+            removed: Some(key("post", post_id)),
+            ..Default::default()
+        },
+        EventKind::ElectricalDeviceAdded { device } => EntityRefs {
+            created: Some(key("device", &device.id)),
+            uses: device.wall_id.iter().map(|id| key("wall", id)).collect(),
+            ..Default::default()
+        },
+        EventKind::ElectricalDeviceRemoved { device_id } => EntityRefs {
+            removed: Some(key("device", device_id)),
+            ..Default::default()
+        },
+        EventKind::FixtureAdded { fixture } => EntityRefs {
+            created: Some(key("fixture", &fixture.id)),
+            uses: vec![key("room", &fixture.room_id)],
+            ..Default::default()
+        },
+        EventKind::FixtureRemoved { fixture_id } => EntityRefs {
+            removed: Some(key("fixture", fixture_id)),
+            ..Default::default()
+        },
+        EventKind::HvacZoneCreated { zone } => EntityRefs {
+            created: Some(key("hvac_zone", &zone.id)),
+            uses: vec![key("building", &zone.building_id)],
+            ..Default::default()
+        },
+        EventKind::HvacZoneRemoved { zone_id } => EntityRefs {
+            removed: Some(key("hvac_zone", zone_id)),
+            ..Default::default()
+        },
+        EventKind::DeckAdded { deck } => EntityRefs {
+            created: Some(key("deck", &deck.id)),
+            ..Default::default()
+        },
+        EventKind::DeckRemoved { deck_id } => EntityRefs {
+            removed: Some(key("deck", deck_id)),
+            ..Default::default()
+        },
+        EventKind::GarageConfigured { garage } => EntityRefs {
+            created: Some(key("garage", &garage.id)),
+            uses: vec![key("room", &garage.room_id)],
+            ..Default::default()
+        },
+        EventKind::GarageRemoved { garage_id } => EntityRefs {
+            removed: Some(key("garage", garage_id)),
+            ..Default::default()
+        },
+        EventKind::GarageSlabSet { garage_id, .. } => EntityRefs {
+            uses: vec![key("garage", garage_id)],
+            ..Default::default()
+        },
+        EventKind::HvacZoneRoomAssigned { room_id, zone_id } => EntityRefs {
+            uses: std::iter::once(key("room", room_id))
+                .chain(zone_id.iter().map(|id| key("hvac_zone", id)))
+                .collect(),
+            ..Default::default()
+        },
+        _ => EntityRefs::default(),
+    }
+}
+
+/// Every event-sourced entity in a store, keyed for comparison
+fn project_state(store: &Store) -> BTreeMap<String, serde_json::Value> {
+    fn add<K: std::fmt::Display, V: Serialize>(
+        state: &mut BTreeMap<String, serde_json::Value>,
+        prefix: &str,
+        map: &HashMap<K, V>,
+    ) {
+        for (id, value) in map {
+            let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+            state.insert(format!("{}:{}", prefix, id), value);
+        }
+    }
+
+    let mut state = BTreeMap::new();
+    add(&mut state, "project", &store.projects);
+    add(&mut state, "site", &store.sites);
+    add(&mut state, "building", &store.buildings);
+    add(&mut state, "level", &store.levels);
+    add(&mut state, "footprint", &store.footprints);
+    add(&mut state, "grid", &store.grids);
+    add(&mut state, "wall", &store.walls);
+    add(&mut state, "room", &store.rooms);
+    add(&mut state, "opening", &store.openings);
+    add(&mut state, "filter", &store.saved_filters);
+    add(&mut state, "script", &store.saved_scripts);
+    add(&mut state, "constraint", &store.constraints);
+    add(&mut state, "callout", &store.detail_callouts);
+    add(&mut state, "roof", &store.roofs);
+    add(&mut state, "stair", &store.stairs);
+    add(&mut state, "slab", &store.floor_slabs);
+    add(&mut state, "ceiling", &store.ceilings);
+    add(&mut state, "foundation", &store.foundations);
+    add(&mut state, "beam", &store.beams);
+    add(&mut state, "post", &store.posts);
+    add(&mut state, "device", &store.electrical_devices);
+    add(&mut state, "fixture", &store.plumbing_fixtures);
+    add(&mut state, "hvac_zone", &store.hvac_zones);
+    add(&mut state, "deck", &store.decks);
+    add(&mut state, "garage", &store.garages);
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compaction_preserves_state() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store.create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()]).unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(12.0, 12.0))
+            .unwrap();
+
+        // Churn: walls drawn and erased, finishes changed back and forth
+        for i in 0..20 {
+            let x = i as f64;
+            let wall = store
+                .create_wall(level_id, assembly_id, Point2::new(x, 0.0), Point2::new(x, 10.0), 9.0)
+                .unwrap();
+            store.add_opening(wall, OpeningType::Window, 0.5, 3.0, 4.0, 3.0).unwrap();
+            store.remove_wall(wall).unwrap();
+            let floor = if i % 2 == 0 { MaterialType::LVP } else { MaterialType::Tile };
+            store.set_room_finish(room_id, FinishSurface::Floor, floor).unwrap();
+        }
+        let kept = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let early = store.create_snapshot(project_id).unwrap();
+        store.set_room_conditioned(room_id, Some(true)).unwrap();
+        store.set_room_conditioned(room_id, None).unwrap();
+        let late = store.create_snapshot(project_id).unwrap();
+        store.set_building_occupancy(building_id, BuildingOccupancy::Adu).unwrap();
+
+        let report = store.compact_event_log(project_id, 2).unwrap();
+        assert!(report.events_after < report.events_before / 3, "{:?}", report);
+        assert_eq!(report.snapshots_dropped, 1);
+        assert!(store.get_snapshot(early).is_none());
+
+        // The rewritten log replays to the same model, and undo still works
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        assert!(rebuilt.get_wall(kept).is_some());
+        assert_eq!(rebuilt.walls.len(), 1);
+        assert_eq!(rebuilt.get_room(room_id).unwrap().finishes().floor, MaterialType::Tile);
+
+        store.restore_snapshot(late).unwrap();
+        assert_eq!(store.get_building(building_id).unwrap().occupancy, BuildingOccupancy::Dwelling);
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_room(room_id).unwrap().conditioned, Some(true));
+
+        // Revisions from before the compaction force a full resync
+        assert!(store.changes_since(project_id, 3).unwrap().full_resync);
+    }
+
+    #[test]
+    fn test_policy_compacts_automatically() {
+        let mut store = Store::new();
+        store.set_pruning_policy(PruningPolicy { compact_every: Some(50), keep_recent: 10 });
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        for i in 0..500 {
+            let region = if i % 2 == 0 { CodeRegion::us_ibc_2021() } else { CodeRegion::us_irc_2021() };
+            store.set_project_code_region(project_id, region).unwrap();
+        }
+
+        let stats = store.memory_stats();
+        assert!(stats.events <= 61, "{:?}", stats);
+        assert!(stats.event_log_bytes > 0);
+        assert_eq!(store.get_project(project_id).unwrap().code_region, CodeRegion::us_irc_2021());
+    }
+}
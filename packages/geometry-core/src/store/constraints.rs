@@ -0,0 +1,123 @@
+// Named constraint storage
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Named Constraint Operations ==========
+
+    pub fn add_constraint(
+        &mut self,
+        project_id: ProjectId,
+        name: impl Into<String>,
+        kind: ConstraintKind,
+    ) -> Result<ConstraintId> {
+        let name = name.into();
+        if !self.projects.contains_key(&project_id) {
+            return Err(anyhow!("Project not found: {:?}", project_id));
+        }
+
+        if name.trim().is_empty() {
+            return Err(anyhow!("Constraint name must not be empty"));
+        }
+
+        if self.find_constraint(project_id, &name).is_some() {
+            return Err(anyhow!("Constraint already exists: {}", name));
+        }
+
+        for room_id in kind.room_ids() {
+            let room = self.rooms.get(&room_id)
+                .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+            if self.level_project_id(room.level_id) != Some(project_id) {
+                return Err(anyhow!("Room {:?} is not in this project", room_id));
+            }
+        }
+        for wall_id in kind.wall_ids() {
+            let wall = self.walls.get(&wall_id)
+                .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
+            if self.level_project_id(wall.level_id) != Some(project_id) {
+                return Err(anyhow!("Wall {:?} is not in this project", wall_id));
+            }
+        }
+
+        match &kind {
+            ConstraintKind::RoomMinArea { min_area, .. } if *min_area <= 0.0 => {
+                return Err(anyhow!("Minimum area must be positive"));
+            }
+            ConstraintKind::RoomAspectRatio { max_ratio, .. } if *max_ratio < 1.0 => {
+                return Err(anyhow!("Maximum aspect ratio must be at least 1"));
+            }
+            ConstraintKind::WallDistance { wall_a, wall_b, distance } => {
+                if wall_a == wall_b {
+                    return Err(anyhow!("A wall distance needs two different walls"));
+                }
+                if *distance <= 0.0 {
+                    return Err(anyhow!("Wall distance must be positive"));
+                }
+            }
+            ConstraintKind::WallOnGridAxis { wall_id, axis } if self.wall_grid_axis(*wall_id, axis).is_none() => {
+                return Err(anyhow!("Grid axis not found: {}", axis));
+            }
+            _ => {}
+        }
+
+        let constraint = Constraint::new(project_id, name, kind);
+        let constraint_id = constraint.id;
+
+        self.record_event(project_id, EventKind::ConstraintAdded { constraint: constraint.clone() });
+        self.constraints.insert(constraint_id, constraint);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(constraint_id)
+    }
+
+    pub fn get_constraint(&self, id: ConstraintId) -> Option<&Constraint> {
+        self.constraints.get(&id)
+    }
+
+    /// Look up a project's constraint by name (case-insensitive)
+    pub fn find_constraint(&self, project_id: ProjectId, name: &str) -> Option<&Constraint> {
+        self.constraints
+            .values()
+            .find(|c| c.project_id == project_id && c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// All constraints declared in a project, sorted by name
+    pub fn get_project_constraints(&self, project_id: ProjectId) -> Vec<&Constraint> {
+        let mut constraints: Vec<&Constraint> = self.constraints
+            .values()
+            .filter(|c| c.project_id == project_id)
+            .collect();
+        constraints.sort_by(|a, b| a.name.cmp(&b.name));
+        constraints
+    }
+
+    pub fn remove_constraint(&mut self, constraint_id: ConstraintId) -> Result<()> {
+        let constraint = self.constraints.remove(&constraint_id)
+            .ok_or_else(|| anyhow!("Constraint not found: {:?}", constraint_id))?;
+
+        self.record_event(constraint.project_id, EventKind::ConstraintRemoved { constraint_id });
+
+        if let Some(project) = self.projects.get_mut(&constraint.project_id) {
+            project.touch();
+        }
+
+        Ok(())
+    }
+
+    /// Named axis on the grid of the building a wall stands in
+    pub fn wall_grid_axis(&self, wall_id: WallId, name: &str) -> Option<&GridAxis> {
+        let wall = self.walls.get(&wall_id)?;
+        let level = self.levels.get(&wall.level_id)?;
+        self.grids
+            .get(&level.building_id)?
+            .axes
+            .iter()
+            .find(|a| a.name.eq_ignore_ascii_case(name))
+    }
+}
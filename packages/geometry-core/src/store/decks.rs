@@ -0,0 +1,153 @@
+// Decks and porches attached to a level's outline
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::deck::MAX_DECK_DEPTH;
+use crate::domain::*;
+use crate::framing::DeckFramingGenerator;
+use super::Store;
+
+impl Store {
+    // ========== Deck Operations ==========
+
+    /// Attach a deck or porch to edge `edge_index` of a level's footprint,
+    /// starting `offset` feet from the edge's first corner and running
+    /// `width` feet along it. The deck extends `depth` feet outward, with
+    /// its surface `height` feet above grade.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_deck(
+        &mut self,
+        level_id: LevelId,
+        kind: DeckKind,
+        edge_index: usize,
+        offset: f64,
+        width: f64,
+        depth: f64,
+        height: f64,
+    ) -> Result<DeckId> {
+        let footprint = self.get_level_footprint(level_id)
+            .ok_or_else(|| anyhow!("Level has no footprint: {:?}", level_id))?;
+        let outer = &footprint.polygon.outer;
+        if edge_index >= outer.len() {
+            return Err(anyhow!("Footprint has no edge {}", edge_index));
+        }
+        if !(depth > 0.0 && depth <= MAX_DECK_DEPTH) {
+            return Err(anyhow!("Deck depth must be between 0 and {}'", MAX_DECK_DEPTH));
+        }
+        if !height.is_finite() || height < 0.0 {
+            return Err(anyhow!("Deck height must be zero or more"));
+        }
+
+        let (a, b) = (outer[edge_index], outer[(edge_index + 1) % outer.len()]);
+        let length = a.distance_to(&b);
+        if !(width > 0.0 && offset >= 0.0 && offset + width <= length + 1e-6) {
+            return Err(anyhow!("Deck must fit along the {:.1}' edge", length));
+        }
+        let at = |d: f64| Point2::new(a.x + (b.x - a.x) * d / length, a.y + (b.y - a.y) * d / length);
+        // The deck extends right of its ledger, which is outward along a
+        // counter-clockwise outline
+        let (start, end) = if footprint.polygon.signed_area() >= 0.0 {
+            (at(offset), at(offset + width))
+        } else {
+            (at(offset + width), at(offset))
+        };
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+        let deck = Deck::new(level_id, kind, start, end, depth, height);
+        let deck_id = deck.id;
+
+        self.record_event(project_id, EventKind::DeckAdded { deck: deck.clone() });
+        self.decks.insert(deck_id, deck);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(deck_id)
+    }
+
+    pub fn get_deck(&self, id: DeckId) -> Option<&Deck> {
+        self.decks.get(&id)
+    }
+
+    /// Decks and porches on a level
+    pub fn get_level_decks(&self, level_id: LevelId) -> Vec<&Deck> {
+        let mut decks: Vec<&Deck> = self.decks
+            .values()
+            .filter(|d| d.level_id == level_id)
+            .collect();
+        decks.sort_by_key(|d| d.id.to_string());
+        decks
+    }
+
+    pub fn remove_deck(&mut self, deck_id: DeckId) -> Result<()> {
+        let deck = self.decks.remove(&deck_id)
+            .ok_or_else(|| anyhow!("Deck not found: {:?}", deck_id))?;
+
+        if let Some(project_id) = self.level_project_id(deck.level_id) {
+            self.record_event(project_id, EventKind::DeckRemoved { deck_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ledger, joists, decking and railing posts for a deck
+    pub fn get_deck_framing(&self, deck_id: DeckId) -> Result<DeckFramingLayout> {
+        let deck = self.decks.get(&deck_id)
+            .ok_or_else(|| anyhow!("Deck not found: {:?}", deck_id))?;
+        Ok(DeckFramingGenerator::generate(deck)?)
+    }
+
+    /// Plan area of the decks and porches on a building's levels
+    pub fn building_deck_area(&self, building_id: BuildingId) -> f64 {
+        self.get_building_levels(building_id)
+            .iter()
+            .flat_map(|l| self.get_level_decks(l.id))
+            .map(|d| d.area())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_deck() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "House").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0)).unwrap();
+
+        // 16' along the north edge (30' up, running west), 4' from its start
+        let deck_id = store.add_deck(level_id, DeckKind::Deck, 2, 4.0, 16.0, 12.0, 3.0).unwrap();
+        let deck = store.get_deck(deck_id).unwrap();
+        let outline = deck.outline();
+        assert!(outline.outer.iter().all(|p| p.y >= 30.0 - 1e-9));
+        assert!(outline.outer.iter().any(|p| (p.y - 42.0).abs() < 1e-9));
+        assert!(outline.outer.iter().all(|p| p.x >= 20.0 - 1e-9 && p.x <= 36.0 + 1e-9));
+        assert!(store.get_deck_framing(deck_id).unwrap().railing_posts > 0);
+
+        // Decks are counted apart from the footprint
+        let stats = store.get_building_stats(building_id).unwrap();
+        assert!((stats.total_area - 1200.0).abs() < 1e-9);
+        assert!((stats.deck_area - 192.0).abs() < 1e-9);
+
+        assert!(store.add_deck(level_id, DeckKind::Porch, 0, 30.0, 16.0, 6.0, 1.0).is_err());
+        assert!(store.add_deck(level_id, DeckKind::Porch, 4, 0.0, 6.0, 6.0, 1.0).is_err());
+        assert!(store.add_deck(level_id, DeckKind::Porch, 0, 0.0, 6.0, 20.0, 1.0).is_err());
+
+        store.undo(project_id).unwrap();
+        assert!(store.get_deck(deck_id).is_none());
+        store.redo(project_id).unwrap();
+        store.remove_level(level_id).unwrap();
+        assert!(store.get_deck(deck_id).is_none());
+    }
+}
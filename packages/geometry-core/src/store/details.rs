@@ -0,0 +1,227 @@
+// Detail callouts and the detail schedule
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Detail Callout Operations ==========
+
+    /// Place a callout at a plan location
+    pub fn add_detail_callout(
+        &mut self,
+        level_id: LevelId,
+        location: Point2,
+        reference: DetailReference,
+    ) -> Result<DetailCalloutId> {
+        self.insert_detail_callout(DetailCallout::new(level_id, location, reference))
+    }
+
+    /// Place a callout at the midpoint of a wall, linked to it
+    pub fn add_wall_detail_callout(
+        &mut self,
+        wall_id: WallId,
+        reference: DetailReference,
+    ) -> Result<DetailCalloutId> {
+        let wall = self.walls.get(&wall_id)
+            .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
+
+        let mut callout = DetailCallout::new(wall.level_id, wall.midpoint(), reference);
+        callout.wall_id = Some(wall_id);
+        self.insert_detail_callout(callout)
+    }
+
+    /// Place a callout at the center of an opening, linked to it and its wall
+    pub fn add_opening_detail_callout(
+        &mut self,
+        opening_id: OpeningId,
+        reference: DetailReference,
+    ) -> Result<DetailCalloutId> {
+        let opening = self.openings.get(&opening_id)
+            .ok_or_else(|| anyhow!("Opening not found: {:?}", opening_id))?;
+        let wall = self.walls.get(&opening.wall_id)
+            .ok_or_else(|| anyhow!("Wall not found: {:?}", opening.wall_id))?;
+
+        let location = wall.point_at(opening.position_along_wall * wall.length());
+        let mut callout = DetailCallout::new(wall.level_id, location, reference);
+        callout.wall_id = Some(wall.id);
+        callout.opening_id = Some(opening_id);
+        self.insert_detail_callout(callout)
+    }
+
+    fn insert_detail_callout(&mut self, callout: DetailCallout) -> Result<DetailCalloutId> {
+        if callout.reference.detail_number.trim().is_empty() || callout.reference.sheet.trim().is_empty() {
+            return Err(anyhow!("Detail reference needs a detail number and sheet"));
+        }
+        let project_id = self.level_project_id(callout.level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", callout.level_id))?;
+        let callout_id = callout.id;
+
+        self.record_event(project_id, EventKind::DetailCalloutAdded { callout: callout.clone() });
+        self.detail_callouts.insert(callout_id, callout);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(callout_id)
+    }
+
+    pub fn get_detail_callout(&self, id: DetailCalloutId) -> Option<&DetailCallout> {
+        self.detail_callouts.get(&id)
+    }
+
+    /// Callouts on a level, sorted by label
+    pub fn get_level_detail_callouts(&self, level_id: LevelId) -> Vec<&DetailCallout> {
+        let mut callouts: Vec<&DetailCallout> = self.detail_callouts
+            .values()
+            .filter(|c| c.level_id == level_id)
+            .collect();
+        callouts.sort_by_key(|c| c.label());
+        callouts
+    }
+
+    pub fn remove_detail_callout(&mut self, callout_id: DetailCalloutId) -> Result<()> {
+        let callout = self.detail_callouts.remove(&callout_id)
+            .ok_or_else(|| anyhow!("Detail callout not found: {:?}", callout_id))?;
+
+        if let Some(project_id) = self.level_project_id(callout.level_id) {
+            self.record_event(project_id, EventKind::DetailCalloutRemoved { callout_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Every detail referenced in a project, one entry per detail, sorted
+    /// by sheet then detail number
+    pub fn detail_schedule(&self, project_id: ProjectId) -> Vec<DetailScheduleEntry> {
+        let mut entries: Vec<DetailScheduleEntry> = Vec::new();
+
+        let mut callouts: Vec<&DetailCallout> = self.detail_callouts
+            .values()
+            .filter(|c| self.level_project_id(c.level_id) == Some(project_id))
+            .collect();
+        callouts.sort_by_key(|c| c.id.to_string());
+
+        for callout in callouts {
+            let reference = &callout.reference;
+            let existing = entries
+                .iter()
+                .position(|e| e.sheet == reference.sheet && e.detail_number == reference.detail_number);
+            let index = existing.unwrap_or_else(|| {
+                entries.push(DetailScheduleEntry {
+                    label: reference.label(),
+                    kind: reference.kind.clone(),
+                    sheet: reference.sheet.clone(),
+                    detail_number: reference.detail_number.clone(),
+                    callout_ids: Vec::new(),
+                    level_ids: Vec::new(),
+                });
+                entries.len() - 1
+            });
+
+            let entry = &mut entries[index];
+            entry.callout_ids.push(callout.id);
+            if !entry.level_ids.contains(&callout.level_id) {
+                entry.level_ids.push(callout.level_id);
+            }
+        }
+
+        entries.sort_by(|a, b| {
+            a.sheet.cmp(&b.sheet).then_with(|| natural_key(&a.detail_number).cmp(&natural_key(&b.detail_number)))
+        });
+        entries
+    }
+}
+
+/// Sort "2" before "10" while still ordering non-numeric labels
+fn natural_key(s: &str) -> (u64, String) {
+    (s.parse().unwrap_or(u64::MAX), s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Store, ProjectId, LevelId, WallId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        (store, project_id, level_id, wall_id)
+    }
+
+    #[test]
+    fn test_callouts_and_schedule() {
+        let (mut store, project_id, level_id, wall_id) = setup();
+        let opening_id = store
+            .add_opening(wall_id, OpeningType::Window, 0.25, 3.0, 4.0, 3.0)
+            .unwrap();
+
+        let section = DetailReference::new(DetailKind::TypicalWallSection, "1", "A-501");
+        let wall_callout = store.add_wall_detail_callout(wall_id, section.clone()).unwrap();
+        store.add_detail_callout(level_id, Point2::new(0.0, 20.0), section).unwrap();
+        let head = store
+            .add_opening_detail_callout(opening_id, DetailReference::new(DetailKind::WindowHead, "10", "A-501"))
+            .unwrap();
+        store
+            .add_detail_callout(level_id, Point2::new(0.0, 0.0), DetailReference::new(DetailKind::FoundationEdge, "2", "S-301"))
+            .unwrap();
+
+        let head_callout = store.get_detail_callout(head).unwrap();
+        assert!((head_callout.location.x - 5.0).abs() < 1e-10);
+        assert_eq!(store.get_detail_callout(wall_callout).unwrap().location, Point2::new(10.0, 0.0));
+
+        let schedule = store.detail_schedule(project_id);
+        let labels: Vec<&str> = schedule.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, vec!["1/A-501", "10/A-501", "2/S-301"]);
+        assert_eq!(schedule[0].callout_ids.len(), 2);
+
+        // Removing the wall keeps the callouts but drops their links
+        store.remove_wall(wall_id).unwrap();
+        assert_eq!(store.get_detail_callout(head).unwrap().opening_id, None);
+        assert_eq!(store.get_detail_callout(wall_callout).unwrap().wall_id, None);
+
+        // Undo restores the links through replay
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_detail_callout(head).unwrap().opening_id, Some(opening_id));
+    }
+
+    #[test]
+    fn test_callout_validation() {
+        let (mut store, _, level_id, _) = setup();
+        let blank = DetailReference::new(DetailKind::WindowSill, "", "A-501");
+        assert!(store.add_detail_callout(level_id, Point2::new(0.0, 0.0), blank).is_err());
+
+        let reference = DetailReference::new(DetailKind::WindowSill, "4", "A-501");
+        assert!(store.add_detail_callout(LevelId::new(), Point2::new(0.0, 0.0), reference.clone()).is_err());
+        let id = store.add_detail_callout(level_id, Point2::new(0.0, 0.0), reference).unwrap();
+        store.remove_level(level_id).unwrap();
+        assert!(store.get_detail_callout(id).is_none());
+        assert!(store.remove_detail_callout(id).is_err());
+    }
+
+    #[test]
+    fn test_remove_building_drops_callouts() {
+        let (mut store, _, level_id, _) = setup();
+        let reference = DetailReference::new(DetailKind::WindowSill, "4", "A-501");
+        let id = store.add_detail_callout(level_id, Point2::new(0.0, 0.0), reference).unwrap();
+        let building_id = store.get_level(level_id).unwrap().building_id;
+
+        store.remove_building(building_id).unwrap();
+        assert!(store.get_detail_callout(id).is_none());
+        assert!(store.get_level_detail_callouts(level_id).is_empty());
+    }
+}
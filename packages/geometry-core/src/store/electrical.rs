@@ -0,0 +1,461 @@
+// Electrical devices, the rough-in auto-layout and the device schedule
+
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+
+use crate::costing::prices::csv_field;
+use crate::domain::electrical::{MIN_WALL_SPACE, RECEPTACLE_SPACING, SWITCH_OFFSET};
+use crate::domain::*;
+use super::room_derivation::contains;
+use super::Store;
+
+/// How far off a wall's face a point can be and still be mounted in it (feet)
+const WALL_TOLERANCE: f64 = 0.25;
+
+impl Store {
+    // ========== Electrical Device Operations ==========
+
+    /// Place a device at a plan point. It's mounted in `wall_id` when given,
+    /// otherwise in the wall it sits on, if any, and belongs to the room it
+    /// falls in.
+    pub fn add_electrical_device(
+        &mut self,
+        level_id: LevelId,
+        kind: ElectricalDeviceKind,
+        location: Point2,
+        wall_id: Option<WallId>,
+    ) -> Result<DeviceId> {
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        if let Some(wall_id) = wall_id {
+            let wall = self.walls.get(&wall_id)
+                .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
+            if wall.level_id != level_id {
+                return Err(anyhow!("Wall {:?} is not on level {:?}", wall_id, level_id));
+            }
+        }
+
+        let mut device = ElectricalDevice::new(level_id, kind, location);
+        device.wall_id = wall_id.or_else(|| self.wall_at(level_id, &location));
+        device.room_id = self.room_at(level_id, &location);
+        let device_id = device.id;
+
+        self.record_event(project_id, EventKind::ElectricalDeviceAdded { device: device.clone() });
+        self.electrical_devices.insert(device_id, device);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(device_id)
+    }
+
+    pub fn get_electrical_device(&self, id: DeviceId) -> Option<&ElectricalDevice> {
+        self.electrical_devices.get(&id)
+    }
+
+    /// Devices on a level, by kind
+    pub fn get_level_electrical_devices(&self, level_id: LevelId) -> Vec<&ElectricalDevice> {
+        let mut devices: Vec<&ElectricalDevice> = self.electrical_devices
+            .values()
+            .filter(|d| d.level_id == level_id)
+            .collect();
+        devices.sort_by_key(|d| (d.kind, d.id.to_string()));
+        devices
+    }
+
+    pub fn remove_electrical_device(&mut self, device_id: DeviceId) -> Result<()> {
+        let device = self.electrical_devices.remove(&device_id)
+            .ok_or_else(|| anyhow!("Electrical device not found: {:?}", device_id))?;
+
+        if let Some(project_id) = self.level_project_id(device.level_id) {
+            self.record_event(project_id, EventKind::ElectricalDeviceRemoved { device_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lay out receptacles, switches and fixtures for every room on a level.
+    /// Each run of wall between doorways gets receptacles no more than 12'
+    /// apart and no more than 6' from either end; runs under 2' get none.
+    /// Each door gets a switch just past its edge, and each room a ceiling
+    /// fixture at its centroid. Devices from an earlier layout are replaced;
+    /// devices placed by hand are kept.
+    pub fn auto_layout_electrical(&mut self, level_id: LevelId) -> Result<ElectricalLayoutSummary> {
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+        let doors: Vec<(WallId, Point2, f64)> = self.get_level_walls(level_id)
+            .into_iter()
+            .flat_map(|wall| {
+                self.get_wall_openings(wall.id)
+                    .into_iter()
+                    .filter(|o| o.opening_type == OpeningType::Door)
+                    .map(move |o| (wall.id, wall.point_at(o.position_along_wall * wall.length()), o.width))
+            })
+            .collect();
+
+        let mut devices = Vec::new();
+        for room in self.get_level_rooms(level_id) {
+            let ring = &room.boundary.outer;
+            if ring.len() < 3 {
+                continue;
+            }
+            // Perimeter distance where each edge starts
+            let mut starts = Vec::with_capacity(ring.len() + 1);
+            let mut perimeter = 0.0;
+            for i in 0..ring.len() {
+                starts.push(perimeter);
+                perimeter += ring[i].distance_to(&ring[(i + 1) % ring.len()]);
+            }
+            let at = |s: f64| -> Point2 {
+                let s = s.rem_euclid(perimeter);
+                let i = starts.iter().rposition(|&start| start <= s).unwrap_or(0);
+                let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+                let length = a.distance_to(&b).max(1e-9);
+                let t = (s - starts[i]) / length;
+                Point2::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+            };
+
+            // Doorways on the room's boundary, as perimeter spans
+            let mut gaps: Vec<(f64, f64, WallId)> = Vec::new();
+            for &(wall_id, center, width) in &doors {
+                let reach = self.walls.get(&wall_id).map(|w| self.wall_thickness(w) / 2.0).unwrap_or(0.0) + WALL_TOLERANCE;
+                for i in 0..ring.len() {
+                    let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+                    let length = a.distance_to(&b);
+                    if length < 1e-9 {
+                        continue;
+                    }
+                    let (ux, uy) = ((b.x - a.x) / length, (b.y - a.y) / length);
+                    let along = (center.x - a.x) * ux + (center.y - a.y) * uy;
+                    let across = ((center.x - a.x) * uy - (center.y - a.y) * ux).abs();
+                    if across <= reach && (0.0..=length).contains(&along) {
+                        let s = starts[i] + along;
+                        gaps.push((s - width / 2.0, s + width / 2.0, wall_id));
+                        break;
+                    }
+                }
+            }
+            gaps.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut place = |kind: ElectricalDeviceKind, location: Point2, wall_id: Option<WallId>| {
+                let mut device = ElectricalDevice::new(level_id, kind, location);
+                device.wall_id = wall_id.or_else(|| self.wall_at(level_id, &location));
+                device.room_id = Some(room.id);
+                device.auto_placed = true;
+                devices.push(device);
+            };
+
+            // Receptacles along each run of wall between doorways
+            if let Some(kind) = ElectricalDeviceKind::receptacle_for(&room.room_type) {
+                let runs: Vec<(f64, f64)> = if gaps.is_empty() {
+                    vec![(0.0, perimeter)]
+                } else {
+                    (0..gaps.len())
+                        .map(|i| {
+                            let next = gaps.get(i + 1).map(|g| g.0).unwrap_or(gaps[0].0 + perimeter);
+                            (gaps[i].1, next)
+                        })
+                        .collect()
+                };
+                for (from, to) in runs {
+                    let length = to - from;
+                    if length < MIN_WALL_SPACE {
+                        continue;
+                    }
+                    let count = (length / RECEPTACLE_SPACING).ceil().max(1.0) as usize;
+                    for k in 0..count {
+                        place(kind, at(from + (k as f64 + 0.5) * length / count as f64), None);
+                    }
+                }
+            }
+
+            // A switch on the latch side of each door, or one by the start
+            // of the boundary in a room without doors
+            if gaps.is_empty() {
+                place(ElectricalDeviceKind::Switch, at(SWITCH_OFFSET), None);
+            }
+            for &(_, end, wall_id) in &gaps {
+                place(ElectricalDeviceKind::Switch, at(end + SWITCH_OFFSET), Some(wall_id));
+            }
+
+            let mut fixture = ElectricalDevice::new(level_id, ElectricalDeviceKind::LightFixture, room.boundary.centroid());
+            fixture.room_id = Some(room.id);
+            fixture.auto_placed = true;
+            devices.push(fixture);
+        }
+
+        let replaced = self.electrical_devices
+            .values()
+            .filter(|d| d.level_id == level_id && d.auto_placed)
+            .count();
+        let mut summary = ElectricalLayoutSummary { replaced, ..Default::default() };
+        for device in &devices {
+            summary.device_ids.push(device.id);
+            summary.counts.add(device.kind, 1);
+        }
+
+        self.record_event(project_id, EventKind::ElectricalLayoutApplied { level_id, devices: devices.clone() });
+        self.apply_electrical_layout(level_id, devices);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(summary)
+    }
+
+    /// Swap a level's auto-placed devices for `devices`
+    pub(super) fn apply_electrical_layout(&mut self, level_id: LevelId, devices: Vec<ElectricalDevice>) {
+        self.electrical_devices.retain(|_, d| d.level_id != level_id || !d.auto_placed);
+        for device in devices {
+            self.electrical_devices.insert(device.id, device);
+        }
+    }
+
+    /// Device counts per room across a project, ordered by level elevation
+    /// then room name; devices outside any room come last on their level
+    pub fn device_schedule(&self, project_id: ProjectId) -> Result<DeviceSchedule> {
+        let project = self.projects.get(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+
+        let mut levels: Vec<&Level> = project.building_ids
+            .iter()
+            .flat_map(|id| self.get_building_levels(*id))
+            .collect();
+        levels.sort_by(|a, b| a.elevation.total_cmp(&b.elevation));
+
+        let mut schedule = DeviceSchedule::default();
+        for level in levels {
+            let mut entries: Vec<DeviceScheduleEntry> = Vec::new();
+            for device in self.get_level_electrical_devices(level.id) {
+                let room_id = device.room_id.filter(|id| self.rooms.contains_key(id));
+                let index = match entries.iter().position(|e| e.room_id == room_id) {
+                    Some(index) => index,
+                    None => {
+                        entries.push(DeviceScheduleEntry {
+                            level_id: level.id,
+                            level_name: level.name.clone(),
+                            room_id,
+                            room_name: room_id.and_then(|id| self.rooms.get(&id)).map(|r| r.name.clone()),
+                            counts: DeviceCounts::default(),
+                        });
+                        entries.len() - 1
+                    }
+                };
+                entries[index].counts.add(device.kind, 1);
+                schedule.totals.add(device.kind, 1);
+            }
+            entries.sort_by(|a, b| match (&a.room_name, &b.room_name) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            });
+            schedule.entries.extend(entries);
+        }
+
+        Ok(schedule)
+    }
+
+    /// Wall on a level whose body holds a plan point, nearest first
+    pub(super) fn wall_at(&self, level_id: LevelId, point: &Point2) -> Option<WallId> {
+        self.get_level_walls(level_id)
+            .into_iter()
+            .filter_map(|wall| {
+                let (along, offset) = wall.project(point);
+                let reach = self.wall_thickness(wall) / 2.0 + WALL_TOLERANCE;
+                let on = (-WALL_TOLERANCE..=wall.length() + WALL_TOLERANCE).contains(&along) && offset.abs() <= reach;
+                on.then_some((wall.id, offset.abs()))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// Smallest room on a level that holds a plan point or has it on its
+    /// boundary
+    fn room_at(&self, level_id: LevelId, point: &Point2) -> Option<RoomId> {
+        self.get_level_rooms(level_id)
+            .into_iter()
+            .filter(|room| contains(&room.boundary, point) || on_boundary(&room.boundary.outer, point))
+            .min_by(|a, b| a.area().total_cmp(&b.area()))
+            .map(|room| room.id)
+    }
+}
+
+impl DeviceSchedule {
+    /// Schedule as CSV: level, room, a column per device kind and a total,
+    /// with a totals row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("level,room");
+        for kind in ElectricalDeviceKind::ALL {
+            let _ = write!(csv, ",{}", kind.as_str());
+        }
+        csv.push_str(",total\n");
+
+        let mut row = |level: &str, room: &str, counts: &DeviceCounts| {
+            let _ = write!(csv, "{},{}", csv_field(level), csv_field(room));
+            for kind in ElectricalDeviceKind::ALL {
+                let _ = write!(csv, ",{}", counts.get(kind));
+            }
+            let _ = writeln!(csv, ",{}", counts.total());
+        };
+        for entry in &self.entries {
+            row(&entry.level_name, entry.room_name.as_deref().unwrap_or(""), &entry.counts);
+        }
+        row("Total", "", &self.totals);
+        csv
+    }
+}
+
+/// Whether a point lies on a ring's edges, within the wall tolerance
+pub(super) fn on_boundary(ring: &[Point2], point: &Point2) -> bool {
+    (0..ring.len()).any(|i| {
+        let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+        let length = a.distance_to(&b);
+        if length < 1e-9 {
+            return a.distance_to(point) <= WALL_TOLERANCE;
+        }
+        let (ux, uy) = ((b.x - a.x) / length, (b.y - a.y) / length);
+        let along = (point.x - a.x) * ux + (point.y - a.y) * uy;
+        let across = ((point.x - a.x) * uy - (point.y - a.y) * ux).abs();
+        (0.0..=length).contains(&along) && across <= WALL_TOLERANCE
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 20' x 12' living room and a 8' x 6' bath beside it, walls on the
+    /// room boundaries, with a 3' door between them
+    fn setup() -> (Store, ProjectId, LevelId, RoomId, RoomId, WallId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Partition", vec![WallLayer::stud_2x4()])
+            .unwrap();
+        let partition = store
+            .create_wall(level_id, assembly_id, Point2::new(20.0, 0.0), Point2::new(20.0, 12.0), 9.0)
+            .unwrap();
+        store.add_opening(partition, OpeningType::Door, 0.25, 3.0, 6.8, 0.0).unwrap();
+
+        let living = store
+            .create_room(level_id, RoomType::LivingRoom, "Living", Polygon2::rectangle(20.0, 12.0))
+            .unwrap();
+        let bath_outline = Polygon2::new(vec![
+            Point2::new(20.0, 0.0),
+            Point2::new(28.0, 0.0),
+            Point2::new(28.0, 6.0),
+            Point2::new(20.0, 6.0),
+        ]);
+        let bath = store.create_room(level_id, RoomType::Bathroom, "Bath", bath_outline).unwrap();
+        (store, project_id, level_id, living, bath, partition)
+    }
+
+    #[test]
+    fn test_auto_layout_spacing() {
+        let (mut store, project_id, level_id, living, bath, partition) = setup();
+        let summary = store.auto_layout_electrical(level_id).unwrap();
+
+        // Living: 64' of perimeter less the 3' door is one 61' run, six
+        // receptacles. Bath: 28' less the door, 25', three GFCI.
+        assert_eq!(summary.counts.receptacle, 6);
+        assert_eq!(summary.counts.gfci_receptacle, 3);
+        assert_eq!(summary.counts.switch, 2);
+        assert_eq!(summary.counts.light_fixture, 2);
+
+        // No point along the living room's wall line, doorway aside, is more
+        // than 6' from a receptacle
+        let receptacles: Vec<Point2> = store
+            .get_level_electrical_devices(level_id)
+            .into_iter()
+            .filter(|d| d.room_id == Some(living) && d.kind == ElectricalDeviceKind::Receptacle)
+            .map(|d| d.location)
+            .collect();
+        assert_eq!(receptacles.len(), 6);
+        let corners = [(0.0, 0.0), (20.0, 0.0), (20.0, 12.0), (0.0, 12.0), (0.0, 0.0)];
+        for edge in corners.windows(2) {
+            let ((x0, y0), (x1, y1)) = (edge[0], edge[1]);
+            for step in 0..=24 {
+                let t = step as f64 / 24.0;
+                let point = Point2::new(x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+                if point.x == 20.0 && point.y > 1.5 && point.y < 4.5 {
+                    continue;
+                }
+                let nearest = receptacles.iter().map(|r| r.distance_to(&point)).fold(f64::MAX, f64::min);
+                assert!(nearest <= RECEPTACLE_SPACING / 2.0 + 1e-9, "{:?} is {}' from a receptacle", point, nearest);
+            }
+        }
+
+        // Switches sit beside the door, in its wall
+        let switches: Vec<&ElectricalDevice> = store
+            .get_level_electrical_devices(level_id)
+            .into_iter()
+            .filter(|d| d.kind == ElectricalDeviceKind::Switch)
+            .collect();
+        assert!(switches.iter().all(|s| s.wall_id == Some(partition)));
+        assert!(switches.iter().any(|s| s.room_id == Some(bath)));
+
+        // Running again replaces the layout but keeps hand-placed devices
+        store
+            .add_electrical_device(level_id, ElectricalDeviceKind::Receptacle, Point2::new(10.0, 0.0), None)
+            .unwrap();
+        let again = store.auto_layout_electrical(level_id).unwrap();
+        assert_eq!(again.replaced, 13);
+        assert_eq!(store.get_level_electrical_devices(level_id).len(), 14);
+
+        // Undo takes back the second layout in one step
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_level_electrical_devices(level_id).len(), 14);
+        assert!(store.get_electrical_device(again.device_ids[0]).is_none());
+        assert!(store.get_electrical_device(summary.device_ids[0]).is_some());
+    }
+
+    #[test]
+    fn test_devices_and_schedule() {
+        let (mut store, project_id, level_id, living, _, partition) = setup();
+        assert!(store
+            .add_electrical_device(LevelId::new(), ElectricalDeviceKind::Switch, Point2::new(0.0, 0.0), None)
+            .is_err());
+
+        // A device on the partition's face is mounted in it and in the room
+        let switch = store
+            .add_electrical_device(level_id, ElectricalDeviceKind::Switch, Point2::new(19.9, 8.0), None)
+            .unwrap();
+        let device = store.get_electrical_device(switch).unwrap();
+        assert_eq!(device.wall_id, Some(partition));
+        assert_eq!(device.room_id, Some(living));
+        store
+            .add_electrical_device(level_id, ElectricalDeviceKind::LightFixture, Point2::new(40.0, 40.0), None)
+            .unwrap();
+
+        let schedule = store.device_schedule(project_id).unwrap();
+        assert_eq!(schedule.entries.len(), 2);
+        assert_eq!(schedule.entries[0].room_name.as_deref(), Some("Living"));
+        assert_eq!(schedule.entries[1].room_name, None);
+        assert_eq!(schedule.totals.total(), 2);
+        let csv = schedule.to_csv();
+        assert!(csv.starts_with("level,room,receptacle,gfci_receptacle,switch,light_fixture,total\n"));
+        assert!(csv.contains("First Floor,Living,0,0,1,0,1\n"));
+        assert!(csv.ends_with("Total,,0,0,1,1,2\n"));
+
+        // Devices go with their wall, and come back with it on undo
+        store.remove_wall(partition).unwrap();
+        assert!(store.get_electrical_device(switch).is_none());
+        store.undo(project_id).unwrap();
+        assert!(store.get_electrical_device(switch).is_some());
+
+        store.remove_electrical_device(switch).unwrap();
+        assert!(store.remove_electrical_device(switch).is_err());
+        store.remove_level(level_id).unwrap();
+        assert!(store.electrical_devices.is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+// Saved filter storage and evaluation
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Saved Filter Operations ==========
+
+    pub fn save_filter(
+        &mut self,
+        project_id: ProjectId,
+        name: impl Into<String>,
+        target: FilterTarget,
+        conditions: Vec<FilterCondition>,
+    ) -> Result<FilterId> {
+        let name = name.into();
+        if !self.projects.contains_key(&project_id) {
+            return Err(anyhow!("Project not found: {:?}", project_id));
+        }
+
+        if name.trim().is_empty() {
+            return Err(anyhow!("Filter name must not be empty"));
+        }
+
+        if self.find_filter(project_id, &name).is_some() {
+            return Err(anyhow!("Filter already exists: {}", name));
+        }
+
+        let filter = SavedFilter::new(project_id, name, target, conditions);
+        if let Some(condition) = filter.invalid_conditions().first() {
+            return Err(anyhow!("Condition {:?} does not apply to {:?}", condition, target));
+        }
+        let filter_id = filter.id;
+
+        self.record_event(project_id, EventKind::FilterSaved { filter: filter.clone() });
+        self.saved_filters.insert(filter_id, filter);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(filter_id)
+    }
+
+    pub fn get_filter(&self, id: FilterId) -> Option<&SavedFilter> {
+        self.saved_filters.get(&id)
+    }
+
+    /// Look up a project's filter by name (case-insensitive)
+    pub fn find_filter(&self, project_id: ProjectId, name: &str) -> Option<&SavedFilter> {
+        self.saved_filters
+            .values()
+            .find(|f| f.project_id == project_id && f.name.eq_ignore_ascii_case(name))
+    }
+
+    /// All filters saved in a project, sorted by name
+    pub fn get_project_filters(&self, project_id: ProjectId) -> Vec<&SavedFilter> {
+        let mut filters: Vec<&SavedFilter> = self.saved_filters
+            .values()
+            .filter(|f| f.project_id == project_id)
+            .collect();
+        filters.sort_by(|a, b| a.name.cmp(&b.name));
+        filters
+    }
+
+    pub fn remove_filter(&mut self, filter_id: FilterId) -> Result<()> {
+        let filter = self.saved_filters.remove(&filter_id)
+            .ok_or_else(|| anyhow!("Filter not found: {:?}", filter_id))?;
+
+        self.record_event(filter.project_id, EventKind::FilterRemoved { filter_id });
+
+        if let Some(project) = self.projects.get_mut(&filter.project_id) {
+            project.touch();
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate a project's filter by name
+    pub fn evaluate_named_filter(&self, project_id: ProjectId, name: &str) -> Result<FilterResult> {
+        let filter = self.find_filter(project_id, name)
+            .ok_or_else(|| anyhow!("Filter not found: {}", name))?;
+        Ok(self.evaluate(filter))
+    }
+
+    pub fn evaluate_filter(&self, filter_id: FilterId) -> Result<FilterResult> {
+        let filter = self.saved_filters.get(&filter_id)
+            .ok_or_else(|| anyhow!("Filter not found: {:?}", filter_id))?;
+        Ok(self.evaluate(filter))
+    }
+
+    /// Match entities of the filter's project, in building/level order
+    fn evaluate(&self, filter: &SavedFilter) -> FilterResult {
+        let mut result = FilterResult::default();
+
+        let levels: Vec<&Level> = self.projects
+            .get(&filter.project_id)
+            .map(|p| p.building_ids.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|id| self.get_building_levels(*id))
+            .collect();
+
+        for level in levels {
+            match filter.target {
+                FilterTarget::Walls => {
+                    result.walls.extend(
+                        level.wall_ids
+                            .iter()
+                            .filter_map(|id| self.walls.get(id))
+                            .filter(|w| filter.conditions.iter().all(|c| self.wall_matches(w, c)))
+                            .map(|w| w.id),
+                    );
+                }
+                FilterTarget::Rooms => {
+                    result.rooms.extend(
+                        level.room_ids
+                            .iter()
+                            .filter_map(|id| self.rooms.get(id))
+                            .filter(|r| filter.conditions.iter().all(|c| room_matches(r, c)))
+                            .map(|r| r.id),
+                    );
+                }
+                FilterTarget::Openings => {
+                    for wall_id in &level.wall_ids {
+                        let mut openings = self.get_wall_openings(*wall_id);
+                        openings.sort_by(|a, b| a.position_along_wall.total_cmp(&b.position_along_wall));
+                        result.openings.extend(
+                            openings
+                                .into_iter()
+                                .filter(|o| {
+                                    filter.conditions.iter().all(|c| opening_matches(o, level.id, c))
+                                })
+                                .map(|o| o.id),
+                        );
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    fn wall_matches(&self, wall: &Wall, condition: &FilterCondition) -> bool {
+        let assembly = self.wall_assemblies.get(&wall.assembly_id);
+        match condition {
+            FilterCondition::OnLevel { level_id } => wall.level_id == *level_id,
+            FilterCondition::LoadBearing { value } => wall.framing_config.is_load_bearing == *value,
+            FilterCondition::Insulated { value } => {
+                assembly.map(|a| a.is_insulated()).unwrap_or(false) == *value
+            }
+            FilterCondition::AssemblyName { name } => {
+                assembly.map(|a| a.name.eq_ignore_ascii_case(name)).unwrap_or(false)
+            }
+            FilterCondition::MinLength { value } => wall.length() >= *value,
+            FilterCondition::MaxLength { value } => wall.length() <= *value,
+            _ => false,
+        }
+    }
+}
+
+fn room_matches(room: &Room, condition: &FilterCondition) -> bool {
+    match condition {
+        FilterCondition::OnLevel { level_id } => room.level_id == *level_id,
+        FilterCondition::RoomTypeIs { room_type } => room.room_type == *room_type,
+        FilterCondition::MinArea { value } => room.area() >= *value,
+        FilterCondition::MaxArea { value } => room.area() <= *value,
+        FilterCondition::Conditioned { value } => room.is_conditioned() == Some(*value),
+        _ => false,
+    }
+}
+
+fn opening_matches(opening: &Opening, level_id: LevelId, condition: &FilterCondition) -> bool {
+    match condition {
+        FilterCondition::OnLevel { level_id: id } => level_id == *id,
+        FilterCondition::OpeningTypeIs { opening_type } => opening.opening_type == *opening_type,
+        FilterCondition::MinWidth { value } => opening.width >= *value,
+        FilterCondition::MaxWidth { value } => opening.width <= *value,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Store, ProjectId, LevelId, WallAssemblyId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior 2x6", WallAssembly::exterior_2x6().layers)
+            .unwrap();
+        (store, project_id, level_id, assembly_id)
+    }
+
+    #[test]
+    fn test_load_bearing_walls() {
+        let (mut store, project_id, level_id, assembly_id) = setup();
+        let bearing = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(30.0, 0.0), 9.0)
+            .unwrap();
+        let partition = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(0.0, 20.0), 9.0)
+            .unwrap();
+        store.walls.get_mut(&bearing).unwrap().framing_config.is_load_bearing = true;
+        store.walls.get_mut(&partition).unwrap().framing_config.is_load_bearing = false;
+
+        store
+            .save_filter(
+                project_id,
+                "Load-bearing walls",
+                FilterTarget::Walls,
+                vec![FilterCondition::LoadBearing { value: true }],
+            )
+            .unwrap();
+
+        let result = store.evaluate_named_filter(project_id, "load-bearing walls").unwrap();
+        assert_eq!(result.walls, vec![bearing]);
+        assert!(result.rooms.is_empty());
+    }
+
+    #[test]
+    fn test_small_rooms() {
+        let (mut store, project_id, level_id, _) = setup();
+        let closet = store
+            .create_room(level_id, RoomType::Closet, "Closet", Polygon2::rectangle(6.0, 5.0))
+            .unwrap();
+        store
+            .create_room(level_id, RoomType::LivingRoom, "Living", Polygon2::rectangle(20.0, 15.0))
+            .unwrap();
+
+        let filter_id = store
+            .save_filter(
+                project_id,
+                "Rooms under 100 sqft",
+                FilterTarget::Rooms,
+                vec![FilterCondition::MaxArea { value: 100.0 }],
+            )
+            .unwrap();
+
+        assert_eq!(store.evaluate_filter(filter_id).unwrap().rooms, vec![closet]);
+    }
+
+    #[test]
+    fn test_filter_validation() {
+        let (mut store, project_id, _, _) = setup();
+
+        // Wall condition on a room filter
+        let result = store.save_filter(
+            project_id,
+            "Bad",
+            FilterTarget::Rooms,
+            vec![FilterCondition::MinLength { value: 10.0 }],
+        );
+        assert!(result.is_err());
+
+        store.save_filter(project_id, "Windows", FilterTarget::Openings, vec![]).unwrap();
+        assert!(store.save_filter(project_id, "WINDOWS", FilterTarget::Openings, vec![]).is_err());
+        assert!(store.save_filter(ProjectId::new(), "Other", FilterTarget::Walls, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_filter_undo() {
+        let (mut store, project_id, _, _) = setup();
+        let filter_id = store.save_filter(project_id, "All walls", FilterTarget::Walls, vec![]).unwrap();
+
+        store.remove_filter(filter_id).unwrap();
+        assert!(store.get_project_filters(project_id).is_empty());
+
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_filter(filter_id).unwrap().name, "All walls");
+    }
+}
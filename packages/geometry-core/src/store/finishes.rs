@@ -0,0 +1,199 @@
+// Room finish selections and the finish schedule
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use crate::domain::finish::material_name;
+use super::Store;
+
+impl Store {
+    // ========== Finish Operations ==========
+
+    /// Select the finish material for one surface of a room. The material
+    /// must be one the surface allows.
+    pub fn set_room_finish(
+        &mut self,
+        room_id: RoomId,
+        surface: FinishSurface,
+        material: MaterialType,
+    ) -> Result<()> {
+        if !surface.allows(material) {
+            let allowed: Vec<&str> = surface.allowed_materials().iter().map(|m| m.display_name()).collect();
+            return Err(anyhow!(
+                "{} is not a {} finish (allowed: {})",
+                material.display_name(),
+                surface.as_str(),
+                allowed.join(", ")
+            ));
+        }
+        let room = self.rooms.get_mut(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+        room.set_finish(surface, material);
+        let level_id = room.level_id;
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::RoomFinishSet { room_id, surface, material });
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replace every finish selection of a room at once. Each material
+    /// must be one its surface allows.
+    pub fn set_room_finishes(&mut self, room_id: RoomId, finishes: RoomFinishes) -> Result<()> {
+        for surface in [FinishSurface::Floor, FinishSurface::Wall, FinishSurface::Ceiling, FinishSurface::Base] {
+            let material = finishes.get(surface);
+            if !surface.allows(material) {
+                return Err(anyhow!("{} is not a {} finish", material.display_name(), surface.as_str()));
+            }
+        }
+        let room = self.rooms.get_mut(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+        room.finishes = Some(finishes);
+        let level_id = room.level_id;
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::RoomFinishesSet { room_id, finishes });
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Change some of a room's finish selections by name, e.g.
+    /// `("floor", "carpet")` or `("trim", "premium")`, keeping the rest.
+    /// Recorded as one change.
+    pub fn update_room_finishes(&mut self, room_id: RoomId, selections: &[(&str, &str)]) -> Result<RoomFinishes> {
+        let room = self.rooms.get(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+        let mut finishes = room.finishes();
+        for &(key, value) in selections {
+            if matches!(key.to_lowercase().as_str(), "trim" | "trim_level") {
+                finishes.trim = TrimLevel::parse(value)
+                    .ok_or_else(|| anyhow!("Unknown trim level: {} (basic, standard or premium)", value))?;
+                continue;
+            }
+            let surface = FinishSurface::parse(key)
+                .ok_or_else(|| anyhow!("Unknown finish surface: {}", key))?;
+            let material = surface.parse_material(value).ok_or_else(|| {
+                let allowed: Vec<&str> = surface.allowed_materials().iter().map(|m| material_name(*m)).collect();
+                anyhow!("{} is not a {} finish (allowed: {})", value, surface.as_str(), allowed.join(", "))
+            })?;
+            finishes.set(surface, material);
+        }
+        self.set_room_finishes(room_id, finishes)?;
+        Ok(finishes)
+    }
+
+    /// Finishes and quantities for every room in a project, ordered by
+    /// level elevation then room name
+    pub fn finish_schedule(&self, project_id: ProjectId) -> Result<Vec<FinishScheduleEntry>> {
+        let project = self.projects.get(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+
+        let mut levels: Vec<&Level> = project.building_ids
+            .iter()
+            .flat_map(|id| self.get_building_levels(*id))
+            .collect();
+        levels.sort_by(|a, b| a.elevation.total_cmp(&b.elevation));
+
+        let mut entries = Vec::new();
+        for level in levels {
+            let mut rooms = self.get_level_rooms(level.id);
+            rooms.sort_by(|a, b| a.name.cmp(&b.name));
+
+            for room in rooms {
+                let height = room.ceiling_height.unwrap_or(level.floor_to_floor);
+                let floor_sqft = room.area();
+                let perimeter = room.perimeter();
+                entries.push(FinishScheduleEntry {
+                    room_id: room.id,
+                    level_id: level.id,
+                    room_name: room.name.clone(),
+                    room_type: room.room_type.clone(),
+                    finishes: room.finishes(),
+                    floor_sqft,
+                    wall_sqft: perimeter * height,
+                    ceiling_sqft: floor_sqft,
+                    base_lf: perimeter,
+                });
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finish_selection_and_schedule() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let bedroom = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+        store
+            .create_room(level_id, RoomType::Bathroom, "Bath", Polygon2::rectangle(8.0, 5.0))
+            .unwrap();
+
+        assert!(store.set_room_finish(bedroom, FinishSurface::Ceiling, MaterialType::Carpet).is_err());
+        store.set_room_finish(bedroom, FinishSurface::Floor, MaterialType::Carpet).unwrap();
+
+        let schedule = store.finish_schedule(project_id).unwrap();
+        let names: Vec<&str> = schedule.iter().map(|e| e.room_name.as_str()).collect();
+        assert_eq!(names, vec!["Bath", "Bedroom"]);
+        assert_eq!(schedule[0].finishes.floor, MaterialType::Tile);
+        assert_eq!(schedule[1].finishes.floor, MaterialType::Carpet);
+        assert_eq!(schedule[1].finishes.wall, MaterialType::Paint);
+        assert!((schedule[1].wall_sqft - 44.0 * 9.0).abs() < 1e-9);
+
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_room(bedroom).unwrap().finishes().floor, MaterialType::Hardwood);
+    }
+
+    #[test]
+    fn test_update_room_finishes() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let living = store
+            .create_room(level_id, RoomType::LivingRoom, "Living", Polygon2::rectangle(16.0, 14.0))
+            .unwrap();
+        assert_eq!(store.get_room(living).unwrap().finishes().trim, TrimLevel::Premium);
+
+        let finishes = store
+            .update_room_finishes(living, &[("floor", "lvp"), ("ceiling", "drywall"), ("trim", "basic")])
+            .unwrap();
+        assert_eq!(finishes.floor, MaterialType::LVP);
+        assert_eq!(finishes.wall, MaterialType::Paint);
+        assert_eq!(store.get_room(living).unwrap().finishes().trim, TrimLevel::Basic);
+
+        // A bad selection changes nothing
+        assert!(store.update_room_finishes(living, &[("floor", "tile"), ("wall", "carpet")]).is_err());
+        assert!(store.update_room_finishes(living, &[("trim", "gold")]).is_err());
+        assert_eq!(store.get_room(living).unwrap().finishes().floor, MaterialType::LVP);
+
+        // Replay restores it, and it's one undo step
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        assert_eq!(rebuilt.get_room(living).unwrap().finishes().trim, TrimLevel::Basic);
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_room(living).unwrap().finishes().floor, MaterialType::Hardwood);
+    }
+}
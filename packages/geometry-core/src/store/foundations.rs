@@ -0,0 +1,152 @@
+// Foundations under buildings
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Foundation Operations ==========
+
+    /// Put a foundation under a level, with the type's typical dimensions.
+    /// Only a building's lowest level can have one.
+    pub fn create_foundation(&mut self, level_id: LevelId, foundation_type: FoundationType) -> Result<FoundationId> {
+        let level = self.levels.get(&level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        let lowest = self.get_building_levels(level.building_id)
+            .into_iter()
+            .min_by(|a, b| a.elevation.total_cmp(&b.elevation))
+            .map(|l| l.id);
+        if lowest != Some(level_id) {
+            return Err(anyhow!("Only the lowest level of a building has a foundation"));
+        }
+        if self.get_level_foundation(level_id).is_some() {
+            return Err(anyhow!("Level already has a foundation: {:?}", level_id));
+        }
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+        let foundation = Foundation::new(level_id, foundation_type);
+        let foundation_id = foundation.id;
+
+        self.record_event(project_id, EventKind::FoundationCreated { foundation: foundation.clone() });
+        self.foundations.insert(foundation_id, foundation);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(foundation_id)
+    }
+
+    pub fn get_foundation(&self, id: FoundationId) -> Option<&Foundation> {
+        self.foundations.get(&id)
+    }
+
+    pub fn get_level_foundation(&self, level_id: LevelId) -> Option<&Foundation> {
+        self.foundations.values().find(|f| f.level_id == level_id)
+    }
+
+    /// Quantities for a foundation under its level's current footprint
+    pub fn foundation_quantities(&self, foundation_id: FoundationId) -> Option<FoundationQuantities> {
+        let foundation = self.foundations.get(&foundation_id)?;
+        let footprint = self.get_level_footprint(foundation.level_id)?;
+        Some(foundation.quantities(&footprint.polygon))
+    }
+
+    pub fn set_foundation_dimensions(
+        &mut self,
+        foundation_id: FoundationId,
+        dimensions: FoundationDimensions,
+    ) -> Result<()> {
+        let foundation = self.foundations.get_mut(&foundation_id)
+            .ok_or_else(|| anyhow!("Foundation not found: {:?}", foundation_id))?;
+        validate_dimensions(foundation.foundation_type, &dimensions)?;
+        foundation.dimensions = dimensions;
+        let level_id = foundation.level_id;
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::FoundationDimensionsSet { foundation_id, dimensions });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_foundation(&mut self, foundation_id: FoundationId) -> Result<()> {
+        let foundation = self.foundations.remove(&foundation_id)
+            .ok_or_else(|| anyhow!("Foundation not found: {:?}", foundation_id))?;
+
+        if let Some(project_id) = self.level_project_id(foundation.level_id) {
+            self.record_event(project_id, EventKind::FoundationRemoved { foundation_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_dimensions(foundation_type: FoundationType, d: &FoundationDimensions) -> Result<()> {
+    if d.footing_width <= 0.0 || d.footing_depth <= 0.0 {
+        return Err(anyhow!("Footing width and depth must be positive"));
+    }
+    let others = [d.stem_wall_height, d.stem_wall_thickness, d.slab_thickness, d.gravel_depth];
+    if others.iter().any(|v| *v < 0.0) {
+        return Err(anyhow!("Foundation dimensions cannot be negative"));
+    }
+    match foundation_type {
+        FoundationType::SlabOnGrade if d.slab_thickness <= 0.0 => {
+            Err(anyhow!("A slab on grade needs a slab thickness"))
+        }
+        FoundationType::Crawlspace | FoundationType::Basement
+            if d.stem_wall_height <= 0.0 || d.stem_wall_thickness <= 0.0 =>
+        {
+            Err(anyhow!("A {} foundation needs stem walls", foundation_type.as_str()))
+        }
+        _ if d.stem_wall_thickness > d.footing_width => {
+            Err(anyhow!("Stem walls cannot be wider than their footing"))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_foundation_crud() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let first = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let second = store.add_level(building_id, "Second Floor", 9.0, 9.0).unwrap();
+        store.set_level_footprint(first, Polygon2::rectangle(40.0, 30.0)).unwrap();
+
+        assert!(store.create_foundation(second, FoundationType::SlabOnGrade).is_err());
+        let foundation_id = store.create_foundation(first, FoundationType::Basement).unwrap();
+        assert!(store.create_foundation(first, FoundationType::SlabOnGrade).is_err());
+
+        let before = store.foundation_quantities(foundation_id).unwrap();
+        let mut dimensions = store.get_foundation(foundation_id).unwrap().dimensions;
+        dimensions.stem_wall_height = 9.0;
+        store.set_foundation_dimensions(foundation_id, dimensions).unwrap();
+        assert!(store.foundation_quantities(foundation_id).unwrap().stem_wall_cy > before.stem_wall_cy);
+
+        dimensions.stem_wall_height = 0.0;
+        assert!(store.set_foundation_dimensions(foundation_id, dimensions).is_err());
+
+        // Dimension edits replay through undo
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_foundation(foundation_id).unwrap().dimensions.stem_wall_height, 8.0);
+
+        store.remove_level(first).unwrap();
+        assert!(store.foundations.is_empty());
+    }
+}
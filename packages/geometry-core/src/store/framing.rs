@@ -0,0 +1,325 @@
+// Framing generation for stored walls and floors
+// The store keeps walls, openings, and footprints in feet while the framing
+// generators work in inches, so all are scaled before generating. Partitions
+// that tee into a wall come from the level's resolved joins.
+//
+// Wall edits don't regenerate framing straight away. Adding or removing an
+// opening, or a change in how a wall meets its neighbours, marks the wall's
+// layout stale; it is regenerated when next asked for through
+// `refresh_wall_framing` or `regenerate_stale_framing`.
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use crate::framing::{CutList, FloorFramingGenerator, FramingGenerator, LevelCutList, PartitionJunction, WallCutList};
+use super::Store;
+
+const INCHES_PER_FOOT: f64 = 12.0;
+
+impl Store {
+    // ========== Framing Generation ==========
+
+    /// Generate framing for a wall, replacing any layout it already has
+    pub fn generate_wall_framing(&mut self, wall_id: WallId) -> Result<FramingLayoutId> {
+        let wall = self.walls.get(&wall_id)
+            .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
+        let assembly = self.wall_assemblies.get(&wall.assembly_id)
+            .ok_or_else(|| anyhow!("Wall assembly not found: {:?}", wall.assembly_id))?;
+
+        let openings: Vec<Opening> = self
+            .get_wall_openings(wall_id)
+            .into_iter()
+            .map(|opening| Opening {
+                width: opening.width * INCHES_PER_FOOT,
+                height: opening.height * INCHES_PER_FOOT,
+                sill_height: opening.sill_height * INCHES_PER_FOOT,
+                rough_size: opening.rough_size.map(|(w, h)| (w * INCHES_PER_FOOT, h * INCHES_PER_FOOT)),
+                ..opening.clone()
+            })
+            .collect();
+        let layout = FramingGenerator::generate_wall_framing_with_partitions(
+            &wall_in_inches(wall),
+            assembly,
+            &openings,
+            &self.partition_junctions(wall),
+        )?;
+
+        if let Some(old) = wall.framing_layout_id {
+            self.remove_framing_layout(old)?;
+        }
+        self.regeneration.mark_clean(wall_id);
+        self.store_framing_layout(layout)
+    }
+
+    /// Mark a wall's framing stale. Walls that haven't been framed have
+    /// nothing to go stale and are ignored.
+    pub fn invalidate_wall_framing(&mut self, wall_id: WallId) {
+        if self.walls.get(&wall_id).is_some_and(|w| w.framing_layout_id.is_some()) {
+            self.regeneration.invalidate_wall(wall_id);
+        }
+    }
+
+    /// Whether a wall's framing layout is out of date with the wall
+    pub fn is_framing_stale(&self, wall_id: WallId) -> bool {
+        self.regeneration.is_wall_dirty(wall_id)
+    }
+
+    /// The wall's framing layout, regenerated first if it is stale
+    pub fn refresh_wall_framing(&mut self, wall_id: WallId) -> Result<Option<FramingLayoutId>> {
+        if self.is_framing_stale(wall_id) {
+            return self.generate_wall_framing(wall_id).map(Some);
+        }
+        Ok(self.walls.get(&wall_id).and_then(|w| w.framing_layout_id))
+    }
+
+    /// Regenerate every stale framing layout, returning the walls redone
+    pub fn regenerate_stale_framing(&mut self) -> Result<Vec<WallId>> {
+        let mut wall_ids: Vec<WallId> = self.regeneration.get_dirty_walls().iter().copied().collect();
+        wall_ids.sort_by_key(|id| id.to_string());
+        for &wall_id in &wall_ids {
+            self.generate_wall_framing(wall_id)?;
+        }
+        Ok(wall_ids)
+    }
+
+    /// Partitions on the same level whose ends tee into the side of a wall
+    fn partition_junctions(&self, wall: &Wall) -> Vec<PartitionJunction> {
+        let tee = WallJoin::Tee { wall: wall.id };
+        self.get_level_walls(wall.level_id)
+            .into_iter()
+            .flat_map(|other| {
+                [(other.start_join, other.start), (other.end_join, other.end)]
+                    .into_iter()
+                    .filter(|(join, _)| *join == tee)
+                    .map(|(_, point)| PartitionJunction {
+                        position: wall.project(&point).0 * INCHES_PER_FOOT,
+                        thickness: other.framing_config.lumber_size.actual_dimensions().1,
+                    })
+            })
+            .collect()
+    }
+
+    /// Stock lumber to buy for a wall's framing
+    pub fn wall_cut_list(&self, wall_id: WallId) -> Result<CutList> {
+        let layout = self.get_wall_framing_layout(wall_id)
+            .ok_or_else(|| anyhow!("Wall has no framing layout: {:?}", wall_id))?;
+        Ok(CutList::from_layouts([layout]))
+    }
+
+    /// Stock lumber to buy for the framed walls on a level, per wall and
+    /// for the level as a whole. The level total also covers its beams and
+    /// posts.
+    pub fn level_cut_list(&self, level_id: LevelId) -> Result<LevelCutList> {
+        if !self.levels.contains_key(&level_id) {
+            return Err(anyhow!("Level not found: {:?}", level_id));
+        }
+        let mut layouts: Vec<(WallId, &FramingLayout)> = self
+            .get_level_walls(level_id)
+            .into_iter()
+            .filter_map(|wall| self.get_wall_framing_layout(wall.id).map(|layout| (wall.id, layout)))
+            .collect();
+        layouts.sort_by_key(|(wall_id, _)| wall_id.to_string());
+
+        Ok(LevelCutList {
+            level_id,
+            walls: layouts
+                .iter()
+                .map(|&(wall_id, layout)| WallCutList { wall_id, cut_list: CutList::from_layouts([layout]) })
+                .collect(),
+            total: CutList::from_members(
+                layouts.iter().map(|(_, layout)| *layout),
+                &self.get_level_beams(level_id),
+                &self.get_level_posts(level_id),
+            ),
+        })
+    }
+
+    /// Frame the level's floor over its footprint, replacing any floor
+    /// framing it already has
+    pub fn generate_floor_framing(
+        &mut self,
+        level_id: LevelId,
+        config: FloorFramingConfig,
+    ) -> Result<FloorFramingLayoutId> {
+        if !self.levels.contains_key(&level_id) {
+            return Err(anyhow!("Level not found: {:?}", level_id));
+        }
+        let footprint = self.get_level_footprint(level_id)
+            .ok_or_else(|| anyhow!("Level has no footprint: {:?}", level_id))?;
+
+        let scale = |ring: &Vec<Point2>| -> Vec<Point2> {
+            ring.iter().map(|p| Point2::new(p.x * INCHES_PER_FOOT, p.y * INCHES_PER_FOOT)).collect()
+        };
+        let polygon = Polygon2::with_holes(
+            scale(&footprint.polygon.outer),
+            footprint.polygon.holes.iter().map(scale).collect(),
+        );
+        let layout = FloorFramingGenerator::generate(level_id, &polygon, &config)?;
+
+        let layout_id = layout.id;
+        self.floor_framing_layouts.insert(level_id, layout);
+        Ok(layout_id)
+    }
+
+    /// Get the floor framing for a level
+    pub fn get_floor_framing_layout(&self, level_id: LevelId) -> Option<&FloorFramingLayout> {
+        self.floor_framing_layouts.get(&level_id)
+    }
+
+    /// Remove a level's floor framing
+    pub fn remove_floor_framing_layout(&mut self, level_id: LevelId) -> Result<()> {
+        self.floor_framing_layouts.remove(&level_id)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("Floor framing not found for level: {:?}", level_id))
+    }
+}
+
+/// A copy of a wall with its geometry in inches
+fn wall_in_inches(wall: &Wall) -> Wall {
+    let scale = |p: Point2| Point2::new(p.x * INCHES_PER_FOOT, p.y * INCHES_PER_FOOT);
+    Wall {
+        start: scale(wall.start),
+        end: scale(wall.end),
+        height: wall.height * INCHES_PER_FOOT,
+        base_offset: wall.base_offset * INCHES_PER_FOOT,
+        arc: wall.arc.map(|arc| WallArc { radius: arc.radius * INCHES_PER_FOOT }),
+        ..wall.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::costing::CostInput;
+
+    #[test]
+    fn test_generate_wall_framing() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+
+        // L corner with a partition teeing into the middle of the first wall
+        let a = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let b = store
+            .create_wall(level_id, assembly_id, Point2::new(20.0, 0.0), Point2::new(20.0, 12.0), 9.0)
+            .unwrap();
+        store
+            .create_wall(level_id, assembly_id, Point2::new(10.0, 0.0), Point2::new(10.0, 12.0), 9.0)
+            .unwrap();
+        store
+            .add_opening(a, OpeningType::Window, 0.25, 3.0, 4.0, 3.0)
+            .unwrap();
+
+        let layout_id = store.generate_wall_framing(a).unwrap();
+        let layout = store.get_framing_layout(layout_id).unwrap();
+
+        // Framed in inches: a 20' plate is 240" long
+        let plate = &layout.members_of_type(FramingMemberType::BottomPlate)[0];
+        assert!((plate.length - 240.0).abs() < 1e-9);
+        assert_eq!(layout.members_of_type(FramingMemberType::Header).len(), 1);
+        assert_eq!(layout.members_of_type(FramingMemberType::PartitionBacking).len(), 2);
+        let corner_studs = layout.members_of_type(FramingMemberType::CornerStud).len();
+
+        // Only one of the two corner walls carries the corner studs
+        store.generate_wall_framing(b).unwrap();
+        let other = store.get_wall_framing_layout(b).unwrap();
+        assert_eq!(corner_studs + other.members_of_type(FramingMemberType::CornerStud).len(), 2);
+
+        // Regenerating replaces the old layout
+        let regenerated = store.generate_wall_framing(a).unwrap();
+        assert!(store.get_framing_layout(layout_id).is_none());
+        assert_eq!(store.get_wall(a).unwrap().framing_layout_id, Some(regenerated));
+
+        // The window's header goes into the cost takeoff
+        let input = CostInput::from_level(&store, level_id).unwrap();
+        assert_eq!(input.headers.len(), 1);
+        assert_eq!(input.headers[0].count, 1);
+
+        // The level's list cuts the same pieces as its walls' lists
+        let cut_list = store.level_cut_list(level_id).unwrap();
+        assert_eq!(cut_list.walls.len(), 2);
+        let separate: f64 = cut_list.walls.iter().map(|w| w.cut_list.cut_length).sum();
+        assert!((cut_list.total.cut_length - separate).abs() < 1e-6);
+        let own = store.wall_cut_list(a).unwrap();
+        assert!(own.boards.iter().all(|b| b.offcut() >= 0.0));
+        assert!(own.orders.iter().any(|o| o.lumber_size == LumberSize::TwoBySix));
+    }
+
+    #[test]
+    fn test_framing_goes_stale_on_edits() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+
+        let a = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        // Unframed walls never go stale
+        store.add_opening(a, OpeningType::Window, 0.25, 3.0, 4.0, 3.0).unwrap();
+        assert!(!store.is_framing_stale(a));
+
+        store.generate_wall_framing(a).unwrap();
+        assert!(!store.is_framing_stale(a));
+
+        // A new opening leaves the layout with one header until refreshed
+        let door = store.add_opening(a, OpeningType::Door, 0.75, 3.0, 6.67, 0.0).unwrap();
+        assert!(store.is_framing_stale(a));
+        assert_eq!(store.get_wall_framing_layout(a).unwrap().headers.len(), 1);
+        store.refresh_wall_framing(a).unwrap();
+        assert!(!store.is_framing_stale(a));
+        assert_eq!(store.get_wall_framing_layout(a).unwrap().headers.len(), 2);
+
+        // A partition teeing in needs backing behind it
+        let partition = store
+            .create_wall(level_id, assembly_id, Point2::new(10.0, 0.0), Point2::new(10.0, 12.0), 9.0)
+            .unwrap();
+        store.remove_opening(door).unwrap();
+        assert_eq!(store.regenerate_stale_framing().unwrap(), vec![a]);
+        let layout = store.get_wall_framing_layout(a).unwrap();
+        assert_eq!(layout.headers.len(), 1);
+        assert_eq!(layout.members_of_type(FramingMemberType::PartitionBacking).len(), 2);
+
+        // Removing it again stales the wall it teed into
+        store.remove_wall(partition).unwrap();
+        assert!(store.is_framing_stale(a));
+    }
+
+    #[test]
+    fn test_generate_floor_framing() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+
+        // No footprint yet
+        assert!(store.generate_floor_framing(level_id, FloorFramingConfig::default()).is_err());
+
+        store.set_level_footprint(level_id, Polygon2::rectangle(20.0, 12.0)).unwrap();
+        let layout_id = store.generate_floor_framing(level_id, FloorFramingConfig::default()).unwrap();
+        let layout = store.get_floor_framing_layout(level_id).unwrap();
+        assert_eq!(layout.id, layout_id);
+        assert_eq!(layout.joist_count, 8);
+
+        // Regenerating replaces the level's layout, and it goes with the level
+        let regenerated = store.generate_floor_framing(level_id, FloorFramingConfig::default()).unwrap();
+        assert_eq!(store.get_floor_framing_layout(level_id).unwrap().id, regenerated);
+        store.remove_level(level_id).unwrap();
+        assert!(store.get_floor_framing_layout(level_id).is_none());
+    }
+}
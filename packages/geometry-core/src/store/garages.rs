@@ -0,0 +1,234 @@
+// Garage rooms: slab settings, fire separation and vehicle doors
+
+use anyhow::{anyhow, Result};
+
+use crate::analysis::envelope::{collinear_overlap, polygon_edges};
+use crate::domain::*;
+use super::Store;
+
+/// Thinnest slab accepted for a garage (inches)
+const MIN_GARAGE_SLAB: f64 = 3.5;
+
+impl Store {
+    // ========== Garage Operations ==========
+
+    /// Set up a garage room: give it slab settings (kept if it already has
+    /// them) and put the fire separation assembly on the walls it shares
+    /// with the dwelling
+    pub fn configure_garage(&mut self, room_id: RoomId) -> Result<GarageId> {
+        let room = self.rooms.get(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+        if room.room_type != RoomType::Garage {
+            return Err(anyhow!("Room is not a garage: {}", room.name));
+        }
+        let level_id = room.level_id;
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+        let garage_id = match self.get_room_garage(room_id) {
+            Some(garage) => garage.id,
+            None => {
+                let garage = Garage::new(level_id, room_id);
+                let garage_id = garage.id;
+                self.record_event(project_id, EventKind::GarageConfigured { garage: garage.clone() });
+                self.garages.insert(garage_id, garage);
+                garage_id
+            }
+        };
+
+        let mut separations = Vec::new();
+        for wall in self.garage_walls(room_id) {
+            let protected = self.get_wall_assembly(wall.assembly_id)
+                .is_some_and(|a| a.separates_garage());
+            if !protected
+                && self.wall_assembly_role(level_id, wall.start, wall.end)? == AssemblyRole::FireSeparation
+            {
+                separations.push(wall.id);
+            }
+        }
+        if !separations.is_empty() {
+            let assembly_id = self.assembly_for_role(project_id, AssemblyRole::FireSeparation)?;
+            for wall_id in separations {
+                self.set_wall_assembly(wall_id, assembly_id)?;
+            }
+        }
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(garage_id)
+    }
+
+    pub fn get_garage(&self, id: GarageId) -> Option<&Garage> {
+        self.garages.get(&id)
+    }
+
+    /// Slab settings for a garage room, if it has been configured
+    pub fn get_room_garage(&self, room_id: RoomId) -> Option<&Garage> {
+        self.garages.values().find(|g| g.room_id == room_id)
+    }
+
+    pub fn get_level_garages(&self, level_id: LevelId) -> Vec<&Garage> {
+        let mut garages: Vec<&Garage> = self.garages
+            .values()
+            .filter(|g| g.level_id == level_id)
+            .collect();
+        garages.sort_by_key(|g| g.id.to_string());
+        garages
+    }
+
+    pub fn remove_garage(&mut self, garage_id: GarageId) -> Result<()> {
+        let garage = self.garages.remove(&garage_id)
+            .ok_or_else(|| anyhow!("Garage not found: {:?}", garage_id))?;
+
+        if let Some(project_id) = self.level_project_id(garage.level_id) {
+            self.record_event(project_id, EventKind::GarageRemoved { garage_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set the slab thickness (inches) and its fall toward the vehicle door
+    /// (inches per foot)
+    pub fn set_garage_slab(&mut self, garage_id: GarageId, slab_thickness: f64, slab_slope: f64) -> Result<()> {
+        if !slab_thickness.is_finite() || slab_thickness < MIN_GARAGE_SLAB {
+            return Err(anyhow!("Garage slab must be at least {}\" thick", MIN_GARAGE_SLAB));
+        }
+        if !(0.0..=1.0).contains(&slab_slope) {
+            return Err(anyhow!("Garage slab slope must be between 0 and 1 in/ft"));
+        }
+        let garage = self.garages.get_mut(&garage_id)
+            .ok_or_else(|| anyhow!("Garage not found: {:?}", garage_id))?;
+        garage.slab_thickness = slab_thickness;
+        garage.slab_slope = slab_slope;
+        let level_id = garage.level_id;
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::GarageSlabSet { garage_id, slab_thickness, slab_slope });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Walls running along a room's boundary
+    fn garage_walls(&self, room_id: RoomId) -> Vec<&Wall> {
+        let Some(room) = self.rooms.get(&room_id) else {
+            return Vec::new();
+        };
+        let edges = polygon_edges(&room.boundary);
+        let mut walls: Vec<&Wall> = self.get_level_walls(room.level_id)
+            .into_iter()
+            .filter(|w| edges.iter().any(|(a, b)| collinear_overlap(&w.start, &w.end, a, b).is_some()))
+            .collect();
+        walls.sort_by_key(|w| w.id.to_string());
+        walls
+    }
+
+    /// Vehicle doors in the walls around a garage room
+    pub fn garage_vehicle_doors(&self, room_id: RoomId) -> Vec<&Opening> {
+        let mut doors: Vec<&Opening> = self.garage_walls(room_id)
+            .into_iter()
+            .flat_map(|w| self.get_wall_openings(w.id))
+            .filter(|o| o.opening_type.is_garage_door())
+            .collect();
+        doors.sort_by_key(|o| o.id.to_string());
+        doors
+    }
+
+    /// Slab quantities for a garage, draining from the far side of the room
+    /// to its first vehicle door
+    pub fn garage_slab(&self, garage_id: GarageId) -> Result<GarageSlab> {
+        let garage = self.garages.get(&garage_id)
+            .ok_or_else(|| anyhow!("Garage not found: {:?}", garage_id))?;
+        let room = self.rooms.get(&garage.room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", garage.room_id))?;
+
+        let door_wall = self.garage_vehicle_doors(room.id)
+            .first()
+            .and_then(|o| self.walls.get(&o.wall_id));
+        let run = match door_wall {
+            Some(wall) => {
+                let length = wall.start.distance_to(&wall.end);
+                let (dx, dy) = (wall.end.x - wall.start.x, wall.end.y - wall.start.y);
+                room.boundary.outer
+                    .iter()
+                    .map(|p| ((p.x - wall.start.x) * dy - (p.y - wall.start.y) * dx).abs() / length)
+                    .fold(0.0, f64::max)
+            }
+            None => 0.0,
+        };
+
+        Ok(garage.slab(&room.boundary, run))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configure_garage() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "House").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(44.0, 22.0)).unwrap();
+
+        let garage_room = store.create_room(level_id, RoomType::Garage, "Garage", Polygon2::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(22.0, 0.0),
+            Point2::new(22.0, 22.0),
+            Point2::new(0.0, 22.0),
+        ])).unwrap();
+        store.create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::new(vec![
+            Point2::new(22.0, 0.0),
+            Point2::new(44.0, 0.0),
+            Point2::new(44.0, 22.0),
+            Point2::new(22.0, 22.0),
+        ])).unwrap();
+
+        let interior = store.create_wall_assembly("2x4 Interior", vec![]).unwrap();
+        let front = store.create_wall(level_id, interior, Point2::new(0.0, 0.0), Point2::new(22.0, 0.0), 9.0).unwrap();
+        let shared = store.create_wall(level_id, interior, Point2::new(22.0, 0.0), Point2::new(22.0, 22.0), 9.0).unwrap();
+        store.add_opening(front, OpeningType::Other("garage door".to_string()), 0.5, 16.0, 7.0, 0.0).unwrap();
+
+        let synced = store.project_revision(project_id).unwrap();
+        let garage_id = store.configure_garage(garage_room).unwrap();
+        assert_eq!(store.configure_garage(garage_room).unwrap(), garage_id);
+        let shared_assembly = store.get_wall(shared).unwrap().assembly_id;
+        assert!(store.get_wall_assembly(shared_assembly).unwrap().separates_garage());
+        assert_eq!(store.get_wall(front).unwrap().assembly_id, interior);
+
+        // The reassignment replays even though the replayed store lacks
+        // the fire separation assembly
+        let changes = store.changes_since(project_id, synced).unwrap();
+        assert!(changes.walls.modified.contains(&shared));
+        assert_eq!(changes.garages.created, vec![garage_id]);
+
+        // Drains 22' from the back wall to the door at 1/8" per foot
+        let slab = store.garage_slab(garage_id).unwrap();
+        assert!((slab.run - 22.0).abs() < 1e-9);
+        assert!((slab.fall - 2.75).abs() < 1e-9);
+
+        assert!(store.set_garage_slab(garage_id, 2.0, 0.125).is_err());
+        store.set_garage_slab(garage_id, 6.0, 0.25).unwrap();
+        assert!((store.garage_slab(garage_id).unwrap().fall - 5.5).abs() < 1e-9);
+
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_garage(garage_id).unwrap().slab_thickness, 5.0);
+
+        // Retyping the room drops its garage settings
+        store.update_room_meta(garage_room, "Workshop", RoomType::Utility).unwrap();
+        assert!(store.get_garage(garage_id).is_none());
+        assert!(store.configure_garage(garage_room).is_err());
+    }
+}
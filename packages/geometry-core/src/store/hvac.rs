@@ -0,0 +1,150 @@
+// HVAC zones and the rooms assigned to them
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== HVAC Zone Operations ==========
+
+    pub fn create_hvac_zone(&mut self, building_id: BuildingId, name: &str) -> Result<HVACZoneId> {
+        let building = self.buildings.get(&building_id)
+            .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Zone name cannot be empty"));
+        }
+        let project_id = building.project_id;
+
+        let zone = HvacZone::new(building_id, name);
+        let zone_id = zone.id;
+
+        self.record_event(project_id, EventKind::HvacZoneCreated { zone: zone.clone() });
+        self.hvac_zones.insert(zone_id, zone);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(zone_id)
+    }
+
+    pub fn get_hvac_zone(&self, id: HVACZoneId) -> Option<&HvacZone> {
+        self.hvac_zones.get(&id)
+    }
+
+    /// Zones in a building, by name
+    pub fn get_building_hvac_zones(&self, building_id: BuildingId) -> Vec<&HvacZone> {
+        let mut zones: Vec<&HvacZone> = self.hvac_zones
+            .values()
+            .filter(|z| z.building_id == building_id)
+            .collect();
+        zones.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.to_string().cmp(&b.id.to_string())));
+        zones
+    }
+
+    /// Zone a room is assigned to
+    pub fn get_room_hvac_zone(&self, room_id: RoomId) -> Option<&HvacZone> {
+        self.hvac_zones.values().find(|z| z.room_ids.contains(&room_id))
+    }
+
+    /// Remove a zone; its rooms become unzoned
+    pub fn remove_hvac_zone(&mut self, zone_id: HVACZoneId) -> Result<()> {
+        let zone = self.hvac_zones.remove(&zone_id)
+            .ok_or_else(|| anyhow!("HVAC zone not found: {:?}", zone_id))?;
+
+        if let Some(project_id) = self.buildings.get(&zone.building_id).map(|b| b.project_id) {
+            self.record_event(project_id, EventKind::HvacZoneRemoved { zone_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Put a room in a zone of its own building, taking it out of any other
+    /// zone. `None` leaves the room unzoned.
+    pub fn assign_room_to_zone(&mut self, room_id: RoomId, zone_id: Option<HVACZoneId>) -> Result<()> {
+        let room = self.rooms.get(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+        let building_id = self.levels.get(&room.level_id)
+            .map(|l| l.building_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", room.level_id))?;
+        if let Some(zone_id) = zone_id {
+            let zone = self.hvac_zones.get(&zone_id)
+                .ok_or_else(|| anyhow!("HVAC zone not found: {:?}", zone_id))?;
+            if zone.building_id != building_id {
+                return Err(anyhow!("Room {} is not in the zone's building", room.name));
+            }
+        }
+        let project_id = self.buildings.get(&building_id)
+            .map(|b| b.project_id)
+            .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+
+        self.set_room_zone(room_id, zone_id);
+        self.record_event(project_id, EventKind::HvacZoneRoomAssigned { room_id, zone_id });
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn set_room_zone(&mut self, room_id: RoomId, zone_id: Option<HVACZoneId>) {
+        for zone in self.hvac_zones.values_mut() {
+            if Some(zone.id) == zone_id {
+                if !zone.room_ids.contains(&room_id) {
+                    zone.room_ids.push(room_id);
+                }
+            } else {
+                zone.room_ids.retain(|id| *id != room_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_assignment() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let other = store.add_building(project_id, "Shop").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let room = store
+            .create_room(level_id, RoomType::Bedroom, "Bed", Polygon2::rectangle(12.0, 12.0))
+            .unwrap();
+
+        let up = store.create_hvac_zone(building_id, "Upstairs").unwrap();
+        let down = store.create_hvac_zone(building_id, "Downstairs").unwrap();
+        let shop = store.create_hvac_zone(other, "Shop").unwrap();
+        assert!(store.create_hvac_zone(building_id, " ").is_err());
+        assert_eq!(store.get_building_hvac_zones(building_id)[0].id, down);
+
+        store.assign_room_to_zone(room, Some(up)).unwrap();
+        store.assign_room_to_zone(room, Some(down)).unwrap();
+        assert!(store.get_hvac_zone(up).unwrap().room_ids.is_empty());
+        assert_eq!(store.get_room_hvac_zone(room).unwrap().id, down);
+        assert!(store.assign_room_to_zone(room, Some(shop)).is_err());
+
+        // Undo puts the room back in the first zone
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_room_hvac_zone(room).unwrap().id, up);
+
+        // Rooms leave their zone when removed
+        store.remove_room(room).unwrap();
+        assert!(store.get_hvac_zone(up).unwrap().room_ids.is_empty());
+
+        store.remove_building(building_id).unwrap();
+        assert!(store.get_hvac_zone(down).is_none());
+        assert!(store.get_hvac_zone(shop).is_some());
+    }
+}
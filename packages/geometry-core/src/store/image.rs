@@ -0,0 +1,219 @@
+// Store images - the whole store as bytes
+// Moves a store between the main thread and a worker, into IndexedDB or up
+// to a server with every entity exactly as it is, including what lives
+// outside the event logs (framing settings, finishes, undo history). The
+// body is MessagePack rather than bincode or postcard: events and several
+// domain enums are internally tagged, which those formats can't read back.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::*;
+use super::{PruningPolicy, Snapshot, Store};
+
+const IMAGE_MAGIC: &[u8; 4] = b"GSTO";
+const IMAGE_VERSION: u32 = 1;
+/// Magic, then version
+const IMAGE_HEADER: usize = 8;
+
+/// Everything a store holds except live subscribers and open transactions
+#[derive(Serialize, Deserialize)]
+struct StoreImage<'a> {
+    projects: Cow<'a, HashMap<ProjectId, Project>>,
+    sites: Cow<'a, HashMap<SiteId, Site>>,
+    buildings: Cow<'a, HashMap<BuildingId, Building>>,
+    levels: Cow<'a, HashMap<LevelId, Level>>,
+    footprints: Cow<'a, HashMap<FootprintId, Footprint>>,
+    grids: Cow<'a, HashMap<BuildingId, Grid>>,
+    wall_assemblies: Cow<'a, HashMap<WallAssemblyId, WallAssembly>>,
+    walls: Cow<'a, HashMap<WallId, Wall>>,
+    rooms: Cow<'a, HashMap<RoomId, Room>>,
+    openings: Cow<'a, HashMap<OpeningId, Opening>>,
+    opening_catalog: Cow<'a, OpeningCatalog>,
+    framing_layouts: Cow<'a, HashMap<FramingLayoutId, FramingLayout>>,
+    floor_framing_layouts: Cow<'a, HashMap<LevelId, FloorFramingLayout>>,
+    saved_filters: Cow<'a, HashMap<FilterId, SavedFilter>>,
+    saved_scripts: Cow<'a, HashMap<ScriptId, SavedScript>>,
+    constraints: Cow<'a, HashMap<ConstraintId, Constraint>>,
+    detail_callouts: Cow<'a, HashMap<DetailCalloutId, DetailCallout>>,
+    roofs: Cow<'a, HashMap<RoofId, Roof>>,
+    stairs: Cow<'a, HashMap<StairId, Stair>>,
+    floor_slabs: Cow<'a, HashMap<FloorSlabId, FloorSlab>>,
+    ceilings: Cow<'a, HashMap<CeilingAssemblyId, CeilingAssembly>>,
+    foundations: Cow<'a, HashMap<FoundationId, Foundation>>,
+    beams: Cow<'a, HashMap<BeamId, Beam>>,
+    posts: Cow<'a, HashMap<PostId, Post>>,
+    electrical_devices: Cow<'a, HashMap<DeviceId, ElectricalDevice>>,
+    plumbing_fixtures: Cow<'a, HashMap<FixtureId, PlumbingFixture>>,
+    hvac_zones: Cow<'a, HashMap<HVACZoneId, HvacZone>>,
+    decks: Cow<'a, HashMap<DeckId, Deck>>,
+    garages: Cow<'a, HashMap<GarageId, Garage>>,
+    event_logs: Cow<'a, HashMap<ProjectId, EventLog>>,
+    snapshots: Cow<'a, HashMap<SnapshotId, Snapshot>>,
+    last_compaction: Cow<'a, HashMap<ProjectId, EventId>>,
+    pruning_policy: PruningPolicy,
+    /// Walls whose framing layouts no longer match their wall
+    stale_framing: Vec<WallId>,
+}
+
+impl Store {
+    // ========== Binary Images ==========
+
+    /// Write the whole store as bytes: "GSTO", a little-endian u32 version,
+    /// then the entities and event logs as MessagePack. Fails while a
+    /// transaction is open, since its events haven't been released yet.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        if self.held_events.is_some() {
+            return Err(anyhow!("Can't write the store while a transaction is open"));
+        }
+        let image = StoreImage {
+            projects: Cow::Borrowed(&self.projects),
+            sites: Cow::Borrowed(&self.sites),
+            buildings: Cow::Borrowed(&self.buildings),
+            levels: Cow::Borrowed(&self.levels),
+            footprints: Cow::Borrowed(&self.footprints),
+            grids: Cow::Borrowed(&self.grids),
+            wall_assemblies: Cow::Borrowed(&self.wall_assemblies),
+            walls: Cow::Borrowed(&self.walls),
+            rooms: Cow::Borrowed(&self.rooms),
+            openings: Cow::Borrowed(&self.openings),
+            opening_catalog: Cow::Borrowed(&self.opening_catalog),
+            framing_layouts: Cow::Borrowed(&self.framing_layouts),
+            floor_framing_layouts: Cow::Borrowed(&self.floor_framing_layouts),
+            saved_filters: Cow::Borrowed(&self.saved_filters),
+            saved_scripts: Cow::Borrowed(&self.saved_scripts),
+            constraints: Cow::Borrowed(&self.constraints),
+            detail_callouts: Cow::Borrowed(&self.detail_callouts),
+            roofs: Cow::Borrowed(&self.roofs),
+            stairs: Cow::Borrowed(&self.stairs),
+            floor_slabs: Cow::Borrowed(&self.floor_slabs),
+            ceilings: Cow::Borrowed(&self.ceilings),
+            foundations: Cow::Borrowed(&self.foundations),
+            beams: Cow::Borrowed(&self.beams),
+            posts: Cow::Borrowed(&self.posts),
+            electrical_devices: Cow::Borrowed(&self.electrical_devices),
+            plumbing_fixtures: Cow::Borrowed(&self.plumbing_fixtures),
+            hvac_zones: Cow::Borrowed(&self.hvac_zones),
+            decks: Cow::Borrowed(&self.decks),
+            garages: Cow::Borrowed(&self.garages),
+            event_logs: Cow::Borrowed(&self.event_logs),
+            snapshots: Cow::Borrowed(&self.snapshots),
+            last_compaction: Cow::Borrowed(&self.last_compaction),
+            pruning_policy: self.pruning_policy,
+            stale_framing: self.regeneration.get_dirty_walls().iter().copied().collect(),
+        };
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(IMAGE_MAGIC);
+        bytes.extend(IMAGE_VERSION.to_le_bytes());
+        rmp_serde::encode::write_named(&mut bytes, &image)?;
+        Ok(bytes)
+    }
+
+    /// Read a store written by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Store> {
+        if bytes.len() < IMAGE_HEADER || &bytes[..4] != IMAGE_MAGIC {
+            return Err(anyhow!("Not a store image"));
+        }
+        let version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        if version != IMAGE_VERSION {
+            return Err(anyhow!("Unsupported store image version: {}", version));
+        }
+        let image: StoreImage = rmp_serde::from_slice(&bytes[IMAGE_HEADER..])?;
+
+        let mut store = Store {
+            projects: image.projects.into_owned(),
+            sites: image.sites.into_owned(),
+            buildings: image.buildings.into_owned(),
+            levels: image.levels.into_owned(),
+            footprints: image.footprints.into_owned(),
+            grids: image.grids.into_owned(),
+            wall_assemblies: image.wall_assemblies.into_owned(),
+            walls: image.walls.into_owned(),
+            rooms: image.rooms.into_owned(),
+            openings: image.openings.into_owned(),
+            opening_catalog: image.opening_catalog.into_owned(),
+            framing_layouts: image.framing_layouts.into_owned(),
+            floor_framing_layouts: image.floor_framing_layouts.into_owned(),
+            saved_filters: image.saved_filters.into_owned(),
+            saved_scripts: image.saved_scripts.into_owned(),
+            constraints: image.constraints.into_owned(),
+            detail_callouts: image.detail_callouts.into_owned(),
+            roofs: image.roofs.into_owned(),
+            stairs: image.stairs.into_owned(),
+            floor_slabs: image.floor_slabs.into_owned(),
+            ceilings: image.ceilings.into_owned(),
+            foundations: image.foundations.into_owned(),
+            beams: image.beams.into_owned(),
+            posts: image.posts.into_owned(),
+            electrical_devices: image.electrical_devices.into_owned(),
+            plumbing_fixtures: image.plumbing_fixtures.into_owned(),
+            hvac_zones: image.hvac_zones.into_owned(),
+            decks: image.decks.into_owned(),
+            garages: image.garages.into_owned(),
+            event_logs: image.event_logs.into_owned(),
+            snapshots: image.snapshots.into_owned(),
+            last_compaction: image.last_compaction.into_owned(),
+            pruning_policy: image.pruning_policy,
+            ..Store::default()
+        };
+        for wall_id in image.stale_framing {
+            store.regeneration.invalidate_wall(wall_id);
+        }
+        Ok(store)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Portable", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(40.0 / 3.0, 0.0), 9.0)
+            .unwrap();
+        // Framing layouts aren't in the event log
+        let layout_id = store.generate_wall_framing(wall_id).unwrap();
+        store.create_snapshot(project_id).unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+        store.undo(project_id).unwrap();
+
+        let bytes = store.to_bytes().unwrap();
+        let mut read = Store::from_bytes(&bytes).unwrap();
+        assert_eq!(read.get_project(project_id).unwrap().name, "Portable");
+        assert_eq!(read.get_wall(wall_id).unwrap().end, Point2::new(40.0 / 3.0, 0.0));
+        assert_eq!(read.get_wall(wall_id).unwrap().framing_layout_id, Some(layout_id));
+        assert!(read.framing_layouts.contains_key(&layout_id));
+        assert_eq!(read.get_project_snapshots(project_id).len(), 1);
+        assert_eq!(
+            read.get_event_log(project_id).unwrap().len(),
+            store.get_event_log(project_id).unwrap().len(),
+        );
+
+        // Undo history comes along
+        assert!(read.get_room(room_id).is_none());
+        assert!(read.can_redo(project_id));
+        read.redo(project_id).unwrap();
+        assert_eq!(read.get_room(room_id).unwrap().name, "Bedroom");
+
+        assert!(Store::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Store::from_bytes(b"GMSH\x01\x00\x00\x00").is_err());
+        let mut newer = bytes.clone();
+        newer[4] = 2;
+        assert!(Store::from_bytes(&newer).is_err());
+    }
+}
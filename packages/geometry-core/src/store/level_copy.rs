@@ -0,0 +1,223 @@
+// Level duplication and mirroring
+// A duplicate gets its own footprint, rooms, walls and openings with fresh
+// ids, created through the usual store operations so every piece is in the
+// event log. Mirroring flips a level's plan in place. Stairs, structure,
+// slabs and roofs are left for the user to place.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Level Copies ==========
+
+    /// Copy a level's footprint, rooms, walls and openings onto a new level
+    /// of the same building at `new_elevation`
+    pub fn duplicate_level(&mut self, level_id: LevelId, new_elevation: f64) -> Result<LevelId> {
+        let level = self.levels.get(&level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?
+            .clone();
+        let taken = self.get_building_levels(level.building_id)
+            .iter()
+            .any(|l| (l.elevation - new_elevation).abs() < 1e-6);
+        if taken {
+            return Err(anyhow!("Building already has a level at elevation {}", new_elevation));
+        }
+
+        self.transaction(|store| {
+            let copy_id = store.add_level(
+                level.building_id,
+                format!("{} (copy)", level.name),
+                new_elevation,
+                level.floor_to_floor,
+            )?;
+
+            if let Some(footprint) = level.footprint_id.and_then(|id| store.footprints.get(&id)) {
+                let polygon = footprint.polygon.clone();
+                store.set_level_footprint(copy_id, polygon)?;
+            }
+            for footprint_id in &level.auxiliary_footprint_ids {
+                let Some(footprint) = store.footprints.get(footprint_id) else { continue };
+                let polygon = footprint.polygon.clone();
+                store.add_auxiliary_footprint(copy_id, polygon)?;
+            }
+
+            let mut wall_map: HashMap<WallId, WallId> = HashMap::new();
+            for wall_id in &level.wall_ids {
+                let Some(wall) = store.walls.get(wall_id).cloned() else { continue };
+                let new_id = match wall.arc {
+                    Some(arc) => store.create_curved_wall(copy_id, wall.assembly_id, wall.start, wall.end, arc.radius, wall.height)?,
+                    None => store.create_wall(copy_id, wall.assembly_id, wall.start, wall.end, wall.height)?,
+                };
+                if let Some(copy) = store.walls.get_mut(&new_id) {
+                    copy.base_offset = wall.base_offset;
+                    copy.framing_config = wall.framing_config.clone();
+                }
+                wall_map.insert(wall.id, new_id);
+
+                let openings: Vec<Opening> = store.get_wall_openings(wall.id).into_iter().cloned().collect();
+                for opening in openings {
+                    store.copy_opening(&opening, new_id, opening.position_along_wall)?;
+                }
+            }
+
+            for room_id in &level.room_ids {
+                let Some(room) = store.rooms.get(room_id).cloned() else { continue };
+                let new_id = store.create_room(copy_id, room.room_type.clone(), room.name.clone(), room.boundary.clone())?;
+                if room.conditioned.is_some() {
+                    store.set_room_conditioned(new_id, room.conditioned)?;
+                }
+                if let Some(finishes) = room.finishes {
+                    for surface in [FinishSurface::Floor, FinishSurface::Wall, FinishSurface::Ceiling, FinishSurface::Base] {
+                        store.set_room_finish(new_id, surface, finishes.get(surface))?;
+                    }
+                }
+                let bounding: Vec<WallId> = room.bounding_wall_ids
+                    .iter()
+                    .filter_map(|id| wall_map.get(id).copied())
+                    .collect();
+                if !bounding.is_empty() {
+                    store.set_room_bounding_walls(new_id, bounding)?;
+                }
+            }
+
+            Ok(copy_id)
+        })
+    }
+
+    /// Flip a level's footprint, rooms, walls and openings across an axis.
+    /// Walls are reversed as well, so their exterior face, curvature and
+    /// openings land on the mirrored side.
+    pub fn mirror_level(&mut self, level_id: LevelId, axis: MirrorAxis) -> Result<()> {
+        let level = self.levels.get(&level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?
+            .clone();
+
+        self.transaction(|store| {
+            for footprint_id in level.footprint_ids() {
+                if let Some(footprint) = store.footprints.get(&footprint_id) {
+                    let polygon = axis.reflect_polygon(&footprint.polygon);
+                    store.modify_footprint(footprint_id, polygon)?;
+                }
+            }
+
+            for room_id in &level.room_ids {
+                let Some(room) = store.rooms.get(room_id) else { continue };
+                let boundary = axis.reflect_polygon(&room.boundary);
+                store.set_room_boundary(*room_id, boundary)?;
+            }
+
+            for wall_id in &level.wall_ids {
+                let Some(wall) = store.walls.get(wall_id) else { continue };
+                let (start, end) = (axis.reflect(&wall.end), axis.reflect(&wall.start));
+                store.set_wall_endpoints(*wall_id, start, end)?;
+
+                let openings: Vec<(OpeningId, f64)> = store.get_wall_openings(*wall_id)
+                    .iter()
+                    .map(|o| (o.id, o.position_along_wall))
+                    .collect();
+                for (opening_id, position) in openings {
+                    store.set_opening_position(opening_id, 1.0 - position)?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Place a copy of an opening on a wall, keeping its catalog unit and
+    /// properties
+    fn copy_opening(&mut self, opening: &Opening, wall_id: WallId, position: f64) -> Result<OpeningId> {
+        let mut copy = Opening::new(
+            wall_id,
+            opening.opening_type.clone(),
+            position,
+            opening.width,
+            opening.height,
+            opening.sill_height,
+        );
+        copy.set_details(opening.details());
+        self.insert_opening(copy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Store, LevelId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(30.0, 20.0)).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let south = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(30.0, 0.0), 9.0)
+            .unwrap();
+        store.add_opening(south, OpeningType::Window, 0.2, 4.0, 5.0, 2.0).unwrap();
+        let room = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(10.0, 20.0))
+            .unwrap();
+        store.set_room_conditioned(room, Some(true)).unwrap();
+        (store, level_id)
+    }
+
+    #[test]
+    fn test_duplicate_level() {
+        let (mut store, level_id) = setup();
+
+        let copy_id = store.duplicate_level(level_id, 9.0).unwrap();
+        assert_ne!(copy_id, level_id);
+        let copy = store.get_level(copy_id).unwrap();
+        assert_eq!(copy.elevation, 9.0);
+        assert!((store.get_level_footprint(copy_id).unwrap().polygon.area() - 600.0).abs() < 1e-9);
+
+        let walls = store.get_level_walls(copy_id);
+        assert_eq!(walls.len(), 1);
+        let original = store.get_level_walls(level_id)[0].id;
+        assert_ne!(walls[0].id, original);
+        let openings = store.get_wall_openings(walls[0].id);
+        assert_eq!(openings.len(), 1);
+        assert_eq!(openings[0].position_along_wall, 0.2);
+        let rooms = store.get_level_rooms(copy_id);
+        assert_eq!(rooms[0].name, "Bedroom");
+        assert_eq!(rooms[0].conditioned, Some(true));
+
+        assert!(store.duplicate_level(level_id, 0.0).is_err());
+        assert!(store.duplicate_level(LevelId::new(), 18.0).is_err());
+    }
+
+    #[test]
+    fn test_mirror_level() {
+        let (mut store, level_id) = setup();
+
+        store.mirror_level(level_id, MirrorAxis::Vertical(15.0)).unwrap();
+
+        // The bedroom moves to the east end and keeps its winding
+        let room = store.get_level_rooms(level_id)[0];
+        assert!(room.boundary.signed_area() > 0.0);
+        assert!(room.boundary.outer.iter().all(|p| p.x >= 20.0 - 1e-9));
+
+        // The wall is reversed end for end, so it still faces south and
+        // the window moves 6 ft in from the east end
+        let wall = store.get_level_walls(level_id)[0];
+        assert_eq!(wall.start, Point2::new(0.0, 0.0));
+        assert_eq!(wall.end, Point2::new(30.0, 0.0));
+        let opening = store.get_wall_openings(wall.id)[0];
+        assert!((opening.position_along_wall - 0.8).abs() < 1e-9);
+
+        // Mirroring twice restores the plan
+        store.mirror_level(level_id, MirrorAxis::Vertical(15.0)).unwrap();
+        let wall = store.get_level_walls(level_id)[0];
+        assert!((store.get_wall_openings(wall.id)[0].position_along_wall - 0.2).abs() < 1e-9);
+        assert!(store.mirror_level(LevelId::new(), MirrorAxis::Horizontal(0.0)).is_err());
+    }
+}
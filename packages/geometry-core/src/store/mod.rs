@@ -2,11 +2,58 @@
 // Provides CRUD operations that automatically record events
 // Thread-safe via RwLock for concurrent access
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::Sender;
 use std::sync::{Arc, RwLock};
 use anyhow::{anyhow, Result};
 
 use crate::domain::*;
+use crate::framing::RegenerationManager;
+
+mod assembly_selection;
+mod broadcast;
+mod changes;
+mod compaction;
+mod constraints;
+mod decks;
+mod garages;
+mod details;
+mod electrical;
+mod filters;
+mod scripts;
+mod finishes;
+mod foundations;
+mod framing;
+mod hvac;
+mod image;
+mod level_copy;
+mod opening_catalog;
+mod opening_edits;
+mod plumbing;
+mod replay;
+mod roofs;
+mod room_adjacency;
+pub(crate) mod room_derivation;
+mod shards;
+mod stairs;
+mod structural;
+mod slabs;
+mod snapshot;
+mod snapping;
+mod storage;
+mod transaction;
+mod wall_edits;
+mod wall_joins;
+
+pub use changes::{ChangeSet, EntityChanges};
+pub use compaction::{CompactionReport, MemoryStats, PruningPolicy};
+pub use room_adjacency::{RoomAdjacency, RoomAdjacencyGraph, SharedSegment, ADJACENCY_TOLERANCE};
+pub use room_derivation::RoomDerivation;
+pub use shards::ShardedStore;
+pub use snapshot::Snapshot;
+pub use snapping::{Snap, SnapTarget};
+pub use storage::{StorageBackend, MemoryBackend, UnloggedState};
+pub use transaction::BatchCommand;
 
 /// Thread-safe project store
 pub type SharedStore = Arc<RwLock<Store>>;
@@ -17,7 +64,7 @@ pub fn new_shared_store() -> SharedStore {
 }
 
 /// In-memory store for all project data
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Store {
     // Core entities
     pub projects: HashMap<ProjectId, Project>,
@@ -33,11 +80,75 @@ pub struct Store {
     pub rooms: HashMap<RoomId, Room>,
     pub openings: HashMap<OpeningId, Opening>,
 
+    // Window and door units openings can be placed from
+    pub opening_catalog: OpeningCatalog,
+
     // Phase 7 entities - Framing
     pub framing_layouts: HashMap<FramingLayoutId, FramingLayout>,
+    pub floor_framing_layouts: HashMap<LevelId, FloorFramingLayout>,
+
+    // Saved queries per project
+    pub saved_filters: HashMap<FilterId, SavedFilter>,
+
+    // Named Rhai scripts per project
+    pub saved_scripts: HashMap<ScriptId, SavedScript>,
+
+    // Named design constraints per project
+    pub constraints: HashMap<ConstraintId, Constraint>,
+
+    // Plan annotations
+    pub detail_callouts: HashMap<DetailCalloutId, DetailCallout>,
+
+    // Roofs, at most one per level
+    pub roofs: HashMap<RoofId, Roof>,
+
+    // Stairs between levels
+    pub stairs: HashMap<StairId, Stair>,
+
+    // Floor and ceiling assemblies
+    pub floor_slabs: HashMap<FloorSlabId, FloorSlab>,
+    pub ceilings: HashMap<CeilingAssemblyId, CeilingAssembly>,
+
+    // Foundations under each building's lowest level
+    pub foundations: HashMap<FoundationId, Foundation>,
+
+    // Beams and posts placed directly in the model
+    pub beams: HashMap<BeamId, Beam>,
+    pub posts: HashMap<PostId, Post>,
+
+    // Receptacles, switches and fixtures
+    pub electrical_devices: HashMap<DeviceId, ElectricalDevice>,
+
+    // Toilets, sinks, tubs and showers
+    pub plumbing_fixtures: HashMap<FixtureId, PlumbingFixture>,
+
+    // Groups of rooms served together
+    pub hvac_zones: HashMap<HVACZoneId, HvacZone>,
+
+    // Decks and porches off each level's outline
+    pub decks: HashMap<DeckId, Deck>,
+    // Slab settings of garage rooms
+    pub garages: HashMap<GarageId, Garage>,
 
     // Event logs per project
     pub event_logs: HashMap<ProjectId, EventLog>,
+
+    // Checkpoints into the event logs
+    pub snapshots: HashMap<SnapshotId, Snapshot>,
+
+    // Walls whose framing layouts no longer match their wall
+    regeneration: RegenerationManager,
+
+    // Set while re-applying logged events so they aren't recorded twice
+    replaying: bool,
+
+    // Live event subscribers, and events held back by an open transaction
+    subscribers: Vec<Sender<Event>>,
+    held_events: Option<Vec<Event>>,
+
+    // When to compact event logs, and the last event each was compacted at
+    pruning_policy: PruningPolicy,
+    last_compaction: HashMap<ProjectId, EventId>,
 }
 
 impl Store {
@@ -54,7 +165,7 @@ impl Store {
         code_region: CodeRegion,
     ) -> Result<ProjectId> {
         let name = name.into();
-        let project = Project::new(name.clone(), units, code_region.clone());
+        let mut project = Project::new(name.clone(), units, code_region.clone());
         let project_id = project.id;
 
         // Initialize event log and record creation
@@ -68,6 +179,11 @@ impl Store {
             },
         );
 
+        // Match the creation time replay will restore
+        if let Some(event) = event_log.events().first() {
+            project.created_at = event.timestamp;
+            self.broadcast(event.clone());
+        }
         self.projects.insert(project_id, project);
         self.event_logs.insert(project_id, event_log);
 
@@ -82,10 +198,84 @@ impl Store {
         self.projects.get_mut(&id)
     }
 
+    /// Switch the building code a project is checked against
+    pub fn set_project_code_region(&mut self, project_id: ProjectId, region: CodeRegion) -> Result<()> {
+        let project = self.projects.get_mut(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+
+        let old_region = std::mem::replace(&mut project.code_region, region.clone());
+        project.touch();
+
+        self.record_event(
+            project_id,
+            EventKind::ProjectCodeRegionChanged {
+                old_region,
+                new_region: region,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Pick the regional price set a project's estimates use (None for the default prices)
+    pub fn set_project_price_region(&mut self, project_id: ProjectId, region: Option<String>) -> Result<()> {
+        let project = self.projects.get_mut(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+
+        project.price_region = region.clone();
+        project.touch();
+
+        self.record_event(project_id, EventKind::ProjectPriceRegionSet { region });
+
+        Ok(())
+    }
+
+    /// Set the overhead, profit and contingency percentages a project's estimates carry
+    pub fn set_project_markups(&mut self, project_id: ProjectId, markups: Markups) -> Result<()> {
+        let project = self.projects.get_mut(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+
+        project.markups = markups;
+        project.touch();
+
+        self.record_event(project_id, EventKind::ProjectMarkupsSet { markups });
+
+        Ok(())
+    }
+
     pub fn list_projects(&self) -> Vec<&Project> {
         self.projects.values().collect()
     }
 
+    /// A sorted, filtered page of projects
+    pub fn query_projects(&self, query: &ProjectQuery) -> ProjectPage<'_> {
+        let needle = query.name_contains.as_deref().map(str::to_lowercase);
+        let mut projects: Vec<&Project> = self.projects
+            .values()
+            .filter(|p| needle.as_ref().is_none_or(|n| p.name.to_lowercase().contains(n)))
+            .collect();
+        // Ties fall back to the ID so pages don't shuffle between requests
+        projects.sort_by(|a, b| {
+            let order = match query.sort {
+                ProjectSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                ProjectSort::Created => a.created_at.cmp(&b.created_at),
+                ProjectSort::Modified => a.modified_at.cmp(&b.modified_at),
+            };
+            order.then_with(|| a.id.to_string().cmp(&b.id.to_string()))
+        });
+        if query.descending {
+            projects.reverse();
+        }
+
+        let total = projects.len();
+        let projects = projects
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .collect();
+        ProjectPage { projects, total }
+    }
+
     // ========== Site Operations ==========
 
     pub fn create_site(&mut self, project_id: ProjectId) -> Result<SiteId> {
@@ -132,6 +322,112 @@ impl Store {
         Ok(())
     }
 
+    pub fn set_site_setbacks(&mut self, site_id: SiteId, setbacks: Setbacks) -> Result<()> {
+        let values = [setbacks.front, setbacks.back, setbacks.left, setbacks.right];
+        if values.iter().any(|v| !v.is_finite() || *v < 0.0) {
+            return Err(anyhow!("Setbacks must be non-negative"));
+        }
+        let site = self.sites.get_mut(&site_id)
+            .ok_or_else(|| anyhow!("Site not found: {:?}", site_id))?;
+        let project_id = site.project_id;
+        site.setbacks = setbacks.clone();
+
+        self.record_event(project_id, EventKind::SiteSetbacksUpdated {
+            site_id,
+            front: setbacks.front,
+            back: setbacks.back,
+            left: setbacks.left,
+            right: setbacks.right,
+        });
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(())
+    }
+
+    /// Set the lot's front edge, coverage and height limits
+    pub fn set_site_zoning(&mut self, site_id: SiteId, zoning: ZoningRules) -> Result<()> {
+        if zoning.max_lot_coverage.is_some_and(|c| !(c > 0.0 && c <= 1.0)) {
+            return Err(anyhow!("Lot coverage must be a fraction between 0 and 1"));
+        }
+        if zoning.max_height.is_some_and(|h| h <= 0.0) {
+            return Err(anyhow!("Height limit must be positive"));
+        }
+        let site = self.sites.get_mut(&site_id)
+            .ok_or_else(|| anyhow!("Site not found: {:?}", site_id))?;
+        let edges = site.boundary.as_ref().map(|b| b.outer.len()).unwrap_or(0);
+        if edges > 0 && zoning.front_edge >= edges {
+            return Err(anyhow!("Front edge {} is not on the site boundary", zoning.front_edge));
+        }
+        let project_id = site.project_id;
+        site.zoning = zoning.clone();
+
+        self.record_event(project_id, EventKind::SiteZoningSet { site_id, zoning });
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(())
+    }
+
+    /// Turn the plan against true north: the angle (degrees) clockwise from
+    /// the plan's +y axis to north
+    pub fn set_site_north_angle(&mut self, site_id: SiteId, north_angle: f64) -> Result<()> {
+        if !north_angle.is_finite() {
+            return Err(anyhow!("North angle must be a number of degrees"));
+        }
+        let north_angle = north_angle.rem_euclid(360.0);
+        let site = self.sites.get_mut(&site_id)
+            .ok_or_else(|| anyhow!("Site not found: {:?}", site_id))?;
+        let project_id = site.project_id;
+        site.north_angle = north_angle;
+
+        self.record_event(project_id, EventKind::SiteNorthAngleSet { site_id, north_angle });
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(())
+    }
+
+    /// Triangulate the site's ground surface from surveyed XYZ points
+    /// (feet); an empty list clears it
+    pub fn set_site_terrain(&mut self, site_id: SiteId, points: Vec<Point3>) -> Result<()> {
+        let terrain = if points.is_empty() {
+            None
+        } else {
+            Some(Terrain::triangulate(points.clone()).ok_or_else(|| {
+                anyhow!("Terrain needs three or more distinct points not all on one line")
+            })?)
+        };
+        let site = self.sites.get_mut(&site_id)
+            .ok_or_else(|| anyhow!("Site not found: {:?}", site_id))?;
+        let project_id = site.project_id;
+        site.terrain = terrain;
+
+        self.record_event(project_id, EventKind::SiteTerrainSet { site_id, points });
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(())
+    }
+
+    /// Contour lines of the site's terrain every `interval` feet
+    pub fn get_site_contours(&self, site_id: SiteId, interval: f64) -> Result<Vec<Contour>> {
+        if !interval.is_finite() || interval <= 0.0 {
+            return Err(anyhow!("Contour interval must be positive"));
+        }
+        let site = self.sites.get(&site_id)
+            .ok_or_else(|| anyhow!("Site not found: {:?}", site_id))?;
+        Ok(site.terrain.as_ref().map(|t| t.contours(interval)).unwrap_or_default())
+    }
+
     // ========== Building Operations ==========
 
     pub fn add_building(
@@ -163,6 +459,50 @@ impl Store {
         self.buildings.get_mut(&id)
     }
 
+    /// Classify a building as the dwelling, an ADU, a garage or a shed
+    pub fn set_building_occupancy(
+        &mut self,
+        building_id: BuildingId,
+        occupancy: BuildingOccupancy,
+    ) -> Result<()> {
+        let building = self.buildings.get_mut(&building_id)
+            .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+        building.occupancy = occupancy;
+        let project_id = building.project_id;
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        self.record_event(project_id, EventKind::BuildingOccupancySet { building_id, occupancy });
+
+        Ok(())
+    }
+
+    /// Grade the site under a building to an elevation, or None to leave
+    /// the ground as surveyed
+    pub fn set_building_pad_elevation(
+        &mut self,
+        building_id: BuildingId,
+        pad_elevation: Option<f64>,
+    ) -> Result<()> {
+        if pad_elevation.is_some_and(|e| !e.is_finite()) {
+            return Err(anyhow!("Pad elevation must be a number"));
+        }
+        let building = self.buildings.get_mut(&building_id)
+            .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+        building.pad_elevation = pad_elevation;
+        let project_id = building.project_id;
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        self.record_event(project_id, EventKind::BuildingPadElevationSet { building_id, pad_elevation });
+
+        Ok(())
+    }
+
     pub fn remove_building(&mut self, building_id: BuildingId) -> Result<()> {
         let building = self.buildings.remove(&building_id)
             .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
@@ -170,11 +510,25 @@ impl Store {
         // Remove all levels in this building
         for level_id in &building.level_ids {
             if let Some(level) = self.levels.remove(level_id) {
-                if let Some(footprint_id) = level.footprint_id {
+                for footprint_id in level.footprint_ids() {
                     self.footprints.remove(&footprint_id);
                 }
             }
+            self.detail_callouts.retain(|_, c| c.level_id != *level_id);
+            self.roofs.retain(|_, r| r.level_id != *level_id);
+            self.stairs.retain(|_, s| s.from_level_id != *level_id && s.to_level_id != *level_id);
+            self.floor_slabs.retain(|_, s| s.level_id != *level_id);
+            self.ceilings.retain(|_, c| c.level_id != *level_id);
+            self.foundations.retain(|_, f| f.level_id != *level_id);
+            self.beams.retain(|_, b| b.level_id != *level_id);
+            self.posts.retain(|_, p| p.level_id != *level_id);
+            self.electrical_devices.retain(|_, d| d.level_id != *level_id);
+            self.plumbing_fixtures.retain(|_, f| f.level_id != *level_id);
+            self.decks.retain(|_, d| d.level_id != *level_id);
+            self.garages.retain(|_, g| g.level_id != *level_id);
+            self.floor_framing_layouts.remove(level_id);
         }
+        self.hvac_zones.retain(|_, z| z.building_id != building_id);
 
         // Update project
         if let Some(project) = self.projects.get_mut(&building.project_id) {
@@ -247,15 +601,48 @@ impl Store {
         }
     }
 
+    /// Levels of a building from lowest to highest
+    pub fn get_building_levels_by_elevation(&self, building_id: BuildingId) -> Vec<&Level> {
+        let mut levels = self.get_building_levels(building_id);
+        levels.sort_by(|a, b| a.elevation.total_cmp(&b.elevation));
+        levels
+    }
+
+    /// The next level down in the same building, if any
+    pub fn get_level_below(&self, level_id: LevelId) -> Option<&Level> {
+        let level = self.levels.get(&level_id)?;
+        self.get_building_levels(level.building_id)
+            .into_iter()
+            .filter(|l| l.elevation < level.elevation)
+            .max_by(|a, b| a.elevation.total_cmp(&b.elevation))
+    }
+
     pub fn remove_level(&mut self, level_id: LevelId) -> Result<()> {
         let level = self.levels.remove(&level_id)
             .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
 
-        // Remove footprint if exists
-        if let Some(footprint_id) = level.footprint_id {
+        // Remove its footprints
+        for footprint_id in level.footprint_ids() {
             self.footprints.remove(&footprint_id);
         }
 
+        self.detail_callouts.retain(|_, c| c.level_id != level_id);
+        self.roofs.retain(|_, r| r.level_id != level_id);
+        self.stairs.retain(|_, s| s.from_level_id != level_id && s.to_level_id != level_id);
+        self.floor_slabs.retain(|_, s| s.level_id != level_id);
+        self.ceilings.retain(|_, c| c.level_id != level_id);
+        self.foundations.retain(|_, f| f.level_id != level_id);
+        self.beams.retain(|_, b| b.level_id != level_id);
+        self.posts.retain(|_, p| p.level_id != level_id);
+        self.electrical_devices.retain(|_, d| d.level_id != level_id);
+        self.plumbing_fixtures.retain(|_, f| f.level_id != level_id);
+        self.decks.retain(|_, d| d.level_id != level_id);
+        self.garages.retain(|_, g| g.level_id != level_id);
+        self.floor_framing_layouts.remove(&level_id);
+        for zone in self.hvac_zones.values_mut() {
+            zone.room_ids.retain(|id| !level.room_ids.contains(id));
+        }
+
         // Update building
         if let Some(building) = self.buildings.get_mut(&level.building_id) {
             building.level_ids.retain(|id| *id != level_id);
@@ -372,6 +759,15 @@ impl Store {
         Ok(())
     }
 
+    /// Remember the massing solid generated for a footprint, so later
+    /// renders reuse it instead of adding another to the cache
+    pub fn set_footprint_solid(&mut self, footprint_id: FootprintId, solid_id: SolidId) -> Result<()> {
+        let footprint = self.footprints.get_mut(&footprint_id)
+            .ok_or_else(|| anyhow!("Footprint not found: {:?}", footprint_id))?;
+        footprint.solid_id = Some(solid_id);
+        Ok(())
+    }
+
     pub fn offset_footprint(&mut self, footprint_id: FootprintId, distance: f64) -> Result<()> {
         let polygon = {
             let footprint = self.footprints.get(&footprint_id)
@@ -382,52 +778,150 @@ impl Store {
         self.modify_footprint(footprint_id, polygon)
     }
 
+    /// Add a vertex to a footprint's outline, splitting the edge that starts
+    /// at `edge_index`
+    pub fn insert_footprint_vertex(&mut self, footprint_id: FootprintId, edge_index: usize, point: Point2) -> Result<()> {
+        let mut polygon = self.footprint_polygon(footprint_id)?;
+        if edge_index >= polygon.outer.len() {
+            return Err(anyhow!("Footprint has no edge {}", edge_index));
+        }
+        polygon.outer.insert(edge_index + 1, point);
+        self.modify_footprint(footprint_id, polygon)
+    }
+
+    pub fn move_footprint_vertex(&mut self, footprint_id: FootprintId, vertex_index: usize, point: Point2) -> Result<()> {
+        let mut polygon = self.footprint_polygon(footprint_id)?;
+        let vertex = polygon.outer.get_mut(vertex_index)
+            .ok_or_else(|| anyhow!("Footprint has no vertex {}", vertex_index))?;
+        *vertex = point;
+        self.modify_footprint(footprint_id, polygon)
+    }
+
+    pub fn remove_footprint_vertex(&mut self, footprint_id: FootprintId, vertex_index: usize) -> Result<()> {
+        let mut polygon = self.footprint_polygon(footprint_id)?;
+        if vertex_index >= polygon.outer.len() {
+            return Err(anyhow!("Footprint has no vertex {}", vertex_index));
+        }
+        if polygon.outer.len() <= 3 {
+            return Err(anyhow!("A footprint needs at least 3 vertices"));
+        }
+        polygon.outer.remove(vertex_index);
+        self.modify_footprint(footprint_id, polygon)
+    }
+
+    /// Push the edge that starts at `edge_index` out by `distance` feet, or
+    /// pull it in with a negative distance
+    pub fn push_footprint_edge(&mut self, footprint_id: FootprintId, edge_index: usize, distance: f64) -> Result<()> {
+        let polygon = self.footprint_polygon(footprint_id)?
+            .push_edge(edge_index, distance)
+            .ok_or_else(|| anyhow!("Footprint has no edge {}", edge_index))?;
+        self.modify_footprint(footprint_id, polygon)
+    }
+
+    fn footprint_polygon(&self, footprint_id: FootprintId) -> Result<Polygon2> {
+        self.footprints.get(&footprint_id)
+            .map(|f| f.polygon.clone())
+            .ok_or_else(|| anyhow!("Footprint not found: {:?}", footprint_id))
+    }
+
+    /// Add a wing, attached garage or other block alongside a level's
+    /// primary footprint
+    pub fn add_auxiliary_footprint(&mut self, level_id: LevelId, polygon: Polygon2) -> Result<FootprintId> {
+        if !polygon.is_valid() {
+            return Err(anyhow!("Invalid polygon: must be closed, non-degenerate"));
+        }
+
+        let level = self.levels.get_mut(&level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        if level.footprint_id.is_none() {
+            return Err(anyhow!("Level has no primary footprint: {:?}", level_id));
+        }
+
+        let footprint = Footprint::auxiliary(level_id, polygon.clone());
+        let footprint_id = footprint.id;
+        level.auxiliary_footprint_ids.push(footprint_id);
+        let building_id = level.building_id;
+
+        let project_id = self.buildings.get(&building_id)
+            .map(|b| b.project_id)
+            .ok_or_else(|| anyhow!("Building not found for level"))?;
+
+        self.record_event(
+            project_id,
+            EventKind::AuxiliaryFootprintAdded {
+                footprint_id,
+                level_id,
+                polygon,
+            },
+        );
+        self.footprints.insert(footprint_id, footprint);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(footprint_id)
+    }
+
+    /// Every footprint on a level, primary first
+    pub fn get_level_footprints(&self, level_id: LevelId) -> Vec<&Footprint> {
+        self.levels.get(&level_id)
+            .map(|level| level.footprint_ids().filter_map(|id| self.footprints.get(&id)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Remove a footprint from its level. Removing the primary leaves the
+    /// level without one; auxiliary footprints keep their role.
+    pub fn remove_footprint(&mut self, footprint_id: FootprintId) -> Result<()> {
+        let footprint = self.footprints.remove(&footprint_id)
+            .ok_or_else(|| anyhow!("Footprint not found: {:?}", footprint_id))?;
+        let level_id = footprint.level_id;
+
+        if let Some(level) = self.levels.get_mut(&level_id) {
+            if level.footprint_id == Some(footprint_id) {
+                level.footprint_id = None;
+            }
+            level.auxiliary_footprint_ids.retain(|id| *id != footprint_id);
+        }
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::FootprintRemoved { footprint_id, level_id });
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cut a footprint in two along the line through `p1` and `p2`. The
+    /// larger piece keeps the footprint's id and role; the other becomes
+    /// an auxiliary footprint on the same level. Returns (kept, new).
     pub fn split_footprint(
         &mut self,
         footprint_id: FootprintId,
         p1: Point2,
         p2: Point2,
     ) -> Result<(FootprintId, FootprintId)> {
-        let (_poly1, _poly2) = {
+        let (level_id, kept, split_off) = {
             let footprint = self.footprints.get(&footprint_id)
                 .ok_or_else(|| anyhow!("Footprint not found: {:?}", footprint_id))?;
-            
-            footprint.polygon.split(p1, p2)
-                .ok_or_else(|| anyhow!("Failed to split footprint (line might not intersect)"))?
+
+            let (left, right) = footprint.polygon.split(p1, p2)
+                .ok_or_else(|| anyhow!("Failed to split footprint: the line must cut it into exactly two pieces"))?;
+            if left.area() >= right.area() {
+                (footprint.level_id, left, right)
+            } else {
+                (footprint.level_id, right, left)
+            }
         };
 
-        // Get context
-        let _level_id = self.footprints.get(&footprint_id).unwrap().level_id;
-        
-        // Remove old footprint
-        // Note: This removes it from the level too, so we need to be careful
-        // Actually, let's keep the old one as "deleted" or just remove it?
-        // For now, remove it.
-        self.footprints.remove(&footprint_id);
-        
-        // Create two new footprints
-        // We need to handle the level relationship. A level usually has ONE footprint.
-        // If we split, do we create a new level? Or does a level support multiple footprints?
-        // The current Level struct has `footprint_id: Option<FootprintId>`.
-        // So a level can only have ONE footprint.
-        
-        // If we split a footprint, we might be creating "zones" or "rooms", not multiple footprints for the level.
-        // OR, we are splitting the level into two levels? Unlikely.
-        
-        // Re-reading requirements: "split_footprint" might be for creating multiple massings?
-        // If Level only supports one footprint, this operation is invalid unless we change Level.
-        
-        // Let's check Level definition in `domain/project.rs`.
-        // If Level has `footprint_id: Option<FootprintId>`, then we can't have two.
-        
-        // For now, I will return an error explaining this limitation, or just comment it out.
-        // But the plan asked for it.
-        
-        // Maybe `split_footprint` is intended to return two polygons that the user then decides what to do with?
-        // But the signature returns `(FootprintId, FootprintId)`.
-        
-        // I will implement it such that it errors for now, noting the architectural limitation.
-        Err(anyhow!("Cannot split footprint: Level currently supports only one footprint"))
+        self.transaction(|store| {
+            store.modify_footprint(footprint_id, kept)?;
+            let new_id = store.add_auxiliary_footprint(level_id, split_off)?;
+            Ok((footprint_id, new_id))
+        })
     }
 
     // ========== Grid Operations ==========
@@ -476,6 +970,24 @@ impl Store {
         self.grids.get(&building_id)
     }
 
+    /// Snap a point to the building's grid axes within `tolerance`
+    pub fn snap_point_to_grid(&self, building_id: BuildingId, point: Point2, tolerance: f64) -> Result<Point2> {
+        let grid = self.grids.get(&building_id)
+            .ok_or_else(|| anyhow!("Grid not found for building: {:?}", building_id))?;
+        Ok(grid.snap(point, tolerance))
+    }
+
+    /// Resolve a grid reference such as "A/3" against the grid of the
+    /// building a level is in
+    pub fn resolve_grid_point(&self, level_id: LevelId, reference: &str) -> Result<Point2> {
+        let level = self.levels.get(&level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        let grid = self.grids.get(&level.building_id)
+            .ok_or_else(|| anyhow!("Grid not found for building: {:?}", level.building_id))?;
+        grid.resolve(reference)
+            .ok_or_else(|| anyhow!("Grid reference must name two crossing axes, like \"A/3\": {}", reference))
+    }
+
     // ========== Wall Assembly Operations ==========
 
     pub fn create_wall_assembly(
@@ -483,6 +995,8 @@ impl Store {
         name: impl Into<String>,
         layers: Vec<WallLayer>,
     ) -> Result<WallAssemblyId> {
+        validate_wall_layers(&layers)?;
+
         let name = name.into();
         let assembly = WallAssembly::new(name.clone(), layers);
         let assembly_id = assembly.id;
@@ -503,6 +1017,85 @@ impl Store {
         self.wall_assemblies.values().collect()
     }
 
+    /// Replace an assembly's name and layers. Walls built with it are
+    /// re-joined for their new thickness and their framing goes stale.
+    pub fn update_wall_assembly(
+        &mut self,
+        assembly_id: WallAssemblyId,
+        name: impl Into<String>,
+        layers: Vec<WallLayer>,
+    ) -> Result<()> {
+        validate_wall_layers(&layers)?;
+        let assembly = self.wall_assemblies.get_mut(&assembly_id)
+            .ok_or_else(|| anyhow!("Wall assembly not found: {:?}", assembly_id))?;
+        *assembly = WallAssembly { id: assembly_id, ..WallAssembly::new(name, layers) };
+
+        let walls: Vec<&Wall> = self.walls.values().filter(|w| w.assembly_id == assembly_id).collect();
+        let levels: HashSet<LevelId> = walls.iter().map(|w| w.level_id).collect();
+        let stale: Vec<WallId> = walls
+            .iter()
+            .flat_map(|w| [Some(w.id), w.start_join.wall(), w.end_join.wall()])
+            .flatten()
+            .collect();
+
+        for wall_id in stale {
+            self.invalidate_wall_framing(wall_id);
+        }
+        let mut project_ids: HashSet<ProjectId> = levels
+            .iter()
+            .filter_map(|level_id| self.level_project_id(*level_id))
+            .collect();
+        project_ids.extend(
+            self.projects
+                .values()
+                .filter(|p| p.default_assemblies.values().any(|id| *id == assembly_id))
+                .map(|p| p.id),
+        );
+        for level_id in levels {
+            self.refresh_wall_joins(level_id);
+        }
+
+        // The assembly lives outside the logs; an event in each project
+        // using it moves that project's revision
+        let name = self.wall_assemblies[&assembly_id].name.clone();
+        for project_id in project_ids {
+            self.record_event(
+                project_id,
+                EventKind::WallAssemblyUpdated { wall_assembly_id: assembly_id, name: name.clone() },
+            );
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove an assembly that no wall is built with, no project uses as
+    /// a default, and no project history refers to. Undo, redo and snapshot
+    /// restores replay those logs, so they must not reach a removed assembly.
+    pub fn remove_wall_assembly(&mut self, assembly_id: WallAssemblyId) -> Result<()> {
+        if !self.wall_assemblies.contains_key(&assembly_id) {
+            return Err(anyhow!("Wall assembly not found: {:?}", assembly_id));
+        }
+        let walls = self.walls.values().filter(|w| w.assembly_id == assembly_id).count();
+        if walls > 0 {
+            return Err(anyhow!("Wall assembly is used by {} wall(s): {:?}", walls, assembly_id));
+        }
+        if self.projects.values().any(|p| p.default_assemblies.values().any(|id| *id == assembly_id)) {
+            return Err(anyhow!("Wall assembly is a project default: {:?}", assembly_id));
+        }
+        let in_history = self.event_logs.values().any(|log| {
+            log.events().iter().chain(log.undone()).any(|e| e.kind.wall_assembly_id() == Some(assembly_id))
+        });
+        if in_history {
+            return Err(anyhow!("Wall assembly is referenced by project history: {:?}", assembly_id));
+        }
+
+        self.wall_assemblies.remove(&assembly_id);
+        Ok(())
+    }
+
     // ========== Wall Operations ==========
 
     pub fn create_wall(
@@ -512,6 +1105,36 @@ impl Store {
         start: Point2,
         end: Point2,
         height: f64,
+    ) -> Result<WallId> {
+        self.insert_wall(level_id, assembly_id, start, end, height, None)
+    }
+
+    /// Create a wall whose centerline arcs from `start` to `end` with the
+    /// given radius. A positive radius bows the wall to its left.
+    pub fn create_curved_wall(
+        &mut self,
+        level_id: LevelId,
+        assembly_id: WallAssemblyId,
+        start: Point2,
+        end: Point2,
+        radius: f64,
+        height: f64,
+    ) -> Result<WallId> {
+        let arc = WallArc { radius };
+        if !arc.fits_chord(start.distance_to(&end)) {
+            return Err(anyhow!("Arc radius must be at least half the distance between the wall's ends"));
+        }
+        self.insert_wall(level_id, assembly_id, start, end, height, Some(arc))
+    }
+
+    fn insert_wall(
+        &mut self,
+        level_id: LevelId,
+        assembly_id: WallAssemblyId,
+        start: Point2,
+        end: Point2,
+        height: f64,
+        arc: Option<WallArc>,
     ) -> Result<WallId> {
         // Validate level exists
         let level = self.levels.get(&level_id)
@@ -531,7 +1154,8 @@ impl Store {
             return Err(anyhow!("Wall height must be positive"));
         }
 
-        let wall = Wall::new(assembly_id, level_id, start, end, height);
+        let mut wall = Wall::new(assembly_id, level_id, start, end, height);
+        wall.arc = arc;
         let wall_id = wall.id;
         let building_id = level.building_id;
 
@@ -546,6 +1170,7 @@ impl Store {
         if let Some(level) = self.levels.get_mut(&level_id) {
             level.wall_ids.push(wall_id);
         }
+        self.refresh_wall_joins(level_id);
 
         self.record_event(
             project_id,
@@ -556,6 +1181,7 @@ impl Store {
                 start,
                 end,
                 height,
+                arc,
             },
         );
 
@@ -577,6 +1203,85 @@ impl Store {
             .collect()
     }
 
+    /// Walls on a level that carry floor or roof load
+    pub fn get_bearing_walls(&self, level_id: LevelId) -> Vec<&Wall> {
+        self.get_level_walls(level_id)
+            .into_iter()
+            .filter(|wall| wall.framing_config.is_load_bearing)
+            .collect()
+    }
+
+    /// Move a wall's centerline to new end points. Openings keep their
+    /// position along the wall; framing goes stale along with that of the
+    /// walls it meets.
+    pub fn set_wall_endpoints(&mut self, wall_id: WallId, start: Point2, end: Point2) -> Result<()> {
+        let wall = self.walls.get(&wall_id)
+            .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
+        if start.distance_to(&end) < 1e-10 {
+            return Err(anyhow!("Wall start and end points are too close"));
+        }
+        if wall.arc.is_some_and(|arc| !arc.fits_chord(start.distance_to(&end))) {
+            return Err(anyhow!("Arc radius must be at least half the distance between the wall's ends"));
+        }
+        let level_id = wall.level_id;
+        let old_joins = [wall.start_join.wall(), wall.end_join.wall()];
+
+        if let Some(wall) = self.walls.get_mut(&wall_id) {
+            wall.start = start;
+            wall.end = end;
+        }
+        self.invalidate_wall_framing(wall_id);
+        for other in old_joins.into_iter().flatten() {
+            self.invalidate_wall_framing(other);
+        }
+        self.refresh_wall_joins(level_id);
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::WallMoved { wall_id, level_id, start, end });
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Swap a wall's assembly; its joins and framing follow the new thickness
+    pub fn set_wall_assembly(&mut self, wall_id: WallId, assembly_id: WallAssemblyId) -> Result<()> {
+        if !self.wall_assemblies.contains_key(&assembly_id) {
+            return Err(anyhow!("Wall assembly not found: {:?}", assembly_id));
+        }
+        self.assign_wall_assembly(wall_id, assembly_id)
+    }
+
+    /// Point a wall at an assembly without checking the assembly exists.
+    /// Replay uses this, since a log can name assemblies the store it is
+    /// replayed into doesn't hold.
+    pub(super) fn assign_wall_assembly(&mut self, wall_id: WallId, assembly_id: WallAssemblyId) -> Result<()> {
+        let wall = self.walls.get_mut(&wall_id)
+            .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
+        wall.assembly_id = assembly_id;
+        let level_id = wall.level_id;
+        let joined = [wall.start_join.wall(), wall.end_join.wall()];
+
+        self.invalidate_wall_framing(wall_id);
+        for other in joined.into_iter().flatten() {
+            self.invalidate_wall_framing(other);
+        }
+        self.refresh_wall_joins(level_id);
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::WallAssemblySet { wall_id, level_id, assembly_id });
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn remove_wall(&mut self, wall_id: WallId) -> Result<()> {
         let wall = self.walls.remove(&wall_id)
             .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
@@ -588,8 +1293,21 @@ impl Store {
             .map(|opening| opening.id)
             .collect();
 
-        for opening_id in opening_ids {
-            self.openings.remove(&opening_id);
+        for opening_id in &opening_ids {
+            self.openings.remove(opening_id);
+        }
+
+        // Devices mounted in the wall go with it
+        self.electrical_devices.retain(|_, d| d.wall_id != Some(wall_id));
+
+        // Callouts stay on the plan but lose their link to the removed elements
+        for callout in self.detail_callouts.values_mut() {
+            if callout.wall_id == Some(wall_id) {
+                callout.wall_id = None;
+            }
+            if callout.opening_id.is_some_and(|id| opening_ids.contains(&id)) {
+                callout.opening_id = None;
+            }
         }
 
         // Remove wall ID from level's wall_ids vector (reverse relationship)
@@ -603,6 +1321,17 @@ impl Store {
             room.bounding_wall_ids.retain(|id| *id != wall_id);
         }
 
+        // The wall's framing goes with it; walls it met lose a corner or
+        // partition backing
+        if let Some(layout_id) = wall.framing_layout_id {
+            self.framing_layouts.remove(&layout_id);
+        }
+        self.regeneration.mark_clean(wall_id);
+        for other in [wall.start_join.wall(), wall.end_join.wall()].into_iter().flatten() {
+            self.invalidate_wall_framing(other);
+        }
+        self.refresh_wall_joins(level_id);
+
         // Get project_id for event recording
         if let Some(level) = self.levels.get(&level_id) {
             let building_id = level.building_id;
@@ -642,7 +1371,7 @@ impl Store {
         }
 
         let name = name.into();
-        let room = Room::new(level_id, room_type.clone(), name.clone(), boundary);
+        let room = Room::new(level_id, room_type.clone(), name.clone(), boundary.clone());
         let room_id = room.id;
         let building_id = level.building_id;
 
@@ -665,6 +1394,7 @@ impl Store {
                 level_id,
                 room_type,
                 name,
+                boundary,
             },
         );
 
@@ -696,6 +1426,21 @@ impl Store {
             level.room_ids.retain(|id| *id != room_id);
         }
 
+        // Floors and ceilings bounded by the room go with it
+        let bounded = SlabBoundary::Room { room_id };
+        self.floor_slabs.retain(|_, s| s.boundary != bounded);
+        self.ceilings.retain(|_, c| c.boundary != bounded);
+
+        // Fixtures go with their room; devices stay on the plan, outside any room
+        self.plumbing_fixtures.retain(|_, f| f.room_id != room_id);
+        self.garages.retain(|_, g| g.room_id != room_id);
+        self.set_room_zone(room_id, None);
+        for device in self.electrical_devices.values_mut() {
+            if device.room_id == Some(room_id) {
+                device.room_id = None;
+            }
+        }
+
         // Get project_id for event recording
         if let Some(level) = self.levels.get(&level_id) {
             let building_id = level.building_id;
@@ -728,8 +1473,105 @@ impl Store {
             }
         }
 
-        room.set_bounding_walls(wall_ids);
-
+        room.set_bounding_walls(wall_ids);
+
+        Ok(())
+    }
+
+    /// Replace a room's boundary. Framing of the walls bounding the room
+    /// goes stale.
+    pub fn set_room_boundary(&mut self, room_id: RoomId, boundary: Polygon2) -> Result<()> {
+        if !boundary.is_valid() {
+            return Err(anyhow!("Invalid room boundary: must be closed, non-degenerate"));
+        }
+        let room = self.rooms.get_mut(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+        let area_delta = boundary.area() - room.area();
+        room.boundary = boundary.clone();
+        let level_id = room.level_id;
+        let bounding_wall_ids = room.bounding_wall_ids.clone();
+
+        for wall_id in bounding_wall_ids {
+            self.invalidate_wall_framing(wall_id);
+        }
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::RoomBoundarySet { room_id, boundary, area_delta });
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reshape a room from a list of corner points, in either winding and
+    /// with or without the first point repeated at the end. Returns the
+    /// change in floor area.
+    pub fn update_room_boundary(&mut self, room_id: RoomId, mut points: Vec<Point2>) -> Result<f64> {
+        if points.len() > 3 && points.first().zip(points.last()).is_some_and(|(a, b)| a.distance_to(b) < 1e-10) {
+            points.pop();
+        }
+        let mut boundary = Polygon2::new(points);
+        if boundary.signed_area() < 0.0 {
+            boundary.outer.reverse();
+        }
+
+        let old_area = self.rooms.get(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?
+            .area();
+        let new_area = boundary.area();
+        self.set_room_boundary(room_id, boundary)?;
+        Ok(new_area - old_area)
+    }
+
+    /// Rename and retype a room
+    pub fn update_room_meta(&mut self, room_id: RoomId, name: impl Into<String>, room_type: RoomType) -> Result<()> {
+        let name = name.into();
+        if name.trim().is_empty() {
+            return Err(anyhow!("Room name must not be empty"));
+        }
+        let room = self.rooms.get_mut(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+        room.name = name.clone();
+        room.room_type = room_type.clone();
+        let level_id = room.level_id;
+        // A room retyped away from a garage loses its garage settings
+        if room_type != RoomType::Garage {
+            self.garages.retain(|_, g| g.room_id != room_id);
+        }
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::RoomMetaUpdated { room_id, name, room_type });
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Override whether a room is inside the conditioned envelope
+    /// (None reverts to the room type default)
+    pub fn set_room_conditioned(&mut self, room_id: RoomId, conditioned: Option<bool>) -> Result<()> {
+        let room = self.rooms.get_mut(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+        room.conditioned = conditioned;
+        let level_id = room.level_id;
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(
+                project_id,
+                EventKind::RoomConditioningSet { room_id, conditioned },
+            );
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
         Ok(())
     }
 
@@ -744,30 +1586,47 @@ impl Store {
         height: f64,
         sill_height: f64,
     ) -> Result<OpeningId> {
+        self.insert_opening(Opening::new(wall_id, opening_type, position, width, height, sill_height))
+    }
+
+    /// Validate and add an opening, recording its catalog unit and
+    /// properties with it
+    pub(super) fn insert_opening(&mut self, opening: Opening) -> Result<OpeningId> {
         // Validate wall exists
+        let wall_id = opening.wall_id;
         let wall = self.walls.get(&wall_id)
             .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
 
         // Validate opening parameters
-        if position < 0.0 || position > 1.0 {
+        if !(0.0..=1.0).contains(&opening.position_along_wall) {
             return Err(anyhow!("Opening position must be between 0.0 and 1.0"));
         }
 
-        if width <= 0.0 {
+        if opening.width <= 0.0 {
             return Err(anyhow!("Opening width must be positive"));
         }
 
-        if height <= 0.0 {
+        if opening.height <= 0.0 {
             return Err(anyhow!("Opening height must be positive"));
         }
 
-        if sill_height < 0.0 {
+        if opening.sill_height < 0.0 {
             return Err(anyhow!("Opening sill height must be non-negative"));
         }
 
-        let opening = Opening::new(wall_id, opening_type.clone(), position, width, height, sill_height);
         let opening_id = opening.id;
         let level_id = wall.level_id;
+        let details = opening.details();
+        let event = EventKind::OpeningAdded {
+            opening_id,
+            wall_id,
+            opening_type: opening.opening_type.clone(),
+            position: opening.position_along_wall,
+            width: opening.width,
+            height: opening.height,
+            sill_height: opening.sill_height,
+            details: (!details.is_empty()).then_some(details),
+        };
 
         // Get project_id for event recording
         let project_id = {
@@ -777,16 +1636,10 @@ impl Store {
         };
 
         self.openings.insert(opening_id, opening);
+        self.invalidate_wall_framing(wall_id);
 
         if let Some(pid) = project_id {
-            self.record_event(
-                pid,
-                EventKind::OpeningAdded {
-                    opening_id,
-                    wall_id,
-                    opening_type,
-                },
-            );
+            self.record_event(pid, event);
 
             if let Some(project) = self.projects.get_mut(&pid) {
                 project.touch();
@@ -807,12 +1660,55 @@ impl Store {
             .collect()
     }
 
+    /// Slide an opening along its wall (0.0 = start, 1.0 = end)
+    pub fn set_opening_position(&mut self, opening_id: OpeningId, position: f64) -> Result<()> {
+        let wall_id = self.openings.get(&opening_id)
+            .ok_or_else(|| anyhow!("Opening not found: {:?}", opening_id))?
+            .wall_id;
+        self.rehost_opening(opening_id, wall_id, position)
+    }
+
+    /// Put an opening at a position on a wall, which may be another wall
+    /// than the one it's in (as when a wall is split or merged)
+    pub(super) fn rehost_opening(&mut self, opening_id: OpeningId, wall_id: WallId, position: f64) -> Result<()> {
+        if !(0.0..=1.0).contains(&position) {
+            return Err(anyhow!("Opening position must be between 0.0 and 1.0"));
+        }
+        if !self.walls.contains_key(&wall_id) {
+            return Err(anyhow!("Wall not found: {:?}", wall_id));
+        }
+        let opening = self.openings.get_mut(&opening_id)
+            .ok_or_else(|| anyhow!("Opening not found: {:?}", opening_id))?;
+        let old_wall_id = std::mem::replace(&mut opening.wall_id, wall_id);
+        opening.position_along_wall = position;
+        self.invalidate_wall_framing(old_wall_id);
+        self.invalidate_wall_framing(wall_id);
+
+        let project_id = self.walls.get(&wall_id).and_then(|w| self.level_project_id(w.level_id));
+        if let Some(project_id) = project_id {
+            self.record_event(project_id, EventKind::OpeningMoved { opening_id, wall_id, position });
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn remove_opening(&mut self, opening_id: OpeningId) -> Result<()> {
         let opening = self.openings.remove(&opening_id)
             .ok_or_else(|| anyhow!("Opening not found: {:?}", opening_id))?;
 
+        for callout in self.detail_callouts.values_mut() {
+            if callout.opening_id == Some(opening_id) {
+                callout.opening_id = None;
+            }
+        }
+
         // Get project_id for event recording
         let wall_id = opening.wall_id;
+        self.invalidate_wall_framing(wall_id);
         if let Some(wall) = self.walls.get(&wall_id) {
             let level_id = wall.level_id;
             if let Some(level) = self.levels.get(&level_id) {
@@ -891,15 +1787,39 @@ impl Store {
     // ========== Event Log Operations ==========
 
     fn record_event(&mut self, project_id: ProjectId, kind: EventKind) {
-        if let Some(log) = self.event_logs.get_mut(&project_id) {
-            log.push(project_id, kind);
+        // Replayed events are already in the log
+        if self.replaying {
+            return;
         }
+        let Some(log) = self.event_logs.get_mut(&project_id) else {
+            return;
+        };
+        log.push(project_id, kind);
+        if !self.subscribers.is_empty() {
+            let event = log.events().last().cloned();
+            self.broadcast(event.expect("event was just pushed"));
+        }
+        self.maybe_compact(project_id);
+    }
+
+    /// Resolve the owning project of a level (level -> building -> project)
+    pub fn level_project_id(&self, level_id: LevelId) -> Option<ProjectId> {
+        let level = self.levels.get(&level_id)?;
+        self.buildings.get(&level.building_id).map(|b| b.project_id)
     }
 
     pub fn get_event_log(&self, project_id: ProjectId) -> Option<&EventLog> {
         self.event_logs.get(&project_id)
     }
 
+    /// A page of a project's history, see `EventLog::query`
+    pub fn query_events(&self, project_id: ProjectId, query: &EventQuery) -> Result<EventPage<'_>> {
+        self.event_logs
+            .get(&project_id)
+            .map(|log| log.query(query))
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))
+    }
+
     pub fn get_events_since(&self, project_id: ProjectId, after_id: EventId) -> Vec<&Event> {
         self.event_logs
             .get(&project_id)
@@ -907,6 +1827,12 @@ impl Store {
             .unwrap_or_default()
     }
 
+    /// Revision of a project's state, suitable as a cache validator (ETag).
+    /// Undo returns to the revision of the state it restores.
+    pub fn project_revision(&self, project_id: ProjectId) -> Option<EventId> {
+        self.event_logs.get(&project_id).map(|log| log.last_id())
+    }
+
     // ========== Statistics ==========
 
     pub fn get_building_stats(&self, building_id: BuildingId) -> Option<BuildingStats> {
@@ -914,14 +1840,39 @@ impl Store {
         let levels: Vec<&Level> = self.get_building_levels(building_id);
         let footprints: Vec<&Footprint> = building.level_ids
             .iter()
-            .filter_map(|lid| {
-                let level = self.levels.get(lid)?;
-                level.footprint_id.and_then(|fid| self.footprints.get(&fid))
-            })
+            .flat_map(|lid| self.get_level_footprints(*lid))
+            .collect();
+        let rooms: Vec<&Room> = building.level_ids
+            .iter()
+            .flat_map(|lid| self.get_level_rooms(*lid))
+            .collect();
+
+        let mut stats = BuildingStats::compute(building, &levels, &footprints, &rooms);
+        stats.deck_area = self.building_deck_area(building_id);
+        Some(stats)
+    }
+
+    /// Stats for every building in a project, split by occupancy
+    pub fn get_project_stats(&self, project_id: ProjectId) -> Option<ProjectStats> {
+        let project = self.projects.get(&project_id)?;
+        let buildings = project.building_ids
+            .iter()
+            .filter_map(|id| self.get_building_stats(*id))
             .collect();
+        Some(ProjectStats::compute(buildings))
+    }
+}
 
-        Some(BuildingStats::compute(building, &levels, &footprints))
+fn validate_wall_layers(layers: &[WallLayer]) -> Result<()> {
+    for layer in layers {
+        if !layer.thickness.is_finite() || layer.thickness <= 0.0 {
+            return Err(anyhow!("Layer thickness must be positive: {}", layer.material));
+        }
+        if !layer.r_value_per_inch.is_finite() || layer.r_value_per_inch < 0.0 {
+            return Err(anyhow!("Layer R-value per inch must not be negative: {}", layer.material));
+        }
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -971,6 +1922,35 @@ mod tests {
         assert_eq!(log.len(), 3); // 1 create + 2 buildings
     }
 
+    #[test]
+    fn test_query_projects_and_events() {
+        let mut store = Store::new();
+        for name in ["Cabin", "Duplex", "Barn", "Cabin remodel"] {
+            store.create_project(name, UnitSystem::Imperial, CodeRegion::us_irc_2021()).unwrap();
+        }
+
+        let names = |page: ProjectPage| page.projects.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+        let page = store.query_projects(&ProjectQuery { limit: Some(2), ..Default::default() });
+        assert_eq!(page.total, 4);
+        assert_eq!(names(page), vec!["Barn", "Cabin"]);
+        let page = store.query_projects(&ProjectQuery { offset: 2, ..Default::default() });
+        assert_eq!(names(page), vec!["Cabin remodel", "Duplex"]);
+
+        let cabins = store.query_projects(&ProjectQuery {
+            name_contains: Some("CABIN".to_string()),
+            descending: true,
+            ..Default::default()
+        });
+        assert_eq!(cabins.total, 2);
+        assert_eq!(names(cabins), vec!["Cabin remodel", "Cabin"]);
+
+        let project_id = store.query_projects(&ProjectQuery::default()).projects[0].id;
+        store.add_building(project_id, "B1").unwrap();
+        let query = EventQuery { kinds: vec!["building_added".to_string()], ..Default::default() };
+        assert_eq!(store.query_events(project_id, &query).unwrap().total, 1);
+        assert!(store.query_events(ProjectId::new(), &query).is_err());
+    }
+
     #[test]
     fn test_invalid_footprint_rejected() {
         let mut store = Store::new();
@@ -991,6 +1971,81 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_footprint_sketch_edits() {
+        let mut store = Store::new();
+
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "Floor", 0.0, 9.0).unwrap();
+        let footprint_id = store.set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0)).unwrap();
+
+        // Notch the north side and pull the east part in to make an L
+        store.insert_footprint_vertex(footprint_id, 2, Point2::new(25.0, 30.0)).unwrap();
+        store.push_footprint_edge(footprint_id, 2, -15.0).unwrap();
+        let polygon = &store.get_footprint(footprint_id).unwrap().polygon;
+        assert_eq!(polygon.outer.len(), 6);
+        assert!((polygon.area() - 975.0).abs() < 1e-9);
+
+        let index = polygon.outer.iter().position(|p| *p == Point2::new(25.0, 15.0)).unwrap();
+        store.move_footprint_vertex(footprint_id, index, Point2::new(20.0, 15.0)).unwrap();
+        assert!(store.move_footprint_vertex(footprint_id, 6, Point2::new(0.0, 0.0)).is_err());
+        assert!(store.insert_footprint_vertex(footprint_id, 6, Point2::new(0.0, 0.0)).is_err());
+
+        store.remove_footprint_vertex(footprint_id, index).unwrap();
+        assert_eq!(store.get_footprint(footprint_id).unwrap().polygon.outer.len(), 5);
+
+        // Every edit is a footprint event, so undo steps back through them
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_footprint(footprint_id).unwrap().polygon.outer[index], Point2::new(20.0, 15.0));
+    }
+
+    #[test]
+    fn test_multiple_footprints() {
+        let mut store = Store::new();
+
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "Floor", 0.0, 9.0).unwrap();
+        let garage = Polygon2::new(vec![
+            Point2::new(40.0, 0.0),
+            Point2::new(62.0, 0.0),
+            Point2::new(62.0, 22.0),
+            Point2::new(40.0, 22.0),
+        ]);
+        assert!(store.add_auxiliary_footprint(level_id, garage.clone()).is_err());
+
+        let main = store.set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0)).unwrap();
+        let garage_id = store.add_auxiliary_footprint(level_id, garage).unwrap();
+        assert_eq!(store.get_footprint(garage_id).unwrap().role, FootprintRole::Auxiliary);
+
+        // The larger piece stays primary; the rest becomes a wing
+        let (kept, wing) = store.split_footprint(main, Point2::new(30.0, -1.0), Point2::new(30.0, 31.0)).unwrap();
+        assert_eq!(kept, main);
+        assert!((store.get_footprint(main).unwrap().area() - 900.0).abs() < 1e-9);
+        assert!((store.get_footprint(wing).unwrap().area() - 300.0).abs() < 1e-9);
+        assert_eq!(store.get_footprint(wing).unwrap().role, FootprintRole::Auxiliary);
+        assert!(store.split_footprint(main, Point2::new(0.0, 50.0), Point2::new(10.0, 50.0)).is_err());
+
+        let footprints = store.get_level_footprints(level_id);
+        assert_eq!(footprints.len(), 3);
+        assert_eq!(footprints[0].id, main);
+        let stats = store.get_building_stats(building_id).unwrap();
+        assert!((stats.total_area - 1684.0).abs() < 1e-9);
+
+        store.remove_footprint(garage_id).unwrap();
+        assert_eq!(store.get_level_footprints(level_id).len(), 2);
+
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        let ids: Vec<FootprintId> = rebuilt.get_level_footprints(level_id).iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![main, wing]);
+        assert_eq!(rebuilt.get_footprint(wing).unwrap().role, FootprintRole::Auxiliary);
+    }
+
     #[test]
     fn test_building_stats() {
         let mut store = Store::new();
@@ -1009,10 +2064,140 @@ mod tests {
         let stats = store.get_building_stats(building_id).unwrap();
         assert_eq!(stats.level_count, 2);
         assert!((stats.total_area - (1200.0 + 875.0)).abs() < 1e-10);
+        assert!((stats.conditioned_area - stats.total_area).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_conditioned_area_excludes_garage_and_porch() {
+        let mut store = Store::new();
+
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let l1 = store.add_level(building_id, "First", 0.0, 9.0).unwrap();
+        store.set_level_footprint(l1, Polygon2::rectangle(60.0, 30.0)).unwrap();
+
+        store.create_room(l1, RoomType::LivingRoom, "Living", Polygon2::rectangle(30.0, 30.0)).unwrap();
+        store.create_room(l1, RoomType::Garage, "Garage", Polygon2::rectangle(20.0, 24.0)).unwrap();
+        let porch = store
+            .create_room(l1, RoomType::Other("Porch".into()), "Front Porch", Polygon2::rectangle(10.0, 8.0))
+            .unwrap();
+
+        let stats = store.get_building_stats(building_id).unwrap();
+        assert!((stats.total_area - 1800.0).abs() < 1e-10);
+        assert!((stats.unconditioned_area - (480.0 + 80.0)).abs() < 1e-10);
+        assert!((stats.conditioned_area - 1240.0).abs() < 1e-10);
+
+        // An explicit flag overrides the room type
+        store.set_room_conditioned(porch, Some(true)).unwrap();
+        let stats = store.get_building_stats(building_id).unwrap();
+        assert!((stats.conditioned_area - 1320.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_project_stats_with_detached_garage() {
+        let mut store = Store::new();
+
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let house = store.add_building(project_id, "House").unwrap();
+        let garage = store.add_building(project_id, "Garage").unwrap();
+        store.set_building_occupancy(garage, BuildingOccupancy::Garage).unwrap();
+
+        let l1 = store.add_level(house, "First", 0.0, 9.0).unwrap();
+        store.set_level_footprint(l1, Polygon2::rectangle(40.0, 30.0)).unwrap();
+        let g1 = store.add_level(garage, "Slab", 0.0, 10.0).unwrap();
+        store.set_level_footprint(g1, Polygon2::rectangle(24.0, 24.0)).unwrap();
+
+        let stats = store.get_project_stats(project_id).unwrap();
+        assert_eq!(stats.building_count(), 2);
+        assert!((stats.total_area - 1776.0).abs() < 1e-10);
+        assert!((stats.habitable_area - 1200.0).abs() < 1e-10);
+        assert!((stats.accessory_area - 576.0).abs() < 1e-10);
+        assert!((stats.conditioned_area - 1200.0).abs() < 1e-10);
+
+        // Occupancy survives undo/redo replay
+        store.undo(project_id).unwrap();
+        store.redo(project_id).unwrap();
+        assert_eq!(store.get_building(garage).unwrap().occupancy, BuildingOccupancy::Garage);
     }
 
     // ========== Phase 2/3 Tests ==========
 
+    #[test]
+    fn test_update_and_remove_wall_assembly() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test House", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store.create_wall_assembly("Interior", vec![WallLayer::stud_2x4()]).unwrap();
+        let spare = store.create_wall_assembly("Spare", vec![WallLayer::stud_2x4()]).unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(12.0, 0.0), 9.0)
+            .unwrap();
+        store.generate_wall_framing(wall_id).unwrap();
+
+        let revision = store.project_revision(project_id).unwrap();
+        store.update_wall_assembly(assembly_id, "Interior 2x6", vec![WallLayer::stud_2x6()]).unwrap();
+        assert!(store.project_revision(project_id).unwrap() > revision);
+        let changes = store.changes_since(project_id, revision).unwrap();
+        assert_eq!(changes.walls.modified, vec![wall_id]);
+        // Projects that don't use it keep their revision
+        let latest = store.project_revision(project_id);
+        store.update_wall_assembly(spare, "Spare 2x4", vec![WallLayer::stud_2x4()]).unwrap();
+        assert_eq!(store.project_revision(project_id), latest);
+        let assembly = store.get_wall_assembly(assembly_id).unwrap();
+        assert_eq!(assembly.name, "Interior 2x6");
+        assert!((assembly.total_thickness - WallLayer::stud_2x6().thickness).abs() < 1e-10);
+        assert!(store.is_framing_stale(wall_id));
+        assert!(store.update_wall_assembly(assembly_id, "Bad", vec![WallLayer::new("Air", 0.0, false)]).is_err());
+
+        // In use by a wall or as a project default
+        assert!(store.remove_wall_assembly(assembly_id).is_err());
+        store.set_default_assembly(project_id, AssemblyRole::Interior, Some(spare)).unwrap();
+        assert!(store.remove_wall_assembly(spare).is_err());
+        store.set_default_assembly(project_id, AssemblyRole::Interior, None).unwrap();
+        // Still named in the history undo would replay
+        assert!(store.remove_wall_assembly(spare).is_err());
+
+        let unused = store.create_wall_assembly("Unused", vec![WallLayer::stud_2x4()]).unwrap();
+        store.remove_wall_assembly(unused).unwrap();
+        assert!(store.get_wall_assembly(unused).is_none());
+        assert!(store.remove_wall_assembly(unused).is_err());
+    }
+
+    #[test]
+    fn test_undo_after_wall_assembly_removal() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test House", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store.create_wall_assembly("Interior", vec![WallLayer::stud_2x4()]).unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(12.0, 0.0), 9.0)
+            .unwrap();
+        store.remove_wall(wall_id).unwrap();
+
+        // Undoing the removal would bring back a wall built with it
+        assert!(store.remove_wall_assembly(assembly_id).is_err());
+        store.add_level(building_id, "Second Floor", 9.0, 9.0).unwrap();
+        store.undo(project_id).unwrap();
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_wall(wall_id).unwrap().assembly_id, assembly_id);
+        assert!(store.get_wall_assembly(assembly_id).is_some());
+
+        // Nor can it go while the wall's creation waits on the redo stack
+        store.undo(project_id).unwrap();
+        assert!(store.get_wall(wall_id).is_none());
+        assert!(store.remove_wall_assembly(assembly_id).is_err());
+    }
+
     #[test]
     fn test_create_wall_assembly_and_wall() {
         let mut store = Store::new();
@@ -1041,6 +2226,12 @@ mod tests {
         let assembly = store.get_wall_assembly(assembly_id).unwrap();
         assert_eq!(assembly.layers.len(), 4);
         assert!(assembly.total_thickness > 6.0);
+        assert!(assembly.total_r_value() > 19.0);
+
+        // Layers need a thickness and can't conduct better than nothing
+        assert!(store.create_wall_assembly("Bad", vec![WallLayer::new("Air", 0.0, false)]).is_err());
+        let negative = WallLayer::new("Foam", 1.0, false).with_r_value_per_inch(-5.0);
+        assert!(store.create_wall_assembly("Bad", vec![negative]).is_err());
 
         // Create wall
         let wall_id = store
@@ -1096,7 +2287,7 @@ mod tests {
 
         // Create another room
         let boundary2 = Polygon2::rectangle(10.0, 8.0);
-        let room_id2 = store
+        let _room_id2 = store
             .create_room(level_id, RoomType::Bathroom, "Primary Bath", boundary2)
             .unwrap();
 
@@ -1158,7 +2349,7 @@ mod tests {
         assert!((opening.area() - 12.0).abs() < 1e-10);
 
         // Add a door opening
-        let door_id = store
+        let _door_id = store
             .add_opening(
                 wall_id,
                 OpeningType::Door,
@@ -1480,6 +2671,32 @@ mod tests {
         assert!(level.wall_ids.contains(&wall_id2));
     }
 
+    #[test]
+    fn test_create_curved_wall() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Test Wall", vec![WallLayer::stud_2x6()])
+            .unwrap();
+
+        let start = Point2::new(0.0, 0.0);
+        let end = Point2::new(10.0, 0.0);
+        assert!(store.create_curved_wall(level_id, assembly_id, start, end, 4.0, 9.0).is_err());
+        let wall_id = store.create_curved_wall(level_id, assembly_id, start, end, -5.0, 9.0).unwrap();
+
+        let wall = store.get_wall(wall_id).unwrap();
+        assert!((wall.length() - 5.0 * std::f64::consts::PI).abs() < 1e-10);
+        assert!(wall.midpoint().distance_to(&Point2::new(5.0, -5.0)) < 1e-10);
+
+        // The arc survives replay
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        assert_eq!(rebuilt.get_wall(wall_id).unwrap().arc, Some(WallArc { radius: -5.0 }));
+    }
+
     #[test]
     fn test_remove_wall_updates_level_wall_ids() {
         let mut store = Store::new();
@@ -1813,4 +3030,175 @@ mod tests {
         let room = store.get_room(room_id).unwrap();
         assert_eq!(room.bounding_wall_ids.len(), 0);
     }
+
+    #[test]
+    fn test_update_room_boundary_and_meta() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Test Wall", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(15.0, 0.0), 9.0)
+            .unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom 1", Polygon2::rectangle(15.0, 12.0))
+            .unwrap();
+        store.set_room_bounding_walls(room_id, vec![wall_id]).unwrap();
+        store.generate_wall_framing(wall_id).unwrap();
+
+        // Clockwise and closed input is accepted; the room grows by 30 sq ft
+        let points = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(0.0, 14.0),
+            Point2::new(15.0, 14.0),
+            Point2::new(15.0, 0.0),
+            Point2::new(0.0, 0.0),
+        ];
+        let delta = store.update_room_boundary(room_id, points).unwrap();
+        assert!((delta - 30.0).abs() < 1e-9);
+        let room = store.get_room(room_id).unwrap();
+        assert_eq!(room.boundary.outer.len(), 4);
+        assert!(room.boundary.signed_area() > 0.0);
+        assert!(store.is_framing_stale(wall_id));
+
+        let bowtie = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 10.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(0.0, 10.0),
+        ];
+        assert!(store.update_room_boundary(room_id, bowtie).is_err());
+
+        store.update_room_meta(room_id, "Office", RoomType::Office).unwrap();
+        assert!(store.update_room_meta(room_id, " ", RoomType::Office).is_err());
+        let room = store.get_room(room_id).unwrap();
+        assert_eq!(room.name, "Office");
+        assert_eq!(room.room_type, RoomType::Office);
+
+        // Both edits replay and undo
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        assert_eq!(rebuilt.get_room(room_id).unwrap().name, "Office");
+        assert!((rebuilt.get_room(room_id).unwrap().area() - 210.0).abs() < 1e-9);
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_room(room_id).unwrap().name, "Bedroom 1");
+    }
+
+    // ========== Undo/Redo Tests ==========
+
+    #[test]
+    fn test_undo_redo_wall() {
+        let mut store = Store::new();
+
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let opening_id = store
+            .add_opening(wall_id, OpeningType::Window, 0.5, 3.0, 4.0, 3.0)
+            .unwrap();
+
+        // Undo the opening, then the wall
+        store.undo(project_id).unwrap();
+        assert!(store.get_opening(opening_id).is_none());
+        assert!(store.get_wall(wall_id).is_some());
+
+        store.undo(project_id).unwrap();
+        assert!(store.get_wall(wall_id).is_none());
+        assert!(store.get_level(level_id).unwrap().wall_ids.is_empty());
+        assert!(store.can_redo(project_id));
+
+        // Redo brings both back with their original IDs
+        store.redo(project_id).unwrap();
+        store.redo(project_id).unwrap();
+        assert_eq!(store.get_level(level_id).unwrap().wall_ids, vec![wall_id]);
+        let opening = store.get_opening(opening_id).unwrap();
+        assert_eq!(opening.wall_id, wall_id);
+        assert!((opening.width - 3.0).abs() < 1e-10);
+        assert!(!store.can_redo(project_id));
+        assert_eq!(store.get_event_log(project_id).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_undo_removal_restores_entities() {
+        let mut store = Store::new();
+
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let footprint_id = store
+            .set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0))
+            .unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+
+        store.remove_level(level_id).unwrap();
+        assert!(store.get_level(level_id).is_none());
+
+        store.undo(project_id).unwrap();
+        let level = store.get_level(level_id).unwrap();
+        assert_eq!(level.footprint_id, Some(footprint_id));
+        assert_eq!(level.room_ids, vec![room_id]);
+        let room = store.get_room(room_id).unwrap();
+        assert!((room.area() - 120.0).abs() < 1e-10);
+        assert_eq!(store.get_building(building_id).unwrap().level_ids, vec![level_id]);
+    }
+
+    #[test]
+    fn test_undo_limits() {
+        let mut store = Store::new();
+
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+
+        // Project creation can't be undone
+        assert!(!store.can_undo(project_id));
+        assert!(store.undo(project_id).is_err());
+        assert!(store.redo(project_id).is_err());
+
+        store.add_building(project_id, "B1").unwrap();
+        store.undo(project_id).unwrap();
+        assert!(store.get_project(project_id).unwrap().building_ids.is_empty());
+
+        // A new mutation discards the redo stack
+        store.add_building(project_id, "B2").unwrap();
+        assert!(!store.can_redo(project_id));
+        assert!(store.undo(ProjectId::new()).is_err());
+    }
+
+    #[test]
+    fn test_project_revision() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let initial = store.project_revision(project_id).unwrap();
+
+        store.add_building(project_id, "Main").unwrap();
+        let after_add = store.project_revision(project_id).unwrap();
+        assert!(after_add > initial);
+
+        store.undo(project_id).unwrap();
+        assert_eq!(store.project_revision(project_id), Some(initial));
+
+        // A new branch never reuses a revision
+        store.add_building(project_id, "Other").unwrap();
+        assert!(store.project_revision(project_id).unwrap() > after_add);
+        assert!(store.project_revision(ProjectId::new()).is_none());
+    }
 }
@@ -0,0 +1,144 @@
+// Openings placed from the window and door catalog
+// A catalog opening takes its unit size, rough opening, and properties from
+// the entry. The catalog code, rough opening and properties are recorded in
+// the opening's event, so they replay and reload with it.
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Opening Catalog ==========
+
+    /// Add entries from a JSON catalog, replacing those with the same code.
+    /// Returns how many entries were imported.
+    pub fn import_opening_catalog(&mut self, json: &str) -> Result<usize> {
+        let catalog = OpeningCatalog::from_json(json)
+            .map_err(|e| anyhow!("Invalid opening catalog: {}", e))?;
+
+        let sizes = catalog.windows.iter()
+            .map(|w| (&w.code, [w.width, w.height, w.rough_width, w.rough_height]))
+            .chain(catalog.doors.iter().map(|d| (&d.code, [d.width, d.height, d.rough_width, d.rough_height])));
+        for (code, dimensions) in sizes {
+            if dimensions.iter().any(|d| !d.is_finite() || *d <= 0.0) {
+                return Err(anyhow!("Catalog entry {} needs positive sizes", code));
+            }
+        }
+
+        let count = catalog.windows.len() + catalog.doors.len();
+        self.opening_catalog.merge(catalog);
+        Ok(count)
+    }
+
+    /// Place a catalog window or door on a wall. `sill_height` (feet) is
+    /// used for windows; doors sit on the floor.
+    pub fn add_catalog_opening(
+        &mut self,
+        wall_id: WallId,
+        code: &str,
+        position: f64,
+        sill_height: f64,
+    ) -> Result<OpeningId> {
+        let (opening_type, width, height, sill_height, rough_size) = if let Some(window) = self.opening_catalog.window(code) {
+            (OpeningType::Window, window.width, window.height, sill_height, (window.rough_width, window.rough_height))
+        } else if let Some(door) = self.opening_catalog.door(code) {
+            (OpeningType::Door, door.width, door.height, 0.0, (door.rough_width, door.rough_height))
+        } else {
+            return Err(anyhow!("Opening catalog entry not found: {}", code));
+        };
+        let details = OpeningDetails {
+            catalog_code: Some(code.to_string()),
+            rough_size: Some((rough_size.0 / 12.0, rough_size.1 / 12.0)),
+            window_properties: self.opening_catalog.window(code).map(|w| WindowProperties::new(w.u_value, w.shgc)),
+            door_properties: self.opening_catalog.door(code).map(|d| DoorProperties::new(d.is_exterior, d.fire_rating)),
+        };
+
+        let mut opening = Opening::new(wall_id, opening_type, position, width, height, sill_height);
+        opening.set_details(details);
+        self.insert_opening(opening)
+    }
+
+    /// Whether a catalog window qualifies as an emergency escape opening
+    /// where it is placed. None for openings not placed from the catalog or
+    /// that aren't windows.
+    pub fn opening_meets_egress(&self, opening_id: OpeningId) -> Option<bool> {
+        let opening = self.openings.get(&opening_id)?;
+        let window = self.opening_catalog.window(opening.catalog_code.as_deref()?)?;
+        Some(window.meets_egress(opening.sill_height * 12.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryBackend;
+
+    #[test]
+    fn test_add_catalog_opening() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+
+        let door = store.add_catalog_opening(wall_id, "3068", 0.25, 3.0).unwrap();
+        let door_opening = store.get_opening(door).unwrap();
+        assert_eq!(door_opening.opening_type, OpeningType::Door);
+        assert_eq!(door_opening.sill_height, 0.0);
+        assert!(door_opening.door_properties.as_ref().unwrap().is_exterior);
+        assert_eq!(store.opening_meets_egress(door), None);
+
+        // A bedroom window with its sill at 2'
+        let window = store.add_catalog_opening(wall_id, "3060", 0.75, 2.0).unwrap();
+        assert_eq!(store.opening_meets_egress(window), Some(true));
+        assert!(store.add_catalog_opening(wall_id, "9999", 0.5, 2.0).is_err());
+
+        // Framing uses the manufacturer's rough opening
+        let layout_id = store.generate_wall_framing(wall_id).unwrap();
+        let layout = store.get_framing_layout(layout_id).unwrap();
+        let header = layout.headers.iter().find(|h| h.opening_id == window).unwrap();
+        assert!((header.span - 36.0).abs() < 1e-9);
+
+        // Imported entries replace stock ones and can be placed
+        let json = r#"{"windows": [{"code": "3060", "name": "3060 Fixed", "width": 2.958,
+            "height": 5.958, "rough_width": 36.0, "rough_height": 72.0}]}"#;
+        assert_eq!(store.import_opening_catalog(json).unwrap(), 1);
+        assert_eq!(store.opening_meets_egress(window), Some(false));
+        assert!(store.import_opening_catalog(r#"{"doors": [{"code": "x", "name": "x", "width": 0.0,
+            "height": 6.0, "rough_width": 2.0, "rough_height": 82.0}]}"#).is_err());
+
+        // Catalog details are recorded with the opening, so they replay on
+        // redo and reload from storage
+        let small = store.add_catalog_opening(wall_id, "2030", 0.5, 3.0).unwrap();
+        store.undo(project_id).unwrap();
+        assert!(store.get_opening(small).is_none());
+        store.redo(project_id).unwrap();
+        let redone = store.get_opening(small).unwrap();
+        assert_eq!(redone.catalog_code.as_deref(), Some("2030"));
+        assert!(redone.rough_size.is_some());
+        assert!(redone.window_properties.is_some());
+        assert_eq!(store.get_opening(window).unwrap().catalog_code.as_deref(), Some("3060"));
+
+        let mut backend = MemoryBackend::new();
+        store.save_all_to(&mut backend).unwrap();
+        let reloaded = Store::load_from(&backend).unwrap();
+        for id in [door, small] {
+            let before = store.get_opening(id).unwrap();
+            let after = reloaded.get_opening(id).unwrap();
+            assert_eq!(after.catalog_code, before.catalog_code);
+            assert_eq!(after.rough_size, before.rough_size);
+            assert_eq!(after.window_properties.is_some(), before.window_properties.is_some());
+            assert_eq!(after.door_properties.is_some(), before.door_properties.is_some());
+        }
+        assert_eq!(reloaded.opening_meets_egress(small), store.opening_meets_egress(small));
+        assert!(reloaded.opening_meets_egress(small).is_some());
+    }
+}
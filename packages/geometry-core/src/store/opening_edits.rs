@@ -0,0 +1,179 @@
+// Opening editing
+// An opening is moved, resized or retyped in place, so it keeps its id and
+// with it any detail callouts and the framing of its wall, which is marked
+// stale rather than dropped.
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+/// Slack (feet) before an opening counts as past the wall's end or into a
+/// neighbouring opening
+const FIT_TOLERANCE: f64 = 1e-9;
+
+impl Store {
+    // ========== Opening Edits ==========
+
+    /// Change an opening's position, size, sill or type. The result must lie
+    /// within its wall and clear the wall's other openings.
+    pub fn update_opening(&mut self, opening_id: OpeningId, edit: OpeningEdit) -> Result<()> {
+        let opening = self.openings.get(&opening_id)
+            .ok_or_else(|| anyhow!("Opening not found: {:?}", opening_id))?;
+        let wall = self.walls.get(&opening.wall_id)
+            .ok_or_else(|| anyhow!("Wall not found: {:?}", opening.wall_id))?;
+
+        let position = edit.position.unwrap_or(opening.position_along_wall);
+        let width = edit.width.unwrap_or(opening.width);
+        let height = edit.height.unwrap_or(opening.height);
+        let sill_height = edit.sill_height.unwrap_or(opening.sill_height);
+
+        if !(0.0..=1.0).contains(&position) {
+            return Err(anyhow!("Opening position must be between 0.0 and 1.0"));
+        }
+        if width <= 0.0 {
+            return Err(anyhow!("Opening width must be positive"));
+        }
+        if height <= 0.0 {
+            return Err(anyhow!("Opening height must be positive"));
+        }
+        if sill_height < 0.0 {
+            return Err(anyhow!("Opening sill height must be non-negative"));
+        }
+
+        let length = wall.length();
+        let center = position * length;
+        if center - width / 2.0 < -FIT_TOLERANCE || center + width / 2.0 > length + FIT_TOLERANCE {
+            return Err(anyhow!("Opening must stay within the wall's length"));
+        }
+        if sill_height + height > wall.height + FIT_TOLERANCE {
+            return Err(anyhow!("Opening must stay below the top of the wall"));
+        }
+
+        let overlapping = self.get_wall_openings(wall.id)
+            .into_iter()
+            .filter(|other| other.id != opening_id)
+            .find(|other| {
+                let gap = (other.position_along_wall * length - center).abs();
+                gap < (other.width + width) / 2.0 - FIT_TOLERANCE
+            });
+        if let Some(other) = overlapping {
+            return Err(anyhow!("Opening would overlap opening {:?}", other.id));
+        }
+
+        self.apply_opening_edit(opening_id, &edit)
+    }
+
+    /// Write an edit to an opening and record it, without checking how it
+    /// fits on its wall
+    pub(super) fn apply_opening_edit(&mut self, opening_id: OpeningId, edit: &OpeningEdit) -> Result<()> {
+        let opening = self.openings.get_mut(&opening_id)
+            .ok_or_else(|| anyhow!("Opening not found: {:?}", opening_id))?;
+
+        let resized = edit.width.is_some_and(|w| w != opening.width)
+            || edit.height.is_some_and(|h| h != opening.height);
+        let retyped = edit.opening_type.as_ref().is_some_and(|t| *t != opening.opening_type);
+
+        if let Some(position) = edit.position {
+            opening.position_along_wall = position;
+        }
+        if let Some(width) = edit.width {
+            opening.width = width;
+        }
+        if let Some(height) = edit.height {
+            opening.height = height;
+        }
+        if let Some(sill_height) = edit.sill_height {
+            opening.sill_height = sill_height;
+        }
+        if let Some(opening_type) = &edit.opening_type {
+            opening.opening_type = opening_type.clone();
+        }
+
+        // A resized or retyped opening is no longer the catalog unit it was
+        // placed from, and a retyped one drops the other type's properties
+        if resized || retyped {
+            opening.catalog_code = None;
+            opening.rough_size = None;
+        }
+        if retyped {
+            if opening.opening_type != OpeningType::Window {
+                opening.window_properties = None;
+            }
+            if opening.opening_type != OpeningType::Door {
+                opening.door_properties = None;
+            }
+        }
+
+        let wall_id = opening.wall_id;
+        let event = EventKind::OpeningModified {
+            opening_id,
+            wall_id,
+            opening_type: opening.opening_type.clone(),
+            position: opening.position_along_wall,
+            width: opening.width,
+            height: opening.height,
+            sill_height: opening.sill_height,
+        };
+        self.invalidate_wall_framing(wall_id);
+
+        let project_id = self.walls.get(&wall_id).and_then(|w| self.level_project_id(w.level_id));
+        if let Some(project_id) = project_id {
+            self.record_event(project_id, event);
+
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_opening() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let window = store.add_catalog_opening(wall, "3050", 0.25, 3.0).unwrap();
+        store.add_opening(wall, OpeningType::Door, 0.75, 3.0, 6.8, 0.0).unwrap();
+        store.generate_wall_framing(wall).unwrap();
+
+        // Sliding towards the door is fine until the two would overlap
+        store.update_opening(window, OpeningEdit { position: Some(0.5), ..Default::default() }).unwrap();
+        assert!(store.update_opening(window, OpeningEdit { position: Some(0.7), ..Default::default() }).is_err());
+        assert!(store.update_opening(window, OpeningEdit { position: Some(0.02), ..Default::default() }).is_err());
+        assert!(store.update_opening(window, OpeningEdit { height: Some(7.0), ..Default::default() }).is_err());
+        assert_eq!(store.get_opening(window).unwrap().catalog_code.as_deref(), Some("3050"));
+
+        // Resizing leaves the catalog unit behind; retyping drops the
+        // window's properties
+        store
+            .update_opening(window, OpeningEdit { width: Some(4.0), opening_type: Some(OpeningType::Door), sill_height: Some(0.0), ..Default::default() })
+            .unwrap();
+        let opening = store.get_opening(window).unwrap();
+        assert_eq!(opening.width, 4.0);
+        assert_eq!(opening.opening_type, OpeningType::Door);
+        assert!(opening.catalog_code.is_none() && opening.window_properties.is_none());
+        assert!(store.is_framing_stale(wall));
+
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        let replayed = rebuilt.get_opening(window).unwrap();
+        assert_eq!(replayed.position_along_wall, 0.5);
+        assert_eq!(replayed.width, 4.0);
+        assert_eq!(replayed.opening_type, OpeningType::Door);
+    }
+}
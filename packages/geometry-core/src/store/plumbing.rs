@@ -0,0 +1,236 @@
+// Plumbing fixtures, their clearances and wet-wall grouping
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::electrical::on_boundary;
+use super::room_derivation::contains;
+use super::Store;
+
+impl Store {
+    // ========== Plumbing Fixture Operations ==========
+
+    /// Place a fixture in a room with its back edge centered on `location`,
+    /// facing `rotation` degrees from the +X axis. The location must be in
+    /// the room or on its boundary.
+    pub fn add_fixture(
+        &mut self,
+        room_id: RoomId,
+        kind: PlumbingFixtureKind,
+        location: Point2,
+        rotation: f64,
+    ) -> Result<FixtureId> {
+        let room = self.rooms.get(&room_id)
+            .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+        if !rotation.is_finite() {
+            return Err(anyhow!("Rotation must be a number of degrees"));
+        }
+        if !contains(&room.boundary, &location) && !on_boundary(&room.boundary.outer, &location) {
+            return Err(anyhow!("Fixture location is outside room {}", room.name));
+        }
+        let level_id = room.level_id;
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+        let fixture = PlumbingFixture::new(level_id, room_id, kind, location, rotation.rem_euclid(360.0));
+        let fixture_id = fixture.id;
+
+        self.record_event(project_id, EventKind::FixtureAdded { fixture: fixture.clone() });
+        self.plumbing_fixtures.insert(fixture_id, fixture);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(fixture_id)
+    }
+
+    pub fn get_fixture(&self, id: FixtureId) -> Option<&PlumbingFixture> {
+        self.plumbing_fixtures.get(&id)
+    }
+
+    /// Fixtures on a level, by kind
+    pub fn get_level_fixtures(&self, level_id: LevelId) -> Vec<&PlumbingFixture> {
+        let mut fixtures: Vec<&PlumbingFixture> = self.plumbing_fixtures
+            .values()
+            .filter(|f| f.level_id == level_id)
+            .collect();
+        fixtures.sort_by_key(|f| (f.kind, f.id.to_string()));
+        fixtures
+    }
+
+    pub fn get_room_fixtures(&self, room_id: RoomId) -> Vec<&PlumbingFixture> {
+        let mut fixtures: Vec<&PlumbingFixture> = self.plumbing_fixtures
+            .values()
+            .filter(|f| f.room_id == room_id)
+            .collect();
+        fixtures.sort_by_key(|f| (f.kind, f.id.to_string()));
+        fixtures
+    }
+
+    pub fn remove_fixture(&mut self, fixture_id: FixtureId) -> Result<()> {
+        let fixture = self.plumbing_fixtures.remove(&fixture_id)
+            .ok_or_else(|| anyhow!("Fixture not found: {:?}", fixture_id))?;
+
+        if let Some(project_id) = self.level_project_id(fixture.level_id) {
+            self.record_event(project_id, EventKind::FixtureRemoved { fixture_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fixtures on a level whose clear space runs outside their room or is
+    /// blocked by another fixture
+    pub fn check_fixture_clearances(&self, level_id: LevelId) -> Vec<FixtureClearance> {
+        let fixtures = self.get_level_fixtures(level_id);
+        fixtures
+            .iter()
+            .filter_map(|fixture| {
+                let room = self.rooms.get(&fixture.room_id)?;
+                let space = fixture.clear_space();
+                let center = Point2::new(
+                    space.iter().map(|p| p.x).sum::<f64>() / space.len() as f64,
+                    space.iter().map(|p| p.y).sum::<f64>() / space.len() as f64,
+                );
+                // Corners and edge midpoints, nudged inward off the room boundary
+                let samples = (0..space.len()).flat_map(|i| {
+                    let (a, b) = (space[i], space[(i + 1) % space.len()]);
+                    [a, Point2::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0)]
+                });
+                let outside_room = samples
+                    .map(|p| Point2::new(p.x + (center.x - p.x) * 0.01, p.y + (center.y - p.y) * 0.01))
+                    .any(|p| !contains(&room.boundary, &p));
+                let obstructed_by: Vec<FixtureId> = fixtures
+                    .iter()
+                    .filter(|other| fixture.obstructed_by(other))
+                    .map(|other| other.id)
+                    .collect();
+
+                (outside_room || !obstructed_by.is_empty()).then_some(FixtureClearance {
+                    fixture_id: fixture.id,
+                    room_id: fixture.room_id,
+                    kind: fixture.kind,
+                    outside_room,
+                    obstructed_by,
+                })
+            })
+            .collect()
+    }
+
+    /// Group a level's fixtures by the wall behind them. Fixtures on either
+    /// face of the same wall share a rough-in; a fixture on no wall needs
+    /// its own.
+    pub fn wet_walls(&self, level_id: LevelId) -> WetWallAnalysis {
+        let fixtures = self.get_level_fixtures(level_id);
+        let mut groups: Vec<WetWallGroup> = Vec::new();
+        for fixture in &fixtures {
+            let wall_id = self.wall_at(level_id, &fixture.location);
+            let group = match groups.iter_mut().position(|g| wall_id.is_some() && g.wall_id == wall_id) {
+                Some(index) => &mut groups[index],
+                None => {
+                    groups.push(WetWallGroup { wall_id, fixture_ids: Vec::new(), room_ids: Vec::new() });
+                    groups.last_mut().unwrap()
+                }
+            };
+            group.fixture_ids.push(fixture.id);
+            if !group.room_ids.contains(&fixture.room_id) {
+                group.room_ids.push(fixture.room_id);
+            }
+        }
+        groups.sort_by_key(|g| std::cmp::Reverse(g.fixture_ids.len()));
+
+        WetWallAnalysis {
+            level_id,
+            shared_fixture_count: groups.iter().filter(|g| g.is_shared()).map(|g| g.fixture_ids.len()).sum(),
+            fixture_count: fixtures.len(),
+            groups,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An 8' x 8' bath and a 10' x 8' laundry back to back across a
+    /// partition at x = 8
+    fn setup() -> (Store, ProjectId, LevelId, RoomId, RoomId, WallId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Partition", vec![WallLayer::stud_2x4()])
+            .unwrap();
+        let partition = store
+            .create_wall(level_id, assembly_id, Point2::new(8.0, 0.0), Point2::new(8.0, 8.0), 9.0)
+            .unwrap();
+        let bath = store
+            .create_room(level_id, RoomType::Bathroom, "Bath", Polygon2::rectangle(8.0, 8.0))
+            .unwrap();
+        let laundry_outline = Polygon2::new(vec![
+            Point2::new(8.0, 0.0),
+            Point2::new(18.0, 0.0),
+            Point2::new(18.0, 8.0),
+            Point2::new(8.0, 8.0),
+        ]);
+        let laundry = store.create_room(level_id, RoomType::Laundry, "Laundry", laundry_outline).unwrap();
+        (store, project_id, level_id, bath, laundry, partition)
+    }
+
+    #[test]
+    fn test_wet_wall_grouping() {
+        let (mut store, project_id, level_id, bath, laundry, partition) = setup();
+        // Toilet and sink on the bath side of the partition, facing west; a
+        // laundry sink back to back with them; a tub along the west edge
+        let face = 8.0 - 1.75 / 12.0;
+        store.add_fixture(bath, PlumbingFixtureKind::Toilet, Point2::new(face, 2.0), 180.0).unwrap();
+        store.add_fixture(bath, PlumbingFixtureKind::Sink, Point2::new(face, 5.0), 180.0).unwrap();
+        store.add_fixture(laundry, PlumbingFixtureKind::Sink, Point2::new(8.0 + 1.75 / 12.0, 4.0), 0.0).unwrap();
+        let tub = store.add_fixture(bath, PlumbingFixtureKind::Tub, Point2::new(0.0, 4.0), 0.0).unwrap();
+
+        let analysis = store.wet_walls(level_id);
+        assert_eq!(analysis.fixture_count, 4);
+        assert_eq!(analysis.rough_in_count(), 2);
+        assert_eq!(analysis.shared_fixture_count, 3);
+        assert_eq!(analysis.groups[0].wall_id, Some(partition));
+        assert_eq!(analysis.groups[0].room_ids, vec![bath, laundry]);
+        assert_eq!(analysis.groups[1].fixture_ids, vec![tub]);
+        assert!(store.check_fixture_clearances(level_id).is_empty());
+
+        assert!(store
+            .add_fixture(bath, PlumbingFixtureKind::Toilet, Point2::new(12.0, 4.0), 0.0)
+            .is_err());
+        store.undo(project_id).unwrap();
+        assert!(store.get_fixture(tub).is_none());
+    }
+
+    #[test]
+    fn test_fixture_clearances() {
+        let (mut store, project_id, level_id, bath, _, _) = setup();
+        // 12" from the side wall leaves the toilet short of its 15"
+        let toilet = store.add_fixture(bath, PlumbingFixtureKind::Toilet, Point2::new(1.0, 0.0), 90.0).unwrap();
+        let sink = store.add_fixture(bath, PlumbingFixtureKind::Sink, Point2::new(3.0, 0.0), 90.0).unwrap();
+        let issues = store.check_fixture_clearances(level_id);
+        assert_eq!(issues.len(), 1);
+        let toilet_issue = &issues[0];
+        assert_eq!(toilet_issue.fixture_id, toilet);
+        assert!(toilet_issue.outside_room);
+        assert_eq!(toilet_issue.obstructed_by, vec![sink]);
+        assert!(toilet_issue.message().contains("outside the room"));
+
+        store.remove_fixture(toilet).unwrap();
+        assert!(store.check_fixture_clearances(level_id).is_empty());
+
+        // Fixtures go with their room
+        store.remove_room(bath).unwrap();
+        assert!(store.get_fixture(sink).is_none());
+        store.undo(project_id).unwrap();
+        assert!(store.get_fixture(sink).is_some());
+    }
+}
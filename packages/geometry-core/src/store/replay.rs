@@ -0,0 +1,863 @@
+// Event replay for undo/redo
+// Undo pops the last event and rebuilds the project from the remaining log;
+// redo re-applies the popped event. Events carry the IDs of the entities they
+// created, so a rebuilt project keeps the same IDs the UI already holds.
+
+use std::collections::{HashMap, HashSet};
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Undo/Redo Operations ==========
+
+    pub fn can_undo(&self, project_id: ProjectId) -> bool {
+        self.event_logs
+            .get(&project_id)
+            .map(|log| log.can_undo())
+            .unwrap_or(false)
+    }
+
+    pub fn can_redo(&self, project_id: ProjectId) -> bool {
+        self.event_logs
+            .get(&project_id)
+            .map(|log| log.can_redo())
+            .unwrap_or(false)
+    }
+
+    /// Revert the most recent event of a project
+    pub fn undo(&mut self, project_id: ProjectId) -> Result<()> {
+        let log = self.event_logs.get_mut(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+        log.undo().ok_or_else(|| anyhow!("Nothing to undo"))?;
+
+        self.rebuild_project(project_id)
+    }
+
+    /// Re-apply the most recently undone event of a project
+    pub fn redo(&mut self, project_id: ProjectId) -> Result<()> {
+        let log = self.event_logs.get_mut(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+        let event = log.redo().cloned().ok_or_else(|| anyhow!("Nothing to redo"))?;
+
+        self.replay_events(std::slice::from_ref(&event))
+    }
+
+    /// Reconstruct a store holding the project recorded in `event_log`.
+    /// Wall assemblies are shared across projects rather than logged, so the
+    /// rebuilt walls and project defaults reference assembly IDs that live in
+    /// the source store; replay applies them without looking them up.
+    pub fn rebuild_from_events(event_log: &EventLog) -> Result<Store> {
+        let first = event_log.events()
+            .first()
+            .ok_or_else(|| anyhow!("Event log is empty"))?;
+        if !matches!(first.kind, EventKind::ProjectCreated { .. }) {
+            return Err(anyhow!("Event log must start with project creation"));
+        }
+        let project_id = first.project_id;
+        if event_log.events().iter().any(|e| e.project_id != project_id) {
+            return Err(anyhow!("Event log mixes events from several projects"));
+        }
+
+        let mut store = Store::new();
+        store.replay_events(event_log.events())?;
+        store.event_logs.insert(project_id, event_log.clone());
+
+        Ok(store)
+    }
+
+    /// Apply events in order without recording them again
+    fn replay_events(&mut self, events: &[Event]) -> Result<()> {
+        self.replaying = true;
+        let result = events.iter().try_for_each(|event| self.apply_event(event));
+        self.replaying = false;
+
+        // Wall joins are resolved once at the end rather than per wall
+        let level_ids: HashSet<LevelId> = self.walls.values().map(|w| w.level_id).collect();
+        for level_id in level_ids {
+            self.refresh_wall_joins(level_id);
+        }
+        result
+    }
+
+    /// Drop a project's entities and replay its event log from the start.
+    /// State that isn't recorded as events (framing config, bounding walls,
+    /// finishes) is carried over for entities that survive the replay.
+    pub(super) fn rebuild_project(&mut self, project_id: ProjectId) -> Result<()> {
+        let events = self.event_logs
+            .get(&project_id)
+            .map(|log| log.events().to_vec())
+            .unwrap_or_default();
+
+        let (walls, rooms, openings) = self.remove_project_entities(project_id);
+
+        self.replay_events(&events)?;
+        self.restore_unlogged_state(walls, rooms, openings);
+        Ok(())
+    }
+
+    /// Carry state that isn't recorded as events over from earlier copies
+    /// of walls, rooms and openings onto the ones a replay rebuilt. Copies
+    /// of entities that didn't come back are dropped.
+    pub(super) fn restore_unlogged_state(
+        &mut self,
+        walls: HashMap<WallId, Wall>,
+        rooms: HashMap<RoomId, Room>,
+        openings: HashMap<OpeningId, Opening>,
+    ) {
+        for (wall_id, old) in walls {
+            if let Some(wall) = self.walls.get_mut(&wall_id) {
+                wall.base_offset = old.base_offset;
+                wall.framing_config = old.framing_config;
+                wall.framing_layout_id = old.framing_layout_id;
+                // Replay may have changed the wall's openings or joins
+                self.invalidate_wall_framing(wall_id);
+            } else {
+                if let Some(layout_id) = old.framing_layout_id {
+                    self.framing_layouts.remove(&layout_id);
+                }
+                self.regeneration.mark_clean(wall_id);
+            }
+        }
+        self.floor_framing_layouts.retain(|level_id, _| self.levels.contains_key(level_id));
+
+        for (room_id, old) in rooms {
+            let wall_ids: Vec<WallId> = old.bounding_wall_ids
+                .into_iter()
+                .filter(|id| self.walls.contains_key(id))
+                .collect();
+            if let Some(room) = self.rooms.get_mut(&room_id) {
+                room.floor_finish = old.floor_finish;
+                room.ceiling_height = old.ceiling_height;
+                room.bounding_wall_ids = wall_ids;
+            }
+        }
+
+        for (opening_id, old) in openings {
+            if let Some(opening) = self.openings.get_mut(&opening_id) {
+                opening.window_properties = old.window_properties;
+                opening.door_properties = old.door_properties;
+                opening.catalog_code = old.catalog_code;
+                opening.rough_size = old.rough_size;
+            }
+        }
+    }
+
+    /// Remove a project and everything it owns (the event log is kept)
+    #[allow(clippy::type_complexity)]
+    fn remove_project_entities(
+        &mut self,
+        project_id: ProjectId,
+    ) -> (HashMap<WallId, Wall>, HashMap<RoomId, Room>, HashMap<OpeningId, Opening>) {
+        let mut walls = HashMap::new();
+        let mut rooms = HashMap::new();
+        let mut openings = HashMap::new();
+
+        let Some(project) = self.projects.remove(&project_id) else {
+            return (walls, rooms, openings);
+        };
+
+        self.saved_filters.retain(|_, f| f.project_id != project_id);
+        self.saved_scripts.retain(|_, s| s.project_id != project_id);
+        self.constraints.retain(|_, c| c.project_id != project_id);
+
+        if let Some(site_id) = project.site_id {
+            self.sites.remove(&site_id);
+        }
+
+        for building_id in project.building_ids {
+            self.grids.remove(&building_id);
+            let Some(building) = self.buildings.remove(&building_id) else {
+                continue;
+            };
+            self.hvac_zones.retain(|_, z| z.building_id != building_id);
+
+            for level_id in building.level_ids {
+                if let Some(level) = self.levels.remove(&level_id) {
+                    for footprint_id in level.footprint_ids() {
+                        self.footprints.remove(&footprint_id);
+                    }
+                }
+
+                self.detail_callouts.retain(|_, c| c.level_id != level_id);
+                self.roofs.retain(|_, r| r.level_id != level_id);
+                self.stairs.retain(|_, s| s.from_level_id != level_id && s.to_level_id != level_id);
+                self.floor_slabs.retain(|_, s| s.level_id != level_id);
+                self.ceilings.retain(|_, c| c.level_id != level_id);
+                self.foundations.retain(|_, f| f.level_id != level_id);
+                self.beams.retain(|_, b| b.level_id != level_id);
+                self.posts.retain(|_, p| p.level_id != level_id);
+                self.electrical_devices.retain(|_, d| d.level_id != level_id);
+                self.plumbing_fixtures.retain(|_, f| f.level_id != level_id);
+                self.decks.retain(|_, d| d.level_id != level_id);
+                self.garages.retain(|_, g| g.level_id != level_id);
+
+                let wall_ids: Vec<WallId> = self.walls
+                    .values()
+                    .filter(|w| w.level_id == level_id)
+                    .map(|w| w.id)
+                    .collect();
+                for wall_id in wall_ids {
+                    if let Some(wall) = self.walls.remove(&wall_id) {
+                        walls.insert(wall_id, wall);
+                    }
+                }
+
+                let room_ids: Vec<RoomId> = self.rooms
+                    .values()
+                    .filter(|r| r.level_id == level_id)
+                    .map(|r| r.id)
+                    .collect();
+                for room_id in room_ids {
+                    if let Some(room) = self.rooms.remove(&room_id) {
+                        rooms.insert(room_id, room);
+                    }
+                }
+            }
+        }
+
+        let opening_ids: Vec<OpeningId> = self.openings
+            .values()
+            .filter(|o| walls.contains_key(&o.wall_id))
+            .map(|o| o.id)
+            .collect();
+        for opening_id in opening_ids {
+            if let Some(opening) = self.openings.remove(&opening_id) {
+                openings.insert(opening_id, opening);
+            }
+        }
+
+        (walls, rooms, openings)
+    }
+
+    /// Apply a logged event to the store, reusing the IDs it recorded
+    pub(crate) fn apply_event(&mut self, event: &Event) -> Result<()> {
+        let project_id = event.project_id;
+
+        match &event.kind {
+            EventKind::ProjectCreated { name, units, code_region } => {
+                let mut project = Project::new(name.clone(), *units, code_region.clone());
+                project.id = project_id;
+                project.created_at = event.timestamp;
+                self.projects.insert(project_id, project);
+            }
+            EventKind::ProjectRenamed { new_name, .. } => {
+                let project = self.projects.get_mut(&project_id)
+                    .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+                project.name = new_name.clone();
+            }
+
+            EventKind::ProjectCodeRegionChanged { new_region, .. } => {
+                self.set_project_code_region(project_id, new_region.clone())?;
+            }
+            EventKind::ProjectPriceRegionSet { region } => {
+                self.set_project_price_region(project_id, region.clone())?;
+            }
+            EventKind::ProjectMarkupsSet { markups } => {
+                self.set_project_markups(project_id, *markups)?;
+            }
+
+            EventKind::SiteCreated { site_id } => {
+                let project = self.projects.get_mut(&project_id)
+                    .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+                let mut site = Site::new(project_id);
+                site.id = *site_id;
+                project.site_id = Some(*site_id);
+                self.sites.insert(*site_id, site);
+            }
+            EventKind::SiteBoundarySet { site_id, boundary } => {
+                self.set_site_boundary(*site_id, boundary.clone())?;
+            }
+            EventKind::SiteSetbacksUpdated { site_id, front, back, left, right } => {
+                let site = self.sites.get_mut(site_id)
+                    .ok_or_else(|| anyhow!("Site not found: {:?}", site_id))?;
+                site.setbacks = Setbacks {
+                    front: *front,
+                    back: *back,
+                    left: *left,
+                    right: *right,
+                };
+            }
+            EventKind::SiteZoningSet { site_id, zoning } => {
+                self.set_site_zoning(*site_id, zoning.clone())?;
+            }
+            EventKind::SiteNorthAngleSet { site_id, north_angle } => {
+                self.set_site_north_angle(*site_id, *north_angle)?;
+            }
+            EventKind::SiteTerrainSet { site_id, points } => {
+                self.set_site_terrain(*site_id, points.clone())?;
+            }
+
+            EventKind::BuildingAdded { building_id, name } => {
+                let project = self.projects.get_mut(&project_id)
+                    .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+                let mut building = Building::new(project_id, name.clone());
+                building.id = *building_id;
+                project.building_ids.push(*building_id);
+                self.buildings.insert(*building_id, building);
+            }
+            EventKind::BuildingRenamed { building_id, new_name, .. } => {
+                let building = self.buildings.get_mut(building_id)
+                    .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+                building.name = new_name.clone();
+            }
+            EventKind::BuildingRemoved { building_id } => {
+                self.remove_building(*building_id)?;
+            }
+            EventKind::BuildingOccupancySet { building_id, occupancy } => {
+                self.set_building_occupancy(*building_id, *occupancy)?;
+            }
+            EventKind::BuildingPadElevationSet { building_id, pad_elevation } => {
+                self.set_building_pad_elevation(*building_id, *pad_elevation)?;
+            }
+
+            EventKind::LevelAdded { level_id, building_id, name, elevation, floor_to_floor } => {
+                let building = self.buildings.get_mut(building_id)
+                    .ok_or_else(|| anyhow!("Building not found: {:?}", building_id))?;
+                let mut level = Level::new(*building_id, name.clone(), *elevation, *floor_to_floor);
+                level.id = *level_id;
+                building.level_ids.push(*level_id);
+                self.levels.insert(*level_id, level);
+            }
+            EventKind::LevelModified { level_id, name, elevation, floor_to_floor } => {
+                let level = self.levels.get_mut(level_id)
+                    .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+                if let Some(name) = name {
+                    level.name = name.clone();
+                }
+                if let Some(elevation) = elevation {
+                    level.elevation = *elevation;
+                }
+                if let Some(floor_to_floor) = floor_to_floor {
+                    level.floor_to_floor = *floor_to_floor;
+                }
+            }
+            EventKind::LevelRemoved { level_id, .. } => {
+                self.remove_level(*level_id)?;
+            }
+
+            EventKind::FootprintSet { footprint_id, level_id, polygon } => {
+                let level = self.levels.get_mut(level_id)
+                    .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+                if let Some(old_id) = level.footprint_id.replace(*footprint_id) {
+                    self.footprints.remove(&old_id);
+                }
+                let mut footprint = Footprint::new(*level_id, polygon.clone());
+                footprint.id = *footprint_id;
+                self.footprints.insert(*footprint_id, footprint);
+            }
+            EventKind::FootprintModified { footprint_id, polygon } => {
+                self.modify_footprint(*footprint_id, polygon.clone())?;
+            }
+            EventKind::FootprintRemoved { footprint_id, level_id } => {
+                self.footprints.remove(footprint_id);
+                if let Some(level) = self.levels.get_mut(level_id) {
+                    if level.footprint_id == Some(*footprint_id) {
+                        level.footprint_id = None;
+                    }
+                    level.auxiliary_footprint_ids.retain(|id| id != footprint_id);
+                }
+            }
+            EventKind::AuxiliaryFootprintAdded { footprint_id, level_id, polygon } => {
+                let level = self.levels.get_mut(level_id)
+                    .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+                level.auxiliary_footprint_ids.push(*footprint_id);
+                let mut footprint = Footprint::auxiliary(*level_id, polygon.clone());
+                footprint.id = *footprint_id;
+                self.footprints.insert(*footprint_id, footprint);
+            }
+
+            EventKind::GridCreated { building_id } => {
+                self.create_grid(*building_id)?;
+            }
+            EventKind::GridAxisAdded { building_id, axis } => {
+                self.add_grid_axis(*building_id, axis.clone())?;
+            }
+
+            EventKind::WallCreated { wall_id, level_id, assembly_id, start, end, height, arc } => {
+                let level = self.levels.get_mut(level_id)
+                    .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+                let mut wall = Wall::new(*assembly_id, *level_id, *start, *end, *height);
+                wall.id = *wall_id;
+                wall.arc = *arc;
+                level.wall_ids.push(*wall_id);
+                self.walls.insert(*wall_id, wall);
+            }
+            EventKind::WallRemoved { wall_id, .. } => {
+                self.remove_wall(*wall_id)?;
+            }
+            EventKind::WallMoved { wall_id, start, end, .. } => {
+                self.set_wall_endpoints(*wall_id, *start, *end)?;
+            }
+            EventKind::WallAssemblySet { wall_id, assembly_id, .. } => {
+                self.assign_wall_assembly(*wall_id, *assembly_id)?;
+            }
+
+            EventKind::RoomCreated { room_id, level_id, room_type, name, boundary } => {
+                let level = self.levels.get_mut(level_id)
+                    .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+                let mut room = Room::new(*level_id, room_type.clone(), name.clone(), boundary.clone());
+                room.id = *room_id;
+                level.room_ids.push(*room_id);
+                self.rooms.insert(*room_id, room);
+            }
+            EventKind::RoomRemoved { room_id, .. } => {
+                self.remove_room(*room_id)?;
+            }
+            EventKind::RoomConditioningSet { room_id, conditioned } => {
+                self.set_room_conditioned(*room_id, *conditioned)?;
+            }
+            EventKind::RoomFinishSet { room_id, surface, material } => {
+                self.set_room_finish(*room_id, *surface, *material)?;
+            }
+            EventKind::RoomFinishesSet { room_id, finishes } => {
+                self.set_room_finishes(*room_id, *finishes)?;
+            }
+            EventKind::RoomBoundarySet { room_id, boundary, .. } => {
+                self.set_room_boundary(*room_id, boundary.clone())?;
+            }
+            EventKind::RoomMetaUpdated { room_id, name, room_type } => {
+                self.update_room_meta(*room_id, name.clone(), room_type.clone())?;
+            }
+
+            EventKind::OpeningAdded {
+                opening_id,
+                wall_id,
+                opening_type,
+                position,
+                width,
+                height,
+                sill_height,
+                details,
+            } => {
+                if !self.walls.contains_key(wall_id) {
+                    return Err(anyhow!("Wall not found: {:?}", wall_id));
+                }
+                let mut opening = Opening::new(
+                    *wall_id,
+                    opening_type.clone(),
+                    *position,
+                    *width,
+                    *height,
+                    *sill_height,
+                );
+                opening.id = *opening_id;
+                if let Some(details) = details {
+                    opening.set_details(details.clone());
+                }
+                self.openings.insert(*opening_id, opening);
+            }
+            EventKind::OpeningRemoved { opening_id, .. } => {
+                self.remove_opening(*opening_id)?;
+            }
+            EventKind::OpeningMoved { opening_id, wall_id, position } => {
+                self.rehost_opening(*opening_id, *wall_id, *position)?;
+            }
+
+            EventKind::OpeningModified { opening_id, opening_type, position, width, height, sill_height, .. } => {
+                // Applied without the overlap checks: compaction can drop
+                // a sibling's intermediate edits
+                let edit = OpeningEdit {
+                    position: Some(*position),
+                    width: Some(*width),
+                    height: Some(*height),
+                    sill_height: Some(*sill_height),
+                    opening_type: Some(opening_type.clone()),
+                };
+                self.apply_opening_edit(*opening_id, &edit)?;
+            }
+
+            EventKind::DefaultAssemblySet { role, assembly_id } => {
+                self.assign_default_assembly(project_id, *role, *assembly_id)?;
+            }
+            EventKind::FilterSaved { filter } => {
+                self.saved_filters.insert(filter.id, filter.clone());
+            }
+            EventKind::FilterRemoved { filter_id } => {
+                self.remove_filter(*filter_id)?;
+            }
+            EventKind::ScriptSaved { script } => {
+                self.saved_scripts.insert(script.id, script.clone());
+            }
+            EventKind::ScriptRemoved { script_id } => {
+                self.remove_script(*script_id)?;
+            }
+            EventKind::ConstraintAdded { constraint } => {
+                self.constraints.insert(constraint.id, constraint.clone());
+            }
+            EventKind::ConstraintRemoved { constraint_id } => {
+                self.remove_constraint(*constraint_id)?;
+            }
+            EventKind::DetailCalloutAdded { callout } => {
+                self.detail_callouts.insert(callout.id, callout.clone());
+            }
+            EventKind::DetailCalloutRemoved { callout_id } => {
+                self.remove_detail_callout(*callout_id)?;
+            }
+            EventKind::RoofCreated { roof } => {
+                self.roofs.insert(roof.id, roof.clone());
+            }
+            EventKind::RoofRemoved { roof_id } => {
+                self.remove_roof(*roof_id)?;
+            }
+            EventKind::StairCreated { stair } => {
+                self.stairs.insert(stair.id, stair.clone());
+            }
+            EventKind::StairRemoved { stair_id } => {
+                self.remove_stair(*stair_id)?;
+            }
+            EventKind::FloorSlabCreated { slab } => {
+                self.floor_slabs.insert(slab.id, slab.clone());
+            }
+            EventKind::FloorSlabLayersSet { slab_id, layers } => {
+                self.set_floor_slab_layers(*slab_id, layers.clone())?;
+            }
+            EventKind::FloorSlabRemoved { slab_id } => {
+                self.remove_floor_slab(*slab_id)?;
+            }
+            EventKind::CeilingAssemblyCreated { ceiling } => {
+                self.ceilings.insert(ceiling.id, ceiling.clone());
+            }
+            EventKind::CeilingAssemblyLayersSet { ceiling_id, layers } => {
+                self.set_ceiling_layers(*ceiling_id, layers.clone())?;
+            }
+            EventKind::CeilingAssemblyRemoved { ceiling_id } => {
+                self.remove_ceiling(*ceiling_id)?;
+            }
+            EventKind::FoundationCreated { foundation } => {
+                self.foundations.insert(foundation.id, foundation.clone());
+            }
+            EventKind::FoundationDimensionsSet { foundation_id, dimensions } => {
+                self.set_foundation_dimensions(*foundation_id, *dimensions)?;
+            }
+            EventKind::FoundationRemoved { foundation_id } => {
+                self.remove_foundation(*foundation_id)?;
+            }
+            EventKind::BeamCreated { beam } => {
+                self.beams.insert(beam.id, beam.clone());
+            }
+            EventKind::BeamRemoved { beam_id } => {
+                self.remove_beam(*beam_id)?;
+            }
+            EventKind::PostCreated { post } => {
+                self.posts.insert(post.id, post.clone());
+            }
+            EventKind::PostRemoved { post_id } => {
+                self.remove_post(*post_id)?;
+            }
+            EventKind::ElectricalDeviceAdded { device } => {
+                self.electrical_devices.insert(device.id, device.clone());
+            }
+            EventKind::ElectricalDeviceRemoved { device_id } => {
+                self.remove_electrical_device(*device_id)?;
+            }
+            EventKind::ElectricalLayoutApplied { level_id, devices } => {
+                self.apply_electrical_layout(*level_id, devices.clone());
+            }
+            EventKind::FixtureAdded { fixture } => {
+                self.plumbing_fixtures.insert(fixture.id, fixture.clone());
+            }
+            EventKind::FixtureRemoved { fixture_id } => {
+                self.remove_fixture(*fixture_id)?;
+            }
+            EventKind::HvacZoneCreated { zone } => {
+                self.hvac_zones.insert(zone.id, zone.clone());
+            }
+            EventKind::HvacZoneRemoved { zone_id } => {
+                self.remove_hvac_zone(*zone_id)?;
+            }
+            EventKind::HvacZoneRoomAssigned { room_id, zone_id } => {
+                self.assign_room_to_zone(*room_id, *zone_id)?;
+            }
+            EventKind::DeckAdded { deck } => {
+                self.decks.insert(deck.id, deck.clone());
+            }
+            EventKind::DeckRemoved { deck_id } => {
+                self.remove_deck(*deck_id)?;
+            }
+            EventKind::GarageConfigured { garage } => {
+                self.garages.insert(garage.id, garage.clone());
+            }
+            EventKind::GarageRemoved { garage_id } => {
+                self.remove_garage(*garage_id)?;
+            }
+            EventKind::GarageSlabSet { garage_id, slab_thickness, slab_slope } => {
+                self.set_garage_slab(*garage_id, *slab_thickness, *slab_slope)?;
+            }
+
+            // Wall assemblies are shared across projects and solids are a
+            // derived cache, so neither is rebuilt from the log
+            EventKind::WallAssemblyCreated { .. }
+            | EventKind::WallAssemblyUpdated { .. }
+            | EventKind::SolidGenerated { .. }
+            | EventKind::SolidInvalidated { .. } => {}
+        }
+
+        // Use the event's time so replay is deterministic
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.modified_at = event.timestamp;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    /// Everything a project owns, serialized and keyed by ID so maps compare
+    /// independently of HashMap order. modified_at is dropped because live
+    /// mutations stamp it with the wall clock.
+    fn project_state(store: &Store, project_id: ProjectId) -> Value {
+        fn keyed<T: serde::Serialize>(items: Vec<(String, &T)>) -> Value {
+            let mut map = serde_json::Map::new();
+            for (key, item) in items {
+                map.insert(key, serde_json::to_value(item).unwrap());
+            }
+            Value::Object(map)
+        }
+
+        let mut project = serde_json::to_value(store.get_project(project_id).unwrap()).unwrap();
+        project.as_object_mut().unwrap().remove("modified_at");
+
+        let buildings: Vec<&Building> = store.buildings.values()
+            .filter(|b| b.project_id == project_id)
+            .collect();
+        let levels: Vec<&Level> = buildings.iter()
+            .flat_map(|b| store.get_building_levels(b.id))
+            .collect();
+        let level_ids: Vec<LevelId> = levels.iter().map(|l| l.id).collect();
+        let walls: Vec<&Wall> = store.walls.values()
+            .filter(|w| level_ids.contains(&w.level_id))
+            .collect();
+        let building_ids: Vec<BuildingId> = buildings.iter().map(|b| b.id).collect();
+        let on_levels = |items: Vec<(String, Value, LevelId)>| {
+            Value::Object(items.into_iter().filter(|(_, _, l)| level_ids.contains(l)).map(|(k, v, _)| (k, v)).collect())
+        };
+        macro_rules! by_level {
+            ($map:expr, $level:ident) => {
+                on_levels($map.values()
+                    .map(|e| (e.id.to_string(), serde_json::to_value(e).unwrap(), e.$level))
+                    .collect())
+            };
+        }
+
+        serde_json::json!({
+            "project": project,
+            "site": serde_json::to_value(store.sites.values().find(|s| s.project_id == project_id)).unwrap(),
+            "buildings": keyed(buildings.iter().map(|b| (b.id.to_string(), *b)).collect()),
+            "grids": keyed(buildings.iter()
+                .filter_map(|b| store.get_grid(b.id).map(|g| (b.id.to_string(), g)))
+                .collect()),
+            "levels": keyed(levels.iter().map(|l| (l.id.to_string(), *l)).collect()),
+            "footprints": keyed(levels.iter()
+                .flat_map(|l| store.get_level_footprints(l.id))
+                .map(|f| (f.id.to_string(), f))
+                .collect()),
+            "walls": keyed(walls.iter().map(|w| (w.id.to_string(), *w)).collect()),
+            "rooms": keyed(store.rooms.values()
+                .filter(|r| level_ids.contains(&r.level_id))
+                .map(|r| (r.id.to_string(), r))
+                .collect()),
+            "openings": keyed(store.openings.values()
+                .filter(|o| walls.iter().any(|w| w.id == o.wall_id))
+                .map(|o| (o.id.to_string(), o))
+                .collect()),
+            "filters": keyed(store.get_project_filters(project_id).into_iter()
+                .map(|f| (f.id.to_string(), f))
+                .collect()),
+            "scripts": keyed(store.saved_scripts.values()
+                .filter(|s| s.project_id == project_id)
+                .map(|s| (s.id.to_string(), s))
+                .collect()),
+            "hvac_zones": keyed(store.hvac_zones.values()
+                .filter(|z| building_ids.contains(&z.building_id))
+                .map(|z| (z.id.to_string(), z))
+                .collect()),
+            "detail_callouts": by_level!(store.detail_callouts, level_id),
+            "roofs": by_level!(store.roofs, level_id),
+            "stairs": by_level!(store.stairs, from_level_id),
+            "floor_slabs": by_level!(store.floor_slabs, level_id),
+            "ceilings": by_level!(store.ceilings, level_id),
+            "foundations": by_level!(store.foundations, level_id),
+            "beams": by_level!(store.beams, level_id),
+            "posts": by_level!(store.posts, level_id),
+            "electrical_devices": by_level!(store.electrical_devices, level_id),
+            "plumbing_fixtures": by_level!(store.plumbing_fixtures, level_id),
+            "decks": by_level!(store.decks, level_id),
+            "garages": by_level!(store.garages, level_id),
+        })
+    }
+
+    fn assert_replay_equivalent(store: &Store, project_id: ProjectId, step: &str) {
+        let log = store.get_event_log(project_id).unwrap();
+        let rebuilt = Store::rebuild_from_events(log)
+            .unwrap_or_else(|e| panic!("replay failed after {}: {}", step, e));
+        assert_eq!(
+            project_state(store, project_id),
+            project_state(&rebuilt, project_id),
+            "replay diverged after {}",
+            step,
+        );
+    }
+
+    #[test]
+    fn test_replay_equivalence_after_every_mutation() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Replay", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        assert_replay_equivalent(&store, project_id, "create_project");
+
+        let site_id = store.create_site(project_id).unwrap();
+        assert_replay_equivalent(&store, project_id, "create_site");
+
+        store.set_site_boundary(site_id, Polygon2::rectangle(100.0, 120.0)).unwrap();
+        assert_replay_equivalent(&store, project_id, "set_site_boundary");
+
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        assert_replay_equivalent(&store, project_id, "add_building");
+
+        store.create_grid(building_id).unwrap();
+        store
+            .add_grid_axis(
+                building_id,
+                GridAxis {
+                    name: "A".into(),
+                    direction: GridDirection::Vertical,
+                    offset: 0.0,
+                },
+            )
+            .unwrap();
+        assert_replay_equivalent(&store, project_id, "grid");
+
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let upper_id = store.add_level(building_id, "Second Floor", 9.0, 8.0).unwrap();
+        assert_replay_equivalent(&store, project_id, "add_level");
+
+        let footprint_id = store.set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0)).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(42.0, 30.0)).unwrap();
+        assert_replay_equivalent(&store, project_id, "set_level_footprint twice");
+        assert!(store.get_footprint(footprint_id).is_none());
+
+        let current = store.get_level(level_id).unwrap().footprint_id.unwrap();
+        store.modify_footprint(current, Polygon2::rectangle(44.0, 30.0)).unwrap();
+        assert_replay_equivalent(&store, project_id, "modify_footprint");
+
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_a = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(44.0, 0.0), 9.0)
+            .unwrap();
+        let wall_b = store
+            .create_wall(level_id, assembly_id, Point2::new(44.0, 0.0), Point2::new(44.0, 30.0), 9.0)
+            .unwrap();
+        assert_replay_equivalent(&store, project_id, "create_wall");
+
+        let window = store.add_opening(wall_a, OpeningType::Window, 0.3, 3.0, 4.0, 3.0).unwrap();
+        store.add_opening(wall_b, OpeningType::Door, 0.5, 3.0, 6.8, 0.0).unwrap();
+        assert_replay_equivalent(&store, project_id, "add_opening");
+
+        store.remove_opening(window).unwrap();
+        assert_replay_equivalent(&store, project_id, "remove_opening");
+
+        let room_id = store
+            .create_room(level_id, RoomType::Garage, "Garage", Polygon2::rectangle(20.0, 22.0))
+            .unwrap();
+        store.set_room_conditioned(room_id, Some(true)).unwrap();
+        assert_replay_equivalent(&store, project_id, "room");
+
+        store
+            .save_filter(project_id, "Doors", FilterTarget::Openings, vec![])
+            .unwrap();
+        assert_replay_equivalent(&store, project_id, "save_filter");
+
+        // Assembly selection and re-assignment name assemblies that the
+        // replayed store doesn't hold
+        store.set_default_assembly(project_id, AssemblyRole::Exterior, Some(assembly_id)).unwrap();
+        assert_replay_equivalent(&store, project_id, "set_default_assembly");
+
+        store
+            .create_wall_auto(level_id, Point2::new(0.0, 30.0), Point2::new(44.0, 30.0), 9.0)
+            .unwrap();
+        assert_replay_equivalent(&store, project_id, "create_wall_auto");
+
+        let interior = store.create_wall_assembly("Interior", vec![]).unwrap();
+        let shared = store
+            .create_wall(level_id, interior, Point2::new(20.0, 0.0), Point2::new(20.0, 22.0), 9.0)
+            .unwrap();
+        store.set_wall_assembly(wall_a, interior).unwrap();
+        assert_replay_equivalent(&store, project_id, "set_wall_assembly");
+
+        let kitchen = store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::new(vec![
+                Point2::new(20.0, 0.0),
+                Point2::new(44.0, 0.0),
+                Point2::new(44.0, 22.0),
+                Point2::new(20.0, 22.0),
+            ]))
+            .unwrap();
+        let garage_id = store.configure_garage(room_id).unwrap();
+        assert_ne!(store.get_wall(shared).unwrap().assembly_id, interior);
+        store.set_garage_slab(garage_id, 6.0, 0.25).unwrap();
+        assert_replay_equivalent(&store, project_id, "configure_garage");
+
+        store.create_roof(level_id, RoofType::Gable, 6.0, 1.5).unwrap();
+        store
+            .create_stair(level_id, upper_id, Point2::new(30.0, 2.0), 90.0, 3.0, 7.75, 10.0)
+            .unwrap();
+        store.create_floor_slab(level_id, SlabBoundary::Level, FloorSlab::framed_layers()).unwrap();
+        store.create_ceiling(level_id, SlabBoundary::Level, CeilingAssembly::insulated_layers()).unwrap();
+        store.create_foundation(level_id, FoundationType::SlabOnGrade).unwrap();
+        assert_replay_equivalent(&store, project_id, "roof, stair, slabs and foundation");
+
+        let profile = MemberProfile::new(LumberSize::Custom { width: 1.75, depth: 11.875 }, 2);
+        store
+            .create_beam(level_id, Point2::new(0.0, 12.0), Point2::new(16.0, 12.0), profile, FramingMaterial::LVL)
+            .unwrap();
+        let post = MemberProfile::new(LumberSize::FourBySix, 1);
+        store.create_post(level_id, Point2::new(16.0, 12.0), post, FramingMaterial::DF).unwrap();
+        assert_replay_equivalent(&store, project_id, "beams and posts");
+
+        store
+            .add_electrical_device(level_id, ElectricalDeviceKind::Receptacle, Point2::new(30.0, 0.0), Some(wall_a))
+            .unwrap();
+        store.add_fixture(kitchen, PlumbingFixtureKind::Sink, Point2::new(30.0, 1.0), 180.0).unwrap();
+        let zone_id = store.create_hvac_zone(building_id, "Main").unwrap();
+        store.assign_room_to_zone(kitchen, Some(zone_id)).unwrap();
+        store.add_deck(level_id, DeckKind::Deck, 2, 4.0, 16.0, 12.0, 3.0).unwrap();
+        assert_replay_equivalent(&store, project_id, "mechanical and decks");
+
+        store
+            .add_wall_detail_callout(wall_a, DetailReference::new(DetailKind::TypicalWallSection, "1", "A-501"))
+            .unwrap();
+        store.save_script(project_id, "Noop", "let x = 1;").unwrap();
+        store.set_room_finish(kitchen, FinishSurface::Floor, MaterialType::Carpet).unwrap();
+        assert_replay_equivalent(&store, project_id, "callouts, scripts and finishes");
+
+        store.remove_wall(wall_b).unwrap();
+        assert_replay_equivalent(&store, project_id, "remove_wall");
+
+        store.remove_room(room_id).unwrap();
+        assert_replay_equivalent(&store, project_id, "remove_room");
+
+        store.remove_level(upper_id).unwrap();
+        assert_replay_equivalent(&store, project_id, "remove_level");
+
+        store.remove_building(building_id).unwrap();
+        assert_replay_equivalent(&store, project_id, "remove_building");
+    }
+
+    #[test]
+    fn test_rebuild_rejects_bad_logs() {
+        assert!(Store::rebuild_from_events(&EventLog::new()).is_err());
+
+        let mut log = EventLog::new();
+        log.push(ProjectId::new(), EventKind::SiteCreated { site_id: SiteId::new() });
+        assert!(Store::rebuild_from_events(&log).is_err());
+    }
+}
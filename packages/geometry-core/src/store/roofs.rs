@@ -0,0 +1,100 @@
+// Roofs over levels
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+/// Steepest pitch accepted (24:12, a 63° slope)
+const MAX_PITCH: f64 = 24.0;
+
+impl Store {
+    // ========== Roof Operations ==========
+
+    /// Put a roof over a level. Pitch is inches of rise per 12 of run;
+    /// overhang is the eave projection in feet.
+    pub fn create_roof(
+        &mut self,
+        level_id: LevelId,
+        roof_type: RoofType,
+        pitch: f64,
+        overhang: f64,
+    ) -> Result<RoofId> {
+        if !(pitch > 0.0 && pitch <= MAX_PITCH) {
+            return Err(anyhow!("Roof pitch must be between 0 and {}:12, got {}", MAX_PITCH, pitch));
+        }
+        if overhang < 0.0 {
+            return Err(anyhow!("Roof overhang cannot be negative"));
+        }
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        if self.get_level_roof(level_id).is_some() {
+            return Err(anyhow!("Level already has a roof: {:?}", level_id));
+        }
+
+        let roof = Roof::new(level_id, roof_type, pitch, overhang);
+        let roof_id = roof.id;
+
+        self.record_event(project_id, EventKind::RoofCreated { roof: roof.clone() });
+        self.roofs.insert(roof_id, roof);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(roof_id)
+    }
+
+    pub fn get_roof(&self, id: RoofId) -> Option<&Roof> {
+        self.roofs.get(&id)
+    }
+
+    pub fn get_level_roof(&self, level_id: LevelId) -> Option<&Roof> {
+        self.roofs.values().find(|r| r.level_id == level_id)
+    }
+
+    pub fn remove_roof(&mut self, roof_id: RoofId) -> Result<()> {
+        let roof = self.roofs.remove(&roof_id)
+            .ok_or_else(|| anyhow!("Roof not found: {:?}", roof_id))?;
+
+        if let Some(project_id) = self.level_project_id(roof.level_id) {
+            self.record_event(project_id, EventKind::RoofRemoved { roof_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_roof() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+
+        assert!(store.create_roof(level_id, RoofType::Gable, 0.0, 1.0).is_err());
+        assert!(store.create_roof(level_id, RoofType::Gable, 6.0, -1.0).is_err());
+        assert!(store.create_roof(LevelId::new(), RoofType::Gable, 6.0, 1.0).is_err());
+
+        let roof_id = store.create_roof(level_id, RoofType::Hip, 6.0, 1.5).unwrap();
+        assert_eq!(store.get_level_roof(level_id).unwrap().id, roof_id);
+        assert!(store.create_roof(level_id, RoofType::Gable, 6.0, 1.5).is_err());
+
+        store.undo(project_id).unwrap();
+        assert!(store.get_roof(roof_id).is_none());
+        store.redo(project_id).unwrap();
+        assert_eq!(store.get_roof(roof_id).unwrap().roof_type, RoofType::Hip);
+
+        store.remove_level(level_id).unwrap();
+        assert!(store.roofs.is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+// Room adjacency - which rooms on a level share boundary, and where
+// Two rooms are adjacent where their outlines run along each other for more
+// than the tolerance. Each adjacency keeps the shared segments so walls can
+// be generated on them and circulation can check what stands in the way.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::*;
+use crate::geometry::planar_graph::shared_segments;
+use super::Store;
+
+/// Outlines closer than this are treated as touching, and shared stretches
+/// shorter than this are ignored (feet)
+pub const ADJACENCY_TOLERANCE: f64 = 1.0;
+
+/// A stretch of boundary two rooms have in common
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SharedSegment {
+    pub start: Point2,
+    pub end: Point2,
+}
+
+impl SharedSegment {
+    pub fn length(&self) -> f64 {
+        self.start.distance_to(&self.end)
+    }
+}
+
+/// Two rooms that share boundary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomAdjacency {
+    pub room_a: RoomId,
+    pub room_b: RoomId,
+    /// Shared stretches along room A's outline, longest first
+    pub segments: Vec<SharedSegment>,
+    /// Total shared length (feet)
+    pub length: f64,
+}
+
+impl RoomAdjacency {
+    /// The room on the other side from `room_id`, if it's one of the pair
+    pub fn other(&self, room_id: RoomId) -> Option<RoomId> {
+        if room_id == self.room_a {
+            Some(self.room_b)
+        } else if room_id == self.room_b {
+            Some(self.room_a)
+        } else {
+            None
+        }
+    }
+
+    /// Longest shared stretch
+    pub fn longest(&self) -> &SharedSegment {
+        &self.segments[0]
+    }
+}
+
+/// Rooms of a level and the boundary they share
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomAdjacencyGraph {
+    pub level_id: LevelId,
+    /// Rooms in the level's order
+    pub rooms: Vec<RoomId>,
+    pub adjacencies: Vec<RoomAdjacency>,
+}
+
+impl RoomAdjacencyGraph {
+    /// Adjacency between two rooms, in either order
+    pub fn between(&self, a: RoomId, b: RoomId) -> Option<&RoomAdjacency> {
+        self.adjacencies
+            .iter()
+            .find(|adj| (adj.room_a == a && adj.room_b == b) || (adj.room_a == b && adj.room_b == a))
+    }
+
+    pub fn are_adjacent(&self, a: RoomId, b: RoomId) -> bool {
+        self.between(a, b).is_some()
+    }
+
+    /// Rooms sharing boundary with a room
+    pub fn neighbors(&self, room_id: RoomId) -> Vec<RoomId> {
+        self.adjacencies.iter().filter_map(|adj| adj.other(room_id)).collect()
+    }
+}
+
+impl Store {
+    // ========== Room Adjacency ==========
+
+    /// Pairs of rooms on a level that share boundary, with the shared
+    /// segments and their total length
+    pub fn room_adjacency(&self, level_id: LevelId) -> Result<RoomAdjacencyGraph> {
+        let level = self.levels.get(&level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        let rooms: Vec<&Room> = level.room_ids.iter().filter_map(|id| self.rooms.get(id)).collect();
+
+        let mut adjacencies = Vec::new();
+        for (i, a) in rooms.iter().enumerate() {
+            for b in &rooms[i + 1..] {
+                let mut segments: Vec<SharedSegment> = shared_segments(&a.boundary, &b.boundary, ADJACENCY_TOLERANCE)
+                    .into_iter()
+                    .map(|(start, end)| SharedSegment { start, end })
+                    .collect();
+                if segments.is_empty() {
+                    continue;
+                }
+                segments.sort_by(|x, y| y.length().total_cmp(&x.length()));
+                adjacencies.push(RoomAdjacency {
+                    room_a: a.id,
+                    room_b: b.id,
+                    length: segments.iter().map(|s| s.length()).sum(),
+                    segments,
+                });
+            }
+        }
+
+        Ok(RoomAdjacencyGraph {
+            level_id,
+            rooms: rooms.iter().map(|r| r.id).collect(),
+            adjacencies,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_room_adjacency_segments() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+
+        // An L-shaped hall wraps two sides of the kitchen; the office only
+        // touches the kitchen at a corner
+        let kitchen = store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+        let hall = Polygon2::new(vec![
+            Point2::new(12.0, 0.0),
+            Point2::new(16.0, 0.0),
+            Point2::new(16.0, 14.0),
+            Point2::new(0.0, 14.0),
+            Point2::new(0.0, 10.0),
+            Point2::new(12.0, 10.0),
+        ]);
+        let hall = store.create_room(level_id, RoomType::Hallway, "Hall", hall).unwrap();
+        let office = Polygon2::new(vec![
+            Point2::new(16.0, 14.0),
+            Point2::new(26.0, 14.0),
+            Point2::new(26.0, 24.0),
+            Point2::new(16.0, 24.0),
+        ]);
+        let office = store.create_room(level_id, RoomType::Office, "Office", office).unwrap();
+
+        let graph = store.room_adjacency(level_id).unwrap();
+        assert_eq!(graph.rooms, vec![kitchen, hall, office]);
+        let adjacency = graph.between(hall, kitchen).unwrap();
+        assert_eq!(adjacency.segments.len(), 2);
+        assert!((adjacency.length - 22.0).abs() < 1e-9);
+        assert!((adjacency.longest().length() - 12.0).abs() < 1e-9);
+        assert!(!graph.are_adjacent(kitchen, office));
+        assert!(!graph.are_adjacent(hall, office));
+        assert_eq!(graph.neighbors(kitchen), vec![hall]);
+
+        assert!(store.room_adjacency(LevelId::new()).is_err());
+    }
+}
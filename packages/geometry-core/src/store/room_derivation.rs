@@ -0,0 +1,182 @@
+// Rooms derived from the wall network
+// The enclosed faces of a level's wall centerlines are its rooms. Existing
+// rooms are matched to the face they sit in and take its boundary; faces
+// with no room get a new one. This is the inverse of generating walls from
+// room adjacency.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::*;
+use crate::geometry::planar_graph::{interior_point, PlanarGraph};
+use super::Store;
+
+/// Wall ends closer than this meet (feet)
+const WALL_SNAP_TOLERANCE: f64 = 0.01;
+/// Faces smaller than this are gaps between walls, not rooms (sq ft)
+const MIN_ROOM_AREA: f64 = 4.0;
+/// Largest arc between vertices where a room follows a curved wall
+const CURVED_BOUNDARY_MAX_ANGLE: f64 = std::f64::consts::PI / 36.0;
+
+/// What `derive_rooms_from_walls` changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoomDerivation {
+    pub created: Vec<RoomId>,
+    pub updated: Vec<RoomId>,
+    /// Rooms that lie in no enclosed face; left untouched
+    pub unenclosed: Vec<RoomId>,
+}
+
+impl Store {
+    // ========== Room Derivation ==========
+
+    /// Make the level's rooms match the faces enclosed by its walls. Each
+    /// existing room takes the boundary of the face it lies in (the closest
+    /// in area if several rooms share a face); faces without a room get a
+    /// new one. Bounding walls are set for every matched room.
+    pub fn derive_rooms_from_walls(&mut self, level_id: LevelId) -> Result<RoomDerivation> {
+        if !self.levels.contains_key(&level_id) {
+            return Err(anyhow!("Level not found: {:?}", level_id));
+        }
+
+        // Centerline segments, each remembering its wall
+        let mut segments = Vec::new();
+        let mut segment_walls = Vec::new();
+        for wall in self.get_level_walls(level_id) {
+            for pair in wall.centerline_points(CURVED_BOUNDARY_MAX_ANGLE).windows(2) {
+                segments.push((pair[0], pair[1]));
+                segment_walls.push(wall.id);
+            }
+        }
+        let faces: Vec<_> = PlanarGraph::from_segments(&segments, WALL_SNAP_TOLERANCE)
+            .faces()
+            .into_iter()
+            .filter(|face| face.polygon.area() >= MIN_ROOM_AREA)
+            .collect();
+
+        let mut unmatched: Vec<(RoomId, f64, Option<Point2>)> = self
+            .get_level_rooms(level_id)
+            .into_iter()
+            .map(|room| (room.id, room.area(), interior_point(&room.boundary)))
+            .collect();
+        unmatched.sort_by_key(|(id, _, _)| id.to_string());
+
+        let mut derivation = RoomDerivation::default();
+        for face in faces {
+            let area = face.polygon.area();
+            let matched = unmatched
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, _, inside))| inside.is_some_and(|p| contains(&face.polygon, &p)))
+                .min_by(|(_, a), (_, b)| (a.1 - area).abs().total_cmp(&(b.1 - area).abs()))
+                .map(|(i, _)| i);
+
+            let mut wall_ids: Vec<WallId> = face.sources.iter().map(|&s| segment_walls[s]).collect();
+            wall_ids.dedup();
+
+            let room_id = match matched {
+                Some(i) => {
+                    let (room_id, _, _) = unmatched.remove(i);
+                    let current = &self.rooms[&room_id].boundary;
+                    if !same_boundary(current, &face.polygon) {
+                        self.set_room_boundary(room_id, face.polygon)?;
+                        derivation.updated.push(room_id);
+                    }
+                    room_id
+                }
+                None => {
+                    let name = format!("Room {}", self.get_level_rooms(level_id).len() + 1);
+                    let room_id = self.create_room(level_id, RoomType::Other("Room".to_string()), name, face.polygon)?;
+                    derivation.created.push(room_id);
+                    room_id
+                }
+            };
+            self.set_room_bounding_walls(room_id, wall_ids)?;
+        }
+
+        derivation.unenclosed = unmatched.into_iter().map(|(id, _, _)| id).collect();
+        Ok(derivation)
+    }
+}
+
+/// Whether a point lies inside a polygon and outside its holes
+pub(crate) fn contains(polygon: &Polygon2, point: &Point2) -> bool {
+    let inside = |ring: &Vec<Point2>| {
+        let mut inside = false;
+        for i in 0..ring.len() {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            if (a.y > point.y) != (b.y > point.y) && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+                inside = !inside;
+            }
+        }
+        inside
+    };
+    inside(&polygon.outer) && !polygon.holes.iter().any(inside)
+}
+
+/// Whether two boundaries trace the same outline, whatever vertex they start at
+fn same_boundary(a: &Polygon2, b: &Polygon2) -> bool {
+    let same_ring = |x: &Vec<Point2>, y: &Vec<Point2>| {
+        x.len() == y.len()
+            && (0..y.len()).any(|shift| {
+                x.iter()
+                    .enumerate()
+                    .all(|(i, p)| p.distance_to(&y[(i + shift) % y.len()]) < WALL_SNAP_TOLERANCE)
+            })
+    };
+    same_ring(&a.outer, &b.outer)
+        && a.holes.len() == b.holes.len()
+        && a.holes.iter().all(|h| b.holes.iter().any(|g| same_ring(h, g)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_rooms_from_walls() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+
+        // 30x20 box split at x = 12
+        let corners = [(0.0, 0.0), (30.0, 0.0), (30.0, 20.0), (0.0, 20.0)];
+        for i in 0..4 {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            store.create_wall(level_id, assembly_id, Point2::new(a.0, a.1), Point2::new(b.0, b.1), 9.0).unwrap();
+        }
+        let split = store
+            .create_wall(level_id, assembly_id, Point2::new(12.0, 0.0), Point2::new(12.0, 20.0), 9.0)
+            .unwrap();
+
+        // A kitchen drawn roughly where the left room is
+        let kitchen = store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(11.0, 19.0))
+            .unwrap();
+
+        let derivation = store.derive_rooms_from_walls(level_id).unwrap();
+        assert_eq!(derivation.updated, vec![kitchen]);
+        assert_eq!(derivation.created.len(), 1);
+        assert!(derivation.unenclosed.is_empty());
+
+        let kitchen_room = store.get_room(kitchen).unwrap();
+        assert!((kitchen_room.area() - 240.0).abs() < 1e-9);
+        assert_eq!(kitchen_room.room_type, RoomType::Kitchen);
+        assert_eq!(kitchen_room.bounding_wall_ids.len(), 4);
+        assert!(kitchen_room.bounding_wall_ids.contains(&split));
+        let new_room = store.get_room(derivation.created[0]).unwrap();
+        assert!((new_room.area() - 360.0).abs() < 1e-9);
+
+        // Running it again changes nothing, and the boundary survives replay
+        let again = store.derive_rooms_from_walls(level_id).unwrap();
+        assert!(again.created.is_empty() && again.updated.is_empty());
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        assert!((rebuilt.get_room(kitchen).unwrap().area() - 240.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,109 @@
+// Saved script storage
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Saved Script Operations ==========
+
+    /// Save a script under a name, replacing the source of a script already
+    /// saved under it
+    pub fn save_script(
+        &mut self,
+        project_id: ProjectId,
+        name: impl Into<String>,
+        source: impl Into<String>,
+    ) -> Result<ScriptId> {
+        let name = name.into();
+        if !self.projects.contains_key(&project_id) {
+            return Err(anyhow!("Project not found: {:?}", project_id));
+        }
+
+        if name.trim().is_empty() {
+            return Err(anyhow!("Script name must not be empty"));
+        }
+
+        let script = match self.find_script(project_id, &name) {
+            Some(existing) => SavedScript { source: source.into(), ..existing.clone() },
+            None => SavedScript::new(project_id, name, source),
+        };
+        let script_id = script.id;
+
+        self.record_event(project_id, EventKind::ScriptSaved { script: script.clone() });
+        self.saved_scripts.insert(script_id, script);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(script_id)
+    }
+
+    pub fn get_script(&self, id: ScriptId) -> Option<&SavedScript> {
+        self.saved_scripts.get(&id)
+    }
+
+    /// Look up a project's script by name (case-insensitive)
+    pub fn find_script(&self, project_id: ProjectId, name: &str) -> Option<&SavedScript> {
+        self.saved_scripts
+            .values()
+            .find(|s| s.project_id == project_id && s.name.eq_ignore_ascii_case(name))
+    }
+
+    /// All scripts saved in a project, sorted by name
+    pub fn get_project_scripts(&self, project_id: ProjectId) -> Vec<&SavedScript> {
+        let mut scripts: Vec<&SavedScript> = self.saved_scripts
+            .values()
+            .filter(|s| s.project_id == project_id)
+            .collect();
+        scripts.sort_by(|a, b| a.name.cmp(&b.name));
+        scripts
+    }
+
+    pub fn remove_script(&mut self, script_id: ScriptId) -> Result<()> {
+        let script = self.saved_scripts.remove(&script_id)
+            .ok_or_else(|| anyhow!("Script not found: {:?}", script_id))?;
+
+        self.record_event(script.project_id, EventKind::ScriptRemoved { script_id });
+
+        if let Some(project) = self.projects.get_mut(&script.project_id) {
+            project.touch();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_script() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+
+        let bath = store.save_script(project_id, "Standard bath", "let x = 1;").unwrap();
+        store.save_script(project_id, "Closet", "let y = 2;").unwrap();
+        assert!(store.save_script(project_id, " ", "").is_err());
+        assert!(store.save_script(ProjectId::new(), "Other", "").is_err());
+
+        // Saving under the same name edits the script in place
+        assert_eq!(store.save_script(project_id, "standard BATH", "let x = 3;").unwrap(), bath);
+        let names: Vec<&str> = store.get_project_scripts(project_id).iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Closet", "Standard bath"]);
+        assert_eq!(store.get_script(bath).unwrap().source, "let x = 3;");
+
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_script(bath).unwrap().source, "let x = 1;");
+
+        store.remove_script(bath).unwrap();
+        assert!(store.find_script(project_id, "Standard bath").is_none());
+        store.undo(project_id).unwrap();
+        assert!(store.find_script(project_id, "Standard bath").is_some());
+    }
+}
@@ -0,0 +1,198 @@
+// Per-project store shards
+// A server holding every project in one SharedStore serializes all edits
+// behind one lock. ShardedStore gives each project its own Store, so the
+// outer lock is only taken to find, add or drop a shard and edits to
+// different projects never wait on each other.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use anyhow::Result;
+
+use crate::domain::*;
+use super::{SharedStore, StorageBackend, Store};
+
+/// One SharedStore per project
+#[derive(Debug, Default)]
+pub struct ShardedStore {
+    shards: RwLock<HashMap<ProjectId, SharedStore>>,
+}
+
+impl ShardedStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a project in a shard of its own
+    pub fn create_project(
+        &self,
+        name: impl Into<String>,
+        units: UnitSystem,
+        code_region: CodeRegion,
+    ) -> Result<ProjectId> {
+        let mut store = Store::new();
+        let project_id = store.create_project(name, units, code_region)?;
+        self.insert(project_id, store);
+        Ok(project_id)
+    }
+
+    /// Add (or replace) a project's shard
+    pub fn insert(&self, project_id: ProjectId, store: Store) -> SharedStore {
+        let shard = Arc::new(RwLock::new(store));
+        self.shards.write().unwrap().insert(project_id, shard.clone());
+        shard
+    }
+
+    /// The project's store. Lock it for as long as the edit takes; the
+    /// shard map itself is released before this returns.
+    pub fn get(&self, project_id: ProjectId) -> Option<SharedStore> {
+        self.shards.read().unwrap().get(&project_id).cloned()
+    }
+
+    pub fn remove(&self, project_id: ProjectId) -> Option<SharedStore> {
+        self.shards.write().unwrap().remove(&project_id)
+    }
+
+    pub fn project_ids(&self) -> Vec<ProjectId> {
+        self.shards.read().unwrap().keys().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.read().unwrap().is_empty()
+    }
+
+    /// Load every project in a backend into its own shard. Each shard gets
+    /// a copy of the stored wall assemblies; assemblies created afterwards
+    /// stay in the shard they were created in.
+    pub fn load_from(backend: &dyn StorageBackend) -> Result<ShardedStore> {
+        let assemblies = backend.load_assemblies()?;
+        let sharded = ShardedStore::new();
+        for project_id in backend.list_projects()? {
+            let mut store = Store::new();
+            for assembly in &assemblies {
+                store.wall_assemblies.insert(assembly.id, assembly.clone());
+            }
+            store.load_project_from(backend, project_id)?;
+            sharded.insert(project_id, store);
+        }
+        Ok(sharded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{new_shared_store, MemoryBackend};
+    use std::sync::Barrier;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_shards_are_independent() {
+        let sharded = ShardedStore::new();
+        let a = sharded.create_project("A", UnitSystem::Imperial, CodeRegion::us_irc_2021()).unwrap();
+        let b = sharded.create_project("B", UnitSystem::Imperial, CodeRegion::us_irc_2021()).unwrap();
+        assert_eq!(sharded.len(), 2);
+
+        // A long edit on A leaves B free to write
+        let shard_a = sharded.get(a).unwrap();
+        let _editing_a = shard_a.write().unwrap();
+        let shard_b = sharded.get(b).unwrap();
+        let mut store_b = shard_b.try_write().unwrap();
+        store_b.add_building(b, "Main").unwrap();
+        assert!(store_b.get_project(a).is_none());
+        drop(store_b);
+
+        assert!(sharded.remove(b).is_some());
+        assert!(sharded.get(b).is_none());
+        assert_eq!(sharded.project_ids(), vec![a]);
+    }
+
+    #[test]
+    fn test_load_from_backend() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Durable", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let mut backend = MemoryBackend::new();
+        store.save_all_to(&mut backend).unwrap();
+
+        let sharded = ShardedStore::load_from(&backend).unwrap();
+        let shard = sharded.get(project_id).unwrap();
+        let store = shard.read().unwrap();
+        assert!(store.get_building(building_id).is_some());
+        assert!(store.get_wall_assembly(assembly_id).is_some());
+    }
+
+    /// Writers on different projects each hold their lock for a while. With
+    /// one global lock they'd run back to back; sharded, they overlap.
+    #[test]
+    fn test_concurrent_edits_load() {
+        const WRITERS: usize = 8;
+        const EDITS: usize = 5;
+        const HOLD: Duration = Duration::from_millis(10);
+
+        let edit = |store: &mut Store, project_id: ProjectId, i: usize| {
+            store.add_building(project_id, format!("B{}", i)).unwrap();
+            thread::sleep(HOLD);
+        };
+
+        let sharded = Arc::new(ShardedStore::new());
+        let global = new_shared_store();
+        let mut sharded_ids = Vec::new();
+        let mut global_ids = Vec::new();
+        for i in 0..WRITERS {
+            let name = format!("P{}", i);
+            let region = CodeRegion::us_irc_2021();
+            sharded_ids.push(sharded.create_project(name.clone(), UnitSystem::Imperial, region.clone()).unwrap());
+            global_ids.push(global.write().unwrap().create_project(name, UnitSystem::Imperial, region).unwrap());
+        }
+
+        let run = |project_ids: &[ProjectId], write: Arc<dyn Fn(ProjectId, usize) + Send + Sync>| {
+            let barrier = Arc::new(Barrier::new(project_ids.len()));
+            let started = Instant::now();
+            let handles: Vec<_> = project_ids
+                .iter()
+                .map(|&project_id| {
+                    let (write, barrier) = (write.clone(), barrier.clone());
+                    thread::spawn(move || {
+                        barrier.wait();
+                        for i in 0..EDITS {
+                            write(project_id, i);
+                        }
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+            started.elapsed()
+        };
+
+        let shards = sharded.clone();
+        let sharded_time = run(&sharded_ids, Arc::new(move |project_id, i| {
+            let shard = shards.get(project_id).unwrap();
+            edit(&mut shard.write().unwrap(), project_id, i);
+        }));
+        let global_store = global.clone();
+        let global_time = run(&global_ids, Arc::new(move |project_id, i| {
+            edit(&mut global_store.write().unwrap(), project_id, i);
+        }));
+
+        // The global lock can't beat WRITERS * EDITS holds in a row
+        assert!(global_time >= HOLD * (WRITERS * EDITS) as u32);
+        assert!(sharded_time * 2 < global_time, "sharded {:?} vs global {:?}", sharded_time, global_time);
+        for project_id in sharded_ids {
+            let shard = sharded.get(project_id).unwrap();
+            let store = shard.read().unwrap();
+            assert_eq!(store.get_project(project_id).unwrap().building_ids.len(), EDITS);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+// Floor slabs and ceiling assemblies
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Floor and Ceiling Operations ==========
+
+    /// Check that a layer stack and boundary are usable on a level, and
+    /// return the project the level belongs to
+    fn validate_slab(
+        &self,
+        level_id: LevelId,
+        boundary: SlabBoundary,
+        layers: &[SlabLayer],
+    ) -> Result<ProjectId> {
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        if let SlabBoundary::Room { room_id } = boundary {
+            let room = self.rooms.get(&room_id)
+                .ok_or_else(|| anyhow!("Room not found: {:?}", room_id))?;
+            if room.level_id != level_id {
+                return Err(anyhow!("Room {:?} is not on level {:?}", room_id, level_id));
+            }
+        }
+        validate_layers(layers)?;
+        Ok(project_id)
+    }
+
+    /// Plan outline of a floor or ceiling: the level footprint or the room
+    pub fn slab_outline(&self, level_id: LevelId, boundary: SlabBoundary) -> Option<&Polygon2> {
+        match boundary {
+            SlabBoundary::Level => self.get_level_footprint(level_id).map(|fp| &fp.polygon),
+            SlabBoundary::Room { room_id } => self.rooms.get(&room_id).map(|r| &r.boundary),
+        }
+    }
+
+    pub fn create_floor_slab(
+        &mut self,
+        level_id: LevelId,
+        boundary: SlabBoundary,
+        layers: Vec<SlabLayer>,
+    ) -> Result<FloorSlabId> {
+        let project_id = self.validate_slab(level_id, boundary, &layers)?;
+
+        let slab = FloorSlab::new(level_id, boundary, layers);
+        let slab_id = slab.id;
+
+        self.record_event(project_id, EventKind::FloorSlabCreated { slab: slab.clone() });
+        self.floor_slabs.insert(slab_id, slab);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(slab_id)
+    }
+
+    pub fn get_floor_slab(&self, id: FloorSlabId) -> Option<&FloorSlab> {
+        self.floor_slabs.get(&id)
+    }
+
+    pub fn get_level_floor_slabs(&self, level_id: LevelId) -> Vec<&FloorSlab> {
+        self.floor_slabs
+            .values()
+            .filter(|s| s.level_id == level_id)
+            .collect()
+    }
+
+    /// Floor under a room: a slab bounded by the room, else the level's slab
+    pub fn get_room_floor_slab(&self, room_id: RoomId) -> Option<&FloorSlab> {
+        let room = self.rooms.get(&room_id)?;
+        let slabs = self.get_level_floor_slabs(room.level_id);
+        slabs.iter()
+            .find(|s| s.boundary == SlabBoundary::Room { room_id })
+            .or_else(|| slabs.iter().find(|s| s.boundary == SlabBoundary::Level))
+            .copied()
+    }
+
+    /// Floor thickness under a room in feet, defaulting to a framed floor
+    pub fn room_floor_thickness(&self, room_id: RoomId) -> f64 {
+        self.get_room_floor_slab(room_id)
+            .map(|s| s.thickness())
+            .unwrap_or_else(FloorSlab::default_thickness)
+    }
+
+    pub fn set_floor_slab_layers(&mut self, slab_id: FloorSlabId, layers: Vec<SlabLayer>) -> Result<()> {
+        validate_layers(&layers)?;
+        let slab = self.floor_slabs.get_mut(&slab_id)
+            .ok_or_else(|| anyhow!("Floor slab not found: {:?}", slab_id))?;
+        slab.layers = layers.clone();
+        let level_id = slab.level_id;
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::FloorSlabLayersSet { slab_id, layers });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_floor_slab(&mut self, slab_id: FloorSlabId) -> Result<()> {
+        let slab = self.floor_slabs.remove(&slab_id)
+            .ok_or_else(|| anyhow!("Floor slab not found: {:?}", slab_id))?;
+
+        if let Some(project_id) = self.level_project_id(slab.level_id) {
+            self.record_event(project_id, EventKind::FloorSlabRemoved { slab_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn create_ceiling(
+        &mut self,
+        level_id: LevelId,
+        boundary: SlabBoundary,
+        layers: Vec<SlabLayer>,
+    ) -> Result<CeilingAssemblyId> {
+        let project_id = self.validate_slab(level_id, boundary, &layers)?;
+
+        let ceiling = CeilingAssembly::new(level_id, boundary, layers);
+        let ceiling_id = ceiling.id;
+
+        self.record_event(project_id, EventKind::CeilingAssemblyCreated { ceiling: ceiling.clone() });
+        self.ceilings.insert(ceiling_id, ceiling);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(ceiling_id)
+    }
+
+    pub fn get_ceiling(&self, id: CeilingAssemblyId) -> Option<&CeilingAssembly> {
+        self.ceilings.get(&id)
+    }
+
+    pub fn get_level_ceilings(&self, level_id: LevelId) -> Vec<&CeilingAssembly> {
+        self.ceilings
+            .values()
+            .filter(|c| c.level_id == level_id)
+            .collect()
+    }
+
+    /// Ceiling over a room: one bounded by the room, else the level's ceiling
+    pub fn get_room_ceiling(&self, room_id: RoomId) -> Option<&CeilingAssembly> {
+        let room = self.rooms.get(&room_id)?;
+        let ceilings = self.get_level_ceilings(room.level_id);
+        ceilings.iter()
+            .find(|c| c.boundary == SlabBoundary::Room { room_id })
+            .or_else(|| ceilings.iter().find(|c| c.boundary == SlabBoundary::Level))
+            .copied()
+    }
+
+    /// Elevation of a ceiling's underside: the room's ceiling height when
+    /// set, else the underside of the floor of the level above
+    pub fn ceiling_elevation(&self, ceiling_id: CeilingAssemblyId) -> Option<f64> {
+        let ceiling = self.ceilings.get(&ceiling_id)?;
+        let level = self.levels.get(&ceiling.level_id)?;
+
+        if let SlabBoundary::Room { room_id } = ceiling.boundary {
+            if let Some(height) = self.rooms.get(&room_id).and_then(|r| r.ceiling_height) {
+                return Some(level.elevation + height);
+            }
+        }
+
+        let top = level.ceiling_elevation();
+        let above = self.get_building_levels(level.building_id)
+            .into_iter()
+            .find(|l| (l.elevation - top).abs() < 1e-6);
+        let floor_above = above
+            .map(|l| {
+                self.get_level_floor_slabs(l.id)
+                    .into_iter()
+                    .find(|s| s.boundary == SlabBoundary::Level)
+                    .map(|s| s.thickness())
+                    .unwrap_or_else(FloorSlab::default_thickness)
+            })
+            .unwrap_or(0.0);
+        Some(top - floor_above)
+    }
+
+    pub fn set_ceiling_layers(&mut self, ceiling_id: CeilingAssemblyId, layers: Vec<SlabLayer>) -> Result<()> {
+        validate_layers(&layers)?;
+        let ceiling = self.ceilings.get_mut(&ceiling_id)
+            .ok_or_else(|| anyhow!("Ceiling not found: {:?}", ceiling_id))?;
+        ceiling.layers = layers.clone();
+        let level_id = ceiling.level_id;
+
+        if let Some(project_id) = self.level_project_id(level_id) {
+            self.record_event(project_id, EventKind::CeilingAssemblyLayersSet { ceiling_id, layers });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_ceiling(&mut self, ceiling_id: CeilingAssemblyId) -> Result<()> {
+        let ceiling = self.ceilings.remove(&ceiling_id)
+            .ok_or_else(|| anyhow!("Ceiling not found: {:?}", ceiling_id))?;
+
+        if let Some(project_id) = self.level_project_id(ceiling.level_id) {
+            self.record_event(project_id, EventKind::CeilingAssemblyRemoved { ceiling_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_layers(layers: &[SlabLayer]) -> Result<()> {
+    if layers.is_empty() {
+        return Err(anyhow!("Assembly needs at least one layer"));
+    }
+    if let Some(layer) = layers.iter().find(|l| l.thickness <= 0.0) {
+        return Err(anyhow!("Layer '{}' must have a positive thickness", layer.name));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floor_slab_and_ceiling_crud() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "Second Floor", 9.0, 8.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(30.0, 20.0)).unwrap();
+        let bath = store
+            .create_room(level_id, RoomType::Bathroom, "Bath", Polygon2::rectangle(8.0, 10.0))
+            .unwrap();
+        let bed = store
+            .create_room(level_id, RoomType::Bedroom, "Bed", Polygon2::rectangle(12.0, 12.0))
+            .unwrap();
+
+        assert!(store.create_floor_slab(level_id, SlabBoundary::Level, vec![]).is_err());
+        assert!(store
+            .create_floor_slab(LevelId::new(), SlabBoundary::Level, FloorSlab::framed_layers())
+            .is_err());
+
+        let level_slab = store
+            .create_floor_slab(level_id, SlabBoundary::Level, FloorSlab::framed_layers())
+            .unwrap();
+        let mut tiled = FloorSlab::framed_layers();
+        tiled.insert(0, SlabLayer::new("Mortar Bed", MaterialType::ConcreteMix, 1.25));
+        let bath_slab = store
+            .create_floor_slab(level_id, SlabBoundary::Room { room_id: bath }, tiled)
+            .unwrap();
+
+        assert_eq!(store.get_room_floor_slab(bath).unwrap().id, bath_slab);
+        assert_eq!(store.get_room_floor_slab(bed).unwrap().id, level_slab);
+        assert!((store.slab_outline(level_id, SlabBoundary::Level).unwrap().area() - 600.0).abs() < 1e-10);
+        assert!((store.room_floor_thickness(bath) - 11.25 / 12.0).abs() < 1e-10);
+
+        let ceiling_id = store
+            .create_ceiling(level_id, SlabBoundary::Level, CeilingAssembly::insulated_layers())
+            .unwrap();
+        store.set_ceiling_layers(ceiling_id, vec![SlabLayer::gypsum_1_2()]).unwrap();
+        assert_eq!(store.get_room_ceiling(bed).unwrap().layers.len(), 1);
+        // Top level: the ceiling sits at floor-to-floor height
+        assert!((store.ceiling_elevation(ceiling_id).unwrap() - 17.0).abs() < 1e-10);
+
+        // Layer edits and removals replay through undo/redo
+        store.undo(project_id).unwrap();
+        assert_eq!(store.get_ceiling(ceiling_id).unwrap().layers.len(), 2);
+        store.redo(project_id).unwrap();
+        assert_eq!(store.get_ceiling(ceiling_id).unwrap().layers.len(), 1);
+
+        store.remove_room(bath).unwrap();
+        assert!(store.get_floor_slab(bath_slab).is_none());
+        store.undo(project_id).unwrap();
+        assert!(store.get_floor_slab(bath_slab).is_some());
+
+        store.remove_level(level_id).unwrap();
+        assert!(store.floor_slabs.is_empty());
+        assert!(store.ceilings.is_empty());
+    }
+}
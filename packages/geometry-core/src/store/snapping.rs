@@ -0,0 +1,195 @@
+// Snapping - where a cursor point lands while drawing on a level
+// Points win over lines: wall endpoints, room corners and grid crossings
+// are tried first, then wall faces and centerlines and single grid axes,
+// then an orthogonal lock to the previous point. The nearest candidate
+// within the tolerance in the first tier that has one is used, so the
+// drawing UI and a server placing points resolve snaps the same way.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::domain::*;
+use super::Store;
+
+/// What a point snapped to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapTarget {
+    WallEndpoint { wall_id: WallId },
+    /// A point on a wall's centerline or either face
+    WallEdge { wall_id: WallId },
+    RoomCorner { room_id: RoomId },
+    /// Where two grid axes cross
+    GridIntersection { axes: [String; 2] },
+    GridLine { axis: String },
+    /// Lined up horizontally or vertically with the anchor point
+    Orthogonal,
+    /// Nothing in range; the point is unchanged
+    None,
+}
+
+/// A snapped point and what it snapped to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snap {
+    pub point: Point2,
+    pub target: SnapTarget,
+}
+
+struct Candidate {
+    point: Point2,
+    target: SnapTarget,
+    distance: f64,
+}
+
+/// Keep the nearest candidate within `tolerance`
+fn nearest(candidates: impl Iterator<Item = Candidate>, tolerance: f64) -> Option<Candidate> {
+    candidates
+        .filter(|c| c.distance <= tolerance)
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+}
+
+/// Closest point to `p` on the segment from `a` to `b`
+fn closest_on_segment(p: Point2, a: Point2, b: Point2) -> Point2 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len_sq = dx * dx + dy * dy;
+    if len_sq < 1e-12 {
+        return a;
+    }
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / len_sq).clamp(0.0, 1.0);
+    Point2::new(a.x + t * dx, a.y + t * dy)
+}
+
+impl Store {
+    /// Snap `point` to what's drawn on a level within `tolerance` feet.
+    /// `anchor` is the previous point of the line being drawn, if any, for
+    /// the orthogonal lock.
+    pub fn snap_point(&self, level_id: LevelId, point: Point2, tolerance: f64, anchor: Option<Point2>) -> Result<Snap> {
+        let level = self.levels.get(&level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        let walls = self.get_level_walls(level_id);
+        let grid = self.grids.get(&level.building_id);
+        let candidate = |p: Point2, target: SnapTarget| Candidate { point: p, target, distance: p.distance_to(&point) };
+
+        // Points
+        let endpoints = walls.iter().flat_map(|wall| {
+            [wall.start, wall.end].map(|p| candidate(p, SnapTarget::WallEndpoint { wall_id: wall.id }))
+        });
+        let corners = self.get_level_rooms(level_id).into_iter().flat_map(|room| {
+            room.boundary.outer.iter().map(move |&p| candidate(p, SnapTarget::RoomCorner { room_id: room.id }))
+        });
+        let crossings = grid.into_iter().flat_map(|grid| {
+            let across = |direction| grid.axes.iter().filter(move |a| a.direction == direction);
+            across(GridDirection::Vertical).flat_map(move |v| {
+                across(GridDirection::Horizontal).map(move |h| {
+                    candidate(
+                        Point2::new(v.offset, h.offset),
+                        SnapTarget::GridIntersection { axes: [v.name.clone(), h.name.clone()] },
+                    )
+                })
+            })
+        });
+        if let Some(hit) = nearest(endpoints.chain(corners).chain(crossings), tolerance) {
+            return Ok(Snap { point: hit.point, target: hit.target });
+        }
+
+        // Lines
+        let edges = walls.iter().flat_map(|wall| {
+            let half = self.wall_thickness(wall) / 2.0;
+            let (dx, dy) = (wall.end.x - wall.start.x, wall.end.y - wall.start.y);
+            let len = (dx * dx + dy * dy).sqrt().max(1e-12);
+            let (nx, ny) = (-dy / len * half, dx / len * half);
+            [0.0, 1.0, -1.0].map(|side| {
+                let shift = |p: Point2| Point2::new(p.x + nx * side, p.y + ny * side);
+                let p = closest_on_segment(point, shift(wall.start), shift(wall.end));
+                candidate(p, SnapTarget::WallEdge { wall_id: wall.id })
+            })
+        });
+        let axes = grid.into_iter().flat_map(|grid| {
+            grid.axes.iter().map(|axis| {
+                let p = match axis.direction {
+                    GridDirection::Vertical => Point2::new(axis.offset, point.y),
+                    GridDirection::Horizontal => Point2::new(point.x, axis.offset),
+                };
+                candidate(p, SnapTarget::GridLine { axis: axis.name.clone() })
+            })
+        });
+        if let Some(hit) = nearest(edges.chain(axes), tolerance) {
+            return Ok(Snap { point: hit.point, target: hit.target });
+        }
+
+        // Orthogonal lock
+        if let Some(anchor) = anchor {
+            let locks = [Point2::new(anchor.x, point.y), Point2::new(point.x, anchor.y)]
+                .map(|p| candidate(p, SnapTarget::Orthogonal));
+            if let Some(hit) = nearest(locks.into_iter(), tolerance) {
+                return Ok(Snap { point: hit.point, target: hit.target });
+            }
+        }
+
+        Ok(Snap { point, target: SnapTarget::None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap_point() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+        store.create_grid(building_id).unwrap();
+        for (name, direction, offset) in [("A", GridDirection::Vertical, 30.0), ("1", GridDirection::Horizontal, 30.0)] {
+            store
+                .add_grid_axis(building_id, GridAxis { name: name.to_string(), direction, offset })
+                .unwrap();
+        }
+        let snap = |x: f64, y: f64, anchor: Option<Point2>| store.snap_point(level_id, Point2::new(x, y), 0.5, anchor).unwrap();
+
+        // The wall end beats its faces
+        let s = snap(19.8, 0.2, None);
+        assert_eq!(s.point, Point2::new(20.0, 0.0));
+        assert_eq!(s.target, SnapTarget::WallEndpoint { wall_id });
+
+        let s = snap(11.9, 9.8, None);
+        assert_eq!(s.point, Point2::new(12.0, 10.0));
+        assert_eq!(s.target, SnapTarget::RoomCorner { room_id });
+
+        let s = snap(29.7, 30.2, None);
+        assert_eq!(s.point, Point2::new(30.0, 30.0));
+        assert_eq!(s.target, SnapTarget::GridIntersection { axes: ["A".to_string(), "1".to_string()] });
+
+        // Along the wall, the nearer face: 5.5" thick, so 0.229' off center
+        let s = snap(15.0, 0.4, None);
+        assert_eq!(s.target, SnapTarget::WallEdge { wall_id });
+        assert!((s.point.y - 5.5 / 24.0).abs() < 1e-9);
+        assert_eq!(s.point.x, 15.0);
+
+        let s = snap(29.8, 20.0, None);
+        assert_eq!(s.point, Point2::new(30.0, 20.0));
+        assert_eq!(s.target, SnapTarget::GridLine { axis: "A".to_string() });
+
+        // Nothing nearby: locked to the anchor if there is one
+        let s = snap(40.3, 15.0, Some(Point2::new(40.0, 5.0)));
+        assert_eq!(s.point, Point2::new(40.0, 15.0));
+        assert_eq!(s.target, SnapTarget::Orthogonal);
+        let s = snap(45.0, 15.0, None);
+        assert_eq!(s.point, Point2::new(45.0, 15.0));
+        assert_eq!(s.target, SnapTarget::None);
+
+        assert!(store.snap_point(LevelId::new(), Point2::origin(), 0.5, None).is_err());
+    }
+}
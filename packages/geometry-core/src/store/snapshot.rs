@@ -0,0 +1,197 @@
+// Checkpoints for try-and-roll-back workflows
+// A snapshot only records a position in the project's event log, so taking
+// one is O(1). Restoring truncates the log back to that position and rebuilds
+// the project by replay.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::*;
+use super::Store;
+
+/// A checkpoint into a project's event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: SnapshotId,
+    pub project_id: ProjectId,
+    /// Number of events in the log when the snapshot was taken
+    pub event_count: usize,
+    /// ID of the last event at that point (detects rewritten history)
+    pub last_event_id: EventId,
+    pub created_at: DateTime<Utc>,
+    /// Name for the design alternative the snapshot captures
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl Store {
+    // ========== Snapshot Operations ==========
+
+    pub fn create_snapshot(&mut self, project_id: ProjectId) -> Result<SnapshotId> {
+        let log = self.event_logs.get(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+        let last_event_id = log.events()
+            .last()
+            .map(|e| e.id)
+            .ok_or_else(|| anyhow!("Project has no history to snapshot"))?;
+
+        let snapshot = Snapshot {
+            id: SnapshotId::new(),
+            project_id,
+            event_count: log.len(),
+            last_event_id,
+            created_at: Utc::now(),
+            label: None,
+        };
+        let snapshot_id = snapshot.id;
+        self.snapshots.insert(snapshot_id, snapshot);
+
+        Ok(snapshot_id)
+    }
+
+    pub fn get_snapshot(&self, id: SnapshotId) -> Option<&Snapshot> {
+        self.snapshots.get(&id)
+    }
+
+    /// Name a snapshot, e.g. "Three-car garage"
+    pub fn label_snapshot(&mut self, snapshot_id: SnapshotId, label: impl Into<String>) -> Result<()> {
+        let snapshot = self.snapshots.get_mut(&snapshot_id)
+            .ok_or_else(|| anyhow!("Snapshot not found: {:?}", snapshot_id))?;
+        snapshot.label = Some(label.into());
+        Ok(())
+    }
+
+    /// A project's snapshots, oldest first
+    pub fn get_project_snapshots(&self, project_id: ProjectId) -> Vec<&Snapshot> {
+        let mut snapshots: Vec<&Snapshot> = self.snapshots
+            .values()
+            .filter(|s| s.project_id == project_id)
+            .collect();
+        snapshots.sort_by_key(|s| s.event_count);
+        snapshots
+    }
+
+    /// A copy of the store with a snapshot's project rolled back to it,
+    /// for looking at an earlier state without leaving the current one
+    pub fn at_snapshot(&self, snapshot_id: SnapshotId) -> Result<Store> {
+        let mut store = self.clone();
+        store.subscribers.clear();
+        store.restore_snapshot(snapshot_id)?;
+        Ok(store)
+    }
+
+    /// Roll a project back to a snapshot. Later snapshots of the same
+    /// project are discarded since their history no longer exists.
+    pub fn restore_snapshot(&mut self, snapshot_id: SnapshotId) -> Result<()> {
+        let snapshot = self.snapshots.get(&snapshot_id)
+            .ok_or_else(|| anyhow!("Snapshot not found: {:?}", snapshot_id))?
+            .clone();
+
+        let log = self.event_logs.get_mut(&snapshot.project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", snapshot.project_id))?;
+
+        let matches = log.events()
+            .get(snapshot.event_count - 1)
+            .map(|e| e.id == snapshot.last_event_id)
+            .unwrap_or(false);
+        if !matches {
+            return Err(anyhow!("Snapshot no longer matches the project history"));
+        }
+
+        log.truncate(snapshot.event_count);
+        self.snapshots.retain(|_, s| {
+            s.project_id != snapshot.project_id || s.event_count <= snapshot.event_count
+        });
+
+        self.rebuild_project(snapshot.project_id)
+    }
+
+    pub fn remove_snapshot(&mut self, snapshot_id: SnapshotId) -> Result<()> {
+        self.snapshots.remove(&snapshot_id)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("Snapshot not found: {:?}", snapshot_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_snapshot() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(12.0, 10.0))
+            .unwrap();
+
+        let snapshot_id = store.create_snapshot(project_id).unwrap();
+
+        // Try a layout
+        store.remove_room(room_id).unwrap();
+        store
+            .create_room(level_id, RoomType::Bedroom, "Bedroom", Polygon2::rectangle(14.0, 12.0))
+            .unwrap();
+        let later = store.create_snapshot(project_id).unwrap();
+        store.add_level(building_id, "Second Floor", 9.0, 9.0).unwrap();
+
+        store.restore_snapshot(snapshot_id).unwrap();
+        assert_eq!(store.get_level(level_id).unwrap().room_ids, vec![room_id]);
+        assert_eq!(store.get_building(building_id).unwrap().level_ids.len(), 1);
+        assert_eq!(store.get_event_log(project_id).unwrap().len(), 4);
+        assert!(store.get_snapshot(later).is_none());
+
+        // The snapshot stays valid for another attempt
+        store.add_level(building_id, "Second Floor", 9.0, 9.0).unwrap();
+        store.restore_snapshot(snapshot_id).unwrap();
+        assert_eq!(store.get_building(building_id).unwrap().level_ids.len(), 1);
+    }
+
+    #[test]
+    fn test_view_labeled_snapshot() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let base = store.create_snapshot(project_id).unwrap();
+        store.label_snapshot(base, "One story").unwrap();
+        store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let later = store.create_snapshot(project_id).unwrap();
+
+        let snapshots = store.get_project_snapshots(project_id);
+        assert_eq!(snapshots.iter().map(|s| s.id).collect::<Vec<_>>(), vec![base, later]);
+        assert_eq!(snapshots[0].label.as_deref(), Some("One story"));
+
+        // The copy is rolled back; the store and its snapshots are not
+        let earlier = store.at_snapshot(base).unwrap();
+        assert!(earlier.get_building(building_id).unwrap().level_ids.is_empty());
+        assert_eq!(store.get_building(building_id).unwrap().level_ids.len(), 1);
+        assert!(store.get_snapshot(later).is_some());
+        assert!(store.label_snapshot(SnapshotId::new(), "Missing").is_err());
+    }
+
+    #[test]
+    fn test_stale_snapshot() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        store.add_building(project_id, "Main").unwrap();
+        let snapshot_id = store.create_snapshot(project_id).unwrap();
+
+        // Undo past the snapshot and branch off
+        store.undo(project_id).unwrap();
+        store.add_building(project_id, "Other").unwrap();
+
+        assert!(store.restore_snapshot(snapshot_id).is_err());
+        assert!(store.at_snapshot(snapshot_id).is_err());
+        assert!(store.restore_snapshot(SnapshotId::new()).is_err());
+        assert!(store.create_snapshot(ProjectId::new()).is_err());
+    }
+}
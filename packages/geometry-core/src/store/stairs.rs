@@ -0,0 +1,148 @@
+// Stairs between levels
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::stair::{MAX_RISER_HEIGHT, MIN_STAIR_WIDTH, MIN_TREAD_DEPTH};
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Stair Operations ==========
+
+    /// Add a straight stair climbing from one level to the next. Width is in
+    /// feet; riser height and tread depth are in inches and must meet the
+    /// residential code limits.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stair(
+        &mut self,
+        from_level_id: LevelId,
+        to_level_id: LevelId,
+        start: Point2,
+        direction: f64,
+        width: f64,
+        max_riser_height: f64,
+        tread_depth: f64,
+    ) -> Result<StairId> {
+        if width < MIN_STAIR_WIDTH {
+            return Err(anyhow!("Stair width must be at least {} ft, got {}", MIN_STAIR_WIDTH, width));
+        }
+        if !(max_riser_height > 0.0 && max_riser_height <= MAX_RISER_HEIGHT) {
+            return Err(anyhow!(
+                "Riser height must be between 0 and {} in, got {}",
+                MAX_RISER_HEIGHT, max_riser_height
+            ));
+        }
+        if tread_depth < MIN_TREAD_DEPTH {
+            return Err(anyhow!("Tread depth must be at least {} in, got {}", MIN_TREAD_DEPTH, tread_depth));
+        }
+
+        let from = self.levels.get(&from_level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", from_level_id))?;
+        let to = self.levels.get(&to_level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", to_level_id))?;
+        if from.building_id != to.building_id {
+            return Err(anyhow!("Stair levels must be in the same building"));
+        }
+        if to.elevation <= from.elevation {
+            return Err(anyhow!("Stair must climb to a higher level"));
+        }
+        let project_id = self.level_project_id(from_level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", from_level_id))?;
+
+        let stair = Stair::new(
+            from_level_id,
+            to_level_id,
+            start,
+            direction,
+            width,
+            max_riser_height,
+            tread_depth,
+        );
+        let stair_id = stair.id;
+
+        self.record_event(project_id, EventKind::StairCreated { stair: stair.clone() });
+        self.stairs.insert(stair_id, stair);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(stair_id)
+    }
+
+    pub fn get_stair(&self, id: StairId) -> Option<&Stair> {
+        self.stairs.get(&id)
+    }
+
+    /// Stairs starting on a level
+    pub fn get_level_stairs(&self, level_id: LevelId) -> Vec<&Stair> {
+        self.stairs
+            .values()
+            .filter(|s| s.from_level_id == level_id)
+            .collect()
+    }
+
+    /// Solve a stair's flight for the current level elevations
+    pub fn get_stair_run(&self, stair_id: StairId) -> Result<StairRun> {
+        let stair = self.stairs.get(&stair_id)
+            .ok_or_else(|| anyhow!("Stair not found: {:?}", stair_id))?;
+        let from = self.levels.get(&stair.from_level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", stair.from_level_id))?;
+        let to = self.levels.get(&stair.to_level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", stair.to_level_id))?;
+
+        stair.solve(to.elevation - from.elevation)
+            .ok_or_else(|| anyhow!("Stair levels are not stacked: {:?}", stair_id))
+    }
+
+    pub fn remove_stair(&mut self, stair_id: StairId) -> Result<()> {
+        let stair = self.stairs.remove(&stair_id)
+            .ok_or_else(|| anyhow!("Stair not found: {:?}", stair_id))?;
+
+        if let Some(project_id) = self.level_project_id(stair.from_level_id) {
+            self.record_event(project_id, EventKind::StairRemoved { stair_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_stair() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let first = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let second = store.add_level(building_id, "Second Floor", 9.0, 8.0).unwrap();
+        let start = Point2::new(2.0, 5.0);
+
+        assert!(store.create_stair(first, second, start, 0.0, 2.5, 7.75, 10.0).is_err());
+        assert!(store.create_stair(first, second, start, 0.0, 3.0, 8.25, 10.0).is_err());
+        assert!(store.create_stair(first, second, start, 0.0, 3.0, 7.75, 9.0).is_err());
+        assert!(store.create_stair(second, first, start, 0.0, 3.0, 7.75, 10.0).is_err());
+
+        let stair_id = store.create_stair(first, second, start, 90.0, 3.0, 7.75, 10.0).unwrap();
+        let run = store.get_stair_run(stair_id).unwrap();
+        assert_eq!(run.riser_count, 14);
+        let end = store.get_stair(stair_id).unwrap().end(&run);
+        assert!((end.y - (5.0 + run.total_run)).abs() < 1e-9);
+        assert_eq!(store.get_level_stairs(first).len(), 1);
+
+        store.undo(project_id).unwrap();
+        assert!(store.get_stair(stair_id).is_none());
+        store.redo(project_id).unwrap();
+        assert!(store.get_stair(stair_id).is_some());
+
+        store.remove_level(second).unwrap();
+        assert!(store.stairs.is_empty());
+    }
+}
@@ -0,0 +1,363 @@
+// Storage abstraction for durable backends
+// The event log is the source of truth: a backend persists each project's
+// events and the store rebuilds entities by replaying them on load. The few
+// settings that aren't events (wall framing, room finishes, bounding walls)
+// are saved next to the log and laid back over the replayed entities. A
+// SQLite (or any other) backend only has to implement StorageBackend.
+
+use std::collections::{HashMap, HashSet};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::*;
+use super::Store;
+
+/// A durable home for project event logs
+pub trait StorageBackend {
+    /// Replace a project's stored history with `events`.
+    /// Implementations must write all events or none.
+    fn save_project(&mut self, project_id: ProjectId, events: &[Event]) -> Result<()>;
+
+    /// Load a project's events in recorded order
+    fn load_project(&self, project_id: ProjectId) -> Result<Vec<Event>>;
+
+    fn list_projects(&self) -> Result<Vec<ProjectId>>;
+
+    /// Removes the project's events and its unlogged state
+    fn delete_project(&mut self, project_id: ProjectId) -> Result<()>;
+
+    /// Replace the state saved next to a project's events
+    fn save_project_state(&mut self, project_id: ProjectId, state: &UnloggedState) -> Result<()>;
+
+    /// None if only the project's events were saved
+    fn load_project_state(&self, project_id: ProjectId) -> Result<Option<UnloggedState>>;
+
+    /// Wall assemblies are shared across projects, so they're stored apart
+    /// from the event logs
+    fn save_assemblies(&mut self, assemblies: &[WallAssembly]) -> Result<()>;
+
+    fn load_assemblies(&self) -> Result<Vec<WallAssembly>>;
+
+    /// Users, keys and project grants live outside the event logs too, so
+    /// undoing an edit can't change who has access
+    fn save_access(&mut self, access: &AccessControl) -> Result<()>;
+
+    /// An empty AccessControl if none has been saved
+    fn load_access(&self) -> Result<AccessControl>;
+}
+
+/// A project's walls, rooms and openings as they stand, with the framing
+/// layouts generated for them. Only what the event log doesn't record is
+/// taken from these copies on load.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnloggedState {
+    pub walls: Vec<Wall>,
+    pub rooms: Vec<Room>,
+    pub openings: Vec<Opening>,
+    pub framing_layouts: Vec<FramingLayout>,
+    pub floor_framing_layouts: Vec<FloorFramingLayout>,
+}
+
+/// In-memory backend holding serialized event logs (for tests and tooling)
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    projects: HashMap<ProjectId, String>,
+    project_states: HashMap<ProjectId, String>,
+    assemblies: Option<String>,
+    access: Option<String>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn save_project(&mut self, project_id: ProjectId, events: &[Event]) -> Result<()> {
+        // Serialize before touching the map so a failure leaves the old copy
+        let json = serde_json::to_string(events)?;
+        self.projects.insert(project_id, json);
+        Ok(())
+    }
+
+    fn load_project(&self, project_id: ProjectId) -> Result<Vec<Event>> {
+        let json = self.projects.get(&project_id)
+            .ok_or_else(|| anyhow!("Project not found in storage: {:?}", project_id))?;
+        Ok(serde_json::from_str(json)?)
+    }
+
+    fn list_projects(&self) -> Result<Vec<ProjectId>> {
+        Ok(self.projects.keys().copied().collect())
+    }
+
+    fn delete_project(&mut self, project_id: ProjectId) -> Result<()> {
+        self.project_states.remove(&project_id);
+        self.projects.remove(&project_id)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("Project not found in storage: {:?}", project_id))
+    }
+
+    fn save_project_state(&mut self, project_id: ProjectId, state: &UnloggedState) -> Result<()> {
+        let json = serde_json::to_string(state)?;
+        self.project_states.insert(project_id, json);
+        Ok(())
+    }
+
+    fn load_project_state(&self, project_id: ProjectId) -> Result<Option<UnloggedState>> {
+        match self.project_states.get(&project_id) {
+            Some(json) => Ok(Some(serde_json::from_str(json)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn save_assemblies(&mut self, assemblies: &[WallAssembly]) -> Result<()> {
+        self.assemblies = Some(serde_json::to_string(assemblies)?);
+        Ok(())
+    }
+
+    fn load_assemblies(&self) -> Result<Vec<WallAssembly>> {
+        match &self.assemblies {
+            Some(json) => Ok(serde_json::from_str(json)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn save_access(&mut self, access: &AccessControl) -> Result<()> {
+        self.access = Some(serde_json::to_string(access)?);
+        Ok(())
+    }
+
+    fn load_access(&self) -> Result<AccessControl> {
+        match &self.access {
+            Some(json) => Ok(serde_json::from_str(json)?),
+            None => Ok(AccessControl::new()),
+        }
+    }
+}
+
+impl Store {
+    // ========== Persistence Operations ==========
+
+    /// Write a project's event log and unlogged state to a backend
+    pub fn save_project_to(
+        &self,
+        backend: &mut dyn StorageBackend,
+        project_id: ProjectId,
+    ) -> Result<()> {
+        let log = self.event_logs.get(&project_id)
+            .ok_or_else(|| anyhow!("Project not found: {:?}", project_id))?;
+        backend.save_project(project_id, log.events())?;
+        backend.save_project_state(project_id, &self.unlogged_state(project_id))
+    }
+
+    /// Write every project's event log and the wall assemblies to a backend
+    pub fn save_all_to(&self, backend: &mut dyn StorageBackend) -> Result<()> {
+        let assemblies: Vec<WallAssembly> = self.wall_assemblies.values().cloned().collect();
+        backend.save_assemblies(&assemblies)?;
+        for project_id in self.event_logs.keys() {
+            self.save_project_to(backend, *project_id)?;
+        }
+        Ok(())
+    }
+
+    /// Load a project from a backend, replacing any in-memory copy
+    pub fn load_project_from(
+        &mut self,
+        backend: &dyn StorageBackend,
+        project_id: ProjectId,
+    ) -> Result<()> {
+        let events = backend.load_project(project_id)?;
+        if events.iter().any(|e| e.project_id != project_id) {
+            return Err(anyhow!("Stored events belong to another project"));
+        }
+
+        self.event_logs.insert(project_id, EventLog::from_events(events));
+        self.rebuild_project(project_id)?;
+
+        if let Some(state) = backend.load_project_state(project_id)? {
+            for layout in state.framing_layouts {
+                self.framing_layouts.insert(layout.id, layout);
+            }
+            for layout in state.floor_framing_layouts {
+                if self.levels.contains_key(&layout.level_id) {
+                    self.floor_framing_layouts.insert(layout.level_id, layout);
+                }
+            }
+            self.restore_unlogged_state(
+                state.walls.into_iter().map(|w| (w.id, w)).collect(),
+                state.rooms.into_iter().map(|r| (r.id, r)).collect(),
+                state.openings.into_iter().map(|o| (o.id, o)).collect(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Build a store from every project in a backend (e.g. on restart)
+    pub fn load_from(backend: &dyn StorageBackend) -> Result<Store> {
+        let mut store = Store::new();
+        for assembly in backend.load_assemblies()? {
+            store.wall_assemblies.insert(assembly.id, assembly);
+        }
+        for project_id in backend.list_projects()? {
+            store.load_project_from(backend, project_id)?;
+        }
+        Ok(store)
+    }
+
+    /// Copies of a project's walls, rooms and openings and their framing
+    /// layouts, for saving next to its event log
+    fn unlogged_state(&self, project_id: ProjectId) -> UnloggedState {
+        let level_ids: HashSet<LevelId> = self.levels
+            .keys()
+            .copied()
+            .filter(|id| self.level_project_id(*id) == Some(project_id))
+            .collect();
+        let walls: Vec<Wall> = self.walls
+            .values()
+            .filter(|w| level_ids.contains(&w.level_id))
+            .cloned()
+            .collect();
+        let wall_ids: HashSet<WallId> = walls.iter().map(|w| w.id).collect();
+
+        UnloggedState {
+            framing_layouts: walls.iter()
+                .filter_map(|w| self.framing_layouts.get(&w.framing_layout_id?))
+                .cloned()
+                .collect(),
+            rooms: self.rooms
+                .values()
+                .filter(|r| level_ids.contains(&r.level_id))
+                .cloned()
+                .collect(),
+            openings: self.openings
+                .values()
+                .filter(|o| wall_ids.contains(&o.wall_id))
+                .cloned()
+                .collect(),
+            floor_framing_layouts: level_ids.iter()
+                .filter_map(|id| self.floor_framing_layouts.get(id))
+                .cloned()
+                .collect(),
+            walls,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_reload() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Durable", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0)).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(40.0, 0.0), 9.0)
+            .unwrap();
+
+        let mut backend = MemoryBackend::new();
+        store.save_all_to(&mut backend).unwrap();
+
+        let mut reloaded = Store::load_from(&backend).unwrap();
+        assert_eq!(reloaded.get_project(project_id).unwrap().name, "Durable");
+        assert_eq!(reloaded.get_level(level_id).unwrap().wall_ids, vec![wall_id]);
+        assert!(reloaded.get_wall_assembly(assembly_id).is_some());
+        let stats = reloaded.get_building_stats(building_id).unwrap();
+        assert!((stats.total_area - 1200.0).abs() < 1e-10);
+
+        // New events continue the stored numbering
+        let last_id = store.get_event_log(project_id).unwrap().events().last().unwrap().id;
+        reloaded.add_building(project_id, "Garage").unwrap();
+        let log = reloaded.get_event_log(project_id).unwrap();
+        assert_eq!(log.events().last().unwrap().id, last_id + 1);
+    }
+
+    #[test]
+    fn test_save_and_reload_unlogged_state() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Durable", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0)).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        let wall_id = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(40.0, 0.0), 9.0)
+            .unwrap();
+        let room_id = store
+            .create_room(level_id, RoomType::LivingRoom, "Living", Polygon2::rectangle(40.0, 30.0))
+            .unwrap();
+        let opening_id = store.add_opening(wall_id, OpeningType::Window, 0.5, 3.0, 4.0, 3.0).unwrap();
+
+        // None of these are recorded as events
+        let wall = store.walls.get_mut(&wall_id).unwrap();
+        wall.base_offset = 0.5;
+        wall.framing_config.stud_spacing = 24.0;
+        wall.framing_config.is_load_bearing = true;
+        let room = store.rooms.get_mut(&room_id).unwrap();
+        room.floor_finish = "Oak".to_string();
+        room.ceiling_height = Some(10.0);
+        store.openings.get_mut(&opening_id).unwrap().window_properties = Some(WindowProperties::new(0.27, 0.3));
+        store.set_room_bounding_walls(room_id, vec![wall_id]).unwrap();
+        let layout_id = store.generate_wall_framing(wall_id).unwrap();
+        store.generate_floor_framing(level_id, FloorFramingConfig::default()).unwrap();
+
+        let mut backend = MemoryBackend::new();
+        store.save_all_to(&mut backend).unwrap();
+        let reloaded = Store::load_from(&backend).unwrap();
+
+        let wall = reloaded.get_wall(wall_id).unwrap();
+        assert_eq!(wall.base_offset, 0.5);
+        assert_eq!(wall.framing_config.stud_spacing, 24.0);
+        assert!(wall.framing_config.is_load_bearing);
+        assert_eq!(wall.framing_layout_id, Some(layout_id));
+        let layout = reloaded.get_framing_layout(layout_id).unwrap();
+        assert_eq!(layout.members.len(), store.get_framing_layout(layout_id).unwrap().members.len());
+        assert!(reloaded.floor_framing_layouts.contains_key(&level_id));
+
+        let room = reloaded.get_room(room_id).unwrap();
+        assert_eq!(room.floor_finish, "Oak");
+        assert_eq!(room.ceiling_height, Some(10.0));
+        assert_eq!(room.bounding_wall_ids, vec![wall_id]);
+
+        let properties = reloaded.get_opening(opening_id).unwrap().window_properties.as_ref().unwrap();
+        assert_eq!(properties.u_value, 0.27);
+    }
+
+    #[test]
+    fn test_save_and_reload_access() {
+        let mut access = AccessControl::new();
+        let user_id = access.add_user("Owner");
+        access.add_key(user_id, "digest", "laptop").unwrap();
+        let project_id = ProjectId::new();
+        access.grant(project_id, user_id, ProjectRole::Owner).unwrap();
+
+        let mut backend = MemoryBackend::new();
+        assert_eq!(backend.load_access().unwrap(), AccessControl::new());
+        backend.save_access(&access).unwrap();
+        let reloaded = backend.load_access().unwrap();
+        assert_eq!(reloaded, access);
+        assert_eq!(reloaded.authenticate(Some("digest")), Ok(user_id));
+        assert_eq!(reloaded.role(project_id, user_id), Some(ProjectRole::Owner));
+    }
+
+    #[test]
+    fn test_load_missing_project() {
+        let backend = MemoryBackend::new();
+        let mut store = Store::new();
+        assert!(store.load_project_from(&backend, ProjectId::new()).is_err());
+        assert!(Store::load_from(&backend).unwrap().projects.is_empty());
+    }
+}
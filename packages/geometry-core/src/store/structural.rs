@@ -0,0 +1,184 @@
+// Beams and posts
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+impl Store {
+    // ========== Beam and Post Operations ==========
+
+    /// Add a beam between two plan points, flush with the framing above the
+    /// level
+    pub fn create_beam(
+        &mut self,
+        level_id: LevelId,
+        start: Point2,
+        end: Point2,
+        profile: MemberProfile,
+        material: FramingMaterial,
+    ) -> Result<BeamId> {
+        if start.distance_to(&end) < 1e-6 {
+            return Err(anyhow!("Beam start and end must differ"));
+        }
+        let level = self.levels.get(&level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+        let beam = Beam::new(level_id, start, end, profile, material, level.floor_to_floor);
+        let beam_id = beam.id;
+
+        self.record_event(project_id, EventKind::BeamCreated { beam: beam.clone() });
+        self.beams.insert(beam_id, beam);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(beam_id)
+    }
+
+    pub fn get_beam(&self, id: BeamId) -> Option<&Beam> {
+        self.beams.get(&id)
+    }
+
+    pub fn get_level_beams(&self, level_id: LevelId) -> Vec<&Beam> {
+        let mut beams: Vec<&Beam> = self.beams
+            .values()
+            .filter(|b| b.level_id == level_id)
+            .collect();
+        beams.sort_by_key(|b| b.id.to_string());
+        beams
+    }
+
+    pub fn remove_beam(&mut self, beam_id: BeamId) -> Result<()> {
+        let beam = self.beams.remove(&beam_id)
+            .ok_or_else(|| anyhow!("Beam not found: {:?}", beam_id))?;
+
+        if let Some(project_id) = self.level_project_id(beam.level_id) {
+            self.record_event(project_id, EventKind::BeamRemoved { beam_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stand a post on a level's floor. A post under a beam stops at the
+    /// beam's underside; otherwise it runs to the framing above the level.
+    pub fn create_post(
+        &mut self,
+        level_id: LevelId,
+        location: Point2,
+        profile: MemberProfile,
+        material: FramingMaterial,
+    ) -> Result<PostId> {
+        let level = self.levels.get(&level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+        let project_id = self.level_project_id(level_id)
+            .ok_or_else(|| anyhow!("Level not found: {:?}", level_id))?;
+
+        let height = self.get_level_beams(level_id)
+            .into_iter()
+            .filter(|b| b.is_over(&location))
+            .map(|b| b.bottom_height())
+            .fold(level.floor_to_floor, f64::min);
+        if height <= 0.0 {
+            return Err(anyhow!("No room for a post under the beam at {:?}", location));
+        }
+
+        let post = Post::new(level_id, location, profile, material, height);
+        let post_id = post.id;
+
+        self.record_event(project_id, EventKind::PostCreated { post: post.clone() });
+        self.posts.insert(post_id, post);
+
+        if let Some(project) = self.projects.get_mut(&project_id) {
+            project.touch();
+        }
+
+        Ok(post_id)
+    }
+
+    pub fn get_post(&self, id: PostId) -> Option<&Post> {
+        self.posts.get(&id)
+    }
+
+    pub fn get_level_posts(&self, level_id: LevelId) -> Vec<&Post> {
+        let mut posts: Vec<&Post> = self.posts
+            .values()
+            .filter(|p| p.level_id == level_id)
+            .collect();
+        posts.sort_by_key(|p| p.id.to_string());
+        posts
+    }
+
+    pub fn remove_post(&mut self, post_id: PostId) -> Result<()> {
+        let post = self.posts.remove(&post_id)
+            .ok_or_else(|| anyhow!("Post not found: {:?}", post_id))?;
+
+        if let Some(project_id) = self.level_project_id(post.level_id) {
+            self.record_event(project_id, EventKind::PostRemoved { post_id });
+            if let Some(project) = self.projects.get_mut(&project_id) {
+                project.touch();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beam_on_posts() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+
+        let profile = MemberProfile::new(LumberSize::Custom { width: 1.75, depth: 11.875 }, 2);
+        let beam_id = store
+            .create_beam(level_id, Point2::new(0.0, 12.0), Point2::new(16.0, 12.0), profile, FramingMaterial::LVL)
+            .unwrap();
+        assert!((store.get_beam(beam_id).unwrap().top_height - 9.0).abs() < 1e-9);
+
+        // Posts under the beam stop at its underside; others run full height
+        let post = MemberProfile::new(LumberSize::FourBySix, 1);
+        let under = store
+            .create_post(level_id, Point2::new(16.0, 12.0), post, FramingMaterial::DF)
+            .unwrap();
+        let clear = store
+            .create_post(level_id, Point2::new(8.0, 2.0), post, FramingMaterial::DF)
+            .unwrap();
+        assert!((store.get_post(under).unwrap().height - (9.0 - 11.875 / 12.0)).abs() < 1e-9);
+        assert!((store.get_post(clear).unwrap().height - 9.0).abs() < 1e-9);
+        assert_eq!(store.get_level_posts(level_id).len(), 2);
+
+        // Sawn posts go into the level's cut list; the LVL beam is ordered to length
+        let cut_list = store.level_cut_list(level_id).unwrap().total;
+        let post_length = (store.get_post(under).unwrap().height + 9.0) * 12.0;
+        assert!((cut_list.cut_length - post_length).abs() < 1e-9);
+
+        assert!(store
+            .create_beam(level_id, Point2::new(1.0, 1.0), Point2::new(1.0, 1.0), profile, FramingMaterial::LVL)
+            .is_err());
+
+        // Removal replays through undo/redo
+        store.remove_post(clear).unwrap();
+        store.undo(project_id).unwrap();
+        assert!(store.get_post(clear).is_some());
+        store.redo(project_id).unwrap();
+        assert!(store.get_post(clear).is_none());
+
+        store.remove_level(level_id).unwrap();
+        assert!(store.get_beam(beam_id).is_none());
+        assert!(store.get_post(under).is_none());
+    }
+}
@@ -0,0 +1,255 @@
+// All-or-nothing batches of mutations
+// A transaction runs against the live store and restores a copy of the
+// prior state if any step fails, so callers never observe half an edit.
+// BatchCommand is the serializable form used by the wasm batch API.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::domain::*;
+use super::Store;
+
+/// A typed mutation for batch execution. ID fields take either an entity ID
+/// or "$N", meaning the ID created by the Nth command of the same batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchCommand {
+    AddBuilding {
+        project_id: String,
+        name: String,
+    },
+    AddLevel {
+        building_id: String,
+        name: String,
+        elevation: f64,
+        floor_to_floor: f64,
+    },
+    SetLevelFootprint {
+        level_id: String,
+        points: Vec<Point2>,
+    },
+    CreateWall {
+        level_id: String,
+        /// Chosen from the wall's context when omitted
+        #[serde(default)]
+        assembly_id: Option<String>,
+        start: Point2,
+        end: Point2,
+        height: f64,
+    },
+    CreateRoom {
+        level_id: String,
+        room_type: RoomType,
+        name: String,
+        points: Vec<Point2>,
+    },
+    AddOpening {
+        wall_id: String,
+        opening_type: OpeningType,
+        position: f64,
+        width: f64,
+        height: f64,
+        sill_height: f64,
+    },
+    RemoveWall {
+        wall_id: String,
+    },
+    RemoveRoom {
+        room_id: String,
+    },
+    RemoveOpening {
+        opening_id: String,
+    },
+}
+
+impl Store {
+    // ========== Transaction Operations ==========
+
+    /// Run `f` against the store; if it returns an error, every change it
+    /// made is rolled back (including recorded events) and the error is
+    /// returned. Subscribers only see the events of committed transactions.
+    pub fn transaction<T>(&mut self, f: impl FnOnce(&mut Store) -> Result<T>) -> Result<T> {
+        let before = self.clone();
+        let outermost = self.held_events.is_none();
+        if outermost {
+            self.held_events = Some(Vec::new());
+        }
+
+        let result = f(self);
+        if result.is_err() {
+            // Subscribers are kept so ones added inside the transaction stay live
+            let subscribers = std::mem::take(&mut self.subscribers);
+            *self = before;
+            self.subscribers = subscribers;
+        } else if outermost {
+            self.release_held_events();
+        }
+        result
+    }
+
+    /// Apply commands in order as one transaction. Returns the ID created by
+    /// each command (None for removals).
+    pub fn execute_batch(&mut self, commands: &[BatchCommand]) -> Result<Vec<Option<String>>> {
+        self.transaction(|store| {
+            let mut created: Vec<Option<String>> = Vec::with_capacity(commands.len());
+            for (index, command) in commands.iter().enumerate() {
+                let id = store
+                    .apply_command(command, &created)
+                    .map_err(|e| anyhow!("Command {} failed: {}", index, e))?;
+                created.push(id);
+            }
+            Ok(created)
+        })
+    }
+
+    fn apply_command(
+        &mut self,
+        command: &BatchCommand,
+        created: &[Option<String>],
+    ) -> Result<Option<String>> {
+        let id = match command {
+            BatchCommand::AddBuilding { project_id, name } => {
+                let project_id = resolve_id(project_id, created)?;
+                self.add_building(project_id, name.clone())?.to_string()
+            }
+            BatchCommand::AddLevel { building_id, name, elevation, floor_to_floor } => {
+                let building_id = resolve_id(building_id, created)?;
+                self.add_level(building_id, name.clone(), *elevation, *floor_to_floor)?
+                    .to_string()
+            }
+            BatchCommand::SetLevelFootprint { level_id, points } => {
+                let level_id = resolve_id(level_id, created)?;
+                self.set_level_footprint(level_id, Polygon2::new(points.clone()))?
+                    .to_string()
+            }
+            BatchCommand::CreateWall { level_id, assembly_id, start, end, height } => {
+                let level_id = resolve_id(level_id, created)?;
+                let wall_id = match assembly_id {
+                    Some(assembly_id) => {
+                        let assembly_id = resolve_id(assembly_id, created)?;
+                        self.create_wall(level_id, assembly_id, *start, *end, *height)?
+                    }
+                    None => self.create_wall_auto(level_id, *start, *end, *height)?,
+                };
+                wall_id.to_string()
+            }
+            BatchCommand::CreateRoom { level_id, room_type, name, points } => {
+                let level_id = resolve_id(level_id, created)?;
+                self.create_room(level_id, room_type.clone(), name.clone(), Polygon2::new(points.clone()))?
+                    .to_string()
+            }
+            BatchCommand::AddOpening { wall_id, opening_type, position, width, height, sill_height } => {
+                let wall_id = resolve_id(wall_id, created)?;
+                self.add_opening(wall_id, opening_type.clone(), *position, *width, *height, *sill_height)?
+                    .to_string()
+            }
+            BatchCommand::RemoveWall { wall_id } => {
+                self.remove_wall(resolve_id(wall_id, created)?)?;
+                return Ok(None);
+            }
+            BatchCommand::RemoveRoom { room_id } => {
+                self.remove_room(resolve_id(room_id, created)?)?;
+                return Ok(None);
+            }
+            BatchCommand::RemoveOpening { opening_id } => {
+                self.remove_opening(resolve_id(opening_id, created)?)?;
+                return Ok(None);
+            }
+        };
+        Ok(Some(id))
+    }
+}
+
+/// Parse an ID, following "$N" references to earlier commands
+fn resolve_id<T: FromStr>(raw: &str, created: &[Option<String>]) -> Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    let raw = match raw.strip_prefix('$') {
+        Some(index) => {
+            let index: usize = index.parse()
+                .map_err(|_| anyhow!("Invalid command reference: {}", raw))?;
+            created.get(index)
+                .and_then(|id| id.as_deref())
+                .ok_or_else(|| anyhow!("Command reference {} has no created ID", raw))?
+        }
+        None => raw,
+    };
+    T::from_str(raw).map_err(|e| anyhow!("Invalid ID {}: {}", raw, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Store, ProjectId, LevelId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        (store, project_id, level_id)
+    }
+
+    #[test]
+    fn test_transaction_rolls_back() {
+        let (mut store, project_id, level_id) = setup();
+        let events_before = store.get_event_log(project_id).unwrap().len();
+
+        let result: Result<()> = store.transaction(|tx| {
+            tx.create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(12.0, 10.0))?;
+            tx.add_opening(WallId::new(), OpeningType::Window, 1.0, 3.0, 4.0, 3.0)?;
+            Ok(())
+        });
+
+        assert!(result.is_err());
+        assert!(store.get_level(level_id).unwrap().room_ids.is_empty());
+        assert!(store.rooms.is_empty());
+        assert_eq!(store.get_event_log(project_id).unwrap().len(), events_before);
+
+        let room_id = store
+            .transaction(|tx| {
+                tx.create_room(level_id, RoomType::Kitchen, "Kitchen", Polygon2::rectangle(12.0, 10.0))
+            })
+            .unwrap();
+        assert_eq!(store.get_level(level_id).unwrap().room_ids, vec![room_id]);
+    }
+
+    #[test]
+    fn test_execute_batch_with_references() {
+        let (mut store, _, level_id) = setup();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+
+        let commands = vec![
+            BatchCommand::CreateWall {
+                level_id: level_id.to_string(),
+                assembly_id: Some(assembly_id.to_string()),
+                start: Point2::new(0.0, 0.0),
+                end: Point2::new(20.0, 0.0),
+                height: 9.0,
+            },
+            BatchCommand::AddOpening {
+                wall_id: "$0".to_string(),
+                opening_type: OpeningType::Window,
+                position: 0.5,
+                width: 3.0,
+                height: 4.0,
+                sill_height: 3.0,
+            },
+        ];
+        let created = store.execute_batch(&commands).unwrap();
+        let wall_id = WallId::from_str(created[0].as_ref().unwrap()).unwrap();
+        assert_eq!(store.get_wall_openings(wall_id).len(), 1);
+
+        // A bad reference discards the whole batch
+        let mut failing = commands.clone();
+        failing.push(BatchCommand::RemoveWall { wall_id: "$5".to_string() });
+        let err = store.execute_batch(&failing).unwrap_err();
+        assert!(err.to_string().contains("Command 2"));
+        assert_eq!(store.get_level(level_id).unwrap().wall_ids, vec![wall_id]);
+    }
+}
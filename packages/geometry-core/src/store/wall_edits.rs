@@ -0,0 +1,321 @@
+// Wall editing: move, split, merge and trim/extend
+// Every edit is built from the store's own wall and opening operations, so
+// each step lands in the event log and replays. Openings keep their place
+// on the plan: when a wall's extent changes, their positions along it are
+// recomputed, and when a wall is split or merged they move to whichever
+// wall now holds them. Split, merge and trim work on straight walls only.
+
+use anyhow::{anyhow, Result};
+
+use crate::domain::*;
+use super::Store;
+
+/// Wall ends closer than this (feet) count as the same point
+const END_TOLERANCE: f64 = 1e-6;
+
+impl Store {
+    // ========== Wall Edits ==========
+
+    /// Translate a wall by (dx, dy), openings and all
+    pub fn move_wall(&mut self, wall_id: WallId, dx: f64, dy: f64) -> Result<()> {
+        let wall = self.walls.get(&wall_id)
+            .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
+        let start = Point2::new(wall.start.x + dx, wall.start.y + dy);
+        let end = Point2::new(wall.end.x + dx, wall.end.y + dy);
+        self.set_wall_endpoints(wall_id, start, end)
+    }
+
+    /// Split a wall at `t` (0.0 = start, 1.0 = end). The original wall keeps
+    /// the first part; the second part becomes a new wall with the same
+    /// assembly, height and framing settings, which is returned.
+    pub fn split_wall(&mut self, wall_id: WallId, t: f64) -> Result<WallId> {
+        let wall = self.straight_wall(wall_id)?.clone();
+        if !(t > 0.0 && t < 1.0) {
+            return Err(anyhow!("Split point must be strictly between 0.0 and 1.0"));
+        }
+
+        let length = wall.length();
+        let openings: Vec<(OpeningId, f64, f64)> = self.get_wall_openings(wall_id)
+            .iter()
+            .map(|o| (o.id, o.position_along_wall, o.width))
+            .collect();
+        if openings.iter().any(|(_, position, width)| (position - t).abs() * length < width / 2.0) {
+            return Err(anyhow!("Split point falls within an opening"));
+        }
+        let split = wall.point_at(t * length);
+
+        self.transaction(|store| {
+            store.set_wall_endpoints(wall_id, wall.start, split)?;
+            let new_id = store.create_wall(wall.level_id, wall.assembly_id, split, wall.end, wall.height)?;
+            if let Some(new_wall) = store.walls.get_mut(&new_id) {
+                new_wall.base_offset = wall.base_offset;
+                new_wall.framing_config = wall.framing_config.clone();
+            }
+
+            for (opening_id, position, _) in openings {
+                if position < t {
+                    store.rehost_opening(opening_id, wall_id, position / t)?;
+                } else {
+                    store.rehost_opening(opening_id, new_id, (position - t) / (1.0 - t))?;
+                }
+            }
+
+            for room in store.rooms.values_mut() {
+                if let Some(index) = room.bounding_wall_ids.iter().position(|id| *id == wall_id) {
+                    room.bounding_wall_ids.insert(index + 1, new_id);
+                }
+            }
+
+            Ok(new_id)
+        })
+    }
+
+    /// Join two collinear walls that share an end into one. Wall `a` is
+    /// kept, keeping its direction, and takes over `b`'s openings and room
+    /// boundaries; `b` is removed.
+    pub fn merge_walls(&mut self, a: WallId, b: WallId) -> Result<WallId> {
+        if a == b {
+            return Err(anyhow!("Cannot merge a wall with itself"));
+        }
+        let wall_a = self.straight_wall(a)?.clone();
+        let wall_b = self.straight_wall(b)?.clone();
+        if wall_a.level_id != wall_b.level_id {
+            return Err(anyhow!("Walls are on different levels"));
+        }
+        if wall_a.assembly_id != wall_b.assembly_id {
+            return Err(anyhow!("Walls have different assemblies"));
+        }
+        if (wall_a.height - wall_b.height).abs() > 1e-9 {
+            return Err(anyhow!("Walls have different heights"));
+        }
+
+        // The shared end, then the far end of each wall
+        let ends = [
+            (wall_a.end, wall_b.start, wall_a.start, wall_b.end),
+            (wall_a.end, wall_b.end, wall_a.start, wall_b.start),
+            (wall_a.start, wall_b.start, wall_a.end, wall_b.end),
+            (wall_a.start, wall_b.end, wall_a.end, wall_b.start),
+        ];
+        let (shared, _, far_a, far_b) = ends
+            .into_iter()
+            .find(|(pa, pb, _, _)| pa.distance_to(pb) < END_TOLERANCE)
+            .ok_or_else(|| anyhow!("Walls do not share an end"))?;
+
+        // B must carry on along A's line, away from A
+        let (ux, uy) = (shared.x - far_a.x, shared.y - far_a.y);
+        let (vx, vy) = (far_b.x - shared.x, far_b.y - shared.y);
+        let cross = (ux * vy - uy * vx) / (wall_a.length() * wall_b.length());
+        if cross.abs() > 1e-6 || ux * vx + uy * vy <= 0.0 {
+            return Err(anyhow!("Walls are not collinear"));
+        }
+
+        let (start, end) = if shared == wall_a.end { (wall_a.start, far_b) } else { (far_b, wall_a.end) };
+        let merged_length = start.distance_to(&end);
+        let (dx, dy) = ((end.x - start.x) / merged_length, (end.y - start.y) / merged_length);
+
+        // Openings keep their centers on the plan
+        let positions: Vec<(OpeningId, f64)> = [&wall_a, &wall_b]
+            .iter()
+            .flat_map(|wall| {
+                self.get_wall_openings(wall.id).into_iter().map(move |o| {
+                    let center = wall.point_at(o.position_along_wall * wall.length());
+                    let along = (center.x - start.x) * dx + (center.y - start.y) * dy;
+                    (o.id, (along / merged_length).clamp(0.0, 1.0))
+                })
+            })
+            .collect();
+
+        self.transaction(|store| {
+            store.set_wall_endpoints(a, start, end)?;
+            for (opening_id, position) in positions {
+                store.rehost_opening(opening_id, a, position)?;
+            }
+
+            for room in store.rooms.values_mut() {
+                if !room.bounding_wall_ids.contains(&b) {
+                    continue;
+                }
+                if room.bounding_wall_ids.contains(&a) {
+                    room.bounding_wall_ids.retain(|id| *id != b);
+                } else {
+                    for id in room.bounding_wall_ids.iter_mut().filter(|id| **id == b) {
+                        *id = a;
+                    }
+                }
+            }
+
+            store.remove_wall(b)?;
+            Ok(a)
+        })
+    }
+
+    /// Trim or extend a wall so it meets another wall's line. Whichever end
+    /// is nearer the intersection moves; openings keep their place on the
+    /// plan and must still fit on the wall.
+    pub fn trim_extend_to(&mut self, wall_id: WallId, other_id: WallId) -> Result<()> {
+        if wall_id == other_id {
+            return Err(anyhow!("Cannot trim a wall to itself"));
+        }
+        let wall = self.straight_wall(wall_id)?.clone();
+        let other = self.straight_wall(other_id)?.clone();
+        if wall.level_id != other.level_id {
+            return Err(anyhow!("Walls are on different levels"));
+        }
+
+        // wall.start + u * (wall.end - wall.start) lies on the other line
+        let (dx, dy) = (wall.end.x - wall.start.x, wall.end.y - wall.start.y);
+        let (ex, ey) = (other.end.x - other.start.x, other.end.y - other.start.y);
+        let denom = dx * ey - dy * ex;
+        if denom.abs() < 1e-9 * wall.length() * other.length() {
+            return Err(anyhow!("Walls are parallel"));
+        }
+        let (qx, qy) = (other.start.x - wall.start.x, other.start.y - wall.start.y);
+        let u = (qx * ey - qy * ex) / denom;
+        let meet = Point2::new(wall.start.x + u * dx, wall.start.y + u * dy);
+
+        let length = wall.length();
+        let (start, end, shift) = if u >= 0.5 {
+            (wall.start, meet, 0.0)
+        } else {
+            (meet, wall.end, u * length)
+        };
+        let new_length = start.distance_to(&end);
+        if new_length < 1e-10 {
+            return Err(anyhow!("Wall start and end points are too close"));
+        }
+
+        let mut positions: Vec<(OpeningId, f64)> = Vec::new();
+        for opening in self.get_wall_openings(wall_id) {
+            let along = opening.position_along_wall * length - shift;
+            let half = opening.width / 2.0;
+            if along - half < -1e-9 || along + half > new_length + 1e-9 {
+                return Err(anyhow!("Opening {:?} would no longer fit on the wall", opening.id));
+            }
+            positions.push((opening.id, along / new_length));
+        }
+
+        self.transaction(|store| {
+            store.set_wall_endpoints(wall_id, start, end)?;
+            for (opening_id, position) in positions {
+                store.rehost_opening(opening_id, wall_id, position)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn straight_wall(&self, wall_id: WallId) -> Result<&Wall> {
+        let wall = self.walls.get(&wall_id)
+            .ok_or_else(|| anyhow!("Wall not found: {:?}", wall_id))?;
+        if wall.is_curved() {
+            return Err(anyhow!("Curved walls cannot be split, merged or trimmed"));
+        }
+        Ok(wall)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Store, ProjectId, LevelId, WallAssemblyId) {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+        (store, project_id, level_id, assembly_id)
+    }
+
+    fn position(store: &Store, opening_id: OpeningId) -> (WallId, f64) {
+        let opening = store.get_opening(opening_id).unwrap();
+        (opening.wall_id, opening.position_along_wall)
+    }
+
+    #[test]
+    fn test_split_and_merge_walls() {
+        let (mut store, project_id, level_id, assembly_id) = setup();
+        let wall = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(40.0, 0.0), 9.0)
+            .unwrap();
+        let window = store.add_opening(wall, OpeningType::Window, 0.25, 4.0, 5.0, 2.0).unwrap();
+        let door = store.add_opening(wall, OpeningType::Door, 0.75, 3.0, 6.8, 0.0).unwrap();
+        let room = store
+            .create_room(level_id, RoomType::LivingRoom, "Living", Polygon2::rectangle(40.0, 20.0))
+            .unwrap();
+        store.set_room_bounding_walls(room, vec![wall]).unwrap();
+
+        // Splitting through the window is refused
+        assert!(store.split_wall(wall, 0.26).is_err());
+        assert!(store.split_wall(wall, 1.0).is_err());
+
+        let second = store.split_wall(wall, 0.5).unwrap();
+        assert_eq!(store.get_wall(wall).unwrap().end, Point2::new(20.0, 0.0));
+        assert_eq!(store.get_wall(second).unwrap().start, Point2::new(20.0, 0.0));
+        assert_eq!(position(&store, window), (wall, 0.5));
+        assert_eq!(position(&store, door), (second, 0.5));
+        assert_eq!(store.get_room(room).unwrap().bounding_wall_ids, vec![wall, second]);
+
+        // The log replays to the same walls and openings
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        assert_eq!(rebuilt.get_wall(wall).unwrap().end, Point2::new(20.0, 0.0));
+        assert_eq!(position(&rebuilt, door), (second, 0.5));
+
+        store.merge_walls(wall, second).unwrap();
+        assert!(store.get_wall(second).is_none());
+        assert_eq!(store.get_wall(wall).unwrap().end, Point2::new(40.0, 0.0));
+        assert_eq!(position(&store, window), (wall, 0.25));
+        assert_eq!(position(&store, door), (wall, 0.75));
+        assert_eq!(store.get_room(room).unwrap().bounding_wall_ids, vec![wall]);
+
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        assert!(rebuilt.get_wall(second).is_none());
+        assert_eq!(position(&rebuilt, door), (wall, 0.75));
+
+        // Walls at a corner aren't collinear
+        let side = store
+            .create_wall(level_id, assembly_id, Point2::new(40.0, 0.0), Point2::new(40.0, 20.0), 9.0)
+            .unwrap();
+        assert!(store.merge_walls(wall, side).is_err());
+    }
+
+    #[test]
+    fn test_move_and_trim_extend_wall() {
+        let (mut store, _, level_id, assembly_id) = setup();
+        let wall = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), 9.0)
+            .unwrap();
+        let window = store.add_opening(wall, OpeningType::Window, 0.5, 4.0, 5.0, 2.0).unwrap();
+        let east = store
+            .create_wall(level_id, assembly_id, Point2::new(30.0, -5.0), Point2::new(30.0, 5.0), 9.0)
+            .unwrap();
+
+        store.move_wall(wall, 0.0, 2.0).unwrap();
+        assert_eq!(store.get_wall(wall).unwrap().start, Point2::new(0.0, 2.0));
+
+        // Extending to the east wall keeps the window 10 ft from the start
+        store.trim_extend_to(wall, east).unwrap();
+        assert_eq!(store.get_wall(wall).unwrap().end, Point2::new(30.0, 2.0));
+        assert!((position(&store, window).1 - 1.0 / 3.0).abs() < 1e-9);
+
+        // Trimming back from the start to x = 9 would cut the window
+        let west = store
+            .create_wall(level_id, assembly_id, Point2::new(9.0, -5.0), Point2::new(9.0, 5.0), 9.0)
+            .unwrap();
+        assert!(store.trim_extend_to(wall, west).is_err());
+        let west_of_window = store
+            .create_wall(level_id, assembly_id, Point2::new(6.0, -5.0), Point2::new(6.0, 5.0), 9.0)
+            .unwrap();
+        store.trim_extend_to(wall, west_of_window).unwrap();
+        assert_eq!(store.get_wall(wall).unwrap().start, Point2::new(6.0, 2.0));
+        assert!((position(&store, window).1 - 4.0 / 24.0).abs() < 1e-9);
+
+        let parallel = store
+            .create_wall(level_id, assembly_id, Point2::new(0.0, 10.0), Point2::new(20.0, 10.0), 9.0)
+            .unwrap();
+        assert!(store.trim_extend_to(wall, parallel).is_err());
+    }
+}
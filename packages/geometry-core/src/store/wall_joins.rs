@@ -0,0 +1,92 @@
+// Joins between walls, kept on each wall end as walls come and go
+
+use crate::domain::*;
+use crate::geometry::WallNetwork;
+use super::Store;
+
+/// Thickness used for walls whose assembly is missing (feet)
+const FALLBACK_WALL_THICKNESS: f64 = 0.5;
+
+impl Store {
+    // ========== Wall Join Operations ==========
+
+    /// Wall thickness in feet (assembly layers are in inches)
+    pub fn wall_thickness(&self, wall: &Wall) -> f64 {
+        self.wall_assemblies
+            .get(&wall.assembly_id)
+            .map(|a| a.total_thickness / 12.0)
+            .unwrap_or(FALLBACK_WALL_THICKNESS)
+    }
+
+    /// Resolve how the walls on a level meet, for trimming their solids
+    pub fn wall_network(&self, level_id: LevelId) -> WallNetwork {
+        let walls: Vec<(&Wall, f64)> = self
+            .get_level_walls(level_id)
+            .into_iter()
+            .map(|wall| (wall, self.wall_thickness(wall)))
+            .collect();
+        WallNetwork::solve(&walls)
+    }
+
+    /// Re-resolve the joins on a level after a wall is added or removed.
+    /// Replay resolves every level once when it finishes instead.
+    pub(super) fn refresh_wall_joins(&mut self, level_id: LevelId) {
+        if self.replaying {
+            return;
+        }
+        let network = self.wall_network(level_id);
+        let mut changed = Vec::new();
+        for wall in self.walls.values_mut().filter(|w| w.level_id == level_id) {
+            let joins = network.joins(wall.id);
+            if joins != [wall.start_join, wall.end_join] {
+                // Framing on both sides of a join depends on it
+                changed.push(wall.id);
+                changed.extend(joins.iter().chain(&[wall.start_join, wall.end_join]).filter_map(|j| j.wall()));
+                [wall.start_join, wall.end_join] = joins;
+            }
+        }
+        for wall_id in changed {
+            self.invalidate_wall_framing(wall_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_joins_follow_wall_changes() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        let assembly_id = store
+            .create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()])
+            .unwrap();
+
+        let wall = |store: &mut Store, x0, y0, x1, y1| {
+            store
+                .create_wall(level_id, assembly_id, Point2::new(x0, y0), Point2::new(x1, y1), 9.0)
+                .unwrap()
+        };
+        let a = wall(&mut store, 0.0, 0.0, 20.0, 0.0);
+        let b = wall(&mut store, 20.0, 0.0, 20.0, 15.0);
+        let c = wall(&mut store, 10.0, 0.0, 10.0, 15.0);
+
+        assert_eq!(store.get_wall(a).unwrap().end_join, WallJoin::Corner { wall: b });
+        assert_eq!(store.get_wall(b).unwrap().start_join, WallJoin::Corner { wall: a });
+        assert_eq!(store.get_wall(c).unwrap().start_join, WallJoin::Tee { wall: a });
+        assert_eq!(store.get_wall(c).unwrap().end_join, WallJoin::Free);
+
+        // Joins are resolved again on replay
+        let rebuilt = Store::rebuild_from_events(store.get_event_log(project_id).unwrap()).unwrap();
+        assert_eq!(rebuilt.get_wall(c).unwrap().start_join, WallJoin::Tee { wall: a });
+
+        store.remove_wall(a).unwrap();
+        assert_eq!(store.get_wall(b).unwrap().start_join, WallJoin::Free);
+        assert_eq!(store.get_wall(c).unwrap().start_join, WallJoin::Free);
+    }
+}
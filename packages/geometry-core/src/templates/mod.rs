@@ -0,0 +1,570 @@
+// Parametric building templates
+// A template lays out a whole starter model from a handful of parameters:
+// levels, footprints, rooms, walls, openings and the stairs between floors.
+// Everything is created through the store, so the result is an ordinary
+// model to edit from there. If any step fails nothing is created.
+//
+// Plans run along X with the front at Y = 0. Each floor has a front row of
+// rooms, a hallway across the full width and a back row; an attached garage
+// sits off the right-hand side of the ground floor.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::*;
+use crate::domain::stair::{MAX_RISER_HEIGHT, MIN_STAIR_WIDTH, MIN_TREAD_DEPTH};
+use crate::store::Store;
+
+/// Hallway between the front and back rows (feet)
+const HALL_WIDTH: f64 = 4.0;
+/// Narrowest room a row is split into (feet)
+const MIN_ROOM_WIDTH: f64 = 7.0;
+/// Two-car garage (feet)
+const GARAGE_WIDTH: f64 = 22.0;
+const GARAGE_DEPTH: f64 = 22.0;
+/// Where the stairs start along the hallway (feet)
+const STAIR_INSET: f64 = 1.0;
+
+const DOOR_WIDTH: f64 = 3.0;
+const DOOR_HEIGHT: f64 = 6.67;
+const GARAGE_DOOR_WIDTH: f64 = 16.0;
+const GARAGE_DOOR_HEIGHT: f64 = 7.0;
+
+const LEVEL_NAMES: [&str; 4] = ["First Floor", "Second Floor", "Third Floor", "Fourth Floor"];
+
+/// Starter layouts a model can be instantiated from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildingTemplate {
+    /// Single story with the bedrooms down one side of the hall
+    Ranch,
+    /// Living floor with a center foyer, bedrooms upstairs
+    TwoStoryColonial,
+}
+
+impl BuildingTemplate {
+    pub fn all() -> [Self; 2] {
+        [Self::Ranch, Self::TwoStoryColonial]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ranch => "ranch",
+            Self::TwoStoryColonial => "two_story_colonial",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "ranch" => Some(Self::Ranch),
+            "two_story_colonial" | "colonial" => Some(Self::TwoStoryColonial),
+            _ => None,
+        }
+    }
+
+    /// Name given to the building
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Ranch => "Ranch",
+            Self::TwoStoryColonial => "Two-Story Colonial",
+        }
+    }
+
+    /// Parameters used where none are given
+    pub fn default_params(&self) -> TemplateParams {
+        match self {
+            Self::Ranch => TemplateParams {
+                stories: Some(1),
+                width: Some(48.0),
+                depth: Some(28.0),
+                garage: Some(true),
+                bedrooms: Some(3),
+                story_height: Some(9.0),
+            },
+            Self::TwoStoryColonial => TemplateParams {
+                stories: Some(2),
+                width: Some(40.0),
+                depth: Some(30.0),
+                garage: Some(true),
+                bedrooms: Some(3),
+                story_height: Some(9.0),
+            },
+        }
+    }
+
+    /// Front and back rows of the ground floor, before any bedrooms
+    fn ground_rows(&self) -> (Vec<RoomSpec>, Vec<RoomSpec>) {
+        match self {
+            Self::Ranch => (
+                vec![RoomSpec::new(RoomType::LivingRoom, "Living Room"), RoomSpec::new(RoomType::DiningRoom, "Dining Room")],
+                vec![RoomSpec::new(RoomType::Kitchen, "Kitchen"), RoomSpec::new(RoomType::Laundry, "Laundry")],
+            ),
+            Self::TwoStoryColonial => (
+                vec![
+                    RoomSpec::new(RoomType::LivingRoom, "Living Room"),
+                    RoomSpec::new(RoomType::Foyer, "Foyer"),
+                    RoomSpec::new(RoomType::DiningRoom, "Dining Room"),
+                ],
+                vec![
+                    RoomSpec::new(RoomType::FamilyRoom, "Family Room"),
+                    RoomSpec::new(RoomType::Kitchen, "Kitchen"),
+                    RoomSpec::new(RoomType::Bathroom, "Powder Room"),
+                ],
+            ),
+        }
+    }
+
+    /// Room the front door opens into
+    fn entry_room(&self) -> RoomType {
+        match self {
+            Self::Ranch => RoomType::LivingRoom,
+            Self::TwoStoryColonial => RoomType::Foyer,
+        }
+    }
+}
+
+/// Template parameters; any left out come from the template's defaults.
+/// Lengths in feet.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateParams {
+    pub stories: Option<u32>,
+    /// Overall width of the house, not counting the garage
+    pub width: Option<f64>,
+    pub depth: Option<f64>,
+    /// Attached two-car garage on the ground floor
+    pub garage: Option<bool>,
+    pub bedrooms: Option<u32>,
+    /// Floor-to-floor height
+    pub story_height: Option<f64>,
+}
+
+impl TemplateParams {
+    /// Fill gaps from the template's defaults and check the result
+    fn resolve(&self, template: BuildingTemplate) -> Result<Plan> {
+        let defaults = template.default_params();
+        let plan = Plan {
+            stories: self.stories.or(defaults.stories).unwrap_or(1),
+            width: self.width.or(defaults.width).unwrap_or_default(),
+            depth: self.depth.or(defaults.depth).unwrap_or_default(),
+            garage: self.garage.or(defaults.garage).unwrap_or(false),
+            bedrooms: self.bedrooms.or(defaults.bedrooms).unwrap_or(0),
+            story_height: self.story_height.or(defaults.story_height).unwrap_or(9.0),
+        };
+
+        if !(1..=LEVEL_NAMES.len() as u32).contains(&plan.stories) {
+            return Err(anyhow!("Stories must be between 1 and {}, got {}", LEVEL_NAMES.len(), plan.stories));
+        }
+        if !(20.0..=200.0).contains(&plan.width) || !(20.0..=200.0).contains(&plan.depth) {
+            return Err(anyhow!("Width and depth must be between 20 and 200 ft"));
+        }
+        if !(8.0..=12.0).contains(&plan.story_height) {
+            return Err(anyhow!("Story height must be between 8 and 12 ft, got {}", plan.story_height));
+        }
+        if plan.bedrooms > 8 {
+            return Err(anyhow!("At most 8 bedrooms are supported, got {}", plan.bedrooms));
+        }
+
+        Ok(plan)
+    }
+}
+
+/// What a template created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateInstance {
+    pub template: BuildingTemplate,
+    pub building_id: BuildingId,
+    /// Bottom to top
+    pub level_ids: Vec<LevelId>,
+    pub room_ids: Vec<RoomId>,
+    pub wall_ids: Vec<WallId>,
+    pub opening_ids: Vec<OpeningId>,
+    pub stair_ids: Vec<StairId>,
+}
+
+/// Parameters with every gap filled
+#[derive(Debug, Clone, Copy)]
+struct Plan {
+    stories: u32,
+    width: f64,
+    depth: f64,
+    garage: bool,
+    bedrooms: u32,
+    story_height: f64,
+}
+
+impl Plan {
+    /// Front edge of the hallway
+    fn hall_front(&self) -> f64 {
+        (self.depth - HALL_WIDTH) / 2.0
+    }
+
+    fn hall_back(&self) -> f64 {
+        self.hall_front() + HALL_WIDTH
+    }
+
+    fn hall_center(&self) -> f64 {
+        self.depth / 2.0
+    }
+
+    fn garage_depth(&self) -> f64 {
+        GARAGE_DEPTH.min(self.depth)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RoomSpec {
+    room_type: RoomType,
+    name: String,
+}
+
+impl RoomSpec {
+    fn new(room_type: RoomType, name: impl Into<String>) -> Self {
+        Self { room_type, name: name.into() }
+    }
+}
+
+/// Rooms of one floor
+struct FloorProgram {
+    front: Vec<RoomSpec>,
+    back: Vec<RoomSpec>,
+}
+
+/// Create a building in a project from a template
+pub fn instantiate_template(
+    store: &mut Store,
+    project_id: ProjectId,
+    template: BuildingTemplate,
+    params: &TemplateParams,
+) -> Result<TemplateInstance> {
+    if store.get_project(project_id).is_none() {
+        return Err(anyhow!("Project not found: {:?}", project_id));
+    }
+    let plan = params.resolve(template)?;
+    let floors = floor_programs(template, &plan);
+    for floor in &floors {
+        let widest = floor.front.len().max(floor.back.len());
+        if widest as f64 * MIN_ROOM_WIDTH > plan.width {
+            return Err(anyhow!(
+                "A {} ft wide plan can't fit {} rooms in a row",
+                plan.width, widest
+            ));
+        }
+    }
+
+    store.transaction(|store| {
+        let building_id = store.add_building(project_id, template.display_name())?;
+        let mut instance = TemplateInstance {
+            template,
+            building_id,
+            level_ids: Vec::new(),
+            room_ids: Vec::new(),
+            wall_ids: Vec::new(),
+            opening_ids: Vec::new(),
+            stair_ids: Vec::new(),
+        };
+
+        for (index, floor) in floors.iter().enumerate() {
+            let level_id = store.add_level(
+                building_id,
+                LEVEL_NAMES[index],
+                index as f64 * plan.story_height,
+                plan.story_height,
+            )?;
+            instance.level_ids.push(level_id);
+            let ground = index == 0;
+            build_floor(store, &mut instance, level_id, floor, &plan, ground && plan.garage, ground.then_some(template))?;
+        }
+
+        // Stairs climb along the hallway and land in it
+        for pair in instance.level_ids.clone().windows(2) {
+            let stair_id = store.create_stair(
+                pair[0],
+                pair[1],
+                Point2::new(STAIR_INSET, plan.hall_center()),
+                0.0,
+                MIN_STAIR_WIDTH,
+                MAX_RISER_HEIGHT,
+                MIN_TREAD_DEPTH,
+            )?;
+            instance.stair_ids.push(stair_id);
+        }
+
+        Ok(instance)
+    })
+}
+
+/// Rooms for each floor, ground first. Bedrooms share the upper floors, or
+/// join the ground floor in a single-story plan.
+fn floor_programs(template: BuildingTemplate, plan: &Plan) -> Vec<FloorProgram> {
+    let bedroom = |n: u32| RoomSpec::new(RoomType::Bedroom, format!("Bedroom {}", n));
+    // Half the bedrooms along the front, the rest and a bath at the back
+    let bedroom_rows = |first: u32, count: u32| {
+        let front_count = count.div_ceil(2);
+        let front: Vec<RoomSpec> = (first..first + front_count).map(bedroom).collect();
+        let mut back: Vec<RoomSpec> = (first + front_count..first + count).map(bedroom).collect();
+        back.push(RoomSpec::new(RoomType::Bathroom, "Bath"));
+        (front, back)
+    };
+
+    let (mut front, mut back) = template.ground_rows();
+    if plan.stories == 1 {
+        let (bed_front, bed_back) = bedroom_rows(1, plan.bedrooms);
+        front.extend(bed_front);
+        back.extend(bed_back);
+        return vec![FloorProgram { front, back }];
+    }
+
+    let mut floors = vec![FloorProgram { front, back }];
+    let upper = plan.stories - 1;
+    let mut next = 1;
+    for floor in 0..upper {
+        let count = plan.bedrooms / upper + u32::from(floor < plan.bedrooms % upper);
+        let (mut front, back) = bedroom_rows(next, count);
+        if front.is_empty() {
+            front.push(RoomSpec::new(RoomType::FamilyRoom, "Bonus Room"));
+        }
+        floors.push(FloorProgram { front, back });
+        next += count;
+    }
+    floors
+}
+
+/// Footprint, rooms, walls and openings of one floor
+fn build_floor(
+    store: &mut Store,
+    instance: &mut TemplateInstance,
+    level_id: LevelId,
+    floor: &FloorProgram,
+    plan: &Plan,
+    garage: bool,
+    entry: Option<BuildingTemplate>,
+) -> Result<()> {
+    let (w, d) = (plan.width, plan.depth);
+    let (hall_front, hall_back) = (plan.hall_front(), plan.hall_back());
+    let garage_depth = plan.garage_depth();
+
+    let footprint = if !garage {
+        rect(0.0, 0.0, w, d)
+    } else if garage_depth >= d {
+        rect(0.0, 0.0, w + GARAGE_WIDTH, d)
+    } else {
+        Polygon2::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(w + GARAGE_WIDTH, 0.0),
+            Point2::new(w + GARAGE_WIDTH, garage_depth),
+            Point2::new(w, garage_depth),
+            Point2::new(w, d),
+            Point2::new(0.0, d),
+        ])
+    };
+    store.set_level_footprint(level_id, footprint.clone())?;
+
+    // Rooms first so walls pick their assemblies from what they bound
+    let front = split_row(&floor.front, w);
+    let back = split_row(&floor.back, w);
+    for (spec, x0, x1) in &front {
+        let id = store.create_room(level_id, spec.room_type.clone(), spec.name.as_str(), rect(*x0, 0.0, *x1, hall_front))?;
+        instance.room_ids.push(id);
+    }
+    let hall_id = store.create_room(level_id, RoomType::Hallway, "Hall", rect(0.0, hall_front, w, hall_back))?;
+    instance.room_ids.push(hall_id);
+    for (spec, x0, x1) in &back {
+        let id = store.create_room(level_id, spec.room_type.clone(), spec.name.as_str(), rect(*x0, hall_back, *x1, d))?;
+        instance.room_ids.push(id);
+    }
+    if garage {
+        let id = store.create_room(level_id, RoomType::Garage, "Garage", rect(w, 0.0, w + GARAGE_WIDTH, garage_depth))?;
+        instance.room_ids.push(id);
+    }
+
+    // Perimeter, then the hallway sides and partitions room by room
+    let mut segments: Vec<(Point2, Point2)> = Vec::new();
+    let outline = &footprint.outer;
+    for (i, start) in outline.iter().enumerate() {
+        segments.push((*start, outline[(i + 1) % outline.len()]));
+    }
+    for (i, (_, x0, x1)) in front.iter().enumerate() {
+        segments.push((Point2::new(*x0, hall_front), Point2::new(*x1, hall_front)));
+        if i > 0 {
+            segments.push((Point2::new(*x0, 0.0), Point2::new(*x0, hall_front)));
+        }
+    }
+    for (i, (_, x0, x1)) in back.iter().enumerate() {
+        segments.push((Point2::new(*x0, hall_back), Point2::new(*x1, hall_back)));
+        if i > 0 {
+            segments.push((Point2::new(*x0, hall_back), Point2::new(*x0, d)));
+        }
+    }
+    if garage {
+        segments.push((Point2::new(w, 0.0), Point2::new(w, garage_depth)));
+    }
+    let mut walls = Vec::with_capacity(segments.len());
+    for (start, end) in segments {
+        let id = store.create_wall_auto(level_id, start, end, plan.story_height)?;
+        instance.wall_ids.push(id);
+        walls.push((id, start, end));
+    }
+
+    // A door from every room onto the hall and a window to the outside;
+    // the entry room and kitchen get the exterior doors
+    let rows = [(&front, 0.0, hall_front), (&back, d, hall_back)];
+    for (row, outside, hall_side) in rows {
+        for (spec, x0, x1) in row.iter() {
+            let width = x1 - x0;
+            let opening = place_opening(store, &walls, Point2::new(x0 + width / 2.0, hall_side), OpeningType::Door, DOOR_WIDTH, DOOR_HEIGHT, 0.0)?;
+            instance.opening_ids.push(opening);
+
+            let exterior_door = entry.is_some_and(|t| {
+                (outside == 0.0 && spec.room_type == t.entry_room())
+                    || (outside == d && spec.room_type == RoomType::Kitchen)
+            });
+            if spec.room_type == RoomType::Foyer {
+                let opening = place_opening(store, &walls, Point2::new(x0 + width / 2.0, outside), OpeningType::Door, DOOR_WIDTH, DOOR_HEIGHT, 0.0)?;
+                instance.opening_ids.push(opening);
+                continue;
+            }
+            if exterior_door {
+                let opening = place_opening(store, &walls, Point2::new(x0 + width * 0.7, outside), OpeningType::Door, DOOR_WIDTH, DOOR_HEIGHT, 0.0)?;
+                instance.opening_ids.push(opening);
+            }
+
+            let (window_width, window_height, sill) = match spec.room_type {
+                RoomType::Bathroom => (2.0, 3.0, 4.0),
+                RoomType::Kitchen => ((width * 0.3).clamp(2.0, 6.0), 4.0, 3.5),
+                _ => ((width * 0.3).clamp(2.0, 6.0), 5.0, 2.0),
+            };
+            let center = if exterior_door { x0 + width * 0.25 } else { x0 + width / 2.0 };
+            let opening = place_opening(store, &walls, Point2::new(center, outside), OpeningType::Window, window_width, window_height, sill)?;
+            instance.opening_ids.push(opening);
+        }
+    }
+
+    if garage {
+        let garage_door = OpeningType::Other("garage_door".to_string());
+        let opening = place_opening(store, &walls, Point2::new(w + GARAGE_WIDTH / 2.0, 0.0), garage_door, GARAGE_DOOR_WIDTH, GARAGE_DOOR_HEIGHT, 0.0)?;
+        instance.opening_ids.push(opening);
+        // Into the hall where it meets the garage, else midway along
+        let y = if hall_back <= garage_depth { plan.hall_center() } else { garage_depth / 2.0 };
+        let opening = place_opening(store, &walls, Point2::new(w, y), OpeningType::Door, DOOR_WIDTH, DOOR_HEIGHT, 0.0)?;
+        instance.opening_ids.push(opening);
+    }
+
+    Ok(())
+}
+
+/// Rooms side by side across the full width, as (spec, x0, x1)
+fn split_row(specs: &[RoomSpec], width: f64) -> Vec<(RoomSpec, f64, f64)> {
+    let room_width = width / specs.len().max(1) as f64;
+    specs
+        .iter()
+        .enumerate()
+        .map(|(i, spec)| (spec.clone(), i as f64 * room_width, (i + 1) as f64 * room_width))
+        .collect()
+}
+
+/// Add an opening centered on a point of whichever wall runs through it
+fn place_opening(
+    store: &mut Store,
+    walls: &[(WallId, Point2, Point2)],
+    at: Point2,
+    opening_type: OpeningType,
+    width: f64,
+    height: f64,
+    sill_height: f64,
+) -> Result<OpeningId> {
+    for (wall_id, start, end) in walls {
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let length_sq = dx * dx + dy * dy;
+        let t = ((at.x - start.x) * dx + (at.y - start.y) * dy) / length_sq;
+        let foot = Point2::new(start.x + dx * t, start.y + dy * t);
+        if (0.0..=1.0).contains(&t) && foot.distance_to(&at) < 1e-6 {
+            return store.add_opening(*wall_id, opening_type, t, width, height, sill_height);
+        }
+    }
+    Err(anyhow!("No wall at ({}, {}) for {:?}", at.x, at.y, opening_type))
+}
+
+fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> Polygon2 {
+    Polygon2::new(vec![
+        Point2::new(x0, y0),
+        Point2::new(x1, y0),
+        Point2::new(x1, y1),
+        Point2::new(x0, y1),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::rooms_unreachable_from_entry;
+
+    #[test]
+    fn test_two_story_colonial() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let params = TemplateParams {
+            stories: Some(2),
+            width: Some(40.0),
+            depth: Some(30.0),
+            garage: Some(true),
+            bedrooms: Some(3),
+            ..Default::default()
+        };
+
+        let instance = instantiate_template(&mut store, project_id, BuildingTemplate::TwoStoryColonial, &params).unwrap();
+        assert_eq!(instance.level_ids.len(), 2);
+        assert_eq!(instance.stair_ids.len(), 1);
+
+        let ground = store.get_level_rooms(instance.level_ids[0]);
+        let upper = store.get_level_rooms(instance.level_ids[1]);
+        assert_eq!(ground.len(), 8);
+        assert!(ground.iter().any(|r| r.room_type == RoomType::Garage));
+        let bedrooms = upper.iter().filter(|r| r.room_type == RoomType::Bedroom).count();
+        assert_eq!(bedrooms, 3);
+
+        // Every room is reachable: through the front door downstairs and
+        // the stair landing upstairs
+        for level_id in &instance.level_ids {
+            assert!(rooms_unreachable_from_entry(&store, *level_id).unwrap().is_empty());
+        }
+
+        // The garage wall is a fire separation; only the ground floor is L-shaped
+        let fire_separation = store.assembly_for_role(project_id, AssemblyRole::FireSeparation).unwrap();
+        let separation = store
+            .get_level_walls(instance.level_ids[0])
+            .into_iter()
+            .find(|w| w.start == Point2::new(40.0, 0.0) && w.end == Point2::new(40.0, 22.0))
+            .unwrap();
+        assert_eq!(separation.assembly_id, fire_separation);
+        assert_eq!(store.get_level_footprint(instance.level_ids[0]).unwrap().polygon.outer.len(), 6);
+        assert!((store.get_level_footprint(instance.level_ids[1]).unwrap().polygon.area() - 1200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_template_validation() {
+        let mut store = Store::new();
+        let project_id = store
+            .create_project("Test", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+
+        // Defaults: a single-story ranch with its bedrooms on the one floor
+        let ranch = instantiate_template(&mut store, project_id, BuildingTemplate::Ranch, &TemplateParams::default()).unwrap();
+        assert_eq!(ranch.level_ids.len(), 1);
+        assert!(ranch.stair_ids.is_empty());
+        let rooms = store.get_level_rooms(ranch.level_ids[0]);
+        assert_eq!(rooms.iter().filter(|r| r.room_type == RoomType::Bedroom).count(), 3);
+
+        // Six bedrooms on one floor don't fit 24 ft; nothing is left behind
+        let cramped = TemplateParams { width: Some(24.0), bedrooms: Some(6), ..Default::default() };
+        assert!(instantiate_template(&mut store, project_id, BuildingTemplate::Ranch, &cramped).is_err());
+        let too_tall = TemplateParams { stories: Some(5), ..Default::default() };
+        assert!(instantiate_template(&mut store, project_id, BuildingTemplate::TwoStoryColonial, &too_tall).is_err());
+        assert_eq!(store.get_project(project_id).unwrap().building_ids.len(), 1);
+
+        assert_eq!(BuildingTemplate::parse("Two-Story-Colonial"), Some(BuildingTemplate::TwoStoryColonial));
+        assert_eq!(BuildingTemplate::parse("igloo"), None);
+    }
+}
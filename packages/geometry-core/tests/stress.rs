@@ -0,0 +1,264 @@
+// Long-running editing session against a single store
+// Drives a long mix of edits, undos, snapshots and renders, sampling the
+// store's memory stats along the way, and checks that event logs, the solid
+// cache and per-frame mesh buffers stay bounded while the model itself is
+// unchanged by pruning. STRESS_OPS sets the number of operations (tens of
+// thousands for a soak run; the default keeps `cargo test` quick), and
+// STRESS_VERBOSE prints the sampled stats.
+
+use geometry_core::geometry::{
+    extrude_polygon, generate_footprint_massing, new_solid_cache, prune_solid_cache, solid_to_mesh, MeshData, SolidCache,
+};
+use geometry_core::store::{MemoryStats, PruningPolicy, Store};
+use geometry_core::*;
+
+const DEFAULT_OPS: usize = 3000;
+const RENDER_EVERY: usize = 50;
+const MAX_WALLS: usize = 30;
+
+/// Small deterministic generator so failures reproduce
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn range(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (self.next() % 10_000) as f64 / 10_000.0 * (hi - lo)
+    }
+}
+
+struct Session {
+    store: Store,
+    cache: SolidCache,
+    project_id: ProjectId,
+    level_id: LevelId,
+    assembly_id: WallAssemblyId,
+    rng: Lcg,
+}
+
+impl Session {
+    fn new(policy: PruningPolicy) -> Self {
+        let mut store = Store::new();
+        store.set_pruning_policy(policy);
+        let project_id = store
+            .create_project("Soak", UnitSystem::Imperial, CodeRegion::us_irc_2021())
+            .unwrap();
+        let building_id = store.add_building(project_id, "Main").unwrap();
+        let level_id = store.add_level(building_id, "First Floor", 0.0, 9.0).unwrap();
+        store.set_level_footprint(level_id, Polygon2::rectangle(40.0, 30.0)).unwrap();
+        let assembly_id = store.create_wall_assembly("Exterior", vec![WallLayer::stud_2x6()]).unwrap();
+        for (i, room_type) in [RoomType::Kitchen, RoomType::LivingRoom, RoomType::Bedroom, RoomType::Bathroom]
+            .into_iter()
+            .enumerate()
+        {
+            let x = i as f64 * 10.0;
+            let boundary = Polygon2::new(vec![
+                Point2::new(x, 0.0),
+                Point2::new(x + 10.0, 0.0),
+                Point2::new(x + 10.0, 10.0),
+                Point2::new(x, 10.0),
+            ]);
+            let name = room_type.display_name();
+            store.create_room(level_id, room_type, name, boundary).unwrap();
+        }
+
+        Self {
+            store,
+            cache: new_solid_cache(),
+            project_id,
+            level_id,
+            assembly_id,
+            rng: Lcg(0x5eed),
+        }
+    }
+
+    fn random_room(&mut self) -> Option<RoomId> {
+        let rooms: Vec<RoomId> = self.store.get_level_rooms(self.level_id).iter().map(|r| r.id).collect();
+        (!rooms.is_empty()).then(|| rooms[self.rng.below(rooms.len())])
+    }
+
+    fn random_wall(&mut self) -> Option<WallId> {
+        let mut walls: Vec<WallId> = self.store.get_level_walls(self.level_id).iter().map(|w| w.id).collect();
+        walls.sort_by_key(|id| id.to_string());
+        (!walls.is_empty()).then(|| walls[self.rng.below(walls.len())])
+    }
+
+    fn step(&mut self, op: usize) {
+        match self.rng.below(10) {
+            0..=2 => {
+                if self.store.get_level_walls(self.level_id).len() >= MAX_WALLS {
+                    let wall = self.random_wall().unwrap();
+                    self.store.remove_wall(wall).unwrap();
+                    return;
+                }
+                let start = Point2::new(self.rng.range(0.0, 40.0), self.rng.range(0.0, 30.0));
+                let end = Point2::new(start.x + self.rng.range(6.0, 20.0), start.y);
+                let wall = self.store.create_wall(self.level_id, self.assembly_id, start, end, 9.0).unwrap();
+                if self.rng.below(2) == 0 {
+                    self.store.add_opening(wall, OpeningType::Window, 0.5, 3.0, 4.0, 3.0).unwrap();
+                }
+            }
+            3 => {
+                if let Some(wall) = self.random_wall() {
+                    self.store.remove_wall(wall).unwrap();
+                }
+            }
+            4 | 5 => {
+                if let Some(room) = self.random_room() {
+                    let surfaces = [FinishSurface::Floor, FinishSurface::Wall, FinishSurface::Ceiling, FinishSurface::Base];
+                    let surface = surfaces[self.rng.below(surfaces.len())];
+                    let allowed = surface.allowed_materials();
+                    let material = allowed[self.rng.below(allowed.len())];
+                    self.store.set_room_finish(room, surface, material).unwrap();
+                }
+            }
+            6 => {
+                if let Some(room) = self.random_room() {
+                    let conditioned = [None, Some(true), Some(false)][self.rng.below(3)];
+                    self.store.set_room_conditioned(room, conditioned).unwrap();
+                }
+            }
+            7 => {
+                let footprint = self.store.get_level_footprint(self.level_id).map(|fp| fp.id);
+                if let Some(footprint_id) = footprint {
+                    let polygon = Polygon2::rectangle(self.rng.range(35.0, 50.0), self.rng.range(25.0, 35.0));
+                    self.store.modify_footprint(footprint_id, polygon).unwrap();
+                }
+            }
+            8 => {
+                if self.store.get_event_log(self.project_id).unwrap().can_undo() {
+                    self.store.undo(self.project_id).unwrap();
+                }
+            }
+            _ => {
+                if op.is_multiple_of(500) {
+                    self.store.create_snapshot(self.project_id).unwrap();
+                }
+            }
+        }
+    }
+
+    /// Mesh the level's massing and rooms, then evict solids the model no
+    /// longer refers to. Returns the frame's mesh buffer size in bytes.
+    fn render(&mut self) -> usize {
+        let mut bytes = 0;
+        let mut add = |mesh: MeshData| {
+            bytes += (mesh.positions.len() + mesh.normals.len()) * 4 + mesh.indices.len() * 4;
+        };
+
+        let level = self.store.get_level(self.level_id).unwrap().clone();
+        if let Some(footprint) = self.store.get_level_footprint(self.level_id).cloned() {
+            let solid_id = generate_footprint_massing(&footprint, &level, &self.cache).unwrap();
+            self.store.set_footprint_solid(footprint.id, solid_id).unwrap();
+            let cache = self.cache.read().unwrap();
+            add(solid_to_mesh(&cache[&solid_id], 0.1).unwrap());
+        }
+        for room in self.store.get_level_rooms(self.level_id) {
+            let solid = extrude_polygon(&room.boundary, level.elevation, 0.1).unwrap();
+            add(solid_to_mesh(&solid, 0.1).unwrap());
+        }
+
+        prune_solid_cache(&self.cache, &self.store.live_solid_ids());
+        bytes
+    }
+}
+
+fn stress_ops() -> usize {
+    std::env::var("STRESS_OPS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_OPS)
+}
+
+#[test]
+fn test_long_session_stays_bounded() {
+    let ops = stress_ops();
+    let policy = PruningPolicy {
+        compact_every: Some(1000),
+        keep_recent: 200,
+    };
+    let mut session = Session::new(policy);
+
+    let mut samples: Vec<MemoryStats> = Vec::new();
+    let mut frames = Vec::new();
+    let mut recorded = 0;
+    for op in 1..=ops {
+        let before = session.store.project_revision(session.project_id).unwrap();
+        session.step(op);
+        recorded += (session.store.project_revision(session.project_id).unwrap().saturating_sub(before)) as usize;
+
+        if op.is_multiple_of(RENDER_EVERY) {
+            frames.push(session.render());
+            assert!(session.cache.read().unwrap().len() <= 1, "solid cache grew at op {}", op);
+        }
+        if op.is_multiple_of(1000) {
+            samples.push(session.store.memory_stats());
+        }
+    }
+
+    let stats = session.store.memory_stats();
+    if std::env::var_os("STRESS_VERBOSE").is_some() {
+        eprintln!("{} ops, {} events recorded: {:?}", ops, recorded, stats);
+        for (i, sample) in samples.iter().enumerate() {
+            eprintln!("  after {} ops: {} events, {} bytes", (i + 1) * 1000, sample.events, sample.event_log_bytes);
+        }
+    }
+
+    // History is bounded by the policy plus what the live model needs,
+    // not by how long the session ran
+    let live_bound = policy.compact_every.unwrap() + policy.keep_recent + 4 * MAX_WALLS + 64;
+    assert!(recorded > stats.events, "nothing was compacted");
+    assert!(stats.events <= live_bound, "{} events retained", stats.events);
+    for (i, sample) in samples.iter().enumerate() {
+        assert!(sample.events <= live_bound, "{} events retained after {} ops", sample.events, (i + 1) * 1000);
+    }
+    // Nor does its size creep up between compactions
+    if let Some(first) = samples.first() {
+        let largest = samples.iter().map(|s| s.event_log_bytes).max().unwrap_or(0);
+        assert!(largest <= 2 * first.event_log_bytes, "event log grew to {} bytes", largest);
+    }
+    assert!(stats.snapshots <= 4, "{} snapshots retained", stats.snapshots);
+
+    // Frames don't grow: the same rooms and one massing solid every time
+    let largest = frames.iter().copied().max().unwrap_or(0);
+    assert!(frames.last().copied().unwrap_or(0) * 2 >= largest);
+
+    // The compacted history still replays to the live model
+    let log = session.store.get_event_log(session.project_id).unwrap();
+    let rebuilt = Store::rebuild_from_events(log).unwrap();
+    let mut live_walls: Vec<String> = session.store.walls.keys().map(|id| id.to_string()).collect();
+    let mut rebuilt_walls: Vec<String> = rebuilt.walls.keys().map(|id| id.to_string()).collect();
+    live_walls.sort();
+    rebuilt_walls.sort();
+    assert_eq!(live_walls, rebuilt_walls);
+    assert_eq!(session.store.openings.len(), rebuilt.openings.len());
+    for room in session.store.rooms.values() {
+        let other = rebuilt.get_room(room.id).unwrap();
+        assert_eq!(room.finishes(), other.finishes());
+        assert_eq!(room.conditioned, other.conditioned);
+    }
+    let footprint = session.store.get_level_footprint(session.level_id).map(|fp| fp.polygon.area());
+    let rebuilt_footprint = rebuilt.get_level_footprint(session.level_id).map(|fp| fp.polygon.area());
+    assert_eq!(footprint, rebuilt_footprint);
+}
+
+#[test]
+fn test_uncompacted_history_grows_with_session() {
+    // Without a pruning policy the log keeps every edit; this is the growth
+    // the policy above exists to stop
+    let mut session = Session::new(PruningPolicy::default());
+    for op in 1..=1500 {
+        session.step(op);
+    }
+    let events = session.store.memory_stats().events;
+
+    session.store.compact_event_log(session.project_id, 200).unwrap();
+    assert!(session.store.memory_stats().events < events / 2);
+}
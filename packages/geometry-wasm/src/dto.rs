@@ -0,0 +1,288 @@
+// Typed results returned to JavaScript
+// Each DTO derives Serialize for serde_wasm_bindgen and Tsify, which emits
+// a matching TypeScript interface into the generated .d.ts so the frontend
+// gets typed results. Field names keep the keys the frontend already reads.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+use geometry_core::domain::{BuildingStats, FramingLayout, FloorFramingLayout, Room, Wall, WallJoin};
+use geometry_core::export::PickHit;
+use geometry_core::store::{Snap, SnapTarget, Store};
+
+/// Serialize a result with plain objects for maps, matching what Tsify
+/// declares
+pub(crate) fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Areas and level count of one building
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct BuildingStatsSummary {
+    pub total_area: f64,
+    pub conditioned_area: f64,
+    pub unconditioned_area: f64,
+    pub level_count: usize,
+    /// "dwelling", "adu", "garage" or "shed"
+    pub occupancy: String,
+}
+
+impl From<&BuildingStats> for BuildingStatsSummary {
+    fn from(stats: &BuildingStats) -> Self {
+        Self {
+            total_area: stats.total_area,
+            conditioned_area: stats.conditioned_area,
+            unconditioned_area: stats.unconditioned_area,
+            level_count: stats.level_count,
+            occupancy: stats.occupancy.as_str().to_string(),
+        }
+    }
+}
+
+/// Entity counts across the whole store
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct StoreCounts {
+    pub projects: usize,
+    pub buildings: usize,
+    pub levels: usize,
+    pub walls: usize,
+    pub rooms: usize,
+    pub footprints: usize,
+    pub wall_assemblies: usize,
+}
+
+impl From<&Store> for StoreCounts {
+    fn from(store: &Store) -> Self {
+        Self {
+            projects: store.projects.len(),
+            buildings: store.buildings.len(),
+            levels: store.levels.len(),
+            walls: store.walls.len(),
+            rooms: store.rooms.len(),
+            footprints: store.footprints.len(),
+            wall_assemblies: store.wall_assemblies.len(),
+        }
+    }
+}
+
+/// A room as the LLM state derivation sees it
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct RoomSummary {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub room_type: String,
+    pub area: f64,
+    pub center: [f64; 2],
+    pub dimensions: RoomDimensions,
+}
+
+/// Bounding box size of a room
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct RoomDimensions {
+    pub width: f64,
+    pub depth: f64,
+}
+
+impl RoomSummary {
+    /// `bbox` is the room boundary's (width, depth)
+    pub fn new(room: &Room, bbox: (f64, f64)) -> Self {
+        let centroid = room.boundary.centroid();
+        Self {
+            id: room.id.to_string(),
+            name: room.name.clone(),
+            room_type: room.room_type.display_name(),
+            area: room.area(),
+            center: [centroid.x, centroid.y],
+            dimensions: RoomDimensions { width: bbox.0, depth: bbox.1 },
+        }
+    }
+}
+
+/// A wall's centerline, size and how its ends meet other walls
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct WallSummary {
+    pub id: String,
+    pub start: [f64; 2],
+    pub end: [f64; 2],
+    pub thickness: f64,
+    pub height: f64,
+    #[tsify(type = "\"free\" | { corner: { wall: string } } | { tee: { wall: string } }")]
+    pub start_join: WallJoin,
+    #[tsify(type = "\"free\" | { corner: { wall: string } } | { tee: { wall: string } }")]
+    pub end_join: WallJoin,
+}
+
+impl WallSummary {
+    pub fn new(wall: &Wall, thickness: f64) -> Self {
+        Self {
+            id: wall.id.to_string(),
+            start: [wall.start.x, wall.start.y],
+            end: [wall.end.x, wall.end.y],
+            thickness,
+            height: wall.height,
+            start_join: wall.start_join,
+            end_join: wall.end_join,
+        }
+    }
+}
+
+/// A header over one opening in a framed wall
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderSummary {
+    pub opening_id: String,
+    pub span: f64,
+    pub size: String,
+    pub header_type: String,
+    pub plies: u8,
+}
+
+/// Counts and sizes of a wall's generated framing
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct WallFramingSummary {
+    pub layout_id: String,
+    pub wall_id: String,
+    pub stud_count: u32,
+    pub total_board_feet: f64,
+    pub member_count: usize,
+    pub stud_spacing: f64,
+    pub lumber_size: String,
+    pub double_top_plate: bool,
+    /// Members by type name, e.g. { "King Stud": 4 }
+    #[tsify(type = "Record<string, number>")]
+    pub member_breakdown: BTreeMap<String, u32>,
+    pub headers: Vec<HeaderSummary>,
+}
+
+impl From<&FramingLayout> for WallFramingSummary {
+    fn from(layout: &FramingLayout) -> Self {
+        Self {
+            layout_id: layout.id.to_string(),
+            wall_id: layout.wall_id.to_string(),
+            stud_count: layout.stud_count,
+            total_board_feet: layout.total_board_feet,
+            member_count: layout.members.len(),
+            stud_spacing: layout.stud_spacing,
+            lumber_size: layout.lumber_size.nominal_name(),
+            double_top_plate: layout.double_top_plate,
+            member_breakdown: member_breakdown(layout.members.iter().map(|m| m.member_type.display_name())),
+            headers: layout.headers
+                .iter()
+                .map(|h| HeaderSummary {
+                    opening_id: h.opening_id.to_string(),
+                    span: h.span,
+                    size: h.size.description(),
+                    header_type: h.size.header_type.display_name().to_string(),
+                    plies: h.size.plies,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Counts and sizes of a level's generated floor framing
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct FloorFramingSummary {
+    pub layout_id: String,
+    pub level_id: String,
+    pub joist_count: u32,
+    pub sheathing_sheets: u32,
+    pub total_board_feet: f64,
+    pub member_count: usize,
+    pub lumber_size: String,
+    /// Members by type name, e.g. { "Joist": 22 }
+    #[tsify(type = "Record<string, number>")]
+    pub member_breakdown: BTreeMap<String, u32>,
+}
+
+impl From<&FloorFramingLayout> for FloorFramingSummary {
+    fn from(layout: &FloorFramingLayout) -> Self {
+        Self {
+            layout_id: layout.id.to_string(),
+            level_id: layout.level_id.to_string(),
+            joist_count: layout.joist_count,
+            sheathing_sheets: layout.sheathing_sheets,
+            total_board_feet: layout.total_board_feet,
+            member_count: layout.members.len(),
+            lumber_size: layout.config.lumber_size.nominal_name(),
+            member_breakdown: member_breakdown(layout.members.iter().map(|m| m.member_type.display_name())),
+        }
+    }
+}
+
+fn member_breakdown<'a>(type_names: impl Iterator<Item = &'a str>) -> BTreeMap<String, u32> {
+    let mut breakdown = BTreeMap::new();
+    for name in type_names {
+        *breakdown.entry(name.to_string()).or_insert(0) += 1;
+    }
+    breakdown
+}
+
+/// The wall or room a pick ray hit first
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PickResult {
+    pub entity_id: String,
+    /// "wall" or "room"
+    pub kind: String,
+    pub distance: f64,
+    pub point: [f64; 3],
+    /// Normal of the face hit, turned toward the ray's origin
+    pub normal: [f64; 3],
+}
+
+impl From<&PickHit> for PickResult {
+    fn from(hit: &PickHit) -> Self {
+        Self {
+            entity_id: hit.target.id(),
+            kind: hit.target.kind().to_string(),
+            distance: hit.distance,
+            point: [hit.point.x, hit.point.y, hit.point.z],
+            normal: [hit.normal.x, hit.normal.y, hit.normal.z],
+        }
+    }
+}
+
+/// A snapped drawing point and what it snapped to
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapResult {
+    pub x: f64,
+    pub y: f64,
+    #[tsify(type = "\"wall_endpoint\" | \"wall_edge\" | \"room_corner\" | \"grid_intersection\" | \"grid_line\" | \"orthogonal\" | \"none\"")]
+    pub kind: String,
+    /// The wall or room snapped to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub entity_id: Option<String>,
+    /// Names of the grid axes snapped to
+    pub axes: Vec<String>,
+}
+
+impl From<&Snap> for SnapResult {
+    fn from(snap: &Snap) -> Self {
+        let (kind, entity_id, axes) = match &snap.target {
+            SnapTarget::WallEndpoint { wall_id } => ("wall_endpoint", Some(wall_id.to_string()), Vec::new()),
+            SnapTarget::WallEdge { wall_id } => ("wall_edge", Some(wall_id.to_string()), Vec::new()),
+            SnapTarget::RoomCorner { room_id } => ("room_corner", Some(room_id.to_string()), Vec::new()),
+            SnapTarget::GridIntersection { axes } => ("grid_intersection", None, axes.to_vec()),
+            SnapTarget::GridLine { axis } => ("grid_line", None, vec![axis.clone()]),
+            SnapTarget::Orthogonal => ("orthogonal", None, Vec::new()),
+            SnapTarget::None => ("none", None, Vec::new()),
+        };
+        Self { x: snap.point.x, y: snap.point.y, kind: kind.to_string(), entity_id, axes }
+    }
+}
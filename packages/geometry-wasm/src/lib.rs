@@ -1,18 +1,38 @@
 use wasm_bindgen::prelude::*;
-use geometry_core::store::{SharedStore, new_shared_store};
+use geometry_core::store::{SharedStore, new_shared_store, BatchCommand, PruningPolicy};
 use geometry_core::domain::{
     UnitSystem, CodeRegion, LevelId, ProjectId, BuildingId, WallAssemblyId, WallId, FootprintId,
-    Point2, Point3, Polygon2, RoomType, WallLayer, WallAssembly, RoomId,
-    OpeningId, OpeningType, GridAxis, GridDirection,
-    FramingLayout, FramingMember, FramingMemberType, LumberSize, FramingMaterial,
-    RoughOpening, WallFramingConfig,
+    MirrorAxis, Point2, Point3, Polygon2, Vector3, RoomType, WallLayer, WallAssembly, RoomId,
+    OpeningId, OpeningType, OpeningEdit, GridAxis, GridDirection,
+    LumberSize, WallFramingConfig, CornerStyle, FloorFramingConfig,
+    FilterId, FilterTarget, FilterCondition, ScriptId, ConstraintId, ConstraintKind, SnapshotId, AssemblyRole, BuildingOccupancy,
+    DetailCalloutId, DetailKind, DetailReference, RoofId, RoofType, FinishSurface, StairId,
+    FloorSlab, FloorSlabId, CeilingAssembly, CeilingAssemblyId, SlabBoundary, SlabLayer,
+    FoundationId, FoundationType, FoundationDimensions,
+    BeamId, PostId, MemberProfile, FramingMaterial, SiteId, Setbacks, ZoningRules,
+    DeviceId, ElectricalDeviceKind, FixtureId, PlumbingFixtureKind, HVACZoneId, DeckId, DeckKind, GarageId,
     // Costing types
-    MaterialType, LaborType, PricingUnit, UnitPrice, LaborRate, PriceTable,
+    MaterialType, LaborType, PricingUnit, UnitPrice, LaborRate, PriceTable, Markups,
 };
-use geometry_core::costing::{CostCalculator, CostInput, RoomCostInput, OpeningCostInput};
-use geometry_core::geometry::{solid_to_mesh, extrude_polygon, extrude_polygon_shell, create_box};
+use geometry_core::costing::{CostCalculator, QuantityTakeoff};
+use geometry_core::analysis::{analyze_egress, analyze_envelope, calculate_energy, check_bearing_continuity, check_zoning, circulation_graph, cut_fill, hvac_summary, room_daylight, solar_report};
+use geometry_core::compliance::{check_compliance, switch_code_region};
+use geometry_core::constraints::{check_constraints, check_mutation, solve_constraints};
+use geometry_core::templates::{instantiate_template, BuildingTemplate, TemplateParams};
+use geometry_core::rhai_api::{compile_script, create_engine, execute_saved_script, ExecutionLimits};
+use geometry_core::import::{import_dxf, import_ifc};
+use geometry_core::plan2d::{render_plan_dxf, render_plan_svg, PlanSvgOptions};
+use geometry_core::export::{building_scene, export_building_glb, export_level_glb, export_level_mesh, export_project_ifc, level_combined_mesh, level_room_meshes, level_wall_meshes, LevelPickIndex, MeshExportOptions};
+use geometry_core::sequencing::{sequence_building, SequenceTiming};
+use geometry_core::geometry::planar_graph::shared_edge;
+use geometry_core::geometry::{MeshData, MeshQuality, extrude_polygon, extrude_polygon_shell, create_box, create_oriented_box, generate_beam_solid, generate_post_solid, generate_roof_solid, generate_stair_solids};
 use std::str::FromStr;
-use std::collections::HashMap;
+
+mod dto;
+mod revisions;
+
+use dto::{to_js, BuildingStatsSummary, FloorFramingSummary, PickResult, RoomSummary, SnapResult, StoreCounts, WallFramingSummary, WallSummary};
+use revisions::Revisions;
 
 #[wasm_bindgen]
 pub fn init_panic_hook() {
@@ -20,6 +40,12 @@ pub fn init_panic_hook() {
 }
 
 /// Structured mesh data for Three.js
+///
+/// Buffer contract: the getters return copies that JS owns, so they stay
+/// valid after the mesh is freed or wasm memory grows. `take_buffers`
+/// copies each buffer out once and consumes the mesh, releasing its Rust
+/// memory; use it to hand a mesh to the GPU. Nothing here hands JS a view
+/// into wasm memory.
 #[wasm_bindgen]
 pub struct WasmMesh {
     positions: Vec<f32>,
@@ -27,71 +53,112 @@ pub struct WasmMesh {
     indices: Vec<u32>,
 }
 
+impl From<MeshData> for WasmMesh {
+    fn from(mesh: MeshData) -> Self {
+        Self {
+            positions: mesh.positions,
+            normals: mesh.normals,
+            indices: mesh.indices,
+        }
+    }
+}
+
 #[wasm_bindgen]
 impl WasmMesh {
+    /// Copy of the vertex positions
     #[wasm_bindgen(getter)]
     pub fn positions(&self) -> Float32Array {
-        unsafe { Float32Array::view(&self.positions) }
+        Float32Array::from(self.positions.as_slice())
     }
 
+    /// Copy of the vertex normals
     #[wasm_bindgen(getter)]
     pub fn normals(&self) -> Float32Array {
-        unsafe { Float32Array::view(&self.normals) }
+        Float32Array::from(self.normals.as_slice())
     }
 
+    /// Copy of the triangle indices
     #[wasm_bindgen(getter)]
     pub fn indices(&self) -> Uint32Array {
-        unsafe { Uint32Array::view(&self.indices) }
+        Uint32Array::from(self.indices.as_slice())
+    }
+
+    /// Copy the buffers into JS-owned typed arrays as { positions, normals,
+    /// indices }, then free the mesh. Each buffer is copied exactly once;
+    /// the JS object can't be used afterwards.
+    pub fn take_buffers(self) -> js_sys::Object {
+        let buffers = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&buffers, &JsValue::from_str("positions"), &Float32Array::from(self.positions.as_slice()));
+        let _ = js_sys::Reflect::set(&buffers, &JsValue::from_str("normals"), &Float32Array::from(self.normals.as_slice()));
+        let _ = js_sys::Reflect::set(&buffers, &JsValue::from_str("indices"), &Uint32Array::from(self.indices.as_slice()));
+        buffers
     }
 }
 
-use std::cell::{Cell, RefCell};
+use std::cell::{RefCell, RefMut};
+use std::collections::HashMap;
 
 #[wasm_bindgen]
 pub struct WasmStore {
     inner: SharedStore,
-    mutation_count: Cell<u64>,
+    revisions: RefCell<Revisions>,
+    /// Pick index per level, with the level revision it was built at
+    pick_indexes: RefCell<HashMap<LevelId, (u64, LevelPickIndex)>>,
     cost_calculator: RefCell<CostCalculator>,
 }
 
+impl Default for WasmStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen]
 impl WasmStore {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
         Self {
             inner: new_shared_store(),
-            mutation_count: Cell::new(0),
+            revisions: RefCell::new(Revisions::default()),
+            pick_indexes: RefCell::new(HashMap::new()),
             cost_calculator: RefCell::new(CostCalculator::with_defaults()),
         }
     }
 
-    /// Increment mutation count (called after successful mutations)
-    fn bump_mutation_count(&self) {
-        self.mutation_count.set(self.mutation_count.get() + 1);
+    /// Revisions brought up to date with every project's event log
+    fn synced_revisions(&self, store: &geometry_core::store::Store) -> Result<RefMut<'_, Revisions>, JsValue> {
+        let mut revisions = self.revisions.borrow_mut();
+        revisions.sync(store)?;
+        Ok(revisions)
     }
 
     pub fn create_project(&self, name: &str) -> Result<String, JsValue> {
         let mut store = self.inner.write().map_err(|_| "Failed to acquire write lock")?;
         let id = store.create_project(name, UnitSystem::Imperial, CodeRegion::us_irc_2021())
             .map_err(|e| e.to_string())?;
-        self.bump_mutation_count();
         Ok(id.to_string())
     }
 
-    pub fn render_level(&self, level_id: &str) -> Result<WasmMesh, JsValue> {
+    pub fn render_level(&self, level_id: &str, quality: Option<String>) -> Result<WasmMesh, JsValue> {
         use std::str::FromStr;
+        let quality = mesh_quality(quality)?;
         let level_id = LevelId::from_str(level_id)
             .map_err(|e| e.to_string())?;
         
         let store = self.inner.read().map_err(|_| "Failed to acquire read lock")?;
         let level = store.get_level(level_id).ok_or("Level not found")?;
-        let footprint = store.get_level_footprint(level_id).ok_or("Footprint not found")?;
+        let footprints = store.get_level_footprints(level_id);
+        if footprints.is_empty() {
+            return Err("Footprint not found".into());
+        }
 
-        let solid = extrude_polygon(&footprint.polygon, level.elevation, level.floor_to_floor)
-            .map_err(|e| e.to_string())?;
-        
-        let mesh_data = solid_to_mesh(&solid, 0.1)
-            .map_err(|e| e.to_string())?;
+        // Every footprint on the level, wings and attached garages included
+        let mut mesh_data = MeshData::default();
+        for footprint in footprints {
+            let solid = extrude_polygon(&footprint.polygon, level.elevation, level.floor_to_floor)
+                .map_err(|e| e.to_string())?;
+            mesh_data.append(quality.mesh(&solid, 0.1).map_err(|e| e.to_string())?);
+        }
 
         Ok(WasmMesh {
             positions: mesh_data.positions,
@@ -100,7 +167,7 @@ impl WasmStore {
         })
     }
 
-    pub fn get_all_geometry(&self, building_id: &str) -> Result<js_sys::Array, JsValue> {
+    pub fn get_all_geometry(&self, _building_id: &str) -> Result<js_sys::Array, JsValue> {
         // Placeholder for fetching all geometry (walls, floors, etc.)
         // For now, we'll just return an empty array or basic level geometry
         // This will be expanded as we add more entity types to the store
@@ -118,7 +185,6 @@ impl WasmStore {
         let building_id = store.add_building(project_id, name)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
         Ok(building_id.to_string())
     }
 
@@ -139,10 +205,44 @@ impl WasmStore {
         let level_id = store.add_level(building_id, name, elevation, floor_to_floor)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
         Ok(level_id.to_string())
     }
 
+    /// Copy a level's footprint, rooms, walls and openings to a new level
+    /// at the given elevation
+    pub fn duplicate_level(&self, level_id: &str, new_elevation: f64) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let copy_id = store.duplicate_level(level_id, new_elevation)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(copy_id.to_string())
+    }
+
+    /// Mirror a level's plan in place
+    /// direction: "vertical" (across x = offset) or "horizontal" (across y = offset)
+    pub fn mirror_level(&self, level_id: &str, direction: &str, offset: f64) -> Result<(), JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let axis = match direction.to_lowercase().as_str() {
+            "vertical" | "v" | "y" => MirrorAxis::Vertical(offset),
+            "horizontal" | "h" | "x" => MirrorAxis::Horizontal(offset),
+            _ => return Err(JsValue::from_str(&format!("Unknown mirror direction: {}", direction))),
+        };
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.mirror_level(level_id, axis)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Set a rectangular footprint for a level
     pub fn set_level_footprint_rect(
         &self,
@@ -167,7 +267,6 @@ impl WasmStore {
         let footprint_id = store.set_level_footprint(level_id, polygon)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
         Ok(footprint_id.to_string())
     }
 
@@ -203,10 +302,73 @@ impl WasmStore {
         let footprint_id = store.set_level_footprint(level_id, polygon)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
         Ok(footprint_id.to_string())
     }
 
+    /// Add a wing, attached garage or other block alongside a level's
+    /// primary footprint, from an array of [x, y] points
+    pub fn add_auxiliary_footprint(&self, level_id: &str, points: &JsValue) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let points: Vec<[f64; 2]> = serde_wasm_bindgen::from_value(points.clone())
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse points: {}", e)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let polygon = Polygon2::new(points.iter().map(|p| Point2::new(p[0], p[1])).collect());
+        let footprint_id = store.add_auxiliary_footprint(level_id, polygon)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(footprint_id.to_string())
+    }
+
+    /// Get every footprint on a level, primary first
+    pub fn get_level_footprints(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_level_footprints(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Remove a footprint from its level
+    pub fn remove_footprint(&self, footprint_id: &str) -> Result<(), JsValue> {
+        let footprint_id = FootprintId::from_str(footprint_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_footprint(footprint_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Cut a footprint in two along the line through two points. Returns
+    /// [kept_id, new_id]; the larger piece keeps the footprint's id.
+    pub fn split_footprint(
+        &self,
+        footprint_id: &str,
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+    ) -> Result<Vec<String>, JsValue> {
+        let footprint_id = FootprintId::from_str(footprint_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let (kept, new) = store.split_footprint(footprint_id, Point2::new(x1, y1), Point2::new(x2, y2))
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(vec![kept.to_string(), new.to_string()])
+    }
+
     /// Create a basic wall assembly with a single layer
     pub fn create_wall_assembly(&self, name: &str) -> Result<String, JsValue> {
         let mut store = self.inner.write()
@@ -218,11 +380,55 @@ impl WasmStore {
         let assembly_id = store.create_wall_assembly(name, layers)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
         Ok(assembly_id.to_string())
     }
 
+    /// Create a wall assembly from a JSON array of layers, outside face last:
+    /// [{ material, thickness, r_value_per_inch?, is_structural?, is_insulation? }]
+    /// Thicknesses are in inches and R-values per inch of thickness.
+    pub fn create_wall_assembly_with_layers(&self, name: &str, layers_json: &str) -> Result<String, JsValue> {
+        let layers: Vec<WallLayer> = serde_json::from_str(layers_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid layers JSON: {}", e)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let assembly_id = store.create_wall_assembly(name, layers)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(assembly_id.to_string())
+    }
+
+    /// Replace a wall assembly's name and layers, given as for
+    /// create_wall_assembly_with_layers
+    pub fn update_wall_assembly(&self, assembly_id: &str, name: &str, layers_json: &str) -> Result<(), JsValue> {
+        let assembly_id = WallAssemblyId::from_str(assembly_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let layers: Vec<WallLayer> = serde_json::from_str(layers_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid layers JSON: {}", e)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.update_wall_assembly(assembly_id, name, layers)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Remove a wall assembly no wall, project default or project history uses
+    pub fn remove_wall_assembly(&self, assembly_id: &str) -> Result<(), JsValue> {
+        let assembly_id = WallAssemblyId::from_str(assembly_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_wall_assembly(assembly_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
     /// Create a wall on a level
+    /// An empty assembly_id picks the assembly from the wall's context
     pub fn create_wall(
         &self,
         level_id: &str,
@@ -233,35 +439,73 @@ impl WasmStore {
     ) -> Result<String, JsValue> {
         let level_id = LevelId::from_str(level_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        let assembly_id = WallAssemblyId::from_str(assembly_id)
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let assembly_id = parse_optional_assembly_id(assembly_id)?;
 
-        // Parse start point from JsValue (array of [x, y])
-        let start_arr: Vec<f64> = serde_wasm_bindgen::from_value(start.clone())
-            .map_err(|e| JsValue::from_str(&format!("Failed to parse start point: {}", e)))?;
-        if start_arr.len() < 2 {
-            return Err(JsValue::from_str("Start point must have at least 2 values [x, y]"));
-        }
-        let start_point = Point2::new(start_arr[0], start_arr[1]);
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        // Parse end point from JsValue (array of [x, y])
-        let end_arr: Vec<f64> = serde_wasm_bindgen::from_value(end.clone())
-            .map_err(|e| JsValue::from_str(&format!("Failed to parse end point: {}", e)))?;
-        if end_arr.len() < 2 {
-            return Err(JsValue::from_str("End point must have at least 2 values [x, y]"));
+        // Points are [x, y] arrays or grid references like "A/3"
+        let start_point = parse_level_point(&store, level_id, start, "start point")?;
+        let end_point = parse_level_point(&store, level_id, end, "end point")?;
+
+        let wall_id = match assembly_id {
+            Some(assembly_id) => store.create_wall(level_id, assembly_id, start_point, end_point, height),
+            None => store.create_wall_auto(level_id, start_point, end_point, height),
         }
-        let end_point = Point2::new(end_arr[0], end_arr[1]);
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(wall_id.to_string())
+    }
+
+    /// Create a curved wall whose centerline arcs from start to end
+    /// A positive radius bows the wall to its left; an empty assembly_id
+    /// picks the assembly from the wall's context
+    pub fn create_curved_wall(
+        &self,
+        level_id: &str,
+        assembly_id: &str,
+        start: &JsValue,
+        end: &JsValue,
+        radius: f64,
+        height: f64,
+    ) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let assembly_id = parse_optional_assembly_id(assembly_id)?;
 
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        let wall_id = store.create_wall(level_id, assembly_id, start_point, end_point, height)
+        // Points are [x, y] arrays or grid references like "A/3"
+        let start_point = parse_level_point(&store, level_id, start, "start point")?;
+        let end_point = parse_level_point(&store, level_id, end, "end point")?;
+
+        let assembly_id = match assembly_id {
+            Some(assembly_id) => assembly_id,
+            None => store.select_wall_assembly(level_id, start_point, end_point)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?,
+        };
+        let wall_id = store.create_curved_wall(level_id, assembly_id, start_point, end_point, radius, height)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
         Ok(wall_id.to_string())
     }
 
+    /// Put a different assembly on an existing wall
+    pub fn set_wall_assembly(&self, wall_id: &str, assembly_id: &str) -> Result<(), JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let assembly_id = WallAssemblyId::from_str(assembly_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_wall_assembly(wall_id, assembly_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
     /// Create a room on a level
     pub fn create_room(
         &self,
@@ -276,31 +520,55 @@ impl WasmStore {
         // Parse room_type string to RoomType enum
         let room_type = RoomType::from_str(room_type);
 
-        // Parse points from JsValue (array of [x, y] arrays)
-        let points_array: Vec<Vec<f64>> = serde_wasm_bindgen::from_value(points.clone())
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        // Corners are [x, y] arrays or grid references like "A/3"
+        let points: Vec<PointInput> = serde_wasm_bindgen::from_value(points.clone())
             .map_err(|e| JsValue::from_str(&format!("Failed to parse points: {}", e)))?;
+        let boundary = Polygon2::new(
+            points
+                .into_iter()
+                .map(|p| resolve_level_point(&store, level_id, p, "point"))
+                .collect::<Result<Vec<_>, JsValue>>()?,
+        );
 
-        let polygon_points: Vec<Point2> = points_array
-            .iter()
-            .map(|p| {
-                if p.len() >= 2 {
-                    Point2::new(p[0], p[1])
-                } else {
-                    Point2::new(0.0, 0.0)
-                }
-            })
-            .collect();
+        let room_id = store.create_room(level_id, room_type, name, boundary)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(room_id.to_string())
+    }
 
-        let boundary = Polygon2::new(polygon_points);
+    /// Reshape a room from an array of [x, y] corner points
+    /// Returns the change in floor area (sq ft)
+    pub fn update_room_boundary(&self, room_id: &str, points: &JsValue) -> Result<f64, JsValue> {
+        let room_id = RoomId::from_str(room_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let points: Vec<[f64; 2]> = serde_wasm_bindgen::from_value(points.clone())
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse points: {}", e)))?;
 
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        let room_id = store.create_room(level_id, room_type, name, boundary)
+        let delta = store.update_room_boundary(room_id, points.iter().map(|p| Point2::new(p[0], p[1])).collect())
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
-        Ok(room_id.to_string())
+        Ok(delta)
+    }
+
+    /// Rename and retype a room
+    pub fn update_room_meta(&self, room_id: &str, name: &str, room_type: &str) -> Result<(), JsValue> {
+        let room_id = RoomId::from_str(room_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let room_type = RoomType::from_str(room_type);
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.update_room_meta(room_id, name, room_type)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(())
     }
 
     // ============ PROJECT QUERIES ============
@@ -365,7 +633,7 @@ impl WasmStore {
     }
 
     /// Get building statistics (total area, level count)
-    pub fn get_building_stats(&self, building_id: &str) -> Result<JsValue, JsValue> {
+    pub fn get_building_stats(&self, building_id: &str) -> Result<BuildingStatsSummary, JsValue> {
         let building_id = BuildingId::from_str(building_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
@@ -375,11 +643,52 @@ impl WasmStore {
         let stats = store.get_building_stats(building_id)
             .ok_or_else(|| JsValue::from_str("Building not found or has no stats"))?;
 
-        let obj = js_sys::Object::new();
-        let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("total_area"), &JsValue::from_f64(stats.total_area));
-        let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("level_count"), &JsValue::from_f64(stats.level_count as f64));
+        Ok(BuildingStatsSummary::from(&stats))
+    }
 
-        Ok(obj.into())
+    /// Classify a building: "dwelling", "adu", "garage" or "shed"
+    pub fn set_building_occupancy(&self, building_id: &str, occupancy: &str) -> Result<(), JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let occupancy = BuildingOccupancy::parse(occupancy)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown occupancy: {}", occupancy)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_building_occupancy(building_id, occupancy)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Set the elevation a building's pad is graded to (feet, site datum);
+    /// pass undefined to leave the pad ungraded
+    pub fn set_building_pad_elevation(&self, building_id: &str, pad_elevation: Option<f64>) -> Result<(), JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_building_pad_elevation(building_id, pad_elevation)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Stats for every building in a project
+    /// Returns { buildings: [BuildingStats], total_area, habitable_area, accessory_area }
+    pub fn get_project_stats(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let stats = store.get_project_stats(project_id)
+            .ok_or_else(|| JsValue::from_str("Project not found"))?;
+
+        serde_wasm_bindgen::to_value(&stats)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
     // ============ LEVEL QUERIES ============
@@ -472,6 +781,29 @@ impl WasmStore {
         Ok(wall.assembly_id.to_string())
     }
 
+    /// Get a wall assembly's layers and totals
+    /// Returns { id, name, layers, totalThickness, totalRValue }
+    pub fn get_wall_assembly_details(&self, assembly_id: &str) -> Result<JsValue, JsValue> {
+        let assembly_id = WallAssemblyId::from_str(assembly_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let assembly = store.get_wall_assembly(assembly_id)
+            .ok_or_else(|| JsValue::from_str("Wall assembly not found"))?;
+
+        let details = serde_json::json!({
+            "id": assembly.id.to_string(),
+            "name": assembly.name,
+            "layers": assembly.layers,
+            "totalThickness": assembly.total_thickness,
+            "totalRValue": assembly.total_r_value()
+        });
+
+        Ok(serde_wasm_bindgen::to_value(&details).unwrap_or(JsValue::NULL))
+    }
+
     // ============ EVENT QUERIES ============
 
     /// Get event count for a project
@@ -488,226 +820,2179 @@ impl WasmStore {
         Ok(event_log.len() as u32)
     }
 
-    // ============ DELETE OPERATIONS ============
-
-    /// Remove a building (cascades to remove all levels and footprints)
-    pub fn remove_building(&self, building_id: &str) -> Result<(), JsValue> {
-        let building_id = BuildingId::from_str(building_id)
+    /// Revision of a project's state, for caching rendered output
+    pub fn get_project_revision(&self, project_id: &str) -> Result<f64, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let mut store = self.inner.write()
-            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        store.remove_building(building_id)
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        self.bump_mutation_count();
-        Ok(())
+        store.project_revision(project_id)
+            .map(|rev| rev as f64)
+            .ok_or_else(|| JsValue::from_str("Event log not found"))
     }
 
-    /// Remove a level (cascades to remove footprint)
-    pub fn remove_level(&self, level_id: &str) -> Result<(), JsValue> {
-        let level_id = LevelId::from_str(level_id)
+    /// Entity IDs created/modified/deleted per type since a revision, so the
+    /// scene can patch only the affected meshes. Pass 0 for everything; if
+    /// `full_resync` is set the revision was undone and the scene should be
+    /// rebuilt from the listed entities.
+    pub fn get_changes_since(&self, project_id: &str, event_id: f64) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let mut store = self.inner.write()
-            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        store.remove_level(level_id)
+        let changes = store.changes_since(project_id, event_id as u64)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        self.bump_mutation_count();
-        Ok(())
+        serde_wasm_bindgen::to_value(&changes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
-    /// Offset a footprint by a distance
-    pub fn offset_footprint(&self, footprint_id: &str, distance: f64) -> Result<(), JsValue> {
-        let footprint_id = FootprintId::from_str(footprint_id)
+    // ============ UNDO/REDO ============
+
+    /// Revert the most recent change to a project
+    pub fn undo(&self, project_id: &str) -> Result<(), JsValue> {
+        let project_id = ProjectId::from_str(project_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        store.offset_footprint(footprint_id, distance)
+        store.undo(project_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        self.bump_mutation_count();
         Ok(())
     }
 
-    /// Remove a wall (cascades to remove all openings)
-    pub fn remove_wall(&self, wall_id: &str) -> Result<(), JsValue> {
-        let wall_id = WallId::from_str(wall_id)
+    /// Re-apply the most recently undone change to a project
+    pub fn redo(&self, project_id: &str) -> Result<(), JsValue> {
+        let project_id = ProjectId::from_str(project_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        store.remove_wall(wall_id)
+        store.redo(project_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        self.bump_mutation_count();
         Ok(())
     }
 
-    /// Get the current state as a JS object with entity counts
-    pub fn get_state(&self) -> JsValue {
-        let store = match self.inner.read() {
-            Ok(s) => s,
-            Err(_) => return JsValue::NULL,
+    /// Whether undo/redo are available, as {can_undo, can_redo}
+    pub fn get_undo_state(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let state = serde_json::json!({
+            "can_undo": store.can_undo(project_id),
+            "can_redo": store.can_redo(project_id),
+        });
+        serde_wasm_bindgen::to_value(&state)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ SNAPSHOTS ============
+
+    /// Checkpoint a project so later changes can be rolled back, optionally
+    /// labeled as a design alternative
+    pub fn create_snapshot(&self, project_id: &str, label: Option<String>) -> Result<String, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let snapshot_id = store.create_snapshot(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        if let Some(label) = label {
+            store.label_snapshot(snapshot_id, label)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        }
+        Ok(snapshot_id.to_string())
+    }
+
+    /// Name a snapshot
+    pub fn label_snapshot(&self, snapshot_id: &str, label: &str) -> Result<(), JsValue> {
+        let snapshot_id = SnapshotId::from_str(snapshot_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.label_snapshot(snapshot_id, label)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// A project's snapshots, oldest first
+    /// Returns [{ id, project_id, event_count, last_event_id, created_at, label }]
+    pub fn get_project_snapshots(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_project_snapshots(project_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Roll a project back to a snapshot
+    pub fn restore_snapshot(&self, snapshot_id: &str) -> Result<(), JsValue> {
+        let snapshot_id = SnapshotId::from_str(snapshot_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.restore_snapshot(snapshot_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    // ============ HISTORY COMPACTION ============
+
+    /// Compact a project's history, keeping the newest `keep_recent` events
+    /// intact. Returns {events_before, events_after, snapshots_dropped}.
+    pub fn compact_history(&self, project_id: &str, keep_recent: usize) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let report = store.compact_event_log(project_id, keep_recent)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Compact every project's history automatically after `compact_every`
+    /// new events (undefined turns automatic compaction off)
+    pub fn set_pruning_policy(&self, compact_every: Option<usize>, keep_recent: usize) -> Result<(), JsValue> {
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_pruning_policy(PruningPolicy { compact_every, keep_recent });
+        Ok(())
+    }
+
+    /// Sizes of the store's history, snapshots and entities, for tracking
+    /// memory growth over a long session
+    pub fn get_memory_stats(&self) -> Result<JsValue, JsValue> {
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.memory_stats())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ STORE IMAGES ============
+
+    /// The whole store as bytes, for handing to a worker, keeping in
+    /// IndexedDB or syncing to the server. Nothing is lost, unlike JSON.
+    pub fn export_bytes(&self) -> Result<Vec<u8>, JsValue> {
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        store.to_bytes()
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Replace everything in the store with bytes from `export_bytes`.
+    /// Every level reads as dirty afterwards, so the viewer re-meshes.
+    pub fn import_bytes(&self, bytes: &[u8]) -> Result<(), JsValue> {
+        let imported = geometry_core::store::Store::from_bytes(bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+        *store = imported;
+        *self.revisions.borrow_mut() = Revisions::default();
+        self.pick_indexes.borrow_mut().clear();
+        Ok(())
+    }
+
+    // ============ SAVED FILTERS ============
+
+    /// Save a named filter in a project
+    /// target: "walls", "rooms", or "openings"
+    /// conditions: array like [{type: "max_area", value: 100}]
+    pub fn save_filter(
+        &self,
+        project_id: &str,
+        name: &str,
+        target: &str,
+        conditions: JsValue,
+    ) -> Result<String, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let target = FilterTarget::parse(target)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown filter target: {}", target)))?;
+        let conditions: Vec<FilterCondition> = serde_wasm_bindgen::from_value(conditions)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let filter_id = store.save_filter(project_id, name, target, conditions)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(filter_id.to_string())
+    }
+
+    /// Evaluate a saved filter by name, returning {walls, rooms, openings} ID arrays
+    pub fn evaluate_filter(&self, project_id: &str, name: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let result = store.evaluate_named_filter(project_id, name)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// List a project's saved filters
+    pub fn list_filters(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let filters = store.get_project_filters(project_id);
+        serde_wasm_bindgen::to_value(&filters)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Remove a saved filter
+    pub fn remove_filter(&self, filter_id: &str) -> Result<(), JsValue> {
+        let filter_id = FilterId::from_str(filter_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_filter(filter_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    // ============ SAVED SCRIPTS ============
+
+    /// Save a named Rhai script in a project, replacing the source of one
+    /// already saved under the name
+    pub fn save_script(&self, project_id: &str, name: &str, source: &str) -> Result<String, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        compile_script(&create_engine(self.inner.clone()), source)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let script_id = store.save_script(project_id, name, source)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(script_id.to_string())
+    }
+
+    /// List a project's saved scripts
+    /// Returns [{ id, project_id, name, source }] sorted by name
+    pub fn list_scripts(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let scripts = store.get_project_scripts(project_id);
+        serde_wasm_bindgen::to_value(&scripts)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Remove a saved script
+    pub fn remove_script(&self, script_id: &str) -> Result<(), JsValue> {
+        let script_id = ScriptId::from_str(script_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_script(script_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Run a saved script by name; it sees the project as `project` and
+    /// `params` as `params`
+    /// `timeout_ms` stops the script once it has run that long
+    /// Returns { success, value, error, events_generated,
+    ///           trace: [{ function, args, result, error, line }], aborted }
+    pub fn run_saved_script(
+        &self,
+        project_id: &str,
+        name: &str,
+        params: JsValue,
+        timeout_ms: Option<u32>,
+    ) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let params: rhai::Map = if params.is_undefined() || params.is_null() {
+            rhai::Map::new()
+        } else {
+            serde_wasm_bindgen::from_value::<rhai::Dynamic>(params)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?
+                .try_cast::<rhai::Map>()
+                .ok_or_else(|| JsValue::from_str("Script params must be an object"))?
         };
 
-        let state = js_sys::Object::new();
+        let limits = ExecutionLimits {
+            timeout: timeout_ms.map(|ms| std::time::Duration::from_millis(ms as u64)),
+            cancel: None,
+        };
+
+        let engine = create_engine(self.inner.clone());
+        let result = execute_saved_script(&engine, self.inner.clone(), project_id, name, params, &limits)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(serde_wasm_bindgen::to_value(&result.to_json()).unwrap_or(JsValue::NULL))
+    }
+
+    // ============ NAMED CONSTRAINTS ============
+
+    /// Declare a named constraint in a project
+    /// kind: {type: "room_min_area", room_id, min_area} |
+    ///       {type: "room_aspect_ratio", room_id, max_ratio} |
+    ///       {type: "wall_on_grid_axis", wall_id, axis} |
+    ///       {type: "wall_distance", wall_a, wall_b, distance}
+    pub fn add_constraint(&self, project_id: &str, name: &str, kind: JsValue) -> Result<String, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let kind: ConstraintKind = serde_wasm_bindgen::from_value(kind)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let constraint_id = store.add_constraint(project_id, name, kind)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(constraint_id.to_string())
+    }
+
+    /// List a project's constraints
+    pub fn list_constraints(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let constraints = store.get_project_constraints(project_id);
+        serde_wasm_bindgen::to_value(&constraints)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Remove a constraint
+    pub fn remove_constraint(&self, constraint_id: &str) -> Result<(), JsValue> {
+        let constraint_id = ConstraintId::from_str(constraint_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_constraint(constraint_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Evaluate every constraint of a project
+    /// Returns {project_id, statuses: [{constraint_id, name, kind, satisfied,
+    /// actual, target, message}]}
+    pub fn check_constraints(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let report = check_constraints(&store, project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize constraint report: {}", e)))
+    }
+
+    /// Adjust geometry until the project's constraints hold
+    /// Returns {iterations, adjusted_rooms, moved_walls, report}
+    pub fn solve_constraints(&self, project_id: &str, max_iterations: u32) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let solve = solve_constraints(&mut store, project_id, max_iterations as usize)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&solve)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize solve report: {}", e)))
+    }
+
+    // ============ WALL EDITS ============
+
+    /// Move a wall to new end points ([x, y] arrays)
+    /// Returns the constraints the move broke or resolved: {broken, resolved}
+    pub fn set_wall_endpoints(&self, wall_id: &str, start: JsValue, end: JsValue) -> Result<JsValue, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let start: [f64; 2] = serde_wasm_bindgen::from_value(start)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse start point: {}", e)))?;
+        let end: [f64; 2] = serde_wasm_bindgen::from_value(end)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse end point: {}", e)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+        let project_id = wall_project_id(&store, wall_id)?;
+
+        let (_, changes) = check_mutation(&mut store, project_id, |store| {
+            store.set_wall_endpoints(wall_id, Point2::new(start[0], start[1]), Point2::new(end[0], end[1]))
+        })
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&changes)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize constraint changes: {}", e)))
+    }
+
+    /// Translate a wall by (dx, dy), openings and all
+    /// Returns the constraints the move broke or resolved: {broken, resolved}
+    pub fn move_wall(&self, wall_id: &str, dx: f64, dy: f64) -> Result<JsValue, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+        let project_id = wall_project_id(&store, wall_id)?;
+
+        let (_, changes) = check_mutation(&mut store, project_id, |store| {
+            store.move_wall(wall_id, dx, dy)
+        })
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&changes)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize constraint changes: {}", e)))
+    }
+
+    /// Split a straight wall at t (0 = start, 1 = end); openings go to
+    /// whichever half holds them
+    /// Returns the ID of the new second half
+    pub fn split_wall(&self, wall_id: &str, t: f64) -> Result<String, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let new_id = store.split_wall(wall_id, t)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(new_id.to_string())
+    }
+
+    /// Join two collinear walls that share an end. The first wall is kept
+    /// and takes over the second's openings; the second is removed.
+    /// Returns the ID of the merged wall
+    pub fn merge_walls(&self, wall_a: &str, wall_b: &str) -> Result<String, JsValue> {
+        let wall_a = WallId::from_str(wall_a)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let wall_b = WallId::from_str(wall_b)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let merged = store.merge_walls(wall_a, wall_b)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(merged.to_string())
+    }
+
+    /// Trim or extend a straight wall so it meets another wall's line
+    /// Returns the constraints the edit broke or resolved: {broken, resolved}
+    pub fn trim_extend_to(&self, wall_id: &str, other_id: &str) -> Result<JsValue, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let other_id = WallId::from_str(other_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+        let project_id = wall_project_id(&store, wall_id)?;
+
+        let (_, changes) = check_mutation(&mut store, project_id, |store| {
+            store.trim_extend_to(wall_id, other_id)
+        })
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&changes)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize constraint changes: {}", e)))
+    }
+
+    // ============ BUILDING TEMPLATES ============
+
+    /// Create a starter building from a template ("ranch", "two_story_colonial")
+    /// params: {stories, width, depth, garage, bedrooms, story_height}, any
+    /// of which can be left out
+    /// Returns {template, building_id, level_ids, room_ids, wall_ids,
+    /// opening_ids, stair_ids}
+    pub fn instantiate_template(&self, project_id: &str, template: &str, params: JsValue) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let template = BuildingTemplate::parse(template)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown building template: {}", template)))?;
+        let params: TemplateParams = if params.is_undefined() || params.is_null() {
+            TemplateParams::default()
+        } else {
+            serde_wasm_bindgen::from_value(params)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?
+        };
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let instance = instantiate_template(&mut store, project_id, template, &params)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&instance)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize template instance: {}", e)))
+    }
+
+    /// Available templates with their default parameters
+    pub fn list_templates(&self) -> Result<JsValue, JsValue> {
+        let templates: Vec<serde_json::Value> = BuildingTemplate::all()
+            .iter()
+            .map(|t| serde_json::json!({
+                "name": t.as_str(),
+                "displayName": t.display_name(),
+                "defaults": t.default_params()
+            }))
+            .collect();
+
+        serde_wasm_bindgen::to_value(&templates)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize templates: {}", e)))
+    }
+
+    // ============ DETAIL CALLOUTS ============
+
+    /// Place a detail callout on a level plan
+    /// kind: "wall_section", "foundation_edge", "window_head", "window_jamb", "window_sill", ...
+    pub fn add_detail_callout(
+        &self,
+        level_id: &str,
+        x: f64,
+        y: f64,
+        kind: &str,
+        detail_number: &str,
+        sheet: &str,
+    ) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let reference = DetailReference::new(DetailKind::parse(kind), detail_number, sheet);
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let callout_id = store.add_detail_callout(level_id, Point2::new(x, y), reference)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(callout_id.to_string())
+    }
+
+    /// Place a detail callout at an opening (e.g. window head/jamb/sill)
+    pub fn add_opening_detail_callout(
+        &self,
+        opening_id: &str,
+        kind: &str,
+        detail_number: &str,
+        sheet: &str,
+    ) -> Result<String, JsValue> {
+        let opening_id = OpeningId::from_str(opening_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let reference = DetailReference::new(DetailKind::parse(kind), detail_number, sheet);
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let callout_id = store.add_opening_detail_callout(opening_id, reference)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(callout_id.to_string())
+    }
+
+    /// Place a detail callout at the middle of a wall (e.g. typical wall section)
+    pub fn add_wall_detail_callout(
+        &self,
+        wall_id: &str,
+        kind: &str,
+        detail_number: &str,
+        sheet: &str,
+    ) -> Result<String, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let reference = DetailReference::new(DetailKind::parse(kind), detail_number, sheet);
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let callout_id = store.add_wall_detail_callout(wall_id, reference)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(callout_id.to_string())
+    }
+
+    /// Remove a detail callout
+    pub fn remove_detail_callout(&self, callout_id: &str) -> Result<(), JsValue> {
+        let callout_id = DetailCalloutId::from_str(callout_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_detail_callout(callout_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Callouts on a level, as [{ id, level_id, location, reference, wall_id, opening_id }]
+    pub fn get_level_detail_callouts(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_level_detail_callouts(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Detail schedule for a project, as [{ label, kind, sheet, detail_number, callout_ids, level_ids }]
+    pub fn get_detail_schedule(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.detail_schedule(project_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ BATCH OPERATIONS ============
+
+    /// Apply an array of mutation commands as one all-or-nothing batch.
+    /// Each command is {type: "create_wall" | "add_opening" | ..., ...fields};
+    /// ID fields may be "$N" to reference the Nth command's created ID.
+    /// Returns the created IDs (null for removals).
+    pub fn batch_execute(&self, commands: JsValue) -> Result<JsValue, JsValue> {
+        let commands: Vec<BatchCommand> = serde_wasm_bindgen::from_value(commands)
+            .map_err(|e| JsValue::from_str(&format!("Invalid batch: {}", e)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let created = store.execute_batch(&commands)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&created)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ CODE COMPLIANCE ============
+
+    /// Switch a project to another code (e.g. "IRC", 2012) and re-check it.
+    /// Returns {old_region, new_region, newly_violated, resolved, report}.
+    pub fn set_project_code_region(
+        &self,
+        project_id: &str,
+        code: &str,
+        year: u16,
+    ) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let change = switch_code_region(&mut store, project_id, CodeRegion::new(code, year))
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&change)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Check a project against its current code region
+    pub fn check_compliance(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let report = check_compliance(&store, project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ DELETE OPERATIONS ============
+
+    /// Remove a building (cascades to remove all levels and footprints)
+    pub fn remove_building(&self, building_id: &str) -> Result<(), JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_building(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Remove a level (cascades to remove footprint)
+    pub fn remove_level(&self, level_id: &str) -> Result<(), JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_level(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Offset a footprint by a distance
+    pub fn offset_footprint(&self, footprint_id: &str, distance: f64) -> Result<(), JsValue> {
+        let footprint_id = FootprintId::from_str(footprint_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.offset_footprint(footprint_id, distance)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Remove a wall (cascades to remove all openings)
+    pub fn remove_wall(&self, wall_id: &str) -> Result<(), JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_wall(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Get the current state as a JS object with entity counts
+    pub fn get_state(&self) -> Result<StoreCounts, JsValue> {
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        Ok(StoreCounts::from(&*store))
+    }
+
+    // ============ OPENING OPERATIONS ============
+
+    /// Add an opening (door/window) to a wall
+    /// position: 0.0 = start of wall, 1.0 = end of wall
+    pub fn add_opening(
+        &self,
+        wall_id: &str,
+        opening_type: &str,
+        position: f64,
+        width: f64,
+        height: f64,
+        sill_height: f64,
+    ) -> Result<String, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        // Parse opening type from string
+        let opening_type = OpeningType::from_str(opening_type);
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let opening_id = store.add_opening(
+            wall_id,
+            opening_type,
+            position,
+            width,
+            height,
+            sill_height,
+        ).map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(opening_id.to_string())
+    }
+
+    /// Add a window or door from the opening catalog by its size code
+    /// (e.g. "3068"). sill_height is ignored for doors.
+    pub fn add_catalog_opening(
+        &self,
+        wall_id: &str,
+        code: &str,
+        position: f64,
+        sill_height: f64,
+    ) -> Result<String, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let opening_id = store.add_catalog_opening(wall_id, code, position, sill_height)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(opening_id.to_string())
+    }
+
+    /// Import window and door entries from JSON ({ "windows": [...], "doors": [...] }),
+    /// replacing catalog entries with the same code. Returns the number imported.
+    pub fn import_opening_catalog(&self, json: &str) -> Result<u32, JsValue> {
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let count = store.import_opening_catalog(json)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(count as u32)
+    }
+
+    /// Get the opening catalog
+    pub fn get_opening_catalog(&self) -> Result<JsValue, JsValue> {
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.opening_catalog)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize opening catalog: {}", e)))
+    }
+
+    /// Get all opening IDs for a wall
+    pub fn get_wall_openings(&self, wall_id: &str) -> Result<js_sys::Array, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let openings = store.get_wall_openings(wall_id);
+        let arr = js_sys::Array::new();
+        for opening in openings {
+            arr.push(&JsValue::from_str(&opening.id.to_string()));
+        }
+        Ok(arr)
+    }
+
+    /// Move, resize or retype an opening in place
+    /// changes: {position, width, height, sill_height, opening_type}, any of
+    /// which can be left out
+    pub fn update_opening(&self, opening_id: &str, changes: JsValue) -> Result<(), JsValue> {
+        let opening_id = OpeningId::from_str(opening_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let edit: OpeningEdit = serde_wasm_bindgen::from_value(changes)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse opening changes: {}", e)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.update_opening(opening_id, edit)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Remove an opening from a wall
+    pub fn remove_opening(&self, opening_id: &str) -> Result<(), JsValue> {
+        let opening_id = OpeningId::from_str(opening_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_opening(opening_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    // ============ GRID OPERATIONS ============
+
+    /// Create a grid for a building
+    pub fn create_grid(&self, building_id: &str) -> Result<(), JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.create_grid(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Add a grid axis to a building's grid
+    /// direction: "horizontal" or "vertical"
+    pub fn add_grid_axis(
+        &self,
+        building_id: &str,
+        name: &str,
+        direction: &str,
+        offset: f64,
+    ) -> Result<(), JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        // Parse direction
+        let grid_direction = match direction.to_lowercase().as_str() {
+            "horizontal" | "h" => GridDirection::Horizontal,
+            "vertical" | "v" => GridDirection::Vertical,
+            _ => return Err(JsValue::from_str("Invalid direction: use 'horizontal' or 'vertical'")),
+        };
+
+        let axis = GridAxis {
+            name: name.to_string(),
+            direction: grid_direction,
+            offset,
+        };
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.add_grid_axis(building_id, axis)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Snap a point to the building's grid axes within `tolerance` feet.
+    /// Returns [x, y].
+    pub fn snap_point_to_grid(&self, building_id: &str, x: f64, y: f64, tolerance: f64) -> Result<Vec<f64>, JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let point = store.snap_point_to_grid(building_id, Point2::new(x, y), tolerance)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(vec![point.x, point.y])
+    }
+
+    /// Snap a drawing point on a level within `tolerance` feet: to wall
+    /// endpoints, room corners and grid crossings first, then wall faces
+    /// and grid axes, then an orthogonal lock to the anchor (the previous
+    /// point of the line being drawn), if given. Returns the point and
+    /// what it snapped to; kind "none" leaves the point as given.
+    pub fn snap_point(
+        &self,
+        level_id: &str,
+        x: f64,
+        y: f64,
+        tolerance: f64,
+        anchor_x: Option<f64>,
+        anchor_y: Option<f64>,
+    ) -> Result<SnapResult, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let anchor = anchor_x.zip(anchor_y).map(|(ax, ay)| Point2::new(ax, ay));
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let snap = store.snap_point(level_id, Point2::new(x, y), tolerance, anchor)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(SnapResult::from(&snap))
+    }
+
+    /// Render a building's grid axes as thin strips for overlay display,
+    /// one per axis, at `elevation`. Each axis runs `extension` feet past
+    /// the outermost axes crossing it.
+    pub fn render_grid(&self, building_id: &str, elevation: f64, extension: f64, quality: Option<String>) -> Result<js_sys::Array, JsValue> {
+        let quality = mesh_quality(quality)?;
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let grid = store.get_grid(building_id)
+            .ok_or_else(|| JsValue::from_str("Grid not found"))?;
+
+        // Span of the axes running the other way; a lone direction gets a
+        // span of its own extension
+        let span = |direction: GridDirection| {
+            let offsets = grid.axes.iter().filter(|a| a.direction == direction).map(|a| a.offset);
+            let (min, max) = offsets.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), o| (lo.min(o), hi.max(o)));
+            if min.is_finite() { (min - extension, max + extension) } else { (-extension, extension) }
+        };
+
+        let result = js_sys::Array::new();
+        for axis in &grid.axes {
+            let (start, end) = match axis.direction {
+                GridDirection::Horizontal => {
+                    let (x0, x1) = span(GridDirection::Vertical);
+                    (Point2::new(x0, axis.offset), Point2::new(x1, axis.offset))
+                }
+                GridDirection::Vertical => {
+                    let (y0, y1) = span(GridDirection::Horizontal);
+                    (Point2::new(axis.offset, y0), Point2::new(axis.offset, y1))
+                }
+            };
+
+            // A 1" strip, centered on the axis
+            let (length, width) = (start.distance_to(&end), 1.0 / 12.0);
+            let rotation = (end.y - start.y).atan2(end.x - start.x);
+            let origin = Point3::new(
+                start.x + width / 2.0 * rotation.sin(),
+                start.y - width / 2.0 * rotation.cos(),
+                elevation,
+            );
+            let Ok(solid) = create_oriented_box(length, width, 0.01, &origin, rotation) else {
+                continue;
+            };
+            let Ok(mesh_data) = quality.mesh(&solid, 0.1) else {
+                continue;
+            };
+            let mesh = WasmMesh {
+                positions: mesh_data.positions,
+                normals: mesh_data.normals,
+                indices: mesh_data.indices,
+            };
+
+            let direction = match axis.direction {
+                GridDirection::Horizontal => "horizontal",
+                GridDirection::Vertical => "vertical",
+            };
+            let obj = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("mesh"), &mesh.into());
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("name"), &JsValue::from_str(&axis.name));
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("direction"), &JsValue::from_str(direction));
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("offset"), &JsValue::from_f64(axis.offset));
+            result.push(&obj.into());
+        }
+
+        Ok(result)
+    }
+
+    // ============ STAIRS ============
+
+    /// Add a straight stair from one level up to another. `direction` is the
+    /// plan direction of travel in degrees from +X; width is in feet, riser
+    /// height and tread depth in inches.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stair(
+        &self,
+        from_level_id: &str,
+        to_level_id: &str,
+        start_x: f64,
+        start_y: f64,
+        direction: f64,
+        width: f64,
+        max_riser_height: f64,
+        tread_depth: f64,
+    ) -> Result<String, JsValue> {
+        let from_level_id = LevelId::from_str(from_level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let to_level_id = LevelId::from_str(to_level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let stair_id = store.create_stair(
+            from_level_id,
+            to_level_id,
+            Point2::new(start_x, start_y),
+            direction,
+            width,
+            max_riser_height,
+            tread_depth,
+        ).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(stair_id.to_string())
+    }
+
+    pub fn remove_stair(&self, stair_id: &str) -> Result<(), JsValue> {
+        let stair_id = StairId::from_str(stair_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_stair(stair_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Solved riser count, riser height, tread depth and run for a stair
+    pub fn get_stair_run(&self, stair_id: &str) -> Result<JsValue, JsValue> {
+        let stair_id = StairId::from_str(stair_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let run = store.get_stair_run(stair_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&run)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Render every stair starting on a level
+    /// Returns an array of { mesh: WasmMesh, stairId: string, part: "tread" | "stringer" }
+    pub fn render_stairs(&self, level_id: &str, quality: Option<String>) -> Result<js_sys::Array, JsValue> {
+        let quality = mesh_quality(quality)?;
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let level = store.get_level(level_id)
+            .ok_or_else(|| JsValue::from_str("Level not found"))?;
+
+        let result = js_sys::Array::new();
+        for stair in store.get_level_stairs(level_id) {
+            let Ok(run) = store.get_stair_run(stair.id) else {
+                continue;
+            };
+            let Ok(solids) = generate_stair_solids(stair, &run, level.elevation) else {
+                continue;
+            };
+
+            let parts = solids.treads.iter().map(|s| ("tread", s))
+                .chain(solids.stringers.iter().map(|s| ("stringer", s)));
+            for (part, solid) in parts {
+                let Ok(mesh_data) = quality.mesh(solid, 0.1) else {
+                    continue;
+                };
+                let mesh = WasmMesh {
+                    positions: mesh_data.positions,
+                    normals: mesh_data.normals,
+                    indices: mesh_data.indices,
+                };
+
+                let obj = js_sys::Object::new();
+                let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("mesh"), &mesh.into());
+                let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("stairId"), &JsValue::from_str(&stair.id.to_string()));
+                let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("part"), &JsValue::from_str(part));
+                result.push(&obj.into());
+            }
+        }
+
+        Ok(result)
+    }
+
+    // ============ BEAMS AND POSTS ============
+
+    /// Add a beam between two plan points (feet). `lumber_size` is a nominal
+    /// size ("2x10") or actual inches ("1.75x11.875"); `material` is one of
+    /// spf, df, syp, lvl, psl or steel.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_beam(
+        &self,
+        level_id: &str,
+        start_x: f64,
+        start_y: f64,
+        end_x: f64,
+        end_y: f64,
+        lumber_size: &str,
+        plies: u8,
+        material: &str,
+    ) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let profile = parse_member_profile(lumber_size, plies)?;
+        let material = parse_framing_material(material)?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let beam_id = store.create_beam(
+            level_id,
+            Point2::new(start_x, start_y),
+            Point2::new(end_x, end_y),
+            profile,
+            material,
+        ).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(beam_id.to_string())
+    }
+
+    pub fn remove_beam(&self, beam_id: &str) -> Result<(), JsValue> {
+        let beam_id = BeamId::from_str(beam_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_beam(beam_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Stand a post at a plan point (feet). Sizes and materials as for
+    /// create_beam; a post under a beam stops at the beam.
+    pub fn create_post(
+        &self,
+        level_id: &str,
+        x: f64,
+        y: f64,
+        lumber_size: &str,
+        plies: u8,
+        material: &str,
+    ) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let profile = parse_member_profile(lumber_size, plies)?;
+        let material = parse_framing_material(material)?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let post_id = store.create_post(level_id, Point2::new(x, y), profile, material)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(post_id.to_string())
+    }
+
+    pub fn remove_post(&self, post_id: &str) -> Result<(), JsValue> {
+        let post_id = PostId::from_str(post_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_post(post_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Render a level's beams and posts
+    /// Returns an array of { mesh: WasmMesh, memberId: string, kind: "beam" | "post", description: string }
+    pub fn render_beams_and_posts(&self, level_id: &str, quality: Option<String>) -> Result<js_sys::Array, JsValue> {
+        let quality = mesh_quality(quality)?;
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let level = store.get_level(level_id)
+            .ok_or_else(|| JsValue::from_str("Level not found"))?;
+
+        let beams = store.get_level_beams(level_id)
+            .into_iter()
+            .map(|b| (b.id.to_string(), "beam", b.profile.description(), generate_beam_solid(b, level.elevation)));
+        let posts = store.get_level_posts(level_id)
+            .into_iter()
+            .map(|p| (p.id.to_string(), "post", p.profile.description(), generate_post_solid(p, level.elevation)));
+
+        let result = js_sys::Array::new();
+        for (member_id, kind, description, solid) in beams.chain(posts) {
+            let Ok(solid) = solid else {
+                continue;
+            };
+            let Ok(mesh_data) = quality.mesh(&solid, 0.05) else {
+                continue;
+            };
+            let mesh = WasmMesh {
+                positions: mesh_data.positions,
+                normals: mesh_data.normals,
+                indices: mesh_data.indices,
+            };
+
+            let obj = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("mesh"), &mesh.into());
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("memberId"), &JsValue::from_str(&member_id));
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("kind"), &JsValue::from_str(kind));
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("description"), &JsValue::from_str(&description));
+            result.push(&obj.into());
+        }
+
+        Ok(result)
+    }
+
+    // ============ ELECTRICAL ============
+
+    /// Place an electrical device at a plan point (feet). `kind` is one of
+    /// receptacle, gfci_receptacle, switch or light_fixture. The device is
+    /// mounted in `wall_id` when given, otherwise in the wall it sits on.
+    pub fn add_electrical_device(
+        &self,
+        level_id: &str,
+        kind: &str,
+        x: f64,
+        y: f64,
+        wall_id: Option<String>,
+    ) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let kind = ElectricalDeviceKind::parse(kind)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown device kind: {}", kind)))?;
+        let wall_id = wall_id
+            .map(|id| WallId::from_str(&id))
+            .transpose()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let device_id = store.add_electrical_device(level_id, kind, Point2::new(x, y), wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(device_id.to_string())
+    }
+
+    pub fn remove_electrical_device(&self, device_id: &str) -> Result<(), JsValue> {
+        let device_id = DeviceId::from_str(device_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_electrical_device(device_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Devices on a level, as [{ id, level_id, kind, location, wall_id, room_id, auto_placed }]
+    pub fn get_level_electrical_devices(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_level_electrical_devices(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Lay out receptacles, switches and fixtures for every room on a level,
+    /// replacing an earlier auto-layout. Returns { device_ids, counts, replaced }.
+    pub fn auto_layout_electrical(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let summary = store.auto_layout_electrical(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&summary)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Device counts per room for a project. `format` is "json" (default)
+    /// for { entries, totals } or "csv" for a string.
+    pub fn get_device_schedule(&self, project_id: &str, format: Option<String>) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let schedule = store.device_schedule(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        match format.as_deref() {
+            None | Some("json") => serde_wasm_bindgen::to_value(&schedule)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize schedule: {}", e))),
+            Some("csv") => Ok(JsValue::from_str(&schedule.to_csv())),
+            Some(other) => Err(JsValue::from_str(&format!("Unknown schedule format: {}", other))),
+        }
+    }
+
+    // ============ PLUMBING ============
+
+    /// Place a fixture in a room with its back edge centered on (x, y),
+    /// facing `rotation` degrees from the +X axis. `kind` is one of toilet,
+    /// sink, tub or shower.
+    pub fn add_fixture(&self, room_id: &str, kind: &str, x: f64, y: f64, rotation: f64) -> Result<String, JsValue> {
+        let room_id = RoomId::from_str(room_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let kind = PlumbingFixtureKind::parse(kind)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown fixture kind: {}", kind)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let fixture_id = store.add_fixture(room_id, kind, Point2::new(x, y), rotation)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(fixture_id.to_string())
+    }
+
+    pub fn remove_fixture(&self, fixture_id: &str) -> Result<(), JsValue> {
+        let fixture_id = FixtureId::from_str(fixture_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_fixture(fixture_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Fixtures on a level, as [{ id, level_id, room_id, kind, location, rotation }]
+    pub fn get_level_fixtures(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_level_fixtures(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Fixtures on a level without their clear floor space, as
+    /// [{ fixture_id, room_id, kind, outside_room, obstructed_by }]
+    pub fn check_fixture_clearances(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.check_fixture_clearances(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// How a level's fixtures group onto wet walls, as
+    /// { level_id, groups: [{ wall_id, fixture_ids, room_ids }], fixture_count, shared_fixture_count }
+    pub fn get_wet_walls(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.wet_walls(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ HVAC ZONES ============
+
+    pub fn create_hvac_zone(&self, building_id: &str, name: &str) -> Result<String, JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let zone_id = store.create_hvac_zone(building_id, name)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(zone_id.to_string())
+    }
+
+    /// Remove a zone; its rooms become unzoned
+    pub fn remove_hvac_zone(&self, zone_id: &str) -> Result<(), JsValue> {
+        let zone_id = HVACZoneId::from_str(zone_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_hvac_zone(zone_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
 
-        // Count entities
-        let _ = js_sys::Reflect::set(
-            &state,
-            &JsValue::from_str("projects"),
-            &JsValue::from_f64(store.projects.len() as f64),
-        );
-        let _ = js_sys::Reflect::set(
-            &state,
-            &JsValue::from_str("buildings"),
-            &JsValue::from_f64(store.buildings.len() as f64),
-        );
-        let _ = js_sys::Reflect::set(
-            &state,
-            &JsValue::from_str("levels"),
-            &JsValue::from_f64(store.levels.len() as f64),
-        );
-        let _ = js_sys::Reflect::set(
-            &state,
-            &JsValue::from_str("walls"),
-            &JsValue::from_f64(store.walls.len() as f64),
-        );
-        let _ = js_sys::Reflect::set(
-            &state,
-            &JsValue::from_str("rooms"),
-            &JsValue::from_f64(store.rooms.len() as f64),
-        );
-        let _ = js_sys::Reflect::set(
-            &state,
-            &JsValue::from_str("footprints"),
-            &JsValue::from_f64(store.footprints.len() as f64),
-        );
-        let _ = js_sys::Reflect::set(
-            &state,
-            &JsValue::from_str("wall_assemblies"),
-            &JsValue::from_f64(store.wall_assemblies.len() as f64),
-        );
+    /// Move a room into a zone, or out of any zone when `zone_id` is omitted
+    pub fn assign_room_to_zone(&self, room_id: &str, zone_id: Option<String>) -> Result<(), JsValue> {
+        let room_id = RoomId::from_str(room_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let zone_id = zone_id
+            .map(|id| HVACZoneId::from_str(&id))
+            .transpose()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        state.into()
+        store.assign_room_to_zone(room_id, zone_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
     }
 
-    // ============ OPENING OPERATIONS ============
+    /// Zones in a building, by name: [{ id, building_id, name, room_ids }]
+    pub fn get_building_hvac_zones(&self, building_id: &str) -> Result<JsValue, JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    /// Add an opening (door/window) to a wall
-    /// position: 0.0 = start of wall, 1.0 = end of wall
-    pub fn add_opening(
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_building_hvac_zones(building_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ DECKS AND PORCHES ============
+
+    /// Attach a deck or porch ("deck" or "porch") to footprint edge
+    /// `edge_index`, `offset` feet from its first corner and `width` feet
+    /// along it. It extends `depth` feet out, its surface `height` feet
+    /// above grade.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_deck(
         &self,
-        wall_id: &str,
-        opening_type: &str,
-        position: f64,
+        level_id: &str,
+        kind: &str,
+        edge_index: usize,
+        offset: f64,
         width: f64,
+        depth: f64,
         height: f64,
-        sill_height: f64,
     ) -> Result<String, JsValue> {
-        let wall_id = WallId::from_str(wall_id)
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let kind = DeckKind::parse(kind)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown deck kind: {}", kind)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let deck_id = store.add_deck(level_id, kind, edge_index, offset, width, depth, height)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(deck_id.to_string())
+    }
+
+    pub fn remove_deck(&self, deck_id: &str) -> Result<(), JsValue> {
+        let deck_id = DeckId::from_str(deck_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_deck(deck_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Decks and porches on a level
+    pub fn get_level_decks(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_level_decks(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Ledger, rims, joists, decking and railing posts for a deck
+    pub fn get_deck_framing(&self, deck_id: &str) -> Result<JsValue, JsValue> {
+        let deck_id = DeckId::from_str(deck_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let layout = store.get_deck_framing(deck_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&layout)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ GARAGES ============
+
+    /// Set up a garage room's slab and put the fire separation assembly on
+    /// the walls it shares with the dwelling. Returns the garage id.
+    pub fn configure_garage(&self, room_id: &str) -> Result<String, JsValue> {
+        let room_id = RoomId::from_str(room_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let garage_id = store.configure_garage(room_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(garage_id.to_string())
+    }
+
+    pub fn remove_garage(&self, garage_id: &str) -> Result<(), JsValue> {
+        let garage_id = GarageId::from_str(garage_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_garage(garage_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Set a garage's slab thickness (inches) and its fall toward the
+    /// vehicle door (inches per foot)
+    pub fn set_garage_slab(&self, garage_id: &str, slab_thickness: f64, slab_slope: f64) -> Result<(), JsValue> {
+        let garage_id = GarageId::from_str(garage_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_garage_slab(garage_id, slab_thickness, slab_slope)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Configured garages on a level
+    pub fn get_level_garages(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_level_garages(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Slab quantities and drainage fall for a garage
+    pub fn get_garage_slab(&self, garage_id: &str) -> Result<JsValue, JsValue> {
+        let garage_id = GarageId::from_str(garage_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let slab = store.garage_slab(garage_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&slab)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ FLOORS AND CEILINGS ============
+
+    /// Add a floor slab under a level, or under one room when `room_id` is
+    /// given. `layers` is an array of { name, material, thickness (inches),
+    /// r_value_per_inch? } from the top down; omit it for subfloor over
+    /// 2x10 joists.
+    pub fn create_floor_slab(
+        &self,
+        level_id: &str,
+        room_id: Option<String>,
+        layers: JsValue,
+    ) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let boundary = parse_slab_boundary(room_id.as_deref())?;
+        let layers = parse_slab_layers(layers)?.unwrap_or_else(FloorSlab::framed_layers);
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let slab_id = store.create_floor_slab(level_id, boundary, layers)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(slab_id.to_string())
+    }
+
+    pub fn set_floor_slab_layers(&self, slab_id: &str, layers: JsValue) -> Result<(), JsValue> {
+        let slab_id = FloorSlabId::from_str(slab_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let layers = parse_slab_layers(layers)?
+            .ok_or_else(|| JsValue::from_str("Layers are required"))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_floor_slab_layers(slab_id, layers)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn remove_floor_slab(&self, slab_id: &str) -> Result<(), JsValue> {
+        let slab_id = FloorSlabId::from_str(slab_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_floor_slab(slab_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Add a ceiling over a level, or over one room when `room_id` is given.
+    /// `layers` runs from the room side up; omit it for insulated drywall.
+    pub fn create_ceiling(
+        &self,
+        level_id: &str,
+        room_id: Option<String>,
+        layers: JsValue,
+    ) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let boundary = parse_slab_boundary(room_id.as_deref())?;
+        let layers = parse_slab_layers(layers)?.unwrap_or_else(CeilingAssembly::insulated_layers);
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let ceiling_id = store.create_ceiling(level_id, boundary, layers)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(ceiling_id.to_string())
+    }
+
+    pub fn set_ceiling_layers(&self, ceiling_id: &str, layers: JsValue) -> Result<(), JsValue> {
+        let ceiling_id = CeilingAssemblyId::from_str(ceiling_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let layers = parse_slab_layers(layers)?
+            .ok_or_else(|| JsValue::from_str("Layers are required"))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_ceiling_layers(ceiling_id, layers)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn remove_ceiling(&self, ceiling_id: &str) -> Result<(), JsValue> {
+        let ceiling_id = CeilingAssemblyId::from_str(ceiling_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.remove_ceiling(ceiling_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Floor slabs and ceilings on a level as { floors: [...], ceilings: [...] }
+    pub fn get_level_slabs(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let floors = serde_wasm_bindgen::to_value(&store.get_level_floor_slabs(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let ceilings = serde_wasm_bindgen::to_value(&store.get_level_ceilings(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let obj = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("floors"), &floors);
+        let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("ceilings"), &ceilings);
+        Ok(obj.into())
+    }
+
+    /// Render the floor slabs and ceilings on a level
+    /// Returns an array of { mesh: WasmMesh, id: string, kind: "floor" | "ceiling" }
+    pub fn render_slabs(&self, level_id: &str, quality: Option<String>) -> Result<js_sys::Array, JsValue> {
+        let quality = mesh_quality(quality)?;
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let level = store.get_level(level_id)
+            .ok_or_else(|| JsValue::from_str("Level not found"))?;
+
+        // (id, kind, boundary, bottom, thickness)
+        let mut parts = Vec::new();
+        for slab in store.get_level_floor_slabs(level_id) {
+            let thickness = slab.thickness();
+            parts.push((slab.id.to_string(), "floor", slab.boundary, level.elevation - thickness, thickness));
+        }
+        for ceiling in store.get_level_ceilings(level_id) {
+            let Some(bottom) = store.ceiling_elevation(ceiling.id) else {
+                continue;
+            };
+            parts.push((ceiling.id.to_string(), "ceiling", ceiling.boundary, bottom, ceiling.thickness()));
+        }
+
+        let result = js_sys::Array::new();
+        for (id, kind, boundary, bottom, thickness) in parts {
+            let Some(outline) = store.slab_outline(level_id, boundary) else {
+                continue;
+            };
+            let Ok(solid) = extrude_polygon(outline, bottom, thickness) else {
+                continue;
+            };
+            let Ok(mesh_data) = quality.mesh(&solid, 0.1) else {
+                continue;
+            };
+            let mesh = WasmMesh {
+                positions: mesh_data.positions,
+                normals: mesh_data.normals,
+                indices: mesh_data.indices,
+            };
+
+            let obj = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("mesh"), &mesh.into());
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("id"), &JsValue::from_str(&id));
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("kind"), &JsValue::from_str(kind));
+            result.push(&obj.into());
+        }
+
+        Ok(result)
+    }
+
+    // ============ ROOM FINISHES ============
+
+    /// Select the finish for a room surface ("floor", "wall", "ceiling" or
+    /// "base") from the material catalog
+    pub fn set_room_finish(&self, room_id: &str, surface: &str, material_type: &str) -> Result<(), JsValue> {
+        let room_id = RoomId::from_str(room_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let surface = FinishSurface::parse(surface)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown finish surface: {}", surface)))?;
+        let material = parse_material_type(material_type)?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_room_finish(room_id, surface, material)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Change several finish selections of a room in one step from a JSON
+    /// object, e.g. { "floor": "carpet", "ceiling": "drywall", "trim": "premium" }.
+    /// Surfaces left out keep their selection. Returns the room's finishes.
+    pub fn set_room_finishes(&self, room_id: &str, finishes_json: &str) -> Result<JsValue, JsValue> {
+        let room_id = RoomId::from_str(room_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let selections: std::collections::BTreeMap<String, String> = serde_json::from_str(finishes_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid finishes JSON: {}", e)))?;
+        let selections: Vec<(&str, &str)> = selections.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let finishes = store.update_room_finishes(room_id, &selections)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&finishes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Finish schedule for every room in a project
+    pub fn get_finish_schedule(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let schedule = store.finish_schedule(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&schedule)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ ROOFS ============
+
+    /// Put a roof over a level. `roof_type` is "gable", "hip" or "shed";
+    /// pitch is rise per 12 of run and overhang is in feet.
+    pub fn create_roof(
+        &self,
+        level_id: &str,
+        roof_type: &str,
+        pitch: f64,
+        overhang: f64,
+    ) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let roof_type = RoofType::parse(roof_type)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown roof type: {}", roof_type)))?;
 
-        // Parse opening type from string
-        let opening_type = OpeningType::from_str(opening_type);
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let roof_id = store.create_roof(level_id, roof_type, pitch, overhang)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(roof_id.to_string())
+    }
+
+    pub fn remove_roof(&self, roof_id: &str) -> Result<(), JsValue> {
+        let roof_id = RoofId::from_str(roof_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        let opening_id = store.add_opening(
-            wall_id,
-            opening_type,
-            position,
-            width,
-            height,
-            sill_height,
-        ).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        store.remove_roof(roof_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
 
-        self.bump_mutation_count();
-        Ok(opening_id.to_string())
+    /// The roof over a level, or null
+    pub fn get_level_roof(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_level_roof(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
-    /// Get all opening IDs for a wall
-    pub fn get_wall_openings(&self, wall_id: &str) -> Result<js_sys::Array, JsValue> {
-        let wall_id = WallId::from_str(wall_id)
+    /// Planes, edge lengths (feet) and downspouts of a roof
+    /// Returns { planes: [{ plan_area, surface_area, eave_length, downspouts }],
+    ///           eave, rake, ridge, hip, valley }
+    pub fn get_roof_topology(&self, roof_id: &str) -> Result<JsValue, JsValue> {
+        let roof_id = RoofId::from_str(roof_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let store = self.inner.read()
             .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        let openings = store.get_wall_openings(wall_id);
-        let arr = js_sys::Array::new();
-        for opening in openings {
-            arr.push(&JsValue::from_str(&opening.id.to_string()));
-        }
-        Ok(arr)
+        let roof = store.get_roof(roof_id)
+            .ok_or_else(|| JsValue::from_str("Roof not found"))?;
+        let footprint = store.get_level_footprint(roof.level_id)
+            .ok_or_else(|| JsValue::from_str("Footprint not found"))?;
+        let topology = roof.topology(&footprint.polygon)
+            .ok_or_else(|| JsValue::from_str("Footprint must have at least 3 vertices"))?;
+
+        serde_wasm_bindgen::to_value(&topology)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
-    /// Remove an opening from a wall
-    pub fn remove_opening(&self, opening_id: &str) -> Result<(), JsValue> {
-        let opening_id = OpeningId::from_str(opening_id)
+    /// Render a roof on top of its level's walls
+    pub fn render_roof(&self, roof_id: &str, quality: Option<String>) -> Result<WasmMesh, JsValue> {
+        let quality = mesh_quality(quality)?;
+        let roof_id = RoofId::from_str(roof_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let roof = store.get_roof(roof_id)
+            .ok_or_else(|| JsValue::from_str("Roof not found"))?;
+        let level = store.get_level(roof.level_id)
+            .ok_or_else(|| JsValue::from_str("Level not found"))?;
+        let footprint = store.get_level_footprint(roof.level_id)
+            .ok_or_else(|| JsValue::from_str("Footprint not found"))?;
+
+        let solid = generate_roof_solid(roof, &footprint.polygon, level.elevation + level.floor_to_floor)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mesh_data = quality.mesh(&solid, 0.1)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(WasmMesh {
+            positions: mesh_data.positions,
+            normals: mesh_data.normals,
+            indices: mesh_data.indices,
+        })
+    }
+
+    // ============ FOUNDATIONS ============
+
+    /// Put a foundation under a building's lowest level. `foundation_type`
+    /// is "slab_on_grade", "crawlspace" or "basement".
+    pub fn create_foundation(&self, level_id: &str, foundation_type: &str) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let foundation_type = FoundationType::parse(foundation_type)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown foundation type: {}", foundation_type)))?;
 
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        store.remove_opening(opening_id)
+        let foundation_id = store.create_foundation(level_id, foundation_type)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        self.bump_mutation_count();
-        Ok(())
+        Ok(foundation_id.to_string())
     }
 
-    // ============ GRID OPERATIONS ============
+    /// Set footing, stem wall and slab dimensions:
+    /// {footing_width, footing_depth, stem_wall_height, stem_wall_thickness,
+    /// slab_thickness, gravel_depth} (inches, stem wall height in feet)
+    pub fn set_foundation_dimensions(&self, foundation_id: &str, dimensions: JsValue) -> Result<(), JsValue> {
+        let foundation_id = FoundationId::from_str(foundation_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let dimensions: FoundationDimensions = serde_wasm_bindgen::from_value(dimensions)
+            .map_err(|e| JsValue::from_str(&format!("Invalid dimensions: {}", e)))?;
 
-    /// Create a grid for a building
-    pub fn create_grid(&self, building_id: &str) -> Result<(), JsValue> {
-        let building_id = BuildingId::from_str(building_id)
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_foundation_dimensions(foundation_id, dimensions)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn remove_foundation(&self, foundation_id: &str) -> Result<(), JsValue> {
+        let foundation_id = FoundationId::from_str(foundation_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        store.create_grid(building_id)
+        store.remove_foundation(foundation_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        self.bump_mutation_count();
         Ok(())
     }
 
-    /// Add a grid axis to a building's grid
-    /// direction: "horizontal" or "vertical"
-    pub fn add_grid_axis(
-        &self,
-        building_id: &str,
-        name: &str,
-        direction: &str,
-        offset: f64,
-    ) -> Result<(), JsValue> {
+    /// The foundation under a level, or null
+    pub fn get_level_foundation(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        serde_wasm_bindgen::to_value(&store.get_level_foundation(level_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Concrete (cubic yards), gravel, forms, rebar and vapor barrier for a
+    /// foundation under its level's footprint
+    pub fn get_foundation_quantities(&self, foundation_id: &str) -> Result<JsValue, JsValue> {
+        let foundation_id = FoundationId::from_str(foundation_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let quantities = store.foundation_quantities(foundation_id)
+            .ok_or_else(|| JsValue::from_str("Foundation or footprint not found"))?;
+        serde_wasm_bindgen::to_value(&quantities)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ 2D PLANS ============
+
+    /// Render a level's floor plan as an SVG string for direct embedding
+    pub fn render_plan_svg(&self, level_id: &str) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        render_plan_svg(&store, level_id, &PlanSvgOptions::default())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Export a level's floor plan as DXF file bytes for CAD tools
+    pub fn export_plan_dxf(&self, level_id: &str) -> Result<Vec<u8>, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        render_plan_dxf(&store, level_id)
+            .map(String::into_bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ MODEL EXPORT ============
+
+    /// Export a level as GLB bytes (walls, slabs, roof, framing, stairs)
+    pub fn export_level_glb(&self, level_id: &str) -> Result<Vec<u8>, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        export_level_glb(&store, level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Export every level of a building as GLB bytes
+    pub fn export_building_glb(&self, building_id: &str) -> Result<Vec<u8>, JsValue> {
         let building_id = BuildingId::from_str(building_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        // Parse direction
-        let grid_direction = match direction.to_lowercase().as_str() {
-            "horizontal" | "h" => GridDirection::Horizontal,
-            "vertical" | "v" => GridDirection::Vertical,
-            _ => return Err(JsValue::from_str("Invalid direction: use 'horizontal' or 'vertical'")),
-        };
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        let axis = GridAxis {
-            name: name.to_string(),
-            direction: grid_direction,
-            offset,
-        };
+        export_building_glb(&store, building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Export a level's combined mesh as Wavefront OBJ text.
+    /// `options` is an optional { weld, weld_tolerance, recompute_normals }.
+    pub fn export_level_obj(&self, level_id: &str, options: JsValue) -> Result<String, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let options = parse_mesh_export_options(options)?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        export_level_mesh(&store, level_id, &options)
+            .map(|mesh| mesh.to_obj())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Export a level's combined mesh as binary STL bytes
+    pub fn export_level_stl(&self, level_id: &str, options: JsValue) -> Result<Vec<u8>, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let options = parse_mesh_export_options(options)?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        export_level_mesh(&store, level_id, &options)
+            .map(|mesh| mesh.to_stl())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Export a project as IFC4 text (.ifc) for BIM tools
+    pub fn export_project_ifc(&self, project_id: &str) -> Result<String, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        export_project_ifc(&store, project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // ============ MODEL IMPORT ============
+
+    /// Import footprints (closed polylines) and walls (lines on WALL layers)
+    /// from DXF text onto a level; returns an ImportSummary
+    pub fn import_dxf(&self, level_id: &str, source: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        store.add_grid_axis(building_id, axis)
+        let summary = import_dxf(&mut store, level_id, source)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        self.bump_mutation_count();
-        Ok(())
+
+        serde_wasm_bindgen::to_value(&summary)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize import summary: {}", e)))
+    }
+
+    /// Import storeys, walls, slabs and spaces from IFC text as a new
+    /// building in the project; returns an ImportSummary
+    pub fn import_ifc(&self, project_id: &str, source: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let summary = import_ifc(&mut store, project_id, source)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&summary)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize import summary: {}", e)))
     }
 
     // ============ SHELL AND ROOM RENDERING ============
@@ -721,7 +3006,9 @@ impl WasmStore {
         &self,
         level_id: &str,
         wall_thickness: f64,
+        quality: Option<String>,
     ) -> Result<WasmMesh, JsValue> {
+        let quality = mesh_quality(quality)?;
         let level_id = LevelId::from_str(level_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
@@ -731,18 +3018,23 @@ impl WasmStore {
         let level = store.get_level(level_id)
             .ok_or_else(|| JsValue::from_str("Level not found"))?;
 
-        let footprint = store.get_level_footprint(level_id)
-            .ok_or_else(|| JsValue::from_str("Footprint not found"))?;
+        let footprints = store.get_level_footprints(level_id);
+        if footprints.is_empty() {
+            return Err(JsValue::from_str("Footprint not found"));
+        }
 
-        let solid = extrude_polygon_shell(
-            &footprint.polygon,
-            level.elevation,
-            level.floor_to_floor,
-            wall_thickness,
-        ).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut mesh_data = MeshData::default();
+        for footprint in footprints {
+            let solid = extrude_polygon_shell(
+                &footprint.polygon,
+                level.elevation,
+                level.floor_to_floor,
+                wall_thickness,
+            ).map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let mesh_data = solid_to_mesh(&solid, 0.1)
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            mesh_data.append(quality.mesh(&solid, 0.1)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?);
+        }
 
         Ok(WasmMesh {
             positions: mesh_data.positions,
@@ -753,56 +3045,23 @@ impl WasmStore {
 
     /// Render all rooms on a level as floor plates
     ///
-    /// Returns an array of meshes, one per room, each as thin (0.5') slabs
-    pub fn render_rooms(&self, level_id: &str) -> Result<js_sys::Array, JsValue> {
+    /// Returns an array of meshes, one per room, each as the floor slab under
+    /// the room (the modeled slab's thickness, else a framed floor)
+    pub fn render_rooms(&self, level_id: &str, quality: Option<String>) -> Result<js_sys::Array, JsValue> {
+        let quality = mesh_quality(quality)?;
         let level_id = LevelId::from_str(level_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let store = self.inner.read()
             .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        let level = store.get_level(level_id)
-            .ok_or_else(|| JsValue::from_str("Level not found"))?;
+        let rooms = level_room_meshes(&store, level_id, quality)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let rooms = store.get_level_rooms(level_id);
         let result = js_sys::Array::new();
-
-        // Room floor plate thickness: 0.5 feet (6 inches)
-        let floor_thickness = 0.5;
-
         for room in rooms {
-            // Skip rooms with invalid boundaries
-            if !room.boundary.is_valid() {
-                // Log warning but continue processing other rooms
-                continue;
-            }
-
-            // Extrude room boundary as thin floor slab
-            // Place floor plate at level elevation
-            match extrude_polygon(&room.boundary, level.elevation, floor_thickness) {
-                Ok(solid) => {
-                    match solid_to_mesh(&solid, 0.1) {
-                        Ok(mesh_data) => {
-                            let mesh = WasmMesh {
-                                positions: mesh_data.positions,
-                                normals: mesh_data.normals,
-                                indices: mesh_data.indices,
-                            };
-                            result.push(&mesh.into());
-                        }
-                        Err(_) => {
-                            // Skip rooms that fail to mesh
-                            continue;
-                        }
-                    }
-                }
-                Err(_) => {
-                    // Skip rooms that fail to extrude
-                    continue;
-                }
-            }
+            result.push(&WasmMesh::from(room.mesh).into());
         }
-
         Ok(result)
     }
 
@@ -813,83 +3072,169 @@ impl WasmStore {
         &self,
         level_id: &str,
         wall_thickness: f64,
+        quality: Option<String>,
     ) -> Result<JsValue, JsValue> {
-        let level_id_parsed = LevelId::from_str(level_id)
+        let quality = mesh_quality(quality)?;
+        let level_id = LevelId::from_str(level_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let store = self.inner.read()
             .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        let level = store.get_level(level_id_parsed)
-            .ok_or_else(|| JsValue::from_str("Level not found"))?;
+        let combined = level_combined_mesh(&store, level_id, wall_thickness, quality)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         // Create result object
         let result = js_sys::Object::new();
 
-        // Render shell if footprint exists
-        if let Some(footprint) = store.get_level_footprint(level_id_parsed) {
-            match extrude_polygon_shell(
-                &footprint.polygon,
-                level.elevation,
-                level.floor_to_floor,
-                wall_thickness,
-            ) {
-                Ok(solid) => {
-                    if let Ok(mesh_data) = solid_to_mesh(&solid, 0.1) {
-                        let shell_mesh = WasmMesh {
-                            positions: mesh_data.positions,
-                            normals: mesh_data.normals,
-                            indices: mesh_data.indices,
-                        };
-                        let _ = js_sys::Reflect::set(
-                            &result,
-                            &JsValue::from_str("shell"),
-                            &shell_mesh.into(),
-                        );
-                    }
-                }
-                Err(e) => {
-                    // Set shell to null with error message
-                    let _ = js_sys::Reflect::set(
-                        &result,
-                        &JsValue::from_str("shellError"),
-                        &JsValue::from_str(&e.to_string()),
-                    );
-                }
-            }
+        if let Some(e) = combined.shell_error {
+            // Leave the shell out, with the error message
+            let _ = js_sys::Reflect::set(
+                &result,
+                &JsValue::from_str("shellError"),
+                &JsValue::from_str(&e),
+            );
+        } else if let Some(shell) = combined.shell {
+            let _ = js_sys::Reflect::set(
+                &result,
+                &JsValue::from_str("shell"),
+                &WasmMesh::from(shell).into(),
+            );
+        }
+
+        let rooms_array = js_sys::Array::new();
+        for room in combined.rooms {
+            rooms_array.push(&WasmMesh::from(room.mesh).into());
         }
+        let _ = js_sys::Reflect::set(
+            &result,
+            &JsValue::from_str("rooms"),
+            &rooms_array.into(),
+        );
+
+        Ok(result.into())
+    }
+
+    /// Render a whole building in one call: per level, the shell, walls,
+    /// rooms, openings and every other part (slabs, roof, framing, beams,
+    /// posts, decks, stairs)
+    ///
+    /// Returns { buildingId, name, levels: [{ levelId, name, elevation, height, quality,
+    /// shell?, shellError?, walls: [{ wallId, thickness, mesh }],
+    /// rooms: [{ roomId, mesh }], openings: Opening[],
+    /// parts: [{ kind, id, name, material, mesh }] }] }, lowest level first.
+    /// `wall_thickness` is the shell's, default 0.667 (8" walls). With
+    /// `focus_level_id`, that level is meshed at `quality` and each level
+    /// away from it one step coarser; every level reports its `quality`.
+    pub fn render_building(
+        &self,
+        building_id: &str,
+        wall_thickness: Option<f64>,
+        quality: Option<String>,
+        focus_level_id: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let quality = mesh_quality(quality)?;
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let focus = focus_level_id
+            .map(|id| LevelId::from_str(&id))
+            .transpose()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let scene = building_scene(&store, building_id, wall_thickness.unwrap_or(0.667), quality, focus)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        // Render rooms
-        let rooms = store.get_level_rooms(level_id_parsed);
-        let rooms_array = js_sys::Array::new();
-        let floor_thickness = 0.5;
+        let set = |obj: &js_sys::Object, key: &str, value: &JsValue| {
+            let _ = js_sys::Reflect::set(obj, &JsValue::from_str(key), value);
+        };
 
-        for room in rooms {
-            if !room.boundary.is_valid() {
-                continue;
+        let levels = js_sys::Array::new();
+        for level in scene.levels {
+            let obj = js_sys::Object::new();
+            set(&obj, "levelId", &JsValue::from_str(&level.level_id.to_string()));
+            set(&obj, "name", &JsValue::from_str(&level.name));
+            set(&obj, "elevation", &JsValue::from_f64(level.elevation));
+            set(&obj, "height", &JsValue::from_f64(level.height));
+            set(&obj, "quality", &JsValue::from_str(level.quality.as_str()));
+            if let Some(e) = level.shell_error {
+                set(&obj, "shellError", &JsValue::from_str(&e));
+            } else if let Some(shell) = level.shell {
+                set(&obj, "shell", &WasmMesh::from(shell).into());
             }
 
-            if let Ok(solid) = extrude_polygon(&room.boundary, level.elevation, floor_thickness) {
-                if let Ok(mesh_data) = solid_to_mesh(&solid, 0.1) {
-                    let mesh = WasmMesh {
-                        positions: mesh_data.positions,
-                        normals: mesh_data.normals,
-                        indices: mesh_data.indices,
-                    };
-                    rooms_array.push(&mesh.into());
-                }
+            let walls = js_sys::Array::new();
+            for wall in level.walls {
+                let item = js_sys::Object::new();
+                set(&item, "wallId", &JsValue::from_str(&wall.wall_id.to_string()));
+                set(&item, "thickness", &JsValue::from_f64(wall.thickness));
+                set(&item, "mesh", &WasmMesh::from(wall.mesh).into());
+                walls.push(&item);
             }
-        }
+            set(&obj, "walls", &walls);
+
+            let rooms = js_sys::Array::new();
+            for room in level.rooms {
+                let item = js_sys::Object::new();
+                set(&item, "roomId", &JsValue::from_str(&room.room_id.to_string()));
+                set(&item, "mesh", &WasmMesh::from(room.mesh).into());
+                rooms.push(&item);
+            }
+            set(&obj, "rooms", &rooms);
+
+            set(&obj, "openings", &serde_wasm_bindgen::to_value(&level.openings).unwrap_or(JsValue::NULL));
+
+            let parts = js_sys::Array::new();
+            for part in level.parts {
+                let item = js_sys::Object::new();
+                set(&item, "kind", &JsValue::from_str(part.kind));
+                set(&item, "id", &JsValue::from_str(&part.id));
+                set(&item, "name", &JsValue::from_str(&part.name));
+                set(&item, "material", &JsValue::from_str(part.slot.name()));
+                set(&item, "mesh", &WasmMesh::from(part.mesh).into());
+                parts.push(&item);
+            }
+            set(&obj, "parts", &parts);
 
-        let _ = js_sys::Reflect::set(
-            &result,
-            &JsValue::from_str("rooms"),
-            &rooms_array.into(),
-        );
+            levels.push(&obj);
+        }
 
+        let result = js_sys::Object::new();
+        set(&result, "buildingId", &JsValue::from_str(&scene.building_id.to_string()));
+        set(&result, "name", &JsValue::from_str(&scene.name));
+        set(&result, "levels", &levels);
         Ok(result.into())
     }
 
+    /// The wall or room a ray hits first, as { entityId, kind, distance,
+    /// point, normal }, or null. The ray is in the meshes' coordinates
+    /// (plan feet, z up): `ray_origin` and `ray_dir` are [x, y, z]. The
+    /// level's index is kept until something on the level changes.
+    pub fn pick_entity(&self, level_id: &str, ray_origin: &[f64], ray_dir: &[f64]) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let (origin, direction) = match (ray_origin, ray_dir) {
+            ([ox, oy, oz], [dx, dy, dz]) => (Point3::new(*ox, *oy, *oz), Vector3::new(*dx, *dy, *dz)),
+            _ => return Err(JsValue::from_str("Ray origin and direction must be [x, y, z]")),
+        };
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+        let revision = self.synced_revisions(&store)?.level_revision(level_id);
+
+        let mut indexes = self.pick_indexes.borrow_mut();
+        let stale = indexes.get(&level_id).is_none_or(|(built_at, _)| *built_at != revision);
+        if stale {
+            let index = LevelPickIndex::build(&store, level_id)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            indexes.insert(level_id, (revision, index));
+        }
+        let hit = indexes[&level_id].1.pick(origin, direction);
+        to_js(&hit.as_ref().map(PickResult::from))
+    }
+
     // ============ STATE DERIVATION QUERY METHODS ============
 
     /// Get all rooms for a level with full details for state derivation
@@ -905,27 +3250,12 @@ impl WasmStore {
             Err(_) => return JsValue::NULL,
         };
 
-        let rooms = store.get_level_rooms(level_id);
-        let room_summaries: Vec<serde_json::Value> = rooms
-            .iter()
-            .map(|room| {
-                let centroid = room.boundary.centroid();
-                let bbox = compute_bounding_box(&room.boundary);
-                serde_json::json!({
-                    "id": room.id.to_string(),
-                    "name": room.name,
-                    "type": room.room_type.display_name(),
-                    "area": room.area(),
-                    "center": [centroid.x, centroid.y],
-                    "dimensions": {
-                        "width": bbox.0,
-                        "depth": bbox.1
-                    }
-                })
-            })
+        let room_summaries: Vec<RoomSummary> = store.get_level_rooms(level_id)
+            .into_iter()
+            .map(|room| RoomSummary::new(room, compute_bounding_box(&room.boundary)))
             .collect();
 
-        serde_wasm_bindgen::to_value(&room_summaries).unwrap_or(JsValue::NULL)
+        to_js(&room_summaries).unwrap_or(JsValue::NULL)
     }
 
     /// Get all walls for a level with full details for state derivation
@@ -942,27 +3272,12 @@ impl WasmStore {
             Err(_) => return JsValue::NULL,
         };
 
-        let walls = store.get_level_walls(level_id);
-        let wall_summaries: Vec<serde_json::Value> = walls
-            .iter()
-            .map(|wall| {
-                // Get wall assembly thickness
-                let thickness = store
-                    .get_wall_assembly(wall.assembly_id)
-                    .map(|a| a.total_thickness / 12.0) // Convert inches to feet
-                    .unwrap_or(0.5);
-
-                serde_json::json!({
-                    "id": wall.id.to_string(),
-                    "start": [wall.start.x, wall.start.y],
-                    "end": [wall.end.x, wall.end.y],
-                    "thickness": thickness,
-                    "height": wall.height
-                })
-            })
+        let wall_summaries: Vec<WallSummary> = store.get_level_walls(level_id)
+            .into_iter()
+            .map(|wall| WallSummary::new(wall, store.wall_thickness(wall)))
             .collect();
 
-        serde_wasm_bindgen::to_value(&wall_summaries).unwrap_or(JsValue::NULL)
+        to_js(&wall_summaries).unwrap_or(JsValue::NULL)
     }
 
     /// Get complete observable state for LLM feedback
@@ -978,13 +3293,15 @@ impl WasmStore {
             Err(_) => return JsValue::NULL,
         };
 
-        // Get rooms
+        // Get rooms, with window area against floor area
         let rooms = store.get_level_rooms(level_id_parsed);
+        let level = store.get_level(level_id_parsed);
         let room_summaries: Vec<serde_json::Value> = rooms
             .iter()
             .map(|room| {
                 let centroid = room.boundary.centroid();
                 let bbox = compute_bounding_box(&room.boundary);
+                let daylight = level.map(|level| room_daylight(&store, level, room));
                 serde_json::json!({
                     "id": room.id.to_string(),
                     "name": room.name,
@@ -994,7 +3311,9 @@ impl WasmStore {
                     "dimensions": {
                         "width": bbox.0,
                         "depth": bbox.1
-                    }
+                    },
+                    "glazingRatio": daylight.as_ref().map(|d| d.glazing_ratio()),
+                    "ventilationRatio": daylight.as_ref().map(|d| d.ventilation_ratio())
                 })
             })
             .collect();
@@ -1069,15 +3388,16 @@ impl WasmStore {
                 }
             });
 
-        // Calculate room adjacencies (rooms that share an edge or are within 1 ft)
-        let mut adjacencies: Vec<(String, String)> = Vec::new();
-        for i in 0..rooms.len() {
-            for j in (i + 1)..rooms.len() {
-                if rooms_are_adjacent(&rooms[i].boundary, &rooms[j].boundary, 1.0) {
-                    adjacencies.push((rooms[i].id.to_string(), rooms[j].id.to_string()));
-                }
-            }
-        }
+        // Room adjacencies (rooms that share a stretch of boundary)
+        let adjacencies: Vec<(String, String)> = store
+            .room_adjacency(level_id_parsed)
+            .map(|graph| {
+                graph.adjacencies
+                    .iter()
+                    .map(|adj| (adj.room_a.to_string(), adj.room_b.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
 
         // Identify circulation spaces (hallways, foyers, etc.)
         let circulation: Vec<String> = rooms
@@ -1093,10 +3413,66 @@ impl WasmStore {
             .map(|r| r.id.to_string())
             .collect();
 
-        // Build constraints (simplified for now - can be enhanced)
-        let satisfied: Vec<String> = Vec::new();
-        let violated: Vec<String> = Vec::new();
-        let warnings: Vec<String> = Vec::new();
+        // Which structure this level belongs to (dwelling, ADU, garage, shed)
+        let structure = store
+            .get_level(level_id_parsed)
+            .and_then(|level| store.get_building(level.building_id))
+            .map(|building| serde_json::json!({
+                "buildingId": building.id.to_string(),
+                "name": building.name,
+                "occupancy": building.occupancy.as_str()
+            }))
+            .unwrap_or(serde_json::Value::Null);
+
+        // Code compliance for the level's project. Project-wide findings
+        // (no level) are reported on every level.
+        let mut satisfied: Vec<String> = Vec::new();
+        let mut violated: Vec<String> = Vec::new();
+        let mut warnings: Vec<String> = Vec::new();
+        let report = store
+            .get_level(level_id_parsed)
+            .and_then(|level| store.get_building(level.building_id))
+            .and_then(|building| check_compliance(&store, building.project_id).ok());
+        // Rooms cut off from every entry
+        let mut unreachable: Vec<String> = Vec::new();
+        if let Ok(graph) = circulation_graph(&store, level_id_parsed) {
+            if graph.entries.is_empty() && !graph.rooms.is_empty() {
+                warnings.push("No entry: the level has no exterior door or stair toward grade".to_string());
+            } else {
+                for room_id in graph.unreachable_from_entry() {
+                    if let Some(room) = store.get_room(room_id) {
+                        warnings.push(format!("{} can't be reached from an entry through doors or openings", room.name));
+                    }
+                    unreachable.push(room_id.to_string());
+                }
+            }
+        }
+
+        // Broken design constraints of the project
+        let constraints = store
+            .get_level(level_id_parsed)
+            .and_then(|level| store.get_building(level.building_id))
+            .and_then(|building| check_constraints(&store, building.project_id).ok());
+        if let Some(constraints) = constraints {
+            for status in constraints.broken() {
+                violated.push(format!("Constraint {}: {}", status.name, status.message));
+            }
+        }
+
+        if let Some(report) = report {
+            satisfied = report.satisfied().iter().map(|rule| rule.as_str().to_string()).collect();
+            for violation in &report.violations {
+                if violation.level_id.is_some_and(|id| id != level_id_parsed) {
+                    continue;
+                }
+                let entry = format!("{}: {}", violation.section, violation.message);
+                if violation.is_warning() {
+                    warnings.push(entry);
+                } else {
+                    violated.push(entry);
+                }
+            }
+        }
 
         let observable_state = serde_json::json!({
             "floorplan": {
@@ -1111,13 +3487,15 @@ impl WasmStore {
                     "depth": footprint_depth
                 },
                 "roomAdjacencies": adjacencies,
-                "circulation": circulation
+                "circulation": circulation,
+                "unreachableRooms": unreachable
             },
             "constraints": {
                 "satisfied": satisfied,
                 "violated": violated,
                 "warnings": warnings
             },
+            "structure": structure,
             "footprint": {
                 "width": footprint_width,
                 "depth": footprint_depth
@@ -1127,101 +3505,101 @@ impl WasmStore {
         serde_wasm_bindgen::to_value(&observable_state).unwrap_or(JsValue::NULL)
     }
 
-    /// Get mutation counter for cache invalidation
-    /// This counter increments on every mutation operation
-    pub fn get_mutation_count(&self) -> u64 {
-        self.mutation_count.get()
+    /// Level IDs changed since the viewer last meshed them, including
+    /// levels that were deleted. Revisions are read from the event logs, so
+    /// this also picks up everything a script did.
+    pub fn get_dirty_levels(&self) -> Result<Vec<String>, JsValue> {
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+        let revisions = self.synced_revisions(&store)?;
+        Ok(revisions.dirty_levels().iter().map(|id| id.to_string()).collect())
+    }
+
+    /// Wall IDs on a level created, changed or deleted since it was last
+    /// meshed. A deleted wall's ID is listed so its mesh can be dropped.
+    pub fn get_dirty_walls(&self, level_id: &str) -> Result<Vec<String>, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+        let revisions = self.synced_revisions(&store)?;
+        Ok(revisions.dirty_walls(level_id))
+    }
+
+    /// Record that the viewer has re-meshed a level; it stays clean until
+    /// something on it changes again
+    pub fn mark_level_meshed(&self, level_id: &str) -> Result<(), JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+        self.synced_revisions(&store)?.mark_meshed(level_id);
+        Ok(())
+    }
+
+    /// Revision of a level, raised whenever anything on it changes. 0 if
+    /// it hasn't been seen.
+    pub fn get_level_revision(&self, level_id: &str) -> Result<f64, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+        Ok(self.synced_revisions(&store)?.level_revision(level_id) as f64)
+    }
+
+    /// Revision of a wall, room, opening or other level entity, raised
+    /// whenever it changes. 0 if it hasn't been seen.
+    pub fn get_entity_revision(&self, entity_id: &str) -> Result<f64, JsValue> {
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+        Ok(self.synced_revisions(&store)?.entity_revision(entity_id) as f64)
     }
 
     // ============================================================================
     // WALL RENDERING
     // ============================================================================
 
-    /// Render all walls on a level as individual box meshes
+    /// Render all walls on a level as individual meshes, mitered at corners
+    /// and trimmed at tees, with their door and window openings cut out
     /// Returns an array of { mesh: WasmMesh, wallId: string, thickness: number }
     #[wasm_bindgen]
-    pub fn render_walls(&self, level_id: &str) -> Result<js_sys::Array, JsValue> {
+    pub fn render_walls(&self, level_id: &str, quality: Option<String>) -> Result<js_sys::Array, JsValue> {
+        let quality = mesh_quality(quality)?;
         let level_id = LevelId::from_str(level_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let store = self.inner.read()
             .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        let level = store.get_level(level_id)
-            .ok_or_else(|| JsValue::from_str("Level not found"))?;
+        let walls = level_wall_meshes(&store, level_id, quality)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let walls = store.get_level_walls(level_id);
         let result = js_sys::Array::new();
-
         for wall in walls {
-            // Get wall assembly for thickness
-            let thickness = store.get_wall_assembly(wall.assembly_id)
-                .map(|a| a.total_thickness / 12.0) // Convert inches to feet
-                .unwrap_or(0.5);
-
-            // Build wall polygon from start/end + perpendicular offset by half thickness
-            let half_thickness = thickness / 2.0;
-            let (px, py) = wall.perpendicular();
-
-            // Create four corners of the wall footprint
-            let p1 = Point2::new(
-                wall.start.x + px * half_thickness,
-                wall.start.y + py * half_thickness,
-            );
-            let p2 = Point2::new(
-                wall.end.x + px * half_thickness,
-                wall.end.y + py * half_thickness,
-            );
-            let p3 = Point2::new(
-                wall.end.x - px * half_thickness,
-                wall.end.y - py * half_thickness,
-            );
-            let p4 = Point2::new(
-                wall.start.x - px * half_thickness,
-                wall.start.y - py * half_thickness,
-            );
-
-            let wall_polygon = Polygon2::new(vec![p1, p2, p3, p4]);
-            let base_z = level.elevation + wall.base_offset;
-
-            // Extrude the wall polygon
-            match extrude_polygon(&wall_polygon, base_z, wall.height) {
-                Ok(solid) => {
-                    match solid_to_mesh(&solid, 0.1) {
-                        Ok(mesh_data) => {
-                            let mesh = WasmMesh {
-                                positions: mesh_data.positions,
-                                normals: mesh_data.normals,
-                                indices: mesh_data.indices,
-                            };
-
-                            // Create result object with mesh and metadata
-                            let obj = js_sys::Object::new();
-                            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("mesh"), &mesh.into());
-                            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("wallId"), &JsValue::from_str(&wall.id.to_string()));
-                            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("thickness"), &JsValue::from_f64(thickness));
-
-                            result.push(&obj.into());
-                        }
-                        Err(_) => continue,
-                    }
-                }
-                Err(_) => continue,
-            }
+            // Create result object with mesh and metadata
+            let obj = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("mesh"), &WasmMesh::from(wall.mesh).into());
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("wallId"), &JsValue::from_str(&wall.wall_id.to_string()));
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("thickness"), &JsValue::from_f64(wall.thickness));
+            result.push(&obj.into());
         }
-
         Ok(result)
     }
 
     /// Render framing members for a specific wall
     /// Returns array of { mesh: WasmMesh, memberType: string, lumberSize: string }
     #[wasm_bindgen]
-    pub fn render_wall_framing(&self, wall_id: &str) -> Result<js_sys::Array, JsValue> {
+    pub fn render_wall_framing(&self, wall_id: &str, quality: Option<String>) -> Result<js_sys::Array, JsValue> {
+        let quality = mesh_quality(quality)?;
         let wall_id = WallId::from_str(wall_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let store = self.inner.read()
-            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        // Stale framing is regenerated before it is drawn
+        store.refresh_wall_framing(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let wall = store.get_wall(wall_id)
             .ok_or_else(|| JsValue::from_str("Wall not found"))?;
@@ -1260,7 +3638,7 @@ impl WasmStore {
 
             match create_box(box_width, box_depth, box_height, &origin) {
                 Ok(solid) => {
-                    match solid_to_mesh(&solid, 0.05) {
+                    match quality.mesh(&solid, 0.05) {
                         Ok(mesh_data) => {
                             let mesh = WasmMesh {
                                 positions: mesh_data.positions,
@@ -1286,12 +3664,104 @@ impl WasmStore {
         Ok(result)
     }
 
+    /// Generate floor framing for a level over its footprint
+    /// joist_direction is in degrees from the +x axis, joist_spacing in inches
+    #[wasm_bindgen]
+    pub fn generate_floor_framing(
+        &self,
+        level_id: &str,
+        joist_direction: f64,
+        joist_spacing: f64,
+        lumber_size: &str,
+    ) -> Result<FloorFramingSummary, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let lumber = match lumber_size.to_lowercase().as_str() {
+            "2x8" => LumberSize::TwoByEight,
+            "2x10" => LumberSize::TwoByTen,
+            "2x12" => LumberSize::TwoByTwelve,
+            _ => return Err(JsValue::from_str("Invalid joist size. Use: 2x8, 2x10, or 2x12")),
+        };
+        let config = FloorFramingConfig {
+            joist_direction: joist_direction.to_radians(),
+            joist_spacing,
+            lumber_size: lumber,
+            ..Default::default()
+        };
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.generate_floor_framing(level_id, config)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let layout = store.get_floor_framing_layout(level_id)
+            .ok_or_else(|| JsValue::from_str("Floor framing layout not found after generation"))?;
+
+        Ok(FloorFramingSummary::from(layout))
+    }
+
+    /// Render a level's floor framing as 3D meshes
+    /// Returns array of { mesh, memberType, lumberSize, memberId }
+    #[wasm_bindgen]
+    pub fn render_floor_framing(&self, level_id: &str, quality: Option<String>) -> Result<js_sys::Array, JsValue> {
+        let quality = mesh_quality(quality)?;
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let level = store.get_level(level_id)
+            .ok_or_else(|| JsValue::from_str("Level not found"))?;
+
+        let layout = store.get_floor_framing_layout(level_id)
+            .ok_or_else(|| JsValue::from_str("No floor framing found for level. Generate floor framing first."))?;
+
+        let result = js_sys::Array::new();
+
+        for member in &layout.members {
+            // Floor members all lie flat: length in plan, width beside it, depth up
+            let (width, depth) = member.lumber_size.actual_dimensions();
+            let origin = Point3::new(
+                member.position.x / 12.0,
+                member.position.y / 12.0,
+                level.elevation + member.position.z / 12.0,
+            );
+
+            let Ok(solid) = create_oriented_box(member.length / 12.0, width / 12.0, depth / 12.0, &origin, member.rotation) else {
+                continue;
+            };
+            let Ok(mesh_data) = quality.mesh(&solid, 0.05) else {
+                continue;
+            };
+            let mesh = WasmMesh {
+                positions: mesh_data.positions,
+                normals: mesh_data.normals,
+                indices: mesh_data.indices,
+            };
+
+            let obj = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("mesh"), &mesh.into());
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("memberType"), &JsValue::from_str(member.member_type.display_name()));
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("lumberSize"), &JsValue::from_str(&member.lumber_size.nominal_name()));
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("memberId"), &JsValue::from_str(&member.id.to_string()));
+
+            result.push(&obj.into());
+        }
+
+        Ok(result)
+    }
+
     // ============================================================================
     // WALL CREATION (ALTERNATIVE SIGNATURES)
     // ============================================================================
 
     /// Create a wall between two points using coordinate values directly
+    /// An empty assembly_id picks the assembly from the wall's context
     #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
     pub fn create_wall_coords(
         &self,
         level_id: &str,
@@ -1304,8 +3774,7 @@ impl WasmStore {
     ) -> Result<String, JsValue> {
         let level_id = LevelId::from_str(level_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
-        let assembly_id = WallAssemblyId::from_str(assembly_id)
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let assembly_id = parse_optional_assembly_id(assembly_id)?;
 
         let start = Point2::new(start_x, start_y);
         let end = Point2::new(end_x, end_y);
@@ -1313,10 +3782,12 @@ impl WasmStore {
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        let wall_id = store.create_wall(level_id, assembly_id, start, end, height)
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let wall_id = match assembly_id {
+            Some(assembly_id) => store.create_wall(level_id, assembly_id, start, end, height),
+            None => store.create_wall_auto(level_id, start, end, height),
+        }
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
         Ok(wall_id.to_string())
     }
 
@@ -1353,10 +3824,32 @@ impl WasmStore {
         let assembly_id = store.create_wall_assembly(name, layers)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
         Ok(assembly_id.to_string())
     }
 
+    /// Set the project's assembly for a wall role
+    /// role: "exterior", "fire_separation", "wet_area", "interior"; empty assembly_id clears it
+    #[wasm_bindgen]
+    pub fn set_default_assembly(
+        &self,
+        project_id: &str,
+        role: &str,
+        assembly_id: &str,
+    ) -> Result<(), JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let role: AssemblyRole = serde_wasm_bindgen::from_value(JsValue::from_str(role))
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let assembly_id = parse_optional_assembly_id(assembly_id)?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_default_assembly(project_id, role, assembly_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
     /// Auto-generate walls for a level based on room types and adjacencies
     /// Returns summary: { wallsCreated: number, decisions: [{ room1, room2, wallType, reason }] }
     #[wasm_bindgen]
@@ -1367,13 +3860,10 @@ impl WasmStore {
         let mut store = self.inner.write()
             .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        // Get all rooms on level
-        let rooms: Vec<_> = store.get_level_rooms(level_id)
-            .into_iter()
-            .map(|r| (r.id, r.room_type.clone(), r.name.clone(), r.boundary.clone()))
-            .collect();
+        let graph = store.room_adjacency(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        if rooms.is_empty() {
+        if graph.rooms.is_empty() {
             return Ok(serde_wasm_bindgen::to_value(&serde_json::json!({
                 "wallsCreated": 0,
                 "decisions": [],
@@ -1381,18 +3871,6 @@ impl WasmStore {
             })).unwrap_or(JsValue::NULL));
         }
 
-        // Get or create default wall assembly
-        let assembly_id = {
-            let existing = store.list_wall_assemblies().first().map(|a| a.id);
-            if let Some(id) = existing {
-                id
-            } else {
-                let layers = vec![WallLayer::stud_2x6()];
-                store.create_wall_assembly("Default Wall", layers)
-                    .map_err(|e| JsValue::from_str(&e.to_string()))?
-            }
-        };
-
         let level = store.get_level(level_id)
             .ok_or_else(|| JsValue::from_str("Level not found"))?;
         let wall_height = level.floor_to_floor;
@@ -1400,45 +3878,47 @@ impl WasmStore {
         let mut walls_created = 0;
         let mut decisions: Vec<serde_json::Value> = Vec::new();
 
-        // Compute room adjacencies and decide on walls
-        for i in 0..rooms.len() {
-            for j in (i + 1)..rooms.len() {
-                let (id1, type1, name1, boundary1) = &rooms[i];
-                let (id2, type2, name2, boundary2) = &rooms[j];
-
-                // Check if rooms share an edge (are adjacent)
-                if let Some((shared_start, shared_end)) = find_shared_edge(boundary1, boundary2, 1.0) {
-                    // Decide if wall should exist based on room types
-                    let (wall_type, reason) = decide_wall_type(type1, type2);
-
-                    decisions.push(serde_json::json!({
-                        "room1": name1,
-                        "room2": name2,
-                        "room1Id": id1.to_string(),
-                        "room2Id": id2.to_string(),
-                        "wallType": wall_type,
-                        "reason": reason
-                    }));
-
-                    // Create wall if needed
-                    if wall_type == "full" {
-                        let wall_result = store.create_wall(
-                            level_id,
-                            assembly_id,
-                            shared_start,
-                            shared_end,
-                            wall_height,
-                        );
-
-                        if wall_result.is_ok() {
-                            walls_created += 1;
-                        }
+        // Decide on a wall for each pair of adjacent rooms
+        for adjacency in &graph.adjacencies {
+            let (Some(room1), Some(room2)) = (store.get_room(adjacency.room_a), store.get_room(adjacency.room_b)) else {
+                continue;
+            };
+            let (name1, name2) = (room1.name.clone(), room2.name.clone());
+
+            // Decide if wall should exist based on room types
+            let (wall_type, reason) = decide_wall_type(&room1.room_type, &room2.room_type);
+            let longest = adjacency.longest();
+            let role = store.wall_assembly_role(level_id, longest.start, longest.end)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+            decisions.push(serde_json::json!({
+                "room1": name1,
+                "room2": name2,
+                "room1Id": adjacency.room_a.to_string(),
+                "room2Id": adjacency.room_b.to_string(),
+                "wallType": wall_type,
+                "assemblyRole": role.as_str(),
+                "sharedLength": adjacency.length,
+                "reason": reason
+            }));
+
+            // Create walls along every shared segment, with an assembly suited to its rooms
+            if wall_type == "full" {
+                for segment in &adjacency.segments {
+                    let wall_result = store.create_wall_auto(
+                        level_id,
+                        segment.start,
+                        segment.end,
+                        wall_height,
+                    );
+
+                    if wall_result.is_ok() {
+                        walls_created += 1;
                     }
                 }
             }
         }
 
-        self.bump_mutation_count();
 
         Ok(serde_wasm_bindgen::to_value(&serde_json::json!({
             "wallsCreated": walls_created,
@@ -1446,6 +3926,43 @@ impl WasmStore {
         })).unwrap_or(JsValue::NULL))
     }
 
+    /// Derive room boundaries from the faces enclosed by a level's walls,
+    /// the inverse of auto_generate_walls. Existing rooms take the boundary
+    /// of the face they sit in; enclosed faces without a room get one.
+    /// Returns { created: string[], updated: string[], unenclosed: string[] }
+    #[wasm_bindgen]
+    pub fn derive_rooms_from_walls(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let derivation = store.derive_rooms_from_walls(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&derivation)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Pairs of rooms on a level that share boundary
+    /// Returns { level_id, rooms: string[],
+    ///           adjacencies: [{ room_a, room_b, segments: [{ start, end }], length }] }
+    #[wasm_bindgen]
+    pub fn get_room_adjacencies(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let graph = store.room_adjacency(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&graph)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize room adjacency: {}", e)))
+    }
+
     /// Set wall type between two rooms (override auto decision)
     /// wall_type: "full" | "none" | "half" | "cased_opening"
     #[wasm_bindgen]
@@ -1479,7 +3996,7 @@ impl WasmStore {
         let boundary2 = room2.boundary.clone();
 
         // Find shared edge
-        let (shared_start, shared_end) = find_shared_edge(&boundary1, &boundary2, 1.0)
+        let (shared_start, shared_end) = shared_edge(&boundary1, &boundary2, 1.0)
             .ok_or_else(|| JsValue::from_str("Rooms do not share an edge"))?;
 
         // Extract level data before mutable operations
@@ -1502,10 +4019,7 @@ impl WasmStore {
         // Find and remove any existing wall between these rooms
         let walls_to_remove: Vec<_> = store.get_level_walls(level_id)
             .iter()
-            .filter(|w| {
-                let wall_on_edge = is_wall_on_edge(w, &shared_start, &shared_end, 0.5);
-                wall_on_edge
-            })
+            .filter(|w| is_wall_on_edge(w, &shared_start, &shared_end, 0.5))
             .map(|w| w.id)
             .collect();
 
@@ -1583,282 +4097,573 @@ impl WasmStore {
             }
         };
 
-        self.bump_mutation_count();
-        Ok(serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL))
-    }
+        Ok(serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL))
+    }
+
+    // ============================================================================
+    // FRAMING GENERATION
+    // ============================================================================
+
+    /// Generate framing for a wall (or regenerate if already exists)
+    /// Returns framing summary with member counts and board feet
+    #[wasm_bindgen]
+    pub fn generate_wall_framing(&self, wall_id: &str) -> Result<WallFramingSummary, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let layout_id = store.generate_wall_framing(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let layout = store.get_framing_layout(layout_id)
+            .ok_or_else(|| JsValue::from_str("Framing layout not found"))?;
+
+        let summary = WallFramingSummary::from(layout);
+
+        Ok(summary)
+    }
+
+    /// Whether a wall's framing is out of date since an edit to the wall,
+    /// its openings, or the walls it meets
+    #[wasm_bindgen]
+    pub fn is_framing_stale(&self, wall_id: &str) -> Result<bool, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        Ok(store.is_framing_stale(wall_id))
+    }
+
+    /// Get framing summary for a wall, regenerating it only if stale
+    #[wasm_bindgen]
+    pub fn get_wall_framing_summary(&self, wall_id: &str) -> Result<WallFramingSummary, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.refresh_wall_framing(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let layout = store.get_wall_framing_layout(wall_id)
+            .ok_or_else(|| JsValue::from_str("No framing layout found for wall"))?;
+
+        Ok(WallFramingSummary::from(layout))
+    }
+
+    /// Cut list for a wall's framing: stock boards to buy and the pieces
+    /// cut from each, with waste
+    #[wasm_bindgen]
+    pub fn get_wall_cut_list(&self, wall_id: &str) -> Result<JsValue, JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.refresh_wall_framing(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let cut_list = store.wall_cut_list(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&cut_list)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize cut list: {}", e)))
+    }
+
+    /// Cut lists for every framed wall on a level and for the level overall
+    /// Returns { level_id, walls: [{ wall_id, cut_list }], total }
+    #[wasm_bindgen]
+    pub fn get_level_cut_list(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.regenerate_stale_framing()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let cut_list = store.level_cut_list(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&cut_list)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize cut list: {}", e)))
+    }
+
+    /// Set framing configuration for a wall
+    #[wasm_bindgen]
+    pub fn set_wall_framing_config(
+        &self,
+        wall_id: &str,
+        stud_spacing: f64,
+        lumber_size: &str,
+        double_top_plate: bool,
+        is_load_bearing: bool,
+    ) -> Result<(), JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let lumber = match lumber_size.to_lowercase().as_str() {
+            "2x4" => LumberSize::TwoByFour,
+            "2x6" => LumberSize::TwoBySix,
+            "2x8" => LumberSize::TwoByEight,
+            "2x10" => LumberSize::TwoByTen,
+            "2x12" => LumberSize::TwoByTwelve,
+            _ => return Err(JsValue::from_str("Invalid lumber size. Use: 2x4, 2x6, 2x8, 2x10, or 2x12")),
+        };
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let wall = store.walls.get_mut(&wall_id)
+            .ok_or_else(|| JsValue::from_str("Wall not found"))?;
+
+        // Corner and blocking rules are set separately and kept
+        wall.framing_config = WallFramingConfig::new(stud_spacing, lumber, double_top_plate, is_load_bearing)
+            .with_corner_style(wall.framing_config.corner_style)
+            .with_blocking_rows(std::mem::take(&mut wall.framing_config.blocking_rows));
+        store.invalidate_wall_framing(wall_id);
+        Ok(())
+    }
+
+    /// Set the width of floor and roof bearing on a wall (feet), used to
+    /// size its headers
+    #[wasm_bindgen]
+    pub fn set_wall_tributary_width(&self, wall_id: &str, width: f64) -> Result<(), JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        if !width.is_finite() || width <= 0.0 {
+            return Err(JsValue::from_str("Tributary width must be positive"));
+        }
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let wall = store.walls.get_mut(&wall_id)
+            .ok_or_else(|| JsValue::from_str("Wall not found"))?;
+
+        wall.framing_config = wall.framing_config.clone().with_tributary_width(width);
+        store.invalidate_wall_framing(wall_id);
+        Ok(())
+    }
+
+    /// Set corner and blocking rules for a wall's framing
+    /// corner_style: "three_stud" or "california"
+    /// blocking_rows: heights of blocking rows above the bottom of the wall (inches)
+    #[wasm_bindgen]
+    pub fn set_wall_framing_rules(
+        &self,
+        wall_id: &str,
+        corner_style: &str,
+        blocking_rows: Vec<f64>,
+        fire_blocking: bool,
+    ) -> Result<(), JsValue> {
+        let wall_id = WallId::from_str(wall_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let corner_style = match corner_style.to_lowercase().as_str() {
+            "three_stud" => CornerStyle::ThreeStud,
+            "california" => CornerStyle::California,
+            _ => return Err(JsValue::from_str("Invalid corner style. Use: three_stud or california")),
+        };
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let wall = store.walls.get_mut(&wall_id)
+            .ok_or_else(|| JsValue::from_str("Wall not found"))?;
+
+        wall.framing_config = wall.framing_config.clone()
+            .with_corner_style(corner_style)
+            .with_blocking_rows(blocking_rows)
+            .with_fire_blocking(fire_blocking);
+        store.invalidate_wall_framing(wall_id);
+        Ok(())
+    }
+
+    // ============================================================================
+    // ENVELOPE ANALYSIS
+    // ============================================================================
+
+    /// Report thermal envelope leakage points on a level
+    /// Returns { level_id, issues: [{ kind, message, wall_ids, room_ids, start, end }] }
+    #[wasm_bindgen]
+    pub fn get_envelope_report(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let report = analyze_envelope(&store, level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize envelope report: {}", e)))
+    }
+
+    // ============================================================================
+    // EGRESS ANALYSIS
+    // ============================================================================
+
+    /// Check bedroom escape openings and exit paths on a level
+    /// Returns { level_id, bedrooms: [{ room_id, qualifying, unverified }],
+    ///           exits: [{ room_id, opening_id, stair_id }],
+    ///           issues: [{ kind, message, room_ids, opening_ids }] }
+    #[wasm_bindgen]
+    pub fn get_egress_report(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let report = analyze_egress(&store, level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize egress report: {}", e)))
+    }
+
+    // ============================================================================
+    // CIRCULATION
+    // ============================================================================
+
+    /// Room connectivity through doors, cased openings and open boundary,
+    /// with the walking route from the nearest entry to each reachable room
+    /// Returns { level_id, rooms, entries,
+    ///           passages: [{ room_a, room_b, kind, opening_id, location }],
+    ///           unreachable: string[],
+    ///           paths: [{ room_id, rooms: string[], length }] }
+    #[wasm_bindgen]
+    pub fn get_circulation(&self, level_id: &str) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let graph = circulation_graph(&store, level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let paths: Vec<serde_json::Value> = graph.rooms
+            .iter()
+            .filter_map(|room_id| {
+                graph.path_from_entry(*room_id).map(|path| serde_json::json!({
+                    "room_id": room_id.to_string(),
+                    "rooms": path.rooms.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                    "length": path.length
+                }))
+            })
+            .collect();
+
+        let result = serde_json::json!({
+            "level_id": graph.level_id.to_string(),
+            "rooms": graph.rooms.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+            "entries": graph.entries.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+            "passages": graph.passages,
+            "unreachable": graph.unreachable_from_entry().iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+            "paths": paths
+        });
+
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize circulation: {}", e)))
+    }
+
+    // ============================================================================
+    // SITE AND ZONING
+    // ============================================================================
+
+    /// Create the project's site (one per project)
+    #[wasm_bindgen]
+    pub fn create_site(&self, project_id: &str) -> Result<String, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let site_id = store.create_site(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(site_id.to_string())
+    }
+
+    /// Set the lot boundary from an array of [x, y] points
+    #[wasm_bindgen]
+    pub fn set_site_boundary(&self, site_id: &str, points: &JsValue) -> Result<(), JsValue> {
+        let site_id = SiteId::from_str(site_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let points: Vec<[f64; 2]> = serde_wasm_bindgen::from_value(points.clone())
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse points: {}", e)))?;
+        let boundary = Polygon2::new(points.iter().map(|p| Point2::new(p[0], p[1])).collect());
+        if !boundary.is_valid() {
+            return Err(JsValue::from_str("Invalid polygon: must be closed, non-degenerate"));
+        }
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_site_boundary(site_id, boundary)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Set the front, rear and side setbacks (feet)
+    #[wasm_bindgen]
+    pub fn set_site_setbacks(
+        &self,
+        site_id: &str,
+        front: f64,
+        back: f64,
+        left: f64,
+        right: f64,
+    ) -> Result<(), JsValue> {
+        let site_id = SiteId::from_str(site_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_site_setbacks(site_id, Setbacks { front, back, left, right })
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Set which boundary edge faces the street, and the lot coverage
+    /// (fraction) and height (feet) limits; pass undefined for no limit
+    #[wasm_bindgen]
+    pub fn set_site_zoning(
+        &self,
+        site_id: &str,
+        front_edge: u32,
+        max_lot_coverage: Option<f64>,
+        max_height: Option<f64>,
+    ) -> Result<(), JsValue> {
+        let site_id = SiteId::from_str(site_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        let zoning = ZoningRules {
+            front_edge: front_edge as usize,
+            max_lot_coverage,
+            max_height,
+        };
+        store.set_site_zoning(site_id, zoning)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Turn the plan against true north: degrees clockwise from the plan's
+    /// +y axis to north
+    #[wasm_bindgen]
+    pub fn set_site_north_angle(&self, site_id: &str, north_angle: f64) -> Result<(), JsValue> {
+        let site_id = SiteId::from_str(site_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_site_north_angle(site_id, north_angle)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Set the site terrain from an array of surveyed [x, y, z] points;
+    /// pass an empty array to clear it
+    #[wasm_bindgen]
+    pub fn set_site_terrain(&self, site_id: &str, points: &JsValue) -> Result<(), JsValue> {
+        let site_id = SiteId::from_str(site_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let points: Vec<[f64; 3]> = serde_wasm_bindgen::from_value(points.clone())
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse points: {}", e)))?;
+        let points = points.iter().map(|p| Point3::new(p[0], p[1], p[2])).collect();
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_site_terrain(site_id, points)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(())
+    }
+
+    /// Contour lines across the site terrain every `interval` feet
+    /// Returns [{ elevation, segments: [[{ x, y }, { x, y }]] }]
+    #[wasm_bindgen]
+    pub fn get_site_contours(&self, site_id: &str, interval: f64) -> Result<JsValue, JsValue> {
+        let site_id = SiteId::from_str(site_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let contours = store.get_site_contours(site_id, interval)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    // ============================================================================
-    // FRAMING GENERATION
-    // ============================================================================
+        serde_wasm_bindgen::to_value(&contours)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize contours: {}", e)))
+    }
 
-    /// Generate framing for a wall (or regenerate if already exists)
-    /// Returns framing summary with member counts and board feet
+    /// Cut and fill (cubic yards) to grade a building's pad to an elevation
+    /// Returns { building_id, pad_elevation, pad_area, unsurveyed_area, cut_cy, fill_cy }
     #[wasm_bindgen]
-    pub fn generate_wall_framing(&self, wall_id: &str) -> Result<JsValue, JsValue> {
-        let wall_id = WallId::from_str(wall_id)
+    pub fn get_cut_fill(&self, building_id: &str, pad_elevation: f64) -> Result<JsValue, JsValue> {
+        let building_id = BuildingId::from_str(building_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let mut store = self.inner.write()
-            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        // Get wall data
-        let wall = store.get_wall(wall_id)
-            .ok_or_else(|| JsValue::from_str("Wall not found"))?;
+        let earthwork = cut_fill(&store, building_id, pad_elevation)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let wall_length = wall.length() * 12.0; // Convert to inches
-        let wall_height = wall.height * 12.0;   // Convert to inches
-        let framing_config = wall.framing_config.clone();
-        let wall_start = wall.start.clone();
-        let (dir_x, dir_y) = wall.direction();
+        serde_wasm_bindgen::to_value(&earthwork)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize cut/fill: {}", e)))
+    }
 
-        // Get openings for this wall
-        let openings: Vec<_> = store.get_wall_openings(wall_id)
-            .iter()
-            .map(|o| RoughOpening::new(
-                o.id,
-                o.width * 12.0,  // Convert to inches
-                o.height * 12.0,
-                o.position_along_wall * wall_length, // Convert position to inches along wall
-            ))
-            .collect();
+    /// Check building footprints against the site's setbacks, coverage and height limits
+    /// Returns { project_id, site_id, lot_area, covered_area, lot_coverage, max_lot_coverage,
+    ///           setback_violations: [{ building_id, level_id, side, lot_edge, start, end,
+    ///                                  required, distance, encroachment }],
+    ///           height_violations: [{ building_id, height, max_height }] }
+    #[wasm_bindgen]
+    pub fn check_zoning(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        // Remove existing layout if present
-        if let Some(layout_id) = store.get_wall(wall_id).and_then(|w| w.framing_layout_id) {
-            let _ = store.remove_framing_layout(layout_id);
-        }
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        // Generate new framing layout
-        let mut layout = FramingLayout::new(wall_id, framing_config.stud_spacing, framing_config.lumber_size);
-        layout.double_top_plate = framing_config.double_top_plate;
-
-        // Calculate plate thickness (for stud height calculation)
-        let (_, plate_depth) = framing_config.lumber_size.actual_dimensions();
-        let num_top_plates = if framing_config.double_top_plate { 2 } else { 1 };
-        let stud_height = wall_height - plate_depth - (num_top_plates as f64 * plate_depth);
-
-        // Generate bottom plate
-        layout.add_member(FramingMember::bottom_plate(
-            Point3::new(wall_start.x * 12.0, wall_start.y * 12.0, 0.0),
-            wall_length,
-            framing_config.lumber_size,
-            dir_y.atan2(dir_x),
-            wall_id,
-        ));
-
-        // Generate top plate(s)
-        let top_plate_z = wall_height - plate_depth;
-        layout.add_member(FramingMember::top_plate(
-            Point3::new(wall_start.x * 12.0, wall_start.y * 12.0, top_plate_z),
-            wall_length,
-            framing_config.lumber_size,
-            dir_y.atan2(dir_x),
-            wall_id,
-        ));
-
-        if framing_config.double_top_plate {
-            layout.add_member(FramingMember::new(
-                FramingMemberType::DoubleTopPlate,
-                framing_config.lumber_size,
-                FramingMaterial::SPF,
-                Point3::new(wall_start.x * 12.0, wall_start.y * 12.0, top_plate_z - plate_depth),
-                wall_length,
-                dir_y.atan2(dir_x),
-                wall_id,
-            ));
-        }
+        let report = check_zoning(&store, project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        // Generate studs at regular spacing
-        let stud_base_z = plate_depth;
-        let mut position = 0.0;
-        let mut stud_index = 0;
-
-        while position <= wall_length {
-            // Check if this position conflicts with an opening
-            let in_opening = openings.iter().any(|o| {
-                let opening_start = o.position_along_wall - o.width / 2.0;
-                let opening_end = o.position_along_wall + o.width / 2.0;
-                position > opening_start && position < opening_end
-            });
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize zoning report: {}", e)))
+    }
 
-            if !in_opening {
-                // Calculate stud position in world coordinates
-                let stud_x = wall_start.x * 12.0 + dir_x * position;
-                let stud_y = wall_start.y * 12.0 + dir_y * position;
+    // ============================================================================
+    // ENERGY MODEL
+    // ============================================================================
 
-                layout.add_member(FramingMember::stud(
-                    Point3::new(stud_x, stud_y, stud_base_z),
-                    stud_height,
-                    framing_config.lumber_size,
-                    wall_id,
-                ));
-            }
+    /// Envelope UA (Btu/h·°F) of a building, per level and per component kind
+    /// Returns { building_id, ua,
+    ///           levels: [{ level_id, name, ua, components: [{ kind, level_id, wall_id, opening_id,
+    ///                                                         quantity, u_factor, ua, insulated }] }],
+    ///           insulation: [{ kind, quantity, ua, effective_r, uninsulated }],
+    ///           solar: [{ facing, wall_area, window_area, window_wall_ratio, heating_gain, cooling_gain }] }
+    #[wasm_bindgen]
+    pub fn get_energy_summary(&self, building_id: &str) -> Result<JsValue, JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-            stud_index += 1;
-            position = stud_index as f64 * framing_config.stud_spacing;
-        }
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        // Generate framing around openings (king studs, jack studs, headers)
-        for opening in &openings {
-            let opening_start = opening.position_along_wall - opening.width / 2.0;
-            let opening_end = opening.position_along_wall + opening.width / 2.0;
-
-            // King studs (full height on each side)
-            for king_pos in [opening_start - 1.5, opening_end + 1.5] {
-                if king_pos >= 0.0 && king_pos <= wall_length {
-                    let stud_x = wall_start.x * 12.0 + dir_x * king_pos;
-                    let stud_y = wall_start.y * 12.0 + dir_y * king_pos;
-
-                    layout.add_member(FramingMember::new(
-                        FramingMemberType::KingStud,
-                        framing_config.lumber_size,
-                        FramingMaterial::SPF,
-                        Point3::new(stud_x, stud_y, stud_base_z),
-                        stud_height,
-                        0.0,
-                        wall_id,
-                    ).with_opening(opening.opening_id));
-                }
-            }
+        let summary = calculate_energy(&store, building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-            // Jack studs (support header)
-            let jack_height = opening.height + opening.header_depth;
-            for jack_pos in [opening_start, opening_end] {
-                if jack_pos >= 0.0 && jack_pos <= wall_length {
-                    let stud_x = wall_start.x * 12.0 + dir_x * jack_pos;
-                    let stud_y = wall_start.y * 12.0 + dir_y * jack_pos;
-
-                    layout.add_member(FramingMember::new(
-                        FramingMemberType::JackStud,
-                        framing_config.lumber_size,
-                        FramingMaterial::SPF,
-                        Point3::new(stud_x, stud_y, stud_base_z),
-                        jack_height,
-                        0.0,
-                        wall_id,
-                    ).with_opening(opening.opening_id));
-                }
-            }
+        serde_wasm_bindgen::to_value(&summary)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize energy summary: {}", e)))
+    }
 
-            // Header
-            let header_pos_x = wall_start.x * 12.0 + dir_x * opening_start;
-            let header_pos_y = wall_start.y * 12.0 + dir_y * opening_start;
-            let header_z = stud_base_z + opening.height;
-
-            layout.add_member(FramingMember::new(
-                FramingMemberType::Header,
-                LumberSize::TwoByEight, // Headers typically larger
-                FramingMaterial::SPF,
-                Point3::new(header_pos_x, header_pos_y, header_z),
-                opening.width,
-                dir_y.atan2(dir_x),
-                wall_id,
-            ).with_opening(opening.opening_id));
-
-            // Sill (for windows)
-            if opening.requires_sill {
-                let sill_z = stud_base_z; // At bottom plate level
-                layout.add_member(FramingMember::new(
-                    FramingMemberType::Sill,
-                    framing_config.lumber_size,
-                    FramingMaterial::SPF,
-                    Point3::new(header_pos_x, header_pos_y, sill_z),
-                    opening.width,
-                    dir_y.atan2(dir_x),
-                    wall_id,
-                ).with_opening(opening.opening_id));
-            }
-        }
+    /// Design heating and cooling loads (Btu/h), supply and return registers
+    /// and duct runs for a building's conditioned rooms
+    /// Returns { building_id, heating_load, cooling_load, cooling_tons,
+    ///           supply_registers, return_registers, duct_runs,
+    ///           rooms: [{ room_id, level_id, name, zone_id, floor_area, glazing_area, ua,
+    ///                     heating_load, cooling_load, supply_registers }],
+    ///           zones: [{ zone_id, name, room_ids, level_ids, floor_area, heating_load,
+    ///                     cooling_load, supply_registers, return_registers }] }
+    #[wasm_bindgen]
+    pub fn get_hvac_summary(&self, building_id: &str) -> Result<JsValue, JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        // Recalculate totals
-        layout.recalculate_totals();
-
-        // Create summary before storing
-        let summary = serde_json::json!({
-            "layoutId": layout.id.to_string(),
-            "wallId": wall_id.to_string(),
-            "studCount": layout.stud_count,
-            "totalBoardFeet": layout.total_board_feet,
-            "memberCount": layout.members.len(),
-            "studSpacing": layout.stud_spacing,
-            "lumberSize": layout.lumber_size.nominal_name(),
-            "doubleTopPlate": layout.double_top_plate
-        });
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        // Store the layout
-        store.store_framing_layout(layout)
+        let summary = hvac_summary(&store, building_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.bump_mutation_count();
-        Ok(serde_wasm_bindgen::to_value(&summary).unwrap_or(JsValue::NULL))
+        serde_wasm_bindgen::to_value(&summary)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize HVAC summary: {}", e)))
     }
 
-    /// Get framing summary for a wall (without regenerating)
+    /// Window area by facade and clear-day solar gain (Btu/day), with the
+    /// share of each window the roof overhang shades
+    /// Returns { building_id, north_angle, heating_gain, cooling_gain,
+    ///           facades: [{ facing, wall_area, window_area, window_wall_ratio, heating_gain, cooling_gain }],
+    ///           windows: [{ opening_id, wall_id, level_id, facing, azimuth, area, shgc, overhang,
+    ///                       winter_shading, summer_shading, heating_gain, cooling_gain }] }
     #[wasm_bindgen]
-    pub fn get_wall_framing_summary(&self, wall_id: &str) -> Result<JsValue, JsValue> {
-        let wall_id = WallId::from_str(wall_id)
+    pub fn get_solar_report(&self, building_id: &str) -> Result<JsValue, JsValue> {
+        let building_id = BuildingId::from_str(building_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         let store = self.inner.read()
             .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        let layout = store.get_wall_framing_layout(wall_id)
-            .ok_or_else(|| JsValue::from_str("No framing layout found for wall"))?;
+        let report = solar_report(&store, building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        // Build member breakdown
-        let mut member_breakdown: std::collections::HashMap<String, u32> = std::collections::HashMap::new();
-        for member in &layout.members {
-            let type_name = member.member_type.display_name().to_string();
-            *member_breakdown.entry(type_name).or_insert(0) += 1;
-        }
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize solar report: {}", e)))
+    }
 
-        let summary = serde_json::json!({
-            "layoutId": layout.id.to_string(),
-            "wallId": layout.wall_id.to_string(),
-            "studCount": layout.stud_count,
-            "totalBoardFeet": layout.total_board_feet,
-            "memberCount": layout.members.len(),
-            "studSpacing": layout.stud_spacing,
-            "lumberSize": layout.lumber_size.nominal_name(),
-            "doubleTopPlate": layout.double_top_plate,
-            "memberBreakdown": member_breakdown
-        });
+    // ============================================================================
+    // BEARING CONTINUITY
+    // ============================================================================
+
+    /// Check that bearing walls on upper levels sit on walls below
+    /// Returns { building_id, supports: [{ wall_id, supported_by, beam_ids }],
+    ///           issues: [{ kind, message, level_id, wall_id, supporting_wall_ids, start, end, length }] }
+    #[wasm_bindgen]
+    pub fn check_bearing_continuity(&self, building_id: &str) -> Result<JsValue, JsValue> {
+        let building_id = BuildingId::from_str(building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let report = check_bearing_continuity(&store, building_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        Ok(serde_wasm_bindgen::to_value(&summary).unwrap_or(JsValue::NULL))
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize bearing report: {}", e)))
     }
 
-    /// Set framing configuration for a wall
+    // ============================================================================
+    // CONSTRUCTION SEQUENCING
+    // ============================================================================
+
+    /// Get the construction sequence manifest for a building
+    /// timing: optional { foundation, floor_framing, wall, roof, opening, finish } durations in seconds
+    /// Returns { building_id, total_duration, steps: [{ index, phase, level_id, entity, label, start_time, duration }] }
     #[wasm_bindgen]
-    pub fn set_wall_framing_config(
-        &self,
-        wall_id: &str,
-        stud_spacing: f64,
-        lumber_size: &str,
-        double_top_plate: bool,
-        is_load_bearing: bool,
-    ) -> Result<(), JsValue> {
-        let wall_id = WallId::from_str(wall_id)
+    pub fn get_construction_sequence(&self, building_id: &str, timing: &JsValue) -> Result<JsValue, JsValue> {
+        let building_id = BuildingId::from_str(building_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let lumber = match lumber_size.to_lowercase().as_str() {
-            "2x4" => LumberSize::TwoByFour,
-            "2x6" => LumberSize::TwoBySix,
-            "2x8" => LumberSize::TwoByEight,
-            "2x10" => LumberSize::TwoByTen,
-            "2x12" => LumberSize::TwoByTwelve,
-            _ => return Err(JsValue::from_str("Invalid lumber size. Use: 2x4, 2x6, 2x8, 2x10, or 2x12")),
+        let timing: SequenceTiming = if timing.is_undefined() || timing.is_null() {
+            SequenceTiming::default()
+        } else {
+            serde_wasm_bindgen::from_value(timing.clone())
+                .map_err(|e| JsValue::from_str(&format!("Failed to parse timing: {}", e)))?
         };
 
-        let config = WallFramingConfig::new(stud_spacing, lumber, double_top_plate, is_load_bearing);
-
-        let mut store = self.inner.write()
-            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-        let wall = store.walls.get_mut(&wall_id)
-            .ok_or_else(|| JsValue::from_str("Wall not found"))?;
+        let sequence = sequence_building(&store, building_id, &timing)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        wall.framing_config = config;
-        self.bump_mutation_count();
-        Ok(())
+        serde_wasm_bindgen::to_value(&sequence)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize sequence: {}", e)))
     }
 
     // ============================================================================
@@ -1872,141 +4677,83 @@ impl WasmStore {
         let level_id = LevelId::from_str(level_id)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let store = self.inner.read()
-            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
-
-        // Build cost input from store data
-        let cost_input = self.build_cost_input(&store, level_id)?;
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        // Calculate estimate
-        let calculator = self.cost_calculator.borrow();
-        let estimate = calculator.calculate(&cost_input);
+        // Calculate estimate with the project's regional prices, if any
+        let estimate = self.cost_calculator.borrow()
+            .estimate_level(&mut store, level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         // Serialize to JS
         serde_wasm_bindgen::to_value(&estimate)
             .map_err(|e| JsValue::from_str(&format!("Failed to serialize estimate: {}", e)))
     }
 
-    /// Build a CostInput from store data for a given level
-    fn build_cost_input(
-        &self,
-        store: &geometry_core::store::Store,
-        level_id: LevelId,
-    ) -> Result<CostInput, JsValue> {
-        let level = store.get_level(level_id)
-            .ok_or_else(|| JsValue::from_str("Level not found"))?;
-
-        // Get footprint data
-        let footprint = store.get_level_footprint(level_id);
-        let footprint_sqft = footprint
-            .as_ref()
-            .map(|fp| fp.polygon.area())
-            .unwrap_or(0.0);
-        let exterior_perimeter = footprint
-            .as_ref()
-            .map(|fp| fp.polygon.perimeter())
-            .unwrap_or(0.0);
+    /// Take off raw quantities for a level, without prices
+    /// format: "json" (default) returns a serialized QuantityTakeoff, "csv" returns CSV text
+    #[wasm_bindgen]
+    pub fn generate_quantity_takeoff(&self, level_id: &str, format: Option<String>) -> Result<JsValue, JsValue> {
+        let level_id = LevelId::from_str(level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        // Get rooms
-        let rooms = store.get_level_rooms(level_id);
-        let total_floor_area: f64 = rooms.iter().map(|r| r.area()).sum();
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
 
-        // Build room cost inputs
-        let room_inputs: Vec<RoomCostInput> = rooms
-            .iter()
-            .map(|room| {
-                let perimeter = room.boundary.perimeter();
-                let floor_sqft = room.area();
-                let wall_sqft = perimeter * level.floor_to_floor;
-                RoomCostInput {
-                    id: room.id,
-                    room_type: room.room_type.display_name().to_string(),
-                    floor_sqft,
-                    wall_sqft,
-                    perimeter_ft: perimeter,
-                }
-            })
-            .collect();
+        // Stud and plate counts come from framing layouts
+        store.regenerate_stale_framing()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        // Get walls and calculate wall areas
-        let walls = store.get_level_walls(level_id);
-        let mut exterior_wall_linear_ft = 0.0;
-        let mut interior_wall_linear_ft = 0.0;
+        let takeoff = QuantityTakeoff::from_level(&store, level_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        for wall in &walls {
-            let wall_length = wall.length();
-            // For now, assume all walls are interior
-            // TODO: Determine exterior vs interior based on footprint boundary
-            interior_wall_linear_ft += wall_length;
+        match format.as_deref() {
+            None | Some("json") => serde_wasm_bindgen::to_value(&takeoff)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize takeoff: {}", e))),
+            Some("csv") => Ok(JsValue::from_str(&takeoff.to_csv())),
+            Some(other) => Err(JsValue::from_str(&format!("Unknown takeoff format: {}", other))),
         }
+    }
 
-        // Use footprint perimeter as exterior wall estimate if no explicit exterior walls
-        if exterior_wall_linear_ft == 0.0 {
-            exterior_wall_linear_ft = exterior_perimeter;
-        }
+    /// Generate a cost estimate for every structure in a project
+    /// Returns { project_id, sections: [{ building_id, building_name, occupancy, levels, total }],
+    ///           habitable_total, accessory_total, grand_total }
+    #[wasm_bindgen]
+    pub fn generate_project_estimate(&self, project_id: &str) -> Result<JsValue, JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let exterior_wall_sqft = exterior_wall_linear_ft * level.floor_to_floor;
-
-        // Build opening cost inputs
-        let mut opening_inputs: Vec<OpeningCostInput> = Vec::new();
-        let mut opening_counts: HashMap<String, (OpeningId, String, f64, f64, u32)> = HashMap::new();
-
-        for wall in &walls {
-            let openings = store.get_wall_openings(wall.id);
-            for opening in openings {
-                let opening_type = match &opening.opening_type {
-                    geometry_core::domain::OpeningType::Door => "exterior_door".to_string(),
-                    geometry_core::domain::OpeningType::Window => "window".to_string(),
-                    geometry_core::domain::OpeningType::Other(name) => {
-                        if name.to_lowercase().contains("interior") {
-                            "interior_door".to_string()
-                        } else if name.to_lowercase().contains("garage") {
-                            "garage_door".to_string()
-                        } else {
-                            "window".to_string()
-                        }
-                    }
-                };
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
 
-                // Group by type and dimensions for counting
-                let key = format!("{}_{}x{}", opening_type, opening.width as i32, opening.height as i32);
-                let entry = opening_counts.entry(key).or_insert((
-                    opening.id,
-                    opening_type,
-                    opening.width,
-                    opening.height,
-                    0,
-                ));
-                entry.4 += 1;
-            }
-        }
+        let calculator = self.cost_calculator.borrow();
+        let estimate = calculator.calculate_project(&store, project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        for (_, (id, opening_type, width, height, count)) in opening_counts {
-            opening_inputs.push(OpeningCostInput {
-                id,
-                opening_type,
-                width,
-                height,
-                count,
-            });
-        }
+        serde_wasm_bindgen::to_value(&estimate)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize estimate: {}", e)))
+    }
 
-        // Estimate roof area (simple multiplier for pitch)
-        let roof_sqft = footprint_sqft * 1.1; // 10% overhang/pitch factor
+    /// Cost difference between two snapshots of a project
+    /// Returns { project_id, snapshot_a, snapshot_b, label_a, label_b,
+    ///           items: [{ building_name, level_name, category, description, quantity_a, quantity_b,
+    ///                     total_a, total_b, delta }], total_a, total_b, total_delta }
+    #[wasm_bindgen]
+    pub fn compare_estimates(&self, snapshot_a: &str, snapshot_b: &str) -> Result<JsValue, JsValue> {
+        let snapshot_a = SnapshotId::from_str(snapshot_a)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let snapshot_b = SnapshotId::from_str(snapshot_b)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        Ok(CostInput {
-            level_id,
-            footprint_sqft,
-            total_floor_area: if total_floor_area > 0.0 { total_floor_area } else { footprint_sqft },
-            exterior_wall_linear_ft,
-            exterior_wall_sqft,
-            interior_wall_linear_ft,
-            roof_sqft,
-            foundation_sqft: footprint_sqft,
-            rooms: room_inputs,
-            openings: opening_inputs,
-            wall_height: level.floor_to_floor,
-        })
+        let store = self.inner.read()
+            .map_err(|_| JsValue::from_str("Failed to acquire read lock"))?;
+
+        let comparison = self.cost_calculator.borrow()
+            .compare_snapshots(&store, snapshot_a, snapshot_b)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&comparison)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize comparison: {}", e)))
     }
 
     /// Set a material price in the price table
@@ -2066,23 +4813,80 @@ impl WasmStore {
     }
 
     /// Import a price table from JSON
-    /// Merges with existing prices (overwrites matching keys)
+    /// Merges with existing prices (overwrites matching keys). A table with
+    /// a region tag is merged into that region's price set instead.
     #[wasm_bindgen]
     pub fn import_price_table(&self, table_json: &JsValue) -> Result<(), JsValue> {
         let imported: PriceTable = serde_wasm_bindgen::from_value(table_json.clone())
             .map_err(|e| JsValue::from_str(&format!("Failed to parse price table: {}", e)))?;
 
-        let mut calculator = self.cost_calculator.borrow_mut();
+        self.cost_calculator.borrow_mut().import_price_table(imported);
+        Ok(())
+    }
 
-        // Merge material prices
-        for (material, price) in imported.material_prices {
-            calculator.set_material_price(material, price);
-        }
+    /// Export the price table as CSV, or a region's price set when given
+    /// Columns: kind, type, unit, price, description, supplier, last_updated
+    #[wasm_bindgen]
+    pub fn export_price_csv(&self, region: Option<String>) -> Result<String, JsValue> {
+        let calculator = self.cost_calculator.borrow();
+        let table = match region.as_deref() {
+            Some(region) => calculator.price_set(region)
+                .ok_or_else(|| JsValue::from_str(&format!("Price set not found: {}", region)))?,
+            None => calculator.price_table(),
+        };
+        Ok(table.to_csv())
+    }
 
-        // Merge labor rates
-        for (labor, rate) in imported.labor_rates {
-            calculator.set_labor_rate(labor, rate);
-        }
+    /// Import prices from CSV into the price table, or into a region's price
+    /// set when given (created if new). Nothing is applied if any row is invalid.
+    /// Returns { imported, errors: [{ row, message }] }
+    #[wasm_bindgen]
+    pub fn import_price_csv(&self, text: &str, region: Option<String>) -> Result<JsValue, JsValue> {
+        let result = self.cost_calculator
+            .borrow_mut()
+            .import_price_csv(text, region.as_deref());
+
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize import result: {}", e)))
+    }
+
+    /// Get the regions that have a price set loaded
+    #[wasm_bindgen]
+    pub fn get_price_set_regions(&self) -> Result<JsValue, JsValue> {
+        let calculator = self.cost_calculator.borrow();
+        serde_wasm_bindgen::to_value(&calculator.price_set_regions())
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize regions: {}", e)))
+    }
+
+    /// Price a project's estimates with a region's price set (None for the default prices)
+    #[wasm_bindgen]
+    pub fn set_project_price_region(&self, project_id: &str, region: Option<String>) -> Result<(), JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_project_price_region(project_id, region)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Set the markups a project's estimates carry
+    /// markups: { contingency_percent, overhead_percent, profit_percent }, missing fields are 0
+    #[wasm_bindgen]
+    pub fn set_project_markups(&self, project_id: &str, markups: JsValue) -> Result<(), JsValue> {
+        let project_id = ProjectId::from_str(project_id)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let markups: Markups = serde_wasm_bindgen::from_value(markups)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse markups: {}", e)))?;
+
+        let mut store = self.inner.write()
+            .map_err(|_| JsValue::from_str("Failed to acquire write lock"))?;
+
+        store.set_project_markups(project_id, markups)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         Ok(())
     }
@@ -2096,22 +4900,31 @@ impl WasmStore {
             "concrete_forms",
             "concrete_vapor_barrier",
             "concrete_gravel",
+            "fill_soil",
             "lumber_2x4",
             "lumber_2x6",
             "lumber_2x8",
             "lumber_2x10",
             "lumber_2x12",
             "lvl_beam",
+            "structural_post",
             "sheathing",
             "asphalt_shingles",
             "metal_roofing",
             "tile_roofing",
             "roofing_underlayment",
+            "drip_edge",
+            "ridge_cap",
+            "valley_flashing",
+            "gutter",
+            "downspout",
             "vinyl_siding",
             "hardie_board",
             "stucco",
             "brick",
             "stone",
+            "deck_board",
+            "deck_railing",
             "window_unit",
             "exterior_door",
             "interior_door",
@@ -2126,7 +4939,14 @@ impl WasmStore {
             "trim",
             "truss",
             "light_fixture",
+            "receptacle",
+            "gfci_receptacle",
+            "light_switch",
             "plumbing_fixture",
+            "toilet",
+            "sink",
+            "bathtub",
+            "shower",
             "cabinet",
             "countertop",
             "appliance",
@@ -2142,11 +4962,14 @@ impl WasmStore {
             "general_labor",
             "skilled_labor",
             "framing_carpentry",
+            "excavation",
+            "fill_compaction",
             "concrete_subgrade_prep",
             "concrete_form_install",
             "concrete_rebar_install",
             "concrete_place_finish",
             "roofing_install",
+            "gutter_install",
             "siding_install",
             "drywall_install",
             "painting_labor",
@@ -2182,6 +5005,143 @@ impl WasmStore {
 // COSTING HELPER FUNCTIONS
 // ============================================================================
 
+/// Floor or ceiling layer as passed from JS
+#[derive(serde::Deserialize)]
+struct SlabLayerInput {
+    name: String,
+    material: String,
+    thickness: f64,
+    #[serde(default)]
+    r_value_per_inch: Option<f64>,
+}
+
+/// Parse an optional array of slab layers; None when the argument is omitted
+fn parse_mesh_export_options(options: JsValue) -> Result<MeshExportOptions, JsValue> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(MeshExportOptions::default());
+    }
+    serde_wasm_bindgen::from_value(options)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse mesh export options: {}", e)))
+}
+
+fn parse_slab_layers(layers: JsValue) -> Result<Option<Vec<SlabLayer>>, JsValue> {
+    if layers.is_undefined() || layers.is_null() {
+        return Ok(None);
+    }
+    let inputs: Vec<SlabLayerInput> = serde_wasm_bindgen::from_value(layers)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse layers: {}", e)))?;
+    inputs
+        .into_iter()
+        .map(|l| {
+            let mut layer = SlabLayer::new(l.name, parse_material_type(&l.material)?, l.thickness);
+            layer.r_value_per_inch = l.r_value_per_inch;
+            Ok(layer)
+        })
+        .collect::<Result<Vec<_>, JsValue>>()
+        .map(Some)
+}
+
+/// Whole level when no room is given, else that room
+fn parse_slab_boundary(room_id: Option<&str>) -> Result<SlabBoundary, JsValue> {
+    match room_id {
+        None => Ok(SlabBoundary::Level),
+        Some(id) => {
+            let room_id = RoomId::from_str(id)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            Ok(SlabBoundary::Room { room_id })
+        }
+    }
+}
+
+/// A plan point as passed from JS: [x, y] or a grid reference like "A/3"
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum PointInput {
+    Coords(Vec<f64>),
+    Grid(String),
+}
+
+/// Parse a point on a level, resolving grid references against the grid
+/// of the level's building
+fn parse_level_point(
+    store: &geometry_core::store::Store,
+    level_id: LevelId,
+    value: &JsValue,
+    label: &str,
+) -> Result<Point2, JsValue> {
+    let input: PointInput = serde_wasm_bindgen::from_value(value.clone())
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse {}: {}", label, e)))?;
+    resolve_level_point(store, level_id, input, label)
+}
+
+fn resolve_level_point(
+    store: &geometry_core::store::Store,
+    level_id: LevelId,
+    input: PointInput,
+    label: &str,
+) -> Result<Point2, JsValue> {
+    match input {
+        PointInput::Coords(c) if c.len() >= 2 => Ok(Point2::new(c[0], c[1])),
+        PointInput::Coords(_) => Err(JsValue::from_str(&format!("Expected [x, y] for {}", label))),
+        PointInput::Grid(reference) => store.resolve_grid_point(level_id, &reference)
+            .map_err(|e| JsValue::from_str(&e.to_string())),
+    }
+}
+
+/// Mesh quality named by a render call's `quality` argument, high if
+/// omitted
+fn mesh_quality(quality: Option<String>) -> Result<MeshQuality, JsValue> {
+    match quality {
+        None => Ok(MeshQuality::High),
+        Some(name) => MeshQuality::parse(&name)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown mesh quality: {} (expected high, medium or low)", name))),
+    }
+}
+
+/// Project a wall belongs to
+fn wall_project_id(store: &geometry_core::store::Store, wall_id: WallId) -> Result<ProjectId, JsValue> {
+    store.get_wall(wall_id)
+        .and_then(|wall| store.get_level(wall.level_id))
+        .and_then(|level| store.get_building(level.building_id))
+        .map(|building| building.project_id)
+        .ok_or_else(|| JsValue::from_str(&format!("Wall not found: {}", wall_id)))
+}
+
+/// Parse a beam or post profile from a nominal size ("2x10", "4x4") or
+/// actual inches ("1.75x11.875")
+fn parse_member_profile(lumber_size: &str, plies: u8) -> Result<MemberProfile, JsValue> {
+    let lumber_size = match lumber_size.to_lowercase().as_str() {
+        "2x4" => LumberSize::TwoByFour,
+        "2x6" => LumberSize::TwoBySix,
+        "2x8" => LumberSize::TwoByEight,
+        "2x10" => LumberSize::TwoByTen,
+        "2x12" => LumberSize::TwoByTwelve,
+        "4x4" => LumberSize::FourByFour,
+        "4x6" => LumberSize::FourBySix,
+        other => {
+            let dims: Vec<f64> = other.split('x').filter_map(|d| d.trim().parse().ok()).collect();
+            match dims.as_slice() {
+                [width, depth] if *width > 0.0 && *depth > 0.0 => LumberSize::Custom { width: *width, depth: *depth },
+                _ => return Err(JsValue::from_str(&format!("Invalid member size: {}", lumber_size))),
+            }
+        }
+    };
+    Ok(MemberProfile::new(lumber_size, plies))
+}
+
+/// Parse a FramingMaterial from a string
+fn parse_framing_material(s: &str) -> Result<FramingMaterial, JsValue> {
+    match s.to_lowercase().as_str() {
+        "spf" => Ok(FramingMaterial::SPF),
+        "df" => Ok(FramingMaterial::DF),
+        "syp" => Ok(FramingMaterial::SYP),
+        "lvl" => Ok(FramingMaterial::LVL),
+        "psl" => Ok(FramingMaterial::PSL),
+        "steel" => Ok(FramingMaterial::Steel),
+        _ => Err(JsValue::from_str(&format!("Unknown framing material: {}", s))),
+    }
+}
+
 /// Parse a MaterialType from a snake_case string
 fn parse_material_type(s: &str) -> Result<MaterialType, JsValue> {
     match s.to_lowercase().as_str() {
@@ -2190,22 +5150,31 @@ fn parse_material_type(s: &str) -> Result<MaterialType, JsValue> {
         "concrete_forms" => Ok(MaterialType::ConcreteForms),
         "concrete_vapor_barrier" => Ok(MaterialType::ConcreteVaporBarrier),
         "concrete_gravel" => Ok(MaterialType::ConcreteGravel),
+        "fill_soil" => Ok(MaterialType::FillSoil),
         "lumber_2x4" => Ok(MaterialType::Lumber2x4),
         "lumber_2x6" => Ok(MaterialType::Lumber2x6),
         "lumber_2x8" => Ok(MaterialType::Lumber2x8),
         "lumber_2x10" => Ok(MaterialType::Lumber2x10),
         "lumber_2x12" => Ok(MaterialType::Lumber2x12),
         "lvl_beam" => Ok(MaterialType::LVLBeam),
+        "structural_post" => Ok(MaterialType::StructuralPost),
         "sheathing" => Ok(MaterialType::Sheathing),
         "asphalt_shingles" => Ok(MaterialType::AsphaltShingles),
         "metal_roofing" => Ok(MaterialType::MetalRoofing),
         "tile_roofing" => Ok(MaterialType::TileRoofing),
         "roofing_underlayment" => Ok(MaterialType::RoofingUnderlayment),
+        "drip_edge" => Ok(MaterialType::DripEdge),
+        "ridge_cap" => Ok(MaterialType::RidgeCap),
+        "valley_flashing" => Ok(MaterialType::ValleyFlashing),
+        "gutter" => Ok(MaterialType::Gutter),
+        "downspout" => Ok(MaterialType::Downspout),
         "vinyl_siding" => Ok(MaterialType::VinylSiding),
         "hardie_board" => Ok(MaterialType::HardieBoard),
         "stucco" => Ok(MaterialType::Stucco),
         "brick" => Ok(MaterialType::Brick),
         "stone" => Ok(MaterialType::Stone),
+        "deck_board" => Ok(MaterialType::DeckBoard),
+        "deck_railing" => Ok(MaterialType::DeckRailing),
         "window_unit" => Ok(MaterialType::WindowUnit),
         "exterior_door" => Ok(MaterialType::ExteriorDoor),
         "interior_door" => Ok(MaterialType::InteriorDoor),
@@ -2220,7 +5189,14 @@ fn parse_material_type(s: &str) -> Result<MaterialType, JsValue> {
         "trim" => Ok(MaterialType::Trim),
         "truss" => Ok(MaterialType::Truss),
         "light_fixture" => Ok(MaterialType::LightFixture),
+        "receptacle" => Ok(MaterialType::Receptacle),
+        "gfci_receptacle" => Ok(MaterialType::GfciReceptacle),
+        "light_switch" => Ok(MaterialType::LightSwitch),
         "plumbing_fixture" => Ok(MaterialType::PlumbingFixture),
+        "toilet" => Ok(MaterialType::Toilet),
+        "sink" => Ok(MaterialType::Sink),
+        "bathtub" => Ok(MaterialType::Bathtub),
+        "shower" => Ok(MaterialType::Shower),
         "cabinet" => Ok(MaterialType::Cabinet),
         "countertop" => Ok(MaterialType::Countertop),
         "appliance" => Ok(MaterialType::Appliance),
@@ -2234,11 +5210,14 @@ fn parse_labor_type(s: &str) -> Result<LaborType, JsValue> {
         "general_labor" => Ok(LaborType::GeneralLabor),
         "skilled_labor" => Ok(LaborType::SkilledLabor),
         "framing_carpentry" => Ok(LaborType::FramingCarpentry),
+        "excavation" => Ok(LaborType::Excavation),
+        "fill_compaction" => Ok(LaborType::FillCompaction),
         "concrete_subgrade_prep" => Ok(LaborType::ConcreteSubgradePrep),
         "concrete_form_install" => Ok(LaborType::ConcreteFormInstall),
         "concrete_rebar_install" => Ok(LaborType::ConcreteRebarInstall),
         "concrete_place_finish" => Ok(LaborType::ConcretePlaceFinish),
         "roofing_install" => Ok(LaborType::RoofingInstall),
+        "gutter_install" => Ok(LaborType::GutterInstall),
         "siding_install" => Ok(LaborType::SidingInstall),
         "drywall_install" => Ok(LaborType::DrywallInstall),
         "painting_labor" => Ok(LaborType::PaintingLabor),
@@ -2284,35 +5263,6 @@ fn compute_bounding_box(polygon: &geometry_core::domain::Polygon2) -> (f64, f64)
     (max_x - min_x, max_y - min_y)
 }
 
-/// Check if two room polygons are adjacent (within tolerance)
-fn rooms_are_adjacent(
-    poly1: &geometry_core::domain::Polygon2,
-    poly2: &geometry_core::domain::Polygon2,
-    tolerance: f64,
-) -> bool {
-    // Check if any vertex of poly1 is close to any edge of poly2
-    for pt1 in &poly1.outer {
-        for i in 0..poly2.outer.len() {
-            let j = (i + 1) % poly2.outer.len();
-            let dist = point_to_segment_distance(pt1, &poly2.outer[i], &poly2.outer[j]);
-            if dist < tolerance {
-                return true;
-            }
-        }
-    }
-    // Check the reverse
-    for pt2 in &poly2.outer {
-        for i in 0..poly1.outer.len() {
-            let j = (i + 1) % poly1.outer.len();
-            let dist = point_to_segment_distance(pt2, &poly1.outer[i], &poly1.outer[j]);
-            if dist < tolerance {
-                return true;
-            }
-        }
-    }
-    false
-}
-
 /// Calculate distance from a point to a line segment
 fn point_to_segment_distance(
     pt: &geometry_core::domain::Point2,
@@ -2341,68 +5291,14 @@ fn point_to_segment_distance(
     (diff_x * diff_x + diff_y * diff_y).sqrt()
 }
 
-/// Find the shared edge between two polygons (if any)
-/// Returns the start and end points of the shared edge
-fn find_shared_edge(
-    poly1: &geometry_core::domain::Polygon2,
-    poly2: &geometry_core::domain::Polygon2,
-    tolerance: f64,
-) -> Option<(Point2, Point2)> {
-    // Check each edge of poly1 against each edge of poly2
-    for i in 0..poly1.outer.len() {
-        let j = (i + 1) % poly1.outer.len();
-        let edge1_start = &poly1.outer[i];
-        let edge1_end = &poly1.outer[j];
-
-        for k in 0..poly2.outer.len() {
-            let l = (k + 1) % poly2.outer.len();
-            let edge2_start = &poly2.outer[k];
-            let edge2_end = &poly2.outer[l];
-
-            // Check if edges overlap (share a segment)
-            if edges_overlap(edge1_start, edge1_end, edge2_start, edge2_end, tolerance) {
-                // Return the overlapping segment
-                let shared_start = Point2::new(
-                    (edge1_start.x + edge2_end.x) / 2.0,
-                    (edge1_start.y + edge2_end.y) / 2.0,
-                );
-                let shared_end = Point2::new(
-                    (edge1_end.x + edge2_start.x) / 2.0,
-                    (edge1_end.y + edge2_start.y) / 2.0,
-                );
-
-                // Ensure we have a meaningful edge (not a point)
-                if shared_start.distance_to(&shared_end) > tolerance {
-                    return Some((shared_start, shared_end));
-                }
-            }
-        }
+/// Parse an assembly ID argument where an empty string means "choose for me"
+fn parse_optional_assembly_id(assembly_id: &str) -> Result<Option<WallAssemblyId>, JsValue> {
+    if assembly_id.trim().is_empty() {
+        return Ok(None);
     }
-    None
-}
-
-/// Check if two edges overlap (share a segment within tolerance)
-fn edges_overlap(
-    e1_start: &geometry_core::domain::Point2,
-    e1_end: &geometry_core::domain::Point2,
-    e2_start: &geometry_core::domain::Point2,
-    e2_end: &geometry_core::domain::Point2,
-    tolerance: f64,
-) -> bool {
-    // Check if edges are collinear and overlapping
-    // First check if points are close to the other edge
-    let d1_start = point_to_segment_distance(e1_start, e2_start, e2_end);
-    let d1_end = point_to_segment_distance(e1_end, e2_start, e2_end);
-    let d2_start = point_to_segment_distance(e2_start, e1_start, e1_end);
-    let d2_end = point_to_segment_distance(e2_end, e1_start, e1_end);
-
-    // At least two endpoints should be close to the other edge for overlap
-    let close_count = [d1_start, d1_end, d2_start, d2_end]
-        .iter()
-        .filter(|&&d| d < tolerance)
-        .count();
-
-    close_count >= 2
+    WallAssemblyId::from_str(assembly_id)
+        .map(Some)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
 /// Decide what wall type should exist between two room types
@@ -2448,9 +5344,9 @@ fn decide_wall_type(
         ("full", "Privacy room requires wall")
     } else if is_open_concept_pair(room1_type, room2_type) {
         ("none", "Open concept between living spaces")
-    } else if is_circulation(room1_type) && !privacy_rooms(room2_type) {
-        ("none", "Circulation space open to living area")
-    } else if is_circulation(room2_type) && !privacy_rooms(room1_type) {
+    } else if (is_circulation(room1_type) && !privacy_rooms(room2_type))
+        || (is_circulation(room2_type) && !privacy_rooms(room1_type))
+    {
         ("none", "Circulation space open to living area")
     } else if matches!(room1_type, RoomType::Garage) || matches!(room2_type, RoomType::Garage) {
         ("full", "Garage requires fire separation")
@@ -0,0 +1,163 @@
+// Per-level and per-entity revision counters
+// Revisions are derived from each project's event log the first time they
+// are asked for after a change, so mutations (including a whole Rhai
+// script) cost nothing extra. A level is dirty while its revision is newer
+// than the one the viewer last meshed; the walls that made it dirty are
+// listed so only their meshes need rebuilding.
+
+use std::collections::HashMap;
+
+use geometry_core::domain::{EventId, LevelId, ProjectId};
+use geometry_core::store::{EntityChanges, Store};
+use wasm_bindgen::JsValue;
+
+#[derive(Debug, Clone, Copy)]
+struct EntityRevision {
+    kind: &'static str,
+    level: Option<LevelId>,
+    revision: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Revisions {
+    /// Last revision handed out; revisions start at 1
+    latest: u64,
+    /// Event ID each project's log has been read through
+    synced: HashMap<ProjectId, EventId>,
+    levels: HashMap<LevelId, u64>,
+    /// Keyed by entity ID; deleted entities stay so their level is known
+    entities: HashMap<String, EntityRevision>,
+    /// Revision of each level when the viewer last meshed it
+    meshed: HashMap<LevelId, u64>,
+}
+
+impl Revisions {
+    /// Read any events recorded since the last sync
+    pub fn sync(&mut self, store: &Store) -> Result<(), JsValue> {
+        for &project_id in store.projects.keys() {
+            let Some(last) = store.project_revision(project_id) else { continue };
+            let from = self.synced.get(&project_id).copied().unwrap_or(0);
+            if last == from {
+                continue;
+            }
+            let changes = store.changes_since(project_id, from)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            if changes.full_resync {
+                // Entities deleted by the undo aren't listed; redraw all
+                let known: Vec<LevelId> = self.levels.keys().copied().collect();
+                for level_id in known {
+                    self.bump_level(level_id);
+                }
+            }
+
+            for level_id in touched(&changes.levels) {
+                self.bump_level(level_id);
+            }
+            for building_id in changes.buildings.modified.iter() {
+                // Pad elevation and grids show on every level
+                let levels: Vec<LevelId> = store.get_building_levels(*building_id).iter().map(|l| l.id).collect();
+                for level_id in levels {
+                    self.bump_level(level_id);
+                }
+            }
+            self.bump_all(&changes.walls, "wall", |id| store.walls.get(id).map(|w| w.level_id));
+            self.bump_all(&changes.openings, "opening", |id| {
+                store.openings.get(id).and_then(|o| store.walls.get(&o.wall_id)).map(|w| w.level_id)
+            });
+            self.bump_all(&changes.rooms, "room", |id| store.rooms.get(id).map(|r| r.level_id));
+            self.bump_all(&changes.footprints, "footprint", |id| store.footprints.get(id).map(|f| f.level_id));
+            self.bump_all(&changes.detail_callouts, "detail_callout", |id| {
+                store.detail_callouts.get(id).map(|c| c.level_id)
+            });
+            self.bump_all(&changes.roofs, "roof", |id| store.roofs.get(id).map(|r| r.level_id));
+            self.bump_all(&changes.stairs, "stair", |id| store.stairs.get(id).map(|s| s.from_level_id));
+            self.bump_all(&changes.floor_slabs, "floor_slab", |id| store.floor_slabs.get(id).map(|s| s.level_id));
+            self.bump_all(&changes.ceilings, "ceiling", |id| store.ceilings.get(id).map(|c| c.level_id));
+            self.bump_all(&changes.foundations, "foundation", |id| store.foundations.get(id).map(|f| f.level_id));
+            self.bump_all(&changes.beams, "beam", |id| store.beams.get(id).map(|b| b.level_id));
+            self.bump_all(&changes.posts, "post", |id| store.posts.get(id).map(|p| p.level_id));
+            self.bump_all(&changes.electrical_devices, "electrical_device", |id| {
+                store.electrical_devices.get(id).map(|d| d.level_id)
+            });
+            self.bump_all(&changes.plumbing_fixtures, "plumbing_fixture", |id| {
+                store.plumbing_fixtures.get(id).map(|f| f.level_id)
+            });
+            self.bump_all(&changes.decks, "deck", |id| store.decks.get(id).map(|d| d.level_id));
+            self.bump_all(&changes.garages, "garage", |id| store.garages.get(id).map(|g| g.level_id));
+
+            self.synced.insert(project_id, changes.to);
+        }
+        Ok(())
+    }
+
+    pub fn level_revision(&self, level_id: LevelId) -> u64 {
+        self.levels.get(&level_id).copied().unwrap_or(0)
+    }
+
+    pub fn entity_revision(&self, entity_id: &str) -> u64 {
+        self.entities.get(entity_id).map_or(0, |e| e.revision)
+    }
+
+    /// Levels changed since the viewer last meshed them
+    pub fn dirty_levels(&self) -> Vec<LevelId> {
+        self.levels
+            .iter()
+            .filter(|(level_id, revision)| **revision > self.meshed_revision(**level_id))
+            .map(|(level_id, _)| *level_id)
+            .collect()
+    }
+
+    /// Walls on a level changed (or deleted) since it was last meshed
+    pub fn dirty_walls(&self, level_id: LevelId) -> Vec<String> {
+        let meshed = self.meshed_revision(level_id);
+        self.entities
+            .iter()
+            .filter(|(_, e)| e.kind == "wall" && e.level == Some(level_id) && e.revision > meshed)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// The viewer has re-meshed the level at its current revision
+    pub fn mark_meshed(&mut self, level_id: LevelId) {
+        self.meshed.insert(level_id, self.latest);
+    }
+
+    fn meshed_revision(&self, level_id: LevelId) -> u64 {
+        self.meshed.get(&level_id).copied().unwrap_or(0)
+    }
+
+    fn bump_level(&mut self, level_id: LevelId) {
+        self.latest += 1;
+        self.levels.insert(level_id, self.latest);
+    }
+
+    /// Bump every entity in `changes`. Deleted entities keep the level
+    /// recorded when they were last seen.
+    fn bump_all<T: ToString>(
+        &mut self,
+        changes: &EntityChanges<T>,
+        kind: &'static str,
+        level_of: impl Fn(&T) -> Option<LevelId>,
+    ) {
+        for id in changes.created.iter().chain(&changes.modified) {
+            self.bump(id.to_string(), kind, level_of(id));
+        }
+        for id in &changes.deleted {
+            self.bump(id.to_string(), kind, None);
+        }
+    }
+
+    fn bump(&mut self, entity_id: String, kind: &'static str, level: Option<LevelId>) {
+        self.latest += 1;
+        let revision = self.latest;
+        let level = level.or_else(|| self.entities.get(&entity_id).and_then(|e| e.level));
+        if let Some(level_id) = level {
+            self.levels.insert(level_id, revision);
+        }
+        self.entities.insert(entity_id, EntityRevision { kind, level, revision });
+    }
+}
+
+fn touched<T: Copy>(changes: &EntityChanges<T>) -> impl Iterator<Item = T> + '_ {
+    changes.created.iter().chain(&changes.modified).chain(&changes.deleted).copied()
+}